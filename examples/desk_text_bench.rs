@@ -0,0 +1,212 @@
+//!
+//! 受付机の文字の組み方と描き方を、画面外で何フレームも計る
+//!
+//!     cargo run --release --features visual_regression --example desk_text_bench -- [フレーム数]
+//!
+//! 文字の描画をキャッシュしたり使い回したりしたときに、速くなったかを数字で確かめるためのもの
+//!
+extern crate suzu;
+
+use std::time::Instant;
+
+use ggez::graphics as ggraphics;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::FontInformation;
+use torifune::numeric;
+
+use suzu::core::bench_stats::FrameTimeSamples;
+use suzu::core::preferences::TextOrientation;
+use suzu::core::request_generator::RequestGenerator;
+use suzu::core::visual_regression::VisualRegressionHarness;
+use suzu::core::{FontID, GensoDate, ReturnBookInformation, SuzuContext, TextureID};
+use suzu::object::task_object::tt_main_component::{TextBalloon, TextBalloonPhraseType};
+use suzu::object::task_object::tt_menu_component::{DateMenu, SimpleMessageMenu};
+use suzu::object::task_object::tt_sub_component::*;
+
+const DEFAULT_FRAMES: usize = 600;
+
+///
+/// 組み直しは描画より重いので、描画の何分の一かの回数だけ計る
+///
+const LAYOUT_ROUNDS_DIVISOR: usize = 10;
+
+const BALLOON_COUNT: usize = 36;
+const MESSAGE_MENU_COUNT: usize = 6;
+const DATE_MENU_COUNT: usize = 2;
+
+const PHRASES: [&str; 6] = [
+    "この本を借りたいのですが",
+    "返却に来ました",
+    "霧雨魔理沙です",
+    "いつまで借りられますか",
+    "また来ますね",
+    "ありがとうございました",
+];
+
+///
+/// 受付机に一度に出ているものを全部まとめたもの
+///
+struct DeskTextLoad {
+    page: BorrowingRecordBookPage,
+    balloons: Vec<TextBalloon>,
+    message_menus: Vec<SimpleMessageMenu>,
+    date_menus: Vec<DateMenu>,
+}
+
+impl DeskTextLoad {
+    fn new<'a>(ctx: &mut SuzuContext<'a>, request: &ReturnBookInformation) -> Self {
+        let page = BorrowingRecordBookPage::new(
+            ctx,
+            numeric::Rect::new(0.0, 0.0, 1000.0, 550.0),
+            TextureID::Paper1,
+            BorrowingRecordBookPageData::from(request),
+            0,
+        );
+
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0x000000ff),
+        );
+
+        // 縦書きと横書きを半分ずつ、画面に格子状に並べる
+        let balloons = (0..BALLOON_COUNT)
+            .map(|index| {
+                let orientation = if index % 2 == 0 {
+                    TextOrientation::Vertical
+                } else {
+                    TextOrientation::Horizontal
+                };
+
+                TextBalloon::new(
+                    ctx.context,
+                    numeric::Rect::new(
+                        (index % 9) as f32 * 150.0,
+                        (index / 9) as f32 * 190.0,
+                        300.0,
+                        500.0,
+                    ),
+                    PHRASES[index % PHRASES.len()],
+                    TextBalloonPhraseType::SimplePhrase,
+                    font_info.clone(),
+                    orientation,
+                )
+            })
+            .collect();
+
+        let message_menus = (0..MESSAGE_MENU_COUNT)
+            .map(|index| {
+                SimpleMessageMenu::new(
+                    ctx,
+                    "貸出期間".to_string(),
+                    PHRASES[index % PHRASES.len()].to_string(),
+                    28.0,
+                    24.0,
+                    0,
+                )
+            })
+            .collect();
+
+        let date_menus = (0..DATE_MENU_COUNT)
+            .map(|index| DateMenu::new(ctx, GensoDate::new(112, 7, 23 + index as u8), 0))
+            .collect();
+
+        DeskTextLoad {
+            page: page,
+            balloons: balloons,
+            message_menus: message_menus,
+            date_menus: date_menus,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        self.page.draw(ctx)?;
+
+        for balloon in self.balloons.iter_mut() {
+            balloon.draw(ctx)?;
+        }
+
+        for menu in self.message_menus.iter_mut() {
+            menu.draw(ctx)?;
+        }
+
+        for menu in self.date_menus.iter_mut() {
+            menu.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// 客が話すたびに起きる吹き出しの組み直し
+    ///
+    fn replace_balloon_texts(&mut self, ctx: &mut ggez::Context, frame: usize) {
+        for (index, balloon) in self.balloons.iter_mut().enumerate() {
+            balloon.replace_text(
+                ctx,
+                PHRASES[(index + frame) % PHRASES.len()],
+                TextBalloonPhraseType::SimplePhrase,
+            );
+        }
+    }
+}
+
+fn print_summary(label: &str, samples: &FrameTimeSamples) {
+    match samples.summary() {
+        Some(summary) => println!("{}", summary.to_report(label)),
+        None => println!("{:<16} no samples", label),
+    }
+}
+
+fn main() {
+    let frames = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_FRAMES);
+
+    let mut harness = VisualRegressionHarness::new();
+
+    let request = harness.with_context(|ctx| {
+        let mut rng = StdRng::seed_from_u64(3737);
+        let books: Vec<_> = ctx.resource.iter_available_books().cloned().collect();
+        RequestGenerator::new(3).generate_returning(
+            &mut rng,
+            &books,
+            "稗田阿求",
+            &GensoDate::new(112, 7, 23),
+        )
+    });
+
+    // 受付机のものを全部作り直すのに掛かる時間
+    let mut layout_samples = FrameTimeSamples::new();
+    for _ in 0..(frames / LAYOUT_ROUNDS_DIVISOR).max(1) {
+        let start = Instant::now();
+        let _ = harness.with_context(|ctx| DeskTextLoad::new(ctx, &request));
+        layout_samples.push(start.elapsed());
+    }
+
+    let mut load = harness.with_context(|ctx| DeskTextLoad::new(ctx, &request));
+
+    let draw_samples = harness
+        .render_frames(frames, |ctx, _| load.draw(ctx))
+        .unwrap();
+
+    let relayout_samples = harness
+        .render_frames(frames, |ctx, frame| {
+            load.replace_balloon_texts(ctx, frame);
+            load.draw(ctx)
+        })
+        .unwrap();
+
+    println!(
+        "desk text bench: {} balloons, {} menus, {} frames",
+        BALLOON_COUNT,
+        MESSAGE_MENU_COUNT + DATE_MENU_COUNT,
+        frames
+    );
+    print_summary("layout", &layout_samples);
+    print_summary("draw", &draw_samples);
+    print_summary("relayout+draw", &relayout_samples);
+}
@@ -1,6 +1,16 @@
 <?xml version="1.0" encoding="UTF-8"?>
 <tileset version="1.4" tiledversion="1.4.2" name="wafu2" tilewidth="32" tileheight="32" tilecount="400" columns="8">
  <image source="ST-Libry-I01.png" width="256" height="1600"/>
+ <tile id="10">
+  <properties>
+   <property name="terrain" value="doorway"/>
+  </properties>
+ </tile>
+ <tile id="13">
+  <properties>
+   <property name="terrain" value="tatami"/>
+  </properties>
+ </tile>
  <tile id="116">
   <objectgroup draworder="index" id="2">
    <object id="1" x="0" y="0" width="32" height="32"/>
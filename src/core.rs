@@ -1,10 +1,69 @@
+pub mod album;
+pub mod ambient_zone;
+pub mod assistant;
+pub mod auto_shelving;
+pub mod background_sim;
+pub mod bench_stats;
+pub mod book_fair;
+pub mod book_inspection;
 pub mod book_management;
+pub mod color_grading;
+pub mod config;
+pub mod content_warning;
+pub mod credits;
+pub mod crowd_density;
 pub mod crypt;
+pub mod customer_watchdog;
+pub mod day_modifier;
+pub mod debug_console;
+pub mod decision_log;
+pub mod desk_autosave;
+pub mod desk_nuisance;
+pub mod diary;
+pub mod difficulty;
+pub mod economy;
+pub mod errand;
+pub mod evaluation;
+pub mod first_run;
+pub mod game_rng;
 pub mod game_system;
+pub mod gamepad;
+pub mod gesture;
+pub mod gift_wrapping;
+pub mod input_queue;
+pub mod instrument;
+pub mod kosuzu_comment;
+pub mod ledger_audit;
+pub mod logger;
+pub mod mail;
 pub mod map_parser;
+pub mod membership;
+pub mod memo_parser;
+pub mod name_generator;
+pub mod number_format;
+pub mod paper_archive;
+pub mod positional_audio;
+pub mod preferences;
+pub mod quicksave;
+pub mod record_validation;
+pub mod recoverable_error;
+pub mod reputation;
+pub mod request_generator;
+pub mod reservation;
+pub mod resource_locator;
+pub mod score_attack;
+pub mod shop_expansion;
+pub mod shortcut_palette;
+pub mod text_layout;
+pub mod time_scale;
 pub mod util;
+#[cfg(feature = "visual_regression")]
+pub mod visual_regression;
+pub mod voice_blip;
+pub mod weather;
 
 use game_system::WeekWorkSchedule;
+use shortcut_palette::PaletteKey;
 use ggez::*;
 use ggez::{audio::SoundSource, graphics as ggraphics};
 
@@ -31,20 +90,23 @@ use std::str::FromStr;
 use crate::{object::scenario_object::SuzunaStatusPageID, scene};
 use crate::{
     object::{
-        scenario_object::SuzunaAdAgencyType, task_object::tt_sub_component::BorrowingRecordBookData,
+        scenario_object::SuzunaAdAgencyType,
+        task_object::tt_sub_component::{BorrowingRecordBookData, BorrowingRecordBookPageData},
     },
-    parse_toml_file,
+    instrument, log_debug, log_error, log_info, log_warn, parse_toml_file,
 };
 
 use std::fs::File;
 use std::io::{Read, Write};
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 extern crate chrono;
 extern crate serde_json;
 
 use crate::object::scenario_object::SuzunaAdType;
-use number_to_jk::number_to_jk;
 
 extern crate num;
 
@@ -467,11 +529,11 @@ impl TextureID {
 }
 
 impl TextureID {
-    pub fn select_random() -> Self {
-        TextureID::from_u32(rand::random::<u32>() % (Self::Unknown as u32)).unwrap()
+    pub fn select_random<R: Rng>(rng: &mut R) -> Self {
+        TextureID::from_u32(rng.gen_range(0, Self::Unknown as u32)).unwrap()
     }
 
-    pub fn random_large_book_scratch_fair() -> TextureID {
+    pub fn random_large_book_scratch_fair<R: Rng>(rng: &mut R) -> TextureID {
         let candidate = [
             TextureID::LargeBookScratchFair1,
             TextureID::LargeBookScratchFair2,
@@ -479,10 +541,10 @@ impl TextureID {
             TextureID::LargeBookScratchFair4,
         ];
 
-        util::random_select(candidate.iter()).unwrap().clone()
+        util::random_select(rng, candidate.iter()).unwrap().clone()
     }
 
-    pub fn random_large_book_scratch_bad() -> TextureID {
+    pub fn random_large_book_scratch_bad<R: Rng>(rng: &mut R) -> TextureID {
         let candidate = [
             TextureID::LargeBookScratchBad1,
             TextureID::LargeBookScratchBad2,
@@ -490,7 +552,7 @@ impl TextureID {
             TextureID::LargeBookScratchBad4,
         ];
 
-        util::random_select(candidate.iter()).unwrap().clone()
+        util::random_select(rng, candidate.iter()).unwrap().clone()
     }
 }
 
@@ -528,9 +590,66 @@ pub enum SoundID {
     EndBGM,
     ResultSE,
     FinalResultSE,
+    AmbientStreet,
+    AmbientBirds,
     Unknown,
 }
 
+impl FromStr for SoundID {
+    type Err = ();
+
+    fn from_str(sound_str: &str) -> Result<Self, Self::Err> {
+        match sound_str {
+            "Title" => Ok(Self::Title),
+            "SeTurnThePage" => Ok(Self::SeTurnThePage),
+            "SeCustomerBell" => Ok(Self::SeCustomerBell),
+            "SeMessage" => Ok(Self::SeMessage),
+            "ScenarioBGM" => Ok(Self::ScenarioBGM),
+            "ShopBGM" => Ok(Self::ShopBGM),
+            "EndBGM" => Ok(Self::EndBGM),
+            "ResultSE" => Ok(Self::ResultSE),
+            "FinalResultSE" => Ok(Self::FinalResultSE),
+            "AmbientStreet" => Ok(Self::AmbientStreet),
+            "AmbientBirds" => Ok(Self::AmbientBirds),
+            _ => Err(()),
+        }
+    }
+}
+
+///
+/// game_data.tomlのscene_bgmに書かれていない場面で流す曲
+///
+pub fn default_scene_bgm(scene: scene::SceneID) -> Option<SoundID> {
+    match scene {
+        scene::SceneID::Title | scene::SceneID::Save => Some(SoundID::Title),
+        scene::SceneID::Scenario => Some(SoundID::ScenarioBGM),
+        scene::SceneID::SuzunaShop | scene::SceneID::MainDesk => Some(SoundID::ShopBGM),
+        scene::SceneID::End | scene::SceneID::Credits => Some(SoundID::EndBGM),
+        _ => None,
+    }
+}
+
+///
+/// game_data.tomlのscene_bgm(場面の名前 = 曲の名前)を読む。読めない組は知らせて飛ばす
+///
+pub fn parse_scene_bgm_table(raw: &HashMap<String, String>) -> HashMap<scene::SceneID, SoundID> {
+    let mut table = HashMap::new();
+
+    for (scene_name, sound_name) in raw.iter() {
+        match (
+            scene::SceneID::from_str(scene_name),
+            SoundID::from_str(sound_name),
+        ) {
+            (Ok(scene_id), Ok(sound_id)) => {
+                table.insert(scene_id, sound_id);
+            }
+            _ => log_warn!("unknown scene bgm entry: {} = {}", scene_name, sound_name),
+        }
+    }
+
+    table
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BookCondition {
     Good,
@@ -557,8 +676,8 @@ impl BookCondition {
         }
     }
 
-    pub fn probability_random(pb: &[u8]) -> Self {
-        let mut random = rand::random::<usize>() % 100;
+    pub fn probability_random<R: Rng>(rng: &mut R, pb: &[u8]) -> Self {
+        let mut random = rng.gen_range(0, 100);
 
         for (index, p) in pb.iter().enumerate() {
             if random < *p as usize {
@@ -588,11 +707,26 @@ pub struct BookInformation {
     pub size: String,
     pub billing_number: u16,
     pub base_price: u32,
+    #[serde(default = "default_book_popularity")]
+    pub popularity: u32,
     condition: BookCondition,
     unique_id: u64,
+    #[serde(default)]
+    anomaly: Option<book_inspection::BookAnomaly>,
+    #[serde(default)]
+    reading: Option<String>,
+}
+
+pub const DEFAULT_BOOK_POPULARITY: u32 = 10;
+
+fn default_book_popularity() -> u32 {
+    DEFAULT_BOOK_POPULARITY
 }
 
 impl BookInformation {
+    ///
+    /// 状態は良で作る。貸し出す本はclone_with_new_id_conditionで状態を選び直す
+    ///
     pub fn new(
         name: String,
         pages: usize,
@@ -606,20 +740,38 @@ impl BookInformation {
             size: size,
             billing_number: billing_number,
             base_price: base_price,
-            condition: BookCondition::probability_random(&[70, 20, 10]),
+            popularity: DEFAULT_BOOK_POPULARITY,
+            condition: BookCondition::Good,
             unique_id: util::get_unique_id(),
+            anomaly: None,
+            reading: None,
         }
     }
 
-    pub fn clone_with_new_id_condition(&self) -> Self {
+    ///
+    /// 新しいIDを振って複製する。本の状態はrngで選び直す
+    ///
+    pub fn clone_with_new_id_condition<R: Rng>(&self, rng: &mut R) -> Self {
         let mut cloned = self.clone();
 
-        cloned.condition = BookCondition::probability_random(&[70, 20, 10]);
+        cloned.condition = BookCondition::probability_random(rng, &[70, 20, 10]);
         cloned.unique_id = util::get_unique_id();
+        cloned.anomaly = None;
 
         return cloned;
     }
 
+    ///
+    /// 贋作や妖魔本であれば、その種類を返す
+    ///
+    pub fn get_anomaly(&self) -> Option<book_inspection::BookAnomaly> {
+        self.anomaly
+    }
+
+    pub fn set_anomaly(&mut self, anomaly: Option<book_inspection::BookAnomaly>) {
+        self.anomaly = anomaly;
+    }
+
     pub fn get_condition_string(&self) -> String {
         self.condition.to_string()
     }
@@ -632,6 +784,24 @@ impl BookInformation {
         &self.name
     }
 
+    ///
+    /// 難しい題名の読み。題名の横に振り仮名として添える
+    ///
+    pub fn get_reading(&self) -> Option<&str> {
+        self.reading.as_ref().map(|reading| reading.as_str())
+    }
+
+    pub fn set_reading(&mut self, reading: Option<String>) {
+        self.reading = reading;
+    }
+
+    ///
+    /// 題名か読みのどちらかが、titleと一致すればtrue
+    ///
+    pub fn is_called(&self, title: &str) -> bool {
+        self.name == title || self.get_reading() == Some(title)
+    }
+
     pub fn get_pages(&self) -> usize {
         self.pages
     }
@@ -649,8 +819,8 @@ pub enum RentalLimit {
 }
 
 impl RentalLimit {
-    pub fn random() -> RentalLimit {
-        match rand::random::<u32>() % 2 {
+    pub fn random<R: Rng>(rng: &mut R) -> RentalLimit {
+        match rng.gen_range(0, 2) {
             0 => RentalLimit::ShortTerm,
             1 => RentalLimit::LongTerm,
             _ => panic!("Exception"),
@@ -674,7 +844,17 @@ impl RentalLimit {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// 各月の日数。添字が月で、0は使わない
+///
+const DAYS_IN_MONTH: [u8; 13] = [0, 31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+const DAYS_IN_SEASON: i64 = 365;
+
+///
+/// # 幻想郷の日付
+/// フィールドの順(年、月、日)で大小を比べる
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct GensoDate {
     pub season: u32,
     pub month: u8,
@@ -699,20 +879,11 @@ impl GensoDate {
     }
 
     pub fn to_string(&self) -> String {
-        format!(
-            "{}季 {}月 {}日",
-            number_to_jk(self.season as u64),
-            number_to_jk(self.month as u64),
-            number_to_jk(self.day as u64)
-        )
+        number_format::NumberFormat::japanese().date(self)
     }
 
     pub fn to_short_string(&self) -> String {
-        format!(
-            "{}月{}日",
-            number_to_jk(self.month as u64),
-            number_to_jk(self.day as u64)
-        )
+        number_format::NumberFormat::japanese().short_date(self)
     }
 
     pub fn to_month_string_eng_short(&self) -> String {
@@ -730,74 +901,94 @@ impl GensoDate {
             11 => "Nov.",
             12 => "Dec.",
             _ => {
-                eprintln!("Invalid month");
+                log_warn!("invalid month: {}", self.month);
                 "Dec."
             }
         }
         .to_string()
     }
 
-    pub fn add_day_chain(mut self, day: i32) -> Self {
-        self.add_day(day);
-        self
+    ///
+    /// monthの日数。月として正しくなければ0
+    ///
+    pub fn days_in_month(month: u8) -> u8 {
+        match DAYS_IN_MONTH.get(month as usize) {
+            Some(days) => *days,
+            None => 0,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.day >= 1 && self.day <= Self::days_in_month(self.month)
     }
 
-    pub fn add_day(&mut self, mut day: i32) {
-        static MONTH: [i32; 13] = [0, 31, 28, 31, 30, 30, 30, 31, 31, 30, 31, 30, 31];
+    ///
+    /// 0年1月1日を0として数えた通し日数
+    ///
+    fn to_serial_day(&self) -> i64 {
+        let month = self.month.max(1).min(12) as usize;
+        let days_before_month: i64 = DAYS_IN_MONTH[1..month].iter().map(|days| *days as i64).sum();
 
-        while self.day as i32 + day > MONTH[self.month as usize] {
-            day -= MONTH[self.month as usize] - self.day as i32;
-            self.day = 0;
-            self.month += 1;
+        self.season as i64 * DAYS_IN_SEASON + days_before_month + self.day as i64 - 1
+    }
 
-            if self.month > 12 {
-                self.season += 1;
-                self.month %= 12;
-            }
+    ///
+    /// 通し日数から日付に戻す。0年1月1日より前にはならない
+    ///
+    fn from_serial_day(serial_day: i64) -> Self {
+        let serial_day = serial_day.max(0);
+        let mut rest = serial_day % DAYS_IN_SEASON;
+        let mut month = 1;
+
+        while rest >= DAYS_IN_MONTH[month] as i64 {
+            rest -= DAYS_IN_MONTH[month] as i64;
+            month += 1;
         }
 
-        self.day += day as u8;
+        GensoDate::new(
+            (serial_day / DAYS_IN_SEASON) as u32,
+            month as u8,
+            rest as u8 + 1,
+        )
+    }
+
+    pub fn add_days_chain(mut self, days: i32) -> Self {
+        self.add_days(days);
+        self
     }
 
     ///
-    /// self -> 7/1
-    /// date2 -> 7/8
-    /// return 7
+    /// 日付をdays日進める。負の値なら戻す
     ///
-    pub fn diff_day(&self, date2: &Self) -> i32 {
-        static MONTH: [i32; 13] = [0, 31, 28, 31, 30, 30, 30, 31, 31, 30, 31, 30, 31];
+    pub fn add_days(&mut self, days: i32) {
+        *self = Self::from_serial_day(self.to_serial_day() + days as i64);
+    }
 
-        let greater_self = self.month.partial_cmp(&date2.month).unwrap();
+    ///
+    /// 日付をdays日前に戻す
+    ///
+    pub fn sub_days(&mut self, days: i32) {
+        self.add_days(-days);
+    }
 
-        match greater_self {
-            std::cmp::Ordering::Less => {
-                let mut diff = MONTH[self.month as usize] - self.day as i32;
-                for month_index in (self.month + 1)..date2.month {
-                    diff += MONTH[month_index as usize];
-                }
-                diff + date2.day as i32
-            }
-            std::cmp::Ordering::Equal => {
-                let diff = if self.day > date2.day {
-                    self.day - date2.day
-                } else {
-                    date2.day - self.day
-                };
+    pub fn sub_days_chain(mut self, days: i32) -> Self {
+        self.sub_days(days);
+        self
+    }
 
-                diff as i32
-            }
-            std::cmp::Ordering::Greater => {
-                let mut diff = MONTH[date2.month as usize] - date2.day as i32;
-                for month_index in (date2.month + 1)..self.month {
-                    diff += MONTH[month_index as usize];
-                }
-                -(diff + self.day as i32)
-            }
-        }
+    ///
+    /// self -> 7/1
+    /// date2 -> 7/8
+    /// return 7
+    ///
+    /// date2の方が前なら負になる。年や月をまたいでも数える
+    ///
+    pub fn diff_days(&self, date2: &Self) -> i32 {
+        (date2.to_serial_day() - self.to_serial_day()) as i32
     }
-    
+
     pub fn rental_limit_type(&self, limit: &GensoDate) -> Option<RentalLimit> {
-        let day_diff = self.diff_day(&limit);
+        let day_diff = self.diff_days(&limit);
 
         if day_diff >= 0 {
             if day_diff == 0 {
@@ -814,24 +1005,15 @@ impl GensoDate {
         }
     }
 
+    ///
+    /// selfがdateより後の日ならtrue
+    ///
     pub fn is_past(&self, date: &GensoDate) -> bool {
-        match self.season.cmp(&date.season) {
-            std::cmp::Ordering::Less => false,
-            std::cmp::Ordering::Greater => true,
-            std::cmp::Ordering::Equal => match self.month.cmp(&date.month) {
-                std::cmp::Ordering::Less => false,
-                std::cmp::Ordering::Greater => true,
-                std::cmp::Ordering::Equal => match self.day.cmp(&date.day) {
-                    std::cmp::Ordering::Less | std::cmp::Ordering::Equal => false,
-                    std::cmp::Ordering::Greater => true,
-                },
-            },
-        }
+        self > date
     }
 
     pub fn is_week_first(&self) -> bool {
-        let diff = self.diff_day(&GensoDate::new(112, 7, 23));
-	println!("diff -> {}", diff);
+        let diff = self.diff_days(&GensoDate::new(112, 7, 23));
         diff % 7 == 0
     }
 
@@ -901,7 +1083,7 @@ impl ScenarioTable {
         if let Some(s) = self.scenario_table.get(&date) {
             Some(s.to_string())
         } else {
-            println!("Error: Invalid Date => {:?}", date);
+            log_error!("no scenario for the date: {:?}", date);
             None
         }
     }
@@ -910,16 +1092,22 @@ impl ScenarioTable {
         if let Some(s) = self.general_scenario.get(id) {
             Some(s.to_string())
         } else {
-            println!("Error: Invalid General Scenario ID => {:?}", id);
+            log_error!("invalid general scenario id: {:?}", id);
             None
         }
     }
 }
 
+///
+/// ## front
+/// 入り口から近い、目に付きやすい棚かどうか
+///
 #[derive(Debug, Deserialize, Clone)]
 pub struct BookShelfInformation {
     billing_number_begin: u16,
     billing_number_end: u16,
+    #[serde(default)]
+    front: bool,
 }
 
 impl BookShelfInformation {
@@ -927,9 +1115,26 @@ impl BookShelfInformation {
         BookShelfInformation {
             billing_number_begin: begin,
             billing_number_end: end,
+            front: false,
         }
     }
 
+    pub fn is_front(&self) -> bool {
+        self.front
+    }
+
+    pub fn set_front(&mut self, front: bool) {
+        self.front = front;
+    }
+
+    pub fn get_begin_number(&self) -> u16 {
+        self.billing_number_begin
+    }
+
+    pub fn get_end_number(&self) -> u16 {
+        self.billing_number_end
+    }
+
     pub fn contains_number(&self, inquire_number: u16) -> bool {
         self.billing_number_begin <= inquire_number && inquire_number <= self.billing_number_end
     }
@@ -1030,6 +1235,15 @@ impl AdAgencyCostTable {
     }
 }
 
+///
+/// # 曜日ごとの客足
+///
+/// ## weekday_curve, weekend_curve
+/// 平日と土日の、一時間ごとの客足の倍率
+///
+/// ## festivals
+/// 客足が跳ね上がる祭りの日
+///
 pub struct DailyCustomerDist {
     sun: Clock,
     mon: Clock,
@@ -1038,11 +1252,42 @@ pub struct DailyCustomerDist {
     thu: Clock,
     fri: Clock,
     sat: Clock,
+    weekday_curve: Vec<f32>,
+    weekend_curve: Vec<f32>,
+    festivals: Vec<crowd_density::FestivalDay>,
 }
 
 impl DailyCustomerDist {
     pub fn from_toml_file(ctx: &mut ggez::Context, path: &str) -> Self {
         let root = parse_toml_file!(ctx, path);
+
+        let parse_curve = |key: &str| -> Vec<f32> {
+            match root.get("arrival-curve").and_then(|curve| curve.get(key)) {
+                Some(curve) => curve
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|scale| scale.as_float().unwrap() as f32)
+                    .collect(),
+                None => Vec::new(),
+            }
+        };
+
+        let festivals = match root.get("festival") {
+            Some(array) => array
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|elem| crowd_density::FestivalDay {
+                    name: elem["name"].as_str().unwrap().to_string(),
+                    month: elem["month"].as_integer().unwrap() as u8,
+                    day: elem["day"].as_integer().unwrap() as u8,
+                    scale: elem["scale"].as_float().unwrap() as f32,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
         DailyCustomerDist {
             sun: root["sun"].as_integer().unwrap() as Clock,
             mon: root["mon"].as_integer().unwrap() as Clock,
@@ -1051,11 +1296,53 @@ impl DailyCustomerDist {
             thu: root["thu"].as_integer().unwrap() as Clock,
             fri: root["fri"].as_integer().unwrap() as Clock,
             sat: root["sat"].as_integer().unwrap() as Clock,
+            weekday_curve: parse_curve("weekday"),
+            weekend_curve: parse_curve("weekend"),
+            festivals: festivals,
         }
     }
 
+    ///
+    /// 0が日曜、6が土曜
+    ///
+    fn weekday_offset(day: &GensoDate) -> i32 {
+        GensoDate::new(112, 7, 23).diff_days(day) % 7
+    }
+
+    ///
+    /// 一週間をならした、客が来る間隔
+    ///
+    fn reference_dist(&self) -> Clock {
+        (self.sun + self.mon + self.tue + self.wed + self.thu + self.fri + self.sat) / 7
+    }
+
+    ///
+    /// その日の客足の曲線。曜日の基準に、平日か土日かの曲線と祭りの倍率を重ねる
+    ///
+    pub fn get_arrival_curve(&self, day: &GensoDate) -> crowd_density::ArrivalCurve {
+        let offset = DailyCustomerDist::weekday_offset(day);
+        let hourly = if offset == 0 || offset == 6 {
+            self.weekend_curve.clone()
+        } else {
+            self.weekday_curve.clone()
+        };
+
+        let festival = self
+            .festivals
+            .iter()
+            .find(|festival| festival.month == day.month && festival.day == day.day)
+            .cloned();
+
+        crowd_density::ArrivalCurve::new(
+            self.get_customer_dist_default(day),
+            self.reference_dist(),
+            hourly,
+            festival,
+        )
+    }
+
     pub fn get_customer_dist_default(&self, day: &GensoDate) -> Clock {
-        let offset = GensoDate::new(112, 7, 23).diff_day(day) % 7;
+        let offset = DailyCustomerDist::weekday_offset(day);
         match offset {
             0 => self.sun,
             1 => self.mon,
@@ -1065,13 +1352,23 @@ impl DailyCustomerDist {
             5 => self.fri,
             6 => self.sat,
             _ => {
-                eprintln!("Error offset => {}, {:?}", offset, day);
+                log_warn!("invalid day offset {}: {:?}", offset, day);
                 self.sun
             }
         }
     }
 }
 
+///
+/// 熟練の店番で作り足す客の名前の数
+///
+pub const PROCEDURAL_CUSTOMER_COUNT: usize = 30;
+
+///
+/// 熟練の店番で作り足す本の数
+///
+pub const PROCEDURAL_BOOK_COUNT: usize = 40;
+
 #[derive(Deserialize)]
 pub struct RawConfigFile {
     texture_paths: Vec<String>,
@@ -1087,6 +1384,10 @@ pub struct RawConfigFile {
     ad_agency_cost_table: HashMap<String, u32>,
     ad_agency_gain_table: HashMap<String, u32>,
     daily_customer_dist_path: String,
+    #[serde(default)]
+    name_bank_path: Option<String>,
+    #[serde(default)]
+    scene_bgm: HashMap<String, String>,
 }
 
 impl RawConfigFile {
@@ -1110,13 +1411,22 @@ pub struct GameResource {
     books_information: Vec<BookInformation>,
     map_data: Vec<MapConstractData>,
     scenario_table: ScenarioTable,
-    sounds: Vec<sound::SoundData>,
+    sounds: Vec<Option<sound::SoundData>>,
     bgm_manager: sound::SoundManager,
     se_manager: sound::SoundManager,
     bgm_table: HashMap<SoundID, Option<sound::SoundHandler>>,
+    positional_se: positional_audio::PositionalAudio,
+    ambient_se: HashMap<SoundID, sound::SoundHandler>,
+    scene_bgm: HashMap<scene::SceneID, SoundID>,
+    master_volume: f32,
     ad_info: AdCostTable,
     ad_agency_info: AdAgencyCostTable,
     daily_customer_dist: DailyCustomerDist,
+    name_bank: Option<name_generator::NameBank>,
+    base_customers_name: Vec<String>,
+    base_books_information: Vec<BookInformation>,
+    procedural_seed: Option<u64>,
+    rng: game_rng::GameRng,
 }
 
 impl GameResource {
@@ -1132,40 +1442,49 @@ impl GameResource {
         let mut sounds = Vec::new();
         let mut texture_paths_map = HashMap::new();
 
-        print!("Setup textures delay loading ... ");
         for (index, texture_path) in src_file.texture_paths.iter().enumerate() {
             texture_paths_map.insert(
                 TextureID::from_u32(index as u32).unwrap(),
                 texture_path.clone(),
             );
         }
-        println!("done");
+        log_debug!("{} textures are set up for delay loading", texture_paths_map.len());
 
         for font_path in &src_file.font_paths {
-            print!("Loading font {}...", font_path);
             fonts.push(ggraphics::Font::new(ctx, font_path).unwrap());
-            println!(" done!");
+            log_debug!("loaded font {}", font_path);
         }
 
         for sb_data in &src_file.sprite_batch_information {
-            print!("Loading font {}...", sb_data.path);
             sprite_batchs.push(TileBatch::new(
                 ggraphics::Image::new(ctx, &sb_data.path).unwrap(),
                 numeric::Vector2u::new(sb_data.sprite_x_size as u32, sb_data.sprite_y_size as u32),
                 numeric::Point2f::new(0.0, 0.0),
                 0,
             ));
-            println!(" done!");
+            log_debug!("loaded sprite batch {}", sb_data.path);
         }
 
+        // 読めなかった音は鳴らさずに続ける。SoundIDと並びを合わせるため、Noneで埋めておく
         for sound_path in &src_file.sound_file_path {
-            let sound_data = sound::SoundData::new(ctx, sound_path).unwrap();
-            println!(
-                "sound path -> {}, canplay? => {:?}",
-                sound_path,
-                sound_data.can_play()
-            );
-            sounds.push(sound_data);
+            match sound::SoundData::new(ctx, sound_path) {
+                Ok(sound_data) => {
+                    log_debug!(
+                        "loaded sound {}, can play: {:?}",
+                        sound_path,
+                        sound_data.can_play()
+                    );
+                    sounds.push(Some(sound_data));
+                }
+                Err(e) => {
+                    recoverable_error::report(recoverable_error::RecoverableError::new(
+                        recoverable_error::ErrorSource::Sound,
+                        "音ガ読メマセン",
+                        &format!("{}\n{}", sound_path, e),
+                    ));
+                    sounds.push(None);
+                }
+            }
         }
 
         let scenario_table = ScenarioTable::new(ctx, &src_file.scenario_table_path);
@@ -1173,18 +1492,34 @@ impl GameResource {
         let daily_customer_dist =
             DailyCustomerDist::from_toml_file(ctx, &src_file.daily_customer_dist_path);
 
+        let name_bank = src_file.name_bank_path.as_ref().map(|path| {
+            let s = util::read_from_resources_as_string(ctx, path);
+            match name_generator::NameBank::from_toml_str(&s) {
+                Ok(bank) => bank,
+                Err(e) => panic!("Failed to parse toml: {}", e),
+            }
+        });
+
         let mut bgm_table: HashMap<SoundID, Option<sound::SoundHandler>> = HashMap::new();
         bgm_table.insert(SoundID::Title, None);
         bgm_table.insert(SoundID::ScenarioBGM, None);
         bgm_table.insert(SoundID::ShopBGM, None);
         bgm_table.insert(SoundID::EndBGM, None);
 
+        // 場面ごとの曲に選ばれた音は、BGMとして鳴らせるようにする
+        let scene_bgm = parse_scene_bgm_table(&src_file.scene_bgm);
+        for sound_id in scene_bgm.values() {
+            bgm_table.entry(sound_id.clone()).or_insert(None);
+        }
+
         GameResource {
             texture_resource_paths: texture_paths_map,
             textures: textures,
             fonts: fonts,
 
             tile_batchs: sprite_batchs,
+            base_customers_name: src_file.customers_name.clone(),
+            base_books_information: src_file.books_information.clone(),
             customers_name: src_file.customers_name,
             books_information: src_file.books_information,
             map_data: src_file.map_information,
@@ -1193,24 +1528,145 @@ impl GameResource {
             bgm_manager: sound::SoundManager::new(),
             se_manager: sound::SoundManager::new(),
             bgm_table: bgm_table,
+            positional_se: positional_audio::PositionalAudio::new(),
+            ambient_se: HashMap::new(),
+            scene_bgm: scene_bgm,
+            master_volume: 1.0,
             ad_info: AdCostTable::from_data(src_file.ad_cost_table, src_file.ad_gain_table),
             ad_agency_info: AdAgencyCostTable::from_data(
                 src_file.ad_agency_cost_table,
                 src_file.ad_agency_gain_table,
             ),
             daily_customer_dist: daily_customer_dist,
+            name_bank: name_bank,
+            procedural_seed: None,
+            rng: game_rng::GameRng::from_new_seed(),
+        }
+    }
+
+    ///
+    /// 熟練の店番では、語の蔵から客の名前と本の題名を作り足す
+    ///
+    /// 同じseedからは同じ名前と題名が出来るので、続きから遊んでも貸し出した本の題名は変わらない。
+    /// Noneを渡すと、作り足した名前と題名を片付ける
+    ///
+    pub fn setup_procedural_catalog(&mut self, seed: Option<u64>) {
+        if self.procedural_seed == seed {
+            return;
+        }
+        self.procedural_seed = seed;
+
+        self.customers_name = self.base_customers_name.clone();
+        self.books_information = self.base_books_information.clone();
+
+        let (seed, bank) = match (seed, self.name_bank.as_ref()) {
+            (Some(seed), Some(bank)) => (seed, bank.clone()),
+            _ => return,
+        };
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut generator = name_generator::NameGenerator::new(
+            bank,
+            self.base_books_information
+                .iter()
+                .map(|book| book.name.clone())
+                .collect(),
+            self.base_customers_name.clone(),
+        );
+
+        for _ in 0..PROCEDURAL_CUSTOMER_COUNT {
+            if let Some(name) = generator.customer_name(&mut rng) {
+                self.customers_name.push(name);
+            }
+        }
+
+        let mut billing_number = self
+            .base_books_information
+            .iter()
+            .map(|book| book.billing_number)
+            .max()
+            .unwrap_or(0);
+        for _ in 0..PROCEDURAL_BOOK_COUNT {
+            let title = match generator.book_title(&mut rng) {
+                Some(title) => title,
+                None => break,
+            };
+
+            // 頁数や大きさ、値段は、元からある本を手本にする
+            let template = match self.base_books_information.choose(&mut rng) {
+                Some(template) => template,
+                None => break,
+            };
+            billing_number += 1;
+            let mut book_info = BookInformation::new(
+                title,
+                template.pages,
+                template.size.clone(),
+                billing_number,
+                template.base_price,
+            );
+            book_info.set_reading(generator.title_reading(&book_info.name));
+            self.books_information.push(book_info);
         }
+
+        log_info!(
+            "procedural catalog: {} customers, {} books",
+            self.customers_name.len(),
+            self.books_information.len()
+        );
     }
 
     fn load_texture_delay(&mut self, ctx: &mut ggez::Context, id: TextureID) -> ggraphics::Image {
-        let path = self
-            .texture_resource_paths
-            .get(&id)
-            .expect("Delay texture load: Invalid TextureID");
-        print!("delay texture loading -> {} ... ", path);
-        let texture = ggraphics::Image::new(ctx, path).expect("Delay texture load: Invalid Path");
+        let path = match self.texture_resource_paths.get(&id) {
+            Some(path) => path.clone(),
+            None => {
+                return self.missing_texture(
+                    ctx,
+                    id,
+                    recoverable_error::RecoverableError::new(
+                        recoverable_error::ErrorSource::Texture,
+                        "画像ガ登録サレテイマセン",
+                        &format!("TextureID::{:?} has no path in game_data.toml", id),
+                    ),
+                )
+            }
+        };
+
+        let texture = match ggraphics::Image::new(ctx, &path) {
+            Ok(texture) => texture,
+            Err(e) => {
+                return self.missing_texture(
+                    ctx,
+                    id,
+                    recoverable_error::RecoverableError::new(
+                        recoverable_error::ErrorSource::Texture,
+                        "画像ガ読メマセン",
+                        &format!("TextureID::{:?}\n{}\n{}", id, path, e),
+                    ),
+                );
+            }
+        };
+        self.textures.insert(id, texture.clone());
+        log_debug!("loaded texture {}", path);
+
+        texture
+    }
+
+    ///
+    /// 読めなかった画像の代わりに、目立つ色の小さな画像を使う
+    /// 同じ画像で何度も知らせないよう、代わりの画像も覚えておく
+    ///
+    fn missing_texture(
+        &mut self,
+        ctx: &mut ggez::Context,
+        id: TextureID,
+        error: recoverable_error::RecoverableError,
+    ) -> ggraphics::Image {
+        recoverable_error::report(error);
+
+        let texture =
+            ggraphics::Image::solid(ctx, 16, ggraphics::Color::from_rgba_u32(0xff00ffff)).unwrap();
         self.textures.insert(id, texture.clone());
-        println!("done!");
 
         texture
     }
@@ -1232,25 +1688,36 @@ impl GameResource {
         }
     }
 
-    pub fn get_map_data(&self, _id: u32) -> Option<MapConstractData> {
-        for map_data in &self.map_data {
-            println!("FIXME!!");
-            return Some(map_data.clone());
-        }
+    ///
+    /// idのマップを返す。見つからなければ最初のマップを返す
+    ///
+    pub fn get_map_data(&self, id: u32) -> Option<MapConstractData> {
+        self.map_data
+            .iter()
+            .find(|map_data| map_data.id == id)
+            .or_else(|| self.map_data.first())
+            .cloned()
+    }
 
-        None
+    ///
+    /// 遊びの中で使う乱数。同じseedからは同じ客や本が来る
+    ///
+    pub fn rng(&mut self) -> &mut game_rng::GameRng {
+        &mut self.rng
     }
 
-    pub fn book_random_select(&self) -> &BookInformation {
-        &self
-            .books_information
-            .get(rand::random::<usize>() % self.books_information.len())
-            .unwrap()
+    pub fn reseed_rng(&mut self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+
+    pub fn book_random_select(&mut self) -> &BookInformation {
+        let index = self.rng.below(self.books_information.len());
+        self.books_information.get(index).unwrap()
     }
 
     pub fn search_book_with_title(&self, title: &str) -> Option<&BookInformation> {
         for book_info in self.books_information.iter() {
-            if book_info.name == title {
+            if book_info.is_called(title) {
                 return Some(book_info);
             }
         }
@@ -1262,11 +1729,13 @@ impl GameResource {
         self.books_information.iter()
     }
 
-    pub fn customer_random_select(&self) -> &str {
-        &self
-            .customers_name
-            .get(rand::random::<usize>() % self.customers_name.len())
-            .unwrap()
+    pub fn iter_customers_name(&self) -> std::slice::Iter<String> {
+        self.customers_name.iter()
+    }
+
+    pub fn customer_random_select(&mut self) -> &str {
+        let index = self.rng.below(self.customers_name.len());
+        self.customers_name.get(index).unwrap()
     }
 
     pub fn ref_tile_batch(&self, id: TileBatchTextureID) -> TileBatch {
@@ -1295,27 +1764,114 @@ impl GameResource {
     ) {
         if let Some(sound_handler) = self.bgm_table.get(&sound_id) {
             if sound_handler.is_none() {
-                let sound_data = self.sounds.get(sound_id.clone() as usize).unwrap();
-                let handler = self.bgm_manager.play(ctx, sound_data.clone(), flags);
+                let sound_data = match self.ref_sound_data(sound_id.clone()) {
+                    Some(sound_data) => sound_data.clone(),
+                    None => return,
+                };
+                let handler = self.bgm_manager.play(ctx, sound_data, flags);
                 self.bgm_table.insert(sound_id.clone(), Some(handler));
             }
         }
     }
 
+    ///
+    /// 読めなかった音はNone。読めなかったことは読み込んだときに知らせている
+    ///
+    fn ref_sound_data(&self, sound_id: SoundID) -> Option<&sound::SoundData> {
+        self.sounds
+            .get(sound_id as usize)
+            .and_then(|sound_data| sound_data.as_ref())
+    }
+
     pub fn play_sound_as_se(
         &mut self,
         ctx: &mut ggez::Context,
         sound_id: SoundID,
         flags: Option<sound::SoundPlayFlags>,
-    ) -> sound::SoundHandler {
-        let sound_data = self.sounds.get(sound_id as usize).unwrap();
-        self.se_manager.play(ctx, sound_data.clone(), flags)
+    ) -> Option<sound::SoundHandler> {
+        let sound_data = self.ref_sound_data(sound_id)?.clone();
+        Some(self.se_manager.play(ctx, sound_data, flags))
     }
 
     pub fn is_se_playing(&self, handler: sound::SoundHandler) -> bool {
         self.ref_se(handler).playing()
     }
 
+    ///
+    /// マップ上のmap_positionで鳴る効果音を再生する
+    /// 音量はカメラの中心からの距離で決まり、update_positional_soundで追従する
+    ///
+    pub fn play_sound_at(
+        &mut self,
+        ctx: &mut ggez::Context,
+        sound_id: SoundID,
+        map_position: numeric::Point2f,
+        base_volume: f32,
+    ) -> Option<sound::SoundHandler> {
+        let volume = self.positional_se.volume_at(map_position, base_volume);
+        let handler = self.play_sound_as_se(
+            ctx,
+            sound_id,
+            Some(sound::SoundPlayFlags::new(10, 1.0, false, volume)),
+        )?;
+        self.positional_se.add(handler.clone(), map_position, base_volume);
+        Some(handler)
+    }
+
+    ///
+    /// カメラの位置から、鳴っている位置付きの効果音の音量を計算し直す
+    ///
+    pub fn update_positional_sound(&mut self, camera: &numeric::Rect) {
+        self.positional_se.set_listener(camera);
+
+        let se_manager = &mut self.se_manager;
+        // ggezの音源は左右の振り分けを持たないため、今は音量だけを反映する
+        self.positional_se.update(|handler, volume, _pan| {
+            if !se_manager.ref_sound(handler.clone()).playing() {
+                return false;
+            }
+
+            se_manager.ref_sound_mut(handler).set_volume(volume);
+            true
+        });
+    }
+
+    ///
+    /// 繰り返し鳴らしている環境音の音量を変える。0になった音は止め、鳴っていない音は鳴らし始める
+    ///
+    pub fn set_ambient_volume(&mut self, ctx: &mut ggez::Context, sound_id: SoundID, volume: f32) {
+        if volume <= 0.0 {
+            if let Some(handler) = self.ambient_se.remove(&sound_id) {
+                self.se_manager.stop(ctx, handler);
+            }
+            return;
+        }
+
+        if let Some(handler) = self.ambient_se.get(&sound_id) {
+            if self.se_manager.ref_sound(handler.clone()).playing() {
+                self.se_manager
+                    .ref_sound_mut(handler.clone())
+                    .set_volume(volume);
+                return;
+            }
+        }
+
+        let handler = self.play_sound_as_se(
+            ctx,
+            sound_id.clone(),
+            Some(sound::SoundPlayFlags::new(0, 1.0, true, volume)),
+        );
+        if let Some(handler) = handler {
+            self.ambient_se.insert(sound_id, handler);
+        }
+    }
+
+    pub fn stop_ambient_sound(&mut self, ctx: &mut ggez::Context) {
+        for (_, handler) in self.ambient_se.drain() {
+            self.se_manager.stop(ctx, handler);
+        }
+    }
+
     pub fn is_bgm_playing(&self, handler: sound::SoundHandler) -> bool {
         self.ref_se(handler).playing()
     }
@@ -1329,6 +1885,22 @@ impl GameResource {
         }
     }
 
+    ///
+    /// その場面で流す曲。game_data.tomlのscene_bgmに無ければ、標準の曲
+    ///
+    pub fn get_scene_bgm(&self, scene: scene::SceneID) -> Option<SoundID> {
+        self.scene_bgm
+            .get(&scene)
+            .cloned()
+            .or_else(|| default_scene_bgm(scene))
+    }
+
+    pub fn stop_scene_bgm(&mut self, ctx: &mut ggez::Context, scene: scene::SceneID) {
+        if let Some(sound_id) = self.get_scene_bgm(scene) {
+            self.stop_bgm(ctx, sound_id);
+        }
+    }
+
     pub fn stop_se(&mut self, ctx: &mut ggez::Context, handler: sound::SoundHandler) {
         self.bgm_manager.stop(ctx, handler);
     }
@@ -1349,12 +1921,25 @@ impl GameResource {
         self.se_manager.ref_sound_mut(handler)
     }
 
+    ///
+    /// BGMと効果音の両方に掛ける音量。次にchange_bgm_volume, change_se_volumeを呼んだときから効く
+    ///
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume;
+    }
+
+    pub fn get_master_volume(&self) -> f32 {
+        self.master_volume
+    }
+
     pub fn change_bgm_volume(&mut self, volume: f32) {
-        self.bgm_manager.change_global_volume(volume);
+        self.bgm_manager
+            .change_global_volume(volume * self.master_volume);
     }
 
     pub fn change_se_volume(&mut self, volume: f32) {
-        self.se_manager.change_global_volume(volume);
+        self.se_manager
+            .change_global_volume(volume * self.master_volume);
     }
 
     pub fn get_default_ad_cost(&self, ty: crate::object::scenario_object::SuzunaAdType) -> u32 {
@@ -1379,6 +1964,10 @@ impl GameResource {
     pub fn get_todays_customer_dist(&self, date: &GensoDate) -> Clock {
         self.daily_customer_dist.get_customer_dist_default(date)
     }
+
+    pub fn get_todays_arrival_curve(&self, date: &GensoDate) -> crowd_density::ArrivalCurve {
+        self.daily_customer_dist.get_arrival_curve(date)
+    }
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -1443,7 +2032,7 @@ impl MouseInformation {
         match self.last_clicked.get(&button) {
             Some(x) => x.point,
             None => {
-                eprintln!("No such a mouse button");
+                log_warn!("no such a mouse button");
                 numeric::Point2f::new(0.0, 0.0)
             }
         }
@@ -1455,7 +2044,7 @@ impl MouseInformation {
             .insert(button, MouseActionRecord::new(point, t))
             == None
         {
-            eprintln!("Not basic button is clicked.");
+            log_warn!("not basic button is clicked");
         }
     }
 
@@ -1463,7 +2052,7 @@ impl MouseInformation {
         match self.last_dragged.get(&button) {
             Some(x) => x.point,
             None => {
-                eprintln!("No such a mouse button");
+                log_warn!("no such a mouse button");
                 numeric::Point2f::new(0.0, 0.0)
             }
         }
@@ -1475,7 +2064,7 @@ impl MouseInformation {
             .insert(button, MouseActionRecord::new(point, t))
             .is_none()
         {
-            eprintln!("No such a mouse button")
+            log_warn!("no such a mouse button")
         }
     }
 
@@ -1483,7 +2072,7 @@ impl MouseInformation {
         match self.last_down.get(&button) {
             Some(x) => x.point,
             None => {
-                eprintln!("No such a mouse button");
+                log_warn!("no such a mouse button");
                 numeric::Point2f::new(0.0, 0.0)
             }
         }
@@ -1495,7 +2084,7 @@ impl MouseInformation {
             .insert(button, MouseActionRecord::new(point, t))
             .is_none()
         {
-            eprintln!("No such a mouse button")
+            log_warn!("no such a mouse button")
         }
     }
 
@@ -1503,7 +2092,7 @@ impl MouseInformation {
         match self.last_up.get(&button) {
             Some(x) => x.point,
             None => {
-                eprintln!("No such a mouse button");
+                log_warn!("no such a mouse button");
                 numeric::Point2f::new(0.0, 0.0)
             }
         }
@@ -1515,7 +2104,7 @@ impl MouseInformation {
             .insert(button, MouseActionRecord::new(point, t))
             .is_none()
         {
-            eprintln!("No such a mouse button")
+            log_warn!("no such a mouse button")
         }
     }
 
@@ -1523,7 +2112,7 @@ impl MouseInformation {
         match self.dragging.get(&button) {
             Some(x) => *x,
             None => {
-                eprintln!("No such a mouse button");
+                log_warn!("no such a mouse button");
                 false
             }
         }
@@ -1531,24 +2120,39 @@ impl MouseInformation {
 
     pub fn update_dragging(&mut self, button: MouseButton, drag: bool) {
         if self.dragging.insert(button, drag).is_none() {
-            eprintln!("No such a mouse button")
+            log_warn!("no such a mouse button")
         }
     }
 }
 
+///
+/// # 熟練の店番の設定
+///
+/// ## procedural_seed
+/// 客の名前と本の題名を作るときのseed
+///
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TimeAttackModeData {
     limit: GensoDate,
+    #[serde(default)]
+    procedural_seed: u64,
 }
 
 impl TimeAttackModeData {
     pub fn new(limit: GensoDate) -> Self {
-        TimeAttackModeData { limit: limit }
+        TimeAttackModeData {
+            limit: limit,
+            procedural_seed: game_rng::new_seed(),
+        }
     }
 
     pub fn get_limit(&self) -> &GensoDate {
         &self.limit
     }
+
+    pub fn get_procedural_seed(&self) -> u64 {
+        self.procedural_seed
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1564,7 +2168,7 @@ impl GameMode {
 
     pub fn time_attack() -> Self {
         Self::TimeAttack(TimeAttackModeData::new(
-            GensoDate::new(112, 7, 23).add_day_chain(60),
+            GensoDate::new(112, 7, 23).add_days_chain(60),
         ))
     }
 
@@ -1596,6 +2200,8 @@ pub struct TaskResult {
     pub not_shelved_books: Vec<BookInformation>, // 返却済, 未配架
     pub borrowing_books: Vec<BookInformation>,   // 貸出中
     pub total_money: i32,                        // 稼いだ金額
+    #[serde(default)]
+    pub record_scores: Vec<record_validation::RecordPageScore>, // 署名した頁の採点
 }
 
 impl TaskResult {
@@ -1605,6 +2211,7 @@ impl TaskResult {
             not_shelved_books: Vec::new(),
             total_money: 1000,
             borrowing_books: Vec::new(),
+            record_scores: Vec::new(),
         }
     }
 
@@ -1615,6 +2222,8 @@ impl TaskResult {
         self.borrowing_books
             .extend(task_result.borrowing_books.clone());
         self.total_money += task_result.total_money;
+        self.record_scores
+            .extend(task_result.record_scores.clone());
 
         self
     }
@@ -1624,6 +2233,7 @@ impl TaskResult {
         self.not_shelved_books.clear();
         self.borrowing_books.clear();
         self.total_money = 0;
+        self.record_scores.clear();
 
         self
     }
@@ -1654,11 +2264,66 @@ impl SceneStack {
     }
 }
 
+pub const REGULAR_CLOSING_HOUR: u8 = 18;
+pub const LATE_NIGHT_CLOSING_HOUR: u8 = 20;
+
+///
+/// 夜間営業中に受け付けた仕事の料金の倍率
+///
+pub const LATE_NIGHT_PAY_RATE: f32 = 1.5;
+
+///
+/// 夜間営業中、一分ごとに溜まる疲労
+///
+pub const LATE_NIGHT_FATIGUE_PER_MINUTE: f32 = 0.25;
+
+pub const FATIGUE_MAX: f32 = 100.0;
+
+///
+/// 体力がこれを下回ると、足取りが重くなり手元も揺れる
+///
+pub const STAMINA_LOW: f32 = 30.0;
+
+///
+/// 走っている間、一フレームごとに溜まる疲労
+///
+pub const RUNNING_FATIGUE_PER_FRAME: f32 = 0.01;
+
+///
+/// これより多く本を抱えて歩くと疲れる
+///
+pub const CARRY_FREE_BOOKS: usize = 5;
+pub const CARRYING_FATIGUE_PER_BOOK: f32 = 0.002;
+
+///
+/// これより速く動いていれば走っているとみなす
+///
+const RUNNING_SPEED: f32 = 4.0;
+
+///
+/// 一晩寝て回復する疲労
+///
+const OVERNIGHT_FATIGUE_RECOVERY: f32 = 20.0;
+
+///
+/// # 鈴奈庵の状態
+///
+/// ## fatigue
+/// 小鈴の疲労。FATIGUE_MAXから引いた値が体力になる
+/// 走ったり本を抱えたり、夜間営業をしたりすると溜まり、移動速度と失敗への評判の下がり方に響く
+///
+/// ## late_night
+/// 今日は夜間営業をしているか
+///
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SuzunaAnStatus {
     pub jinyou_balance: f32,
     reputation: f32,
     pub kosuzu_hp: f32,
+    #[serde(default)]
+    fatigue: f32,
+    #[serde(default)]
+    late_night: bool,
 }
 
 pub enum ReputationEvent {
@@ -1671,6 +2336,8 @@ impl SuzunaAnStatus {
             jinyou_balance: 0.0,
             reputation: 50.0,
             kosuzu_hp: 100.0,
+            fatigue: 0.0,
+            late_night: false,
         }
     }
 
@@ -1682,30 +2349,144 @@ impl SuzunaAnStatus {
         let next = self.reputation + diff;
         self.reputation = if next > 100.0 { 100.0 } else { next };
     }
-}
 
-#[derive(Clone)]
-pub struct BorrowingInformation {
-    pub borrowing: Vec<BookInformation>,
-    pub borrower: String,
-    pub borrow_date: GensoDate,
-    pub return_date: GensoDate,
-    pub rental_limit: RentalLimit,
-}
+    ///
+    /// 客の満足に関わる出来事を評判に映す
+    ///
+    pub fn apply_satisfaction(&mut self, event: reputation::SatisfactionEvent) {
+        self.add_reputation(event.reputation_diff());
+    }
 
-impl BorrowingInformation {
-    pub fn new(
-        borrowing: Vec<BookInformation>,
-        borrower: &str,
-        borrow_date: GensoDate,
-        rental_limit: RentalLimit,
+    pub fn get_fatigue(&self) -> f32 {
+        self.fatigue
+    }
+
+    pub fn add_fatigue(&mut self, diff: f32) {
+        self.fatigue = (self.fatigue + diff).max(0.0).min(FATIGUE_MAX);
+    }
+
+    pub fn is_late_night(&self) -> bool {
+        self.late_night
+    }
+
+    pub fn start_late_night(&mut self) {
+        self.late_night = true;
+    }
+
+    pub fn closing_hour(&self) -> u8 {
+        if self.late_night {
+            LATE_NIGHT_CLOSING_HOUR
+        } else {
+            REGULAR_CLOSING_HOUR
+        }
+    }
+
+    ///
+    /// 夜間営業の倍率を掛けた料金
+    ///
+    pub fn apply_late_night_pay(&self, fee: i32) -> i32 {
+        if self.late_night {
+            (fee as f32 * LATE_NIGHT_PAY_RATE) as i32
+        } else {
+            fee
+        }
+    }
+
+    pub fn get_stamina(&self) -> f32 {
+        FATIGUE_MAX - self.fatigue
+    }
+
+    pub fn is_stamina_low(&self) -> bool {
+        self.get_stamina() < STAMINA_LOW
+    }
+
+    pub fn restore_stamina(&mut self, amount: f32) {
+        self.add_fatigue(-amount);
+    }
+
+    ///
+    /// 移動した分だけ体力を減らす。speedはその時の移動速度の大きさ
+    ///
+    pub fn drain_stamina_by_moving(&mut self, speed: f32, carrying_books: usize) {
+        if speed <= 0.0 {
+            return;
+        }
+
+        if speed > RUNNING_SPEED {
+            self.add_fatigue(RUNNING_FATIGUE_PER_FRAME);
+        }
+
+        if carrying_books > CARRY_FREE_BOOKS {
+            self.add_fatigue(
+                (carrying_books - CARRY_FREE_BOOKS) as f32 * CARRYING_FATIGUE_PER_BOOK,
+            );
+        }
+    }
+
+    ///
+    /// 疲労による移動速度の倍率。体力が少なくなると落ち始め、尽きると0.6倍になる
+    ///
+    pub fn fatigue_speed_rate(&self) -> f32 {
+        if !self.is_stamina_low() {
+            return 1.0;
+        }
+
+        1.0 - (0.4 * (1.0 - (self.get_stamina() / STAMINA_LOW)))
+    }
+
+    ///
+    /// 体力が少ないときにドラッグ中の手元に加える揺れ
+    ///
+    pub fn drag_wobble(&self, t: Clock) -> numeric::Vector2f {
+        if !self.is_stamina_low() {
+            return numeric::Vector2f::new(0.0, 0.0);
+        }
+
+        let amplitude = 3.0 * (1.0 - (self.get_stamina() / STAMINA_LOW));
+        numeric::Vector2f::new(
+            amplitude * (t as f32 * 0.3).sin(),
+            amplitude * (t as f32 * 0.23).cos(),
+        )
+    }
+
+    ///
+    /// 疲労による、失敗で下がる評判の倍率。疲労が最大のとき2倍になる
+    ///
+    pub fn fatigue_penalty_rate(&self) -> f32 {
+        1.0 + (self.fatigue / FATIGUE_MAX)
+    }
+
+    ///
+    /// 日を跨いだときに呼ぶ。夜間営業を終え、疲労を少し回復する
+    ///
+    pub fn pass_night(&mut self) {
+        self.late_night = false;
+        self.add_fatigue(-OVERNIGHT_FATIGUE_RECOVERY);
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BorrowingInformation {
+    pub borrowing: Vec<BookInformation>,
+    pub borrower: String,
+    pub borrow_date: GensoDate,
+    pub return_date: GensoDate,
+    pub rental_limit: RentalLimit,
+}
+
+impl BorrowingInformation {
+    pub fn new(
+        borrowing: Vec<BookInformation>,
+        borrower: &str,
+        borrow_date: GensoDate,
+        rental_limit: RentalLimit,
     ) -> Self {
         let mut return_date = borrow_date.clone();
 
         match rental_limit {
-            RentalLimit::Today => return_date.add_day(0),
-            RentalLimit::ShortTerm => return_date.add_day(7),
-            RentalLimit::LongTerm => return_date.add_day(14),
+            RentalLimit::Today => return_date.add_days(0),
+            RentalLimit::ShortTerm => return_date.add_days(7),
+            RentalLimit::LongTerm => return_date.add_days(14),
         }
 
         BorrowingInformation {
@@ -1717,13 +2498,27 @@ impl BorrowingInformation {
         }
     }
 
+    ///
+    /// 今日を基準に、borrowerの貸出の依頼をその日の難しさで生成する
+    ///
+    pub fn new_random(
+        game_data: &mut GameResource,
+        difficulty: &difficulty::Difficulty,
+        borrower: &str,
+        today: &GensoDate,
+    ) -> Self {
+        difficulty
+            .request_generator()
+            .generate_borrowing(
+                &mut game_data.rng,
+                game_data.books_information.as_slice(),
+                borrower,
+                today,
+            )
+    }
+
     pub fn calc_fee(&self) -> i32 {
-        (self
-            .borrowing
-            .iter()
-            .map(|info| info.base_price)
-            .fold(0, |sum, price| sum + price) as f32
-            * self.rental_limit.fee_rate()) as i32
+        economy::rental_fee(&self.borrowing, &self.rental_limit)
     }
 }
 
@@ -1750,24 +2545,24 @@ impl ReturnBookInformation {
         }
     }
 
+    ///
+    /// 今日を基準に、返却の依頼をその日の難しさで生成する
+    ///
     pub fn new_random(
-        game_data: &GameResource,
-        borrow_date: GensoDate,
-        return_date: GensoDate,
+        game_data: &mut GameResource,
+        difficulty: &difficulty::Difficulty,
+        today: &GensoDate,
     ) -> Self {
-        let borrowing_num = (rand::random::<u32>() % 5) + 1;
-        let mut borrow_books = Vec::new();
-
-        for _ in 0..borrowing_num {
-            borrow_books.push(game_data.book_random_select().clone());
-        }
-
-        Self::new(
-            borrow_books,
-            game_data.customer_random_select(),
-            borrow_date,
-            return_date,
-        )
+        let customer_name = game_data.customer_random_select().to_string();
+        difficulty
+            .request_generator()
+            .with_anomaly_rate(book_inspection::DEFAULT_ANOMALY_RATE)
+            .generate_returning(
+                &mut game_data.rng,
+                game_data.books_information.as_slice(),
+                &customer_name,
+                today,
+            )
     }
 
     pub fn get_rental_limit(&self) -> RentalLimit {
@@ -1783,12 +2578,12 @@ pub struct SuzunaBookPool {
 }
 
 impl SuzunaBookPool {
-    pub fn new(game_data: &GameResource) -> Self {
+    pub fn new(game_data: &mut GameResource) -> Self {
         let mut books = Vec::new();
 
-        for book_info in game_data.iter_available_books() {
+        for book_info in game_data.books_information.iter() {
             for _ in 0..5 {
-                let cloned = book_info.clone_with_new_id_condition();
+                let cloned = book_info.clone_with_new_id_condition(&mut game_data.rng);
                 books.push(cloned);
             }
         }
@@ -1804,35 +2599,33 @@ impl SuzunaBookPool {
         self.books.extend(book_info_vec);
     }
 
-    pub fn generate_borrowing_request(
+    ///
+    /// 題名が重複しないように1冊以上、max_books冊以下の本をプールから取り出す
+    /// プールが空の場合は空のVecを返す
+    ///
+    fn take_random_books<R: Rng>(&mut self, rng: &mut R, max_books: usize) -> Vec<BookInformation> {
+        let mut indices =
+            request_generator::RequestGenerator::new(max_books).select_book_indices(rng, &self.books);
+
+        // 後ろから取り出せばswap_removeでインデックスがずれない
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        indices
+            .iter()
+            .map(|index| self.books.swap_remove(*index))
+            .collect()
+    }
+
+    pub fn generate_borrowing_request<R: Rng>(
         &mut self,
+        rng: &mut R,
         customer_name: &str,
         borrow_date: GensoDate,
         rental_limit: RentalLimit,
+        max_books: usize,
     ) -> BorrowingInformation {
-        let mut borrowing_books: Vec<BookInformation> = Vec::new();
-        for _ in 0..((rand::random::<u32>() % 5) + 1) {
-            if self.books.is_empty() {
-                break;
-            }
-
-            let book_info = self
-                .books
-                .swap_remove(rand::random::<usize>() % self.books.len());
-
-            if borrowing_books
-                .iter()
-                .any(|info| info.name == book_info.name)
-            {
-                // 戻してloop再開
-                self.books.push(book_info);
-                continue;
-            }
+        let borrowing_books = self.take_random_books(rng, max_books);
 
-            borrowing_books.push(book_info);
-        }
-
-        println!(
+        log_debug!(
             "generated books count: {}, books_len = {}",
             borrowing_books.len(),
             self.books.len()
@@ -1841,38 +2634,19 @@ impl SuzunaBookPool {
         BorrowingInformation::new(borrowing_books, customer_name, borrow_date, rental_limit)
     }
 
-    pub fn generate_returning_request(
+    pub fn generate_returning_request<R: Rng>(
         &mut self,
+        rng: &mut R,
         customer_name: &str,
         borrow_date: GensoDate,
         rental_limit: RentalLimit,
     ) -> ReturnBookInformation {
-        let mut returning_books: Vec<BookInformation> = Vec::new();
-
-        for _ in 0..((rand::random::<u32>() % 5) + 1) {
-            if self.books.is_empty() {
-                break;
-            }
-
-            let book_info = self
-                .books
-                .swap_remove(rand::random::<usize>() % self.books.len());
-            if returning_books
-                .iter()
-                .any(|info| info.name == book_info.name)
-            {
-                // 既に同じ本を取り出している
-                self.push_book(book_info);
-                continue;
-            }
-
-            returning_books.push(book_info);
-        }
+        let returning_books = self.take_random_books(rng, request_generator::REQUEST_MAX_BOOKS);
 
         let mut return_date = borrow_date.clone();
         match rental_limit {
-            RentalLimit::ShortTerm => return_date.add_day(7),
-            RentalLimit::LongTerm => return_date.add_day(14),
+            RentalLimit::ShortTerm => return_date.add_days(7),
+            RentalLimit::LongTerm => return_date.add_days(14),
             _ => (),
         };
 
@@ -1893,42 +2667,42 @@ pub struct ReturningRequestPool {
 impl ReturningRequestPool {
     pub fn new(
         book_pool: &mut SuzunaBookPool,
-        game_data: &GameResource,
+        game_data: &mut GameResource,
         today: &GensoDate,
     ) -> Self {
         let mut returning_request = Vec::new();
 
-        let mut day = today.clone();
-        day.day -= 12;
-	
+        // 月をまたいでも正しい日付になるように戻す
+        let mut day = today.clone().sub_days_chain(12);
+
         for _ in 1..=5 {
             for _ in 1..=2 {
                 let rental_limit = RentalLimit::LongTerm;
 
+                let customer_name = game_data.customer_random_select().to_string();
                 returning_request.push(book_pool.generate_returning_request(
-                    game_data.customer_random_select(),
+                    &mut game_data.rng,
+                    &customer_name,
                     day,
                     rental_limit,
                 ));
             }
-            day.add_day(1);
+            day.add_days(1);
         }
 
         for _ in 1..=7 {
             for _ in 1..=3 {
-                let rental_limit = match rand::random::<u32>() % 2 {
-                    0 => RentalLimit::ShortTerm,
-                    1 => RentalLimit::LongTerm,
-                    _ => panic!(""),
-                };
+                let rental_limit = RentalLimit::random(&mut game_data.rng);
 
+                let customer_name = game_data.customer_random_select().to_string();
                 returning_request.push(book_pool.generate_returning_request(
-                    game_data.customer_random_select(),
+                    &mut game_data.rng,
+                    &customer_name,
                     day,
                     rental_limit,
                 ));
             }
-            day.add_day(1);
+            day.add_days(1);
         }
 
         ReturningRequestPool {
@@ -1946,7 +2720,10 @@ impl ReturningRequestPool {
         self.returning_request.push(returning_book_info);
     }
 
-    pub fn select_returning_request_random(&mut self) -> Option<ReturnBookInformation> {
+    pub fn select_returning_request_random<R: Rng>(
+        &mut self,
+        rng: &mut R,
+    ) -> Option<ReturnBookInformation> {
         let request_len = self.returning_request.len();
 
         if request_len == 0 {
@@ -1955,7 +2732,7 @@ impl ReturningRequestPool {
 
         Some(
             self.returning_request
-                .swap_remove(rand::random::<usize>() % request_len),
+                .swap_remove(rng.gen_range(0, request_len)),
         )
     }
 
@@ -1985,12 +2762,56 @@ pub struct SavableData {
     pub game_mode: GameMode,
     pub run_tutorial: bool,
     pub scenario_save_data: Option<ScenarioSceneSaveData>,
+    #[serde(default = "book_management::BookPopularityStats::new")]
+    pub book_stats: book_management::BookPopularityStats,
+    #[serde(default)]
+    pub day_modifiers: day_modifier::DayModifiers,
+    #[serde(default)]
+    pub difficulty_assist: game_system::DifficultyAssist,
+    #[serde(default)]
+    pub practice_request: Option<MistakeRecord>,
+    #[serde(default)]
+    pub reservation_ledger: reservation::ReservationLedger,
+    #[serde(default)]
+    pub inspection_record: book_inspection::InspectionRecord,
+    #[serde(default)]
+    pub wrapping_supplies: gift_wrapping::WrappingSupplies,
+    #[serde(default)]
+    pub shop_expansion: shop_expansion::ShopExpansionState,
+    #[serde(default)]
+    pub assistant: assistant::AssistantState,
+    #[serde(default)]
+    pub album: album::PhotoAlbum,
+    #[serde(default)]
+    pub book_fair: book_fair::BookFairRecord,
+    #[serde(default)]
+    pub mail_box: mail::MailBox,
+    #[serde(default)]
+    pub decision_log: decision_log::DecisionLog,
+    #[serde(default)]
+    pub membership: membership::MembershipRegistry,
+    #[serde(default)]
+    pub diary: diary::Diary,
+    #[serde(default)]
+    pub misfiled_books: auto_shelving::MisfiledBooks,
+    #[serde(default = "game_rng::new_seed")]
+    pub rng_seed: u64,
+    #[serde(default)]
+    pub economy: economy::Economy,
+    #[serde(default)]
+    pub special_customers: reputation::SpecialCustomers,
+    #[serde(default)]
+    pub paper_archive: paper_archive::PaperArchive,
 }
 
 impl SavableData {
-    pub fn new(game_data: &GameResource, game_mode: GameMode) -> Self {
+    pub fn new(game_data: &mut GameResource, game_mode: GameMode) -> Self {
         let date = GensoDate::new(112, 7, 23);
 
+        // 始めの貸出記録も、このseedから作る
+        let rng_seed = game_rng::new_seed();
+        game_data.reseed_rng(rng_seed);
+
         let mut suzuna_book_pool = SuzunaBookPool::new(game_data);
         let returning_request_pool =
             ReturningRequestPool::new(&mut suzuna_book_pool, game_data, &date);
@@ -2029,11 +2850,35 @@ impl SavableData {
             game_mode: game_mode,
             run_tutorial: true,
 	    scenario_save_data: None,
+            book_stats: book_management::BookPopularityStats::new(),
+            day_modifiers: day_modifier::DayModifiers::new(),
+            difficulty_assist: game_system::DifficultyAssist::new(),
+            practice_request: None,
+            reservation_ledger: reservation::ReservationLedger::new(),
+            inspection_record: book_inspection::InspectionRecord::new(),
+            wrapping_supplies: gift_wrapping::WrappingSupplies::new(),
+            shop_expansion: shop_expansion::ShopExpansionState::new(),
+            assistant: assistant::AssistantState::new(),
+            album: album::PhotoAlbum::new(),
+            book_fair: book_fair::BookFairRecord::new(&date),
+            mail_box: mail::MailBox::new(),
+            decision_log: decision_log::DecisionLog::new(),
+            membership: membership::MembershipRegistry::new(),
+            diary: diary::Diary::new(),
+            misfiled_books: auto_shelving::MisfiledBooks::new(),
+            rng_seed: rng_seed,
+            economy: economy::Economy::new(),
+            special_customers: reputation::SpecialCustomers::new(),
+            paper_archive: paper_archive::PaperArchive::new(),
         }
     }
 
     pub fn save(&self, slot: u8) -> Result<(), Box<dyn std::error::Error>> {
-        let mut file = File::create(&format!("./resources/save{}", slot))?;
+        self.save_to_path(&format!("./resources/save{}", slot))
+    }
+
+    pub fn save_to_path(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = File::create(path)?;
 
         file.write_all(
             crypt::crypt_str(&serde_json::to_string(self).unwrap())
@@ -2049,12 +2894,16 @@ impl SavableData {
     pub fn delete(slot: u8) {
         match std::fs::remove_file(&format!("./resources/save{}", slot)) {
             Ok(_) => (),
-            Err(e) => eprintln!("{}", e),
+            Err(e) => log_warn!("failed to delete save data {}: {}", slot, e),
         }
     }
 
     pub fn new_load(slot: u8) -> Result<SavableData, ()> {
-        let file = std::fs::File::open(&format!("./resources/save{}", slot));
+        Self::load_from_path(&format!("./resources/save{}", slot))
+    }
+
+    pub fn load_from_path(path: &str) -> Result<SavableData, ()> {
+        let file = std::fs::File::open(path);
         if file.is_err() {
             return Err(());
         }
@@ -2089,6 +2938,23 @@ impl SavableData {
         self.award_data = data.award_data;
         self.game_mode = data.game_mode;
         self.run_tutorial = data.run_tutorial;
+        self.book_stats = data.book_stats;
+        self.day_modifiers = data.day_modifiers;
+        self.difficulty_assist = data.difficulty_assist;
+        self.practice_request = data.practice_request;
+        self.reservation_ledger = data.reservation_ledger;
+        self.inspection_record = data.inspection_record;
+        self.wrapping_supplies = data.wrapping_supplies;
+        self.shop_expansion = data.shop_expansion;
+        self.assistant = data.assistant;
+        self.album = data.album;
+        self.book_fair = data.book_fair;
+        self.mail_box = data.mail_box;
+        self.decision_log = data.decision_log;
+        self.membership = data.membership;
+        self.diary = data.diary;
+        self.misfiled_books = data.misfiled_books;
+        self.paper_archive = data.paper_archive;
     }
 
     pub fn change_ad_status(&mut self, ad_type: SuzunaAdType, status: bool) {
@@ -2143,6 +3009,161 @@ pub struct ResultReportStringTable {
     pub shelving_is_done: String,
     pub condition_eval_mistakes: String,
     pub total_ad_cost: String,
+    pub desk_accuracy: String,
+    pub record_accuracy: String,
+    pub average_handling_time: String,
+    pub late_night_time: String,
+}
+
+///
+/// # 机作業の集計
+/// 一日の受付業務の成績。机画面のStatsBarと日毎の結果画面で共有する
+///
+#[derive(Clone, Debug)]
+pub struct DeskSessionStats {
+    completed_requests: u32,
+    evaluated_books: usize,
+    eval_mistakes: usize,
+    total_handling_clock: Clock,
+    earned_money: i32,
+    total_score: i32,
+    record_checked: usize,
+    record_correct: usize,
+}
+
+impl DeskSessionStats {
+    pub fn new() -> Self {
+        DeskSessionStats {
+            completed_requests: 0,
+            evaluated_books: 0,
+            eval_mistakes: 0,
+            total_handling_clock: 0,
+            earned_money: 0,
+            total_score: 0,
+            record_checked: 0,
+            record_correct: 0,
+        }
+    }
+
+    pub fn record_task(
+        &mut self,
+        evaluated_books: usize,
+        eval_mistakes: usize,
+        handling_clock: Clock,
+        earned_money: i32,
+    ) {
+        self.completed_requests += 1;
+        self.evaluated_books += evaluated_books;
+        self.eval_mistakes += eval_mistakes;
+        self.total_handling_clock += handling_clock;
+        self.earned_money += earned_money;
+    }
+
+    pub fn get_completed_requests(&self) -> u32 {
+        self.completed_requests
+    }
+
+    ///
+    /// 状態評価の正解率（%）。まだ評価していない場合は100
+    ///
+    pub fn accuracy_percent(&self) -> u32 {
+        if self.evaluated_books == 0 {
+            return 100;
+        }
+
+        let correct = self.evaluated_books.saturating_sub(self.eval_mistakes);
+        ((correct * 100) / self.evaluated_books) as u32
+    }
+
+    pub fn average_handling_clock(&self) -> Clock {
+        if self.completed_requests == 0 {
+            0
+        } else {
+            self.total_handling_clock / self.completed_requests as Clock
+        }
+    }
+
+    pub fn get_earned_money(&self) -> i32 {
+        self.earned_money
+    }
+
+    ///
+    /// 受付の種類ごとの決まりで付けた点を加える
+    ///
+    pub fn add_score(&mut self, score: i32) {
+        self.total_score += score;
+    }
+
+    pub fn get_total_score(&self) -> i32 {
+        self.total_score
+    }
+
+    ///
+    /// 署名した頁の採点を、帳簿の正確さの集計に加える
+    ///
+    pub fn record_page_score(&mut self, score: &record_validation::RecordPageScore) {
+        self.record_checked += score.checked;
+        self.record_correct += score.count_correct();
+    }
+
+    ///
+    /// 帳簿の記入欄の正解率（%）。まだ採点していない場合は100
+    ///
+    pub fn record_accuracy_percent(&self) -> u32 {
+        if self.record_checked == 0 {
+            return 100;
+        }
+
+        ((self.record_correct * 100) / self.record_checked) as u32
+    }
+}
+
+///
+/// # 状態評価を誤った返却受付の記録
+/// 日毎の結果画面で見直したり、翌朝に練習として受け直したりするのに使う
+///
+/// ## order
+/// その日の何件目の受付か
+///
+/// ## request
+/// 元の返却受付。練習の受付はここから作り直す
+///
+/// ## submitted_page
+/// 署名したときの頁の内容
+///
+/// ## evaluations
+/// 返却された本と、記入した状態の組
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MistakeRecord {
+    pub order: u32,
+    pub request: ReturnBookInformation,
+    pub submitted_page: BorrowingRecordBookPageData,
+    pub evaluations: Vec<(BookInformation, BookCondition)>,
+}
+
+impl MistakeRecord {
+    pub fn count_mistake(&self) -> usize {
+        self.evaluations
+            .iter()
+            .filter(|(book_info, eval)| book_info.get_condition() != *eval)
+            .count()
+    }
+
+    ///
+    /// 本来の状態がconditionである本を見誤っていればtrue
+    ///
+    pub fn mistook_condition(&self, condition: &BookCondition) -> bool {
+        self.evaluations.iter().any(|(book_info, eval)| {
+            book_info.get_condition() == *condition && book_info.get_condition() != *eval
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MistakeSortKey {
+    Order,
+    MistakeCount,
 }
 
 #[derive(Clone, Debug)]
@@ -2152,6 +3173,9 @@ pub struct ResultReport {
     total_customers_waiting_time: Clock,
     condition_eval_mistakes: usize,
     total_ad_cost: i32,
+    desk_stats: DeskSessionStats,
+    late_night_minutes: u32,
+    mistake_records: Vec<MistakeRecord>,
 }
 
 impl ResultReport {
@@ -2162,7 +3186,55 @@ impl ResultReport {
             total_customers_waiting_time: 0,
             condition_eval_mistakes: 0,
             total_ad_cost: 0,
+            desk_stats: DeskSessionStats::new(),
+            late_night_minutes: 0,
+            mistake_records: Vec::new(),
+        }
+    }
+
+    pub fn add_mistake_record(&mut self, record: MistakeRecord) {
+        self.mistake_records.push(record);
+    }
+
+    pub fn has_mistake_record(&self) -> bool {
+        !self.mistake_records.is_empty()
+    }
+
+    ///
+    /// 見直し用に、誤った受付を並べ替えて返す
+    /// filterが指定された場合は、本来の状態がfilterである本を見誤った受付だけを返す
+    ///
+    pub fn review_mistakes(
+        &self,
+        sort_key: MistakeSortKey,
+        filter: Option<&BookCondition>,
+    ) -> Vec<MistakeRecord> {
+        let mut records: Vec<MistakeRecord> = self
+            .mistake_records
+            .iter()
+            .filter(|record| match filter {
+                Some(condition) => record.mistook_condition(condition),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        match sort_key {
+            MistakeSortKey::Order => records.sort_by_key(|record| record.order),
+            MistakeSortKey::MistakeCount => {
+                records.sort_by(|a, b| b.count_mistake().cmp(&a.count_mistake()))
+            }
         }
+
+        records
+    }
+
+    pub fn get_desk_stats(&self) -> &DeskSessionStats {
+        &self.desk_stats
+    }
+
+    pub fn update_desk_stats(&mut self, stats: DeskSessionStats) {
+        self.desk_stats = stats;
     }
 
     pub fn add_new_book_id(&mut self, id: u64) {
@@ -2185,8 +3257,19 @@ impl ResultReport {
         self.total_ad_cost += cost;
     }
 
-    pub fn create_table(&self) -> ResultReportStringTable {
-        ResultReportStringTable::new(self)
+    pub fn add_late_night_minute(&mut self) {
+        self.late_night_minutes += 1;
+    }
+
+    pub fn get_late_night_minutes(&self) -> u32 {
+        self.late_night_minutes
+    }
+
+    pub fn create_table(
+        &self,
+        number_format: number_format::NumberFormat,
+    ) -> ResultReportStringTable {
+        ResultReportStringTable::new(self, number_format)
     }
 
     pub fn new_books_shelving_is_done(&self) -> bool {
@@ -2266,21 +3349,31 @@ impl ResultReport {
 }
 
 impl ResultReportStringTable {
-    pub fn new(result_report: &ResultReport) -> Self {
+    pub fn new(result_report: &ResultReport, number_format: number_format::NumberFormat) -> Self {
         ResultReportStringTable {
-            total_customers_waiting_time: number_to_jk::number_to_jk(
-                result_report.total_customers_waiting_time / 60,
-            ),
+            total_customers_waiting_time: number_format
+                .number(result_report.total_customers_waiting_time / 60),
             shelving_is_done: if result_report.new_books_shelving_is_done() {
                 "達成"
             } else {
                 "未達成"
             }
             .to_string(),
-            condition_eval_mistakes: number_to_jk::number_to_jk(
-                result_report.condition_eval_mistakes as u64,
-            ),
-            total_ad_cost: number_to_jk::number_to_jk(result_report.total_ad_cost as u64),
+            condition_eval_mistakes: number_format
+                .number(result_report.condition_eval_mistakes as u64),
+            total_ad_cost: number_format.number(result_report.total_ad_cost as u64),
+            desk_accuracy: format!("{}%", result_report.desk_stats.accuracy_percent()),
+            record_accuracy: format!("{}%", result_report.desk_stats.record_accuracy_percent()),
+            average_handling_time: number_format
+                .number(result_report.desk_stats.average_handling_clock() / 60),
+            late_night_time: if result_report.late_night_minutes == 0 {
+                "無し".to_string()
+            } else {
+                format!(
+                    "{}分",
+                    number_format.number(result_report.late_night_minutes as u64)
+                )
+            },
         }
     }
 }
@@ -2293,9 +3386,23 @@ pub struct GameConfig {
     pause_when_inactive: bool,
     fullscreen_mode: bool,
     extra_unlocked: bool,
+    #[serde(default = "GameConfig::default_idle_timeout_minute")]
+    idle_timeout_minute: u32,
+    #[serde(default = "GameConfig::default_shop_menu_width")]
+    shop_menu_width: f32,
+    #[serde(default)]
+    shop_menu_collapsed: bool,
 }
 
 impl GameConfig {
+    fn default_idle_timeout_minute() -> u32 {
+        5
+    }
+
+    fn default_shop_menu_width() -> f32 {
+        450.0
+    }
+
     pub fn new_from_toml(ctx: &mut ggez::Context, path: &str) -> Self {
         match File::open("./game_config") {
             Ok(mut file) => {
@@ -2360,6 +3467,33 @@ impl GameConfig {
 	self.fullscreen_mode = flag;
     }
 
+    ///
+    /// 無操作とみなすまでの時間（分）。0の場合は無効
+    ///
+    pub fn get_idle_timeout_minute(&self) -> u32 {
+        self.idle_timeout_minute
+    }
+
+    pub fn set_idle_timeout_minute(&mut self, minute: u32) {
+        self.idle_timeout_minute = minute;
+    }
+
+    pub fn get_shop_menu_width(&self) -> f32 {
+        self.shop_menu_width
+    }
+
+    pub fn set_shop_menu_width(&mut self, width: f32) {
+        self.shop_menu_width = width;
+    }
+
+    pub fn is_shop_menu_collapsed(&self) -> bool {
+        self.shop_menu_collapsed
+    }
+
+    pub fn set_shop_menu_collapsed(&mut self, flag: bool) {
+        self.shop_menu_collapsed = flag;
+    }
+
     pub fn save_config(&self) {
         let mut file = File::create("./game_config").expect("failed to create game config file.");
 
@@ -2511,6 +3645,8 @@ pub struct SuzuContext<'ctx> {
     pub resource: &'ctx mut GameResource,
     pub savable_data: &'ctx mut Option<SavableData>,
     pub config: &'ctx mut GameConfig,
+    pub preferences: &'ctx mut preferences::Preferences,
+    pub input_history: &'ctx input_queue::InputHistory,
     pub process_utility: ProcessUtility<'ctx>,
     pub permanent_save_data: &'ctx mut PermanentSaveData,
 }
@@ -2528,6 +3664,18 @@ impl<'ctx> SuzuContext<'ctx> {
         self.resource.ref_texture(self.context, id)
     }
 
+    pub fn text_orientation(&self) -> preferences::TextOrientation {
+        self.preferences.text_orientation()
+    }
+
+    pub fn save_window_placement(&mut self) {
+        save_window_placement(self.context, self.config, self.preferences);
+    }
+
+    pub fn number_format(&self) -> number_format::NumberFormat {
+        number_format::NumberFormat::new(self.preferences.get_locale())
+    }
+
     pub fn is_bgm_playing(&self, handler: sound::SoundHandler) -> bool {
         self.resource.is_bgm_playing(handler)
     }
@@ -2536,6 +3684,19 @@ impl<'ctx> SuzuContext<'ctx> {
         self.resource.is_se_playing(handler)
     }
 
+    ///
+    /// その場面の曲を、fade_inフレーム掛けて流し始める
+    ///
+    pub fn play_scene_bgm(&mut self, scene: scene::SceneID, fade_in: Clock) {
+        if let Some(sound_id) = self.resource.get_scene_bgm(scene) {
+            let volume = self.config.get_bgm_volume();
+            self.play_sound_as_bgm(
+                sound_id,
+                Some(sound::SoundPlayFlags::new(fade_in, 1.0, true, volume)),
+            );
+        }
+    }
+
     pub fn play_sound_as_bgm(&mut self, sound_id: SoundID, flags: Option<sound::SoundPlayFlags>) {
         self.resource
             .play_sound_as_bgm(self.context, sound_id, flags);
@@ -2545,19 +3706,60 @@ impl<'ctx> SuzuContext<'ctx> {
         &mut self,
         sound_id: SoundID,
         flags: Option<sound::SoundPlayFlags>,
-    ) -> sound::SoundHandler {
+    ) -> Option<sound::SoundHandler> {
         self.resource
             .play_sound_as_se(self.context, sound_id, flags)
     }
 
+    ///
+    /// 文字送りの声を一つ鳴らす。声を消しているか、音量が0なら鳴らさない
+    ///
+    pub fn play_voice_blip(&mut self, archetype: voice_blip::VoiceArchetype) {
+        let volume = self.preferences.get_voice_blip_volume();
+        if self.preferences.is_voice_blip_muted() || volume <= 0.0 {
+            return;
+        }
+
+        self.resource.play_sound_as_se(
+            self.context,
+            SoundID::SeMessage,
+            Some(sound::SoundPlayFlags::new(0, archetype.pitch(), false, volume)),
+        );
+    }
+
+    pub fn play_sound_at(
+        &mut self,
+        sound_id: SoundID,
+        map_position: numeric::Point2f,
+    ) -> Option<sound::SoundHandler> {
+        let volume = self.config.get_se_volume();
+        self.resource.play_sound_at(self.context, sound_id, map_position, volume)
+    }
+
+    ///
+    /// 混ぜ具合に合わせて、環境音の音量を効果音の音量で鳴らす
+    ///
+    pub fn update_ambient_sound(&mut self, mixer: &ambient_zone::AmbientMixer) {
+        let se_volume = self.config.get_se_volume();
+
+        for (kind, level) in mixer.iter_levels() {
+            if let Some(sound_id) = kind.sound_id() {
+                self.resource
+                    .set_ambient_volume(self.context, sound_id, level * se_volume);
+            }
+        }
+    }
+
     pub fn change_bgm_volume(&mut self, volume: f32) {
         self.resource.change_bgm_volume(volume / 100.0);
         self.config.set_bgm_volume_100(volume);
+        self.preferences.set_bgm_volume(volume / 100.0);
     }
 
     pub fn change_se_volume(&mut self, volume: f32) {
         self.resource.change_se_volume(volume / 100.0);
         self.config.set_se_volume_100(volume);
+        self.preferences.set_se_volume(volume / 100.0);
     }
 
     pub fn pay_ad_cost(&mut self) -> i32 {
@@ -2570,14 +3772,31 @@ impl<'ctx> SuzuContext<'ctx> {
     pub fn holding_week_schedule_is_available(&self) -> bool {
         self.take_save_data()
             .week_schedule
-            .update_is_not_required(&self.take_save_data().date)
+            .update_is_not_required(&self.today())
     }
 
-    pub fn go_next_day(&mut self) {
-        self.take_save_data_mut().date.add_day(1);
+    ///
+    /// 今日の日付。日付は保存データが持ち、go_next_dayでだけ進む
+    ///
+    pub fn today(&self) -> GensoDate {
+        self.take_save_data().date
     }
 
-    pub fn current_total_ad_cost(&self) -> i32 {
+    pub fn go_next_day(&mut self) {
+        let save_data = self.take_save_data_mut();
+        save_data.date.add_days(1);
+        save_data.suzunaan_status.pass_night();
+        save_data.day_modifiers.clear();
+
+        // 仕上がった郵便の写本を送り、代金を受け取る
+        let date = save_data.date;
+        for request in save_data.mail_box.take_finished_copies(&date) {
+            log_info!("postal copy sent: {} to {}", request.title, request.sender);
+            save_data.task_result.add_total_money(request.fee() as i32);
+        }
+    }
+
+    pub fn current_total_ad_cost(&self) -> i32 {
         let mut total_ad_cost = 0;
 
         for ad_type in vec![
@@ -2636,7 +3855,7 @@ impl<'ctx> SuzuContext<'ctx> {
     }
 
     pub fn reset_save_data(&mut self, game_mode: GameMode) {
-        *self.savable_data = Some(SavableData::new(&self.resource, game_mode));
+        *self.savable_data = Some(SavableData::new(self.resource, game_mode));
     }
 
     pub fn save(&mut self, slot_id: u8) -> Result<(), ()> {
@@ -2670,6 +3889,11 @@ pub enum TopScene {
     SaveScene(scene::save_scene::SaveScene),
     TitleScene(scene::title_scene::TitleScene),
     EndScene(scene::end_scene::EndScene),
+    AlbumScene(scene::album_scene::AlbumScene),
+    BookFairScene(scene::book_fair_scene::BookFairScene),
+    LedgerAuditScene(scene::ledger_audit_scene::LedgerAuditScene),
+    ScoreAttackScene(scene::score_attack_scene::ScoreAttackScene),
+    CreditsScene(scene::credits_scene::CreditsScene),
     Null(scene::NullScene),
 }
 
@@ -2681,6 +3905,11 @@ impl TopScene {
             TopScene::SaveScene(scene) => scene,
             TopScene::TitleScene(scene) => scene,
             TopScene::EndScene(scene) => scene,
+            TopScene::AlbumScene(scene) => scene,
+            TopScene::BookFairScene(scene) => scene,
+            TopScene::LedgerAuditScene(scene) => scene,
+            TopScene::ScoreAttackScene(scene) => scene,
+            TopScene::CreditsScene(scene) => scene,
             TopScene::Null(scene) => scene,
         }
     }
@@ -2692,6 +3921,11 @@ impl TopScene {
             TopScene::SaveScene(scene) => scene,
             TopScene::TitleScene(scene) => scene,
             TopScene::EndScene(scene) => scene,
+            TopScene::AlbumScene(scene) => scene,
+            TopScene::BookFairScene(scene) => scene,
+            TopScene::LedgerAuditScene(scene) => scene,
+            TopScene::ScoreAttackScene(scene) => scene,
+            TopScene::CreditsScene(scene) => scene,
             TopScene::Null(scene) => scene,
         }
     }
@@ -2702,6 +3936,58 @@ impl TopScene {
             _ => None,
         }
     }
+
+    ///
+    /// 場面ごとの設定を引くときの名前
+    ///
+    pub fn scene_name(&self) -> &'static str {
+        match self {
+            TopScene::ScenarioScene(_) => "Scenario",
+            TopScene::SuzunaScene(_) => "SuzunaShop",
+            TopScene::SaveScene(_) => "Save",
+            TopScene::TitleScene(_) => "Title",
+            TopScene::EndScene(_) => "End",
+            TopScene::AlbumScene(_) => "Album",
+            TopScene::BookFairScene(_) => "BookFair",
+            TopScene::LedgerAuditScene(_) => "LedgerAudit",
+            TopScene::ScoreAttackScene(_) => "ScoreAttack",
+            TopScene::CreditsScene(_) => "Credits",
+            TopScene::Null(_) => "Null",
+        }
+    }
+}
+
+///
+/// 次に起動したときに戻せるよう、今の窓を覚えておく
+///
+fn save_window_placement(
+    ctx: &mut ggez::Context,
+    game_config: &GameConfig,
+    preferences: &mut preferences::Preferences,
+) {
+    let fullscreen = game_config.is_fullscreen_mode_configed();
+    let ui_scale = preferences.get_ui_scale();
+    let mut placement = preferences.get_window_placement().unwrap_or_else(|| {
+        preferences::WindowPlacement::new(
+            WINDOW_SIZE_X as f32 * ui_scale,
+            WINDOW_SIZE_Y as f32 * ui_scale,
+        )
+    });
+
+    // 全画面の間の位置と大きさは、窓に戻したときのものではないので覚えない
+    if !fullscreen {
+        let (width, height) = ggraphics::drawable_size(ctx);
+        placement.position = ggraphics::window(ctx)
+            .outer_position()
+            .ok()
+            .map(|position| (position.x, position.y));
+        placement.width = width;
+        placement.height = height;
+    }
+    placement.fullscreen = fullscreen;
+
+    preferences.set_window_placement(placement);
+    preferences.save();
 }
 
 struct SceneController {
@@ -2714,10 +4000,105 @@ struct SceneController {
     game_config: GameConfig,
     redraw_request: scene::DrawRequest,
     permanent_save_data: PermanentSaveData,
+    last_input_clock: u64,
+    preferences: preferences::Preferences,
+    input_history: input_queue::InputHistory,
+    frame_budget: instrument::FrameBudgetMonitor,
+    frame_work: std::time::Duration,
+    budget_notice: Option<(torifune::graphics::object::UniText, Clock)>,
+    simulation_clock: time_scale::SimulationClock,
+    frame_steps: u32,
+    debug_console: Option<debug_console::DebugConsole>,
+    error_notification: crate::object::notify::NotificationArea,
+    log_viewer: Option<crate::object::log_viewer::LogViewer>,
+    color_grading: color_grading::ColorGrading,
+    color_grading_layer: crate::object::color_grading_layer::ColorGradingLayer,
+    gamepad_stick: numeric::Vector2f,
 }
 
 impl SceneController {
+    ///
+    /// 前回の窓の位置に戻す。前回のモニタが外されていれば、主画面に収まるよう寄せる
+    ///
+    fn restore_window_placement(
+        ctx: &mut ggez::Context,
+        placement: &preferences::WindowPlacement,
+    ) -> preferences::WindowPlacement {
+        let window = ggraphics::window(ctx);
+        let monitor_rect = |monitor: ggez::winit::monitor::MonitorHandle| {
+            let position = monitor.position();
+            let size = monitor.size();
+            numeric::Rect::new(
+                position.x as f32,
+                position.y as f32,
+                size.width as f32,
+                size.height as f32,
+            )
+        };
+
+        let displays: Vec<numeric::Rect> = window.available_monitors().map(monitor_rect).collect();
+        let primary = window
+            .primary_monitor()
+            .map(monitor_rect)
+            .or_else(|| displays.first().cloned());
+
+        let placement = match primary {
+            Some(primary) => placement.restore_on(&displays, &primary),
+            None => *placement,
+        };
+
+        if let Some((x, y)) = placement.position {
+            window.set_outer_position(ggez::winit::dpi::PhysicalPosition::new(x, y));
+        }
+
+        placement
+    }
+
+    pub fn save_window_placement(&mut self, ctx: &mut ggez::Context) {
+        save_window_placement(ctx, &self.game_config, &mut self.preferences);
+    }
+
     pub fn new<'a>(ctx: &mut ggez::Context, game_data: &'a mut GameResource) -> SceneController {
+        let mut game_status = None;
+        let mut game_config = GameConfig::new_from_toml(ctx, "/default_game_config.toml");
+
+        // シーンを作る前にユーザー設定を反映する
+        let mut preferences = preferences::Preferences::load_or_migrate(&game_config);
+        preferences.apply_to_config(&mut game_config);
+
+        // 前回閉じたときの窓に戻す。全画面で閉じていれば全画面で始める
+        let window_placement = preferences.get_window_placement().map(|placement| {
+            game_config.set_fullscreen_mode_config(placement.fullscreen);
+            Self::restore_window_placement(ctx, &placement)
+        });
+
+        // settings.tomlがあれば、前回の窓の大きさよりそちらを優先する
+        let settings = config::Settings::load();
+        if let Some(settings) = settings.as_ref() {
+            game_config.set_fullscreen_mode_config(settings.fullscreen);
+        }
+        game_data.set_master_volume(
+            settings
+                .as_ref()
+                .map_or(1.0, |settings| settings.master_volume),
+        );
+
+        if !game_config.is_fullscreen_mode_configed() {
+            let ui_scale = preferences.get_ui_scale();
+            let (width, height) = match (settings.as_ref(), window_placement) {
+                (Some(settings), _) => settings.resolution,
+                (None, Some(placement)) => (placement.width, placement.height),
+                (None, None) => (
+                    WINDOW_SIZE_X as f32 * ui_scale,
+                    WINDOW_SIZE_Y as f32 * ui_scale,
+                ),
+            };
+            match ggraphics::set_drawable_size(ctx, width, height) {
+                Ok(_) => (),
+                Err(e) => log_warn!("failed to resize the window: {}", e),
+            }
+        }
+
         let window_size = ggraphics::drawable_size(ctx);
 
         let mut root_screen = SubScreen::new(
@@ -2732,14 +4113,12 @@ impl SceneController {
             numeric::Vector2f::new(window_size.0.round(), window_size.1.round()),
         );
 
-        let mut game_status = None;
-        let mut game_config = GameConfig::new_from_toml(ctx, "/default_game_config.toml");
-
 	if game_config.is_fullscreen_mode_configed() {
 	    ggraphics::set_fullscreen(ctx, ggez::conf::FullscreenType::Desktop);
 	}
 
         let mut permanent_save_data = PermanentSaveData::from_toml();
+        let input_history = input_queue::InputHistory::new();
 
         let mut _redraw_request = scene::DrawRequest::Draw;
 
@@ -2753,12 +4132,49 @@ impl SceneController {
             resource: game_data,
             savable_data: &mut game_status,
             config: &mut game_config,
+            preferences: &mut preferences,
+            input_history: &input_history,
             process_utility: ProcessUtility {
                 redraw_request: &mut _redraw_request,
             },
             permanent_save_data: &mut permanent_save_data,
         });
 
+        // 遊び続けられる不具合は、どのシーンでも右上に知らせる
+        let error_notification = crate::object::notify::NotificationArea::new(
+            &mut SuzuContext {
+                context: ctx,
+                resource: game_data,
+                savable_data: &mut game_status,
+                config: &mut game_config,
+                preferences: &mut preferences,
+                input_history: &input_history,
+                process_utility: ProcessUtility {
+                    redraw_request: &mut _redraw_request,
+                },
+                permanent_save_data: &mut permanent_save_data,
+            },
+            numeric::Point2f::new(WINDOW_SIZE_X as f32 - 10.0, 10.0),
+            0,
+        );
+
+        let frame_budget =
+            instrument::FrameBudgetMonitor::new(preferences.get_frame_budget_micros());
+        let simulation_clock = time_scale::SimulationClock::new(preferences.get_time_scale());
+
+        // コンソールから速さを変えられるのはデバッグビルドだけ
+        let debug_console = if cfg!(debug_assertions) {
+            Some(debug_console::DebugConsole::spawn())
+        } else {
+            None
+        };
+
+        let color_grading = color_grading::ColorGrading::new(
+            color_grading::ColorGrading::scene_presets_from_toml_str(
+                &util::read_from_resources_as_string(ctx, color_grading::COLOR_GRADING_TABLE_PATH),
+            ),
+        );
+
         SceneController {
             //current_scene: TopScene::ScenarioScene(current_scene),
             current_scene: TopScene::TitleScene(current_scene),
@@ -2770,9 +4186,70 @@ impl SceneController {
             game_config: game_config,
             redraw_request: scene::DrawRequest::Draw,
             permanent_save_data: permanent_save_data,
+            last_input_clock: 0,
+            preferences: preferences,
+            input_history: input_history,
+            frame_budget: frame_budget,
+            frame_work: std::time::Duration::new(0, 0),
+            budget_notice: None,
+            simulation_clock: simulation_clock,
+            frame_steps: 1,
+            debug_console: debug_console,
+            error_notification: error_notification,
+            log_viewer: None,
+            color_grading: color_grading,
+            color_grading_layer: crate::object::color_grading_layer::ColorGradingLayer::new(
+                numeric::Rect::new(0.0, 0.0, WINDOW_SIZE_X as f32, WINDOW_SIZE_Y as f32),
+            ),
+            gamepad_stick: numeric::Vector2f::new(0.0, 0.0),
+        }
+    }
+
+    ///
+    /// ユーザー設定の割り当てを優先して仮想キーに変換する
+    ///
+    fn real_to_virtual(&self, keycode: KeyCode) -> tdev::VirtualKey {
+        match self.preferences.remap_key(keycode) {
+            Some(vkey) => vkey,
+            None => self.key_map.real_to_virtual(keycode),
         }
     }
 
+    fn notify_input(&mut self) {
+        self.last_input_clock = self.global_clock;
+    }
+
+    ///
+    /// 設定された時間入力が無ければ、シーンに通知する
+    ///
+    fn check_idle_timeout(&mut self, ctx: &mut ggez::Context, game_data: &mut GameResource) {
+        let timeout_minute = self.game_config.get_idle_timeout_minute() as u64;
+        if timeout_minute == 0 {
+            return;
+        }
+
+        // 60fps前提
+        if self.global_clock - self.last_input_clock < timeout_minute * 60 * 60 {
+            return;
+        }
+
+        self.notify_input();
+        self.current_scene
+            .abs_mut()
+            .idle_timeout_event(&mut SuzuContext {
+                context: ctx,
+                resource: game_data,
+                savable_data: &mut self.game_status,
+                config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
+                process_utility: ProcessUtility {
+                    redraw_request: &mut self.redraw_request,
+                },
+                permanent_save_data: &mut self.permanent_save_data,
+            });
+    }
+
     fn switch_scene_with_swap<'a>(
         &mut self,
         ctx: &mut ggez::Context,
@@ -2784,6 +4261,8 @@ impl SceneController {
             resource: game_data,
             savable_data: &mut self.game_status,
             config: &mut self.game_config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
             process_utility: ProcessUtility {
                 redraw_request: &mut self.redraw_request,
             },
@@ -2824,6 +4303,20 @@ impl SceneController {
                             scene::scenario_scene::ScenarioSelect::DayBegin,
                         ))
                 }
+                TopScene::BookFairScene(_) => {
+                    self.current_scene =
+                        TopScene::ScenarioScene(scene::scenario_scene::ScenarioScene::new(
+                            &mut ctx,
+                            scene::scenario_scene::ScenarioSelect::DayBegin,
+                        ))
+                }
+                TopScene::LedgerAuditScene(_) => {
+                    self.current_scene =
+                        TopScene::ScenarioScene(scene::scenario_scene::ScenarioScene::new(
+                            &mut ctx,
+                            scene::scenario_scene::ScenarioSelect::DayBegin,
+                        ))
+                }
                 _ => (),
             },
             scene::SceneID::Title => {
@@ -2837,6 +4330,27 @@ impl SceneController {
             scene::SceneID::End => {
                 self.current_scene = TopScene::EndScene(scene::end_scene::EndScene::new(&mut ctx))
             }
+            scene::SceneID::BookFair => {
+                self.current_scene =
+                    TopScene::BookFairScene(scene::book_fair_scene::BookFairScene::new(&mut ctx))
+            }
+            scene::SceneID::LedgerAudit => {
+                self.current_scene = TopScene::LedgerAuditScene(
+                    scene::ledger_audit_scene::LedgerAuditScene::new(&mut ctx),
+                )
+            }
+            scene::SceneID::ScoreAttack => {
+                self.current_scene = TopScene::ScoreAttackScene(
+                    scene::score_attack_scene::ScoreAttackScene::new(&mut ctx),
+                )
+            }
+            scene::SceneID::Credits => {
+                self.current_scene =
+                    TopScene::CreditsScene(scene::credits_scene::CreditsScene::new(
+                        &mut ctx,
+                        scene::SceneTransition::SwapTransition,
+                    ))
+            }
             scene::SceneID::Null => self.current_scene = TopScene::Null(scene::NullScene::new()),
             _ => (),
         }
@@ -2853,6 +4367,8 @@ impl SceneController {
             resource: game_data,
             savable_data: &mut self.game_status,
             config: &mut self.game_config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
             process_utility: ProcessUtility {
                 redraw_request: &mut self.redraw_request,
             },
@@ -2866,6 +4382,15 @@ impl SceneController {
             scene::SceneID::Save => Some(TopScene::SaveScene(scene::save_scene::SaveScene::new(
                 &mut ctx,
             ))),
+            scene::SceneID::Album => Some(TopScene::AlbumScene(
+                scene::album_scene::AlbumScene::new(&mut ctx),
+            )),
+            scene::SceneID::Credits => Some(TopScene::CreditsScene(
+                scene::credits_scene::CreditsScene::new(
+                    &mut ctx,
+                    scene::SceneTransition::PoppingTransition,
+                ),
+            )),
             scene::SceneID::Null => Some(TopScene::Null(scene::NullScene::new())),
             _ => None,
         };
@@ -2877,41 +4402,185 @@ impl SceneController {
     }
 
     fn run_pre_process(&mut self, ctx: &mut ggez::Context, game_data: &mut GameResource) {
+        let start = std::time::Instant::now();
+
+        self.poll_debug_console(game_data);
+        self.frame_steps = self.simulation_clock.steps_this_frame();
+
         //println!("{}", perf_measure!(
         {
-            self.current_scene.abs_mut().pre_process(&mut SuzuContext {
-                context: ctx,
-                resource: game_data,
-                savable_data: &mut self.game_status,
-                config: &mut self.game_config,
-                process_utility: ProcessUtility {
-                    redraw_request: &mut self.redraw_request,
-                },
-                permanent_save_data: &mut self.permanent_save_data,
-            });
+            // 速さに合わせて、一フレームの間にシーンを何度か進める。止めている間は進めない
+            for step in 0..self.frame_steps {
+                if step > 0 {
+                    self.run_scene_post_process(ctx, game_data);
+                }
+
+                self.current_scene.abs_mut().pre_process(&mut SuzuContext {
+                    context: ctx,
+                    resource: game_data,
+                    savable_data: &mut self.game_status,
+                    config: &mut self.game_config,
+                    preferences: &mut self.preferences,
+                    input_history: &self.input_history,
+                    process_utility: ProcessUtility {
+                        redraw_request: &mut self.redraw_request,
+                    },
+                    permanent_save_data: &mut self.permanent_save_data,
+                });
+            }
+
+            self.check_idle_timeout(ctx, game_data);
+            self.update_error_notification(ctx, game_data);
+
+            if let Some(log_viewer) = self.log_viewer.as_mut() {
+                if log_viewer.update() {
+                    self.redraw_request = scene::DrawRequest::Draw;
+                }
+            }
         }
         //));
+
+        self.frame_work += start.elapsed();
+    }
+
+    ///
+    /// このフレームまでに起きた遊び続けられる不具合を、通知に並べる
+    ///
+    fn update_error_notification(&mut self, ctx: &mut ggez::Context, game_data: &mut GameResource) {
+        let mut suzu_ctx = SuzuContext {
+            context: ctx,
+            resource: game_data,
+            savable_data: &mut self.game_status,
+            config: &mut self.game_config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
+            process_utility: ProcessUtility {
+                redraw_request: &mut self.redraw_request,
+            },
+            permanent_save_data: &mut self.permanent_save_data,
+        };
+
+        for error in recoverable_error::take_reported() {
+            self.error_notification.insert_recoverable_error(
+                &mut suzu_ctx,
+                &error,
+                self.global_clock,
+            );
+        }
+
+        self.error_notification
+            .update(&mut suzu_ctx, self.global_clock);
     }
 
     fn run_drawing_process(&mut self, ctx: &mut ggez::Context) {
+        let start = std::time::Instant::now();
+
         //println!("{}", perf_measure!(
         {
             sub_screen::stack_screen(ctx, &self.root_screen);
 
-            self.current_scene.abs_mut().drawing_process(ctx);
+            instrument!(
+                instrument::ProbeCategory::DrawSubmit,
+                self.current_scene.abs_mut().drawing_process(ctx)
+            );
+
+            self.color_grading_layer
+                .draw(ctx, &self.color_grading.current(self.global_clock))
+                .unwrap();
+
+            if let Some((notice, _)) = self.budget_notice.as_mut() {
+                notice.draw(ctx).unwrap();
+            }
+
+            self.error_notification.draw(ctx).unwrap();
+
+            if let Some(log_viewer) = self.log_viewer.as_mut() {
+                log_viewer.draw(ctx).unwrap();
+            }
 
             sub_screen::pop_screen(ctx);
             self.root_screen.draw(ctx).unwrap();
         }
         //) as f32 / 1000000.0);
+
+        self.frame_work += start.elapsed();
+    }
+
+    ///
+    /// このフレームの処理時間を予算と比べ、超えていれば一番重かった処理を知らせる
+    /// 画面に出すのはデバッグビルドだけ
+    ///
+    fn check_frame_budget(&mut self, game_data: &GameResource) {
+        let counters = instrument::take_frame_counters();
+        let frame_micros = self.frame_work.as_micros() as u64;
+        self.frame_work = std::time::Duration::new(0, 0);
+
+        if let Some((_, shown_at)) = self.budget_notice.as_ref() {
+            if self.global_clock > shown_at + instrument::BUDGET_NOTICE_TIME {
+                self.budget_notice = None;
+                self.redraw_request = scene::DrawRequest::Draw;
+            }
+        }
+
+        let warning = match self
+            .frame_budget
+            .end_frame(&counters, frame_micros, self.global_clock)
+        {
+            Some(warning) => warning,
+            None => return,
+        };
+
+        let message = warning.to_message();
+        log_warn!("{}", message);
+
+        if cfg!(debug_assertions) {
+            self.budget_notice = Some((
+                torifune::graphics::object::UniText::new(
+                    message,
+                    numeric::Point2f::new(10.0, 10.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    FontInformation::new(
+                        game_data.get_font(FontID::Cinema),
+                        numeric::Vector2f::new(20.0, 20.0),
+                        ggraphics::Color::from_rgba_u32(0xcc2222ff),
+                    ),
+                ),
+                self.global_clock,
+            ));
+            self.redraw_request = scene::DrawRequest::Draw;
+        }
     }
 
     fn run_post_process<'a>(&mut self, ctx: &mut ggez::Context, game_data: &'a mut GameResource) {
+        let start = std::time::Instant::now();
+
+        if self.frame_steps > 0 {
+            self.run_scene_post_process(ctx, game_data);
+        }
+
+        if self.global_clock % 120 == 0 {
+            log_debug!("fps: {}", ggez::timer::fps(ctx));
+        }
+        self.global_clock += 1;
+        self.redraw_request = scene::DrawRequest::Skip;
+
+        self.frame_work += start.elapsed();
+        self.check_frame_budget(game_data);
+    }
+
+    ///
+    /// シーンの時計を進め、シーンの切り替えを行う
+    ///
+    fn run_scene_post_process(&mut self, ctx: &mut ggez::Context, game_data: &mut GameResource) {
         let mut suzu_ctx = SuzuContext {
             context: ctx,
             resource: game_data,
             savable_data: &mut self.game_status,
             config: &mut self.game_config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
             process_utility: ProcessUtility {
                 redraw_request: &mut self.redraw_request,
             },
@@ -2938,16 +4607,229 @@ impl SceneController {
                         .abs_mut()
                         .scene_popping_return_handler(&mut suzu_ctx);
                 } else {
-                    eprintln!("Scene Stack is Empty!!");
+                    log_error!("scene stack is empty");
                 }
             }
         }
 
-        if self.global_clock % 120 == 0 {
-            println!("fps: {}", ggez::timer::fps(ctx));
+        self.update_color_grading();
+    }
+
+    ///
+    /// 場面が変わっていればその場面の色味に移し、台本からの色味の指示を当てる
+    ///
+    fn update_color_grading(&mut self) {
+        self.color_grading
+            .enter_scene(self.current_scene.scene_name(), self.global_clock);
+
+        for (preset, duration) in color_grading::take_requests() {
+            self.color_grading
+                .set_preset(preset, duration, self.global_clock);
         }
-        self.global_clock += 1;
-        self.redraw_request = scene::DrawRequest::Skip;
+
+        if self.color_grading.is_blending(self.global_clock) {
+            self.redraw_request = scene::DrawRequest::Draw;
+        }
+    }
+
+    ///
+    /// 速さを変えるキー。遅くする、速くするはいつでも使える。止める、一フレーム進めるはデバッグビルドだけ
+    ///
+    fn real_to_time_scale_command(keycode: KeyCode) -> Option<time_scale::TimeScaleCommand> {
+        match keycode {
+            KeyCode::F5 => Some(time_scale::TimeScaleCommand::Slower),
+            KeyCode::F6 => Some(time_scale::TimeScaleCommand::Faster),
+            KeyCode::F7 if cfg!(debug_assertions) => {
+                Some(time_scale::TimeScaleCommand::TogglePause)
+            }
+            KeyCode::F8 if cfg!(debug_assertions) => Some(time_scale::TimeScaleCommand::Step),
+            _ => None,
+        }
+    }
+
+    fn apply_time_scale_command(&mut self, command: time_scale::TimeScaleCommand) {
+        let before = self.simulation_clock.get_scale();
+        self.simulation_clock.apply(command);
+
+        let scale = self.simulation_clock.get_scale();
+        if scale != before {
+            // ゆっくり遊びたい人のために、次に起動したときも同じ速さにする
+            self.preferences.set_time_scale(scale);
+            self.preferences.save();
+        }
+
+        log_info!(
+            "time scale: {}{}",
+            scale.label(),
+            if self.simulation_clock.is_paused() {
+                " (paused)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    ///
+    /// 最近の記録を画面の下に出す、出さないを切り替える
+    ///
+    fn toggle_log_viewer(&mut self, game_data: &GameResource) {
+        self.log_viewer = match self.log_viewer {
+            Some(_) => None,
+            None => Some(crate::object::log_viewer::LogViewer::new(
+                numeric::Rect::new(
+                    10.0,
+                    WINDOW_SIZE_Y as f32 - 420.0,
+                    WINDOW_SIZE_X as f32 - 20.0,
+                    410.0,
+                ),
+                game_data.get_font(FontID::Cinema),
+            )),
+        };
+        self.redraw_request = scene::DrawRequest::Draw;
+    }
+
+    fn apply_log_command(&mut self, game_data: &GameResource, command: logger::LogCommand) {
+        match command {
+            logger::LogCommand::ShowFilter => {
+                log_info!("log filter: {}", logger::get_filter_spec())
+            }
+            logger::LogCommand::SetFilter(filter) => {
+                logger::set_filter(filter);
+                log_info!("log filter: {}", logger::get_filter_spec());
+            }
+            logger::LogCommand::ToggleViewer => self.toggle_log_viewer(game_data),
+        }
+    }
+
+    fn poll_debug_console(&mut self, game_data: &GameResource) {
+        let lines = match self.debug_console.as_ref() {
+            Some(console) => console.poll(),
+            None => return,
+        };
+
+        for line in lines {
+            match logger::LogCommand::parse(&line) {
+                Some(Ok(command)) => self.apply_log_command(game_data, command),
+                Some(Err(e)) => log_warn!("{}", e),
+                None => match time_scale::TimeScaleCommand::parse(&line) {
+                    Some(command) => self.apply_time_scale_command(command),
+                    None => log_warn!("unknown console command: {}", line),
+                },
+            }
+        }
+    }
+
+    ///
+    /// 空いているか一番古いクイックセーブの枠へ保存する。店の中を歩いている間だけ使える
+    ///
+    fn quicksave(&mut self, ctx: &mut Context, game_data: &mut GameResource) {
+        if !self.current_scene.abs().quicksave_available() {
+            return;
+        }
+
+        let message = match self.game_status.as_ref().map(quicksave::quicksave) {
+            Some(Ok(slot)) => format!("{}番ニ保存シマシタ", slot + 1),
+            Some(Err(e)) => {
+                log_warn!("failed to quicksave: {}", e);
+                "保存デキマセンデシタ".to_string()
+            }
+            None => return,
+        };
+
+        self.current_scene.abs_mut().quicksave_notification(
+            &mut SuzuContext {
+                context: ctx,
+                resource: game_data,
+                savable_data: &mut self.game_status,
+                config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
+                process_utility: ProcessUtility {
+                    redraw_request: &mut self.redraw_request,
+                },
+                permanent_save_data: &mut self.permanent_save_data,
+            },
+            message,
+        );
+        self.redraw_request = scene::DrawRequest::Draw;
+    }
+
+    ///
+    /// 一番新しいクイックセーブか自動保存を読み、その日の朝からやり直す
+    ///
+    fn quickload(&mut self, ctx: &mut Context, game_data: &mut GameResource) {
+        if !self.current_scene.abs().quicksave_available() {
+            return;
+        }
+
+        let data = match quicksave::quickload() {
+            Some(data) => data,
+            None => return,
+        };
+
+        game_data.stop_scene_bgm(ctx, scene::SceneID::SuzunaShop);
+        self.game_status.replace(data);
+        self.switch_scene_with_swap(ctx, game_data, scene::SceneID::Scenario);
+        self.redraw_request = scene::DrawRequest::Draw;
+    }
+
+    ///
+    /// ショートカットの一覧を操作するキー。Ctrl+Pで開け閉めする
+    ///
+    fn real_to_palette_key(keycode: KeyCode, keymods: KeyMods) -> Option<PaletteKey> {
+        match keycode {
+            KeyCode::P if keymods.contains(KeyMods::CTRL) => Some(PaletteKey::Toggle),
+            KeyCode::Up => Some(PaletteKey::Up),
+            KeyCode::Down => Some(PaletteKey::Down),
+            KeyCode::Return | KeyCode::NumpadEnter => Some(PaletteKey::Enter),
+            KeyCode::Escape => Some(PaletteKey::Escape),
+            KeyCode::Back => Some(PaletteKey::Backspace),
+            KeyCode::Tab => Some(PaletteKey::Tab),
+            _ => None,
+        }
+    }
+
+    fn shortcut_palette_event(
+        &mut self,
+        ctx: &mut Context,
+        game_data: &mut GameResource,
+        key: PaletteKey,
+    ) -> bool {
+        let consumed = self.current_scene.abs_mut().shortcut_palette_event(
+            &mut SuzuContext {
+                context: ctx,
+                resource: game_data,
+                savable_data: &mut self.game_status,
+                config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
+                process_utility: ProcessUtility {
+                    redraw_request: &mut self.redraw_request,
+                },
+                permanent_save_data: &mut self.permanent_save_data,
+            },
+            key,
+        );
+
+        if consumed {
+            self.redraw_request = scene::DrawRequest::Draw;
+        }
+
+        consumed
+    }
+
+    fn text_input_event(
+        &mut self,
+        ctx: &mut Context,
+        game_data: &mut GameResource,
+        character: char,
+    ) {
+        if character.is_control() {
+            return;
+        }
+
+        self.notify_input();
+        self.shortcut_palette_event(ctx, game_data, PaletteKey::Char(character));
     }
 
     fn key_down_event(
@@ -2955,21 +4837,61 @@ impl SceneController {
         ctx: &mut Context,
         game_data: &mut GameResource,
         keycode: KeyCode,
-        _keymods: KeyMods,
+        keymods: KeyMods,
         _repeat: bool,
     ) {
+        self.notify_input();
+
+        if let Some(command) = Self::real_to_time_scale_command(keycode) {
+            self.apply_time_scale_command(command);
+            return;
+        }
+
+        // 記録を画面で見られるのはデバッグビルドだけ
+        if keycode == KeyCode::F9 && cfg!(debug_assertions) {
+            self.toggle_log_viewer(game_data);
+            return;
+        }
+
+        match keycode {
+            KeyCode::F2 => {
+                self.quicksave(ctx, game_data);
+                return;
+            }
+            KeyCode::F3 => {
+                self.quickload(ctx, game_data);
+                return;
+            }
+            _ => (),
+        }
+
+        if let Some(palette_key) = Self::real_to_palette_key(keycode, keymods) {
+            if self.shortcut_palette_event(ctx, game_data, palette_key) {
+                return;
+            }
+        }
+
+        // 一覧に文字を打っている間は、シーンを操作しない
+        if self.current_scene.abs().shortcut_palette_is_open() {
+            return;
+        }
+
+        let vkey = self.real_to_virtual(keycode);
+
         self.current_scene.abs_mut().key_down_event(
             &mut SuzuContext {
                 context: ctx,
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
                 permanent_save_data: &mut self.permanent_save_data,
             },
-            self.key_map.real_to_virtual(keycode),
+            vkey,
         );
 
         self.redraw_request = scene::DrawRequest::Draw;
@@ -2982,82 +4904,201 @@ impl SceneController {
         keycode: KeyCode,
         _keymods: KeyMods,
     ) {
+        self.notify_input();
+
+        if self.current_scene.abs().shortcut_palette_is_open() {
+            return;
+        }
+
+        let vkey = self.real_to_virtual(keycode);
+
         self.current_scene.abs_mut().key_up_event(
             &mut SuzuContext {
                 context: ctx,
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
                 permanent_save_data: &mut self.permanent_save_data,
             },
-            self.key_map.real_to_virtual(keycode),
+            vkey,
         );
 
         self.redraw_request = scene::DrawRequest::Draw;
     }
 
-    fn mouse_motion_event<'a>(
+    ///
+    /// パッドのボタンを、割り当てた操作としてシーンへ届ける
+    /// 決定や上下のボタンは、キーボードのEnterや矢印キーと同じく一覧や選択肢に先に届ける
+    ///
+    fn gamepad_button_down_event(
         &mut self,
         ctx: &mut Context,
         game_data: &mut GameResource,
-        point: numeric::Point2f,
-        offset: numeric::Vector2f,
+        button: ginput::gamepad::gilrs::Button,
     ) {
-        self.current_scene.abs_mut().mouse_motion_event(
+        self.notify_input();
+
+        let action = match self.preferences.remap_gamepad_button(button) {
+            Some(action) => action,
+            None => return,
+        };
+
+        if let Some(palette_key) = action.palette_key() {
+            if self.shortcut_palette_event(ctx, game_data, palette_key) {
+                return;
+            }
+        }
+
+        if self.current_scene.abs().shortcut_palette_is_open() {
+            return;
+        }
+
+        if let gamepad::GamepadAction::Key(vkey) = action {
+            self.current_scene.abs_mut().key_down_event(
+                &mut SuzuContext {
+                    context: ctx,
+                    resource: game_data,
+                    savable_data: &mut self.game_status,
+                    config: &mut self.game_config,
+                    preferences: &mut self.preferences,
+                    input_history: &self.input_history,
+                    process_utility: ProcessUtility {
+                        redraw_request: &mut self.redraw_request,
+                    },
+                    permanent_save_data: &mut self.permanent_save_data,
+                },
+                vkey,
+            );
+
+            self.redraw_request = scene::DrawRequest::Draw;
+        }
+    }
+
+    fn gamepad_button_up_event(
+        &mut self,
+        ctx: &mut Context,
+        game_data: &mut GameResource,
+        button: ginput::gamepad::gilrs::Button,
+    ) {
+        self.notify_input();
+
+        if self.current_scene.abs().shortcut_palette_is_open() {
+            return;
+        }
+
+        if let Some(gamepad::GamepadAction::Key(vkey)) =
+            self.preferences.remap_gamepad_button(button)
+        {
+            self.current_scene.abs_mut().key_up_event(
+                &mut SuzuContext {
+                    context: ctx,
+                    resource: game_data,
+                    savable_data: &mut self.game_status,
+                    config: &mut self.game_config,
+                    preferences: &mut self.preferences,
+                    input_history: &self.input_history,
+                    process_utility: ProcessUtility {
+                        redraw_request: &mut self.redraw_request,
+                    },
+                    permanent_save_data: &mut self.permanent_save_data,
+                },
+                vkey,
+            );
+
+            self.redraw_request = scene::DrawRequest::Draw;
+        }
+    }
+
+    ///
+    /// 左スティックの傾きを覚えておき、遊びを除いた向きをシーンへ届ける
+    ///
+    fn gamepad_axis_event(
+        &mut self,
+        ctx: &mut Context,
+        game_data: &mut GameResource,
+        axis: ginput::gamepad::gilrs::Axis,
+        value: f32,
+    ) {
+        match axis {
+            ginput::gamepad::gilrs::Axis::LeftStickX => self.gamepad_stick.x = value,
+            ginput::gamepad::gilrs::Axis::LeftStickY => self.gamepad_stick.y = value,
+            _ => return,
+        }
+
+        self.notify_input();
+
+        if self.current_scene.abs().shortcut_palette_is_open() {
+            return;
+        }
+
+        let direction = gamepad::stick_direction(self.gamepad_stick);
+
+        self.current_scene.abs_mut().gamepad_stick_event(
             &mut SuzuContext {
                 context: ctx,
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
                 permanent_save_data: &mut self.permanent_save_data,
             },
-            point,
-            offset,
+            direction,
         );
     }
 
-    fn mouse_button_down_event(
+    fn mouse_motion_event<'a>(
         &mut self,
-        ctx: &mut ggez::Context,
+        ctx: &mut Context,
         game_data: &mut GameResource,
-        button: ginput::mouse::MouseButton,
         point: numeric::Point2f,
+        offset: numeric::Vector2f,
     ) {
-        self.current_scene.abs_mut().mouse_button_down_event(
+        self.notify_input();
+
+        self.current_scene.abs_mut().mouse_motion_event(
             &mut SuzuContext {
                 context: ctx,
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
                 permanent_save_data: &mut self.permanent_save_data,
             },
-            button,
             point,
+            offset,
         );
     }
 
-    fn mouse_button_up_event(
+    fn mouse_button_down_event(
         &mut self,
         ctx: &mut ggez::Context,
         game_data: &mut GameResource,
         button: ginput::mouse::MouseButton,
         point: numeric::Point2f,
     ) {
-        self.current_scene.abs_mut().mouse_button_up_event(
+        self.notify_input();
+
+        self.current_scene.abs_mut().mouse_button_down_event(
             &mut SuzuContext {
                 context: ctx,
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
@@ -3068,6 +5109,41 @@ impl SceneController {
         );
     }
 
+    fn mouse_button_up_event(
+        &mut self,
+        ctx: &mut ggez::Context,
+        game_data: &mut GameResource,
+        button: ginput::mouse::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        self.notify_input();
+
+        let mut suzu_ctx = SuzuContext {
+            context: ctx,
+            resource: game_data,
+            savable_data: &mut self.game_status,
+            config: &mut self.game_config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
+            process_utility: ProcessUtility {
+                redraw_request: &mut self.redraw_request,
+            },
+            permanent_save_data: &mut self.permanent_save_data,
+        };
+
+        // 不具合の通知の上でのクリックは、シーンに渡さない
+        if self
+            .error_notification
+            .click_handler(&mut suzu_ctx, point, self.global_clock)
+        {
+            return;
+        }
+
+        self.current_scene
+            .abs_mut()
+            .mouse_button_up_event(&mut suzu_ctx, button, point);
+    }
+
     fn mouse_wheel_scroll_event<'a>(
         &mut self,
         ctx: &mut ggez::Context,
@@ -3075,6 +5151,8 @@ impl SceneController {
         x: f32,
         y: f32,
     ) {
+        self.notify_input();
+
         let point = ggez::input::mouse::position(ctx);
         self.current_scene.abs_mut().mouse_wheel_event(
             &mut SuzuContext {
@@ -3082,6 +5160,8 @@ impl SceneController {
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
@@ -3103,6 +5183,8 @@ impl SceneController {
             resource: game_data,
             savable_data: &mut self.game_status,
             config: &mut self.game_config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
             process_utility: ProcessUtility {
                 redraw_request: &mut self.redraw_request,
             },
@@ -3118,6 +5200,8 @@ impl SceneController {
                 resource: game_data,
                 savable_data: &mut self.game_status,
                 config: &mut self.game_config,
+                preferences: &mut self.preferences,
+                input_history: &self.input_history,
                 process_utility: ProcessUtility {
                     redraw_request: &mut self.redraw_request,
                 },
@@ -3132,10 +5216,13 @@ pub struct State {
     scene_controller: SceneController,
     game_data: GameResource,
     window_scale: numeric::Vector2f,
+    input_queue: input_queue::InputQueue,
 }
 
 impl ggez::event::EventHandler for State {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.dispatch_input_events(ctx);
+
         self.scene_controller
             .run_pre_process(ctx, &mut self.game_data);
 
@@ -3167,68 +5254,110 @@ impl ggez::event::EventHandler for State {
     }
 
     fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        self.scene_controller.save_window_placement(ctx);
         ggez::event::quit(ctx);
         false
     }
 
     fn key_down_event(
         &mut self,
-        ctx: &mut ggez::Context,
+        _ctx: &mut ggez::Context,
         keycode: KeyCode,
         keymods: KeyMods,
         repeat: bool,
     ) {
-        self.scene_controller
-            .key_down_event(ctx, &mut self.game_data, keycode, keymods, repeat);
+        self.input_queue.push(
+            input_queue::InputEvent::KeyDown(keycode, keymods, repeat),
+            self.clock,
+        );
     }
 
-    fn key_up_event(&mut self, ctx: &mut ggez::Context, keycode: KeyCode, keymods: KeyMods) {
-        self.scene_controller
-            .key_up_event(ctx, &mut self.game_data, keycode, keymods);
+    fn key_up_event(&mut self, _ctx: &mut ggez::Context, keycode: KeyCode, keymods: KeyMods) {
+        self.input_queue
+            .push(input_queue::InputEvent::KeyUp(keycode, keymods), self.clock);
     }
 
-    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
-        self.scene_controller.mouse_motion_event(
-            ctx,
-            &mut self.game_data,
-            numeric::Point2f::new(x * self.window_scale.x, y * self.window_scale.y),
-            numeric::Vector2f::new(dx * self.window_scale.x, dy * self.window_scale.y),
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, dx: f32, dy: f32) {
+        self.input_queue.push(
+            input_queue::InputEvent::MouseMotion(
+                numeric::Point2f::new(x * self.window_scale.x, y * self.window_scale.y),
+                numeric::Vector2f::new(dx * self.window_scale.x, dy * self.window_scale.y),
+            ),
+            self.clock,
         );
     }
 
     fn mouse_button_down_event(
         &mut self,
-        ctx: &mut Context,
+        _ctx: &mut Context,
         button: ginput::mouse::MouseButton,
         x: f32,
         y: f32,
     ) {
-        self.scene_controller.mouse_button_down_event(
-            ctx,
-            &mut self.game_data,
-            button,
-            numeric::Point2f::new(x * self.window_scale.x, y * self.window_scale.y),
+        self.input_queue.push(
+            input_queue::InputEvent::MouseButtonDown(
+                button,
+                numeric::Point2f::new(x * self.window_scale.x, y * self.window_scale.y),
+            ),
+            self.clock,
         );
     }
 
     fn mouse_button_up_event(
         &mut self,
-        ctx: &mut Context,
+        _ctx: &mut Context,
         button: ginput::mouse::MouseButton,
         x: f32,
         y: f32,
     ) {
-        self.scene_controller.mouse_button_up_event(
-            ctx,
-            &mut self.game_data,
-            button,
-            numeric::Point2f::new(x * self.window_scale.x, y * self.window_scale.y),
+        self.input_queue.push(
+            input_queue::InputEvent::MouseButtonUp(
+                button,
+                numeric::Point2f::new(x * self.window_scale.x, y * self.window_scale.y),
+            ),
+            self.clock,
         );
     }
 
-    fn mouse_wheel_event(&mut self, ctx: &mut Context, x: f32, y: f32) {
-        self.scene_controller
-            .mouse_wheel_scroll_event(ctx, &mut self.game_data, x, y);
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, x: f32, y: f32) {
+        self.input_queue
+            .push(input_queue::InputEvent::MouseWheel(x, y), self.clock);
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        self.input_queue
+            .push(input_queue::InputEvent::TextInput(character), self.clock);
+    }
+
+    fn gamepad_button_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: ginput::gamepad::gilrs::Button,
+        _id: ginput::gamepad::GamepadId,
+    ) {
+        self.input_queue
+            .push(input_queue::InputEvent::GamepadButtonDown(button), self.clock);
+    }
+
+    fn gamepad_button_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: ginput::gamepad::gilrs::Button,
+        _id: ginput::gamepad::GamepadId,
+    ) {
+        self.input_queue
+            .push(input_queue::InputEvent::GamepadButtonUp(button), self.clock);
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        _ctx: &mut Context,
+        axis: ginput::gamepad::gilrs::Axis,
+        value: f32,
+        _id: ginput::gamepad::GamepadId,
+    ) {
+        self.input_queue
+            .push(input_queue::InputEvent::GamepadAxis(axis, value), self.clock);
     }
 
     fn focus_event(&mut self, ctx: &mut Context, gained: bool) {
@@ -3241,7 +5370,7 @@ impl ggez::event::EventHandler for State {
     }
 
     fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
-	println!("resize, {}, {}", width, height);
+	log_debug!("resize, {}, {}", width, height);
 	self.scene_controller.root_screen.set_scale(
 	    numeric::Vector2f::new(width / WINDOW_SIZE_X as f32, height / WINDOW_SIZE_Y as f32)
 	);
@@ -3252,25 +5381,66 @@ impl ggez::event::EventHandler for State {
 }
 
 impl State {
+    ///
+    /// 溜まった入力イベントを受け取った順にシーンへ届ける
+    ///
+    fn dispatch_input_events(&mut self, ctx: &mut Context) {
+        self.scene_controller.input_history.update_now(self.clock);
+
+        while let Some(timed_event) = self.input_queue.pop() {
+            self.scene_controller.input_history.record(&timed_event);
+
+            match timed_event.event {
+                input_queue::InputEvent::KeyDown(keycode, keymods, repeat) => self
+                    .scene_controller
+                    .key_down_event(ctx, &mut self.game_data, keycode, keymods, repeat),
+                input_queue::InputEvent::KeyUp(keycode, keymods) => self
+                    .scene_controller
+                    .key_up_event(ctx, &mut self.game_data, keycode, keymods),
+                input_queue::InputEvent::MouseMotion(point, offset) => self
+                    .scene_controller
+                    .mouse_motion_event(ctx, &mut self.game_data, point, offset),
+                input_queue::InputEvent::MouseButtonDown(button, point) => self
+                    .scene_controller
+                    .mouse_button_down_event(ctx, &mut self.game_data, button, point),
+                input_queue::InputEvent::MouseButtonUp(button, point) => self
+                    .scene_controller
+                    .mouse_button_up_event(ctx, &mut self.game_data, button, point),
+                input_queue::InputEvent::MouseWheel(x, y) => self
+                    .scene_controller
+                    .mouse_wheel_scroll_event(ctx, &mut self.game_data, x, y),
+                input_queue::InputEvent::TextInput(character) => self
+                    .scene_controller
+                    .text_input_event(ctx, &mut self.game_data, character),
+                input_queue::InputEvent::GamepadButtonDown(button) => self
+                    .scene_controller
+                    .gamepad_button_down_event(ctx, &mut self.game_data, button),
+                input_queue::InputEvent::GamepadButtonUp(button) => self
+                    .scene_controller
+                    .gamepad_button_up_event(ctx, &mut self.game_data, button),
+                input_queue::InputEvent::GamepadAxis(axis, value) => self
+                    .scene_controller
+                    .gamepad_axis_event(ctx, &mut self.game_data, axis, value),
+            }
+        }
+    }
+
     pub fn new(ctx: &mut Context, mut game_data: GameResource) -> GameResult<State> {
         let scene_controller = SceneController::new(ctx, &mut game_data);
 
-        game_data
-            .bgm_manager
-            .change_global_volume(scene_controller.game_config.bgm_volume);
-        game_data
-            .se_manager
-            .change_global_volume(scene_controller.game_config.se_volume);
+        game_data.change_bgm_volume(scene_controller.game_config.bgm_volume);
+        game_data.change_se_volume(scene_controller.game_config.se_volume);
 
 	let window_size = ggez::graphics::drawable_size(&ctx);
 
-	println!("window size -> ({}, {})", window_size.0, window_size.1);
+	log_debug!("window size -> ({}, {})", window_size.0, window_size.1);
 	
         let s = State {
             clock: 0,
             fps: 0.0,
             scene_controller: scene_controller,
             game_data: game_data,
+            input_queue: input_queue::InputQueue::new(),
 	    window_scale: numeric::Vector2f::new(WINDOW_SIZE_X as f32 / window_size.0, WINDOW_SIZE_Y as f32 / window_size.1),
         };
 
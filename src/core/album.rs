@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+use super::GensoDate;
+
+///
+/// アルバムの一頁に貼る写真の数
+///
+pub const ALBUM_PHOTOS_PER_PAGE: usize = 4;
+
+///
+/// 週の集計を作るまでに溜める日数
+///
+pub const ALBUM_DAYS_PER_WEEK: usize = 7;
+
+///
+/// # アルバムに貼る写真
+///
+/// ## DayReceipt
+/// 営業を終えた一日の受取。受け付けた数、稼ぎ、評価を残す
+///
+/// ## WeeklyChart
+/// 七日分の稼ぎを並べた週の集計。last_dateは集計した最後の日
+///
+/// ## CutsceneStill
+/// 寸劇で映った背景の一枚。textureはTextureIDの番号
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AlbumPhoto {
+    DayReceipt {
+        date: GensoDate,
+        completed_requests: u32,
+        earned_money: i32,
+        eval: f32,
+    },
+    WeeklyChart {
+        last_date: GensoDate,
+        daily_money: Vec<i32>,
+    },
+    CutsceneStill {
+        date: GensoDate,
+        texture: u32,
+    },
+}
+
+impl AlbumPhoto {
+    pub fn get_date(&self) -> GensoDate {
+        match self {
+            AlbumPhoto::DayReceipt { date, .. } => *date,
+            AlbumPhoto::WeeklyChart { last_date, .. } => *last_date,
+            AlbumPhoto::CutsceneStill { date, .. } => *date,
+        }
+    }
+
+    pub fn caption(&self) -> String {
+        match self {
+            AlbumPhoto::DayReceipt { date, .. } => format!("{} 受取", date.to_short_string()),
+            AlbumPhoto::WeeklyChart { last_date, .. } => {
+                format!("{}迄ノ一週間", last_date.to_short_string())
+            }
+            AlbumPhoto::CutsceneStill { date, .. } => format!("{} 一幕", date.to_short_string()),
+        }
+    }
+}
+
+///
+/// # 写真帖
+/// セーブデータごとに持つ、過ぎた日々の記録
+///
+/// ## photos
+/// 貼った順の写真
+///
+/// ## pending_week
+/// まだ週の集計にしていない日々の稼ぎ
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhotoAlbum {
+    photos: Vec<AlbumPhoto>,
+    pending_week: Vec<i32>,
+}
+
+impl PhotoAlbum {
+    pub fn new() -> Self {
+        PhotoAlbum {
+            photos: Vec::new(),
+            pending_week: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.photos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.photos.is_empty()
+    }
+
+    pub fn get_photos(&self) -> &Vec<AlbumPhoto> {
+        &self.photos
+    }
+
+    ///
+    /// 営業を終えた一日の受取を貼る。七日溜まれば週の集計も続けて貼る
+    ///
+    pub fn record_day(
+        &mut self,
+        date: GensoDate,
+        completed_requests: u32,
+        earned_money: i32,
+        eval: f32,
+    ) {
+        self.photos.push(AlbumPhoto::DayReceipt {
+            date: date,
+            completed_requests: completed_requests,
+            earned_money: earned_money,
+            eval: eval,
+        });
+
+        self.pending_week.push(earned_money);
+        if self.pending_week.len() >= ALBUM_DAYS_PER_WEEK {
+            let daily_money = std::mem::replace(&mut self.pending_week, Vec::new());
+            self.photos.push(AlbumPhoto::WeeklyChart {
+                last_date: date,
+                daily_money: daily_money,
+            });
+        }
+    }
+
+    ///
+    /// 寸劇の一枚を貼る。同じ背景は最初に見た一枚だけ残し、新たに貼れたときはtrueを返す
+    ///
+    pub fn record_still(&mut self, date: GensoDate, texture: u32) -> bool {
+        let already = self.photos.iter().any(|photo| match photo {
+            AlbumPhoto::CutsceneStill { texture: t, .. } => *t == texture,
+            _ => false,
+        });
+
+        if already {
+            return false;
+        }
+
+        self.photos.push(AlbumPhoto::CutsceneStill {
+            date: date,
+            texture: texture,
+        });
+        true
+    }
+
+    pub fn page_count(&self) -> usize {
+        ((self.photos.len() + ALBUM_PHOTOS_PER_PAGE - 1) / ALBUM_PHOTOS_PER_PAGE).max(1)
+    }
+
+    ///
+    /// page_index頁に貼られた写真。範囲外の頁は空になる
+    ///
+    pub fn page(&self, page_index: usize) -> &[AlbumPhoto] {
+        let begin = (page_index * ALBUM_PHOTOS_PER_PAGE).min(self.photos.len());
+        let end = (begin + ALBUM_PHOTOS_PER_PAGE).min(self.photos.len());
+        &self.photos[begin..end]
+    }
+}
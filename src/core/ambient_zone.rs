@@ -0,0 +1,246 @@
+use torifune::numeric;
+
+use super::SoundID;
+use crate::log_warn;
+
+///
+/// 区域の縁からこれだけ(タイル数)離れるまで、薄れながら聞こえる
+///
+pub const DEFAULT_AMBIENT_FALLOFF: f32 = 3.0;
+
+///
+/// 区域を移ったとき、聞こえる音が入れ替わり終わるまでのフレーム数
+///
+pub const AMBIENT_CROSSFADE_FRAMES: u32 = 90;
+
+///
+/// # 環境音の種類
+///
+/// ## Street
+/// 戸口の外の通りの賑わい
+///
+/// ## Birds
+/// 窓の外の鳥の声
+///
+/// ## Quiet
+/// 音の無い区域。ここでは他の環境音が遠のく
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmbientKind {
+    Street,
+    Birds,
+    Quiet,
+}
+
+impl AmbientKind {
+    ///
+    /// 実際に音を鳴らす種類
+    ///
+    pub const AUDIBLE: [AmbientKind; 2] = [AmbientKind::Street, AmbientKind::Birds];
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "street" => Some(AmbientKind::Street),
+            "birds" => Some(AmbientKind::Birds),
+            "quiet" => Some(AmbientKind::Quiet),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            AmbientKind::Street => "street",
+            AmbientKind::Birds => "birds",
+            AmbientKind::Quiet => "quiet",
+        }
+    }
+
+    pub fn sound_id(&self) -> Option<SoundID> {
+        match self {
+            AmbientKind::Street => Some(SoundID::AmbientStreet),
+            AmbientKind::Birds => Some(SoundID::AmbientBirds),
+            AmbientKind::Quiet => None,
+        }
+    }
+}
+
+///
+/// # 環境音の区域
+/// イベントマップのTOMLに[[ambient-zone]]として書く。位置と大きさはタイル数
+///
+/// ## volume
+/// 区域の中での音量。Quietでは、他の音をどれだけ遠のかせるか
+///
+/// ## falloff
+/// 区域の縁から、音が聞こえなくなるまでの距離
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbientZone {
+    pub kind: AmbientKind,
+    pub area: numeric::Rect,
+    pub volume: f32,
+    pub falloff: f32,
+}
+
+impl AmbientZone {
+    pub fn new(kind: AmbientKind, area: numeric::Rect, volume: f32, falloff: f32) -> Self {
+        AmbientZone {
+            kind: kind,
+            area: area,
+            volume: volume,
+            falloff: falloff,
+        }
+    }
+
+    pub fn from_toml_object(toml_script: &toml::Value) -> Option<Self> {
+        let kind_str = toml_script.get("sound")?.as_str()?;
+        let kind = match AmbientKind::from_str(kind_str) {
+            Some(kind) => kind,
+            None => {
+                log_warn!("unknown ambient sound: {}", kind_str);
+                return None;
+            }
+        };
+
+        let number = |key: &str| {
+            toml_script.get(key).and_then(|value| {
+                value
+                    .as_float()
+                    .or_else(|| value.as_integer().map(|i| i as f64))
+            })
+        };
+
+        Some(AmbientZone::new(
+            kind,
+            numeric::Rect::new(
+                number("x")? as f32,
+                number("y")? as f32,
+                number("w")? as f32,
+                number("h")? as f32,
+            ),
+            number("volume").unwrap_or(1.0) as f32,
+            number("falloff").unwrap_or(DEFAULT_AMBIENT_FALLOFF as f64) as f32,
+        ))
+    }
+
+    pub fn to_toml_value(&self) -> toml::Value {
+        let mut table = toml::value::Table::new();
+
+        table.insert(
+            "sound".to_string(),
+            toml::Value::String(self.kind.to_str().to_string()),
+        );
+        for (key, value) in [
+            ("x", self.area.x),
+            ("y", self.area.y),
+            ("w", self.area.w),
+            ("h", self.area.h),
+            ("volume", self.volume),
+            ("falloff", self.falloff),
+        ]
+        .iter()
+        {
+            table.insert(key.to_string(), toml::Value::Float(*value as f64));
+        }
+
+        toml::Value::Table(table)
+    }
+
+    ///
+    /// listener(タイル単位)での効き具合。区域の中なら1.0、縁からfalloff離れると0.0
+    ///
+    pub fn weight_at(&self, listener: numeric::Point2f) -> f32 {
+        let dx = (self.area.x - listener.x)
+            .max(listener.x - (self.area.x + self.area.w))
+            .max(0.0);
+        let dy = (self.area.y - listener.y)
+            .max(listener.y - (self.area.y + self.area.h))
+            .max(0.0);
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        if distance <= 0.0 {
+            1.0
+        } else if self.falloff <= 0.0 {
+            0.0
+        } else {
+            (1.0 - distance / self.falloff).max(0.0)
+        }
+    }
+}
+
+///
+/// listener(タイル単位)で聞こえるべき、鳴らす種類ごとの音量
+///
+pub fn target_levels(zones: &[AmbientZone], listener: numeric::Point2f) -> Vec<(AmbientKind, f32)> {
+    let strongest = |kind: AmbientKind| {
+        zones
+            .iter()
+            .filter(|zone| zone.kind == kind)
+            .map(|zone| zone.weight_at(listener) * zone.volume)
+            .fold(0.0, f32::max)
+    };
+
+    let quiet = strongest(AmbientKind::Quiet).min(1.0);
+
+    AmbientKind::AUDIBLE
+        .iter()
+        .map(|kind| (*kind, (strongest(*kind) * (1.0 - quiet)).max(0.0).min(1.0)))
+        .collect()
+}
+
+///
+/// # 環境音の混ぜ具合
+/// 聞こえるべき音量へ少しずつ寄せて、区域を移るときに音を入れ替える
+///
+/// ## levels
+/// 鳴らす種類ごとの、今の音量
+///
+pub struct AmbientMixer {
+    levels: Vec<(AmbientKind, f32)>,
+    fade_frames: u32,
+}
+
+impl AmbientMixer {
+    pub fn new(fade_frames: u32) -> Self {
+        AmbientMixer {
+            levels: AmbientKind::AUDIBLE
+                .iter()
+                .map(|kind| (*kind, 0.0))
+                .collect(),
+            fade_frames: fade_frames,
+        }
+    }
+
+    pub fn get_level(&self, kind: AmbientKind) -> f32 {
+        self.levels
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, level)| *level)
+            .unwrap_or(0.0)
+    }
+
+    pub fn iter_levels(&self) -> std::slice::Iter<'_, (AmbientKind, f32)> {
+        self.levels.iter()
+    }
+
+    ///
+    /// 一フレーム分、聞こえるべき音量へ寄せる
+    ///
+    pub fn update(&mut self, targets: &[(AmbientKind, f32)]) {
+        let step = 1.0 / self.fade_frames.max(1) as f32;
+
+        for (kind, level) in self.levels.iter_mut() {
+            let target = targets
+                .iter()
+                .find(|(k, _)| k == kind)
+                .map(|(_, target)| *target)
+                .unwrap_or(0.0);
+
+            *level = if *level < target {
+                (*level + step).min(target)
+            } else {
+                (*level - step).max(target)
+            };
+        }
+    }
+}
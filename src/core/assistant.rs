@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+
+use super::shop_expansion::{ShopExpansionState, ShopExpansionType};
+use super::BookInformation;
+
+///
+/// 手伝いを雇えるようになる評判。二間目を増築した後でなければ雇えない
+///
+pub const ASSISTANT_UNLOCK_REPUTATION: f32 = 75.0;
+
+///
+/// 手伝いが仕事をする間隔（店の時計で何分か）
+///
+pub const ASSISTANT_ACTION_INTERVAL: u32 = 20;
+
+///
+/// 配架を任せたとき、一度に棚へ戻す本の数
+///
+pub const ASSISTANT_SHELVING_PER_ACTION: usize = 2;
+
+///
+/// 挨拶を任せたとき、一度の挨拶で上がる評判
+///
+pub const ASSISTANT_GREETING_REPUTATION: f32 = 0.3;
+
+///
+/// 雇ったばかりの手伝いの失敗率
+///
+const BASE_ERROR_RATE: f32 = 0.35;
+
+///
+/// どれだけ仕込んでも残る失敗率
+///
+const MIN_ERROR_RATE: f32 = 0.05;
+
+///
+/// 仕込み一つあたりに下がる失敗率
+///
+const ERROR_RATE_PER_TRAINING: f32 = 0.01;
+
+///
+/// 手伝いが仕事を一つこなすごとに積む仕込み
+///
+const TRAINING_PER_ACTION: u32 = 1;
+
+///
+/// 手伝いの失敗を直してやるごとに積む仕込み。教えた方が身に付く
+///
+const TRAINING_PER_CORRECTION: u32 = 3;
+
+///
+/// # 手伝いに任せる仕事
+///
+/// ## Shelving
+/// 未配架の本を棚に戻す
+///
+/// ## Greeting
+/// 入ってきた客に挨拶をする
+///
+/// ## SimpleReturns
+/// 列の先頭が返却だけの客なら、代わりに受け付ける
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AssistantRole {
+    Shelving,
+    Greeting,
+    SimpleReturns,
+}
+
+impl AssistantRole {
+    pub const ALL: [AssistantRole; 3] = [
+        AssistantRole::Shelving,
+        AssistantRole::Greeting,
+        AssistantRole::SimpleReturns,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            AssistantRole::Shelving => "配架",
+            AssistantRole::Greeting => "挨拶",
+            AssistantRole::SimpleReturns => "返却受付",
+        }
+    }
+}
+
+///
+/// # 手伝いの失敗
+/// 直さずに店を閉めると、それぞれの分だけ評判が落ちる
+///
+/// ## MisShelved
+/// 本を違う棚に差してしまった。直すと未配架の本に戻る
+///
+/// ## UnrecordedReturn
+/// 返却を受け付けたのに記録し忘れた。直すと返却済みになる
+///
+/// ## RudeGreeting
+/// 客への挨拶がぞんざいだった
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AssistantMistake {
+    MisShelved(BookInformation),
+    UnrecordedReturn(String),
+    RudeGreeting,
+}
+
+impl AssistantMistake {
+    pub fn description(&self) -> String {
+        match self {
+            AssistantMistake::MisShelved(book) => format!("{} 誤配架", book.name),
+            AssistantMistake::UnrecordedReturn(title) => format!("{} 返却記録漏レ", title),
+            AssistantMistake::RudeGreeting => "無愛想ナ挨拶".to_string(),
+        }
+    }
+
+    ///
+    /// 直さずに店を閉めたときの評判の増減
+    ///
+    pub fn reputation_penalty(&self) -> f32 {
+        match self {
+            AssistantMistake::MisShelved(_) => -1.0,
+            AssistantMistake::UnrecordedReturn(_) => -1.5,
+            AssistantMistake::RudeGreeting => -0.5,
+        }
+    }
+}
+
+///
+/// # 店の手伝い
+///
+/// ## role
+/// 今任せている仕事。何も任せていなければNone
+///
+/// ## assigned_hour
+/// 最後に仕事を任せ直した時刻（時）。任せ直せるのは一時間に一度まで
+///
+/// ## training
+/// これまでに積んだ仕込み。多いほど失敗しなくなる
+///
+/// ## mistakes
+/// まだ直していない失敗
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssistantState {
+    unlocked: bool,
+    role: Option<AssistantRole>,
+    assigned_hour: Option<u8>,
+    training: u32,
+    mistakes: Vec<AssistantMistake>,
+}
+
+impl AssistantState {
+    pub fn new() -> Self {
+        AssistantState {
+            unlocked: false,
+            role: None,
+            assigned_hour: None,
+            training: 0,
+            mistakes: Vec::new(),
+        }
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.unlocked
+    }
+
+    ///
+    /// 二間目があり、評判も十分なら手伝いを雇う。新たに雇えたときだけtrueを返す
+    ///
+    pub fn try_unlock(&mut self, expansion: &ShopExpansionState, reputation: f32) -> bool {
+        if self.unlocked
+            || !expansion.is_purchased(ShopExpansionType::SecondRoom)
+            || reputation < ASSISTANT_UNLOCK_REPUTATION
+        {
+            return false;
+        }
+
+        self.unlocked = true;
+        true
+    }
+
+    pub fn get_role(&self) -> Option<AssistantRole> {
+        self.role
+    }
+
+    pub fn get_training(&self) -> u32 {
+        self.training
+    }
+
+    ///
+    /// 仕事を任せる。同じ時間のうちに任せ直そうとしたときはfalseを返す
+    ///
+    pub fn assign(&mut self, role: Option<AssistantRole>, hour: u8) -> bool {
+        if !self.unlocked || self.assigned_hour == Some(hour) || self.role == role {
+            return false;
+        }
+
+        self.role = role;
+        self.assigned_hour = Some(hour);
+        true
+    }
+
+    pub fn error_rate(&self) -> f32 {
+        (BASE_ERROR_RATE - self.training as f32 * ERROR_RATE_PER_TRAINING).max(MIN_ERROR_RATE)
+    }
+
+    ///
+    /// 0.0から1.0の乱数rollで、今回の仕事を失敗するか決める
+    ///
+    pub fn is_mistake(&self, roll: f32) -> bool {
+        roll < self.error_rate()
+    }
+
+    ///
+    /// 仕事を一つこなした結果を記録する
+    ///
+    pub fn record_action(&mut self, mistake: Option<AssistantMistake>) {
+        self.training += TRAINING_PER_ACTION;
+        if let Some(mistake) = mistake {
+            self.mistakes.push(mistake);
+        }
+    }
+
+    pub fn get_mistakes(&self) -> &Vec<AssistantMistake> {
+        &self.mistakes
+    }
+
+    ///
+    /// index番目の失敗を直す。直した失敗を返す
+    ///
+    pub fn correct(&mut self, index: usize) -> Option<AssistantMistake> {
+        if index >= self.mistakes.len() {
+            return None;
+        }
+
+        self.training += TRAINING_PER_CORRECTION;
+        Some(self.mistakes.remove(index))
+    }
+
+    ///
+    /// 店を閉めるときに呼ぶ。直されなかった失敗の分の評判の増減を返し、任せた仕事も解く
+    ///
+    pub fn close_day(&mut self) -> f32 {
+        let penalty = self
+            .mistakes
+            .drain(..)
+            .map(|mistake| mistake.reputation_penalty())
+            .sum();
+
+        self.role = None;
+        self.assigned_hour = None;
+        penalty
+    }
+}
@@ -0,0 +1,137 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{BookInformation, GameMode, GensoDate};
+use crate::log_warn;
+
+///
+/// # 閉店間際の一括配架の調整値
+/// resources/auto_shelving.tomlから読む。書かれていない値は既定値を使う
+///
+/// ## story_misfile_rate
+/// 通常の遊び方で、一冊を違う棚に差してしまう割合
+///
+/// ## time_attack_misfile_rate
+/// 熟練の遊び方での割合
+///
+/// ## assist_misfile_rate
+/// 受付の補助を受けているときの割合。遊び方の割合より高ければ、遊び方の方を使う
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoShelvingTuning {
+    pub story_misfile_rate: f32,
+    pub time_attack_misfile_rate: f32,
+    pub assist_misfile_rate: f32,
+}
+
+impl Default for AutoShelvingTuning {
+    fn default() -> Self {
+        AutoShelvingTuning {
+            story_misfile_rate: 0.2,
+            time_attack_misfile_rate: 0.35,
+            assist_misfile_rate: 0.1,
+        }
+    }
+}
+
+impl AutoShelvingTuning {
+    pub fn from_toml_str(s: &str) -> Self {
+        match toml::from_str(s) {
+            Ok(tuning) => tuning,
+            Err(e) => {
+                log_warn!("failed to parse auto shelving tuning: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    ///
+    /// 遊び方と補助の有無から、一冊を差し違える割合を決める。0.0から1.0に収める
+    ///
+    pub fn misfile_rate(&self, game_mode: &GameMode, assist: bool) -> f32 {
+        let mode_rate = if game_mode.is_story_mode() {
+            self.story_misfile_rate
+        } else {
+            self.time_attack_misfile_rate
+        };
+
+        let rate = if assist {
+            mode_rate.min(self.assist_misfile_rate)
+        } else {
+            mode_rate
+        };
+
+        rate.max(0.0).min(1.0)
+    }
+}
+
+///
+/// その日の一括配架に使う乱数。同じ日なら、やり直しても同じ本を差し違える
+///
+pub fn day_rng(date: &GensoDate) -> StdRng {
+    let seed = (date.season as u64 * 400) + (date.month as u64 * 32) + date.day as u64;
+    StdRng::seed_from_u64(seed.wrapping_mul(2654435761))
+}
+
+///
+/// itemsを一つずつ棚に戻し、正しく戻せたものと差し違えたものに分ける
+///
+pub fn split_misfiled<T, R: Rng>(
+    items: Vec<T>,
+    misfile_rate: f32,
+    rng: &mut R,
+) -> (Vec<T>, Vec<T>) {
+    items
+        .into_iter()
+        .partition(|_| rng.gen::<f32>() >= misfile_rate)
+}
+
+///
+/// # 一括配架で差し違えた本
+/// 翌日の朝に見つかり、未配架の本として差し直すことになる
+///
+/// ## filed_on
+/// 一括配架をした日。この日のうちは見つからない
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MisfiledBooks {
+    books: Vec<BookInformation>,
+    filed_on: Option<GensoDate>,
+}
+
+impl MisfiledBooks {
+    pub fn new() -> Self {
+        MisfiledBooks {
+            books: Vec::new(),
+            filed_on: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.books.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+
+    pub fn record(&mut self, books: Vec<BookInformation>, today: &GensoDate) {
+        self.books.extend(books);
+        self.filed_on = Some(*today);
+    }
+
+    ///
+    /// 一括配架をした日より後になっていれば、差し直す本を全て取り出す
+    ///
+    pub fn take_corrections(&mut self, today: &GensoDate) -> Vec<BookInformation> {
+        match self.filed_on {
+            Some(filed_on) if today.is_past(&filed_on) => {
+                self.filed_on = None;
+                self.books.drain(..).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
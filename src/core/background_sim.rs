@@ -0,0 +1,147 @@
+use rand::Rng;
+use torifune::core::Clock;
+
+///
+/// 机に向かっている間、店の時計が一分進むのに掛かるフレーム数
+///
+pub const DESK_FRAMES_PER_SHOP_MINUTE: Clock = 360;
+
+///
+/// 我慢の限りのこの割合まで待たされた客は、待ちくたびれたと知らせる
+///
+pub const RESTLESS_PATIENCE_RATIO: f32 = 0.75;
+
+///
+/// 机に向かっている間に、戸口で待たせておける客の数
+///
+pub const MAX_BACKGROUND_ARRIVALS: usize = 3;
+
+///
+/// # 机に向かっている間に店で起きたこと
+///
+/// ## CustomerArrived
+/// 客が戸口に来た
+///
+/// ## ArrivalGaveUp
+/// 戸口で待っていた客が、待ちくたびれて帰った
+///
+/// ## HourChimed
+/// 時の鐘が鳴った。何時になったか
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundEvent {
+    CustomerArrived,
+    ArrivalGaveUp,
+    HourChimed(u8),
+}
+
+///
+/// # 机に向かっている間の店の様子
+/// 店の画面を動かさずに、客の出入りと時の流れだけを軽く追う
+///
+/// ## elapsed
+/// 机に向かってからのフレーム数
+///
+/// ## start_minutes
+/// 机に向かったときの店の時計(0時からの分)
+///
+/// ## arrivals
+/// 戸口で待っている客が、来たときのelapsed
+///
+/// ## restless_warned
+/// 待ちくたびれたと既に知らせた、列の客の並んだ時刻
+///
+pub struct BackgroundShopSim {
+    elapsed: Clock,
+    start_minutes: u32,
+    patience: Clock,
+    arrivals: Vec<Clock>,
+    restless_warned: Vec<Clock>,
+}
+
+impl BackgroundShopSim {
+    pub fn new(start_minutes: u32, patience: Clock) -> Self {
+        BackgroundShopSim {
+            elapsed: 0,
+            start_minutes: start_minutes,
+            patience: patience,
+            arrivals: Vec::new(),
+            restless_warned: Vec::new(),
+        }
+    }
+
+    pub fn get_elapsed(&self) -> Clock {
+        self.elapsed
+    }
+
+    pub fn pending_arrivals(&self) -> usize {
+        self.arrivals.len()
+    }
+
+    ///
+    /// 今の店の時計が何時か
+    ///
+    pub fn current_hour(&self) -> u8 {
+        ((self.start_minutes + (self.elapsed / DESK_FRAMES_PER_SHOP_MINUTE) as u32) / 60) as u8
+    }
+
+    ///
+    /// 一フレーム進める。arrivalは、このフレームに客が来たかどうか
+    ///
+    pub fn tick(&mut self, arrival: bool) -> Vec<BackgroundEvent> {
+        let mut events = Vec::new();
+
+        let hour_before = self.current_hour();
+        self.elapsed += 1;
+        if self.current_hour() != hour_before {
+            events.push(BackgroundEvent::HourChimed(self.current_hour()));
+        }
+
+        if arrival && self.arrivals.len() < MAX_BACKGROUND_ARRIVALS {
+            self.arrivals.push(self.elapsed);
+            events.push(BackgroundEvent::CustomerArrived);
+        }
+
+        // 戸口で待つ客も、我慢の限りを超えれば帰ってしまう
+        let elapsed = self.elapsed;
+        let patience = self.patience;
+        let before = self.arrivals.len();
+        self.arrivals.retain(|since| elapsed - since <= patience);
+        for _ in self.arrivals.len()..before {
+            events.push(BackgroundEvent::ArrivalGaveUp);
+        }
+
+        events
+    }
+
+    ///
+    /// queued_atに列に並んだ客が、nowで初めて待ちくたびれたならtrue。一人につき一度だけ知らせる
+    ///
+    pub fn check_restless(&mut self, queued_at: Clock, now: Clock) -> bool {
+        let waited = now.saturating_sub(queued_at);
+        if (waited as f32) < self.patience as f32 * RESTLESS_PATIENCE_RATIO
+            || self.restless_warned.contains(&queued_at)
+        {
+            return false;
+        }
+
+        self.restless_warned.push(queued_at);
+        true
+    }
+
+    ///
+    /// 店に戻ったとき、戸口で待っていた客の数を返して、店へ入れる
+    ///
+    pub fn take_arrivals(&mut self) -> usize {
+        let count = self.arrivals.len();
+        self.arrivals.clear();
+        count
+    }
+}
+
+///
+/// add_timingフレームに一度の割合で、客が来るかを決める
+///
+pub fn roll_arrival<R: Rng>(rng: &mut R, add_timing: Clock) -> bool {
+    add_timing > 0 && rng.gen_range(0, add_timing) == 0
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+///
+/// # 計測したフレームごとの時間
+/// 単位はマイクロ秒
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameTimeSamples {
+    micros: Vec<u64>,
+}
+
+impl FrameTimeSamples {
+    pub fn new() -> Self {
+        FrameTimeSamples { micros: Vec::new() }
+    }
+
+    pub fn push(&mut self, elapsed: Duration) {
+        self.micros.push(elapsed.as_micros() as u64);
+    }
+
+    pub fn push_micros(&mut self, micros: u64) {
+        self.micros.push(micros);
+    }
+
+    pub fn len(&self) -> usize {
+        self.micros.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.micros.is_empty()
+    }
+
+    ///
+    /// 一つも計測していなければNone
+    ///
+    pub fn summary(&self) -> Option<FrameTimeSummary> {
+        if self.micros.is_empty() {
+            return None;
+        }
+
+        let mut sorted = self.micros.clone();
+        sorted.sort();
+
+        let frames = sorted.len();
+        Some(FrameTimeSummary {
+            frames: frames,
+            mean: sorted.iter().sum::<u64>() / frames as u64,
+            median: sorted[frames / 2],
+            p95: sorted[((frames * 95) / 100).min(frames - 1)],
+            max: sorted[frames - 1],
+        })
+    }
+}
+
+///
+/// # 計測結果のまとめ
+/// 単位はマイクロ秒
+///
+/// ## p95
+/// 遅い方から5%を除いた中で最も遅いフレーム
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameTimeSummary {
+    pub frames: usize,
+    pub mean: u64,
+    pub median: u64,
+    pub p95: u64,
+    pub max: u64,
+}
+
+impl FrameTimeSummary {
+    pub fn to_report(&self, label: &str) -> String {
+        format!(
+            "{:<16} frames {:>5}  mean {:>8.3}ms  median {:>8.3}ms  p95 {:>8.3}ms  max {:>8.3}ms",
+            label,
+            self.frames,
+            self.mean as f32 / 1000.0,
+            self.median as f32 / 1000.0,
+            self.p95 as f32 / 1000.0,
+            self.max as f32 / 1000.0
+        )
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use torifune::core::Clock;
+
+use super::{BookInformation, GensoDate};
+
+///
+/// 本市で受け付ける客の数
+///
+pub const BOOK_FAIR_REQUESTS: usize = 16;
+
+///
+/// 本市の窓口に同時に並ぶ客の数。台に並べる題名札も同じ数
+///
+pub const BOOK_FAIR_COUNTER_SLOTS: usize = 4;
+
+///
+/// 本市の制限時間。60fps前提で90秒
+///
+pub const BOOK_FAIR_TIME_LIMIT: Clock = 90 * 60;
+
+///
+/// 番付に載せる回数
+///
+pub const BOOK_FAIR_LEADERBOARD_SIZE: usize = 5;
+
+///
+/// 一人渡すごとの点数。続けて正しく渡すと、連続の数に応じて上乗せする
+///
+const SERVE_SCORE: i32 = 100;
+const COMBO_BONUS: i32 = 20;
+
+///
+/// 取り違えたときに引く点数
+///
+const MISS_PENALTY: i32 = 50;
+
+///
+/// 余った時間一秒あたりの点数
+///
+const TIME_BONUS_PER_SECOND: i32 = 5;
+
+///
+/// 一冊あたりの本市の売上
+///
+const FAIR_PRICE: i32 = 40;
+
+///
+/// 暦の上の季節。本市は季節ごとに一度だけ開く
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FairSeason {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl FairSeason {
+    pub fn from_month(month: u8) -> Self {
+        match month {
+            3..=5 => FairSeason::Spring,
+            6..=8 => FairSeason::Summer,
+            9..=11 => FairSeason::Autumn,
+            _ => FairSeason::Winter,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            FairSeason::Spring => "春",
+            FairSeason::Summer => "夏",
+            FairSeason::Autumn => "秋",
+            FairSeason::Winter => "冬",
+        }
+    }
+}
+
+///
+/// dateの属する季節。一月と二月は前の年の冬に数える
+///
+pub fn fair_season_of(date: &GensoDate) -> (u32, FairSeason) {
+    let season = FairSeason::from_month(date.month);
+    let year = if season == FairSeason::Winter && date.month <= 2 {
+        date.season.saturating_sub(1)
+    } else {
+        date.season
+    };
+
+    (year, season)
+}
+
+///
+/// # 本市の客一人分の求め
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FairRequest {
+    pub customer: String,
+    pub book: BookInformation,
+}
+
+impl FairRequest {
+    pub fn new(customer: String, book: BookInformation) -> Self {
+        FairRequest {
+            customer: customer,
+            book: book,
+        }
+    }
+}
+
+///
+/// # 本市一回分の結果
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookFairResult {
+    pub date: GensoDate,
+    pub served: u32,
+    pub missed: u32,
+    pub best_combo: u32,
+    pub score: i32,
+}
+
+impl BookFairResult {
+    pub fn earned_money(&self) -> i32 {
+        self.served as i32 * FAIR_PRICE
+    }
+
+    ///
+    /// 評判の増減。渡した数に応じて上がり、取り違えると下がる
+    ///
+    pub fn reputation_gain(&self) -> f32 {
+        (self.served as f32 * 0.5) - (self.missed as f32 * 0.5)
+    }
+}
+
+///
+/// # 本市の受付
+/// 窓口に並ぶ客と台の題名札を持ち、題名札を選んで客に渡す
+///
+/// ## waiting
+/// まだ窓口に来ていない客
+///
+/// ## counter
+/// 窓口の客。渡し終えた枠はNone
+///
+/// ## shelf
+/// 台の題名札。窓口の客と同じ並びにならないよう、ずらして置く
+///
+pub struct BookFairSession {
+    waiting: VecDeque<FairRequest>,
+    counter: Vec<Option<FairRequest>>,
+    shelf: Vec<Option<BookInformation>>,
+    selected_title: Option<usize>,
+    served: u32,
+    missed: u32,
+    combo: u32,
+    best_combo: u32,
+    score: i32,
+}
+
+impl BookFairSession {
+    pub fn new(requests: Vec<FairRequest>) -> Self {
+        let mut waiting: VecDeque<FairRequest> = requests.into_iter().collect();
+
+        let counter: Vec<Option<FairRequest>> = (0..BOOK_FAIR_COUNTER_SLOTS)
+            .map(|_| waiting.pop_front())
+            .collect();
+
+        let shelf = (0..BOOK_FAIR_COUNTER_SLOTS)
+            .map(|index| {
+                counter[(index + 1) % BOOK_FAIR_COUNTER_SLOTS]
+                    .as_ref()
+                    .map(|request| request.book.clone())
+            })
+            .collect();
+
+        BookFairSession {
+            waiting: waiting,
+            counter: counter,
+            shelf: shelf,
+            selected_title: None,
+            served: 0,
+            missed: 0,
+            combo: 0,
+            best_combo: 0,
+            score: 0,
+        }
+    }
+
+    pub fn get_counter(&self) -> &Vec<Option<FairRequest>> {
+        &self.counter
+    }
+
+    pub fn get_shelf(&self) -> &Vec<Option<BookInformation>> {
+        &self.shelf
+    }
+
+    pub fn get_selected_title(&self) -> Option<usize> {
+        self.selected_title
+    }
+
+    pub fn get_score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn get_combo(&self) -> u32 {
+        self.combo
+    }
+
+    ///
+    /// 窓口と列に残っている客の数
+    ///
+    pub fn remaining(&self) -> usize {
+        self.waiting.len() + self.counter.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    ///
+    /// 台の題名札を選ぶ。同じ札をもう一度選ぶと選び直しになる
+    ///
+    pub fn select_title(&mut self, index: usize) {
+        if self.shelf.get(index).map_or(true, |title| title.is_none()) {
+            return;
+        }
+
+        self.selected_title = if self.selected_title == Some(index) {
+            None
+        } else {
+            Some(index)
+        };
+    }
+
+    ///
+    /// 選んでいる題名札を窓口の客に渡す
+    ///
+    /// 渡せればSome。求めと合っていればtrue。札を選んでいないか、客のいない枠ならNone
+    ///
+    pub fn serve(&mut self, counter_index: usize) -> Option<bool> {
+        let title_index = self.selected_title?;
+        let request = self.counter.get(counter_index)?.as_ref()?;
+
+        if self.shelf[title_index].as_ref() != Some(&request.book) {
+            self.missed += 1;
+            self.combo = 0;
+            self.score = (self.score - MISS_PENALTY).max(0);
+            self.selected_title = None;
+            return Some(false);
+        }
+
+        self.score += SERVE_SCORE + (self.combo as i32 * COMBO_BONUS);
+        self.served += 1;
+        self.combo += 1;
+        self.best_combo = self.best_combo.max(self.combo);
+        self.selected_title = None;
+
+        // 空いた窓口には列の次の客が入り、その客の本は今渡した札の場所に置く
+        let next = self.waiting.pop_front();
+        self.shelf[title_index] = next.as_ref().map(|request| request.book.clone());
+        self.counter[counter_index] = next;
+
+        Some(true)
+    }
+
+    ///
+    /// 本市を締める。余った時間の分だけ点数を上乗せする
+    ///
+    pub fn finish(&self, date: GensoDate, remaining_time: Clock) -> BookFairResult {
+        let time_bonus = (remaining_time / 60) as i32 * TIME_BONUS_PER_SECOND;
+
+        BookFairResult {
+            date: date,
+            served: self.served,
+            missed: self.missed,
+            best_combo: self.best_combo,
+            score: self.score + time_bonus,
+        }
+    }
+}
+
+///
+/// # セーブデータごとの本市の記録
+///
+/// ## held_season
+/// 最後に本市を開いた季節
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookFairRecord {
+    results: Vec<BookFairResult>,
+    held_season: Option<(u32, FairSeason)>,
+}
+
+impl BookFairRecord {
+    ///
+    /// 始めた季節は本市を開いたものとして数え、最初の本市は次の季節に開く
+    ///
+    pub fn new(start: &GensoDate) -> Self {
+        BookFairRecord {
+            results: Vec::new(),
+            held_season: Some(fair_season_of(start)),
+        }
+    }
+
+    ///
+    /// dateの季節に、まだ本市を開いていないか
+    ///
+    pub fn is_due(&self, date: &GensoDate) -> bool {
+        self.held_season != Some(fair_season_of(date))
+    }
+
+    pub fn record(&mut self, result: BookFairResult) {
+        self.held_season = Some(fair_season_of(&result.date));
+        self.results.push(result);
+    }
+
+    pub fn get_results(&self) -> &Vec<BookFairResult> {
+        &self.results
+    }
+
+    ///
+    /// 点数の高い順に、BOOK_FAIR_LEADERBOARD_SIZE回分を返す
+    ///
+    pub fn leaderboard(&self) -> Vec<&BookFairResult> {
+        let mut ranking: Vec<&BookFairResult> = self.results.iter().collect();
+        ranking.sort_by(|a, b| b.score.cmp(&a.score));
+        ranking.truncate(BOOK_FAIR_LEADERBOARD_SIZE);
+        ranking
+    }
+}
@@ -0,0 +1,248 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+///
+/// 返却される本に、贋作か妖魔本が紛れ込む割合
+///
+pub const DEFAULT_ANOMALY_RATE: f32 = 0.03;
+
+///
+/// 紛れ込んだ本のうち、妖魔本である割合。残りは贋作
+///
+pub const FORBIDDEN_TOME_SHARE: f32 = 0.3;
+
+///
+/// # 紛れ込んだ怪しい本の種類
+///
+/// ## Forgery
+/// 鈴奈庵の蔵書を真似た贋作。蔵書印と紙に綻びがある
+///
+/// ## ForbiddenTome
+/// 人里に出回ってはいけない妖魔本。綴じ糸と妖気で見分ける
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BookAnomaly {
+    Forgery,
+    ForbiddenTome,
+}
+
+impl BookAnomaly {
+    ///
+    /// rateの確率で怪しい本の種類を選ぶ。紛れ込まなければNone
+    ///
+    pub fn random_select<R: Rng>(rng: &mut R, rate: f32) -> Option<Self> {
+        if rng.gen::<f32>() >= rate {
+            return None;
+        }
+
+        if rng.gen::<f32>() < FORBIDDEN_TOME_SHARE {
+            Some(BookAnomaly::ForbiddenTome)
+        } else {
+            Some(BookAnomaly::Forgery)
+        }
+    }
+
+    ///
+    /// 虫眼鏡で調べたときに、pointに綻びが見つかるならtrue
+    ///
+    pub fn has_tell(&self, point: InspectionPoint) -> bool {
+        match self {
+            BookAnomaly::Forgery => {
+                point == InspectionPoint::Seal || point == InspectionPoint::Paper
+            }
+            BookAnomaly::ForbiddenTome => {
+                point == InspectionPoint::Binding || point == InspectionPoint::Aura
+            }
+        }
+    }
+
+    pub fn expected_decision(&self) -> InspectionDecision {
+        match self {
+            BookAnomaly::Forgery => InspectionDecision::Confiscate,
+            BookAnomaly::ForbiddenTome => InspectionDecision::Report,
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            BookAnomaly::Forgery => "贋作",
+            BookAnomaly::ForbiddenTome => "妖魔本",
+        }
+    }
+}
+
+///
+/// # 検分の項目
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InspectionPoint {
+    Seal,
+    Paper,
+    Binding,
+    Aura,
+}
+
+impl InspectionPoint {
+    pub const ALL: [InspectionPoint; 4] = [
+        InspectionPoint::Seal,
+        InspectionPoint::Paper,
+        InspectionPoint::Binding,
+        InspectionPoint::Aura,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            InspectionPoint::Seal => "蔵書印",
+            InspectionPoint::Paper => "紙",
+            InspectionPoint::Binding => "綴じ糸",
+            InspectionPoint::Aura => "妖気",
+        }
+    }
+
+    ///
+    /// 虫眼鏡で調べたときに分かること
+    ///
+    pub fn finding(&self, anomaly: Option<BookAnomaly>) -> &'static str {
+        let has_tell = anomaly.map_or(false, |anomaly| anomaly.has_tell(*self));
+
+        match (self, has_tell) {
+            (InspectionPoint::Seal, false) => "鈴奈庵の印",
+            (InspectionPoint::Seal, true) => "印の朱が滲む",
+            (InspectionPoint::Paper, false) => "古い和紙",
+            (InspectionPoint::Paper, true) => "新しすぎる紙",
+            (InspectionPoint::Binding, false) => "普通の綴じ",
+            (InspectionPoint::Binding, true) => "見慣れぬ糸",
+            (InspectionPoint::Aura, false) => "何も感じない",
+            (InspectionPoint::Aura, true) => "ぞわりとする",
+        }
+    }
+}
+
+///
+/// # 検分した本の扱い
+///
+/// ## Accept
+/// そのまま受け取る
+///
+/// ## Confiscate
+/// 店で預かり、持ち主には返さない
+///
+/// ## Report
+/// 博麗神社に届け出る
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InspectionDecision {
+    Accept,
+    Confiscate,
+    Report,
+}
+
+impl InspectionDecision {
+    pub const ALL: [InspectionDecision; 3] = [
+        InspectionDecision::Accept,
+        InspectionDecision::Confiscate,
+        InspectionDecision::Report,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            InspectionDecision::Accept => "受け入れる",
+            InspectionDecision::Confiscate => "没収する",
+            InspectionDecision::Report => "届け出る",
+        }
+    }
+}
+
+///
+/// # 検分の結果
+///
+/// ## money
+/// 所持金の増減
+///
+/// ## reputation
+/// 評判の増減
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InspectionOutcome {
+    pub correct: bool,
+    pub money: i32,
+    pub reputation: f32,
+}
+
+///
+/// 本の正体と扱いから、検分の結果を決める
+/// 普通の本を疑うと客の機嫌を損ね、妖魔本を見逃すと大きく評判を落とす
+///
+pub fn judge(anomaly: Option<BookAnomaly>, decision: InspectionDecision) -> InspectionOutcome {
+    let (correct, money, reputation) = match (anomaly, decision) {
+        (None, InspectionDecision::Accept) => (true, 0, 0.0),
+        (None, _) => (false, -100, -2.0),
+        (Some(BookAnomaly::Forgery), InspectionDecision::Confiscate) => (true, 200, 1.0),
+        (Some(BookAnomaly::Forgery), InspectionDecision::Report) => (false, 0, 0.5),
+        (Some(BookAnomaly::Forgery), InspectionDecision::Accept) => (false, -300, -1.0),
+        (Some(BookAnomaly::ForbiddenTome), InspectionDecision::Report) => (true, 300, 3.0),
+        (Some(BookAnomaly::ForbiddenTome), InspectionDecision::Confiscate) => (false, 0, -1.0),
+        (Some(BookAnomaly::ForbiddenTome), InspectionDecision::Accept) => (false, -500, -5.0),
+    };
+
+    InspectionOutcome {
+        correct: correct,
+        money: money,
+        reputation: reputation,
+    }
+}
+
+///
+/// # これまでの検分の記録
+/// 見抜いた数が節目に達すると、小鈴の独り言で話が少し進む
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InspectionRecord {
+    pub confiscated_forgeries: u32,
+    pub reported_tomes: u32,
+    pub mistakes: u32,
+}
+
+impl InspectionRecord {
+    pub fn new() -> Self {
+        InspectionRecord {
+            confiscated_forgeries: 0,
+            reported_tomes: 0,
+            mistakes: 0,
+        }
+    }
+
+    ///
+    /// 検分の結果を記録し、節目に達していれば小鈴の独り言を返す
+    ///
+    pub fn record(
+        &mut self,
+        anomaly: Option<BookAnomaly>,
+        decision: InspectionDecision,
+    ) -> Option<&'static str> {
+        if !judge(anomaly, decision).correct {
+            self.mistakes += 1;
+            return None;
+        }
+
+        match anomaly {
+            Some(BookAnomaly::Forgery) => {
+                self.confiscated_forgeries += 1;
+                match self.confiscated_forgeries {
+                    1 => Some("誰がこんな贋作を作ったのかしら"),
+                    5 => Some("贋作の出処、マミゾウさんなら知ってるかも"),
+                    _ => None,
+                }
+            }
+            Some(BookAnomaly::ForbiddenTome) => {
+                self.reported_tomes += 1;
+                match self.reported_tomes {
+                    1 => Some("霊夢さんに知らせておかなくちゃ"),
+                    3 => Some("最近、妖魔本が多すぎる気がする"),
+                    _ => None,
+                }
+            }
+            None => None,
+        }
+    }
+}
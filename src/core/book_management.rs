@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
 use crate::parse_toml_file;
 
 use super::{GameResource, GensoDate};
@@ -11,7 +14,7 @@ pub struct DayNewBooks {
 }
 
 impl DayNewBooks {
-    pub fn from_toml_value(toml_value: &toml::Value) -> Self {
+    pub fn from_toml_value<R: Rng>(rng: &mut R, toml_value: &toml::Value) -> Self {
         let mut new_books = Vec::new();
 
         for books_information in toml_value.as_array().unwrap() {
@@ -28,7 +31,7 @@ impl DayNewBooks {
             );
 
             for _ in 0..book_num {
-                new_books.push(book_info.clone_with_new_id_condition());
+                new_books.push(book_info.clone_with_new_id_condition(rng));
             }
         }
 
@@ -41,12 +44,12 @@ impl DayNewBooks {
         self.new_books.clone()
     }
 
-    pub fn random(resource: &GameResource, num: usize, unit: usize) -> Self {
+    pub fn random(resource: &mut GameResource, num: usize, unit: usize) -> Self {
 	let mut books = Vec::new();
 	for _ in 0..num {
-	    let info = resource.book_random_select();
+	    let info = resource.book_random_select().clone();
 	    for _ in 0..unit {
-		books.push(info.clone_with_new_id_condition());
+		books.push(info.clone_with_new_id_condition(resource.rng()));
 	    }
 	}
 
@@ -79,7 +82,10 @@ impl NewBookSchedule {
                 date_data["day"].as_integer().unwrap() as u8,
             );
 
-            let day_new_books = DayNewBooks::from_toml_value(&struct_table["books_information"]);
+            let day_new_books = DayNewBooks::from_toml_value(
+                ctx.resource.rng(),
+                &struct_table["books_information"],
+            );
 
             schedule_map.insert(genso_date, day_new_books);
         }
@@ -93,3 +99,150 @@ impl NewBookSchedule {
         self.new_book_schedule.get(date)
     }
 }
+
+///
+/// 人気の本として掲示する冊数
+///
+pub const BESTSELLER_TITLES: usize = 5;
+
+///
+/// 人気の本を手前の棚に配架したときの追加収入（円）
+///
+pub const FRONT_SHELF_BESTSELLER_BONUS: i32 = 50;
+
+///
+/// 週間の集計に含める日数
+///
+const BOOK_STATS_WEEK_DAYS: i32 = 7;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct DailyBookCount {
+    date: GensoDate,
+    requested: u32,
+    lent: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct BookPopularityEntry {
+    total_requested: u32,
+    total_lent: u32,
+    daily: Vec<DailyBookCount>,
+}
+
+impl BookPopularityEntry {
+    fn new() -> Self {
+        BookPopularityEntry {
+            total_requested: 0,
+            total_lent: 0,
+            daily: Vec::new(),
+        }
+    }
+
+    fn daily_count_mut(&mut self, date: &GensoDate) -> &mut DailyBookCount {
+        // 集計期間より古い記録は捨てる
+        let week_first = date.clone().sub_days_chain(BOOK_STATS_WEEK_DAYS - 1);
+        self.daily.retain(|count| !week_first.is_past(&count.date));
+
+        match self.daily.iter().position(|count| count.date == *date) {
+            Some(index) => &mut self.daily[index],
+            None => {
+                self.daily.push(DailyBookCount {
+                    date: date.clone(),
+                    requested: 0,
+                    lent: 0,
+                });
+                self.daily.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn week_score(&self, today: &GensoDate) -> u32 {
+        let week_first = today.clone().sub_days_chain(BOOK_STATS_WEEK_DAYS - 1);
+
+        self.daily
+            .iter()
+            .filter(|count| !week_first.is_past(&count.date) && !count.date.is_past(today))
+            .map(|count| count.requested + count.lent)
+            .sum()
+    }
+}
+
+///
+/// # 本ごとの貸出の希望回数と貸出回数の記録
+/// 題名ごとに集計する
+///
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BookPopularityStats {
+    entries: HashMap<String, BookPopularityEntry>,
+}
+
+impl BookPopularityStats {
+    pub fn new() -> Self {
+        BookPopularityStats {
+            entries: HashMap::new(),
+        }
+    }
+
+    fn entry_mut(&mut self, name: &str) -> &mut BookPopularityEntry {
+        self.entries
+            .entry(name.to_string())
+            .or_insert_with(BookPopularityEntry::new)
+    }
+
+    pub fn record_request(&mut self, name: &str, date: &GensoDate) {
+        let entry = self.entry_mut(name);
+        entry.total_requested += 1;
+        entry.daily_count_mut(date).requested += 1;
+    }
+
+    pub fn record_lend(&mut self, name: &str, date: &GensoDate) {
+        let entry = self.entry_mut(name);
+        entry.total_lent += 1;
+        entry.daily_count_mut(date).lent += 1;
+    }
+
+    pub fn record_requested_books(&mut self, books: &[BookInformation], date: &GensoDate) {
+        for book in books {
+            self.record_request(&book.name, date);
+        }
+    }
+
+    pub fn record_lent_books(&mut self, books: &[BookInformation], date: &GensoDate) {
+        for book in books {
+            self.record_lend(&book.name, date);
+        }
+    }
+
+    pub fn get_total_requested(&self, name: &str) -> u32 {
+        self.entries
+            .get(name)
+            .map_or(0, |entry| entry.total_requested)
+    }
+
+    pub fn get_total_lent(&self, name: &str) -> u32 {
+        self.entries.get(name).map_or(0, |entry| entry.total_lent)
+    }
+
+    ///
+    /// todayまでの一週間で、希望回数と貸出回数の合計が多い順に題名を返す
+    /// 同数の場合は題名順
+    ///
+    pub fn top_titles_in_week(&self, today: &GensoDate, n: usize) -> Vec<(String, u32)> {
+        let mut ranking: Vec<(String, u32)> = self
+            .entries
+            .iter()
+            .map(|(name, entry)| (name.clone(), entry.week_score(today)))
+            .filter(|(_, score)| *score > 0)
+            .collect();
+
+        ranking.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranking.truncate(n);
+        ranking
+    }
+
+    pub fn is_bestseller(&self, name: &str, today: &GensoDate) -> bool {
+        self.top_titles_in_week(today, BESTSELLER_TITLES)
+            .iter()
+            .any(|(title, _)| title == name)
+    }
+}
@@ -0,0 +1,233 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use torifune::core::Clock;
+
+use crate::log_warn;
+
+///
+/// 場面ごとの色味の表
+///
+pub const COLOR_GRADING_TABLE_PATH: &str = "/color_grading.toml";
+
+///
+/// 場面が変わったとき、色味が移り変わるのに掛かるフレーム数
+///
+pub const SCENE_GRADE_BLEND_FRAMES: Clock = 60;
+
+///
+/// # 画面の色味
+///
+/// ## gain
+/// 赤、緑、青に掛ける倍率。画面に乗算で重ねる
+///
+/// ## lift
+/// 赤、緑、青に足す量。画面に加算で重ねる
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorGrade {
+    pub gain: [f32; 3],
+    pub lift: [f32; 3],
+}
+
+impl ColorGrade {
+    pub fn neutral() -> Self {
+        ColorGrade {
+            gain: [1.0, 1.0, 1.0],
+            lift: [0.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn is_neutral(&self) -> bool {
+        *self == Self::neutral()
+    }
+
+    ///
+    /// selfからtoへ、ratio(0.0 - 1.0)だけ寄せた色味
+    ///
+    pub fn lerp(&self, to: &ColorGrade, ratio: f32) -> ColorGrade {
+        let ratio = ratio.max(0.0).min(1.0);
+        let mix = |a: [f32; 3], b: [f32; 3]| {
+            [
+                a[0] + ((b[0] - a[0]) * ratio),
+                a[1] + ((b[1] - a[1]) * ratio),
+                a[2] + ((b[2] - a[2]) * ratio),
+            ]
+        };
+
+        ColorGrade {
+            gain: mix(self.gain, to.gain),
+            lift: mix(self.lift, to.lift),
+        }
+    }
+
+    ///
+    /// 一つの色(0.0 - 1.0)に、この色味を当てた結果
+    ///
+    pub fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let channel = |i: usize| ((rgb[i] * self.gain[i]) + self.lift[i]).max(0.0).min(1.0);
+        [channel(0), channel(1), channel(2)]
+    }
+}
+
+///
+/// # 色味の型
+///
+/// ## Neutral
+/// 色味を付けない
+///
+/// ## Sepia
+/// 思い出の場面に使う、懐かしい茶色
+///
+/// ## ColdBlue
+/// 張り詰めた場面に使う、冷たい青
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorGradePreset {
+    Neutral,
+    Sepia,
+    ColdBlue,
+}
+
+impl ColorGradePreset {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "neutral" => Some(ColorGradePreset::Neutral),
+            "sepia" => Some(ColorGradePreset::Sepia),
+            "cold-blue" => Some(ColorGradePreset::ColdBlue),
+            _ => None,
+        }
+    }
+
+    pub fn to_grade(&self) -> ColorGrade {
+        match self {
+            ColorGradePreset::Neutral => ColorGrade::neutral(),
+            ColorGradePreset::Sepia => ColorGrade {
+                gain: [1.0, 0.86, 0.66],
+                lift: [0.08, 0.05, 0.0],
+            },
+            ColorGradePreset::ColdBlue => ColorGrade {
+                gain: [0.78, 0.88, 1.0],
+                lift: [0.0, 0.02, 0.06],
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SceneGradeTable {
+    #[serde(default)]
+    scenes: HashMap<String, ColorGradePreset>,
+}
+
+///
+/// # 色味の移り変わり
+/// 前の色味から次の色味へ、durationフレーム掛けて寄せる
+///
+/// ## scene_presets
+/// 場面の名前ごとの色味。resources/color_grading.tomlから読む
+///
+/// ## scene
+/// 今いる場面の名前。同じ場面にいる間は、台本が変えた色味を保つ
+///
+pub struct ColorGrading {
+    from: ColorGrade,
+    to: ColorGrade,
+    preset: ColorGradePreset,
+    start: Clock,
+    duration: Clock,
+    scene_presets: HashMap<String, ColorGradePreset>,
+    scene: Option<String>,
+}
+
+impl ColorGrading {
+    pub fn new(scene_presets: HashMap<String, ColorGradePreset>) -> Self {
+        ColorGrading {
+            from: ColorGrade::neutral(),
+            to: ColorGrade::neutral(),
+            preset: ColorGradePreset::Neutral,
+            start: 0,
+            duration: 0,
+            scene_presets: scene_presets,
+            scene: None,
+        }
+    }
+
+    ///
+    /// 場面ごとの色味の表を読む。読めなければ、どの場面にも色味を付けない
+    ///
+    pub fn scene_presets_from_toml_str(s: &str) -> HashMap<String, ColorGradePreset> {
+        match toml::from_str::<SceneGradeTable>(s) {
+            Ok(table) => table.scenes,
+            Err(e) => {
+                log_warn!("failed to parse color grading table: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    pub fn get_preset(&self) -> ColorGradePreset {
+        self.preset
+    }
+
+    ///
+    /// nowから、durationフレーム掛けてpresetの色味に移る
+    ///
+    pub fn set_preset(&mut self, preset: ColorGradePreset, duration: Clock, now: Clock) {
+        self.from = self.current(now);
+        self.to = preset.to_grade();
+        self.preset = preset;
+        self.start = now;
+        self.duration = duration;
+    }
+
+    ///
+    /// 場面に入ったとき、その場面の色味に移る。表に無い場面では色味を外す
+    ///
+    pub fn enter_scene(&mut self, scene_name: &str, now: Clock) {
+        if self.scene.as_ref().map(|scene| scene.as_str()) == Some(scene_name) {
+            return;
+        }
+        self.scene = Some(scene_name.to_string());
+
+        let preset = self
+            .scene_presets
+            .get(scene_name)
+            .copied()
+            .unwrap_or(ColorGradePreset::Neutral);
+
+        if preset != self.preset {
+            self.set_preset(preset, SCENE_GRADE_BLEND_FRAMES, now);
+        }
+    }
+
+    pub fn current(&self, now: Clock) -> ColorGrade {
+        if self.duration == 0 || now >= self.start + self.duration {
+            return self.to;
+        }
+
+        let ratio = now.saturating_sub(self.start) as f32 / self.duration as f32;
+        self.from.lerp(&self.to, ratio)
+    }
+
+    pub fn is_blending(&self, now: Clock) -> bool {
+        now < self.start + self.duration
+    }
+}
+
+thread_local! {
+    static GRADE_REQUESTS: RefCell<Vec<(ColorGradePreset, Clock)>> = RefCell::new(Vec::new());
+}
+
+///
+/// 台本から色味を変える。SceneControllerが次のフレームで取り出して当てる
+///
+pub fn request(preset: ColorGradePreset, duration: Clock) {
+    GRADE_REQUESTS.with(|requests| requests.borrow_mut().push((preset, duration)));
+}
+
+pub fn take_requests() -> Vec<(ColorGradePreset, Clock)> {
+    GRADE_REQUESTS.with(|requests| requests.replace(Vec::new()))
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::Path;
+
+use ggez::conf::{FullscreenType, WindowMode, WindowSetup};
+use serde::{Deserialize, Serialize};
+
+use super::preferences::{WINDOW_MIN_HEIGHT, WINDOW_MIN_WIDTH};
+use super::{WINDOW_SIZE_X, WINDOW_SIZE_Y};
+use crate::{log_info, log_warn};
+
+///
+/// 起動時に読む画面と音の設定。手で書き換えるためのもので、ゲームからは書き込まない
+///
+pub const SETTINGS_PATH: &str = "./settings.toml";
+
+///
+/// # 起動時の設定
+/// 書かれていない項目は標準の値になる
+///
+/// ## resolution
+/// 窓の大きさ(幅, 高さ)
+///
+/// ## fullscreen
+/// 全画面で始めるか
+///
+/// ## vsync
+/// 垂直同期を待つか
+///
+/// ## master_volume
+/// BGMと効果音の両方に掛ける音量(0.0 - 1.0)
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub resolution: (f32, f32),
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub master_volume: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            resolution: (WINDOW_SIZE_X as f32, WINDOW_SIZE_Y as f32),
+            fullscreen: false,
+            vsync: true,
+            master_volume: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    ///
+    /// 読めなければNone。変な値は使える範囲に収める
+    ///
+    pub fn from_toml_str(s: &str) -> Option<Self> {
+        match toml::from_str::<Settings>(s) {
+            Ok(settings) => Some(settings.sanitized()),
+            Err(e) => {
+                log_warn!("failed to parse settings: {}", e);
+                None
+            }
+        }
+    }
+
+    ///
+    /// 設定ファイルを読む。無いか読めなければNone
+    ///
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let s = match fs::read_to_string(path.as_ref()) {
+            Ok(s) => s,
+            Err(_) => {
+                log_info!(
+                    "{} is not found. default settings are used.",
+                    path.as_ref().display()
+                );
+                return None;
+            }
+        };
+
+        Self::from_toml_str(&s)
+    }
+
+    pub fn load() -> Option<Self> {
+        Self::load_from_path(SETTINGS_PATH)
+    }
+
+    pub fn load_or_default() -> Self {
+        Self::load().unwrap_or_default()
+    }
+
+    fn sanitized(mut self) -> Self {
+        let (width, height) = self.resolution;
+        self.resolution = if width.is_finite() && height.is_finite() {
+            (width.max(WINDOW_MIN_WIDTH), height.max(WINDOW_MIN_HEIGHT))
+        } else {
+            Self::default().resolution
+        };
+
+        self.master_volume = if self.master_volume.is_finite() {
+            self.master_volume.max(0.0).min(1.0)
+        } else {
+            1.0
+        };
+
+        self
+    }
+
+    pub fn fullscreen_type(&self) -> FullscreenType {
+        if self.fullscreen {
+            FullscreenType::Desktop
+        } else {
+            FullscreenType::Windowed
+        }
+    }
+
+    pub fn apply_to_window_setup(&self, setup: WindowSetup) -> WindowSetup {
+        setup.vsync(self.vsync)
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        WindowMode::default()
+            .dimensions(self.resolution.0, self.resolution.1)
+            .fullscreen_type(self.fullscreen_type())
+            .resizable(false)
+    }
+}
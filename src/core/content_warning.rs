@@ -0,0 +1,101 @@
+use crate::log_warn;
+
+///
+/// 注意書きの選択肢を差し込む場面は、元の場面のIDにこの値を足したIDへ移す
+///
+pub const CONTENT_WARNING_ID_OFFSET: i32 = 100000;
+
+pub const SKIP_CHOICE_TEXT: &str = "飛バス";
+pub const WATCH_CHOICE_TEXT: &str = "見ル";
+
+///
+/// # 場面に付ける注意書きの種類
+/// シナリオのtomlでは、tags = ["scary", "loud"] のように書く
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentTag {
+    Scary,
+    Loud,
+}
+
+impl ContentTag {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "scary" => Some(ContentTag::Scary),
+            "loud" => Some(ContentTag::Loud),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContentTag::Scary => "怖イ描写",
+            ContentTag::Loud => "大キナ音",
+        }
+    }
+}
+
+///
+/// 名前の並びから注意書きを作る。知らない名前は読み飛ばし、同じ注意書きは一つにまとめる
+///
+pub fn parse_tags<'a, I: IntoIterator<Item = &'a str>>(names: I) -> Vec<ContentTag> {
+    let mut tags = Vec::new();
+
+    for name in names {
+        match ContentTag::from_str(name) {
+            Some(tag) if !tags.contains(&tag) => tags.push(tag),
+            Some(_) => (),
+            None => log_warn!("Unknown content tag: {}", name),
+        }
+    }
+
+    tags
+}
+
+///
+/// # 注意書きのある場面の扱い
+///
+/// ## offer_skip
+/// 場面の前に、飛ばすか見るかを選ばせる
+///
+/// ## mute_sudden_se
+/// 文字送りの音や声など、急に鳴る音を鳴らさない
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneTreatment {
+    pub offer_skip: bool,
+    pub mute_sudden_se: bool,
+}
+
+impl SceneTreatment {
+    pub fn as_is() -> Self {
+        SceneTreatment {
+            offer_skip: false,
+            mute_sudden_se: false,
+        }
+    }
+}
+
+///
+/// 注意書きの設定と場面の注意書きから、場面の扱いを決める
+///
+/// 飛ばす先が書かれている場面だけ、飛ばすかどうかを選ばせる。見る場合も、大きな音は控えめにする
+///
+pub fn scene_treatment(enabled: bool, tags: &[ContentTag], can_skip: bool) -> SceneTreatment {
+    if !enabled || tags.is_empty() {
+        return SceneTreatment::as_is();
+    }
+
+    SceneTreatment {
+        offer_skip: can_skip,
+        mute_sudden_se: tags.contains(&ContentTag::Loud),
+    }
+}
+
+///
+/// 場面の前に出す注意書きの文
+///
+pub fn warning_header(tags: &[ContentTag]) -> String {
+    let labels: Vec<&str> = tags.iter().map(|tag| tag.label()).collect();
+    format!("コノ先ニハ{}ガアリマス。飛バシマスカ？", labels.join("ヤ"))
+}
@@ -0,0 +1,143 @@
+use serde::Deserialize;
+
+///
+/// 流れる速さの段階。0段目は止める
+///
+pub const CREDITS_SPEED_LEVELS: [f32; 4] = [0.0, 0.6, 1.2, 3.6];
+
+///
+/// 開いたときの速さの段階
+///
+pub const DEFAULT_SPEED_LEVEL: usize = 2;
+
+///
+/// # 制作の一項
+///
+/// ## role
+/// 担当。右端の列に書く
+///
+/// ## names
+/// 担当した人の名前。一人一列で左へ並べる
+///
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CreditSection {
+    pub role: String,
+    pub names: Vec<String>,
+}
+
+///
+/// # 制作の一覧
+/// resources/credits.tomlから読む
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreditsTable {
+    #[serde(rename = "section")]
+    sections: Vec<CreditSection>,
+}
+
+impl CreditsTable {
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    pub fn get_sections(&self) -> &Vec<CreditSection> {
+        &self.sections
+    }
+
+    pub fn names_count(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| section.names.len())
+            .sum()
+    }
+}
+
+///
+/// # 流れ具合
+///
+/// ## offset
+/// 流れた長さ。lengthまで流れたら終わり
+///
+/// ## speed_level
+/// CREDITS_SPEED_LEVELSの何段目で流すか
+///
+#[derive(Debug, Clone)]
+pub struct CreditsScroll {
+    offset: f32,
+    length: f32,
+    speed_level: usize,
+}
+
+impl CreditsScroll {
+    pub fn new(length: f32) -> Self {
+        CreditsScroll {
+            offset: 0.0,
+            length: length,
+            speed_level: DEFAULT_SPEED_LEVEL,
+        }
+    }
+
+    ///
+    /// 一フレーム分流し、動いた長さを返す
+    ///
+    pub fn advance(&mut self) -> f32 {
+        let prev = self.offset;
+        self.offset = (self.offset + self.get_speed()).min(self.length);
+        self.offset - prev
+    }
+
+    ///
+    /// 速さの段階を一つ上げる。上げられなければfalse
+    ///
+    pub fn speed_up(&mut self) -> bool {
+        if self.speed_level + 1 < CREDITS_SPEED_LEVELS.len() {
+            self.speed_level += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// 速さの段階を一つ下げる。止まっていればfalse
+    ///
+    pub fn speed_down(&mut self) -> bool {
+        if self.speed_level > 0 {
+            self.speed_level -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn skip(&mut self) {
+        self.offset = self.length;
+    }
+
+    pub fn get_speed(&self) -> f32 {
+        CREDITS_SPEED_LEVELS[self.speed_level]
+    }
+
+    pub fn get_speed_level(&self) -> usize {
+        self.speed_level
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.speed_level == 0
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.offset >= self.length
+    }
+
+    pub fn get_offset(&self) -> f32 {
+        self.offset
+    }
+
+    ///
+    /// 奥の絵ほど小さいrateで、ゆっくり流す
+    ///
+    pub fn parallax(&self, rate: f32) -> f32 {
+        self.offset * rate
+    }
+}
@@ -0,0 +1,163 @@
+use torifune::core::Clock;
+
+///
+/// 客足の曲線の最初の値が何時の分か。開店の時刻
+///
+pub const ARRIVAL_CURVE_START_HOUR: u8 = 9;
+
+///
+/// いつもの客足に対する倍率がこれより低ければ閑散とした日
+///
+const QUIET_DAY_THRESHOLD: f32 = 0.85;
+
+///
+/// いつもの客足に対する倍率がこれより高ければ混み合う日
+///
+const BUSY_DAY_THRESHOLD: f32 = 1.2;
+
+///
+/// # 祭りの日
+/// その日は一日中、客足がscale倍になる
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FestivalDay {
+    pub name: String,
+    pub month: u8,
+    pub day: u8,
+    pub scale: f32,
+}
+
+///
+/// # その日の客足の見込み
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Busyness {
+    Quiet,
+    Normal,
+    Busy,
+    Festival,
+}
+
+impl Busyness {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Busyness::Quiet => "閑散",
+            Busyness::Normal => "平常",
+            Busyness::Busy => "繁盛",
+            Busyness::Festival => "祭リ",
+        }
+    }
+
+    ///
+    /// 朝の仕度の間に、配架の段取りの目安として伝える一言
+    ///
+    pub fn advice(&self) -> &'static str {
+        match self {
+            Busyness::Quiet => "今日ハ客足ガ少ナソウ",
+            Busyness::Normal => "今日ハイツモ通リノ客足",
+            Busyness::Busy => "今日ハ混ミソウ 配架ハ早メニ",
+            Busyness::Festival => "祭リデ大賑ワイ 配架ハ開店前ニ",
+        }
+    }
+}
+
+///
+/// # 一日の客足の曲線
+///
+/// ## base
+/// その曜日の、客が来る間隔の基準。小さいほど客が来やすい
+///
+/// ## reference
+/// 一週間をならした基準の間隔。混み具合を比べるのに使う
+///
+/// ## hourly
+/// ARRIVAL_CURVE_START_HOUR時から一時間ごとの客足の倍率
+///
+/// ## festival
+/// 今日が祭りの日なら、その祭り
+///
+#[derive(Debug, Clone)]
+pub struct ArrivalCurve {
+    base: Clock,
+    reference: Clock,
+    hourly: Vec<f32>,
+    festival: Option<FestivalDay>,
+}
+
+impl ArrivalCurve {
+    pub fn new(
+        base: Clock,
+        reference: Clock,
+        hourly: Vec<f32>,
+        festival: Option<FestivalDay>,
+    ) -> Self {
+        ArrivalCurve {
+            base: base,
+            reference: reference,
+            hourly: hourly,
+            festival: festival,
+        }
+    }
+
+    pub fn get_base(&self) -> Clock {
+        self.base
+    }
+
+    pub fn get_festival(&self) -> Option<&FestivalDay> {
+        self.festival.as_ref()
+    }
+
+    fn day_scale(&self) -> f32 {
+        self.festival
+            .as_ref()
+            .map_or(1.0, |festival| festival.scale)
+    }
+
+    ///
+    /// hour時の客足の倍率。曲線の外の時刻は、端の値をそのまま使う
+    ///
+    pub fn hourly_scale(&self, hour: u8) -> f32 {
+        if self.hourly.is_empty() {
+            return 1.0;
+        }
+
+        let index = hour.saturating_sub(ARRIVAL_CURVE_START_HOUR) as usize;
+        self.hourly[index.min(self.hourly.len() - 1)]
+    }
+
+    ///
+    /// hour時に客が来る間隔。random_add_customerはこの間隔に一度の割合で客を増やす
+    ///
+    pub fn add_timing(&self, hour: u8) -> Clock {
+        let scale = self.hourly_scale(hour) * self.day_scale();
+        if scale <= 0.0 {
+            return Clock::max_value();
+        }
+
+        ((self.base as f32 / scale) as Clock).max(1)
+    }
+
+    ///
+    /// 一日を通した混み具合の見込み
+    ///
+    pub fn expected_busyness(&self) -> Busyness {
+        if self.festival.is_some() {
+            return Busyness::Festival;
+        }
+
+        let mean = if self.hourly.is_empty() {
+            1.0
+        } else {
+            self.hourly.iter().sum::<f32>() / self.hourly.len() as f32
+        };
+        let relative = mean * (self.reference as f32 / self.base.max(1) as f32);
+
+        if relative < QUIET_DAY_THRESHOLD {
+            Busyness::Quiet
+        } else if relative > BUSY_DAY_THRESHOLD {
+            Busyness::Busy
+        } else {
+            Busyness::Normal
+        }
+    }
+}
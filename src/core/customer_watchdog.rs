@@ -0,0 +1,141 @@
+use torifune::core::Clock;
+use torifune::numeric;
+
+///
+/// これだけの間、進んでいない客は動けなくなったものとみなす
+/// 読書処で座って読んでいる間は止まっているので、それより長くとる
+///
+pub const STUCK_TIMEOUT: Clock = 15 * 60;
+
+///
+/// 動けなくなった客に、ルートを計算し直させる回数
+///
+pub const REPATH_ATTEMPTS: u8 = 2;
+
+///
+/// 前に見たときから、これ以上動いていれば進んでいるとみなす
+///
+const PROGRESS_DISTANCE: f32 = 4.0;
+
+///
+/// 近くの通れるタイルを探す範囲
+///
+const NEAREST_SEARCH_RADIUS: u32 = 8;
+
+///
+/// # 動けなくなった客への手当て
+///
+/// ## Repath
+/// 今いる場所からルートを計算し直させる
+///
+/// ## Teleport
+/// 近くの通れるタイルへ移して、ルートを計算し直させる
+///
+/// ## Despawn
+/// 何をしても動けないので、店から帰ってもらう
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogAction {
+    Keep,
+    Repath,
+    Teleport,
+    Despawn,
+}
+
+///
+/// # 客が進んでいるかの見張り
+///
+/// ## last_progress
+/// 最後に進んだのを見た時刻
+///
+/// ## attempts
+/// 進んでいないのを見つけて手当てした回数。進めば0に戻す
+///
+#[derive(Debug, Clone)]
+pub struct ProgressWatch {
+    last_position: numeric::Point2f,
+    last_progress: Clock,
+    attempts: u8,
+}
+
+impl ProgressWatch {
+    pub fn new(position: numeric::Point2f, t: Clock) -> Self {
+        ProgressWatch {
+            last_position: position,
+            last_progress: t,
+            attempts: 0,
+        }
+    }
+
+    ///
+    /// 今の位置を見て、手当てが要るかを返す
+    /// 手当てをしたら、また同じだけ待ってから次の手当てに移る
+    ///
+    pub fn observe(&mut self, position: numeric::Point2f, t: Clock) -> WatchdogAction {
+        let dx = position.x - self.last_position.x;
+        let dy = position.y - self.last_position.y;
+        if (dx * dx + dy * dy).sqrt() >= PROGRESS_DISTANCE {
+            self.last_position = position;
+            self.last_progress = t;
+            self.attempts = 0;
+            return WatchdogAction::Keep;
+        }
+
+        if t < self.last_progress + STUCK_TIMEOUT {
+            return WatchdogAction::Keep;
+        }
+
+        self.last_progress = t;
+        self.attempts += 1;
+        if self.attempts <= REPATH_ATTEMPTS {
+            WatchdogAction::Repath
+        } else if self.attempts == REPATH_ATTEMPTS + 1 {
+            WatchdogAction::Teleport
+        } else {
+            WatchdogAction::Despawn
+        }
+    }
+
+    pub fn stuck_since(&self) -> Clock {
+        self.last_progress
+    }
+
+    pub fn get_attempts(&self) -> u8 {
+        self.attempts
+    }
+}
+
+///
+/// fromから近い順に、通れるタイルを探す。fromが通れればfromを返す
+/// 距離はマンハッタン距離で測り、同じ距離なら上、左から順に見る
+///
+pub fn nearest_open_tile<F>(
+    from: numeric::Vector2u,
+    map_size: numeric::Vector2u,
+    is_blocked: F,
+) -> Option<numeric::Vector2u>
+where
+    F: Fn(numeric::Vector2u) -> bool,
+{
+    for radius in 0..=NEAREST_SEARCH_RADIUS as i64 {
+        for dy in -radius..=radius {
+            let rest = radius - dy.abs();
+            let mut dxs = vec![-rest, rest];
+            dxs.dedup();
+            for dx in dxs {
+                let x = from.x as i64 + dx;
+                let y = from.y as i64 + dy;
+                if x < 0 || y < 0 || x >= map_size.x as i64 || y >= map_size.y as i64 {
+                    continue;
+                }
+
+                let tile = numeric::Vector2u::new(x as u32, y as u32);
+                if !is_blocked(tile) {
+                    return Some(tile);
+                }
+            }
+        }
+    }
+
+    None
+}
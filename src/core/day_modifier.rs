@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// 昼食の効果が切れる時刻
+///
+pub const LUNCH_BUFF_UNTIL_HOUR: u32 = 17;
+
+///
+/// 昼食を食べると、どの献立でもこれだけ体力が戻る
+///
+pub const LUNCH_STAMINA_RESTORE: f32 = 30.0;
+
+///
+/// StaminaRegenが効いている間、一分ごとに戻る体力
+///
+pub const STAMINA_REGEN_PER_MINUTE: f32 = 0.1;
+
+///
+/// PatienceAuraが効いている間、客が待ってくれる時間の倍率
+///
+pub const PATIENCE_AURA_RATE: f32 = 1.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DayModifierType {
+    StaminaRegen,
+    PatienceAura,
+    Focus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LunchMenu {
+    Onigiri,
+    Soba,
+    Dango,
+}
+
+impl LunchMenu {
+    pub fn from_opecode(opecode: &str) -> Option<Self> {
+        match opecode {
+            "LunchOnigiri" => Some(LunchMenu::Onigiri),
+            "LunchSoba" => Some(LunchMenu::Soba),
+            "LunchDango" => Some(LunchMenu::Dango),
+            _ => None,
+        }
+    }
+
+    pub fn modifier_type(&self) -> DayModifierType {
+        match self {
+            LunchMenu::Onigiri => DayModifierType::StaminaRegen,
+            LunchMenu::Soba => DayModifierType::PatienceAura,
+            LunchMenu::Dango => DayModifierType::Focus,
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            LunchMenu::Onigiri => "握リ飯",
+            LunchMenu::Soba => "蕎麦",
+            LunchMenu::Dango => "団子",
+        }
+        .to_string()
+    }
+
+    pub fn effect_description(&self) -> String {
+        match self {
+            LunchMenu::Onigiri => "体力ガ少シヅツ戻ル",
+            LunchMenu::Soba => "客ガ長ク待ッテクレル",
+            LunchMenu::Dango => "手元ガ揺レナイ",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct TimedModifier {
+    modifier_type: DayModifierType,
+    until_minute: u32,
+}
+
+///
+/// # 時間を限って効く補正
+/// 時刻は店の時計の、その日の0時からの分で数える
+///
+/// ## now_minute
+/// 店の時計が最後に進んだ時刻。机の画面でもこの時刻で判定する
+///
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DayModifiers {
+    modifiers: Vec<TimedModifier>,
+    now_minute: u32,
+}
+
+impl DayModifiers {
+    pub fn new() -> Self {
+        DayModifiers {
+            modifiers: Vec::new(),
+            now_minute: 0,
+        }
+    }
+
+    pub fn to_minute(hour: u32, minute: u32) -> u32 {
+        (hour * 60) + minute
+    }
+
+    ///
+    /// until_minuteまで効く補正を加える。同じ種類の補正が既にあれば期限を延ばす
+    ///
+    pub fn add(&mut self, modifier_type: DayModifierType, until_minute: u32) {
+        for modifier in self.modifiers.iter_mut() {
+            if modifier.modifier_type == modifier_type {
+                modifier.until_minute = modifier.until_minute.max(until_minute);
+                return;
+            }
+        }
+
+        self.modifiers.push(TimedModifier {
+            modifier_type: modifier_type,
+            until_minute: until_minute,
+        });
+    }
+
+    ///
+    /// 時刻を進め、期限の切れた補正を捨てる
+    ///
+    pub fn update_minute(&mut self, now_minute: u32) {
+        self.now_minute = now_minute;
+        self.modifiers
+            .retain(|modifier| modifier.until_minute > now_minute);
+    }
+
+    pub fn is_active(&self, modifier_type: DayModifierType) -> bool {
+        self.modifiers.iter().any(|modifier| {
+            modifier.modifier_type == modifier_type && modifier.until_minute > self.now_minute
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.modifiers.clear();
+        self.now_minute = 0;
+    }
+}
@@ -0,0 +1,45 @@
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::thread;
+
+///
+/// # デバッグ用のコンソール
+/// 起動した端末に打った行を、別のスレッドで読んで溜めておく
+///
+pub struct DebugConsole {
+    receiver: mpsc::Receiver<String>,
+}
+
+impl DebugConsole {
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let stdin = std::io::stdin();
+            for line in stdin.lock().lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+
+                // ゲームが終わって受け手が居なくなったら読むのをやめる
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        DebugConsole { receiver: receiver }
+    }
+
+    ///
+    /// 前に呼んでから打たれた行を、空行を除いて取り出す
+    ///
+    pub fn poll(&self) -> Vec<String> {
+        self.receiver
+            .try_iter()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
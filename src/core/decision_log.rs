@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::GensoDate;
+use crate::log_debug;
+
+///
+/// # 物語の結末を左右する決断の種類
+///
+/// ## Refusal
+/// 客や依頼を断った
+///
+/// ## Confiscation
+/// 検分した本を没収した
+///
+/// ## Report
+/// 検分した本を博麗神社に届け出た
+///
+/// ## Policy
+/// 店の方針を選んだ。シナリオの選択肢にdecisionを書いたもの
+///
+/// ## Affinity
+/// 誰かとの仲が深まった節目
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DecisionKind {
+    Refusal,
+    Confiscation,
+    Report,
+    Policy,
+    Affinity,
+}
+
+impl DecisionKind {
+    pub const ALL: [DecisionKind; 5] = [
+        DecisionKind::Refusal,
+        DecisionKind::Confiscation,
+        DecisionKind::Report,
+        DecisionKind::Policy,
+        DecisionKind::Affinity,
+    ];
+
+    ///
+    /// tomlに書く名前から読む
+    ///
+    pub fn from_str(s: &str) -> Option<Self> {
+        DecisionKind::ALL
+            .iter()
+            .find(|kind| kind.to_str() == s)
+            .copied()
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DecisionKind::Refusal => "refusal",
+            DecisionKind::Confiscation => "confiscation",
+            DecisionKind::Report => "report",
+            DecisionKind::Policy => "policy",
+            DecisionKind::Affinity => "affinity",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DecisionKind::Refusal => "断リ",
+            DecisionKind::Confiscation => "没収",
+            DecisionKind::Report => "届出",
+            DecisionKind::Policy => "方針",
+            DecisionKind::Affinity => "縁",
+        }
+    }
+}
+
+///
+/// # 記録した決断一つ
+///
+/// ## key
+/// 何についての決断か。本の題名や、選択肢に書いたdecisionの名前
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub kind: DecisionKind,
+    pub key: String,
+    pub date: GensoDate,
+}
+
+///
+/// # 選ばれた結末
+///
+/// ## scenario
+/// 結末の場面のシナリオ。無ければそのまま終わりの画面に移る
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ending {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub scenario: Option<String>,
+    #[serde(default)]
+    pub base: f32,
+    #[serde(default)]
+    pub weights: HashMap<String, f32>,
+}
+
+impl Ending {
+    ///
+    /// 決断の記録から、この結末の点数を出す
+    ///
+    /// 決断ごとに、種類の重み("confiscation")と、その決断だけの重み("policy:late_night")を足す
+    ///
+    pub fn score(&self, log: &DecisionLog) -> f32 {
+        log.iter().fold(self.base, |score, decision| {
+            let kind = decision.kind.to_str();
+            let specific = format!("{}:{}", kind, decision.key);
+
+            score
+                + self.weights.get(kind).copied().unwrap_or(0.0)
+                + self.weights.get(&specific).copied().unwrap_or(0.0)
+        })
+    }
+}
+
+///
+/// # 決断から結末を選ぶ表
+/// resources/ending_table.tomlから読む
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct EndingTable {
+    #[serde(rename = "ending")]
+    endings: Vec<Ending>,
+}
+
+impl EndingTable {
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    ///
+    /// 点数の一番高い結末を選ぶ。同じ点数なら表の先に書いた方
+    ///
+    pub fn select(&self, log: &DecisionLog) -> Option<&Ending> {
+        let mut selected: Option<(&Ending, f32)> = None;
+
+        for ending in self.endings.iter() {
+            let score = ending.score(log);
+            if selected.map_or(true, |(_, best)| score > best) {
+                selected = Some((ending, score));
+            }
+        }
+
+        selected.map(|(ending, _)| ending)
+    }
+}
+
+///
+/// # 決断の記録
+///
+/// ## ending
+/// 物語の終わりに選ばれた結末。選ぶのは一度だけ
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionLog {
+    decisions: Vec<Decision>,
+    ending: Option<Ending>,
+}
+
+impl DecisionLog {
+    pub fn new() -> Self {
+        DecisionLog {
+            decisions: Vec::new(),
+            ending: None,
+        }
+    }
+
+    pub fn record(&mut self, kind: DecisionKind, key: &str, date: GensoDate) {
+        log_debug!("decision: {} {}", kind.to_str(), key);
+        self.decisions.push(Decision {
+            kind: kind,
+            key: key.to_string(),
+            date: date,
+        });
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Decision> {
+        self.decisions.iter()
+    }
+
+    pub fn count(&self, kind: DecisionKind) -> usize {
+        self.decisions
+            .iter()
+            .filter(|decision| decision.kind == kind)
+            .count()
+    }
+
+    ///
+    /// 一度でも記録した種類ごとの回数。終わりの画面の振り返りに使う
+    ///
+    pub fn summary(&self) -> Vec<(DecisionKind, usize)> {
+        DecisionKind::ALL
+            .iter()
+            .map(|kind| (*kind, self.count(*kind)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    pub fn get_ending(&self) -> Option<&Ending> {
+        self.ending.as_ref()
+    }
+
+    pub fn set_ending(&mut self, ending: Ending) {
+        self.ending = Some(ending);
+    }
+}
@@ -0,0 +1,111 @@
+use std::fs::File;
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::crypt;
+use super::GensoDate;
+use crate::log_warn;
+use crate::object::task_object::tt_main_component::CustomerRequest;
+use crate::object::task_object::tt_menu_component::KosuzuMemory;
+use crate::object::task_object::tt_sub_component::BorrowingRecordBookPageData;
+
+///
+/// 帳簿への書き込みがこの回数に達するたびに書きかけの頁を保存する
+///
+pub const AUTOSAVE_EDIT_INTERVAL: u32 = 3;
+
+const DESK_AUTOSAVE_PATH: &str = "./desk_autosave";
+
+///
+/// # 受付の途中で書いていた頁の控え
+/// 受付の途中でゲームが落ちても、次に机へ向かったときに続きから書けるようにする
+/// 受付を終えるか、机を離れると消す
+///
+/// ## request
+/// 応対していた客の依頼
+///
+/// ## page_index
+/// 書いていた頁の位置
+///
+/// ## date
+/// 控えを取った日。日付が違う控えは使わない
+///
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeskAutosave {
+    pub date: GensoDate,
+    pub request: CustomerRequest,
+    pub page_index: usize,
+    pub page_data: BorrowingRecordBookPageData,
+    pub kosuzu_memory: KosuzuMemory,
+}
+
+impl DeskAutosave {
+    pub fn new(
+        date: GensoDate,
+        request: CustomerRequest,
+        page_index: usize,
+        page_data: BorrowingRecordBookPageData,
+        kosuzu_memory: KosuzuMemory,
+    ) -> Self {
+        DeskAutosave {
+            date: date,
+            request: request,
+            page_index: page_index,
+            page_data: page_data,
+            kosuzu_memory: kosuzu_memory,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = File::create(DESK_AUTOSAVE_PATH)?;
+
+        file.write_all(
+            crypt::crypt_str(&serde_json::to_string(self).unwrap())
+                .unwrap()
+                .as_slice(),
+        )?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    pub fn load() -> Option<Self> {
+        let mut file = File::open(DESK_AUTOSAVE_PATH).ok()?;
+
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).ok()?;
+
+        let content = crypt::decrypt_str(&buf)?;
+        serde_json::from_str(&content).ok()
+    }
+
+    ///
+    /// dateの日に取った控えがあれば返す。別の日の控えは消す
+    ///
+    pub fn load_for(date: &GensoDate) -> Option<Self> {
+        let autosave = Self::load()?;
+
+        if autosave.date == *date {
+            Some(autosave)
+        } else {
+            Self::delete();
+            None
+        }
+    }
+
+    pub fn exists() -> bool {
+        std::path::Path::new(DESK_AUTOSAVE_PATH).exists()
+    }
+
+    pub fn delete() {
+        if !Self::exists() {
+            return;
+        }
+
+        match std::fs::remove_file(DESK_AUTOSAVE_PATH) {
+            Ok(_) => (),
+            Err(e) => log_warn!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use torifune::core::Clock;
+use torifune::numeric;
+
+use crate::log_warn;
+
+///
+/// 遊んでいる人が物を掴んでいるなどで起こせなかったとき、次に試すまでの時間
+///
+pub const NUISANCE_RETRY_DELAY: Clock = 90;
+
+///
+/// # 受付机で不意に起きる邪魔
+///
+/// ## Gust
+/// 戸口からの風で、押さえの無い紙が散らばる
+///
+/// ## Cat
+/// 猫が机に飛び乗って、物を一つ押しのける
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NuisanceKind {
+    Gust,
+    Cat,
+}
+
+impl NuisanceKind {
+    pub fn to_str(&self) -> &str {
+        match self {
+            NuisanceKind::Gust => "突風",
+            NuisanceKind::Cat => "猫",
+        }
+    }
+}
+
+///
+/// # 受付机の邪魔の調整値
+/// resources/desk_nuisance.tomlから読む。書かれていない値は既定値を使う
+///
+/// ## first_delay_seconds
+/// 受付を始めてから、最初の邪魔が起きうるまでの時間
+///
+/// ## min_interval_seconds, max_interval_seconds
+/// 邪魔と邪魔の間の時間。この間から毎回選ぶ
+///
+/// ## gust_weight, cat_weight
+/// どちらの邪魔が起きるかの重み。0にすればその邪魔は起きない
+///
+/// ## gust_max_papers
+/// 一度の風で飛ぶ紙の数の上限
+///
+/// ## gust_distance
+/// 紙が飛ぶ距離の上限
+///
+/// ## cat_push_distance
+/// 猫が物を押しのける距離
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeskNuisanceTuning {
+    pub enabled: bool,
+    pub first_delay_seconds: u64,
+    pub min_interval_seconds: u64,
+    pub max_interval_seconds: u64,
+    pub gust_weight: u32,
+    pub cat_weight: u32,
+    pub gust_max_papers: usize,
+    pub gust_distance: f32,
+    pub cat_push_distance: f32,
+}
+
+impl Default for DeskNuisanceTuning {
+    fn default() -> Self {
+        DeskNuisanceTuning {
+            enabled: true,
+            first_delay_seconds: 40,
+            min_interval_seconds: 60,
+            max_interval_seconds: 150,
+            gust_weight: 2,
+            cat_weight: 1,
+            gust_max_papers: 3,
+            gust_distance: 260.0,
+            cat_push_distance: 160.0,
+        }
+    }
+}
+
+impl DeskNuisanceTuning {
+    pub fn from_toml_str(s: &str) -> Self {
+        match toml::from_str(s) {
+            Ok(tuning) => tuning,
+            Err(e) => {
+                log_warn!("failed to parse desk nuisance tuning: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn pick_interval<R: Rng>(&self, rng: &mut R) -> Clock {
+        let min = self.min_interval_seconds.min(self.max_interval_seconds);
+        let max = self.min_interval_seconds.max(self.max_interval_seconds);
+        rng.gen_range(min, max + 1) * 60
+    }
+
+    fn pick_kind<R: Rng>(&self, rng: &mut R) -> Option<NuisanceKind> {
+        let total = self.gust_weight + self.cat_weight;
+        if total == 0 {
+            return None;
+        }
+
+        if rng.gen_range(0, total) < self.gust_weight {
+            Some(NuisanceKind::Gust)
+        } else {
+            Some(NuisanceKind::Cat)
+        }
+    }
+}
+
+///
+/// # 受付机の邪魔を起こす時を決める
+///
+/// ## next_at
+/// 次に邪魔が起きる時刻。Noneなら邪魔は起きない
+///
+pub struct DeskNuisanceScheduler {
+    tuning: DeskNuisanceTuning,
+    next_at: Option<Clock>,
+}
+
+impl DeskNuisanceScheduler {
+    ///
+    /// easyなら、調整値に関わらず邪魔は起きない
+    ///
+    pub fn new(tuning: DeskNuisanceTuning, easy: bool, t: Clock) -> Self {
+        let next_at = if tuning.enabled && !easy {
+            Some(t + tuning.first_delay_seconds * 60)
+        } else {
+            None
+        };
+
+        DeskNuisanceScheduler {
+            tuning: tuning,
+            next_at: next_at,
+        }
+    }
+
+    pub fn get_tuning(&self) -> &DeskNuisanceTuning {
+        &self.tuning
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.next_at.is_some()
+    }
+
+    pub fn get_next_at(&self) -> Option<Clock> {
+        self.next_at
+    }
+
+    ///
+    /// 途中で易しくしたときなどに、以後の邪魔を止める
+    ///
+    pub fn disable(&mut self) {
+        self.next_at = None;
+    }
+
+    ///
+    /// 時が来ていれば起きる邪魔を選び、次の時刻を決め直す
+    ///
+    pub fn poll<R: Rng>(&mut self, t: Clock, rng: &mut R) -> Option<NuisanceKind> {
+        match self.next_at {
+            Some(next_at) if next_at <= t => {
+                self.next_at = Some(t + self.tuning.pick_interval(rng));
+                self.tuning.pick_kind(rng)
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// 起こせなかった邪魔を、少し後にもう一度試す
+    ///
+    pub fn retry_later(&mut self, t: Clock) {
+        if self.next_at.is_some() {
+            self.next_at = Some(t + NUISANCE_RETRY_DELAY);
+        }
+    }
+}
+
+///
+/// 風で飛ばされた紙の行き先。positionからwindの向きへ飛び、sizeの紙がareaからはみ出ないよう収める
+///
+pub fn scatter_destination(
+    position: numeric::Point2f,
+    size: numeric::Vector2f,
+    area: numeric::Rect,
+    wind: numeric::Vector2f,
+) -> numeric::Point2f {
+    let max_x = (area.x + area.w - size.x).max(area.x);
+    let max_y = (area.y + area.h - size.y).max(area.y);
+
+    numeric::Point2f::new(
+        (position.x + wind.x).max(area.x).min(max_x),
+        (position.y + wind.y).max(area.y).min(max_y),
+    )
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+
+use super::decision_log::{DecisionKind, DecisionLog};
+use super::preferences::NICKNAME_PLACEHOLDER;
+use super::GensoDate;
+use crate::log_debug;
+
+///
+/// 一日の日記に選べる書き出しの数
+///
+pub const DIARY_PROMPT_MAX: usize = 3;
+
+///
+/// 書き出しの後に書き足せる文字数
+///
+pub const DIARY_TEXT_MAX_CHARS: usize = 24;
+
+///
+/// これ以上受け付けた日は、忙しかった日として扱う
+///
+const BUSY_DAY_REQUESTS: u32 = 6;
+
+///
+/// # 日記を書く日の出来事
+/// 書き出しを出すかどうかは、これだけを見て決める
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaySummary {
+    pub completed_requests: u32,
+    pub earned_money: i32,
+    pub refusals: usize,
+    pub confiscations: usize,
+}
+
+impl DaySummary {
+    ///
+    /// 受付の集計と、その日に記録した決断からまとめる
+    ///
+    pub fn new(
+        completed_requests: u32,
+        earned_money: i32,
+        log: &DecisionLog,
+        today: &GensoDate,
+    ) -> Self {
+        let count_today = |kind: DecisionKind| {
+            log.iter()
+                .filter(|decision| decision.kind == kind && &decision.date == today)
+                .count()
+        };
+
+        DaySummary {
+            completed_requests: completed_requests,
+            earned_money: earned_money,
+            refusals: count_today(DecisionKind::Refusal),
+            confiscations: count_today(DecisionKind::Confiscation)
+                + count_today(DecisionKind::Report),
+        }
+    }
+}
+
+///
+/// # 書き出しを出す条件
+///
+/// ## Confiscated
+/// 妖魔本を没収したか、届け出た日
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiaryCondition {
+    Always,
+    Busy,
+    Quiet,
+    Earned,
+    Refused,
+    Confiscated,
+}
+
+impl DiaryCondition {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "always" => Some(DiaryCondition::Always),
+            "busy" => Some(DiaryCondition::Busy),
+            "quiet" => Some(DiaryCondition::Quiet),
+            "earned" => Some(DiaryCondition::Earned),
+            "refused" => Some(DiaryCondition::Refused),
+            "confiscated" => Some(DiaryCondition::Confiscated),
+            _ => None,
+        }
+    }
+
+    pub fn is_met(&self, day: &DaySummary) -> bool {
+        match self {
+            DiaryCondition::Always => true,
+            DiaryCondition::Busy => day.completed_requests >= BUSY_DAY_REQUESTS,
+            DiaryCondition::Quiet => day.completed_requests < BUSY_DAY_REQUESTS / 2,
+            DiaryCondition::Earned => day.earned_money > 0,
+            DiaryCondition::Refused => day.refusals > 0,
+            DiaryCondition::Confiscated => day.confiscations > 0,
+        }
+    }
+}
+
+///
+/// # 日記の書き出し
+///
+/// ## when
+/// 書き出しを出す日の条件。書かなければ毎日出す
+///
+/// ## flag
+/// 選んだときに立てる印。シナリオのswitchがflagで見る
+///
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DiaryPrompt {
+    pub text: String,
+    #[serde(default)]
+    pub when: Option<String>,
+    #[serde(default)]
+    pub flag: Option<String>,
+}
+
+impl DiaryPrompt {
+    ///
+    /// 読めない条件の書き出しは出さない
+    ///
+    pub fn is_available(&self, day: &DaySummary) -> bool {
+        match self.when.as_ref() {
+            Some(when) => DiaryCondition::parse(when).map_or(false, |cond| cond.is_met(day)),
+            None => true,
+        }
+    }
+}
+
+///
+/// # 日記の書き出しの表
+/// resources/diary_prompts.tomlから読む
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiaryPromptTable {
+    #[serde(rename = "prompt")]
+    prompts: Vec<DiaryPrompt>,
+}
+
+impl DiaryPromptTable {
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    ///
+    /// 書き出しの中の呼び名の印を、nicknameに置き換える
+    ///
+    pub fn with_nickname(mut self, nickname: &str) -> Self {
+        for prompt in self.prompts.iter_mut() {
+            prompt.text = prompt.text.replace(NICKNAME_PLACEHOLDER, nickname);
+        }
+        self
+    }
+
+    pub fn available(&self, day: &DaySummary) -> Vec<DiaryPrompt> {
+        self.prompts
+            .iter()
+            .filter(|prompt| prompt.is_available(day))
+            .cloned()
+            .collect()
+    }
+}
+
+///
+/// # 一日分の日記
+///
+/// ## fragments
+/// 選んだ書き出し
+///
+/// ## text
+/// 書き足した文
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiaryEntry {
+    pub date: GensoDate,
+    pub fragments: Vec<String>,
+    pub text: String,
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+impl DiaryEntry {
+    pub fn compose(date: GensoDate, prompts: &[DiaryPrompt], text: &str) -> Self {
+        DiaryEntry {
+            date: date,
+            fragments: prompts.iter().map(|prompt| prompt.text.clone()).collect(),
+            text: text.chars().take(DIARY_TEXT_MAX_CHARS).collect(),
+            flags: prompts
+                .iter()
+                .filter_map(|prompt| prompt.flag.clone())
+                .collect(),
+        }
+    }
+
+    ///
+    /// 書き出しと書き足した文を続けて読めるようにする
+    ///
+    pub fn body_text(&self) -> String {
+        let mut body = self.fragments.join("");
+        body.push_str(&self.text);
+        body
+    }
+}
+
+///
+/// # 小鈴の日記
+/// 書いた日記は店のメニューから読み返せる
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Diary {
+    entries: Vec<DiaryEntry>,
+}
+
+impl Diary {
+    pub fn new() -> Self {
+        Diary {
+            entries: Vec::new(),
+        }
+    }
+
+    ///
+    /// 日記を書く。同じ日に書き直した場合は置き換える
+    ///
+    pub fn write(&mut self, entry: DiaryEntry) {
+        log_debug!("diary: {} {:?}", entry.date.to_short_string(), entry.flags);
+        self.entries.retain(|written| written.date != entry.date);
+        self.entries.push(entry);
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.flags.iter().any(|f| f == flag))
+    }
+
+    ///
+    /// 新しい日記から順に返す
+    ///
+    pub fn iter_recent(&self) -> impl Iterator<Item = &DiaryEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use torifune::core::Clock;
+
+use super::request_generator::{RequestGenerator, REQUEST_MAX_BOOKS, RETURN_DATE_MAX_OFFSET};
+use super::{GameMode, GensoDate};
+use crate::log_warn;
+
+///
+/// # その日の難しさ
+///
+/// ## spawn_scale
+/// 客足の倍率。大きいほど客が来やすい
+///
+/// ## max_books
+/// 一つの依頼に含まれる本の最大冊数
+///
+/// ## short_term_rate
+/// 貸出の期間が短期になる割合。短期が多いほど返却期限が詰まる
+///
+/// ## return_date_offset
+/// 返却に来る客の返却期限が、今日から何日までずれるか
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    pub spawn_scale: f32,
+    pub max_books: usize,
+    pub short_term_rate: f32,
+    pub return_date_offset: i32,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty {
+            spawn_scale: 1.0,
+            max_books: REQUEST_MAX_BOOKS,
+            short_term_rate: 0.5,
+            return_date_offset: RETURN_DATE_MAX_OFFSET,
+        }
+    }
+}
+
+impl Difficulty {
+    ///
+    /// 客が来る間隔を、客足の倍率で縮める
+    ///
+    pub fn scale_add_timing(&self, add_timing: Clock) -> Clock {
+        if self.spawn_scale <= 0.0 {
+            return Clock::max_value();
+        }
+
+        ((add_timing as f32 / self.spawn_scale) as Clock).max(1)
+    }
+
+    ///
+    /// この難しさで依頼を作るRequestGenerator
+    ///
+    pub fn request_generator(&self) -> RequestGenerator {
+        RequestGenerator::new(self.max_books)
+            .with_short_term_rate(self.short_term_rate)
+            .with_return_date_offset(self.return_date_offset)
+    }
+}
+
+///
+/// # 難しさの調整値
+/// resources/difficulty.tomlから読む。書かれていない値は既定値を使う
+/// 最初の日のstart_*から、ramp_days日かけてend_*へ移っていく
+///
+/// ## ramp_days
+/// 最も難しくなるまでの日数
+///
+/// ## time_attack_full
+/// 熟練の遊び方では、最初の日から最も難しくする
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyTuning {
+    pub ramp_days: u32,
+    pub time_attack_full: bool,
+    pub start_spawn_scale: f32,
+    pub end_spawn_scale: f32,
+    pub start_max_books: usize,
+    pub end_max_books: usize,
+    pub start_short_term_rate: f32,
+    pub end_short_term_rate: f32,
+    pub start_return_date_offset: i32,
+    pub end_return_date_offset: i32,
+}
+
+impl Default for DifficultyTuning {
+    fn default() -> Self {
+        DifficultyTuning {
+            ramp_days: 28,
+            time_attack_full: true,
+            start_spawn_scale: 0.8,
+            end_spawn_scale: 1.3,
+            start_max_books: 2,
+            end_max_books: REQUEST_MAX_BOOKS,
+            start_short_term_rate: 0.3,
+            end_short_term_rate: 0.6,
+            start_return_date_offset: 1,
+            end_return_date_offset: RETURN_DATE_MAX_OFFSET,
+        }
+    }
+}
+
+impl DifficultyTuning {
+    pub fn from_toml_str(s: &str) -> Self {
+        match toml::from_str(s) {
+            Ok(tuning) => tuning,
+            Err(e) => {
+                log_warn!("failed to parse difficulty tuning: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    ///
+    /// 最初の日からdays日経ったときの進み具合。0.0から1.0に収める
+    ///
+    pub fn progress(&self, days: u32) -> f32 {
+        if self.ramp_days == 0 {
+            return 1.0;
+        }
+
+        (days as f32 / self.ramp_days as f32).min(1.0)
+    }
+
+    ///
+    /// 進み具合progress(0.0 - 1.0)での難しさ
+    ///
+    pub fn at_progress(&self, progress: f32) -> Difficulty {
+        let progress = progress.max(0.0).min(1.0);
+        let lerp = |start: f32, end: f32| start + (end - start) * progress;
+
+        Difficulty {
+            spawn_scale: lerp(self.start_spawn_scale, self.end_spawn_scale).max(0.0),
+            max_books: (lerp(self.start_max_books as f32, self.end_max_books as f32).round()
+                as usize)
+                .max(1),
+            short_term_rate: lerp(self.start_short_term_rate, self.end_short_term_rate)
+                .max(0.0)
+                .min(1.0),
+            return_date_offset: lerp(
+                self.start_return_date_offset as f32,
+                self.end_return_date_offset as f32,
+            )
+            .round()
+            .max(0.0) as i32,
+        }
+    }
+
+    ///
+    /// 遊び方と今日の日付から、その日の難しさを決める
+    ///
+    pub fn for_date(&self, game_mode: &GameMode, date: &GensoDate) -> Difficulty {
+        if self.time_attack_full && !game_mode.is_story_mode() {
+            return self.at_progress(1.0);
+        }
+
+        let days = GensoDate::new(112, 7, 23).diff_days(date).max(0) as u32;
+        self.at_progress(self.progress(days))
+    }
+}
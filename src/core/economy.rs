@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use super::{BookInformation, GensoDate, RentalLimit, ReturnBookInformation, TaskResult};
+
+///
+/// 返却期限を一日過ぎるごとに、一冊あたりで頂く延滞料
+///
+pub const LATE_RETURN_FEE_PER_BOOK_DAY: i32 = 20;
+
+///
+/// 延滞料を数える日数の上限。これより遅れても同じ額にする
+///
+pub const LATE_RETURN_MAX_DAYS: u32 = 14;
+
+///
+/// 本の値段と貸出期間から貸出料を決める
+///
+pub fn rental_fee(books: &[BookInformation], rental_limit: &RentalLimit) -> i32 {
+    (books
+        .iter()
+        .map(|info| info.base_price)
+        .fold(0, |sum, price| sum + price) as f32
+        * rental_limit.fee_rate()) as i32
+}
+
+///
+/// 返却期限return_dateから、todayまでに何日遅れたか。期限内なら0
+///
+pub fn late_days(return_date: &GensoDate, today: &GensoDate) -> u32 {
+    if !today.is_past(return_date) {
+        return 0;
+    }
+
+    (return_date.diff_days(today).abs() as u32).min(LATE_RETURN_MAX_DAYS)
+}
+
+///
+/// 返却の依頼で頂く延滞料
+///
+pub fn late_return_fee(info: &ReturnBookInformation, today: &GensoDate) -> i32 {
+    late_days(&info.return_date, today) as i32
+        * info.returning.len() as i32
+        * LATE_RETURN_FEE_PER_BOOK_DAY
+}
+
+///
+/// # 鈴奈庵の収入の内訳
+/// 所持金そのものはTaskResultのtotal_moneyが持ち、ここでは何で得たかを積み上げる
+/// 日毎の額は、その日の始めの値との差で出す
+///
+/// ## rental_fees
+/// 貸出料の合計
+///
+/// ## late_fees
+/// 延滞料の合計
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Economy {
+    pub rental_fees: i32,
+    pub late_fees: i32,
+}
+
+impl Economy {
+    pub fn new() -> Self {
+        Economy {
+            rental_fees: 0,
+            late_fees: 0,
+        }
+    }
+
+    pub fn receive_rental_fee(&mut self, task_result: &mut TaskResult, fee: i32) {
+        self.rental_fees += fee;
+        task_result.total_money += fee;
+    }
+
+    ///
+    /// 貸出を取り消したときに、受け取った貸出料を返す
+    ///
+    pub fn refund_rental_fee(&mut self, task_result: &mut TaskResult, fee: i32) {
+        self.rental_fees -= fee;
+        task_result.total_money -= fee;
+    }
+
+    pub fn receive_late_fee(&mut self, task_result: &mut TaskResult, fee: i32) {
+        self.late_fees += fee;
+        task_result.total_money += fee;
+    }
+
+    ///
+    /// beginからどれだけ増えたか。その日の内訳を出すのに使う
+    ///
+    pub fn diff_from(&self, begin: &Economy) -> Economy {
+        Economy {
+            rental_fees: self.rental_fees - begin.rental_fees,
+            late_fees: self.late_fees - begin.late_fees,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use rand::Rng;
+
+///
+/// 配達先の通りのマップ。game_data.tomlのmap_informationのid
+///
+pub const STREET_MAP_ID: u32 = 10;
+
+///
+/// 営業中、毎時ちょうどに配達を頼まれる割合
+///
+pub const DELIVERY_ASK_RATE: f32 = 0.15;
+
+///
+/// 配達を引き受けてから届けるまでに許される時間（店の時計で何分か）
+///
+pub const DELIVERY_TIME_BUDGET: u32 = 60;
+
+///
+/// 留守の間、列に並んだ客を一人さばくのにかかる時間（店の時計で何分か）
+///
+pub const AUTO_HANDLE_INTERVAL: u32 = 15;
+
+///
+/// 留守の間にさばいた貸出で受け取れる代金の割合
+///
+pub const AUTO_HANDLE_FEE_RATE: f32 = 0.6;
+
+///
+/// 留守の間にさばいた客一人ごとの評判の増減
+///
+pub const AUTO_HANDLE_REPUTATION_PENALTY: f32 = -0.5;
+
+///
+/// # 配達先
+/// 通りのマップに並ぶ家のどれか
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeliveryDestination {
+    HieidaMansion,
+    Terakoya,
+    Teahouse,
+}
+
+impl DeliveryDestination {
+    pub const ALL: [DeliveryDestination; 3] = [
+        DeliveryDestination::HieidaMansion,
+        DeliveryDestination::Terakoya,
+        DeliveryDestination::Teahouse,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            DeliveryDestination::HieidaMansion => "稗田邸",
+            DeliveryDestination::Terakoya => "寺子屋",
+            DeliveryDestination::Teahouse => "茶屋",
+        }
+    }
+
+    ///
+    /// 通りのどのあたりにあるか
+    ///
+    pub fn direction_hint(&self) -> &'static str {
+        match self {
+            DeliveryDestination::HieidaMansion => "通リノ西",
+            DeliveryDestination::Terakoya => "通リノ中程",
+            DeliveryDestination::Teahouse => "通リノ東",
+        }
+    }
+
+    ///
+    /// 通りのマップで、戸口の前のタイルの位置
+    ///
+    pub fn tile_position(&self) -> (u32, u32) {
+        match self {
+            DeliveryDestination::HieidaMansion => (6, 4),
+            DeliveryDestination::Terakoya => (14, 4),
+            DeliveryDestination::Teahouse => (22, 4),
+        }
+    }
+
+    ///
+    /// (x, y)のタイルか、その隣が戸口の前になっている配達先を返す
+    ///
+    pub fn at(x: u32, y: u32) -> Option<Self> {
+        DeliveryDestination::ALL.iter().copied().find(|dest| {
+            let (dx, dy) = dest.tile_position();
+            (dx as i32 - x as i32).abs() + (dy as i32 - y as i32).abs() <= 1
+        })
+    }
+}
+
+///
+/// # 配達の結果
+///
+/// ## on_time
+/// 時間内に届けられたか
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeliveryOutcome {
+    pub on_time: bool,
+    pub money: i32,
+    pub reputation: f32,
+}
+
+///
+/// # 写本の配達の依頼
+///
+/// ## accepted_minute
+/// 引き受けた時刻（0時からの分）
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryRequest {
+    pub book_title: String,
+    pub destination: DeliveryDestination,
+    pub accepted_minute: u32,
+}
+
+impl DeliveryRequest {
+    pub fn new(book_title: &str, destination: DeliveryDestination, accepted_minute: u32) -> Self {
+        DeliveryRequest {
+            book_title: book_title.to_string(),
+            destination: destination,
+            accepted_minute: accepted_minute,
+        }
+    }
+
+    pub fn random<R: Rng>(rng: &mut R, book_title: &str, accepted_minute: u32) -> Self {
+        let destination =
+            DeliveryDestination::ALL[rng.gen_range(0, DeliveryDestination::ALL.len())];
+        DeliveryRequest::new(book_title, destination, accepted_minute)
+    }
+
+    pub fn deadline_minute(&self) -> u32 {
+        self.accepted_minute + DELIVERY_TIME_BUDGET
+    }
+
+    pub fn is_overdue(&self, now_minute: u32) -> bool {
+        now_minute > self.deadline_minute()
+    }
+
+    ///
+    /// now_minuteに届けたときの結果。遅れると代金は減り、評判も落ちる
+    ///
+    pub fn outcome(&self, now_minute: u32) -> DeliveryOutcome {
+        if self.is_overdue(now_minute) {
+            DeliveryOutcome {
+                on_time: false,
+                money: 100,
+                reputation: -1.0,
+            }
+        } else {
+            DeliveryOutcome {
+                on_time: true,
+                money: 300,
+                reputation: 2.0,
+            }
+        }
+    }
+
+    ///
+    /// 届けられないまま店を閉めたときの結果
+    ///
+    pub fn abandoned_outcome(&self) -> DeliveryOutcome {
+        DeliveryOutcome {
+            on_time: false,
+            money: 0,
+            reputation: -3.0,
+        }
+    }
+}
+
+///
+/// 留守の間にさばいた貸出で受け取る代金
+///
+pub fn auto_handled_fee(fee: i32) -> i32 {
+    (fee as f32 * AUTO_HANDLE_FEE_RATE) as i32
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use torifune::core::Clock;
+
+///
+/// # 受付の種類
+/// 種類ごとに評価の決まりを変える
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    Borrowing,
+    Returning,
+    Copying,
+}
+
+///
+/// # 受付一件の成り行き
+/// 評価の決まりは、これだけを見て点を付ける
+///
+/// ## evaluated_books
+/// 状態を評価した本の数。状態を評価しない受付では0
+///
+/// ## handling_clock
+/// 客が来てから受付を終えるまでの時間
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestOutcome {
+    pub kind: RequestKind,
+    pub evaluated_books: usize,
+    pub eval_mistakes: usize,
+    pub handling_clock: Clock,
+    pub earned_money: i32,
+}
+
+impl RequestOutcome {
+    pub fn new(
+        kind: RequestKind,
+        evaluated_books: usize,
+        eval_mistakes: usize,
+        handling_clock: Clock,
+        earned_money: i32,
+    ) -> Self {
+        RequestOutcome {
+            kind: kind,
+            evaluated_books: evaluated_books,
+            eval_mistakes: eval_mistakes,
+            handling_clock: handling_clock,
+            earned_money: earned_money,
+        }
+    }
+
+    pub fn correct_evaluations(&self) -> usize {
+        self.evaluated_books.saturating_sub(self.eval_mistakes)
+    }
+}
+
+///
+/// # 受付一件に点を付ける決まり
+/// 受付の種類ごとに実装し、Evaluatorに登録する
+///
+pub trait EvaluationRule {
+    fn evaluate(&self, outcome: &RequestOutcome) -> i32;
+}
+
+///
+/// limit以内に受付を終えれば、早いほど多く付ける上乗せ。60fps前提で秒ごとに一点
+///
+fn speed_bonus(handling_clock: Clock, limit: Clock) -> i32 {
+    (limit.saturating_sub(handling_clock) / 60) as i32
+}
+
+///
+/// 貸出は、手早く帳簿を付けられたかだけを見る
+///
+pub struct BorrowingRule;
+
+impl EvaluationRule for BorrowingRule {
+    fn evaluate(&self, outcome: &RequestOutcome) -> i32 {
+        10 + speed_bonus(outcome.handling_clock, 60 * 60)
+    }
+}
+
+///
+/// 返却は、本の状態を見誤らなかったかを重く見る
+///
+pub struct ReturningRule;
+
+impl EvaluationRule for ReturningRule {
+    fn evaluate(&self, outcome: &RequestOutcome) -> i32 {
+        10 + (outcome.correct_evaluations() as i32 * 3) - (outcome.eval_mistakes as i32 * 8)
+            + speed_bonus(outcome.handling_clock, 90 * 60)
+    }
+}
+
+///
+/// 写本は手間が掛かるので、時間の余裕を長く取り、売上に応じて付ける
+///
+pub struct CopyingRule;
+
+impl EvaluationRule for CopyingRule {
+    fn evaluate(&self, outcome: &RequestOutcome) -> i32 {
+        10 + (outcome.earned_money / 100) + speed_bonus(outcome.handling_clock, 180 * 60)
+    }
+}
+
+///
+/// # 受付の評価
+/// 受付の種類ごとの決まりを表に持ち、種類に合った決まりで点を付ける
+///
+/// ## rules
+/// 受付の種類と決まりの表。新しい種類の受付は、ここに決まりを登録するだけで評価できる
+///
+pub struct Evaluator {
+    rules: HashMap<RequestKind, Box<dyn EvaluationRule>>,
+}
+
+impl Evaluator {
+    ///
+    /// 貸出、返却、写本の決まりを登録した評価
+    ///
+    pub fn new() -> Self {
+        let mut evaluator = Evaluator::empty();
+
+        evaluator.register(RequestKind::Borrowing, Box::new(BorrowingRule));
+        evaluator.register(RequestKind::Returning, Box::new(ReturningRule));
+        evaluator.register(RequestKind::Copying, Box::new(CopyingRule));
+
+        evaluator
+    }
+
+    pub fn empty() -> Self {
+        Evaluator {
+            rules: HashMap::new(),
+        }
+    }
+
+    ///
+    /// kindの決まりを登録する。既に登録されていれば置き換える
+    ///
+    pub fn register(&mut self, kind: RequestKind, rule: Box<dyn EvaluationRule>) {
+        self.rules.insert(kind, rule);
+    }
+
+    pub fn has_rule(&self, kind: RequestKind) -> bool {
+        self.rules.contains_key(&kind)
+    }
+
+    ///
+    /// 受付に点を付ける。決まりの無い種類の受付は評価せず、None
+    ///
+    pub fn evaluate(&self, outcome: &RequestOutcome) -> Option<i32> {
+        self.rules
+            .get(&outcome.kind)
+            .map(|rule| rule.evaluate(outcome))
+    }
+}
@@ -0,0 +1,130 @@
+use super::preferences::{ControlScheme, Locale, Preferences, NICKNAME_MAX_CHARS};
+
+///
+/// # 初めての設定の手順
+///
+/// ## Language
+/// 表示する言語を選ぶ
+///
+/// ## Nickname
+/// 呼び名を打ち込む
+///
+/// ## Controls
+/// マウスとキーボードのどちらを主に使うか選ぶ
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FirstRunStep {
+    Language,
+    Nickname,
+    Controls,
+}
+
+impl FirstRunStep {
+    pub fn title(&self) -> &'static str {
+        match self {
+            FirstRunStep::Language => "言葉ヲ選ンデ下サイ",
+            FirstRunStep::Nickname => "呼ビ名ヲ打ッテ下サイ",
+            FirstRunStep::Controls => "操作ノ型ヲ選ンデ下サイ",
+        }
+    }
+}
+
+///
+/// # 初めての設定
+/// 選んだ値は、最後まで進んだときにまとめて設定へ書き込む
+///
+pub struct FirstRunWizard {
+    step: FirstRunStep,
+    locale: Locale,
+    nickname: String,
+    control_scheme: ControlScheme,
+}
+
+impl FirstRunWizard {
+    ///
+    /// 選び直すときのために、今の設定を初めの値にする
+    ///
+    pub fn new(preferences: &Preferences) -> Self {
+        FirstRunWizard {
+            step: FirstRunStep::Language,
+            locale: preferences.get_locale(),
+            nickname: preferences.get_nickname().to_string(),
+            control_scheme: preferences.get_control_scheme(),
+        }
+    }
+
+    pub fn get_step(&self) -> FirstRunStep {
+        self.step
+    }
+
+    pub fn get_locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    pub fn get_nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn get_control_scheme(&self) -> ControlScheme {
+        self.control_scheme
+    }
+
+    pub fn set_control_scheme(&mut self, scheme: ControlScheme) {
+        self.control_scheme = scheme;
+    }
+
+    pub fn is_editing_nickname(&self) -> bool {
+        self.step == FirstRunStep::Nickname
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if !self.is_editing_nickname()
+            || c.is_control()
+            || self.nickname.chars().count() >= NICKNAME_MAX_CHARS
+        {
+            return;
+        }
+
+        self.nickname.push(c);
+    }
+
+    pub fn pop_char(&mut self) {
+        if self.is_editing_nickname() {
+            self.nickname.pop();
+        }
+    }
+
+    ///
+    /// 次の手順へ進む。最後の手順で呼べばtrueを返す
+    ///
+    pub fn next(&mut self) -> bool {
+        self.step = match self.step {
+            FirstRunStep::Language => FirstRunStep::Nickname,
+            FirstRunStep::Nickname => FirstRunStep::Controls,
+            FirstRunStep::Controls => return true,
+        };
+
+        false
+    }
+
+    pub fn back(&mut self) {
+        self.step = match self.step {
+            FirstRunStep::Language | FirstRunStep::Nickname => FirstRunStep::Language,
+            FirstRunStep::Controls => FirstRunStep::Nickname,
+        };
+    }
+
+    ///
+    /// 選んだ値を設定に書き込み、初めての起動を終える
+    ///
+    pub fn apply(&self, preferences: &mut Preferences) {
+        preferences.set_locale(self.locale);
+        preferences.set_nickname(&self.nickname);
+        preferences.set_control_scheme(self.control_scheme);
+        preferences.finish_first_run();
+    }
+}
@@ -0,0 +1,89 @@
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+
+use super::GensoDate;
+
+///
+/// 新しく始めた遊びに付けるseed。ここだけは毎回違う値になる
+///
+pub fn new_seed() -> u64 {
+    rand::random::<u64>()
+}
+
+///
+/// 保存したseedと日付から、その日に使うseedを作る。同じ日をやり直せば同じ並びになる
+///
+pub fn day_seed(seed: u64, date: &GensoDate) -> u64 {
+    let day = (date.season as u64 * 400) + (date.month as u64 * 32) + date.day as u64;
+    seed ^ day.wrapping_mul(0x9e3779b97f4a7c15)
+}
+
+///
+/// # 遊びの中で使う乱数
+/// seedを覚えておくので、不具合を報告してもらえば同じ並びを作り直せる
+///
+/// ## seed
+/// 最後に与えたseed
+///
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed: seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_new_seed() -> Self {
+        Self::new(new_seed())
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    ///
+    /// 0からupper未満の数を一つ選ぶ。upperが0なら0
+    ///
+    pub fn below(&mut self, upper: usize) -> usize {
+        if upper == 0 {
+            0
+        } else {
+            self.rng.gen_range(0, upper)
+        }
+    }
+
+    ///
+    /// rate(0.0 - 1.0)の確率でtrue
+    ///
+    pub fn chance(&mut self, rate: f32) -> bool {
+        self.rng.gen::<f32>() < rate
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
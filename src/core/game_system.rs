@@ -1,5 +1,7 @@
 use super::*;
 
+use rand::Rng;
+
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum GoingOutEvent {
     AkyuTei,
@@ -76,12 +78,12 @@ impl WeekWorkSchedule {
     }
 
     pub fn update_is_not_required(&self, date: &GensoDate) -> bool {
-        let diff = self.first_day.diff_day(date);
+        let diff = self.first_day.diff_days(date);
         diff < 7 && diff >= 0 && !self.schedule.contains(&None)
     }
 
     pub fn get_schedule_of(&self, day: &GensoDate) -> Option<DayWorkType> {
-        let diff = self.first_day.diff_day(day);
+        let diff = self.first_day.diff_days(day);
         if diff < 0 {
             return None;
         }
@@ -120,3 +122,221 @@ impl AwardData {
         self.customer_count += count;
     }
 }
+
+///
+/// 補助を勧めるかどうかを判断するのに使う、直近の受付の数
+///
+pub const ASSIST_WINDOW: usize = 4;
+
+///
+/// 直近の正確さ（%）の平均がこれを下回ると補助を勧める
+///
+pub const ASSIST_OFFER_ACCURACY: u32 = 60;
+
+///
+/// 補助中に直近の正確さ（%）の平均がこれ以上になると、補助を自動で切る
+///
+pub const ASSIST_RECOVERED_ACCURACY: u32 = 85;
+
+///
+/// 補助中、客が列で待ってくれる時間の倍率
+///
+pub const ASSIST_PATIENCE_RATE: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssistChange {
+    Keep,
+    Offer,
+    Recovered,
+}
+
+///
+/// # 状態評価に苦戦しているときの補助
+///
+/// ## recent_accuracy
+/// 状態評価のあった直近の受付の正確さ（%）
+///
+/// ## offered
+/// 補助を勧めて、まだ正確さが戻っていない間はtrue。何度も勧めないようにする
+///
+/// ## accepted_count, declined_count
+/// 勧められた補助を受け入れた回数と断った回数
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DifficultyAssist {
+    recent_accuracy: VecDeque<u32>,
+    enabled: bool,
+    offered: bool,
+    accepted_count: u32,
+    declined_count: u32,
+}
+
+impl DifficultyAssist {
+    pub fn new() -> Self {
+        DifficultyAssist {
+            recent_accuracy: VecDeque::new(),
+            enabled: false,
+            offered: false,
+            accepted_count: 0,
+            declined_count: 0,
+        }
+    }
+
+    ///
+    /// 直近の正確さの平均。記録が無い場合は100
+    ///
+    pub fn recent_accuracy_percent(&self) -> u32 {
+        if self.recent_accuracy.is_empty() {
+            return 100;
+        }
+
+        self.recent_accuracy.iter().sum::<u32>() / self.recent_accuracy.len() as u32
+    }
+
+    ///
+    /// 受付の結果を記録し、補助を勧めるか、補助を切るかを返す
+    ///
+    pub fn record_request(&mut self, evaluated_books: usize, eval_mistakes: usize) -> AssistChange {
+        // 状態評価の無い受付は判断材料にしない
+        if evaluated_books == 0 {
+            return AssistChange::Keep;
+        }
+
+        let correct = evaluated_books.saturating_sub(eval_mistakes);
+        self.recent_accuracy
+            .push_back(((correct * 100) / evaluated_books) as u32);
+        while self.recent_accuracy.len() > ASSIST_WINDOW {
+            self.recent_accuracy.pop_front();
+        }
+
+        if self.recent_accuracy.len() < ASSIST_WINDOW {
+            return AssistChange::Keep;
+        }
+
+        let accuracy = self.recent_accuracy_percent();
+
+        if accuracy >= ASSIST_RECOVERED_ACCURACY {
+            self.offered = false;
+            if self.enabled {
+                self.enabled = false;
+                return AssistChange::Recovered;
+            }
+        } else if accuracy < ASSIST_OFFER_ACCURACY && !self.enabled && !self.offered {
+            self.offered = true;
+            return AssistChange::Offer;
+        }
+
+        AssistChange::Keep
+    }
+
+    ///
+    /// 補助を有効にする。補助を受けてからの正確さで回復を判断する
+    ///
+    pub fn accept(&mut self) {
+        self.enabled = true;
+        self.accepted_count += 1;
+        self.recent_accuracy.clear();
+    }
+
+    pub fn decline(&mut self) {
+        self.declined_count += 1;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn get_accepted_count(&self) -> u32 {
+        self.accepted_count
+    }
+
+    pub fn get_declined_count(&self) -> u32 {
+        self.declined_count
+    }
+}
+
+///
+/// 客がお年寄りである確率
+///
+pub const ELDERLY_CUSTOMER_RATE: f32 = 0.1;
+
+///
+/// 客が身重である確率
+///
+pub const EXPECTING_CUSTOMER_RATE: f32 = 0.05;
+
+///
+/// 待ち列から順番を飛ばして客を呼んだとき、追い越された客一人あたりに下がる評判
+///
+pub const QUEUE_FAIRNESS_PENALTY: f32 = 0.5;
+
+///
+/// # 列で先に通してもらえる客の事情
+/// お年寄りと身重の客は、先に並んでいる普通の客より前に入る
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CustomerPriority {
+    Normal,
+    Elderly,
+    Expecting,
+}
+
+impl CustomerPriority {
+    pub fn random_select<R: Rng>(rng: &mut R) -> Self {
+        let r = rng.gen::<f32>();
+
+        if r < EXPECTING_CUSTOMER_RATE {
+            CustomerPriority::Expecting
+        } else if r < EXPECTING_CUSTOMER_RATE + ELDERLY_CUSTOMER_RATE {
+            CustomerPriority::Elderly
+        } else {
+            CustomerPriority::Normal
+        }
+    }
+
+    pub fn is_prioritized(&self) -> bool {
+        *self != CustomerPriority::Normal
+    }
+
+    ///
+    /// 客の頭上と待ち列の一覧に出す印
+    ///
+    pub fn marker_text(&self) -> Option<&'static str> {
+        match self {
+            CustomerPriority::Normal => None,
+            CustomerPriority::Elderly => Some("御年寄"),
+            CustomerPriority::Expecting => Some("身重"),
+        }
+    }
+}
+
+///
+/// 新しく並ぶ客が入る位置
+/// 先に通してもらえる客は、先に並んでいる同じ扱いの客のすぐ後ろに入る
+///
+pub fn priority_insert_index(queued: &[CustomerPriority], new: CustomerPriority) -> usize {
+    if !new.is_prioritized() {
+        return queued.len();
+    }
+
+    queued
+        .iter()
+        .position(|priority| !priority.is_prioritized())
+        .unwrap_or(queued.len())
+}
+
+///
+/// 列のindex番目の客を先に呼んだとき、追い越される客の数
+/// 先に通してもらえる客が、普通の客を追い越すのは数えない
+///
+pub fn count_skipped_customers(queued: &[CustomerPriority], index: usize) -> usize {
+    let called = match queued.get(index) {
+        Some(priority) => *priority,
+        None => return 0,
+    };
+
+    queued[..index]
+        .iter()
+        .filter(|priority| priority.is_prioritized() || !called.is_prioritized())
+        .count()
+}
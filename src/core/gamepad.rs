@@ -0,0 +1,119 @@
+use ggez::input::gamepad::gilrs::Button;
+use torifune::device as tdev;
+use torifune::numeric;
+
+use super::preferences::virtual_key_from_str;
+use super::shortcut_palette::PaletteKey;
+
+///
+/// スティックをこれより倒していなければ、倒していないとみなす
+///
+pub const STICK_DEAD_ZONE: f32 = 0.25;
+
+///
+/// 標準のボタンの割り当て。ボタン名と操作名の組
+///
+pub const DEFAULT_GAMEPAD_BINDINGS: [(&str, &str); 7] = [
+    ("South", "Action1"),
+    ("East", "Action2"),
+    ("West", "Action3"),
+    ("Start", "Action4"),
+    ("Select", "Escape"),
+    ("DPadUp", "Up"),
+    ("DPadDown", "Down"),
+];
+
+///
+/// # パッドのボタンで起こす操作
+///
+/// ## Key
+/// 仮想キー。キーボードのキーと同じくシーンへ届ける
+///
+/// ## Palette
+/// ショートカットの一覧や一時停止の画面、選択肢を操作するキー
+///
+pub enum GamepadAction {
+    Key(tdev::VirtualKey),
+    Palette(PaletteKey),
+}
+
+impl GamepadAction {
+    ///
+    /// 一覧や選択肢に先に届けるキー。決定のボタンはEnterとして扱う
+    ///
+    pub fn palette_key(&self) -> Option<PaletteKey> {
+        match self {
+            GamepadAction::Palette(key) => Some(*key),
+            GamepadAction::Key(tdev::VirtualKey::Action1) => Some(PaletteKey::Enter),
+            GamepadAction::Key(_) => None,
+        }
+    }
+}
+
+pub fn button_from_str(button_str: &str) -> Option<Button> {
+    let button = match button_str {
+        "South" => Button::South,
+        "East" => Button::East,
+        "North" => Button::North,
+        "West" => Button::West,
+        "LeftTrigger" => Button::LeftTrigger,
+        "LeftTrigger2" => Button::LeftTrigger2,
+        "RightTrigger" => Button::RightTrigger,
+        "RightTrigger2" => Button::RightTrigger2,
+        "Select" => Button::Select,
+        "Start" => Button::Start,
+        "LeftThumb" => Button::LeftThumb,
+        "RightThumb" => Button::RightThumb,
+        "DPadUp" => Button::DPadUp,
+        "DPadDown" => Button::DPadDown,
+        "DPadLeft" => Button::DPadLeft,
+        "DPadRight" => Button::DPadRight,
+        _ => return None,
+    };
+
+    Some(button)
+}
+
+///
+/// "Action1".."Action5"は仮想キー、"Up"、"Down"、"Enter"、"Escape"、"Tab"は一覧を操作するキーになる
+///
+pub fn gamepad_action_from_str(action_str: &str) -> Option<GamepadAction> {
+    if let Some(vkey) = virtual_key_from_str(action_str) {
+        return Some(GamepadAction::Key(vkey));
+    }
+
+    let key = match action_str {
+        "Up" => PaletteKey::Up,
+        "Down" => PaletteKey::Down,
+        "Enter" => PaletteKey::Enter,
+        "Escape" => PaletteKey::Escape,
+        "Tab" => PaletteKey::Tab,
+        _ => return None,
+    };
+
+    Some(GamepadAction::Palette(key))
+}
+
+///
+/// 標準の割り当てでbuttonに付いている操作
+///
+pub fn default_gamepad_action(button: Button) -> Option<GamepadAction> {
+    DEFAULT_GAMEPAD_BINDINGS
+        .iter()
+        .find(|(button_str, _)| button_from_str(button_str) == Some(button))
+        .and_then(|(_, action_str)| gamepad_action_from_str(action_str))
+}
+
+///
+/// 左スティックの傾き(上が正)を、画面上の向き(下が正)に直す
+/// 遊びの内側は(0, 0)、外側は遊びの端から測り直して長さ1.0までに収める
+///
+pub fn stick_direction(stick: numeric::Vector2f) -> numeric::Vector2f {
+    let length = (stick.x.powf(2.0) + stick.y.powf(2.0)).sqrt();
+    if !length.is_finite() || length <= STICK_DEAD_ZONE {
+        return numeric::Vector2f::new(0.0, 0.0);
+    }
+
+    let rate = ((length - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE)).min(1.0) / length;
+    numeric::Vector2f::new(stick.x * rate, -stick.y * rate)
+}
@@ -0,0 +1,168 @@
+use torifune::distance;
+use torifune::numeric;
+
+///
+/// これより短いなぞりは、手ぶれとみなして何もしなかったことにする
+///
+pub const GESTURE_MIN_LENGTH: f32 = 30.0;
+
+///
+/// 輪とみなすのに必要な、なぞった向きの変化の合計（ラジアン）
+///
+const LOOP_MIN_TURNING: f32 = 1.6 * std::f32::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrokeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+///
+/// # なぞり方の種類
+///
+/// ## Stroke
+/// 一方向へのなぞり
+///
+/// ## Loop
+/// 輪を描くなぞり。値は0.0から1.0の円らしさ
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    Stroke(StrokeDirection),
+    Loop(f32),
+    Unknown,
+}
+
+///
+/// # マウスでなぞった軌跡を読み取る
+/// beginからfinishまでに渡された点を一本の軌跡として扱う
+///
+pub struct GestureRecognizer {
+    points: Vec<numeric::Point2f>,
+    recording: bool,
+}
+
+impl GestureRecognizer {
+    pub fn new() -> Self {
+        GestureRecognizer {
+            points: Vec::new(),
+            recording: false,
+        }
+    }
+
+    pub fn begin(&mut self, point: numeric::Point2f) {
+        self.points.clear();
+        self.points.push(point);
+        self.recording = true;
+    }
+
+    pub fn push(&mut self, point: numeric::Point2f) {
+        if self.recording {
+            self.points.push(point);
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn finish(&mut self) -> Gesture {
+        self.recording = false;
+        let gesture = classify(&self.points);
+        self.points.clear();
+
+        gesture
+    }
+}
+
+fn path_length(points: &[numeric::Point2f]) -> f32 {
+    points
+        .windows(2)
+        .fold(0.0, |sum, pair| sum + distance!(pair[0], pair[1]))
+}
+
+///
+/// 隣り合う線分の向きの変化を、符号付きで足し合わせる
+///
+fn total_turning(points: &[numeric::Point2f]) -> f32 {
+    let mut turning = 0.0;
+    let mut last_angle: Option<f32> = None;
+
+    for pair in points.windows(2) {
+        let (dx, dy) = (pair[1].x - pair[0].x, pair[1].y - pair[0].y);
+        if dx == 0.0 && dy == 0.0 {
+            continue;
+        }
+
+        let angle = dy.atan2(dx);
+        if let Some(last) = last_angle {
+            let mut diff = angle - last;
+            if diff > std::f32::consts::PI {
+                diff -= 2.0 * std::f32::consts::PI;
+            } else if diff < -std::f32::consts::PI {
+                diff += 2.0 * std::f32::consts::PI;
+            }
+            turning += diff;
+        }
+        last_angle = Some(angle);
+    }
+
+    turning
+}
+
+///
+/// 重心からの距離のばらつきが小さいほど1.0に近づく
+///
+fn circularity(points: &[numeric::Point2f]) -> f32 {
+    let n = points.len() as f32;
+    let center = points.iter().fold(numeric::Point2f::new(0.0, 0.0), |c, p| {
+        numeric::Point2f::new(c.x + (p.x / n), c.y + (p.y / n))
+    });
+
+    let radii: Vec<f32> = points.iter().map(|p| distance!(*p, center)).collect();
+    let mean = radii.iter().sum::<f32>() / n;
+    if mean == 0.0 {
+        return 0.0;
+    }
+
+    let variance = radii.iter().map(|r| (r - mean).powi(2)).sum::<f32>() / n;
+    (1.0 - (variance.sqrt() / mean)).max(0.0).min(1.0)
+}
+
+///
+/// 軌跡からなぞり方を決める。短すぎる軌跡はUnknownになる
+///
+pub fn classify(points: &[numeric::Point2f]) -> Gesture {
+    if points.len() < 2 || path_length(points) < GESTURE_MIN_LENGTH {
+        return Gesture::Unknown;
+    }
+
+    if total_turning(points).abs() >= LOOP_MIN_TURNING {
+        return Gesture::Loop(circularity(points));
+    }
+
+    let first = points.first().unwrap();
+    let last = points.last().unwrap();
+    let (dx, dy) = (last.x - first.x, last.y - first.y);
+
+    // 行って戻ってきただけの軌跡は、向きが決まらない
+    if (dx.powi(2) + dy.powi(2)).sqrt() < GESTURE_MIN_LENGTH {
+        return Gesture::Unknown;
+    }
+
+    let direction = if dx.abs() > dy.abs() {
+        if dx > 0.0 {
+            StrokeDirection::Right
+        } else {
+            StrokeDirection::Left
+        }
+    } else if dy > 0.0 {
+        StrokeDirection::Down
+    } else {
+        StrokeDirection::Up
+    };
+
+    Gesture::Stroke(direction)
+}
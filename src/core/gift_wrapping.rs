@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+use super::gesture::Gesture;
+
+///
+/// 本を借りる客が、贈り物として包んでほしいと頼む割合
+///
+pub const GIFT_WRAPPING_ASK_RATE: f32 = 0.1;
+
+///
+/// 包装用品を一度に置いておける数
+///
+pub const WRAPPING_SUPPLIES_CAPACITY: u32 = 8;
+
+///
+/// これより少なくなると、朝のうちに仕入れ直す
+///
+pub const WRAPPING_SUPPLIES_RESTOCK_THRESHOLD: u32 = 2;
+
+pub const WRAPPING_PAPER_PRICE: i32 = 20;
+pub const WRAPPING_STRING_PRICE: i32 = 10;
+
+///
+/// # 包み紙の角
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperCorner {
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+impl PaperCorner {
+    pub const ALL: [PaperCorner; 4] = [
+        PaperCorner::TopLeft,
+        PaperCorner::TopRight,
+        PaperCorner::BottomRight,
+        PaperCorner::BottomLeft,
+    ];
+}
+
+///
+/// 角を折る順番。左右を折ってから下、最後に上を被せる
+///
+pub const FOLD_ORDER: [PaperCorner; 4] = [
+    PaperCorner::BottomLeft,
+    PaperCorner::TopRight,
+    PaperCorner::BottomRight,
+    PaperCorner::TopLeft,
+];
+
+///
+/// # 包装用品の在庫
+/// 一つ包むごとに包み紙と紐を一つずつ使う
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrappingSupplies {
+    pub paper: u32,
+    pub string: u32,
+}
+
+impl Default for WrappingSupplies {
+    fn default() -> Self {
+        WrappingSupplies::new()
+    }
+}
+
+impl WrappingSupplies {
+    pub fn new() -> Self {
+        WrappingSupplies {
+            paper: WRAPPING_SUPPLIES_CAPACITY,
+            string: WRAPPING_SUPPLIES_CAPACITY,
+        }
+    }
+
+    pub fn can_wrap(&self) -> bool {
+        self.paper > 0 && self.string > 0
+    }
+
+    ///
+    /// 一つ包む分を使う。足りなければ何も使わずにfalseを返す
+    ///
+    pub fn consume(&mut self) -> bool {
+        if !self.can_wrap() {
+            return false;
+        }
+
+        self.paper -= 1;
+        self.string -= 1;
+        true
+    }
+
+    ///
+    /// 少なくなった用品を満杯まで仕入れ、その代金を返す
+    ///
+    pub fn restock_if_low(&mut self) -> i32 {
+        let mut cost = 0;
+
+        if self.paper <= WRAPPING_SUPPLIES_RESTOCK_THRESHOLD {
+            cost += (WRAPPING_SUPPLIES_CAPACITY - self.paper) as i32 * WRAPPING_PAPER_PRICE;
+            self.paper = WRAPPING_SUPPLIES_CAPACITY;
+        }
+
+        if self.string <= WRAPPING_SUPPLIES_RESTOCK_THRESHOLD {
+            cost += (WRAPPING_SUPPLIES_CAPACITY - self.string) as i32 * WRAPPING_STRING_PRICE;
+            self.string = WRAPPING_SUPPLIES_CAPACITY;
+        }
+
+        cost
+    }
+}
+
+///
+/// # 包装の手順
+/// FOLD_ORDERの順に角を折り、最後に紐を輪にして結ぶ
+///
+/// ## fold_precision
+/// 折った角ごとの、中央にどれだけ揃えられたか（0.0から1.0）
+///
+/// ## fold_mistakes
+/// 順番を間違えて角を掴んだ回数
+///
+/// ## knot_quality
+/// 結び目の出来。結ぶまではNone
+///
+#[derive(Debug, Clone)]
+pub struct WrappingSession {
+    fold_precision: Vec<f32>,
+    fold_mistakes: u32,
+    knot_quality: Option<f32>,
+}
+
+impl WrappingSession {
+    pub fn new() -> Self {
+        WrappingSession {
+            fold_precision: Vec::new(),
+            fold_mistakes: 0,
+            knot_quality: None,
+        }
+    }
+
+    pub fn next_corner(&self) -> Option<PaperCorner> {
+        FOLD_ORDER.get(self.fold_precision.len()).copied()
+    }
+
+    pub fn is_folded(&self, corner: PaperCorner) -> bool {
+        FOLD_ORDER[..self.fold_precision.len()].contains(&corner)
+    }
+
+    ///
+    /// 角を折る。順番通りの角ならtrueを返し、違えば間違いとして数える
+    ///
+    pub fn fold(&mut self, corner: PaperCorner, precision: f32) -> bool {
+        if self.next_corner() != Some(corner) {
+            self.fold_mistakes += 1;
+            return false;
+        }
+
+        self.fold_precision.push(precision.max(0.0).min(1.0));
+        true
+    }
+
+    pub fn all_folded(&self) -> bool {
+        self.next_corner().is_none()
+    }
+
+    ///
+    /// 紐を結ぶ。角を全て折った後に輪を描いたときだけ結べる
+    ///
+    pub fn tie(&mut self, gesture: Gesture) -> bool {
+        if !self.all_folded() || self.knot_quality.is_some() {
+            return false;
+        }
+
+        match gesture {
+            Gesture::Loop(circularity) => {
+                self.knot_quality = Some(circularity);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.knot_quality.is_some()
+    }
+
+    ///
+    /// 仕上がりの点数（0から100）。折りの揃い方を6割、結び目を4割とし、間違い一回につき10点引く
+    ///
+    pub fn quality(&self) -> u32 {
+        let fold = if self.fold_precision.is_empty() {
+            0.0
+        } else {
+            self.fold_precision.iter().sum::<f32>() / self.fold_precision.len() as f32
+        };
+        let knot = self.knot_quality.unwrap_or(0.0);
+
+        let score = (fold * 60.0) + (knot * 40.0) - (self.fold_mistakes * 10) as f32;
+        score.max(0.0).min(100.0) as u32
+    }
+}
+
+///
+/// 仕上がりの点数に応じた心付け。雑な包みには何も出ない
+///
+pub fn wrapping_tip(quality: u32) -> i32 {
+    match quality {
+        0..=39 => 0,
+        40..=69 => 50,
+        70..=89 => 100,
+        _ => 200,
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use ggez::input::gamepad::gilrs::{Axis, Button};
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::input::mouse::MouseButton;
+use torifune::core::Clock;
+use torifune::distance;
+use torifune::numeric;
+
+///
+/// 履歴を保持する最大の時間。これより古い入力は捨てる
+///
+const INPUT_HISTORY_MAX_TICKS: Clock = 180;
+
+#[derive(Clone, Copy, Debug)]
+pub enum InputEvent {
+    KeyDown(KeyCode, KeyMods, bool),
+    KeyUp(KeyCode, KeyMods),
+    MouseMotion(numeric::Point2f, numeric::Vector2f),
+    MouseButtonDown(MouseButton, numeric::Point2f),
+    MouseButtonUp(MouseButton, numeric::Point2f),
+    MouseWheel(f32, f32),
+    TextInput(char),
+    GamepadButtonDown(Button),
+    GamepadButtonUp(Button),
+    GamepadAxis(Axis, f32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimedInputEvent {
+    pub event: InputEvent,
+    pub clock: Clock,
+}
+
+///
+/// # 入力イベントのキュー
+/// 受け取った順番と時刻を保ったまま、次の更新でシーンへ届ける
+///
+pub struct InputQueue {
+    queue: VecDeque<TimedInputEvent>,
+}
+
+impl InputQueue {
+    pub fn new() -> Self {
+        InputQueue {
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, event: InputEvent, clock: Clock) {
+        self.queue.push_back(TimedInputEvent {
+            event: event,
+            clock: clock,
+        });
+    }
+
+    pub fn pop(&mut self) -> Option<TimedInputEvent> {
+        self.queue.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct MousePressRecord {
+    button: MouseButton,
+    point: numeric::Point2f,
+    clock: Clock,
+}
+
+///
+/// # 直近の入力の履歴
+/// ダブルクリックやドラッグ開始の判定に使う
+///
+/// ## now
+/// 最後に入力を配送した時刻
+///
+pub struct InputHistory {
+    mouse_presses: VecDeque<MousePressRecord>,
+    key_presses: VecDeque<(KeyCode, Clock)>,
+    last_pressed_points: HashMap<MouseButton, numeric::Point2f>,
+    now: Clock,
+}
+
+impl InputHistory {
+    pub fn new() -> Self {
+        InputHistory {
+            mouse_presses: VecDeque::new(),
+            key_presses: VecDeque::new(),
+            last_pressed_points: HashMap::new(),
+            now: 0,
+        }
+    }
+
+    pub fn get_now(&self) -> Clock {
+        self.now
+    }
+
+    ///
+    /// 時刻を進め、古くなった履歴を捨てる
+    ///
+    pub fn update_now(&mut self, now: Clock) {
+        self.now = now;
+
+        while let Some(record) = self.mouse_presses.front() {
+            if Self::elapsed(now, record.clock) > INPUT_HISTORY_MAX_TICKS {
+                self.mouse_presses.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        while let Some((_, clock)) = self.key_presses.front() {
+            if Self::elapsed(now, *clock) > INPUT_HISTORY_MAX_TICKS {
+                self.key_presses.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn elapsed(now: Clock, clock: Clock) -> Clock {
+        if now >= clock {
+            now - clock
+        } else {
+            0
+        }
+    }
+
+    pub fn record(&mut self, timed_event: &TimedInputEvent) {
+        match timed_event.event {
+            InputEvent::MouseButtonDown(button, point) => {
+                self.mouse_presses.push_back(MousePressRecord {
+                    button: button,
+                    point: point,
+                    clock: timed_event.clock,
+                });
+                self.last_pressed_points.insert(button, point);
+            }
+            InputEvent::KeyDown(keycode, _, repeat) => {
+                if !repeat {
+                    self.key_presses.push_back((keycode, timed_event.clock));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    ///
+    /// 直近ticksの間にbuttonが押された回数
+    ///
+    pub fn count_pressed_within(&self, button: MouseButton, ticks: Clock) -> usize {
+        self.mouse_presses
+            .iter()
+            .filter(|record| {
+                record.button == button && Self::elapsed(self.now, record.clock) <= ticks
+            })
+            .count()
+    }
+
+    pub fn pressed_within(&self, button: MouseButton, ticks: Clock) -> bool {
+        self.count_pressed_within(button, ticks) > 0
+    }
+
+    pub fn key_pressed_within(&self, keycode: KeyCode, ticks: Clock) -> bool {
+        self.key_presses
+            .iter()
+            .any(|(key, clock)| *key == keycode && Self::elapsed(self.now, *clock) <= ticks)
+    }
+
+    ///
+    /// 直前の2回の押下がticks以内かつmax_distance以内の位置で行われたか
+    ///
+    pub fn is_double_click(&self, button: MouseButton, ticks: Clock, max_distance: f32) -> bool {
+        let mut presses = self
+            .mouse_presses
+            .iter()
+            .rev()
+            .filter(|record| record.button == button);
+
+        match (presses.next(), presses.next()) {
+            (Some(last), Some(prev)) => {
+                Self::elapsed(last.clock, prev.clock) <= ticks
+                    && Self::elapsed(self.now, last.clock) <= ticks
+                    && distance!(last.point, prev.point) <= max_distance
+            }
+            _ => false,
+        }
+    }
+
+    ///
+    /// 履歴の保持時間に関係なく、最後にbuttonが押された位置
+    ///
+    pub fn last_pressed_point(&self, button: MouseButton) -> Option<numeric::Point2f> {
+        self.last_pressed_points.get(&button).copied()
+    }
+
+    ///
+    /// 最後に押された位置からthreshold以上動いていればドラッグとみなす
+    ///
+    pub fn exceeds_drag_threshold(
+        &self,
+        button: MouseButton,
+        point: numeric::Point2f,
+        threshold: f32,
+    ) -> bool {
+        match self.last_pressed_point(button) {
+            Some(pressed) => distance!(pressed, point) >= threshold,
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use torifune::core::Clock;
+
+/// 一度警告を出したら、しばらくは出さない。60fps前提で二秒
+pub const BUDGET_WARNING_COOLDOWN: Clock = 120;
+/// 警告を画面に出しておく時間
+pub const BUDGET_NOTICE_TIME: Clock = 180;
+
+const PROBE_CATEGORIES: usize = 4;
+
+///
+/// # 計測する処理の種類
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeCategory {
+    Pathfinding = 0,
+    Collision,
+    TextLayout,
+    DrawSubmit,
+}
+
+impl ProbeCategory {
+    pub fn all() -> [ProbeCategory; PROBE_CATEGORIES] {
+        [
+            ProbeCategory::Pathfinding,
+            ProbeCategory::Collision,
+            ProbeCategory::TextLayout,
+            ProbeCategory::DrawSubmit,
+        ]
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            ProbeCategory::Pathfinding => "経路探索",
+            ProbeCategory::Collision => "当タリ判定",
+            ProbeCategory::TextLayout => "文字組ミ",
+            ProbeCategory::DrawSubmit => "描画",
+        }
+    }
+}
+
+///
+/// # 一フレームの中で、処理の種類ごとに掛かった時間
+/// 単位はマイクロ秒
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameCounters {
+    micros: [u64; PROBE_CATEGORIES],
+}
+
+impl FrameCounters {
+    pub fn new() -> Self {
+        FrameCounters {
+            micros: [0; PROBE_CATEGORIES],
+        }
+    }
+
+    pub fn add(&mut self, category: ProbeCategory, micros: u64) {
+        self.micros[category as usize] += micros;
+    }
+
+    pub fn get(&self, category: ProbeCategory) -> u64 {
+        self.micros[category as usize]
+    }
+
+    pub fn total(&self) -> u64 {
+        self.micros.iter().sum()
+    }
+
+    ///
+    /// 最も時間を食った処理。何も計測されていなければNone
+    ///
+    pub fn top_offender(&self) -> Option<(ProbeCategory, u64)> {
+        ProbeCategory::all()
+            .iter()
+            .map(|category| (*category, self.get(*category)))
+            .filter(|(_, micros)| *micros > 0)
+            .max_by_key(|(_, micros)| *micros)
+    }
+}
+
+thread_local! {
+    static FRAME_COUNTERS: RefCell<FrameCounters> = RefCell::new(FrameCounters::new());
+}
+
+///
+/// instrument!から呼ばれる。計測した時間を今のフレームに足す
+///
+pub fn record(category: ProbeCategory, elapsed: Duration) {
+    FRAME_COUNTERS.with(|counters| {
+        counters
+            .borrow_mut()
+            .add(category, elapsed.as_micros() as u64)
+    });
+}
+
+///
+/// 今のフレームの計測結果を取り出し、次のフレームのために空にする
+///
+pub fn take_frame_counters() -> FrameCounters {
+    FRAME_COUNTERS.with(|counters| counters.replace(FrameCounters::new()))
+}
+
+///
+/// 式を評価し、掛かった時間をcategoryに足す。式の値はそのまま返す
+///
+#[macro_export]
+macro_rules! instrument {
+    ($category: expr, $x: expr) => {{
+        let start = std::time::Instant::now();
+        let result = $x;
+        $crate::core::instrument::record($category, start.elapsed());
+        result
+    }};
+}
+
+///
+/// # 予算超過の警告
+///
+/// ## offender
+/// そのフレームで最も時間を食った処理
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetWarning {
+    pub frame_micros: u64,
+    pub budget_micros: u64,
+    pub offender: Option<(ProbeCategory, u64)>,
+}
+
+impl BudgetWarning {
+    pub fn to_message(&self) -> String {
+        let mut message = format!(
+            "処理落チ {:.1}ms / {:.1}ms",
+            self.frame_micros as f32 / 1000.0,
+            self.budget_micros as f32 / 1000.0
+        );
+
+        if let Some((category, micros)) = self.offender {
+            message.push_str(&format!(
+                "　主因 {} {:.1}ms",
+                category.to_str(),
+                micros as f32 / 1000.0
+            ));
+        }
+
+        message
+    }
+}
+
+///
+/// # フレームの時間予算の見張り
+///
+/// ## budget_micros
+/// 一フレームに使ってよい時間。0なら見張らない
+///
+/// ## last_warning
+/// 最後に警告を出した時刻
+///
+pub struct FrameBudgetMonitor {
+    budget_micros: u64,
+    last_warning: Option<Clock>,
+}
+
+impl FrameBudgetMonitor {
+    pub fn new(budget_micros: u64) -> Self {
+        FrameBudgetMonitor {
+            budget_micros: budget_micros,
+            last_warning: None,
+        }
+    }
+
+    pub fn get_budget_micros(&self) -> u64 {
+        self.budget_micros
+    }
+
+    pub fn set_budget_micros(&mut self, budget_micros: u64) {
+        self.budget_micros = budget_micros;
+    }
+
+    ///
+    /// フレームの終わりに呼ぶ。予算を超えていて、警告を控えている最中でなければ警告を返す
+    ///
+    pub fn end_frame(
+        &mut self,
+        counters: &FrameCounters,
+        frame_micros: u64,
+        now: Clock,
+    ) -> Option<BudgetWarning> {
+        if self.budget_micros == 0 || frame_micros <= self.budget_micros {
+            return None;
+        }
+
+        if let Some(last) = self.last_warning {
+            if now < last + BUDGET_WARNING_COOLDOWN {
+                return None;
+            }
+        }
+
+        self.last_warning = Some(now);
+        Some(BudgetWarning {
+            frame_micros: frame_micros,
+            budget_micros: self.budget_micros,
+            offender: counters.top_offender(),
+        })
+    }
+}
@@ -0,0 +1,185 @@
+use rand::Rng;
+use torifune::core::Clock;
+
+///
+/// 一度独り言を言ってから、次に言えるようになるまでのフレーム数
+///
+pub const COMMENT_COOLDOWN: Clock = 1800;
+
+///
+/// 吹き出しを出しておくフレーム数
+///
+pub const COMMENT_SHOW_DURATION: Clock = 180;
+
+///
+/// これより長い題名の本を手に取ると、ぼやくことがある
+///
+pub const LONG_TITLE_CHARS: usize = 10;
+
+///
+/// # 独り言のきっかけ
+///
+/// ## RainyDay
+/// 雨の日に店を開けた
+///
+/// ## CustomerCalling
+/// 客に呼ばれた
+///
+/// ## CustomerGaveUp
+/// 並んでいた客が待ちきれずに帰った
+///
+/// ## HourChimed
+/// 店の時計が一時間進んだ
+///
+/// ## LongBookTitle
+/// 題名の長い本を棚に運ぶ
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentTrigger {
+    RainyDay,
+    CustomerCalling,
+    CustomerGaveUp,
+    HourChimed,
+    LongBookTitle,
+}
+
+///
+/// # 独り言の一つ
+///
+/// ## weight
+/// 同じきっかけの中での選ばれやすさ
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommentLine {
+    pub trigger: CommentTrigger,
+    pub text: &'static str,
+    pub weight: u32,
+}
+
+const fn line(trigger: CommentTrigger, text: &'static str, weight: u32) -> CommentLine {
+    CommentLine {
+        trigger: trigger,
+        text: text,
+        weight: weight,
+    }
+}
+
+///
+/// 標準の独り言
+///
+pub const STANDARD_COMMENT_LINES: [CommentLine; 12] = [
+    line(CommentTrigger::RainyDay, "また雨…", 3),
+    line(CommentTrigger::RainyDay, "本が湿気ないと良いけど", 2),
+    line(CommentTrigger::RainyDay, "今日はお客さん少なそう", 1),
+    line(CommentTrigger::CustomerCalling, "はーい、今行きます", 3),
+    line(CommentTrigger::CustomerCalling, "忙しくなってきた", 1),
+    line(CommentTrigger::CustomerGaveUp, "待たせちゃったなあ", 3),
+    line(CommentTrigger::CustomerGaveUp, "もう少し急がないと", 2),
+    line(CommentTrigger::HourChimed, "もうこんな時間", 2),
+    line(CommentTrigger::HourChimed, "ちょっと休みたいなあ", 1),
+    line(CommentTrigger::LongBookTitle, "この題名長いなあ", 3),
+    line(CommentTrigger::LongBookTitle, "題名だけで疲れちゃう", 1),
+    line(
+        CommentTrigger::LongBookTitle,
+        "誰が付けたんだろう、この題名",
+        1,
+    ),
+];
+
+///
+/// linesからtriggerの独り言を重みで選ぶ。avoidと同じ独り言は、他に候補があれば選ばない
+///
+pub fn pick_comment<R: Rng>(
+    lines: &[CommentLine],
+    trigger: CommentTrigger,
+    avoid: Option<&str>,
+    rng: &mut R,
+) -> Option<&'static str> {
+    let candidates: Vec<&CommentLine> = lines
+        .iter()
+        .filter(|line| line.trigger == trigger && line.weight > 0)
+        .collect();
+
+    let candidates: Vec<&CommentLine> = if candidates.len() > 1 {
+        candidates
+            .into_iter()
+            .filter(|line| Some(line.text) != avoid)
+            .collect()
+    } else {
+        candidates
+    };
+
+    let total: u32 = candidates.iter().map(|line| line.weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.gen_range(0, total);
+    for line in candidates {
+        if roll < line.weight {
+            return Some(line.text);
+        }
+        roll -= line.weight;
+    }
+
+    None
+}
+
+///
+/// # 小鈴の独り言
+/// 遊びを遮らないよう、一度言ったらしばらくは黙る
+///
+/// ## last_comment_at
+/// 最後に独り言を言った時刻
+///
+/// ## last_text
+/// 最後に言った独り言。続けて同じことは言わない
+///
+pub struct KosuzuCommentary {
+    lines: Vec<CommentLine>,
+    cooldown: Clock,
+    last_comment_at: Option<Clock>,
+    last_text: Option<&'static str>,
+}
+
+impl KosuzuCommentary {
+    pub fn new(lines: Vec<CommentLine>, cooldown: Clock) -> Self {
+        KosuzuCommentary {
+            lines: lines,
+            cooldown: cooldown,
+            last_comment_at: None,
+            last_text: None,
+        }
+    }
+
+    pub fn standard() -> Self {
+        Self::new(STANDARD_COMMENT_LINES.to_vec(), COMMENT_COOLDOWN)
+    }
+
+    pub fn can_comment(&self, t: Clock) -> bool {
+        match self.last_comment_at {
+            Some(last) => t.saturating_sub(last) >= self.cooldown,
+            None => true,
+        }
+    }
+
+    ///
+    /// triggerに反応して独り言を選ぶ。黙っている間や、言うことが無ければNone
+    ///
+    pub fn react<R: Rng>(
+        &mut self,
+        trigger: CommentTrigger,
+        t: Clock,
+        rng: &mut R,
+    ) -> Option<&'static str> {
+        if !self.can_comment(t) {
+            return None;
+        }
+
+        let text = pick_comment(&self.lines, trigger, self.last_text, rng)?;
+        self.last_comment_at = Some(t);
+        self.last_text = Some(text);
+
+        Some(text)
+    }
+}
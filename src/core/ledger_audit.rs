@@ -0,0 +1,266 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use torifune::core::Clock;
+
+use crate::core::GensoDate;
+use crate::object::task_object::tt_sub_component::BorrowingRecordBookPageData;
+
+/// 監査で帳簿から写す行の数。新しい頁から数える
+pub const AUDIT_ROWS: usize = 8;
+/// 写せる行がこれより少ない週は監査をしない
+pub const AUDIT_MIN_ROWS: usize = 3;
+/// わざと紛れ込ませる不整合の数
+pub const AUDIT_FLAWS: usize = 3;
+pub const AUDIT_TIME_LIMIT: Clock = 60 * 60;
+
+/// 見つけた不整合一つで取り戻せる売上
+pub const AUDIT_RECOVERY_PER_FLAW: i32 = 150;
+/// 正しい行に印を付けたときに差し引く額
+pub const AUDIT_WRONG_MARK_PENALTY: i32 = 50;
+
+///
+/// # 帳簿の不整合
+///
+/// ## DateOutOfOrder
+/// 貸出日が返却日より後になっている
+///
+/// ## MissingStatus
+/// 返却済みなのに、状態の書かれていない本がある
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditFlaw {
+    DateOutOfOrder,
+    MissingStatus,
+}
+
+///
+/// # 監査表の一行
+/// 帳簿の一頁を写したもの
+///
+/// ## statuses
+/// 状態が書かれている本の数
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub customer: String,
+    pub rental_date: GensoDate,
+    pub return_date: GensoDate,
+    pub books: usize,
+    pub statuses: usize,
+    pub returned: bool,
+}
+
+impl AuditEntry {
+    pub fn new(
+        customer: String,
+        rental_date: GensoDate,
+        return_date: GensoDate,
+        books: usize,
+        statuses: usize,
+        returned: bool,
+    ) -> Self {
+        AuditEntry {
+            customer: customer,
+            rental_date: rental_date,
+            return_date: return_date,
+            books: books,
+            statuses: statuses,
+            returned: returned,
+        }
+    }
+
+    ///
+    /// 貸出の署名まで済んだ頁だけを写す
+    ///
+    pub fn from_page(page: &BorrowingRecordBookPageData) -> Option<Self> {
+        if !page.borrowing_is_signed || page.borrowing_book_title.is_empty() {
+            return None;
+        }
+
+        Some(AuditEntry::new(
+            page.customer_name.clone()?,
+            page.rental_date?,
+            page.return_date?,
+            page.borrowing_book_title.len(),
+            page.borrowing_book_status.len(),
+            page.returning_is_signed,
+        ))
+    }
+
+    pub fn find_flaw(&self) -> Option<AuditFlaw> {
+        if self.rental_date.is_past(&self.return_date) {
+            Some(AuditFlaw::DateOutOfOrder)
+        } else if self.returned && self.statuses < self.books {
+            Some(AuditFlaw::MissingStatus)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// 行を書き換えて、flawの不整合を作る
+    ///
+    pub fn inject_flaw(&mut self, flaw: AuditFlaw) {
+        match flaw {
+            AuditFlaw::DateOutOfOrder => {
+                std::mem::swap(&mut self.rental_date, &mut self.return_date);
+                // 当日返却の頁は入れ替えても同じ日付なので、一日ずらす
+                if !self.rental_date.is_past(&self.return_date) {
+                    self.rental_date = self.return_date.add_days_chain(1);
+                }
+            }
+            AuditFlaw::MissingStatus => {
+                self.returned = true;
+                self.statuses = self.books.saturating_sub(1);
+            }
+        }
+    }
+}
+
+///
+/// 帳簿の頁から、新しいものをAUDIT_ROWS行まで写す
+///
+pub fn collect_entries(pages: &[BorrowingRecordBookPageData]) -> Vec<AuditEntry> {
+    let mut entries: Vec<AuditEntry> = pages
+        .iter()
+        .rev()
+        .filter_map(|page| AuditEntry::from_page(page))
+        .take(AUDIT_ROWS)
+        .collect();
+    entries.reverse();
+    entries
+}
+
+///
+/// 不整合を紛れ込ませる行と、その種類を適当に選ぶ
+///
+pub fn pick_flaws<R: Rng>(rng: &mut R, rows: usize) -> Vec<(usize, AuditFlaw)> {
+    let mut indices: Vec<usize> = (0..rows).collect();
+    indices.shuffle(rng);
+
+    indices
+        .into_iter()
+        .take(AUDIT_FLAWS)
+        .map(|row| {
+            let flaw = if rng.gen::<bool>() {
+                AuditFlaw::DateOutOfOrder
+            } else {
+                AuditFlaw::MissingStatus
+            };
+            (row, flaw)
+        })
+        .collect()
+}
+
+///
+/// # 監査の結果
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditResult {
+    pub found: usize,
+    pub missed: usize,
+    pub wrong_marks: usize,
+}
+
+impl AuditResult {
+    pub fn recovered_money(&self) -> i32 {
+        ((self.found as i32 * AUDIT_RECOVERY_PER_FLAW)
+            - (self.wrong_marks as i32 * AUDIT_WRONG_MARK_PENALTY))
+            .max(0)
+    }
+}
+
+///
+/// # 週末の帳簿監査
+/// 写した帳簿の行に不整合を紛れ込ませ、制限時間内に印を付けさせる
+///
+/// ## marked
+/// 行ごとの、印を付けたかどうか
+///
+/// ## wrong_marks
+/// 不整合の無い行に印を付けた回数
+///
+pub struct LedgerAudit {
+    entries: Vec<AuditEntry>,
+    marked: Vec<bool>,
+    wrong_marks: usize,
+}
+
+impl LedgerAudit {
+    pub fn new(mut entries: Vec<AuditEntry>, flaws: &[(usize, AuditFlaw)]) -> Self {
+        for (row, flaw) in flaws.iter() {
+            if let Some(entry) = entries.get_mut(*row) {
+                entry.inject_flaw(*flaw);
+            }
+        }
+
+        LedgerAudit {
+            marked: vec![false; entries.len()],
+            entries: entries,
+            wrong_marks: 0,
+        }
+    }
+
+    pub fn get_entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    pub fn is_marked(&self, row: usize) -> bool {
+        self.marked.get(row).copied().unwrap_or(false)
+    }
+
+    ///
+    /// 元の帳簿の書き損じも、不整合として数える
+    ///
+    pub fn flaw_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.find_flaw().is_some())
+            .count()
+    }
+
+    pub fn found(&self) -> usize {
+        self.entries
+            .iter()
+            .zip(self.marked.iter())
+            .filter(|(entry, marked)| **marked && entry.find_flaw().is_some())
+            .count()
+    }
+
+    pub fn wrong_marks(&self) -> usize {
+        self.wrong_marks
+    }
+
+    ///
+    /// rowに印を付ける。不整合を見つけたらSome(true)、外れならSome(false)
+    /// 印を付け済みの行や、表に無い行はNone
+    ///
+    pub fn mark(&mut self, row: usize) -> Option<bool> {
+        if row >= self.entries.len() || self.marked[row] {
+            return None;
+        }
+
+        self.marked[row] = true;
+        let hit = self.entries[row].find_flaw().is_some();
+        if !hit {
+            self.wrong_marks += 1;
+        }
+
+        Some(hit)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.found() == self.flaw_count()
+    }
+
+    pub fn finish(&self) -> AuditResult {
+        let found = self.found();
+
+        AuditResult {
+            found: found,
+            missed: self.flaw_count() - found,
+            wrong_marks: self.wrong_marks,
+        }
+    }
+}
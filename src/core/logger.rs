@@ -0,0 +1,426 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+///
+/// 起動時の絞り込みを読む環境変数。"info,suzu::scene=debug" のように書く
+///
+pub const LOG_FILTER_ENV: &str = "SUZU_LOG";
+
+///
+/// 何も指定されていないときの絞り込み
+///
+pub const DEFAULT_LOG_FILTER: &str = "info";
+
+///
+/// 画面で見返せるように手元に残しておく記録の数
+///
+pub const RECENT_LOG_CAPACITY: usize = 256;
+
+///
+/// # 記録の重さ
+/// 上ほど重い。絞り込みでInfoを選ぶと、Info以上の重さのものだけが残る
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(format!("unknown log level: {}", level)),
+        }
+    }
+}
+
+///
+/// "off"ならNone、それ以外はその重さ
+///
+fn parse_level_filter(level: &str) -> Result<Option<LogLevel>, String> {
+    if level.eq_ignore_ascii_case("off") {
+        Ok(None)
+    } else {
+        LogLevel::from_str(level).map(Some)
+    }
+}
+
+fn level_filter_to_str(level: Option<LogLevel>) -> &'static str {
+    match level {
+        Some(level) => level.to_str(),
+        None => "OFF",
+    }
+}
+
+///
+/// # 記録の絞り込み
+/// "info,suzu::scene=debug,map_parser=off" のように、全体の重さとモジュールごとの重さを並べる
+///
+/// ## default_level
+/// どのモジュールの指定にも当てはまらない記録に使う重さ。Noneなら何も残さない
+///
+/// ## modules
+/// モジュールの道筋と重さの組。一番長く当てはまったものを使う
+/// 道筋はクレートの名前を省いて書いてもよい
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    default_level: Option<LogLevel>,
+    modules: Vec<(String, Option<LogLevel>)>,
+}
+
+impl LogFilter {
+    pub fn new(default_level: Option<LogLevel>) -> Self {
+        LogFilter {
+            default_level: default_level,
+            modules: Vec::new(),
+        }
+    }
+
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = LogFilter::new(Some(LogLevel::Info));
+
+        for directive in spec.split(',').map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            let mut parts = directive.splitn(2, '=');
+            let left = parts.next().unwrap().trim();
+
+            match parts.next() {
+                Some(level) => {
+                    if left.is_empty() {
+                        return Err(format!("module is missing: {}", directive));
+                    }
+                    let level = parse_level_filter(level.trim())?;
+                    filter.set_module_level(left, level);
+                }
+                None => filter.default_level = parse_level_filter(left)?,
+            }
+        }
+
+        Ok(filter)
+    }
+
+    ///
+    /// 同じモジュールの指定が既にあれば置き換える
+    ///
+    pub fn set_module_level(&mut self, module: &str, level: Option<LogLevel>) {
+        match self.modules.iter_mut().find(|(m, _)| m == module) {
+            Some(entry) => entry.1 = level,
+            None => self.modules.push((module.to_string(), level)),
+        }
+    }
+
+    fn module_matches(target: &str, module: &str) -> bool {
+        let matches = |path: &str| {
+            path == module || (path.starts_with(module) && path[module.len()..].starts_with("::"))
+        };
+
+        // "suzu::scene::shop_scene" は "scene::shop_scene" とも書ける
+        matches(target)
+            || target
+                .find("::")
+                .map(|index| matches(&target[index + 2..]))
+                .unwrap_or(false)
+    }
+
+    ///
+    /// targetに効く重さ
+    ///
+    pub fn level_for(&self, target: &str) -> Option<LogLevel> {
+        self.modules
+            .iter()
+            .filter(|(module, _)| Self::module_matches(target, module))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default_level)
+    }
+
+    pub fn enabled(&self, level: LogLevel, target: &str) -> bool {
+        match self.level_for(target) {
+            Some(max_level) => level <= max_level,
+            None => false,
+        }
+    }
+
+    ///
+    /// parseで読み直せる形に戻す
+    ///
+    pub fn to_spec(&self) -> String {
+        let mut directives = vec![level_filter_to_str(self.default_level).to_ascii_lowercase()];
+        for (module, level) in self.modules.iter() {
+            directives.push(format!(
+                "{}={}",
+                module,
+                level_filter_to_str(*level).to_ascii_lowercase()
+            ));
+        }
+
+        directives.join(",")
+    }
+}
+
+///
+/// # 一件の記録
+///
+/// ## target
+/// 記録したモジュールの道筋
+///
+/// ## timestamp
+/// 記録した時刻。時:分:秒.ミリ秒
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub target: String,
+    pub timestamp: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} {:<5} {}: {}",
+            self.timestamp,
+            self.level.to_str(),
+            self.target,
+            self.message
+        )
+    }
+}
+
+///
+/// # 最近の記録
+/// capacityを超えたら古いものから捨てる
+///
+/// ## total
+/// これまでに入れた記録の数。画面の表示を作り直すかの判断に使う
+///
+pub struct RecentLog {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    total: u64,
+}
+
+impl RecentLog {
+    pub fn new(capacity: usize) -> Self {
+        RecentLog {
+            records: VecDeque::with_capacity(capacity),
+            capacity: capacity,
+            total: 0,
+        }
+    }
+
+    pub fn push(&mut self, record: LogRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+        self.total += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn get_total(&self) -> u64 {
+        self.total
+    }
+
+    ///
+    /// 新しい方からcount件を、古い順に返す
+    ///
+    pub fn tail(&self, count: usize) -> Vec<LogRecord> {
+        let skip = self.records.len().saturating_sub(count);
+        self.records.iter().skip(skip).cloned().collect()
+    }
+}
+
+struct Logger {
+    filter: LogFilter,
+    recent: RecentLog,
+}
+
+impl Logger {
+    fn new() -> Self {
+        let spec = std::env::var(LOG_FILTER_ENV).unwrap_or_else(|_| DEFAULT_LOG_FILTER.to_string());
+        let filter = match LogFilter::parse(&spec) {
+            Ok(filter) => filter,
+            Err(e) => {
+                eprintln!("{}: {}", LOG_FILTER_ENV, e);
+                LogFilter::new(Some(LogLevel::Info))
+            }
+        };
+
+        Logger {
+            filter: filter,
+            recent: RecentLog::new(RECENT_LOG_CAPACITY),
+        }
+    }
+}
+
+// ゲームの処理は一つのスレッドで回っているので、記録もスレッドごとに持つ
+thread_local! {
+    static LOGGER: RefCell<Logger> = RefCell::new(Logger::new());
+}
+
+///
+/// 絞り込みを通った記録を端末に出し、最近の記録に残す
+/// 直接呼ばずに、log_info!などのマクロから呼ぶ
+///
+pub fn log(level: LogLevel, target: &str, message: String) {
+    LOGGER.with(|logger| {
+        let mut logger = logger.borrow_mut();
+        if !logger.filter.enabled(level, target) {
+            return;
+        }
+
+        let record = LogRecord {
+            level: level,
+            target: target.to_string(),
+            timestamp: chrono::Local::now().format("%H:%M:%S%.3f").to_string(),
+            message: message,
+        };
+
+        match level {
+            LogLevel::Error | LogLevel::Warn => eprintln!("{}", record.to_line()),
+            _ => println!("{}", record.to_line()),
+        }
+
+        logger.recent.push(record);
+    });
+}
+
+pub fn set_filter(filter: LogFilter) {
+    LOGGER.with(|logger| logger.borrow_mut().filter = filter);
+}
+
+pub fn get_filter_spec() -> String {
+    LOGGER.with(|logger| logger.borrow().filter.to_spec())
+}
+
+///
+/// 新しい方からcount件の記録と、これまでに残した記録の数
+///
+pub fn recent_records(count: usize) -> (Vec<LogRecord>, u64) {
+    LOGGER.with(|logger| {
+        let logger = logger.borrow();
+        (logger.recent.tail(count), logger.recent.get_total())
+    })
+}
+
+///
+/// # デバッグ用のコンソールから打つ、記録の指示
+/// "log" で今の絞り込みを出し、"log info,suzu::scene=debug" で絞り込みを変え、
+/// "log view" で画面の記録を開け閉めする
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogCommand {
+    ShowFilter,
+    SetFilter(LogFilter),
+    ToggleViewer,
+}
+
+impl LogCommand {
+    ///
+    /// logで始まらない行はNone。絞り込みが読めなければErr
+    ///
+    pub fn parse(line: &str) -> Option<Result<Self, String>> {
+        let mut words = line.splitn(2, char::is_whitespace);
+        if words.next()? != "log" {
+            return None;
+        }
+
+        let command = match words.next().map(|rest| rest.trim()) {
+            None | Some("") => Ok(LogCommand::ShowFilter),
+            Some("view") => Ok(LogCommand::ToggleViewer),
+            Some(spec) => LogFilter::parse(spec).map(LogCommand::SetFilter),
+        };
+
+        Some(command)
+    }
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg: tt)*) => {
+        $crate::core::logger::log(
+            $crate::core::logger::LogLevel::Error,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg: tt)*) => {
+        $crate::core::logger::log(
+            $crate::core::logger::LogLevel::Warn,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg: tt)*) => {
+        $crate::core::logger::log(
+            $crate::core::logger::LogLevel::Info,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg: tt)*) => {
+        $crate::core::logger::log(
+            $crate::core::logger::LogLevel::Debug,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg: tt)*) => {
+        $crate::core::logger::log(
+            $crate::core::logger::LogLevel::Trace,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
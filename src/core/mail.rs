@@ -0,0 +1,267 @@
+use std::collections::BTreeSet;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{BookInformation, GensoDate};
+
+///
+/// 一朝に届く手紙の数の上限
+///
+pub const MAIL_MAX_LETTERS: usize = 3;
+
+///
+/// 手紙が話の種になる割合。まだ読んでいない話の種が残っている場合だけ
+///
+pub const STORY_LETTER_RATE: f32 = 0.2;
+
+///
+/// 礼状を読んだときに上がる評判
+///
+pub const THANK_YOU_REPUTATION: f32 = 2.0;
+
+///
+/// 郵便の写本を仕上げて送り返すまでの日数
+///
+pub const POSTAL_COPY_DAYS: i32 = 2;
+
+pub const POSTAL_COPY_BASE_FEE: u32 = 200;
+pub const POSTAL_COPY_FEE_PER_PAGE: u32 = 2;
+
+///
+/// 話の種になる手紙。フラグ、差出人、本文の順
+///
+pub const STORY_LETTERS: [(&str, &str, &str); 3] = [
+    (
+        "letter_akyu_engi",
+        "稗田阿求",
+        "幻想郷縁起ノ写シヲ頼ミタイ。近イ内ニ伺イマス",
+    ),
+    (
+        "letter_reimu_warning",
+        "博麗霊夢",
+        "近頃、里デ妖魔本ノ気配ガスル。怪シイ本ニハ気ヲ付ケテ",
+    ),
+    (
+        "letter_marisa_rumor",
+        "霧雨魔理沙",
+        "珍シイ本ガ入ッタッテ噂ヲ聞イタゾ。今度見セテクレ",
+    ),
+];
+
+///
+/// # 手紙の中身
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LetterContent {
+    CopyRequest { title: String, pages: u32 },
+    ThankYou { reputation: f32 },
+    StoryHook { flag: String, text: String },
+}
+
+///
+/// # 郵便で届いた手紙
+///
+/// ## id
+/// 郵便受けの中で手紙を見分ける番号
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Letter {
+    pub id: u32,
+    pub sender: String,
+    pub received: GensoDate,
+    pub content: LetterContent,
+}
+
+impl Letter {
+    ///
+    /// 便箋に書かれている文
+    ///
+    pub fn body_text(&self) -> String {
+        match &self.content {
+            LetterContent::CopyRequest { title, .. } => format!(
+                "「{}」ノ写本ヲ一部オ願イシタイ。出来上ガッタラ送ッテ下サイ",
+                title
+            ),
+            LetterContent::ThankYou { .. } => {
+                "先日ハ良イ本ヲ貸シテ頂キ、アリガトウゴザイマシタ".to_string()
+            }
+            LetterContent::StoryHook { text, .. } => text.clone(),
+        }
+    }
+}
+
+///
+/// # 郵便で受けた写本の依頼
+///
+/// ## due
+/// 写本を送り返す日
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostalCopyRequest {
+    pub sender: String,
+    pub title: String,
+    pub pages: u32,
+    pub due: GensoDate,
+}
+
+impl PostalCopyRequest {
+    pub fn fee(&self) -> u32 {
+        POSTAL_COPY_BASE_FEE + (self.pages * POSTAL_COPY_FEE_PER_PAGE)
+    }
+}
+
+///
+/// # 郵便受け
+/// 毎朝手紙を届け、開けた手紙の中身を写本の依頼やフラグに振り分ける
+///
+/// ## unread
+/// 届いたが、まだ開けていない手紙。開けるまで毎朝机に置き直す
+///
+/// ## story_flags
+/// 読んだ話の種の手紙のフラグ
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MailBox {
+    unread: Vec<Letter>,
+    copy_requests: Vec<PostalCopyRequest>,
+    story_flags: BTreeSet<String>,
+    last_delivery: Option<GensoDate>,
+    next_letter_id: u32,
+}
+
+impl MailBox {
+    pub fn new() -> Self {
+        MailBox {
+            unread: Vec::new(),
+            copy_requests: Vec::new(),
+            story_flags: BTreeSet::new(),
+            last_delivery: None,
+            next_letter_id: 0,
+        }
+    }
+
+    fn post(&mut self, sender: &str, today: &GensoDate, content: LetterContent) {
+        self.unread.push(Letter {
+            id: self.next_letter_id,
+            sender: sender.to_string(),
+            received: *today,
+            content: content,
+        });
+        self.next_letter_id += 1;
+    }
+
+    fn next_story_letter(&self) -> Option<(&'static str, &'static str, &'static str)> {
+        STORY_LETTERS.iter().cloned().find(|(flag, _, _)| {
+            !self.has_flag(flag)
+                && !self.unread.iter().any(|letter| match &letter.content {
+                    LetterContent::StoryHook { flag: f, .. } => f == flag,
+                    _ => false,
+                })
+        })
+    }
+
+    ///
+    /// 朝の郵便を受け取り、机に置く手紙を返す。開けていない手紙も一緒に返す
+    ///
+    /// 同じ日に二度呼んでも、手紙は増えない
+    ///
+    pub fn deliver_morning<R: Rng>(
+        &mut self,
+        rng: &mut R,
+        today: &GensoDate,
+        senders: &[String],
+        books: &[BookInformation],
+    ) -> Vec<Letter> {
+        if self.last_delivery.as_ref() == Some(today) {
+            return self.unread.clone();
+        }
+        self.last_delivery = Some(*today);
+
+        for _ in 0..rng.gen_range(0, MAIL_MAX_LETTERS + 1) {
+            if rng.gen::<f32>() < STORY_LETTER_RATE {
+                if let Some((flag, sender, text)) = self.next_story_letter() {
+                    self.post(
+                        sender,
+                        today,
+                        LetterContent::StoryHook {
+                            flag: flag.to_string(),
+                            text: text.to_string(),
+                        },
+                    );
+                    continue;
+                }
+            }
+
+            let sender = match senders.choose(rng) {
+                Some(sender) => sender.clone(),
+                None => continue,
+            };
+
+            let content = match books.choose(rng) {
+                Some(book) if rng.gen::<bool>() => LetterContent::CopyRequest {
+                    title: book.name.clone(),
+                    pages: book.pages as u32,
+                },
+                _ => LetterContent::ThankYou {
+                    reputation: THANK_YOU_REPUTATION,
+                },
+            };
+            self.post(&sender, today, content);
+        }
+
+        self.unread.clone()
+    }
+
+    ///
+    /// 手紙を開ける。写本の依頼は依頼の列に並べ、話の種はフラグを立てる
+    ///
+    /// 開けた手紙を返す。既に開けた手紙ならNone
+    ///
+    pub fn open(&mut self, id: u32, today: &GensoDate) -> Option<Letter> {
+        let index = self.unread.iter().position(|letter| letter.id == id)?;
+        let letter = self.unread.remove(index);
+
+        match &letter.content {
+            LetterContent::CopyRequest { title, pages } => {
+                self.copy_requests.push(PostalCopyRequest {
+                    sender: letter.sender.clone(),
+                    title: title.clone(),
+                    pages: *pages,
+                    due: today.add_days_chain(POSTAL_COPY_DAYS),
+                })
+            }
+            LetterContent::StoryHook { flag, .. } => {
+                self.story_flags.insert(flag.clone());
+            }
+            LetterContent::ThankYou { .. } => (),
+        }
+
+        Some(letter)
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.unread.len()
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.story_flags.contains(flag)
+    }
+
+    pub fn get_copy_requests(&self) -> &Vec<PostalCopyRequest> {
+        &self.copy_requests
+    }
+
+    ///
+    /// 送り返す日を迎えた写本の依頼を列から取り出す
+    ///
+    pub fn take_finished_copies(&mut self, today: &GensoDate) -> Vec<PostalCopyRequest> {
+        let (finished, rest) = self
+            .copy_requests
+            .drain(..)
+            .partition(|request| request.due == *today || today.is_past(&request.due));
+        self.copy_requests = rest;
+        finished
+    }
+}
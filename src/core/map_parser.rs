@@ -1,5 +1,7 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
 use std::rc::Rc;
 
 use pathfinding::prelude::dijkstra;
@@ -16,8 +18,72 @@ use torifune::{mintp, mintv};
 
 use collision::prelude::*;
 
+use crate::core::customer_watchdog;
+use crate::core::resource_locator::ResourceLocator;
 use crate::object::collision::*;
 use crate::object::map_object::MapObject;
+use crate::{log_debug, log_trace, log_warn};
+
+///
+/// 地形の速さの倍率が1.0のときの、経路探索での一歩の重み
+///
+pub const TERRAIN_BASE_COST: usize = 10;
+
+///
+/// 誰かが立っているタイルへ踏み込むときに足す重み。通れなくはしないので、他に道が無ければ通り抜ける
+///
+pub const OCCUPIED_TILE_COST: usize = TERRAIN_BASE_COST * 6;
+
+///
+/// 一フレームに差し替えるアニメーションタイルの数の上限。残りは次のフレームに回す
+///
+pub const MAX_ANIMATED_TILE_UPDATES_PER_FRAME: usize = 64;
+
+///
+/// タイルのアニメーションの時間(ミリ秒)をフレーム数に直すときの、一秒のフレーム数
+///
+const TILE_ANIMATION_FPS: f32 = 60.0;
+
+///
+/// # タイルの地形
+/// タイルセットの各タイルに、terrainプロパティで指定する
+/// 指定の無いタイルはTatamiとして扱う
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TerrainType {
+    Tatami,
+    Doorway,
+    OutsidePath,
+}
+
+impl TerrainType {
+    pub fn from_property(s: &str) -> Option<Self> {
+        match s {
+            "tatami" => Some(TerrainType::Tatami),
+            "doorway" => Some(TerrainType::Doorway),
+            "path" => Some(TerrainType::OutsidePath),
+            _ => None,
+        }
+    }
+
+    ///
+    /// 歩く速さの倍率
+    ///
+    pub fn speed_rate(&self) -> f32 {
+        match self {
+            TerrainType::Tatami => 1.0,
+            TerrainType::Doorway => 0.6,
+            TerrainType::OutsidePath => 1.3,
+        }
+    }
+
+    ///
+    /// 経路探索での一歩の重み。速く歩ける地形ほど軽い
+    ///
+    pub fn path_cost(&self) -> usize {
+        (TERRAIN_BASE_COST as f32 / self.speed_rate()).round() as usize
+    }
+}
 
 ///
 /// # TileSetの情報を保持している構造体
@@ -34,12 +100,20 @@ use crate::object::map_object::MapObject;
 /// ### first_gid
 /// このtilesetのfirst_gidを格納している
 ///
+/// ### terrain_info
+/// terrainプロパティを持つタイルの地形。キーはfirst_gidからの相対的なid
+///
+/// ### animation_info
+/// アニメーションを持つタイルのコマの並び。キーはfirst_gidからの相対的なid
+///
 pub struct TileSet {
     tile_size: numeric::Vector2u,
     tile_size_ratio: numeric::Vector2f,
     tile_count: numeric::Vector2u,
     first_gid: u32,
     collision_info: HashMap<u32, Vec<CollisionType>>,
+    terrain_info: HashMap<u32, TerrainType>,
+    animation_info: HashMap<u32, TileAnimation>,
 }
 
 impl TileSet {
@@ -47,8 +121,30 @@ impl TileSet {
         // tilesetが使用する画像を読み込む
         let tiled_image = tileset.images.get(0).unwrap();
         let mut collision_info: HashMap<u32, Vec<CollisionType>> = HashMap::new();
+        let mut terrain_info: HashMap<u32, TerrainType> = HashMap::new();
+        let mut animation_info: HashMap<u32, TileAnimation> = HashMap::new();
 
         for tile in &tileset.tiles {
+            if let Some(frames) = &tile.animation {
+                let frames: Vec<(u32, u32)> = frames
+                    .iter()
+                    .map(|frame| (frame.tile_id, frame.duration))
+                    .collect();
+                if let Some(animation) = TileAnimation::from_millis_frames(&frames) {
+                    animation_info.insert(tile.id, animation);
+                }
+            }
+
+            if let Some(tiled::PropertyValue::StringValue(terrain)) = tile.properties.get("terrain")
+            {
+                match TerrainType::from_property(terrain) {
+                    Some(terrain_type) => {
+                        terrain_info.insert(tile.id, terrain_type);
+                    }
+                    None => log_warn!("unknown terrain: {}", terrain),
+                }
+            }
+
             if let Some(group) = &tile.objectgroup {
                 for object in &group.objects {
                     let c = match &object.shape {
@@ -96,6 +192,8 @@ impl TileSet {
                 tile_count: tile_count,
                 first_gid: tileset.first_gid,
                 collision_info: collision_info,
+                terrain_info: terrain_info,
+                animation_info: animation_info,
             },
             image,
         )
@@ -223,6 +321,306 @@ impl TileSet {
     fn get_first_gid(&self) -> u32 {
         self.first_gid
     }
+
+    fn get_terrain(&self, gid: u32) -> Option<TerrainType> {
+        if !self.contains_gid(gid) {
+            return None;
+        }
+
+        self.terrain_info.get(&(gid - self.first_gid)).copied()
+    }
+
+    fn get_animation(&self, gid: u32) -> Option<&TileAnimation> {
+        if !self.contains_gid(gid) {
+            return None;
+        }
+
+        self.animation_info.get(&(gid - self.first_gid))
+    }
+}
+
+///
+/// # タイルのアニメーション
+/// タイルセットでアニメーションを指定されたタイルの、コマの並び
+///
+/// ## frames
+/// タイルセットの中での相対的なidと、そのコマを表示するフレーム数
+///
+/// ## total
+/// 一周にかかるフレーム数
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileAnimation {
+    frames: Vec<(u32, Clock)>,
+    total: Clock,
+}
+
+impl TileAnimation {
+    ///
+    /// TMXのコマ(相対的なidと、表示する時間(ミリ秒))から作る。コマが無ければNone
+    ///
+    pub fn from_millis_frames(frames: &[(u32, u32)]) -> Option<Self> {
+        if frames.is_empty() {
+            return None;
+        }
+
+        let frames: Vec<(u32, Clock)> = frames
+            .iter()
+            .map(|&(tile_id, duration)| {
+                let frame_count = (duration as f32 / 1000.0 * TILE_ANIMATION_FPS).round();
+                (tile_id, frame_count.max(1.0) as Clock)
+            })
+            .collect();
+        let total = frames.iter().map(|(_, duration)| duration).sum();
+
+        Some(TileAnimation {
+            frames: frames,
+            total: total,
+        })
+    }
+
+    ///
+    /// 時刻tに表示するコマの、タイルセットの中での相対的なid
+    ///
+    pub fn tile_id_at(&self, t: Clock) -> u32 {
+        let mut rest = t % self.total;
+
+        for (tile_id, duration) in self.frames.iter() {
+            if rest < *duration {
+                return *tile_id;
+            }
+            rest -= duration;
+        }
+
+        self.frames.last().unwrap().0
+    }
+
+    pub fn get_total_frames(&self) -> Clock {
+        self.total
+    }
+}
+
+///
+/// # オブジェクトレイヤーに置かれた目印
+/// TMXのオブジェクトから、MapLayoutを組み立てるのに使う所だけを抜き出したもの
+///
+/// ## kind
+/// オブジェクトのtype。player_spawn, customer_entrance, counter, exit, check_point,
+/// queue_slot, browse_spot, decorationのどれか
+///
+/// ## x, y
+/// タイルセットの大きさでのピクセル座標。拡大率は掛かっていない
+///
+/// ## order
+/// orderプロパティ。同じtypeの目印を並べる順番
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutObject {
+    pub kind: String,
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub order: i32,
+}
+
+///
+/// # マップの配置情報
+/// 主人公の出現位置や客の入り口、勘定場のタイルなどをTMXのオブジェクトレイヤーから読む
+/// マップに目印が無いものは、鈴奈庵のマップでの位置を使う
+///
+/// ## player_spawn
+/// 主人公が現れるマップ座標
+///
+/// ## customer_entrances
+/// 客が店に入ってくるマップ座標。複数あれば客ごとに選ぶ
+///
+/// ## counter_tile
+/// 客が本を借りに並ぶ勘定場のタイル
+///
+/// ## exit_tile
+/// 用事が済んだ客が帰っていくタイル
+///
+/// ## check_point
+/// 本を探しに来た客が、確認を待って並び始めるタイル
+///
+/// ## queue_slots
+/// 勘定場に並ぶ客の位置。足りない分は、最後の二つの間隔で伸ばす
+///
+/// ## browse_spots
+/// 客が本を眺めに立ち寄るタイル
+///
+/// ## decoration_anchors
+/// 飾りや小物を描く位置。名前で引く
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapLayout {
+    pub player_spawn: numeric::Point2f,
+    pub customer_entrances: Vec<numeric::Point2f>,
+    pub counter_tile: numeric::Vector2u,
+    pub exit_tile: numeric::Vector2u,
+    pub check_point: numeric::Vector2u,
+    pub queue_slots: Vec<numeric::Vector2u>,
+    pub browse_spots: Vec<numeric::Vector2u>,
+    pub decoration_anchors: HashMap<String, numeric::Vector2u>,
+}
+
+impl MapLayout {
+    ///
+    /// 鈴奈庵のマップでの配置
+    ///
+    pub fn shop_default() -> Self {
+        let mut decoration_anchors = HashMap::new();
+        decoration_anchors.insert("umbrella_stand".to_string(), numeric::Vector2u::new(16, 13));
+
+        MapLayout {
+            player_spawn: numeric::Point2f::new(172.0, 1330.0),
+            customer_entrances: vec![numeric::Point2f::new(1430.0, 1246.0)],
+            counter_tile: numeric::Vector2u::new(5, 14),
+            exit_tile: numeric::Vector2u::new(15, 14),
+            check_point: numeric::Vector2u::new(15, 4),
+            queue_slots: vec![numeric::Vector2u::new(5, 14), numeric::Vector2u::new(6, 14)],
+            browse_spots: vec![numeric::Vector2u::new(10, 4), numeric::Vector2u::new(6, 4)],
+            decoration_anchors: decoration_anchors,
+        }
+    }
+
+    ///
+    /// オブジェクトレイヤーの目印から配置を組み立てる。目印の無いものはshop_defaultのまま
+    ///
+    pub fn from_objects(
+        objects: &[LayoutObject],
+        tile_size: numeric::Vector2u,
+        scale: numeric::Vector2f,
+    ) -> Self {
+        let mut layout = Self::shop_default();
+
+        let mut sorted: Vec<&LayoutObject> = objects.iter().collect();
+        sorted.sort_by_key(|object| object.order);
+
+        let to_map =
+            |object: &LayoutObject| numeric::Point2f::new(object.x * scale.x, object.y * scale.y);
+        let to_tile = |object: &LayoutObject| {
+            numeric::Vector2u::new(
+                (object.x.max(0.0) / tile_size.x as f32) as u32,
+                (object.y.max(0.0) / tile_size.y as f32) as u32,
+            )
+        };
+        let collect_points = |kind: &str| -> Vec<numeric::Point2f> {
+            sorted
+                .iter()
+                .filter(|object| object.kind == kind)
+                .map(|object| to_map(object))
+                .collect()
+        };
+        let collect_tiles = |kind: &str| -> Vec<numeric::Vector2u> {
+            sorted
+                .iter()
+                .filter(|object| object.kind == kind)
+                .map(|object| to_tile(object))
+                .collect()
+        };
+
+        if let Some(spawn) = collect_points("player_spawn").first() {
+            layout.player_spawn = *spawn;
+        }
+
+        let entrances = collect_points("customer_entrance");
+        if !entrances.is_empty() {
+            layout.customer_entrances = entrances;
+        }
+
+        if let Some(counter) = collect_tiles("counter").first() {
+            layout.counter_tile = *counter;
+        }
+
+        if let Some(exit) = collect_tiles("exit").first() {
+            layout.exit_tile = *exit;
+        }
+
+        if let Some(check_point) = collect_tiles("check_point").first() {
+            layout.check_point = *check_point;
+        }
+
+        let queue_slots = collect_tiles("queue_slot");
+        if !queue_slots.is_empty() {
+            layout.queue_slots = queue_slots;
+        }
+
+        let browse_spots = collect_tiles("browse_spot");
+        if !browse_spots.is_empty() {
+            layout.browse_spots = browse_spots;
+        }
+
+        for object in sorted.iter().filter(|object| object.kind == "decoration") {
+            layout
+                .decoration_anchors
+                .insert(object.name.clone(), to_tile(object));
+        }
+
+        for object in sorted.iter() {
+            match object.kind.as_str() {
+                "player_spawn" | "customer_entrance" | "counter" | "exit" | "check_point"
+                | "queue_slot" | "browse_spot" | "decoration" => (),
+                _ => log_warn!("unknown map layout object: {}", object.kind),
+            }
+        }
+
+        layout
+    }
+
+    ///
+    /// index番目の客の入り口。入り口が複数あれば、indexで選ぶ
+    ///
+    pub fn customer_entrance(&self, index: usize) -> numeric::Point2f {
+        self.customer_entrances[index % self.customer_entrances.len()]
+    }
+
+    ///
+    /// 勘定場の列のindex番目の位置
+    ///
+    pub fn queue_slot(&self, index: usize) -> numeric::Vector2u {
+        if index < self.queue_slots.len() {
+            return self.queue_slots[index];
+        }
+
+        let last = *self.queue_slots.last().unwrap();
+        let (step_x, step_y) = if self.queue_slots.len() >= 2 {
+            let before = self.queue_slots[self.queue_slots.len() - 2];
+            (
+                last.x as i64 - before.x as i64,
+                last.y as i64 - before.y as i64,
+            )
+        } else {
+            (1, 0)
+        };
+
+        let extra = (index - self.queue_slots.len() + 1) as i64;
+        numeric::Vector2u::new(
+            (last.x as i64 + (step_x * extra)).max(0) as u32,
+            (last.y as i64 + (step_y * extra)).max(0) as u32,
+        )
+    }
+
+    pub fn get_decoration_anchor(&self, name: &str) -> Option<numeric::Vector2u> {
+        self.decoration_anchors.get(name).copied()
+    }
+}
+
+///
+/// # SpriteBatchに積んだアニメーションタイル
+///
+/// ## base_gid
+/// マップに置かれているタイルのgid。アニメーションはこのタイルに付いている
+///
+/// ## current_gid
+/// 今SpriteBatchに積んでいるコマのgid
+///
+struct AnimatedTileEntry {
+    first_gid: u32,
+    sprite_index: ggraphics::spritebatch::SpriteIdx,
+    base_gid: u32,
+    current_gid: u32,
+    dest: numeric::Point2f,
 }
 
 #[derive(Clone, Copy)]
@@ -231,14 +629,23 @@ pub struct CollisionMapNode {
     pub cost: i32,
 }
 
+///
+/// ## terrain_costs
+/// タイルごとの、そのタイルへ踏み込むときの重み。terrain_costs[x][y]
+///
 pub struct CollisionMap {
     map: Vec<Vec<CollisionMapNode>>,
     size: numeric::Vector2u,
     collision_objects: Vec<numeric::Vector2u>,
+    terrain_costs: Vec<Vec<usize>>,
 }
 
 impl CollisionMap {
-    pub fn new(size: numeric::Vector2u, collision_objects: Vec<numeric::Vector2u>) -> Self {
+    pub fn new(
+        size: numeric::Vector2u,
+        collision_objects: Vec<numeric::Vector2u>,
+        terrain_costs: Vec<Vec<usize>>,
+    ) -> Self {
         CollisionMap {
             map: vec![
                 vec![
@@ -252,14 +659,40 @@ impl CollisionMap {
             ],
             size: size,
             collision_objects: collision_objects,
+            terrain_costs: terrain_costs,
         }
     }
 
+    fn terrain_cost(&self, position: &numeric::Point2i) -> usize {
+        self.terrain_costs
+            .get(position.x as usize)
+            .and_then(|column| column.get(position.y as usize))
+            .copied()
+            .unwrap_or(TERRAIN_BASE_COST)
+    }
+
     pub fn not_contains(&self, position: numeric::Point2i) -> bool {
         position.x < 0
-            && position.x >= self.size.x as i32
-            && position.y < 0
-            && position.y >= self.size.y as i32
+            || position.x >= self.size.x as i32
+            || position.y < 0
+            || position.y >= self.size.y as i32
+    }
+
+    ///
+    /// タイルを通れなくする、または通れるようにする。経路探索はこの後から変わる
+    ///
+    pub fn set_blocked(&mut self, position: numeric::Vector2u, blocked: bool) {
+        if blocked {
+            if !self.collision_objects.contains(&position) {
+                self.collision_objects.push(position);
+            }
+        } else {
+            self.collision_objects.retain(|object| *object != position);
+        }
+    }
+
+    pub fn is_blocked(&self, position: numeric::Vector2u) -> bool {
+        self.collision_objects.contains(&position)
     }
 
     fn is_movable_position(&self, x: i32, y: i32) -> bool {
@@ -281,10 +714,22 @@ impl CollisionMap {
         &self,
         start: numeric::Point2i,
         goal: numeric::Point2i,
+    ) -> Option<Vec<numeric::Point2i>> {
+        self.find_path_avoiding(start, goal, &[])
+    }
+
+    ///
+    /// occupiedのタイルはなるべく避けて通るルートを探す
+    ///
+    pub fn find_path_avoiding(
+        &self,
+        start: numeric::Point2i,
+        goal: numeric::Point2i,
+        occupied: &[numeric::Vector2u],
     ) -> Option<Vec<numeric::Point2i>> {
         let result = dijkstra(
             &start,
-            |point| self.successors(point),
+            |point| self.successors(point, occupied),
             |point| point.eq(&goal),
         );
 
@@ -295,7 +740,11 @@ impl CollisionMap {
         }
     }
 
-    fn successors(&self, point: &numeric::Point2i) -> Vec<(numeric::Point2i, usize)> {
+    fn successors(
+        &self,
+        point: &numeric::Point2i,
+        occupied: &[numeric::Vector2u],
+    ) -> Vec<(numeric::Point2i, usize)> {
         let mut successors_list = Vec::new();
 
         let cand_point = numeric::Point2i::new(point.x as i32, point.y as i32 - 1);
@@ -318,7 +767,287 @@ impl CollisionMap {
             successors_list.push(cand_point);
         }
 
-        successors_list.into_iter().map(|p| (p, 1)).collect()
+        successors_list
+            .into_iter()
+            .map(|p| {
+                let mut cost = self.terrain_cost(&p);
+                if occupied.contains(&numeric::Vector2u::new(p.x as u32, p.y as u32)) {
+                    cost += OCCUPIED_TILE_COST;
+                }
+                (p, cost)
+            })
+            .collect()
+    }
+}
+
+///
+/// # TMXを読めなかった理由
+///
+/// ## Io
+/// ファイルを開けなかった
+///
+/// ## Parse
+/// tiledが読めなかった。知らない圧縮の形式や、見つからない外部タイルセットもここに入る
+///
+/// ## NoTilesetImage
+/// 画像を持たないタイルセット。タイルごとに画像を持つタイルセットには対応していない
+///
+/// ## EmptyInfiniteMap
+/// 無限マップに、チャンクが一つも無い
+///
+#[derive(Debug)]
+pub enum MapParseError {
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    Parse {
+        path: String,
+        error: tiled::TiledError,
+    },
+    NoTilesetImage {
+        path: String,
+        tileset: String,
+    },
+    EmptyInfiniteMap {
+        path: String,
+    },
+}
+
+impl fmt::Display for MapParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapParseError::Io { path, error } => {
+                write!(f, "failed to read map {}: {}", path, error)
+            }
+            MapParseError::Parse { path, error } => {
+                write!(f, "failed to parse map {}: {}", path, error)
+            }
+            MapParseError::NoTilesetImage { path, tileset } => write!(
+                f,
+                "tileset {} in map {} has no image. image collection tilesets are not supported",
+                tileset, path
+            ),
+            MapParseError::EmptyInfiniteMap { path } => {
+                write!(f, "infinite map {} has no chunks", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapParseError {}
+
+///
+/// TMXを読み、描画や衝突判定でそのまま扱える形に整える
+///
+/// 外部タイルセット(.tsx)の画像は、.tsxからの相対パスを素材の置き場所からのパスに直す
+/// 無限マップは、全てのチャンクを囲む一枚の有限なレイヤーに並べ直す
+///
+pub fn load_tmx(path: &str) -> Result<tiled::Map, MapParseError> {
+    let tmx = std::fs::read_to_string(path).map_err(|error| MapParseError::Io {
+        path: path.to_string(),
+        error: error,
+    })?;
+
+    let mut tile_map =
+        tiled::parse_file(Path::new(path)).map_err(|error| MapParseError::Parse {
+            path: path.to_string(),
+            error: error,
+        })?;
+
+    if let Some(tileset) = tile_map.tilesets.iter().find(|ts| ts.images.is_empty()) {
+        return Err(MapParseError::NoTilesetImage {
+            path: path.to_string(),
+            tileset: tileset.name.clone(),
+        });
+    }
+
+    if !flatten_infinite_layers(&mut tile_map) {
+        return Err(MapParseError::EmptyInfiniteMap {
+            path: path.to_string(),
+        });
+    }
+
+    resolve_tileset_images(&mut tile_map, path, &tmx);
+
+    Ok(tile_map)
+}
+
+///
+/// TMXの中で外部タイルセットを参照している所を、first_gidと.tsxのパスの組で返す
+///
+pub fn external_tileset_sources(tmx: &str) -> Vec<(u32, String)> {
+    let attribute = |tag: &str, name: &str| {
+        let key = format!(" {}=\"", name);
+        let begin = tag.find(&key)? + key.len();
+        let end = tag[begin..].find('"')? + begin;
+        Some(tag[begin..end].to_string())
+    };
+
+    tmx.match_indices("<tileset")
+        .filter_map(|(begin, _)| {
+            let end = tmx[begin..].find('>')? + begin;
+            let tag = &tmx[begin..end];
+            let first_gid = attribute(tag, "firstgid")?.parse::<u32>().ok()?;
+            let source = attribute(tag, "source")?;
+            Some((first_gid, source))
+        })
+        .collect()
+}
+
+///
+/// 無限マップのチャンクを、全てのチャンクを囲む有限なレイヤーに並べ直す
+/// オブジェクトの位置も、左上のチャンクが原点に来るようにずらす
+///
+/// 無限なレイヤーがあるのにチャンクが一つも無ければfalseを返す
+///
+fn flatten_infinite_layers(tile_map: &mut tiled::Map) -> bool {
+    let mut has_infinite_layer = false;
+    let mut bounds: Option<(i32, i32, i32, i32)> = None;
+
+    for layer in tile_map.layers.iter() {
+        if let tiled::LayerData::Infinite(chunks) = &layer.tiles {
+            has_infinite_layer = true;
+
+            for chunk in chunks.values() {
+                let (left, top) = (chunk.x, chunk.y);
+                let (right, bottom) = (chunk.x + chunk.width as i32, chunk.y + chunk.height as i32);
+                bounds = Some(match bounds {
+                    Some((l, t, r, b)) => (l.min(left), t.min(top), r.max(right), b.max(bottom)),
+                    None => (left, top, right, bottom),
+                });
+            }
+        }
+    }
+
+    if !has_infinite_layer {
+        return true;
+    }
+
+    let (left, top, right, bottom) = match bounds {
+        Some(bounds) => bounds,
+        None => return false,
+    };
+    let (width, height) = ((right - left) as usize, (bottom - top) as usize);
+
+    for layer in tile_map.layers.iter_mut() {
+        let mut tiles = vec![vec![tiled::LayerTile::new(0); width]; height];
+
+        if let tiled::LayerData::Infinite(chunks) = &layer.tiles {
+            for chunk in chunks.values() {
+                for (y, row) in chunk.tiles.iter().enumerate() {
+                    for (x, tile) in row.iter().enumerate() {
+                        tiles[(chunk.y - top) as usize + y][(chunk.x - left) as usize + x] =
+                            tile.clone();
+                    }
+                }
+            }
+        } else {
+            // 無限マップの中の有限なレイヤーは、原点から置かれている
+            for (y, row) in finite_tiles(layer).iter().enumerate() {
+                for (x, tile) in row.iter().enumerate() {
+                    let (dest_x, dest_y) = (x as i32 - left, y as i32 - top);
+                    if dest_x >= 0 && dest_y >= 0 && dest_x < width as i32 && dest_y < height as i32
+                    {
+                        tiles[dest_y as usize][dest_x as usize] = tile.clone();
+                    }
+                }
+            }
+        }
+
+        layer.tiles = tiled::LayerData::Finite(tiles);
+    }
+
+    let offset = numeric::Vector2f::new(
+        (left * tile_map.tile_width as i32) as f32,
+        (top * tile_map.tile_height as i32) as f32,
+    );
+    for object in tile_map
+        .object_groups
+        .iter_mut()
+        .flat_map(|group| group.objects.iter_mut())
+    {
+        object.x -= offset.x;
+        object.y -= offset.y;
+    }
+
+    log_debug!(
+        "flattened infinite map: origin ({}, {}), size {}x{}",
+        left,
+        top,
+        width,
+        height
+    );
+    tile_map.width = width as u32;
+    tile_map.height = height as u32;
+
+    true
+}
+
+///
+/// タイルセットの画像のパスを、素材の置き場所からのパスに直す
+///
+/// Tiledは画像を.tsx(埋め込みならTMX)からの相対パスで書く。そこに画像が無ければ、
+/// 素材の置き場所からのパスとして書かれたものとみなして、そのままにする
+///
+fn resolve_tileset_images(tile_map: &mut tiled::Map, path: &str, tmx: &str) {
+    let resources = match ResourceLocator::from_env()
+        .locate()
+        .ok()
+        .and_then(|dir| std::fs::canonicalize(dir).ok())
+    {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let map_dir = Path::new(path)
+        .parent()
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or_default();
+    let sources = external_tileset_sources(tmx);
+
+    for tileset in tile_map.tilesets.iter_mut() {
+        let base_dir = match sources.iter().find(|(gid, _)| *gid == tileset.first_gid) {
+            Some((_, tsx)) => map_dir
+                .join(tsx)
+                .parent()
+                .map(|dir| dir.to_path_buf())
+                .unwrap_or_else(|| map_dir.clone()),
+            None => map_dir.clone(),
+        };
+
+        for image in tileset.images.iter_mut() {
+            let relative = std::fs::canonicalize(base_dir.join(&image.source))
+                .ok()
+                .and_then(|image_path| {
+                    image_path
+                        .strip_prefix(&resources)
+                        .ok()
+                        .map(|p| p.to_path_buf())
+                });
+
+            if let Some(relative) = relative {
+                let source = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<String>>()
+                    .join("/");
+                log_trace!("tileset image {} -> /{}", image.source, source);
+                image.source = source;
+            }
+        }
+    }
+}
+
+///
+/// レイヤーのタイルの並び。無限マップは、読み込んだときに有限なものへ並べ直してある
+///
+fn finite_tiles(layer: &tiled::Layer) -> &Vec<Vec<tiled::LayerTile>> {
+    match &layer.tiles {
+        tiled::LayerData::Finite(tiles) => tiles,
+        tiled::LayerData::Infinite(_) => {
+            unreachable!("infinite layers are flattened when the map is loaded")
+        }
     }
 }
 
@@ -337,11 +1066,36 @@ impl CollisionMap {
 /// ### drwob_essential
 /// 描画を行うときの情報
 ///
+/// ### animated_tiles
+/// SpriteBatchに積んだタイルのうち、アニメーションするもの。SpriteBatchを積み直すと作り直す
+///
+/// ### animation_cursor
+/// 次のフレームで、animated_tilesのどこからコマを進めるか
+///
+/// ### layout
+/// オブジェクトレイヤーから読んだ、主人公や客の出入りする位置
+///
+/// ### collision_overrides
+/// 家具を動かしたり戸を開けたりして、TMXから変わったタイルの衝突判定。trueなら通れない
+///
+/// ### collision_changes
+/// 通れるかどうかが変わったタイル。客のルートを計算し直すために取り出す
+///
+/// ### occupied_tiles
+/// 今、客や主人公が立っているタイル。経路探索はここをなるべく避ける
+///
 pub struct StageObjectMap {
     tile_map: tiled::Map,
     tilesets: Vec<TileSet>,
     tilesets_batchs: HashMap<u32, ggraphics::spritebatch::SpriteBatch>,
     collision_map: Option<CollisionMap>,
+    speed_overlay: HashMap<(u32, u32), f32>,
+    collision_overrides: HashMap<(u32, u32), bool>,
+    collision_changes: Vec<numeric::Vector2u>,
+    occupied_tiles: Vec<numeric::Vector2u>,
+    animated_tiles: Vec<AnimatedTileEntry>,
+    animation_cursor: usize,
+    layout: MapLayout,
     camera: Rc<RefCell<numeric::Rect>>,
     scale: numeric::Vector2f,
     redraw_request: bool,
@@ -358,10 +1112,10 @@ impl StageObjectMap {
         scale: numeric::Vector2f,
     ) -> StageObjectMap {
         // マップ情報を読み込む
-        println!("FIXME: map_parse.rs StageObjectMap::new");
+        log_debug!("FIXME: map_parse.rs StageObjectMap::new");
         //let file = ggez::filesystem::open(ctx, path).unwrap();
         //let tile_map = tiled::parse(file).unwrap();
-        let tile_map = tiled::parse_file(std::path::Path::new(path)).unwrap();
+        let tile_map = load_tmx(path).unwrap_or_else(|e| panic!("{}", e));
 
         // タイルセットを読み込み、それと同時にタイルセットの画像からSpriteBatchを生成する
         let mut batchs = HashMap::new();
@@ -381,11 +1135,24 @@ impl StageObjectMap {
         let mut canvas = SubScreen::new(ctx, canvas_rect, 0, ggraphics::Color::from_rgba_u32(0));
         canvas.set_filter(ggraphics::FilterMode::Nearest);
 
+        let layout = MapLayout::from_objects(
+            &Self::layout_objects(&tile_map),
+            numeric::Vector2u::new(tile_map.tile_width, tile_map.tile_height),
+            scale,
+        );
+
         StageObjectMap {
             tile_map: tile_map,
             tilesets: tilesets,
             tilesets_batchs: batchs,
             collision_map: None,
+            speed_overlay: HashMap::new(),
+            collision_overrides: HashMap::new(),
+            collision_changes: Vec::new(),
+            occupied_tiles: Vec::new(),
+            animated_tiles: Vec::new(),
+            animation_cursor: 0,
+            layout: layout,
             camera: camera,
             scale: scale,
             canvas: canvas,
@@ -394,6 +1161,29 @@ impl StageObjectMap {
         }
     }
 
+    /// オブジェクトレイヤーの全てのオブジェクトを、配置の目印として取り出す
+    fn layout_objects(tile_map: &tiled::Map) -> Vec<LayoutObject> {
+        tile_map
+            .object_groups
+            .iter()
+            .flat_map(|group| group.objects.iter())
+            .map(|object| LayoutObject {
+                kind: object.obj_type.clone(),
+                name: object.name.clone(),
+                x: object.x,
+                y: object.y,
+                order: match object.properties.get("order") {
+                    Some(tiled::PropertyValue::IntValue(order)) => *order,
+                    _ => 0,
+                },
+            })
+            .collect()
+    }
+
+    pub fn get_layout(&self) -> &MapLayout {
+        &self.layout
+    }
+
     /// 引数で受け取ったタイルの情報から、そのタイルがカメラに写るか調べるメソッド
     fn tile_is_inside_of_camera(&self, dest: numeric::Point2f, size: numeric::Vector2u) -> bool {
         let rect = numeric::Rect::new(
@@ -436,6 +1226,12 @@ impl StageObjectMap {
         ctx: &mut ggez::Context,
         chara: &MapObject,
     ) -> CollisionInformation {
+        // 後から通れなくしたタイルは、タイル全体を衝突範囲にする
+        let info = self.check_blocked_tile_collision(ctx, chara);
+        if info.collision {
+            return info;
+        }
+
         // 全てのレイヤーで衝突検査
         for layer in self.tile_map.layers.iter() {
             if !layer.visible {
@@ -459,10 +1255,7 @@ impl StageObjectMap {
                         continue;
                     }
 
-                    let tiles = match &layer.tiles {
-                        tiled::LayerData::Finite(tiles) => tiles,
-                        _ => panic!(""),
-                    };
+                    let tiles = finite_tiles(layer);
                     let tile = match tiles.get(y as usize) {
                         Some(row) => match row.get(x as usize) {
                             Some(tile) => tile,
@@ -477,6 +1270,11 @@ impl StageObjectMap {
                         continue;
                     }
 
+                    // 後から通れるようにしたタイルは、TMXの衝突判定を無視する
+                    if self.collision_overrides.get(&(x as u32, y as u32)) == Some(&false) {
+                        continue;
+                    }
+
                     let tileset = self.get_tileset_by_gid(gid).unwrap(); // 目的のタイルセットを取り出す
                     let tile_size = tileset.tile_size; // 利用するタイルセットのタイルサイズを取得
 
@@ -510,6 +1308,40 @@ impl StageObjectMap {
         CollisionInformation::new_not_collision()
     }
 
+    fn check_blocked_tile_collision(
+        &self,
+        ctx: &mut ggez::Context,
+        chara: &MapObject,
+    ) -> CollisionInformation {
+        let tileset = match self.tilesets.first() {
+            Some(tileset) => tileset,
+            None => return CollisionInformation::new_not_collision(),
+        };
+        let tile_size = self.get_tile_drawing_size();
+
+        for (&(x, y), _) in self
+            .collision_overrides
+            .iter()
+            .filter(|(_, blocked)| **blocked)
+        {
+            let dest_pos = self.camera_relative_position(numeric::Point2f::new(
+                x as f32 * tile_size.x,
+                y as f32 * tile_size.y,
+            ));
+            let tile_col = collision::Aabb2::new(
+                cgmath::Point2::<f32>::new(dest_pos.x, dest_pos.y),
+                cgmath::Point2::<f32>::new(dest_pos.x + tile_size.x, dest_pos.y + tile_size.y),
+            );
+
+            let info = tileset.__check_character_collision(ctx, &tile_col, chara);
+            if info.collision {
+                return info;
+            }
+        }
+
+        CollisionInformation::new_not_collision()
+    }
+
     pub fn get_tile_size(&self) -> numeric::Point2u {
         numeric::Point2u::new(self.tile_map.tile_width, self.tile_map.tile_height)
     }
@@ -564,7 +1396,7 @@ impl StageObjectMap {
     }
 
     /// sprite batch処理を実際に行うメソッド
-    fn update_sprite_batch(&mut self) {
+    fn update_sprite_batch(&mut self, t: Clock) {
         if !self.update_batch_request {
             return;
         }
@@ -573,6 +1405,8 @@ impl StageObjectMap {
 
         // batch処理を全てクリア
         self.clear_all_batchs();
+        self.animated_tiles.clear();
+        self.animation_cursor = 0;
 
         // 全てのレイヤーで描画を実行
         for layer in self.tile_map.layers.iter() {
@@ -581,10 +1415,7 @@ impl StageObjectMap {
                 continue;
             }
 
-            let tiles = match &layer.tiles {
-                tiled::LayerData::Finite(tiles) => tiles,
-                _ => panic!(""),
-            };
+            let tiles = finite_tiles(layer);
             // 二次元のマップデータを全てbatch処理に掛ける
             for (y, row) in tiles.iter().enumerate() {
                 for (x, &tile) in row.iter().enumerate() {
@@ -605,7 +1436,14 @@ impl StageObjectMap {
                         continue;
                     }
 
-                    let crop = tileset.gid_to_crop(gid); // クロップする部分をgidから計算
+                    // アニメーションするタイルは、今の時刻のコマを積む
+                    let animated = tileset.get_animation(gid).is_some();
+                    let current_gid = match tileset.get_animation(gid) {
+                        Some(animation) => tileset.get_first_gid() + animation.tile_id_at(t),
+                        None => gid,
+                    };
+
+                    let crop = tileset.gid_to_crop(current_gid); // クロップする部分をgidから計算
                     let first_gid = tileset.get_first_gid(); // batch処理を行うタイルセットのfirst_gidを取得
 
                     let batch = self.tilesets_batchs.get_mut(&first_gid).unwrap(); // sprite batchをfirst_gidから取得
@@ -615,9 +1453,77 @@ impl StageObjectMap {
                         .scale(mintv!(self.scale))
                         .dest(mintp!(dest_pos));
                     // batch処理を追加
-                    batch.add(draw_param);
+                    let sprite_index = batch.add(draw_param);
+
+                    if animated {
+                        self.animated_tiles.push(AnimatedTileEntry {
+                            first_gid: first_gid,
+                            sprite_index: sprite_index,
+                            base_gid: gid,
+                            current_gid: current_gid,
+                            dest: dest_pos,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    ///
+    /// アニメーションするタイルのコマを進める
+    /// 一フレームに差し替えるのはMAX_ANIMATED_TILE_UPDATES_PER_FRAME個までで、
+    /// 残りは次のフレームで続きから調べる
+    ///
+    fn advance_tile_animations(&mut self, t: Clock) {
+        if self.animated_tiles.is_empty() {
+            return;
+        }
+
+        let tile_count = self.animated_tiles.len();
+        let mut updated = 0;
+        let mut checked = 0;
+
+        while checked < tile_count && updated < MAX_ANIMATED_TILE_UPDATES_PER_FRAME {
+            let index = (self.animation_cursor + checked) % tile_count;
+            checked += 1;
+
+            let entry = &self.animated_tiles[index];
+            let tileset = match self.get_tileset_by_gid(entry.base_gid) {
+                Some(tileset) => tileset,
+                None => continue,
+            };
+            let next_gid = match tileset.get_animation(entry.base_gid) {
+                Some(animation) => tileset.get_first_gid() + animation.tile_id_at(t),
+                None => continue,
+            };
+
+            if next_gid == entry.current_gid {
+                continue;
+            }
+
+            let crop = tileset.gid_to_crop(next_gid);
+            let draw_param = ggraphics::DrawParam::default()
+                .src(numeric::Rect::new(crop.x, crop.y, crop.w, crop.h))
+                .scale(mintv!(self.scale))
+                .dest(mintp!(entry.dest));
+
+            let batch = self.tilesets_batchs.get_mut(&entry.first_gid).unwrap();
+            match batch.set(entry.sprite_index, draw_param) {
+                Ok(_) => (),
+                Err(e) => {
+                    log_warn!("failed to update animated tile: {}", e);
+                    continue;
                 }
             }
+
+            self.animated_tiles[index].current_gid = next_gid;
+            updated += 1;
+        }
+
+        self.animation_cursor = (self.animation_cursor + checked) % tile_count;
+
+        if updated > 0 {
+            self.redraw_request = true;
         }
     }
 
@@ -625,16 +1531,13 @@ impl StageObjectMap {
         let mut collision_locations = Vec::new();
 
         for layer in self.tile_map.layers.iter() {
-            println!("name -> {}", layer.name);
+            log_trace!("name -> {}", layer.name);
             if !layer.visible {
                 // レイヤーが非表示設定になっていれば、衝突オブジェクトの検索を行わない
                 continue;
             }
 
-            let tiles = match &layer.tiles {
-                tiled::LayerData::Finite(tiles) => tiles,
-                _ => panic!(""),
-            };
+            let tiles = finite_tiles(layer);
             // 二次元のマップデータを全てbatch処理に掛ける
             for (y, row) in tiles.iter().enumerate() {
                 for (x, &tile) in row.iter().enumerate() {
@@ -655,12 +1558,200 @@ impl StageObjectMap {
         collision_locations
     }
 
+    ///
+    /// タイルの地形を返す。上のレイヤーに地形の指定があれば、そちらを優先する
+    ///
+    pub fn get_terrain(&self, tile_position: numeric::Vector2u) -> TerrainType {
+        let mut terrain = TerrainType::Tatami;
+
+        for layer in self.tile_map.layers.iter() {
+            if !layer.visible {
+                continue;
+            }
+
+            let tiles = finite_tiles(layer);
+
+            let gid = match tiles
+                .get(tile_position.y as usize)
+                .and_then(|row| row.get(tile_position.x as usize))
+            {
+                Some(tile) => tile.gid,
+                None => continue,
+            };
+
+            // gidが0のときは、何も配置されていない
+            if gid == 0 {
+                continue;
+            }
+
+            if let Some(tileset) = self.get_tileset_by_gid(gid) {
+                if let Some(t) = tileset.get_terrain(gid) {
+                    terrain = t;
+                }
+            }
+        }
+
+        terrain
+    }
+
+    ///
+    /// マップ上の位置での歩く速さの倍率
+    ///
+    pub fn terrain_speed_rate(&self, map_position: numeric::Point2f) -> f32 {
+        match self.map_position_to_tile_position(map_position) {
+            Some(tile_position) => {
+                let overlay = self
+                    .speed_overlay
+                    .get(&(tile_position.x, tile_position.y))
+                    .copied()
+                    .unwrap_or(1.0);
+                self.get_terrain(tile_position).speed_rate() * overlay
+            }
+            None => 1.0,
+        }
+    }
+
+    ///
+    /// 水溜まりなど、地形の上に一時的に置かれたものによる歩く速さの倍率を設定する
+    /// Noneを渡すと元に戻る
+    ///
+    pub fn set_tile_speed_overlay(&mut self, tile_position: numeric::Vector2u, rate: Option<f32>) {
+        let key = (tile_position.x, tile_position.y);
+        match rate {
+            Some(rate) => {
+                self.speed_overlay.insert(key, rate);
+            }
+            None => {
+                self.speed_overlay.remove(&key);
+            }
+        }
+    }
+
+    ///
+    /// TMXのタイルに衝突判定があるか
+    ///
+    fn has_tmx_collision(&self, tile_position: numeric::Vector2u) -> bool {
+        self.tile_map
+            .layers
+            .iter()
+            .filter(|layer| layer.visible)
+            .any(|layer| {
+                let tiles = finite_tiles(layer);
+
+                match tiles
+                    .get(tile_position.y as usize)
+                    .and_then(|row| row.get(tile_position.x as usize))
+                {
+                    Some(tile) => tile.gid != 0 && self.is_collisionable_tile(tile.gid),
+                    None => false,
+                }
+            })
+    }
+
+    ///
+    /// タイルが通れないか。後から変えた衝突判定があれば、そちらを優先する
+    ///
+    pub fn is_tile_blocked(&self, tile_position: numeric::Vector2u) -> bool {
+        match self
+            .collision_overrides
+            .get(&(tile_position.x, tile_position.y))
+        {
+            Some(blocked) => *blocked,
+            None => self.has_tmx_collision(tile_position),
+        }
+    }
+
+    ///
+    /// タイルの衝突判定を上書きする。Noneを渡すとTMXの通りに戻る
+    /// 通れるかどうかが変わったときは経路探索用のマップも直し、trueを返す
+    ///
+    pub fn set_tile_collision(
+        &mut self,
+        tile_position: numeric::Vector2u,
+        blocked: Option<bool>,
+    ) -> bool {
+        let before = self.is_tile_blocked(tile_position);
+
+        let key = (tile_position.x, tile_position.y);
+        match blocked {
+            Some(blocked) => {
+                self.collision_overrides.insert(key, blocked);
+            }
+            None => {
+                self.collision_overrides.remove(&key);
+            }
+        }
+
+        let after = self.is_tile_blocked(tile_position);
+        if before == after {
+            return false;
+        }
+
+        if let Some(collision_map) = self.collision_map.as_mut() {
+            collision_map.set_blocked(tile_position, after);
+        }
+        self.collision_changes.push(tile_position);
+
+        true
+    }
+
+    ///
+    /// top_leftからsize分の範囲のタイルの衝突判定をまとめて上書きする
+    /// 通れるかどうかが変わったタイルの数を返す
+    ///
+    pub fn set_region_collision(
+        &mut self,
+        top_left: numeric::Vector2u,
+        size: numeric::Vector2u,
+        blocked: Option<bool>,
+    ) -> usize {
+        let mut changed = 0;
+
+        for x in top_left.x..(top_left.x + size.x) {
+            for y in top_left.y..(top_left.y + size.y) {
+                if self.set_tile_collision(numeric::Vector2u::new(x, y), blocked) {
+                    changed += 1;
+                }
+            }
+        }
+
+        changed
+    }
+
+    ///
+    /// 前に取り出してから、通れるかどうかが変わったタイルを取り出す
+    ///
+    pub fn take_collision_changes(&mut self) -> Vec<numeric::Vector2u> {
+        std::mem::replace(&mut self.collision_changes, Vec::new())
+    }
+
+    fn build_terrain_costs(&self) -> Vec<Vec<usize>> {
+        (0..self.tile_map.width)
+            .map(|x| {
+                (0..self.tile_map.height)
+                    .map(|y| self.get_terrain(numeric::Vector2u::new(x, y)).path_cost())
+                    .collect()
+            })
+            .collect()
+    }
+
     pub fn build_collision_map(&mut self) {
-        let collision_points = self.search_collision_locations();
+        let mut collision_points = self.search_collision_locations();
+        let terrain_costs = self.build_terrain_costs();
+
+        // 後から変えた衝突判定は、作り直しても残す
+        for (&(x, y), &blocked) in self.collision_overrides.iter() {
+            let position = numeric::Vector2u::new(x, y);
+            collision_points.retain(|point| *point != position);
+            if blocked {
+                collision_points.push(position);
+            }
+        }
 
         self.collision_map = Some(CollisionMap::new(
             numeric::Vector2u::new(self.tile_map.width, self.tile_map.height),
             collision_points.clone(),
+            terrain_costs,
         ));
     }
 
@@ -688,15 +1779,30 @@ impl StageObjectMap {
         )
     }
 
+    ///
+    /// 誰かが立っているタイルを差し替える。毎フレーム、客を動かす前に渡す
+    ///
+    pub fn set_occupied_tiles(&mut self, tiles: Vec<numeric::Vector2u>) {
+        self.occupied_tiles = tiles;
+    }
+
+    pub fn is_tile_occupied(&self, tile_position: numeric::Vector2u) -> bool {
+        self.occupied_tiles.contains(&tile_position)
+    }
+
+    ///
+    /// 通れないタイルを通らず、誰かが立っているタイルはなるべく避けるルートを探す
+    ///
     pub fn find_shortest_route(
         &self,
         start: numeric::Vector2u,
         goal: numeric::Vector2u,
     ) -> Option<Vec<numeric::Vector2u>> {
         if let Some(collision_map) = self.collision_map.as_ref() {
-            if let Some(path) = collision_map.find_path(
+            if let Some(path) = collision_map.find_path_avoiding(
                 numeric::Point2i::new(start.x as i32, start.y as i32),
                 numeric::Point2i::new(goal.x as i32, goal.y as i32),
+                &self.occupied_tiles,
             ) {
                 Some(
                     path.iter()
@@ -711,6 +1817,17 @@ impl StageObjectMap {
         }
     }
 
+    ///
+    /// fromに近い、通れるタイルを探す
+    ///
+    pub fn nearest_open_tile(&self, from: numeric::Vector2u) -> Option<numeric::Vector2u> {
+        customer_watchdog::nearest_open_tile(
+            from,
+            numeric::Vector2u::new(self.tile_map.width, self.tile_map.height),
+            |tile| self.is_tile_blocked(tile),
+        )
+    }
+
     pub fn get_map_size(&self) -> numeric::Vector2f {
         let tile_size = self.get_tile_drawing_size();
 
@@ -771,8 +1888,9 @@ impl DrawableComponent for StageObjectMap {
 impl DrawableObject for StageObjectMap {}
 
 impl Updatable for StageObjectMap {
-    fn update(&mut self, _ctx: &mut ggez::Context, _t: Clock) {
-        self.update_sprite_batch();
+    fn update(&mut self, _ctx: &mut ggez::Context, t: Clock) {
+        self.update_sprite_batch(t);
+        self.advance_tile_animations(t);
     }
 }
 
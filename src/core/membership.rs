@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::GensoDate;
+
+///
+/// 会員証の有効日数。更新すると、その日からこの日数だけ延びる
+///
+pub const MEMBERSHIP_VALID_DAYS: i32 = 60;
+
+///
+/// 会員証の更新料
+///
+pub const MEMBERSHIP_RENEWAL_FEE: i32 = 100;
+
+///
+/// 会員証を確かめ損ねたときに引く点
+///
+pub const MEMBERSHIP_MISTAKE_PENALTY: i32 = 8;
+
+///
+/// # 会員証を見た結果
+///
+/// ## OverLimit
+/// 今回借りる分を足すと貸出上限を超える
+///
+/// ## Expired
+/// 有効期限が切れている。期限切れは上限より先に見る
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CardCheck {
+    Valid,
+    OverLimit,
+    Expired,
+}
+
+///
+/// # 客の会員証
+///
+/// ## limit
+/// 一度に借りていられる本の数
+///
+/// ## lending
+/// 今借りている本の数
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MembershipCard {
+    pub holder: String,
+    pub limit: usize,
+    pub lending: usize,
+    pub expiry: GensoDate,
+}
+
+impl MembershipCard {
+    pub fn new(holder: &str, limit: usize, lending: usize, expiry: GensoDate) -> Self {
+        MembershipCard {
+            holder: holder.to_string(),
+            limit: limit,
+            lending: lending,
+            expiry: expiry,
+        }
+    }
+
+    pub fn is_expired(&self, today: &GensoDate) -> bool {
+        today.is_past(&self.expiry)
+    }
+
+    ///
+    /// requested冊を貸してよいか見る
+    ///
+    pub fn check(&self, requested: usize, today: &GensoDate) -> CardCheck {
+        if self.is_expired(today) {
+            CardCheck::Expired
+        } else if self.lending + requested > self.limit {
+            CardCheck::OverLimit
+        } else {
+            CardCheck::Valid
+        }
+    }
+
+    ///
+    /// 有効期限をtodayからMEMBERSHIP_VALID_DAYS日後にする
+    ///
+    pub fn renew(&mut self, today: &GensoDate) {
+        self.expiry = today.add_days_chain(MEMBERSHIP_VALID_DAYS);
+    }
+}
+
+///
+/// # 会員名簿
+/// 客の名前ごとに会員証を持つ。初めて来た客には、その場で会員証を作る
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MembershipRegistry {
+    cards: HashMap<String, MembershipCard>,
+}
+
+impl MembershipRegistry {
+    pub fn new() -> Self {
+        MembershipRegistry {
+            cards: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, holder: &str) -> Option<&MembershipCard> {
+        self.cards.get(holder)
+    }
+
+    ///
+    /// 名簿に無い客なら、issueで作った会員証を載せる
+    ///
+    pub fn get_or_issue<F>(&mut self, holder: &str, issue: F) -> &MembershipCard
+    where
+        F: FnOnce() -> MembershipCard,
+    {
+        self.cards.entry(holder.to_string()).or_insert_with(issue)
+    }
+
+    pub fn lend(&mut self, holder: &str, count: usize) {
+        if let Some(card) = self.cards.get_mut(holder) {
+            card.lending += count;
+        }
+    }
+
+    ///
+    /// 本が返ってきたときに呼ぶ。名簿に無い客なら何もしない
+    ///
+    pub fn give_back(&mut self, holder: &str, count: usize) {
+        if let Some(card) = self.cards.get_mut(holder) {
+            card.lending = card.lending.saturating_sub(count);
+        }
+    }
+
+    ///
+    /// 会員証を更新する。更新した会員証を返す
+    ///
+    pub fn renew(&mut self, holder: &str, today: &GensoDate) -> Option<MembershipCard> {
+        let card = self.cards.get_mut(holder)?;
+        card.renew(today);
+        Some(card.clone())
+    }
+}
@@ -0,0 +1,216 @@
+use super::{BookInformation, GensoDate};
+
+///
+/// メモ一枚に書ける文字数
+///
+pub const MEMO_MAX_CHARS: usize = 60;
+
+///
+/// 今日を基準に読む日付の言葉。長いものから照らし合わせる
+///
+const RELATIVE_DAYS: &[(&str, i32)] = &[("明後日", 2), ("明日", 1), ("今日", 0), ("昨日", -1)];
+
+///
+/// # メモから拾い上げた語
+/// 机の上では、記憶できる札として並べる
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoChip {
+    Date(GensoDate),
+    Book(BookInformation),
+    Customer(String),
+}
+
+fn digit_value(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => c.to_digit(10),
+        '０'..='９' => Some(c as u32 - '０' as u32),
+        _ => None,
+    }
+}
+
+fn kanji_digit_value(c: char) -> Option<u32> {
+    "〇一二三四五六七八九"
+        .chars()
+        .position(|k| k == c)
+        .map(|value| value as u32)
+}
+
+///
+/// startから数を読む。算用数字と漢数字（三十一まで）を読み、値と読んだ文字数を返す
+///
+fn read_number(chars: &[char], start: usize) -> Option<(u32, usize)> {
+    let arabic_len = chars[start..]
+        .iter()
+        .take_while(|c| digit_value(**c).is_some())
+        .count();
+    if arabic_len > 0 {
+        let value = chars[start..start + arabic_len]
+            .iter()
+            .fold(0u32, |value, c| {
+                value
+                    .saturating_mul(10)
+                    .saturating_add(digit_value(*c).unwrap())
+            });
+        return Some((value, arabic_len));
+    }
+
+    let mut tens = 0;
+    let mut ones = 0;
+    let mut len = 0;
+    for c in chars[start..].iter() {
+        if *c == '十' {
+            tens = ones.max(1) * 10;
+            ones = 0;
+        } else if let Some(value) = kanji_digit_value(*c) {
+            ones = value;
+        } else {
+            break;
+        }
+        len += 1;
+    }
+
+    if len == 0 {
+        None
+    } else {
+        Some((tens + ones, len))
+    }
+}
+
+fn starts_with(chars: &[char], start: usize, word: &[char]) -> bool {
+    !word.is_empty() && chars[start..].starts_with(word)
+}
+
+///
+/// startから日付を読む。読めた日付と、読んだ文字数を返す
+///
+fn read_date(chars: &[char], start: usize, today: &GensoDate) -> Option<(GensoDate, usize)> {
+    for (word, offset) in RELATIVE_DAYS.iter() {
+        let word: Vec<char> = word.chars().collect();
+        if starts_with(chars, start, &word) {
+            let date = if *offset < 0 {
+                today.sub_days_chain(-offset)
+            } else {
+                today.add_days_chain(*offset)
+            };
+            return Some((date, word.len()));
+        }
+    }
+
+    let (first, first_len) = read_number(chars, start)?;
+    let mut pos = start + first_len;
+
+    match chars.get(pos) {
+        Some('月') | Some('/') | Some('／') => {
+            let separator = chars[pos];
+            pos += 1;
+
+            let (day, day_len) = read_number(chars, pos)?;
+            pos += day_len;
+
+            if chars.get(pos) == Some(&'日') {
+                pos += 1;
+            } else if separator == '月' {
+                return None;
+            }
+
+            if !(1..=12).contains(&first)
+                || !(1..=GensoDate::days_in_month(first as u8) as u32).contains(&day)
+            {
+                return None;
+            }
+
+            Some((
+                GensoDate::new(today.season, first as u8, day as u8),
+                pos - start,
+            ))
+        }
+        Some('日') if chars.get(pos + 1) == Some(&'後') => {
+            if !(1..=31).contains(&first) {
+                return None;
+            }
+
+            Some((today.add_days_chain(first as i32), first_len + 2))
+        }
+        _ => None,
+    }
+}
+
+///
+/// 文字列を丸ごと一つの日付として読む。余計な文字が残れば、None
+///
+pub fn parse_date(text: &str, today: &GensoDate) -> Option<GensoDate> {
+    let chars: Vec<char> = text.trim().chars().collect();
+    if chars.is_empty() {
+        return None;
+    }
+
+    match read_date(&chars, 0, today) {
+        Some((date, len)) if len == chars.len() => Some(date),
+        _ => None,
+    }
+}
+
+///
+/// # 走り書きのメモを読む
+/// 日付と、知っている本の題名や客の名前を拾い上げる
+///
+/// ## words
+/// 照らし合わせる題名と名前。長いものから並べておき、最も長く一致したものを採る
+///
+pub struct MemoParser {
+    words: Vec<(Vec<char>, MemoChip)>,
+}
+
+impl MemoParser {
+    pub fn new(books: Vec<BookInformation>, customers: Vec<String>) -> Self {
+        let mut words: Vec<(Vec<char>, MemoChip)> = books
+            .into_iter()
+            .map(|book| (book.name.chars().collect(), MemoChip::Book(book)))
+            .chain(
+                customers
+                    .into_iter()
+                    .map(|name| (name.chars().collect(), MemoChip::Customer(name))),
+            )
+            .collect();
+
+        words.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+        MemoParser { words: words }
+    }
+
+    fn read_word(&self, chars: &[char], start: usize) -> Option<(MemoChip, usize)> {
+        self.words
+            .iter()
+            .find(|(word, _)| starts_with(chars, start, word))
+            .map(|(word, chip)| (chip.clone(), word.len()))
+    }
+
+    ///
+    /// メモを頭から読み、拾い上げた語を書かれた順に返す。同じ語は一度だけ返す
+    ///
+    pub fn parse(&self, text: &str, today: &GensoDate) -> Vec<MemoChip> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut chips = Vec::new();
+        let mut pos = 0;
+
+        while pos < chars.len() {
+            let found = self.read_word(&chars, pos).or_else(|| {
+                read_date(&chars, pos, today).map(|(date, len)| (MemoChip::Date(date), len))
+            });
+
+            match found {
+                Some((chip, len)) => {
+                    if !chips.contains(&chip) {
+                        chips.push(chip);
+                    }
+                    pos += len;
+                }
+                // 日付にならなかった数は、途中から読み直さないよう丸ごと飛ばす
+                None => pos += read_number(&chars, pos).map_or(1, |(_, len)| len),
+            }
+        }
+
+        chips
+    }
+}
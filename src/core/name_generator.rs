@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+
+///
+/// 縦書きの背表紙に収まる題名の文字数
+///
+pub const MAX_TITLE_CHARS: usize = 8;
+
+///
+/// 縦書きの名札に収まる名前の文字数
+///
+pub const MAX_NAME_CHARS: usize = 5;
+
+///
+/// 題名どうしで、少なくともこれだけの文字が違っていなければならない
+///
+pub const MIN_DIFFERING_CHARS: usize = 2;
+
+///
+/// 条件に合う語が出来るまで組み直す回数の上限
+///
+pub const MAX_GENERATION_ATTEMPTS: usize = 64;
+
+///
+/// # 名前と題名の語の蔵
+/// tomlから読み、語を組み合わせて客の名前と本の題名を作る
+///
+/// ## name_heads, name_tails
+/// 名前の頭と尾。頭に尾を一つか二つ付けて名前にする
+///
+/// ## title_heads, title_subjects, title_suffixes
+/// 題名の頭、主題、添え字。頭と主題を並べ、時々添え字を付ける
+///
+/// ## readings
+/// 題名に使う語の読み。作った題名の振り仮名になる
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct NameBank {
+    pub name_heads: Vec<String>,
+    pub name_tails: Vec<String>,
+    pub title_heads: Vec<String>,
+    pub title_subjects: Vec<String>,
+    #[serde(default)]
+    pub title_suffixes: Vec<String>,
+    #[serde(default)]
+    pub readings: HashMap<String, String>,
+}
+
+impl NameBank {
+    pub fn from_toml_str(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    ///
+    /// 題名を頭から、読みの分かる一番長い語に切り分けて読みを繋ぐ。読めない所があればNone
+    ///
+    pub fn reading_of(&self, title: &str) -> Option<String> {
+        let mut reading = String::new();
+        let mut rest = title;
+
+        while !rest.is_empty() {
+            let (word, word_reading) = self
+                .readings
+                .iter()
+                .filter(|(word, _)| !word.is_empty() && rest.starts_with(word.as_str()))
+                .max_by_key(|(word, _)| word.len())?;
+
+            reading.push_str(word_reading);
+            rest = &rest[word.len()..];
+        }
+
+        if reading.is_empty() {
+            None
+        } else {
+            Some(reading)
+        }
+    }
+}
+
+///
+/// 縦書きで崩れない文字か。かな、漢字、々だけを許す
+///
+/// 長音符や英数字は縦書きで向きが変わるので使わない
+///
+pub fn is_vertical_char(c: char) -> bool {
+    match c {
+        '\u{3041}'..='\u{3096}' | '\u{30a1}'..='\u{30fa}' | '\u{4e00}'..='\u{9fff}' | '々' => true,
+        _ => false,
+    }
+}
+
+pub fn is_vertical_safe(s: &str, max_chars: usize) -> bool {
+    let len = s.chars().count();
+    len > 0 && len <= max_chars && s.chars().all(is_vertical_char)
+}
+
+///
+/// 同じ位置で違う文字の数に、長さの差を足したもの
+///
+pub fn differing_chars(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let differ = a.iter().zip(b.iter()).filter(|(x, y)| x != y).count();
+
+    differ + (a.len() as isize - b.len() as isize).abs() as usize
+}
+
+///
+/// 片方がもう片方の頭になっていると、メモを読むときに短い方と取り違える
+///
+fn overlaps(a: &str, b: &str) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+///
+/// # 客の名前と本の題名を作る
+/// 作った語は覚えておき、既にある語と紛らわしいものは作り直す
+///
+pub struct NameGenerator {
+    bank: NameBank,
+    titles: Vec<String>,
+    names: Vec<String>,
+}
+
+impl NameGenerator {
+    ///
+    /// 既にある題名と名前を渡す。これらと紛らわしい語は作らない
+    ///
+    pub fn new(bank: NameBank, titles: Vec<String>, names: Vec<String>) -> Self {
+        NameGenerator {
+            bank: bank,
+            titles: titles,
+            names: names,
+        }
+    }
+
+    fn compose_name<R: Rng>(&self, rng: &mut R) -> Option<String> {
+        let mut name = self.bank.name_heads.choose(rng)?.clone();
+        name.push_str(self.bank.name_tails.choose(rng)?);
+        if rng.gen_range(0, 3) == 0 {
+            name.push_str(self.bank.name_tails.choose(rng)?);
+        }
+
+        Some(name)
+    }
+
+    fn compose_title<R: Rng>(&self, rng: &mut R) -> Option<String> {
+        let mut title = self.bank.title_heads.choose(rng)?.clone();
+        title.push_str(self.bank.title_subjects.choose(rng)?);
+        if rng.gen::<bool>() {
+            if let Some(suffix) = self.bank.title_suffixes.choose(rng) {
+                title.push_str(suffix);
+            }
+        }
+
+        Some(title)
+    }
+
+    pub fn is_acceptable_name(&self, name: &str) -> bool {
+        is_vertical_safe(name, MAX_NAME_CHARS)
+            && !self
+                .names
+                .iter()
+                .chain(self.titles.iter())
+                .any(|known| overlaps(name, known))
+    }
+
+    pub fn is_acceptable_title(&self, title: &str) -> bool {
+        is_vertical_safe(title, MAX_TITLE_CHARS)
+            && !self.names.iter().any(|known| overlaps(title, known))
+            && self.titles.iter().all(|known| {
+                !overlaps(title, known) && differing_chars(title, known) >= MIN_DIFFERING_CHARS
+            })
+    }
+
+    ///
+    /// 新しい客の名前を作る。語の組み合わせが尽きて作れなければNone
+    ///
+    pub fn customer_name<R: Rng>(&mut self, rng: &mut R) -> Option<String> {
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let name = self.compose_name(rng)?;
+            if self.is_acceptable_name(&name) {
+                self.names.push(name.clone());
+                return Some(name);
+            }
+        }
+
+        None
+    }
+
+    ///
+    /// 作った題名の読み。語の蔵に読みが無ければNone
+    ///
+    pub fn title_reading(&self, title: &str) -> Option<String> {
+        self.bank.reading_of(title)
+    }
+
+    ///
+    /// 新しい本の題名を作る。語の組み合わせが尽きて作れなければNone
+    ///
+    pub fn book_title<R: Rng>(&mut self, rng: &mut R) -> Option<String> {
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let title = self.compose_title(rng)?;
+            if self.is_acceptable_title(&title) {
+                self.titles.push(title.clone());
+                return Some(title);
+            }
+        }
+
+        None
+    }
+}
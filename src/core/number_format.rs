@@ -0,0 +1,96 @@
+use number_to_jk::number_to_jk;
+
+use super::preferences::Locale;
+use super::GensoDate;
+
+///
+/// # 数字の書き方
+/// 暦や書類、表に数字を出すときは、ここを通して書く
+///
+/// ## locale
+/// 日本語なら漢数字、それ以外ならアラビア数字で書く
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    locale: Locale,
+}
+
+impl NumberFormat {
+    pub fn new(locale: Locale) -> Self {
+        NumberFormat { locale: locale }
+    }
+
+    ///
+    /// 言語設定を見られない場所で使う漢数字の書き方
+    ///
+    pub fn japanese() -> Self {
+        Self::new(Locale::Japanese)
+    }
+
+    pub fn get_locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn number(&self, n: u64) -> String {
+        match self.locale {
+            Locale::Japanese => number_to_jk(n),
+            Locale::English => n.to_string(),
+        }
+    }
+
+    ///
+    /// 「三冊」「五人」のように単位を付けて書く
+    ///
+    pub fn count(&self, n: u64, unit: &str) -> String {
+        format!("{}{}", self.number(n), unit)
+    }
+
+    pub fn money(&self, n: u64) -> String {
+        self.count(n, "円")
+    }
+
+    ///
+    /// 一から数える順番
+    ///
+    pub fn ordinal(&self, index: usize) -> String {
+        self.number(index as u64 + 1)
+    }
+
+    ///
+    /// 季は「第三季」のように書く
+    ///
+    pub fn season(&self, season: u32) -> String {
+        match self.locale {
+            Locale::Japanese => format!("第{}季", self.number(season as u64)),
+            Locale::English => format!("Season {}", season),
+        }
+    }
+
+    pub fn date(&self, date: &GensoDate) -> String {
+        match self.locale {
+            Locale::Japanese => format!(
+                "{} {}月 {}日",
+                self.season(date.season),
+                self.number(date.month as u64),
+                self.number(date.day as u64)
+            ),
+            Locale::English => format!(
+                "{}, {} {}",
+                self.season(date.season),
+                date.to_month_string_eng_short(),
+                date.day
+            ),
+        }
+    }
+
+    pub fn short_date(&self, date: &GensoDate) -> String {
+        match self.locale {
+            Locale::Japanese => format!(
+                "{}月{}日",
+                self.number(date.month as u64),
+                self.number(date.day as u64)
+            ),
+            Locale::English => format!("{} {}", date.to_month_string_eng_short(), date.day),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use super::mail::{Letter, LetterContent};
+use super::GensoDate;
+
+///
+/// 書類箱の控えに残しておく通数。越えたら古いものから捨てる
+///
+pub const PAPER_ARCHIVE_MAX_ENTRIES: usize = 200;
+
+///
+/// # 書類箱に仕舞った手紙
+///
+/// ## date
+/// 仕舞った日
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedLetter {
+    pub date: GensoDate,
+    pub letter: Letter,
+}
+
+impl ArchivedLetter {
+    ///
+    /// 控えに書く一行。差出人と用件だけ
+    ///
+    pub fn summary_text(&self) -> String {
+        match &self.letter.content {
+            LetterContent::CopyRequest { title, pages } => format!(
+                "{}ヨリ「{}」{}頁ノ写本依頼",
+                self.letter.sender, title, pages
+            ),
+            LetterContent::ThankYou { .. } => format!("{}ヨリ礼状", self.letter.sender),
+            LetterContent::StoryHook { .. } => format!("{}ヨリ便リ", self.letter.sender),
+        }
+    }
+}
+
+///
+/// # 書類箱
+/// 机の上で読み終えた手紙を仕舞い、仕舞った日ごとに控えておく
+/// 控えは店のメニューから読み返せる
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaperArchive {
+    entries: Vec<ArchivedLetter>,
+}
+
+impl PaperArchive {
+    pub fn new() -> Self {
+        PaperArchive {
+            entries: Vec::new(),
+        }
+    }
+
+    ///
+    /// 手紙を仕舞う。同じ手紙を二度仕舞った場合は何もしない
+    ///
+    pub fn archive(&mut self, letter: Letter, today: &GensoDate) {
+        if self.contains(letter.id) {
+            return;
+        }
+
+        self.entries.push(ArchivedLetter {
+            date: *today,
+            letter: letter,
+        });
+
+        if self.entries.len() > PAPER_ARCHIVE_MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn contains(&self, letter_id: u32) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.letter.id == letter_id)
+    }
+
+    ///
+    /// dateに仕舞った手紙
+    ///
+    pub fn iter_day<'a>(&'a self, date: &'a GensoDate) -> impl Iterator<Item = &'a ArchivedLetter> {
+        self.entries.iter().filter(move |entry| &entry.date == date)
+    }
+
+    pub fn count_on(&self, date: &GensoDate) -> usize {
+        self.iter_day(date).count()
+    }
+
+    ///
+    /// 新しく仕舞ったものから順に返す
+    ///
+    pub fn iter_recent(&self) -> impl Iterator<Item = &ArchivedLetter> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
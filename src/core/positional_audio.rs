@@ -0,0 +1,108 @@
+use torifune::numeric;
+use torifune::sound;
+
+///
+/// カメラの中心からこれ以上離れた音は聞こえない
+///
+pub const AUDIBLE_DISTANCE: f32 = 1000.0;
+
+///
+/// カメラの中心から横にこれだけ離れると、完全に片側から聞こえる
+///
+pub const PAN_WIDTH: f32 = 683.0;
+
+pub fn camera_center(camera: &numeric::Rect) -> numeric::Point2f {
+    numeric::Point2f::new(camera.x + (camera.w / 2.0), camera.y + (camera.h / 2.0))
+}
+
+///
+/// 距離による音量の倍率。近いほど1.0に近く、AUDIBLE_DISTANCE以上は0.0
+///
+pub fn attenuation(source: numeric::Point2f, listener: numeric::Point2f) -> f32 {
+    let distance = ((source.x - listener.x).powi(2) + (source.y - listener.y).powi(2)).sqrt();
+
+    if distance >= AUDIBLE_DISTANCE {
+        0.0
+    } else {
+        let ratio = 1.0 - (distance / AUDIBLE_DISTANCE);
+        ratio * ratio
+    }
+}
+
+///
+/// 左右の振り分け。-1.0で左、1.0で右
+///
+pub fn pan(source: numeric::Point2f, listener: numeric::Point2f) -> f32 {
+    ((source.x - listener.x) / PAN_WIDTH).max(-1.0).min(1.0)
+}
+
+struct PositionalSound {
+    handler: sound::SoundHandler,
+    position: numeric::Point2f,
+    base_volume: f32,
+}
+
+///
+/// # マップ上の位置を持つ効果音
+/// カメラが動くたびに、鳴っている音の音量と左右の振り分けを計算し直す
+///
+/// ## listener
+/// 音を聞く位置。カメラの中心
+///
+pub struct PositionalAudio {
+    sounds: Vec<PositionalSound>,
+    listener: numeric::Point2f,
+}
+
+impl PositionalAudio {
+    pub fn new() -> Self {
+        PositionalAudio {
+            sounds: Vec::new(),
+            listener: numeric::Point2f::new(0.0, 0.0),
+        }
+    }
+
+    pub fn set_listener(&mut self, camera: &numeric::Rect) {
+        self.listener = camera_center(camera);
+    }
+
+    pub fn get_listener(&self) -> numeric::Point2f {
+        self.listener
+    }
+
+    pub fn volume_at(&self, position: numeric::Point2f, base_volume: f32) -> f32 {
+        base_volume * attenuation(position, self.listener)
+    }
+
+    pub fn add(
+        &mut self,
+        handler: sound::SoundHandler,
+        position: numeric::Point2f,
+        base_volume: f32,
+    ) {
+        self.sounds.push(PositionalSound {
+            handler: handler,
+            position: position,
+            base_volume: base_volume,
+        });
+    }
+
+    ///
+    /// 音ごとにfを呼ぶ。引数は(handler, 音量, 左右の振り分け)
+    /// fがfalseを返した音は鳴り終わったものとして捨てる
+    ///
+    pub fn update<F>(&mut self, mut f: F)
+    where
+        F: FnMut(sound::SoundHandler, f32, f32) -> bool,
+    {
+        let listener = self.listener;
+
+        self.sounds.retain(|sound| {
+            f(
+                sound.handler.clone(),
+                sound.base_volume * attenuation(sound.position, listener),
+                pan(sound.position, listener),
+            )
+        });
+    }
+}
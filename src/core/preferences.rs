@@ -0,0 +1,725 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use ggez::input::gamepad::gilrs::Button;
+use ggez::input::keyboard::KeyCode;
+use serde::{Deserialize, Serialize};
+use torifune::device as tdev;
+use torifune::numeric;
+
+use super::gamepad::{
+    button_from_str, default_gamepad_action, gamepad_action_from_str, GamepadAction,
+};
+use super::quicksave::AutosaveFrequency;
+use super::time_scale::TimeScale;
+use super::{GameConfig, WINDOW_SIZE_X, WINDOW_SIZE_Y};
+use crate::log_warn;
+
+const PREFERENCES_DIR_NAME: &str = "suzu";
+const PREFERENCES_FILE_NAME: &str = "preferences.toml";
+
+pub const UI_SCALE_MIN: f32 = 0.75;
+pub const UI_SCALE_MAX: f32 = 1.5;
+
+///
+/// 窓をこれより小さくは戻さない
+///
+pub const WINDOW_MIN_WIDTH: f32 = 683.0;
+pub const WINDOW_MIN_HEIGHT: f32 = 384.0;
+
+///
+/// 台詞や日記の中で、この印を呼び名に置き換える
+///
+pub const NICKNAME_PLACEHOLDER: &str = "{nickname}";
+
+///
+/// 呼び名が決まっていないときの呼び名
+///
+pub const DEFAULT_NICKNAME: &str = "小鈴";
+
+pub const NICKNAME_MAX_CHARS: usize = 8;
+
+///
+/// # 文字を並べる向き
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextOrientation {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    Japanese,
+    English,
+}
+
+///
+/// # 操作の型
+///
+/// ## Mouse
+/// マウスを主に使う。キーの割り当ては標準のまま
+///
+/// ## Keyboard
+/// キーボードを主に使う。Z、Enterで決定、Xで取り消しなど、手元のキーで進められるよう割り当てる
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ControlScheme {
+    Mouse,
+    Keyboard,
+}
+
+impl ControlScheme {
+    ///
+    /// この型で使うキーの割り当て。物理キー名と仮想キー名の組
+    ///
+    pub fn key_bindings(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ControlScheme::Mouse => &[],
+            ControlScheme::Keyboard => &[
+                ("Z", "Action1"),
+                ("Return", "Action1"),
+                ("X", "Action2"),
+                ("C", "Action3"),
+                ("V", "Action4"),
+            ],
+        }
+    }
+}
+
+impl Locale {
+    ///
+    /// 縦書きに馴染みの無い言語では横書きで表示する
+    ///
+    pub fn text_orientation(&self) -> TextOrientation {
+        match self {
+            Locale::Japanese => TextOrientation::Vertical,
+            Locale::English => TextOrientation::Horizontal,
+        }
+    }
+}
+
+///
+/// dirsクレートのconfig_dirと同じ場所を返す
+///
+/// Linux: $XDG_CONFIG_HOME, 無ければ$HOME/.config
+/// macOS: $HOME/Library/Application Support
+/// Windows: %APPDATA%
+///
+pub fn platform_config_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| {
+            let mut path = PathBuf::from(home);
+            path.push("Library");
+            path.push("Application Support");
+            path
+        })
+    } else {
+        match env::var_os("XDG_CONFIG_HOME").map(PathBuf::from) {
+            Some(path) if path.is_absolute() => Some(path),
+            _ => env::var_os("HOME").map(|home| {
+                let mut path = PathBuf::from(home);
+                path.push(".config");
+                path
+            }),
+        }
+    }
+}
+
+pub fn keycode_from_str(key_str: &str) -> Option<KeyCode> {
+    let keycode = match key_str {
+        "A" => KeyCode::A,
+        "B" => KeyCode::B,
+        "C" => KeyCode::C,
+        "D" => KeyCode::D,
+        "E" => KeyCode::E,
+        "F" => KeyCode::F,
+        "G" => KeyCode::G,
+        "H" => KeyCode::H,
+        "I" => KeyCode::I,
+        "J" => KeyCode::J,
+        "K" => KeyCode::K,
+        "L" => KeyCode::L,
+        "M" => KeyCode::M,
+        "N" => KeyCode::N,
+        "O" => KeyCode::O,
+        "P" => KeyCode::P,
+        "Q" => KeyCode::Q,
+        "R" => KeyCode::R,
+        "S" => KeyCode::S,
+        "T" => KeyCode::T,
+        "U" => KeyCode::U,
+        "V" => KeyCode::V,
+        "W" => KeyCode::W,
+        "X" => KeyCode::X,
+        "Y" => KeyCode::Y,
+        "Z" => KeyCode::Z,
+        "Key0" => KeyCode::Key0,
+        "Key1" => KeyCode::Key1,
+        "Key2" => KeyCode::Key2,
+        "Key3" => KeyCode::Key3,
+        "Key4" => KeyCode::Key4,
+        "Key5" => KeyCode::Key5,
+        "Key6" => KeyCode::Key6,
+        "Key7" => KeyCode::Key7,
+        "Key8" => KeyCode::Key8,
+        "Key9" => KeyCode::Key9,
+        "Space" => KeyCode::Space,
+        "Return" => KeyCode::Return,
+        "Escape" => KeyCode::Escape,
+        "Tab" => KeyCode::Tab,
+        "Back" => KeyCode::Back,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "LShift" => KeyCode::LShift,
+        "RShift" => KeyCode::RShift,
+        "LControl" => KeyCode::LControl,
+        "RControl" => KeyCode::RControl,
+        _ => return None,
+    };
+
+    Some(keycode)
+}
+
+pub fn virtual_key_from_str(vkey_str: &str) -> Option<tdev::VirtualKey> {
+    let vkey = match vkey_str {
+        "Action1" => tdev::VirtualKey::Action1,
+        "Action2" => tdev::VirtualKey::Action2,
+        "Action3" => tdev::VirtualKey::Action3,
+        "Action4" => tdev::VirtualKey::Action4,
+        "Action5" => tdev::VirtualKey::Action5,
+        _ => return None,
+    };
+
+    Some(vkey)
+}
+
+///
+/// # 前回閉じたときの窓
+///
+/// ## position
+/// 窓の左上の、画面上の位置(画素)。一度も窓で閉じていなければNone
+///
+/// ## width, height
+/// 窓の中の描画できる大きさ
+///
+/// ## fullscreen
+/// 全画面で閉じたか。全画面の間は、位置と大きさは窓に戻したときのものを残す
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    pub position: Option<(i32, i32)>,
+    pub width: f32,
+    pub height: f32,
+    pub fullscreen: bool,
+}
+
+impl WindowPlacement {
+    pub fn new(width: f32, height: f32) -> Self {
+        WindowPlacement {
+            position: None,
+            width: width,
+            height: height,
+            fullscreen: false,
+        }
+    }
+
+    ///
+    /// 窓の左上がdisplayの中にあるか
+    ///
+    pub fn is_on_display(&self, display: &numeric::Rect) -> bool {
+        match self.position {
+            Some((x, y)) => {
+                let (x, y) = (x as f32, y as f32);
+                x >= display.x
+                    && y >= display.y
+                    && x < display.x + display.w
+                    && y < display.y + display.h
+            }
+            None => true,
+        }
+    }
+
+    ///
+    /// displayに収まるよう、大きさを縮めて位置を寄せる
+    ///
+    pub fn clamp_to_display(&self, display: &numeric::Rect) -> Self {
+        let width = self.width.min(display.w).max(WINDOW_MIN_WIDTH);
+        let height = self.height.min(display.h).max(WINDOW_MIN_HEIGHT);
+
+        let position = self.position.map(|(x, y)| {
+            let max_x = (display.x + display.w - width).max(display.x);
+            let max_y = (display.y + display.h - height).max(display.y);
+            (
+                (x as f32).max(display.x).min(max_x) as i32,
+                (y as f32).max(display.y).min(max_y) as i32,
+            )
+        });
+
+        WindowPlacement {
+            position: position,
+            width: width,
+            height: height,
+            fullscreen: self.fullscreen,
+        }
+    }
+
+    ///
+    /// 起動したときに戻す窓。前回のモニタが外されていれば、主画面に収める
+    ///
+    pub fn restore_on(&self, displays: &[numeric::Rect], primary: &numeric::Rect) -> Self {
+        match displays.iter().find(|display| self.is_on_display(display)) {
+            Some(display) => self.clamp_to_display(display),
+            None => self.clamp_to_display(primary),
+        }
+    }
+}
+
+///
+/// # セーブデータと独立した、ユーザーごとの設定
+/// プラットフォームの設定ディレクトリにTOMLで保存する
+///
+/// ## key_bindings
+/// 物理キー名 -> 仮想キー名 (例: "Z" = "Action1")
+/// 指定の無いキーは標準の割り当てを使う
+///
+/// ## ui_scale
+/// ウィンドウの大きさの倍率
+///
+/// ## reduce_motion
+/// 画面全体が動く演出を控えめにする
+///
+/// ## locale
+/// 表示する言語。文字の向きもこれで決まる
+///
+/// ## frame_budget_ms
+/// 一フレームの処理に使ってよい時間(ミリ秒)。超えると重い処理を知らせる。0なら知らせない
+///
+/// ## voice_blip_volume
+/// 台詞の文字送りで鳴る声の音量
+///
+/// ## mute_voice_blips
+/// 文字送りの声を消す
+///
+/// ## content_warnings
+/// 注意書きのある場面の前で飛ばすかどうかを尋ね、大きな音を控えめにする
+///
+/// ## time_scale
+/// ゲームの進む速さ。ゆっくり遊びたいときは0.5倍にする
+///
+/// ## window
+/// 前回閉じたときの窓。次に起動したときに戻す
+///
+/// ## autosave_frequency
+/// 自動で保存する間隔
+///
+/// ## nickname
+/// 台詞や日記で使う呼び名。空なら標準の呼び名を使う
+///
+/// ## control_scheme
+/// 初めて起動したときに選んだ操作の型
+///
+/// ## kosuzu_comments
+/// 店番中に小鈴の独り言を吹き出しで出す
+///
+/// ## gamepad_bindings
+/// パッドのボタン名 -> 操作名 (例: "South" = "Action1")
+/// 指定の無いボタンは標準の割り当てを使う
+///
+/// ## first_run
+/// 設定ファイルが無いまま起動したか。保存はしない
+///
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Preferences {
+    #[serde(default = "Preferences::default_volume")]
+    bgm_volume: f32,
+    #[serde(default = "Preferences::default_volume")]
+    se_volume: f32,
+    #[serde(default = "Preferences::default_ui_scale")]
+    ui_scale: f32,
+    #[serde(default)]
+    reduce_motion: bool,
+    #[serde(default)]
+    key_bindings: HashMap<String, String>,
+    #[serde(default = "Preferences::default_locale")]
+    locale: Locale,
+    #[serde(default = "Preferences::default_frame_budget_ms")]
+    frame_budget_ms: f32,
+    #[serde(default = "Preferences::default_volume")]
+    voice_blip_volume: f32,
+    #[serde(default)]
+    mute_voice_blips: bool,
+    #[serde(default)]
+    content_warnings: bool,
+    #[serde(default = "Preferences::default_time_scale")]
+    time_scale: TimeScale,
+    #[serde(default)]
+    window: Option<WindowPlacement>,
+    #[serde(default = "Preferences::default_autosave_frequency")]
+    autosave_frequency: AutosaveFrequency,
+    #[serde(default)]
+    nickname: String,
+    #[serde(default = "Preferences::default_control_scheme")]
+    control_scheme: ControlScheme,
+    #[serde(default = "Preferences::default_kosuzu_comments")]
+    kosuzu_comments: bool,
+    #[serde(default)]
+    gamepad_bindings: HashMap<String, String>,
+    #[serde(skip)]
+    first_run: bool,
+}
+
+impl Preferences {
+    fn default_volume() -> f32 {
+        0.5
+    }
+
+    fn default_ui_scale() -> f32 {
+        1.0
+    }
+
+    fn default_locale() -> Locale {
+        Locale::Japanese
+    }
+
+    fn default_frame_budget_ms() -> f32 {
+        1000.0 / 60.0
+    }
+
+    fn default_time_scale() -> TimeScale {
+        TimeScale::Normal
+    }
+
+    fn default_autosave_frequency() -> AutosaveFrequency {
+        AutosaveFrequency::Daily
+    }
+
+    fn default_control_scheme() -> ControlScheme {
+        ControlScheme::Mouse
+    }
+
+    fn default_kosuzu_comments() -> bool {
+        true
+    }
+
+    pub fn new() -> Self {
+        Preferences {
+            bgm_volume: Self::default_volume(),
+            se_volume: Self::default_volume(),
+            ui_scale: Self::default_ui_scale(),
+            reduce_motion: false,
+            key_bindings: HashMap::new(),
+            locale: Self::default_locale(),
+            frame_budget_ms: Self::default_frame_budget_ms(),
+            voice_blip_volume: Self::default_volume(),
+            mute_voice_blips: false,
+            content_warnings: false,
+            time_scale: Self::default_time_scale(),
+            window: None,
+            autosave_frequency: Self::default_autosave_frequency(),
+            nickname: String::new(),
+            control_scheme: Self::default_control_scheme(),
+            kosuzu_comments: Self::default_kosuzu_comments(),
+            gamepad_bindings: HashMap::new(),
+            first_run: false,
+        }
+    }
+
+    pub fn file_path() -> Option<PathBuf> {
+        platform_config_dir().map(|mut path| {
+            path.push(PREFERENCES_DIR_NAME);
+            path.push(PREFERENCES_FILE_NAME);
+            path
+        })
+    }
+
+    pub fn from_toml_str(s: &str) -> Option<Self> {
+        match toml::from_str::<Preferences>(s) {
+            Ok(mut preferences) => {
+                preferences.set_ui_scale(preferences.ui_scale);
+                Some(preferences)
+            }
+            Err(e) => {
+                log_warn!("failed to parse preferences: {}", e);
+                None
+            }
+        }
+    }
+
+    ///
+    /// 設定ファイルを読み込む。無いか読めなければNone
+    ///
+    pub fn load() -> Option<Self> {
+        let s = fs::read_to_string(Self::file_path()?).ok()?;
+        Self::from_toml_str(&s)
+    }
+
+    ///
+    /// 設定ファイルを読み込む。無い場合はGameConfigの値を引き継いで作り、初めての起動として扱う
+    ///
+    pub fn load_or_migrate(game_config: &GameConfig) -> Self {
+        if let Some(preferences) = Self::load() {
+            return preferences;
+        }
+
+        let mut preferences = Self::new();
+        preferences.bgm_volume = game_config.get_bgm_volume();
+        preferences.se_volume = game_config.get_se_volume();
+        preferences.first_run = Self::file_path().map_or(false, |path| !path.exists());
+        preferences
+    }
+
+    pub fn save(&self) {
+        let path = match Self::file_path() {
+            Some(path) => path,
+            None => {
+                log_warn!("config directory is not found. preferences are not saved.");
+                return;
+            }
+        };
+
+        if let Some(dir) = path.parent() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                log_warn!("failed to create {}: {}", dir.display(), e);
+                return;
+            }
+        }
+
+        match fs::write(&path, toml::to_string(self).unwrap()) {
+            Ok(_) => (),
+            Err(e) => log_warn!("failed to write {}: {}", path.display(), e),
+        }
+    }
+
+    ///
+    /// 音量などゲーム設定と重複する値をGameConfigへ反映する
+    ///
+    pub fn apply_to_config(&self, game_config: &mut GameConfig) {
+        game_config.set_bgm_volume_100(self.bgm_volume * 100.0);
+        game_config.set_se_volume_100(self.se_volume * 100.0);
+    }
+
+    pub fn get_bgm_volume(&self) -> f32 {
+        self.bgm_volume
+    }
+
+    pub fn set_bgm_volume(&mut self, volume: f32) {
+        self.bgm_volume = volume;
+    }
+
+    pub fn get_se_volume(&self) -> f32 {
+        self.se_volume
+    }
+
+    pub fn set_se_volume(&mut self, volume: f32) {
+        self.se_volume = volume;
+    }
+
+    pub fn get_ui_scale(&self) -> f32 {
+        self.ui_scale
+    }
+
+    ///
+    /// 倍率を変えたら、次に起動したときの窓もその倍率の大きさにする
+    ///
+    pub fn set_ui_scale(&mut self, scale: f32) {
+        let scale = scale.max(UI_SCALE_MIN).min(UI_SCALE_MAX);
+        if scale != self.ui_scale {
+            if let Some(window) = self.window.as_mut() {
+                window.width = WINDOW_SIZE_X as f32 * scale;
+                window.height = WINDOW_SIZE_Y as f32 * scale;
+            }
+        }
+
+        self.ui_scale = scale;
+    }
+
+    pub fn is_reduce_motion(&self) -> bool {
+        self.reduce_motion
+    }
+
+    pub fn set_reduce_motion(&mut self, flag: bool) {
+        self.reduce_motion = flag;
+    }
+
+    pub fn get_locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    pub fn text_orientation(&self) -> TextOrientation {
+        self.locale.text_orientation()
+    }
+
+    pub fn get_frame_budget_ms(&self) -> f32 {
+        self.frame_budget_ms
+    }
+
+    pub fn set_frame_budget_ms(&mut self, budget_ms: f32) {
+        self.frame_budget_ms = budget_ms.max(0.0);
+    }
+
+    pub fn get_frame_budget_micros(&self) -> u64 {
+        (self.frame_budget_ms * 1000.0) as u64
+    }
+
+    pub fn get_voice_blip_volume(&self) -> f32 {
+        self.voice_blip_volume
+    }
+
+    pub fn set_voice_blip_volume(&mut self, volume: f32) {
+        self.voice_blip_volume = volume.max(0.0).min(1.0);
+    }
+
+    pub fn is_voice_blip_muted(&self) -> bool {
+        self.mute_voice_blips
+    }
+
+    pub fn set_voice_blip_muted(&mut self, flag: bool) {
+        self.mute_voice_blips = flag;
+    }
+
+    pub fn is_content_warning_enabled(&self) -> bool {
+        self.content_warnings
+    }
+
+    pub fn set_content_warning_enabled(&mut self, flag: bool) {
+        self.content_warnings = flag;
+    }
+
+    pub fn get_time_scale(&self) -> TimeScale {
+        self.time_scale
+    }
+
+    pub fn set_time_scale(&mut self, scale: TimeScale) {
+        self.time_scale = scale;
+    }
+
+    pub fn get_window_placement(&self) -> Option<WindowPlacement> {
+        self.window
+    }
+
+    pub fn set_window_placement(&mut self, placement: WindowPlacement) {
+        self.window = Some(placement);
+    }
+
+    pub fn get_autosave_frequency(&self) -> AutosaveFrequency {
+        self.autosave_frequency
+    }
+
+    pub fn set_autosave_frequency(&mut self, frequency: AutosaveFrequency) {
+        self.autosave_frequency = frequency;
+    }
+
+    pub fn get_nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    ///
+    /// 前後の空白を除き、長すぎる分は切り詰める
+    ///
+    pub fn set_nickname(&mut self, nickname: &str) {
+        self.nickname = nickname.trim().chars().take(NICKNAME_MAX_CHARS).collect();
+    }
+
+    pub fn nickname_or_default(&self) -> &str {
+        if self.nickname.is_empty() {
+            DEFAULT_NICKNAME
+        } else {
+            &self.nickname
+        }
+    }
+
+    ///
+    /// textの中の呼び名の印を、呼び名に置き換える
+    ///
+    pub fn interpolate_nickname(&self, text: &str) -> String {
+        text.replace(NICKNAME_PLACEHOLDER, self.nickname_or_default())
+    }
+
+    pub fn get_control_scheme(&self) -> ControlScheme {
+        self.control_scheme
+    }
+
+    ///
+    /// 操作の型を変え、キーの割り当てをその型のものに置き換える
+    ///
+    pub fn set_control_scheme(&mut self, scheme: ControlScheme) {
+        self.control_scheme = scheme;
+        self.key_bindings.clear();
+        for (key_str, vkey_str) in scheme.key_bindings() {
+            self.bind_key(key_str, vkey_str);
+        }
+    }
+
+    pub fn is_kosuzu_comment_enabled(&self) -> bool {
+        self.kosuzu_comments
+    }
+
+    pub fn set_kosuzu_comment_enabled(&mut self, flag: bool) {
+        self.kosuzu_comments = flag;
+    }
+
+    pub fn is_first_run(&self) -> bool {
+        self.first_run
+    }
+
+    pub fn finish_first_run(&mut self) {
+        self.first_run = false;
+    }
+
+    pub fn bind_key(&mut self, key_str: &str, vkey_str: &str) -> bool {
+        if keycode_from_str(key_str).is_none() || virtual_key_from_str(vkey_str).is_none() {
+            return false;
+        }
+
+        self.key_bindings
+            .insert(key_str.to_string(), vkey_str.to_string());
+        true
+    }
+
+    ///
+    /// ユーザーが割り当てを変更しているキーなら、その仮想キーを返す
+    ///
+    pub fn remap_key(&self, keycode: KeyCode) -> Option<tdev::VirtualKey> {
+        for (key_str, vkey_str) in self.key_bindings.iter() {
+            if keycode_from_str(key_str) == Some(keycode) {
+                return virtual_key_from_str(vkey_str);
+            }
+        }
+
+        None
+    }
+
+    pub fn bind_gamepad_button(&mut self, button_str: &str, action_str: &str) -> bool {
+        if button_from_str(button_str).is_none() || gamepad_action_from_str(action_str).is_none() {
+            return false;
+        }
+
+        self.gamepad_bindings
+            .insert(button_str.to_string(), action_str.to_string());
+        true
+    }
+
+    ///
+    /// パッドのボタンに割り当てた操作を返す。ユーザーが変更していなければ標準の割り当てを使う
+    ///
+    pub fn remap_gamepad_button(&self, button: Button) -> Option<GamepadAction> {
+        for (button_str, action_str) in self.gamepad_bindings.iter() {
+            if button_from_str(button_str) == Some(button) {
+                return gamepad_action_from_str(action_str);
+            }
+        }
+
+        default_gamepad_action(button)
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use super::SavableData;
+use crate::{log_info, log_warn};
+
+///
+/// 順番に使い回すクイックセーブの枠の数
+///
+pub const QUICKSAVE_SLOTS: usize = 3;
+
+///
+/// セーブ画面で使う枠の数。枠の番号は1から数える
+///
+pub const SAVE_SLOTS: u8 = 4;
+
+const AUTOSAVE_PATH: &str = "./resources/autosave";
+
+///
+/// # 自動で保存する間隔
+///
+/// ## Off
+/// 自動では保存しない
+///
+/// ## Daily
+/// 日が替わるたびに保存する
+///
+/// ## Hourly
+/// 日が替わるときに加えて、店の時計が一時間進むたびに保存する
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AutosaveFrequency {
+    Off,
+    Daily,
+    Hourly,
+}
+
+impl AutosaveFrequency {
+    pub const ALL: [AutosaveFrequency; 3] = [
+        AutosaveFrequency::Off,
+        AutosaveFrequency::Daily,
+        AutosaveFrequency::Hourly,
+    ];
+
+    ///
+    /// 設定画面の目盛り(0から)との対応
+    ///
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL[index.min(Self::ALL.len() - 1)]
+    }
+
+    pub fn to_index(&self) -> usize {
+        Self::ALL.iter().position(|f| f == self).unwrap()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutosaveFrequency::Off => "シナイ",
+            AutosaveFrequency::Daily => "毎日",
+            AutosaveFrequency::Hourly => "毎時",
+        }
+    }
+
+    pub fn saves_on_new_day(&self) -> bool {
+        *self != AutosaveFrequency::Off
+    }
+
+    pub fn saves_on_hour(&self) -> bool {
+        *self == AutosaveFrequency::Hourly
+    }
+}
+
+fn quicksave_path(slot: usize) -> String {
+    format!("./resources/quicksave{}", slot)
+}
+
+///
+/// 次に書き込む枠。空いている枠があればその最初、無ければ一番古い枠
+///
+pub fn next_quicksave_slot(modified: &[Option<SystemTime>]) -> usize {
+    if let Some(empty) = modified.iter().position(|time| time.is_none()) {
+        return empty;
+    }
+
+    modified
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, time)| time.unwrap())
+        .map(|(slot, _)| slot)
+        .unwrap_or(0)
+}
+
+///
+/// 一番新しく書き込んだ枠。一つも無ければNone
+///
+pub fn newest_slot(modified: &[Option<SystemTime>]) -> Option<usize> {
+    modified
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, time)| time.map(|time| (slot, time)))
+        .max_by_key(|(_, time)| *time)
+        .map(|(slot, _)| slot)
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn quicksave_modified_times() -> Vec<Option<SystemTime>> {
+    (0..QUICKSAVE_SLOTS)
+        .map(|slot| modified_time(&quicksave_path(slot)))
+        .collect()
+}
+
+///
+/// 一番古い枠へ書き込み、書き込んだ枠を返す
+///
+pub fn quicksave(data: &SavableData) -> Result<usize, Box<dyn std::error::Error>> {
+    let slot = next_quicksave_slot(&quicksave_modified_times());
+    data.save_to_path(&quicksave_path(slot))?;
+    log_info!("quicksaved to slot {}", slot);
+
+    Ok(slot)
+}
+
+fn quickload_paths() -> Vec<String> {
+    (0..QUICKSAVE_SLOTS)
+        .map(|slot| quicksave_path(slot))
+        .chain(std::iter::once(AUTOSAVE_PATH.to_string()))
+        .collect()
+}
+
+///
+/// 続きから始めるときに探すファイル。セーブ画面の枠、クイックセーブ、自動保存の順
+///
+pub fn continue_paths() -> Vec<String> {
+    (1..=SAVE_SLOTS)
+        .map(|slot| format!("./resources/save{}", slot))
+        .chain(quickload_paths())
+        .collect()
+}
+
+fn load_newest(paths: &[String]) -> Option<SavableData> {
+    let times: Vec<Option<SystemTime>> = paths.iter().map(|path| modified_time(path)).collect();
+    let path = &paths[newest_slot(&times)?];
+
+    match SavableData::load_from_path(path) {
+        Ok(data) => {
+            log_info!("loaded the newest save from {}", path);
+            Some(data)
+        }
+        Err(_) => {
+            log_warn!("failed to load {}", path);
+            None
+        }
+    }
+}
+
+///
+/// クイックセーブと自動保存のうち、一番新しいものを読む
+///
+pub fn quickload() -> Option<SavableData> {
+    load_newest(&quickload_paths())
+}
+
+///
+/// セーブ画面の枠も含めて、一番新しいセーブを読む
+///
+pub fn load_newest_save() -> Option<SavableData> {
+    load_newest(&continue_paths())
+}
+
+pub fn autosave(data: &SavableData) {
+    match data.save_to_path(AUTOSAVE_PATH) {
+        Ok(_) => log_info!("autosaved: {}", data.date.to_string()),
+        Err(e) => log_warn!("failed to autosave: {}", e),
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::object::task_object::tt_main_component::CustomerRequest;
+use crate::object::task_object::tt_sub_component::BorrowingRecordBookPageData;
+
+///
+/// # 帳簿の記入の誤り
+///
+/// ## MissingTitle
+/// 依頼にある本の題目が書かれていない
+///
+/// ## ExtraTitle
+/// 依頼に無い本の題目が書かれている
+///
+/// ## WrongStatus
+/// 返却された本の状態が書かれていないか、本来の状態と違う
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordMistake {
+    WrongBorrower,
+    WrongRentalDate,
+    WrongReturnDate,
+    MissingTitle(String),
+    ExtraTitle(String),
+    WrongStatus(String),
+}
+
+///
+/// # 帳簿一頁の採点
+///
+/// ## checked
+/// 確かめた記入欄の数
+///
+/// ## mistakes
+/// 見つかった誤り
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordPageScore {
+    pub checked: usize,
+    pub mistakes: Vec<RecordMistake>,
+}
+
+impl RecordPageScore {
+    pub fn count_correct(&self) -> usize {
+        self.checked.saturating_sub(self.mistakes.len())
+    }
+
+    ///
+    /// 正しく書けた欄の割合（%）。確かめた欄が無ければ100
+    ///
+    pub fn score(&self) -> u32 {
+        if self.checked == 0 {
+            return 100;
+        }
+
+        (self.count_correct() * 100 / self.checked) as u32
+    }
+
+    pub fn is_perfect(&self) -> bool {
+        self.mistakes.is_empty()
+    }
+}
+
+///
+/// 署名した頁の記入を、その頁を書かせた依頼と突き合わせて採点する
+/// 客の名前、貸出日、返却期限、本の題目は貸出と返却の両方で、本の状態は返却でだけ確かめる
+///
+pub fn validate_page(
+    page: &BorrowingRecordBookPageData,
+    request: &CustomerRequest,
+) -> RecordPageScore {
+    let (borrower, rental_date, return_date, books) = match request {
+        CustomerRequest::Borrowing(info) => (
+            &info.borrower,
+            &info.borrow_date,
+            &info.return_date,
+            &info.borrowing,
+        ),
+        CustomerRequest::Returning(info) => (
+            &info.borrower,
+            &info.borrow_date,
+            &info.return_date,
+            &info.returning,
+        ),
+    };
+
+    let mut checked = 3;
+    let mut mistakes = Vec::new();
+
+    if page.customer_name.as_ref() != Some(borrower) {
+        mistakes.push(RecordMistake::WrongBorrower);
+    }
+    if page.rental_date.as_ref() != Some(rental_date) {
+        mistakes.push(RecordMistake::WrongRentalDate);
+    }
+    if page.return_date.as_ref() != Some(return_date) {
+        mistakes.push(RecordMistake::WrongReturnDate);
+    }
+
+    // 同じ題目の本が複数あっても一冊ずつ数えるため、書かれた題目を消し込んでいく
+    let mut written: Vec<&str> = page
+        .borrowing_book_title
+        .iter()
+        .map(|(_, info)| info.name.as_str())
+        .collect();
+    for book in books.iter() {
+        checked += 1;
+        match written.iter().position(|name| *name == book.name) {
+            Some(index) => {
+                written.remove(index);
+            }
+            None => mistakes.push(RecordMistake::MissingTitle(book.name.clone())),
+        }
+    }
+    for name in written {
+        checked += 1;
+        mistakes.push(RecordMistake::ExtraTitle(name.to_string()));
+    }
+
+    if let CustomerRequest::Returning(_) = request {
+        // 状態は題目と同じ行に書く
+        for (title_pos, info) in page.borrowing_book_title.iter() {
+            checked += 1;
+            let status = page
+                .borrowing_book_status
+                .iter()
+                .find(|(status_pos, _)| status_pos.x == title_pos.x)
+                .map(|(_, status)| status);
+
+            if status != Some(&info.get_condition()) {
+                mistakes.push(RecordMistake::WrongStatus(info.name.clone()));
+            }
+        }
+    }
+
+    RecordPageScore {
+        checked: checked,
+        mistakes: mistakes,
+    }
+}
@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::resource_locator::platform_data_dir;
+use crate::{log_error, log_warn};
+
+const ERROR_LOG_DIR_NAME: &str = "suzu";
+const ERROR_LOG_FILE_NAME: &str = "error.log";
+
+///
+/// 記録がこれより大きくなったら、古い記録に回す
+///
+pub const ERROR_LOG_MAX_BYTES: u64 = 256 * 1024;
+
+///
+/// 古い記録を何世代まで残すか
+///
+pub const ERROR_LOG_GENERATIONS: usize = 3;
+
+///
+/// # 不具合が起きた所
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorSource {
+    Texture,
+    Sound,
+    Script,
+    Resource,
+}
+
+impl ErrorSource {
+    pub fn to_str(&self) -> &str {
+        match self {
+            ErrorSource::Texture => "画像",
+            ErrorSource::Sound => "音",
+            ErrorSource::Script => "台本",
+            ErrorSource::Resource => "素材",
+        }
+    }
+
+    fn to_log_tag(&self) -> &str {
+        match self {
+            ErrorSource::Texture => "texture",
+            ErrorSource::Sound => "sound",
+            ErrorSource::Script => "script",
+            ErrorSource::Resource => "resource",
+        }
+    }
+}
+
+///
+/// # 遊び続けられる不具合
+/// 落とさずに代わりのもので続け、通知と記録で知らせる
+///
+/// ## summary
+/// 通知に出す一行
+///
+/// ## details
+/// 通知を開いたときに出す詳しい内容。記録にも書く
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoverableError {
+    pub source: ErrorSource,
+    pub summary: String,
+    pub details: String,
+}
+
+impl RecoverableError {
+    pub fn new(source: ErrorSource, summary: &str, details: &str) -> Self {
+        RecoverableError {
+            source: source,
+            summary: summary.to_string(),
+            details: details.to_string(),
+        }
+    }
+
+    pub fn to_log_line(&self) -> String {
+        format!(
+            "[{}] {}: {}",
+            self.source.to_log_tag(),
+            self.summary,
+            self.details.replace('\n', " / ")
+        )
+    }
+}
+
+///
+/// # 回していく記録のファイル
+/// pathが大きくなりすぎたらpath.1へ、path.1はpath.2へと送り、generationsより古いものは消す
+///
+pub struct RotatingLog {
+    path: PathBuf,
+    max_bytes: u64,
+    generations: usize,
+}
+
+impl RotatingLog {
+    pub fn new(path: PathBuf, max_bytes: u64, generations: usize) -> Self {
+        RotatingLog {
+            path: path,
+            max_bytes: max_bytes,
+            generations: generations,
+        }
+    }
+
+    pub fn get_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    ///
+    /// generation世代前の記録の場所。0なら今の記録
+    ///
+    pub fn rotated_path(&self, generation: usize) -> PathBuf {
+        if generation == 0 {
+            return self.path.clone();
+        }
+
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", generation));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        let oldest = self.rotated_path(self.generations);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+
+        for generation in (0..self.generations).rev() {
+            let from = self.rotated_path(generation);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(generation + 1))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn append(&self, line: &str) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let current_size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if current_size > 0 && current_size + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)
+    }
+}
+
+///
+/// 遊んでいる人のデータの置き場所にある不具合の記録
+///
+pub fn default_log() -> Option<RotatingLog> {
+    platform_data_dir().map(|mut path| {
+        path.push(ERROR_LOG_DIR_NAME);
+        path.push(ERROR_LOG_FILE_NAME);
+        RotatingLog::new(path, ERROR_LOG_MAX_BYTES, ERROR_LOG_GENERATIONS)
+    })
+}
+
+thread_local! {
+    static REPORTED_ERRORS: RefCell<Vec<RecoverableError>> = RefCell::new(Vec::new());
+}
+
+///
+/// 不具合を記録に書き、通知を待つ列に入れる
+/// 通知はSceneControllerが次のフレームで取り出して出す
+///
+pub fn report(error: RecoverableError) {
+    let line = error.to_log_line();
+    log_error!("{}", line);
+
+    if let Some(log) = default_log() {
+        if let Err(e) = log.append(&line) {
+            log_warn!("failed to write {}: {}", log.get_path().display(), e);
+        }
+    }
+
+    REPORTED_ERRORS.with(|errors| errors.borrow_mut().push(error));
+}
+
+///
+/// 通知を待っている不具合を全て取り出す
+///
+pub fn take_reported() -> Vec<RecoverableError> {
+    REPORTED_ERRORS.with(|errors| errors.replace(Vec::new()))
+}
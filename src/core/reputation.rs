@@ -0,0 +1,145 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use torifune::core::Clock;
+
+///
+/// ここまでなら待たせても、客の機嫌は損ねない
+///
+pub const WAITING_GRACE: Clock = 60 * 5;
+/// 猶予を過ぎてから、一分待たせるごとに下がる評判
+pub const WAITING_PENALTY_PER_MINUTE: f32 = 0.05;
+pub const WAITING_PENALTY_MAX: f32 = 1.0;
+/// 待たせずに受付まで通せたときに上がる評判
+pub const SERVED_BONUS: f32 = 0.2;
+/// 帳簿の書き誤り一つで下がる評判
+pub const RECORD_MISTAKE_PENALTY: f32 = 0.3;
+pub const REFUSED_PENALTY: f32 = 0.5;
+pub const GAVE_UP_PENALTY: f32 = 1.0;
+
+///
+/// 評判がこれだけあると、客足が普段通りになる
+///
+pub const STANDARD_REPUTATION: f32 = 50.0;
+/// 評判が0から100まで動いたときの客足の倍率の幅
+pub const SPAWN_SCALE_RANGE: f32 = 0.6;
+
+///
+/// 特別な客と、来るようになる評判
+///
+pub const SPECIAL_CUSTOMERS: [(&str, f32); 3] =
+    [("稗田阿求", 60.0), ("霧雨魔理沙", 70.0), ("博麗霊夢", 85.0)];
+
+///
+/// 来るようになった特別な客が、普通の客の代わりに来る割合
+///
+pub const SPECIAL_CUSTOMER_VISIT_RATE: f32 = 0.08;
+
+///
+/// # 客の満足に関わる出来事
+///
+/// ## Served
+/// 列に並んだ客を受付に通した。waitingは並んでいた時間
+///
+/// ## RecordMistakes
+/// 署名した頁に書き誤りがあった
+///
+/// ## Refused
+/// 客の頼みを断った
+///
+/// ## GaveUp
+/// 待ちくたびれた客が帰ってしまった
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SatisfactionEvent {
+    Served { waiting: Clock },
+    RecordMistakes(usize),
+    Refused,
+    GaveUp,
+}
+
+impl SatisfactionEvent {
+    ///
+    /// この出来事で変わる評判
+    ///
+    pub fn reputation_diff(&self) -> f32 {
+        match self {
+            SatisfactionEvent::Served { waiting } => {
+                if *waiting <= WAITING_GRACE {
+                    SERVED_BONUS
+                } else {
+                    let minutes = (*waiting - WAITING_GRACE) as f32 / 60.0;
+                    -(minutes * WAITING_PENALTY_PER_MINUTE).min(WAITING_PENALTY_MAX)
+                }
+            }
+            SatisfactionEvent::RecordMistakes(mistakes) => {
+                -(*mistakes as f32 * RECORD_MISTAKE_PENALTY)
+            }
+            SatisfactionEvent::Refused => -REFUSED_PENALTY,
+            SatisfactionEvent::GaveUp => -GAVE_UP_PENALTY,
+        }
+    }
+}
+
+///
+/// 評判による客足の倍率。STANDARD_REPUTATIONで1.0になる
+///
+pub fn spawn_scale(reputation: f32) -> f32 {
+    let reputation = reputation.max(0.0).min(100.0);
+    1.0 + ((reputation - STANDARD_REPUTATION) / 100.0) * SPAWN_SCALE_RANGE
+}
+
+///
+/// 客が来る間隔を、評判による客足の倍率で縮める
+///
+pub fn scale_add_timing(add_timing: Clock, reputation: f32) -> Clock {
+    ((add_timing as f32 / spawn_scale(reputation)) as Clock).max(1)
+}
+
+///
+/// # 来るようになった特別な客
+/// 一度来るようになった客は、評判が下がっても来なくはならない
+///
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpecialCustomers {
+    unlocked: Vec<String>,
+}
+
+impl SpecialCustomers {
+    pub fn new() -> Self {
+        SpecialCustomers {
+            unlocked: Vec::new(),
+        }
+    }
+
+    ///
+    /// reputationで来るようになった客を加え、新しく加えた客の名前を返す
+    ///
+    pub fn unlock(&mut self, reputation: f32) -> Vec<String> {
+        let mut new_customers = Vec::new();
+
+        for (name, required) in SPECIAL_CUSTOMERS.iter() {
+            if reputation >= *required && !self.is_unlocked(name) {
+                self.unlocked.push(name.to_string());
+                new_customers.push(name.to_string());
+            }
+        }
+
+        new_customers
+    }
+
+    pub fn is_unlocked(&self, name: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == name)
+    }
+
+    ///
+    /// 普通の客の代わりに来る特別な客を選ぶ。来ない場合はNone
+    ///
+    pub fn pick_visitor<R: Rng>(&self, rng: &mut R) -> Option<String> {
+        if self.unlocked.is_empty() || rng.gen::<f32>() >= SPECIAL_CUSTOMER_VISIT_RATE {
+            return None;
+        }
+
+        let index = rng.gen_range(0, self.unlocked.len());
+        self.unlocked.get(index).cloned()
+    }
+}
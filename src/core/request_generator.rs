@@ -0,0 +1,204 @@
+use rand::Rng;
+
+use super::book_inspection::BookAnomaly;
+use super::{BookInformation, BorrowingInformation, GensoDate, RentalLimit, ReturnBookInformation};
+
+///
+/// 一つの依頼に含まれる本の最大冊数
+///
+pub const REQUEST_MAX_BOOKS: usize = 5;
+
+///
+/// 返却日が今日からどれだけずれていても良いか（日数）
+///
+pub const RETURN_DATE_MAX_OFFSET: i32 = 3;
+
+///
+/// # 客の依頼を生成する
+/// 依頼には必ず1冊以上、重複しない本が含まれる
+///
+/// ## max_books
+/// 一つの依頼に含まれる本の最大冊数
+///
+/// ## anomaly_rate
+/// 選んだ本が贋作か妖魔本にすり替わる確率。0のときは乱数を消費しない
+///
+/// ## short_term_rate
+/// 貸出の期間が短期になる確率
+///
+/// ## return_date_offset
+/// 返却期限が今日から何日までずれるか
+///
+pub struct RequestGenerator {
+    max_books: usize,
+    anomaly_rate: f32,
+    short_term_rate: f32,
+    return_date_offset: i32,
+}
+
+impl RequestGenerator {
+    pub fn new(max_books: usize) -> Self {
+        RequestGenerator {
+            max_books: if max_books == 0 { 1 } else { max_books },
+            anomaly_rate: 0.0,
+            short_term_rate: 0.5,
+            return_date_offset: RETURN_DATE_MAX_OFFSET,
+        }
+    }
+
+    pub fn with_anomaly_rate(mut self, anomaly_rate: f32) -> Self {
+        self.anomaly_rate = anomaly_rate.max(0.0).min(1.0);
+        self
+    }
+
+    pub fn with_short_term_rate(mut self, short_term_rate: f32) -> Self {
+        self.short_term_rate = short_term_rate.max(0.0).min(1.0);
+        self
+    }
+
+    pub fn with_return_date_offset(mut self, return_date_offset: i32) -> Self {
+        self.return_date_offset = return_date_offset.max(0);
+        self
+    }
+
+    pub fn get_max_books(&self) -> usize {
+        self.max_books
+    }
+
+    pub fn get_anomaly_rate(&self) -> f32 {
+        self.anomaly_rate
+    }
+
+    ///
+    /// candidatesから、題名が重複しないように本を選んでインデックスを返す
+    /// 本の人気度に比例して選ばれやすくなる
+    /// candidatesが空の場合のみ空のVecを返す
+    ///
+    pub fn select_book_indices<R: Rng>(
+        &self,
+        rng: &mut R,
+        candidates: &[BookInformation],
+    ) -> Vec<usize> {
+        // 題名ごとに最初の一冊を代表として扱う
+        let mut groups: Vec<(usize, u32)> = Vec::new();
+        for (index, book_info) in candidates.iter().enumerate() {
+            if !groups
+                .iter()
+                .any(|(g_index, _)| candidates[*g_index].name == book_info.name)
+            {
+                groups.push((index, std::cmp::max(book_info.popularity, 1)));
+            }
+        }
+
+        if groups.is_empty() {
+            return Vec::new();
+        }
+
+        let count = rng.gen_range(1, std::cmp::min(self.max_books, groups.len()) + 1);
+        let mut selected = Vec::new();
+
+        for _ in 0..count {
+            let total_weight = groups.iter().fold(0, |sum, (_, weight)| sum + weight);
+            let mut random = rng.gen_range(0, total_weight);
+
+            let position = groups
+                .iter()
+                .position(|(_, weight)| {
+                    if random < *weight {
+                        true
+                    } else {
+                        random -= *weight;
+                        false
+                    }
+                })
+                .unwrap();
+
+            selected.push(groups.swap_remove(position).0);
+        }
+
+        selected
+    }
+
+    pub fn select_books<R: Rng>(
+        &self,
+        rng: &mut R,
+        candidates: &[BookInformation],
+    ) -> Vec<BookInformation> {
+        let indices = self.select_book_indices(rng, candidates);
+        let mut books = Vec::new();
+
+        for index in indices {
+            let mut book_info = candidates[index].clone_with_new_id_condition(rng);
+            if self.anomaly_rate > 0.0 {
+                book_info.set_anomaly(BookAnomaly::random_select(rng, self.anomaly_rate));
+            }
+            books.push(book_info);
+        }
+
+        books
+    }
+
+    ///
+    /// 今日を基準に、もっともらしい(貸出日, 返却期限)を生成する
+    /// 返却期限は今日の前後return_date_offset日以内になる
+    ///
+    pub fn returning_dates<R: Rng>(
+        &self,
+        rng: &mut R,
+        today: &GensoDate,
+    ) -> (GensoDate, GensoDate) {
+        let rental_days = match self.random_rental_limit(rng) {
+            RentalLimit::LongTerm => 14,
+            _ => 7,
+        };
+
+        let offset = rng.gen_range(-self.return_date_offset, self.return_date_offset + 1);
+        let return_date = if offset < 0 {
+            today.clone().sub_days_chain(-offset)
+        } else {
+            today.clone().add_days_chain(offset)
+        };
+
+        (return_date.clone().sub_days_chain(rental_days), return_date)
+    }
+
+    pub fn generate_borrowing<R: Rng>(
+        &self,
+        rng: &mut R,
+        candidates: &[BookInformation],
+        borrower: &str,
+        today: &GensoDate,
+    ) -> BorrowingInformation {
+        BorrowingInformation::new(
+            self.select_books(rng, candidates),
+            borrower,
+            today.clone(),
+            self.random_rental_limit(rng),
+        )
+    }
+
+    pub fn generate_returning<R: Rng>(
+        &self,
+        rng: &mut R,
+        candidates: &[BookInformation],
+        borrower: &str,
+        today: &GensoDate,
+    ) -> ReturnBookInformation {
+        let (borrow_date, return_date) = self.returning_dates(rng, today);
+
+        ReturnBookInformation::new(
+            self.select_books(rng, candidates),
+            borrower,
+            borrow_date,
+            return_date,
+        )
+    }
+
+    fn random_rental_limit<R: Rng>(&self, rng: &mut R) -> RentalLimit {
+        if rng.gen::<f32>() < self.short_term_rate {
+            RentalLimit::ShortTerm
+        } else {
+            RentalLimit::LongTerm
+        }
+    }
+}
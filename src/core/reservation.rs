@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use super::GensoDate;
+
+///
+/// 貸出中の本を頼んだ客が、予約を申し出る割合
+///
+pub const RESERVATION_ASK_RATE: f32 = 0.15;
+
+///
+/// # 貸出中の本の予約
+///
+/// ## revisit_date
+/// 本が返ってきて取り置いた後、客が受け取りに来る日。返ってくるまではNone
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookReservation {
+    pub customer_name: String,
+    pub book_title: String,
+    pub reserved_date: GensoDate,
+    pub revisit_date: Option<GensoDate>,
+}
+
+impl BookReservation {
+    pub fn new(customer_name: &str, book_title: &str, reserved_date: GensoDate) -> Self {
+        BookReservation {
+            customer_name: customer_name.to_string(),
+            book_title: book_title.to_string(),
+            reserved_date: reserved_date,
+            revisit_date: None,
+        }
+    }
+
+    pub fn is_set_aside(&self) -> bool {
+        self.revisit_date.is_some()
+    }
+
+    ///
+    /// todayが受け取りに来る日か、それを過ぎていればtrue
+    ///
+    pub fn is_revisit_due(&self, today: &GensoDate) -> bool {
+        match self.revisit_date.as_ref() {
+            Some(date) => date == today || today.is_past(date),
+            None => false,
+        }
+    }
+}
+
+///
+/// # 予約台帳
+/// 予約は本が返ってくるまで残し、返ってきたら取り置きの知らせを出して翌日の来店を待つ
+///
+/// ## reminders
+/// 本が返ってきたが、まだ取り置きを知らせていない予約
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReservationLedger {
+    reservations: Vec<BookReservation>,
+    reminders: Vec<BookReservation>,
+}
+
+impl ReservationLedger {
+    pub fn new() -> Self {
+        ReservationLedger {
+            reservations: Vec::new(),
+            reminders: Vec::new(),
+        }
+    }
+
+    ///
+    /// 予約を台帳に書く。同じ客が同じ本を既に予約していた場合はfalseを返す
+    ///
+    pub fn reserve(&mut self, customer_name: &str, book_title: &str, today: &GensoDate) -> bool {
+        if self
+            .reservations
+            .iter()
+            .any(|r| r.customer_name == customer_name && r.book_title == book_title)
+        {
+            return false;
+        }
+
+        self.reservations
+            .push(BookReservation::new(customer_name, book_title, *today));
+        true
+    }
+
+    pub fn is_reserved_by(&self, customer_name: &str, book_title: &str) -> bool {
+        self.reservations
+            .iter()
+            .any(|r| r.customer_name == customer_name && r.book_title == book_title)
+    }
+
+    ///
+    /// 本が返ってきたときに呼ぶ。取り置きを待っている最も古い予約に翌日の来店日を付ける
+    ///
+    pub fn book_returned(
+        &mut self,
+        book_title: &str,
+        today: &GensoDate,
+    ) -> Option<BookReservation> {
+        let reservation = self
+            .reservations
+            .iter_mut()
+            .find(|r| r.book_title == book_title && !r.is_set_aside())?;
+
+        reservation.revisit_date = Some(today.add_days_chain(1));
+        self.reminders.push(reservation.clone());
+
+        Some(reservation.clone())
+    }
+
+    pub fn take_reminders(&mut self) -> Vec<BookReservation> {
+        self.reminders.drain(..).collect()
+    }
+
+    ///
+    /// 今日受け取りに来る予約を台帳から取り出す
+    ///
+    pub fn take_revisits(&mut self, today: &GensoDate) -> Vec<BookReservation> {
+        let (due, rest) = self
+            .reservations
+            .drain(..)
+            .partition(|r| r.is_revisit_due(today));
+        self.reservations = rest;
+
+        due
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<BookReservation> {
+        self.reservations.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.reservations.len()
+    }
+}
@@ -0,0 +1,161 @@
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+///
+/// 素材の置き場所を直接指定する環境変数
+///
+pub const RESOURCES_ENV_VAR: &str = "SUZU_RESOURCES";
+
+const RESOURCE_DIR_NAME: &str = "resources";
+const DATA_DIR_NAME: &str = "suzu";
+
+///
+/// このファイルがあるディレクトリを素材の置き場所とみなす
+///
+pub const RESOURCE_MARKER_FILE: &str = "game_data.toml";
+
+///
+/// dirsクレートのdata_dirと同じ場所を返す
+///
+/// Linux: $XDG_DATA_HOME, 無ければ$HOME/.local/share
+/// macOS: $HOME/Library/Application Support
+/// Windows: %APPDATA%
+///
+pub fn platform_data_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| {
+            let mut path = PathBuf::from(home);
+            path.push("Library");
+            path.push("Application Support");
+            path
+        })
+    } else {
+        match env::var_os("XDG_DATA_HOME").map(PathBuf::from) {
+            Some(path) if path.is_absolute() => Some(path),
+            _ => env::var_os("HOME").map(|home| {
+                let mut path = PathBuf::from(home);
+                path.push(".local");
+                path.push("share");
+                path
+            }),
+        }
+    }
+}
+
+///
+/// # 素材が見つからなかった
+///
+/// ## searched
+/// 探した場所。探した順に並ぶ
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceNotFound {
+    pub searched: Vec<PathBuf>,
+}
+
+impl fmt::Display for ResourceNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "resource directory containing {} was not found. searched:",
+            RESOURCE_MARKER_FILE
+        )?;
+        for path in self.searched.iter() {
+            writeln!(f, "  {}", path.display())?;
+        }
+        write!(
+            f,
+            "set {} to the resources directory to use another location.",
+            RESOURCES_ENV_VAR
+        )
+    }
+}
+
+impl std::error::Error for ResourceNotFound {}
+
+///
+/// # 素材の置き場所を探す
+/// 候補のディレクトリを順に調べ、最初にgame_data.tomlがあった場所を使う
+///
+/// ## candidates
+/// 探す場所。前にあるものほど優先する
+///
+pub struct ResourceLocator {
+    candidates: Vec<PathBuf>,
+}
+
+impl ResourceLocator {
+    pub fn new(candidates: Vec<PathBuf>) -> Self {
+        ResourceLocator {
+            candidates: candidates,
+        }
+    }
+
+    ///
+    /// 環境変数、開発時のマニフェストの場所、実行ファイルの隣、プラットフォームのデータ置き場、
+    /// 作業ディレクトリの順に探す
+    ///
+    pub fn from_env() -> Self {
+        let mut candidates = Vec::new();
+
+        if let Some(dir) = env::var_os(RESOURCES_ENV_VAR) {
+            candidates.push(PathBuf::from(dir));
+        }
+
+        if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+            let mut path = PathBuf::from(manifest_dir);
+            path.push(RESOURCE_DIR_NAME);
+            candidates.push(path);
+        }
+
+        if let Some(exe_dir) = env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        {
+            candidates.push(exe_dir.join(RESOURCE_DIR_NAME));
+
+            // macOSのアプリケーションバンドルでは、Contents/MacOSの隣のContents/Resourcesに置く
+            if cfg!(target_os = "macos") {
+                candidates.push(exe_dir.join("..").join("Resources").join(RESOURCE_DIR_NAME));
+            }
+        }
+
+        if let Some(data_dir) = platform_data_dir() {
+            candidates.push(data_dir.join(DATA_DIR_NAME).join(RESOURCE_DIR_NAME));
+        }
+
+        candidates.push(PathBuf::from(RESOURCE_DIR_NAME));
+
+        Self::new(candidates)
+    }
+
+    pub fn get_candidates(&self) -> &Vec<PathBuf> {
+        &self.candidates
+    }
+
+    pub fn locate(&self) -> Result<PathBuf, ResourceNotFound> {
+        match self
+            .candidates
+            .iter()
+            .find(|dir| dir.join(RESOURCE_MARKER_FILE).is_file())
+        {
+            Some(dir) => Ok(dir.clone()),
+            None => Err(ResourceNotFound {
+                searched: self.candidates.clone(),
+            }),
+        }
+    }
+}
+
+///
+/// 素材の置き場所を探す。見つからなければ、探した場所を並べて止まる
+///
+pub fn resource_dir() -> PathBuf {
+    match ResourceLocator::from_env().locate() {
+        Ok(dir) => dir,
+        Err(e) => panic!("{}", e),
+    }
+}
@@ -0,0 +1,432 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use torifune::core::Clock;
+
+use super::book_fair::FairRequest;
+use super::resource_locator::platform_data_dir;
+use super::BookInformation;
+use crate::log_warn;
+
+///
+/// 番付に載せる回数
+///
+pub const SCORE_ATTACK_LEADERBOARD_SIZE: usize = 10;
+
+///
+/// 番付に書く頭文字の数
+///
+pub const INITIALS_LEN: usize = 3;
+
+const LEADERBOARD_DIR_NAME: &str = "suzu";
+const LEADERBOARD_FILE_NAME: &str = "score_attack.json";
+
+///
+/// # 腕試しの調整値
+/// 物語の店番とは別に、resources/score_attack.tomlから読む。書かれていない値は既定値を使う
+///
+/// ## combo_step
+/// 続けて一度で正しく渡すたびに増える倍率
+///
+/// ## perfect_bonus_seconds
+/// 一度で正しく渡したときに延びる残り時間
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreAttackTuning {
+    pub time_limit_seconds: u64,
+    pub counter_slots: usize,
+    pub serve_score: i32,
+    pub combo_step: f32,
+    pub max_multiplier: f32,
+    pub miss_penalty: i32,
+    pub perfect_bonus_seconds: u64,
+}
+
+impl Default for ScoreAttackTuning {
+    fn default() -> Self {
+        ScoreAttackTuning {
+            time_limit_seconds: 120,
+            counter_slots: 4,
+            serve_score: 100,
+            combo_step: 0.25,
+            max_multiplier: 4.0,
+            miss_penalty: 80,
+            perfect_bonus_seconds: 1,
+        }
+    }
+}
+
+impl ScoreAttackTuning {
+    pub fn from_toml_str(s: &str) -> Self {
+        match toml::from_str(s) {
+            Ok(tuning) => tuning,
+            Err(e) => {
+                log_warn!("failed to parse score attack tuning: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn time_limit(&self) -> Clock {
+        self.time_limit_seconds * 60
+    }
+
+    ///
+    /// combo回続けて一度で正しく渡した後の倍率
+    ///
+    pub fn combo_multiplier(&self, combo: u32) -> f32 {
+        (1.0 + (self.combo_step * combo as f32)).min(self.max_multiplier)
+    }
+}
+
+///
+/// # 腕試し一回分の結果
+///
+/// ## perfect
+/// 一度も取り違えずに渡した客の数
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreAttackResult {
+    pub served: u32,
+    pub perfect: u32,
+    pub missed: u32,
+    pub best_combo: u32,
+    pub score: i32,
+}
+
+///
+/// # 腕試しの受付
+/// 本市と同じく題名札を選んで窓口の客に渡す。客は途切れず、時間が切れるまで続く
+///
+/// ## fumbled
+/// 窓口の枠ごとの、今の客に一度でも取り違えたか
+///
+/// ## remaining_time
+/// 残り時間。一度で正しく渡すと延びる
+///
+pub struct ScoreAttackSession {
+    tuning: ScoreAttackTuning,
+    counter: Vec<Option<FairRequest>>,
+    shelf: Vec<Option<BookInformation>>,
+    fumbled: Vec<bool>,
+    selected_title: Option<usize>,
+    served: u32,
+    perfect: u32,
+    missed: u32,
+    combo: u32,
+    best_combo: u32,
+    score: i32,
+    remaining_time: Clock,
+}
+
+impl ScoreAttackSession {
+    ///
+    /// refillは窓口に新しい客を呼ぶたびに呼ばれる
+    ///
+    pub fn new<F: FnMut() -> FairRequest>(tuning: ScoreAttackTuning, mut refill: F) -> Self {
+        let slots = tuning.counter_slots.max(1);
+
+        let counter: Vec<Option<FairRequest>> = (0..slots).map(|_| Some(refill())).collect();
+
+        // 窓口の客と同じ並びにならないよう、ずらして置く
+        let shelf = (0..slots)
+            .map(|index| {
+                counter[(index + 1) % slots]
+                    .as_ref()
+                    .map(|request| request.book.clone())
+            })
+            .collect();
+
+        ScoreAttackSession {
+            remaining_time: tuning.time_limit(),
+            tuning: tuning,
+            counter: counter,
+            shelf: shelf,
+            fumbled: vec![false; slots],
+            selected_title: None,
+            served: 0,
+            perfect: 0,
+            missed: 0,
+            combo: 0,
+            best_combo: 0,
+            score: 0,
+        }
+    }
+
+    pub fn get_counter(&self) -> &Vec<Option<FairRequest>> {
+        &self.counter
+    }
+
+    pub fn get_shelf(&self) -> &Vec<Option<BookInformation>> {
+        &self.shelf
+    }
+
+    pub fn get_selected_title(&self) -> Option<usize> {
+        self.selected_title
+    }
+
+    pub fn get_score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn get_combo(&self) -> u32 {
+        self.combo
+    }
+
+    pub fn get_served(&self) -> u32 {
+        self.served
+    }
+
+    pub fn current_multiplier(&self) -> f32 {
+        self.tuning.combo_multiplier(self.combo)
+    }
+
+    pub fn get_remaining_time(&self) -> Clock {
+        self.remaining_time
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining_time == 0
+    }
+
+    ///
+    /// 一フレーム分、残り時間を減らす
+    ///
+    pub fn tick(&mut self) {
+        self.remaining_time = self.remaining_time.saturating_sub(1);
+    }
+
+    ///
+    /// 台の題名札を選ぶ。同じ札をもう一度選ぶと選び直しになる
+    ///
+    pub fn select_title(&mut self, index: usize) {
+        if self.is_over() || self.shelf.get(index).map_or(true, |title| title.is_none()) {
+            return;
+        }
+
+        self.selected_title = if self.selected_title == Some(index) {
+            None
+        } else {
+            Some(index)
+        };
+    }
+
+    ///
+    /// 選んでいる題名札を窓口の客に渡す
+    ///
+    /// 渡せればSome。求めと合っていればtrue。札を選んでいないか、時間切れならNone
+    ///
+    pub fn serve<F: FnOnce() -> FairRequest>(
+        &mut self,
+        counter_index: usize,
+        refill: F,
+    ) -> Option<bool> {
+        if self.is_over() {
+            return None;
+        }
+
+        let title_index = self.selected_title?;
+        let request = self.counter.get(counter_index)?.as_ref()?;
+        self.selected_title = None;
+
+        if self.shelf[title_index].as_ref() != Some(&request.book) {
+            self.missed += 1;
+            self.combo = 0;
+            self.fumbled[counter_index] = true;
+            self.score = (self.score - self.tuning.miss_penalty).max(0);
+            return Some(false);
+        }
+
+        // 一度で正しく渡したときだけ連続に数え、倍率を上げる
+        if self.fumbled[counter_index] {
+            self.score += self.tuning.serve_score;
+        } else {
+            self.score +=
+                (self.tuning.serve_score as f32 * self.tuning.combo_multiplier(self.combo)) as i32;
+            self.perfect += 1;
+            self.combo += 1;
+            self.best_combo = self.best_combo.max(self.combo);
+            self.remaining_time += self.tuning.perfect_bonus_seconds * 60;
+        }
+        self.served += 1;
+
+        let next = refill();
+        self.shelf[title_index] = Some(next.book.clone());
+        self.counter[counter_index] = Some(next);
+        self.fumbled[counter_index] = false;
+
+        Some(true)
+    }
+
+    pub fn finish(&self) -> ScoreAttackResult {
+        ScoreAttackResult {
+            served: self.served,
+            perfect: self.perfect,
+            missed: self.missed,
+            best_combo: self.best_combo,
+            score: self.score,
+        }
+    }
+}
+
+///
+/// # 番付に載った一回分
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub initials: String,
+    pub score: i32,
+    pub served: u32,
+    pub best_combo: u32,
+}
+
+///
+/// # 腕試しの番付
+/// セーブデータとは別に、プラットフォームのデータ置き場にjsonで置く
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreAttackLeaderboard {
+    entries: Vec<LeaderboardEntry>,
+}
+
+impl ScoreAttackLeaderboard {
+    pub fn new() -> Self {
+        ScoreAttackLeaderboard {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn file_path() -> Option<PathBuf> {
+        platform_data_dir().map(|mut path| {
+            path.push(LEADERBOARD_DIR_NAME);
+            path.push(LEADERBOARD_FILE_NAME);
+            path
+        })
+    }
+
+    pub fn from_json_str(s: &str) -> Option<Self> {
+        match serde_json::from_str(s) {
+            Ok(leaderboard) => Some(leaderboard),
+            Err(e) => {
+                log_warn!("failed to parse score attack leaderboard: {}", e);
+                None
+            }
+        }
+    }
+
+    ///
+    /// 番付のファイルを読む。無いか読めなければ空の番付
+    ///
+    pub fn load() -> Self {
+        Self::file_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|s| Self::from_json_str(&s))
+            .unwrap_or_else(Self::new)
+    }
+
+    pub fn save(&self) {
+        let path = match Self::file_path() {
+            Some(path) => path,
+            None => {
+                log_warn!("data directory is not found. leaderboard is not saved.");
+                return;
+            }
+        };
+
+        if let Some(dir) = path.parent() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                log_warn!("failed to create {}: {}", dir.display(), e);
+                return;
+            }
+        }
+
+        match fs::write(&path, serde_json::to_string(self).unwrap()) {
+            Ok(_) => (),
+            Err(e) => log_warn!("failed to write {}: {}", path.display(), e),
+        }
+    }
+
+    pub fn get_entries(&self) -> &Vec<LeaderboardEntry> {
+        &self.entries
+    }
+
+    ///
+    /// scoreが番付に載るか
+    ///
+    pub fn qualifies(&self, score: i32) -> bool {
+        score > 0
+            && (self.entries.len() < SCORE_ATTACK_LEADERBOARD_SIZE
+                || self.entries.last().map_or(true, |last| score > last.score))
+    }
+
+    ///
+    /// 番付に載せ、何位になったかを返す(0始まり)。載らなければNone
+    ///
+    /// 同じ点数なら、先に載った方を上にする
+    ///
+    pub fn insert(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| e.score < entry.score)
+            .unwrap_or_else(|| self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(SCORE_ATTACK_LEADERBOARD_SIZE);
+
+        Some(rank)
+    }
+}
+
+///
+/// # 番付に書く頭文字の入力
+/// 英字だけを受け付け、大文字にする
+///
+#[derive(Debug, Clone, Default)]
+pub struct InitialsInput {
+    chars: String,
+}
+
+impl InitialsInput {
+    pub fn new() -> Self {
+        InitialsInput {
+            chars: String::new(),
+        }
+    }
+
+    ///
+    /// 一文字打つ。受け付けたらtrue
+    ///
+    pub fn push(&mut self, c: char) -> bool {
+        if !c.is_ascii_alphabetic() || self.chars.len() >= INITIALS_LEN {
+            return false;
+        }
+
+        self.chars.push(c.to_ascii_uppercase());
+        true
+    }
+
+    pub fn pop(&mut self) {
+        self.chars.pop();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    pub fn get(&self) -> &str {
+        &self.chars
+    }
+
+    ///
+    /// 入力途中の表示。打っていない所は下線で埋める
+    ///
+    pub fn display(&self) -> String {
+        format!("{:_<width$}", self.chars, width = INITIALS_LEN)
+    }
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use torifune::core::Clock;
+use torifune::numeric;
+
+use super::GensoDate;
+
+///
+/// 断った増築を、また勧めるまでの日数
+///
+pub const EXPANSION_REOFFER_INTERVAL: i32 = 3;
+
+///
+/// 読書処がある日に、来た客がまず読書処へ寄る割合
+///
+pub const READING_CORNER_VISIT_RATE: f32 = 0.35;
+
+///
+/// 読書処で本を読んでいる時間
+///
+pub const CUSTOMER_READING_TIME: Clock = 600;
+
+///
+/// 読書処で読んでいった客が、そのまま本を借りていく割合
+///
+pub const READ_THEN_BORROW_RATE: f32 = 0.8;
+
+///
+/// 読書処の座布団の位置。二間目を増築しても同じ場所に残る
+///
+const READING_CORNER_SPOTS: [(u32, u32); 4] = [(4, 21), (7, 21), (9, 21), (12, 21)];
+
+///
+/// 二間目の本棚の前の通路。客が本を眺めに行く先に加える
+///
+const SECOND_ROOM_DESTINATIONS: [(u32, u32); 4] = [(2, 28), (6, 28), (10, 28), (14, 28)];
+
+///
+/// # 鈴奈庵の増築
+///
+/// ## ReadingCorner
+/// 店の奥に設ける読書処。客が座って本を読んでいく
+///
+/// ## SecondRoom
+/// 読書処の奥に続く二間目。本棚が増える
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShopExpansionType {
+    ReadingCorner,
+    SecondRoom,
+}
+
+impl ShopExpansionType {
+    pub const ALL: [ShopExpansionType; 2] = [
+        ShopExpansionType::ReadingCorner,
+        ShopExpansionType::SecondRoom,
+    ];
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            ShopExpansionType::ReadingCorner => "読書処",
+            ShopExpansionType::SecondRoom => "二間目",
+        }
+    }
+
+    pub fn price(&self) -> i32 {
+        match self {
+            ShopExpansionType::ReadingCorner => 3000,
+            ShopExpansionType::SecondRoom => 8000,
+        }
+    }
+
+    pub fn required_reputation(&self) -> f32 {
+        match self {
+            ShopExpansionType::ReadingCorner => 60.0,
+            ShopExpansionType::SecondRoom => 70.0,
+        }
+    }
+
+    ///
+    /// 増築した後の鈴奈庵のマップ。game_data.tomlのmap_informationのid
+    ///
+    pub fn map_id(&self) -> u32 {
+        match self {
+            ShopExpansionType::ReadingCorner => 2,
+            ShopExpansionType::SecondRoom => 3,
+        }
+    }
+
+    ///
+    /// 先に済ませておく必要のある増築
+    ///
+    pub fn prerequisite(&self) -> Option<ShopExpansionType> {
+        match self {
+            ShopExpansionType::ReadingCorner => None,
+            ShopExpansionType::SecondRoom => Some(ShopExpansionType::ReadingCorner),
+        }
+    }
+
+    ///
+    /// 増築を勧めるときに流すシナリオ
+    ///
+    pub fn offer_scenario_path(&self) -> &'static str {
+        match self {
+            ShopExpansionType::ReadingCorner => "/scenario/expansion_reading_corner.toml",
+            ShopExpansionType::SecondRoom => "/scenario/expansion_second_room.toml",
+        }
+    }
+}
+
+///
+/// # 増築の記録
+///
+/// ## declined_date
+/// 最後に増築を断った日。しばらくは勧めない
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShopExpansionState {
+    purchased: Vec<ShopExpansionType>,
+    declined_date: Option<GensoDate>,
+}
+
+impl ShopExpansionState {
+    pub fn new() -> Self {
+        ShopExpansionState {
+            purchased: Vec::new(),
+            declined_date: None,
+        }
+    }
+
+    pub fn is_purchased(&self, expansion: ShopExpansionType) -> bool {
+        self.purchased.contains(&expansion)
+    }
+
+    ///
+    /// 所持金と評判が節目に達していれば、次に勧める増築を返す
+    ///
+    pub fn next_offer(
+        &self,
+        money: i32,
+        reputation: f32,
+        today: &GensoDate,
+    ) -> Option<ShopExpansionType> {
+        if let Some(declined) = self.declined_date.as_ref() {
+            if declined.diff_days(today).abs() < EXPANSION_REOFFER_INTERVAL {
+                return None;
+            }
+        }
+
+        ShopExpansionType::ALL.iter().copied().find(|expansion| {
+            !self.is_purchased(*expansion)
+                && expansion
+                    .prerequisite()
+                    .map_or(true, |pre| self.is_purchased(pre))
+                && money >= expansion.price()
+                && reputation >= expansion.required_reputation()
+        })
+    }
+
+    ///
+    /// 増築を済ませる。先に必要な増築が済んでいなければfalseを返す
+    ///
+    pub fn purchase(&mut self, expansion: ShopExpansionType) -> bool {
+        if self.is_purchased(expansion)
+            || !expansion
+                .prerequisite()
+                .map_or(true, |pre| self.is_purchased(pre))
+        {
+            return false;
+        }
+
+        self.purchased.push(expansion);
+        self.declined_date = None;
+        true
+    }
+
+    pub fn decline(&mut self, today: &GensoDate) {
+        self.declined_date = Some(*today);
+    }
+
+    ///
+    /// 増築を反映したマップ。何も増築していなければdefault_map_idを返す
+    ///
+    pub fn current_map_id(&self, default_map_id: u32) -> u32 {
+        ShopExpansionType::ALL
+            .iter()
+            .rev()
+            .find(|expansion| self.is_purchased(**expansion))
+            .map_or(default_map_id, |expansion| expansion.map_id())
+    }
+
+    ///
+    /// 客が座って本を読める場所。読書処がなければ空
+    ///
+    pub fn reading_spots(&self) -> Vec<numeric::Vector2u> {
+        if !self.is_purchased(ShopExpansionType::ReadingCorner) {
+            return Vec::new();
+        }
+
+        READING_CORNER_SPOTS
+            .iter()
+            .map(|(x, y)| numeric::Vector2u::new(*x, *y))
+            .collect()
+    }
+
+    ///
+    /// 増築で増えた、客が本を眺めに行く先
+    ///
+    pub fn customer_destinations(&self) -> Vec<numeric::Vector2u> {
+        if !self.is_purchased(ShopExpansionType::SecondRoom) {
+            return Vec::new();
+        }
+
+        SECOND_ROOM_DESTINATIONS
+            .iter()
+            .map(|(x, y)| numeric::Vector2u::new(*x, *y))
+            .collect()
+    }
+}
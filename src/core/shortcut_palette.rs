@@ -0,0 +1,275 @@
+///
+/// 一度に一覧へ出す候補の数
+///
+pub const SHORTCUT_PALETTE_VISIBLE_ROWS: usize = 8;
+
+///
+/// # ショートカットの一覧に届けるキー入力
+/// シーンの仮想キーとは別に、実際のキーと文字入力から作る
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaletteKey {
+    Toggle,
+    Up,
+    Down,
+    Enter,
+    Escape,
+    Backspace,
+    Tab,
+    Char(char),
+}
+
+///
+/// # 一覧に出す操作
+///
+/// ## label
+/// 一覧に出す名前
+///
+/// ## reading
+/// labelの読み。ひらがなで書き、ローマ字に直して入力と照らし合わせる
+///
+#[derive(Debug, Clone)]
+pub struct ShortcutEntry<T> {
+    pub command: T,
+    pub label: String,
+    pub reading: String,
+}
+
+impl<T> ShortcutEntry<T> {
+    pub fn new(command: T, label: &str, reading: &str) -> Self {
+        ShortcutEntry {
+            command: command,
+            label: label.to_string(),
+            reading: reading.to_string(),
+        }
+    }
+}
+
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => std::char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+///
+/// 五十音の行ごとの読み。同じ位置の文字とローマ字が対応する
+///
+const KANA_ROWS: &[(&str, &str)] = &[
+    ("あいうえお", "a i u e o"),
+    ("かきくけこ", "ka ki ku ke ko"),
+    ("さしすせそ", "sa shi su se so"),
+    ("たちつてと", "ta chi tsu te to"),
+    ("なにぬねの", "na ni nu ne no"),
+    ("はひふへほ", "ha hi fu he ho"),
+    ("まみむめも", "ma mi mu me mo"),
+    ("やゆよ", "ya yu yo"),
+    ("らりるれろ", "ra ri ru re ro"),
+    ("わをん", "wa wo n"),
+    ("がぎぐげご", "ga gi gu ge go"),
+    ("ざじずぜぞ", "za ji zu ze zo"),
+    ("だぢづでど", "da ji zu de do"),
+    ("ばびぶべぼ", "ba bi bu be bo"),
+    ("ぱぴぷぺぽ", "pa pi pu pe po"),
+    ("ぁぃぅぇぉ", "a i u e o"),
+    ("ゃゅょ", "ya yu yo"),
+];
+
+fn hiragana_to_romaji(c: char) -> Option<&'static str> {
+    for (kana, romaji) in KANA_ROWS.iter() {
+        if let Some(index) = kana.chars().position(|k| k == c) {
+            return romaji.split(' ').nth(index);
+        }
+    }
+
+    None
+}
+
+///
+/// ひらがなとカタカナをヘボン式のローマ字に直す。それ以外の文字は小文字にしてそのまま残す
+///
+pub fn kana_to_romaji(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(to_hiragana).collect();
+    let mut romaji = String::new();
+    let mut double_next = false;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+
+        if c == 'っ' {
+            double_next = true;
+            index += 1;
+            continue;
+        }
+
+        let mut syllable = match hiragana_to_romaji(c) {
+            Some(s) => s.to_string(),
+            None => {
+                // 長音や記号はそのまま残すと入力と合わなくなるので捨てる
+                if c != 'ー' {
+                    romaji.extend(c.to_lowercase());
+                }
+                index += 1;
+                continue;
+            }
+        };
+
+        // きゃ、しゅ、ちょのような拗音
+        if let Some(small) = chars
+            .get(index + 1)
+            .filter(|n| ['ゃ', 'ゅ', 'ょ'].contains(n))
+        {
+            let vowel = &hiragana_to_romaji(*small).unwrap()[1..];
+            syllable = match syllable.as_str() {
+                "shi" => format!("sh{}", vowel),
+                "chi" => format!("ch{}", vowel),
+                "ji" => format!("j{}", vowel),
+                _ => format!("{}y{}", &syllable[..syllable.len() - 1], vowel),
+            };
+            index += 1;
+        }
+
+        if double_next {
+            let head = if syllable.starts_with("ch") {
+                't'
+            } else {
+                syllable.chars().next().unwrap()
+            };
+            romaji.push(head);
+            double_next = false;
+        }
+
+        romaji.push_str(&syllable);
+        index += 1;
+    }
+
+    romaji
+}
+
+///
+/// queryの文字がtargetに順番通りに含まれていれば点数を返す
+/// 続けて一致した文字と、先頭で一致した文字に点を足す
+///
+pub fn fuzzy_score(query: &str, target: &str) -> Option<u32> {
+    let target: Vec<char> = target.chars().collect();
+    let mut score = 0;
+    let mut search_from = 0;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.chars() {
+        let found = (search_from..target.len()).find(|i| target[*i] == q)?;
+
+        score += 10;
+        if found == 0 {
+            score += 8;
+        }
+        if last_match.map_or(false, |last| last + 1 == found) {
+            score += 5;
+        }
+
+        last_match = Some(found);
+        search_from = found + 1;
+    }
+
+    Some(score)
+}
+
+///
+/// # ショートカットの一覧
+/// 入力された文字で操作を絞り込み、選ばれている操作を返す
+///
+/// ## matches
+/// 絞り込んだ操作のentriesでの位置。点数の高い順
+///
+pub struct ShortcutPalette<T: Copy> {
+    entries: Vec<ShortcutEntry<T>>,
+    query: String,
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl<T: Copy> ShortcutPalette<T> {
+    pub fn new(entries: Vec<ShortcutEntry<T>>) -> Self {
+        let mut palette = ShortcutPalette {
+            entries: entries,
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+        };
+        palette.refresh_matches();
+
+        palette
+    }
+
+    fn score(&self, entry: &ShortcutEntry<T>) -> Option<u32> {
+        if self.query.is_empty() {
+            return Some(0);
+        }
+
+        let query = kana_to_romaji(&self.query);
+        let by_reading = fuzzy_score(&query, &kana_to_romaji(&entry.reading));
+        let by_label = fuzzy_score(&self.query, &entry.label);
+
+        by_reading.max(by_label)
+    }
+
+    fn refresh_matches(&mut self) {
+        let mut scored: Vec<(usize, u32)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| self.score(entry).map(|score| (index, score)))
+            .collect();
+        scored.sort_by(|a, b| b.1.cmp(&a.1));
+
+        self.matches = scored.into_iter().map(|(index, _)| index).collect();
+        self.selected = 0;
+    }
+
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.refresh_matches();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.query.pop();
+        self.refresh_matches();
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+        }
+    }
+
+    pub fn get_selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_command(&self) -> Option<T> {
+        self.matches
+            .get(self.selected)
+            .map(|index| self.entries[*index].command)
+    }
+
+    ///
+    /// 絞り込んだ操作を点数の高い順に返す
+    ///
+    pub fn matched_entries(&self) -> Vec<&ShortcutEntry<T>> {
+        self.matches
+            .iter()
+            .map(|index| &self.entries[*index])
+            .collect()
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::mpsc;
+use std::thread;
+
+///
+/// # 行に割る前のテキストの一塊
+///
+/// ## text
+/// 塊の全文
+///
+/// ## length
+/// 今表示している文字数
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutSegment {
+    pub text: String,
+    pub length: usize,
+}
+
+impl LayoutSegment {
+    pub fn new(text: &str, length: usize) -> Self {
+        LayoutSegment {
+            text: text.to_string(),
+            length: length,
+        }
+    }
+}
+
+///
+/// # 割り終えた一行
+///
+/// ## segment
+/// この行が、何番目の塊から出たか
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaidOutLine {
+    pub segment: usize,
+    pub text: String,
+}
+
+///
+/// textの頭から切り出す。今までのScenarioTextSegment::sliceと同じく、length + 1文字まで含める
+///
+pub fn slice_chars(text: &str, length: usize) -> &str {
+    match text.char_indices().nth(length + 1) {
+        Some((bytes, _)) => &text[..bytes],
+        None => text,
+    }
+}
+
+///
+/// 各塊を表示している文字数で切り出し、改行で行に割る
+///
+pub fn layout_lines(segments: &[LayoutSegment]) -> Vec<LaidOutLine> {
+    let mut lines = Vec::new();
+
+    for (index, segment) in segments.iter().enumerate() {
+        for line in slice_chars(&segment.text, segment.length).lines() {
+            lines.push(LaidOutLine {
+                segment: index,
+                text: line.to_string(),
+            });
+        }
+    }
+
+    lines
+}
+
+///
+/// newの各行が、oldの同じ位置の行とそのまま同じか。同じ行は作り直さずに使い回せる
+///
+pub fn reusable_lines(old: &[LaidOutLine], new: &[LaidOutLine]) -> Vec<bool> {
+    new.iter()
+        .enumerate()
+        .map(|(index, line)| old.get(index) == Some(line))
+        .collect()
+}
+
+struct TextLayoutJob {
+    serial: u64,
+    segments: Vec<LayoutSegment>,
+}
+
+///
+/// # 別のスレッドで割り終えた行
+///
+/// ## serial
+/// submitが返した番号
+///
+pub struct TextLayoutResult {
+    pub serial: u64,
+    pub lines: Vec<LaidOutLine>,
+}
+
+///
+/// # 行割りを受け持つスレッド
+/// 長い頁を毎フレーム割り直すと引っ掛かるので、別のスレッドで割り、次のフレーム以降で受け取る
+///
+/// ## last_segments
+/// 最後に頼んだ中身。同じ中身は二度頼まない
+///
+pub struct TextLayoutWorker {
+    sender: mpsc::Sender<TextLayoutJob>,
+    receiver: mpsc::Receiver<TextLayoutResult>,
+    next_serial: u64,
+    last_segments: Option<Vec<LayoutSegment>>,
+}
+
+impl TextLayoutWorker {
+    pub fn spawn() -> Self {
+        let (job_sender, job_receiver) = mpsc::channel::<TextLayoutJob>();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            // 頼み手が居なくなれば、受け取りに失敗して終わる
+            for job in job_receiver.iter() {
+                let result = TextLayoutResult {
+                    serial: job.serial,
+                    lines: layout_lines(&job.segments),
+                };
+
+                if result_sender.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+
+        TextLayoutWorker {
+            sender: job_sender,
+            receiver: result_receiver,
+            next_serial: 0,
+            last_segments: None,
+        }
+    }
+
+    ///
+    /// 行割りを頼み、その番号を返す。前に頼んだ中身と同じならNone
+    ///
+    pub fn submit(&mut self, segments: Vec<LayoutSegment>) -> Option<u64> {
+        if self.last_segments.as_ref() == Some(&segments) {
+            return None;
+        }
+
+        let serial = self.next_serial;
+        self.next_serial += 1;
+        self.last_segments = Some(segments.clone());
+
+        self.sender
+            .send(TextLayoutJob {
+                serial: serial,
+                segments: segments,
+            })
+            .ok()?;
+
+        Some(serial)
+    }
+
+    ///
+    /// 次に頼むときは、中身が同じでも割り直させる
+    ///
+    pub fn forget_last(&mut self) {
+        self.last_segments = None;
+    }
+
+    ///
+    /// 前に呼んでから割り終わった結果を、頼んだ順に取り出す
+    ///
+    pub fn poll(&self) -> Vec<TextLayoutResult> {
+        self.receiver.try_iter().collect()
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// # ゲームの進む速さ
+/// Clockで動くものだけに効く。入力や画面の操作は速さに関わらず毎フレーム受け付ける
+///
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TimeScale {
+    Half,
+    Normal,
+    Double,
+}
+
+impl TimeScale {
+    ///
+    /// 一フレームで進める量。半フレームを1として数える
+    ///
+    fn half_steps(&self) -> u32 {
+        match self {
+            TimeScale::Half => 1,
+            TimeScale::Normal => 2,
+            TimeScale::Double => 4,
+        }
+    }
+
+    pub fn slower(&self) -> Self {
+        match self {
+            TimeScale::Double => TimeScale::Normal,
+            _ => TimeScale::Half,
+        }
+    }
+
+    pub fn faster(&self) -> Self {
+        match self {
+            TimeScale::Half => TimeScale::Normal,
+            _ => TimeScale::Double,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "0.5" | "0.5x" => Some(TimeScale::Half),
+            "1" | "1x" => Some(TimeScale::Normal),
+            "2" | "2x" => Some(TimeScale::Double),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeScale::Half => "0.5x",
+            TimeScale::Normal => "1x",
+            TimeScale::Double => "2x",
+        }
+    }
+}
+
+///
+/// # 速さを変える指示
+/// デバッグ用のキーと、コンソールに打った行から作る
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeScaleCommand {
+    Set(TimeScale),
+    Slower,
+    Faster,
+    TogglePause,
+    Step,
+}
+
+impl TimeScaleCommand {
+    ///
+    /// "speed 0.5", "speed 2x", "pause", "step" のような行を読む
+    ///
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+
+        match (words.next()?, words.next(), words.next()) {
+            ("speed", Some("slower"), None) => Some(TimeScaleCommand::Slower),
+            ("speed", Some("faster"), None) => Some(TimeScaleCommand::Faster),
+            ("speed", Some(scale), None) => TimeScale::parse(scale).map(TimeScaleCommand::Set),
+            ("pause", None, None) => Some(TimeScaleCommand::TogglePause),
+            ("step", None, None) => Some(TimeScaleCommand::Step),
+            _ => None,
+        }
+    }
+}
+
+///
+/// # フレームごとに何回シーンを進めるかを決める
+///
+/// ## remainder
+/// 0.5倍のときに持ち越した半フレーム
+///
+/// ## pending_steps
+/// 止めている間に、一フレームずつ進めるよう頼まれた回数
+///
+pub struct SimulationClock {
+    scale: TimeScale,
+    paused: bool,
+    remainder: u32,
+    pending_steps: u32,
+}
+
+impl SimulationClock {
+    pub fn new(scale: TimeScale) -> Self {
+        SimulationClock {
+            scale: scale,
+            paused: false,
+            remainder: 0,
+            pending_steps: 0,
+        }
+    }
+
+    pub fn get_scale(&self) -> TimeScale {
+        self.scale
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    ///
+    /// 指示を反映する。一フレーム進める指示は、動いていれば先に止める
+    ///
+    pub fn apply(&mut self, command: TimeScaleCommand) {
+        match command {
+            TimeScaleCommand::Set(scale) => self.scale = scale,
+            TimeScaleCommand::Slower => self.scale = self.scale.slower(),
+            TimeScaleCommand::Faster => self.scale = self.scale.faster(),
+            TimeScaleCommand::TogglePause => {
+                self.paused = !self.paused;
+                self.pending_steps = 0;
+            }
+            TimeScaleCommand::Step => {
+                self.paused = true;
+                self.pending_steps += 1;
+            }
+        }
+    }
+
+    ///
+    /// このフレームでシーンを進める回数
+    ///
+    pub fn steps_this_frame(&mut self) -> u32 {
+        if self.paused {
+            let steps = self.pending_steps;
+            self.pending_steps = 0;
+            return steps;
+        }
+
+        self.remainder += self.scale.half_steps();
+        let steps = self.remainder / 2;
+        self.remainder %= 2;
+
+        steps
+    }
+}
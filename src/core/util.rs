@@ -1,13 +1,19 @@
 use std::cell::RefCell;
 use std::io::Read;
+
+use rand::Rng;
 use torifune::numeric;
 
 pub fn year_to_season(year: i64) -> i64 {
     year
 }
 
-pub fn random_select<T>(mut i: std::slice::Iter<T>) -> Option<&T> {
-    i.nth(rand::random::<usize>() % i.len())
+pub fn random_select<'a, T, R: Rng>(rng: &mut R, mut i: std::slice::Iter<'a, T>) -> Option<&'a T> {
+    if i.len() == 0 {
+        return None;
+    }
+    let index = rng.gen_range(0, i.len());
+    i.nth(index)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -157,13 +163,13 @@ pub fn get_unique_id() -> u64 {
     })
 }
 
-pub fn random_point_in_rect(rect: numeric::Rect) -> numeric::Point2f {
+pub fn random_point_in_rect<R: Rng>(rng: &mut R, rect: numeric::Rect) -> numeric::Point2f {
     let begin_x = rect.left() as usize;
     let begin_y = rect.top() as usize;
 
     numeric::Point2f::new(
-        (begin_x + rand::random::<usize>() % rect.w as usize) as f32,
-        (begin_y + rand::random::<usize>() % rect.h as usize) as f32,
+        (begin_x + rng.gen::<usize>() % rect.w as usize) as f32,
+        (begin_y + rng.gen::<usize>() % rect.h as usize) as f32,
     )
 }
 
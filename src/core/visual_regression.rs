@@ -0,0 +1,316 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use ggez::conf::{WindowMode, WindowSetup};
+use ggez::graphics as ggraphics;
+
+use super::bench_stats::FrameTimeSamples;
+use super::{
+    input_queue, preferences, resource_locator, GameConfig, GameMode, GameResource,
+    PermanentSaveData, ProcessUtility, SavableData, SuzuContext, WINDOW_SIZE_X, WINDOW_SIZE_Y,
+};
+use crate::log_info;
+use crate::scene::DrawRequest;
+
+///
+/// 参照画像を置くディレクトリ。CARGO_MANIFEST_DIRからの相対パス
+///
+pub const REFERENCE_DIR: &str = "tests/visual_reference";
+
+///
+/// この環境変数が設定されていると、比較せずに参照画像を書き直す
+///
+pub const UPDATE_REFERENCE_ENV: &str = "SUZU_UPDATE_REFERENCE";
+
+const RGBA_IMAGE_MAGIC: &[u8; 4] = b"SZRG";
+
+///
+/// # RGBAの画素列
+/// 参照画像は、先頭にマジックナンバーと幅・高さ(リトルエンディアンのu32)を付けて保存する
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        RgbaImage {
+            width: width,
+            height: height,
+            pixels: pixels,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + self.pixels.len());
+        bytes.extend_from_slice(RGBA_IMAGE_MAGIC);
+        bytes.extend_from_slice(&self.width.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&self.pixels);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 12 || &bytes[0..4] != RGBA_IMAGE_MAGIC {
+            return None;
+        }
+
+        let mut width = [0; 4];
+        let mut height = [0; 4];
+        width.copy_from_slice(&bytes[4..8]);
+        height.copy_from_slice(&bytes[8..12]);
+
+        let image = RgbaImage::new(
+            u32::from_le_bytes(width),
+            u32::from_le_bytes(height),
+            bytes[12..].to_vec(),
+        );
+
+        if image.pixels.len() != (image.width * image.height * 4) as usize {
+            return None;
+        }
+
+        Some(image)
+    }
+}
+
+///
+/// # 二枚の画像の差
+///
+/// ## differing_pixels
+/// どれかのチャンネルがchannel_toleranceより大きくずれた画素の数
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageDiff {
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    pub max_channel_diff: u8,
+}
+
+impl ImageDiff {
+    ///
+    /// ずれた画素の割合がpixel_ratio_tolerance以下ならtrue
+    ///
+    pub fn is_within(&self, pixel_ratio_tolerance: f32) -> bool {
+        if self.total_pixels == 0 {
+            return true;
+        }
+
+        (self.differing_pixels as f32 / self.total_pixels as f32) <= pixel_ratio_tolerance
+    }
+}
+
+///
+/// 大きさが違う場合はNoneを返す
+///
+pub fn compare_rgba(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    channel_tolerance: u8,
+) -> Option<ImageDiff> {
+    if expected.width != actual.width || expected.height != actual.height {
+        return None;
+    }
+
+    let mut differing_pixels = 0;
+    let mut max_channel_diff = 0;
+
+    for (e, a) in expected.pixels.chunks(4).zip(actual.pixels.chunks(4)) {
+        let pixel_diff = e
+            .iter()
+            .zip(a.iter())
+            .map(|(e, a)| (*e as i16 - *a as i16).abs() as u8)
+            .max()
+            .unwrap_or(0);
+
+        max_channel_diff = max_channel_diff.max(pixel_diff);
+        if pixel_diff > channel_tolerance {
+            differing_pixels += 1;
+        }
+    }
+
+    Some(ImageDiff {
+        differing_pixels: differing_pixels,
+        total_pixels: (expected.width * expected.height) as usize,
+        max_channel_diff: max_channel_diff,
+    })
+}
+
+fn reference_path(name: &str) -> PathBuf {
+    let mut path = match env::var("CARGO_MANIFEST_DIR") {
+        Ok(manifest_dir) => PathBuf::from(manifest_dir),
+        Err(_) => PathBuf::from("."),
+    };
+    path.push(REFERENCE_DIR);
+    path.push(format!("{}.rgba", name));
+    path
+}
+
+///
+/// # 見た目の回帰テスト用の環境
+/// 非表示のウィンドウを作り、シーンや部品を画面外のCanvasに描画して参照画像と比べる
+/// セーブデータは読まず、毎回新しいゲームの状態から始める
+///
+pub struct VisualRegressionHarness {
+    context: ggez::Context,
+    _event_loop: ggez::event::EventLoop<()>,
+    resource: GameResource,
+    savable_data: Option<SavableData>,
+    config: GameConfig,
+    preferences: preferences::Preferences,
+    input_history: input_queue::InputHistory,
+    permanent_save_data: PermanentSaveData,
+    redraw_request: DrawRequest,
+}
+
+impl VisualRegressionHarness {
+    pub fn new() -> Self {
+        let resource_dir = resource_locator::resource_dir();
+
+        let (mut context, event_loop) =
+            ggez::ContextBuilder::new("suzu_visual_regression", "akichi")
+                .window_setup(WindowSetup::default().title("visual regression"))
+                .window_mode(WindowMode {
+                    visible: false,
+                    ..WindowMode::default().dimensions(WINDOW_SIZE_X as f32, WINDOW_SIZE_Y as f32)
+                })
+                .add_resource_path(resource_dir)
+                .build()
+                .unwrap();
+
+        let mut resource = GameResource::new(&mut context, "/game_data.toml".to_owned());
+        let config = GameConfig::new_from_toml(&mut context, "/default_game_config.toml");
+        let savable_data = SavableData::new(&mut resource, GameMode::story());
+
+        VisualRegressionHarness {
+            context: context,
+            _event_loop: event_loop,
+            resource: resource,
+            savable_data: Some(savable_data),
+            config: config,
+            preferences: preferences::Preferences::new(),
+            input_history: input_queue::InputHistory::new(),
+            permanent_save_data: PermanentSaveData::new_empty(),
+            redraw_request: DrawRequest::InitDraw,
+        }
+    }
+
+    pub fn with_context<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'a> FnOnce(&mut SuzuContext<'a>) -> R,
+    {
+        f(&mut SuzuContext {
+            context: &mut self.context,
+            resource: &mut self.resource,
+            savable_data: &mut self.savable_data,
+            config: &mut self.config,
+            preferences: &mut self.preferences,
+            input_history: &self.input_history,
+            process_utility: ProcessUtility {
+                redraw_request: &mut self.redraw_request,
+            },
+            permanent_save_data: &mut self.permanent_save_data,
+        })
+    }
+
+    ///
+    /// fで描画したものを、ウィンドウと同じ大きさの画面外のCanvasから読み出す
+    ///
+    pub fn render<F>(&mut self, f: F) -> ggez::GameResult<RgbaImage>
+    where
+        F: FnOnce(&mut ggez::Context) -> ggez::GameResult<()>,
+    {
+        let ctx = &mut self.context;
+        let canvas = ggraphics::Canvas::with_window_size(ctx)?;
+
+        ggraphics::set_canvas(ctx, Some(&canvas));
+        ggraphics::clear(ctx, ggraphics::Color::from_rgba_u32(0xffffffff));
+        let result = f(ctx);
+        ggraphics::set_canvas(ctx, None);
+        result?;
+
+        let image = canvas.image();
+        Ok(RgbaImage::new(
+            image.width() as u32,
+            image.height() as u32,
+            image.to_rgba8(ctx)?,
+        ))
+    }
+
+    ///
+    /// fで何フレームも画面外のCanvasへ描き、フレームごとの時間を返す
+    /// 画素は読み出さないので、計った時間に読み出しの分は入らない
+    ///
+    pub fn render_frames<F>(
+        &mut self,
+        frames: usize,
+        mut f: F,
+    ) -> ggez::GameResult<FrameTimeSamples>
+    where
+        F: FnMut(&mut ggez::Context, usize) -> ggez::GameResult<()>,
+    {
+        let ctx = &mut self.context;
+        let canvas = ggraphics::Canvas::with_window_size(ctx)?;
+        let mut samples = FrameTimeSamples::new();
+
+        ggraphics::set_canvas(ctx, Some(&canvas));
+        for frame in 0..frames {
+            let start = Instant::now();
+            ggraphics::clear(ctx, ggraphics::Color::from_rgba_u32(0xffffffff));
+            if let Err(e) = f(ctx, frame) {
+                ggraphics::set_canvas(ctx, None);
+                return Err(e);
+            }
+            samples.push(start.elapsed());
+        }
+        ggraphics::set_canvas(ctx, None);
+
+        Ok(samples)
+    }
+
+    ///
+    /// 参照画像と比べる。参照画像が無いか、UPDATE_REFERENCE_ENVが設定されている場合は書き出す
+    ///
+    pub fn check_reference(
+        &self,
+        name: &str,
+        actual: &RgbaImage,
+        channel_tolerance: u8,
+        pixel_ratio_tolerance: f32,
+    ) -> Result<ImageDiff, String> {
+        let path = reference_path(name);
+
+        let reference = fs::read(&path)
+            .ok()
+            .and_then(|bytes| RgbaImage::from_bytes(&bytes));
+
+        let reference = match reference {
+            Some(reference) if env::var(UPDATE_REFERENCE_ENV).is_err() => reference,
+            _ => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                fs::write(&path, actual.to_bytes()).map_err(|e| e.to_string())?;
+                log_info!("wrote reference image: {}", path.display());
+                actual.clone()
+            }
+        };
+
+        match compare_rgba(&reference, actual, channel_tolerance) {
+            Some(diff) if diff.is_within(pixel_ratio_tolerance) => Ok(diff),
+            Some(diff) => Err(format!(
+                "{}: {} of {} pixels differ (max channel diff {})",
+                name, diff.differing_pixels, diff.total_pixels, diff.max_channel_diff
+            )),
+            None => Err(format!(
+                "{}: size mismatch, reference {}x{}, actual {}x{}",
+                name, reference.width, reference.height, actual.width, actual.height
+            )),
+        }
+    }
+}
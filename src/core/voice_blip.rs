@@ -0,0 +1,142 @@
+use torifune::core::Clock;
+
+///
+/// 吹き出しの台詞を読む速さ。一フレームに進む文字数
+///
+pub const BALLOON_READ_FPC: f32 = 0.5;
+
+///
+/// 一つの台詞で鳴らす声の数の上限。長い台詞で鳴りっぱなしにならないように
+///
+pub const MAX_BLIPS_PER_LINE: usize = 24;
+
+///
+/// # 声の型
+/// 型ごとに高さと、何文字ごとに鳴らすかが違う
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceArchetype {
+    Kosuzu,
+    Child,
+    Adult,
+    Elder,
+    Youkai,
+}
+
+impl VoiceArchetype {
+    pub fn pitch(&self) -> f32 {
+        match self {
+            VoiceArchetype::Kosuzu => 1.3,
+            VoiceArchetype::Child => 1.5,
+            VoiceArchetype::Adult => 1.0,
+            VoiceArchetype::Elder => 0.8,
+            VoiceArchetype::Youkai => 0.65,
+        }
+    }
+
+    ///
+    /// 何文字ごとに声を鳴らすか
+    ///
+    pub fn chars_per_blip(&self) -> usize {
+        match self {
+            VoiceArchetype::Kosuzu | VoiceArchetype::Child => 2,
+            VoiceArchetype::Adult | VoiceArchetype::Youkai => 3,
+            VoiceArchetype::Elder => 4,
+        }
+    }
+
+    ///
+    /// 名前から声の型を決める。同じ名前の客は、いつも同じ声で話す
+    ///
+    pub fn from_name(name: &str) -> Self {
+        if name == "小鈴" || name == "本居小鈴" {
+            return VoiceArchetype::Kosuzu;
+        }
+
+        let hash = name.chars().fold(0_u32, |hash, c| {
+            hash.wrapping_mul(31).wrapping_add(c as u32)
+        });
+
+        match hash % 4 {
+            0 => VoiceArchetype::Child,
+            1 => VoiceArchetype::Adult,
+            2 => VoiceArchetype::Elder,
+            _ => VoiceArchetype::Youkai,
+        }
+    }
+}
+
+///
+/// 声を鳴らさない文字。空白、句読点、括弧など
+///
+pub fn is_silent_char(c: char) -> bool {
+    c.is_whitespace() || "、。，．！？!?…・「」『』（）()ー〜".contains(c)
+}
+
+///
+/// # 文字送りに合わせた声
+/// 新しく出た文字を数え、決まった文字数ごとに一度鳴らす。台詞の最初の文字では必ず鳴る
+///
+/// ## pending
+/// 最後に鳴らしてから出た、声を鳴らす文字の数
+///
+pub struct BlipCounter {
+    archetype: VoiceArchetype,
+    pending: usize,
+}
+
+impl BlipCounter {
+    pub fn new(archetype: VoiceArchetype) -> Self {
+        BlipCounter {
+            archetype: archetype,
+            pending: archetype.chars_per_blip() - 1,
+        }
+    }
+
+    pub fn get_archetype(&self) -> VoiceArchetype {
+        self.archetype
+    }
+
+    ///
+    /// 新しい台詞に入るときに呼ぶ
+    ///
+    pub fn reset(&mut self) {
+        self.pending = self.archetype.chars_per_blip() - 1;
+    }
+
+    pub fn feed_char(&mut self, c: char) -> bool {
+        if is_silent_char(c) {
+            return false;
+        }
+
+        self.pending += 1;
+        if self.pending >= self.archetype.chars_per_blip() {
+            self.pending = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// 新しく表示された文字を渡す。一フレームに鳴らすのは一度までなので、鳴らすかどうかだけを返す
+    ///
+    pub fn feed(&mut self, text: &str) -> bool {
+        text.chars()
+            .fold(false, |blip, c| self.feed_char(c) || blip)
+    }
+}
+
+///
+/// 吹き出しのように台詞が一度に出るときは、読む速さで文字が出たものとして声を鳴らす時刻を並べる
+///
+pub fn balloon_blip_schedule(text: &str, archetype: VoiceArchetype) -> Vec<Clock> {
+    let mut counter = BlipCounter::new(archetype);
+
+    text.chars()
+        .enumerate()
+        .filter(|(_, c)| counter.feed_char(*c))
+        .map(|(index, _)| (index as f32 / BALLOON_READ_FPC) as Clock)
+        .take(MAX_BLIPS_PER_LINE)
+        .collect()
+}
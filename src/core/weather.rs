@@ -0,0 +1,183 @@
+use torifune::core::Clock;
+use torifune::numeric;
+
+use super::GensoDate;
+
+///
+/// 雨の降る日の割合
+///
+pub const RAINY_DAY_RATE: f32 = 0.25;
+
+///
+/// 曇る日の割合。雨の日の分を除いた残りから数える
+///
+pub const CLOUDY_DAY_RATE: f32 = 0.25;
+
+///
+/// 傘を傘立てに置いた客から、雫が垂れ続ける時間
+///
+pub const DRIP_DURATION: Clock = 900;
+
+///
+/// 雫の垂れている客が、一分ごとに床に水溜まりを作る割合
+///
+pub const DRIP_RATE: f32 = 0.08;
+
+///
+/// 水溜まりの上を歩く速さの倍率
+///
+pub const PUDDLE_SPEED_RATE: f32 = 0.5;
+
+///
+/// 店の床に同時にできる水溜まりの数
+///
+pub const PUDDLE_MAX: usize = 8;
+
+///
+/// 雑巾の届く距離（タイル）
+///
+pub const MOP_REACH: u32 = 2;
+
+///
+/// 水溜まりを一つ拭くたびに溜まる疲労
+///
+pub const MOP_FATIGUE: f32 = 1.5;
+
+///
+/// 戸口の傘立てに立てられる傘の数
+///
+pub const UMBRELLA_STAND_CAPACITY: u32 = 4;
+
+///
+/// # その日の天気
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    Sunny,
+    Cloudy,
+    Rainy,
+}
+
+impl Weather {
+    ///
+    /// 日付から天気を決める。同じ日なら何度読み込み直しても同じ天気になる
+    ///
+    pub fn of_day(date: &GensoDate) -> Self {
+        let seed = (date.season * 400) + (date.month as u32 * 32) + date.day as u32;
+        let roll = (seed.wrapping_mul(2654435761) >> 16) % 100;
+
+        if (roll as f32) < RAINY_DAY_RATE * 100.0 {
+            Weather::Rainy
+        } else if (roll as f32) < (RAINY_DAY_RATE + CLOUDY_DAY_RATE) * 100.0 {
+            Weather::Cloudy
+        } else {
+            Weather::Sunny
+        }
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Weather::Sunny => "晴レ",
+            Weather::Cloudy => "曇リ",
+            Weather::Rainy => "雨",
+        }
+    }
+
+    pub fn is_rainy(&self) -> bool {
+        *self == Weather::Rainy
+    }
+}
+
+///
+/// # 戸口の傘立て
+/// 一杯になると、後から来た客は傘を持ったまま店に入る
+///
+#[derive(Debug, Clone)]
+pub struct UmbrellaStand {
+    count: u32,
+}
+
+impl UmbrellaStand {
+    pub fn new() -> Self {
+        UmbrellaStand { count: 0 }
+    }
+
+    pub fn get_count(&self) -> u32 {
+        self.count
+    }
+
+    ///
+    /// 傘を立てる。一杯で立てられなければfalseを返す
+    ///
+    pub fn put(&mut self) -> bool {
+        if self.count >= UMBRELLA_STAND_CAPACITY {
+            return false;
+        }
+
+        self.count += 1;
+        true
+    }
+
+    pub fn take(&mut self) {
+        self.count = self.count.saturating_sub(1);
+    }
+}
+
+///
+/// # 床の水溜まり
+/// タイルごとに一つまで
+///
+#[derive(Debug, Clone)]
+pub struct PuddleSet {
+    puddles: Vec<numeric::Vector2u>,
+}
+
+impl PuddleSet {
+    pub fn new() -> Self {
+        PuddleSet {
+            puddles: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, tile: numeric::Vector2u) -> bool {
+        self.puddles.contains(&tile)
+    }
+
+    pub fn len(&self) -> usize {
+        self.puddles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.puddles.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<numeric::Vector2u> {
+        self.puddles.iter()
+    }
+
+    ///
+    /// 水溜まりを作る。既にあるタイルや、床が水浸しのときはfalseを返す
+    ///
+    pub fn add(&mut self, tile: numeric::Vector2u) -> bool {
+        if self.puddles.len() >= PUDDLE_MAX || self.contains(tile) {
+            return false;
+        }
+
+        self.puddles.push(tile);
+        true
+    }
+
+    ///
+    /// player_tileから雑巾の届くtileの水溜まりを拭く。拭けたときはtrueを返す
+    ///
+    pub fn mop(&mut self, tile: numeric::Vector2u, player_tile: numeric::Vector2u) -> bool {
+        let distance = (tile.x as i32 - player_tile.x as i32).abs()
+            + (tile.y as i32 - player_tile.y as i32).abs();
+        if distance as u32 > MOP_REACH || !self.contains(tile) {
+            return false;
+        }
+
+        self.puddles.retain(|puddle| *puddle != tile);
+        true
+    }
+}
@@ -4,42 +4,33 @@ extern crate serde;
 extern crate toml;
 
 use ggez::*;
-use std::env;
-use std::path;
 
-use ggez::conf::{FullscreenType, WindowMode};
 use suzu::core::*;
 
 pub fn main() {
-    let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-        let mut path = path::PathBuf::from(manifest_dir);
-        path.push("resources");
-        path
-    } else {
-        path::PathBuf::from("resources")
+    let resource_dir = resource_locator::resource_dir();
+
+    // settings.tomlがあればその大きさで、無ければ前回閉じたときの大きさで窓を開く。位置はState::newで戻す
+    let settings = config::Settings::load();
+    let window_placement = preferences::Preferences::load().and_then(|p| p.get_window_placement());
+    let (width, height) = match (settings.as_ref(), window_placement) {
+        (Some(settings), _) => settings.resolution,
+        (None, Some(placement)) if !placement.fullscreen => (placement.width, placement.height),
+        _ => (WINDOW_SIZE_X as f32, WINDOW_SIZE_Y as f32),
     };
+    let settings = settings.unwrap_or_default();
 
     let (mut ctx, event_loop) = ContextBuilder::new("suzu", "akichi")
         .window_setup(
-            conf::WindowSetup::default()
-                .icon("/tile_textures/menu_art3.png")
-                .title("電氣貸本屋")
-                .samples(ggez::conf::NumSamples::Four),
+            settings.apply_to_window_setup(
+                conf::WindowSetup::default()
+                    .icon("/tile_textures/menu_art3.png")
+                    .title("電氣貸本屋")
+                    .samples(ggez::conf::NumSamples::Four),
+            ),
         )
         .add_resource_path(resource_dir)
-        .window_mode(WindowMode {
-            width: 1366.0,
-            height: 768.0,
-            maximized: false,
-            fullscreen_type: FullscreenType::Windowed,
-            borderless: false,
-            min_width: 0.0,
-            max_width: 0.0,
-            min_height: 0.0,
-            max_height: 0.0,
-            visible: true,
-            resizable: false,
-        })
+        .window_mode(settings.window_mode().dimensions(width, height))
         .build()
         .unwrap();
 
@@ -1,12 +1,30 @@
+pub mod album_object;
+pub mod book_fair_object;
+pub mod cell_alignment;
 pub mod character_factory;
 pub mod collision;
+pub mod color_grading_layer;
+pub mod conversation_focus;
+pub mod credits_object;
+pub mod customer_emotion;
+pub mod desk_camera;
+pub mod desk_rotation;
+pub mod desk_shortcut;
+pub mod drop_target;
 pub mod effect;
 pub mod effect_object;
 pub mod end_object;
+pub mod highlight;
+pub mod ledger_audit_object;
+pub mod log_viewer;
+#[cfg(feature = "map_editor")]
+pub mod map_editor;
 pub mod map_object;
 pub mod move_fn;
 pub mod notify;
+pub mod pause;
 pub mod save_scene_object;
+pub mod score_attack_object;
 pub mod scenario;
 pub mod scenario_object;
 pub mod shop_object;
@@ -14,6 +32,8 @@ pub mod simulation_ui;
 pub mod task_object;
 pub mod task_result_object;
 pub mod title_object;
+pub mod tooltip;
+pub mod tween;
 pub mod util_object;
 
 use ggez::graphics as ggraphics;
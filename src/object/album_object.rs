@@ -0,0 +1,613 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::shape::MeshShape;
+use torifune::graphics::object::sub_screen;
+use torifune::graphics::object::sub_screen::SubScreen;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+use crate::core::album::{AlbumPhoto, PhotoAlbum};
+use crate::core::{FontID, SoundID, SuzuContext, TextureID, TileBatchTextureID};
+use crate::flush_delay_event_and_redraw_check;
+use crate::object::util_object::*;
+use crate::scene::{DelayEventList, DrawRequest};
+
+///
+/// # 写真帖に貼られた一枚
+/// 頁を開いたときに作られるので、寸劇の一枚のテクスチャもそのときに初めて読み込まれる
+///
+/// ## chart_bars
+/// 週の集計の棒。週の集計でなければ空
+///
+pub struct AlbumPhotoFrame {
+    rect: numeric::Rect,
+    caption: UniText,
+    body_text: Vec<UniText>,
+    thumbnail: Option<UniTexture>,
+    chart_bars: Vec<numeric::Rect>,
+}
+
+impl AlbumPhotoFrame {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, photo: &AlbumPhoto, rect: numeric::Rect) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(22.0, 22.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let caption = UniText::new(
+            photo.caption(),
+            numeric::Point2f::new(rect.x + 14.0, rect.bottom() - 36.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info.clone(),
+        );
+
+        let mut body_lines = Vec::new();
+        let mut thumbnail = None;
+        let mut chart_bars = Vec::new();
+
+        match photo {
+            AlbumPhoto::DayReceipt {
+                completed_requests,
+                earned_money,
+                eval,
+                ..
+            } => {
+                body_lines.push(format!(
+                    "受付　{}件",
+                    ctx.number_format().number(*completed_requests as u64)
+                ));
+                body_lines.push(format!("稼ギ　{}円", earned_money));
+                body_lines.push(format!("評価　{:.1}", eval));
+            }
+            AlbumPhoto::WeeklyChart { daily_money, .. } => {
+                let max_money = daily_money.iter().cloned().max().unwrap_or(0).max(1);
+                let chart_height = rect.h - 90.0;
+                let slot_width = (rect.w - 40.0) / daily_money.len().max(1) as f32;
+
+                for (index, money) in daily_money.iter().enumerate() {
+                    let height = chart_height * (*money).max(0) as f32 / max_money as f32;
+                    chart_bars.push(numeric::Rect::new(
+                        rect.x + 20.0 + (slot_width * index as f32) + (slot_width * 0.2),
+                        rect.y + 20.0 + chart_height - height,
+                        slot_width * 0.6,
+                        height,
+                    ));
+                }
+
+                body_lines.push(format!("計　{}円", daily_money.iter().sum::<i32>()));
+            }
+            AlbumPhoto::CutsceneStill { texture, .. } => match TextureID::from_u32(*texture) {
+                Some(texture_id) => {
+                    let mut still = UniTexture::new(
+                        ctx.ref_texture(texture_id),
+                        numeric::Point2f::new(rect.x + 10.0, rect.y + 10.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                    );
+                    still.fit_scale(
+                        ctx.context,
+                        numeric::Vector2f::new(rect.w - 20.0, rect.h - 60.0),
+                    );
+                    thumbnail = Some(still);
+                }
+                None => body_lines.push("色褪セタ一枚".to_string()),
+            },
+        }
+
+        // 週の集計では、合計を棒の上に重ねないよう右上に寄せる
+        let body_origin = if chart_bars.is_empty() {
+            numeric::Point2f::new(rect.x + 24.0, rect.y + 24.0)
+        } else {
+            numeric::Point2f::new(rect.right() - 180.0, rect.y + 8.0)
+        };
+
+        let body_text = body_lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, line)| {
+                UniText::new(
+                    line,
+                    numeric::Point2f::new(body_origin.x, body_origin.y + (index as f32 * 36.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )
+            })
+            .collect();
+
+        AlbumPhotoFrame {
+            rect: rect,
+            caption: caption,
+            body_text: body_text,
+            thumbnail: thumbnail,
+            chart_bars: chart_bars,
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xf6eee0ff),
+        )
+        .add_to_builder(&mut builder);
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::stroke(2.0),
+            ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+        )
+        .add_to_builder(&mut builder);
+
+        for bar in self.chart_bars.iter() {
+            shape::Rectangle::new(
+                *bar,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0x8c3a3aff),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        if let Some(thumbnail) = self.thumbnail.as_mut() {
+            thumbnail.draw(ctx)?;
+        }
+
+        for text in self.body_text.iter_mut() {
+            text.draw(ctx)?;
+        }
+
+        self.caption.draw(ctx)
+    }
+}
+
+///
+/// # 写真帖
+/// セーブデータごとの写真帖を、記録簿と同じように頁を繰って眺める
+///
+/// ## albums
+/// 各セーブデータの写真帖。セーブデータの無い枠はNone
+///
+/// ## page_frames
+/// 今開いている頁の写真だけを持つ
+///
+pub struct PhotoAlbumBook {
+    canvas: SubScreen,
+    size: numeric::Vector2f,
+    background: UniTexture,
+    appearance_frame: TileBatchFrame,
+    title_text: UniText,
+    page_text: UniText,
+    empty_text: Option<UniText>,
+    profile_buttons: Vec<SelectButton>,
+    albums: Vec<Option<PhotoAlbum>>,
+    current_profile: usize,
+    current_page: usize,
+    page_frames: Vec<AlbumPhotoFrame>,
+    next_page_ope: UniTexture,
+    prev_page_ope: UniTexture,
+    next10_button: SelectButton,
+    prev10_button: SelectButton,
+    page_scroll_event_list: DelayEventList<Self>,
+    redraw_request: DrawRequest,
+}
+
+impl PhotoAlbumBook {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        window_rect: numeric::Rect,
+        albums: Vec<Option<PhotoAlbum>>,
+        draw_depth: i8,
+    ) -> Self {
+        let appearance_frame = TileBatchFrame::new(
+            ctx.resource,
+            TileBatchTextureID::TaishoStyle1,
+            numeric::Rect::new(0.0, 0.0, window_rect.w, window_rect.h),
+            numeric::Vector2f::new(0.75, 0.75),
+            0,
+        );
+
+        let background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.4, 1.4),
+            0.0,
+            0,
+        );
+
+        let mut title_text = UniText::new(
+            "鈴奈庵写真帖".to_string(),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(36.0, 36.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+        title_text.make_center(
+            ctx.context,
+            numeric::Point2f::new(window_rect.w / 2.0, 50.0),
+        );
+
+        let page_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(window_rect.w / 2.0 - 60.0, window_rect.h - 50.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(24.0, 24.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let profile_buttons = (0..albums.len())
+            .map(|index| {
+                let texture = Box::new(TextButtonTexture::new(
+                    ctx,
+                    numeric::Point2f::new(0.0, 0.0),
+                    format!("其ノ{}", ctx.number_format().number((index + 1) as u64)),
+                    FontInformation::new(
+                        ctx.resource.get_font(FontID::Cinema),
+                        numeric::Vector2f::new(22.0, 22.0),
+                        ggraphics::Color::from_rgba_u32(0xf6e1d5ff),
+                    ),
+                    10.0,
+                    ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+                    0,
+                ));
+
+                SelectButton::new(
+                    ctx,
+                    numeric::Rect::new(60.0 + (index as f32 * 110.0), 30.0, 90.0, 50.0),
+                    texture,
+                )
+            })
+            .collect();
+
+        let mut prev_page_ope = UniTexture::new(
+            ctx.ref_texture(TextureID::GoNextPageLeft),
+            numeric::Point2f::new(30.0, window_rect.h - 70.0),
+            numeric::Vector2f::new(0.55, 0.55),
+            0.0,
+            0,
+        );
+        prev_page_ope.hide();
+
+        let next_page_ope = UniTexture::new(
+            ctx.ref_texture(TextureID::GoNextPageRight),
+            numeric::Point2f::new(window_rect.w - 80.0, window_rect.h - 70.0),
+            numeric::Vector2f::new(0.55, 0.55),
+            0.0,
+            0,
+        );
+
+        let prev10_button = Self::page_jump_button(
+            ctx,
+            "前10",
+            numeric::Rect::new(100.0, window_rect.h - 70.0, 60.0, 60.0),
+        );
+        let next10_button = Self::page_jump_button(
+            ctx,
+            "次10",
+            numeric::Rect::new(window_rect.w - 160.0, window_rect.h - 70.0, 60.0, 60.0),
+        );
+
+        let mut album_book = PhotoAlbumBook {
+            canvas: SubScreen::new(
+                ctx.context,
+                window_rect,
+                draw_depth,
+                ggraphics::Color::from_rgba_u32(0),
+            ),
+            size: numeric::Vector2f::new(window_rect.w, window_rect.h),
+            background: background,
+            appearance_frame: appearance_frame,
+            title_text: title_text,
+            page_text: page_text,
+            empty_text: None,
+            profile_buttons: profile_buttons,
+            albums: albums,
+            current_profile: 0,
+            current_page: 0,
+            page_frames: Vec::new(),
+            next_page_ope: next_page_ope,
+            prev_page_ope: prev_page_ope,
+            next10_button: next10_button,
+            prev10_button: prev10_button,
+            page_scroll_event_list: DelayEventList::new(),
+            redraw_request: DrawRequest::InitDraw,
+        };
+
+        album_book.open_page(ctx);
+        album_book
+    }
+
+    fn page_jump_button<'a>(
+        ctx: &mut SuzuContext<'a>,
+        label: &str,
+        rect: numeric::Rect,
+    ) -> SelectButton {
+        let texture = Box::new(TextButtonTexture::new(
+            ctx,
+            numeric::Point2f::new(0.0, 0.0),
+            label.to_string(),
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(22.0, 22.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+            10.0,
+            ggraphics::Color::from_rgba_u32(0xfcf0eaff),
+            0,
+        ));
+
+        SelectButton::new(ctx, rect, texture)
+    }
+
+    fn current_album(&self) -> Option<&PhotoAlbum> {
+        match self.albums.get(self.current_profile) {
+            Some(album) => album.as_ref(),
+            None => None,
+        }
+    }
+
+    fn page_count(&self) -> usize {
+        self.current_album().map_or(1, |album| album.page_count())
+    }
+
+    ///
+    /// 写真のおおよその配置。一頁に二段二列で貼る
+    ///
+    fn photo_rect(&self, index: usize) -> numeric::Rect {
+        let size = self.size;
+        let w = (size.x - 180.0) / 2.0;
+        let h = (size.y - 220.0) / 2.0;
+
+        numeric::Rect::new(
+            70.0 + ((index % 2) as f32 * (w + 40.0)),
+            100.0 + ((index / 2) as f32 * (h + 20.0)),
+            w,
+            h,
+        )
+    }
+
+    ///
+    /// current_pageの頁を開く。写真はこのときに作るので、開いていない頁のテクスチャは読み込まれない
+    ///
+    fn open_page<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let photos = match self.current_album() {
+            Some(album) => album.page(self.current_page).to_vec(),
+            None => Vec::new(),
+        };
+
+        self.page_frames = photos
+            .iter()
+            .enumerate()
+            .map(|(index, photo)| AlbumPhotoFrame::new(ctx, photo, self.photo_rect(index)))
+            .collect();
+
+        self.empty_text = if self.page_frames.is_empty() {
+            let message = if self.current_album().is_some() {
+                "マダ写真ガ有リマセン"
+            } else {
+                "記録無シ"
+            };
+
+            let mut text = UniText::new(
+                message.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                FontInformation::new(
+                    ctx.resource.get_font(FontID::Cinema),
+                    numeric::Vector2f::new(30.0, 30.0),
+                    ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+                ),
+            );
+            let size = self.size;
+            text.make_center(
+                ctx.context,
+                numeric::Point2f::new(size.x / 2.0, size.y / 2.0),
+            );
+            Some(text)
+        } else {
+            None
+        };
+
+        self.page_text.replace_text(format!(
+            "{} / {} 頁",
+            ctx.number_format().number((self.current_page + 1) as u64),
+            ctx.number_format().number(self.page_count() as u64)
+        ));
+
+        self.check_move_page_icon_visibility();
+    }
+
+    fn check_move_page_icon_visibility(&mut self) {
+        self.prev_page_ope.appear();
+        self.next_page_ope.appear();
+
+        if self.current_page == 0 {
+            self.prev_page_ope.hide();
+        }
+
+        if self.current_page + 1 >= self.page_count() {
+            self.next_page_ope.hide();
+        }
+
+        self.redraw_request = DrawRequest::Draw;
+    }
+
+    fn switch_profile<'a>(&mut self, ctx: &mut SuzuContext<'a>, profile: usize) {
+        if self.current_profile == profile {
+            return;
+        }
+
+        self.page_scroll_event_list.clear();
+        self.current_profile = profile;
+        self.current_page = 0;
+        self.open_page(ctx);
+    }
+
+    fn next_page<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if self.current_page + 1 < self.page_count() {
+            self.current_page += 1;
+            self.open_page(ctx);
+            ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+        }
+    }
+
+    fn prev_page<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if self.current_page > 0 {
+            self.current_page -= 1;
+            self.open_page(ctx);
+            ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+        }
+    }
+
+    ///
+    /// 記録簿と同じく、十頁分を少しずつ繰っていく
+    ///
+    fn scroll_pages<'a>(&mut self, ctx: &mut SuzuContext<'a>, forward: bool, t: Clock) {
+        self.page_scroll_event_list.clear();
+
+        for i in 0..10 {
+            let reachable = if forward {
+                self.current_page + i + 1 < self.page_count()
+            } else {
+                self.current_page > i
+            };
+
+            if !reachable {
+                break;
+            }
+
+            self.page_scroll_event_list.add_event(
+                Box::new(move |slf: &mut Self, ctx, _| {
+                    if forward {
+                        slf.current_page += 1;
+                    } else {
+                        slf.current_page -= 1;
+                    }
+                    slf.open_page(ctx);
+                }),
+                t + (i * 2) as Clock,
+            );
+        }
+
+        ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+    }
+
+    pub fn click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> bool {
+        let rpoint = self.canvas.relative_point(point);
+
+        let clicked_profile = self
+            .profile_buttons
+            .iter()
+            .position(|button| button.contains(ctx.context, rpoint));
+        if let Some(profile) = clicked_profile {
+            self.switch_profile(ctx, profile);
+            return true;
+        }
+
+        if self.next_page_ope.is_visible() && self.next_page_ope.contains(ctx.context, rpoint) {
+            self.next_page(ctx);
+            return true;
+        } else if self.prev_page_ope.is_visible()
+            && self.prev_page_ope.contains(ctx.context, rpoint)
+        {
+            self.prev_page(ctx);
+            return true;
+        } else if self.next10_button.contains(ctx.context, rpoint) {
+            self.scroll_pages(ctx, true, t);
+            return true;
+        } else if self.prev10_button.contains(ctx.context, rpoint) {
+            self.scroll_pages(ctx, false, t);
+            return true;
+        }
+
+        false
+    }
+
+    pub fn update<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        flush_delay_event_and_redraw_check!(self, self.page_scroll_event_list, ctx, t, {})
+    }
+}
+
+impl DrawableComponent for PhotoAlbumBook {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            if self.redraw_request != DrawRequest::Skip {
+                self.redraw_request = DrawRequest::Skip;
+                sub_screen::stack_screen(ctx, &self.canvas);
+
+                self.background.draw(ctx)?;
+                self.appearance_frame.draw(ctx)?;
+                self.title_text.draw(ctx)?;
+
+                for button in self.profile_buttons.iter_mut() {
+                    button.draw(ctx)?;
+                }
+
+                for frame in self.page_frames.iter_mut() {
+                    frame.draw(ctx)?;
+                }
+
+                if let Some(empty_text) = self.empty_text.as_mut() {
+                    empty_text.draw(ctx)?;
+                }
+
+                self.prev_page_ope.draw(ctx)?;
+                self.next_page_ope.draw(ctx)?;
+                self.prev10_button.draw(ctx)?;
+                self.next10_button.draw(ctx)?;
+                self.page_text.draw(ctx)?;
+
+                sub_screen::pop_screen(ctx);
+            }
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
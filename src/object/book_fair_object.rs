@@ -0,0 +1,386 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+use torifune::{mintp, numeric};
+
+use crate::core::book_fair::*;
+use crate::core::{FontID, GensoDate, SuzuContext};
+use crate::object::util_object::*;
+
+use crate::core::number_format::NumberFormat;
+
+///
+/// 札の上でクリックされたもの
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookFairAction {
+    None,
+    TitleSelected,
+    Served(bool),
+}
+
+///
+/// # 本市の受付台
+/// 上段に窓口の客の札、下段に題名札を並べる。題名札を選んでから客の札を押すと渡す
+///
+/// ## customer_texts
+/// 客の札ごとの、名前と求める題名
+///
+/// ## title_texts
+/// 題名札ごとの題名。空いた札はNone
+///
+pub struct BookFairCounter {
+    session: BookFairSession,
+    customer_rects: Vec<numeric::Rect>,
+    title_rects: Vec<numeric::Rect>,
+    customer_texts: Vec<Vec<UniText>>,
+    title_texts: Vec<Option<UniText>>,
+    status_text: UniText,
+    timer_text: UniText,
+    name_font: FontInformation,
+    card_font: FontInformation,
+    number_format: NumberFormat,
+}
+
+impl BookFairCounter {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, requests: Vec<FairRequest>) -> Self {
+        let card_w = 280.0;
+        let slot_w = card_w + 30.0;
+        let left = (crate::core::WINDOW_SIZE_X as f32 - (slot_w * BOOK_FAIR_COUNTER_SLOTS as f32))
+            / 2.0
+            + 15.0;
+
+        let customer_rects = (0..BOOK_FAIR_COUNTER_SLOTS)
+            .map(|index| numeric::Rect::new(left + (index as f32 * slot_w), 130.0, card_w, 170.0))
+            .collect();
+        let title_rects = (0..BOOK_FAIR_COUNTER_SLOTS)
+            .map(|index| numeric::Rect::new(left + (index as f32 * slot_w), 430.0, card_w, 110.0))
+            .collect();
+
+        let status_font = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let status_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(left, 600.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            status_font.clone(),
+        );
+
+        let timer_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(left + (slot_w * 3.0), 600.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            status_font,
+        );
+
+        let mut counter = BookFairCounter {
+            session: BookFairSession::new(requests),
+            customer_rects: customer_rects,
+            title_rects: title_rects,
+            customer_texts: Vec::new(),
+            title_texts: Vec::new(),
+            status_text: status_text,
+            timer_text: timer_text,
+            name_font: FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(30.0, 30.0),
+                ggraphics::Color::from_rgba_u32(0x222222ff),
+            ),
+            card_font: FontInformation::new(
+                ctx.resource.get_font(FontID::BitMap1),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+            ),
+            number_format: ctx.number_format(),
+        };
+
+        counter.update_cards();
+        counter.update_timer(BOOK_FAIR_TIME_LIMIT);
+        counter
+    }
+
+    ///
+    /// 受付の中身に合わせて札の文字を作り直す
+    ///
+    fn update_cards(&mut self) {
+        let name_font = self.name_font.clone();
+        let card_font = self.card_font.clone();
+
+        self.customer_texts = self
+            .session
+            .get_counter()
+            .iter()
+            .zip(self.customer_rects.iter())
+            .map(|(slot, rect)| match slot {
+                Some(request) => vec![
+                    UniText::new(
+                        format!("{}様", request.customer),
+                        numeric::Point2f::new(rect.x + 20.0, rect.y + 24.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        name_font.clone(),
+                    ),
+                    UniText::new(
+                        format!("所望　{}", request.book.name),
+                        numeric::Point2f::new(rect.x + 20.0, rect.y + 100.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        card_font.clone(),
+                    ),
+                ],
+                None => Vec::new(),
+            })
+            .collect();
+
+        self.title_texts = self
+            .session
+            .get_shelf()
+            .iter()
+            .zip(self.title_rects.iter())
+            .map(|(title, rect)| {
+                title.as_ref().map(|book| {
+                    UniText::new(
+                        book.name.clone(),
+                        numeric::Point2f::new(rect.x + 20.0, rect.y + 44.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        card_font.clone(),
+                    )
+                })
+            })
+            .collect();
+
+        self.status_text.replace_text(format!(
+            "残リ{}人　{}点　{}連続",
+            self.number_format.number(self.session.remaining() as u64),
+            self.session.get_score(),
+            self.number_format.number(self.session.get_combo() as u64)
+        ));
+    }
+
+    pub fn update_timer(&mut self, remaining_time: Clock) {
+        self.timer_text
+            .replace_text(format!("残リ時間 {}秒", remaining_time / 60));
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.session.is_finished()
+    }
+
+    pub fn finish(&self, date: GensoDate, remaining_time: Clock) -> BookFairResult {
+        self.session.finish(date, remaining_time)
+    }
+
+    pub fn click_handler(&mut self, point: numeric::Point2f) -> BookFairAction {
+        if let Some(index) = self
+            .title_rects
+            .iter()
+            .position(|rect| rect.contains(mintp!(point)))
+        {
+            self.session.select_title(index);
+            return BookFairAction::TitleSelected;
+        }
+
+        if let Some(index) = self
+            .customer_rects
+            .iter()
+            .position(|rect| rect.contains(mintp!(point)))
+        {
+            if let Some(correct) = self.session.serve(index) {
+                self.update_cards();
+                return BookFairAction::Served(correct);
+            }
+        }
+
+        BookFairAction::None
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        let selected = self.session.get_selected_title();
+
+        for (rect, slot) in self
+            .customer_rects
+            .iter()
+            .zip(self.session.get_counter().iter())
+        {
+            let fill = if slot.is_some() {
+                0xf6eee0ff
+            } else {
+                0xd8d0c0ff
+            };
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(fill),
+            )
+            .add_to_builder(&mut builder);
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::stroke(2.0),
+                ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        for (index, (rect, title)) in self
+            .title_rects
+            .iter()
+            .zip(self.session.get_shelf().iter())
+            .enumerate()
+        {
+            let fill = if title.is_some() {
+                0xfcf0eaff
+            } else {
+                0xd8d0c0ff
+            };
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(fill),
+            )
+            .add_to_builder(&mut builder);
+
+            // 選んでいる題名札は赤く縁取る
+            let (width, color) = if selected == Some(index) {
+                (5.0, 0xaa2222ff)
+            } else {
+                (2.0, 0x5a4f3fff)
+            };
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::stroke(width),
+                ggraphics::Color::from_rgba_u32(color),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        for texts in self.customer_texts.iter_mut() {
+            for text in texts.iter_mut() {
+                text.draw(ctx)?;
+            }
+        }
+
+        for text in self.title_texts.iter_mut().flatten() {
+            text.draw(ctx)?;
+        }
+
+        self.status_text.draw(ctx)?;
+        self.timer_text.draw(ctx)
+    }
+}
+
+///
+/// # 本市の結果と番付
+///
+pub struct BookFairResultPanel {
+    rect: numeric::Rect,
+    lines: Vec<UniText>,
+}
+
+impl BookFairResultPanel {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        rect: numeric::Rect,
+        result: &BookFairResult,
+        leaderboard: &[BookFairResult],
+    ) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let mut texts = vec![
+            "今季ノ本市".to_string(),
+            format!(
+                "受付　{}人",
+                ctx.number_format().number(result.served as u64)
+            ),
+            format!(
+                "取リ違エ　{}回",
+                ctx.number_format().number(result.missed as u64)
+            ),
+            format!(
+                "最長連続　{}",
+                ctx.number_format().number(result.best_combo as u64)
+            ),
+            format!("点数　{}点", result.score),
+            format!("売上　{}円", result.earned_money()),
+            String::new(),
+            "本市番付".to_string(),
+        ];
+
+        for (rank, entry) in leaderboard.iter().enumerate() {
+            let mark = if entry == result { "　←今回" } else { "" };
+            texts.push(format!(
+                "{}位　{}　{}点{}",
+                ctx.number_format().ordinal(rank),
+                ctx.number_format().date(&entry.date),
+                entry.score,
+                mark
+            ));
+        }
+
+        let lines = texts
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| {
+                UniText::new(
+                    text,
+                    numeric::Point2f::new(rect.x + 40.0, rect.y + 30.0 + (index as f32 * 38.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )
+            })
+            .collect();
+
+        BookFairResultPanel {
+            rect: rect,
+            lines: lines,
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xf6eee0ee),
+        )
+        .add_to_builder(&mut builder);
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::stroke(2.0),
+            ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+        )
+        .add_to_builder(&mut builder);
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        for line in self.lines.iter_mut() {
+            line.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use torifune::numeric;
+
+///
+/// # 枠の中のどこに文字を寄せるか
+///
+/// ## TopRight
+/// 右上に寄せる。縦書きの欄で、書き出しを罫線に揃えたいときに使う
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellAnchor {
+    Center,
+    TopRight,
+}
+
+///
+/// # 文字の位置を画素に揃えるか
+///
+/// ## Round
+/// 四捨五入で揃える。roundup2f!と同じ
+///
+/// ## Floor
+/// 左上に向けて切り捨てる
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelSnap {
+    Off,
+    Round,
+    Floor,
+}
+
+impl PixelSnap {
+    ///
+    /// valueを揃え、lower ~ upperに収める
+    ///
+    fn apply(&self, value: f32, lower: f32, upper: f32) -> f32 {
+        let (snapped, lower, upper) = match self {
+            PixelSnap::Off => return value.max(lower).min(upper.max(lower)),
+            PixelSnap::Round => (value.round(), lower.ceil(), upper.floor()),
+            PixelSnap::Floor => (value.floor(), lower.ceil(), upper.floor()),
+        };
+
+        snapped.max(lower).min(upper.max(lower))
+    }
+}
+
+///
+/// # 枠に文字を置いた結果
+///
+/// ## fit_ratio
+/// 枠に収めるための縮小率。収まっていれば1.0
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellPlacement {
+    pub position: numeric::Point2f,
+    pub fit_ratio: f32,
+}
+
+///
+/// # 表ごとの文字の揃え方
+///
+/// ## padding
+/// 罫線から文字までの余白。文字はこの内側からはみ出さない
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellAlignment {
+    pub anchor: CellAnchor,
+    pub snap: PixelSnap,
+    pub padding: f32,
+}
+
+impl CellAlignment {
+    pub fn new(anchor: CellAnchor, snap: PixelSnap, padding: f32) -> Self {
+        CellAlignment {
+            anchor: anchor,
+            snap: snap,
+            padding: padding,
+        }
+    }
+
+    ///
+    /// 余白を除いた、文字を置ける範囲
+    ///
+    pub fn inner_rect(&self, cell: numeric::Rect) -> numeric::Rect {
+        let padding_x = self.padding.min(cell.w / 2.0);
+        let padding_y = self.padding.min(cell.h / 2.0);
+
+        numeric::Rect::new(
+            cell.x + padding_x,
+            cell.y + padding_y,
+            cell.w - (padding_x * 2.0),
+            cell.h - (padding_y * 2.0),
+        )
+    }
+
+    ///
+    /// 大きさtext_sizeの文字を、枠cellのどこに置くかを決める
+    ///
+    /// 余白の内側に収まらない文字は縮めてから置く
+    ///
+    pub fn place(&self, cell: numeric::Rect, text_size: numeric::Vector2f) -> CellPlacement {
+        let inner = self.inner_rect(cell);
+
+        let mut fit_ratio: f32 = 1.0;
+        if text_size.x > inner.w && text_size.x > 0.0 {
+            fit_ratio = fit_ratio.min(inner.w / text_size.x);
+        }
+        if text_size.y > inner.h && text_size.y > 0.0 {
+            fit_ratio = fit_ratio.min(inner.h / text_size.y);
+        }
+
+        let width = text_size.x * fit_ratio;
+        let height = text_size.y * fit_ratio;
+
+        let (x, y) = match self.anchor {
+            CellAnchor::Center => (
+                inner.x + ((inner.w - width) / 2.0),
+                inner.y + ((inner.h - height) / 2.0),
+            ),
+            CellAnchor::TopRight => (inner.x + inner.w - width, inner.y),
+        };
+
+        CellPlacement {
+            position: numeric::Point2f::new(
+                self.snap.apply(x, inner.x, inner.x + inner.w - width),
+                self.snap.apply(y, inner.y, inner.y + inner.h - height),
+            ),
+            fit_ratio: fit_ratio,
+        }
+    }
+}
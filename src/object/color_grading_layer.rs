@@ -0,0 +1,47 @@
+use ggez::graphics as ggraphics;
+use ggez::graphics::Drawable;
+use torifune::numeric;
+
+use crate::core::color_grading::ColorGrade;
+
+///
+/// # 画面全体に重ねる色味の層
+/// 乗算の板でgainを、加算の板でliftを当てる
+///
+pub struct ColorGradingLayer {
+    rect: numeric::Rect,
+}
+
+impl ColorGradingLayer {
+    pub fn new(rect: numeric::Rect) -> Self {
+        ColorGradingLayer { rect: rect }
+    }
+
+    pub fn draw(&self, ctx: &mut ggez::Context, grade: &ColorGrade) -> ggez::GameResult<()> {
+        if grade.is_neutral() {
+            return Ok(());
+        }
+
+        let mut gain_mesh = ggraphics::Mesh::new_rectangle(
+            ctx,
+            ggraphics::DrawMode::fill(),
+            self.rect,
+            ggraphics::Color::new(grade.gain[0], grade.gain[1], grade.gain[2], 1.0),
+        )?;
+        gain_mesh.set_blend_mode(Some(ggraphics::BlendMode::Multiply));
+        ggraphics::draw(ctx, &gain_mesh, ggraphics::DrawParam::default())?;
+
+        if grade.lift.iter().any(|lift| *lift > 0.0) {
+            let mut lift_mesh = ggraphics::Mesh::new_rectangle(
+                ctx,
+                ggraphics::DrawMode::fill(),
+                self.rect,
+                ggraphics::Color::new(grade.lift[0], grade.lift[1], grade.lift[2], 1.0),
+            )?;
+            lift_mesh.set_blend_mode(Some(ggraphics::BlendMode::Add));
+            ggraphics::draw(ctx, &lift_mesh, ggraphics::DrawParam::default())?;
+        }
+
+        Ok(())
+    }
+}
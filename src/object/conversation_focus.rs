@@ -0,0 +1,115 @@
+use torifune::core::Clock;
+use torifune::numeric;
+
+use super::tween::*;
+
+///
+/// 客が話し始めてから寄り切るまでの時間
+///
+pub const FOCUS_IN_TIME: Clock = 40;
+
+///
+/// 書類に手を付けてから元の構図に戻るまでの時間
+///
+pub const FOCUS_OUT_TIME: Clock = 20;
+
+///
+/// 寄り切ったときの拡大率の増分
+///
+pub const FOCUS_ZOOM: f32 = 0.06;
+
+///
+/// 寄り切ったときに机に掛ける暗さ
+///
+pub const FOCUS_DESK_DIM: f32 = 0.3;
+
+///
+/// # 机での会話に寄せる構図
+/// 客の吹き出しが出ると客の影に少し寄り、机を暗くする。書類に手を付けると元に戻す
+///
+/// ## amount
+/// 0.0で元の構図、1.0で寄り切った構図
+///
+pub struct ConversationFocus {
+    amount: Tween<f32>,
+    focused: bool,
+}
+
+impl ConversationFocus {
+    pub fn new() -> Self {
+        ConversationFocus {
+            amount: tween(0.0, 0.0, 0, Easing::Linear, 0),
+            focused: false,
+        }
+    }
+
+    ///
+    /// 客が話し始めたときに呼ぶ。寄っている途中からでも続けて寄る
+    ///
+    pub fn speak(&mut self, t: Clock) {
+        if self.focused {
+            return;
+        }
+
+        self.focused = true;
+        self.amount = tween(
+            self.amount.value_at(t),
+            1.0,
+            FOCUS_IN_TIME,
+            Easing::EaseOutCubic,
+            t,
+        );
+    }
+
+    ///
+    /// 書類に手を付けたときに呼ぶ
+    ///
+    pub fn release(&mut self, t: Clock) {
+        if !self.focused {
+            return;
+        }
+
+        self.focused = false;
+        self.amount = tween(
+            self.amount.value_at(t),
+            0.0,
+            FOCUS_OUT_TIME,
+            Easing::EaseOutQuad,
+            t,
+        );
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn is_moving_at(&self, t: Clock) -> bool {
+        !self.amount.is_finished_at(t)
+    }
+
+    pub fn amount_at(&self, t: Clock) -> f32 {
+        self.amount.value_at(t)
+    }
+
+    pub fn zoom_at(&self, t: Clock) -> f32 {
+        1.0 + (FOCUS_ZOOM * self.amount_at(t))
+    }
+
+    pub fn desk_dim_at(&self, t: Clock) -> f32 {
+        FOCUS_DESK_DIM * self.amount_at(t)
+    }
+}
+
+///
+/// centerを中心にzoom倍したとき、originに置いた物がどこに来るか
+///
+pub fn zoom_position(
+    origin: numeric::Point2f,
+    center: numeric::Point2f,
+    zoom: f32,
+) -> numeric::Point2f {
+    numeric::Point2f::new(
+        center.x + ((origin.x - center.x) * zoom),
+        center.y + ((origin.y - center.y) * zoom),
+    )
+}
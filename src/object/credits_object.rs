@@ -0,0 +1,331 @@
+use ggez::graphics as ggraphics;
+use rand::Rng;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::shape::MeshShape;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+use crate::core::credits::{CreditSection, CreditsTable};
+use crate::core::{FontID, SuzuContext, TextureID};
+
+///
+/// 項と項の間の空き
+///
+const SECTION_GAP: f32 = 140.0;
+
+///
+/// 担当の列と名前の列の間の空き
+///
+const COLUMN_GAP: f32 = 24.0;
+
+///
+/// 火花が消えるまでの時間
+///
+const SPARK_LIFETIME: Clock = 45;
+
+///
+/// 一度に散る火花の数
+///
+const SPARKS_PER_BURST: usize = 16;
+
+const SPARK_GRAVITY: f32 = 0.08;
+
+///
+/// # 制作の一覧に書かれた一列
+///
+/// ## base_position
+/// 流し始める前の位置。流れた分だけ上へずらして描く
+///
+/// ## is_name
+/// 名前の列ならtrue。担当の列はクリックしても何も起きない
+///
+struct CreditColumn {
+    text: VerticalText,
+    base_position: numeric::Point2f,
+    is_name: bool,
+}
+
+///
+/// # 流れる制作の一覧
+/// 項ごとに担当を右端の列に、名前をその左へ縦書きで並べ、項を下へ積んで下から上へ流す
+///
+/// ## length
+/// 最後の項が画面の上へ抜けきるまでに流れる長さ
+///
+pub struct CreditsRoll {
+    columns: Vec<CreditColumn>,
+    length: f32,
+}
+
+impl CreditsRoll {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, table: &CreditsTable, area: numeric::Rect) -> Self {
+        let role_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(40.0, 40.0),
+            ggraphics::Color::from_rgba_u32(0xf6e1d5ff),
+        );
+        let name_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(32.0, 32.0),
+            ggraphics::Color::from_rgba_u32(0xe8d0b8ff),
+        );
+
+        let mut columns = Vec::new();
+        // 最初の項は画面の下端から出てくる
+        let mut top = area.bottom();
+
+        for section in table.get_sections().iter() {
+            let section_height = Self::layout_section(
+                ctx,
+                section,
+                &role_font,
+                &name_font,
+                area.x + (area.w / 2.0),
+                top,
+                &mut columns,
+            );
+            top += section_height + SECTION_GAP;
+        }
+
+        CreditsRoll {
+            columns: columns,
+            length: top - area.y,
+        }
+    }
+
+    ///
+    /// 一つの項を、centerを中心にtopから並べ、項の高さを返す
+    ///
+    fn layout_section<'a>(
+        ctx: &mut SuzuContext<'a>,
+        section: &CreditSection,
+        role_font: &FontInformation,
+        name_font: &FontInformation,
+        center: f32,
+        top: f32,
+        columns: &mut Vec<CreditColumn>,
+    ) -> f32 {
+        let mut texts = vec![(
+            VerticalText::new(
+                section.role.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                role_font.clone(),
+            ),
+            false,
+        )];
+        for name in section.names.iter() {
+            texts.push((
+                VerticalText::new(
+                    name.to_string(),
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    name_font.clone(),
+                ),
+                true,
+            ));
+        }
+
+        let sizes: Vec<numeric::Vector2f> = texts
+            .iter()
+            .map(|(text, _)| text.get_drawing_size(ctx.context))
+            .collect();
+        let total_width =
+            sizes.iter().map(|size| size.x).sum::<f32>() + (COLUMN_GAP * (sizes.len() - 1) as f32);
+        let section_height = sizes.iter().fold(0.0, |max: f32, size| max.max(size.y));
+
+        // 縦書きなので、担当を右端に置いて左へ名前を並べる
+        let mut right = center + (total_width / 2.0);
+        for ((mut text, is_name), size) in texts.into_iter().zip(sizes.iter()) {
+            let position = numeric::Point2f::new(right - size.x, top);
+            text.set_position(position);
+            columns.push(CreditColumn {
+                text: text,
+                base_position: position,
+                is_name: is_name,
+            });
+            right -= size.x + COLUMN_GAP;
+        }
+
+        section_height
+    }
+
+    pub fn get_length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn scroll_to(&mut self, offset: f32) {
+        for column in self.columns.iter_mut() {
+            column.text.set_position(numeric::Point2f::new(
+                column.base_position.x,
+                column.base_position.y - offset,
+            ));
+        }
+    }
+
+    ///
+    /// 名前がクリックされていれば、その名前の中心を返す
+    ///
+    pub fn click_name<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> Option<numeric::Point2f> {
+        self.columns
+            .iter()
+            .filter(|column| column.is_name)
+            .find(|column| column.text.contains(ctx.context, point))
+            .map(|column| {
+                let position = column.text.get_position();
+                let size = column.text.get_drawing_size(ctx.context);
+                numeric::Point2f::new(position.x + (size.x / 2.0), position.y + (size.y / 2.0))
+            })
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        for column in self.columns.iter_mut() {
+            column.text.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+}
+
+///
+/// # 奥行きのある背景の一枚
+/// 同じ絵を縦に二枚並べ、流れた長さにrateを掛けた分だけ上へ巡らせる
+///
+/// ## rate
+/// 奥の絵ほど小さくして、ゆっくり流す
+///
+pub struct ParallaxLayer {
+    upper: UniTexture,
+    lower: UniTexture,
+    height: f32,
+    rate: f32,
+}
+
+impl ParallaxLayer {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        texture_id: TextureID,
+        scale: numeric::Vector2f,
+        alpha: f32,
+        rate: f32,
+    ) -> Self {
+        let mut upper = UniTexture::new(
+            ctx.ref_texture(texture_id),
+            numeric::Point2f::new(0.0, 0.0),
+            scale,
+            0.0,
+            0,
+        );
+        upper.set_alpha(alpha);
+        let height = upper.get_drawing_size(ctx.context).y;
+
+        let mut lower = UniTexture::new(
+            ctx.ref_texture(texture_id),
+            numeric::Point2f::new(0.0, height),
+            scale,
+            0.0,
+            0,
+        );
+        lower.set_alpha(alpha);
+
+        ParallaxLayer {
+            upper: upper,
+            lower: lower,
+            height: height,
+            rate: rate,
+        }
+    }
+
+    pub fn scroll_to(&mut self, offset: f32) {
+        let y = -((offset * self.rate) % self.height);
+        self.upper.set_position(numeric::Point2f::new(0.0, y));
+        self.lower
+            .set_position(numeric::Point2f::new(0.0, y + self.height));
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        self.upper.draw(ctx)?;
+        self.lower.draw(ctx)
+    }
+}
+
+struct CreditSpark {
+    position: numeric::Point2f,
+    velocity: numeric::Vector2f,
+    born: Clock,
+    color: ggraphics::Color,
+}
+
+///
+/// # 名前をクリックしたときに散る火花
+///
+pub struct CreditSparks {
+    sparks: Vec<CreditSpark>,
+}
+
+impl CreditSparks {
+    pub fn new() -> Self {
+        CreditSparks { sparks: Vec::new() }
+    }
+
+    pub fn burst<R: Rng>(&mut self, rng: &mut R, center: numeric::Point2f, t: Clock) {
+        let palette = [0xf6e1d5ff, 0xf0c060ff, 0xe87a5aff, 0xb0d8f0ff];
+
+        for index in 0..SPARKS_PER_BURST {
+            let angle = (index as f32 / SPARKS_PER_BURST as f32) * std::f32::consts::PI * 2.0
+                + rng.gen::<f32>() * 0.4;
+            let speed = 2.0 + rng.gen::<f32>() * 3.0;
+            self.sparks.push(CreditSpark {
+                position: center,
+                velocity: numeric::Vector2f::new(angle.cos() * speed, angle.sin() * speed),
+                born: t,
+                color: ggraphics::Color::from_rgba_u32(palette[rng.gen_range(0, palette.len())]),
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sparks.is_empty()
+    }
+
+    pub fn update(&mut self, t: Clock) {
+        self.sparks.retain(|spark| t < spark.born + SPARK_LIFETIME);
+
+        for spark in self.sparks.iter_mut() {
+            spark.position += spark.velocity;
+            spark.velocity.y += SPARK_GRAVITY;
+            spark.color.a = 1.0 - ((t - spark.born) as f32 / SPARK_LIFETIME as f32);
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.sparks.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = ggraphics::MeshBuilder::new();
+        for spark in self.sparks.iter() {
+            shape::Circle::new(
+                spark.position,
+                3.0,
+                0.1,
+                ggraphics::DrawMode::fill(),
+                spark.color,
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())
+    }
+}
@@ -0,0 +1,158 @@
+use torifune::core::Clock;
+use torifune::numeric;
+
+///
+/// 客を待たせてから、苛立ち始めるまでの時間
+///
+pub const CUSTOMER_IMPATIENT_CLOCK: Clock = 900;
+
+///
+/// 客の影のアニメーションの一コマの長さ
+///
+pub const CUSTOMER_EMOTION_FRAME_SPEED: Clock = 8;
+
+///
+/// # 客の影の顔付き
+/// TextureAnimationのモードとして使う
+///
+/// ## Idle, Impatient
+/// 待っている間の顔付き。繰り返し再生する
+///
+/// ## Happy, Angry
+/// 受け渡しへの反応。一度だけ再生し、待っている間の顔付きに戻る
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CustomerEmotion {
+    Idle,
+    Impatient,
+    Happy,
+    Angry,
+}
+
+impl CustomerEmotion {
+    ///
+    /// TextureAnimationに渡すモードの順番
+    ///
+    pub fn mode_order() -> Vec<Self> {
+        vec![
+            CustomerEmotion::Idle,
+            CustomerEmotion::Impatient,
+            CustomerEmotion::Happy,
+            CustomerEmotion::Angry,
+        ]
+    }
+
+    ///
+    /// 受け渡しへの反応。正しく受け渡せれば喜び、誤れば怒る
+    ///
+    pub fn reaction(correct: bool) -> Self {
+        if correct {
+            CustomerEmotion::Happy
+        } else {
+            CustomerEmotion::Angry
+        }
+    }
+
+    ///
+    /// 一回分のアニメーションのコマ数
+    ///
+    pub fn frame_count(&self) -> usize {
+        match self {
+            CustomerEmotion::Idle => 8,
+            CustomerEmotion::Impatient => 4,
+            CustomerEmotion::Happy => 6,
+            CustomerEmotion::Angry => 8,
+        }
+    }
+
+    ///
+    /// 受け渡しへの反応ならtrue
+    ///
+    pub fn is_reaction(&self) -> bool {
+        match self {
+            CustomerEmotion::Happy | CustomerEmotion::Angry => true,
+            _ => false,
+        }
+    }
+
+    ///
+    /// frameコマ目で、影を元の位置からずらす量
+    ///
+    /// 待っている間は小さく上下し、苛立つと足踏みするように左右へ揺れる。
+    /// 喜ぶと跳ね、怒ると細かく震える
+    ///
+    pub fn pose_offset(&self, frame: usize) -> numeric::Vector2f {
+        match self {
+            CustomerEmotion::Idle => {
+                let y = [0.0, 0.0, -1.0, -1.0, -2.0, -1.0, -1.0, 0.0];
+                numeric::Vector2f::new(0.0, y[frame % y.len()])
+            }
+            CustomerEmotion::Impatient => {
+                let x = [-2.0, 0.0, 2.0, 0.0];
+                numeric::Vector2f::new(x[frame % x.len()], 0.0)
+            }
+            CustomerEmotion::Happy => {
+                let y = [0.0, -6.0, -10.0, -6.0, 0.0, -3.0];
+                numeric::Vector2f::new(0.0, y[frame % y.len()])
+            }
+            CustomerEmotion::Angry => {
+                let x = [-3.0, 3.0];
+                numeric::Vector2f::new(x[frame % x.len()], 0.0)
+            }
+        }
+    }
+
+    ///
+    /// 影に掛ける色。透明度は影の方のものを使う
+    ///
+    pub fn tint(&self) -> u32 {
+        match self {
+            CustomerEmotion::Idle => 0xffffffff,
+            CustomerEmotion::Impatient => 0xf0e0d0ff,
+            CustomerEmotion::Happy => 0xfff8e0ff,
+            CustomerEmotion::Angry => 0xffb0b0ff,
+        }
+    }
+}
+
+///
+/// # 客の機嫌
+/// 待たせた時間と受け渡しの結果から、影の顔付きを決める
+///
+/// ## resting
+/// 反応を再生し終えた後に戻る、待っている間の顔付き
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomerMood {
+    resting: CustomerEmotion,
+}
+
+impl CustomerMood {
+    pub fn new() -> Self {
+        CustomerMood {
+            resting: CustomerEmotion::Idle,
+        }
+    }
+
+    pub fn get_resting(&self) -> CustomerEmotion {
+        self.resting
+    }
+
+    ///
+    /// 待たせている時間を渡す。待っている間の顔付きが変わったときは、その顔付きを返す
+    ///
+    pub fn update_waiting(&mut self, waiting: Clock) -> Option<CustomerEmotion> {
+        let resting = if waiting >= CUSTOMER_IMPATIENT_CLOCK {
+            CustomerEmotion::Impatient
+        } else {
+            CustomerEmotion::Idle
+        };
+
+        if self.resting == resting {
+            return None;
+        }
+
+        self.resting = resting;
+        Some(resting)
+    }
+}
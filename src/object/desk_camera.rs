@@ -0,0 +1,150 @@
+use torifune::numeric;
+
+///
+/// 机の拡大率の下限と上限。下限では机全体が見える
+///
+pub const DESK_ZOOM_MIN: f32 = 1.0;
+pub const DESK_ZOOM_MAX: f32 = 2.5;
+
+///
+/// ホイール一段で変わる拡大率の倍率
+///
+pub const DESK_ZOOM_STEP: f32 = 1.15;
+
+///
+/// # 机を覗き込むカメラ
+/// 机の上の座標(world)と、机のSubScreenの上の座標(local)を行き来する
+///
+/// ## offset
+/// 画面の左上に映っている、机の上の点
+///
+/// ## view_size
+/// 机のSubScreenの大きさ。机そのものの大きさでもある
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeskCamera {
+    zoom: f32,
+    offset: numeric::Point2f,
+    view_size: numeric::Vector2f,
+}
+
+impl DeskCamera {
+    pub fn new(view_size: numeric::Vector2f) -> Self {
+        DeskCamera {
+            zoom: DESK_ZOOM_MIN,
+            offset: numeric::Point2f::new(0.0, 0.0),
+            view_size: view_size,
+        }
+    }
+
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn get_offset(&self) -> numeric::Point2f {
+        self.offset
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > DESK_ZOOM_MIN
+    }
+
+    pub fn to_world(&self, local: numeric::Point2f) -> numeric::Point2f {
+        numeric::Point2f::new(
+            self.offset.x + (local.x / self.zoom),
+            self.offset.y + (local.y / self.zoom),
+        )
+    }
+
+    pub fn to_local(&self, world: numeric::Point2f) -> numeric::Point2f {
+        numeric::Point2f::new(
+            (world.x - self.offset.x) * self.zoom,
+            (world.y - self.offset.y) * self.zoom,
+        )
+    }
+
+    pub fn rect_to_local(&self, world: numeric::Rect) -> numeric::Rect {
+        let top_left = self.to_local(numeric::Point2f::new(world.x, world.y));
+        numeric::Rect::new(
+            top_left.x,
+            top_left.y,
+            world.w * self.zoom,
+            world.h * self.zoom,
+        )
+    }
+
+    ///
+    /// 机の外が映らないように、offsetを寄せる
+    ///
+    fn clamp_offset(&mut self) {
+        let max_x = self.view_size.x - (self.view_size.x / self.zoom);
+        let max_y = self.view_size.y - (self.view_size.y / self.zoom);
+        self.offset = numeric::Point2f::new(
+            self.offset.x.max(0.0).min(max_x),
+            self.offset.y.max(0.0).min(max_y),
+        );
+    }
+
+    ///
+    /// localの点を中心に、ホイールwheel段分だけ拡大する。負なら縮める
+    ///
+    /// 見え方が変わればtrueを返す
+    ///
+    pub fn zoom_at(&mut self, local: numeric::Point2f, wheel: f32) -> bool {
+        let next_zoom = (self.zoom * DESK_ZOOM_STEP.powf(wheel))
+            .max(DESK_ZOOM_MIN)
+            .min(DESK_ZOOM_MAX);
+        if (next_zoom - self.zoom).abs() < std::f32::EPSILON {
+            return false;
+        }
+
+        // カーソルの下にある机の点が、拡大した後も同じ所に映るようにする
+        let anchor = self.to_world(local);
+        self.zoom = next_zoom;
+        self.offset = numeric::Point2f::new(
+            anchor.x - (local.x / self.zoom),
+            anchor.y - (local.y / self.zoom),
+        );
+        self.clamp_offset();
+
+        true
+    }
+
+    ///
+    /// 画面の上でdeltaだけ掴んで動かしたように、机を動かす
+    ///
+    /// 見え方が変わればtrueを返す
+    ///
+    pub fn pan_by(&mut self, delta: numeric::Vector2f) -> bool {
+        let before = self.offset;
+        self.offset = numeric::Point2f::new(
+            self.offset.x - (delta.x / self.zoom),
+            self.offset.y - (delta.y / self.zoom),
+        );
+        self.clamp_offset();
+
+        before != self.offset
+    }
+
+    pub fn reset(&mut self) {
+        self.zoom = DESK_ZOOM_MIN;
+        self.offset = numeric::Point2f::new(0.0, 0.0);
+    }
+
+    ///
+    /// 机の上の物を描くときに掛ける変換。worldからlocalへ写す
+    ///
+    pub fn to_matrix(&self) -> mint::ColumnMatrix4<f32> {
+        mint::ColumnMatrix4::from([
+            [self.zoom, 0.0, 0.0, 0.0],
+            [0.0, self.zoom, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [
+                -self.offset.x * self.zoom,
+                -self.offset.y * self.zoom,
+                0.0,
+                1.0,
+            ],
+        ])
+    }
+}
@@ -0,0 +1,87 @@
+use std::f32::consts::PI;
+
+use torifune::numeric;
+
+///
+/// Q/Eを押している間、1フレームで回す角度
+///
+pub const DESK_ROTATE_SPEED: f32 = 0.04;
+
+///
+/// Shiftを押しながらホイールを一段回したときに回す角度
+///
+pub const DESK_WHEEL_ROTATE_STEP: f32 = PI / 24.0;
+
+///
+/// 角度を-πからπの間に収める
+///
+pub fn normalize_rotation(rad: f32) -> f32 {
+    let mut rad = rad % (2.0 * PI);
+    if rad > PI {
+        rad -= 2.0 * PI;
+    } else if rad <= -PI {
+        rad += 2.0 * PI;
+    }
+
+    rad
+}
+
+fn rotate_around(point: numeric::Point2f, pivot: numeric::Point2f, rad: f32) -> numeric::Point2f {
+    let (sin, cos) = rad.sin_cos();
+    let dx = point.x - pivot.x;
+    let dy = point.y - pivot.y;
+
+    numeric::Point2f::new(
+        pivot.x + (dx * cos) - (dy * sin),
+        pivot.y + (dx * sin) + (dy * cos),
+    )
+}
+
+///
+/// pivotを中心にradだけ回した物の上の点pointを、回す前の点に戻す
+///
+pub fn unrotate_point(
+    pivot: numeric::Point2f,
+    rad: f32,
+    point: numeric::Point2f,
+) -> numeric::Point2f {
+    rotate_around(point, pivot, -rad)
+}
+
+///
+/// areaをpivotを中心にradだけ回したときに、pointが上にあるか
+///
+pub fn rotated_contains(
+    area: numeric::Rect,
+    pivot: numeric::Point2f,
+    rad: f32,
+    point: numeric::Point2f,
+) -> bool {
+    area.contains(unrotate_point(pivot, rad, point))
+}
+
+///
+/// areaをpivotを中心にradだけ回したときに、全体を囲む矩形
+///
+pub fn rotated_bounds(area: numeric::Rect, pivot: numeric::Point2f, rad: f32) -> numeric::Rect {
+    let corners = [
+        numeric::Point2f::new(area.left(), area.top()),
+        numeric::Point2f::new(area.right(), area.top()),
+        numeric::Point2f::new(area.left(), area.bottom()),
+        numeric::Point2f::new(area.right(), area.bottom()),
+    ];
+
+    let mut left = std::f32::MAX;
+    let mut top = std::f32::MAX;
+    let mut right = std::f32::MIN;
+    let mut bottom = std::f32::MIN;
+    for corner in corners.iter() {
+        let p = rotate_around(*corner, pivot, rad);
+        left = left.min(p.x);
+        top = top.min(p.y);
+        right = right.max(p.x);
+        bottom = bottom.max(p.y);
+    }
+
+    numeric::Rect::new(left, top, right - left, bottom - top)
+}
@@ -0,0 +1,126 @@
+use torifune::numeric;
+
+use crate::core::shortcut_palette::PaletteKey;
+
+///
+/// 本の状態のメニューの選択肢の数。良、可、悪、削除の順に並ぶ
+///
+pub const BOOK_STATUS_CHOICES: usize = 4;
+
+///
+/// # 机の上の操作を、キーで行うもの
+///
+/// ## ChooseEntry
+/// 数字のキー。開いているメニューの、その番目の選択肢を選ぶ。0始まり
+///
+/// ## NextCell
+/// Tab。帳簿の次の欄のメニューを開く
+///
+/// ## HandOver
+/// 空白。掴んでいる物を客へ渡す
+///
+/// ## CancelDrag
+/// Escape。掴んでいる物を、掴む前の所へ戻す
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeskShortcut {
+    ChooseEntry(usize),
+    NextCell,
+    HandOver,
+    CancelDrag,
+}
+
+impl DeskShortcut {
+    pub fn from_palette_key(key: PaletteKey) -> Option<Self> {
+        match key {
+            PaletteKey::Tab => Some(DeskShortcut::NextCell),
+            PaletteKey::Escape => Some(DeskShortcut::CancelDrag),
+            PaletteKey::Char(' ') | PaletteKey::Char('　') => Some(DeskShortcut::HandOver),
+            PaletteKey::Char(c) => match c.to_digit(10) {
+                Some(n) if n > 0 => Some(DeskShortcut::ChooseEntry(n as usize - 1)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+///
+/// # Tabで巡る帳簿の欄
+///
+/// ## BookTitle, BookStatus
+/// 本の欄。何冊目の本か
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordBookCell {
+    CustomerName,
+    BorrowDate,
+    ReturnDate,
+    BookTitle(u32),
+    BookStatus(u32),
+}
+
+impl RecordBookCell {
+    ///
+    /// 客の情報の欄ならtrue。falseなら本の欄
+    ///
+    pub fn is_customer_info(&self) -> bool {
+        match self {
+            RecordBookCell::CustomerName
+            | RecordBookCell::BorrowDate
+            | RecordBookCell::ReturnDate => true,
+            _ => false,
+        }
+    }
+
+    ///
+    /// 欄の表の上での位置
+    ///
+    pub fn grid_position(&self) -> numeric::Vector2u {
+        match self {
+            RecordBookCell::CustomerName => numeric::Vector2u::new(2, 1),
+            RecordBookCell::BorrowDate => numeric::Vector2u::new(1, 1),
+            RecordBookCell::ReturnDate => numeric::Vector2u::new(0, 1),
+            RecordBookCell::BookTitle(n) => numeric::Vector2u::new(*n, 0),
+            RecordBookCell::BookStatus(n) => numeric::Vector2u::new(*n, 1),
+        }
+    }
+}
+
+///
+/// Tabで巡る欄を、巡る順に返す
+///
+/// 貸出なら名前、日付、本の題目。返却なら返却日と本の状態。
+/// 本の表の最後の列は見出しなので巡らない
+///
+pub fn record_book_tab_cells(borrowing: bool, books_table_rows: usize) -> Vec<RecordBookCell> {
+    let book_rows = books_table_rows.saturating_sub(1) as u32;
+
+    if borrowing {
+        let mut cells = vec![
+            RecordBookCell::CustomerName,
+            RecordBookCell::BorrowDate,
+            RecordBookCell::ReturnDate,
+        ];
+        cells.extend((0..book_rows).map(RecordBookCell::BookTitle));
+        cells
+    } else {
+        let mut cells = vec![RecordBookCell::ReturnDate];
+        cells.extend((0..book_rows).map(RecordBookCell::BookStatus));
+        cells
+    }
+}
+
+///
+/// 今の欄の次の欄の番号。最後の次は最初に戻る。欄が無ければNone
+///
+pub fn next_cell_index(current: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    match current {
+        Some(index) => Some((index + 1) % len),
+        None => Some(0),
+    }
+}
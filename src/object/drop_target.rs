@@ -0,0 +1,196 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+use super::highlight::pulse_ratio;
+
+///
+/// 置ける所を示す色
+///
+pub const DROP_TARGET_COLOR: u32 = 0xffd70000;
+
+///
+/// 置ける所の透明度。明滅の弱いときと強いとき
+///
+pub const DROP_TARGET_ALPHA_MIN: f32 = 0.12;
+pub const DROP_TARGET_ALPHA_MAX: f32 = 0.3;
+
+///
+/// 掴んでいる物が今まさに上にある所は、これだけ濃くする
+///
+pub const DROP_TARGET_HOVER_ALPHA: f32 = 0.45;
+
+///
+/// # 掴んだ物を置ける所
+///
+/// ## HandOver
+/// 客との受け渡しの場所。机から上へ持っていく
+///
+/// ## ShelvingBox
+/// 返却された本を入れておく箱
+///
+/// ## MoneyBox
+/// 引き出した銭箱。硬貨だけを入れられる
+///
+/// ## Desk
+/// 受け渡しの場所や箱から、机へ戻すとき
+///
+/// ## Archive
+/// 読み終えた手紙を仕舞う書類箱
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropTargetKind {
+    HandOver,
+    ShelvingBox,
+    MoneyBox,
+    Desk,
+    Archive,
+}
+
+///
+/// # 置ける所一つ分
+///
+/// ## area
+/// TaskTableの上での範囲
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropTarget {
+    pub kind: DropTargetKind,
+    pub area: numeric::Rect,
+}
+
+impl DropTarget {
+    pub fn new(kind: DropTargetKind, area: numeric::Rect) -> Self {
+        DropTarget {
+            kind: kind,
+            area: area,
+        }
+    }
+}
+
+///
+/// 示し始めてからelapsedフレーム経ったときの透明度
+///
+pub fn drop_target_alpha(elapsed: Clock, hovered: bool) -> f32 {
+    if hovered {
+        DROP_TARGET_HOVER_ALPHA
+    } else {
+        DROP_TARGET_ALPHA_MIN
+            + (DROP_TARGET_ALPHA_MAX - DROP_TARGET_ALPHA_MIN) * pulse_ratio(elapsed)
+    }
+}
+
+///
+/// # 置ける所を光らせる層
+/// 物を掴んでいる間だけ、置ける所を明滅させる。掴んでいる点が上にある所は濃くする
+///
+/// ## start
+/// 光らせ始めた時刻。掴み直すまでは数え直さない
+///
+pub struct DropTargetLayer {
+    targets: Vec<DropTarget>,
+    hovered: Option<DropTargetKind>,
+    start: Clock,
+    now: Clock,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl DropTargetLayer {
+    pub fn new(depth: i8) -> Self {
+        DropTargetLayer {
+            targets: Vec::new(),
+            hovered: None,
+            start: 0,
+            now: 0,
+            drwob_essential: DrawableObjectEssential::new(true, depth),
+        }
+    }
+
+    ///
+    /// 置ける所を差し替える。pointは掴んでいる点
+    ///
+    pub fn set_targets(&mut self, targets: Vec<DropTarget>, point: numeric::Point2f) {
+        if self.targets.is_empty() {
+            self.start = self.now;
+        }
+
+        self.targets = targets;
+        self.hovered = self.target_at(point);
+    }
+
+    pub fn clear(&mut self) {
+        self.targets.clear();
+        self.hovered = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    ///
+    /// pointの上にある、置ける所の種類
+    ///
+    pub fn target_at(&self, point: numeric::Point2f) -> Option<DropTargetKind> {
+        self.targets
+            .iter()
+            .find(|target| target.area.contains(point))
+            .map(|target| target.kind)
+    }
+
+    pub fn get_hovered(&self) -> Option<DropTargetKind> {
+        self.hovered
+    }
+
+    ///
+    /// 時刻を進める。光らせている間は、描き直しが要るのでtrueを返す
+    ///
+    pub fn update(&mut self, t: Clock) -> bool {
+        self.now = t;
+        self.is_active()
+    }
+}
+
+impl DrawableComponent for DropTargetLayer {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if !self.is_visible() || self.targets.is_empty() {
+            return Ok(());
+        }
+
+        let elapsed = self.now.saturating_sub(self.start);
+        let mut builder = ggraphics::MeshBuilder::new();
+
+        for target in self.targets.iter() {
+            let mut color = ggraphics::Color::from_rgba_u32(DROP_TARGET_COLOR);
+            color.a = drop_target_alpha(elapsed, self.hovered == Some(target.kind));
+
+            shape::Rectangle::new(target.area, ggraphics::DrawMode::fill(), color)
+                .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
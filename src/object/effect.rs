@@ -1,6 +1,8 @@
 use torifune::core::Clock;
 use torifune::graphics::object::*;
 
+use super::tween::*;
+
 ///
 /// # required_time
 /// アニメーションにかける時間
@@ -9,22 +11,7 @@ use torifune::graphics::object::*;
 /// アニメーションが開始する時間, 未来を指定することもできる
 ///
 pub fn fade_in(required_time: Clock, start: Clock) -> GenericEffectFn {
-    Box::new(
-        move |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
-            if start <= t {
-                let elapsed_time = t - start;
-                if elapsed_time < required_time {
-                    obj.set_alpha(elapsed_time as f32 / required_time as f32);
-                    EffectFnStatus::EffectContinue
-                } else {
-                    obj.set_alpha(1.0);
-                    EffectFnStatus::EffectFinish
-                }
-            } else {
-                EffectFnStatus::EffectContinue
-            }
-        },
-    )
+    alpha_tween_effect(tween(0.0, 1.0, required_time, Easing::Linear, start))
 }
 
 pub fn constant_rotating(speed_rad: f32, start: Clock) -> GenericEffectFn {
@@ -52,26 +39,13 @@ pub fn alpha_effect(
     init_alpha: u8,
     fin_alpha: u8,
 ) -> GenericEffectFn {
-    let init_ratio_alpha = init_alpha as f32 / 255.0;
-    let alpha_offset = fin_alpha as i32 - init_alpha as i32;
-    let diff_alpha_per_clock = alpha_offset as f32 / 255.0 / required_time as f32;
-
-    Box::new(
-        move |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
-            if start <= t {
-                let elapsed_time = t - start;
-                if elapsed_time < required_time {
-                    obj.set_alpha(init_ratio_alpha + (diff_alpha_per_clock * elapsed_time as f32));
-                    EffectFnStatus::EffectContinue
-                } else {
-                    obj.set_alpha(fin_alpha as f32 * (1.0 / 255.0));
-                    EffectFnStatus::EffectFinish
-                }
-            } else {
-                EffectFnStatus::EffectContinue
-            }
-        },
-    )
+    alpha_tween_effect(tween(
+        init_alpha as f32 / 255.0,
+        fin_alpha as f32 / 255.0,
+        required_time,
+        Easing::Linear,
+        start,
+    ))
 }
 
 ///
@@ -82,17 +56,22 @@ pub fn alpha_effect(
 /// アニメーションが開始する時間, 未来を指定することもできる
 ///
 pub fn fade_out(required_time: Clock, start: Clock) -> GenericEffectFn {
+    alpha_tween_effect(tween(1.0, 0.0, required_time, Easing::Linear, start))
+}
+
+///
+/// 任意のTweenでアルファ値を変化させる
+///
+pub fn alpha_tween_effect(alpha_tween: Tween<f32>) -> GenericEffectFn {
     Box::new(
         move |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
-            if start <= t {
-                let elapsed_time = t - start;
-                if elapsed_time <= required_time {
-                    obj.set_alpha(1.0 - (elapsed_time as f32 / required_time as f32));
-                    EffectFnStatus::EffectContinue
-                } else {
-                    obj.set_alpha(0.0);
-                    EffectFnStatus::EffectFinish
-                }
+            if !alpha_tween.has_started(t) {
+                return EffectFnStatus::EffectContinue;
+            }
+
+            obj.set_alpha(alpha_tween.value_at(t));
+            if alpha_tween.is_finished_at(t) {
+                EffectFnStatus::EffectFinish
             } else {
                 EffectFnStatus::EffectContinue
             }
@@ -101,20 +80,20 @@ pub fn fade_out(required_time: Clock, start: Clock) -> GenericEffectFn {
 }
 
 pub fn appear_bale_down_from_top(required_time: Clock, called_clock: Clock) -> GenericEffectFn {
+    let crop_tween = tween(0.0, 1.0, required_time, Easing::Linear, called_clock);
+
     Box::new(
         move |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
-            if called_clock <= t {
-                let elapsed_time = t - called_clock;
-                let mut current_crop = obj.get_crop();
-                if elapsed_time < required_time {
-                    current_crop.h = elapsed_time as f32 / required_time as f32;
-                    obj.set_crop(current_crop);
-                    EffectFnStatus::EffectContinue
-                } else {
-                    current_crop.h = 1.0;
-                    obj.set_crop(current_crop);
-                    EffectFnStatus::EffectFinish
-                }
+            if !crop_tween.has_started(t) {
+                return EffectFnStatus::EffectContinue;
+            }
+
+            let mut current_crop = obj.get_crop();
+            current_crop.h = crop_tween.value_at(t);
+            obj.set_crop(current_crop);
+
+            if crop_tween.is_finished_at(t) {
+                EffectFnStatus::EffectFinish
             } else {
                 EffectFnStatus::EffectContinue
             }
@@ -139,20 +118,20 @@ pub fn appear_bale_up_from_bottom(required_time: Clock, called_clock: Clock) ->
 }
 
 pub fn hide_bale_down_from_top(required_time: Clock, called_clock: Clock) -> GenericEffectFn {
+    let crop_tween = tween(1.0, 0.0, required_time, Easing::Linear, called_clock);
+
     Box::new(
         move |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
-            if called_clock <= t {
-                let elapsed_time = t - called_clock;
-                let mut current_crop = obj.get_crop();
-                if elapsed_time < required_time {
-                    current_crop.h = 1.0 - (elapsed_time as f32 / required_time as f32);
-                    obj.set_crop(current_crop);
-                    EffectFnStatus::EffectContinue
-                } else {
-                    current_crop.h = 0.0;
-                    obj.set_crop(current_crop);
-                    EffectFnStatus::EffectFinish
-                }
+            if !crop_tween.has_started(t) {
+                return EffectFnStatus::EffectContinue;
+            }
+
+            let mut current_crop = obj.get_crop();
+            current_crop.h = crop_tween.value_at(t);
+            obj.set_crop(current_crop);
+
+            if crop_tween.is_finished_at(t) {
+                EffectFnStatus::EffectFinish
             } else {
                 EffectFnStatus::EffectContinue
             }
@@ -175,3 +154,32 @@ pub fn hide_bale_up_from_bottom(required_time: Clock, called_clock: Clock) -> Ge
         },
     )
 }
+
+///
+/// # 風に煽られたように、揺れながら回って元の向きに戻る
+///
+/// ## swing_rad
+/// 最初の揺れの大きさ。時間と共に小さくなる
+///
+/// ## swings
+/// required_timeの間に揺れる回数
+///
+pub fn tumble(required_time: Clock, swing_rad: f32, swings: f32, start: Clock) -> GenericEffectFn {
+    Box::new(
+        move |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
+            if t < start {
+                return EffectFnStatus::EffectContinue;
+            }
+
+            let progress = (t - start) as f32 / required_time as f32;
+            if progress >= 1.0 {
+                obj.set_rotation(0.0);
+                return EffectFnStatus::EffectFinish;
+            }
+
+            let swing = (progress * swings * std::f32::consts::PI * 2.0).sin();
+            obj.set_rotation(swing * swing_rad * (1.0 - progress));
+            EffectFnStatus::EffectContinue
+        },
+    )
+}
@@ -2,6 +2,7 @@ use ggez::graphics as ggraphics;
 
 use torifune::core::Clock;
 use torifune::graphics::drawable::*;
+use torifune::graphics::object::shape;
 use torifune::graphics::object::sub_screen;
 use torifune::graphics::object::sub_screen::SubScreen;
 use torifune::graphics::object::tile_batch::*;
@@ -27,6 +28,8 @@ pub struct ScreenTileEffect {
     st_effect_type: SceneTransitionEffectType,
     canvas: SubScreen,
     tiling_effect_type: TilingEffectType,
+    animation_time: Clock,
+    uniform_fade: bool,
 }
 
 impl ScreenTileEffect {
@@ -50,6 +53,9 @@ impl ScreenTileEffect {
             st_effect_type: st_effect_type,
             tiling_effect_type: tiling_effect_type,
             effect_start: t,
+            animation_time: animation_time,
+            // 動きを減らす設定の場合は、タイルを波打たせず全体を一様にフェードさせる
+            uniform_fade: ctx.preferences.is_reduce_motion(),
         }
     }
 
@@ -61,12 +67,25 @@ impl ScreenTileEffect {
 
         for x in (0..crate::core::WINDOW_SIZE_X).step_by(size.x as usize) {
             for y in (0..crate::core::WINDOW_SIZE_Y).step_by(size.y as usize) {
-                let alpha = match self.st_effect_type {
-                    SceneTransitionEffectType::Close => {
-                        elapsed / ((size.x as i16 + x + y) as f32 * self.animation_rate)
+                let alpha = if self.uniform_fade {
+                    let ratio = if self.animation_time == 0 {
+                        1.0
+                    } else {
+                        elapsed / self.animation_time as f32
+                    };
+
+                    match self.st_effect_type {
+                        SceneTransitionEffectType::Close => ratio,
+                        SceneTransitionEffectType::Open => 1.0 - ratio,
                     }
-                    SceneTransitionEffectType::Open => {
-                        1.0 - (elapsed / ((size.x as i16 + x + y) as f32 * self.animation_rate))
+                } else {
+                    match self.st_effect_type {
+                        SceneTransitionEffectType::Close => {
+                            elapsed / ((size.x as i16 + x + y) as f32 * self.animation_rate)
+                        }
+                        SceneTransitionEffectType::Open => {
+                            1.0 - (elapsed / ((size.x as i16 + x + y) as f32 * self.animation_rate))
+                        }
                     }
                 };
 
@@ -129,3 +148,430 @@ impl Effectable for ScreenTileEffect {
         self.update_batch(t);
     }
 }
+
+///
+/// # シーン遷移の演出
+/// 遷移を始めるシーンが種類を選び、Box<dyn SceneTransitionEffect>として保持する
+///
+pub trait SceneTransitionEffect: DrawableComponent + Effectable {}
+
+impl SceneTransitionEffect for ScreenTileEffect {}
+
+#[derive(Clone, Copy)]
+pub enum SceneTransitionEffectKind {
+    Shoji,
+    CircleIris(numeric::Point2f),
+    PageSlide,
+    CrossFade,
+}
+
+///
+/// 画面全体を覆うシーン遷移の演出を生成する
+/// 動きを減らす設定の場合、画面が大きく動く演出はCrossFadeに置き換える
+///
+pub fn new_scene_transition_effect<'a>(
+    ctx: &mut SuzuContext<'a>,
+    kind: SceneTransitionEffectKind,
+    animation_time: Clock,
+    st_effect_type: SceneTransitionEffectType,
+    depth: i8,
+    t: Clock,
+) -> Box<dyn SceneTransitionEffect> {
+    let rect = numeric::Rect::new(
+        0.0,
+        0.0,
+        crate::core::WINDOW_SIZE_X as f32,
+        crate::core::WINDOW_SIZE_Y as f32,
+    );
+
+    let kind = match kind {
+        SceneTransitionEffectKind::CircleIris(_) | SceneTransitionEffectKind::PageSlide
+            if ctx.preferences.is_reduce_motion() =>
+        {
+            SceneTransitionEffectKind::CrossFade
+        }
+        _ => kind,
+    };
+
+    match kind {
+        SceneTransitionEffectKind::Shoji => Box::new(ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            rect,
+            animation_time,
+            st_effect_type,
+            TilingEffectType::WholeTile,
+            depth,
+            t,
+        )),
+        SceneTransitionEffectKind::CircleIris(center) => Box::new(CircleIrisEffect::new(
+            ctx.context,
+            rect,
+            center,
+            animation_time,
+            st_effect_type,
+            depth,
+            t,
+        )),
+        SceneTransitionEffectKind::PageSlide => Box::new(PageSlideEffect::new(
+            ctx.context,
+            rect,
+            animation_time,
+            st_effect_type,
+            depth,
+            t,
+        )),
+        SceneTransitionEffectKind::CrossFade => Box::new(CrossFadeEffect::new(
+            ctx.context,
+            rect,
+            animation_time,
+            st_effect_type,
+            depth,
+            t,
+        )),
+    }
+}
+
+///
+/// 画面が覆われている割合を0.0 ~ 1.0で返す
+///
+fn transition_cover_ratio(
+    st_effect_type: &SceneTransitionEffectType,
+    effect_start: Clock,
+    animation_time: Clock,
+    t: Clock,
+) -> f32 {
+    let ratio = if animation_time == 0 {
+        1.0
+    } else {
+        (t - effect_start) as f32 / animation_time as f32
+    };
+    let ratio = ratio.max(0.0).min(1.0);
+
+    match st_effect_type {
+        SceneTransitionEffectType::Close => ratio,
+        SceneTransitionEffectType::Open => 1.0 - ratio,
+    }
+}
+
+///
+/// # 円形に閉じる(開く)遷移
+/// centerを中心に、円の外側を塗りつぶしていく
+///
+pub struct CircleIrisEffect {
+    mesh: Option<ggraphics::Mesh>,
+    center: numeric::Point2f,
+    max_radius: f32,
+    effect_start: Clock,
+    animation_time: Clock,
+    st_effect_type: SceneTransitionEffectType,
+    canvas: SubScreen,
+}
+
+impl CircleIrisEffect {
+    pub fn new(
+        ctx: &mut ggez::Context,
+        rect: numeric::Rect,
+        center: numeric::Point2f,
+        animation_time: Clock,
+        st_effect_type: SceneTransitionEffectType,
+        depth: i8,
+        t: Clock,
+    ) -> Self {
+        // 中心から最も遠い角までの距離
+        let far_x = util::max(center.x - rect.x, rect.x + rect.w - center.x);
+        let far_y = util::max(center.y - rect.y, rect.y + rect.h - center.y);
+
+        let mut effect = CircleIrisEffect {
+            mesh: None,
+            center: numeric::Point2f::new(center.x - rect.x, center.y - rect.y),
+            max_radius: (far_x * far_x + far_y * far_y).sqrt(),
+            effect_start: t,
+            animation_time: animation_time,
+            st_effect_type: st_effect_type,
+            canvas: SubScreen::new(ctx, rect, depth, ggraphics::Color::from_rgba_u32(0)),
+        };
+        effect.update_mesh(ctx, t);
+
+        effect
+    }
+
+    pub fn update_mesh(&mut self, ctx: &mut ggez::Context, t: Clock) {
+        let cover = transition_cover_ratio(
+            &self.st_effect_type,
+            self.effect_start,
+            self.animation_time,
+            t,
+        );
+        let radius = self.max_radius * (1.0 - cover);
+
+        // 円の外側を太い輪で塗りつぶす
+        let ring_width = self.max_radius * 2.0;
+        let mut builder = ggraphics::MeshBuilder::new();
+        builder
+            .circle(
+                ggraphics::DrawMode::stroke(ring_width),
+                self.center,
+                radius + (ring_width / 2.0),
+                0.5,
+                ggraphics::Color::BLACK,
+            )
+            .expect("failed to create circle");
+
+        self.mesh = builder.build(ctx).ok();
+    }
+}
+
+impl DrawableComponent for CircleIrisEffect {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            if let Some(mesh) = self.mesh.as_ref() {
+                ggraphics::draw(ctx, mesh, ggraphics::DrawParam::default())?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl Effectable for CircleIrisEffect {
+    fn effect(&mut self, ctx: &mut ggez::Context, t: Clock) {
+        self.update_mesh(ctx, t);
+    }
+}
+
+impl SceneTransitionEffect for CircleIrisEffect {}
+
+///
+/// # 頁をめくるように横へ滑る遷移
+/// Closeでは右から紙が滑り込み、Openでは左へ抜けていく
+///
+pub struct PageSlideEffect {
+    page: shape::Rectangle,
+    page_edge: shape::Rectangle,
+    page_mesh: Option<ggraphics::Mesh>,
+    page_size: numeric::Vector2f,
+    effect_start: Clock,
+    animation_time: Clock,
+    st_effect_type: SceneTransitionEffectType,
+    canvas: SubScreen,
+}
+
+impl PageSlideEffect {
+    pub fn new(
+        ctx: &mut ggez::Context,
+        rect: numeric::Rect,
+        animation_time: Clock,
+        st_effect_type: SceneTransitionEffectType,
+        depth: i8,
+        t: Clock,
+    ) -> Self {
+        let mut effect = PageSlideEffect {
+            page: shape::Rectangle::new(
+                numeric::Rect::new(0.0, 0.0, rect.w, rect.h),
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0xe8dcc4ff),
+            ),
+            page_edge: shape::Rectangle::new(
+                numeric::Rect::new(-8.0, 0.0, 8.0, rect.h),
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0x00000060),
+            ),
+            page_mesh: None,
+            page_size: numeric::Vector2f::new(rect.w, rect.h),
+            effect_start: t,
+            animation_time: animation_time,
+            st_effect_type: st_effect_type,
+            canvas: SubScreen::new(ctx, rect, depth, ggraphics::Color::from_rgba_u32(0)),
+        };
+        effect.effect(ctx, t);
+
+        effect
+    }
+
+    fn build_mesh(&self, ctx: &mut ggez::Context) -> Option<ggraphics::Mesh> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        self.page.add_to_builder(&mut builder);
+        self.page_edge.add_to_builder(&mut builder);
+        builder.build(ctx).ok()
+    }
+
+    fn page_offset(&self, t: Clock) -> f32 {
+        let cover = transition_cover_ratio(
+            &self.st_effect_type,
+            self.effect_start,
+            self.animation_time,
+            t,
+        );
+        let width = self.page_size.x;
+
+        match self.st_effect_type {
+            SceneTransitionEffectType::Close => width * (1.0 - cover),
+            SceneTransitionEffectType::Open => -width * (1.0 - cover),
+        }
+    }
+}
+
+impl DrawableComponent for PageSlideEffect {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            if let Some(mesh) = self.page_mesh.as_ref() {
+                ggraphics::draw(ctx, mesh, ggraphics::DrawParam::default())?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl Effectable for PageSlideEffect {
+    fn effect(&mut self, ctx: &mut ggez::Context, t: Clock) {
+        let offset = self.page_offset(t);
+
+        self.page = shape::Rectangle::new(
+            numeric::Rect::new(offset, 0.0, self.page_size.x, self.page_size.y),
+            self.page.get_mode(),
+            self.page.get_color(),
+        );
+        self.page_edge = shape::Rectangle::new(
+            numeric::Rect::new(offset - 8.0, 0.0, 8.0, self.page_size.y),
+            self.page_edge.get_mode(),
+            self.page_edge.get_color(),
+        );
+        self.page_mesh = self.build_mesh(ctx);
+    }
+}
+
+impl SceneTransitionEffect for PageSlideEffect {}
+
+///
+/// # 一色を挟んで切り替わる遷移
+/// Closeで画面が徐々に塗りつぶされ、Openで次の画面が浮かび上がる
+///
+pub struct CrossFadeEffect {
+    effect_start: Clock,
+    animation_time: Clock,
+    st_effect_type: SceneTransitionEffectType,
+    canvas: SubScreen,
+}
+
+impl CrossFadeEffect {
+    pub fn new(
+        ctx: &mut ggez::Context,
+        rect: numeric::Rect,
+        animation_time: Clock,
+        st_effect_type: SceneTransitionEffectType,
+        depth: i8,
+        t: Clock,
+    ) -> Self {
+        let mut effect = CrossFadeEffect {
+            effect_start: t,
+            animation_time: animation_time,
+            st_effect_type: st_effect_type,
+            canvas: SubScreen::new(ctx, rect, depth, ggraphics::Color::from_rgba_u32(0xff)),
+        };
+        effect.update_alpha(t);
+
+        effect
+    }
+
+    pub fn update_alpha(&mut self, t: Clock) {
+        let cover = transition_cover_ratio(
+            &self.st_effect_type,
+            self.effect_start,
+            self.animation_time,
+            t,
+        );
+        self.canvas.set_alpha(cover);
+    }
+}
+
+impl DrawableComponent for CrossFadeEffect {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl Effectable for CrossFadeEffect {
+    fn effect(&mut self, _: &mut ggez::Context, t: Clock) {
+        self.update_alpha(t);
+    }
+}
+
+impl SceneTransitionEffect for CrossFadeEffect {}
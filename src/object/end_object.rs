@@ -1,7 +1,7 @@
+use torifune::core::*;
 use torifune::graphics::drawable::*;
 use torifune::graphics::object::*;
 use torifune::numeric;
-use torifune::{core::*, sound::SoundPlayFlags};
 
 use crate::core::util::read_from_resources_as_string;
 use crate::{
@@ -38,12 +38,11 @@ impl EndSceneFlow {
         let mut result_vtext_list = Vec::new();
         let mut credit_vtext_list = Vec::new();
         let mut book_collection = Vec::new();
-        let mut pos = numeric::Point2f::new(1000.0, 90.0);
 
-        vec![
+        let mut result_lines = vec![
             format!(
                 "評判\n　{}",
-                number_to_jk::number_to_jk(
+                ctx.number_format().number(
                     ctx.take_save_data()
                         .suzunaan_status
                         .get_current_reputation() as u64
@@ -51,35 +50,63 @@ impl EndSceneFlow {
             ),
             format!(
                 "総収入\n　　{}円",
-                number_to_jk::number_to_jk(ctx.take_save_data().task_result.total_money as u64)
+                ctx.number_format()
+                    .number(ctx.take_save_data().task_result.total_money as u64)
             ),
             format!(
                 "接客回数\n　{}回",
-                number_to_jk::number_to_jk(ctx.take_save_data().award_data.customer_count as u64)
+                ctx.number_format()
+                    .number(ctx.take_save_data().award_data.customer_count as u64)
             ),
             format!(
                 "貸出回数\n　{}回",
-                number_to_jk::number_to_jk(ctx.take_save_data().award_data.borrowing_count as u64)
+                ctx.number_format()
+                    .number(ctx.take_save_data().award_data.borrowing_count as u64)
             ),
             format!(
                 "返却回数\n　{}回",
-                number_to_jk::number_to_jk(ctx.take_save_data().award_data.returning_count as u64)
+                ctx.number_format()
+                    .number(ctx.take_save_data().award_data.returning_count as u64)
             ),
             format!(
                 "配架冊数\n　{}冊",
-                number_to_jk::number_to_jk(ctx.take_save_data().award_data.shelving_count as u64)
+                ctx.number_format()
+                    .number(ctx.take_save_data().award_data.shelving_count as u64)
             ),
             format!(
                 "誤評価数\n　{}回",
-                number_to_jk::number_to_jk(
+                ctx.number_format().number(
                     ctx.take_save_data()
                         .award_data
                         .returning_check_mistake_count as u64
                 )
             ),
-        ]
-        .into_iter()
-        .for_each(|s| {
+        ];
+
+        // 決断の振り返りと、それで決まった結末
+        let decision_log = &ctx.take_save_data().decision_log;
+        let decision_summary: Vec<String> = decision_log
+            .summary()
+            .iter()
+            .map(|(kind, count)| {
+                format!(
+                    "{}{}回",
+                    kind.label(),
+                    ctx.number_format().number(*count as u64)
+                )
+            })
+            .collect();
+        if !decision_summary.is_empty() {
+            result_lines.push(format!("決断\n　{}", decision_summary.join("\n　")));
+        }
+        if let Some(ending) = decision_log.get_ending() {
+            result_lines.push(format!("結末\n　{}", ending.title));
+        }
+
+        // 項目が増えた分だけ右から並べ始める
+        let mut pos = numeric::Point2f::new(300.0 + (100.0 * result_lines.len() as f32), 90.0);
+
+        result_lines.into_iter().for_each(|s| {
             let mut vtext = VerticalText::new(
                 s,
                 pos,
@@ -331,15 +358,7 @@ On-Jin ～音人～
             animation_start += 400;
         }
 
-        ctx.play_sound_as_bgm(
-            SoundID::EndBGM,
-            Some(SoundPlayFlags::new(
-                3000,
-                1.0,
-                true,
-                ctx.config.get_bgm_volume(),
-            )),
-        );
+        ctx.play_scene_bgm(SceneID::End, 3000);
 
         ctx.permanent_save_data.story_cleared();
 
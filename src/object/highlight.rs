@@ -0,0 +1,325 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+///
+/// 縁取りの太さ(ピクセル)
+///
+pub const HIGHLIGHT_OUTLINE_WIDTH: f32 = 3.0;
+
+///
+/// 明滅が一巡りするフレーム数
+///
+pub const HIGHLIGHT_PULSE_PERIOD: Clock = 60;
+
+///
+/// 明滅が一番強いときに、元の色から目立たせる色へ寄せる割合
+///
+pub const HIGHLIGHT_PULSE_STRENGTH: f32 = 0.6;
+
+///
+/// 明滅する縁取りが一番薄いときの濃さ。一番濃いときは指定した色のまま
+///
+pub const HIGHLIGHT_PULSE_OUTLINE_MIN_ALPHA: f32 = 0.3;
+
+///
+/// 机の上で選んでいる物の縁取りの色
+///
+pub const SELECTION_HIGHLIGHT_COLOR: u32 = 0xcc8822ff;
+
+///
+/// 説明の途中で指し示す物の色
+///
+pub const TUTORIAL_HIGHLIGHT_COLOR: u32 = 0xffd700ff;
+
+///
+/// 呼んでいる客を知らせる色
+///
+pub const CALLING_HIGHLIGHT_COLOR: u32 = 0xff8844ff;
+
+///
+/// 客が話している間、客の影に付ける縁取りの色
+///
+pub const FOCUS_HIGHLIGHT_COLOR: u32 = 0xfff4d6aa;
+
+///
+/// 調べる構えのとき、クリックで覚えられる物に付ける縁取りの色
+///
+pub const INSPECT_HIGHLIGHT_COLOR: u32 = 0x66ccffff;
+
+///
+/// # 目立たせ方
+///
+/// ## Outline
+/// 目立たせる色を乗せた写しを八方にずらして下に敷き、縁取りのように見せる
+///
+/// ## Pulse
+/// 元の色と目立たせる色の間を、ゆっくり行き来させる
+///
+/// ## PulseOutline
+/// 縁取りの濃さを、ゆっくり明滅させる
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HighlightStyle {
+    Outline,
+    Pulse,
+    PulseOutline,
+}
+
+///
+/// # 物を目立たせる指定
+/// 目立たせたい物が持ち、描くときにdraw_highlightedへ渡す
+///
+/// ## start
+/// 目立たせ始めた時刻。明滅はここから数える
+///
+/// ## now
+/// 最後にupdateで渡された時刻
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Highlight {
+    style: HighlightStyle,
+    color: ggraphics::Color,
+    start: Clock,
+    now: Clock,
+}
+
+impl Highlight {
+    pub fn new(style: HighlightStyle, color: ggraphics::Color, t: Clock) -> Self {
+        Highlight {
+            style: style,
+            color: color,
+            start: t,
+            now: t,
+        }
+    }
+
+    pub fn outline(color: ggraphics::Color, t: Clock) -> Self {
+        Self::new(HighlightStyle::Outline, color, t)
+    }
+
+    pub fn pulse(color: ggraphics::Color, t: Clock) -> Self {
+        Self::new(HighlightStyle::Pulse, color, t)
+    }
+
+    pub fn pulse_outline(color: ggraphics::Color, t: Clock) -> Self {
+        Self::new(HighlightStyle::PulseOutline, color, t)
+    }
+
+    pub fn get_style(&self) -> HighlightStyle {
+        self.style
+    }
+
+    pub fn get_color(&self) -> ggraphics::Color {
+        self.color
+    }
+
+    ///
+    /// 時刻を進める。描き直しが要るならtrueを返す
+    ///
+    pub fn update(&mut self, t: Clock) -> bool {
+        self.now = t;
+        self.is_animated()
+    }
+
+    ///
+    /// 時間と共に見た目が変わるか
+    ///
+    pub fn is_animated(&self) -> bool {
+        match self.style {
+            HighlightStyle::Pulse | HighlightStyle::PulseOutline => true,
+            HighlightStyle::Outline => false,
+        }
+    }
+
+    ///
+    /// 今の明滅の強さ(0.0 - 1.0)
+    ///
+    pub fn pulse_ratio(&self) -> f32 {
+        pulse_ratio(self.now.saturating_sub(self.start))
+    }
+
+    ///
+    /// 元の色baseに、今の明滅の強さで目立たせる色を混ぜる
+    ///
+    pub fn tint(&self, base: ggraphics::Color) -> ggraphics::Color {
+        mix_color(
+            base,
+            self.color,
+            self.pulse_ratio() * HIGHLIGHT_PULSE_STRENGTH,
+        )
+    }
+
+    ///
+    /// 縁取りに使う色。明滅する縁取りなら、今の明滅の強さで濃さを変える
+    ///
+    pub fn outline_color(&self) -> ggraphics::Color {
+        let mut color = self.color;
+        if self.style == HighlightStyle::PulseOutline {
+            color.a *= HIGHLIGHT_PULSE_OUTLINE_MIN_ALPHA
+                + (1.0 - HIGHLIGHT_PULSE_OUTLINE_MIN_ALPHA) * self.pulse_ratio();
+        }
+
+        color
+    }
+}
+
+///
+/// # 調べる構え
+/// Shiftを押している間だけ、クリックでHoldDataを覚えられる物を明滅する縁取りで示す
+/// TaskTableが持ち、机、客の居る所、返却箱へ写しを配って、それぞれが描くときに見る
+///
+/// ## highlight
+/// 構えている間の縁取り。構えていなければNone
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InspectMode {
+    highlight: Option<Highlight>,
+}
+
+impl InspectMode {
+    pub fn new() -> Self {
+        InspectMode { highlight: None }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.highlight.is_some()
+    }
+
+    ///
+    /// 構えているかを渡す。構え始めたときと止めたときはtrueを返す
+    ///
+    pub fn set_active(&mut self, active: bool, t: Clock) -> bool {
+        if active == self.is_active() {
+            return false;
+        }
+
+        self.highlight = if active {
+            Some(Highlight::pulse_outline(
+                ggraphics::Color::from_rgba_u32(INSPECT_HIGHLIGHT_COLOR),
+                t,
+            ))
+        } else {
+            None
+        };
+
+        true
+    }
+
+    ///
+    /// 時刻を進める。構えている間は明滅するので、描き直しが要るならtrueを返す
+    ///
+    pub fn update(&mut self, t: Clock) -> bool {
+        match self.highlight.as_mut() {
+            Some(highlight) => highlight.update(t),
+            None => false,
+        }
+    }
+
+    ///
+    /// 物を描くときの縁取り。構えていて、クリックでHoldDataを覚えられる物だけに付ける
+    ///
+    pub fn highlight_for(&self, has_click_data: bool) -> Option<Highlight> {
+        if has_click_data {
+            self.highlight
+        } else {
+            None
+        }
+    }
+}
+
+///
+/// 目立たせ始めてからelapsedフレーム経ったときの明滅の強さ。0.0から始まり、周期の半ばで1.0になる
+///
+pub fn pulse_ratio(elapsed: Clock) -> f32 {
+    let phase = (elapsed % HIGHLIGHT_PULSE_PERIOD) as f32 / HIGHLIGHT_PULSE_PERIOD as f32;
+    (1.0 - (phase * std::f32::consts::PI * 2.0).cos()) / 2.0
+}
+
+///
+/// baseからtoへratioだけ寄せた色。透明度はbaseのまま
+///
+pub fn mix_color(base: ggraphics::Color, to: ggraphics::Color, ratio: f32) -> ggraphics::Color {
+    let ratio = ratio.max(0.0).min(1.0);
+    let mix = |a: f32, b: f32| a + ((b - a) * ratio);
+
+    ggraphics::Color::new(
+        mix(base.r, to.r),
+        mix(base.g, to.g),
+        mix(base.b, to.b),
+        base.a,
+    )
+}
+
+///
+/// 縁取りの写しをずらす八方の向き
+///
+pub fn outline_offsets(width: f32) -> [numeric::Vector2f; 8] {
+    let diagonal = width * std::f32::consts::FRAC_1_SQRT_2;
+
+    [
+        numeric::Vector2f::new(-width, 0.0),
+        numeric::Vector2f::new(width, 0.0),
+        numeric::Vector2f::new(0.0, -width),
+        numeric::Vector2f::new(0.0, width),
+        numeric::Vector2f::new(-diagonal, -diagonal),
+        numeric::Vector2f::new(diagonal, -diagonal),
+        numeric::Vector2f::new(-diagonal, diagonal),
+        numeric::Vector2f::new(diagonal, diagonal),
+    ]
+}
+
+///
+/// # 目立たせて描けるもの
+/// TextureObjectなら何でも使える。highlightがNoneなら、いつも通りに描く
+///
+pub trait Highlightable {
+    fn draw_highlighted(
+        &mut self,
+        ctx: &mut ggez::Context,
+        highlight: Option<&Highlight>,
+    ) -> ggez::GameResult<()>;
+}
+
+impl<T: TextureObject + ?Sized> Highlightable for T {
+    fn draw_highlighted(
+        &mut self,
+        ctx: &mut ggez::Context,
+        highlight: Option<&Highlight>,
+    ) -> ggez::GameResult<()> {
+        let highlight = match highlight {
+            Some(highlight) if self.is_visible() => highlight,
+            _ => return self.draw(ctx),
+        };
+
+        let color = self.get_color();
+
+        match highlight.get_style() {
+            HighlightStyle::Outline | HighlightStyle::PulseOutline => {
+                let position = self.get_position();
+
+                self.set_color(highlight.outline_color());
+                for offset in outline_offsets(HIGHLIGHT_OUTLINE_WIDTH).iter() {
+                    self.set_position(numeric::Point2f::new(
+                        position.x + offset.x,
+                        position.y + offset.y,
+                    ));
+                    self.draw(ctx)?;
+                }
+
+                self.set_position(position);
+                self.set_color(color);
+                self.draw(ctx)
+            }
+            HighlightStyle::Pulse => {
+                self.set_color(highlight.tint(color));
+                let result = self.draw(ctx);
+                self.set_color(color);
+                result
+            }
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+use torifune::numeric;
+use torifune::roundup2f;
+
+use crate::core::ledger_audit::*;
+use crate::core::{FontID, SuzuContext, TileBatchTextureID};
+use crate::object::util_object::*;
+use crate::set_table_frame_cell_center;
+
+use crate::core::number_format::NumberFormat;
+
+///
+/// # 監査表
+/// 帳簿を写した行を枠に並べる。行を押すと印を付ける
+///
+/// ## cell_texts
+/// 見出しの行を除いた、行ごとの欄の文字
+///
+pub struct LedgerAuditSheet {
+    audit: LedgerAudit,
+    frame: TableFrame,
+    header_texts: Vec<UniText>,
+    cell_texts: Vec<Vec<UniText>>,
+    status_text: UniText,
+    timer_text: UniText,
+    number_format: NumberFormat,
+}
+
+impl LedgerAuditSheet {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, pos: numeric::Point2f, audit: LedgerAudit) -> Self {
+        let rows = audit.get_entries().len();
+        let frame = TableFrame::new(
+            ctx.resource,
+            pos,
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![40.0; rows + 1], vec![180.0, 200.0, 200.0, 160.0]),
+            numeric::Vector2f::new(0.5, 0.5),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let header_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0x222222ff),
+        );
+        let cell_font = FontInformation::new(
+            ctx.resource.get_font(FontID::BitMap1),
+            numeric::Vector2f::new(20.0, 20.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let mut header_texts = Vec::new();
+        for (index, s) in vec!["御客", "貸出日", "返却日", "状態"].iter().enumerate() {
+            let mut text = UniText::new(
+                s.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                header_font.clone(),
+            );
+            set_table_frame_cell_center!(
+                ctx.context,
+                frame,
+                text,
+                numeric::Vector2u::new(index as u32, 0)
+            );
+            header_texts.push(text);
+        }
+
+        let mut cell_texts = Vec::new();
+        for (row, entry) in audit.get_entries().iter().enumerate() {
+            let status = if entry.returned {
+                format!("{}冊中{}冊", entry.books, entry.statuses)
+            } else {
+                "貸出中".to_string()
+            };
+
+            let mut texts = Vec::new();
+            for (column, s) in vec![
+                entry.customer.clone(),
+                ctx.number_format().short_date(&entry.rental_date),
+                ctx.number_format().short_date(&entry.return_date),
+                status,
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let mut text = UniText::new(
+                    s,
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    cell_font.clone(),
+                );
+                set_table_frame_cell_center!(
+                    ctx.context,
+                    frame,
+                    text,
+                    numeric::Vector2u::new(column as u32, row as u32 + 1)
+                );
+                texts.push(text);
+            }
+            cell_texts.push(texts);
+        }
+
+        let area = frame.get_area();
+        let status_font = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let status_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(area.x, area.y + area.h + 30.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            status_font.clone(),
+        );
+        let timer_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(area.x + area.w - 200.0, area.y + area.h + 30.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            status_font,
+        );
+
+        let mut sheet = LedgerAuditSheet {
+            audit: audit,
+            frame: frame,
+            header_texts: header_texts,
+            cell_texts: cell_texts,
+            status_text: status_text,
+            timer_text: timer_text,
+            number_format: ctx.number_format(),
+        };
+
+        sheet.update_status();
+        sheet.update_timer(AUDIT_TIME_LIMIT);
+        sheet
+    }
+
+    fn update_status(&mut self) {
+        self.status_text.replace_text(format!(
+            "印{}箇所　外レ{}回",
+            self.number_format.number(self.audit.found() as u64),
+            self.number_format.number(self.audit.wrong_marks() as u64)
+        ));
+    }
+
+    pub fn update_timer(&mut self, remaining_time: Clock) {
+        self.timer_text
+            .replace_text(format!("残リ時間 {}秒", remaining_time / 60));
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.audit.is_complete()
+    }
+
+    pub fn finish(&self) -> AuditResult {
+        self.audit.finish()
+    }
+
+    ///
+    /// 押された行に印を付ける。見出しの行や、印を付け済みの行ではNone
+    ///
+    pub fn click_handler(
+        &mut self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> Option<bool> {
+        let grid = self.frame.get_grid_position(ctx, point)?;
+        if grid.y == 0 {
+            return None;
+        }
+
+        let hit = self.audit.mark(grid.y as usize - 1)?;
+        self.update_status();
+        Some(hit)
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let area = self.frame.get_area();
+        let frame_pos = self.frame.get_position();
+        let mut builder = ggraphics::MeshBuilder::new();
+
+        shape::Rectangle::new(
+            area,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xf6eee0ff),
+        )
+        .add_to_builder(&mut builder);
+
+        // 印を付けた行は、当たりなら朱、外れなら灰で塗る
+        for row in 0..self.audit.get_entries().len() {
+            if !self.audit.is_marked(row) {
+                continue;
+            }
+
+            let color = if self.audit.get_entries()[row].find_flaw().is_some() {
+                0xcc444466
+            } else {
+                0x66666666
+            };
+            let center = self
+                .frame
+                .get_center_of(numeric::Vector2u::new(0, row as u32 + 1), frame_pos);
+            shape::Rectangle::new(
+                numeric::Rect::new(area.x, center.y - 20.0, area.w, 40.0),
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(color),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        self.frame.draw(ctx)?;
+
+        for text in self.header_texts.iter_mut() {
+            text.draw(ctx)?;
+        }
+
+        for texts in self.cell_texts.iter_mut() {
+            for text in texts.iter_mut() {
+                text.draw(ctx)?;
+            }
+        }
+
+        self.status_text.draw(ctx)?;
+        self.timer_text.draw(ctx)
+    }
+}
+
+///
+/// # 監査の結果
+///
+pub struct LedgerAuditResultPanel {
+    rect: numeric::Rect,
+    lines: Vec<UniText>,
+}
+
+impl LedgerAuditResultPanel {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, rect: numeric::Rect, result: &AuditResult) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let texts = vec![
+            "今週ノ帳簿監査".to_string(),
+            format!(
+                "見ツケタ不整合　{}件",
+                ctx.number_format().number(result.found as u64)
+            ),
+            format!(
+                "見逃シ　{}件",
+                ctx.number_format().number(result.missed as u64)
+            ),
+            format!(
+                "外レ　{}回",
+                ctx.number_format().number(result.wrong_marks as u64)
+            ),
+            format!("取リ戻シタ売上　{}円", result.recovered_money()),
+        ];
+
+        let lines = texts
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| {
+                UniText::new(
+                    text,
+                    numeric::Point2f::new(rect.x + 40.0, rect.y + 30.0 + (index as f32 * 44.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )
+            })
+            .collect();
+
+        LedgerAuditResultPanel {
+            rect: rect,
+            lines: lines,
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xf6eee0ee),
+        )
+        .add_to_builder(&mut builder);
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::stroke(2.0),
+            ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+        )
+        .add_to_builder(&mut builder);
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        for line in self.lines.iter_mut() {
+            line.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+}
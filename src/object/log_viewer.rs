@@ -0,0 +1,121 @@
+use ggez::graphics as ggraphics;
+
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::shape::MeshShape;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+use crate::core::logger::{self, LogLevel, LogRecord};
+
+///
+/// 画面に出す記録の行数
+///
+const LOG_VIEWER_LINES: usize = 20;
+
+const LOG_VIEWER_FONT_SIZE: f32 = 16.0;
+
+const LOG_VIEWER_LINE_SPACING: f32 = 4.0;
+
+///
+/// これより長い行は切って出す
+///
+const LOG_VIEWER_MAX_CHARS: usize = 140;
+
+fn level_color(level: LogLevel) -> ggraphics::Color {
+    ggraphics::Color::from_rgba_u32(match level {
+        LogLevel::Error => 0xff6060ff,
+        LogLevel::Warn => 0xf0c060ff,
+        LogLevel::Info => 0xf0f0f0ff,
+        LogLevel::Debug => 0xa0c8f0ff,
+        LogLevel::Trace => 0x909090ff,
+    })
+}
+
+///
+/// # 最近の記録を画面の下に重ねて出すもの
+/// 新しい記録が増えたときだけ文字を作り直す
+///
+/// ## shown_total
+/// 今の文字を作ったときに、それまで残されていた記録の数
+///
+pub struct LogViewer {
+    area: numeric::Rect,
+    font: ggraphics::Font,
+    lines: Vec<UniText>,
+    shown_total: Option<u64>,
+}
+
+impl LogViewer {
+    pub fn new(area: numeric::Rect, font: ggraphics::Font) -> Self {
+        LogViewer {
+            area: area,
+            font: font,
+            lines: Vec::new(),
+            shown_total: None,
+        }
+    }
+
+    fn record_to_text(&self, record: &LogRecord, index: usize) -> UniText {
+        let full_line = record.to_line();
+        let mut line: String = full_line.chars().take(LOG_VIEWER_MAX_CHARS).collect();
+        if line.len() < full_line.len() {
+            line.push('…');
+        }
+
+        UniText::new(
+            line,
+            numeric::Point2f::new(
+                self.area.x + 8.0,
+                self.area.y
+                    + 8.0
+                    + (index as f32 * (LOG_VIEWER_FONT_SIZE + LOG_VIEWER_LINE_SPACING)),
+            ),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                self.font,
+                numeric::Vector2f::new(LOG_VIEWER_FONT_SIZE, LOG_VIEWER_FONT_SIZE),
+                level_color(record.level),
+            ),
+        )
+    }
+
+    ///
+    /// 記録が増えていれば文字を作り直してtrueを返す
+    ///
+    pub fn update(&mut self) -> bool {
+        let (records, total) = logger::recent_records(LOG_VIEWER_LINES);
+        if self.shown_total == Some(total) {
+            return false;
+        }
+
+        self.lines = records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| self.record_to_text(record, index))
+            .collect();
+        self.shown_total = Some(total);
+
+        true
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        shape::Rectangle::new(
+            self.area,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0x000000c0),
+        )
+        .add_to_builder(&mut builder);
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        for line in self.lines.iter_mut() {
+            line.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,583 @@
+
+use ggez::graphics as ggraphics;
+
+use sub_screen::SubScreen;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::shape::MeshShape;
+use torifune::graphics::object::sub_screen;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+use crate::core::map_parser as mp;
+use crate::core::*;
+use crate::object::map_object::*;
+use crate::scene::SceneID;
+use crate::{log_info, log_warn};
+
+const EDITOR_FORM_ROW_HEIGHT: f32 = 36.0;
+const EDITOR_FORM_WIDTH: f32 = 420.0;
+
+///
+/// エディタで切り替えられる遷移先のシーン
+///
+const EDITOR_SWITCH_SCENES: [SceneID; 6] = [
+    SceneID::MainDesk,
+    SceneID::SuzunaShop,
+    SceneID::Scenario,
+    SceneID::Save,
+    SceneID::Title,
+    SceneID::End,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum EditorEventKind {
+    Text,
+    SwitchScene,
+    BookShelf,
+    Builtin,
+}
+
+impl EditorEventKind {
+    fn next(&self) -> Self {
+        match self {
+            Self::Text => Self::SwitchScene,
+            Self::SwitchScene => Self::BookShelf,
+            Self::BookShelf => Self::Builtin,
+            Self::Builtin => Self::Text,
+        }
+    }
+
+    fn to_str(&self) -> &str {
+        match self {
+            Self::Text => "テキスト",
+            Self::SwitchScene => "シーン切替",
+            Self::BookShelf => "本棚",
+            Self::Builtin => "組込イベント",
+        }
+    }
+}
+
+///
+/// # 編集中のイベントの内容
+/// 種類を切り替えても他の種類の入力値を保持しておく
+///
+struct MapEventDraft {
+    kind: EditorEventKind,
+    trigger: EventTrigger,
+    text: String,
+    switch_scene_index: usize,
+    shelf_begin: u16,
+    shelf_end: u16,
+    shelf_front: bool,
+    builtin_symbol: BuiltinEventSymbol,
+}
+
+impl MapEventDraft {
+    fn new() -> Self {
+        MapEventDraft {
+            kind: EditorEventKind::BookShelf,
+            trigger: EventTrigger::Action,
+            text: "（未設定）".to_string(),
+            switch_scene_index: 0,
+            shelf_begin: 1,
+            shelf_end: 1,
+            shelf_front: false,
+            builtin_symbol: BuiltinEventSymbol::SelectShelvingBook,
+        }
+    }
+
+    fn from_element(element: &MapEventElement) -> Self {
+        let mut draft = Self::new();
+        draft.trigger = element.get_trigger_method();
+
+        match element {
+            MapEventElement::TextEvent(text) => {
+                draft.kind = EditorEventKind::Text;
+                draft.text = text.get_text().to_string();
+            }
+            MapEventElement::SwitchScene(switch_scene) => {
+                draft.kind = EditorEventKind::SwitchScene;
+                draft.switch_scene_index = EDITOR_SWITCH_SCENES
+                    .iter()
+                    .position(|id| *id == switch_scene.get_switch_scene_id())
+                    .unwrap_or(0);
+            }
+            MapEventElement::BookStoreEvent(book_store_event) => {
+                let shelf_info = book_store_event.get_book_shelf_info();
+                draft.kind = EditorEventKind::BookShelf;
+                draft.shelf_begin = shelf_info.get_begin_number();
+                draft.shelf_end = shelf_info.get_end_number();
+                draft.shelf_front = shelf_info.is_front();
+            }
+            MapEventElement::BuiltinEvent(builtin_event) => {
+                draft.kind = EditorEventKind::Builtin;
+                draft.builtin_symbol = builtin_event.get_event_symbol();
+            }
+        }
+
+        draft
+    }
+
+    fn to_element(&self) -> MapEventElement {
+        match self.kind {
+            EditorEventKind::Text => {
+                MapEventElement::TextEvent(MapTextEvent::new(self.trigger, &self.text))
+            }
+            EditorEventKind::SwitchScene => MapEventElement::SwitchScene(MapEventSceneSwitch::new(
+                self.trigger,
+                EDITOR_SWITCH_SCENES[self.switch_scene_index],
+            )),
+            EditorEventKind::BookShelf => {
+                let mut shelf_info = BookShelfInformation::new(self.shelf_begin, self.shelf_end);
+                shelf_info.set_front(self.shelf_front);
+                MapEventElement::BookStoreEvent(BookStoreEvent::new(self.trigger, shelf_info))
+            }
+            EditorEventKind::Builtin => {
+                MapEventElement::BuiltinEvent(BuiltinEvent::new(self.trigger, self.builtin_symbol))
+            }
+        }
+    }
+
+    ///
+    /// フォームに表示する各行の文字列
+    ///
+    fn row_strings(&self) -> Vec<String> {
+        let mut rows = vec![
+            format!("種類: {}", self.kind.to_str()),
+            format!(
+                "発動: {}",
+                match self.trigger {
+                    EventTrigger::Action => "調べる",
+                    EventTrigger::Touch => "触れる",
+                }
+            ),
+        ];
+
+        match self.kind {
+            EditorEventKind::Text => rows.push(format!("文章: {}", self.text)),
+            EditorEventKind::SwitchScene => rows.push(format!(
+                "遷移先: {}",
+                EDITOR_SWITCH_SCENES[self.switch_scene_index].to_str()
+            )),
+            EditorEventKind::BookShelf => {
+                rows.push(format!("開始番号: < {} >", self.shelf_begin));
+                rows.push(format!("終了番号: < {} >", self.shelf_end));
+                rows.push(format!(
+                    "手前の棚: {}",
+                    if self.shelf_front { "はい" } else { "いいえ" }
+                ));
+            }
+            EditorEventKind::Builtin => {
+                rows.push(format!("組込: {}", self.builtin_symbol.to_str()))
+            }
+        }
+
+        rows.push("[適用]  [削除]  [閉じる]".to_string());
+        rows
+    }
+
+    ///
+    /// 行がクリックされたときに値を変更する
+    /// increaseは行の右半分がクリックされたかどうか
+    ///
+    fn click_row(&mut self, row: usize, increase: bool) {
+        match row {
+            0 => self.kind = self.kind.next(),
+            1 => {
+                self.trigger = match self.trigger {
+                    EventTrigger::Action => EventTrigger::Touch,
+                    EventTrigger::Touch => EventTrigger::Action,
+                }
+            }
+            2 => match self.kind {
+                EditorEventKind::SwitchScene => {
+                    self.switch_scene_index =
+                        (self.switch_scene_index + 1) % EDITOR_SWITCH_SCENES.len();
+                }
+                EditorEventKind::BookShelf => {
+                    self.shelf_begin = Self::step_number(self.shelf_begin, increase);
+                    if self.shelf_end < self.shelf_begin {
+                        self.shelf_end = self.shelf_begin;
+                    }
+                }
+                // 文章はキーボード入力が無いので、書き出したTOMLを直接編集する
+                _ => (),
+            },
+            3 => {
+                if self.kind == EditorEventKind::BookShelf {
+                    self.shelf_end = Self::step_number(self.shelf_end, increase);
+                    if self.shelf_end < self.shelf_begin {
+                        self.shelf_begin = self.shelf_end;
+                    }
+                }
+            }
+            4 => {
+                if self.kind == EditorEventKind::BookShelf {
+                    self.shelf_front = !self.shelf_front;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn step_number(n: u16, increase: bool) -> u16 {
+        if increase {
+            n.saturating_add(1)
+        } else if n > 1 {
+            n - 1
+        } else {
+            1
+        }
+    }
+}
+
+pub enum MapEditorAction {
+    Keep,
+    Apply(numeric::Point2i, MapEventElement),
+    Remove(numeric::Point2i),
+    Close,
+}
+
+///
+/// # イベントを編集するフォーム
+///
+/// ## target
+/// 編集対象のタイル座標
+///
+struct MapEventEditorForm {
+    canvas: SubScreen,
+    target: numeric::Point2i,
+    draft: MapEventDraft,
+    row_texts: Vec<UniText>,
+    font_info: FontInformation,
+    redraw: bool,
+}
+
+impl MapEventEditorForm {
+    fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        pos: numeric::Point2f,
+        target: numeric::Point2i,
+        draft: MapEventDraft,
+        font_info: FontInformation,
+    ) -> Self {
+        let mut form = MapEventEditorForm {
+            canvas: SubScreen::new(
+                ctx.context,
+                numeric::Rect::new(
+                    pos.x,
+                    pos.y,
+                    EDITOR_FORM_WIDTH,
+                    EDITOR_FORM_ROW_HEIGHT * 6.0,
+                ),
+                0,
+                ggraphics::Color::from_rgba_u32(0xffffffdd),
+            ),
+            target: target,
+            draft: draft,
+            row_texts: Vec::new(),
+            font_info: font_info,
+            redraw: true,
+        };
+
+        form.update_rows();
+        form
+    }
+
+    fn update_rows(&mut self) {
+        let mut rows = vec![format!(
+            "イベント編集 ({}, {})",
+            self.target.x, self.target.y
+        )];
+        rows.extend(self.draft.row_strings());
+
+        self.row_texts = rows
+            .iter()
+            .enumerate()
+            .map(|(index, row)| {
+                UniText::new(
+                    row.to_string(),
+                    numeric::Point2f::new(10.0, 6.0 + (index as f32 * EDITOR_FORM_ROW_HEIGHT)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    self.font_info.clone(),
+                )
+            })
+            .collect();
+        self.redraw = true;
+    }
+
+    fn contains(&self, point: numeric::Point2f) -> bool {
+        self.canvas.contains(point)
+    }
+
+    fn click_handler(&mut self, point: numeric::Point2f) -> MapEditorAction {
+        let rpoint = self.canvas.relative_point(point);
+        let row = (rpoint.y / EDITOR_FORM_ROW_HEIGHT) as usize;
+
+        // 0行目は見出し
+        if row == 0 {
+            return MapEditorAction::Keep;
+        }
+
+        // 最終行はボタン
+        if row == self.row_texts.len() - 1 {
+            let ratio = rpoint.x / EDITOR_FORM_WIDTH;
+            return if ratio < 0.3 {
+                MapEditorAction::Apply(self.target, self.draft.to_element())
+            } else if ratio < 0.6 {
+                MapEditorAction::Remove(self.target)
+            } else {
+                MapEditorAction::Close
+            };
+        }
+
+        self.draft
+            .click_row(row - 1, rpoint.x > EDITOR_FORM_WIDTH / 2.0);
+        self.update_rows();
+
+        MapEditorAction::Keep
+    }
+}
+
+impl DrawableComponent for MapEventEditorForm {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            if self.redraw {
+                self.redraw = false;
+                sub_screen::stack_screen(ctx, &self.canvas);
+
+                for text in self.row_texts.iter_mut() {
+                    text.draw(ctx)?;
+                }
+
+                sub_screen::pop_screen(ctx);
+            }
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+///
+/// # 開発用のマップイベントエディタ
+/// map_editorフィーチャが有効な場合のみ使える
+///
+/// ## enabled
+/// エディタモードに入っているかどうか
+///
+/// ## form
+/// 編集中のイベントのフォーム
+///
+pub struct MapEventEditor {
+    enabled: bool,
+    form: Option<MapEventEditorForm>,
+    header: UniText,
+    font_info: FontInformation,
+}
+
+impl MapEventEditor {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(22.0, 22.0),
+            ggraphics::Color::from_rgba_u32(0x000000ff),
+        );
+
+        MapEventEditor {
+            enabled: false,
+            form: None,
+            header: UniText::new(
+                "イベント編集モード (Action5で終了)".to_string(),
+                numeric::Point2f::new(10.0, 740.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                FontInformation::new(
+                    ctx.resource.get_font(FontID::Cinema),
+                    numeric::Vector2f::new(20.0, 20.0),
+                    ggraphics::Color::from_rgba_u32(0xff2020ff),
+                ),
+            ),
+            font_info: font_info,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.form = None;
+        log_info!("map editor: {}", if self.enabled { "on" } else { "off" });
+    }
+
+    ///
+    /// クリックされたタイルのフォームを開くか、フォームの操作を行う
+    /// 変更があった場合はイベントマップのTOMLを書き戻す
+    ///
+    pub fn click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        camera: &numeric::Rect,
+        tile_size: numeric::Vector2f,
+        event_map: &mut MapEventList,
+    ) {
+        if !self.enabled {
+            return;
+        }
+
+        ctx.process_utility.redraw();
+
+        if let Some(form) = self.form.as_mut() {
+            if form.contains(point) {
+                match form.click_handler(point) {
+                    MapEditorAction::Apply(position, element) => {
+                        event_map.register_event(position, element);
+                        Self::write_event_map(event_map);
+                        self.form = None;
+                    }
+                    MapEditorAction::Remove(position) => {
+                        event_map.remove_event(position);
+                        Self::write_event_map(event_map);
+                        self.form = None;
+                    }
+                    MapEditorAction::Close => self.form = None,
+                    MapEditorAction::Keep => (),
+                }
+                return;
+            }
+        }
+
+        let target = numeric::Point2i::new(
+            ((point.x + camera.x) / tile_size.x) as i32,
+            ((point.y + camera.y) / tile_size.y) as i32,
+        );
+
+        let draft = match event_map.get_event(target) {
+            Some(element) => MapEventDraft::from_element(element),
+            None => MapEventDraft::new(),
+        };
+
+        // クリックした位置を隠さないように画面の反対側に出す
+        let form_pos = if point.x < 683.0 {
+            numeric::Point2f::new(1366.0 - EDITOR_FORM_WIDTH - 20.0, 80.0)
+        } else {
+            numeric::Point2f::new(20.0, 80.0)
+        };
+
+        self.form = Some(MapEventEditorForm::new(
+            ctx,
+            form_pos,
+            target,
+            draft,
+            self.font_info.clone(),
+        ));
+    }
+
+    fn write_event_map(event_map: &MapEventList) {
+        let mut file_path = resource_locator::resource_dir();
+        file_path.push(event_map.get_source_path().trim_start_matches('/'));
+
+        match std::fs::write(&file_path, event_map.to_toml_string()) {
+            Ok(_) => log_info!("map editor: wrote {}", file_path.display()),
+            Err(e) => log_warn!("map editor: failed to write {}: {}", file_path.display(), e),
+        }
+    }
+
+    ///
+    /// イベントの発動範囲と編集中のタイルを描画する
+    ///
+    pub fn draw_preview(
+        &mut self,
+        ctx: &mut ggez::Context,
+        event_map: &MapEventList,
+        camera: &numeric::Rect,
+        tile_size: numeric::Vector2f,
+    ) -> ggez::GameResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let mut builder = ggraphics::MeshBuilder::new();
+        let mut has_shape = false;
+        let screen = numeric::Rect::new(0.0, 0.0, 1366.0, 768.0);
+
+        for (position, element) in event_map.iter_events() {
+            let display_pos = mp::map_to_display(
+                &numeric::Point2f::new(
+                    position.x as f32 * tile_size.x,
+                    position.y as f32 * tile_size.y,
+                ),
+                camera,
+            );
+            let area = numeric::Rect::new(display_pos.x, display_pos.y, tile_size.x, tile_size.y);
+
+            if !area.overlaps(&screen) {
+                continue;
+            }
+
+            let color = match element.get_trigger_method() {
+                EventTrigger::Action => ggraphics::Color::from_rgba_u32(0x2060ff70),
+                EventTrigger::Touch => ggraphics::Color::from_rgba_u32(0xff802070),
+            };
+            shape::Rectangle::new(area, ggraphics::DrawMode::fill(), color)
+                .add_to_builder(&mut builder);
+            has_shape = true;
+        }
+
+        if let Some(form) = self.form.as_ref() {
+            let display_pos = mp::map_to_display(
+                &numeric::Point2f::new(
+                    form.target.x as f32 * tile_size.x,
+                    form.target.y as f32 * tile_size.y,
+                ),
+                camera,
+            );
+            shape::Rectangle::new(
+                numeric::Rect::new(display_pos.x, display_pos.y, tile_size.x, tile_size.y),
+                ggraphics::DrawMode::stroke(3.0),
+                ggraphics::Color::from_rgba_u32(0xff2020ff),
+            )
+            .add_to_builder(&mut builder);
+            has_shape = true;
+        }
+
+        if has_shape {
+            let mesh = builder.build(ctx)?;
+            ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+        }
+
+        self.header.draw(ctx)?;
+
+        if let Some(form) = self.form.as_mut() {
+            form.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::VecDeque;
 use std::str::FromStr;
 
 use ggez::graphics as ggraphics;
+use rand::{Rng, RngCore};
 
 use torifune::core::Clock;
 use torifune::distance;
@@ -10,13 +11,23 @@ use torifune::graphics::drawable::*;
 use torifune::graphics::object::*;
 use torifune::numeric;
 
+use crate::core::ambient_zone::AmbientZone;
+use crate::core::customer_watchdog::{ProgressWatch, WatchdogAction};
+use crate::core::difficulty::Difficulty;
+use crate::core::game_rng::GameRng;
+use crate::core::game_system::CustomerPriority;
 use crate::core::map_parser as mp;
+use crate::core::reservation::BookReservation;
+use crate::core::shop_expansion;
 use crate::core::*;
 use crate::flush_delay_event;
+use crate::instrument;
 use crate::object::collision::*;
+use crate::object::highlight::{Highlight, Highlightable};
 use crate::object::task_object::tt_main_component::CustomerRequest;
 use crate::object::util_object::*;
 use crate::scene::{DelayEventList, SceneID};
+use crate::{log_debug, log_warn};
 
 use super::task_object::tt_main_component::CustomerRequestOrder;
 
@@ -65,6 +76,7 @@ pub struct MapObject {
     speed_info: TextureSpeedInfo,
     map_position: TwoStepPoint,
     collision_crop: numeric::Rect,
+    highlight: Option<Highlight>,
 }
 
 impl MapObject {
@@ -87,6 +99,7 @@ impl MapObject {
             speed_info: speed_info,
             object: TextureAnimation::new(obj, mode_order, textures, mode, frame_speed),
             collision_crop: collision_crop,
+            highlight: None,
         }
     }
 
@@ -304,6 +317,27 @@ impl MapObject {
         self.object.try_next_frame(t);
     }
 
+    ///
+    /// 目立たせ方を変える。Noneなら目立たせない
+    ///
+    pub fn set_highlight(&mut self, highlight: Option<Highlight>) {
+        self.highlight = highlight;
+    }
+
+    pub fn get_highlight(&self) -> Option<&Highlight> {
+        self.highlight.as_ref()
+    }
+
+    ///
+    /// 目立たせ方の時刻を進める。描き直しが要るならtrueを返す
+    ///
+    pub fn update_highlight(&mut self, t: Clock) -> bool {
+        match self.highlight.as_mut() {
+            Some(highlight) => highlight.update(t),
+            None => false,
+        }
+    }
+
     ///
     /// マップ上の座標を動かす
     ///
@@ -345,7 +379,10 @@ impl MapObject {
 impl DrawableComponent for MapObject {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
         if self.is_visible() {
-            self.obj_mut().draw(ctx).unwrap();
+            let highlight = self.highlight;
+            self.obj_mut()
+                .draw_highlighted(ctx, highlight.as_ref())
+                .unwrap();
         }
         Ok(())
     }
@@ -457,8 +494,16 @@ impl PlayableCharacter {
         self.character.move_map(offset);
     }
 
-    pub fn move_map_current_speed_x(&mut self, ctx: &mut ggez::Context, border: numeric::Vector2f) {
-        let x_speed = self.get_character_object().speed_info().get_speed().x;
+    ///
+    /// speed_rateは地形による速さの倍率
+    ///
+    pub fn move_map_current_speed_x(
+        &mut self,
+        ctx: &mut ggez::Context,
+        border: numeric::Vector2f,
+        speed_rate: f32,
+    ) {
+        let x_speed = self.get_character_object().speed_info().get_speed().x * speed_rate;
         let current_position = self.get_map_position();
         let next_position = self.get_map_position().x + x_speed;
         let drawing_size = self.character.obj().get_drawing_size(ctx);
@@ -475,8 +520,13 @@ impl PlayableCharacter {
         }
     }
 
-    pub fn move_map_current_speed_y(&mut self, ctx: &mut ggez::Context, border: numeric::Vector2f) {
-        let y_speed = self.get_character_object().speed_info().get_speed().y;
+    pub fn move_map_current_speed_y(
+        &mut self,
+        ctx: &mut ggez::Context,
+        border: numeric::Vector2f,
+        speed_rate: f32,
+    ) {
+        let y_speed = self.get_character_object().speed_info().get_speed().y * speed_rate;
         let current_position = self.get_map_position();
         let next_position = self.get_map_position().y + y_speed;
         let drawing_size = self.character.obj().get_drawing_size(ctx);
@@ -573,8 +623,8 @@ impl CustomerDestPoint {
         }
     }
 
-    pub fn random_select(&self) -> numeric::Vector2u {
-        let random_index = rand::random::<usize>() % self.candidates.len();
+    pub fn random_select<R: Rng>(&self, rng: &mut R) -> numeric::Vector2u {
+        let random_index = rng.gen_range(0, self.candidates.len());
         *self.candidates.get(random_index).unwrap()
     }
 }
@@ -610,6 +660,10 @@ impl CustomerMoveQueue {
     pub fn clear(&mut self) {
         self.queue.clear();
     }
+
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<numeric::Point2f> {
+        self.queue.iter()
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -625,19 +679,68 @@ pub enum CustomerCharacterStatus {
 
 pub struct CustomerInformation {
     pub name: String,
+    pub priority: CustomerPriority,
 }
 
 impl CustomerInformation {
-    pub fn new(name: &str) -> Self {
+    pub fn new(name: &str, priority: CustomerPriority) -> Self {
         CustomerInformation {
             name: name.to_string(),
+            priority: priority,
         }
     }
 }
 
+///
+/// # 読書処に寄る客の様子
+///
+/// ## NoVisit
+/// 読書処には寄らない
+///
+/// ## Planned
+/// まだ読書処へ向かっていない。値は座る場所
+///
+/// ## Heading
+/// 読書処へ向かっている
+///
+/// ## Done
+/// 読書処で読み終えた
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReadingCornerVisit {
+    NoVisit,
+    Planned(numeric::Vector2u),
+    Heading,
+    Done,
+}
+
+///
+/// # 雨の日に客が持ってくる傘
+///
+/// ## InStand
+/// 戸口の傘立てに置いてきた。帰るときに持って帰る
+///
+/// ## Holding
+/// 傘立てが一杯で、持ったまま店に入った。店にいる間ずっと雫が垂れる
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UmbrellaStatus {
+    NoUmbrella,
+    InStand,
+    Holding,
+}
+
+///
+/// 次のタイルを誰かに塞がれているとき、避ける道を探し直す間隔
+///
+const BLOCKED_TILE_RECHECK_INTERVAL: Clock = 30;
+
 ///
 /// マップ上に表示するキャラクターの情報
 ///
+/// ## rng
+/// 店の中を歩き回る先を選ぶ乱数。店に入るときにGameResourceの乱数から分ける
+///
 pub struct CustomerCharacter {
     event_list: DelayEventList<Self>,
     character: MapObject,
@@ -647,14 +750,43 @@ pub struct CustomerCharacter {
     shopping_is_done: bool,
     current_goal: numeric::Point2f,
     customer_info: CustomerInformation,
+    priority_marker: Option<UniText>,
+    reservation: Option<BookReservation>,
+    reading_visit: ReadingCornerVisit,
+    umbrella: UmbrellaStatus,
+    drip_until: Clock,
+    progress_watch: Option<ProgressWatch>,
+    waiting_for_tile: bool,
+    next_blocked_check: Clock,
+    rng: GameRng,
 }
 
 impl CustomerCharacter {
     pub fn new(
-        game_data: &GameResource,
+        game_data: &mut GameResource,
         character: MapObject,
         move_data: CustomerDestPoint,
     ) -> Self {
+        let priority = CustomerPriority::random_select(game_data.rng());
+        let rng = GameRng::new(game_data.rng().next_u64());
+        let customer_name = game_data.customer_random_select().to_string();
+
+        // 先に通してもらえる客には、頭上に事情を示す印を出す
+        let priority_marker = priority.marker_text().map(|text| {
+            UniText::new(
+                text.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                FontInformation::new(
+                    game_data.get_font(FontID::Cinema),
+                    numeric::Vector2f::new(16.0, 16.0),
+                    ggraphics::Color::from_rgba_u32(0xbb2222ff),
+                ),
+            )
+        });
+
         CustomerCharacter {
             event_list: DelayEventList::new(),
             character: character,
@@ -663,10 +795,70 @@ impl CustomerCharacter {
             customer_status: CustomerCharacterStatus::Ready,
             shopping_is_done: false,
             current_goal: numeric::Point2f::new(0.0, 0.0),
-            customer_info: CustomerInformation::new(game_data.customer_random_select()),
+            customer_info: CustomerInformation::new(&customer_name, priority),
+            priority_marker: priority_marker,
+            reservation: None,
+            reading_visit: ReadingCornerVisit::NoVisit,
+            umbrella: UmbrellaStatus::NoUmbrella,
+            drip_until: 0,
+            progress_watch: None,
+            waiting_for_tile: false,
+            next_blocked_check: 0,
+            rng: rng,
         }
     }
 
+    ///
+    /// 取り置いた本を受け取りに来た客にする。名前は予約した客のものに替える
+    ///
+    pub fn set_reservation(&mut self, reservation: BookReservation) {
+        self.customer_info.name = reservation.customer_name.clone();
+        self.reservation = Some(reservation);
+    }
+
+    ///
+    /// 普通の客の代わりに、評判で来るようになった特別な客にする
+    ///
+    pub fn set_special_customer(&mut self, name: String) {
+        self.customer_info.name = name;
+    }
+
+    ///
+    /// 本棚を見て回る前に、読書処のspotに座って本を読んでいく客にする
+    ///
+    pub fn plan_reading(&mut self, spot: numeric::Vector2u) {
+        self.reading_visit = ReadingCornerVisit::Planned(spot);
+    }
+
+    ///
+    /// 雨の中を来た客にする。傘立てに置けなければ、傘を持ったまま入ってくる
+    ///
+    pub fn come_in_from_rain(&mut self, umbrella_in_stand: bool, drip_until: Clock) {
+        if umbrella_in_stand {
+            self.umbrella = UmbrellaStatus::InStand;
+            self.drip_until = drip_until;
+        } else {
+            self.umbrella = UmbrellaStatus::Holding;
+            self.drip_until = Clock::max_value();
+        }
+    }
+
+    pub fn is_dripping(&self, t: Clock) -> bool {
+        self.umbrella != UmbrellaStatus::NoUmbrella && t < self.drip_until
+    }
+
+    pub fn has_umbrella_in_stand(&self) -> bool {
+        self.umbrella == UmbrellaStatus::InStand
+    }
+
+    pub fn get_customer_name(&self) -> &str {
+        &self.customer_info.name
+    }
+
+    pub fn get_priority(&self) -> CustomerPriority {
+        self.customer_info.priority
+    }
+
     ///
     /// 現在のマップ位置から、指定された目的地までのルートを計算するメソッド
     ///
@@ -687,7 +879,10 @@ impl CustomerCharacter {
             // マップ位置が不正であった場合は、検索を行わない
             if let Some(map_start_pos) = maybe_start {
                 // ルートを計算し、返す
-                let maybe_route = map_data.find_shortest_route(map_start_pos, dest);
+                let maybe_route = instrument!(
+                    instrument::ProbeCategory::Pathfinding,
+                    map_data.find_shortest_route(map_start_pos, dest)
+                );
                 if let Some(route) = maybe_route {
                     return Some(
                         route
@@ -733,8 +928,14 @@ impl CustomerCharacter {
 
     ///
     /// ゴールへの適切な速度を計算・更新する
+    /// 速さは今いるタイルの地形で変わる
     ///
-    fn override_move_effect(&mut self, ctx: &mut ggez::Context, goal_point: numeric::Point2f) {
+    fn override_move_effect(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        goal_point: numeric::Point2f,
+    ) {
         // 現在のマップ位置
         let current = self
             .get_character_object()
@@ -759,7 +960,8 @@ impl CustomerCharacter {
             };
 
             // 基本的な速さは一致するようにしたいため、次のように計算する
-            let speed = numeric::Vector2f::new(rad.cos() * 1.4, rad.sin() * 1.4);
+            let base_speed = 1.4 * map_data.terrain_speed_rate(current);
+            let speed = numeric::Vector2f::new(rad.cos() * base_speed, rad.sin() * base_speed);
 
             // 向きによってアニメーションを更新
             self.update_animation_mode_with_rad(rad);
@@ -777,8 +979,28 @@ impl CustomerCharacter {
         map_data: &mp::StageObjectMap,
         t: Clock,
     ) {
-        let goal = self.move_data.random_select();
-        self.determine_next_goal(ctx, map_data, goal, t)
+        match self.reading_visit {
+            ReadingCornerVisit::Planned(spot) => {
+                self.reading_visit = ReadingCornerVisit::Heading;
+                self.determine_next_goal(ctx, map_data, spot, 100, t);
+            }
+            ReadingCornerVisit::Heading => {
+                // 読書処に着いたので、しばらく座って読んでから本棚へ戻る
+                self.reading_visit = ReadingCornerVisit::Done;
+                let goal = self.move_data.random_select(&mut self.rng);
+                self.determine_next_goal(
+                    ctx,
+                    map_data,
+                    goal,
+                    shop_expansion::CUSTOMER_READING_TIME,
+                    t,
+                );
+            }
+            _ => {
+                let goal = self.move_data.random_select(&mut self.rng);
+                self.determine_next_goal(ctx, map_data, goal, 100, t);
+            }
+        }
     }
 
     fn determine_next_goal(
@@ -786,6 +1008,7 @@ impl CustomerCharacter {
         ctx: &mut ggez::Context,
         map_data: &mp::StageObjectMap,
         goal: numeric::Vector2u,
+        wait: Clock,
         t: Clock,
     ) {
         // ルート検索
@@ -800,7 +1023,7 @@ impl CustomerCharacter {
                     customer.customer_status = CustomerCharacterStatus::Ready;
                 }
             }),
-            t + 100,
+            t + wait,
         );
 
         self.customer_status = CustomerCharacterStatus::WaitOnBookShelf;
@@ -825,7 +1048,7 @@ impl CustomerCharacter {
         // 情報をキューから取り出し、速度を計算し直す
         let maybe_next_position = self.move_queue.dequeue();
         if let Some(next_position) = maybe_next_position {
-            self.override_move_effect(ctx, next_position);
+            self.override_move_effect(ctx, map_data, next_position);
             self.current_goal = next_position;
             self.customer_status = CustomerCharacterStatus::Moving;
         }
@@ -853,7 +1076,7 @@ impl CustomerCharacter {
             self.update_move_effect(ctx, map_data, t);
             Ok(())
         } else {
-            println!(
+            log_debug!(
                 "failed, collision_top: {:?}, start -> {:?}, point -> {:?}, dest -> {:?}",
                 self.character
                     .get_map_position_with_collision_top_offset(ctx),
@@ -869,6 +1092,131 @@ impl CustomerCharacter {
         }
     }
 
+    ///
+    /// 歩いている途中のルートが、通れるかどうかの変わったタイルを通るなら、ルートを計算し直す
+    /// 新しいルートが見つからなければ、元のルートのまま歩く
+    ///
+    /// ルートを計算し直したらtrueを返す
+    ///
+    pub fn replan_route(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        changed_tiles: &[numeric::Vector2u],
+    ) -> bool {
+        match self.customer_status {
+            CustomerCharacterStatus::Moving
+            | CustomerCharacterStatus::GettingOut
+            | CustomerCharacterStatus::GoToCheck => (),
+            _ => return false,
+        }
+
+        let route: Vec<numeric::Point2f> = std::iter::once(self.current_goal)
+            .chain(self.move_queue.iter().copied())
+            .collect();
+        let route_tiles: Vec<numeric::Vector2u> = route
+            .iter()
+            .filter_map(|point| map_data.map_position_to_tile_position(*point))
+            .collect();
+
+        if !route_tiles.iter().any(|tile| changed_tiles.contains(tile)) {
+            return false;
+        }
+
+        let dest = match route_tiles.last() {
+            Some(dest) => *dest,
+            None => return false,
+        };
+
+        self.move_queue.clear();
+        match self.find_route(ctx, map_data, dest) {
+            Some(next_route) => {
+                self.move_queue.enqueue(next_route);
+                if let Some(next_position) = self.move_queue.dequeue() {
+                    self.override_move_effect(ctx, map_data, next_position);
+                    self.current_goal = next_position;
+                }
+                true
+            }
+            None => {
+                // 行き先まで通れる道が無くなった。元のルートに戻す
+                self.move_queue.enqueue(route.into_iter().skip(1).collect());
+                false
+            }
+        }
+    }
+
+    ///
+    /// 今立っているタイル
+    ///
+    pub fn current_tile(
+        &self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+    ) -> Option<numeric::Vector2u> {
+        map_data.map_position_to_tile_position(
+            self.character
+                .get_map_position_with_collision_top_offset(ctx),
+        )
+    }
+
+    ///
+    /// 次に踏み込むタイルに誰かが立っていれば、避けるルートを計算し直す
+    /// 避けられなければ、空くまでその場で待つ。ルートを変えたか待ち始めたらtrueを返す
+    ///
+    pub fn avoid_occupied_tile(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        t: Clock,
+    ) -> bool {
+        match self.customer_status {
+            CustomerCharacterStatus::Moving
+            | CustomerCharacterStatus::GettingOut
+            | CustomerCharacterStatus::GoToCheck => (),
+            _ => return false,
+        }
+
+        let next_tile = match map_data.map_position_to_tile_position(self.current_goal) {
+            Some(tile) => tile,
+            None => return false,
+        };
+
+        if self.current_tile(ctx, map_data) == Some(next_tile)
+            || !map_data.is_tile_occupied(next_tile)
+        {
+            if self.waiting_for_tile {
+                // 空いたので、また歩き出す
+                self.waiting_for_tile = false;
+                let goal = self.current_goal;
+                self.override_move_effect(ctx, map_data, goal);
+            }
+            return false;
+        }
+
+        if t < self.next_blocked_check {
+            return false;
+        }
+        self.next_blocked_check = t + BLOCKED_TILE_RECHECK_INTERVAL;
+
+        if self.replan_route(ctx, map_data, &[next_tile]) {
+            let still_blocked = std::iter::once(self.current_goal)
+                .chain(self.move_queue.iter().copied())
+                .take(2)
+                .any(|point| map_data.map_position_to_tile_position(point) == Some(next_tile));
+            if !still_blocked {
+                self.waiting_for_tile = false;
+                return true;
+            }
+        }
+
+        // 避けて通る道が無い。空くまで待つ
+        self.waiting_for_tile = true;
+        self.reset_speed();
+        self.update_animation_for_stop();
+        true
+    }
+
     pub fn get_out_shop(
         &mut self,
         ctx: &mut ggez::Context,
@@ -910,11 +1258,75 @@ impl CustomerCharacter {
         let current = self
             .get_character_object()
             .get_map_position_with_collision_top_offset(ctx);
-        distance!(current, self.current_goal) < 1.5
+        let speed = self.get_character_object().speed_info().get_speed();
+
+        // 速い地形では一歩が大きくなるので、ゴールを跨がないよう一歩分までは到達とみなす
+        let step = (speed.x.powi(2) + speed.y.powi(2)).sqrt();
+        distance!(current, self.current_goal) < step.max(1.5)
+    }
+
+    fn generate_borrowing_request<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        difficulty: &Difficulty,
+        today: &GensoDate,
+    ) -> BorrowingInformation {
+        BorrowingInformation::new_random(ctx.resource, difficulty, &self.customer_info.name, today)
+    }
+
+    ///
+    /// 予約した本を借りる依頼。本が見つからなければNone
+    ///
+    fn generate_reserved_request<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        today: &GensoDate,
+    ) -> Option<BorrowingInformation> {
+        let reservation = self.reservation.as_ref()?;
+        let book_info = ctx
+            .resource
+            .iter_available_books()
+            .find(|book| book.name == reservation.book_title)?
+            .clone();
+        let book_info = book_info.clone_with_new_id_condition(ctx.resource.rng());
+
+        Some(BorrowingInformation::new(
+            vec![book_info],
+            &self.customer_info.name,
+            today.clone(),
+            RentalLimit::random(ctx.resource.rng()),
+        ))
     }
 
-    fn generate_hold_request<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> CustomerRequest {
-        let random_select = rand::random::<usize>() % 2
+    ///
+    /// 客の頼みごとを決める。冊数や返却期限はその日の難しさに従う
+    ///
+    fn generate_hold_request<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        difficulty: &Difficulty,
+    ) -> CustomerRequest {
+        if self.reservation.is_some() {
+            let today = ctx.take_save_data().date.clone();
+            if let Some(borrowing) = self.generate_reserved_request(ctx, &today) {
+                return CustomerRequest::Borrowing(borrowing);
+            }
+        }
+
+        // 読書処で読んでいった客は、気に入った本を借りていくことが多い
+        if self.reading_visit == ReadingCornerVisit::Done
+            && ctx
+                .resource
+                .rng()
+                .chance(shop_expansion::READ_THEN_BORROW_RATE)
+        {
+            let today = ctx.take_save_data().date.clone();
+            return CustomerRequest::Borrowing(
+                self.generate_borrowing_request(ctx, difficulty, &today),
+            );
+        }
+
+        let random_select = ctx.resource.rng().below(2)
             + if !ctx
                 .take_save_data()
                 .record_book_data
@@ -929,15 +1341,12 @@ impl CustomerCharacter {
         match random_select {
             0 => CustomerRequest::Returning(ReturnBookInformation::new_random(
                 ctx.resource,
-                today,
-                GensoDate::new(128, 12, 20),
-            )),
-            _ => CustomerRequest::Borrowing(BorrowingInformation::new(
-                vec![ctx.resource.book_random_select().clone()],
-                &self.customer_info.name,
-                today,
-                RentalLimit::random(),
+                difficulty,
+                &today,
             )),
+            _ => {
+                CustomerRequest::Borrowing(self.generate_borrowing_request(ctx, difficulty, &today))
+            }
         }
     }
 
@@ -945,20 +1354,16 @@ impl CustomerCharacter {
         &mut self,
         ctx: &mut SuzuContext<'a>,
         order: CustomerRequestOrder,
+        difficulty: &Difficulty,
     ) -> CustomerRequest {
         let today = ctx.take_save_data().date.clone();
 
         match order {
             CustomerRequestOrder::ReturningOrder => CustomerRequest::Returning(
-                ReturnBookInformation::new_random(ctx.resource, today, GensoDate::new(128, 12, 20)),
+                ReturnBookInformation::new_random(ctx.resource, difficulty, &today),
             ),
             CustomerRequestOrder::BorrowingOrder => {
-                CustomerRequest::Borrowing(BorrowingInformation::new(
-                    vec![ctx.resource.book_random_select().clone()],
-                    &self.customer_info.name,
-                    today,
-                    RentalLimit::random(),
-                ))
+                CustomerRequest::Borrowing(self.generate_borrowing_request(ctx, difficulty, &today))
             }
         }
     }
@@ -1062,7 +1467,7 @@ impl CustomerCharacter {
                 // キューが空ではない場合
                 // 情報をキューから取り出し、速度を計算し直す
                 if let Some(next_position) = self.move_queue.dequeue() {
-                    self.override_move_effect(ctx.context, next_position);
+                    self.override_move_effect(ctx.context, map_data, next_position);
                     self.current_goal = next_position;
                 }
             }
@@ -1085,7 +1490,7 @@ impl CustomerCharacter {
                 // キューが空ではない場合
                 // 情報をキューから取り出し、速度を計算し直す
                 if let Some(next_position) = self.move_queue.dequeue() {
-                    self.override_move_effect(ctx.context, next_position);
+                    self.override_move_effect(ctx.context, map_data, next_position);
                     self.current_goal = next_position;
                 } else {
                     self.check_been_counter(map_data, goal, counter);
@@ -1103,6 +1508,133 @@ impl CustomerCharacter {
         }
     }
 
+    ///
+    /// 進んでいるかを見張り、動けなくなっていれば手当てを返す
+    /// 受付を待っている間と、店を出た後は見張らない
+    ///
+    pub fn observe_progress(&mut self, ctx: &mut ggez::Context, t: Clock) -> WatchdogAction {
+        let position = self
+            .character
+            .get_map_position_with_collision_top_offset(ctx);
+
+        match self.customer_status {
+            CustomerCharacterStatus::WaitOnClerk | CustomerCharacterStatus::GotOut => {
+                self.progress_watch = Some(ProgressWatch::new(position, t));
+                WatchdogAction::Keep
+            }
+            _ => match self.progress_watch.as_mut() {
+                Some(watch) => watch.observe(position, t),
+                None => {
+                    self.progress_watch = Some(ProgressWatch::new(position, t));
+                    WatchdogAction::Keep
+                }
+            },
+        }
+    }
+
+    ///
+    /// 今いる場所から、行き先までのルートを計算し直す
+    /// 行き先が分からなければ、別の本棚へ向かわせる。ルートが見つかればtrue
+    ///
+    pub fn repath(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        exit: numeric::Vector2u,
+        t: Clock,
+    ) -> bool {
+        let status = self.customer_status;
+        let heading = match status {
+            CustomerCharacterStatus::Moving
+            | CustomerCharacterStatus::GettingOut
+            | CustomerCharacterStatus::GoToCheck => Some(self.current_goal),
+            _ => None,
+        };
+
+        let dest = if self.shopping_is_done {
+            exit
+        } else {
+            self.move_queue
+                .iter()
+                .last()
+                .copied()
+                .or(heading)
+                .and_then(|point| map_data.map_position_to_tile_position(point))
+                .unwrap_or_else(|| self.move_data.random_select(&mut self.rng))
+        };
+
+        if self.set_destination_forced(ctx, map_data, dest, t).is_err() {
+            return false;
+        }
+
+        // 出口や受付へ向かう途中なら、向かう先は変わらない
+        if status == CustomerCharacterStatus::GettingOut
+            || status == CustomerCharacterStatus::GoToCheck
+        {
+            self.customer_status = status;
+        }
+
+        true
+    }
+
+    ///
+    /// 近くの通れるタイルへ移す。移せる場所が無ければfalse
+    ///
+    pub fn teleport_to_open_tile(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+    ) -> bool {
+        let current = self
+            .character
+            .get_map_position_with_collision_top_offset(ctx);
+        let tile = match map_data
+            .map_position_to_tile_position(current)
+            .and_then(|tile| map_data.nearest_open_tile(tile))
+        {
+            Some(tile) => tile,
+            None => return false,
+        };
+
+        self.move_queue.clear();
+        self.reset_speed();
+        self.character.set_map_position_with_collision_top_offset(
+            ctx,
+            map_data.tile_position_to_map_position(tile),
+        );
+
+        true
+    }
+
+    ///
+    /// 見張りが動けなくなった客を見つけたときに残す記録
+    ///
+    pub fn stuck_diagnostic(
+        &self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+    ) -> String {
+        let position = self
+            .character
+            .get_map_position_with_collision_top_offset(ctx);
+        let (stuck_since, attempts) = self
+            .progress_watch
+            .as_ref()
+            .map_or((0, 0), |watch| (watch.stuck_since(), watch.get_attempts()));
+
+        format!(
+            "customer {:?}: status {:?}, position {:?} (tile {:?}), goal {:?}, queued {} points, stuck since {}, attempts {}",
+            self.customer_info.name,
+            self.customer_status,
+            position,
+            map_data.map_position_to_tile_position(position),
+            self.current_goal,
+            self.move_queue.len(),
+            stuck_since,
+            attempts
+        )
+    }
+
     pub fn is_wait_on_clerk(&self) -> bool {
         self.customer_status == CustomerCharacterStatus::WaitOnClerk
     }
@@ -1111,11 +1643,12 @@ impl CustomerCharacter {
         &mut self,
         ctx: &mut SuzuContext<'a>,
         order: Option<CustomerRequestOrder>,
+        difficulty: &Difficulty,
     ) -> Option<CustomerRequest> {
         if self.customer_status == CustomerCharacterStatus::WaitOnClerk {
             match order {
-                Some(order) => Some(self.generate_hold_request_with_order(ctx, order)),
-                None => Some(self.generate_hold_request(ctx)),
+                Some(order) => Some(self.generate_hold_request_with_order(ctx, order, difficulty)),
+                None => Some(self.generate_hold_request(ctx, difficulty)),
             }
         } else {
             None
@@ -1182,6 +1715,12 @@ impl DrawableComponent for CustomerCharacter {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
         if self.is_visible() {
             self.get_mut_character_object().draw(ctx).unwrap();
+
+            if let Some(marker) = self.priority_marker.as_mut() {
+                let position = self.character.obj().get_position();
+                marker.set_position(numeric::Point2f::new(position.x, position.y - 20.0));
+                marker.draw(ctx)?;
+            }
         }
         Ok(())
     }
@@ -1242,6 +1781,15 @@ impl FromStr for EventTrigger {
     }
 }
 
+impl EventTrigger {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Action => "action",
+            Self::Touch => "touch",
+        }
+    }
+}
+
 pub trait MapEvent {
     fn get_trigger_method(&self) -> EventTrigger;
 }
@@ -1252,6 +1800,13 @@ pub struct MapTextEvent {
 }
 
 impl MapTextEvent {
+    pub fn new(trigger: EventTrigger, text: &str) -> Self {
+        MapTextEvent {
+            trigger: trigger,
+            text: text.to_string(),
+        }
+    }
+
     pub fn from_toml_object(toml_script: &toml::value::Value) -> Self {
         MapTextEvent {
             trigger: EventTrigger::from_str(toml_script.get("trigger").unwrap().as_str().unwrap())
@@ -1268,6 +1823,13 @@ impl MapTextEvent {
     pub fn get_text(&self) -> &str {
         &self.text
     }
+
+    fn write_toml_table(&self, table: &mut toml::value::Table) {
+        table.insert(
+            "text".to_string(),
+            toml::Value::String(self.text.to_string()),
+        );
+    }
 }
 
 impl MapEvent for MapTextEvent {
@@ -1307,6 +1869,13 @@ impl MapEventSceneSwitch {
     pub fn get_switch_scene_id(&self) -> SceneID {
         self.switch_scene
     }
+
+    fn write_toml_table(&self, table: &mut toml::value::Table) {
+        table.insert(
+            "switch-scene-id".to_string(),
+            toml::Value::String(self.switch_scene.to_str().to_string()),
+        );
+    }
 }
 
 impl MapEvent for MapEventSceneSwitch {
@@ -1321,9 +1890,16 @@ pub struct BookStoreEvent {
 }
 
 impl BookStoreEvent {
+    pub fn new(trigger: EventTrigger, book_shelf_info: BookShelfInformation) -> Self {
+        BookStoreEvent {
+            trigger: trigger,
+            book_shelf_info: book_shelf_info,
+        }
+    }
+
     pub fn from_toml_object(toml_script: &toml::value::Value) -> Self {
         let shelf_info = toml_script.get("shelf-info").unwrap().as_table().unwrap();
-        let book_shelf_info = BookShelfInformation::new(
+        let mut book_shelf_info = BookShelfInformation::new(
             shelf_info
                 .get("begin-number")
                 .unwrap()
@@ -1331,6 +1907,11 @@ impl BookStoreEvent {
                 .unwrap() as u16,
             shelf_info.get("end-number").unwrap().as_integer().unwrap() as u16,
         );
+        book_shelf_info.set_front(
+            shelf_info
+                .get("front")
+                .map_or(false, |front| front.as_bool().unwrap()),
+        );
 
         BookStoreEvent {
             trigger: EventTrigger::from_str(toml_script.get("trigger").unwrap().as_str().unwrap())
@@ -1342,6 +1923,22 @@ impl BookStoreEvent {
     pub fn get_book_shelf_info(&self) -> &BookShelfInformation {
         &self.book_shelf_info
     }
+
+    fn write_toml_table(&self, table: &mut toml::value::Table) {
+        let mut shelf_info = toml::value::Table::new();
+        shelf_info.insert(
+            "begin-number".to_string(),
+            toml::Value::Integer(self.book_shelf_info.get_begin_number() as i64),
+        );
+        shelf_info.insert(
+            "end-number".to_string(),
+            toml::Value::Integer(self.book_shelf_info.get_end_number() as i64),
+        );
+        if self.book_shelf_info.is_front() {
+            shelf_info.insert("front".to_string(), toml::Value::Boolean(true));
+        }
+        table.insert("shelf-info".to_string(), toml::Value::Table(shelf_info));
+    }
 }
 
 impl MapEvent for BookStoreEvent {
@@ -1353,6 +1950,9 @@ impl MapEvent for BookStoreEvent {
 #[derive(PartialEq, Clone, Copy)]
 pub enum BuiltinEventSymbol {
     SelectShelvingBook = 0,
+    GoErrand,
+    ReturnShop,
+    DeliverBook,
 }
 
 impl FromStr for BuiltinEventSymbol {
@@ -1361,11 +1961,25 @@ impl FromStr for BuiltinEventSymbol {
     fn from_str(builtin_event_symbol: &str) -> Result<Self, Self::Err> {
         match builtin_event_symbol {
             "select-shelving-book" => Ok(Self::SelectShelvingBook),
+            "go-errand" => Ok(Self::GoErrand),
+            "return-shop" => Ok(Self::ReturnShop),
+            "deliver-book" => Ok(Self::DeliverBook),
             _ => panic!("Error: BuiltinEventSymbol::from_str"),
         }
     }
 }
 
+impl BuiltinEventSymbol {
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::SelectShelvingBook => "select-shelving-book",
+            Self::GoErrand => "go-errand",
+            Self::ReturnShop => "return-shop",
+            Self::DeliverBook => "deliver-book",
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct BuiltinEvent {
     trigger: EventTrigger,
@@ -1373,6 +1987,13 @@ pub struct BuiltinEvent {
 }
 
 impl BuiltinEvent {
+    pub fn new(trigger: EventTrigger, event_symbol: BuiltinEventSymbol) -> Self {
+        BuiltinEvent {
+            trigger: trigger,
+            event_symbol: event_symbol,
+        }
+    }
+
     pub fn from_toml_object(toml_script: &toml::value::Value) -> Self {
         let builtin_event_info = toml_script
             .get("builtin-event-info")
@@ -1392,6 +2013,18 @@ impl BuiltinEvent {
     pub fn get_event_symbol(&self) -> BuiltinEventSymbol {
         self.event_symbol
     }
+
+    fn write_toml_table(&self, table: &mut toml::value::Table) {
+        let mut builtin_event_info = toml::value::Table::new();
+        builtin_event_info.insert(
+            "symbol".to_string(),
+            toml::Value::String(self.event_symbol.to_str().to_string()),
+        );
+        table.insert(
+            "builtin-event-info".to_string(),
+            toml::Value::Table(builtin_event_info),
+        );
+    }
 }
 
 impl MapEvent for BuiltinEvent {
@@ -1418,8 +2051,54 @@ impl MapEvent for MapEventElement {
     }
 }
 
+impl MapEventElement {
+    ///
+    /// イベントマップのTOMLで使われる種類名
+    ///
+    pub fn get_type_str(&self) -> &str {
+        match self {
+            Self::TextEvent(_) => "text",
+            Self::SwitchScene(_) => "switch-scene",
+            Self::BookStoreEvent(_) => "book-shelf",
+            Self::BuiltinEvent(_) => "builtin-event",
+        }
+    }
+
+    ///
+    /// MapEventList::from_fileで読み込める形式のTOMLに変換する
+    ///
+    pub fn to_toml_value(&self, position: numeric::Point2i) -> toml::Value {
+        let mut table = toml::value::Table::new();
+
+        table.insert(
+            "type".to_string(),
+            toml::Value::String(self.get_type_str().to_string()),
+        );
+        table.insert(
+            "trigger".to_string(),
+            toml::Value::String(self.get_trigger_method().to_str().to_string()),
+        );
+
+        match self {
+            Self::TextEvent(text) => text.write_toml_table(&mut table),
+            Self::SwitchScene(switch_scene) => switch_scene.write_toml_table(&mut table),
+            Self::BookStoreEvent(book_store_event) => book_store_event.write_toml_table(&mut table),
+            Self::BuiltinEvent(builtin_event) => builtin_event.write_toml_table(&mut table),
+        }
+
+        let mut position_table = toml::value::Table::new();
+        position_table.insert("x".to_string(), toml::Value::Integer(position.x as i64));
+        position_table.insert("y".to_string(), toml::Value::Integer(position.y as i64));
+        table.insert("position".to_string(), toml::Value::Table(position_table));
+
+        toml::Value::Table(table)
+    }
+}
+
 pub struct MapEventList {
     event_table: HashMap<numeric::Point2i, MapEventElement>,
+    ambient_zones: Vec<AmbientZone>,
+    source_path: String,
 }
 
 impl MapEventList {
@@ -1465,14 +2144,32 @@ impl MapEventList {
                             MapEventElement::BuiltinEvent(BuiltinEvent::from_toml_object(elem)),
                         );
                     }
-                    _ => eprintln!("Error"),
+                    _ => log_warn!("unknown map event type"),
                 }
             } else {
-                eprintln!("Error");
+                log_warn!("map event type is missing");
             }
         }
 
-        MapEventList { event_table: table }
+        let ambient_zones = match root.get("ambient-zone").and_then(|zones| zones.as_array()) {
+            Some(zones) => zones
+                .iter()
+                .filter_map(|zone| {
+                    let parsed = AmbientZone::from_toml_object(zone);
+                    if parsed.is_none() {
+                        log_warn!("invalid ambient zone in {}", file_path);
+                    }
+                    parsed
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        MapEventList {
+            event_table: table,
+            ambient_zones: ambient_zones,
+            source_path: file_path.to_string(),
+        }
     }
 
     pub fn register_event(&mut self, point: numeric::Point2i, event: MapEventElement) -> &mut Self {
@@ -1480,6 +2177,58 @@ impl MapEventList {
         self
     }
 
+    pub fn remove_event(&mut self, point: numeric::Point2i) -> Option<MapEventElement> {
+        self.event_table.remove(&point)
+    }
+
+    pub fn get_event(&self, point: numeric::Point2i) -> Option<&MapEventElement> {
+        self.event_table.get(&point)
+    }
+
+    pub fn iter_events(
+        &self,
+    ) -> std::collections::hash_map::Iter<numeric::Point2i, MapEventElement> {
+        self.event_table.iter()
+    }
+
+    pub fn get_ambient_zones(&self) -> &Vec<AmbientZone> {
+        &self.ambient_zones
+    }
+
+    pub fn get_source_path(&self) -> &str {
+        &self.source_path
+    }
+
+    ///
+    /// 読み込み時と同じ形式のTOML文字列に変換する
+    /// 差分が見やすいように座標順に並べる
+    ///
+    pub fn to_toml_string(&self) -> String {
+        let mut positions: Vec<&numeric::Point2i> = self.event_table.keys().collect();
+        positions.sort_by(|a, b| (a.y, a.x).cmp(&(b.y, b.x)));
+
+        let array = positions
+            .iter()
+            .map(|p| self.event_table.get(p).unwrap().to_toml_value(**p))
+            .collect();
+
+        let mut root = toml::value::Table::new();
+        root.insert("event-panel".to_string(), toml::Value::Array(array));
+        if !self.ambient_zones.is_empty() {
+            root.insert(
+                "ambient-zone".to_string(),
+                toml::Value::Array(
+                    self.ambient_zones
+                        .iter()
+                        .map(|zone| zone.to_toml_value())
+                        .collect(),
+                ),
+            );
+        }
+
+        toml::to_string(&toml::Value::Table(root)).unwrap()
+    }
+
     pub fn check_event(
         &self,
         trigger: EventTrigger,
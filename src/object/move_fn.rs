@@ -4,6 +4,8 @@ use torifune::graphics as tg;
 use torifune::graphics::object::GenericMoveFn;
 use torifune::numeric;
 
+use super::tween::*;
+
 pub fn stop() -> Option<GenericMoveFn> {
     None
 }
@@ -63,6 +65,43 @@ pub fn devide_distance(dest: numeric::Point2f, divide_c: f32) -> Option<GenericM
     ))
 }
 
+///
+/// # fromからdestまでTweenで移動する
+/// tは移動関数を設定した時点からの経過時間
+///
+pub fn tween_move(
+    from: numeric::Point2f,
+    dest: numeric::Point2f,
+    duration: Clock,
+    easing: Easing,
+) -> Option<GenericMoveFn> {
+    tween_move_after(from, dest, 0, duration, easing)
+}
+
+///
+/// # delayだけ待ってから、fromからdestまでTweenで移動する
+/// 待っている間はfromに留まる
+///
+pub fn tween_move_after(
+    from: numeric::Point2f,
+    dest: numeric::Point2f,
+    delay: Clock,
+    duration: Clock,
+    easing: Easing,
+) -> Option<GenericMoveFn> {
+    let pos_tween = tween(from, dest, duration, easing, delay);
+
+    Some(Box::new(
+        move |p: &dyn tg::object::MovableObject, t: Clock| {
+            if pos_tween.is_finished_at(t) && p.get_position() == dest {
+                return None;
+            }
+
+            Some(pos_tween.value_at(t))
+        },
+    ))
+}
+
 pub fn move_constant(speed: numeric::Vector2f) -> Option<GenericMoveFn> {
     Some(Box::new(
         move |p: &dyn tg::object::MovableObject, _t: Clock| {
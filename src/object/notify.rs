@@ -7,8 +7,9 @@ use torifune::core::Clock;
 use torifune::graphics::drawable::{DrawableComponent, DrawableObjectEssential};
 use torifune::graphics::object::sub_screen;
 use torifune::graphics::object::*;
-use torifune::numeric;
+use torifune::{mintp, numeric};
 
+use crate::core::recoverable_error::RecoverableError;
 use crate::core::{FontID, SuzuContext, TextureID, TileBatchTextureID};
 use crate::flush_delay_event;
 use crate::object::util_object::*;
@@ -19,11 +20,48 @@ use crate::scene::*;
 pub enum NotificationType {
     Time = 0,
     CustomerCalling,
+    RecoverableError,
+}
+
+///
+/// 通知を出しておく時間
+///
+const DEFAULT_DISPLAY_TIME: Clock = 120;
+
+///
+/// # 通知の中をクリックした結果
+///
+/// ## Dismiss
+/// 通知を閉じる
+///
+/// ## Resized
+/// 通知の大きさが変わった。開いて読んでいる間は、自動では閉じない
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationClick {
+    Ignored,
+    Dismiss,
+    Resized,
 }
 
 pub trait NotificationContents: DrawableComponent {
     fn required_size(&self) -> numeric::Vector2f;
     fn get_notification_type(&self) -> NotificationType;
+
+    ///
+    /// pointは通知の左上からの位置
+    ///
+    fn click_handler<'a>(
+        &mut self,
+        _ctx: &mut SuzuContext<'a>,
+        _point: numeric::Point2f,
+    ) -> NotificationClick {
+        NotificationClick::Ignored
+    }
+
+    fn display_time(&self) -> Clock {
+        DEFAULT_DISPLAY_TIME
+    }
 }
 
 pub struct NotificationContentsData {
@@ -142,6 +180,242 @@ impl NotificationContents for GeneralNotificationContents {
     }
 }
 
+///
+/// 不具合の通知を出しておく時間。詳しくを開けば、閉じるまで出しておく
+///
+const ERROR_TOAST_DISPLAY_TIME: Clock = 480;
+
+///
+/// 詳しい内容を一行に書く文字数
+///
+const ERROR_DETAILS_LINE_CHARS: usize = 36;
+
+const ERROR_TOAST_PADDING: f32 = 16.0;
+const ERROR_TOAST_LINE_HEIGHT: f32 = 26.0;
+
+///
+/// # 遊び続けられる不具合の通知
+/// 一行の要約と、開くと出る詳しい内容を横書きで出す
+///
+/// ## expander_rect
+/// 「詳しく」の場所。クリックで詳しい内容を開け閉めする
+///
+/// ## close_rect
+/// 「×」の場所。クリックで通知を閉じる
+///
+pub struct ErrorToastContents {
+    header_text: UniText,
+    summary_text: UniText,
+    expander_text: UniText,
+    close_text: UniText,
+    details_text: Vec<UniText>,
+    expanded: bool,
+    expander_rect: numeric::Rect,
+    close_rect: numeric::Rect,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl ErrorToastContents {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, error: &RecoverableError, depth: i8) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(20.0, 20.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+        let header_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(22.0, 22.0),
+            ggraphics::Color::from_rgba_u32(0x8c2a2aff),
+        );
+
+        let new_text = |text: String, font_info: FontInformation| {
+            UniText::new(
+                text,
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info,
+            )
+        };
+
+        let header_text = new_text(
+            format!("不具合（{}）", error.source.to_str()),
+            header_font_info,
+        );
+        let summary_text = new_text(error.summary.to_string(), font_info);
+        let expander_text = new_text("詳シク".to_string(), font_info);
+        let close_text = new_text("×".to_string(), header_font_info);
+
+        let details_text = error
+            .details
+            .lines()
+            .flat_map(|line| {
+                let chars: Vec<char> = line.chars().collect();
+                chars
+                    .chunks(ERROR_DETAILS_LINE_CHARS)
+                    .map(|chunk| chunk.iter().collect::<String>())
+                    .collect::<Vec<String>>()
+            })
+            .map(|line| new_text(line, font_info))
+            .collect();
+
+        let mut toast = ErrorToastContents {
+            header_text: header_text,
+            summary_text: summary_text,
+            expander_text: expander_text,
+            close_text: close_text,
+            details_text: details_text,
+            expanded: false,
+            expander_rect: numeric::Rect::new(0.0, 0.0, 0.0, 0.0),
+            close_rect: numeric::Rect::new(0.0, 0.0, 0.0, 0.0),
+            drwob_essential: DrawableObjectEssential::new(true, depth),
+        };
+        toast.layout(ctx.context);
+
+        toast
+    }
+
+    fn content_width(&self, ctx: &mut ggez::Context) -> f32 {
+        let mut width = self.header_text.get_drawing_size(ctx).x
+            + self.close_text.get_drawing_size(ctx).x
+            + ERROR_TOAST_PADDING;
+        width = width.max(self.summary_text.get_drawing_size(ctx).x);
+
+        if self.expanded {
+            for text in self.details_text.iter() {
+                width = width.max(text.get_drawing_size(ctx).x);
+            }
+        }
+
+        width + (ERROR_TOAST_PADDING * 2.0)
+    }
+
+    ///
+    /// 開け閉めするたびに、上から並べ直す
+    ///
+    fn layout(&mut self, ctx: &mut ggez::Context) {
+        let width = self.content_width(ctx);
+        let mut y = ERROR_TOAST_PADDING;
+
+        self.header_text
+            .set_position(numeric::Point2f::new(ERROR_TOAST_PADDING, y));
+        let close_size = self.close_text.get_drawing_size(ctx);
+        let close_position = numeric::Point2f::new(width - ERROR_TOAST_PADDING - close_size.x, y);
+        self.close_text.set_position(close_position);
+        self.close_rect = numeric::Rect::new(
+            close_position.x,
+            close_position.y,
+            close_size.x,
+            close_size.y,
+        );
+        y += ERROR_TOAST_LINE_HEIGHT + 4.0;
+
+        self.summary_text
+            .set_position(numeric::Point2f::new(ERROR_TOAST_PADDING, y));
+        y += ERROR_TOAST_LINE_HEIGHT;
+
+        if self.expanded {
+            for text in self.details_text.iter_mut() {
+                text.set_position(numeric::Point2f::new(ERROR_TOAST_PADDING, y));
+                y += ERROR_TOAST_LINE_HEIGHT;
+            }
+        }
+
+        self.expander_text.replace_text(if self.expanded {
+            "閉ジル".to_string()
+        } else {
+            "詳シク".to_string()
+        });
+        let expander_size = self.expander_text.get_drawing_size(ctx);
+        let expander_position = numeric::Point2f::new(ERROR_TOAST_PADDING, y);
+        self.expander_text.set_position(expander_position);
+        self.expander_rect = numeric::Rect::new(
+            expander_position.x,
+            expander_position.y,
+            expander_size.x,
+            expander_size.y,
+        );
+    }
+}
+
+impl DrawableComponent for ErrorToastContents {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.header_text.draw(ctx)?;
+            self.close_text.draw(ctx)?;
+            self.summary_text.draw(ctx)?;
+
+            if self.expanded {
+                for text in self.details_text.iter_mut() {
+                    text.draw(ctx)?;
+                }
+            }
+
+            if !self.details_text.is_empty() {
+                self.expander_text.draw(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+impl NotificationContents for ErrorToastContents {
+    fn required_size(&self) -> numeric::Vector2f {
+        numeric::Vector2f::new(
+            self.close_rect.right() + ERROR_TOAST_PADDING,
+            self.expander_rect.bottom() + ERROR_TOAST_PADDING,
+        )
+    }
+
+    fn get_notification_type(&self) -> NotificationType {
+        NotificationType::RecoverableError
+    }
+
+    fn click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> NotificationClick {
+        if self.close_rect.contains(mintp!(point)) {
+            return NotificationClick::Dismiss;
+        }
+
+        if !self.details_text.is_empty() && self.expander_rect.contains(mintp!(point)) {
+            self.expanded = !self.expanded;
+            self.layout(ctx.context);
+            return NotificationClick::Resized;
+        }
+
+        NotificationClick::Ignored
+    }
+
+    fn display_time(&self) -> Clock {
+        ERROR_TOAST_DISPLAY_TIME
+    }
+}
+
 pub struct NotificationArea {
     default_animation_time: Clock,
     background: UniTexture,
@@ -151,6 +425,8 @@ pub struct NotificationArea {
     queued_contents: VecDeque<Box<dyn NotificationContents>>,
     contents: Option<Box<dyn NotificationContents>>,
     area: Option<EffectableWrap<MovableWrap<SubScreen>>>,
+    shown_generation: u32,
+    hiding: bool,
     drwob_essential: DrawableObjectEssential,
     redraw_request: DrawRequest,
 }
@@ -178,6 +454,8 @@ impl NotificationArea {
             queued_contents: VecDeque::new(),
             contents: None,
             area: None,
+            shown_generation: 0,
+            hiding: false,
             drwob_essential: DrawableObjectEssential::new(true, depth),
             redraw_request: DrawRequest::InitDraw,
         }
@@ -208,19 +486,22 @@ impl NotificationArea {
             self.new_appearance_frame(ctx);
             self.set_appear_animation(t);
         } else {
-            // 重複した内容は通知キューに入れない
+            // 重複した内容は通知キューに入れない。不具合は別々の内容なので、全て並べる
             let newer_type = contents.get_notification_type();
-            let current_type = self.contents.as_ref().unwrap().get_notification_type();
+            if newer_type != NotificationType::RecoverableError {
+                let current_type = self.contents.as_ref().unwrap().get_notification_type();
+                if current_type == newer_type {
+                    return;
+                }
 
-            if current_type != newer_type {
-                for contents in &self.queued_contents {
-                    if contents.get_notification_type() == contents.get_notification_type() {
+                for queued in &self.queued_contents {
+                    if queued.get_notification_type() == newer_type {
                         return;
                     }
                 }
-
-                self.queued_contents.push_back(contents);
             }
+
+            self.queued_contents.push_back(contents);
         }
 
         self.redraw_request = DrawRequest::Draw;
@@ -236,8 +517,19 @@ impl NotificationArea {
         self.insert_new_contents(ctx, contents, t);
     }
 
+    pub fn insert_recoverable_error<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        error: &RecoverableError,
+        t: Clock,
+    ) {
+        let contents = Box::new(ErrorToastContents::new(ctx, error, 0));
+        self.insert_new_contents(ctx, contents, t);
+    }
+
     fn set_hide_animation(&mut self, t: Clock) {
         if let Some(area) = self.area.as_mut() {
+            self.hiding = true;
             area.clear_effect();
             area.override_move_func(
                 move_fn::devide_distance(numeric::Point2f::new(1366.0, 10.0), 0.2),
@@ -249,6 +541,7 @@ impl NotificationArea {
                 Box::new(move |slf: &mut NotificationArea, ctx, t| {
                     slf.area = None;
                     slf.contents = None;
+                    slf.hiding = false;
                     let next = slf.queued_contents.pop_front();
                     if let Some(next_contents) = next {
                         slf.insert_new_contents(ctx, next_contents, t);
@@ -261,20 +554,69 @@ impl NotificationArea {
     }
 
     fn set_appear_animation(&mut self, t: Clock) {
+        let display_time = self.contents.as_ref().unwrap().display_time();
+
         if let Some(area) = self.area.as_mut() {
             area.clear_effect();
             area.add_effect(vec![effect::fade_in(self.default_animation_time, t)]);
 
-            let scheduled = t + 120;
+            // 出している間に開かれたり閉じられたりしたら、この予定は取り消す
+            self.shown_generation += 1;
+            let generation = self.shown_generation;
+            let scheduled = t + display_time;
             self.event_list.add_event(
                 Box::new(move |slf: &mut NotificationArea, _, _| {
-                    slf.set_hide_animation(scheduled);
+                    if slf.shown_generation == generation {
+                        slf.set_hide_animation(scheduled);
+                    }
                 }),
                 scheduled,
             );
         }
     }
 
+    ///
+    /// 出している通知の上をクリックしていればtrue
+    ///
+    pub fn click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> bool {
+        let position = match self.area.as_ref() {
+            Some(area) if !self.hiding => area.get_position(),
+            _ => return false,
+        };
+        let size = match self.contents.as_ref() {
+            Some(contents) => contents.required_size(),
+            None => return false,
+        };
+
+        let rect = numeric::Rect::new(position.x, position.y, size.x, size.y);
+        if !rect.contains(mintp!(point)) {
+            return false;
+        }
+
+        let local = numeric::Point2f::new(point.x - position.x, point.y - position.y);
+        match self.contents.as_mut().unwrap().click_handler(ctx, local) {
+            NotificationClick::Ignored => (),
+            NotificationClick::Dismiss => {
+                self.shown_generation += 1;
+                self.set_hide_animation(t);
+            }
+            NotificationClick::Resized => {
+                self.shown_generation += 1;
+                self.update_area_canvas(ctx.context, t);
+                self.new_appearance_frame(ctx);
+            }
+        }
+
+        ctx.process_utility.redraw();
+        self.redraw_request = DrawRequest::Draw;
+        true
+    }
+
     fn update_area_canvas(&mut self, ctx: &mut ggez::Context, t: Clock) {
         let area_size = self.contents.as_ref().unwrap().required_size();
         self.area = Some(EffectableWrap::new(
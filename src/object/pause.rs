@@ -0,0 +1,338 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::*;
+
+use super::title_object::ConfigPanel;
+use super::DarkEffectPanel;
+
+pub enum PauseResult {
+    ReleasePause,
+    GoToTitle,
+}
+
+///
+/// # 一時停止の画面
+/// 画面を暗くし、設定、開始画面へ、再開を並べる。マウスでもキーボードでも選べる
+///
+/// ## cursored_index
+/// 今選んでいる項目。マウスを重ねるか、上下のキーで動かす
+///
+/// ## config_panel
+/// 設定を開いている間はSome
+///
+pub struct PauseScreenSet {
+    entries: Vec<VerticalText>,
+    cursored_index: Option<usize>,
+    drwob_essential: DrawableObjectEssential,
+    config_panel: Option<ConfigPanel>,
+    dark_effect: DarkEffectPanel,
+    idle_prompt: VerticalText,
+    is_paused_now: bool,
+}
+
+impl PauseScreenSet {
+    const CONFIG_ENTRY: usize = 0;
+    const TITLE_ENTRY: usize = 1;
+    const RESUME_ENTRY: usize = 2;
+
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, depth: i8, t: Clock) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(28.0, 28.0),
+            ggraphics::Color::BLACK,
+        );
+
+        let mut entries_vtext = Vec::new();
+        let mut text_pos = numeric::Point2f::new(750.0, 200.0);
+
+        for text in vec!["設定", "開始画面へ", "再開"] {
+            let mut vtext = VerticalText::new(
+                text.to_string(),
+                text_pos,
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info.clone(),
+            );
+            vtext.hide();
+            entries_vtext.push(vtext);
+
+            text_pos.x -= 50.0;
+        }
+
+        let mut idle_prompt = VerticalText::new(
+            "休憩しますか?".to_string(),
+            numeric::Point2f::new(850.0, 200.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info.clone(),
+        );
+        idle_prompt.hide();
+
+        PauseScreenSet {
+            entries: entries_vtext,
+            drwob_essential: DrawableObjectEssential::new(true, depth),
+            cursored_index: None,
+            config_panel: None,
+            dark_effect: DarkEffectPanel::new(
+                ctx.context,
+                numeric::Rect::new(0.0, 0.0, WINDOW_SIZE_X as f32, WINDOW_SIZE_Y as f32),
+                t,
+            ),
+            idle_prompt: idle_prompt,
+            is_paused_now: false,
+        }
+    }
+
+    ///
+    /// 再描画要求有り
+    ///
+    fn select_entries_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, index: usize) {
+        if let Some(cursored_index) = self.cursored_index {
+            if cursored_index == index {
+                return;
+            }
+        }
+
+        self.unselect_entries_handler();
+        self.entries
+            .get_mut(index)
+            .unwrap()
+            .set_color(ggraphics::Color::from_rgba_u32(0x222222ff));
+
+        self.cursored_index = Some(index);
+        ctx.process_utility.redraw();
+    }
+
+    fn unselect_entries_handler(&mut self) {
+        for vtext in self.entries.iter_mut() {
+            vtext.set_color(ggraphics::Color::BLACK);
+        }
+    }
+
+    ///
+    /// 選んだ項目を実行する
+    ///
+    fn run_entry<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        index: usize,
+        t: Clock,
+    ) -> Option<PauseResult> {
+        match index {
+            Self::CONFIG_ENTRY => {
+                self.config_panel = Some(ConfigPanel::new(
+                    ctx,
+                    numeric::Rect::new(50.0, 50.0, 1266.0, 668.0),
+                    0,
+                    t,
+                ));
+                ctx.process_utility.redraw();
+                None
+            }
+            Self::TITLE_ENTRY => Some(PauseResult::GoToTitle),
+            Self::RESUME_ENTRY => Some(PauseResult::ReleasePause),
+            _ => panic!("index is out of bounds"),
+        }
+    }
+
+    pub fn dragging_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+        t: Clock,
+    ) {
+        if let Some(panel) = self.config_panel.as_mut() {
+            panel.mouse_dragging_handler(ctx, button, point, t);
+        }
+    }
+
+    pub fn mouse_motion_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        if self.config_panel.is_none() {
+            for (index, vtext) in self.entries.iter().enumerate() {
+                if vtext.contains(ctx.context, point) {
+                    self.select_entries_handler(ctx, index);
+                    return;
+                }
+            }
+        }
+
+        // ここまで到達するのは、すべてのテキストにカーソルが重なっていなかった場合
+        if self.cursored_index.is_some() {
+            self.unselect_entries_handler();
+            self.cursored_index = None;
+        }
+    }
+
+    pub fn mouse_button_down<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+        t: Clock,
+    ) {
+        if let Some(panel) = self.config_panel.as_mut() {
+            panel.mouse_button_down(ctx, button, point, t);
+        }
+    }
+
+    pub fn mouse_click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> Option<PauseResult> {
+        if let Some(panel) = self.config_panel.as_mut() {
+            if let Some(_) = panel.mouse_button_up(ctx, point, t) {
+                // ここに到達する場合は、"init-menu"に移動するとき
+                // ここでは、ConfigPanelのExitととしてみなす
+                ctx.process_utility.redraw();
+                self.config_panel = None;
+            }
+            return None;
+        }
+
+        let clicked = self
+            .entries
+            .iter()
+            .position(|vtext| vtext.contains(ctx.context, point));
+
+        match clicked {
+            Some(index) => self.run_entry(ctx, index, t),
+            None => None,
+        }
+    }
+
+    ///
+    /// 上下で項目を選び、Enterで実行する。Escapeは設定を開いていれば設定を閉じ、そうでなければ再開する
+    ///
+    pub fn key_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        key: PaletteKey,
+        t: Clock,
+    ) -> Option<PauseResult> {
+        if let Some(panel) = self.config_panel.as_mut() {
+            // 中止と同じく、適用していない変更は捨てて閉じる
+            if key == PaletteKey::Escape {
+                panel.recover_original_config(ctx);
+                self.config_panel = None;
+                ctx.process_utility.redraw();
+            }
+            return None;
+        }
+
+        let last = self.entries.len() - 1;
+        match key {
+            PaletteKey::Up => {
+                let index = self
+                    .cursored_index
+                    .map_or(0, |index| index.saturating_sub(1));
+                self.select_entries_handler(ctx, index);
+                None
+            }
+            PaletteKey::Down => {
+                let index = self.cursored_index.map_or(0, |index| (index + 1).min(last));
+                self.select_entries_handler(ctx, index);
+                None
+            }
+            PaletteKey::Enter => match self.cursored_index {
+                Some(index) => self.run_entry(ctx, index, t),
+                None => None,
+            },
+            PaletteKey::Escape => Some(PauseResult::ReleasePause),
+            _ => None,
+        }
+    }
+
+    pub fn effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.dark_effect.run_effect(ctx, t);
+    }
+
+    pub fn exit_pause(&mut self, t: Clock) {
+        self.is_paused_now = false;
+        self.dark_effect.new_effect(8, t, 235, 0);
+        for vtext in self.entries.iter_mut() {
+            vtext.hide();
+        }
+        self.idle_prompt.hide();
+        if let Some(config_panel) = self.config_panel.as_mut() {
+            config_panel.hide();
+        }
+    }
+
+    pub fn enter_pause(&mut self, t: Clock) {
+        self.is_paused_now = true;
+        self.dark_effect.new_effect(8, t, 0, 235);
+        for vtext in self.entries.iter_mut() {
+            vtext.appear();
+        }
+        if let Some(config_panel) = self.config_panel.as_mut() {
+            config_panel.appear();
+        }
+    }
+
+    ///
+    /// 無操作が続いた時のポーズ。休憩を促す文言を添える
+    ///
+    pub fn enter_idle_pause(&mut self, t: Clock) {
+        self.enter_pause(t);
+        self.idle_prompt.appear();
+    }
+
+    pub fn is_paused_now(&self) -> bool {
+        self.is_paused_now
+    }
+}
+
+impl DrawableComponent for PauseScreenSet {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.dark_effect.draw(ctx)?;
+
+            for vtext in self.entries.iter_mut() {
+                vtext.draw(ctx)?;
+            }
+            self.idle_prompt.draw(ctx)?;
+
+            if let Some(panel) = self.config_panel.as_mut() {
+                panel.draw(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    #[inline(always)]
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    #[inline(always)]
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    #[inline(always)]
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    #[inline(always)]
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
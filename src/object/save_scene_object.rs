@@ -13,7 +13,7 @@ use torifune::impl_texture_object_for_wrapped;
 use crate::{object::util_object::*, scene::DrawRequest};
 use crate::{core::*, set_table_frame_cell_center};
 
-use number_to_jk::number_to_jk;
+use crate::core::number_format::NumberFormat;
 
 pub enum SaveDataOperation {
     Saving,
@@ -159,7 +159,8 @@ impl DrawableSaveEntry {
 	    redraw_request: DrawRequest::InitDraw,
         };
 
-        entry.update_entry_contents(ctx.context, ctx.resource, &savable_data);
+        let number_format = ctx.number_format();
+        entry.update_entry_contents(ctx.context, ctx.resource, number_format, &savable_data);
         entry
     }
 
@@ -197,6 +198,7 @@ impl DrawableSaveEntry {
         &mut self,
         ctx: &mut ggez::Context,
         resource: &GameResource,
+        number_format: NumberFormat,
         savable_data: &SavableData,
     ) {
         self.desc_text.clear();
@@ -265,11 +267,7 @@ impl DrawableSaveEntry {
         self.desc_text.push(day_desc_text);
 
         let mut date_text = VerticalText::new(
-            format!(
-                "{}月{}日",
-                number_to_jk(savable_data.date.month as u64),
-                number_to_jk(savable_data.date.day as u64)
-            ),
+            number_format.short_date(&savable_data.date),
             numeric::Point2f::new(220.0, 60.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -309,10 +307,7 @@ impl DrawableSaveEntry {
         self.desc_text.push(money_desc_text);
 
         let mut money_text = VerticalText::new(
-            format!(
-                "{}円",
-                number_to_jk(savable_data.task_result.total_money as u64)
-            ),
+            number_format.money(savable_data.task_result.total_money as u64),
             numeric::Point2f::new(150.0, 60.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -347,8 +342,9 @@ impl DrawableSaveEntry {
             _ => (),
         }
 
+        let number_format = ctx.number_format();
         if let Some(data) = ctx.savable_data.as_mut() {
-            self.update_entry_contents(ctx.context, ctx.resource, data);
+            self.update_entry_contents(ctx.context, ctx.resource, number_format, data);
         }
 
 	self.redraw_request = DrawRequest::Draw;
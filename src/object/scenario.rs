@@ -14,6 +14,15 @@ use super::*;
 use crate::{core::ScenarioSceneSaveData, parse_toml_file};
 use crate::scene::scenario_scene::ScenarioContext;
 use crate::scene::{SceneID, SceneTransition};
+use crate::core::color_grading::{self, ColorGradePreset};
+use crate::core::content_warning::{self, ContentTag};
+use crate::core::decision_log::DecisionKind;
+use crate::core::preferences;
+use crate::core::recoverable_error::{self, ErrorSource, RecoverableError};
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::text_layout::{self, LaidOutLine, LayoutSegment, TextLayoutWorker};
+use crate::core::voice_blip::{BlipCounter, VoiceArchetype};
+use crate::log_debug;
 use crate::{core::SoundID, object::util_object::*};
 use crate::{
     core::{FontID, GameResource, SuzuContext, TextureID, TileBatchTextureID},
@@ -48,6 +57,7 @@ impl ScenarioTextSegment {
         obj: &toml::value::Table,
         game_data: &GameResource,
         default: &ScenarioTextAttribute,
+        nickname: &str,
     ) -> Self {
         let text = if let Some(text_path) = obj.get("text_src_path").as_ref() {
             std::fs::read_to_string(text_path.as_str().unwrap()).unwrap()
@@ -57,7 +67,8 @@ impl ScenarioTextSegment {
                 .as_str()
                 .unwrap()
                 .to_string()
-        };
+        }
+        .replace(preferences::NICKNAME_PLACEHOLDER, nickname);
 
         let fpc = if let Some(fpc) = obj.get("fpc") {
             fpc.as_float().unwrap() as f32
@@ -90,10 +101,6 @@ impl ScenarioTextSegment {
         }
     }
 
-    fn slice_text_bytes(&self, begin: usize, end: usize) -> &str {
-        unsafe { self.text.as_str().get_unchecked(begin..end) }
-    }
-
     fn last_line_length(&self) -> usize {
         let mut length: usize = 0;
         let it = self.text.chars().rev();
@@ -109,21 +116,7 @@ impl ScenarioTextSegment {
     }
 
     pub fn slice(&self, length: usize) -> &str {
-        let mut reached_flag = false;
-
-        let str_bytes = self.text.as_str().as_bytes().len();
-
-        for (count, (bytes, _)) in self.text.as_str().char_indices().enumerate() {
-            if reached_flag {
-                return self.slice_text_bytes(0, bytes as usize);
-            }
-
-            if count == length {
-                reached_flag = true;
-            }
-        }
-
-        return self.slice_text_bytes(0, str_bytes as usize);
+        text_layout::slice_chars(&self.text, length)
     }
 
     pub fn get_fpc(&self) -> f32 {
@@ -313,10 +306,20 @@ pub struct ScenarioText {
     next_scenario_id: ScenarioElementID,
     background_texture_id: Option<TextureID>,
     tachie_data: TachieData,
+    voice: VoiceArchetype,
+    content_tags: Vec<ContentTag>,
+    skip_to: Option<ScenarioElementID>,
 }
 
 impl ScenarioText {
-    pub fn new(toml_scripts: &toml::value::Value, game_data: &GameResource) -> Self {
+    ///
+    /// 台詞の中の呼び名の印は、nicknameに置き換える
+    ///
+    pub fn new(
+        toml_scripts: &toml::value::Value,
+        game_data: &GameResource,
+        nickname: &str,
+    ) -> Self {
         let id = toml_scripts.get("id").unwrap().as_integer().unwrap() as i32;
         let next_id = toml_scripts.get("next-id").unwrap().as_integer().unwrap() as i32;
 
@@ -343,7 +346,7 @@ impl ScenarioText {
         for elem in toml_scripts.get("text").unwrap().as_array().unwrap() {
             if let toml::Value::Table(scenario) = elem {
                 seq_text.push(ScenarioTextSegment::from_toml_using_default(
-                    scenario, game_data, &default,
+                    scenario, game_data, &default, nickname,
                 ));
             }
         }
@@ -381,6 +384,26 @@ impl ScenarioText {
             }
         };
 
+        // 話し手の名前で文字送りの声が決まる。指定が無ければ小鈴の声
+        let voice = match toml_scripts.get("voice") {
+            Some(name) => VoiceArchetype::from_name(name.as_str().unwrap()),
+            None => VoiceArchetype::Kosuzu,
+        };
+
+        // 注意書きと、飛ばしたときの行き先
+        let content_tags = match toml_scripts.get("tags") {
+            Some(tags) => content_warning::parse_tags(
+                tags.as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|tag| tag.as_str().unwrap()),
+            ),
+            None => Vec::new(),
+        };
+        let skip_to = toml_scripts
+            .get("skip-to")
+            .map(|id| id.as_integer().unwrap() as ScenarioElementID);
+
         ScenarioText {
             seq_text: seq_text,
             iterator: 0.0,
@@ -390,6 +413,9 @@ impl ScenarioText {
             next_scenario_id: next_id,
             background_texture_id: background_texture_id,
             tachie_data: tachie_data,
+            voice: voice,
+            content_tags: content_tags,
+            skip_to: skip_to,
         }
     }
 
@@ -407,6 +433,34 @@ impl ScenarioText {
         }
     }
 
+    ///
+    /// from文字目からto文字目の手前までの文字列
+    ///
+    fn revealed_text(&self, from: usize, to: usize) -> String {
+        self.seq_text
+            .iter()
+            .flat_map(|seg| seg.text.chars())
+            .skip(from)
+            .take(to.saturating_sub(from))
+            .collect()
+    }
+
+    pub fn get_voice(&self) -> VoiceArchetype {
+        self.voice
+    }
+
+    pub fn get_content_tags(&self) -> &Vec<ContentTag> {
+        &self.content_tags
+    }
+
+    pub fn get_skip_to(&self) -> Option<ScenarioElementID> {
+        self.skip_to
+    }
+
+    pub fn content_treatment(&self, enabled: bool) -> content_warning::SceneTreatment {
+        content_warning::scene_treatment(enabled, &self.content_tags, self.skip_to.is_some())
+    }
+
     pub fn reset_segment(&mut self) {
         self.current_segment_index = 0;
     }
@@ -427,6 +481,13 @@ impl ScenarioText {
         self.scenario_id
     }
 
+    ///
+    /// 前に注意書きの選択肢を差し込むときに、この場面のIDを付け替える
+    ///
+    pub fn set_scenario_id(&mut self, id: ScenarioElementID) {
+        self.scenario_id = id;
+    }
+
     pub fn reset(&mut self) {
         self.iterator = 0.0;
         self.current_segment_index = 0;
@@ -441,8 +502,15 @@ impl ScenarioText {
     }
 }
 
+///
+/// # シナリオの分岐
+///
+/// ## flag
+/// opecodeがflagのとき、日記で立てたこの印を見る
+///
 pub struct ScenarioSwitch {
     opecode: String,
+    flag: Option<String>,
     self_id: ScenarioElementID,
     yes_branch: ScenarioElementID,
     no_branch: ScenarioElementID,
@@ -453,6 +521,7 @@ impl ScenarioSwitch {
 	ScenarioSwitch {
 	    self_id: toml_scripts["id"].as_integer().unwrap() as i32,
 	    opecode: toml_scripts["opecode"].as_str().unwrap().to_string(),
+	    flag: toml_scripts.get("flag").map(|flag| flag.as_str().unwrap().to_string()),
 	    yes_branch: toml_scripts["yes"].as_integer().unwrap() as i32,
 	    no_branch: toml_scripts["no"].as_integer().unwrap() as i32,
 	}
@@ -462,6 +531,10 @@ impl ScenarioSwitch {
 	self.opecode.as_str()
     }
 
+    pub fn get_flag(&self) -> Option<&str> {
+	self.flag.as_ref().map(|flag| flag.as_str())
+    }
+
     pub fn get_yes_branch(&self) -> ScenarioElementID {
 	self.yes_branch
     }
@@ -478,10 +551,15 @@ impl ScenarioSwitch {
 ///
 /// 選択肢のデータを保持する構造体
 ///
+/// ## decisions
+/// 選んだときに決断として記録するもの。選択肢にdecision = "名前"と書く。
+/// 種類はdecision-kindで指定し、書かなければ方針の決断になる
+///
 pub struct ChoicePatternData {
     header_text: String,
     text: Vec<String>,
     jump_scenario_id: Vec<ScenarioElementID>,
+    decisions: Vec<Option<(DecisionKind, String)>>,
     scenario_id: ScenarioElementID,
     background_texture_id: Option<TextureID>,
     tachie_data: TachieData,
@@ -493,6 +571,7 @@ impl ChoicePatternData {
 
         let mut choice_pattern_array = Vec::new();
         let mut jump_scenario_array = Vec::new();
+        let mut decision_array = Vec::new();
 
         for elem in toml_scripts
             .get("choice-pattern")
@@ -503,6 +582,16 @@ impl ChoicePatternData {
             choice_pattern_array.push(elem.get("pattern").unwrap().as_str().unwrap().to_string());
             jump_scenario_array
                 .push(elem.get("jump-id").unwrap().as_integer().unwrap() as ScenarioElementID);
+
+            let decision = elem.get("decision").map(|key| {
+                let kind = match elem.get("decision-kind") {
+                    Some(kind) => DecisionKind::from_str(kind.as_str().unwrap())
+                        .expect("Unknown decision-kind"),
+                    None => DecisionKind::Policy,
+                };
+                (kind, key.as_str().unwrap().to_string())
+            });
+            decision_array.push(decision);
         }
 
         let background_texture_id = if let Some(background_tid_str) = toml_scripts.get("background")
@@ -543,12 +632,34 @@ impl ChoicePatternData {
                 .to_string(),
             text: choice_pattern_array,
             jump_scenario_id: jump_scenario_array,
+            decisions: decision_array,
             scenario_id: id,
             background_texture_id: background_texture_id,
             tachie_data: tachie_data,
         }
     }
 
+    ///
+    /// 注意書きのある場面の前に出す、飛ばすか見るかの選択肢
+    ///
+    pub fn content_warning_prompt(scenario_text: &ScenarioText, id: ScenarioElementID) -> Self {
+        ChoicePatternData {
+            header_text: content_warning::warning_header(scenario_text.get_content_tags()),
+            text: vec![
+                content_warning::SKIP_CHOICE_TEXT.to_string(),
+                content_warning::WATCH_CHOICE_TEXT.to_string(),
+            ],
+            jump_scenario_id: vec![
+                scenario_text.get_skip_to().unwrap(),
+                scenario_text.get_scenario_id(),
+            ],
+            decisions: vec![None, None],
+            scenario_id: id,
+            background_texture_id: scenario_text.get_background_texture_id(),
+            tachie_data: scenario_text.get_tachie_data(),
+        }
+    }
+
     pub fn get_scenario_id(&self) -> ScenarioElementID {
         self.scenario_id
     }
@@ -560,6 +671,12 @@ impl ChoicePatternData {
     pub fn get_tachie_data(&self) -> TachieData {
         self.tachie_data.clone()
     }
+
+    pub fn get_decision(&self, index: usize) -> Option<&(DecisionKind, String)> {
+        self.decisions
+            .get(index)
+            .and_then(|decision| decision.as_ref())
+    }
 }
 
 pub struct ChoiceBox {
@@ -652,6 +769,28 @@ impl ChoiceBox {
             }
         }
     }
+
+    ///
+    /// キーやパッドで、選んでいる選択肢を隣へ動かす。端では止まり、何も選んでいなければ先頭を選ぶ
+    ///
+    pub fn move_selection<'a>(&mut self, ctx: &mut SuzuContext<'a>, forward: bool) {
+        let last = self.panels.len().saturating_sub(1);
+        let next = match self.selecting {
+            Some(index) if forward => (index + 1).min(last),
+            Some(index) => index.saturating_sub(1),
+            None => 0,
+        };
+
+        for (index, panel) in self.panels.iter_mut().enumerate() {
+            if index == next {
+                panel.make_this_hovered_status(ctx);
+            } else {
+                panel.make_this_none_status(ctx);
+            }
+        }
+
+        self.selecting = Some(next);
+    }
 }
 
 impl DrawableComponent for ChoiceBox {
@@ -791,20 +930,38 @@ pub struct ScheduleStartEssential {
 //     tachie_data: TachieData,
 // }
 
+///
+/// # 画面の色味を変える命令
+/// 色味を変え終わるのを待たずに、next_idの場面へ進む
+///
+pub struct ColorGradeEssential {
+    scenario_id: ScenarioElementID,
+    next_id: ScenarioElementID,
+    preset: ColorGradePreset,
+    duration: Clock,
+}
+
 pub enum ScenarioBuiltinCommand {
     ScheduleStart(ScheduleStartEssential),
+    ColorGrade(ColorGradeEssential),
 }
 
 impl ScenarioBuiltinCommand {
-    pub fn from_toml_object(toml_scripts: &toml::value::Value) -> Self {
-        match toml_scripts
-            .get("opecode")
-            .unwrap()
-            .as_str()
-            .expect("invalid ScenarioBuiltinCommand opecode format")
-        {
+    ///
+    /// 読めない命令はErrで返す。台本の他の部分はそのまま続けられる
+    ///
+    pub fn from_toml_object(toml_scripts: &toml::value::Value) -> Result<Self, String> {
+        let opecode = match toml_scripts.get("opecode").and_then(|op| op.as_str()) {
+            Some(opecode) => opecode,
+            None => return Err("builtin command without opecode".to_string()),
+        };
+
+        match opecode {
             "StartSchedule" => {
-                let id = toml_scripts.get("id").unwrap().as_integer().unwrap() as i32;
+                let id = match toml_scripts.get("id").and_then(|id| id.as_integer()) {
+                    Some(id) => id as i32,
+                    None => return Err("StartSchedule without id".to_string()),
+                };
                 let background_texture_id =
                     if let Some(background_tid_str) = toml_scripts.get("background") {
                         Some(TextureID::from_str(background_tid_str.as_str().unwrap()).unwrap())
@@ -834,31 +991,64 @@ impl ScenarioBuiltinCommand {
                     TachieData::new_empty()
                 };
 
-                Self::ScheduleStart(ScheduleStartEssential {
+                Ok(Self::ScheduleStart(ScheduleStartEssential {
                     scenario_id: id,
                     background_texture_id: background_texture_id,
                     tachie_data: tachie_data,
-                })
+                }))
             }
-            _ => panic!("Invalid ScenarioBuiltinCommand opecode"),
+            "ColorGrade" => {
+                let id = match toml_scripts.get("id").and_then(|id| id.as_integer()) {
+                    Some(id) => id as i32,
+                    None => return Err("ColorGrade without id".to_string()),
+                };
+                let next_id = match toml_scripts.get("next-id").and_then(|id| id.as_integer()) {
+                    Some(id) => id as i32,
+                    None => return Err("ColorGrade without next-id".to_string()),
+                };
+                let preset_str = toml_scripts
+                    .get("preset")
+                    .and_then(|preset| preset.as_str())
+                    .unwrap_or("neutral");
+                let preset = match ColorGradePreset::from_str(preset_str) {
+                    Some(preset) => preset,
+                    None => return Err(format!("unknown color grade preset {}", preset_str)),
+                };
+                let duration = toml_scripts
+                    .get("duration")
+                    .and_then(|duration| duration.as_integer())
+                    .map(|duration| duration.max(0) as Clock)
+                    .unwrap_or(color_grading::SCENE_GRADE_BLEND_FRAMES);
+
+                Ok(Self::ColorGrade(ColorGradeEssential {
+                    scenario_id: id,
+                    next_id: next_id,
+                    preset: preset,
+                    duration: duration,
+                }))
+            }
+            _ => Err(format!("unknown opecode {}", opecode)),
         }
     }
 
     pub fn get_scenario_id(&self) -> ScenarioElementID {
         match self {
             ScenarioBuiltinCommand::ScheduleStart(data) => data.scenario_id,
+            ScenarioBuiltinCommand::ColorGrade(data) => data.scenario_id,
         }
     }
 
     pub fn get_background_texture_id(&self) -> Option<TextureID> {
         match self {
             ScenarioBuiltinCommand::ScheduleStart(data) => data.background_texture_id.clone(),
+            ScenarioBuiltinCommand::ColorGrade(_) => None,
         }
     }
 
     pub fn get_tachie_info(&self) -> TachieData {
         match self {
             ScenarioBuiltinCommand::ScheduleStart(data) => data.tachie_data.clone(),
+            ScenarioBuiltinCommand::ColorGrade(_) => TachieData::new_empty(),
         }
     }
 }
@@ -967,6 +1157,8 @@ impl Scenario {
 	save_data: Option<&ScenarioSceneSaveData>,
     ) -> Self {
         let game_data = &ctx.resource;
+        let content_warnings = ctx.preferences.is_content_warning_enabled();
+        let nickname = ctx.preferences.nickname_or_default().to_string();
 
         let mut scenario = ScenarioElementPool::new_empty();
 
@@ -996,7 +1188,7 @@ impl Scenario {
 
         let first_scenario_id = 
 	    if let Some(save_data) = save_data {
-		println!("first id -> {}", save_data.scenario_id);
+		log_debug!("first id -> {}", save_data.scenario_id);
 		save_data.scenario_id as i64
 	    } else {
 		root["first-scenario-id"].as_integer().unwrap()
@@ -1008,7 +1200,23 @@ impl Scenario {
             if let Some(type_info) = elem.get("type") {
                 match type_info.as_str().unwrap() {
                     "scenario" => {
-                        scenario.add(ScenarioElement::Text(ScenarioText::new(elem, game_data)));
+                        let mut text = ScenarioText::new(elem, game_data, &nickname);
+                        let treatment = content_warning::scene_treatment(
+                            content_warnings,
+                            text.get_content_tags(),
+                            text.get_skip_to().is_some(),
+                        );
+
+                        // 注意書きの選択肢が元の場面のIDを引き継ぎ、場面は別のIDへ移る
+                        if treatment.offer_skip {
+                            let id = text.get_scenario_id();
+                            text.set_scenario_id(id + content_warning::CONTENT_WARNING_ID_OFFSET);
+                            scenario.add(ScenarioElement::ChoiceSwitch(
+                                ChoicePatternData::content_warning_prompt(&text, id),
+                            ));
+                        }
+
+                        scenario.add(ScenarioElement::Text(text));
                     }
                     "choice" => {
                         scenario.add(ScenarioElement::ChoiceSwitch(
@@ -1020,18 +1228,29 @@ impl Scenario {
                             ScenarioFinishAndWaitData::from_toml_object(elem, game_data),
                         ));
                     }
-                    "builtin" => {
-                        scenario.add(ScenarioElement::BuiltinCommand(
-                            ScenarioBuiltinCommand::from_toml_object(elem),
-                        ));
-                    }
+                    "builtin" => match ScenarioBuiltinCommand::from_toml_object(elem) {
+                        Ok(command) => scenario.add(ScenarioElement::BuiltinCommand(command)),
+                        Err(e) => recoverable_error::report(RecoverableError::new(
+                            ErrorSource::Script,
+                            "台本ノ命令ガ読メマセン",
+                            &format!("{}\n{}", file_path, e),
+                        )),
+                    },
 		    "switch" => {
 			scenario.add(ScenarioElement::Switch(ScenarioSwitch::from_toml_object(elem)));
 		    }
-                    _ => eprintln!("Error"),
+                    other => recoverable_error::report(RecoverableError::new(
+                        ErrorSource::Script,
+                        "台本ノ要素ガ読メマセン",
+                        &format!("{}\nunknown element type {}", file_path, other),
+                    )),
                 }
             } else {
-                eprintln!("Error");
+                recoverable_error::report(RecoverableError::new(
+                    ErrorSource::Script,
+                    "台本ノ要素ガ読メマセン",
+                    &format!("{}\nelement without type", file_path),
+                ));
             }
         }
 
@@ -1214,11 +1433,29 @@ pub enum TextBoxStatus {
     FixedText,
 }
 
+///
+/// # 行割りを頼んで、まだ当てていない頁
+///
+/// ## fonts
+/// 頼んだときの、各セグメントのフォント
+///
+/// ## finished
+/// 頼んだときに、頁の最後まで表示していたか
+///
+struct PendingLayout {
+    serial: u64,
+    fonts: Vec<FontInformation>,
+    finished: bool,
+}
+
 pub struct TextBox {
     box_lines: usize,
-    buffered_text: VecDeque<SimpleText>,
     head_line_number: u32,
-    text: VecDeque<SimpleText>,
+    lines: Vec<SimpleText>,
+    layout: Vec<LaidOutLine>,
+    kept_lines: usize,
+    layout_worker: TextLayoutWorker,
+    pending_layouts: VecDeque<PendingLayout>,
     line_arrow: UniTexture,
     text_box_status: TextBoxStatus,
     appearance_frame: TileBatchFrame,
@@ -1260,9 +1497,12 @@ impl TextBox {
 
         let mut text_box = TextBox {
             box_lines: box_lines,
-            buffered_text: VecDeque::new(),
             head_line_number: 0,
-            text: VecDeque::new(),
+            lines: Vec::new(),
+            layout: Vec::new(),
+            kept_lines: 0,
+            layout_worker: TextLayoutWorker::spawn(),
+            pending_layouts: VecDeque::new(),
             text_box_status: TextBoxStatus::UpdatingText,
             background: background,
             appearance_frame: appr_frame,
@@ -1294,48 +1534,47 @@ impl TextBox {
         sub_screen::pop_screen(ctx.context);
     }
 
-    // ScenarioTextSegmentを改行で分割しVec<SimpleText>に変換する
-    pub fn text_from_segment(segment: &ScenarioTextSegment, length: usize) -> Vec<SimpleText> {
-        let mut text_lines = Vec::new();
-
-        for line in segment.slice(length).lines() {
-            text_lines.push(SimpleText::new(
-                tobj::MovableText::new(
-                    Box::new(tobj::UniText::new(
-                        line.to_string(),
-                        numeric::Point2f::new(0.0, 0.0),
-                        numeric::Vector2f::new(1.0, 1.0),
-                        0.0,
-                        0,
-                        segment.attribute.font_info,
-                    )),
-                    None,
+    fn text_line(text: &str, font_info: FontInformation) -> SimpleText {
+        SimpleText::new(
+            tobj::MovableText::new(
+                Box::new(tobj::UniText::new(
+                    text.to_string(),
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
                     0,
-                ),
-                Vec::new(),
-            ));
-        }
+                    font_info,
+                )),
+                None,
+                0,
+            ),
+            Vec::new(),
+        )
+    }
 
-        text_lines
+    ///
+    /// 表示している行のうち、ボックスに収まる最後のbox_lines行
+    ///
+    fn shown_lines(&self) -> std::ops::Range<usize> {
+        self.kept_lines.saturating_sub(self.box_lines)..self.kept_lines
     }
 
+    ///
+    /// 表示する文字数に合わせて行割りを別のスレッドに頼み、割り終わっていた結果を当てる
+    /// 戻り値は、何番目のセグメントまで表示したか
+    ///
     pub fn update_scenario_text<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
         scenario: &ScenarioText,
     ) -> usize {
-        let before_lines = self.text.len() + self.buffered_text.len();
-
-        // 表示するテキストバッファをクリア。これで、新しくテキストを詰めていく
-        self.text.clear();
-        self.buffered_text.clear();
-
         // 表示する文字数を取得。この値を減算していき文字数チェックを行う
         let mut remain = scenario.current_iterator() as i32;
 
-        let mut segs: Vec<(i32, &ScenarioTextSegment)> = Vec::new();
+        let mut segments = Vec::new();
+        let mut fonts = Vec::new();
 
-        // buffered または textにpushされるテキストを保持するTextSegmentを取得
+        // 表示するテキストを保持するTextSegmentを取得
         for seg in scenario.seq_text_iter() {
             // このテキストセグメントの文字数を取得
             let seg_str_len = seg.str_len() as i32;
@@ -1346,7 +1585,8 @@ impl TextBox {
                 remain
             };
 
-            segs.push((slice_len, seg));
+            segments.push(LayoutSegment::new(&seg.text, slice_len as usize));
+            fonts.push(seg.attribute.font_info);
 
             if remain < seg_str_len {
                 break;
@@ -1355,54 +1595,104 @@ impl TextBox {
             remain -= seg_str_len;
         }
 
-        let mut text_lines = VecDeque::new();
-        for (slice_len, seg) in segs.iter() {
-            for line in Self::text_from_segment(seg, *slice_len as usize) {
-                text_lines.push_back(line);
+        let current_segment = segments.len() - 1;
+
+        if let Some(serial) = self.layout_worker.submit(segments) {
+            self.pending_layouts.push_back(PendingLayout {
+                serial: serial,
+                fonts: fonts,
+                finished: scenario.iterator_finish(),
+            });
+        }
+
+        // 割り終わった結果が幾つか溜まっていれば、一番新しいものだけを当てる
+        let mut latest = None;
+        for result in self.layout_worker.poll() {
+            // 固定の文を出したときに捨てた頼みの結果は、当てずに読み飛ばす
+            while self
+                .pending_layouts
+                .front()
+                .map_or(false, |pending| pending.serial < result.serial)
+            {
+                self.pending_layouts.pop_front();
             }
+
+            if self
+                .pending_layouts
+                .front()
+                .map_or(false, |pending| pending.serial == result.serial)
+            {
+                latest = Some((result.lines, self.pending_layouts.pop_front().unwrap()));
+            }
+        }
+
+        if let Some((lines, pending)) = latest {
+            self.apply_layout(ctx, lines, pending);
         }
 
+        current_segment
+    }
+
+    fn apply_layout<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        layout: Vec<LaidOutLine>,
+        pending: PendingLayout,
+    ) {
+        let before_lines = self.kept_lines;
+
+        // 前と同じ行はそのまま使い、変わった行だけ作り直す
+        let reusable = text_layout::reusable_lines(&self.layout, &layout);
+        let mut old_lines: Vec<Option<SimpleText>> = self.lines.drain(..).map(Some).collect();
+        for (index, line) in layout.iter().enumerate() {
+            let text = match old_lines.get_mut(index) {
+                Some(old) if reusable[index] => old.take().unwrap(),
+                _ => Self::text_line(&line.text, pending.fonts[line.segment]),
+            };
+            self.lines.push(text);
+        }
+        self.layout = layout;
+        self.kept_lines = self.lines.len();
+
         if !self.complete_and_wait_current_line {
-            if text_lines.len() > before_lines && text_lines.len() > self.box_lines {
+            if self.kept_lines > before_lines && self.kept_lines > self.box_lines {
                 self.text_box_status = TextBoxStatus::WaitNextLineKey;
                 self.complete_and_wait_current_line = true;
-                text_lines.pop_back();
+                self.kept_lines -= 1;
             }
         } else {
             self.complete_and_wait_current_line = false;
         }
 
-        for _ in 0..self.box_lines {
-            if let Some(text) = text_lines.pop_back() {
-                self.text.push_front(text);
-            }
-        }
-
-        self.buffered_text = text_lines;
-
         // ボックスに入ったSimpleTextの位置を設定
         let mut pos = numeric::Point2f::new(60.0, 60.0);
-        for line in &mut self.text {
+        let shown = self.shown_lines();
+        for line in &mut self.lines[shown.clone()] {
             line.set_position(pos);
             pos.y += line.get_font_scale().y;
         }
 
-        if self.text_box_status == TextBoxStatus::WaitNextLineKey || scenario.iterator_finish() {
-            self.line_arrow.appear();
-            let last_text_drawing_area = self.text.back().unwrap().get_drawing_area(ctx.context);
-            let pos = numeric::Point2f::new(
-                last_text_drawing_area.x + last_text_drawing_area.w,
-                last_text_drawing_area.y,
-            );
-            self.line_arrow.set_position(pos);
+        if self.text_box_status == TextBoxStatus::WaitNextLineKey || pending.finished {
+            if let Some(last) = self.lines[shown].last() {
+                self.line_arrow.appear();
+                let last_text_drawing_area = last.get_drawing_area(ctx.context);
+                let pos = numeric::Point2f::new(
+                    last_text_drawing_area.x + last_text_drawing_area.w,
+                    last_text_drawing_area.y,
+                );
+                self.line_arrow.set_position(pos);
+            }
         }
-
-        segs.len() - 1
     }
 
     pub fn set_fixed_text(&mut self, text: String, font_info: FontInformation) {
-        self.text.clear();
-        self.text.push_back(SimpleText::new(
+        // 固定の文を出した後は、同じ頁でも割り直させる
+        self.layout.clear();
+        self.layout_worker.forget_last();
+        self.pending_layouts.clear();
+        self.lines.clear();
+        self.kept_lines = 1;
+        self.lines.push(SimpleText::new(
             tobj::MovableText::new(
                 Box::new(tobj::UniText::new(
                     text,
@@ -1441,7 +1731,8 @@ impl DrawableComponent for TextBox {
             sub_screen::stack_screen(ctx, &self.canvas);
 
             self.const_canvas.draw(ctx)?;
-            for d in &mut self.text {
+            let shown = self.shown_lines();
+            for d in &mut self.lines[shown] {
                 d.draw(ctx)?;
             }
 
@@ -1665,6 +1956,7 @@ pub struct ScenarioEvent {
     appearance_frame: TileBatchFrame,
     redraw_request: DrawRequest,
     se_handlers: [Option<SoundHandler>; 1],
+    voice_blip: BlipCounter,
 }
 
 impl ScenarioEvent {
@@ -1716,6 +2008,7 @@ impl ScenarioEvent {
             tachie: event_tachie,
             redraw_request: DrawRequest::InitDraw,
             se_handlers: [None],
+            voice_blip: BlipCounter::new(VoiceArchetype::Kosuzu),
         }
     }
 
@@ -1754,6 +2047,13 @@ impl ScenarioEvent {
         }
     }
 
+    ///
+    /// 今の場面の背景。背景を持たない場面ではNone
+    ///
+    pub fn current_background_texture(&self) -> Option<TextureID> {
+        self.scenario.ref_current_element().get_background_texture()
+    }
+
     pub fn update_event_background<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         // 現在のScenarioElementに背景がある場合、背景を変更
         // そうでない場合は、何もしない
@@ -1811,8 +2111,22 @@ impl ScenarioEvent {
             ScenarioElement::Text(scenario_text) => {
                 if self.scenario_box.get_text_box_status() == TextBoxStatus::UpdatingText {
                     // 表示する文字数を更新
+                    let before = scenario_text.current_iterator();
                     scenario_text.update_iterator();
 
+                    // 新しく出た文字に合わせて、話し手の声を鳴らす
+                    if self.voice_blip.get_archetype() != scenario_text.get_voice() {
+                        self.voice_blip = BlipCounter::new(scenario_text.get_voice());
+                    }
+                    let revealed =
+                        scenario_text.revealed_text(before, scenario_text.current_iterator());
+                    let mute = scenario_text
+                        .content_treatment(ctx.preferences.is_content_warning_enabled())
+                        .mute_sudden_se;
+                    if self.voice_blip.feed(&revealed) && !mute {
+                        ctx.play_voice_blip(self.voice_blip.get_archetype());
+                    }
+
                     // 何行目までのテキストが表示されたか？
                     let current_segment =
                         self.scenario_box.update_scenario_text(ctx, &scenario_text);
@@ -1877,6 +2191,17 @@ impl ScenarioEvent {
                 ScenarioBuiltinCommand::ScheduleStart(_) => {
                     self.status = ScenarioEventStatus::StartSchedule;
                 }
+                ScenarioBuiltinCommand::ColorGrade(data) => {
+                    color_grading::request(data.preset, data.duration);
+                    let next_id = data.next_id;
+
+                    self.scenario.update_current_page_index(next_id);
+                    if let ScenarioElement::Text(obj) = self.scenario.ref_current_element_mut() {
+                        obj.reset();
+                    }
+                    self.update_event_background(ctx);
+                    self.update_event_tachie(ctx, 0);
+                }
             },
 	    ScenarioElement::Switch(switch) => {
 		let next_id = match switch.get_opecode() {
@@ -1887,6 +2212,14 @@ impl ScenarioEvent {
 			    switch.get_no_branch()
 			}
 		    },
+		    "flag" => {
+			let flag = switch.get_flag().expect("Scenario Script BUG: flag switch without flag");
+			if ctx.take_save_data().diary.has_flag(flag) {
+			    switch.get_yes_branch()
+			} else {
+			    switch.get_no_branch()
+			}
+		    },
 		    _ => panic!("Scenario Script BUG"),
 		};
 
@@ -1969,6 +2302,11 @@ impl ScenarioEvent {
                 self.redraw_request = DrawRequest::Draw;
                 self.scenario_box.text_box.line_arrow.hide();
 
+                // 大きな音の注意書きがある場面では、文字送りの音を鳴らさない
+                let mute = scenario_text
+                    .content_treatment(ctx.preferences.is_content_warning_enabled())
+                    .mute_sudden_se;
+
                 // 最後まで到達していた場合、新しいScenarioElementに遷移し、テキストボックスをリセット
                 if scenario_text.iterator_finish() {
                     self.scenario.go_next_scenario_from_text_scenario();
@@ -1987,30 +2325,32 @@ impl ScenarioEvent {
                     // すでにchoice_boxがNoneなら、text_boxの行を進める動作
                     self.go_next_line();
 
-                    if self.se_handlers[0].is_none()
-                        || !ctx.is_se_playing(self.se_handlers[0].unwrap())
+                    if !mute
+                        && (self.se_handlers[0].is_none()
+                            || !ctx.is_se_playing(self.se_handlers[0].unwrap()))
                     {
-                        self.se_handlers[0] = Some(ctx.play_sound_as_se(SoundID::SeMessage, None));
+                        self.se_handlers[0] = ctx.play_sound_as_se(SoundID::SeMessage, None);
                     }
                 }
             }
             ScenarioElement::ChoiceSwitch(_) => {
-                if click_point.is_none() {
-                    return;
-                }
+                // クリックでなければ、キーやパッドで選んでいる選択肢に決める
+                if let Some(click_point) = click_point {
+                    let rpoint = self.canvas.relative_point(click_point);
 
-                let rpoint = self.canvas.relative_point(click_point.unwrap());
+                    self.scenario_box
+                        .choice_box
+                        .as_mut()
+                        .unwrap()
+                        .cursor_select(ctx, rpoint);
+                }
 
-                self.scenario_box
-                    .choice_box
-                    .as_mut()
-                    .unwrap()
-                    .cursor_select(ctx, rpoint);
                 let maybe_index = self.scenario_box.get_choice_selecting_index();
                 if maybe_index.is_none() {
                     return;
                 }
 
+                self.record_choice_decision(ctx, maybe_index.unwrap());
                 self.scenario
                     .go_next_scenario_from_choice_scenario(maybe_index.unwrap());
                 self.update_event_background(ctx);
@@ -2053,6 +2393,74 @@ impl ScenarioEvent {
         }
     }
 
+    ///
+    /// 選択肢は上下のキーで選び、Enterで決める。キーを受け取ったらtrueを返す
+    ///
+    pub fn choice_key_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        key: PaletteKey,
+        t: Clock,
+    ) -> bool {
+        match key {
+            PaletteKey::Up => self.move_choice_cursor(ctx, false),
+            PaletteKey::Down => self.move_choice_cursor(ctx, true),
+            PaletteKey::Enter => self.decide_choice(ctx, t),
+            _ => false,
+        }
+    }
+
+    ///
+    /// 選択肢を出していれば、選んでいる選択肢を隣へ動かしてtrueを返す
+    ///
+    fn move_choice_cursor<'a>(&mut self, ctx: &mut SuzuContext<'a>, forward: bool) -> bool {
+        match self.scenario.ref_current_element() {
+            ScenarioElement::ChoiceSwitch(_) => (),
+            _ => return false,
+        }
+
+        match self.scenario_box.choice_box.as_mut() {
+            Some(choice) => choice.move_selection(ctx, forward),
+            None => return false,
+        }
+
+        self.redraw_request = DrawRequest::Draw;
+        ctx.process_utility.redraw();
+        true
+    }
+
+    ///
+    /// 選択肢を選んでいれば、それに決めてtrueを返す
+    ///
+    fn decide_choice<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) -> bool {
+        match self.scenario.ref_current_element() {
+            ScenarioElement::ChoiceSwitch(_) => (),
+            _ => return false,
+        }
+
+        if self.scenario_box.get_choice_selecting_index().is_none() {
+            return false;
+        }
+
+        self.key_down_action1(ctx, None, t);
+        true
+    }
+
+    ///
+    /// 選んだ選択肢が決断なら記録する
+    ///
+    fn record_choice_decision<'a>(&mut self, ctx: &mut SuzuContext<'a>, index: usize) {
+        let decision = match self.scenario.ref_current_element() {
+            ScenarioElement::ChoiceSwitch(choice) => choice.get_decision(index).cloned(),
+            _ => None,
+        };
+
+        if let (Some((kind, key)), Some(save_data)) = (decision, ctx.savable_data.as_mut()) {
+            let today = save_data.date;
+            save_data.decision_log.record(kind, &key, today);
+        }
+    }
+
     pub fn get_scenario_id_for_saving(&self) -> i32 {
 	let len = self.scenario.element_id_stack.len();
 	self.scenario.element_id_stack[len - 2 as usize]
@@ -2155,6 +2563,7 @@ type = \"scenario\"
 id = 201
 next-id = 202
 background = \"SightBackground1\"
+tags = [\"loud\"]
 
 [scenario-group.tachie-data]
 right = \"KosuzuTachie1\"
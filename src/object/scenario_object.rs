@@ -205,11 +205,7 @@ impl SuzunaStatusMainPage {
             table_frame: table_frame,
             desc_text: desc_text,
             day_text: VerticalText::new(
-                format!(
-                    "{}月{}日",
-                    number_to_jk::number_to_jk(ctx.take_save_data().date.month as u64),
-                    number_to_jk::number_to_jk(ctx.take_save_data().date.day as u64),
-                ),
+                ctx.number_format().short_date(&ctx.take_save_data().date),
                 numeric::Point2f::new(590.0, 50.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1697,7 +1693,7 @@ impl WeekScheduleWindow {
                 .take_save_data()
                 .week_schedule
                 .get_first_day()
-                .diff_day(&ctx.take_save_data().date);
+                .diff_days(&ctx.take_save_data().date);
             let p = frame.get_grid_topleft(
                 numeric::Vector2u::new(date_diff.abs() as u32, 0),
                 numeric::Vector2f::new(0.0, 0.0),
@@ -1915,7 +1911,7 @@ impl StackMessagePassingWindow<WeekScheduleMessage> for WeekScheduleWindow {
                     .take_save_data()
                     .week_schedule
                     .get_first_day()
-                    .diff_day(&ctx.take_save_data().date);
+                    .diff_days(&ctx.take_save_data().date);
                 let p = self.frame.get_grid_topleft(
                     numeric::Vector2u::new(date_diff.abs() as u32, 0),
                     numeric::Vector2f::new(0.0, 0.0),
@@ -0,0 +1,455 @@
+use ggez::graphics as ggraphics;
+
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+use torifune::{mintp, numeric};
+
+use crate::core::book_fair::FairRequest;
+use crate::core::score_attack::*;
+use crate::core::{FontID, SuzuContext};
+use crate::object::book_fair_object::BookFairAction;
+use crate::object::util_object::*;
+
+use crate::core::number_format::NumberFormat;
+
+///
+/// # 腕試しの受付台
+/// 本市の受付台と同じ並び。上段に窓口の客の札、下段に題名札を置く
+///
+pub struct ScoreAttackCounter {
+    session: ScoreAttackSession,
+    customer_rects: Vec<numeric::Rect>,
+    title_rects: Vec<numeric::Rect>,
+    customer_texts: Vec<Vec<UniText>>,
+    title_texts: Vec<Option<UniText>>,
+    status_text: UniText,
+    timer_text: UniText,
+    name_font: FontInformation,
+    card_font: FontInformation,
+    number_format: NumberFormat,
+}
+
+impl ScoreAttackCounter {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, session: ScoreAttackSession) -> Self {
+        let slots = session.get_counter().len();
+        let slot_w = ((crate::core::WINDOW_SIZE_X as f32 - 60.0) / slots as f32).min(310.0);
+        let card_w = slot_w - 30.0;
+        let left = (crate::core::WINDOW_SIZE_X as f32 - (slot_w * slots as f32)) / 2.0 + 15.0;
+
+        let customer_rects = (0..slots)
+            .map(|index| numeric::Rect::new(left + (index as f32 * slot_w), 130.0, card_w, 170.0))
+            .collect();
+        let title_rects = (0..slots)
+            .map(|index| numeric::Rect::new(left + (index as f32 * slot_w), 430.0, card_w, 110.0))
+            .collect();
+
+        let status_font = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let status_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(left, 600.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            status_font.clone(),
+        );
+
+        let timer_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(crate::core::WINDOW_SIZE_X as f32 - 330.0, 600.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            status_font,
+        );
+
+        let mut counter = ScoreAttackCounter {
+            session: session,
+            customer_rects: customer_rects,
+            title_rects: title_rects,
+            customer_texts: Vec::new(),
+            title_texts: Vec::new(),
+            status_text: status_text,
+            timer_text: timer_text,
+            name_font: FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(30.0, 30.0),
+                ggraphics::Color::from_rgba_u32(0x222222ff),
+            ),
+            card_font: FontInformation::new(
+                ctx.resource.get_font(FontID::BitMap1),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+            ),
+            number_format: ctx.number_format(),
+        };
+
+        counter.update_cards();
+        counter.update_timer();
+        counter
+    }
+
+    ///
+    /// 受付の中身に合わせて札の文字を作り直す
+    ///
+    fn update_cards(&mut self) {
+        let name_font = self.name_font.clone();
+        let card_font = self.card_font.clone();
+
+        self.customer_texts = self
+            .session
+            .get_counter()
+            .iter()
+            .zip(self.customer_rects.iter())
+            .map(|(slot, rect)| match slot {
+                Some(request) => vec![
+                    UniText::new(
+                        format!("{}様", request.customer),
+                        numeric::Point2f::new(rect.x + 20.0, rect.y + 24.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        name_font.clone(),
+                    ),
+                    UniText::new(
+                        format!("所望　{}", request.book.name),
+                        numeric::Point2f::new(rect.x + 20.0, rect.y + 100.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        card_font.clone(),
+                    ),
+                ],
+                None => Vec::new(),
+            })
+            .collect();
+
+        self.title_texts = self
+            .session
+            .get_shelf()
+            .iter()
+            .zip(self.title_rects.iter())
+            .map(|(title, rect)| {
+                title.as_ref().map(|book| {
+                    UniText::new(
+                        book.name.clone(),
+                        numeric::Point2f::new(rect.x + 20.0, rect.y + 44.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        card_font.clone(),
+                    )
+                })
+            })
+            .collect();
+
+        self.status_text.replace_text(format!(
+            "受付{}人　{}点　{}連続　倍率{:.2}",
+            self.number_format.number(self.session.get_served() as u64),
+            self.session.get_score(),
+            self.number_format.number(self.session.get_combo() as u64),
+            self.session.current_multiplier()
+        ));
+    }
+
+    pub fn update_timer(&mut self) {
+        self.timer_text.replace_text(format!(
+            "残リ時間 {}秒",
+            self.session.get_remaining_time() / 60
+        ));
+    }
+
+    ///
+    /// 一フレーム進める。秒が変わったらtrue
+    ///
+    pub fn tick(&mut self) -> bool {
+        self.session.tick();
+        if self.session.get_remaining_time() % 60 == 0 {
+            self.update_timer();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.session.is_over()
+    }
+
+    pub fn finish(&self) -> ScoreAttackResult {
+        self.session.finish()
+    }
+
+    pub fn click_handler<F: FnOnce() -> FairRequest>(
+        &mut self,
+        point: numeric::Point2f,
+        refill: F,
+    ) -> BookFairAction {
+        if let Some(index) = self
+            .title_rects
+            .iter()
+            .position(|rect| rect.contains(mintp!(point)))
+        {
+            self.session.select_title(index);
+            return BookFairAction::TitleSelected;
+        }
+
+        if let Some(index) = self
+            .customer_rects
+            .iter()
+            .position(|rect| rect.contains(mintp!(point)))
+        {
+            if let Some(correct) = self.session.serve(index, refill) {
+                self.update_cards();
+                self.update_timer();
+                return BookFairAction::Served(correct);
+            }
+        }
+
+        BookFairAction::None
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        let selected = self.session.get_selected_title();
+
+        for rect in self.customer_rects.iter() {
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0xf6eee0ff),
+            )
+            .add_to_builder(&mut builder);
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::stroke(2.0),
+                ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        for (index, rect) in self.title_rects.iter().enumerate() {
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0xfcf0eaff),
+            )
+            .add_to_builder(&mut builder);
+
+            // 選んでいる題名札は赤く縁取る
+            let (width, color) = if selected == Some(index) {
+                (5.0, 0xaa2222ff)
+            } else {
+                (2.0, 0x5a4f3fff)
+            };
+            shape::Rectangle::new(
+                *rect,
+                ggraphics::DrawMode::stroke(width),
+                ggraphics::Color::from_rgba_u32(color),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        for texts in self.customer_texts.iter_mut() {
+            for text in texts.iter_mut() {
+                text.draw(ctx)?;
+            }
+        }
+
+        for text in self.title_texts.iter_mut().flatten() {
+            text.draw(ctx)?;
+        }
+
+        self.status_text.draw(ctx)?;
+        self.timer_text.draw(ctx)
+    }
+}
+
+///
+/// # 腕試しの結果と番付
+/// 番付に載る点数なら、先に頭文字を打たせる
+///
+/// ## initials
+/// 頭文字を打っている間はSome
+///
+/// ## rank
+/// 今回が載った順位
+///
+pub struct ScoreAttackResultPanel {
+    rect: numeric::Rect,
+    result: ScoreAttackResult,
+    initials: Option<InitialsInput>,
+    rank: Option<usize>,
+    lines: Vec<UniText>,
+    font_info: FontInformation,
+    number_format: NumberFormat,
+}
+
+impl ScoreAttackResultPanel {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        rect: numeric::Rect,
+        result: ScoreAttackResult,
+        leaderboard: &ScoreAttackLeaderboard,
+    ) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+        );
+
+        let initials = if leaderboard.qualifies(result.score) {
+            Some(InitialsInput::new())
+        } else {
+            None
+        };
+
+        let mut panel = ScoreAttackResultPanel {
+            rect: rect,
+            result: result,
+            initials: initials,
+            rank: None,
+            lines: Vec::new(),
+            font_info: font_info,
+            number_format: ctx.number_format(),
+        };
+        panel.update_lines(leaderboard);
+        panel
+    }
+
+    pub fn is_entering_initials(&self) -> bool {
+        self.initials.is_some()
+    }
+
+    ///
+    /// 頭文字の入力を受け取る。入力を受け付けたらtrue
+    ///
+    pub fn initials_input(&mut self, c: char, leaderboard: &ScoreAttackLeaderboard) -> bool {
+        let accepted = match self.initials.as_mut() {
+            Some(initials) => initials.push(c),
+            None => return false,
+        };
+
+        self.update_lines(leaderboard);
+        accepted
+    }
+
+    pub fn initials_backspace(&mut self, leaderboard: &ScoreAttackLeaderboard) {
+        if let Some(initials) = self.initials.as_mut() {
+            initials.pop();
+        }
+        self.update_lines(leaderboard);
+    }
+
+    ///
+    /// 打った頭文字で番付に載せる。一文字も打っていなければ何もしない
+    ///
+    pub fn commit_initials(&mut self, leaderboard: &mut ScoreAttackLeaderboard) -> bool {
+        let initials = match self.initials.as_ref() {
+            Some(initials) if !initials.is_empty() => initials.get().to_string(),
+            _ => return false,
+        };
+
+        self.rank = leaderboard.insert(LeaderboardEntry {
+            initials: initials,
+            score: self.result.score,
+            served: self.result.served,
+            best_combo: self.result.best_combo,
+        });
+        self.initials = None;
+        leaderboard.save();
+        self.update_lines(leaderboard);
+
+        true
+    }
+
+    fn update_lines(&mut self, leaderboard: &ScoreAttackLeaderboard) {
+        let mut texts = vec![
+            format!(
+                "受付　{}人　(一度デ渡シタ客　{}人)",
+                self.number_format.number(self.result.served as u64),
+                self.number_format.number(self.result.perfect as u64)
+            ),
+            format!(
+                "取リ違エ　{}回　最長連続　{}",
+                self.number_format.number(self.result.missed as u64),
+                self.number_format.number(self.result.best_combo as u64)
+            ),
+            format!("点数　{}点", self.result.score),
+            String::new(),
+        ];
+
+        match self.initials.as_ref() {
+            Some(initials) => {
+                texts.push("番付入リ！頭文字ヲ打ッテ下サイ".to_string());
+                texts.push(format!("{}　(Enterデ決定)", initials.display()));
+            }
+            None => {
+                texts.push("腕試シ番付".to_string());
+                for (rank, entry) in leaderboard.get_entries().iter().enumerate() {
+                    let mark = if self.rank == Some(rank) {
+                        "　←今回"
+                    } else {
+                        ""
+                    };
+                    texts.push(format!(
+                        "{}位　{}　{}点{}",
+                        self.number_format.ordinal(rank),
+                        entry.initials,
+                        entry.score,
+                        mark
+                    ));
+                }
+            }
+        }
+
+        let rect = self.rect;
+        let font_info = self.font_info.clone();
+        self.lines = texts
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| {
+                UniText::new(
+                    text,
+                    numeric::Point2f::new(rect.x + 40.0, rect.y + 24.0 + (index as f32 * 34.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )
+            })
+            .collect();
+    }
+
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xf6eee0ee),
+        )
+        .add_to_builder(&mut builder);
+        shape::Rectangle::new(
+            self.rect,
+            ggraphics::DrawMode::stroke(2.0),
+            ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+        )
+        .add_to_builder(&mut builder);
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+        for line in self.lines.iter_mut() {
+            line.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+}
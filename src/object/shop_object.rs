@@ -14,7 +14,14 @@ use torifune::impl_texture_object_for_wrapped;
 use torifune::numeric;
 use torifune::roundup2f;
 
+use crate::core::assistant::{AssistantRole, AssistantState};
+use crate::core::diary::Diary;
+use crate::core::game_system::CustomerPriority;
+use crate::core::paper_archive::PaperArchive;
 use crate::object::move_fn;
+use crate::object::simulation_ui::Meter;
+use crate::object::task_object::tt_main_component::{TextBalloon, TextBalloonPhraseType};
+use crate::object::tween;
 use crate::object::util_object::*;
 use crate::object::Clickable;
 use crate::scene::DelayEventList;
@@ -25,18 +32,23 @@ use crate::flush_delay_event;
 use crate::flush_delay_event_and_redraw_check;
 use crate::parse_toml_file;
 
-use number_to_jk::number_to_jk;
+use crate::core::number_format::NumberFormat;
 
 pub struct SelectBookWindowContents {
     table_frame: TableFrame,
     table_frame_init_position: numeric::Point2f,
     book_title_text: Vec<VerticalText>,
+    furigana_text: Vec<VerticalText>,
     billing_number_text: Vec<VerticalText>,
+    lent_count_text: Vec<VerticalText>,
     selecting_book_index: Vec<usize>,
     book_font: FontInformation,
+    furigana_font: FontInformation,
+    book_stats: book_management::BookPopularityStats,
     drwob_essential: DrawableObjectEssential,
     position: numeric::Point2f,
     select_limit: usize,
+    number_format: NumberFormat,
 }
 
 impl SelectBookWindowContents {
@@ -45,12 +57,14 @@ impl SelectBookWindowContents {
         font_info: FontInformation,
         window_rect: numeric::Rect,
         select_limit: usize,
+        book_stats: book_management::BookPopularityStats,
+        number_format: NumberFormat,
     ) -> SelectBookWindowContents {
         let mut table_frame = TableFrame::new(
             game_data,
             numeric::Point2f::new(0.0, 0.0),
             TileBatchTextureID::OldStyleFrame,
-            FrameData::new(vec![140.0, 400.0], vec![42.0; 128]),
+            FrameData::new(vec![140.0, 300.0, 100.0], vec![42.0; 128]),
             numeric::Vector2f::new(0.3, 0.3),
 	    ggraphics::FilterMode::Nearest,
             0,
@@ -61,26 +75,42 @@ impl SelectBookWindowContents {
             40.0,
         ));
 
+        let furigana_font = FontInformation::new(
+            game_data.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(
+                font_info.scale.x * FURIGANA_FONT_RATIO,
+                font_info.scale.y * FURIGANA_FONT_RATIO,
+            ),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
         SelectBookWindowContents {
             table_frame_init_position: table_frame.get_position(),
             table_frame: table_frame,
             book_title_text: Vec::new(),
+            furigana_text: Vec::new(),
             billing_number_text: Vec::new(),
+            lent_count_text: Vec::new(),
             selecting_book_index: Vec::new(),
             book_font: font_info,
+            furigana_font: furigana_font,
+            book_stats: book_stats,
             drwob_essential: DrawableObjectEssential::new(true, 0),
             position: numeric::Point2f::new(0.0, 0.0),
             select_limit: select_limit,
+            number_format: number_format,
         }
     }
 
     fn update_contents(&mut self, ctx: &mut ggez::Context, book_info: &Vec<BookInformation>) {
         self.book_title_text.clear();
+        self.furigana_text.clear();
         self.billing_number_text.clear();
+        self.lent_count_text.clear();
 
         for (index, info) in book_info.iter().enumerate() {
             let mut billing_number_text = VerticalText::new(
-                number_to_jk(info.billing_number as u64),
+                self.number_format.number(info.billing_number as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -97,6 +127,19 @@ impl SelectBookWindowContents {
                 self.book_font.clone(),
             );
 
+            let mut lent_count_text = VerticalText::new(
+                format!(
+                    "{}回",
+                    self.number_format
+                        .number(self.book_stats.get_total_lent(&info.name) as u64)
+                ),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                self.book_font.clone(),
+            );
+
             let table_pos_x = (self.table_frame.get_rows() - 1 - index) as u32;
 
             set_table_frame_cell_center!(
@@ -113,8 +156,25 @@ impl SelectBookWindowContents {
                 numeric::Vector2u::new(table_pos_x, 1)
             );
 
+            if let Some(reading) = info.get_reading() {
+                self.furigana_text.push(new_furigana_vtext(
+                    ctx,
+                    &mut book_title_text,
+                    reading,
+                    self.furigana_font,
+                ));
+            }
+
+            set_table_frame_cell_center!(
+                ctx,
+                self.table_frame,
+                lent_count_text,
+                numeric::Vector2u::new(table_pos_x, 2)
+            );
+
             self.book_title_text.push(book_title_text);
             self.billing_number_text.push(billing_number_text);
+            self.lent_count_text.push(lent_count_text);
         }
     }
 
@@ -159,9 +219,17 @@ impl DrawableComponent for SelectBookWindowContents {
                 vtext.draw(ctx)?;
             }
 
+            for vtext in &mut self.furigana_text {
+                vtext.draw(ctx)?;
+            }
+
             for vtext in &mut self.billing_number_text {
                 vtext.draw(ctx)?;
             }
+
+            for vtext in &mut self.lent_count_text {
+                vtext.draw(ctx)?;
+            }
         }
         Ok(())
     }
@@ -226,10 +294,18 @@ impl Scrollable for SelectBookWindowContents {
             vtext.move_diff(offset);
         }
 
+        for vtext in &mut self.furigana_text {
+            vtext.move_diff(offset);
+        }
+
         for vtext in &mut self.billing_number_text {
             vtext.move_diff(offset);
         }
 
+        for vtext in &mut self.lent_count_text {
+            vtext.move_diff(offset);
+        }
+
         self.position += offset;
     }
 }
@@ -270,7 +346,7 @@ impl SelectBookWindow {
             ctx.resource,
             numeric::Point2f::new(0.0, 0.0),
             TileBatchTextureID::OldStyleFrame,
-            FrameData::new(vec![140.0, 400.0], vec![42.0]),
+            FrameData::new(vec![140.0, 300.0, 100.0], vec![42.0]),
             numeric::Vector2f::new(0.3, 0.3),
 	    ggraphics::FilterMode::Nearest,
             0,
@@ -296,6 +372,15 @@ impl SelectBookWindow {
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
             0,
+            font_info.clone(),
+        );
+
+        let mut cell_desc3 = VerticalText::new(
+            "貸出".to_string(),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
             font_info,
         );
 
@@ -311,9 +396,21 @@ impl SelectBookWindow {
             cell_desc2,
             numeric::Vector2u::new(0, 1)
         );
+        set_table_frame_cell_center!(
+            ctx.context,
+            desc_frame,
+            cell_desc3,
+            numeric::Vector2u::new(0, 2)
+        );
 
-        let contents =
-            SelectBookWindowContents::new(ctx.resource, font_info, window_rect, select_limit);
+        let contents = SelectBookWindowContents::new(
+            ctx.resource,
+            font_info,
+            window_rect,
+            select_limit,
+            ctx.take_save_data().book_stats.clone(),
+            ctx.number_format(),
+        );
 
         let background = UniTexture::new(
             ctx.ref_texture(TextureID::TextBackground),
@@ -340,7 +437,7 @@ impl SelectBookWindow {
             ),
             appearance_frame: appr_frame,
             desc_frame: desc_frame,
-            cell_desc: vec![cell_desc1, cell_desc2],
+            cell_desc: vec![cell_desc1, cell_desc2, cell_desc3],
             contents: ScrollableWindow::new(
                 ctx.context,
                 numeric::Rect::new(36.0, 12.0, window_rect.w - 160.0, window_rect.h - 24.0),
@@ -760,11 +857,14 @@ pub struct SelectStoringBookWindow {
     storable_text: Vec<VerticalText>,
     billing_number_text: Vec<VerticalText>,
     book_title_text: Vec<VerticalText>,
+    furigana_text: Vec<VerticalText>,
     selecting_book_index: Vec<usize>,
     book_storable: Vec<bool>,
     background: UniTexture,
     book_font: FontInformation,
+    furigana_font: FontInformation,
     redraw_request: DrawRequest,
+    number_format: NumberFormat,
 }
 
 impl SelectStoringBookWindow {
@@ -774,6 +874,7 @@ impl SelectStoringBookWindow {
         title: &str,
         book_shelf_info: &BookShelfInformation,
         book_info: Vec<BookInformation>,
+        bestsellers: &Vec<String>,
     ) -> Self {
         let mut table_frame = TableFrame::new(
             ctx.resource,
@@ -810,6 +911,12 @@ impl SelectStoringBookWindow {
             ggraphics::Color::from_rgba_u32(0xff),
         );
 
+        let furigana_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(28.0 * FURIGANA_FONT_RATIO, 28.0 * FURIGANA_FONT_RATIO),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
         let mut storable_desc_text = VerticalText::new(
             "返却可否".to_string(),
             numeric::Point2f::new(0.0, 0.0),
@@ -889,14 +996,17 @@ impl SelectStoringBookWindow {
             billing_number_text: Vec::new(),
             storable_text: Vec::new(),
             book_title_text: Vec::new(),
+            furigana_text: Vec::new(),
             selecting_book_index: Vec::new(),
             book_storable: Vec::new(),
             background: background_texture,
             book_font: normal_font_info,
+            furigana_font: furigana_font_info,
             redraw_request: DrawRequest::InitDraw,
+            number_format: ctx.number_format(),
         };
 
-        window.update_contents(ctx.context, book_shelf_info, &book_info);
+        window.update_contents(ctx.context, book_shelf_info, &book_info, bestsellers);
 
         window
     }
@@ -906,22 +1016,33 @@ impl SelectStoringBookWindow {
         ctx: &mut ggez::Context,
         book_shelf_info: &BookShelfInformation,
         book_info: &Vec<BookInformation>,
+        bestsellers: &Vec<String>,
     ) {
         self.storable_text.clear();
         self.billing_number_text.clear();
         self.book_title_text.clear();
+        self.furigana_text.clear();
 
         // ここには、そのインデックスの本が配架可能かどうかがboolで入る
         self.book_storable.clear();
 
         for (index, info) in book_info.iter().enumerate() {
+            // 手前の棚には、人気の本を請求番号に関係なく置ける
+            let is_featured = book_shelf_info.is_front() && bestsellers.contains(&info.name);
             // 配架可能か？
-            let is_storable = book_shelf_info.contains_number(info.billing_number);
+            let is_storable = is_featured || book_shelf_info.contains_number(info.billing_number);
             // 配架可能状態をpush
             self.book_storable.push(is_storable);
 
             let mut storable_text = VerticalText::new(
-                (if is_storable { "可" } else { "不可" }).to_string(),
+                (if is_featured {
+                    "人気"
+                } else if is_storable {
+                    "可"
+                } else {
+                    "不可"
+                })
+                .to_string(),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -930,7 +1051,7 @@ impl SelectStoringBookWindow {
             );
 
             let mut number_text = VerticalText::new(
-                number_to_jk(info.billing_number as u64),
+                self.number_format.number(info.billing_number as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -970,6 +1091,15 @@ impl SelectStoringBookWindow {
                 numeric::Vector2u::new(table_pos_x, 2)
             );
 
+            if let Some(reading) = info.get_reading() {
+                self.furigana_text.push(new_furigana_vtext(
+                    ctx,
+                    &mut title_text,
+                    reading,
+                    self.furigana_font,
+                ));
+            }
+
             self.storable_text.push(storable_text);
             self.billing_number_text.push(number_text);
             self.book_title_text.push(title_text);
@@ -1028,6 +1158,10 @@ impl DrawableComponent for SelectStoringBookWindow {
                     vtext.draw(ctx)?;
                 }
 
+                for vtext in &mut self.furigana_text {
+                    vtext.draw(ctx)?;
+                }
+
                 for vtext in &mut self.billing_number_text {
                     vtext.draw(ctx)?;
                 }
@@ -1125,6 +1259,7 @@ pub struct SelectStoreBookUI {
     store_button: FramedButton,
     reset_select_button: FramedButton,
     book_shelf_info: BookShelfInformation,
+    bestsellers: Vec<String>,
     redraw_request: DrawRequest,
 }
 
@@ -1137,6 +1272,15 @@ impl SelectStoreBookUI {
     ) -> Self {
         shelving_book.sort_by(|a, b| a.billing_number.cmp(&b.billing_number));
 
+        let today = ctx.take_save_data().date;
+        let bestsellers: Vec<String> = ctx
+            .take_save_data()
+            .book_stats
+            .top_titles_in_week(&today, book_management::BESTSELLER_TITLES)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
 	let store_button = FramedButton::create_design_ok1(
 	    ctx,
 	    numeric::Point2f::new(1000.0, 200.0),
@@ -1160,12 +1304,14 @@ impl SelectStoreBookUI {
                 "配架中",
                 &book_shelf_info,
                 shelving_book.clone(),
+                &bestsellers,
             ),
             shelving_books: shelving_book,
             stored_books: Vec::new(),
             store_button: store_button,
             reset_select_button: reset_select_button,
             book_shelf_info: book_shelf_info,
+            bestsellers: bestsellers,
             redraw_request: DrawRequest::InitDraw,
         }
     }
@@ -1186,8 +1332,12 @@ impl SelectStoreBookUI {
 
     fn update_window(&mut self, ctx: &mut ggez::Context) {
         self.sort_book_info_greater();
-        self.select_book_window
-            .update_contents(ctx, &self.book_shelf_info, &self.shelving_books);
+        self.select_book_window.update_contents(
+            ctx,
+            &self.book_shelf_info,
+            &self.shelving_books,
+            &self.bestsellers,
+        );
         self.redraw_request = DrawRequest::Draw;
     }
 
@@ -1195,6 +1345,14 @@ impl SelectStoreBookUI {
         self.select_book_window.sort_selecting_index_less();
         for selecting_index in self.select_book_window.get_selecting_index().iter() {
             let returned = self.shelving_books.swap_remove(*selecting_index);
+
+            // 人気の本を手前の棚に並べると、よく借りられるので収入が増える
+            if self.book_shelf_info.is_front() && self.bestsellers.contains(&returned.name) {
+                ctx.take_save_data_mut()
+                    .task_result
+                    .add_total_money(book_management::FRONT_SHELF_BESTSELLER_BONUS);
+            }
+
             self.stored_books.push(returned);
             ctx.take_save_data_mut().award_data.shelving_count += 1;
         }
@@ -1408,7 +1566,7 @@ impl ShelvingDetailContents {
 
         for (index, book_info) in player_shelving.iter().enumerate() {
             let mut billing_number_text = VerticalText::new(
-                format!("{}", number_to_jk(book_info.billing_number as u64)),
+                ctx.number_format().number(book_info.billing_number as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1529,6 +1687,258 @@ impl DrawableComponent for ShelvingDetailContents {
     }
 }
 
+///
+/// # 手伝いに仕事を任せるページ
+///
+/// ## role_text
+/// 任せられる仕事の一覧。先頭は何も任せない「休ミ」
+///
+/// ## mistake_text
+/// まだ直していない手伝いの失敗。押すと直す
+///
+pub struct AssistantDetailContents {
+    canvas: MovableWrap<SubScreen>,
+    menu_rect: numeric::Rect,
+    title: VerticalText,
+    role_text: Vec<VerticalText>,
+    training_text: VerticalText,
+    mistake_text: Vec<VerticalText>,
+    background: UniTexture,
+}
+
+impl AssistantDetailContents {
+    ///
+    /// 一度に並べる失敗の数
+    ///
+    const MISTAKE_DISPLAY_MAX: usize = 7;
+
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, menu_rect: numeric::Rect, t: Clock) -> Self {
+        let title = VerticalText::new(
+            "手伝イ".to_string(),
+            numeric::Point2f::new(menu_rect.w - 110.0, 70.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(40.0, 40.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let training_text = VerticalText::new(
+            String::new(),
+            numeric::Point2f::new(menu_rect.w - 410.0, 70.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(24.0, 24.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let background = UniTexture::new(
+            ctx.ref_texture(TextureID::MenuArt2),
+            numeric::Point2f::new(menu_rect.w - 1366.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+
+        AssistantDetailContents {
+            canvas: MovableWrap::new(
+                Box::new(SubScreen::new(
+                    ctx.context,
+                    menu_rect,
+                    0,
+                    ggraphics::Color::from_rgba_u32(0xffffffff),
+                )),
+                None,
+                t,
+            ),
+            menu_rect: menu_rect,
+            title: title,
+            role_text: Vec::new(),
+            training_text: training_text,
+            mistake_text: Vec::new(),
+            background: background,
+        }
+    }
+
+    pub fn update_contents<'a>(&mut self, ctx: &mut SuzuContext<'a>, assistant: &AssistantState) {
+        let current_role = assistant.get_role();
+        let roles = std::iter::once(None).chain(AssistantRole::ALL.iter().copied().map(Some));
+
+        self.role_text = roles
+            .enumerate()
+            .map(|(index, role)| {
+                let color = if role == current_role {
+                    ggraphics::Color::from_rgba_u32(0xaa2222ff)
+                } else {
+                    ggraphics::Color::from_rgba_u32(0xff)
+                };
+
+                VerticalText::new(
+                    role.map_or("休ミ", |role| role.to_str()).to_string(),
+                    numeric::Point2f::new(self.menu_rect.w - 170.0 - (index as f32 * 50.0), 70.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    FontInformation::new(
+                        ctx.resource.get_font(FontID::JpFude1),
+                        numeric::Vector2f::new(32.0, 32.0),
+                        color,
+                    ),
+                )
+            })
+            .collect();
+
+        self.training_text = VerticalText::new(
+            format!(
+                "失敗　{}割",
+                ctx.number_format()
+                    .number((assistant.error_rate() * 10.0).round() as u64)
+            ),
+            numeric::Point2f::new(self.menu_rect.w - 410.0, 70.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(24.0, 24.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let mistake_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        self.mistake_text = assistant
+            .get_mistakes()
+            .iter()
+            .take(Self::MISTAKE_DISPLAY_MAX)
+            .enumerate()
+            .map(|(index, mistake)| {
+                VerticalText::new(
+                    mistake.description(),
+                    numeric::Point2f::new(self.menu_rect.w - 110.0 - (index as f32 * 45.0), 330.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    mistake_font.clone(),
+                )
+            })
+            .collect();
+    }
+
+    ///
+    /// 押された仕事を返す。「休ミ」が押されたときはSome(None)
+    ///
+    pub fn click_role<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> Option<Option<AssistantRole>> {
+        let rpoint = self.canvas.relative_point(point);
+
+        self.role_text
+            .iter()
+            .position(|text| text.contains(ctx.context, rpoint))
+            .map(|index| index.checked_sub(1).map(|index| AssistantRole::ALL[index]))
+    }
+
+    ///
+    /// 押された失敗が何番目かを返す
+    ///
+    pub fn click_mistake<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> Option<usize> {
+        let rpoint = self.canvas.relative_point(point);
+
+        self.mistake_text
+            .iter()
+            .position(|text| text.contains(ctx.context, rpoint))
+    }
+
+    pub fn slide_appear(&mut self, slide_position: numeric::Point2f, t: Clock) {
+        self.canvas
+            .override_move_func(move_fn::devide_distance(slide_position, 0.5), t);
+    }
+
+    pub fn slide_hide(&mut self, t: Clock) {
+        self.canvas.override_move_func(
+            move_fn::devide_distance(numeric::Point2f::new(-self.menu_rect.w, 0.0), 0.2),
+            t,
+        );
+    }
+
+    ///
+    /// # 再描画要求有り
+    ///
+    pub fn move_and_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if !self.canvas.is_stop() {
+            self.canvas.move_with_func(t);
+            ctx.process_utility.redraw();
+        }
+    }
+}
+
+impl DrawableComponent for AssistantDetailContents {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.background.draw(ctx)?;
+            self.title.draw(ctx)?;
+            self.training_text.draw(ctx)?;
+
+            for vtext in &mut self.role_text {
+                vtext.draw(ctx)?;
+            }
+
+            for vtext in &mut self.mistake_text {
+                vtext.draw(ctx)?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    #[inline(always)]
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    #[inline(always)]
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    #[inline(always)]
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    #[inline(always)]
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
 ///
 /// メニューに表示するやつ
 ///
@@ -1542,6 +1952,8 @@ pub struct ShopMenuContents {
     not_shelved_num: VerticalText,
     kosuzu_level: VerticalText,
     kosuzu_level_num: VerticalText,
+    money: VerticalText,
+    money_num: VerticalText,
     drwob_essential: DrawableObjectEssential,
 }
 
@@ -1564,8 +1976,8 @@ impl ShopMenuContents {
             day_text: VerticalText::new(
                 format!(
                     "日付　{}月 {}日",
-                    number_to_jk(date.month as u64),
-                    number_to_jk(date.day as u64)
+                    ctx.number_format().number(date.month as u64),
+                    ctx.number_format().number(date.day as u64)
                 ),
                 numeric::Point2f::new(350.0, 70.0),
                 numeric::Vector2f::new(1.0, 1.0),
@@ -1582,10 +1994,8 @@ impl ShopMenuContents {
                 normal_scale_font,
             ),
             copy_request_num: VerticalText::new(
-                format!(
-                    "{}人",
-                    number_to_jk(ctx.take_save_data().award_data.customer_count as u64)
-                ),
+                ctx.number_format()
+                    .count(ctx.take_save_data().award_data.customer_count as u64, "人"),
                 numeric::Point2f::new(230.0, 170.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1601,7 +2011,7 @@ impl ShopMenuContents {
                 normal_scale_font,
             ),
             wait_for_return_num: VerticalText::new(
-                format!("{}冊", number_to_jk(0)),
+                ctx.number_format().count(0, "冊"),
                 numeric::Point2f::new(130.0, 170.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1617,7 +2027,7 @@ impl ShopMenuContents {
                 normal_scale_font,
             ),
             not_shelved_num: VerticalText::new(
-                format!("{}冊", number_to_jk(0)),
+                ctx.number_format().count(0, "冊"),
                 numeric::Point2f::new(30.0, 170.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1635,7 +2045,7 @@ impl ShopMenuContents {
             kosuzu_level_num: VerticalText::new(
                 format!(
                     "{}",
-                    number_to_jk(
+                    ctx.number_format().number(
                         ctx.take_save_data()
                             .suzunaan_status
                             .get_current_reputation() as u64
@@ -1647,10 +2057,51 @@ impl ShopMenuContents {
                 0,
                 large_scale_font,
             ),
+            money: VerticalText::new(
+                format!("所持金"),
+                numeric::Point2f::new(175.0, 370.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                normal_scale_font,
+            ),
+            money_num: VerticalText::new(
+                ctx.number_format()
+                    .money(ctx.take_save_data().task_result.total_money as u64),
+                numeric::Point2f::new(130.0, 470.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                large_scale_font,
+            ),
             drwob_essential: DrawableObjectEssential::new(true, 0),
         }
     }
 
+    ///
+    /// メニューの幅に合わせて各項目の位置を再計算する
+    /// 各項目の基準位置は、幅450pxのときのもの
+    ///
+    pub fn relayout(&mut self, width: f32) {
+        let k = width / SHOP_MENU_DEFAULT_WIDTH;
+
+        for (vtext, base) in vec![
+            (&mut self.day_text, numeric::Point2f::new(350.0, 70.0)),
+            (&mut self.copy_request, numeric::Point2f::new(275.0, 70.0)),
+            (&mut self.copy_request_num, numeric::Point2f::new(230.0, 170.0)),
+            (&mut self.wait_for_return, numeric::Point2f::new(175.0, 70.0)),
+            (&mut self.wait_for_return_num, numeric::Point2f::new(130.0, 170.0)),
+            (&mut self.not_shelved, numeric::Point2f::new(75.0, 70.0)),
+            (&mut self.not_shelved_num, numeric::Point2f::new(30.0, 170.0)),
+            (&mut self.kosuzu_level, numeric::Point2f::new(275.0, 370.0)),
+            (&mut self.kosuzu_level_num, numeric::Point2f::new(230.0, 470.0)),
+            (&mut self.money, numeric::Point2f::new(175.0, 370.0)),
+            (&mut self.money_num, numeric::Point2f::new(130.0, 470.0)),
+        ] {
+            vtext.set_position(numeric::Point2f::new(base.x * k, base.y));
+        }
+    }
+
     pub fn update_contents<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         let _normal_scale_font = FontInformation::new(
             ctx.resource.get_font(FontID::JpFude1),
@@ -1670,8 +2121,8 @@ impl ShopMenuContents {
         self.day_text = VerticalText::new(
             format!(
                 "日付　{}月 {}日",
-                number_to_jk(date.month as u64),
-                number_to_jk(date.day as u64)
+                ctx.number_format().number(date.month as u64),
+                ctx.number_format().number(date.day as u64)
             ),
             numeric::Point2f::new(350.0, 70.0),
             numeric::Vector2f::new(1.0, 1.0),
@@ -1681,10 +2132,8 @@ impl ShopMenuContents {
         );
 
         self.wait_for_return_num = VerticalText::new(
-            format!(
-                "{}冊",
-                number_to_jk(task_result.borrowing_books.len() as u64)
-            ),
+            ctx.number_format()
+                .count(task_result.borrowing_books.len() as u64, "冊"),
             numeric::Point2f::new(130.0, 170.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -1693,10 +2142,8 @@ impl ShopMenuContents {
         );
 
         self.not_shelved_num = VerticalText::new(
-            format!(
-                "{}冊",
-                number_to_jk(task_result.not_shelved_books.len() as u64)
-            ),
+            ctx.number_format()
+                .count(task_result.not_shelved_books.len() as u64, "冊"),
             numeric::Point2f::new(30.0, 170.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -1705,13 +2152,23 @@ impl ShopMenuContents {
         );
 
         self.kosuzu_level_num = VerticalText::new(
-            format!("{}", number_to_jk((task_result.done_works / 3) as u64)),
+            ctx.number_format()
+                .number((task_result.done_works / 3) as u64),
             numeric::Point2f::new(230.0, 470.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
             0,
             large_scale_font,
         );
+
+        self.money_num = VerticalText::new(
+            ctx.number_format().money(task_result.total_money as u64),
+            numeric::Point2f::new(130.0, 470.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            large_scale_font,
+        );
     }
 }
 
@@ -1731,6 +2188,9 @@ impl DrawableComponent for ShopMenuContents {
 
             self.kosuzu_level.draw(ctx).unwrap();
             self.kosuzu_level_num.draw(ctx).unwrap();
+
+            self.money.draw(ctx).unwrap();
+            self.money_num.draw(ctx).unwrap();
         }
 
         Ok(())
@@ -1762,16 +2222,36 @@ impl DrawableComponent for ShopMenuContents {
     }
 }
 
+pub const SHOP_MENU_DEFAULT_WIDTH: f32 = 450.0;
+pub const SHOP_MENU_MIN_WIDTH: f32 = 300.0;
+pub const SHOP_MENU_MAX_WIDTH: f32 = 700.0;
+const SHOP_MENU_TAB_WIDTH: f32 = 28.0;
+const SHOP_MENU_HANDLE_WIDTH: f32 = 12.0;
+
 pub struct ShopMenu {
     canvas: MovableWrap<SubScreen>,
     menu_contents: ShopMenuContents,
     background: UniTexture,
     menu_canvas_size: numeric::Vector2f,
     now_appear: bool,
+    resizing: bool,
+    collapsed: bool,
+    expanded_width: f32,
 }
 
 impl ShopMenu {
     pub fn new<'a>(ctx: &mut SuzuContext<'a>, size: numeric::Vector2f, t: Clock) -> Self {
+        let expanded_width = size.x.max(SHOP_MENU_MIN_WIDTH).min(SHOP_MENU_MAX_WIDTH);
+        let collapsed = ctx.config.is_shop_menu_collapsed();
+        let size = numeric::Vector2f::new(
+            if collapsed {
+                SHOP_MENU_TAB_WIDTH
+            } else {
+                expanded_width
+            },
+            size.y,
+        );
+
         let mut canvas = SubScreen::new(
             ctx.context,
             numeric::Rect::new(-size.x, 0.0, size.x, size.y),
@@ -1780,6 +2260,12 @@ impl ShopMenu {
         );
         canvas.hide();
 
+        let mut menu_contents = ShopMenuContents::new(ctx);
+        menu_contents.relayout(expanded_width);
+        if collapsed {
+            menu_contents.hide();
+        }
+
         ShopMenu {
             canvas: MovableWrap::new(Box::new(canvas), None, t),
             background: UniTexture::new(
@@ -1789,23 +2275,154 @@ impl ShopMenu {
                 0.0,
                 0,
             ),
-            menu_contents: ShopMenuContents::new(ctx),
+            menu_contents: menu_contents,
             menu_canvas_size: size,
             now_appear: false,
+            resizing: false,
+            collapsed: collapsed,
+            expanded_width: expanded_width,
+        }
+    }
+
+    pub fn get_width(&self) -> f32 {
+        self.menu_canvas_size.x
+    }
+
+    ///
+    /// メニューの幅を変更する。canvasは作り直し、中身は再配置する
+    ///
+    fn change_width<'a>(&mut self, ctx: &mut SuzuContext<'a>, width: f32, t: Clock) {
+        if self.menu_canvas_size.x == width {
+            return;
+        }
+
+        self.menu_canvas_size.x = width;
+
+        let mut canvas = SubScreen::new(
+            ctx.context,
+            numeric::Rect::new(
+                if self.now_appear { 0.0 } else { -width },
+                0.0,
+                width,
+                self.menu_canvas_size.y,
+            ),
+            self.canvas.get_drawing_depth(),
+            ggraphics::Color::from_rgba_u32(0xffffffff),
+        );
+        if !self.now_appear {
+            canvas.hide();
+        }
+        self.canvas = MovableWrap::new(Box::new(canvas), None, t);
+
+        self.background
+            .set_position(numeric::Point2f::new(width - 1366.0, 0.0));
+
+        if !self.collapsed {
+            self.menu_contents.relayout(width);
+        }
+
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 右端のつまみ（折りたたみ時はタブ全体）にpointが含まれるか
+    ///
+    pub fn contains_resize_handle(&self, point: numeric::Point2f) -> bool {
+        if !self.now_appear || !self.canvas.is_stop() {
+            return false;
+        }
+
+        let width = self.menu_canvas_size.x;
+        let handle_width = if self.collapsed {
+            SHOP_MENU_TAB_WIDTH
+        } else {
+            SHOP_MENU_HANDLE_WIDTH
+        };
+
+        point.x >= width - handle_width && point.x <= width && point.y <= self.menu_canvas_size.y
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.resizing
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    pub fn start_resize(&mut self) {
+        if !self.collapsed {
+            self.resizing = true;
+        }
+    }
+
+    pub fn resize_dragging<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f, t: Clock) {
+        if !self.resizing {
+            return;
+        }
+
+        // 最小幅より大きく縮めようとした場合は、タブに折りたたむ
+        if point.x < SHOP_MENU_MIN_WIDTH / 2.0 {
+            self.resizing = false;
+            self.collapse(ctx, t);
+            return;
         }
+
+        let width = point.x.max(SHOP_MENU_MIN_WIDTH).min(SHOP_MENU_MAX_WIDTH);
+        self.expanded_width = width;
+        self.change_width(ctx, width, t);
+    }
+
+    pub fn finish_resize<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.resizing = false;
+        self.save_layout_config(ctx);
+    }
+
+    pub fn collapse<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.collapsed = true;
+        self.menu_contents.hide();
+        self.change_width(ctx, SHOP_MENU_TAB_WIDTH, t);
+        self.save_layout_config(ctx);
+    }
+
+    pub fn expand<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.collapsed = false;
+        self.menu_contents.appear();
+        let width = self.expanded_width;
+        self.change_width(ctx, width, t);
+        self.menu_contents.relayout(width);
+        self.save_layout_config(ctx);
+    }
+
+    fn save_layout_config<'a>(&self, ctx: &mut SuzuContext<'a>) {
+        ctx.config.set_shop_menu_width(self.expanded_width);
+        ctx.config.set_shop_menu_collapsed(self.collapsed);
+        ctx.config.save_config();
     }
 
     pub fn slide_toggle(&mut self, t: Clock) {
+        let current_pos = self.canvas.get_position();
+
         if self.now_appear {
             self.canvas.override_move_func(
-                move_fn::devide_distance(numeric::Point2f::new(-self.menu_canvas_size.x, 0.0), 0.5),
+                move_fn::tween_move(
+                    current_pos,
+                    numeric::Point2f::new(-self.menu_canvas_size.x, 0.0),
+                    15,
+                    tween::Easing::EaseOutCubic,
+                ),
                 t,
             );
             self.now_appear = false;
             self.hide();
         } else {
             self.canvas.override_move_func(
-                move_fn::devide_distance(numeric::Point2f::new(0.0, 0.0), 0.5),
+                move_fn::tween_move(
+                    current_pos,
+                    numeric::Point2f::new(0.0, 0.0),
+                    15,
+                    tween::Easing::EaseOutCubic,
+                ),
                 t,
             );
             self.now_appear = true;
@@ -1832,37 +2449,412 @@ impl ShopMenu {
     }
 }
 
-impl DrawableComponent for ShopMenu {
+impl DrawableComponent for ShopMenu {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.background.draw(ctx)?;
+            self.menu_contents.draw(ctx).unwrap();
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+///
+/// # 小鈴の日記を読み返すページ
+/// 新しい日記から、右から左へ縦書きで並べる
+///
+/// ## entry_text
+/// 日付の列と本文の列。本文は長ければ折り返す
+///
+pub struct DiaryDetailContents {
+    canvas: MovableWrap<SubScreen>,
+    menu_rect: numeric::Rect,
+    title: VerticalText,
+    entry_text: Vec<VerticalText>,
+    background: UniTexture,
+}
+
+impl DiaryDetailContents {
+    ///
+    /// 本文の一列に並べる文字数
+    ///
+    const BODY_COLUMN_CHARS: usize = 22;
+
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, menu_rect: numeric::Rect, t: Clock) -> Self {
+        let title = VerticalText::new(
+            "日記".to_string(),
+            numeric::Point2f::new(menu_rect.w - 110.0, 70.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(40.0, 40.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let background = UniTexture::new(
+            ctx.ref_texture(TextureID::MenuArt2),
+            numeric::Point2f::new(menu_rect.w - 1366.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+
+        DiaryDetailContents {
+            canvas: MovableWrap::new(
+                Box::new(SubScreen::new(
+                    ctx.context,
+                    menu_rect,
+                    0,
+                    ggraphics::Color::from_rgba_u32(0xffffffff),
+                )),
+                None,
+                t,
+            ),
+            menu_rect: menu_rect,
+            title: title,
+            entry_text: Vec::new(),
+            background: background,
+        }
+    }
+
+    pub fn update_contents<'a>(&mut self, ctx: &mut SuzuContext<'a>, diary: &Diary) {
+        let date_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(22.0, 22.0),
+            ggraphics::Color::from_rgba_u32(0xaa2222ff),
+        );
+        let body_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        self.entry_text.clear();
+
+        if diary.is_empty() {
+            self.entry_text.push(VerticalText::new(
+                "マダ何モ書イテイナイ".to_string(),
+                numeric::Point2f::new(self.menu_rect.w - 170.0, 70.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                body_font,
+            ));
+            return;
+        }
+
+        let mut x = self.menu_rect.w - 170.0;
+
+        'entries: for entry in diary.iter_recent() {
+            let body: Vec<char> = entry.body_text().chars().collect();
+            let mut columns = vec![(ctx.number_format().short_date(&entry.date), date_font)];
+            for chunk in body.chunks(Self::BODY_COLUMN_CHARS) {
+                columns.push((chunk.iter().collect(), body_font));
+            }
+
+            for (text, font) in columns {
+                if x < 20.0 {
+                    break 'entries;
+                }
+
+                self.entry_text.push(VerticalText::new(
+                    text,
+                    numeric::Point2f::new(x, 70.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font,
+                ));
+                x -= 36.0;
+            }
+
+            x -= 20.0;
+        }
+    }
+
+    pub fn slide_appear(&mut self, slide_position: numeric::Point2f, t: Clock) {
+        self.canvas
+            .override_move_func(move_fn::devide_distance(slide_position, 0.5), t);
+    }
+
+    pub fn slide_hide(&mut self, t: Clock) {
+        self.canvas.override_move_func(
+            move_fn::devide_distance(numeric::Point2f::new(-self.menu_rect.w, 0.0), 0.2),
+            t,
+        );
+    }
+
+    ///
+    /// # 再描画要求有り
+    ///
+    pub fn move_and_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if !self.canvas.is_stop() {
+            self.canvas.move_with_func(t);
+            ctx.process_utility.redraw();
+        }
+    }
+}
+
+impl DrawableComponent for DiaryDetailContents {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.background.draw(ctx)?;
+            self.title.draw(ctx)?;
+
+            for vtext in &mut self.entry_text {
+                vtext.draw(ctx)?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    #[inline(always)]
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    #[inline(always)]
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    #[inline(always)]
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    #[inline(always)]
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+///
+/// # 書類箱に仕舞った手紙の控えを読み返すページ
+/// 仕舞った日ごとに、新しいものから右から左へ縦書きで並べる
+///
+/// ## entry_text
+/// 日付の列と、一通ごとの用件の列
+///
+pub struct ArchiveDetailContents {
+    canvas: MovableWrap<SubScreen>,
+    menu_rect: numeric::Rect,
+    title: VerticalText,
+    entry_text: Vec<VerticalText>,
+    background: UniTexture,
+}
+
+impl ArchiveDetailContents {
+    ///
+    /// 用件の一列に並べる文字数
+    ///
+    const SUMMARY_COLUMN_CHARS: usize = 22;
+
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, menu_rect: numeric::Rect, t: Clock) -> Self {
+        let title = VerticalText::new(
+            "書類箱".to_string(),
+            numeric::Point2f::new(menu_rect.w - 110.0, 70.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(40.0, 40.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let background = UniTexture::new(
+            ctx.ref_texture(TextureID::MenuArt2),
+            numeric::Point2f::new(menu_rect.w - 1366.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+
+        ArchiveDetailContents {
+            canvas: MovableWrap::new(
+                Box::new(SubScreen::new(
+                    ctx.context,
+                    menu_rect,
+                    0,
+                    ggraphics::Color::from_rgba_u32(0xffffffff),
+                )),
+                None,
+                t,
+            ),
+            menu_rect: menu_rect,
+            title: title,
+            entry_text: Vec::new(),
+            background: background,
+        }
+    }
+
+    pub fn update_contents<'a>(&mut self, ctx: &mut SuzuContext<'a>, archive: &PaperArchive) {
+        let date_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(22.0, 22.0),
+            ggraphics::Color::from_rgba_u32(0xaa2222ff),
+        );
+        let summary_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(26.0, 26.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        self.entry_text.clear();
+
+        if archive.is_empty() {
+            self.entry_text.push(VerticalText::new(
+                "マダ何モ仕舞ッテイナイ".to_string(),
+                numeric::Point2f::new(self.menu_rect.w - 170.0, 70.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                summary_font,
+            ));
+            return;
+        }
+
+        let mut x = self.menu_rect.w - 170.0;
+        let mut last_date = None;
+
+        'entries: for entry in archive.iter_recent() {
+            let mut columns = Vec::new();
+
+            // 日が変わったところで、日付の列を挟む
+            if last_date != Some(entry.date) {
+                if last_date.is_some() {
+                    x -= 20.0;
+                }
+                columns.push((ctx.number_format().short_date(&entry.date), date_font));
+                last_date = Some(entry.date);
+            }
+
+            let summary: Vec<char> = entry.summary_text().chars().collect();
+            for chunk in summary.chunks(Self::SUMMARY_COLUMN_CHARS) {
+                columns.push((chunk.iter().collect(), summary_font));
+            }
+
+            for (text, font) in columns {
+                if x < 20.0 {
+                    break 'entries;
+                }
+
+                self.entry_text.push(VerticalText::new(
+                    text,
+                    numeric::Point2f::new(x, 70.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font,
+                ));
+                x -= 36.0;
+            }
+        }
+    }
+
+    pub fn slide_appear(&mut self, slide_position: numeric::Point2f, t: Clock) {
+        self.canvas
+            .override_move_func(move_fn::devide_distance(slide_position, 0.5), t);
+    }
+
+    pub fn slide_hide(&mut self, t: Clock) {
+        self.canvas.override_move_func(
+            move_fn::devide_distance(numeric::Point2f::new(-self.menu_rect.w, 0.0), 0.2),
+            t,
+        );
+    }
+
+    ///
+    /// # 再描画要求有り
+    ///
+    pub fn move_and_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if !self.canvas.is_stop() {
+            self.canvas.move_with_func(t);
+            ctx.process_utility.redraw();
+        }
+    }
+}
+
+impl DrawableComponent for ArchiveDetailContents {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
         if self.is_visible() {
             sub_screen::stack_screen(ctx, &self.canvas);
 
             self.background.draw(ctx)?;
-            self.menu_contents.draw(ctx).unwrap();
+            self.title.draw(ctx)?;
+
+            for vtext in &mut self.entry_text {
+                vtext.draw(ctx)?;
+            }
 
             sub_screen::pop_screen(ctx);
             self.canvas.draw(ctx)?;
         }
-
         Ok(())
     }
 
+    #[inline(always)]
     fn hide(&mut self) {
         self.canvas.hide();
     }
 
+    #[inline(always)]
     fn appear(&mut self) {
         self.canvas.appear();
     }
 
+    #[inline(always)]
     fn is_visible(&self) -> bool {
         self.canvas.is_visible()
     }
 
+    #[inline(always)]
     fn set_drawing_depth(&mut self, depth: i8) {
         self.canvas.set_drawing_depth(depth);
     }
 
+    #[inline(always)]
     fn get_drawing_depth(&self) -> i8 {
         self.canvas.get_drawing_depth()
     }
@@ -1872,11 +2864,17 @@ impl DrawableComponent for ShopMenu {
 pub enum ShopDetailMenuSymbol {
     ShelvingBooks = 0,
     SuzunaMap,
+    Assistant,
+    Diary,
+    Archive,
     None,
 }
 
 pub struct ShopDetailMenuContents {
     shelving_info: ShelvingDetailContents,
+    assistant_info: AssistantDetailContents,
+    diary_info: DiaryDetailContents,
+    archive_info: ArchiveDetailContents,
     drwob_essential: DrawableObjectEssential,
     contents_switch: ShopDetailMenuSymbol,
     appear_position: numeric::Point2f,
@@ -1892,6 +2890,9 @@ impl ShopDetailMenuContents {
     ) -> Self {
         ShopDetailMenuContents {
             shelving_info: ShelvingDetailContents::new(ctx, shelving_rect, t),
+            assistant_info: AssistantDetailContents::new(ctx, shelving_rect, t),
+            diary_info: DiaryDetailContents::new(ctx, shelving_rect, t),
+            archive_info: ArchiveDetailContents::new(ctx, shelving_rect, t),
             drwob_essential: DrawableObjectEssential::new(false, 0),
             contents_switch: ShopDetailMenuSymbol::None,
             appear_position: appear_position,
@@ -1905,27 +2906,74 @@ impl ShopDetailMenuContents {
         player_shelving: &Vec<BookInformation>,
     ) {
         self.shelving_info.update_contents(ctx, player_shelving);
+
+        let assistant = ctx.take_save_data().assistant.clone();
+        self.assistant_info.update_contents(ctx, &assistant);
+
+        let diary = ctx.take_save_data().diary.clone();
+        self.diary_info.update_contents(ctx, &diary);
+
+        let archive = ctx.take_save_data().paper_archive.clone();
+        self.archive_info.update_contents(ctx, &archive);
     }
 
     pub fn detail_menu_is_open(&self) -> bool {
         self.now_appear
     }
 
+    pub fn is_showing(&self, contents: ShopDetailMenuSymbol) -> bool {
+        self.now_appear && self.contents_switch == contents
+    }
+
     pub fn hide_toggle(&mut self, t: Clock) {
         self.now_appear = false;
         self.shelving_info.slide_hide(t);
+        self.assistant_info.slide_hide(t);
+        self.diary_info.slide_hide(t);
+        self.archive_info.slide_hide(t);
         self.hide();
     }
 
+    ///
+    /// contents_switchのページを出し、他のページは引っ込める
+    ///
     pub fn appear_toggle(&mut self, t: Clock) {
         self.now_appear = true;
-        self.shelving_info.slide_appear(self.appear_position, t);
+        match self.contents_switch {
+            ShopDetailMenuSymbol::Assistant => {
+                self.shelving_info.slide_hide(t);
+                self.diary_info.slide_hide(t);
+                self.archive_info.slide_hide(t);
+                self.assistant_info.slide_appear(self.appear_position, t);
+            }
+            ShopDetailMenuSymbol::Diary => {
+                self.shelving_info.slide_hide(t);
+                self.assistant_info.slide_hide(t);
+                self.archive_info.slide_hide(t);
+                self.diary_info.slide_appear(self.appear_position, t);
+            }
+            ShopDetailMenuSymbol::Archive => {
+                self.shelving_info.slide_hide(t);
+                self.assistant_info.slide_hide(t);
+                self.diary_info.slide_hide(t);
+                self.archive_info.slide_appear(self.appear_position, t);
+            }
+            _ => {
+                self.assistant_info.slide_hide(t);
+                self.diary_info.slide_hide(t);
+                self.archive_info.slide_hide(t);
+                self.shelving_info.slide_appear(self.appear_position, t);
+            }
+        }
         self.appear();
     }
 
     pub fn slide_toggle(&mut self, t: Clock) {
         match self.contents_switch {
-            ShopDetailMenuSymbol::ShelvingBooks => {
+            ShopDetailMenuSymbol::ShelvingBooks
+            | ShopDetailMenuSymbol::Assistant
+            | ShopDetailMenuSymbol::Diary
+            | ShopDetailMenuSymbol::Archive => {
                 if self.now_appear {
                     self.hide_toggle(t);
                 } else {
@@ -1948,6 +2996,9 @@ impl ShopDetailMenuContents {
     ///
     pub fn move_and_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         self.shelving_info.move_and_effect(ctx, t);
+        self.assistant_info.move_and_effect(ctx, t);
+        self.diary_info.move_and_effect(ctx, t);
+        self.archive_info.move_and_effect(ctx, t);
     }
 }
 
@@ -1955,6 +3006,9 @@ impl DrawableComponent for ShopDetailMenuContents {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
         if self.is_visible() {
             self.shelving_info.draw(ctx)?;
+            self.assistant_info.draw(ctx)?;
+            self.diary_info.draw(ctx)?;
+            self.archive_info.draw(ctx)?;
         }
 
         Ok(())
@@ -2166,6 +3220,7 @@ impl Clickable for SimpleBookListViewer {
 pub struct ShopMenuMaster {
     first_menu: ShopMenu,
     detail_menu: ShopDetailMenuContents,
+    assistant_unlocked: bool,
     canvas: SubScreen,
 }
 
@@ -2175,14 +3230,18 @@ impl ShopMenuMaster {
         first_menu_size: numeric::Vector2f,
         t: Clock,
     ) -> Self {
+        let first_menu = ShopMenu::new(ctx, first_menu_size, t);
+        let first_menu_width = first_menu.get_width();
+
         ShopMenuMaster {
-            first_menu: ShopMenu::new(ctx, first_menu_size, t),
+            first_menu: first_menu,
             detail_menu: ShopDetailMenuContents::new(
                 ctx,
-                numeric::Point2f::new(first_menu_size.x, 0.0),
+                numeric::Point2f::new(first_menu_width, 0.0),
                 numeric::Rect::new(-450.0, 0.0, 450.0, 768.0),
                 t,
             ),
+            assistant_unlocked: ctx.take_save_data().assistant.is_unlocked(),
             canvas: SubScreen::new(
                 ctx.context,
                 numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
@@ -2203,6 +3262,21 @@ impl ShopMenuMaster {
                 .shelving_info
                 .canvas
                 .contains(ctx.context, point)
+            || self
+                .detail_menu
+                .assistant_info
+                .canvas
+                .contains(ctx.context, point)
+            || self
+                .detail_menu
+                .diary_info
+                .canvas
+                .contains(ctx.context, point)
+            || self
+                .detail_menu
+                .archive_info
+                .canvas
+                .contains(ctx.context, point)
     }
 
     pub fn update_contents(
@@ -2212,6 +3286,40 @@ impl ShopMenuMaster {
     ) {
         self.first_menu.update_menu_contents(ctx);
         self.detail_menu.update_contents(ctx, player_shelving);
+        self.assistant_unlocked = ctx.take_save_data().assistant.is_unlocked();
+    }
+
+    pub fn contains_resize_handle(&self, point: numeric::Point2f) -> bool {
+        self.first_menu.contains_resize_handle(point)
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.first_menu.is_resizing()
+    }
+
+    ///
+    /// つまみが押された時の処理。折りたたみ中ならタブを展開する
+    ///
+    pub fn resize_handle_down<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.first_menu.is_collapsed() {
+            self.first_menu.expand(ctx, t);
+            self.sync_detail_menu_position();
+        } else {
+            self.first_menu.start_resize();
+        }
+    }
+
+    pub fn resize_dragging<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f, t: Clock) {
+        self.first_menu.resize_dragging(ctx, point, t);
+        self.sync_detail_menu_position();
+    }
+
+    pub fn finish_resize<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.first_menu.finish_resize(ctx);
+    }
+
+    fn sync_detail_menu_position(&mut self) {
+        self.detail_menu.appear_position.x = self.first_menu.get_width();
     }
 
     pub fn toggle_first_menu(&mut self, t: Clock) {
@@ -2231,21 +3339,80 @@ impl ShopMenuMaster {
 
     pub fn menu_key_action(&mut self, vkey: VirtualKey, t: Clock) {
         match vkey {
+            VirtualKey::Action2 => {
+                if self.assistant_unlocked {
+                    self.toggle_detail_contents(ShopDetailMenuSymbol::Assistant, t);
+                }
+            }
             VirtualKey::Action3 => {
                 self.toggle_detail_menu(t);
             }
+            VirtualKey::Action5 => {
+                // 日記を出しているときに押すと、書類箱の控えへめくる
+                let contents = if self.detail_menu.is_showing(ShopDetailMenuSymbol::Diary)
+                    || self.detail_menu.is_showing(ShopDetailMenuSymbol::Archive)
+                {
+                    ShopDetailMenuSymbol::Archive
+                } else {
+                    ShopDetailMenuSymbol::Diary
+                };
+                self.toggle_detail_contents(contents, t);
+            }
             _ => (),
         }
     }
 
     pub fn toggle_detail_menu(&mut self, t: Clock) {
-        if self.first_menu_is_open() {
-            self.detail_menu
-                .set_slide_contents(ShopDetailMenuSymbol::ShelvingBooks);
+        self.toggle_detail_contents(ShopDetailMenuSymbol::ShelvingBooks, t);
+    }
+
+    ///
+    /// 別のページが出ていれば入れ替え、同じページが出ていれば引っ込める
+    ///
+    fn toggle_detail_contents(&mut self, contents: ShopDetailMenuSymbol, t: Clock) {
+        if !self.first_menu_is_open() {
+            return;
+        }
+
+        if self.detail_menu_is_open() && !self.detail_menu.is_showing(contents) {
+            self.detail_menu.set_slide_contents(contents);
+            self.detail_menu.appear_toggle(t);
+        } else {
+            self.detail_menu.set_slide_contents(contents);
             self.detail_menu.slide_toggle(t);
         }
     }
 
+    ///
+    /// 手伝いのページで押された仕事を返す。「休ミ」が押されたときはSome(None)
+    ///
+    pub fn click_assistant_role<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> Option<Option<AssistantRole>> {
+        if !self.detail_menu.is_showing(ShopDetailMenuSymbol::Assistant) {
+            return None;
+        }
+
+        self.detail_menu.assistant_info.click_role(ctx, point)
+    }
+
+    ///
+    /// 手伝いのページで押された失敗が何番目かを返す
+    ///
+    pub fn click_assistant_mistake<'a>(
+        &self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> Option<usize> {
+        if !self.detail_menu.is_showing(ShopDetailMenuSymbol::Assistant) {
+            return None;
+        }
+
+        self.detail_menu.assistant_info.click_mistake(ctx, point)
+    }
+
     ///
     /// # 再描画要求有り
     ///
@@ -2616,6 +3783,13 @@ impl ShopClock {
     pub fn equals(&self, hour: u8, minute: u8) -> bool {
         self.hour == hour && self.minute == minute
     }
+
+    ///
+    /// 0時からの経過分
+    ///
+    pub fn total_minutes(&self) -> u32 {
+        (self.hour as u32 * 60) + self.minute as u32
+    }
 }
 
 impl std::fmt::Display for ShopClock {
@@ -2790,6 +3964,324 @@ impl DrawableComponent for DrawableShopClock {
     }
 }
 
+///
+/// # 小鈴の体力を示すゲージ
+/// 体力が少なくなるとゲージの色が変わる
+///
+pub struct StaminaGauge {
+    label: UniText,
+    meter: Meter,
+    low_meter: Meter,
+    stamina: f32,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl StaminaGauge {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        pos: numeric::Point2f,
+        stamina: f32,
+        depth: i8,
+    ) -> Self {
+        let label = UniText::new(
+            "体力".to_string(),
+            pos,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba(30, 20, 12, 255),
+            ),
+        );
+
+        let meter_pos = numeric::Point2f::new(pos.x + 50.0, pos.y + 2.0);
+        let new_meter = |remain_color: ggraphics::Color| {
+            Meter::new(
+                meter_pos,
+                numeric::Rect::new(0.0, 0.0, 160.0, 18.0),
+                ggraphics::Color::from_rgba(90, 80, 63, 255),
+                numeric::Rect::new(2.0, 2.0, 156.0, 14.0),
+                ggraphics::Color::from_rgba(219, 212, 184, 255),
+                remain_color,
+                FATIGUE_MAX,
+                FATIGUE_MAX,
+            )
+        };
+
+        let mut gauge = StaminaGauge {
+            label: label,
+            meter: new_meter(ggraphics::Color::from_rgba(98, 150, 88, 255)),
+            low_meter: new_meter(ggraphics::Color::from_rgba(190, 72, 56, 255)),
+            stamina: FATIGUE_MAX,
+            drwob_essential: DrawableObjectEssential::new(true, depth),
+        };
+        gauge.update_stamina(stamina);
+
+        gauge
+    }
+
+    pub fn update_stamina(&mut self, stamina: f32) {
+        if self.stamina == stamina {
+            return;
+        }
+
+        self.stamina = stamina;
+        for meter in vec![&mut self.meter, &mut self.low_meter] {
+            let diff = stamina - meter.get_value();
+            meter.add(diff);
+        }
+    }
+}
+
+impl DrawableComponent for StaminaGauge {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.label.draw(ctx)?;
+
+            if self.stamina < STAMINA_LOW {
+                self.low_meter.draw(ctx)?;
+            } else {
+                self.meter.draw(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+///
+/// # 小鈴の独り言の吹き出し
+/// 体力ゲージの脇に出し、しばらくすると消える。クリックは受け取らない
+///
+/// ## hide_at
+/// 吹き出しを消す時刻。出していなければNone
+///
+pub struct KosuzuCommentBalloon {
+    balloon: TextBalloon,
+    hide_at: Option<Clock>,
+}
+
+impl KosuzuCommentBalloon {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, rect: numeric::Rect) -> Self {
+        let mut balloon = TextBalloon::new(
+            ctx.context,
+            rect,
+            "",
+            TextBalloonPhraseType::SimplePhrase,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+            ctx.text_orientation(),
+        );
+        balloon.hide();
+
+        KosuzuCommentBalloon {
+            balloon: balloon,
+            hide_at: None,
+        }
+    }
+
+    pub fn show_comment(&mut self, ctx: &mut ggez::Context, text: &str, t: Clock) {
+        self.balloon
+            .replace_text(ctx, text, TextBalloonPhraseType::SimplePhrase);
+        self.balloon.appear();
+        self.hide_at = Some(t + kosuzu_comment::COMMENT_SHOW_DURATION);
+    }
+
+    ///
+    /// 出しておく時間が過ぎたら消す。消したらtrueを返す
+    ///
+    pub fn update(&mut self, t: Clock) -> bool {
+        match self.hide_at {
+            Some(hide_at) if hide_at <= t => {
+                self.balloon.hide();
+                self.hide_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl DrawableComponent for KosuzuCommentBalloon {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        self.balloon.draw(ctx)
+    }
+
+    fn hide(&mut self) {
+        self.balloon.hide();
+    }
+
+    fn appear(&mut self) {
+        self.balloon.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.balloon.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.balloon.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.balloon.get_drawing_depth()
+    }
+}
+
+///
+/// # 待ち列の一覧
+/// 並んでいる順に客の名前を出す。名前を押すと、その客を先に呼ぶ
+///
+pub struct CustomerQueuePanel {
+    header: UniText,
+    entries: Vec<UniText>,
+    position: numeric::Point2f,
+    font_info: FontInformation,
+    drwob_essential: DrawableObjectEssential,
+    number_format: NumberFormat,
+}
+
+impl CustomerQueuePanel {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, pos: numeric::Point2f, depth: i8) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(18.0, 18.0),
+            ggraphics::Color::from_rgba(30, 20, 12, 255),
+        );
+
+        let header = UniText::new(
+            "待チ列".to_string(),
+            pos,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba(30, 20, 12, 255),
+            ),
+        );
+
+        CustomerQueuePanel {
+            header: header,
+            entries: Vec::new(),
+            position: pos,
+            font_info: font_info,
+            drwob_essential: DrawableObjectEssential::new(true, depth),
+            number_format: ctx.number_format(),
+        }
+    }
+
+    ///
+    /// 列に並んでいる客の名前と事情を、並んでいる順に渡す
+    ///
+    pub fn update_entries(&mut self, entries: &[(String, CustomerPriority)]) {
+        let mut position = numeric::Point2f::new(self.position.x + 10.0, self.position.y + 28.0);
+
+        self.entries = entries
+            .iter()
+            .enumerate()
+            .map(|(index, (name, priority))| {
+                let text = match priority.marker_text() {
+                    Some(marker) => {
+                        format!(
+                            "{} {}〔{}〕",
+                            self.number_format.ordinal(index),
+                            name,
+                            marker
+                        )
+                    }
+                    None => format!("{} {}", self.number_format.ordinal(index), name),
+                };
+
+                let entry = UniText::new(
+                    text,
+                    position,
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    self.font_info,
+                );
+                position.y += 24.0;
+
+                entry
+            })
+            .collect();
+    }
+
+    ///
+    /// 押された名前が列の何番目かを返す
+    ///
+    pub fn click_entry(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> Option<usize> {
+        if !self.is_visible() {
+            return None;
+        }
+
+        self.entries
+            .iter()
+            .position(|entry| entry.contains(ctx, point))
+    }
+}
+
+impl DrawableComponent for CustomerQueuePanel {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() && !self.entries.is_empty() {
+            self.header.draw(ctx)?;
+
+            for entry in self.entries.iter_mut() {
+                entry.draw(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
 pub struct ShopMapViewer {
     canvas: SubScreen,
     map_texture: UniTexture,
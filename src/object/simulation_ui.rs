@@ -18,8 +18,6 @@ use crate::{
 };
 use torifune::{mintp, roundup2f};
 
-use number_to_jk::number_to_jk;
-
 struct Counter<T> {
     count: T,
 }
@@ -230,7 +228,7 @@ impl ScenarioMenuContents {
         }
 
         let mut reputation_text = VerticalText::new(
-            number_to_jk(
+            ctx.number_format().number(
                 ctx.take_save_data()
                     .suzunaan_status
                     .get_current_reputation() as u64,
@@ -250,10 +248,8 @@ impl ScenarioMenuContents {
         );
 
         let mut money_text = VerticalText::new(
-            format!(
-                "{}円",
-                number_to_jk(ctx.take_save_data().task_result.total_money as u64)
-            ),
+            ctx.number_format()
+                .money(ctx.take_save_data().task_result.total_money as u64),
             numeric::Point2f::new(0.0, 0.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -269,7 +265,7 @@ impl ScenarioMenuContents {
         );
 
         let mut kosuzu_level_text = VerticalText::new(
-            format!("{}", number_to_jk(0)),
+            ctx.number_format().number(0),
             numeric::Point2f::new(0.0, 0.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
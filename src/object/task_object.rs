@@ -6,6 +6,7 @@ pub mod tt_sub_component;
 use ggez::graphics as ggraphics;
 use ggez::input as ginput;
 use ginput::mouse::CursorIcon;
+use rand::RngCore;
 
 use torifune::core::Clock;
 use torifune::device::VirtualKey;
@@ -18,9 +19,27 @@ use torifune::impl_texture_object_for_wrapped;
 use torifune::numeric;
 
 use crate::add_delay_event;
+use crate::core::book_inspection::{self, InspectionDecision};
+use crate::core::book_management::BESTSELLER_TITLES;
+use crate::core::decision_log::DecisionKind;
+use crate::core::desk_autosave::{DeskAutosave, AUTOSAVE_EDIT_INTERVAL};
+use crate::core::desk_nuisance::{DeskNuisanceScheduler, DeskNuisanceTuning, NuisanceKind};
+use crate::core::game_rng::GameRng;
+use crate::core::mail::LetterContent;
+use crate::core::membership::MembershipCard;
+use crate::core::memo_parser;
+use crate::core::shortcut_palette::PaletteKey;
 use crate::core::util;
 use crate::flush_delay_event;
 use crate::flush_delay_event_and_redraw_check;
+use crate::object::customer_emotion::{CustomerEmotion, CUSTOMER_EMOTION_FRAME_SPEED};
+use crate::object::desk_rotation::{DESK_ROTATE_SPEED, DESK_WHEEL_ROTATE_STEP};
+use crate::object::desk_shortcut::{
+    next_cell_index, record_book_tab_cells, DeskShortcut, BOOK_STATUS_CHOICES,
+};
+use crate::object::drop_target::{DropTarget, DropTargetKind, DropTargetLayer};
+use crate::object::highlight::{Highlight, InspectMode};
+use crate::object::tooltip::Tooltip;
 use crate::object::util_object::*;
 use crate::object::{effect, move_fn};
 use crate::scene::*;
@@ -30,11 +49,19 @@ use tt_sub_component::*;
 
 use super::{Clickable, DarkEffectPanel};
 use crate::core::{
-    BorrowingInformation, GensoDate, RentalLimit, ReturnBookInformation, SuzuContext, TextureID,
-    TileBatchTextureID,
+    BookInformation, BorrowingInformation, FontID, GensoDate, RentalLimit, ReturnBookInformation,
+    SuzuContext, TextureID, TileBatchTextureID,
 };
+use crate::{log_debug, log_trace, log_warn};
+
+const DESK_NUISANCE_TUNING_PATH: &str = "/desk_nuisance.toml";
 
-use number_to_jk::number_to_jk;
+///
+/// 帳簿の欄に手で書ける文字数
+///
+const MANUAL_ENTRY_MAX_CHARS: usize = 12;
+/// 手書きの欄を開くダブルクリックの間隔
+const MANUAL_ENTRY_DOUBLE_CLICK_TICKS: Clock = 20;
 
 pub struct TaskTable {
     canvas: SubScreen,
@@ -44,14 +71,13 @@ pub struct TaskTable {
     staging_object: Option<TaskTableStagingObject>,
     kosuzu_memory: KosuzuMemory,
     dark_effect_panel: DarkEffectPanel,
+    desk_dim_panel: DarkEffectPanel,
     shelving_box: ShelvingBookBox,
     event_list: DelayEventList<TaskTable>,
     borrowing_record_book: BorrowingRecordBook,
     record_book_is_staged: bool,
     manual_book_is_staged: bool,
-    customer_silhouette_menu: CustomerMenuGroup,
-    on_desk_menu: OnDeskMenuGroup,
-    record_book_menu: RecordBookMenuGroup,
+    menus: MenuHost,
     current_customer_request: Option<CustomerRequest>,
     kosuzu_phrase: KosuzuPhrase,
     today: GensoDate,
@@ -59,6 +85,18 @@ pub struct TaskTable {
     return_late_checked: bool,
     appearance_frame: TileBatchFrame,
     current_page_book_condition_report: Option<BookConditionEvalReport>,
+    last_held_data: HoldData,
+    signed_page_data: Option<BorrowingRecordBookPageData>,
+    autosave_enabled: bool,
+    edit_count: u32,
+    inspected_books: Vec<u64>,
+    nuisance: DeskNuisanceScheduler,
+    manual_entry: TextInput,
+    manual_entry_point: Option<numeric::Point2f>,
+    drop_targets: DropTargetLayer,
+    tooltip: Tooltip,
+    record_book_focus: Option<usize>,
+    inspect_mode: InspectMode,
 }
 
 impl TaskTable {
@@ -76,6 +114,7 @@ impl TaskTable {
         let sight = SuzuMiniSight::new(ctx, sight_rect, t);
 
         let mut desk = DeskObjects::new(ctx, desk_rect);
+        let desk_dim_panel = DarkEffectPanel::new(ctx.context, desk_rect, 0);
 
         let texture = UniTexture::new(
             ctx.ref_texture(TextureID::Chobo1),
@@ -104,6 +143,38 @@ impl TaskTable {
         record_book.enable_large();
         desk.add_object(TaskItem::Texture(record_book));
 
+        let today = ctx.take_save_data().date;
+        let ranking = ctx
+            .take_save_data()
+            .book_stats
+            .top_titles_in_week(&today, BESTSELLER_TITLES);
+        desk.add_object(factory::create_popular_book_board(
+            ctx,
+            numeric::Point2f::new(520.0, 150.0),
+            &ranking,
+            t,
+        ));
+        desk.add_object(factory::create_memo_pad(ctx, numeric::Point2f::new(760.0, 150.0), t));
+
+        // 朝の郵便は、少しずつずらして重ねて置く
+        let senders: Vec<String> = ctx.resource.iter_customers_name().cloned().collect();
+        let books: Vec<BookInformation> = ctx.resource.iter_available_books().cloned().collect();
+        let letters = ctx
+            .savable_data
+            .as_mut()
+            .expect("save data not found")
+            .mail_box
+            .deliver_morning(ctx.resource.rng(), &today, &senders, &books);
+        for (index, letter) in letters.into_iter().enumerate() {
+            let offset = index as f32 * 12.0;
+            desk.add_object(factory::create_letter(
+                ctx,
+                numeric::Point2f::new(300.0 + offset, 30.0 + offset),
+                letter,
+                t,
+            ));
+        }
+
         let texture = UniTexture::new(
             ctx.ref_texture(TextureID::Chobo1),
             numeric::Point2f::new(0.0, 0.0),
@@ -153,6 +224,28 @@ impl TaskTable {
         }
         record_book.hide();
 
+        // 易しくする補助が有効な間は、机を散らかす邪魔を起こさない
+        let nuisance = DeskNuisanceScheduler::new(
+            DeskNuisanceTuning::from_toml_str(&util::read_from_resources_as_string(
+                ctx.context,
+                DESK_NUISANCE_TUNING_PATH,
+            )),
+            ctx.take_save_data().difficulty_assist.is_enabled(),
+            t,
+        );
+
+        let manual_entry = TextInput::new(
+            ctx,
+            numeric::Rect::new(0.0, 0.0, 240.0, 40.0),
+            MANUAL_ENTRY_MAX_CHARS,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(22.0, 22.0),
+                ggraphics::Color::from_rgba_u32(0x000000ff),
+            ),
+            0,
+        );
+
         TaskTable {
             canvas: SubScreen::new(
                 ctx.context,
@@ -170,14 +263,13 @@ impl TaskTable {
                 numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
                 0,
             ),
+            desk_dim_panel: desk_dim_panel,
             shelving_box: shelving_box,
             event_list: DelayEventList::new(),
             borrowing_record_book: record_book,
             record_book_is_staged: false,
             manual_book_is_staged: false,
-            customer_silhouette_menu: CustomerMenuGroup::new(0),
-            on_desk_menu: OnDeskMenuGroup::new(0),
-            record_book_menu: RecordBookMenuGroup::new(0),
+            menus: MenuHost::new(0),
             current_customer_request: None,
             kosuzu_phrase: KosuzuPhrase::new(ctx, 0),
             today: ctx.take_save_data().date,
@@ -185,6 +277,25 @@ impl TaskTable {
             return_late_checked: false,
             appearance_frame: appr_frame,
             current_page_book_condition_report: None,
+            last_held_data: HoldData::None,
+            signed_page_data: None,
+            autosave_enabled: true,
+            edit_count: 0,
+            inspected_books: Vec::new(),
+            nuisance: nuisance,
+            manual_entry: manual_entry,
+            manual_entry_point: None,
+            drop_targets: DropTargetLayer::new(0),
+            tooltip: Tooltip::new(
+                FontInformation::new(
+                    ctx.resource.get_font(FontID::JpFude1),
+                    numeric::Vector2f::new(16.0, 16.0),
+                    ggraphics::Color::from_rgba_u32(0xf0f0f0ff),
+                ),
+                0,
+            ),
+            record_book_focus: None,
+            inspect_mode: InspectMode::new(),
         }
     }
 
@@ -196,30 +307,110 @@ impl TaskTable {
         &self.kosuzu_memory
     }
 
-    fn select_dragging_object<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
-        let rpoint = self.canvas.relative_point(point);
+    ///
+    /// 練習のように、書きかけの頁を残す必要の無い受付で使う
+    ///
+    pub fn disable_autosave(&mut self) {
+        self.autosave_enabled = false;
+    }
 
-        // メニューがオブジェクトの上に表示されている場合、ドラッグする
-        // オブジェクトの走査は行わない
-        if self.some_full_screen_object_is_appeared() {
-            return ();
+    ///
+    /// 帳簿を書き換えるたびに呼ぶ。AUTOSAVE_EDIT_INTERVAL回ごとに書きかけの頁を控える
+    ///
+    fn record_book_edited<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if !self.autosave_enabled {
+            return;
         }
 
-        if self
-            .record_book_menu
-            .is_contains_any_menus(ctx.context, rpoint)
-        {
-            return ();
+        self.edit_count += 1;
+        if self.edit_count % AUTOSAVE_EDIT_INTERVAL != 0 {
+            return;
         }
 
-        if self
-            .customer_silhouette_menu
-            .is_contains_any_menus(ctx.context, rpoint)
+        let request = match self.current_customer_request.as_ref() {
+            Some(request) => request.clone(),
+            None => return,
+        };
+        let page_data = match self.borrowing_record_book.get_current_page_data() {
+            Some(page_data) => page_data,
+            None => return,
+        };
+
+        let autosave = DeskAutosave::new(
+            ctx.take_save_data().date,
+            request,
+            self.borrowing_record_book.get_current_page_index(),
+            page_data,
+            self.kosuzu_memory.clone(),
+        );
+
+        if let Err(e) = autosave.save() {
+            log_warn!("failed to autosave the record book page: {}", e);
+        }
+    }
+
+    ///
+    /// 控えておいた書きかけの頁と、小鈴の覚えている内容を戻す
+    ///
+    pub fn restore_autosave<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        autosave: DeskAutosave,
+        t: Clock,
+    ) {
+        self.borrowing_record_book.restore_page(ctx, autosave.page_index, autosave.page_data, t);
+        self.kosuzu_memory = autosave.kosuzu_memory;
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 補助が有効なら、最後に覚えた情報を書き込む欄を記録簿の上で示す
+    ///
+    pub fn update_assist_highlight<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let target = if ctx.take_save_data().difficulty_assist.is_enabled()
+            && self.last_held_data != HoldData::None
         {
+            Some(self.last_held_data.clone())
+        } else {
+            None
+        };
+
+        self.borrowing_record_book.set_assist_target(target);
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 机の上のobject_typeの物を目立たせる。説明の途中で指し示すときに使う
+    ///
+    pub fn highlight_desk_objects<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        object_type: DeskObjectType,
+        highlight: Option<Highlight>,
+    ) {
+        self.desk.set_highlight_by_type(object_type, highlight);
+        ctx.process_utility.redraw();
+    }
+
+    pub fn insert_kosuzu_phrase<'a>(&mut self, ctx: &mut SuzuContext<'a>, text: &str, t: Clock) {
+        self.kosuzu_phrase.insert_new_phrase(ctx, text, t);
+    }
+
+    fn hold_data_for_assist<'a>(&mut self, ctx: &mut SuzuContext<'a>, hold_data: HoldData) {
+        self.last_held_data = hold_data;
+        self.update_assist_highlight(ctx);
+    }
+
+    fn select_dragging_object<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let rpoint = self.canvas.relative_point(point);
+
+        // メニューがオブジェクトの上に表示されている場合、ドラッグする
+        // オブジェクトの走査は行わない
+        if self.some_full_screen_object_is_appeared() {
             return ();
         }
 
-        if self.on_desk_menu.is_contains_any_menus(ctx.context, rpoint) {
+        if self.menus.is_contains_any_menus(ctx.context, rpoint) {
             return ();
         }
 
@@ -228,11 +419,35 @@ impl TaskTable {
 
     pub fn mouse_motion_handler<'a>(
         &mut self,
-        _: &mut SuzuContext<'a>,
+        ctx: &mut SuzuContext<'a>,
         point: numeric::Point2f,
         _: numeric::Vector2f,
     ) {
         self.borrowing_record_book.mouse_motion_handler(point);
+        self.hover_tooltip(ctx, point);
+    }
+
+    ///
+    /// カーソルの下にある物の説明を、説明の札に渡す
+    ///
+    fn hover_tooltip<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let rpoint = self.canvas.relative_point(point);
+
+        let text = if self.some_full_screen_object_is_appeared()
+            || self.menus.is_contains_any_menus(ctx.context, rpoint)
+        {
+            None
+        } else {
+            let sight_point = self.sight.canvas.relative_point(rpoint);
+            self.sight
+                .silhouette
+                .silhouette_tooltip_text(ctx.context, sight_point)
+                .or_else(|| self.desk.tooltip_text_at(ctx.context, rpoint))
+        };
+
+        if self.tooltip.hover(text, rpoint) {
+            ctx.process_utility.redraw();
+        }
     }
 
     fn slide_appear_record_book(&mut self, t: Clock) {
@@ -251,12 +466,42 @@ impl TaskTable {
             t,
         );
         self.record_book_is_staged = false;
+        self.record_book_focus = None;
 
-        self.record_book_menu.close_all(t);
+        self.menus.record_book_mut().close_all(t);
 
         self.dark_effect_panel.new_effect(8, t, 200, 0);
     }
     
+    ///
+    /// 帳簿を開く。他に全画面の物が出ていて開けなかった場合はfalseを返す
+    ///
+    pub fn open_record_book(&mut self, t: Clock) -> bool {
+        if self.some_full_screen_object_is_appeared() {
+            return false;
+        }
+
+        self.slide_appear_record_book(t);
+        self.sight.release_conversation_focus(t);
+        self.record_book_is_staged = true;
+        true
+    }
+
+    pub fn close_record_book(&mut self, t: Clock) {
+        if self.staging_object.is_some() {
+            self.event_list.add_event(
+                Box::new(|tt: &mut Self, _, _| tt.staging_object = None),
+                t + 100,
+            );
+        }
+
+        self.slide_hide_record_book(t);
+    }
+
+    pub fn record_book_is_staged(&self) -> bool {
+        self.record_book_is_staged
+    }
+
     fn try_open_borrowing_record_book<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
@@ -270,9 +515,7 @@ impl TaskTable {
         if clicked_object_type.is_some() {
             match clicked_object_type.unwrap() {
                 OnDeskType::BorrowingRecordBook => {
-                    if !self.some_full_screen_object_is_appeared() {
-                        self.slide_appear_record_book(t);
-                        self.record_book_is_staged = true;
+                    if self.open_record_book(t) {
                         return true;
                     }
                 }
@@ -301,12 +544,73 @@ impl TaskTable {
         self.sight.dragging_handler(ctx, rpoint, rlast);
         self.desk.dragging_handler(ctx, rpoint);
         self.shelving_box.dragging_handler(ctx, rpoint, rlast);
+
+        self.update_drop_targets(ctx, rpoint);
     }
 
     pub fn unselect_dragging_object<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         self.sight.unselect_dragging_object(ctx.context, t);
         self.desk.unselect_dragging_object(ctx);
         self.shelving_box.unselect_dragging_object(t);
+        self.archive_dropped_letters(ctx, t);
+
+        if self.drop_targets.is_active() {
+            self.drop_targets.clear();
+            ctx.process_utility.redraw();
+        }
+    }
+
+    ///
+    /// 今掴んでいる物を置ける所を集めて、光らせる
+    ///
+    /// 受け渡しを断られる物や、箱に入れられない物の行き先は光らせない
+    ///
+    fn update_drop_targets<'a>(&mut self, ctx: &mut SuzuContext<'a>, rpoint: numeric::Point2f) {
+        let sight_area = self.sight.canvas.get_drawing_area(ctx.context);
+        let desk_area = self.desk.canvas.get_drawing_area(ctx.context);
+        let box_area = self.shelving_box.canvas.get_drawing_area(ctx.context);
+        let mut targets = Vec::new();
+
+        if let Some(dragging) = self.desk.ref_dragging() {
+            if !dragging.is_handover_locked() {
+                targets.push(DropTarget::new(DropTargetKind::HandOver, sight_area));
+            }
+
+            if !dragging.is_shelving_box_handover_locked() {
+                targets.push(DropTarget::new(DropTargetKind::ShelvingBox, box_area));
+            }
+
+            if ArchiveDrawer::is_acceptable_for_archive(dragging) {
+                let area = self.desk.archive_drop_area(ctx.context);
+                targets.push(DropTarget::new(
+                    DropTargetKind::Archive,
+                    numeric::Rect::new(desk_area.x + area.x, desk_area.y + area.y, area.w, area.h),
+                ));
+            }
+
+            if MoneyBox::is_acceptable_for_moneybox(dragging) {
+                if let Some(area) = self.desk.money_box_drop_area(ctx.context) {
+                    targets.push(DropTarget::new(
+                        DropTargetKind::MoneyBox,
+                        numeric::Rect::new(
+                            desk_area.x + area.x,
+                            desk_area.y + area.y,
+                            area.w,
+                            area.h,
+                        ),
+                    ));
+                }
+            }
+        } else if self.sight.has_dragging() || self.shelving_box.has_dragging() {
+            targets.push(DropTarget::new(DropTargetKind::Desk, desk_area));
+        }
+
+        if targets.is_empty() && !self.drop_targets.is_active() {
+            return;
+        }
+
+        self.drop_targets.set_targets(targets, rpoint);
+        ctx.process_utility.redraw();
     }
 
     pub fn hand_over_check<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
@@ -513,25 +817,176 @@ impl TaskTable {
         self.desk.update(ctx, t);
         self.shelving_box.update(ctx, t);
         self.check_sight_drop_to_desk(ctx, t);
+        if self.drop_targets.update(t) {
+            ctx.process_utility.redraw();
+        }
+        if self.tooltip.update(t) {
+            ctx.process_utility.redraw();
+        }
         self.borrowing_record_book.update(ctx, t);
-        self.record_book_menu.update(ctx, t);
-        self.customer_silhouette_menu.update(ctx, t);
-        self.on_desk_menu.update(ctx, t);
+        self.menus.update(ctx, t);
         self.kosuzu_phrase.update(ctx, t);
         self.info_panel.update(ctx, t);
         self.check_task_is_done(ctx);
+        self.update_nuisance(ctx, t);
+        self.rotate_dragging_by_key(ctx);
+        self.update_inspect_mode(ctx, t);
 
         self.dark_effect_panel.run_effect(ctx, t);
+        self.desk_dim_panel
+            .set_alpha(self.sight.conversation_desk_dim(t));
+    }
+
+    ///
+    /// 机の物を掴んでいる間、Q/Eを押していれば回す
+    ///
+    fn rotate_dragging_by_key<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if !self.desk.has_dragging() {
+            return;
+        }
+
+        let mut rad = 0.0;
+        if ginput::keyboard::is_key_pressed(ctx.context, ginput::keyboard::KeyCode::Q) {
+            rad -= DESK_ROTATE_SPEED;
+        }
+        if ginput::keyboard::is_key_pressed(ctx.context, ginput::keyboard::KeyCode::E) {
+            rad += DESK_ROTATE_SPEED;
+        }
+
+        if rad != 0.0 {
+            self.desk.rotate_dragging(ctx, rad);
+        }
+    }
+
+    ///
+    /// Shiftを押している間は調べる構えになり、覚えられる物を縁取る
+    ///
+    /// 帳簿などを広げている間は、下の物が見えないので構えない
+    ///
+    fn update_inspect_mode<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let active = ginput::keyboard::is_mod_active(ctx.context, ginput::keyboard::KeyMods::SHIFT)
+            && !self.some_full_screen_object_is_appeared();
+
+        let changed = self.inspect_mode.set_active(active, t);
+        if !self.inspect_mode.update(t) && !changed {
+            return;
+        }
+
+        self.desk.set_inspect_mode(self.inspect_mode);
+        self.sight.set_inspect_mode(self.inspect_mode);
+        self.shelving_box.set_inspect_mode(self.inspect_mode);
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 時が来ていれば、突風や猫で机を散らかす
+    ///
+    fn update_nuisance<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if ctx.take_save_data().difficulty_assist.is_enabled() {
+            self.nuisance.disable();
+            return;
+        }
+
+        let kind = match self.nuisance.poll(t, ctx.resource.rng()) {
+            Some(kind) => kind,
+            None => return,
+        };
+
+        // 物を掴んでいる間や、本を広げている間は邪魔しない
+        if self.desk.has_dragging() || self.some_full_screen_object_is_appeared() {
+            self.nuisance.retry_later(t);
+            return;
+        }
+
+        // ctxも渡すので、ゲームの乱数から分けた乱数で散らかす
+        let tuning = self.nuisance.get_tuning().clone();
+        let mut rng = GameRng::new(ctx.resource.rng().next_u64());
+        let happened = match kind {
+            NuisanceKind::Gust => self.desk.scatter_loose_papers(ctx, &tuning, &mut rng, t) > 0,
+            NuisanceKind::Cat => self.desk.push_by_cat(ctx, &tuning, &mut rng, t),
+        };
+
+        if happened {
+            log_debug!("desk nuisance: {}", kind.to_str());
+            let phrase = match kind {
+                NuisanceKind::Gust => "あっ、風で紙が……",
+                NuisanceKind::Cat => "こら、机に乗っちゃだめ",
+            };
+            self.kosuzu_phrase.insert_new_phrase(ctx, phrase, t);
+        }
     }
 
-    pub fn finish_customer_event(&mut self, now: Clock) {
-        self.sight.finish_customer_event(now);
+    pub fn finish_customer_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        tone: CustomerFarewellTone,
+        now: Clock,
+    ) {
+        self.sight.finish_customer_event(ctx, tone, now);
     }
 
     pub fn task_is_done(&self) -> bool {
         self.task_is_done
     }
 
+    ///
+    /// 客の影を作る。顔付きごとのアニメーションは、今は同じ立ち絵を揺らして見せる
+    ///
+    fn new_customer_silhouette<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        t: Clock,
+    ) -> TextureAnimation<CustomerEmotion> {
+        let texture = ctx.ref_texture(TextureID::Mob1TachieDefault);
+
+        let mut silhouette = SimpleObject::new(
+            MovableUniTexture::new(
+                Box::new(UniTexture::new(
+                    texture.clone(),
+                    position,
+                    numeric::Vector2f::new(0.18, 0.18),
+                    0.0,
+                    0,
+                )),
+                None,
+                t,
+            ),
+            vec![
+                effect::appear_bale_down_from_top(50, t),
+                effect::fade_in(50, t),
+            ],
+        );
+        silhouette.set_alpha(0.0);
+
+        let modes = CustomerEmotion::mode_order();
+        let textures = modes
+            .iter()
+            .map(|emotion| vec![texture.clone(); emotion.frame_count()])
+            .collect();
+
+        TextureAnimation::new(
+            silhouette,
+            modes,
+            textures,
+            CustomerEmotion::Idle,
+            CUSTOMER_EMOTION_FRAME_SPEED,
+        )
+    }
+
+    ///
+    /// 客を待たせている時間を、客の影の顔付きに映す
+    ///
+    pub fn update_customer_waiting(&mut self, waiting: Clock) {
+        self.sight.update_customer_waiting(waiting);
+    }
+
+    ///
+    /// 受け渡しが正しかったかどうかに、客の影を反応させる
+    ///
+    pub fn react_customer(&mut self, correct: bool) {
+        self.sight.react_customer(correct);
+    }
+
     fn start_borrowing_customer_event<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
@@ -554,24 +1009,8 @@ impl TaskTable {
             position.x += 20.0;
         }
 
-        let mut new_silhouette = SimpleObject::new(
-            MovableUniTexture::new(
-                Box::new(UniTexture::new(
-                    ctx.ref_texture(TextureID::Mob1TachieDefault),
-                    numeric::Point2f::new(130.0, 15.0),
-                    numeric::Vector2f::new(0.18, 0.18),
-                    0.0,
-                    0,
-                )),
-                None,
-                t,
-            ),
-            vec![
-                effect::appear_bale_down_from_top(50, t),
-                effect::fade_in(50, t),
-            ],
-        );
-        new_silhouette.set_alpha(0.0);
+        let new_silhouette =
+            Self::new_customer_silhouette(ctx, numeric::Point2f::new(130.0, 15.0), t);
         self.sight.silhouette_new_customer_update(
             ctx.context,
             new_silhouette,
@@ -606,24 +1045,8 @@ impl TaskTable {
             position.x += 20.0;
         }
 
-        let mut new_silhouette = SimpleObject::new(
-            MovableUniTexture::new(
-                Box::new(UniTexture::new(
-                    ctx.ref_texture(TextureID::Mob1TachieDefault),
-                    numeric::Point2f::new(100.0, 20.0),
-                    numeric::Vector2f::new(0.18, 0.18),
-                    0.0,
-                    0,
-                )),
-                None,
-                t,
-            ),
-            vec![
-                effect::appear_bale_down_from_top(50, t),
-                effect::fade_in(50, t),
-            ],
-        );
-        new_silhouette.set_alpha(0.0);
+        let new_silhouette =
+            Self::new_customer_silhouette(ctx, numeric::Point2f::new(100.0, 20.0), t);
         self.sight.silhouette_new_customer_update(
             ctx.context,
             new_silhouette,
@@ -638,6 +1061,8 @@ impl TaskTable {
 
     pub fn start_customer_event(&mut self, ctx: &mut SuzuContext, info: CustomerRequest, t: Clock) {
         self.current_customer_request = Some(info.clone());
+        self.signed_page_data = None;
+        self.hold_data_for_assist(ctx, HoldData::None);
 
         match info {
             CustomerRequest::Borrowing(info) => self.start_borrowing_customer_event(ctx, info, t),
@@ -645,14 +1070,39 @@ impl TaskTable {
         }
     }
 
+    ///
+    /// 客の会員証を机に出す。会員証も本と一緒に客へ返す
+    ///
+    pub fn present_membership_card<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        card: MembershipCard,
+        t: Clock,
+    ) {
+        let card =
+            factory::create_membership_card(ctx, numeric::Point2f::new(160.0, 40.0), card, t);
+        self.desk.add_customer_object(card);
+        ctx.process_utility.redraw();
+    }
+
+    pub fn take_membership_check_request(&mut self) -> Option<MembershipCard> {
+        self.desk.take_membership_check_request()
+    }
+
+    pub fn replace_membership_card<'a>(&mut self, ctx: &mut SuzuContext<'a>, card: MembershipCard) {
+        self.desk.replace_membership_card(ctx, card);
+        ctx.process_utility.redraw();
+    }
+
     pub fn add_fee_coins<'a>(&mut self, ctx: &mut SuzuContext<'a>, price: u32, t: Clock) {
         let coins = factory::create_coins(ctx, price, t);
 
         for mut coin in coins {
             coin.get_object_mut()
-                .set_position(util::random_point_in_rect(numeric::Rect::new(
-                    10.0, 10.0, 100.0, 100.0,
-                )));
+                .set_position(util::random_point_in_rect(
+                    ctx.resource.rng(),
+                    numeric::Rect::new(10.0, 10.0, 100.0, 100.0),
+                ));
             self.desk.add_object(coin);
         }
     }
@@ -665,6 +1115,7 @@ impl TaskTable {
                     item_counts += 1;
                 }
                 TaskItem::Coin(_) => item_counts += 1,
+                TaskItem::Card(_) => item_counts += 1,
                 _ => (),
             }
         }
@@ -678,6 +1129,7 @@ impl TaskTable {
                 TaskItem::Book(_) => {
                     item_counts += 1;
                 }
+                TaskItem::Card(_) => item_counts += 1,
                 _ => (),
             }
         }
@@ -748,14 +1200,7 @@ impl TaskTable {
     ) {
         match vkey {
             VirtualKey::Action3 => {
-                if self.staging_object.is_some() {
-                    self.event_list.add_event(
-                        Box::new(|tt: &mut Self, _, _| tt.staging_object = None),
-                        t + 100,
-                    );
-                }
-
-                self.slide_hide_record_book(t);
+                self.close_record_book(t);
             }
             _ => (),
         }
@@ -796,6 +1241,8 @@ impl TaskTable {
             }
         }
 
+        self.signed_page_data = self.borrowing_record_book.get_current_page_data();
+
         self.sight.silhouette.insert_new_balloon_phrase(
             "どうぞ".to_string(),
             TextBalloonPhraseType::SimplePhrase,
@@ -803,9 +1250,15 @@ impl TaskTable {
             t,
         );
 
+        ctx.take_save_data_mut()
+            .book_stats
+            .record_lent_books(&written_books, &self.today);
+
         for book in written_books {
             self.kosuzu_memory.add_book_to_written_list(book);
         }
+
+        DeskAutosave::delete();
     }
 
     pub fn signing_returning_handler<'a>(
@@ -841,10 +1294,20 @@ impl TaskTable {
             }
         }
 
+        self.signed_page_data = self.borrowing_record_book.get_current_page_data();
+        DeskAutosave::delete();
+
         self.borrowing_record_book
             .get_current_page_condition_eval_report()
     }
 
+    ///
+    /// 貸出か返却の署名をしたときの頁の内容
+    ///
+    pub fn get_signed_page_data(&self) -> Option<&BorrowingRecordBookPageData> {
+        self.signed_page_data.as_ref()
+    }
+
     ///
     /// メニューのエントリをクリックしていたらtrueを返し、そうでなければfalseを返す
     ///
@@ -855,25 +1318,21 @@ impl TaskTable {
         point: numeric::Point2f,
         t: Clock,
     ) -> bool {
-        if !self
-            .record_book_menu
-            .click_book_status_menu(ctx, button, point, t)
-            && !self
-                .record_book_menu
-                .click_book_title_menu(ctx, button, point, t)
-            && !self.record_book_menu.click_date_menu(ctx, button, point, t)
-            && !self
-                .record_book_menu
-                .click_customer_name_menu(ctx, button, point, t)
-            && !self
-                .record_book_menu
-                .click_date_check_menu(ctx, button, point, t)
+        // 重なったメニューのうち、一番上のものだけがクリックを受け取る
+        if self
+            .menus
+            .record_book_mut()
+            .click_menu(ctx, button, point, t)
+            .is_none()
         {
-            // メニューをクリックしていない場合はfalseをクリックして終了
             return false;
         }
 
-        if self.record_book_menu.date_check_menu_check_button_clicked() {
+        if self
+            .menus
+            .record_book_mut()
+            .date_check_menu_check_button_clicked()
+        {
             if let Some(return_date) = self.borrowing_record_book.get_current_page_return_date() {
                 if self.today.is_past(&return_date) {
                     if self.return_late_checked {
@@ -894,30 +1353,17 @@ impl TaskTable {
             }
         }
 
-        if let Some(index) = self.record_book_menu.book_status_menu_last_clicked() {
-            let menu_position = self
-                .record_book_menu
-                .get_book_status_menu_position()
-                .unwrap();
-            if index <= 2 {
-                // 良, 可, 悪
-                self.borrowing_record_book.insert_book_status_via_choice(
-                    ctx.context,
-                    index,
-                    menu_position,
-                );
-            } else {
-                // 削除
-                self.borrowing_record_book
-                    .remove_book_status_at(ctx.context, menu_position);
-            }
-
+        if let Some(index) = self.menus.record_book_mut().book_status_menu_last_clicked() {
+            self.apply_book_status_choice(ctx, index);
             return true;
         }
 
-        if let Some((index, book_info)) = self.record_book_menu.book_title_menu_last_clicked() {
+        if let Some((index, book_info)) =
+            self.menus.record_book_mut().book_title_menu_last_clicked()
+        {
             let menu_position = self
-                .record_book_menu
+                .menus
+                .record_book_mut()
                 .get_book_title_menu_position()
                 .unwrap();
             self.borrowing_record_book.insert_book_title_to_books_frame(
@@ -928,34 +1374,75 @@ impl TaskTable {
 
             self.kosuzu_memory.remove_book_info_at(index);
 
+            self.record_book_edited(ctx);
             return true;
         }
 
-        if let Some((_, date)) = self.record_book_menu.date_menu_last_clicked() {
-            let menu_position = self.record_book_menu.get_date_menu_position().unwrap();
+        if let Some((_, date)) = self.menus.record_book_mut().date_menu_last_clicked() {
+            let menu_position = self
+                .menus
+                .record_book_mut()
+                .get_date_menu_position()
+                .unwrap();
             let maybe_rental_limit = self.today.rental_limit_type(&date);
             if let Some(rental_limit) = maybe_rental_limit {
                 self.borrowing_record_book
                     .insert_date_data_to_customer_info(ctx, menu_position, date, rental_limit);
             }
 
+            self.record_book_edited(ctx);
             return true;
         }
 
-        if let Some((_, name)) = self.record_book_menu.customer_name_menu_last_clicked() {
+        if let Some((index, _)) = self
+            .menus
+            .record_book_mut()
+            .customer_name_menu_last_clicked()
+        {
             let menu_position = self
-                .record_book_menu
+                .menus
+                .record_book_mut()
                 .get_customer_name_menu_position()
                 .unwrap();
-            self.borrowing_record_book
-                .insert_customer_name_data_to_customer_info(ctx.context, menu_position, name);
 
+            // 書いた名前は、小鈴の覚えから消す
+            if let Some(name) = self.kosuzu_memory.get_customer_name_remove(index) {
+                self.borrowing_record_book
+                    .insert_customer_name_data_to_customer_info(ctx.context, menu_position, name);
+            }
+
+            self.record_book_edited(ctx);
             return true;
         }
 
         false
     }
 
+    ///
+    /// 本の状態のメニューで選んだものを、帳簿に書く
+    ///
+    fn apply_book_status_choice<'a>(&mut self, ctx: &mut SuzuContext<'a>, index: usize) {
+        let menu_position = self
+            .menus
+            .record_book_mut()
+            .get_book_status_menu_position()
+            .unwrap();
+        if index <= 2 {
+            // 良, 可, 悪
+            self.borrowing_record_book.insert_book_status_via_choice(
+                ctx.context,
+                index,
+                menu_position,
+            );
+        } else {
+            // 削除
+            self.borrowing_record_book
+                .remove_book_status_at(ctx.context, menu_position);
+        }
+
+        self.record_book_edited(ctx);
+    }
+
     ///
     /// 新しく、客の名前をsightのtext_balloonに表示させる
     ///
@@ -1023,10 +1510,16 @@ impl TaskTable {
                     let rental_limit = info.get_rental_limit();
                     let phrase_text = match &rental_limit {
                         RentalLimit::ShortTerm => {
-                            format!("{}に\n短期で借りました", info.borrow_date.to_short_string())
+                            format!(
+                                "{}に\n短期で借りました",
+                                ctx.number_format().short_date(&info.borrow_date)
+                            )
                         }
                         RentalLimit::LongTerm => {
-                            format!("{}に\n長期で借りました", info.borrow_date.to_short_string())
+                            format!(
+                                "{}に\n長期で借りました",
+                                ctx.number_format().short_date(&info.borrow_date)
+                            )
                         }
                         _ => "".to_string(),
                     };
@@ -1048,7 +1541,10 @@ impl TaskTable {
     }
 
     fn show_kosuzu_payment_message<'a>(&mut self, ctx: &mut SuzuContext<'a>, price: u32, t: Clock) {
-        let msg = format!("合計{}円になります", number_to_jk(price as u64));
+        let msg = format!(
+            "合計{}円になります",
+            ctx.number_format().number(price as u64)
+        );
         self.kosuzu_phrase.insert_new_phrase(ctx, &msg, t);
 
         self.sight
@@ -1083,27 +1579,26 @@ impl TaskTable {
         point: numeric::Point2f,
         t: Clock,
     ) -> bool {
-        println!("check customer_silhouette");
+        log_trace!("check customer_silhouette");
         if !self
-            .customer_silhouette_menu
+            .menus
+            .customer_mut()
             .click_customer_question_menu(ctx, button, point, t)
         {
             // メニューをクリックしていない場合はfalseをクリックして終了
-            println!("not clicked");
+            log_trace!("not clicked");
             return false;
         }
 
-        if let Some(index) = self
-            .customer_silhouette_menu
-            .question_menu_last_clicked_index()
-        {
+        if let Some(index) = self.menus.customer_mut().question_menu_last_clicked_index() {
             match index {
                 0 => {
                     if let Some(customer_request) = self.current_customer_request.as_ref() {
                         let name = customer_request.get_customer_name();
                         self.kosuzu_memory.add_customer_name(name.clone());
-                        self.info_panel.set_customer_name(ctx, name);
+                        self.info_panel.set_customer_name(ctx, name.clone());
                         self.insert_custmer_name_phrase(ctx, t);
+                        self.hold_data_for_assist(ctx, HoldData::CustomerName(name));
                     }
                 }
                 1 => self.insert_rental_limit_phrase(ctx, t),
@@ -1127,16 +1622,17 @@ impl TaskTable {
         t: Clock,
     ) -> bool {
         if !self
-            .on_desk_menu
+            .menus
+            .on_desk_mut()
             .click_desk_book_menu(ctx, button, point, t)
         {
             // メニューをクリックしていない場合はfalseをクリックして終了
-            println!("not clicked");
+            log_trace!("not clicked");
             return false;
         }
 
-        if let Some(index) = self.on_desk_menu.desk_book_menu_last_clicked() {
-            if let Some(book_info) = self.on_desk_menu.get_desk_menu_target_book_info() {
+        if let Some(index) = self.menus.on_desk_mut().desk_book_menu_last_clicked() {
+            if let Some(book_info) = self.menus.on_desk_mut().get_desk_menu_target_book_info() {
                 return match index {
                     //
                     // 題名を記憶する
@@ -1154,7 +1650,27 @@ impl TaskTable {
                                 .add_book_info(ctx, book_info.clone(), point, t);
 
                             // internal memory
-                            self.kosuzu_memory.add_book_info(book_info);
+                            self.kosuzu_memory.add_book_info(book_info.clone());
+                            self.hold_data_for_assist(ctx, HoldData::BookName(book_info));
+                        }
+                        true
+                    }
+                    //
+                    // 虫眼鏡で検分する
+                    //
+                    1 => {
+                        if self.inspected_books.contains(&book_info.get_unique_id()) {
+                            self.kosuzu_phrase
+                                .insert_new_phrase(ctx, "この本はもう検分した", t);
+                        } else {
+                            let position =
+                                self.menus.on_desk_mut().get_desk_book_menu_click_position();
+                            self.menus.opening_on_desk().show_book_inspection_menu(
+                                ctx,
+                                position.unwrap_or(point),
+                                book_info,
+                                t,
+                            );
                         }
                         true
                     }
@@ -1166,6 +1682,72 @@ impl TaskTable {
         false
     }
 
+    ///
+    /// 検分メニューをクリックしていたらtrueを返し、そうでなければfalseを返す
+    /// 扱いが決まった場合は、その結果を反映してメニューを閉じる
+    ///
+    fn click_book_inspection_menu<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> bool {
+        if !self
+            .menus
+            .on_desk_mut()
+            .click_book_inspection_menu(ctx, button, point, t)
+        {
+            return false;
+        }
+
+        if let Some((book_info, decision)) = self.menus.on_desk_mut().book_inspection_decision() {
+            self.decide_book_inspection(ctx, &book_info, decision, t);
+            self.menus.on_desk_mut().close_book_inspection_menu(t);
+        }
+
+        true
+    }
+
+    fn decide_book_inspection<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        book_info: &BookInformation,
+        decision: InspectionDecision,
+        t: Clock,
+    ) {
+        let anomaly = book_info.get_anomaly();
+        let outcome = book_inspection::judge(anomaly, decision);
+        self.inspected_books.push(book_info.get_unique_id());
+
+        let story_hook = {
+            let save_data = ctx.take_save_data_mut();
+            save_data.task_result.total_money += outcome.money;
+            save_data.suzunaan_status.add_reputation(outcome.reputation);
+
+            let decision_kind = match decision {
+                InspectionDecision::Confiscate => Some(DecisionKind::Confiscation),
+                InspectionDecision::Report => Some(DecisionKind::Report),
+                InspectionDecision::Accept => None,
+            };
+            if let Some(kind) = decision_kind {
+                let today = save_data.date;
+                save_data.decision_log.record(kind, &book_info.name, today);
+            }
+
+            save_data.inspection_record.record(anomaly, decision)
+        };
+
+        let phrase = match (story_hook, outcome.correct, anomaly) {
+            (Some(hook), _, _) => hook.to_string(),
+            (None, true, Some(anomaly)) => format!("やっぱり{}だった", anomaly.to_str()),
+            (None, true, None) => "普通の本だった".to_string(),
+            (None, false, Some(anomaly)) => format!("しまった、{}だったみたい", anomaly.to_str()),
+            (None, false, None) => "疑ってしまって悪いことしたかな".to_string(),
+        };
+        self.kosuzu_phrase.insert_new_phrase(ctx, &phrase, t);
+    }
+
     ///
     /// book_info_frameに関するメニューを表示する
     ///
@@ -1199,7 +1781,7 @@ impl TaskTable {
                         match request {
                             CustomerRequest::Borrowing(_) => match lock_status {
                                 RecordBookLockStatus::BorrowingOk => {
-                                    self.record_book_menu.show_book_title_menu(
+                                    self.menus.opening_record_book().show_book_title_menu(
                                         ctx,
                                         click_point,
                                         &self.kosuzu_memory,
@@ -1223,7 +1805,7 @@ impl TaskTable {
                                             numeric::Vector2u::new(grid_pos.unwrap().x, 0),
                                         )
                                     {
-                                        self.record_book_menu.show_book_status_menu(
+                                        self.menus.opening_record_book().show_book_status_menu(
                                             ctx,
                                             click_point,
                                             t,
@@ -1231,7 +1813,11 @@ impl TaskTable {
                                     }
                                 }
                                 RecordBookLockStatus::ReturningVary => {
-                                    self.record_book_menu.show_locked_menu(ctx, click_point, t);
+                                    self.menus.opening_record_book().show_locked_menu(
+                                        ctx,
+                                        click_point,
+                                        t,
+                                    );
                                 }
                                 _ => (),
                             },
@@ -1274,7 +1860,7 @@ impl TaskTable {
                                 .current_page_is_borrowing_signed()
                                 != RecordBookLockStatus::BorrowingLocked
                             {
-                                self.record_book_menu.show_customer_name_menu(
+                                self.menus.opening_record_book().show_customer_name_menu(
                                     ctx,
                                     click_point,
                                     &self.kosuzu_memory,
@@ -1296,7 +1882,7 @@ impl TaskTable {
                                 .current_page_is_borrowing_signed()
                                 != RecordBookLockStatus::BorrowingLocked
                             {
-                                self.record_book_menu.show_date_menu(
+                                self.menus.opening_record_book().show_date_menu(
                                     ctx,
                                     click_point,
                                     self.today.clone(),
@@ -1310,7 +1896,7 @@ impl TaskTable {
                                     let return_date =
                                         self.borrowing_record_book.get_current_page_return_date();
                                     if let Some(return_date) = return_date {
-                                        self.record_book_menu.show_date_check_menu(
+                                        self.menus.opening_record_book().show_date_check_menu(
                                             ctx,
                                             click_point,
                                             self.today.clone(),
@@ -1350,7 +1936,8 @@ impl TaskTable {
             .silhouette
             .contains_character_silhouette(ctx.context, rpoint)
         {
-            self.customer_silhouette_menu
+            self.menus
+                .opening_customer()
                 .show_customer_question_menu(ctx, click_point, t);
             true
         } else {
@@ -1369,18 +1956,21 @@ impl TaskTable {
         click_point: numeric::Point2f,
         t: Clock,
     ) -> bool {
-        let rpoint = self.desk.canvas.relative_point(click_point);
+        let rpoint = self.desk.desk_point(click_point);
+        let mut memo_chip = None;
+        let mut clicked = false;
 
         for dobj in self.desk.get_desk_objects_list().iter().rev() {
-            if dobj.get_object().contains(ctx.context, rpoint) {
+            if dobj.contains_rotated(ctx.context, rpoint) {
+                let object_point = dobj.unrotated_point(ctx.context, rpoint);
                 let dobj_ref = &dobj.get_object();
                 let obj_type = dobj_ref.get_type();
-                let hold_data = dobj_ref.click_hold_data(ctx.context, rpoint);
+                let hold_data = dobj_ref.click_hold_data(ctx.context, object_point);
 
                 match obj_type {
                     OnDeskType::Book => match hold_data {
                         HoldData::BookName(info) => {
-                            self.on_desk_menu.show_desk_book_menu(
+                            self.menus.opening_on_desk().show_desk_book_menu(
                                 ctx,
                                 click_point,
                                 info.clone(),
@@ -1389,53 +1979,462 @@ impl TaskTable {
                         }
                         _ => panic!(""),
                     },
+                    OnDeskType::MemoPad => {
+                        if hold_data != HoldData::None {
+                            memo_chip = Some(hold_data);
+                        }
+                    }
                     _ => (),
                 }
 
-                return true;
+                clicked = true;
+                break;
             }
         }
 
-        false
-    }
+        if let Some(hold_data) = memo_chip {
+            self.memorize_memo_chip(ctx, hold_data, click_point, t);
+        }
 
-    pub fn get_target_page_book_condition_eval_report(&self) -> Option<&BookConditionEvalReport> {
-        self.current_page_book_condition_report.as_ref()
+        clicked
     }
 
-    fn try_show_menus<'a>(
+    ///
+    /// 覚書の札を小鈴に記憶させる
+    ///
+    fn memorize_memo_chip<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
+        hold_data: HoldData,
         click_point: numeric::Point2f,
         t: Clock,
     ) {
-        // 既に表示されている場合は、メニューを消して終了
-        if self.record_book_menu.is_some_menu_opened() {
-            self.record_book_menu.close_all(t);
-            return ();
+        match &hold_data {
+            HoldData::BookName(book_info) => {
+                self.info_panel.add_book_info(ctx, book_info.clone(), click_point, t);
+                self.kosuzu_memory.add_book_info(book_info.clone());
+            }
+            HoldData::CustomerName(name) => {
+                self.kosuzu_memory.add_customer_name(name.clone());
+                self.info_panel.set_customer_name(ctx, name.clone());
+            }
+            HoldData::Date(date) => self.kosuzu_memory.add_date(date.clone()),
+            _ => return,
         }
 
-        // 既に表示されている場合は、メニューを消して終了
-        if self.customer_silhouette_menu.is_some_menu_opened() {
-            self.customer_silhouette_menu.close_all(t);
-            return ();
+        self.kosuzu_phrase.insert_new_phrase(ctx, "メモの内容を覚えた", t);
+        self.hold_data_for_assist(ctx, hold_data);
+    }
+
+    ///
+    /// 開けた手紙の中身を郵便受けに渡す。礼状なら評判が上がる
+    ///
+    fn read_opened_letters<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        for id in self.desk.take_opened_letters() {
+            let today = self.today;
+            let letter = match ctx.take_save_data_mut().mail_box.open(id, &today) {
+                Some(letter) => letter,
+                None => continue,
+            };
+
+            let phrase = match letter.content {
+                LetterContent::CopyRequest { title, .. } => format!("「{}」の写本を頼まれた", title),
+                LetterContent::ThankYou { reputation } => {
+                    ctx.take_save_data_mut().suzunaan_status.add_reputation(reputation);
+                    "お礼の手紙だ".to_string()
+                }
+                LetterContent::StoryHook { .. } => format!("{}さんからの手紙だ", letter.sender),
+            };
+            self.kosuzu_phrase.insert_new_phrase(ctx, &phrase, t);
         }
+    }
 
-        if self.on_desk_menu.is_some_menu_opened() {
-            self.on_desk_menu.close_all(t);
-            return ();
+    ///
+    /// 書類箱に落とされた手紙を、仕舞った日の控えに残す
+    ///
+    fn archive_dropped_letters<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let letters = self.desk.take_archived_letters();
+        if letters.is_empty() {
+            return;
+        }
+
+        let today = self.today;
+        for letter in letters {
+            ctx.take_save_data_mut()
+                .paper_archive
+                .archive(letter, &today);
+        }
+        self.kosuzu_phrase
+            .insert_new_phrase(ctx, "手紙を書類箱に仕舞った", t);
+    }
+
+    ///
+    /// Escapeが押されたとき、開いているメニューを閉じる。閉じるものがあればtrueを返す
+    ///
+    pub fn close_menus_by_escape(&mut self, t: Clock) -> bool {
+        self.menus.close_by_escape(t)
+    }
+
+    ///
+    /// 机の上の操作をキーで行う。キーを使ったらtrueを返す
+    ///
+    pub fn desk_shortcut_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        shortcut: DeskShortcut,
+        t: Clock,
+    ) -> bool {
+        match shortcut {
+            DeskShortcut::ChooseEntry(index) => self.choose_book_status_by_key(ctx, index, t),
+            DeskShortcut::NextCell => self.focus_next_record_book_cell(ctx, t),
+            DeskShortcut::HandOver => self.hand_over_dragging_by_key(ctx, t),
+            DeskShortcut::CancelDrag => self.cancel_dragging_by_key(ctx),
+        }
+    }
+
+    ///
+    /// 本の状態のメニューが開いていれば、index番目の選択肢を選んで閉じる
+    ///
+    fn choose_book_status_by_key<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        index: usize,
+        t: Clock,
+    ) -> bool {
+        if index >= BOOK_STATUS_CHOICES
+            || !self
+                .menus
+                .record_book_mut()
+                .is_opened(RecordBookMenuKind::BookStatus)
+        {
+            return false;
+        }
+
+        self.apply_book_status_choice(ctx, index);
+        self.menus.record_book_mut().close_book_status_menu(t);
+        true
+    }
+
+    ///
+    /// 帳簿の次の欄をクリックしたときと同じく、その欄のメニューを開く
+    ///
+    /// 今の客の用件では開けない欄は飛ばす
+    ///
+    fn focus_next_record_book_cell<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) -> bool {
+        if !self.record_book_is_staged {
+            return false;
         }
 
-        if self.current_customer_request.is_some() {
-            let record_book_lock_status = match self.current_customer_request.as_ref().unwrap() {
-                CustomerRequest::Borrowing(_) => self
-                    .borrowing_record_book
-                    .current_page_is_borrowing_signed(),
-                CustomerRequest::Returning(_) => self
-                    .borrowing_record_book
-                    .check_current_page_is_matched_with(self.current_customer_request.as_ref()),
+        let lock_status = match self.record_book_lock_status() {
+            Some(lock_status) => lock_status,
+            None => return false,
+        };
+        let books_table_rows = match self.borrowing_record_book.get_books_table_rows() {
+            Some(rows) => rows,
+            None => return false,
+        };
+        let borrowing = match self.current_customer_request.as_ref() {
+            Some(CustomerRequest::Borrowing(_)) => true,
+            _ => false,
+        };
+        let cells = record_book_tab_cells(borrowing, books_table_rows);
+
+        self.menus.record_book_mut().dismiss_all();
+
+        let mut focus = self.record_book_focus;
+        for _ in 0..cells.len() {
+            focus = next_cell_index(focus, cells.len());
+            let cell = cells[focus.unwrap()];
+            let point = match self.borrowing_record_book.get_cell_center(cell) {
+                Some(point) => point,
+                None => break,
             };
 
+            if cell.is_customer_info() {
+                self.try_show_menus_regarding_customer_info(ctx, point, &lock_status, t);
+            } else {
+                self.try_show_menus_regarding_book_info(ctx, point, &lock_status, t);
+            }
+
+            if self.menus.record_book_mut().is_some_menu_opened() {
+                break;
+            }
+        }
+
+        self.record_book_focus = focus;
+        ctx.process_utility.redraw();
+        true
+    }
+
+    ///
+    /// 机で掴んでいる物を、受け渡しの場所まで持っていって手を離す
+    ///
+    /// 渡せない物を掴んでいるときは何もしない
+    ///
+    fn hand_over_dragging_by_key<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) -> bool {
+        match self.desk.ref_dragging() {
+            Some(dragging) if !dragging.is_handover_locked() => (),
+            _ => return false,
+        }
+
+        let mut dragging = self.desk.release_dragging().unwrap();
+        self.apply_d2s_point_convertion(ctx.context, &mut dragging);
+
+        // 受け渡しの場所の縁に掛かっていると机へ落ちるので、内側へ収める
+        let area = dragging.get_object().get_drawing_area(ctx.context);
+        let sight_height = self.sight.canvas.get_drawing_area(ctx.context).h;
+        dragging
+            .get_object_mut()
+            .set_position(numeric::Point2f::new(area.x, sight_height - area.h - 1.0));
+
+        self.sight.insert_dragging(dragging);
+        self.unselect_dragging_object(ctx, t);
+        true
+    }
+
+    ///
+    /// 机で掴んでいる物を、掴む前の所へ戻す
+    ///
+    fn cancel_dragging_by_key<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> bool {
+        if !self.desk.cancel_dragging(ctx) {
+            return false;
+        }
+
+        self.drop_targets.clear();
+        ctx.process_utility.redraw();
+        true
+    }
+
+    ///
+    /// 場面を移るときなどに、開いているメニューを全て閉じる
+    ///
+    pub fn close_all_menus(&mut self, t: Clock) {
+        self.menus.close_all(t);
+    }
+
+    pub fn memo_is_editing(&self) -> bool {
+        self.desk.memo_is_editing()
+    }
+
+    pub fn manual_entry_is_editing(&self) -> bool {
+        self.manual_entry.is_editing()
+    }
+
+    ///
+    /// 帳簿の客の情報の欄をダブルクリックしたら、手で書き込む欄を開く
+    ///
+    /// 開いた場合、true, そうでなければ、false
+    ///
+    fn try_start_manual_entry<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        click_point: numeric::Point2f,
+        t: Clock,
+    ) -> bool {
+        if !self.record_book_is_staged
+            || !ctx.input_history.is_double_click(
+                ginput::mouse::MouseButton::Left,
+                MANUAL_ENTRY_DOUBLE_CLICK_TICKS,
+                8.0,
+            )
+        {
+            return false;
+        }
+
+        match self.current_customer_request.as_ref() {
+            Some(CustomerRequest::Borrowing(_)) => (),
+            _ => return false,
+        }
+
+        if self
+            .borrowing_record_book
+            .current_page_is_borrowing_signed()
+            == RecordBookLockStatus::BorrowingLocked
+        {
+            return false;
+        }
+
+        let placeholder = match self
+            .borrowing_record_book
+            .get_customer_info_frame_grid_position(ctx.context, click_point)
+        {
+            Some(grid_pos) if grid_pos == numeric::Vector2u::new(2, 1) => "お名前",
+            Some(grid_pos)
+                if grid_pos == numeric::Vector2u::new(1, 1)
+                    || grid_pos == numeric::Vector2u::new(0, 1) =>
+            {
+                "七月三十日"
+            }
+            _ => return false,
+        };
+
+        // 一度目のクリックで開いたメニューは閉じておく
+        self.menus.close_all(t);
+
+        self.manual_entry.set_placeholder(placeholder);
+        self.manual_entry.start_editing(click_point, "");
+        self.manual_entry_point = Some(click_point);
+        ctx.process_utility.redraw();
+
+        true
+    }
+
+    fn cancel_manual_entry<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.manual_entry.finish_editing();
+        self.manual_entry_point = None;
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 手で書いた文字を、開いたときの欄に書き込む
+    ///
+    fn commit_manual_entry<'a>(&mut self, ctx: &mut SuzuContext<'a>, text: String, t: Clock) {
+        let point = match self.manual_entry_point.take() {
+            Some(point) => point,
+            None => return,
+        };
+
+        if text.is_empty() {
+            return;
+        }
+
+        let grid_pos = match self
+            .borrowing_record_book
+            .get_customer_info_frame_grid_position(ctx.context, point)
+        {
+            Some(grid_pos) => grid_pos,
+            None => return,
+        };
+
+        if grid_pos == numeric::Vector2u::new(2, 1) {
+            self.borrowing_record_book
+                .insert_customer_name_data_to_customer_info(ctx.context, point, text);
+            self.record_book_edited(ctx);
+            return;
+        }
+
+        let maybe_rental_limit = memo_parser::parse_date(&text, &self.today)
+            .and_then(|date| Some((date, self.today.rental_limit_type(&date)?)));
+        match maybe_rental_limit {
+            Some((date, rental_limit)) => {
+                self.borrowing_record_book
+                    .insert_date_data_to_customer_info(ctx, point, date, rental_limit);
+                self.record_book_edited(ctx);
+            }
+            None => {
+                log_debug!("manual entry is not a valid date: {}", text);
+                self.insert_kosuzu_message_set(ctx, "その日付では書けない", t);
+            }
+        }
+    }
+
+    ///
+    /// 手で書き込む欄を開いていれば、キー入力を渡す
+    ///
+    /// 開いていなければ、false
+    ///
+    pub fn manual_entry_key_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        key: PaletteKey,
+        t: Clock,
+    ) -> bool {
+        if !self.manual_entry.is_editing() {
+            return false;
+        }
+
+        match self.manual_entry.key_event(key) {
+            TextInputEvent::Committed(text) => self.commit_manual_entry(ctx, text, t),
+            TextInputEvent::Canceled => self.manual_entry_point = None,
+            TextInputEvent::Changed | TextInputEvent::Nothing => (),
+        }
+
+        ctx.process_utility.redraw();
+        true
+    }
+
+    pub fn record_book_search_is_editing(&self) -> bool {
+        self.borrowing_record_book.search_is_editing()
+    }
+
+    ///
+    /// 帳簿の検索の欄を開いていれば、キー入力を渡す
+    ///
+    pub fn record_book_search_key_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        key: PaletteKey,
+        t: Clock,
+    ) -> bool {
+        self.borrowing_record_book.search_key_event(ctx, key, t)
+    }
+
+    pub fn memo_key_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        if self.desk.memo_key_event(key) {
+            ctx.process_utility.redraw();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn desk_selected_count(&self) -> usize {
+        self.desk.selected_count()
+    }
+
+    pub fn desk_has_selected_bundle(&self) -> bool {
+        self.desk.has_selected_bundle()
+    }
+
+    ///
+    /// 机の上で選んだ書類を束ねる
+    ///
+    pub fn bundle_desk_selection<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) -> bool {
+        if self.desk.bundle_selection(ctx, t) {
+            ctx.process_utility.redraw();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn unbundle_desk_selection<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> bool {
+        self.desk.unbundle_selection(ctx)
+    }
+
+    pub fn get_target_page_book_condition_eval_report(&self) -> Option<&BookConditionEvalReport> {
+        self.current_page_book_condition_report.as_ref()
+    }
+
+    ///
+    /// 今の客の用件から見た、帳簿の今の頁の状態。客が居なければNone
+    ///
+    fn record_book_lock_status(&self) -> Option<RecordBookLockStatus> {
+        let lock_status = match self.current_customer_request.as_ref()? {
+            CustomerRequest::Borrowing(_) => self
+                .borrowing_record_book
+                .current_page_is_borrowing_signed(),
+            CustomerRequest::Returning(_) => self
+                .borrowing_record_book
+                .check_current_page_is_matched_with(self.current_customer_request.as_ref()),
+        };
+
+        Some(lock_status)
+    }
+
+    fn try_show_menus<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        click_point: numeric::Point2f,
+        t: Clock,
+    ) {
+        // 既に表示されている場合は、メニューを消して終了
+        if self.menus.is_some_menu_opened() {
+            self.menus.close_all(t);
+            return ();
+        }
+
+        if let Some(record_book_lock_status) = self.record_book_lock_status() {
             if self.try_show_menus_regarding_book_info(
                 ctx,
                 click_point,
@@ -1482,8 +2481,30 @@ impl TaskTable {
         y: f32,
     ) {
         let rpoint = self.canvas.relative_point(point);
+
+        // 机の物を掴んでいれば、Shiftを押しながらのホイールで回す
+        if self.desk.has_dragging()
+            && ginput::keyboard::is_mod_active(ctx.context, ginput::keyboard::KeyMods::SHIFT)
+        {
+            self.desk.rotate_dragging(ctx, y * DESK_WHEEL_ROTATE_STEP);
+            return;
+        }
+
+        // 帳簿を開いていなければ、机の上はホイールで拡大する
+        if !self.borrowing_record_book.is_visible() && self.desk.zoom_by_wheel(ctx, rpoint, y) {
+            return;
+        }
+
         self.sight.mouse_wheel_event(ctx, rpoint, x, y);
     }
+
+    ///
+    /// 中ボタンで掴んでいる間は、机を動かす
+    ///
+    pub fn pan_desk<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let rpoint = self.canvas.relative_point(point);
+        self.desk.pan_to(ctx, rpoint);
+    }
 }
 
 impl DrawableComponent for TaskTable {
@@ -1495,6 +2516,8 @@ impl DrawableComponent for TaskTable {
             self.sight.draw(ctx).unwrap();
             self.desk.draw(ctx).unwrap();
             self.shelving_box.draw(ctx).unwrap();
+            self.drop_targets.draw(ctx).unwrap();
+            self.desk_dim_panel.draw(ctx).unwrap();
 
             if let Some(staging_object) = self.staging_object.as_mut() {
                 staging_object.draw(ctx)?;
@@ -1507,10 +2530,10 @@ impl DrawableComponent for TaskTable {
             self.appearance_frame.draw(ctx)?;
 
             self.borrowing_record_book.draw(ctx)?;
+            self.manual_entry.draw(ctx)?;
             self.kosuzu_phrase.draw(ctx)?;
-            self.customer_silhouette_menu.draw(ctx)?;
-            self.record_book_menu.draw(ctx)?;
-            self.on_desk_menu.draw(ctx)?;
+            self.menus.draw(ctx)?;
+            self.tooltip.draw(ctx)?;
 
             sub_screen::pop_screen(ctx);
             self.canvas.draw(ctx).unwrap();
@@ -1553,10 +2576,20 @@ impl Clickable for TaskTable {
     fn button_down<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
-        _: Clock,
-        _: ggez::input::mouse::MouseButton,
+        t: Clock,
+        button: ggez::input::mouse::MouseButton,
         point: numeric::Point2f,
     ) {
+        if button == ginput::mouse::MouseButton::Middle {
+            self.desk.start_panning(self.canvas.relative_point(point));
+            return;
+        }
+
+        // 机の書類に手を付けたら、会話に寄せた構図を戻す
+        if self.sight.out_of_desk(self.canvas.relative_point(point)) {
+            self.sight.release_conversation_focus(t);
+        }
+
         self.select_dragging_object(ctx, point);
     }
 
@@ -1564,30 +2597,18 @@ impl Clickable for TaskTable {
         &mut self,
         ctx: &mut SuzuContext<'a>,
         t: Clock,
-        _: ggez::input::mouse::MouseButton,
+        button: ggez::input::mouse::MouseButton,
         point: numeric::Point2f,
     ) {
         let rpoint = self.canvas.relative_point(point);
 
-        // ボタンが離されたとき、メニュー外にあった場合、すべてのメニューを消す
-        if !self
-            .record_book_menu
-            .is_contains_any_menus(ctx.context, rpoint)
-        {
-            self.record_book_menu.close_all(t);
-        }
-
-        if !self
-            .customer_silhouette_menu
-            .is_contains_any_menus(ctx.context, rpoint)
-        {
-            self.customer_silhouette_menu.close_all(t);
+        if button == ginput::mouse::MouseButton::Middle {
+            self.desk.finish_panning();
+            return;
         }
 
-        if !self.on_desk_menu.is_contains_any_menus(ctx.context, rpoint) {
-            println!("close all!");
-            self.on_desk_menu.close_all(t);
-        }
+        // ボタンが離されたとき、メニュー外にあった場合、すべてのメニューを消す
+        self.menus.close_if_outside(ctx.context, rpoint, t);
     }
 
     fn on_click<'a>(
@@ -1599,8 +2620,17 @@ impl Clickable for TaskTable {
     ) {
         let rpoint = self.canvas.relative_point(point);
 
+        if self.manual_entry.is_editing() && !self.manual_entry.contains(rpoint) {
+            self.cancel_manual_entry(ctx);
+        }
+
+        if button == ginput::mouse::MouseButton::Left && self.try_start_manual_entry(ctx, rpoint, t)
+        {
+            return;
+        }
+
         if self.click_record_book_menu(ctx, button, rpoint, t) {
-            self.record_book_menu.close_all(t);
+            self.menus.record_book_mut().close_all(t);
             return;
         }
 
@@ -1624,15 +2654,21 @@ impl Clickable for TaskTable {
         }
 
         if self.click_customer_silhouette_menu(ctx, button, rpoint, t) {
-            self.customer_silhouette_menu.close_all(t);
+            self.menus.customer_mut().close_all(t);
+            return;
+        }
+
+        if self.click_book_inspection_menu(ctx, button, point, t) {
             return;
         }
 
         if self.click_desk_book_menu(ctx, button, point, t) {
-            self.on_desk_menu.close_all(t);
+            self.menus.on_desk_mut().close_desk_book_menu(t);
             return;
         }
 
+        self.desk.finish_memo_editing_outside(ctx, rpoint);
+
         // メニューをクリックしていない場合に、新しいメニュー表示処理を走らせる
         self.try_show_menus(ctx, rpoint, t);
 
@@ -1641,6 +2677,8 @@ impl Clickable for TaskTable {
         }
 
         if self.desk.click_handler(ctx, t, button, rpoint) {
+            self.read_opened_letters(ctx, t);
+
             // クリックハンドラが呼び出されたので終了
             return;
         }
@@ -1653,7 +2691,14 @@ impl Clickable for TaskTable {
     ) -> ggez::input::mouse::CursorIcon {
         let rpoint = self.canvas.relative_point(point);
 
-        let mut cursor_status = self.desk.check_mouse_cursor_status(ctx, rpoint);
+        let mut cursor_status = self
+            .borrowing_record_book
+            .check_mouse_cursor_status(ctx, rpoint);
+        if cursor_status != CursorIcon::Default {
+            return cursor_status;
+        }
+
+        cursor_status = self.desk.check_mouse_cursor_status(ctx, rpoint);
 
         if cursor_status != CursorIcon::Default {
             return cursor_status;
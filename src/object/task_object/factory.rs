@@ -1,5 +1,8 @@
 use torifune::core::Clock;
 
+use crate::core::mail::Letter;
+use crate::core::membership::MembershipCard;
+use crate::core::memo_parser::MemoParser;
 use crate::core::util;
 use crate::core::*;
 use crate::object::task_object::tt_sub_component::*;
@@ -14,11 +17,11 @@ pub fn create_dobj_book<'a>(
 ) -> TaskItem {
     let (texture, scale) = match book_info.size.as_str() {
         "大判本" => (
-            *util::random_select(LARGE_BOOK_TEXTURE.iter()).unwrap(),
+            *util::random_select(ctx.resource.rng(), LARGE_BOOK_TEXTURE.iter()).unwrap(),
             numeric::Vector2f::new(0.1, 0.1),
         ),
         "中判本" => (
-            *util::random_select(MIDDLE_BOOK_TEXTURE.iter()).unwrap(),
+            *util::random_select(ctx.resource.rng(), MIDDLE_BOOK_TEXTURE.iter()).unwrap(),
             numeric::Vector2f::new(0.15, 0.15),
         ),
         _ => panic!("invalid book size info"),
@@ -91,3 +94,148 @@ pub fn create_coins<'a>(ctx: &mut SuzuContext<'a>, mut price: u32, t: Clock) ->
 
     coins
 }
+
+pub fn create_popular_book_board<'a>(
+    ctx: &mut SuzuContext<'a>,
+    pos: numeric::Point2f,
+    ranking: &Vec<(String, u32)>,
+    t: Clock,
+) -> TaskItem {
+    let mut s_texture = UniTexture::new(
+        ctx.ref_texture(TextureID::Paper1),
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Vector2f::new(1.0, 1.0),
+        0.0,
+        0,
+    );
+    s_texture.fit_scale(ctx.context, numeric::Vector2f::new(50.0, 65.0));
+
+    let mut board = TaskBoard::new(
+        OnDeskTexture::new(ctx.context, s_texture, OnDeskType::PopularBookBoard),
+        PopularBookBoard::new(ctx, pos, ranking),
+        0,
+        true,
+        true,
+        DeskObjectType::SuzunaObject,
+        t,
+    );
+    board.enable_large();
+
+    TaskItem::Board(board)
+}
+
+pub fn create_memo_pad<'a>(ctx: &mut SuzuContext<'a>, pos: numeric::Point2f, t: Clock) -> TaskItem {
+    let mut s_texture = UniTexture::new(
+        ctx.ref_texture(TextureID::Paper1),
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Vector2f::new(1.0, 1.0),
+        0.0,
+        0,
+    );
+    s_texture.fit_scale(ctx.context, numeric::Vector2f::new(50.0, 75.0));
+
+    let parser = MemoParser::new(
+        ctx.resource.iter_available_books().cloned().collect(),
+        ctx.resource.iter_customers_name().cloned().collect(),
+    );
+    let today = ctx.take_save_data().date;
+
+    let mut memo = TaskMemo::new(
+        OnDeskTexture::new(ctx.context, s_texture, OnDeskType::MemoPad),
+        MemoPad::new(ctx, pos, parser, today),
+        0,
+        true,
+        true,
+        DeskObjectType::SuzunaObject,
+        t,
+    );
+    memo.enable_large();
+
+    TaskItem::Memo(memo)
+}
+
+pub fn create_desk_bundle<'a>(
+    ctx: &mut SuzuContext<'a>,
+    items: Vec<TaskItem>,
+    t: Clock,
+) -> TaskItem {
+    let mut s_texture = UniTexture::new(
+        ctx.ref_texture(TextureID::Paper1),
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Vector2f::new(1.0, 1.0),
+        0.0,
+        0,
+    );
+    s_texture.fit_scale(ctx.context, numeric::Vector2f::new(50.0, 75.0));
+
+    let mut bundle = TaskBundle::new(
+        OnDeskTexture::new(ctx.context, s_texture, OnDeskType::Bundle),
+        DeskBundle::new(ctx, items),
+        0,
+        true,
+        true,
+        DeskObjectType::SuzunaObject,
+        t,
+    );
+    bundle.enable_large();
+
+    TaskItem::Bundle(bundle)
+}
+
+pub fn create_letter<'a>(
+    ctx: &mut SuzuContext<'a>,
+    pos: numeric::Point2f,
+    letter: Letter,
+    t: Clock,
+) -> TaskItem {
+    let mut s_texture = UniTexture::new(
+        ctx.ref_texture(TextureID::Paper1),
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Vector2f::new(1.0, 1.0),
+        0.0,
+        0,
+    );
+    s_texture.fit_scale(ctx.context, numeric::Vector2f::new(50.0, 35.0));
+
+    let mut letter = TaskLetter::new(
+        OnDeskTexture::new(ctx.context, s_texture, OnDeskType::Letter),
+        LetterSheet::new(ctx, pos, letter),
+        0,
+        true,
+        true,
+        DeskObjectType::SuzunaObject,
+        t,
+    );
+    letter.enable_large();
+
+    TaskItem::Letter(letter)
+}
+
+pub fn create_membership_card<'a>(
+    ctx: &mut SuzuContext<'a>,
+    pos: numeric::Point2f,
+    card: MembershipCard,
+    t: Clock,
+) -> TaskItem {
+    let mut s_texture = UniTexture::new(
+        ctx.ref_texture(TextureID::Paper1),
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Vector2f::new(1.0, 1.0),
+        0.0,
+        0,
+    );
+    s_texture.fit_scale(ctx.context, numeric::Vector2f::new(50.0, 32.0));
+
+    let mut card = TaskCard::new(
+        OnDeskTexture::new(ctx.context, s_texture, OnDeskType::MembershipCard),
+        MembershipCardSheet::new(ctx, pos, card),
+        0,
+        true,
+        true,
+        DeskObjectType::CustomerObject,
+        t,
+    );
+    card.enable_large();
+
+    TaskItem::Card(card)
+}
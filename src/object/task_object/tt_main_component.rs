@@ -17,15 +17,33 @@ use torifune::{mintp, mintp_new, numeric};
 use crate::core::FontID;
 use crate::flush_delay_event;
 use crate::flush_delay_event_and_redraw_check;
+use crate::instrument;
+use crate::object::conversation_focus::{self, ConversationFocus};
+use crate::object::customer_emotion::{
+    CustomerEmotion, CustomerMood, CUSTOMER_EMOTION_FRAME_SPEED,
+};
+use crate::object::desk_camera::DeskCamera;
+use crate::object::highlight::{Highlight, Highlightable, InspectMode, FOCUS_HIGHLIGHT_COLOR};
 use crate::object::util_object::*;
 use crate::object::{effect, move_fn};
 use crate::scene::*;
 use crate::set_table_frame_cell_center;
 
+use super::factory;
 use super::tt_menu_component::*;
 use super::tt_sub_component::*;
 use super::Clickable;
+use crate::core::evaluation::RequestKind;
+use crate::core::mail::Letter;
+use crate::core::membership::MembershipCard;
+use crate::core::preferences::TextOrientation;
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::voice_blip::{balloon_blip_schedule, VoiceArchetype};
 use crate::core::*;
+use crate::object::tween::{tween, Easing, Tween};
+use crate::{log_debug, log_trace};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
 
 pub enum TaskTableStagingObject {
     BorrowingRecordBook(BorrowingRecordBook),
@@ -74,16 +92,38 @@ impl DrawableComponent for TaskTableStagingObject {
     }
 }
 
+///
+/// # 机の上
+///
+/// ## group_dragging
+/// まとめて掴んでいる選択中の物と、掴んだ点からの位置
+///
+/// ## drag_origin
+/// 掴む前の所。一つだけ掴んだときはその物の位置、まとめて掴んだときは掴んだ点
+///
+/// ## rubber_band
+/// 範囲選択の始点と終点
+///
 pub struct DeskObjects {
     pub canvas: SubScreen,
     pub desk_objects: DeskObjectContainer,
     pub dragging: Option<TaskItem>,
+    group_dragging: Vec<(TaskItem, numeric::Vector2f)>,
+    drag_origin: Option<numeric::Point2f>,
+    rubber_band: Option<(numeric::Point2f, numeric::Point2f)>,
     pub table_texture: SimpleObject,
     money_box: MovableWrap<MoneyBox>,
+    archive_drawer: ArchiveDrawer,
+    archived_letters: Vec<Letter>,
     event_list: DelayEventList<Self>,
     appearance_frame: TileBatchFrame,
     money_box_is_pulled: bool,
     draw_request: DrawRequest,
+    desk_area: numeric::Rect,
+    desk_cat: Option<DeskCat>,
+    camera: DeskCamera,
+    panning_point: Option<numeric::Point2f>,
+    inspect_mode: InspectMode,
 }
 
 impl DeskObjects {
@@ -107,6 +147,9 @@ impl DeskObjects {
             ),
             desk_objects: desk_objects,
             dragging: None,
+            group_dragging: Vec::new(),
+            drag_origin: None,
+            rubber_band: None,
             table_texture: SimpleObject::new(
                 MovableUniTexture::new(
                     Box::new(UniTexture::new(
@@ -130,24 +173,110 @@ impl DeskObjects {
                 None,
                 0,
             ),
+            archive_drawer: ArchiveDrawer::new(
+                ctx,
+                numeric::Rect::new(20.0, rect.h - 120.0, 150.0, 100.0),
+                0,
+            ),
+            archived_letters: Vec::new(),
             event_list: DelayEventList::new(),
             appearance_frame: appr_frame,
             money_box_is_pulled: false,
             draw_request: DrawRequest::InitDraw,
+            desk_area: numeric::Rect::new(0.0, 0.0, rect.w, rect.h),
+            desk_cat: None,
+            camera: DeskCamera::new(numeric::Vector2f::new(rect.w, rect.h)),
+            panning_point: None,
+            inspect_mode: InspectMode::new(),
         }
     }
 
+    ///
+    /// TaskTableの上の点を、机の上の点に直す。拡大や移動の分も戻す
+    ///
+    pub fn desk_point(&self, point: numeric::Point2f) -> numeric::Point2f {
+        self.camera.to_world(self.canvas.relative_point(point))
+    }
+
+    ///
+    /// ホイールで、カーソルの下を中心に机を拡大する。机の外なら何もしない
+    ///
+    pub fn zoom_by_wheel<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        wheel: f32,
+    ) -> bool {
+        if !self.canvas.contains(point) {
+            return false;
+        }
+
+        if self
+            .camera
+            .zoom_at(self.canvas.relative_point(point), wheel)
+        {
+            self.draw_request = DrawRequest::Draw;
+            ctx.process_utility.redraw();
+        }
+
+        true
+    }
+
+    pub fn start_panning(&mut self, point: numeric::Point2f) {
+        if self.canvas.contains(point) {
+            self.panning_point = Some(point);
+        }
+    }
+
+    ///
+    /// 中ボタンで掴んだ所がpointに来るように、机を動かす
+    ///
+    pub fn pan_to<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let last = match self.panning_point {
+            Some(last) => last,
+            None => return,
+        };
+
+        if self
+            .camera
+            .pan_by(numeric::Vector2f::new(point.x - last.x, point.y - last.y))
+        {
+            self.draw_request = DrawRequest::Draw;
+            ctx.process_utility.redraw();
+        }
+        self.panning_point = Some(point);
+    }
+
+    pub fn finish_panning(&mut self) {
+        self.panning_point = None;
+    }
+
+    pub fn is_panning(&self) -> bool {
+        self.panning_point.is_some()
+    }
+
+    pub fn reset_camera(&mut self) {
+        self.camera.reset();
+        self.draw_request = DrawRequest::Draw;
+    }
+
     fn drag_current_object<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let rpoint = self.desk_point(point);
+
         if let Some(obj) = &mut self.dragging {
 	    ctx.process_utility.redraw();
             let area = match obj {
                 TaskItem::Book(item) => item.get_large_object().get_drawing_area(ctx.context),
                 TaskItem::Texture(item) => item.get_large_object().get_drawing_area(ctx.context),
                 TaskItem::Coin(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Board(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Memo(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Bundle(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Letter(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Card(item) => item.get_large_object().get_drawing_area(ctx.context),
             };
 
             let canvas_size = self.canvas.get_drawing_size(ctx.context);
-            let rpoint = self.canvas.relative_point(point);
 
             if (canvas_size.x - rpoint.x) <= 0.0 {
                 return;
@@ -185,8 +314,91 @@ impl DeskObjects {
         }
     }
 
+    ///
+    /// 選択した物を、互いの位置を保ったまま動かす。まとまりごと机からはみ出さないように寄せる
+    ///
+    fn drag_group<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let canvas_size = self.canvas.get_drawing_size(ctx.context);
+        let rpoint = self.desk_point(point);
+
+        let mut left = std::f32::MAX;
+        let mut top = std::f32::MAX;
+        let mut right = std::f32::MIN;
+        let mut bottom = std::f32::MIN;
+        for (item, offset) in self.group_dragging.iter() {
+            let area = item.get_object().get_drawing_area(ctx.context);
+            let x = rpoint.x + offset.x;
+            let y = rpoint.y + offset.y;
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x + area.w);
+            bottom = bottom.max(y + area.h);
+        }
+
+        let shift = numeric::Vector2f::new(
+            if left < 0.0 {
+                -left
+            } else if right > canvas_size.x {
+                canvas_size.x - right
+            } else {
+                0.0
+            },
+            if top < 0.0 {
+                -top
+            } else if bottom > canvas_size.y {
+                canvas_size.y - bottom
+            } else {
+                0.0
+            },
+        );
+
+        for (item, offset) in self.group_dragging.iter_mut() {
+            item.get_object_mut().set_position(numeric::Point2f::new(
+                rpoint.x + offset.x + shift.x,
+                rpoint.y + offset.y + shift.y,
+            ));
+        }
+
+        ctx.process_utility.redraw();
+        self.draw_request = DrawRequest::Draw;
+    }
+
     pub fn dragging_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
-        self.drag_current_object(ctx, point);
+        if let Some((start, _)) = self.rubber_band {
+            self.rubber_band = Some((start, self.desk_point(point)));
+            ctx.process_utility.redraw();
+            self.draw_request = DrawRequest::Draw;
+        } else if !self.group_dragging.is_empty() {
+            self.drag_group(ctx, point);
+        } else {
+            self.drag_current_object(ctx, point);
+        }
+    }
+
+    ///
+    /// 選択中の物を全て机から取り上げ、まとめて掴む
+    ///
+    fn start_group_dragging<'a>(&mut self, ctx: &mut SuzuContext<'a>, rpoint: numeric::Point2f) {
+        let (selected, rest): (Vec<TaskItem>, Vec<TaskItem>) = self
+            .desk_objects
+            .get_raw_container_mut()
+            .drain(..)
+            .partition(|item| item.is_selected());
+        *self.desk_objects.get_raw_container_mut() = rest;
+
+        self.group_dragging = selected
+            .into_iter()
+            .map(|mut item| {
+                item.as_movable_object_mut().override_move_func(None, 0);
+                item.get_object_mut().start_dragging(ctx);
+                let position = item.get_object().get_position();
+                let offset = numeric::Vector2f::new(position.x - rpoint.x, position.y - rpoint.y);
+                (item, offset)
+            })
+            .collect();
+        self.drag_origin = Some(rpoint);
+
+        self.draw_request = DrawRequest::Draw;
     }
 
     pub fn select_dragging_object<'a>(
@@ -197,7 +409,7 @@ impl DeskObjects {
         let mut dragging_object_index = 0;
         let mut drag_start = false;
 
-        let rpoint = self.canvas.relative_point(point);
+        let rpoint = self.desk_point(point);
 
         // オブジェクトは深度が深い順にソートされているので、
         // 逆順から検索していくことで、最も手前に表示されているオブジェクトを
@@ -209,7 +421,7 @@ impl DeskObjects {
             .rev()
             .enumerate()
         {
-            if obj.get_object().contains(ctx.context, rpoint) {
+            if obj.contains_rotated(ctx.context, rpoint) {
                 obj.as_movable_object_mut().override_move_func(None, 0);
                 dragging_object_index = self.desk_objects.len() - index - 1;
                 drag_start = true;
@@ -218,6 +430,16 @@ impl DeskObjects {
         }
 
         if drag_start {
+            // 選択中の物を掴んだときは、選択した物をまとめて動かす
+            if self.desk_objects.get_raw_container()[dragging_object_index].is_selected()
+                && self.selected_count() > 1
+            {
+                self.start_group_dragging(ctx, rpoint);
+                return;
+            }
+
+            self.clear_selection();
+
             // 元々、最前面に表示されていたオブジェクトのdepthに設定する
             let mut dragging = self
                 .desk_objects
@@ -225,11 +447,17 @@ impl DeskObjects {
                 .swap_remove(dragging_object_index);
 
             dragging.get_object_mut().start_dragging(ctx);
+            self.drag_origin = Some(dragging.get_object().get_position());
 
             let object_area = match &dragging {
                 TaskItem::Book(item) => item.get_large_object().get_drawing_area(ctx.context),
                 TaskItem::Texture(item) => item.get_large_object().get_drawing_area(ctx.context),
                 TaskItem::Coin(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Board(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Memo(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Bundle(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Letter(item) => item.get_large_object().get_drawing_area(ctx.context),
+                TaskItem::Card(item) => item.get_large_object().get_drawing_area(ctx.context),
             };
 
             dragging.set_drag_point(numeric::Vector2f::new(
@@ -241,6 +469,65 @@ impl DeskObjects {
 
             self.desk_objects.sort_with_depth();
             self.draw_request = DrawRequest::Draw;
+        } else if self.canvas.contains(point) {
+            // 何も無い所から引いたときは、範囲選択を始める
+            self.clear_selection();
+            self.rubber_band = Some((rpoint, rpoint));
+        }
+    }
+
+    ///
+    /// 銭箱が引き出されていれば、机の上での銭箱の範囲を返す
+    ///
+    pub fn money_box_drop_area(&self, ctx: &mut ggez::Context) -> Option<numeric::Rect> {
+        if self.money_box_is_pulled {
+            Some(
+                self.camera
+                    .rect_to_local(self.money_box.get_drawing_area(ctx)),
+            )
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// 机の上での書類箱の範囲
+    ///
+    pub fn archive_drop_area(&self, ctx: &mut ggez::Context) -> numeric::Rect {
+        self.camera
+            .rect_to_local(self.archive_drawer.get_drawing_area(ctx))
+    }
+
+    ///
+    /// 書類箱に仕舞った手紙を取り出す
+    ///
+    pub fn take_archived_letters(&mut self) -> Vec<Letter> {
+        std::mem::replace(&mut self.archived_letters, Vec::new())
+    }
+
+    fn try_insert_archive_drawer<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        item: TaskItem,
+    ) -> Option<TaskItem> {
+        if !ArchiveDrawer::is_acceptable_for_archive(&item) {
+            return Some(item);
+        }
+
+        // 便箋は大きいので、真ん中が箱の上にあれば仕舞う
+        let drawer_area = self.archive_drawer.get_drawing_area(ctx.context);
+        if !drawer_area.contains(item.get_object().get_center(ctx.context)) {
+            return Some(item);
+        }
+
+        match item {
+            TaskItem::Letter(letter) => {
+                self.archived_letters
+                    .push(letter.get_large_object().get_letter().clone());
+                self.archive_drawer.count_up();
+                None
+            }
+            _ => Some(item),
         }
     }
 
@@ -275,17 +562,83 @@ impl DeskObjects {
         }
     }
 
+    ///
+    /// 手を離した物を、机の上の最前面に置く
+    ///
+    fn put_on_desk<'a>(&mut self, ctx: &mut SuzuContext<'a>, mut item: TaskItem) {
+        let min = self.desk_objects.get_minimum_depth();
+        item.get_object_mut().set_drawing_depth(min);
+        item.get_object_mut().finish_dragging(ctx);
+        self.desk_objects.change_depth_equally(1);
+
+        self.desk_objects.add_item(item);
+    }
+
+    ///
+    /// 掴んでいる物を、箱などに入れずに掴む前の所へ戻す。戻す物が無ければfalse
+    ///
+    pub fn cancel_dragging<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> bool {
+        let origin = match self.drag_origin {
+            Some(origin) => origin,
+            None => return false,
+        };
+
+        if let Some(mut item) = self.release_dragging() {
+            self.drag_origin = None;
+            item.get_object_mut().set_position(origin);
+            self.put_on_desk(ctx, item);
+        } else if !self.group_dragging.is_empty() {
+            for (item, offset) in self.group_dragging.iter_mut() {
+                item.get_object_mut().set_position(numeric::Point2f::new(
+                    origin.x + offset.x,
+                    origin.y + offset.y,
+                ));
+            }
+            self.unselect_dragging_object(ctx);
+        } else {
+            return false;
+        }
+
+        self.desk_objects.sort_with_depth();
+        self.draw_request = DrawRequest::Draw;
+        ctx.process_utility.redraw();
+        true
+    }
+
     pub fn unselect_dragging_object<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.drag_origin = None;
+
         if self.dragging.is_some() {
             let dragged = self.release_dragging().unwrap();
+            let dropped = match self.try_insert_money_box(ctx, dragged) {
+                Some(item) => self.try_insert_archive_drawer(ctx, item),
+                None => None,
+            };
 
-            if let Some(mut item) = self.try_insert_money_box(ctx, dragged) {
-                let min = self.desk_objects.get_minimum_depth();
-                item.get_object_mut().set_drawing_depth(min);
-                item.get_object_mut().finish_dragging(ctx);
-                self.desk_objects.change_depth_equally(1);
+            if let Some(item) = dropped {
+                self.put_on_desk(ctx, item);
+            }
 
-                self.desk_objects.add_item(item);
+            self.desk_objects.sort_with_depth();
+            self.draw_request = DrawRequest::Draw;
+            ctx.process_utility.redraw();
+        }
+
+        if let Some((start, end)) = self.rubber_band.take() {
+            let band = Self::band_rect(start, end);
+            for item in self.desk_objects.get_raw_container_mut().iter_mut() {
+                let area = item.rotated_drawing_area(ctx.context);
+                item.set_selected(band.w > 0.0 && band.h > 0.0 && band.overlaps(&area));
+            }
+
+            self.draw_request = DrawRequest::Draw;
+            ctx.process_utility.redraw();
+        }
+
+        if !self.group_dragging.is_empty() {
+            // 掴んだときの重なり順のまま、最前面に戻す
+            for (item, _) in std::mem::replace(&mut self.group_dragging, Vec::new()) {
+                self.put_on_desk(ctx, item);
             }
 
             self.desk_objects.sort_with_depth();
@@ -294,6 +647,143 @@ impl DeskObjects {
         }
     }
 
+    fn band_rect(start: numeric::Point2f, end: numeric::Point2f) -> numeric::Rect {
+        numeric::Rect::new(
+            start.x.min(end.x),
+            start.y.min(end.y),
+            (start.x - end.x).abs(),
+            (start.y - end.y).abs(),
+        )
+    }
+
+    pub fn clear_selection(&mut self) {
+        for item in self.desk_objects.get_raw_container_mut().iter_mut() {
+            item.set_selected(false);
+        }
+        self.draw_request = DrawRequest::Draw;
+    }
+
+    ///
+    /// object_typeの物を目立たせる。Noneなら目立たせるのを止める
+    ///
+    pub fn set_highlight_by_type(
+        &mut self,
+        object_type: DeskObjectType,
+        highlight: Option<Highlight>,
+    ) {
+        for item in self.desk_objects.get_raw_container_mut().iter_mut() {
+            if item.get_object_type() == object_type {
+                item.set_highlight(highlight);
+            }
+        }
+        self.draw_request = DrawRequest::Draw;
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.desk_objects
+            .get_raw_container()
+            .iter()
+            .filter(|item| item.is_selected())
+            .count()
+    }
+
+    pub fn has_selected_bundle(&self) -> bool {
+        self.desk_objects
+            .get_raw_container()
+            .iter()
+            .any(|item| match item {
+                TaskItem::Bundle(_) => item.is_selected(),
+                _ => false,
+            })
+    }
+
+    ///
+    /// 最前面に置く。深度を揃えてから加える
+    ///
+    fn put_on_top(&mut self, mut item: TaskItem) {
+        let min = self.desk_objects.get_minimum_depth();
+        item.get_object_mut().set_drawing_depth(min);
+        self.desk_objects.change_depth_equally(1);
+        self.desk_objects.add_item(item);
+    }
+
+    ///
+    /// 選択中の書類を一つの束にまとめる
+    ///
+    /// 束ねられる物が二つ未満なら、何もせずfalse
+    ///
+    pub fn bundle_selection<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) -> bool {
+        let bundleable = |item: &TaskItem| item.is_selected() && item.is_bundleable();
+
+        if self
+            .desk_objects
+            .get_raw_container()
+            .iter()
+            .filter(|item| bundleable(*item))
+            .count()
+            < 2
+        {
+            return false;
+        }
+
+        let (items, rest): (Vec<TaskItem>, Vec<TaskItem>) = self
+            .desk_objects
+            .get_raw_container_mut()
+            .drain(..)
+            .partition(|item| bundleable(item));
+        *self.desk_objects.get_raw_container_mut() = rest;
+
+        let items = items
+            .into_iter()
+            .map(|mut item| {
+                item.set_selected(false);
+                item
+            })
+            .collect();
+
+        let mut bundle = factory::create_desk_bundle(ctx, items, t);
+        bundle.set_selected(true);
+        self.put_on_top(bundle);
+
+        self.desk_objects.sort_with_depth();
+        self.draw_request = DrawRequest::Draw;
+        true
+    }
+
+    ///
+    /// 選択中の束を解き、中の書類を元の並びで机に戻す
+    ///
+    pub fn unbundle_selection<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> bool {
+        if !self.has_selected_bundle() {
+            return false;
+        }
+
+        let (bundles, rest): (Vec<TaskItem>, Vec<TaskItem>) = self
+            .desk_objects
+            .get_raw_container_mut()
+            .drain(..)
+            .partition(|item| match item {
+                TaskItem::Bundle(_) => item.is_selected(),
+                _ => false,
+            });
+        *self.desk_objects.get_raw_container_mut() = rest;
+
+        for bundle in bundles {
+            if let TaskItem::Bundle(mut bundle) = bundle {
+                let origin = bundle.get_object().get_position();
+                for mut item in bundle.get_large_object_mut().untie(origin) {
+                    item.set_selected(true);
+                    self.put_on_top(item);
+                }
+            }
+        }
+
+        self.desk_objects.sort_with_depth();
+        self.draw_request = DrawRequest::Draw;
+        ctx.process_utility.redraw();
+        true
+    }
+
     ///
     /// # 再描画要求有り
     ///
@@ -308,6 +798,18 @@ impl DeskObjects {
 
             p.as_movable_object_mut().move_with_func(t);
             p.as_effectable_object().effect(ctx.context, t);
+
+            if p.update_highlight(t) {
+                self.draw_request = DrawRequest::Draw;
+                ctx.process_utility.redraw();
+            }
+
+            if let TaskItem::Letter(item) = p {
+                if item.get_large_object_mut().update_unfold(ctx.context, t) {
+                    self.draw_request = DrawRequest::Draw;
+                    ctx.process_utility.redraw();
+                }
+            }
         }
 
         if !self.money_box.is_stop() {
@@ -315,6 +817,169 @@ impl DeskObjects {
             self.draw_request = DrawRequest::Draw;
             ctx.process_utility.redraw();
         }
+
+        if let Some(cat) = self.desk_cat.as_mut() {
+            if cat.update(t) {
+                self.draw_request = DrawRequest::Draw;
+                ctx.process_utility.redraw();
+            } else {
+                self.desk_cat = None;
+            }
+        }
+    }
+
+    ///
+    /// 本が上に載っておらず、動いてもいない紙なら、風で飛ぶ
+    ///
+    fn is_loose_paper(ctx: &mut ggez::Context, items: &[TaskItem], index: usize) -> bool {
+        let item = &items[index];
+        match item {
+            TaskItem::Memo(_) | TaskItem::Letter(_) | TaskItem::Card(_) => (),
+            _ => return false,
+        }
+
+        if !item.as_movable_object().is_stop() {
+            return false;
+        }
+
+        // 後ろにあるものほど手前に描かれている
+        let area = item.get_object().get_drawing_area(ctx);
+        !items[index + 1..].iter().any(|upper| {
+            if let TaskItem::Book(_) = upper {
+                upper.get_object().get_drawing_area(ctx).overlaps(&area)
+            } else {
+                false
+            }
+        })
+    }
+
+    ///
+    /// 戸口からの風で、押さえの無い紙を揺らしながら飛ばす。飛んだ紙の数を返す
+    ///
+    pub fn scatter_loose_papers<'a, R: rand::Rng>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        tuning: &desk_nuisance::DeskNuisanceTuning,
+        rng: &mut R,
+        t: Clock,
+    ) -> usize {
+        let items = self.desk_objects.get_raw_container();
+        let mut papers: Vec<usize> = (0..items.len())
+            .filter(|index| Self::is_loose_paper(ctx.context, items, *index))
+            .collect();
+        papers.shuffle(rng);
+        papers.truncate(tuning.gust_max_papers);
+
+        // 戸口は机の右手にあるので、風は左へ吹く
+        let wind = numeric::Vector2f::new(
+            -rng.gen_range(0.5_f32, 1.0) * tuning.gust_distance,
+            rng.gen_range(-0.3_f32, 0.3) * tuning.gust_distance,
+        );
+
+        for index in papers.iter() {
+            let item = &mut self.desk_objects.get_raw_container_mut()[*index];
+            let area = item.get_object().get_drawing_area(ctx.context);
+            let from = item.get_object().get_position();
+            let dest = desk_nuisance::scatter_destination(
+                from,
+                numeric::Vector2f::new(area.w, area.h),
+                self.desk_area,
+                wind * rng.gen_range(0.6_f32, 1.0),
+            );
+
+            item.as_movable_object_mut().override_move_func(
+                move_fn::tween_move(from, dest, GUST_TUMBLE_TIME, Easing::EaseOutCubic),
+                t,
+            );
+            item.as_effectable_object().add_effect(vec![effect::tumble(
+                GUST_TUMBLE_TIME,
+                0.5,
+                2.5,
+                t,
+            )]);
+        }
+
+        if !papers.is_empty() {
+            self.draw_request = DrawRequest::Draw;
+            ctx.process_utility.redraw();
+        }
+
+        papers.len()
+    }
+
+    ///
+    /// 猫が机に飛び乗り、帳簿以外の物を一つ机の端の方へ押しのける。押すものが無ければfalse
+    ///
+    pub fn push_by_cat<'a, R: rand::Rng>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        tuning: &desk_nuisance::DeskNuisanceTuning,
+        rng: &mut R,
+        t: Clock,
+    ) -> bool {
+        if self.desk_cat.is_some() {
+            return false;
+        }
+
+        let candidates: Vec<usize> = self
+            .desk_objects
+            .get_raw_container()
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                item.get_object_type() != DeskObjectType::BorrowRecordBook
+                    && item.as_movable_object().is_stop()
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let index = match candidates.choose(rng) {
+            Some(index) => *index,
+            None => return false,
+        };
+
+        let item = &mut self.desk_objects.get_raw_container_mut()[index];
+        let area = item.get_object().get_drawing_area(ctx.context);
+        let from = item.get_object().get_position();
+
+        // 机の真ん中から遠ざける向きに押す
+        let direction = if area.x + (area.w / 2.0) < self.desk_area.w / 2.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        let dest = desk_nuisance::scatter_destination(
+            from,
+            numeric::Vector2f::new(area.w, area.h),
+            self.desk_area,
+            numeric::Vector2f::new(direction * tuning.cat_push_distance, 0.0),
+        );
+
+        item.as_movable_object_mut().override_move_func(
+            move_fn::tween_move_after(
+                from,
+                dest,
+                DESK_CAT_ENTER_TIME,
+                DESK_CAT_PUSH_TIME,
+                Easing::EaseInOutQuad,
+            ),
+            t,
+        );
+
+        // 押す物の、押す向きと反対の脇に立って押す
+        let push_start = numeric::Point2f::new(
+            if direction > 0.0 {
+                area.x - DESK_CAT_BODY_RADIUS
+            } else {
+                area.right() + DESK_CAT_BODY_RADIUS
+            },
+            area.y + (area.h / 2.0),
+        );
+        self.desk_cat = Some(DeskCat::new(push_start, dest.x - from.x, direction, t));
+
+        self.draw_request = DrawRequest::Draw;
+        ctx.process_utility.redraw();
+
+        true
     }
 
     pub fn check_clicked_desk_object_type<'a>(
@@ -322,7 +987,7 @@ impl DeskObjects {
         ctx: &mut SuzuContext<'a>,
         point: numeric::Point2f,
     ) -> Option<OnDeskType> {
-        let rpoint = self.canvas.relative_point(point);
+        let rpoint = self.desk_point(point);
         let mut click_flag = false;
         let mut object_type: Option<OnDeskType> = None;
 
@@ -336,7 +1001,7 @@ impl DeskObjects {
             .rev()
             .enumerate()
         {
-            if obj.get_object().contains(ctx.context, rpoint) {
+            if obj.contains_rotated(ctx.context, rpoint) {
                 click_flag = true;
 
                 object_type = Some(obj.get_object().get_type());
@@ -372,6 +1037,26 @@ impl DeskObjects {
         self.dragging.is_some()
     }
 
+    pub fn set_inspect_mode(&mut self, inspect_mode: InspectMode) {
+        self.inspect_mode = inspect_mode;
+        self.draw_request = DrawRequest::Draw;
+    }
+
+    ///
+    /// 掴んでいる物をradだけ回す。何も掴んでいなければfalse
+    ///
+    pub fn rotate_dragging<'a>(&mut self, ctx: &mut SuzuContext<'a>, rad: f32) -> bool {
+        match self.dragging.as_mut() {
+            Some(dragging) => {
+                dragging.rotate_by(rad);
+                self.draw_request = DrawRequest::Draw;
+                ctx.process_utility.redraw();
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn insert_dragging(&mut self, obj: TaskItem) {
         let d = std::mem::replace(&mut self.dragging, Some(obj));
         if d.is_some() {
@@ -419,12 +1104,14 @@ impl DeskObjects {
             return false;
         }
 
-        let rpoint = self.canvas.relative_point(point);
+        let rpoint = self.desk_point(point);
 
         for dobj in self.desk_objects.get_raw_container_mut().iter_mut().rev() {
-            if dobj.get_object_mut().contains(ctx.context, rpoint) {
+            if dobj.contains_rotated(ctx.context, rpoint) {
                 self.draw_request = DrawRequest::Draw;
-                dobj.get_object_mut().button_up(ctx, t, button, rpoint);
+                let object_point = dobj.unrotated_point(ctx.context, rpoint);
+                dobj.get_object_mut()
+                    .button_up(ctx, t, button, object_point);
 
                 return true;
             }
@@ -473,23 +1160,19 @@ impl DeskObjects {
         point: numeric::Point2f,
     ) -> CursorIcon {
         if self.canvas.get_drawing_area(ctx).contains(mintp!(point)) {
-            let rpoint = self.canvas.relative_point(point);
+            let rpoint = self.desk_point(point);
 
             // オブジェクトは深度が深い順にソートされているので、
             // 逆順から検索していくことで、最も手前に表示されているオブジェクトを
             // 取り出すことができる
             for obj in self.desk_objects.get_raw_container_mut().iter_mut().rev() {
-                if obj
-                    .get_object()
-                    .get_drawing_area(ctx)
-                    .contains(mintp!(rpoint))
-                {
+                if obj.contains_rotated(ctx, rpoint) {
                     return CursorIcon::Grabbing;
                 }
             }
 
             if let Some(dragging) = self.dragging.as_ref() {
-                if dragging.get_object().contains(ctx, rpoint) {
+                if dragging.contains_rotated(ctx, rpoint) {
                     return CursorIcon::Grabbing;
                 }
             }
@@ -498,9 +1181,277 @@ impl DeskObjects {
         CursorIcon::Default
     }
 
+    ///
+    /// pointで一番手前に見えている物の説明。何かを掴んでいる間は出さない
+    ///
+    pub fn tooltip_text_at(
+        &self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> Option<String> {
+        if self.dragging.is_some()
+            || !self.group_dragging.is_empty()
+            || self.rubber_band.is_some()
+            || !self.canvas.get_drawing_area(ctx).contains(mintp!(point))
+        {
+            return None;
+        }
+
+        let rpoint = self.desk_point(point);
+        self.desk_objects
+            .get_raw_container()
+            .iter()
+            .rev()
+            .find(|obj| obj.contains_rotated(ctx, rpoint))
+            .and_then(|obj| obj.get_object().tooltip_text())
+    }
+
     pub fn get_desk_objects_list(&self) -> &Vec<TaskItem> {
         self.desk_objects.get_raw_container()
     }
+
+    fn get_memo_pad(&self) -> Option<&MemoPad> {
+        self.desk_objects
+            .get_raw_container()
+            .iter()
+            .find_map(|obj| match obj {
+                TaskItem::Memo(item) => Some(item.get_large_object()),
+                _ => None,
+            })
+    }
+
+    fn get_memo_pad_mut(&mut self) -> Option<&mut MemoPad> {
+        self.desk_objects
+            .get_raw_container_mut()
+            .iter_mut()
+            .find_map(|obj| match obj {
+                TaskItem::Memo(item) => Some(item.get_large_object_mut()),
+                _ => None,
+            })
+    }
+
+    ///
+    /// 開けたばかりの手紙の番号を取り出す
+    ///
+    pub fn take_opened_letters(&mut self) -> Vec<u32> {
+        self.desk_objects
+            .get_raw_container_mut()
+            .iter_mut()
+            .filter_map(|obj| match obj {
+                TaskItem::Letter(item) => item.get_large_object_mut().take_newly_opened(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    ///
+    /// クリックされた会員証があれば、その中身を取り出す
+    ///
+    pub fn take_membership_check_request(&mut self) -> Option<MembershipCard> {
+        self.desk_objects
+            .get_raw_container_mut()
+            .iter_mut()
+            .find_map(|obj| match obj {
+                TaskItem::Card(item) => item.get_large_object_mut().take_check_request(),
+                _ => None,
+            })
+    }
+
+    ///
+    /// 机の上の、同じ客の会員証を書き換える
+    ///
+    pub fn replace_membership_card<'a>(&mut self, ctx: &mut SuzuContext<'a>, card: MembershipCard) {
+        for obj in self.desk_objects.get_raw_container_mut().iter_mut() {
+            if let TaskItem::Card(item) = obj {
+                if item.get_large_object().get_card().holder == card.holder {
+                    item.get_large_object_mut().replace_card(ctx, card.clone());
+                }
+            }
+        }
+        self.draw_request = DrawRequest::Draw;
+    }
+
+    pub fn memo_is_editing(&self) -> bool {
+        self.get_memo_pad().map_or(false, |memo_pad| memo_pad.is_editing())
+    }
+
+    ///
+    /// 覚書の外をクリックしたときは、書き込みを終える
+    ///
+    pub fn finish_memo_editing_outside<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) {
+        let rpoint = self.desk_point(point);
+
+        if let Some(memo_pad) = self.get_memo_pad_mut() {
+            if memo_pad.is_editing() && !memo_pad.contains(ctx.context, rpoint) {
+                memo_pad.finish_editing();
+                self.draw_request = DrawRequest::Draw;
+            }
+        }
+    }
+
+    ///
+    /// 書き込み中の覚書にキー入力を渡す
+    ///
+    /// 書き込み中でなければ、false
+    ///
+    pub fn memo_key_event(&mut self, key: PaletteKey) -> bool {
+        let memo_pad = match self.get_memo_pad_mut() {
+            Some(memo_pad) if memo_pad.is_editing() => memo_pad,
+            _ => return false,
+        };
+
+        match key {
+            PaletteKey::Char(c) => memo_pad.push_char(c),
+            PaletteKey::Backspace => memo_pad.pop_char(),
+            PaletteKey::Enter | PaletteKey::Escape | PaletteKey::Toggle => {
+                memo_pad.finish_editing()
+            }
+            PaletteKey::Up | PaletteKey::Down | PaletteKey::Tab => (),
+        }
+
+        self.draw_request = DrawRequest::Draw;
+        true
+    }
+}
+
+impl DeskObjects {
+    ///
+    /// 範囲選択の矩形を描く。選択中の物は、それぞれを描くときに縁取る
+    ///
+    fn draw_selection(&self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let mut builder = ggraphics::MeshBuilder::new();
+        let mut empty = true;
+
+        if let Some((start, end)) = self.rubber_band {
+            let band = Self::band_rect(start, end);
+            shape::Rectangle::new(
+                band,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0x6688cc44),
+            )
+            .add_to_builder(&mut builder);
+            shape::Rectangle::new(
+                band,
+                ggraphics::DrawMode::stroke(1.0),
+                ggraphics::Color::from_rgba_u32(0x6688ccff),
+            )
+            .add_to_builder(&mut builder);
+            empty = false;
+        }
+
+        if empty {
+            return Ok(());
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())
+    }
+}
+
+///
+/// 風で飛んだ紙が揺れながら落ち着くまでの時間
+///
+const GUST_TUMBLE_TIME: Clock = 50;
+
+const DESK_CAT_ENTER_TIME: Clock = 30;
+const DESK_CAT_PUSH_TIME: Clock = 40;
+const DESK_CAT_LEAVE_TIME: Clock = 30;
+const DESK_CAT_BODY_RADIUS: f32 = 26.0;
+
+///
+/// # 机に飛び乗ってくる猫の影
+/// 机の奥から押す物の脇へ跳んできて、物と一緒に進み、また奥へ去る
+///
+/// ## facing
+/// 押す向き。右なら1.0、左なら-1.0
+///
+struct DeskCat {
+    path: Vec<Tween<numeric::Point2f>>,
+    position: numeric::Point2f,
+    facing: f32,
+}
+
+impl DeskCat {
+    fn new(push_start: numeric::Point2f, push_length: f32, facing: f32, t: Clock) -> Self {
+        let enter_from = numeric::Point2f::new(push_start.x - (facing * 80.0), -80.0);
+        let push_end = numeric::Point2f::new(push_start.x + push_length, push_start.y);
+        let leave_to = numeric::Point2f::new(push_end.x + (facing * 80.0), -80.0);
+
+        let push_at = t + DESK_CAT_ENTER_TIME;
+        let leave_at = push_at + DESK_CAT_PUSH_TIME;
+
+        DeskCat {
+            path: vec![
+                tween(
+                    enter_from,
+                    push_start,
+                    DESK_CAT_ENTER_TIME,
+                    Easing::EaseOutQuad,
+                    t,
+                ),
+                tween(
+                    push_start,
+                    push_end,
+                    DESK_CAT_PUSH_TIME,
+                    Easing::EaseInOutQuad,
+                    push_at,
+                ),
+                tween(
+                    push_end,
+                    leave_to,
+                    DESK_CAT_LEAVE_TIME,
+                    Easing::EaseInQuad,
+                    leave_at,
+                ),
+            ],
+            position: enter_from,
+            facing: facing,
+        }
+    }
+
+    ///
+    /// 位置を進める。去りきっていればfalse
+    ///
+    fn update(&mut self, t: Clock) -> bool {
+        let current = match self.path.iter().find(|segment| !segment.is_finished_at(t)) {
+            Some(segment) => segment,
+            None => return false,
+        };
+
+        self.position = current.value_at(t);
+        true
+    }
+
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let color = ggraphics::Color::from_rgba_u32(0x2a2220b0);
+        let at = |x: f32, y: f32| {
+            numeric::Point2f::new(self.position.x + (x * self.facing), self.position.y + y)
+        };
+
+        let mut builder = ggraphics::MeshBuilder::new();
+        // 胴、頭、耳、尾の順に丸を重ねる
+        let parts = [
+            (at(0.0, 0.0), DESK_CAT_BODY_RADIUS),
+            (at(-22.0, 4.0), 22.0),
+            (at(30.0, -16.0), 16.0),
+            (at(22.0, -30.0), 6.0),
+            (at(38.0, -30.0), 6.0),
+            (at(-46.0, -2.0), 5.0),
+            (at(-54.0, -10.0), 5.0),
+            (at(-58.0, -20.0), 5.0),
+        ];
+        for (center, radius) in parts.iter() {
+            shape::Circle::new(*center, *radius, 0.1, ggraphics::DrawMode::fill(), color)
+                .add_to_builder(&mut builder);
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())
+    }
 }
 
 impl DrawableComponent for DeskObjects {
@@ -510,18 +1461,45 @@ impl DrawableComponent for DeskObjects {
                 self.draw_request = DrawRequest::Skip;
                 sub_screen::stack_screen(ctx, &self.canvas);
 
+                // 机の上の物は、カメラの拡大と移動を掛けて描く
+                ggraphics::push_transform(ctx, Some(self.camera.to_matrix()));
+                ggraphics::apply_transformations(ctx)?;
+
                 self.table_texture.draw(ctx)?;
+                self.archive_drawer.draw(ctx)?;
 
+                let inspect_mode = self.inspect_mode;
                 for obj in self.desk_objects.get_raw_container_mut() {
-                    obj.get_object_mut().draw(ctx)?;
+                    let highlight = obj
+                        .drawing_highlight()
+                        .or_else(|| inspect_mode.highlight_for(obj.get_object().has_click_data()));
+                    obj.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
                 self.money_box.draw(ctx)?;
 
+                if let Some(cat) = self.desk_cat.as_mut() {
+                    cat.draw(ctx)?;
+                }
+
                 if let Some(d) = self.dragging.as_mut() {
-                    d.get_object_mut().draw(ctx)?;
+                    let highlight = d.drawing_highlight();
+                    d.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
+                }
+
+                for (item, _) in self.group_dragging.iter_mut() {
+                    let highlight = item.drawing_highlight();
+                    item.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
+                self.draw_selection(ctx)?;
+
+                ggraphics::pop_transform(ctx);
+                ggraphics::apply_transformations(ctx)?;
+
                 self.appearance_frame.draw(ctx)?;
 
                 sub_screen::pop_screen(ctx);
@@ -559,8 +1537,16 @@ impl DrawableObject for DeskObjects {
     impl_drawable_object_for_wrapped! {canvas}
 }
 
+///
+/// ## character
+/// 客の影。顔付きごとのアニメーションを持つ
+///
+/// ## mood
+/// 待たせた時間と受け渡しの結果から決まる、客の機嫌
+///
 struct TaskSilhouette {
-    character: Option<SimpleObject>,
+    character: Option<TextureAnimation<CustomerEmotion>>,
+    mood: CustomerMood,
     name: Option<String>,
     canvas: SubScreen,
 }
@@ -569,6 +1555,7 @@ impl TaskSilhouette {
     pub fn new_empty(ctx: &mut ggez::Context, pos_rect: numeric::Rect) -> Self {
         TaskSilhouette {
             character: None,
+            mood: CustomerMood::new(),
             name: None,
             canvas: SubScreen::new(ctx, pos_rect, 0, ggraphics::Color::from_rgba_u32(0)),
         }
@@ -578,8 +1565,9 @@ impl TaskSilhouette {
         self.character.is_some()
     }
 
-    pub fn change_character(&mut self, character: SimpleObject) -> &mut Self {
+    pub fn change_character(&mut self, character: TextureAnimation<CustomerEmotion>) -> &mut Self {
         self.character = Some(character);
+        self.mood = CustomerMood::new();
         self
     }
 
@@ -587,20 +1575,63 @@ impl TaskSilhouette {
         self.name = Some(name);
         self
     }
-    
+
+    ///
+    /// 待たせている時間を渡す。顔付きが変わったときはtrueを返す
+    ///
+    /// 反応を再生している間は、再生し終えた後に戻る顔付きだけを差し替える
+    ///
+    pub fn update_waiting(&mut self, waiting: Clock) -> bool {
+        let resting = match self.mood.update_waiting(waiting) {
+            Some(resting) => resting,
+            None => return false,
+        };
+
+        if let Some(character) = self.character.as_mut() {
+            if character.get_current_mode().is_reaction() {
+                character.set_next_mode(resting);
+            } else {
+                character.change_mode(resting, AnimationType::Loop, resting);
+            }
+        }
+
+        true
+    }
+
+    ///
+    /// 受け渡しへの反応を一度だけ再生する
+    ///
+    pub fn react(&mut self, correct: bool) {
+        let resting = self.mood.get_resting();
+
+        if let Some(character) = self.character.as_mut() {
+            character.change_mode(
+                CustomerEmotion::reaction(correct),
+                AnimationType::OneShot,
+                resting,
+            );
+        }
+    }
+
     pub fn run_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) -> DrawRequest {
-	let mut draw_request = DrawRequest::Skip;
-	
-        if let Some(obj) = self.character.as_mut() {
+        let mut draw_request = DrawRequest::Skip;
+
+        if let Some(character) = self.character.as_mut() {
+            let obj = character.get_mut_object();
             if !obj.is_stop() || !obj.is_empty_effect() {
                 draw_request = DrawRequest::Draw;
             }
             obj.move_with_func(t);
             obj.effect(ctx.context, t);
+
+            if t % CUSTOMER_EMOTION_FRAME_SPEED == 0 {
+                character.try_next_frame(t);
+                draw_request = DrawRequest::Draw;
+            }
         }
 
-	draw_request
-    }    
+        draw_request
+    }
 }
 
 impl DrawableComponent for TaskSilhouette {
@@ -609,7 +1640,24 @@ impl DrawableComponent for TaskSilhouette {
             sub_screen::stack_screen(ctx, &self.canvas);
 
             if let Some(character) = &mut self.character {
-                character.draw(ctx)?;
+                let emotion = character.get_current_mode();
+                let offset = emotion.pose_offset(character.get_current_frame_index());
+                let obj = character.get_mut_object();
+
+                // 顔付きの揺れと色は、描く間だけ掛ける
+                let position = obj.get_position();
+                let color = obj.get_color();
+                let mut tint = ggraphics::Color::from_rgba_u32(emotion.tint());
+                tint.a = color.a;
+
+                obj.set_position(numeric::Point2f::new(
+                    position.x + offset.x,
+                    position.y + offset.y,
+                ));
+                obj.set_color(tint);
+                obj.draw(ctx)?;
+                obj.set_position(position);
+                obj.set_color(color);
             }
 
             sub_screen::pop_screen(ctx);
@@ -654,7 +1702,11 @@ impl Clickable for TaskSilhouette {
         point: numeric::Point2f,
     ) -> ggez::input::mouse::CursorIcon {
         if let Some(character) = &self.character {
-            if character.get_drawing_area(ctx).contains(mintp!(point)) {
+            if character
+                .get_object()
+                .get_drawing_area(ctx)
+                .contains(mintp!(point))
+            {
                 return CursorIcon::Grab;
             }
         }
@@ -676,9 +1728,17 @@ impl OnDesk for TaskSilhouette {
         }
     }
 
+    fn has_click_data(&self) -> bool {
+        self.character.is_some() && self.name.is_some()
+    }
+
     fn get_type(&self) -> OnDeskType {
         OnDeskType::Silhouette
     }
+
+    fn tooltip_text(&self) -> Option<String> {
+        self.name.clone()
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -726,10 +1786,59 @@ impl CustomerDialogue {
     }
 }
 
+///
+/// # 客が帰る際の挨拶の調子
+/// 接客の出来（状態評価のミス数）によって変化する
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CustomerFarewellTone {
+    Satisfied,
+    Neutral,
+    Displeased,
+}
+
+impl CustomerFarewellTone {
+    pub fn from_mistakes(mistakes: usize) -> Self {
+        match mistakes {
+            0 => CustomerFarewellTone::Satisfied,
+            1 => CustomerFarewellTone::Neutral,
+            _ => CustomerFarewellTone::Displeased,
+        }
+    }
+
+    pub fn phrase(&self) -> &str {
+        match self {
+            CustomerFarewellTone::Satisfied => "ありがとう。また来るわ",
+            CustomerFarewellTone::Neutral => "どうも",
+            CustomerFarewellTone::Displeased => "しっかりしてよね",
+        }
+    }
+
+    ///
+    /// # 心付けの確率（1/n）
+    /// Noneの場合は心付けは発生しない
+    ///
+    pub fn tip_chance(&self) -> Option<u32> {
+        match self {
+            CustomerFarewellTone::Satisfied => Some(4),
+            CustomerFarewellTone::Neutral => Some(12),
+            CustomerFarewellTone::Displeased => None,
+        }
+    }
+
+    pub fn reputation_diff(&self) -> f32 {
+        match self {
+            CustomerFarewellTone::Satisfied => 0.5,
+            CustomerFarewellTone::Neutral => 0.0,
+            CustomerFarewellTone::Displeased => -0.5,
+        }
+    }
+}
+
 pub struct TextBalloon {
     canvas: SubScreen,
     back_canvas: SubScreen,
-    text: VerticalText,
+    text: OrientedText,
     phrase_type: TextBalloonPhraseType,
     text_balloon: shape::FramedLeadingRect,
 }
@@ -741,8 +1850,10 @@ impl TextBalloon {
         text: &str,
         phrase_type: TextBalloonPhraseType,
         font_info: FontInformation,
+        orientation: TextOrientation,
     ) -> Self {
-        let mut vtext = VerticalText::new(
+        let mut vtext = OrientedText::new(
+            orientation,
             text.to_string(),
             numeric::Point2f::new(0.0, 0.0),
             numeric::Vector2f::new(1.0, 1.0),
@@ -751,19 +1862,7 @@ impl TextBalloon {
             font_info,
         );
 
-        let vtext_size = vtext.get_drawing_size(ctx);
-
-        let balloon = shape::FramedLeadingRect::new(
-            ctx,
-            numeric::Rect::new(25.0, 0.0, vtext_size.x + 100.0, vtext_size.y + 50.0),
-            0.0,
-            2.0,
-            numeric::Vector2f::new(-25.0, 50.0),
-            numeric::Vector2f::new(15.0, 15.0),
-            ggraphics::Color::WHITE,
-            ggraphics::Color::from_rgb_u32(0x111111),
-            0,
-        );
+        let balloon = Self::new_balloon_shape(ctx, &vtext);
 
         let balloon_area = balloon.get_drawing_area();
         vtext.make_center(
@@ -794,20 +1893,10 @@ impl TextBalloon {
         text: &str,
         phrase_type: TextBalloonPhraseType,
     ) {
-        self.text.replace_text(text.to_string());
-        let vtext_size = self.text.get_drawing_size(ctx);
-
-        self.text_balloon = shape::FramedLeadingRect::new(
-            ctx,
-            numeric::Rect::new(25.0, 0.0, vtext_size.x + 100.0, vtext_size.y + 50.0),
-            0.0,
-            2.0,
-            numeric::Vector2f::new(-25.0, 50.0),
-            numeric::Vector2f::new(15.0, 15.0),
-            ggraphics::Color::WHITE,
-            ggraphics::Color::from_rgb_u32(0x111111),
-            0,
-        );
+        instrument!(instrument::ProbeCategory::TextLayout, {
+            self.text.replace_text(text.to_string());
+            self.text_balloon = Self::new_balloon_shape(ctx, &self.text);
+        });
 
         let balloon_area = self.text_balloon.get_drawing_area();
         self.text.make_center(
@@ -824,6 +1913,30 @@ impl TextBalloon {
     pub fn get_phrase_type(&self) -> &TextBalloonPhraseType {
         &self.phrase_type
     }
+
+    ///
+    /// 文字列の大きさに合わせて吹き出しを作る
+    /// 横書きは行が横に伸びるので、左右の余白を縦書きより詰める
+    ///
+    fn new_balloon_shape(ctx: &mut ggez::Context, text: &OrientedText) -> shape::FramedLeadingRect {
+        let text_size = text.get_drawing_size(ctx);
+        let padding = match text.get_orientation() {
+            TextOrientation::Vertical => numeric::Vector2f::new(100.0, 50.0),
+            TextOrientation::Horizontal => numeric::Vector2f::new(50.0, 40.0),
+        };
+
+        shape::FramedLeadingRect::new(
+            ctx,
+            numeric::Rect::new(25.0, 0.0, text_size.x + padding.x, text_size.y + padding.y),
+            0.0,
+            2.0,
+            numeric::Vector2f::new(-25.0, 50.0),
+            numeric::Vector2f::new(15.0, 15.0),
+            ggraphics::Color::WHITE,
+            ggraphics::Color::from_rgb_u32(0x111111),
+            0,
+        )
+    }
 }
 
 impl DrawableObject for TextBalloon {
@@ -935,13 +2048,23 @@ impl DrawableComponent for TextBalloon {
     }
 }
 
+///
+/// ## focus
+/// 客が話している間、背景と客の影をfocus_centerに向けて少し拡大する
+///
 pub struct SuzuMiniSightSilhouette {
     event_list: DelayEventList<Self>,
     background: UniTexture,
+    background_scale: numeric::Vector2f,
     silhouette: TaskSilhouette,
     text_balloon: EffectableWrap<MovableWrap<TextBalloon>>,
     customer_dialogue: CustomerDialogue,
     chat_box: ChatBox,
+    voice: VoiceArchetype,
+    focus: ConversationFocus,
+    focus_center: numeric::Point2f,
+    focus_highlight: Option<Highlight>,
+    inspect_mode: InspectMode,
     canvas: SubScreen,
 }
 
@@ -962,6 +2085,7 @@ impl SuzuMiniSightSilhouette {
                 numeric::Vector2f::new(22.0, 22.0),
                 ggraphics::Color::from_rgba_u32(0xff),
             ),
+            ctx.text_orientation(),
         ));
         text_balloon.hide();
 
@@ -974,19 +2098,27 @@ impl SuzuMiniSightSilhouette {
         );
         chat_box.add_message_as_mine(ctx, "いらっしゃいませ".to_string());
 
+        let silhouette_rect = numeric::Rect::new(0.0, 0.0, 350.0, 300.0);
+
         SuzuMiniSightSilhouette {
             event_list: DelayEventList::new(),
+            background_scale: background.get_scale(),
             background: background,
-            silhouette: TaskSilhouette::new_empty(
-                ctx.context,
-                numeric::Rect::new(0.0, 0.0, 350.0, 300.0),
-            ),
+            silhouette: TaskSilhouette::new_empty(ctx.context, silhouette_rect),
             text_balloon: EffectableWrap::new(
                 MovableWrap::new(text_balloon, None, 0),
                 vec![effect::fade_in(10, t)],
             ),
             customer_dialogue: CustomerDialogue::new(Vec::new(), Vec::new()),
             chat_box: chat_box,
+            voice: VoiceArchetype::Adult,
+            focus: ConversationFocus::new(),
+            focus_center: numeric::Point2f::new(
+                silhouette_rect.x + (silhouette_rect.w / 2.0),
+                silhouette_rect.y + (silhouette_rect.h / 2.0),
+            ),
+            focus_highlight: None,
+            inspect_mode: InspectMode::new(),
             canvas: SubScreen::new(
                 ctx.context,
                 numeric::Rect::new(0.0, 0.0, rect.w, rect.h),
@@ -1010,10 +2142,18 @@ impl SuzuMiniSightSilhouette {
         }
     }
 
-    fn replace_character(&mut self, chara: SimpleObject, name: String) {
+    fn replace_character(&mut self, chara: TextureAnimation<CustomerEmotion>, name: String) {
         self.silhouette.change_character(chara).update_name(name);
     }
 
+    pub fn update_customer_waiting(&mut self, waiting: Clock) -> bool {
+        self.silhouette.update_waiting(waiting)
+    }
+
+    pub fn react_customer(&mut self, correct: bool) {
+        self.silhouette.react(correct);
+    }
+
     pub fn get_text_balloon_phrase_type(&self) -> &TextBalloonPhraseType {
         &self.text_balloon.get_phrase_type()
     }
@@ -1021,12 +2161,13 @@ impl SuzuMiniSightSilhouette {
     pub fn new_customer_update(
         &mut self,
         _: &mut ggez::Context,
-        chara: SimpleObject,
+        chara: TextureAnimation<CustomerEmotion>,
         name: String,
         dialogue: CustomerDialogue,
         t: Clock,
     ) {
         self.customer_dialogue = dialogue;
+        self.voice = VoiceArchetype::from_name(&name);
 
         let mut delay_time = 0;
         loop {
@@ -1043,6 +2184,8 @@ impl SuzuMiniSightSilhouette {
                     silhouette
                         .text_balloon
                         .add_effect(vec![effect::fade_in(20, called)]);
+                    silhouette.schedule_voice_blips(&line, called);
+                    silhouette.focus.speak(called);
                     silhouette.chat_box.add_message_as_partner(ctx, line);
                 }),
                 t + delay_time,
@@ -1072,9 +2215,49 @@ impl SuzuMiniSightSilhouette {
             draw_request = DrawRequest::Draw;
         }
 
+        if self.focus.is_moving_at(t) {
+            self.apply_focus(t);
+            draw_request = DrawRequest::Draw;
+        }
+
         draw_request
     }
 
+    ///
+    /// 時刻tの寄り具合に合わせて、背景と客の影を拡大する
+    ///
+    fn apply_focus(&mut self, t: Clock) {
+        let zoom = self.focus.zoom_at(t);
+        // 背景も客の影も左上を原点に置いているので、寄せた後の位置は同じになる
+        let position = conversation_focus::zoom_position(
+            numeric::Point2f::new(0.0, 0.0),
+            self.focus_center,
+            zoom,
+        );
+
+        self.background.set_position(position);
+        self.background.set_scale(numeric::Vector2f::new(
+            self.background_scale.x * zoom,
+            self.background_scale.y * zoom,
+        ));
+
+        self.silhouette.set_position(position);
+        self.silhouette
+            .set_scale(numeric::Vector2f::new(zoom, zoom));
+    }
+
+    ///
+    /// 書類に手を付けたら、元の構図に戻し始める
+    ///
+    pub fn release_focus(&mut self, t: Clock) {
+        self.focus.release(t);
+        self.focus_highlight = None;
+    }
+
+    pub fn desk_dim_at(&self, t: Clock) -> f32 {
+        self.focus.desk_dim_at(t)
+    }
+
     pub fn replace_text(
         &mut self,
         ctx: &mut ggez::Context,
@@ -1085,6 +2268,19 @@ impl SuzuMiniSightSilhouette {
         self.text_balloon.appear();
     }
 
+    ///
+    /// 吹き出しの台詞を読む速さに合わせて、客の声を鳴らす
+    ///
+    fn schedule_voice_blips(&mut self, text: &str, now: Clock) {
+        let voice = self.voice;
+        for offset in balloon_blip_schedule(text, voice) {
+            self.event_list.add_event(
+                Box::new(move |_: &mut Self, ctx, _| ctx.play_voice_blip(voice)),
+                now + offset,
+            );
+        }
+    }
+
     pub fn insert_new_balloon_phrase(
         &mut self,
         text: String,
@@ -1093,11 +2289,18 @@ impl SuzuMiniSightSilhouette {
         now: Clock,
     ) {
         self.event_list.add(DelayEvent::new(
-            Box::new(move |silhouette, ctx, _| {
+            Box::new(move |silhouette, ctx, called| {
                 silhouette.replace_text(ctx.context, &text, phrase_type);
                 silhouette
                     .text_balloon
                     .add_effect(vec![effect::fade_in(20, now + delay_time)]);
+                silhouette.schedule_voice_blips(&text, called);
+                silhouette.focus.speak(called);
+                // 話している客の影を縁取る
+                silhouette.focus_highlight = Some(Highlight::outline(
+                    ggraphics::Color::from_rgba_u32(FOCUS_HIGHLIGHT_COLOR),
+                    called,
+                ));
             }),
             now + delay_time,
         ));
@@ -1121,6 +2324,32 @@ impl SuzuMiniSightSilhouette {
             .add_effect(vec![effect::fade_out(20, now)]);
     }
 
+    ///
+    /// 客の別れの挨拶を吹き出しに表示し、しばらくしてから消す
+    ///
+    pub fn run_farewell_effect<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        tone: CustomerFarewellTone,
+        now: Clock,
+    ) {
+        let phrase = tone.phrase().to_string();
+
+        self.replace_text(ctx.context, &phrase, TextBalloonPhraseType::SimplePhrase);
+        self.text_balloon
+            .add_effect(vec![effect::fade_in(10, now)]);
+        self.schedule_voice_blips(&phrase, now);
+        self.focus.release(now);
+        self.chat_box.add_message_as_partner(ctx, phrase);
+        self.chat_box
+            .add_message_as_mine(ctx, "ありがとうございました".to_string());
+
+        self.event_list.add_event(
+            Box::new(|slf: &mut Self, _, t| slf.run_hide_effect(t)),
+            now + 60,
+        );
+    }
+
     pub fn contains_text_balloon(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
         let rpoint = self.canvas.relative_point(point);
         self.text_balloon.contains(ctx, rpoint)
@@ -1134,6 +2363,21 @@ impl SuzuMiniSightSilhouette {
         let rpoint = self.canvas.relative_point(point);
         self.silhouette.contains(ctx, rpoint)
     }
+
+    ///
+    /// pointが客の影の上にあれば、客の名前を返す
+    ///
+    pub fn silhouette_tooltip_text(
+        &self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> Option<String> {
+        if self.contains_character_silhouette(ctx, point) {
+            self.silhouette.tooltip_text()
+        } else {
+            None
+        }
+    }
 }
 
 impl DrawableComponent for SuzuMiniSightSilhouette {
@@ -1143,7 +2387,11 @@ impl DrawableComponent for SuzuMiniSightSilhouette {
 
             self.background.draw(ctx)?;
             if self.silhouette.is_some() {
-                self.silhouette.draw(ctx)?;
+                let highlight = self.focus_highlight.or_else(|| {
+                    self.inspect_mode
+                        .highlight_for(self.silhouette.has_click_data())
+                });
+                self.silhouette.draw_highlighted(ctx, highlight.as_ref())?;
             }
 
             self.text_balloon.draw(ctx)?;
@@ -1224,6 +2472,7 @@ pub struct SuzuMiniSight {
     pub silhouette: SuzuMiniSightSilhouette,
     appearance_frame: TileBatchFrame,
     draw_request: DrawRequest,
+    inspect_mode: InspectMode,
 }
 
 impl SuzuMiniSight {
@@ -1253,13 +2502,14 @@ impl SuzuMiniSight {
             silhouette: silhouette,
             appearance_frame: appr_frame,
             draw_request: DrawRequest::InitDraw,
+            inspect_mode: InspectMode::new(),
         }
     }
 
     pub fn silhouette_new_customer_update(
         &mut self,
         ctx: &mut ggez::Context,
-        chara: SimpleObject,
+        chara: TextureAnimation<CustomerEmotion>,
         name: String,
         dialogue: CustomerDialogue,
         t: Clock,
@@ -1334,11 +2584,50 @@ impl SuzuMiniSight {
         }
     }
 
-    pub fn finish_customer_event(&mut self, now: Clock) {
-        self.silhouette.run_hide_effect(now);
+    pub fn finish_customer_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        tone: CustomerFarewellTone,
+        now: Clock,
+    ) {
+        self.silhouette.run_farewell_effect(ctx, tone, now);
+        self.draw_request = DrawRequest::Draw;
+    }
+
+    ///
+    /// 客を待たせている時間を渡し、影の顔付きに映す
+    ///
+    pub fn update_customer_waiting(&mut self, waiting: Clock) {
+        if self.silhouette.update_customer_waiting(waiting) {
+            self.draw_request = DrawRequest::Draw;
+        }
+    }
+
+    ///
+    /// 受け渡しの結果に、客の影を反応させる
+    ///
+    pub fn react_customer(&mut self, correct: bool) {
+        self.silhouette.react_customer(correct);
+        self.draw_request = DrawRequest::Draw;
+    }
+
+    pub fn release_conversation_focus(&mut self, t: Clock) {
+        self.silhouette.release_focus(t);
+    }
+
+    pub fn set_inspect_mode(&mut self, inspect_mode: InspectMode) {
+        self.inspect_mode = inspect_mode;
+        self.silhouette.inspect_mode = inspect_mode;
         self.draw_request = DrawRequest::Draw;
     }
 
+    ///
+    /// 会話に寄せている間、机に掛ける暗さ
+    ///
+    pub fn conversation_desk_dim(&self, t: Clock) -> f32 {
+        self.silhouette.desk_dim_at(t)
+    }
+
     ///
     /// # 再描画要求有り
     ///
@@ -1504,16 +2793,23 @@ impl DrawableComponent for SuzuMiniSight {
 
                 self.silhouette.draw(ctx)?;
 
+                let inspect_mode = self.inspect_mode;
                 for d in &mut self.dropping {
-                    d.get_object_mut().draw(ctx)?;
+                    let highlight = inspect_mode.highlight_for(d.get_object().has_click_data());
+                    d.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
                 for d in &mut self.dropping_to_desk {
-                    d.get_object_mut().draw(ctx)?;
+                    let highlight = inspect_mode.highlight_for(d.get_object().has_click_data());
+                    d.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
                 if let Some(ref mut d) = self.dragging {
-                    d.get_object_mut().draw(ctx)?;
+                    let highlight = inspect_mode.highlight_for(d.get_object().has_click_data());
+                    d.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
                 self.appearance_frame.draw(ctx)?;
@@ -1579,7 +2875,7 @@ impl HoldData {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum CustomerRequest {
     Borrowing(BorrowingInformation),
     Returning(ReturnBookInformation),
@@ -1599,6 +2895,13 @@ impl CustomerRequest {
             CustomerRequest::Returning(info) => info.get_rental_limit(),
         }
     }
+
+    pub fn get_request_kind(&self) -> RequestKind {
+        match self {
+            CustomerRequest::Borrowing(_) => RequestKind::Borrowing,
+            CustomerRequest::Returning(_) => RequestKind::Returning,
+        }
+    }
 }
 
 impl ToString for CustomerRequest {
@@ -1626,6 +2929,7 @@ pub struct ShelvingBookBox {
     box_front: UniTexture,
     appearance_frame: TileBatchFrame,
     draw_request: DrawRequest,
+    inspect_mode: InspectMode,
 }
 
 impl ShelvingBookBox {
@@ -1681,9 +2985,15 @@ impl ShelvingBookBox {
             box_back: box_back,
             appearance_frame: appr_frame,
             draw_request: DrawRequest::InitDraw,
+            inspect_mode: InspectMode::new(),
         }
     }
 
+    pub fn set_inspect_mode(&mut self, inspect_mode: InspectMode) {
+        self.inspect_mode = inspect_mode;
+        self.draw_request = DrawRequest::Draw;
+    }
+
     pub fn check_data_click(
         &mut self,
         ctx: &mut ggez::Context,
@@ -1726,7 +3036,7 @@ impl ShelvingBookBox {
                 .override_move_func(move_fn::gravity_move(1.0, 10.0, 310.0, 0.5), t);
             dragged.as_effectable_object().add_effect(vec![Box::new(
                 |obj: &mut dyn MovableObject, _: &ggez::Context, t: Clock| {
-                    println!("{}", obj.get_position().y);
+                    log_trace!("{}", obj.get_position().y);
                     if obj.get_position().y >= 310.0 {
                         obj.override_move_func(None, t);
                         EffectFnStatus::EffectFinish
@@ -1849,12 +3159,17 @@ impl DrawableComponent for ShelvingBookBox {
 
                 self.box_back.draw(ctx)?;
 
+                let inspect_mode = self.inspect_mode;
                 for obj in &mut self.shelved {
-                    obj.get_object_mut().draw(ctx)?;
+                    let highlight = inspect_mode.highlight_for(obj.get_object().has_click_data());
+                    obj.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
                 if let Some(ref mut d) = self.dragging {
-                    d.get_object_mut().draw(ctx)?;
+                    let highlight = inspect_mode.highlight_for(d.get_object().has_click_data());
+                    d.get_object_mut()
+                        .draw_highlighted(ctx, highlight.as_ref())?;
                 }
 
                 self.box_front.draw(ctx)?;
@@ -1924,6 +3239,7 @@ impl KosuzuPhrase {
                         numeric::Vector2f::new(21.0, 21.0),
                         ggraphics::Color::BLACK,
                     ),
+                    ctx.text_orientation(),
                 )),
                 None,
                 t,
@@ -1931,6 +3247,13 @@ impl KosuzuPhrase {
             vec![effect::fade_in(10, t)],
         ));
 
+        for offset in balloon_blip_schedule(text, VoiceArchetype::Kosuzu) {
+            self.event_list.add_event(
+                Box::new(|_: &mut Self, ctx, _| ctx.play_voice_blip(VoiceArchetype::Kosuzu)),
+                t + offset,
+            );
+        }
+
         self.event_list.add_event(
             Box::new(|slf: &mut Self, _, t| slf.close_text_balloon(t)),
             t + 240,
@@ -2082,7 +3405,7 @@ impl FloatingMemoryObject {
         let position = self.canvas.get_position();
         let depth = self.canvas.get_drawing_depth();
 
-        println!("required size: {:?}", new_size);
+        log_trace!("required size: {:?}", new_size);
 
         let new_canvas = SubScreen::new(
             ctx.context,
@@ -2297,7 +3620,7 @@ impl TaskInfoContents {
         request_text.insert("youken".to_string(), request_type_vtext);
 
         let mut today_vtext = VerticalText::new(
-            ctx.take_save_data().date.to_short_string(),
+            ctx.number_format().short_date(&ctx.take_save_data().date),
             numeric::Point2f::new(0.0, 0.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -2352,7 +3675,7 @@ impl TaskInfoContents {
 
         let key = "name";
 
-        println!("set name !! => {}", name);
+        log_debug!("set name !! => {}", name);
 
         if self.request_info_text.contains_key(key) {
             self.request_info_text.remove(key);
@@ -2669,6 +3992,137 @@ impl TextureObject for MoneyBox {
     impl_texture_object_for_wrapped! {canvas}
 }
 
+///
+/// # 読み終えた手紙を仕舞う書類箱
+/// 机の隅に置いておき、開けた手紙を落とすと机から片付ける
+///
+/// ## archived_today
+/// この日に仕舞った通数。箱の札に書く
+///
+pub struct ArchiveDrawer {
+    draw_request: DrawRequest,
+    canvas: SubScreen,
+    box_texture: UniTexture,
+    label: UniText,
+    count_text: UniText,
+    archived_today: usize,
+}
+
+impl ArchiveDrawer {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, pos_rect: numeric::Rect, depth: i8) -> Self {
+        let mut box_texture = UniTexture::new(
+            ctx.ref_texture(TextureID::BookBoxBack),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        box_texture.fit_scale(ctx.context, numeric::Vector2f::new(pos_rect.w, pos_rect.h));
+
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(20.0, 20.0),
+            ggraphics::Color::from_rgba_u32(0xf0e8d0ff),
+        );
+
+        ArchiveDrawer {
+            draw_request: DrawRequest::InitDraw,
+            canvas: SubScreen::new(
+                ctx.context,
+                pos_rect,
+                depth,
+                ggraphics::Color::from_rgba_u32(0),
+            ),
+            box_texture: box_texture,
+            label: UniText::new(
+                "書類箱".to_string(),
+                numeric::Point2f::new(10.0, 10.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info.clone(),
+            ),
+            count_text: UniText::new(
+                "0通".to_string(),
+                numeric::Point2f::new(10.0, 36.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info,
+            ),
+            archived_today: 0,
+        }
+    }
+
+    ///
+    /// 仕舞えるのは開けた手紙だけ。開けていない手紙は翌朝また届いてしまう
+    ///
+    pub fn is_acceptable_for_archive(item: &TaskItem) -> bool {
+        match item {
+            TaskItem::Letter(letter) => letter.get_large_object().is_opened(),
+            _ => false,
+        }
+    }
+
+    ///
+    /// 手紙を一通仕舞ったことを札に書く
+    ///
+    pub fn count_up(&mut self) {
+        self.archived_today += 1;
+        self.count_text
+            .replace_text(format!("{}通", self.archived_today));
+        self.draw_request = DrawRequest::Draw;
+    }
+}
+
+impl DrawableComponent for ArchiveDrawer {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            if self.draw_request != DrawRequest::Skip {
+                self.draw_request = DrawRequest::Skip;
+                sub_screen::stack_screen(ctx, &self.canvas);
+
+                self.box_texture.draw(ctx)?;
+                self.label.draw(ctx)?;
+                self.count_text.draw(ctx)?;
+
+                sub_screen::pop_screen(ctx);
+            }
+            self.canvas.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl DrawableObject for ArchiveDrawer {
+    impl_drawable_object_for_wrapped! {canvas}
+}
+
+impl TextureObject for ArchiveDrawer {
+    impl_texture_object_for_wrapped! {canvas}
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum LineJustified {
     Left,
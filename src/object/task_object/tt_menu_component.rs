@@ -1,6 +1,7 @@
 use ggez::graphics as ggraphics;
 
 use torifune::core::{Clock, Updatable};
+use torifune::distance;
 use torifune::graphics::drawable::*;
 use torifune::graphics::object::sub_screen;
 use torifune::graphics::object::sub_screen::SubScreen;
@@ -12,6 +13,11 @@ use torifune::roundup2f;
 
 use super::Clickable;
 use crate::core;
+use crate::core::book_inspection::{InspectionDecision, InspectionPoint};
+use crate::core::gesture::GestureRecognizer;
+use crate::core::gift_wrapping::{PaperCorner, WrappingSession};
+use crate::core::membership::MembershipCard;
+use crate::core::preferences::TextOrientation;
 use crate::core::*;
 use crate::core::{FontID, GensoDate, TextureID, TileBatchTextureID};
 use crate::flush_delay_event;
@@ -21,6 +27,9 @@ use crate::object::util_object::*;
 use crate::scene::*;
 use crate::set_table_frame_cell_center;
 
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct KosuzuMemory {
     remembered_book_info: Vec<BookInformation>,
     borrowing_written_book: Vec<BookInformation>,
@@ -65,6 +74,13 @@ impl KosuzuMemory {
         self.customers_name.push(name);
     }
 
+    ///
+    /// 覚えている客の名前。名前のメニューはこの順に並べる
+    ///
+    pub fn get_customers_name(&self) -> &Vec<String> {
+        &self.customers_name
+    }
+
     pub fn add_date(&mut self, date: GensoDate) {
         self.dates.push(date);
     }
@@ -490,6 +506,7 @@ pub struct BookTitleMenu {
     raw_data: Vec<BookInformation>,
     title_table_frame: TableFrame,
     title_vtext: Vec<VerticalText>,
+    furigana_vtext: Vec<VerticalText>,
     header_text: VerticalText,
     drwob_essential: DrawableObjectEssential,
     last_clicked: Option<usize>,
@@ -502,12 +519,18 @@ impl BookTitleMenu {
         drawing_depth: i8,
     ) -> Self {
         let mut title_vtext = Vec::new();
+        let mut furigana_vtext = Vec::new();
 
         let font_info = FontInformation::new(
             ctx.resource.get_font(FontID::JpFude1),
             numeric::Vector2f::new(32.0, 32.0),
             ggraphics::Color::from_rgba_u32(0xff),
         );
+        let furigana_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(32.0 * FURIGANA_FONT_RATIO, 32.0 * FURIGANA_FONT_RATIO),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
 
         let title_table_frame = TableFrame::new(
             ctx.resource,
@@ -537,6 +560,15 @@ impl BookTitleMenu {
                 numeric::Vector2u::new(index as u32, 0)
             );
 
+            if let Some(reading) = book_info.get_reading() {
+                furigana_vtext.push(new_furigana_vtext(
+                    ctx.context,
+                    &mut vtext,
+                    reading,
+                    furigana_font_info,
+                ));
+            }
+
             title_vtext.push(vtext);
         }
 
@@ -557,6 +589,7 @@ impl BookTitleMenu {
             raw_data: book_info_data,
             title_table_frame: title_table_frame,
             title_vtext: title_vtext,
+            furigana_vtext: furigana_vtext,
             drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
             last_clicked: None,
             header_text: header_text,
@@ -596,6 +629,10 @@ impl DrawableComponent for BookTitleMenu {
                 vtext.draw(ctx)?;
             }
 
+            for vtext in &mut self.furigana_vtext {
+                vtext.draw(ctx)?;
+            }
+
             self.header_text.draw(ctx)?;
         }
         Ok(())
@@ -637,8 +674,8 @@ impl Clickable for BookTitleMenu {
 pub type BookTitleDropMenu = DropDownArea<BookTitleMenu>;
 
 pub struct SimpleMessageMenu {
-    title: VerticalText,
-    message: VerticalText,
+    title: OrientedText,
+    message: OrientedText,
     menu_size: numeric::Vector2f,
     drwob_essential: DrawableObjectEssential,
 }
@@ -652,13 +689,27 @@ impl SimpleMessageMenu {
         msg_font_scale: f32,
         drawing_depth: i8,
     ) -> Self {
-        let menu_size = numeric::Vector2f::new(
-            msg_font_scale + title_font_scale + 120.0,
-            util::max(
-                title.len() as f32 * title_font_scale,
-                message.len() as f32 * msg_font_scale,
-            ) / 2.0,
-        );
+        let orientation = ctx.text_orientation();
+
+        // 文字の並ぶ方向に長く、行の重なる方向に2行分の幅を取る
+        let text_length = util::max(
+            title.len() as f32 * title_font_scale,
+            message.len() as f32 * msg_font_scale,
+        ) / 2.0;
+        let lines_width = msg_font_scale + title_font_scale + 120.0;
+        let (menu_size, title_pos) = match orientation {
+            TextOrientation::Vertical => {
+                let menu_size = numeric::Vector2f::new(lines_width, text_length);
+                (
+                    menu_size,
+                    numeric::Point2f::new(menu_size.x - title_font_scale - 40.0, 35.0),
+                )
+            }
+            TextOrientation::Horizontal => (
+                numeric::Vector2f::new(text_length, lines_width),
+                numeric::Point2f::new(35.0, 40.0),
+            ),
+        };
 
         let title_font_info = FontInformation::new(
             ctx.resource.get_font(FontID::Cinema),
@@ -672,16 +723,18 @@ impl SimpleMessageMenu {
             ggraphics::Color::from_rgba_u32(0xff),
         );
 
-        let title_text = VerticalText::new(
+        let title_text = OrientedText::new(
+            orientation,
             title,
-            numeric::Point2f::new(menu_size.x - title_font_scale - 40.0, 35.0),
+            title_pos,
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
             0,
             title_font_info,
         );
 
-        let mut msg_text = VerticalText::new(
+        let mut msg_text = OrientedText::new(
+            orientation,
             message,
             numeric::Point2f::new(0.0, 0.0),
             numeric::Vector2f::new(1.0, 1.0),
@@ -947,7 +1000,7 @@ impl DateMenu {
 
         let mut loop_date = today.clone();
         for index in 0..3 {
-            let name_vtext_line = loop_date.to_short_string();
+            let name_vtext_line = ctx.number_format().short_date(&loop_date);
             let mut vtext = VerticalText::new(
                 name_vtext_line,
                 numeric::Point2f::new(0.0, 0.0),
@@ -966,7 +1019,7 @@ impl DateMenu {
 
             date_vtext.push(vtext);
             date_data.push(loop_date.clone());
-            loop_date.add_day(7);
+            loop_date.add_days(7);
         }
 
         for (index, s) in vec!["本日", "短期返却日", "長期返却日"].iter().enumerate() {
@@ -1174,7 +1227,7 @@ impl DateCheckMenu {
         }
 
         for (index, date) in vec![today.clone(), return_date].iter().enumerate() {
-            let name_vtext_line = date.to_string();
+            let name_vtext_line = ctx.number_format().date(&date);
             let mut vtext = VerticalText::new(
                 name_vtext_line,
                 numeric::Point2f::new(0.0, 0.0),
@@ -1852,6 +1905,15 @@ impl CustomerMenuGroup {
         self.close_text_balloon_ok_menu(t);
     }
 
+    ///
+    /// 消える演出を待たずに、全てのメニューをすぐに消す
+    ///
+    pub fn dismiss_all(&mut self) {
+        self.event_list = DelayEventList::new();
+        self.customer_question_menu = None;
+        self.text_balloon_ok_menu = None;
+    }
+
     pub fn show_customer_question_menu<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
@@ -1979,425 +2041,563 @@ impl DrawableComponent for CustomerMenuGroup {
     }
 }
 
-pub struct RecordBookMenuGroup {
-    event_list: DelayEventList<Self>,
-    book_status_menu: Option<BookStatusMenu>,
-    book_title_menu: Option<BookTitleDropMenu>,
-    customer_name_menu: Option<CustomerNameDropMenu>,
-    simple_message_menu: Option<SimpleMessageDropMenu>,
-    date_menu: Option<DateDropMenu>,
-    date_check_menu: Option<DateCheckDropMenu>,
-    drwob_essential: DrawableObjectEssential,
-}
+///
+/// メニューを閉じ始めてから、消えるまでのフレーム数
+///
+pub const MENU_CLOSE_DELAY: Clock = 11;
 
-impl RecordBookMenuGroup {
-    pub fn new(drawing_depth: i8) -> Self {
-        RecordBookMenuGroup {
-            event_list: DelayEventList::new(),
-            book_status_menu: None,
-            book_title_menu: None,
-            customer_name_menu: None,
-            simple_message_menu: None,
-            date_menu: None,
-            date_check_menu: None,
-            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
-        }
-    }
+///
+/// # MenuStackに積めるメニュー
+/// 種類ごとに一つまで開ける
+///
+pub trait StackMenu: DrawableComponent {
+    type Kind: Copy + PartialEq;
 
-    pub fn is_some_menu_opened(&self) -> bool {
-        self.book_status_menu.is_some()
-            || self.book_title_menu.is_some()
-            || self.customer_name_menu.is_some()
-            || self.date_menu.is_some()
-            || self.date_check_menu.is_some()
-            || self.simple_message_menu.is_some()
-    }
+    fn menu_kind(&self) -> Self::Kind;
 
-    pub fn close_book_status_menu(&mut self, t: Clock) {
-        let button_group = match self.book_status_menu.as_mut() {
-            Some(it) => it,
-            _ => return,
-        };
-        button_group.add_effect(vec![effect::fade_out(10, t)]);
-        self.event_list.add_event(
-            Box::new(|slf: &mut RecordBookMenuGroup, _, _| slf.book_status_menu = None),
-            t + 11,
-        );
-    }
+    fn menu_contains(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool;
 
-    pub fn close_book_title_menu(&mut self, t: Clock) {
-        let title_menu = match self.book_title_menu.as_mut() {
-            Some(it) => it,
-            _ => return,
-        };
-        title_menu.add_effect(vec![effect::fade_out(10, t)]);
-        self.event_list.add_event(
-            Box::new(|slf: &mut RecordBookMenuGroup, _, _| slf.book_title_menu = None),
-            t + 11,
-        );
-    }
+    fn menu_click<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+        t: Clock,
+    );
 
-    pub fn close_customer_name_menu(&mut self, t: Clock) {
-        let customer_name_menu = match self.customer_name_menu.as_mut() {
-            Some(it) => it,
-            _ => return,
-        };
-        customer_name_menu.add_effect(vec![effect::fade_out(10, t)]);
-        self.event_list.add_event(
-            Box::new(|slf: &mut RecordBookMenuGroup, _, _| slf.customer_name_menu = None),
-            t + 11,
-        );
-    }
+    ///
+    /// 閉じる演出を始める。MENU_CLOSE_DELAYの後にMenuStackから取り除かれる
+    ///
+    fn start_closing(&mut self, t: Clock);
 
-    pub fn close_date_menu(&mut self, t: Clock) {
-        let date_menu = match self.date_menu.as_mut() {
-            Some(it) => it,
-            _ => return,
-        };
-        date_menu.add_effect(vec![effect::fade_out(10, t)]);
-        self.event_list.add_event(
-            Box::new(|slf: &mut RecordBookMenuGroup, _, _| slf.date_menu = None),
-            t + 11,
-        );
-    }
+    fn update_menu(&mut self, ctx: &mut ggez::Context, t: Clock);
+}
 
-    pub fn close_date_check_menu(&mut self, t: Clock) {
-        let date_menu = match self.date_check_menu.as_mut() {
-            Some(it) => it,
-            _ => return,
-        };
-        date_menu.add_effect(vec![effect::fade_out(10, t)]);
-        self.event_list.add_event(
-            Box::new(|slf: &mut RecordBookMenuGroup, _, _| slf.date_check_menu = None),
-            t + 11,
-        );
-    }
+///
+/// # 重ねて開くメニューの束
+/// 後から開いたものほど上に描き、クリックは上のものから順に渡す
+///
+/// ## closing
+/// 閉じる演出中のメニューの種類と、取り除く時刻
+///
+pub struct MenuStack<M: StackMenu> {
+    menus: Vec<M>,
+    closing: Vec<(M::Kind, Clock)>,
+}
 
-    pub fn close_simple_message_menu(&mut self, t: Clock) {
-        let msg_menu = match self.simple_message_menu.as_mut() {
-            Some(it) => it,
-            _ => return,
-        };
-        msg_menu.add_effect(vec![effect::fade_out(10, t)]);
-        self.event_list.add_event(
-            Box::new(|slf: &mut RecordBookMenuGroup, _, _| slf.simple_message_menu = None),
-            t + 11,
-        );
+impl<M: StackMenu> MenuStack<M> {
+    pub fn new() -> Self {
+        MenuStack {
+            menus: Vec::new(),
+            closing: Vec::new(),
+        }
     }
 
-    pub fn contains_book_status_menu(
-        &self,
-        ctx: &mut ggez::Context,
-        point: numeric::Point2f,
-    ) -> bool {
-        self.book_status_menu.is_some()
-            && self.book_status_menu.as_ref().unwrap().contains(ctx, point)
+    ///
+    /// menuを一番上に開く。同じ種類のメニューが開いていれば、差し替える
+    ///
+    pub fn open(&mut self, menu: M) {
+        let kind = menu.menu_kind();
+        self.menus.retain(|opened| opened.menu_kind() != kind);
+        self.closing.retain(|(closing, _)| *closing != kind);
+        self.menus.push(menu);
     }
 
-    pub fn contains_book_title_menu(
-        &self,
-        ctx: &mut ggez::Context,
-        point: numeric::Point2f,
-    ) -> bool {
-        self.book_title_menu.is_some()
-            && self.book_title_menu.as_ref().unwrap().contains(ctx, point)
-    }
+    ///
+    /// kindのメニューを閉じ始める。開いていなければ何もしない
+    ///
+    pub fn close(&mut self, kind: M::Kind, t: Clock) {
+        if self.is_closing(kind) {
+            return;
+        }
 
-    pub fn contains_customer_name_menu(
-        &self,
-        ctx: &mut ggez::Context,
-        point: numeric::Point2f,
-    ) -> bool {
-        self.customer_name_menu.is_some()
-            && self
-                .customer_name_menu
-                .as_ref()
-                .unwrap()
-                .contains(ctx, point)
+        if let Some(menu) = self.get_mut(kind) {
+            menu.start_closing(t);
+            self.closing.push((kind, t + MENU_CLOSE_DELAY));
+        }
     }
 
-    pub fn contains_date_menu(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
-        self.date_menu.is_some() && self.date_menu.as_ref().unwrap().contains(ctx, point)
+    pub fn close_all(&mut self, t: Clock) {
+        let kinds: Vec<M::Kind> = self.menus.iter().map(|menu| menu.menu_kind()).collect();
+        for kind in kinds {
+            self.close(kind, t);
+        }
     }
 
-    pub fn contains_date_check_menu(
-        &self,
-        ctx: &mut ggez::Context,
-        point: numeric::Point2f,
-    ) -> bool {
-        self.date_check_menu.is_some()
-            && self.date_check_menu.as_ref().unwrap().contains(ctx, point)
+    ///
+    /// 閉じる演出を待たずに、全てのメニューをすぐに消す
+    ///
+    pub fn dismiss_all(&mut self) {
+        self.menus.clear();
+        self.closing.clear();
     }
 
-    pub fn contains_simple_message_menu(
-        &self,
-        ctx: &mut ggez::Context,
-        point: numeric::Point2f,
-    ) -> bool {
-        self.simple_message_menu.is_some()
-            && self
-                .simple_message_menu
-                .as_ref()
-                .unwrap()
-                .contains(ctx, point)
+    pub fn is_empty(&self) -> bool {
+        self.menus.is_empty()
     }
 
-    pub fn is_contains_any_menus(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
-        self.contains_book_title_menu(ctx, point)
-            || self.contains_book_status_menu(ctx, point)
-            || self.contains_customer_name_menu(ctx, point)
-            || self.contains_date_menu(ctx, point)
-            || self.contains_date_check_menu(ctx, point)
-            || self.contains_simple_message_menu(ctx, point)
+    pub fn len(&self) -> usize {
+        self.menus.len()
     }
 
-    pub fn get_book_status_menu_position(&self) -> Option<numeric::Point2f> {
-        if let Some(book_status_menu) = self.book_status_menu.as_ref() {
-            Some(book_status_menu.get_click_position())
-        } else {
-            None
-        }
+    pub fn is_opened(&self, kind: M::Kind) -> bool {
+        self.get(kind).is_some()
     }
 
-    pub fn get_book_title_menu_position(&self) -> Option<numeric::Point2f> {
-        if let Some(book_title_menu) = self.book_title_menu.as_ref() {
-            Some(book_title_menu.get_click_position())
-        } else {
-            None
-        }
+    pub fn is_closing(&self, kind: M::Kind) -> bool {
+        self.closing.iter().any(|(closing, _)| *closing == kind)
     }
 
-    pub fn get_customer_name_menu_position(&self) -> Option<numeric::Point2f> {
-        if let Some(customer_name_menu) = self.customer_name_menu.as_ref() {
-            Some(customer_name_menu.get_click_position())
-        } else {
-            None
-        }
+    ///
+    /// 一番上に開いているメニューの種類
+    ///
+    pub fn top_kind(&self) -> Option<M::Kind> {
+        self.menus.last().map(|menu| menu.menu_kind())
     }
 
-    pub fn get_date_menu_position(&self) -> Option<numeric::Point2f> {
-        if let Some(date_menu) = self.date_menu.as_ref() {
-            Some(date_menu.get_click_position())
-        } else {
-            None
-        }
+    pub fn get(&self, kind: M::Kind) -> Option<&M> {
+        self.menus.iter().find(|menu| menu.menu_kind() == kind)
     }
 
-    pub fn get_date_check_menu_position(&self) -> Option<numeric::Point2f> {
-        if let Some(date_menu) = self.date_check_menu.as_ref() {
-            Some(date_menu.get_click_position())
-        } else {
-            None
-        }
+    pub fn get_mut(&mut self, kind: M::Kind) -> Option<&mut M> {
+        self.menus.iter_mut().find(|menu| menu.menu_kind() == kind)
     }
 
-    pub fn get_simple_message_menu_position(&self) -> Option<numeric::Point2f> {
-        if let Some(menu) = self.simple_message_menu.as_ref() {
-            Some(menu.get_click_position())
-        } else {
-            None
-        }
+    pub fn contains(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
+        self.menus.iter().any(|menu| menu.menu_contains(ctx, point))
     }
 
     ///
-    /// メニューのエントリをクリックしていたらtrueを返し、そうでなければfalseを返す
+    /// pointを含むメニューのうち、一番上のものにクリックを渡す
     ///
-    pub fn click_book_status_menu<'a>(
+    /// クリックを渡したメニューの種類を返す。どれも含まなければNone
+    ///
+    pub fn click<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
         button: ggez::input::mouse::MouseButton,
         point: numeric::Point2f,
         t: Clock,
-    ) -> bool {
-        // ボタンエリア内をクリックしていない場合は、即終了
-        if !self.contains_book_status_menu(ctx.context, point) {
-            return false;
+    ) -> Option<M::Kind> {
+        for menu in self.menus.iter_mut().rev() {
+            if menu.menu_contains(ctx.context, point) {
+                menu.menu_click(ctx, button, point, t);
+                return Some(menu.menu_kind());
+            }
         }
 
-        if let Some(book_status_menu) = self.book_status_menu.as_mut() {
-            book_status_menu.on_click(ctx, t, button, point);
-            true
-        } else {
-            false
-        }
+        None
     }
 
     ///
-    /// メニューのエントリをクリックしていたらtrueを返し、そうでなければfalseを返す
+    /// 閉じる演出を終えたメニューを取り除く
     ///
-    pub fn click_book_title_menu<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        button: ggez::input::mouse::MouseButton,
-        point: numeric::Point2f,
-        t: Clock,
-    ) -> bool {
-        // ボタンエリア内をクリックしていない場合は、即終了
-        if !self.contains_book_title_menu(ctx.context, point) {
-            return false;
-        }
+    pub fn flush_closed(&mut self, t: Clock) {
+        let finished: Vec<M::Kind> = self
+            .closing
+            .iter()
+            .filter(|(_, remove_at)| *remove_at <= t)
+            .map(|(kind, _)| *kind)
+            .collect();
 
-        if let Some(book_title_menu) = self.book_title_menu.as_mut() {
-            book_title_menu.on_click(ctx, t, button, point);
-            true
-        } else {
-            false
-        }
+        self.menus
+            .retain(|menu| !finished.contains(&menu.menu_kind()));
+        self.closing.retain(|(_, remove_at)| *remove_at > t);
     }
 
     ///
-    /// メニューのエントリをクリックしていたらtrueを返し、そうでなければfalseを返す
+    /// 開いているメニューがあれば、描き直しが要るのでtrueを返す
     ///
-    pub fn click_customer_name_menu<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        button: ggez::input::mouse::MouseButton,
-        point: numeric::Point2f,
-        t: Clock,
-    ) -> bool {
-        // ボタンエリア内をクリックしていない場合は、即終了
-        if !self.contains_customer_name_menu(ctx.context, point) {
-            return false;
-        }
+    pub fn update(&mut self, ctx: &mut ggez::Context, t: Clock) -> bool {
+        let had_menus = !self.menus.is_empty();
 
-        if let Some(customer_name_menu) = self.customer_name_menu.as_mut() {
-            customer_name_menu.on_click(ctx, t, button, point);
-            true
-        } else {
-            false
+        self.flush_closed(t);
+        for menu in self.menus.iter_mut() {
+            menu.update_menu(ctx, t);
         }
+
+        had_menus
     }
 
-    ///
-    /// メニューのエントリをクリックしていたらtrueを返し、そうでなければfalseを返す
-    ///
-    pub fn click_date_menu<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        button: ggez::input::mouse::MouseButton,
-        point: numeric::Point2f,
-        t: Clock,
-    ) -> bool {
-        // ボタンエリア内をクリックしていない場合は、即終了
-        if !self.contains_date_menu(ctx.context, point) {
-            return false;
+    pub fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        for menu in self.menus.iter_mut() {
+            menu.draw(ctx)?;
         }
 
-        if let Some(date_menu) = self.date_menu.as_mut() {
-            date_menu.on_click(ctx, t, button, point);
-            true
-        } else {
-            false
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordBookMenuKind {
+    BookStatus,
+    BookTitle,
+    CustomerName,
+    Date,
+    DateCheck,
+    SimpleMessage,
+}
+
+///
+/// # 帳簿の上に開くメニュー
+///
+pub enum RecordBookMenu {
+    BookStatus(BookStatusMenu),
+    BookTitle(BookTitleDropMenu),
+    CustomerName(CustomerNameDropMenu),
+    Date(DateDropMenu),
+    DateCheck(DateCheckDropMenu),
+    SimpleMessage(SimpleMessageDropMenu),
+}
+
+impl RecordBookMenu {
+    pub fn get_click_position(&self) -> numeric::Point2f {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.get_click_position(),
+            RecordBookMenu::BookTitle(menu) => menu.get_click_position(),
+            RecordBookMenu::CustomerName(menu) => menu.get_click_position(),
+            RecordBookMenu::Date(menu) => menu.get_click_position(),
+            RecordBookMenu::DateCheck(menu) => menu.get_click_position(),
+            RecordBookMenu::SimpleMessage(menu) => menu.get_click_position(),
         }
     }
+}
 
-    pub fn click_date_check_menu<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        button: ggez::input::mouse::MouseButton,
-        point: numeric::Point2f,
-        t: Clock,
-    ) -> bool {
-        // ボタンエリア内をクリックしていない場合は、即終了
-        if !self.contains_date_check_menu(ctx.context, point) {
-            return false;
+impl DrawableComponent for RecordBookMenu {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.draw(ctx),
+            RecordBookMenu::BookTitle(menu) => menu.draw(ctx),
+            RecordBookMenu::CustomerName(menu) => menu.draw(ctx),
+            RecordBookMenu::Date(menu) => menu.draw(ctx),
+            RecordBookMenu::DateCheck(menu) => menu.draw(ctx),
+            RecordBookMenu::SimpleMessage(menu) => menu.draw(ctx),
         }
+    }
 
-        if let Some(date_menu) = self.date_check_menu.as_mut() {
-            date_menu.on_click(ctx, t, button, point);
-            true
-        } else {
-            false
+    fn hide(&mut self) {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.hide(),
+            RecordBookMenu::BookTitle(menu) => menu.hide(),
+            RecordBookMenu::CustomerName(menu) => menu.hide(),
+            RecordBookMenu::Date(menu) => menu.hide(),
+            RecordBookMenu::DateCheck(menu) => menu.hide(),
+            RecordBookMenu::SimpleMessage(menu) => menu.hide(),
         }
     }
 
-    pub fn book_status_menu_last_clicked(&mut self) -> Option<usize> {
-        if let Some(book_status_menu) = self.book_status_menu.as_mut() {
-            book_status_menu.get_component().get_last_clicked()
-        } else {
-            None
+    fn appear(&mut self) {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.appear(),
+            RecordBookMenu::BookTitle(menu) => menu.appear(),
+            RecordBookMenu::CustomerName(menu) => menu.appear(),
+            RecordBookMenu::Date(menu) => menu.appear(),
+            RecordBookMenu::DateCheck(menu) => menu.appear(),
+            RecordBookMenu::SimpleMessage(menu) => menu.appear(),
         }
     }
 
-    pub fn book_title_menu_last_clicked(&mut self) -> Option<(usize, BookInformation)> {
-        if let Some(book_title_menu) = self.book_title_menu.as_mut() {
-            let component = book_title_menu.get_component();
-            if let Some(index) = component.get_last_clicked_index() {
-                Some((index, component.get_last_clicked_book_info().unwrap()))
-            } else {
-                None
-            }
-        } else {
-            None
+    fn is_visible(&self) -> bool {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.is_visible(),
+            RecordBookMenu::BookTitle(menu) => menu.is_visible(),
+            RecordBookMenu::CustomerName(menu) => menu.is_visible(),
+            RecordBookMenu::Date(menu) => menu.is_visible(),
+            RecordBookMenu::DateCheck(menu) => menu.is_visible(),
+            RecordBookMenu::SimpleMessage(menu) => menu.is_visible(),
         }
     }
 
-    pub fn customer_name_menu_last_clicked(&mut self) -> Option<(usize, String)> {
-        if let Some(customer_name_menu) = self.customer_name_menu.as_mut() {
-            let component = customer_name_menu.get_component();
-            if let Some(index) = component.get_last_clicked_index() {
-                Some((index, component.get_last_clicked_customer_name().unwrap()))
-            } else {
-                None
-            }
-        } else {
-            None
+    fn set_drawing_depth(&mut self, depth: i8) {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.set_drawing_depth(depth),
+            RecordBookMenu::BookTitle(menu) => menu.set_drawing_depth(depth),
+            RecordBookMenu::CustomerName(menu) => menu.set_drawing_depth(depth),
+            RecordBookMenu::Date(menu) => menu.set_drawing_depth(depth),
+            RecordBookMenu::DateCheck(menu) => menu.set_drawing_depth(depth),
+            RecordBookMenu::SimpleMessage(menu) => menu.set_drawing_depth(depth),
         }
     }
 
-    pub fn date_menu_last_clicked(&mut self) -> Option<(usize, GensoDate)> {
-        if let Some(date_menu) = self.date_menu.as_mut() {
-            let component = date_menu.get_component();
-            if let Some(index) = component.get_last_clicked_index() {
-                Some((index, component.get_last_clicked_genso_date().unwrap()))
-            } else {
-                None
-            }
-        } else {
-            None
+    fn get_drawing_depth(&self) -> i8 {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.get_drawing_depth(),
+            RecordBookMenu::BookTitle(menu) => menu.get_drawing_depth(),
+            RecordBookMenu::CustomerName(menu) => menu.get_drawing_depth(),
+            RecordBookMenu::Date(menu) => menu.get_drawing_depth(),
+            RecordBookMenu::DateCheck(menu) => menu.get_drawing_depth(),
+            RecordBookMenu::SimpleMessage(menu) => menu.get_drawing_depth(),
         }
     }
+}
 
-    pub fn date_check_menu_check_button_clicked(&mut self) -> bool {
-        if let Some(date_menu) = self.date_check_menu.as_mut() {
-            date_menu
-                .get_component_mut()
-                .check_button_is_clicked_volatile()
-        } else {
-            false
+impl StackMenu for RecordBookMenu {
+    type Kind = RecordBookMenuKind;
+
+    fn menu_kind(&self) -> RecordBookMenuKind {
+        match self {
+            RecordBookMenu::BookStatus(_) => RecordBookMenuKind::BookStatus,
+            RecordBookMenu::BookTitle(_) => RecordBookMenuKind::BookTitle,
+            RecordBookMenu::CustomerName(_) => RecordBookMenuKind::CustomerName,
+            RecordBookMenu::Date(_) => RecordBookMenuKind::Date,
+            RecordBookMenu::DateCheck(_) => RecordBookMenuKind::DateCheck,
+            RecordBookMenu::SimpleMessage(_) => RecordBookMenuKind::SimpleMessage,
         }
     }
 
-    pub fn close_all(&mut self, t: Clock) {
-        self.close_book_status_menu(t);
-        self.close_book_title_menu(t);
-        self.close_customer_name_menu(t);
-        self.close_date_menu(t);
-        self.close_date_check_menu(t);
-        self.close_simple_message_menu(t);
+    fn menu_contains(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.contains(ctx, point),
+            RecordBookMenu::BookTitle(menu) => menu.contains(ctx, point),
+            RecordBookMenu::CustomerName(menu) => menu.contains(ctx, point),
+            RecordBookMenu::Date(menu) => menu.contains(ctx, point),
+            RecordBookMenu::DateCheck(menu) => menu.contains(ctx, point),
+            RecordBookMenu::SimpleMessage(menu) => menu.contains(ctx, point),
+        }
     }
 
-    pub fn show_book_status_menu<'a>(
+    fn menu_click<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
-        position: numeric::Point2f,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
         t: Clock,
     ) {
-        let button_group =
-            BookStatusButtonGroup::new(ctx, numeric::Rect::new(0.0, 0.0, 70.0, 70.0), 20.0, 0);
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.on_click(ctx, t, button, point),
+            RecordBookMenu::BookTitle(menu) => menu.on_click(ctx, t, button, point),
+            RecordBookMenu::CustomerName(menu) => menu.on_click(ctx, t, button, point),
+            RecordBookMenu::Date(menu) => menu.on_click(ctx, t, button, point),
+            RecordBookMenu::DateCheck(menu) => menu.on_click(ctx, t, button, point),
+            RecordBookMenu::SimpleMessage(menu) => menu.on_click(ctx, t, button, point),
+        }
+    }
 
-        let mut button_group_area = DropDownArea::new(
-            ctx,
-            position,
-            numeric::Rect::new(position.x, position.y, 290.0, 180.0),
-            0,
-            button_group,
-            t,
-        );
+    fn start_closing(&mut self, t: Clock) {
+        let fade_out = vec![effect::fade_out(MENU_CLOSE_DELAY - 1, t)];
+
+        match self {
+            RecordBookMenu::BookStatus(menu) => menu.add_effect(fade_out),
+            RecordBookMenu::BookTitle(menu) => menu.add_effect(fade_out),
+            RecordBookMenu::CustomerName(menu) => menu.add_effect(fade_out),
+            RecordBookMenu::Date(menu) => menu.add_effect(fade_out),
+            RecordBookMenu::DateCheck(menu) => menu.add_effect(fade_out),
+            RecordBookMenu::SimpleMessage(menu) => menu.add_effect(fade_out),
+        }
+    }
+
+    fn update_menu(&mut self, ctx: &mut ggez::Context, t: Clock) {
+        match self {
+            RecordBookMenu::BookStatus(menu) => {
+                menu.move_with_func(t);
+                menu.effect(ctx, t);
+            }
+            RecordBookMenu::BookTitle(menu) => {
+                menu.move_with_func(t);
+                menu.effect(ctx, t);
+            }
+            RecordBookMenu::CustomerName(menu) => {
+                menu.move_with_func(t);
+                menu.effect(ctx, t);
+            }
+            RecordBookMenu::Date(menu) => {
+                menu.move_with_func(t);
+                menu.effect(ctx, t);
+            }
+            RecordBookMenu::DateCheck(menu) => {
+                menu.move_with_func(t);
+                menu.effect(ctx, t);
+            }
+            RecordBookMenu::SimpleMessage(menu) => {
+                menu.move_with_func(t);
+                menu.effect(ctx, t);
+            }
+        }
+    }
+}
+
+///
+/// # 帳簿の上に開くメニューの束
+/// 状態、題目、名前、日付、延滞の確かめ、ひとことのメニューを、MenuStackに積んで扱う
+///
+pub struct RecordBookMenuGroup {
+    menus: MenuStack<RecordBookMenu>,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl RecordBookMenuGroup {
+    pub fn new(drawing_depth: i8) -> Self {
+        RecordBookMenuGroup {
+            menus: MenuStack::new(),
+            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
+        }
+    }
+
+    pub fn is_some_menu_opened(&self) -> bool {
+        !self.menus.is_empty()
+    }
+
+    pub fn is_opened(&self, kind: RecordBookMenuKind) -> bool {
+        self.menus.is_opened(kind)
+    }
+
+    pub fn close_menu(&mut self, kind: RecordBookMenuKind, t: Clock) {
+        self.menus.close(kind, t);
+    }
+
+    pub fn close_book_status_menu(&mut self, t: Clock) {
+        self.close_menu(RecordBookMenuKind::BookStatus, t);
+    }
+
+    pub fn close_book_title_menu(&mut self, t: Clock) {
+        self.close_menu(RecordBookMenuKind::BookTitle, t);
+    }
+
+    pub fn close_customer_name_menu(&mut self, t: Clock) {
+        self.close_menu(RecordBookMenuKind::CustomerName, t);
+    }
+
+    pub fn close_date_menu(&mut self, t: Clock) {
+        self.close_menu(RecordBookMenuKind::Date, t);
+    }
+
+    pub fn close_date_check_menu(&mut self, t: Clock) {
+        self.close_menu(RecordBookMenuKind::DateCheck, t);
+    }
+
+    pub fn close_simple_message_menu(&mut self, t: Clock) {
+        self.close_menu(RecordBookMenuKind::SimpleMessage, t);
+    }
+
+    pub fn is_contains_any_menus(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
+        self.menus.contains(ctx, point)
+    }
+
+    pub fn get_menu_position(&self, kind: RecordBookMenuKind) -> Option<numeric::Point2f> {
+        self.menus.get(kind).map(|menu| menu.get_click_position())
+    }
+
+    pub fn get_book_status_menu_position(&self) -> Option<numeric::Point2f> {
+        self.get_menu_position(RecordBookMenuKind::BookStatus)
+    }
+
+    pub fn get_book_title_menu_position(&self) -> Option<numeric::Point2f> {
+        self.get_menu_position(RecordBookMenuKind::BookTitle)
+    }
+
+    pub fn get_customer_name_menu_position(&self) -> Option<numeric::Point2f> {
+        self.get_menu_position(RecordBookMenuKind::CustomerName)
+    }
+
+    pub fn get_date_menu_position(&self) -> Option<numeric::Point2f> {
+        self.get_menu_position(RecordBookMenuKind::Date)
+    }
+
+    pub fn get_date_check_menu_position(&self) -> Option<numeric::Point2f> {
+        self.get_menu_position(RecordBookMenuKind::DateCheck)
+    }
+
+    pub fn get_simple_message_menu_position(&self) -> Option<numeric::Point2f> {
+        self.get_menu_position(RecordBookMenuKind::SimpleMessage)
+    }
+
+    ///
+    /// pointを含むメニューのうち、一番上のものにクリックを渡す
+    ///
+    /// クリックしたメニューの種類を返す。どのメニューもクリックしていなければNone
+    ///
+    pub fn click_menu<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> Option<RecordBookMenuKind> {
+        self.menus.click(ctx, button, point, t)
+    }
+
+    pub fn book_status_menu_last_clicked(&mut self) -> Option<usize> {
+        match self.menus.get_mut(RecordBookMenuKind::BookStatus) {
+            Some(RecordBookMenu::BookStatus(menu)) => menu.get_component().get_last_clicked(),
+            _ => None,
+        }
+    }
+
+    pub fn book_title_menu_last_clicked(&mut self) -> Option<(usize, BookInformation)> {
+        match self.menus.get_mut(RecordBookMenuKind::BookTitle) {
+            Some(RecordBookMenu::BookTitle(menu)) => {
+                let component = menu.get_component();
+                let index = component.get_last_clicked_index()?;
+                Some((index, component.get_last_clicked_book_info()?))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn customer_name_menu_last_clicked(&mut self) -> Option<(usize, String)> {
+        match self.menus.get_mut(RecordBookMenuKind::CustomerName) {
+            Some(RecordBookMenu::CustomerName(menu)) => {
+                let component = menu.get_component();
+                let index = component.get_last_clicked_index()?;
+                Some((index, component.get_last_clicked_customer_name()?))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn date_menu_last_clicked(&mut self) -> Option<(usize, GensoDate)> {
+        match self.menus.get_mut(RecordBookMenuKind::Date) {
+            Some(RecordBookMenu::Date(menu)) => {
+                let component = menu.get_component();
+                let index = component.get_last_clicked_index()?;
+                Some((index, component.get_last_clicked_genso_date()?))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn date_check_menu_check_button_clicked(&mut self) -> bool {
+        match self.menus.get_mut(RecordBookMenuKind::DateCheck) {
+            Some(RecordBookMenu::DateCheck(menu)) => {
+                menu.get_component_mut().check_button_is_clicked_volatile()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn close_all(&mut self, t: Clock) {
+        self.menus.close_all(t);
+    }
+
+    ///
+    /// 消える演出を待たずに、全てのメニューをすぐに消す
+    ///
+    pub fn dismiss_all(&mut self) {
+        self.menus.dismiss_all();
+    }
+
+    pub fn show_book_status_menu<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        t: Clock,
+    ) {
+        let button_group =
+            BookStatusButtonGroup::new(ctx, numeric::Rect::new(0.0, 0.0, 70.0, 70.0), 20.0, 0);
+
+        let mut button_group_area = DropDownArea::new(
+            ctx,
+            position,
+            numeric::Rect::new(position.x, position.y, 290.0, 180.0),
+            0,
+            button_group,
+            t,
+        );
 
         button_group_area.add_effect(vec![effect::fade_in(10, t)]);
 
-        self.book_status_menu = Some(button_group_area);
+        self.menus
+            .open(RecordBookMenu::BookStatus(button_group_area));
     }
 
     pub fn show_book_title_menu<'a>(
@@ -2441,7 +2641,8 @@ impl RecordBookMenuGroup {
             DropDownArea::new(ctx, position, menu_rect, 0, book_title_menu, t);
         book_title_menu_area.add_effect(vec![effect::fade_in(10, t)]);
 
-        self.book_title_menu = Some(book_title_menu_area);
+        self.menus
+            .open(RecordBookMenu::BookTitle(book_title_menu_area));
     }
 
     fn show_simple_message_menu<'a>(
@@ -2487,7 +2688,7 @@ impl RecordBookMenuGroup {
 
         drop_menu.add_effect(vec![effect::fade_in(10, t)]);
 
-        self.simple_message_menu = Some(drop_menu);
+        self.menus.open(RecordBookMenu::SimpleMessage(drop_menu));
     }
 
     pub fn show_locked_menu<'a>(
@@ -2512,7 +2713,7 @@ impl RecordBookMenuGroup {
         kosuzu_memory: &KosuzuMemory,
         t: Clock,
     ) {
-        if kosuzu_memory.customers_name.is_empty() {
+        if kosuzu_memory.get_customers_name().is_empty() {
             self.show_simple_message_menu(
                 ctx,
                 position,
@@ -2524,7 +2725,7 @@ impl RecordBookMenuGroup {
         }
 
         let mut customer_name_menu =
-            CustomerNameMenu::new(ctx, kosuzu_memory.customers_name.clone(), 0);
+            CustomerNameMenu::new(ctx, kosuzu_memory.get_customers_name().clone(), 0);
 
         let frame_size = customer_name_menu.get_name_frame_size();
 
@@ -2551,7 +2752,8 @@ impl RecordBookMenuGroup {
             DropDownArea::new(ctx, position, menu_rect, 0, customer_name_menu, t);
         customer_name_menu_area.add_effect(vec![effect::fade_in(10, t)]);
 
-        self.customer_name_menu = Some(customer_name_menu_area);
+        self.menus
+            .open(RecordBookMenu::CustomerName(customer_name_menu_area));
     }
 
     pub fn show_date_menu<'a>(
@@ -2587,7 +2789,7 @@ impl RecordBookMenuGroup {
         let mut date_menu_area = DropDownArea::new(ctx, position, menu_rect, 0, date_menu, t);
         date_menu_area.add_effect(vec![effect::fade_in(10, t)]);
 
-        self.date_menu = Some(date_menu_area);
+        self.menus.open(RecordBookMenu::Date(date_menu_area));
     }
 
     pub fn show_date_check_menu<'a>(
@@ -2624,48 +2826,14 @@ impl RecordBookMenuGroup {
         let mut date_menu_area = DropDownArea::new(ctx, position, menu_rect, 0, date_menu, t);
         date_menu_area.add_effect(vec![effect::fade_in(10, t)]);
 
-        self.date_check_menu = Some(date_menu_area);
+        self.menus.open(RecordBookMenu::DateCheck(date_menu_area));
     }
 
     ///
     /// # 再描画要求有り
     ///
     pub fn update<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
-        flush_delay_event_and_redraw_check!(self, self.event_list, ctx, t, {});
-
-        if let Some(book_status_menu) = self.book_status_menu.as_mut() {
-            book_status_menu.move_with_func(t);
-            book_status_menu.effect(ctx.context, t);
-            ctx.process_utility.redraw();
-        }
-
-        if let Some(book_title_menu) = self.book_title_menu.as_mut() {
-            book_title_menu.move_with_func(t);
-            book_title_menu.effect(ctx.context, t);
-            ctx.process_utility.redraw();
-        }
-
-        if let Some(customer_name_menu) = self.customer_name_menu.as_mut() {
-            customer_name_menu.move_with_func(t);
-            customer_name_menu.effect(ctx.context, t);
-            ctx.process_utility.redraw();
-        }
-
-        if let Some(date_menu) = self.date_menu.as_mut() {
-            date_menu.move_with_func(t);
-            date_menu.effect(ctx.context, t);
-            ctx.process_utility.redraw();
-        }
-
-        if let Some(date_menu) = self.date_check_menu.as_mut() {
-            date_menu.move_with_func(t);
-            date_menu.effect(ctx.context, t);
-            ctx.process_utility.redraw();
-        }
-
-        if let Some(msg_menu) = self.simple_message_menu.as_mut() {
-            msg_menu.move_with_func(t);
-            msg_menu.effect(ctx.context, t);
+        if self.menus.update(ctx.context, t) {
             ctx.process_utility.redraw();
         }
     }
@@ -2674,29 +2842,7 @@ impl RecordBookMenuGroup {
 impl DrawableComponent for RecordBookMenuGroup {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
         if self.is_visible() {
-            if let Some(book_status_menu) = self.book_status_menu.as_mut() {
-                book_status_menu.draw(ctx)?;
-            }
-
-            if let Some(book_title_menu) = self.book_title_menu.as_mut() {
-                book_title_menu.draw(ctx)?;
-            }
-
-            if let Some(customer_name_menu) = self.customer_name_menu.as_mut() {
-                customer_name_menu.draw(ctx)?;
-            }
-
-            if let Some(date_menu) = self.date_menu.as_mut() {
-                date_menu.draw(ctx)?;
-            }
-
-            if let Some(date_menu) = self.date_check_menu.as_mut() {
-                date_menu.draw(ctx)?;
-            }
-
-            if let Some(msg_menu) = self.simple_message_menu.as_mut() {
-                msg_menu.draw(ctx)?;
-            }
+            self.menus.draw(ctx)?;
         }
         Ok(())
     }
@@ -2878,6 +3024,7 @@ pub struct DeskBookMenu {
     book_info_drawer: BookInfoDrawer,
     book_info: BookInformation,
     memo_button: SelectButton,
+    magnifier_button: SelectButton,
     drwob_essential: DrawableObjectEssential,
     last_clicked: Option<usize>,
 }
@@ -2905,9 +3052,9 @@ impl DeskBookMenu {
 
         let book_info_drawer = BookInfoDrawer::new(ctx, book_info.clone(), 0);
 
-        let mut button_rect = numeric::Rect::new(110.0, 410.0, 140.0, 50.0);
+        let mut button_rect = numeric::Rect::new(30.0, 410.0, 140.0, 50.0);
         let mut buttons = Vec::new();
-        for s in vec!["メモ"].iter() {
+        for s in vec!["メモ", "虫眼鏡"].iter() {
             let text_texture = TextButtonTexture::new(
                 ctx,
                 numeric::Point2f::new(0.0, 0.0),
@@ -2928,6 +3075,7 @@ impl DeskBookMenu {
             header_text: header_text,
             book_info_drawer: book_info_drawer,
             book_info: book_info,
+            magnifier_button: buttons.pop().unwrap(),
             memo_button: buttons.pop().unwrap(),
             drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
             last_clicked: None,
@@ -2948,6 +3096,8 @@ impl DeskBookMenu {
     pub fn click_handler(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
         if self.memo_button.contains(ctx, point) {
             self.last_clicked = Some(0 as usize);
+        } else if self.magnifier_button.contains(ctx, point) {
+            self.last_clicked = Some(1 as usize);
         }
     }
 
@@ -2974,6 +3124,7 @@ impl DrawableComponent for DeskBookMenu {
             self.book_info_drawer.draw(ctx)?;
 
             self.memo_button.draw(ctx)?;
+            self.magnifier_button.draw(ctx)?;
         }
         Ok(())
     }
@@ -3013,46 +3164,324 @@ impl Clickable for DeskBookMenu {
 
 pub type DeskBookDropMenu = DropDownArea<DeskBookMenu>;
 
-pub struct OnDeskMenuGroup {
-    event_list: DelayEventList<Self>,
-    desk_book_menu: Option<DeskBookDropMenu>,
+///
+/// # 本の検分メニュー
+/// 虫眼鏡で調べる項目を選んで綻びを探し、本の扱いを決める
+///
+/// ## inspected
+/// 虫眼鏡で既に調べた項目
+///
+pub struct BookInspectionMenu {
+    header_text: UniText,
+    checklist_frame: TableFrame,
+    checklist_vtext: Vec<VerticalText>,
+    finding_vtext: Vec<VerticalText>,
+    decision_frame: TableFrame,
+    decision_vtext: Vec<VerticalText>,
+    book_info: BookInformation,
+    inspected: Vec<InspectionPoint>,
     drwob_essential: DrawableObjectEssential,
+    last_decision: Option<InspectionDecision>,
 }
 
-impl OnDeskMenuGroup {
-    pub fn new(drawing_depth: i8) -> Self {
-        OnDeskMenuGroup {
-            event_list: DelayEventList::new(),
-            desk_book_menu: None,
+impl BookInspectionMenu {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        book_info: BookInformation,
+        drawing_depth: i8,
+    ) -> Self {
+        let header_text = UniText::new(
+            "本の検分".to_string(),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(30.0, 30.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+        );
+
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        let checklist_frame = TableFrame::new(
+            ctx.resource,
+            numeric::Point2f::new(0.0, 0.0),
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![110.0, 170.0], vec![56.0; InspectionPoint::ALL.len()]),
+            numeric::Vector2f::new(0.3, 0.3),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let decision_frame = TableFrame::new(
+            ctx.resource,
+            numeric::Point2f::new(0.0, 0.0),
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![140.0], vec![56.0; InspectionDecision::ALL.len()]),
+            numeric::Vector2f::new(0.3, 0.3),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let new_vtext = |s: &str| {
+            VerticalText::new(
+                s.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                drawing_depth,
+                font_info,
+            )
+        };
+
+        let checklist_vtext = InspectionPoint::ALL
+            .iter()
+            .map(|point| new_vtext(point.to_str()))
+            .collect();
+        let finding_vtext = InspectionPoint::ALL
+            .iter()
+            .map(|_| new_vtext("？"))
+            .collect();
+        let decision_vtext = InspectionDecision::ALL
+            .iter()
+            .map(|decision| new_vtext(decision.to_str()))
+            .collect();
+
+        let mut menu = BookInspectionMenu {
+            header_text: header_text,
+            checklist_frame: checklist_frame,
+            checklist_vtext: checklist_vtext,
+            finding_vtext: finding_vtext,
+            decision_frame: decision_frame,
+            decision_vtext: decision_vtext,
+            book_info: book_info,
+            inspected: Vec::new(),
             drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
-        }
-    }
+            last_decision: None,
+        };
 
-    pub fn is_some_menu_opened(&self) -> bool {
-        self.desk_book_menu.is_some()
+        let size = menu.get_menu_size();
+        let checklist_size = menu.checklist_frame.size();
+        let decision_size = menu.decision_frame.size();
+
+        menu.header_text
+            .make_center(ctx.context, numeric::Point2f::new(size.x / 2.0, 44.0));
+        menu.checklist_frame.make_center(numeric::Point2f::new(
+            size.x / 2.0,
+            80.0 + (checklist_size.y / 2.0),
+        ));
+        menu.decision_frame.make_center(numeric::Point2f::new(
+            size.x / 2.0,
+            100.0 + checklist_size.y + (decision_size.y / 2.0),
+        ));
+        menu.locate_texts(ctx.context);
+
+        menu
     }
 
-    pub fn close_desk_book_menu(&mut self, t: Clock) {
-        if let Some(desk_book_menu) = self.desk_book_menu.as_mut() {
-            desk_book_menu.add_effect(vec![effect::fade_out(10, t)]);
-            self.event_list.add_event(
-                Box::new(|slf: &mut OnDeskMenuGroup, _, _| slf.desk_book_menu = None),
-                t + 11,
+    fn locate_texts(&mut self, ctx: &mut ggez::Context) {
+        for (index, vtext) in self.checklist_vtext.iter_mut().enumerate() {
+            set_table_frame_cell_center!(
+                ctx,
+                self.checklist_frame,
+                vtext,
+                numeric::Vector2u::new(index as u32, 0)
             );
         }
-    }
 
-    pub fn contains_desk_book_menu(
-        &self,
-        ctx: &mut ggez::Context,
-        point: numeric::Point2f,
-    ) -> bool {
-        self.desk_book_menu.is_some() && self.desk_book_menu.as_ref().unwrap().contains(ctx, point)
-    }
-
-    pub fn is_contains_any_menus(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
-        self.contains_desk_book_menu(ctx, point)
-    }
+        for (index, vtext) in self.finding_vtext.iter_mut().enumerate() {
+            set_table_frame_cell_center!(
+                ctx,
+                self.checklist_frame,
+                vtext,
+                numeric::Vector2u::new(index as u32, 1)
+            );
+        }
+
+        for (index, vtext) in self.decision_vtext.iter_mut().enumerate() {
+            set_table_frame_cell_center!(
+                ctx,
+                self.decision_frame,
+                vtext,
+                numeric::Vector2u::new(index as u32, 0)
+            );
+        }
+    }
+
+    ///
+    /// 項目をクリックすると虫眼鏡で調べ、扱いをクリックするとそれを選ぶ
+    ///
+    pub fn click_handler(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
+        if let Some(grid_position) = self.checklist_frame.get_grid_position(ctx, point) {
+            let index = grid_position.x as usize;
+            if let Some(inspection_point) = InspectionPoint::ALL.get(index) {
+                if !self.inspected.contains(inspection_point) {
+                    self.inspected.push(*inspection_point);
+
+                    let finding = inspection_point.finding(self.book_info.get_anomaly());
+                    self.finding_vtext[index].replace_text(finding.to_string());
+                    self.locate_texts(ctx);
+                }
+            }
+            return;
+        }
+
+        if let Some(grid_position) = self.decision_frame.get_grid_position(ctx, point) {
+            self.last_decision = InspectionDecision::ALL
+                .get(grid_position.x as usize)
+                .copied();
+        }
+    }
+
+    pub fn get_last_decision(&self) -> Option<InspectionDecision> {
+        self.last_decision
+    }
+
+    pub fn get_target_book_info(&self) -> BookInformation {
+        self.book_info.clone()
+    }
+
+    pub fn get_menu_size(&self) -> numeric::Vector2f {
+        let checklist_size = self.checklist_frame.size();
+        let decision_size = self.decision_frame.size();
+
+        numeric::Vector2f::new(314.0, checklist_size.y + decision_size.y + 130.0)
+    }
+}
+
+impl DrawableComponent for BookInspectionMenu {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.header_text.draw(ctx)?;
+
+            self.checklist_frame.draw(ctx)?;
+            for vtext in &mut self.checklist_vtext {
+                vtext.draw(ctx)?;
+            }
+            for vtext in &mut self.finding_vtext {
+                vtext.draw(ctx)?;
+            }
+
+            self.decision_frame.draw(ctx)?;
+            for vtext in &mut self.decision_vtext {
+                vtext.draw(ctx)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+impl Clickable for BookInspectionMenu {
+    fn on_click<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        _t: Clock,
+        _button: ggez::event::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        self.click_handler(ctx.context, point);
+    }
+}
+
+pub type BookInspectionDropMenu = DropDownArea<BookInspectionMenu>;
+
+pub struct OnDeskMenuGroup {
+    event_list: DelayEventList<Self>,
+    desk_book_menu: Option<DeskBookDropMenu>,
+    book_inspection_menu: Option<BookInspectionDropMenu>,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl OnDeskMenuGroup {
+    pub fn new(drawing_depth: i8) -> Self {
+        OnDeskMenuGroup {
+            event_list: DelayEventList::new(),
+            desk_book_menu: None,
+            book_inspection_menu: None,
+            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
+        }
+    }
+
+    pub fn is_some_menu_opened(&self) -> bool {
+        self.desk_book_menu.is_some() || self.book_inspection_menu.is_some()
+    }
+
+    pub fn close_desk_book_menu(&mut self, t: Clock) {
+        if let Some(desk_book_menu) = self.desk_book_menu.as_mut() {
+            desk_book_menu.add_effect(vec![effect::fade_out(10, t)]);
+            self.event_list.add_event(
+                Box::new(|slf: &mut OnDeskMenuGroup, _, _| slf.desk_book_menu = None),
+                t + 11,
+            );
+        }
+    }
+
+    pub fn contains_desk_book_menu(
+        &self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> bool {
+        self.desk_book_menu.is_some() && self.desk_book_menu.as_ref().unwrap().contains(ctx, point)
+    }
+
+    pub fn close_book_inspection_menu(&mut self, t: Clock) {
+        if let Some(book_inspection_menu) = self.book_inspection_menu.as_mut() {
+            book_inspection_menu.add_effect(vec![effect::fade_out(10, t)]);
+            self.event_list.add_event(
+                Box::new(|slf: &mut OnDeskMenuGroup, _, _| slf.book_inspection_menu = None),
+                t + 11,
+            );
+        }
+    }
+
+    pub fn contains_book_inspection_menu(
+        &self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> bool {
+        self.book_inspection_menu.is_some()
+            && self
+                .book_inspection_menu
+                .as_ref()
+                .unwrap()
+                .contains(ctx, point)
+    }
+
+    pub fn is_contains_any_menus(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
+        self.contains_desk_book_menu(ctx, point) || self.contains_book_inspection_menu(ctx, point)
+    }
+
+    pub fn get_desk_book_menu_click_position(&self) -> Option<numeric::Point2f> {
+        self.desk_book_menu
+            .as_ref()
+            .map(|desk_book_menu| desk_book_menu.get_click_position())
+    }
 
     pub fn get_desk_book_menu_position(&self) -> Option<numeric::Point2f> {
         if let Some(desk_book_menu) = self.desk_book_menu.as_ref() {
@@ -3093,8 +3522,51 @@ impl OnDeskMenuGroup {
         }
     }
 
+    ///
+    /// 検分メニューをクリックしていたらtrueを返し、そうでなければfalseを返す
+    ///
+    pub fn click_book_inspection_menu<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> bool {
+        if !self.contains_book_inspection_menu(ctx.context, point) {
+            return false;
+        }
+
+        if let Some(book_inspection_menu) = self.book_inspection_menu.as_mut() {
+            book_inspection_menu.on_click(ctx, t, button, point);
+            ctx.process_utility.redraw();
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// 検分メニューで扱いが決まっていれば、対象の本と扱いを返す
+    ///
+    pub fn book_inspection_decision(&self) -> Option<(BookInformation, InspectionDecision)> {
+        let menu = self.book_inspection_menu.as_ref()?.get_component();
+        let decision = menu.get_last_decision()?;
+
+        Some((menu.get_target_book_info(), decision))
+    }
+
     pub fn close_all(&mut self, t: Clock) {
         self.close_desk_book_menu(t);
+        self.close_book_inspection_menu(t);
+    }
+
+    ///
+    /// 消える演出を待たずに、全てのメニューをすぐに消す
+    ///
+    pub fn dismiss_all(&mut self) {
+        self.event_list = DelayEventList::new();
+        self.desk_book_menu = None;
+        self.book_inspection_menu = None;
     }
 
     pub fn show_desk_book_menu<'a>(
@@ -3129,6 +3601,35 @@ impl OnDeskMenuGroup {
         self.desk_book_menu = Some(dd_area);
     }
 
+    pub fn show_book_inspection_menu<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        book_info: BookInformation,
+        t: Clock,
+    ) {
+        let menu = BookInspectionMenu::new(ctx, book_info, 0);
+        let menu_size = menu.get_menu_size();
+
+        let pos = util::find_proper_window_position(
+            numeric::Rect::new(position.x, position.y, menu_size.x, menu_size.y),
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                core::WINDOW_SIZE_X as f32,
+                core::WINDOW_SIZE_Y as f32,
+            ),
+        );
+
+        let menu_rect = numeric::Rect::new(pos.x, pos.y, menu_size.x, menu_size.y);
+
+        let mut dd_area = DropDownArea::new(ctx, position, menu_rect, 0, menu, t);
+
+        dd_area.add_effect(vec![effect::fade_in(10, t)]);
+
+        self.book_inspection_menu = Some(dd_area);
+    }
+
     ///
     /// # 再描画要求有り
     ///
@@ -3142,6 +3643,14 @@ impl OnDeskMenuGroup {
             desk_book_menu.move_with_func(t);
             desk_book_menu.effect(ctx.context, t);
         }
+
+        if let Some(book_inspection_menu) = self.book_inspection_menu.as_mut() {
+            if !book_inspection_menu.is_stop() || !book_inspection_menu.is_empty_effect() {
+                ctx.process_utility.redraw();
+            }
+            book_inspection_menu.move_with_func(t);
+            book_inspection_menu.effect(ctx.context, t);
+        }
     }
 
     pub fn get_desk_menu_target_book_info(&self) -> Option<BookInformation> {
@@ -3165,6 +3674,828 @@ impl DrawableComponent for OnDeskMenuGroup {
             if let Some(desk_book_menu) = self.desk_book_menu.as_mut() {
                 desk_book_menu.draw(ctx)?;
             }
+
+            if let Some(book_inspection_menu) = self.book_inspection_menu.as_mut() {
+                book_inspection_menu.draw(ctx)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+///
+/// # 机の上のドロップダウンメニューをまとめて持つもの
+/// 開いているメニューは、いつも多くて一つだけにする。新しく開くときは、開いていたメニューをすぐに消す
+/// メニューの外側をクリックしたとき、Escapeを押したとき、場面を移るときには全て閉じる
+///
+/// ## customer
+/// 客のシルエットに出すメニュー
+///
+/// ## record_book
+/// 帳簿の上に出すメニュー
+///
+/// ## on_desk
+/// 机の上の本に出すメニュー
+///
+pub struct MenuHost {
+    customer: CustomerMenuGroup,
+    record_book: RecordBookMenuGroup,
+    on_desk: OnDeskMenuGroup,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl MenuHost {
+    pub fn new(drawing_depth: i8) -> Self {
+        MenuHost {
+            customer: CustomerMenuGroup::new(0),
+            record_book: RecordBookMenuGroup::new(0),
+            on_desk: OnDeskMenuGroup::new(0),
+            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
+        }
+    }
+
+    pub fn customer_mut(&mut self) -> &mut CustomerMenuGroup {
+        &mut self.customer
+    }
+
+    pub fn record_book_mut(&mut self) -> &mut RecordBookMenuGroup {
+        &mut self.record_book
+    }
+
+    pub fn on_desk_mut(&mut self) -> &mut OnDeskMenuGroup {
+        &mut self.on_desk
+    }
+
+    fn dismiss_all(&mut self) {
+        self.customer.dismiss_all();
+        self.record_book.dismiss_all();
+        self.on_desk.dismiss_all();
+    }
+
+    ///
+    /// 開いているメニューを全て消してから、客のメニューを返す。メニューを開くときはこれを通す
+    ///
+    pub fn opening_customer(&mut self) -> &mut CustomerMenuGroup {
+        self.dismiss_all();
+        &mut self.customer
+    }
+
+    ///
+    /// 開いているメニューを全て消してから、帳簿のメニューを返す。メニューを開くときはこれを通す
+    ///
+    pub fn opening_record_book(&mut self) -> &mut RecordBookMenuGroup {
+        self.dismiss_all();
+        &mut self.record_book
+    }
+
+    ///
+    /// 開いているメニューを全て消してから、机の上のメニューを返す。メニューを開くときはこれを通す
+    ///
+    pub fn opening_on_desk(&mut self) -> &mut OnDeskMenuGroup {
+        self.dismiss_all();
+        &mut self.on_desk
+    }
+
+    pub fn is_some_menu_opened(&self) -> bool {
+        self.customer.is_some_menu_opened()
+            || self.record_book.is_some_menu_opened()
+            || self.on_desk.is_some_menu_opened()
+    }
+
+    pub fn is_contains_any_menus(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
+        self.customer.is_contains_any_menus(ctx, point)
+            || self.record_book.is_contains_any_menus(ctx, point)
+            || self.on_desk.is_contains_any_menus(ctx, point)
+    }
+
+    pub fn close_all(&mut self, t: Clock) {
+        self.customer.close_all(t);
+        self.record_book.close_all(t);
+        self.on_desk.close_all(t);
+    }
+
+    ///
+    /// メニューの外側でボタンが離されたら、全てのメニューを閉じる
+    ///
+    pub fn close_if_outside(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f, t: Clock) {
+        if !self.is_contains_any_menus(ctx, point) {
+            self.close_all(t);
+        }
+    }
+
+    ///
+    /// Escapeが押されたとき。閉じるメニューがあればtrueを返す
+    ///
+    pub fn close_by_escape(&mut self, t: Clock) -> bool {
+        if !self.is_some_menu_opened() {
+            return false;
+        }
+
+        self.close_all(t);
+        true
+    }
+
+    ///
+    /// # 再描画要求有り
+    ///
+    pub fn update<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.record_book.update(ctx, t);
+        self.customer.update(ctx, t);
+        self.on_desk.update(ctx, t);
+    }
+}
+
+impl DrawableComponent for MenuHost {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.customer.draw(ctx)?;
+            self.record_book.draw(ctx)?;
+            self.on_desk.draw(ctx)?;
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+///
+/// # 貸出中の本の予約票
+/// 予約を申し出た客の名前と本の題名を見せ、予約を受けるか断るかを選ばせる
+///
+/// ## choice_table_frame
+/// 左から「予約を受ける」「断る」
+///
+pub struct ReservationForm {
+    background: UniTexture,
+    heading: VerticalText,
+    info_table_frame: TableFrame,
+    info_vtext: Vec<VerticalText>,
+    choice_table_frame: TableFrame,
+    choice_vtext: Vec<VerticalText>,
+    customer_name: String,
+    book_title: String,
+    drwob_essential: DrawableObjectEssential,
+    last_clicked: Option<usize>,
+}
+
+impl ReservationForm {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        customer_name: &str,
+        book_title: &str,
+        drawing_depth: i8,
+    ) -> Self {
+        let form_size = numeric::Vector2f::new(420.0, 440.0);
+
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            position,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(ctx.context, form_size);
+
+        let heading = VerticalText::new(
+            "予約票".to_string(),
+            numeric::Point2f::new(position.x + form_size.x - 56.0, position.y + 30.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            drawing_depth,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(30.0, 30.0),
+                ggraphics::Color::from_rgba_u32(0xaa2222ff),
+            ),
+        );
+
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        let info_table_frame = TableFrame::new(
+            ctx.resource,
+            numeric::Point2f::new(position.x + 160.0, position.y + 20.0),
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![80.0, 300.0], vec![56.0; 2]),
+            numeric::Vector2f::new(0.3, 0.3),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let mut info_vtext = Vec::new();
+        for (index, (label, value)) in vec![("題名", book_title), ("御客", customer_name)]
+            .iter()
+            .enumerate()
+        {
+            for (row, s) in vec![label, value].iter().enumerate() {
+                let mut vtext = VerticalText::new(
+                    s.to_string(),
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    drawing_depth,
+                    font_info,
+                );
+
+                set_table_frame_cell_center!(
+                    ctx.context,
+                    info_table_frame,
+                    vtext,
+                    numeric::Vector2u::new(index as u32, row as u32)
+                );
+
+                info_vtext.push(vtext);
+            }
+        }
+
+        let choice_table_frame = TableFrame::new(
+            ctx.resource,
+            numeric::Point2f::new(position.x + 20.0, position.y + 20.0),
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![220.0], vec![56.0; 2]),
+            numeric::Vector2f::new(0.3, 0.3),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let mut choice_vtext = Vec::new();
+        for (index, s) in vec!["予約を受ける", "断る"].iter().enumerate() {
+            let mut vtext = VerticalText::new(
+                s.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                drawing_depth,
+                font_info,
+            );
+
+            set_table_frame_cell_center!(
+                ctx.context,
+                choice_table_frame,
+                vtext,
+                numeric::Vector2u::new(index as u32, 0)
+            );
+
+            choice_vtext.push(vtext);
+        }
+
+        ReservationForm {
+            background: background,
+            heading: heading,
+            info_table_frame: info_table_frame,
+            info_vtext: info_vtext,
+            choice_table_frame: choice_table_frame,
+            choice_vtext: choice_vtext,
+            customer_name: customer_name.to_string(),
+            book_title: book_title.to_string(),
+            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
+            last_clicked: None,
+        }
+    }
+
+    pub fn click_handler(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
+        let maybe_grid_position = self.choice_table_frame.get_grid_position(ctx, point);
+        if let Some(grid_position) = maybe_grid_position {
+            self.last_clicked = Some(grid_position.x as usize);
+        }
+    }
+
+    pub fn get_last_clicked_index(&self) -> Option<usize> {
+        self.last_clicked
+    }
+
+    pub fn get_customer_name(&self) -> &str {
+        &self.customer_name
+    }
+
+    pub fn get_book_title(&self) -> &str {
+        &self.book_title
+    }
+}
+
+impl DrawableComponent for ReservationForm {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.background.draw(ctx)?;
+            self.heading.draw(ctx)?;
+
+            self.info_table_frame.draw(ctx)?;
+            for vtext in &mut self.info_vtext {
+                vtext.draw(ctx)?;
+            }
+
+            self.choice_table_frame.draw(ctx)?;
+            for vtext in &mut self.choice_vtext {
+                vtext.draw(ctx)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+///
+/// # 会員証の確認票
+/// 机の会員証と今回借りる冊数を見比べ、貸すか、期限切れの会員証を更新するか、断るかを選ばせる
+///
+/// ## choice_table_frame
+/// 左から「貸し出す」「更新する」「断る」
+///
+pub struct MembershipForm {
+    background: UniTexture,
+    heading: VerticalText,
+    info_table_frame: TableFrame,
+    info_vtext: Vec<VerticalText>,
+    choice_table_frame: TableFrame,
+    choice_vtext: Vec<VerticalText>,
+    card: MembershipCard,
+    requested: usize,
+    drwob_essential: DrawableObjectEssential,
+    last_clicked: Option<usize>,
+}
+
+impl MembershipForm {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        card: MembershipCard,
+        requested: usize,
+        drawing_depth: i8,
+    ) -> Self {
+        let form_size = numeric::Vector2f::new(420.0, 440.0);
+
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            position,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(ctx.context, form_size);
+
+        let heading = VerticalText::new(
+            "会員証確認票".to_string(),
+            numeric::Point2f::new(position.x + form_size.x - 56.0, position.y + 30.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            drawing_depth,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(30.0, 30.0),
+                ggraphics::Color::from_rgba_u32(0xaa2222ff),
+            ),
+        );
+
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        let info_table_frame = TableFrame::new(
+            ctx.resource,
+            numeric::Point2f::new(position.x + 200.0, position.y + 20.0),
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![80.0, 300.0], vec![56.0; 2]),
+            numeric::Vector2f::new(0.3, 0.3),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let requested_text = ctx.number_format().count(requested as u64, "冊");
+        let mut info_vtext = Vec::new();
+        for (index, (label, value)) in vec![
+            ("今回", requested_text.as_str()),
+            ("御客", card.holder.as_str()),
+        ]
+        .iter()
+        .enumerate()
+        {
+            for (row, s) in vec![label, value].iter().enumerate() {
+                let mut vtext = VerticalText::new(
+                    s.to_string(),
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    drawing_depth,
+                    font_info,
+                );
+
+                set_table_frame_cell_center!(
+                    ctx.context,
+                    info_table_frame,
+                    vtext,
+                    numeric::Vector2u::new(index as u32, row as u32)
+                );
+
+                info_vtext.push(vtext);
+            }
+        }
+
+        let choice_table_frame = TableFrame::new(
+            ctx.resource,
+            numeric::Point2f::new(position.x + 20.0, position.y + 20.0),
+            TileBatchTextureID::OldStyleFrame,
+            FrameData::new(vec![220.0], vec![56.0; 3]),
+            numeric::Vector2f::new(0.3, 0.3),
+            ggraphics::FilterMode::Nearest,
+            0,
+        );
+
+        let mut choice_vtext = Vec::new();
+        for (index, s) in vec!["貸し出す", "更新する", "断る"].iter().enumerate() {
+            let mut vtext = VerticalText::new(
+                s.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                drawing_depth,
+                font_info,
+            );
+
+            set_table_frame_cell_center!(
+                ctx.context,
+                choice_table_frame,
+                vtext,
+                numeric::Vector2u::new(index as u32, 0)
+            );
+
+            choice_vtext.push(vtext);
+        }
+
+        MembershipForm {
+            background: background,
+            heading: heading,
+            info_table_frame: info_table_frame,
+            info_vtext: info_vtext,
+            choice_table_frame: choice_table_frame,
+            choice_vtext: choice_vtext,
+            card: card,
+            requested: requested,
+            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
+            last_clicked: None,
+        }
+    }
+
+    pub fn click_handler(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
+        let maybe_grid_position = self.choice_table_frame.get_grid_position(ctx, point);
+        if let Some(grid_position) = maybe_grid_position {
+            self.last_clicked = Some(grid_position.x as usize);
+        }
+    }
+
+    pub fn get_last_clicked_index(&self) -> Option<usize> {
+        self.last_clicked
+    }
+
+    pub fn get_card(&self) -> &MembershipCard {
+        &self.card
+    }
+
+    pub fn get_requested(&self) -> usize {
+        self.requested
+    }
+}
+
+impl DrawableComponent for MembershipForm {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.background.draw(ctx)?;
+            self.heading.draw(ctx)?;
+
+            self.info_table_frame.draw(ctx)?;
+            for vtext in &mut self.info_vtext {
+                vtext.draw(ctx)?;
+            }
+
+            self.choice_table_frame.draw(ctx)?;
+            for vtext in &mut self.choice_vtext {
+                vtext.draw(ctx)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
+
+///
+/// # 贈り物の包装台
+/// 包み紙の角を順に中央へ引き寄せて折り、最後に紐を輪に描いて結ぶ
+///
+/// ## held_corner
+/// 掴んで引きずっている角
+///
+pub struct GiftWrappingBoard {
+    background: UniTexture,
+    heading: VerticalText,
+    guide_text: UniText,
+    paper: ggraphics::Mesh,
+    paper_rect: numeric::Rect,
+    corner_marks: Vec<UniText>,
+    center_mark: UniText,
+    session: WrappingSession,
+    gesture: GestureRecognizer,
+    held_corner: Option<PaperCorner>,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl GiftWrappingBoard {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        drawing_depth: i8,
+    ) -> Self {
+        let board_size = numeric::Vector2f::new(440.0, 440.0);
+
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            position,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(ctx.context, board_size);
+
+        let heading = VerticalText::new(
+            "贈答包装".to_string(),
+            numeric::Point2f::new(position.x + board_size.x - 56.0, position.y + 30.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            drawing_depth,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(30.0, 30.0),
+                ggraphics::Color::from_rgba_u32(0xaa2222ff),
+            ),
+        );
+
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xff),
+        );
+
+        let guide_text = UniText::new(
+            "光る角から中央へ折る".to_string(),
+            numeric::Point2f::new(position.x + 30.0, position.y + 390.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            drawing_depth,
+            font_info,
+        );
+
+        let paper_rect = numeric::Rect::new(position.x + 70.0, position.y + 60.0, 280.0, 280.0);
+        let paper_shape = shape::Rectangle::new(
+            paper_rect,
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xc9d9b8ff),
+        );
+        let mut builder = ggraphics::MeshBuilder::new();
+        paper_shape.add_to_builder(&mut builder);
+
+        let corner_marks = PaperCorner::ALL
+            .iter()
+            .map(|_| {
+                UniText::new(
+                    "●".to_string(),
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    drawing_depth,
+                    font_info,
+                )
+            })
+            .collect();
+
+        let mut center_mark = UniText::new(
+            "＋".to_string(),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            drawing_depth,
+            font_info,
+        );
+        center_mark.make_center(ctx.context, Self::rect_center(paper_rect));
+
+        let mut board = GiftWrappingBoard {
+            background: background,
+            heading: heading,
+            guide_text: guide_text,
+            paper: builder.build(ctx.context).unwrap(),
+            paper_rect: paper_rect,
+            corner_marks: corner_marks,
+            center_mark: center_mark,
+            session: WrappingSession::new(),
+            gesture: GestureRecognizer::new(),
+            held_corner: None,
+            drwob_essential: DrawableObjectEssential::new(true, drawing_depth),
+        };
+        board.reset_corner_marks(ctx.context);
+
+        board
+    }
+
+    fn rect_center(rect: numeric::Rect) -> numeric::Point2f {
+        numeric::Point2f::new(rect.x + (rect.w / 2.0), rect.y + (rect.h / 2.0))
+    }
+
+    fn corner_position(&self, corner: PaperCorner) -> numeric::Point2f {
+        let rect = self.paper_rect;
+        match corner {
+            PaperCorner::TopLeft => numeric::Point2f::new(rect.x, rect.y),
+            PaperCorner::TopRight => numeric::Point2f::new(rect.x + rect.w, rect.y),
+            PaperCorner::BottomRight => numeric::Point2f::new(rect.x + rect.w, rect.y + rect.h),
+            PaperCorner::BottomLeft => numeric::Point2f::new(rect.x, rect.y + rect.h),
+        }
+    }
+
+    fn corner_index(corner: PaperCorner) -> usize {
+        PaperCorner::ALL.iter().position(|c| *c == corner).unwrap()
+    }
+
+    ///
+    /// 折っていない角の印を元の位置に戻し、次に折る角だけを赤くする
+    ///
+    fn reset_corner_marks(&mut self, ctx: &mut ggez::Context) {
+        let next_corner = self.session.next_corner();
+
+        for corner in PaperCorner::ALL.iter() {
+            let position = self.corner_position(*corner);
+            let folded = self.session.is_folded(*corner);
+            let mark = &mut self.corner_marks[Self::corner_index(*corner)];
+
+            mark.make_center(ctx, position);
+            mark.set_color(if Some(*corner) == next_corner {
+                ggraphics::Color::from_rgba_u32(0xcc3333ff)
+            } else {
+                ggraphics::Color::from_rgba_u32(0x555555ff)
+            });
+
+            if folded {
+                mark.hide();
+            } else {
+                mark.appear();
+            }
+        }
+    }
+
+    pub fn button_down(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
+        if self.session.all_folded() {
+            self.gesture.begin(point);
+            return;
+        }
+
+        self.held_corner = PaperCorner::ALL
+            .iter()
+            .find(|corner| {
+                !self.session.is_folded(**corner)
+                    && distance!(self.corner_position(**corner), point) < 30.0
+            })
+            .copied();
+
+        if let Some(corner) = self.held_corner {
+            self.corner_marks[Self::corner_index(corner)].make_center(ctx, point);
+        }
+    }
+
+    pub fn mouse_motion(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
+        if self.gesture.is_recording() {
+            self.gesture.push(point);
+        }
+
+        if let Some(corner) = self.held_corner {
+            self.corner_marks[Self::corner_index(corner)].make_center(ctx, point);
+        }
+    }
+
+    pub fn button_up(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
+        if self.gesture.is_recording() {
+            self.gesture.push(point);
+            if self.session.tie(self.gesture.finish()) {
+                self.guide_text.replace_text("包み終わった".to_string());
+            } else {
+                self.guide_text.replace_text("紐を輪にして結ぶ".to_string());
+            }
+            return;
+        }
+
+        if let Some(corner) = self.held_corner.take() {
+            let center = Self::rect_center(self.paper_rect);
+            let precision = 1.0 - (distance!(center, point) / (self.paper_rect.w / 2.0));
+
+            // 中央まで引き寄せなかった角は、折らずに元に戻す
+            if precision > 0.0 && !self.session.fold(corner, precision) {
+                self.guide_text.replace_text("その角はまだ早い".to_string());
+            } else if self.session.all_folded() {
+                self.guide_text.replace_text("紐を輪にして結ぶ".to_string());
+            }
+
+            self.reset_corner_marks(ctx);
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.session.is_done()
+    }
+
+    pub fn get_quality(&self) -> u32 {
+        self.session.quality()
+    }
+}
+
+impl DrawableComponent for GiftWrappingBoard {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            self.background.draw(ctx)?;
+            self.heading.draw(ctx)?;
+
+            ggraphics::draw(ctx, &self.paper, ggraphics::DrawParam::default())?;
+            self.center_mark.draw(ctx)?;
+            for mark in &mut self.corner_marks {
+                mark.draw(ctx)?;
+            }
+
+            self.guide_text.draw(ctx)?;
         }
         Ok(())
     }
@@ -2,6 +2,8 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use ggez::graphics as ggraphics;
+use ggez::input::mouse::CursorIcon;
+use rand::Rng;
 
 use torifune::core::Clock;
 use torifune::graphics::drawable::*;
@@ -15,6 +17,11 @@ use torifune::impl_texture_object_for_wrapped;
 use torifune::numeric;
 use torifune::roundup2f;
 
+use crate::object::desk_rotation::{
+    normalize_rotation, rotated_bounds, rotated_contains, unrotate_point,
+};
+use crate::object::desk_shortcut::RecordBookCell;
+use crate::object::effect;
 use crate::object::move_fn;
 use crate::object::util_object::*;
 use crate::set_table_frame_cell_center;
@@ -27,14 +34,23 @@ use crate::{
 use serde::{Deserialize, Serialize};
 
 use super::{tt_main_component::CustomerRequest, Clickable};
+use crate::core::mail::Letter;
+use crate::core::membership::MembershipCard;
+use crate::core::memo_parser::{MemoChip, MemoParser, MEMO_MAX_CHARS};
+use crate::core::preferences::TextOrientation;
+use crate::core::shortcut_palette::PaletteKey;
 use crate::core::*;
+use crate::object::cell_alignment::{CellAlignment, CellAnchor, PixelSnap};
+use crate::object::highlight::{Highlight, SELECTION_HIGHLIGHT_COLOR};
 use crate::scene::DrawRequest;
 
-use number_to_jk::number_to_jk;
-
+///
+/// # HoldDataを表示する文字列
+/// 文字の向きは言語設定に従う
+///
 pub struct HoldDataVText {
     pub data: HoldData,
-    pub vtext: VerticalText,
+    pub text: OrientedText,
 }
 
 impl HoldDataVText {
@@ -44,9 +60,11 @@ impl HoldDataVText {
         scale: numeric::Vector2f,
         drawing_depth: i8,
         font_info: FontInformation,
+        orientation: TextOrientation,
     ) -> Self {
         HoldDataVText {
-            vtext: VerticalText::new(
+            text: OrientedText::new(
+                orientation,
                 hold_data.to_string(),
                 position,
                 scale,
@@ -60,7 +78,7 @@ impl HoldDataVText {
 
     pub fn reset(&mut self, hold_data: HoldData) {
         self.data = hold_data;
-        self.vtext.replace_text(self.data.to_string());
+        self.text.replace_text(self.data.to_string());
     }
 
     pub fn copy_hold_data(&self) -> HoldData {
@@ -78,39 +96,39 @@ impl HoldDataVText {
 
 impl DrawableComponent for HoldDataVText {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
-        if self.vtext.is_visible() {
-            self.vtext.draw(ctx).unwrap();
+        if self.text.is_visible() {
+            self.text.draw(ctx).unwrap();
         }
 
         Ok(())
     }
 
     fn hide(&mut self) {
-        self.vtext.hide();
+        self.text.hide();
     }
     fn appear(&mut self) {
-        self.vtext.appear();
+        self.text.appear();
     }
 
     fn is_visible(&self) -> bool {
-        self.vtext.is_visible()
+        self.text.is_visible()
     }
 
     fn set_drawing_depth(&mut self, depth: i8) {
-        self.vtext.set_drawing_depth(depth);
+        self.text.set_drawing_depth(depth);
     }
 
     fn get_drawing_depth(&self) -> i8 {
-        self.vtext.get_drawing_depth()
+        self.text.get_drawing_depth()
     }
 }
 
 impl DrawableObject for HoldDataVText {
-    impl_drawable_object_for_wrapped! {vtext}
+    impl_drawable_object_for_wrapped! {text}
 }
 
 impl TextureObject for HoldDataVText {
-    impl_texture_object_for_wrapped! {vtext}
+    impl_texture_object_for_wrapped! {text}
 }
 
 ///
@@ -187,6 +205,11 @@ pub enum OnDeskType {
     Goods,
     Texture,
     Coin,
+    PopularBookBoard,
+    MemoPad,
+    Bundle,
+    Letter,
+    MembershipCard,
 }
 
 pub trait OnDesk: TextureObject + Clickable {
@@ -198,8 +221,22 @@ pub trait OnDesk: TextureObject + Clickable {
         HoldData::None
     }
 
+    ///
+    /// どこかをクリックすれば、HoldDataを覚えられるか。調べる構えのときに縁取る
+    ///
+    fn has_click_data(&self) -> bool {
+        self.get_hold_data() != HoldData::None
+    }
+
     fn get_type(&self) -> OnDeskType;
 
+    ///
+    /// カーソルを暫く乗せたときに出す説明。出さない物はNone
+    ///
+    fn tooltip_text(&self) -> Option<String> {
+        None
+    }
+
     fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {}
 
     fn finish_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {}
@@ -347,20 +384,26 @@ impl OnDeskBook {
 
         let mut scratch_texture = match info.get_condition() {
             BookCondition::Good => None,
-            BookCondition::Fair => Some(UniTexture::new(
-                ctx.ref_texture(TextureID::random_large_book_scratch_fair()),
-                numeric::Point2f::new(6.0, 6.0),
-                numeric::Vector2f::new(0.16, 0.16),
-                0.0,
-                0,
-            )),
-            BookCondition::Bad => Some(UniTexture::new(
-                ctx.ref_texture(TextureID::random_large_book_scratch_bad()),
-                numeric::Point2f::new(6.0, 6.0),
-                numeric::Vector2f::new(0.16, 0.16),
-                0.0,
-                0,
-            )),
+            BookCondition::Fair => {
+                let scratch_id = TextureID::random_large_book_scratch_fair(ctx.resource.rng());
+                Some(UniTexture::new(
+                    ctx.ref_texture(scratch_id),
+                    numeric::Point2f::new(6.0, 6.0),
+                    numeric::Vector2f::new(0.16, 0.16),
+                    0.0,
+                    0,
+                ))
+            }
+            BookCondition::Bad => {
+                let scratch_id = TextureID::random_large_book_scratch_bad(ctx.resource.rng());
+                Some(UniTexture::new(
+                    ctx.ref_texture(scratch_id),
+                    numeric::Point2f::new(6.0, 6.0),
+                    numeric::Vector2f::new(0.16, 0.16),
+                    0.0,
+                    0,
+                ))
+            }
         };
 
         if let Some(texture) = scratch_texture.as_mut() {
@@ -410,20 +453,1263 @@ impl OnDeskBook {
             ),
         );
 
-        title_vtext.make_center(ctx.context, title_center);
+        title_vtext.make_center(ctx.context, title_center);
+
+        OnDeskBook {
+            info: info,
+            book_texture: book_texture,
+            scratch_texture: scratch_texture,
+            shadow: shadow,
+            title: title_vtext,
+            canvas: canvas,
+        }
+    }
+
+    pub fn get_book_info(&self) -> &BookInformation {
+        &self.info
+    }
+
+    pub fn disable_shadow(&mut self) {
+        self.shadow.hide();
+    }
+
+    pub fn enable_shadow(&mut self) {
+        self.shadow.appear();
+    }
+}
+
+impl DrawableComponent for OnDeskBook {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.shadow.draw(ctx)?;
+
+            self.book_texture.draw(ctx)?;
+            self.title.draw(ctx)?;
+
+            if let Some(scratch_texture) = self.scratch_texture.as_mut() {
+                scratch_texture.draw(ctx)?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl DrawableObject for OnDeskBook {
+    fn set_position(&mut self, pos: numeric::Point2f) {
+        self.canvas.set_position(pos);
+    }
+
+    fn get_position(&self) -> numeric::Point2f {
+        self.canvas.get_position()
+    }
+
+    fn move_diff(&mut self, offset: numeric::Vector2f) {
+        self.canvas.move_diff(offset);
+    }
+}
+
+impl TextureObject for OnDeskBook {
+    impl_texture_object_for_wrapped! {canvas}
+}
+
+impl Clickable for OnDeskBook {}
+
+impl OnDesk for OnDeskBook {
+    fn ondesk_whose(&self) -> i32 {
+        0
+    }
+
+    fn click_hold_data(&self, _: &mut ggez::Context, _: numeric::Point2f) -> HoldData {
+        return HoldData::BookName(self.info.clone());
+    }
+
+    fn get_hold_data(&self) -> HoldData {
+        HoldData::BookName(self.info.clone())
+    }
+
+    fn get_type(&self) -> OnDeskType {
+        OnDeskType::Book
+    }
+
+    fn tooltip_text(&self) -> Option<String> {
+        Some(format!("{}　{}頁", self.info.name, self.info.pages))
+    }
+
+    fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.enable_shadow();
+    }
+
+    fn finish_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.disable_shadow();
+    }
+}
+
+///
+/// # 机に置く「人気の本」の掲示
+/// 今週よく求められた本の題名を縦書きで並べる
+///
+pub struct PopularBookBoard {
+    background: UniTexture,
+    heading: VerticalText,
+    entries: Vec<VerticalText>,
+    shadow: ShadowShape,
+    canvas: SubScreen,
+}
+
+impl PopularBookBoard {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        ranking: &Vec<(String, u32)>,
+    ) -> Self {
+        let board_size = numeric::Vector2f::new(200.0, 260.0);
+
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(6.0, 6.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(ctx.context, board_size);
+
+        let shadow_bounds = numeric::Rect::new(0.0, 0.0, board_size.x + 12.0, board_size.y + 12.0);
+        let mut shadow = ShadowShape::new(
+            ctx.context,
+            12.0,
+            shadow_bounds,
+            ggraphics::Color::from_rgba_u32(0xbb),
+            0,
+        );
+        shadow.hide();
+
+        let canvas = SubScreen::new(
+            ctx.context,
+            numeric::Rect::new(position.x, position.y, shadow_bounds.w, shadow_bounds.h),
+            0,
+            ggraphics::Color::from_rgba_u32(0x00000000),
+        );
+
+        let heading = VerticalText::new(
+            "人気の本".to_string(),
+            numeric::Point2f::new(board_size.x - 30.0, 20.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::BitMap1),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba_u32(0xaa2222ff),
+            ),
+        );
+
+        let entry_font = FontInformation::new(
+            ctx.resource.get_font(FontID::BitMap1),
+            numeric::Vector2f::new(14.0, 14.0),
+            ggraphics::Color::from_rgba_u32(0x000000ff),
+        );
+
+        let mut entries = Vec::new();
+        if ranking.is_empty() {
+            entries.push(VerticalText::new(
+                "まだ記録無し".to_string(),
+                numeric::Point2f::new(board_size.x - 70.0, 30.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                entry_font.clone(),
+            ));
+        }
+
+        for (index, (name, _)) in ranking.iter().enumerate() {
+            entries.push(VerticalText::new(
+                format!("{}　{}", ctx.number_format().ordinal(index), name),
+                numeric::Point2f::new(board_size.x - 70.0 - (index as f32 * 28.0), 30.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                entry_font.clone(),
+            ));
+        }
+
+        PopularBookBoard {
+            background: background,
+            heading: heading,
+            entries: entries,
+            shadow: shadow,
+            canvas: canvas,
+        }
+    }
+
+    pub fn disable_shadow(&mut self) {
+        self.shadow.hide();
+    }
+
+    pub fn enable_shadow(&mut self) {
+        self.shadow.appear();
+    }
+}
+
+impl DrawableComponent for PopularBookBoard {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.shadow.draw(ctx)?;
+            self.background.draw(ctx)?;
+            self.heading.draw(ctx)?;
+
+            for entry in self.entries.iter_mut() {
+                entry.draw(ctx)?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl DrawableObject for PopularBookBoard {
+    impl_drawable_object_for_wrapped! {canvas}
+}
+
+impl TextureObject for PopularBookBoard {
+    impl_texture_object_for_wrapped! {canvas}
+}
+
+impl Clickable for PopularBookBoard {}
+
+impl OnDesk for PopularBookBoard {
+    fn ondesk_whose(&self) -> i32 {
+        0
+    }
+
+    fn click_hold_data(&self, _: &mut ggez::Context, _: numeric::Point2f) -> HoldData {
+        HoldData::None
+    }
+
+    fn get_type(&self) -> OnDeskType {
+        OnDeskType::PopularBookBoard
+    }
+
+    fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.enable_shadow();
+    }
+
+    fn finish_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.disable_shadow();
+    }
+}
+
+///
+/// メモ一行に書く文字数
+///
+const MEMO_PAD_LINE_CHARS: usize = 10;
+
+///
+/// メモの下に並べる札の数
+///
+const MEMO_PAD_CHIP_ROWS: usize = 5;
+
+impl From<MemoChip> for HoldData {
+    fn from(chip: MemoChip) -> Self {
+        match chip {
+            MemoChip::Date(date) => HoldData::Date(date),
+            MemoChip::Book(book_info) => HoldData::BookName(book_info),
+            MemoChip::Customer(name) => HoldData::CustomerName(name),
+        }
+    }
+}
+
+///
+/// # 机に置く覚書
+/// 走り書きしたメモから日付や題名、客の名前を拾い、記憶できる札として並べる
+///
+/// ## text
+/// 書かれているメモ
+///
+/// ## chips
+/// メモから拾い上げた札
+///
+/// ## editing
+/// 書き込み中かどうか
+///
+pub struct MemoPad {
+    background: UniTexture,
+    heading: UniText,
+    text: String,
+    lines: Vec<UniText>,
+    chips: Vec<HoldDataVText>,
+    parser: MemoParser,
+    today: GensoDate,
+    editing: bool,
+    line_font: FontInformation,
+    chip_font: FontInformation,
+    shadow: ShadowShape,
+    canvas: SubScreen,
+}
+
+impl MemoPad {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        parser: MemoParser,
+        today: GensoDate,
+    ) -> Self {
+        let pad_size = numeric::Vector2f::new(200.0, 300.0);
+
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(6.0, 6.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(ctx.context, pad_size);
+
+        let shadow_bounds = numeric::Rect::new(0.0, 0.0, pad_size.x + 12.0, pad_size.y + 12.0);
+        let mut shadow = ShadowShape::new(
+            ctx.context,
+            12.0,
+            shadow_bounds,
+            ggraphics::Color::from_rgba_u32(0xbb),
+            0,
+        );
+        shadow.hide();
+
+        let canvas = SubScreen::new(
+            ctx.context,
+            numeric::Rect::new(position.x, position.y, shadow_bounds.w, shadow_bounds.h),
+            0,
+            ggraphics::Color::from_rgba_u32(0x00000000),
+        );
+
+        let heading = UniText::new(
+            "覚書".to_string(),
+            numeric::Point2f::new(20.0, 16.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::BitMap1),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba_u32(0x000000ff),
+            ),
+        );
+
+        let line_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(16.0, 16.0),
+            ggraphics::Color::from_rgba_u32(0x222222ff),
+        );
+
+        let chip_font = FontInformation::new(
+            ctx.resource.get_font(FontID::BitMap1),
+            numeric::Vector2f::new(14.0, 14.0),
+            ggraphics::Color::from_rgba_u32(0xaa2222ff),
+        );
+
+        let mut memo_pad = MemoPad {
+            background: background,
+            heading: heading,
+            text: String::new(),
+            lines: Vec::new(),
+            chips: Vec::new(),
+            parser: parser,
+            today: today,
+            editing: false,
+            line_font: line_font,
+            chip_font: chip_font,
+            shadow: shadow,
+            canvas: canvas,
+        };
+        memo_pad.update_lines();
+
+        memo_pad
+    }
+
+    fn update_lines(&mut self) {
+        let mut chars: Vec<char> = self.text.chars().collect();
+        if self.editing {
+            chars.push('＿');
+        } else if chars.is_empty() {
+            chars.extend("クリックして書き込む".chars());
+        }
+
+        let line_font = self.line_font.clone();
+        self.lines = chars
+            .chunks(MEMO_PAD_LINE_CHARS)
+            .enumerate()
+            .map(|(index, line)| {
+                UniText::new(
+                    line.iter().collect(),
+                    numeric::Point2f::new(20.0, 50.0 + (index as f32 * 22.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    line_font.clone(),
+                )
+            })
+            .collect();
+    }
+
+    fn update_chips(&mut self) {
+        let chip_font = self.chip_font.clone();
+        self.chips = self
+            .parser
+            .parse(&self.text, &self.today)
+            .into_iter()
+            .take(MEMO_PAD_CHIP_ROWS)
+            .enumerate()
+            .map(|(index, chip)| {
+                HoldDataVText::new(
+                    HoldData::from(chip),
+                    numeric::Point2f::new(20.0, 200.0 + (index as f32 * 20.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0,
+                    chip_font.clone(),
+                    TextOrientation::Horizontal,
+                )
+            })
+            .collect();
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn start_editing(&mut self) {
+        self.editing = true;
+        self.update_lines();
+    }
+
+    ///
+    /// 書き込みを終え、メモを読み直して札を並べ直す
+    ///
+    pub fn finish_editing(&mut self) {
+        self.editing = false;
+        self.update_lines();
+        self.update_chips();
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if c.is_control() || self.text.chars().count() >= MEMO_MAX_CHARS {
+            return;
+        }
+
+        self.text.push(c);
+        self.update_lines();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.text.pop();
+        self.update_lines();
+    }
+
+    pub fn disable_shadow(&mut self) {
+        self.shadow.hide();
+    }
+
+    pub fn enable_shadow(&mut self) {
+        self.shadow.appear();
+    }
+}
+
+impl DrawableComponent for MemoPad {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.shadow.draw(ctx)?;
+            self.background.draw(ctx)?;
+            self.heading.draw(ctx)?;
+
+            for line in self.lines.iter_mut() {
+                line.draw(ctx)?;
+            }
+
+            for chip in self.chips.iter_mut() {
+                chip.draw(ctx)?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl DrawableObject for MemoPad {
+    impl_drawable_object_for_wrapped! {canvas}
+}
+
+impl TextureObject for MemoPad {
+    impl_texture_object_for_wrapped! {canvas}
+}
+
+impl Clickable for MemoPad {
+    fn button_up(
+        &mut self,
+        ctx: &mut SuzuContext,
+        _: Clock,
+        _button: ggez::input::mouse::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        // 札の上のクリックは記憶に回すので、書き込みは切り替えない
+        if self.click_hold_data(ctx.context, point) != HoldData::None {
+            return;
+        }
+
+        if self.editing {
+            self.finish_editing();
+        } else {
+            self.start_editing();
+        }
+    }
+}
+
+impl OnDesk for MemoPad {
+    fn ondesk_whose(&self) -> i32 {
+        0
+    }
+
+    fn click_hold_data(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> HoldData {
+        let rpoint = self.canvas.relative_point(point);
+
+        for chip in self.chips.iter() {
+            if chip.contains(ctx, rpoint) {
+                return chip.copy_hold_data();
+            }
+        }
+
+        HoldData::None
+    }
+
+    fn has_click_data(&self) -> bool {
+        self.chips.iter().any(|chip| !chip.is_none())
+    }
+
+    fn get_type(&self) -> OnDeskType {
+        OnDeskType::MemoPad
+    }
+
+    fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.enable_shadow();
+    }
+
+    fn finish_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.disable_shadow();
+    }
+}
+
+///
+/// 束の中で、書類を一枚ごとにずらす幅
+///
+const BUNDLE_PILE_STEP: f32 = 8.0;
+
+///
+/// 束の外周の余白。紐と影を描く分
+///
+const BUNDLE_MARGIN: f32 = 12.0;
+
+///
+/// # 紐で束ねた書類
+/// 束ねた書類を少しずつずらして重ね、一つの物として机の上で扱う
+///
+/// ## items
+/// 束ねた書類と、束ねる前の束の左上からの位置
+///
+pub struct DeskBundle {
+    items: Vec<(TaskItem, numeric::Vector2f)>,
+    shadow: ShadowShape,
+    canvas: SubScreen,
+}
+
+impl DeskBundle {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, items: Vec<TaskItem>) -> Self {
+        let areas: Vec<numeric::Rect> = items
+            .iter()
+            .map(|item| item.get_object().get_drawing_area(ctx.context))
+            .collect();
+
+        let origin = areas.iter().fold(
+            numeric::Point2f::new(std::f32::MAX, std::f32::MAX),
+            |origin, area| numeric::Point2f::new(origin.x.min(area.x), origin.y.min(area.y)),
+        );
+        let max_size = areas.iter().fold(numeric::Vector2f::new(0.0, 0.0), |size, area| {
+            numeric::Vector2f::new(size.x.max(area.w), size.y.max(area.h))
+        });
+
+        let pile = BUNDLE_PILE_STEP * (items.len().saturating_sub(1)) as f32;
+        let bundle_size = numeric::Vector2f::new(
+            max_size.x + pile + (BUNDLE_MARGIN * 2.0),
+            max_size.y + pile + (BUNDLE_MARGIN * 2.0),
+        );
+
+        let mut shadow = ShadowShape::new(
+            ctx.context,
+            12.0,
+            numeric::Rect::new(0.0, 0.0, bundle_size.x, bundle_size.y),
+            ggraphics::Color::from_rgba_u32(0xbb),
+            0,
+        );
+        shadow.hide();
+
+        let canvas = SubScreen::new(
+            ctx.context,
+            numeric::Rect::new(origin.x, origin.y, bundle_size.x, bundle_size.y),
+            0,
+            ggraphics::Color::from_rgba_u32(0x00000000),
+        );
+
+        let items = items
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut item)| {
+                let position = item.get_object().get_position();
+                let offset = numeric::Vector2f::new(position.x - origin.x, position.y - origin.y);
+
+                let step = BUNDLE_MARGIN + (BUNDLE_PILE_STEP * index as f32);
+                item.get_object_mut()
+                    .set_position(numeric::Point2f::new(step, step));
+
+                (item, offset)
+            })
+            .collect();
+
+        DeskBundle {
+            items: items,
+            shadow: shadow,
+            canvas: canvas,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    ///
+    /// 紐を解き、書類を束ねる前の並びでoriginから置き直して返す
+    ///
+    pub fn untie(&mut self, origin: numeric::Point2f) -> Vec<TaskItem> {
+        self.items
+            .drain(..)
+            .map(|(mut item, offset)| {
+                item.get_object_mut().set_position(numeric::Point2f::new(
+                    origin.x + offset.x,
+                    origin.y + offset.y,
+                ));
+                item
+            })
+            .collect()
+    }
+
+    pub fn disable_shadow(&mut self) {
+        self.shadow.hide();
+    }
+
+    pub fn enable_shadow(&mut self) {
+        self.shadow.appear();
+    }
+
+    fn draw_string(&self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        let size = self.canvas.get_drawing_size(ctx);
+        let color = ggraphics::Color::from_rgba_u32(0xaa2222ff);
+        let mut builder = ggraphics::MeshBuilder::new();
+
+        shape::Rectangle::new(
+            numeric::Rect::new(0.0, (size.y / 2.0) - 2.0, size.x, 4.0),
+            ggraphics::DrawMode::fill(),
+            color,
+        )
+        .add_to_builder(&mut builder);
+        shape::Rectangle::new(
+            numeric::Rect::new((size.x / 2.0) - 2.0, 0.0, 4.0, size.y),
+            ggraphics::DrawMode::fill(),
+            color,
+        )
+        .add_to_builder(&mut builder);
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())
+    }
+}
+
+impl DrawableComponent for DeskBundle {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.shadow.draw(ctx)?;
+
+            for (item, _) in self.items.iter_mut() {
+                item.draw(ctx)?;
+            }
+
+            self.draw_string(ctx)?;
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl DrawableObject for DeskBundle {
+    impl_drawable_object_for_wrapped! {canvas}
+}
+
+impl TextureObject for DeskBundle {
+    impl_texture_object_for_wrapped! {canvas}
+}
+
+impl Clickable for DeskBundle {}
+
+impl OnDesk for DeskBundle {
+    fn ondesk_whose(&self) -> i32 {
+        0
+    }
+
+    fn click_hold_data(&self, _: &mut ggez::Context, _: numeric::Point2f) -> HoldData {
+        HoldData::None
+    }
+
+    fn get_type(&self) -> OnDeskType {
+        OnDeskType::Bundle
+    }
+
+    fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.enable_shadow();
+    }
+
+    fn finish_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.disable_shadow();
+    }
+}
+
+///
+/// 畳んだ手紙の大きさ
+///
+const LETTER_FOLDED_SIZE: (f32, f32) = (160.0, 100.0);
+
+///
+/// 開いた便箋の大きさ
+///
+const LETTER_UNFOLDED_SIZE: (f32, f32) = (240.0, 320.0);
+
+///
+/// 手紙を開き切るまでの時間
+///
+const LETTER_UNFOLD_TIME: Clock = 20;
+
+const LETTER_LINE_CHARS: usize = 12;
+
+///
+/// # 郵便で届いた手紙
+/// 畳んだまま机に置かれ、クリックすると便箋が開いて本文が読める
+///
+/// ## unfold_start
+/// 開き始めた時刻。まだ開けていなければNone
+///
+/// ## newly_opened
+/// 開けたが、まだ中身を郵便受けに渡していない
+///
+pub struct LetterSheet {
+    letter: Letter,
+    background: UniTexture,
+    sender_text: UniText,
+    lines: Vec<UniText>,
+    unfold_start: Option<Clock>,
+    unfold_ratio: f32,
+    newly_opened: bool,
+    shadow: ShadowShape,
+    canvas: SubScreen,
+}
+
+impl LetterSheet {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, position: numeric::Point2f, letter: Letter) -> Self {
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(6.0, 6.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(
+            ctx.context,
+            numeric::Vector2f::new(LETTER_FOLDED_SIZE.0, LETTER_FOLDED_SIZE.1),
+        );
+
+        let sender_text = UniText::new(
+            format!("{}ヨリ", letter.sender),
+            numeric::Point2f::new(20.0, 40.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(18.0, 18.0),
+                ggraphics::Color::from_rgba_u32(0x222222ff),
+            ),
+        );
+
+        let line_font = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(16.0, 16.0),
+            ggraphics::Color::from_rgba_u32(0x222222ff),
+        );
+        let chars: Vec<char> = letter.body_text().chars().collect();
+        let lines = chars
+            .chunks(LETTER_LINE_CHARS)
+            .enumerate()
+            .map(|(index, line)| {
+                UniText::new(
+                    line.iter().collect(),
+                    numeric::Point2f::new(20.0, 60.0 + (index as f32 * 24.0)),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    line_font.clone(),
+                )
+            })
+            .collect();
+
+        let (shadow, canvas) = Self::create_canvas(
+            ctx.context,
+            position,
+            numeric::Vector2f::new(LETTER_FOLDED_SIZE.0, LETTER_FOLDED_SIZE.1),
+        );
+
+        LetterSheet {
+            letter: letter,
+            background: background,
+            sender_text: sender_text,
+            lines: lines,
+            unfold_start: None,
+            unfold_ratio: 0.0,
+            newly_opened: false,
+            shadow: shadow,
+            canvas: canvas,
+        }
+    }
+
+    fn create_canvas(
+        ctx: &mut ggez::Context,
+        position: numeric::Point2f,
+        size: numeric::Vector2f,
+    ) -> (ShadowShape, SubScreen) {
+        let bounds = numeric::Rect::new(0.0, 0.0, size.x + 12.0, size.y + 12.0);
+        let mut shadow =
+            ShadowShape::new(ctx, 12.0, bounds, ggraphics::Color::from_rgba_u32(0xbb), 0);
+        shadow.hide();
+
+        let canvas = SubScreen::new(
+            ctx,
+            numeric::Rect::new(position.x, position.y, bounds.w, bounds.h),
+            0,
+            ggraphics::Color::from_rgba_u32(0x00000000),
+        );
+
+        (shadow, canvas)
+    }
+
+    pub fn is_opened(&self) -> bool {
+        self.unfold_start.is_some()
+    }
+
+    pub fn get_letter(&self) -> &Letter {
+        &self.letter
+    }
+
+    ///
+    /// 便箋を開き始める。既に開けていれば何もしない
+    ///
+    pub fn open(&mut self, ctx: &mut ggez::Context, t: Clock) {
+        if self.is_opened() {
+            return;
+        }
+
+        let depth = self.canvas.get_drawing_depth();
+        let (shadow, mut canvas) = Self::create_canvas(
+            ctx,
+            self.canvas.get_position(),
+            numeric::Vector2f::new(LETTER_UNFOLDED_SIZE.0, LETTER_UNFOLDED_SIZE.1),
+        );
+        canvas.set_drawing_depth(depth);
+
+        self.shadow = shadow;
+        self.canvas = canvas;
+        self.sender_text.set_position(numeric::Point2f::new(20.0, 20.0));
+        self.unfold_start = Some(t);
+        self.newly_opened = true;
+    }
+
+    ///
+    /// 開いている途中なら便箋を伸ばす。伸ばしたときはtrue
+    ///
+    pub fn update_unfold(&mut self, ctx: &mut ggez::Context, t: Clock) -> bool {
+        let start = match self.unfold_start {
+            Some(start) if self.unfold_ratio < 1.0 => start,
+            _ => return false,
+        };
+
+        self.unfold_ratio = (t.saturating_sub(start) as f32 / LETTER_UNFOLD_TIME as f32).min(1.0);
+
+        let folded_h = LETTER_FOLDED_SIZE.1 / LETTER_UNFOLDED_SIZE.1;
+        let ratio = folded_h + ((1.0 - folded_h) * self.unfold_ratio);
+        self.background.fit_scale(
+            ctx,
+            numeric::Vector2f::new(LETTER_UNFOLDED_SIZE.0, LETTER_UNFOLDED_SIZE.1 * ratio),
+        );
+
+        true
+    }
+
+    ///
+    /// 開けたばかりの手紙の番号を一度だけ返す
+    ///
+    pub fn take_newly_opened(&mut self) -> Option<u32> {
+        if self.newly_opened {
+            self.newly_opened = false;
+            Some(self.letter.id)
+        } else {
+            None
+        }
+    }
+
+    pub fn disable_shadow(&mut self) {
+        self.shadow.hide();
+    }
+
+    pub fn enable_shadow(&mut self) {
+        self.shadow.appear();
+    }
+}
+
+impl DrawableComponent for LetterSheet {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.shadow.draw(ctx)?;
+            self.background.draw(ctx)?;
+            self.sender_text.draw(ctx)?;
+
+            // 開き切るまでは本文を見せない
+            if self.unfold_ratio >= 1.0 {
+                for line in self.lines.iter_mut() {
+                    line.draw(ctx)?;
+                }
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+impl DrawableObject for LetterSheet {
+    impl_drawable_object_for_wrapped! {canvas}
+}
+
+impl TextureObject for LetterSheet {
+    impl_texture_object_for_wrapped! {canvas}
+}
+
+impl Clickable for LetterSheet {
+    fn button_up(
+        &mut self,
+        ctx: &mut SuzuContext,
+        t: Clock,
+        _button: ggez::input::mouse::MouseButton,
+        _point: numeric::Point2f,
+    ) {
+        self.open(ctx.context, t);
+    }
+}
+
+impl OnDesk for LetterSheet {
+    fn ondesk_whose(&self) -> i32 {
+        0
+    }
+
+    fn click_hold_data(&self, _: &mut ggez::Context, _: numeric::Point2f) -> HoldData {
+        HoldData::None
+    }
+
+    fn get_type(&self) -> OnDeskType {
+        OnDeskType::Letter
+    }
+
+    fn tooltip_text(&self) -> Option<String> {
+        Some(format!(
+            "{}ヨリ　{}着",
+            self.letter.sender,
+            self.letter.received.to_short_string()
+        ))
+    }
+
+    fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.enable_shadow();
+    }
+
+    fn finish_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
+        self.disable_shadow();
+    }
+}
+
+///
+/// 机に置いた会員証の大きさ
+///
+const MEMBERSHIP_CARD_SIZE: (f32, f32) = (200.0, 130.0);
+
+///
+/// # 客が出した会員証
+/// 貸出上限と今借りている数、有効期限を書いてある。クリックすると確認票を頼む
+///
+/// ## check_requested
+/// クリックされたが、まだ確認票を出していない
+///
+pub struct MembershipCardSheet {
+    card: MembershipCard,
+    background: UniTexture,
+    heading: UniText,
+    lines: Vec<UniText>,
+    check_requested: bool,
+    shadow: ShadowShape,
+    canvas: SubScreen,
+}
+
+impl MembershipCardSheet {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        position: numeric::Point2f,
+        card: MembershipCard,
+    ) -> Self {
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(6.0, 6.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(
+            ctx.context,
+            numeric::Vector2f::new(MEMBERSHIP_CARD_SIZE.0, MEMBERSHIP_CARD_SIZE.1),
+        );
+
+        let heading = UniText::new(
+            "鈴奈庵 会員証".to_string(),
+            numeric::Point2f::new(20.0, 14.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(18.0, 18.0),
+                ggraphics::Color::from_rgba_u32(0xaa2222ff),
+            ),
+        );
+
+        let bounds = numeric::Rect::new(
+            0.0,
+            0.0,
+            MEMBERSHIP_CARD_SIZE.0 + 12.0,
+            MEMBERSHIP_CARD_SIZE.1 + 12.0,
+        );
+        let mut shadow = ShadowShape::new(
+            ctx.context,
+            12.0,
+            bounds,
+            ggraphics::Color::from_rgba_u32(0xbb),
+            0,
+        );
+        shadow.hide();
+
+        let canvas = SubScreen::new(
+            ctx.context,
+            numeric::Rect::new(position.x, position.y, bounds.w, bounds.h),
+            0,
+            ggraphics::Color::from_rgba_u32(0x00000000),
+        );
+
+        let mut sheet = MembershipCardSheet {
+            card: card,
+            background: background,
+            heading: heading,
+            lines: Vec::new(),
+            check_requested: false,
+            shadow: shadow,
+            canvas: canvas,
+        };
+        sheet.update_lines(ctx);
+
+        sheet
+    }
+
+    fn update_lines<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(16.0, 16.0),
+            ggraphics::Color::from_rgba_u32(0x222222ff),
+        );
+
+        self.lines = vec![
+            format!("{} 様", self.card.holder),
+            format!(
+                "貸出上限 {}冊",
+                ctx.number_format().number(self.card.limit as u64)
+            ),
+            format!(
+                "貸出中 {}冊",
+                ctx.number_format().number(self.card.lending as u64)
+            ),
+            format!("期限 {}", ctx.number_format().short_date(&self.card.expiry)),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            UniText::new(
+                line,
+                numeric::Point2f::new(20.0, 42.0 + (index as f32 * 22.0)),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info.clone(),
+            )
+        })
+        .collect();
+    }
+
+    pub fn get_card(&self) -> &MembershipCard {
+        &self.card
+    }
 
-        OnDeskBook {
-            info: info,
-            book_texture: book_texture,
-            scratch_texture: scratch_texture,
-            shadow: shadow,
-            title: title_vtext,
-            canvas: canvas,
-        }
+    ///
+    /// 更新した会員証に書き換える
+    ///
+    pub fn replace_card<'a>(&mut self, ctx: &mut SuzuContext<'a>, card: MembershipCard) {
+        self.card = card;
+        self.update_lines(ctx);
     }
 
-    pub fn get_book_info(&self) -> &BookInformation {
-        &self.info
+    ///
+    /// クリックされていれば、確認票に載せる会員証を一度だけ返す
+    ///
+    pub fn take_check_request(&mut self) -> Option<MembershipCard> {
+        if self.check_requested {
+            self.check_requested = false;
+            Some(self.card.clone())
+        } else {
+            None
+        }
     }
 
     pub fn disable_shadow(&mut self) {
@@ -435,18 +1721,17 @@ impl OnDeskBook {
     }
 }
 
-impl DrawableComponent for OnDeskBook {
+impl DrawableComponent for MembershipCardSheet {
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
         if self.is_visible() {
             sub_screen::stack_screen(ctx, &self.canvas);
 
             self.shadow.draw(ctx)?;
+            self.background.draw(ctx)?;
+            self.heading.draw(ctx)?;
 
-            self.book_texture.draw(ctx)?;
-            self.title.draw(ctx)?;
-
-            if let Some(scratch_texture) = self.scratch_texture.as_mut() {
-                scratch_texture.draw(ctx)?;
+            for line in self.lines.iter_mut() {
+                line.draw(ctx)?;
             }
 
             sub_screen::pop_screen(ctx);
@@ -476,41 +1761,45 @@ impl DrawableComponent for OnDeskBook {
     }
 }
 
-impl DrawableObject for OnDeskBook {
-    fn set_position(&mut self, pos: numeric::Point2f) {
-        self.canvas.set_position(pos);
-    }
-
-    fn get_position(&self) -> numeric::Point2f {
-        self.canvas.get_position()
-    }
-
-    fn move_diff(&mut self, offset: numeric::Vector2f) {
-        self.canvas.move_diff(offset);
-    }
+impl DrawableObject for MembershipCardSheet {
+    impl_drawable_object_for_wrapped! {canvas}
 }
 
-impl TextureObject for OnDeskBook {
+impl TextureObject for MembershipCardSheet {
     impl_texture_object_for_wrapped! {canvas}
 }
 
-impl Clickable for OnDeskBook {}
+impl Clickable for MembershipCardSheet {
+    fn button_up(
+        &mut self,
+        _ctx: &mut SuzuContext,
+        _t: Clock,
+        _button: ggez::input::mouse::MouseButton,
+        _point: numeric::Point2f,
+    ) {
+        self.check_requested = true;
+    }
+}
 
-impl OnDesk for OnDeskBook {
+impl OnDesk for MembershipCardSheet {
     fn ondesk_whose(&self) -> i32 {
         0
     }
 
     fn click_hold_data(&self, _: &mut ggez::Context, _: numeric::Point2f) -> HoldData {
-        return HoldData::BookName(self.info.clone());
+        HoldData::None
     }
 
-    fn get_hold_data(&self) -> HoldData {
-        HoldData::BookName(self.info.clone())
+    fn get_type(&self) -> OnDeskType {
+        OnDeskType::MembershipCard
     }
 
-    fn get_type(&self) -> OnDeskType {
-        OnDeskType::Book
+    fn tooltip_text(&self) -> Option<String> {
+        Some(format!(
+            "{}ノ会員証　{}迄",
+            self.card.holder,
+            self.card.expiry.to_short_string()
+        ))
     }
 
     fn start_dragging<'a>(&mut self, _: &mut SuzuContext<'a>) {
@@ -536,6 +1825,10 @@ impl BookConditionEvalReport {
         }
     }
 
+    pub fn count_evaluated(&self) -> usize {
+        self.originals.len()
+    }
+
     pub fn count_mistake(&self) -> usize {
         let mut count: usize = 0;
 
@@ -550,6 +1843,17 @@ impl BookConditionEvalReport {
 
         count
     }
+
+    ///
+    /// 本と、記入された状態の組を返す
+    ///
+    pub fn to_evaluation_pairs(&self) -> Vec<(BookInformation, BookCondition)> {
+        self.originals
+            .iter()
+            .cloned()
+            .zip(self.each_evaluation.iter().cloned())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -565,6 +1869,24 @@ pub struct BorrowingRecordBookPageData {
 }
 
 impl BorrowingRecordBookPageData {
+    ///
+    /// 借りた客の名前か、書いてある本の題目にqueryを含むならtrue。空のqueryには当たらない
+    ///
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() {
+            return false;
+        }
+
+        self.customer_name
+            .as_ref()
+            .map_or(false, |name| name.contains(query))
+            || self
+                .borrowing_book_title
+                .iter()
+                .any(|(_, info)| info.name.contains(query))
+    }
+
     pub fn is_maybe_waiting_returning(&self) -> bool {
         !self.returning_is_signed
             && self.borrowing_is_signed
@@ -619,6 +1941,112 @@ impl BorrowingRecordBookPageData {
     }
 }
 
+///
+/// # 帳簿の検索
+/// 客の名前か本の題目で頁を探し、当たった頁を順に辿る
+///
+/// ## matches
+/// 当たった頁の番号。頁の順に並ぶ
+///
+/// ## cursor
+/// 今開いている当たりの、matchesの中での位置
+///
+pub struct RecordBookSearch {
+    query: String,
+    matches: Vec<usize>,
+    cursor: usize,
+}
+
+impl RecordBookSearch {
+    pub fn new() -> Self {
+        RecordBookSearch {
+            query: String::new(),
+            matches: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    ///
+    /// pagesからqueryを探し、最初に当たった頁の番号を返す
+    ///
+    pub fn search(&mut self, pages: &[BorrowingRecordBookPageData], query: &str) -> Option<usize> {
+        self.query = query.trim().to_string();
+        self.matches = pages
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| page.matches_search(&self.query))
+            .map(|(index, _)| index)
+            .collect();
+        self.cursor = 0;
+
+        self.current_match()
+    }
+
+    pub fn current_match(&self) -> Option<usize> {
+        self.matches.get(self.cursor).copied()
+    }
+
+    ///
+    /// 次の当たりへ進む。最後の次は最初に戻る
+    ///
+    pub fn next_match(&mut self) -> Option<usize> {
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        self.cursor = (self.cursor + 1) % self.matches.len();
+        self.current_match()
+    }
+
+    ///
+    /// 前の当たりへ戻る。最初の前は最後に回る
+    ///
+    pub fn prev_match(&mut self) -> Option<usize> {
+        if self.matches.is_empty() {
+            return None;
+        }
+
+        self.cursor = (self.cursor + self.matches.len() - 1) % self.matches.len();
+        self.current_match()
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.matches.clear();
+        self.cursor = 0;
+    }
+
+    ///
+    /// 帳簿の隅に出す、検索の結果
+    ///
+    pub fn status_text(&self) -> String {
+        if !self.is_active() {
+            String::new()
+        } else if self.matches.is_empty() {
+            format!("「{}」は見当たらない", self.query)
+        } else {
+            format!(
+                "「{}」 {}/{}件",
+                self.query,
+                self.cursor + 1,
+                self.matches.len()
+            )
+        }
+    }
+}
+
 impl From<&ReturnBookInformation> for BorrowingRecordBookPageData {
     fn from(info: &ReturnBookInformation) -> Self {
         let mut borrowing_book_title = Vec::new();
@@ -674,7 +2102,28 @@ impl BorrowingRecordBookData {
         count > 0
     }
 
-    pub fn pick_returning_request_up(&self) -> Option<ReturnBookInformation> {
+    ///
+    /// 返却を待っている頁に書かれた本の題名。重複は除く
+    ///
+    pub fn lent_out_titles(&self) -> Vec<String> {
+        let mut titles: Vec<String> = Vec::new();
+
+        for data in self.pages_data.iter() {
+            if !data.is_maybe_waiting_returning() {
+                continue;
+            }
+
+            for (_, book_info) in data.borrowing_book_title.iter() {
+                if !titles.contains(&book_info.name) {
+                    titles.push(book_info.name.clone());
+                }
+            }
+        }
+
+        titles
+    }
+
+    pub fn pick_returning_request_up<R: Rng>(&self, rng: &mut R) -> Option<ReturnBookInformation> {
         let count = self
             .pages_data
             .iter()
@@ -690,7 +2139,7 @@ impl BorrowingRecordBookData {
             return None;
         }
 
-        let mut picked_data = rand::random::<usize>() % count;
+        let mut picked_data = rng.gen_range(0, count);
 
         for data in self.pages_data.iter() {
             if data.is_maybe_waiting_returning() {
@@ -991,7 +2440,7 @@ impl PayFrame {
         base_price: u32,
     ) {
         let mut vtext = VerticalText::new(
-            format!("{}冊", number_to_jk(count as u64)),
+            ctx.number_format().count(count as u64, "冊"),
             numeric::Point2f::new(0.0, 0.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -1026,7 +2475,8 @@ impl PayFrame {
             self.calculated_price = Some((rental_limit.fee_rate() * base_price as f32) as u32);
 
             let mut vtext = VerticalText::new(
-                format!("{}円", number_to_jk(self.calculated_price.unwrap() as u64)),
+                ctx.number_format()
+                    .money(self.calculated_price.unwrap() as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1103,9 +2553,17 @@ impl DrawableComponent for PayFrame {
     }
 }
 
+///
+/// # 貸出記録の頁
+///
+/// ## customer_info_alignment, books_alignment
+/// 表ごとの文字の揃え方。欄に書き込む文字は全てこれに従って置く
+///
 pub struct BorrowingRecordBookPage {
     customer_info_table: TableFrame,
     books_table: TableFrame,
+    customer_info_alignment: CellAlignment,
+    books_alignment: CellAlignment,
     borrow_book: HashMap<numeric::Vector2u, HoldDataVText>,
     request_information: HashMap<numeric::Vector2u, HoldDataVText>,
     book_head: VerticalText,
@@ -1120,6 +2578,40 @@ pub struct BorrowingRecordBookPage {
 }
 
 impl BorrowingRecordBookPage {
+    ///
+    /// 客の情報の欄の余白
+    ///
+    const CUSTOMER_INFO_PADDING: f32 = 6.0;
+
+    ///
+    /// 本の欄の余白
+    ///
+    const BOOKS_PADDING: f32 = 4.0;
+
+    fn align_customer_info_cell(&mut self, ctx: &mut ggez::Context, grid_pos: numeric::Vector2u) {
+        if let Some(info) = self.request_information.get_mut(&grid_pos) {
+            align_in_table_cell(
+                ctx,
+                &self.customer_info_table,
+                &self.customer_info_alignment,
+                grid_pos,
+                &mut info.text,
+            );
+        }
+    }
+
+    fn align_book_cell(&mut self, ctx: &mut ggez::Context, grid_pos: numeric::Vector2u) {
+        if let Some(info) = self.borrow_book.get_mut(&grid_pos) {
+            align_in_table_cell(
+                ctx,
+                &self.books_table,
+                &self.books_alignment,
+                grid_pos,
+                &mut info.text,
+            );
+        }
+    }
+
     pub fn new<'a>(
         ctx: &mut SuzuContext<'a>,
         rect: ggraphics::Rect,
@@ -1132,54 +2624,34 @@ impl BorrowingRecordBookPage {
         for (position, book_info) in page_data.borrowing_book_title.iter() {
             let info = page.borrow_book.get_mut(&position).unwrap();
             info.reset(HoldData::BookName(book_info.clone()));
-            info.vtext.make_center(
-                ctx.context,
-                page.books_table
-                    .get_center_of(*position, page.books_table.get_position()),
-            );
+            page.align_book_cell(ctx.context, *position);
         }
 
         for (position, book_status) in page_data.borrowing_book_status.iter() {
             let info = page.borrow_book.get_mut(&position).unwrap();
             info.reset(HoldData::BookCondition(book_status.clone()));
-            info.vtext.make_center(
-                ctx.context,
-                page.books_table
-                    .get_center_of(*position, page.books_table.get_position()),
-            );
+            page.align_book_cell(ctx.context, *position);
         }
 
         if let Some(customer_name) = page_data.customer_name {
             let position = numeric::Vector2u::new(2, 1);
             let info = page.request_information.get_mut(&position).unwrap();
             info.reset(HoldData::CustomerName(customer_name.clone()));
-            info.vtext.make_center(
-                ctx.context,
-                page.customer_info_table
-                    .get_center_of(position, page.customer_info_table.get_position()),
-            );
+            page.align_customer_info_cell(ctx.context, position);
         }
 
         if let Some(rental_date) = page_data.rental_date {
             let position = numeric::Vector2u::new(1, 1);
             let info = page.request_information.get_mut(&position).unwrap();
             info.reset(HoldData::Date(rental_date.clone()));
-            info.vtext.make_center(
-                ctx.context,
-                page.customer_info_table
-                    .get_center_of(position, page.customer_info_table.get_position()),
-            );
+            page.align_customer_info_cell(ctx.context, position);
         }
 
         if let Some(return_date) = page_data.return_date {
             let position = numeric::Vector2u::new(0, 1);
             let info = page.request_information.get_mut(&position).unwrap();
             info.reset(HoldData::Date(return_date.clone()));
-            info.vtext.make_center(
-                ctx.context,
-                page.customer_info_table
-                    .get_center_of(position, page.customer_info_table.get_position()),
-            );
+            page.align_customer_info_cell(ctx.context, position);
         }
 
         let base_price = page.base_price_of_written_book();
@@ -1349,6 +2821,7 @@ impl BorrowingRecordBookPage {
             numeric::Vector2f::new(24.0, 24.0),
             ggraphics::Color::from_rgba_u32(0xff),
         );
+        let orientation = ctx.text_orientation();
         let request_info_text = hash![
             (
                 numeric::Vector2u::new(0, 1),
@@ -1357,7 +2830,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1367,7 +2841,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1377,7 +2852,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             )
         ];
@@ -1390,7 +2866,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1400,7 +2877,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1410,7 +2888,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1420,7 +2899,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1430,7 +2910,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1440,7 +2921,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1450,7 +2932,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1460,7 +2943,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1470,7 +2954,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1480,7 +2965,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1490,7 +2976,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             ),
             (
@@ -1500,7 +2987,8 @@ impl BorrowingRecordBookPage {
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0,
-                    info_font.clone()
+                    info_font.clone(),
+                    orientation
                 )
             )
         ];
@@ -1508,6 +2996,16 @@ impl BorrowingRecordBookPage {
         BorrowingRecordBookPage {
             customer_info_table: table_frame,
             books_table: books_table,
+            customer_info_alignment: CellAlignment::new(
+                CellAnchor::TopRight,
+                PixelSnap::Round,
+                Self::CUSTOMER_INFO_PADDING,
+            ),
+            books_alignment: CellAlignment::new(
+                CellAnchor::Center,
+                PixelSnap::Round,
+                Self::BOOKS_PADDING,
+            ),
             borrow_book: borrow_text,
             borrower: borrower,
             request_information: request_info_text,
@@ -1530,6 +3028,25 @@ impl BorrowingRecordBookPage {
         self.books_table.get_rows()
     }
 
+    ///
+    /// 本の欄の、grid_posの升の真ん中
+    ///
+    pub fn get_books_table_center_of(&self, grid_pos: numeric::Vector2u) -> numeric::Point2f {
+        self.books_table
+            .get_center_of(grid_pos, self.books_table.get_position())
+    }
+
+    ///
+    /// 客の情報の欄の、grid_posの升の真ん中
+    ///
+    pub fn get_customer_info_table_center_of(
+        &self,
+        grid_pos: numeric::Vector2u,
+    ) -> numeric::Point2f {
+        self.customer_info_table
+            .get_center_of(grid_pos, self.customer_info_table.get_position())
+    }
+
     pub fn get_calculated_price(&self) -> Option<u32> {
         self.pay_frame.get_calculated_price()
     }
@@ -1593,11 +3110,7 @@ impl BorrowingRecordBookPage {
                 HoldData::CustomerName(_) => {
                     let info = self.request_information.get_mut(&position).unwrap();
                     info.reset(hold_data.clone());
-                    info.vtext.make_center(
-                        ctx,
-                        self.customer_info_table
-                            .get_center_of(position, self.customer_info_table.get_position()),
-                    );
+                    self.align_customer_info_cell(ctx, position);
                 }
                 _ => (),
             }
@@ -1606,11 +3119,7 @@ impl BorrowingRecordBookPage {
                 HoldData::Date(_) => {
                     let info = self.request_information.get_mut(&position).unwrap();
                     info.reset(hold_data.clone());
-                    info.vtext.make_center(
-                        ctx,
-                        self.customer_info_table
-                            .get_center_of(position, self.customer_info_table.get_position()),
-                    );
+                    self.align_customer_info_cell(ctx, position);
                 }
                 _ => (),
             }
@@ -1619,11 +3128,7 @@ impl BorrowingRecordBookPage {
                 HoldData::Date(_) => {
                     let info = self.request_information.get_mut(&position).unwrap();
                     info.reset(hold_data.clone());
-                    info.vtext.make_center(
-                        ctx,
-                        self.customer_info_table
-                            .get_center_of(position, self.customer_info_table.get_position()),
-                    );
+                    self.align_customer_info_cell(ctx, position);
                 }
                 _ => (),
             }
@@ -1744,11 +3249,7 @@ impl BorrowingRecordBookPage {
 
         let info = self.borrow_book.get_mut(&grid_pos).unwrap();
         info.reset(HoldData::BookName(book_info));
-        info.vtext.make_center(
-            ctx.context,
-            self.books_table
-                .get_center_of(grid_pos, self.books_table.get_position()),
-        );
+        self.align_book_cell(ctx.context, grid_pos);
 
         self.pay_frame.update_book_count(
             ctx,
@@ -1781,11 +3282,7 @@ impl BorrowingRecordBookPage {
 
         let info = self.request_information.get_mut(&grid_pos).unwrap();
         info.reset(HoldData::Date(date));
-        info.vtext.make_center(
-            ctx.context,
-            self.customer_info_table
-                .get_center_of(grid_pos, self.customer_info_table.get_position()),
-        );
+        self.align_customer_info_cell(ctx.context, grid_pos);
 
         if grid_pos.x == 0 {
             self.pay_frame.update_rental_limit_text(
@@ -1819,11 +3316,7 @@ impl BorrowingRecordBookPage {
 
         let info = self.request_information.get_mut(&grid_pos).unwrap();
         info.reset(HoldData::CustomerName(customer_name));
-        info.vtext.make_center(
-            ctx,
-            self.customer_info_table
-                .get_center_of(grid_pos, self.customer_info_table.get_position()),
-        );
+        self.align_customer_info_cell(ctx, grid_pos);
 
         DrawRequest::Draw
     }
@@ -1857,11 +3350,7 @@ impl BorrowingRecordBookPage {
             .unwrap();
         let info = self.borrow_book.get_mut(&grid_position).unwrap();
         info.reset(HoldData::BookCondition(BookCondition::from(status_index)));
-        info.vtext.make_center(
-            ctx,
-            self.books_table
-                .get_center_of(grid_position, self.books_table.get_position()),
-        );
+        self.align_book_cell(ctx, grid_position);
     }
 
     fn remove_book_status_data(
@@ -2004,6 +3493,90 @@ impl BorrowingRecordBookPage {
 
 	false
     }
+
+    fn table_cell_rect(table: &TableFrame, position: numeric::Vector2u) -> numeric::Rect {
+        let table_pos = table.get_position();
+        let top_left =
+            table.get_grid_topleft(position, numeric::Vector2f::new(table_pos.x, table_pos.y));
+        let size = table.get_cell_size(position);
+        numeric::Rect::new(top_left.x, top_left.y, size.x, size.y)
+    }
+
+    fn empty_request_cell_rect(&self, position: numeric::Vector2u) -> Option<numeric::Rect> {
+        match self.request_information.get(&position) {
+            Some(info) if info.is_none() => {
+                Some(Self::table_cell_rect(&self.customer_info_table, position))
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// column列の空欄のうち、最初のものの位置を返す
+    /// 状態は、題目が書かれている行にだけ書き込める
+    ///
+    fn first_empty_book_cell_rect(&self, column: u32) -> Option<numeric::Rect> {
+        for index in 0..self.books_table.get_rows() as u32 {
+            let position = numeric::Vector2u::new(index, column);
+            let is_empty = match self.borrow_book.get(&position) {
+                Some(info) => info.is_none(),
+                None => false,
+            };
+            let has_title = column == 0 || self.is_written_book_title_at(index);
+
+            if is_empty && has_title {
+                return Some(Self::table_cell_rect(&self.books_table, position));
+            }
+        }
+
+        None
+    }
+
+    fn is_written_book_title_at(&self, index: u32) -> bool {
+        match self.borrow_book.get(&numeric::Vector2u::new(index, 0)) {
+            Some(info) => !info.is_none(),
+            None => false,
+        }
+    }
+
+    ///
+    /// held_dataを書き込むべき空欄の位置を返す。空欄が無い場合はNone
+    ///
+    fn find_assist_cell(&self, held_data: &HoldData) -> Option<numeric::Rect> {
+        match held_data {
+            HoldData::CustomerName(_) => self.empty_request_cell_rect(numeric::Vector2u::new(2, 1)),
+            HoldData::Date(_) => self
+                .empty_request_cell_rect(numeric::Vector2u::new(1, 1))
+                .or_else(|| self.empty_request_cell_rect(numeric::Vector2u::new(0, 1))),
+            HoldData::BookName(_) => self.first_empty_book_cell_rect(0),
+            HoldData::BookCondition(_) => self.first_empty_book_cell_rect(1),
+            HoldData::None => None,
+        }
+    }
+
+    ///
+    /// 補助として、held_dataを書き込むべき空欄を色付けして示す
+    ///
+    pub fn draw_assist_highlight(
+        &self,
+        ctx: &mut ggez::Context,
+        held_data: &HoldData,
+    ) -> ggez::GameResult<()> {
+        if let Some(rect) = self.find_assist_cell(held_data) {
+            let mut builder = ggraphics::MeshBuilder::new();
+            shape::Rectangle::new(
+                rect,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0xffd70060),
+            )
+            .add_to_builder(&mut builder);
+
+            let mesh = builder.build(ctx)?;
+            ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+        }
+
+        Ok(())
+    }
 }
 
 impl DrawableComponent for BorrowingRecordBookPage {
@@ -2062,6 +3635,55 @@ pub enum RecordBookLockStatus {
     ReturningVary,
 }
 
+///
+/// 帳簿の頁送りの表示。"3/7"のように、今の頁と頁の数を示す
+///
+pub fn record_book_page_indicator(current_page: usize, pages_length: usize) -> String {
+    if pages_length == 0 {
+        return "0/0".to_string();
+    }
+
+    format!(
+        "{}/{}",
+        current_page.min(pages_length - 1) + 1,
+        pages_length
+    )
+}
+
+///
+/// current_pageからstep頁繰った先の頁。最初の頁と最後の頁より先には繰らない
+///
+pub fn record_book_scroll_target(current_page: usize, pages_length: usize, step: i32) -> usize {
+    if pages_length == 0 {
+        return 0;
+    }
+
+    (current_page as i32 + step)
+        .max(0)
+        .min(pages_length as i32 - 1) as usize
+}
+
+///
+/// # 貸出記録の帳簿
+///
+/// ## next_page_ope_mesh, prev_page_ope_mesh
+/// 頁を一枚ずつ繰る印。最後の頁では次へ、最初の頁では前へ、の印を隠す
+///
+/// ## new_page_button
+/// 新しい頁を足すボタン。頁はこのボタンでしか増えない
+///
+/// ## page_indicator
+/// 今何頁目かを示す文字
+///
+/// ## page_flip_cover
+/// 頁を繰ったときに、新しい頁の上で薄れていく白紙
+///
+/// ## search_input
+/// 探す名前か題目を書く欄。search_buttonで開く
+///
+/// ## search_status
+/// 検索の結果。当たりの数と、今何件目か
+///
 pub struct BorrowingRecordBook {
     redraw_request: DrawRequest,
     pages: Vec<BorrowingRecordBookPage>,
@@ -2075,9 +3697,52 @@ pub struct BorrowingRecordBook {
     page_data_backup: BorrowingRecordBookData,
     next10_button: SelectButton,
     prev10_button: SelectButton,
+    new_page_button: SelectButton,
+    page_indicator: UniText,
+    page_flip_cover: EffectableWrap<MovableWrap<UniTexture>>,
+    search_button: SelectButton,
+    search_prev_button: SelectButton,
+    search_next_button: SelectButton,
+    search_input: TextInput,
+    search: RecordBookSearch,
+    search_status: UniText,
+    assist_target: Option<HoldData>,
 }
 
-impl BorrowingRecordBook {
+impl BorrowingRecordBook {
+    ///
+    /// 頁を繰ったときに、白紙が薄れきるまでのフレーム数
+    ///
+    const PAGE_FLIP_TIME: Clock = 8;
+
+    ///
+    /// 検索の欄に書ける文字数
+    ///
+    const SEARCH_MAX_CHARS: usize = 12;
+
+    fn new_side_button<'a>(
+        ctx: &mut SuzuContext<'a>,
+        label: &str,
+        pos_rect: numeric::Rect,
+        color: u32,
+    ) -> SelectButton {
+        let button_texture = Box::new(TextButtonTexture::new(
+            ctx,
+            numeric::Point2f::new(0.0, 0.0),
+            label.to_string(),
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(22.0, 22.0),
+                ggraphics::Color::from_rgba_u32(0xff),
+            ),
+            10.0,
+            ggraphics::Color::from_rgba_u32(color),
+            0,
+        ));
+
+        SelectButton::new(ctx, pos_rect, button_texture)
+    }
+
     pub fn new<'a>(
         ctx: &mut SuzuContext<'a>,
         rect: ggraphics::Rect,
@@ -2163,7 +3828,88 @@ impl BorrowingRecordBook {
             button_texture,
         );
 
-        BorrowingRecordBook {
+        let new_page_button = Self::new_side_button(
+            ctx,
+            "新頁",
+            numeric::Rect::new(0.0, 120.0, 60.0, 60.0),
+            0xf6e1d5ff,
+        );
+
+        let page_indicator = UniText::new(
+            String::new(),
+            numeric::Point2f::new(page_rect.x + (page_rect.w / 2.0) - 24.0, page_rect.h - 32.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(22.0, 22.0),
+                ggraphics::Color::from_rgba_u32(0x000000ff),
+            ),
+        );
+
+        let mut page_flip_cover = EffectableWrap::new(
+            MovableWrap::new(
+                Box::new(UniTexture::new(
+                    ctx.ref_texture(TextureID::Paper1),
+                    numeric::Point2f::new(page_rect.x, page_rect.y),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                )),
+                None,
+                t,
+            ),
+            Vec::new(),
+        );
+        page_flip_cover.set_alpha(0.0);
+
+        let search_button = Self::new_side_button(
+            ctx,
+            "探す",
+            numeric::Rect::new(0.0, 180.0, 60.0, 60.0),
+            0xeef3e2ff,
+        );
+        let search_prev_button = Self::new_side_button(
+            ctx,
+            "前件",
+            numeric::Rect::new(0.0, 240.0, 60.0, 60.0),
+            0xeef3e2ff,
+        );
+        let search_next_button = Self::new_side_button(
+            ctx,
+            "次件",
+            numeric::Rect::new(0.0, 300.0, 60.0, 60.0),
+            0xeef3e2ff,
+        );
+
+        let mut search_input = TextInput::new(
+            ctx,
+            numeric::Rect::new(0.0, 0.0, 280.0, 40.0),
+            Self::SEARCH_MAX_CHARS,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(22.0, 22.0),
+                ggraphics::Color::from_rgba_u32(0x000000ff),
+            ),
+            0,
+        );
+        search_input.set_placeholder("名前か題目");
+
+        let search_status = UniText::new(
+            String::new(),
+            numeric::Point2f::new(page_rect.x + 20.0, page_rect.h - 32.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(20.0, 20.0),
+                ggraphics::Color::from_rgba_u32(0x000000ff),
+            ),
+        );
+
+        let mut record_book = BorrowingRecordBook {
             redraw_request: DrawRequest::InitDraw,
             pages: pages,
             host_rect: host_rect,
@@ -2185,7 +3931,30 @@ impl BorrowingRecordBook {
             page_data_backup: backup,
             next10_button: next10_button,
             prev10_button: prev10_button,
+            new_page_button: new_page_button,
+            page_indicator: page_indicator,
+            page_flip_cover: page_flip_cover,
+            search_button: search_button,
+            search_prev_button: search_prev_button,
+            search_next_button: search_next_button,
+            search_input: search_input,
+            search: RecordBookSearch::new(),
+            search_status: search_status,
             page_scroll_event_list: DelayEventList::new(),
+            assist_target: None,
+        };
+
+        record_book.check_move_page_icon_visibility();
+        record_book
+    }
+
+    ///
+    /// 補助で書き込み先を示すHoldDataを設定する。Noneで補助の表示を消す
+    ///
+    pub fn set_assist_target(&mut self, target: Option<HoldData>) {
+        if self.assist_target != target {
+            self.assist_target = target;
+            self.redraw_request = DrawRequest::Draw;
         }
     }
 
@@ -2210,6 +3979,7 @@ impl BorrowingRecordBook {
         while self.pages.len() < pages_len {
             self.add_empty_page(ctx, t);
         }
+        self.check_move_page_icon_visibility();
 
         ctx.process_utility.redraw();
         self.redraw_request = DrawRequest::Draw;
@@ -2243,32 +4013,142 @@ impl BorrowingRecordBook {
     }
 
     ///
-    /// 次のページが存在する場合は、ページを繰って、trueを返す
-    /// 存在しない場合は、falseを返す
+    /// 新しい頁の上に白紙を重ね、薄れさせていく
     ///
-    fn next_page<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+    fn start_page_flip(&mut self, t: Clock) {
+        self.page_flip_cover.clear_effect();
+        self.page_flip_cover.set_alpha(1.0);
+        self.page_flip_cover
+            .add_effect(vec![effect::fade_out(Self::PAGE_FLIP_TIME, t)]);
         self.redraw_request = DrawRequest::Draw;
+    }
+
+    ///
+    /// 次の頁が存在する場合は、頁を繰る。最後の頁では何もしない
+    ///
+    fn next_page<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.current_page + 1 >= self.pages.len() {
+            return;
+        }
+
         self.current_page += 1;
-        if self.current_page >= self.pages.len() {
-            self.add_empty_page(ctx, t);
+        self.start_page_flip(t);
+        ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+    }
+
+    fn prev_page<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.current_page == 0 {
+            return;
         }
-        self.prev_page_ope_mesh.appear();
+
+        self.current_page -= 1;
+        self.start_page_flip(t);
+        ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+    }
+
+    ///
+    /// 最後に白紙の頁を足し、その頁を開く
+    ///
+    fn open_new_page<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.add_empty_page(ctx, t);
+        self.current_page = self.pages.len() - 1;
+        self.start_page_flip(t);
         ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
     }
 
-    fn prev_page<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+    ///
+    /// 検索で当たった頁を開く
+    ///
+    fn jump_to_search_match<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.search_status.replace_text(self.search.status_text());
         self.redraw_request = DrawRequest::Draw;
-        self.prev_page_ope_mesh.appear();
 
-        if self.current_page > 0 {
-            self.current_page -= 1;
+        let page_index = match self.search.current_match() {
+            Some(index) if index < self.pages.len() => index,
+            _ => return,
+        };
+
+        if page_index != self.current_page {
+            self.page_scroll_event_list.clear();
+            self.current_page = page_index;
+            self.start_page_flip(t);
+            ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+        }
+        self.check_move_page_icon_visibility();
+    }
+
+    fn run_search<'a>(&mut self, ctx: &mut SuzuContext<'a>, query: &str, t: Clock) {
+        let pages_data: Vec<BorrowingRecordBookPageData> = self
+            .pages
+            .iter()
+            .map(|page| page.export_page_data())
+            .collect();
+
+        self.search.search(&pages_data, query);
+        self.jump_to_search_match(ctx, t);
+    }
+
+    pub fn search_is_editing(&self) -> bool {
+        self.search_input.is_editing()
+    }
+
+    ///
+    /// 検索の欄を開いていれば、キー入力を渡す
+    ///
+    /// 開いていなければ、false
+    ///
+    pub fn search_key_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        key: PaletteKey,
+        t: Clock,
+    ) -> bool {
+        if !self.search_input.is_editing() {
+            return false;
+        }
 
-            if self.current_page == 0 {
-                self.prev_page_ope_mesh.hide();
+        match self.search_input.key_event(key) {
+            TextInputEvent::Committed(query) => self.run_search(ctx, &query, t),
+            TextInputEvent::Canceled => {
+                self.search.clear();
+                self.search_status.replace_text(String::new());
             }
+            TextInputEvent::Changed | TextInputEvent::Nothing => (),
+        }
 
-            ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
+        self.redraw_request = DrawRequest::Draw;
+        ctx.process_utility.redraw();
+        true
+    }
+
+    ///
+    /// 頁送りの印やボタンの上ならHandを返す
+    ///
+    pub fn check_mouse_cursor_status(
+        &mut self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> CursorIcon {
+        if !self.is_visible() || !self.contains(ctx, point) {
+            return CursorIcon::Default;
+        }
+
+        let rpoint = self.relative_point(point);
+        if (self.next_page_ope_mesh.is_visible() && self.next_page_ope_mesh.contains(ctx, rpoint))
+            || (self.prev_page_ope_mesh.is_visible()
+                && self.prev_page_ope_mesh.contains(ctx, rpoint))
+            || self.next10_button.contains(ctx, rpoint)
+            || self.prev10_button.contains(ctx, rpoint)
+            || self.new_page_button.contains(ctx, rpoint)
+            || self.search_button.contains(ctx, rpoint)
+            || (self.search.is_active()
+                && (self.search_prev_button.contains(ctx, rpoint)
+                    || self.search_next_button.contains(ctx, rpoint)))
+        {
+            return CursorIcon::Hand;
         }
+
+        CursorIcon::Default
     }
 
     pub fn insert_book_title_to_books_frame<'a>(
@@ -2360,6 +4240,13 @@ impl BorrowingRecordBook {
         }
     }
 
+    pub fn get_current_page_data(&self) -> Option<BorrowingRecordBookPageData> {
+        match self.get_current_page() {
+            Some(page) => Some(page.export_page_data()),
+            None => None,
+        }
+    }
+
     pub fn get_current_page_condition_eval_report(&self) -> Option<BookConditionEvalReport> {
         match self.get_current_page() {
             Some(page) => Some(page.create_current_book_condition_report()),
@@ -2375,49 +4262,78 @@ impl BorrowingRecordBook {
     ) -> bool {
         let rpoint = self.relative_point(point);
 
-        if self.next_page_ope_mesh.contains(ctx.context, rpoint) {
+        if self.search_input.is_editing() {
+            if self.search_input.contains(rpoint) {
+                return true;
+            }
+
+            self.search_input.finish_editing();
+            self.redraw_request = DrawRequest::Draw;
+        }
+
+        if self.search_button.contains(ctx.context, rpoint) {
+            let query = self.search.get_query().to_string();
+            self.search_input
+                .start_editing(numeric::Point2f::new(self.page_rect.x + 20.0, 20.0), &query);
+            self.redraw_request = DrawRequest::Draw;
+            return true;
+        } else if self.search.is_active() && self.search_next_button.contains(ctx.context, rpoint) {
+            self.search.next_match();
+            self.jump_to_search_match(ctx, t);
+            return true;
+        } else if self.search.is_active() && self.search_prev_button.contains(ctx.context, rpoint) {
+            self.search.prev_match();
+            self.jump_to_search_match(ctx, t);
+            return true;
+        }
+
+        if self.next_page_ope_mesh.is_visible()
+            && self.next_page_ope_mesh.contains(ctx.context, rpoint)
+        {
             self.next_page(ctx, t);
             self.check_move_page_icon_visibility();
             return true;
-        } else if self.prev_page_ope_mesh.contains(ctx.context, rpoint) {
-            self.prev_page(ctx);
+        } else if self.prev_page_ope_mesh.is_visible()
+            && self.prev_page_ope_mesh.contains(ctx.context, rpoint)
+        {
+            self.prev_page(ctx, t);
+            self.check_move_page_icon_visibility();
+            return true;
+        } else if self.new_page_button.contains(ctx.context, rpoint) {
+            self.page_scroll_event_list.clear();
+            self.open_new_page(ctx, t);
             self.check_move_page_icon_visibility();
             return true;
         } else if self.next10_button.contains(ctx.context, rpoint) {
             self.redraw_request = DrawRequest::Draw;
             self.page_scroll_event_list.clear();
 
-            for i in 0..10 {
-                if self.current_page + i + 1 < self.pages.len() {
-                    self.page_scroll_event_list.add_event(
-                        Box::new(|slf, _, _| {
-                            slf.current_page += 1;
-                            slf.redraw_request = DrawRequest::Draw;
-                            slf.check_move_page_icon_visibility();
-                        }),
-                        t + (i * 2) as Clock,
-                    );
-                } else {
-                    break;
-                }
+            let target = record_book_scroll_target(self.current_page, self.pages.len(), 10);
+            for i in 0..(target - self.current_page) {
+                self.page_scroll_event_list.add_event(
+                    Box::new(|slf, _, t| {
+                        slf.current_page += 1;
+                        slf.start_page_flip(t);
+                        slf.check_move_page_icon_visibility();
+                    }),
+                    t + (i * 2) as Clock,
+                );
             }
             ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
             self.check_move_page_icon_visibility();
         } else if self.prev10_button.contains(ctx.context, rpoint) {
             self.page_scroll_event_list.clear();
-            for i in 0..10 {
-                if self.current_page as i32 - i > 0 {
-                    self.page_scroll_event_list.add_event(
-                        Box::new(|slf, _, _| {
-                            slf.current_page -= 1;
-                            slf.redraw_request = DrawRequest::Draw;
-                            slf.check_move_page_icon_visibility();
-                        }),
-                        t + (i * 2) as Clock,
-                    );
-                } else {
-                    break;
-                }
+
+            let target = record_book_scroll_target(self.current_page, self.pages.len(), -10);
+            for i in 0..(self.current_page - target) {
+                self.page_scroll_event_list.add_event(
+                    Box::new(|slf, _, t| {
+                        slf.current_page -= 1;
+                        slf.start_page_flip(t);
+                        slf.check_move_page_icon_visibility();
+                    }),
+                    t + (i * 2) as Clock,
+                );
             }
             ctx.play_sound_as_se(SoundID::SeTurnThePage, None);
         }
@@ -2448,12 +4364,47 @@ impl BorrowingRecordBook {
         self.pages.len()
     }
 
+    pub fn get_current_page_index(&self) -> usize {
+        self.current_page
+    }
+
+    ///
+    /// 書きかけの頁をpage_indexの位置に戻し、その頁を開く
+    ///
+    pub fn restore_page<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        page_index: usize,
+        page_data: BorrowingRecordBookPageData,
+        t: Clock,
+    ) {
+        while self.pages.len() <= page_index {
+            self.add_empty_page(ctx, t);
+        }
+
+        self.pages[page_index] = BorrowingRecordBookPage::new(
+            ctx,
+            self.page_rect,
+            TextureID::Paper1,
+            page_data,
+            t,
+        );
+        self.current_page = page_index;
+        self.check_move_page_icon_visibility();
+    }
+
     pub fn update<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         if !self.is_stop() {
             ctx.process_utility.redraw();
             self.move_with_func(t);
         }
 
+        if !self.page_flip_cover.is_empty_effect() {
+            self.page_flip_cover.effect(ctx.context, t);
+            self.redraw_request = DrawRequest::Draw;
+            ctx.process_utility.redraw();
+        }
+
         flush_delay_event_and_redraw_check!(self, self.page_scroll_event_list, ctx, t, {})
     }
 
@@ -2483,6 +4434,25 @@ impl BorrowingRecordBook {
         }
     }
 
+    ///
+    /// 今の頁の欄の升の真ん中を、帳簿の外の座標で返す。Tabで欄を巡るときに、クリックの代わりにする
+    ///
+    pub fn get_cell_center(&self, cell: RecordBookCell) -> Option<numeric::Point2f> {
+        let page = self.get_current_page()?;
+        let grid_pos = cell.grid_position();
+        let page_point = if cell.is_customer_info() {
+            page.get_customer_info_table_center_of(grid_pos)
+        } else {
+            page.get_books_table_center_of(grid_pos)
+        };
+
+        let book_position = self.canvas.get_position();
+        Some(numeric::Point2f::new(
+            book_position.x + page_point.x,
+            book_position.y + page_point.y,
+        ))
+    }
+
     pub fn get_payment_frame_grid_position(
         &self,
         ctx: &mut ggez::Context,
@@ -2543,6 +4513,14 @@ impl BorrowingRecordBook {
             self.prev_page_ope_mesh.hide();
         }
 
+        if self.current_page + 1 >= self.pages.len() {
+            self.next_page_ope_mesh.hide();
+        }
+
+        self.page_indicator.replace_text(record_book_page_indicator(
+            self.current_page,
+            self.pages.len(),
+        ));
         self.redraw_request = DrawRequest::Draw;
     }
 
@@ -2612,13 +4590,29 @@ impl DrawableComponent for BorrowingRecordBook {
 
                 self.next10_button.draw(ctx)?;
                 self.prev10_button.draw(ctx)?;
+                self.new_page_button.draw(ctx)?;
+                self.search_button.draw(ctx)?;
+                if self.search.is_active() {
+                    self.search_prev_button.draw(ctx)?;
+                    self.search_next_button.draw(ctx)?;
+                }
 
                 if self.pages.len() > 0 {
-                    self.pages.get_mut(self.current_page).unwrap().draw(ctx)?;
+                    let page = self.pages.get_mut(self.current_page).unwrap();
+                    page.draw(ctx)?;
+
+                    if let Some(target) = self.assist_target.as_ref() {
+                        page.draw_assist_highlight(ctx, target)?;
+                    }
                 }
 
+                self.page_flip_cover.draw(ctx)?;
+
                 self.prev_page_ope_mesh.draw(ctx)?;
                 self.next_page_ope_mesh.draw(ctx)?;
+                self.page_indicator.draw(ctx)?;
+                self.search_status.draw(ctx)?;
+                self.search_input.draw(ctx)?;
 
                 //self.scope.draw(ctx)?;
 
@@ -2729,6 +4723,8 @@ where
     shelving_box_locked: bool,
     object_type: DeskObjectType,
     drag_point: numeric::Vector2f,
+    selected: bool,
+    highlight: Option<Highlight>,
 }
 
 impl<S, L> TaskItemStruct<S, L>
@@ -2759,6 +4755,8 @@ where
             shelving_box_locked: shelving_box_locked,
             object_type: obj_type,
             drag_point: numeric::Vector2f::new(0.0, 0.0),
+            selected: false,
+            highlight: None,
         }
     }
 
@@ -2877,10 +4875,38 @@ where
     pub fn set_drag_point(&mut self, drag_point: numeric::Vector2f) {
         self.drag_point = drag_point;
     }
+
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+
+    pub fn get_highlight(&self) -> Option<Highlight> {
+        self.highlight
+    }
+
+    pub fn set_highlight(&mut self, highlight: Option<Highlight>) {
+        self.highlight = highlight;
+    }
+
+    pub fn update_highlight(&mut self, t: Clock) -> bool {
+        match self.highlight.as_mut() {
+            Some(highlight) => highlight.update(t),
+            None => false,
+        }
+    }
 }
 
 pub type TaskBook = TaskItemStruct<OnDeskTexture, OnDeskBook>;
 pub type TaskTexture = TaskItemStruct<OnDeskTexture, OnDeskTexture>;
+pub type TaskBoard = TaskItemStruct<OnDeskTexture, PopularBookBoard>;
+pub type TaskMemo = TaskItemStruct<OnDeskTexture, MemoPad>;
+pub type TaskBundle = TaskItemStruct<OnDeskTexture, DeskBundle>;
+pub type TaskLetter = TaskItemStruct<OnDeskTexture, LetterSheet>;
+pub type TaskCard = TaskItemStruct<OnDeskTexture, MembershipCardSheet>;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DeskObjectType {
@@ -2895,6 +4921,11 @@ pub enum TaskItem {
     Book(TaskBook),
     Texture(TaskTexture),
     Coin(TaskTexture),
+    Board(TaskBoard),
+    Memo(TaskMemo),
+    Bundle(TaskBundle),
+    Letter(TaskLetter),
+    Card(TaskCard),
 }
 
 impl TaskItem {
@@ -2903,6 +4934,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.enable_small(),
             TaskItem::Texture(item) => item.enable_small(),
             TaskItem::Coin(item) => item.enable_small(),
+            TaskItem::Board(item) => item.enable_small(),
+            TaskItem::Memo(item) => item.enable_small(),
+            TaskItem::Bundle(item) => item.enable_small(),
+            TaskItem::Letter(item) => item.enable_small(),
+            TaskItem::Card(item) => item.enable_small(),
         }
     }
 
@@ -2911,6 +4947,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.enable_large(),
             TaskItem::Texture(item) => item.enable_large(),
             TaskItem::Coin(item) => item.enable_large(),
+            TaskItem::Board(item) => item.enable_large(),
+            TaskItem::Memo(item) => item.enable_large(),
+            TaskItem::Bundle(item) => item.enable_large(),
+            TaskItem::Letter(item) => item.enable_large(),
+            TaskItem::Card(item) => item.enable_large(),
         }
     }
 
@@ -2919,6 +4960,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_object_type(),
             TaskItem::Texture(item) => item.get_object_type(),
             TaskItem::Coin(item) => item.get_object_type(),
+            TaskItem::Board(item) => item.get_object_type(),
+            TaskItem::Memo(item) => item.get_object_type(),
+            TaskItem::Bundle(item) => item.get_object_type(),
+            TaskItem::Letter(item) => item.get_object_type(),
+            TaskItem::Card(item) => item.get_object_type(),
         }
     }
 
@@ -2927,6 +4973,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_object(),
             TaskItem::Texture(item) => item.get_object(),
             TaskItem::Coin(item) => item.get_object(),
+            TaskItem::Board(item) => item.get_object(),
+            TaskItem::Memo(item) => item.get_object(),
+            TaskItem::Bundle(item) => item.get_object(),
+            TaskItem::Letter(item) => item.get_object(),
+            TaskItem::Card(item) => item.get_object(),
         }
     }
 
@@ -2935,6 +4986,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_object_mut(),
             TaskItem::Texture(item) => item.get_object_mut(),
             TaskItem::Coin(item) => item.get_object_mut(),
+            TaskItem::Board(item) => item.get_object_mut(),
+            TaskItem::Memo(item) => item.get_object_mut(),
+            TaskItem::Bundle(item) => item.get_object_mut(),
+            TaskItem::Letter(item) => item.get_object_mut(),
+            TaskItem::Card(item) => item.get_object_mut(),
         }
     }
 
@@ -2943,6 +4999,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_movable_object(),
             TaskItem::Texture(item) => item.get_movable_object(),
             TaskItem::Coin(item) => item.get_movable_object(),
+            TaskItem::Board(item) => item.get_movable_object(),
+            TaskItem::Memo(item) => item.get_movable_object(),
+            TaskItem::Bundle(item) => item.get_movable_object(),
+            TaskItem::Letter(item) => item.get_movable_object(),
+            TaskItem::Card(item) => item.get_movable_object(),
         }
     }
 
@@ -2951,6 +5012,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_movable_object_mut(),
             TaskItem::Texture(item) => item.get_movable_object_mut(),
             TaskItem::Coin(item) => item.get_movable_object_mut(),
+            TaskItem::Board(item) => item.get_movable_object_mut(),
+            TaskItem::Memo(item) => item.get_movable_object_mut(),
+            TaskItem::Bundle(item) => item.get_movable_object_mut(),
+            TaskItem::Letter(item) => item.get_movable_object_mut(),
+            TaskItem::Card(item) => item.get_movable_object_mut(),
         }
     }
 
@@ -2959,6 +5025,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_effectable_object(),
             TaskItem::Texture(item) => item.get_effectable_object(),
             TaskItem::Coin(item) => item.get_effectable_object(),
+            TaskItem::Board(item) => item.get_effectable_object(),
+            TaskItem::Memo(item) => item.get_effectable_object(),
+            TaskItem::Bundle(item) => item.get_effectable_object(),
+            TaskItem::Letter(item) => item.get_effectable_object(),
+            TaskItem::Card(item) => item.get_effectable_object(),
         }
     }
 
@@ -2967,6 +5038,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.is_handover_locked(),
             TaskItem::Texture(item) => item.is_handover_locked(),
             TaskItem::Coin(item) => item.is_handover_locked(),
+            TaskItem::Board(item) => item.is_handover_locked(),
+            TaskItem::Memo(item) => item.is_handover_locked(),
+            TaskItem::Bundle(item) => item.is_handover_locked(),
+            TaskItem::Letter(item) => item.is_handover_locked(),
+            TaskItem::Card(item) => item.is_handover_locked(),
         }
     }
 
@@ -2975,6 +5051,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.lock_handover(),
             TaskItem::Texture(item) => item.lock_handover(),
             TaskItem::Coin(item) => item.lock_handover(),
+            TaskItem::Board(item) => item.lock_handover(),
+            TaskItem::Memo(item) => item.lock_handover(),
+            TaskItem::Bundle(item) => item.lock_handover(),
+            TaskItem::Letter(item) => item.lock_handover(),
+            TaskItem::Card(item) => item.lock_handover(),
         }
     }
 
@@ -2983,6 +5064,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.unlock_handover(),
             TaskItem::Texture(item) => item.unlock_handover(),
             TaskItem::Coin(item) => item.unlock_handover(),
+            TaskItem::Board(item) => item.unlock_handover(),
+            TaskItem::Memo(item) => item.unlock_handover(),
+            TaskItem::Bundle(item) => item.unlock_handover(),
+            TaskItem::Letter(item) => item.unlock_handover(),
+            TaskItem::Card(item) => item.unlock_handover(),
         }
     }
 
@@ -2991,6 +5077,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.is_shelving_box_handover_locked(),
             TaskItem::Texture(item) => item.is_shelving_box_handover_locked(),
             TaskItem::Coin(item) => item.is_shelving_box_handover_locked(),
+            TaskItem::Board(item) => item.is_shelving_box_handover_locked(),
+            TaskItem::Memo(item) => item.is_shelving_box_handover_locked(),
+            TaskItem::Bundle(item) => item.is_shelving_box_handover_locked(),
+            TaskItem::Letter(item) => item.is_shelving_box_handover_locked(),
+            TaskItem::Card(item) => item.is_shelving_box_handover_locked(),
         }
     }
 
@@ -2999,6 +5090,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.lock_shelving_box_handover(),
             TaskItem::Texture(item) => item.lock_shelving_box_handover(),
             TaskItem::Coin(item) => item.lock_shelving_box_handover(),
+            TaskItem::Board(item) => item.lock_shelving_box_handover(),
+            TaskItem::Memo(item) => item.lock_shelving_box_handover(),
+            TaskItem::Bundle(item) => item.lock_shelving_box_handover(),
+            TaskItem::Letter(item) => item.lock_shelving_box_handover(),
+            TaskItem::Card(item) => item.lock_shelving_box_handover(),
         }
     }
 
@@ -3007,6 +5103,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.unlock_shelving_box_handover(),
             TaskItem::Texture(item) => item.unlock_shelving_box_handover(),
             TaskItem::Coin(item) => item.unlock_shelving_box_handover(),
+            TaskItem::Board(item) => item.unlock_shelving_box_handover(),
+            TaskItem::Memo(item) => item.unlock_shelving_box_handover(),
+            TaskItem::Bundle(item) => item.unlock_shelving_box_handover(),
+            TaskItem::Letter(item) => item.unlock_shelving_box_handover(),
+            TaskItem::Card(item) => item.unlock_shelving_box_handover(),
         }
     }
 
@@ -3015,6 +5116,11 @@ impl TaskItem {
             TaskItem::Book(item) => item.get_drag_point(),
             TaskItem::Texture(item) => item.get_drag_point(),
             TaskItem::Coin(item) => item.get_drag_point(),
+            TaskItem::Board(item) => item.get_drag_point(),
+            TaskItem::Memo(item) => item.get_drag_point(),
+            TaskItem::Bundle(item) => item.get_drag_point(),
+            TaskItem::Letter(item) => item.get_drag_point(),
+            TaskItem::Card(item) => item.get_drag_point(),
         }
     }
 
@@ -3023,8 +5129,163 @@ impl TaskItem {
             TaskItem::Book(item) => item.set_drag_point(drag_point),
             TaskItem::Texture(item) => item.set_drag_point(drag_point),
             TaskItem::Coin(item) => item.set_drag_point(drag_point),
+            TaskItem::Board(item) => item.set_drag_point(drag_point),
+            TaskItem::Memo(item) => item.set_drag_point(drag_point),
+            TaskItem::Bundle(item) => item.set_drag_point(drag_point),
+            TaskItem::Letter(item) => item.set_drag_point(drag_point),
+            TaskItem::Card(item) => item.set_drag_point(drag_point),
+        }
+    }
+
+    pub fn is_selected(&self) -> bool {
+        match self {
+            TaskItem::Book(item) => item.is_selected(),
+            TaskItem::Texture(item) => item.is_selected(),
+            TaskItem::Coin(item) => item.is_selected(),
+            TaskItem::Board(item) => item.is_selected(),
+            TaskItem::Memo(item) => item.is_selected(),
+            TaskItem::Bundle(item) => item.is_selected(),
+            TaskItem::Letter(item) => item.is_selected(),
+            TaskItem::Card(item) => item.is_selected(),
+        }
+    }
+
+    pub fn set_selected(&mut self, selected: bool) {
+        match self {
+            TaskItem::Book(item) => item.set_selected(selected),
+            TaskItem::Texture(item) => item.set_selected(selected),
+            TaskItem::Coin(item) => item.set_selected(selected),
+            TaskItem::Board(item) => item.set_selected(selected),
+            TaskItem::Memo(item) => item.set_selected(selected),
+            TaskItem::Bundle(item) => item.set_selected(selected),
+            TaskItem::Letter(item) => item.set_selected(selected),
+            TaskItem::Card(item) => item.set_selected(selected),
+        }
+    }
+
+    pub fn get_highlight(&self) -> Option<Highlight> {
+        match self {
+            TaskItem::Book(item) => item.get_highlight(),
+            TaskItem::Texture(item) => item.get_highlight(),
+            TaskItem::Coin(item) => item.get_highlight(),
+            TaskItem::Board(item) => item.get_highlight(),
+            TaskItem::Memo(item) => item.get_highlight(),
+            TaskItem::Bundle(item) => item.get_highlight(),
+            TaskItem::Letter(item) => item.get_highlight(),
+            TaskItem::Card(item) => item.get_highlight(),
+        }
+    }
+
+    pub fn set_highlight(&mut self, highlight: Option<Highlight>) {
+        match self {
+            TaskItem::Book(item) => item.set_highlight(highlight),
+            TaskItem::Texture(item) => item.set_highlight(highlight),
+            TaskItem::Coin(item) => item.set_highlight(highlight),
+            TaskItem::Board(item) => item.set_highlight(highlight),
+            TaskItem::Memo(item) => item.set_highlight(highlight),
+            TaskItem::Bundle(item) => item.set_highlight(highlight),
+            TaskItem::Letter(item) => item.set_highlight(highlight),
+            TaskItem::Card(item) => item.set_highlight(highlight),
+        }
+    }
+
+    ///
+    /// 目立たせ方の時刻を進める。描き直しが要るならtrueを返す
+    ///
+    pub fn update_highlight(&mut self, t: Clock) -> bool {
+        match self {
+            TaskItem::Book(item) => item.update_highlight(t),
+            TaskItem::Texture(item) => item.update_highlight(t),
+            TaskItem::Coin(item) => item.update_highlight(t),
+            TaskItem::Board(item) => item.update_highlight(t),
+            TaskItem::Memo(item) => item.update_highlight(t),
+            TaskItem::Bundle(item) => item.update_highlight(t),
+            TaskItem::Letter(item) => item.update_highlight(t),
+            TaskItem::Card(item) => item.update_highlight(t),
+        }
+    }
+
+    ///
+    /// 描くときの目立たせ方。指定が無くても、選んでいる物は縁取る
+    ///
+    pub fn drawing_highlight(&self) -> Option<Highlight> {
+        if let Some(highlight) = self.get_highlight() {
+            return Some(highlight);
+        }
+
+        if self.is_selected() {
+            Some(Highlight::outline(
+                ggraphics::Color::from_rgba_u32(SELECTION_HIGHLIGHT_COLOR),
+                0,
+            ))
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// 紐で束ねられる書類かどうか
+    /// 客の持ち物、帳簿、硬貨、書き込み中の覚書、束そのものは束ねない
+    ///
+    pub fn is_bundleable(&self) -> bool {
+        match self {
+            TaskItem::Coin(_) | TaskItem::Bundle(_) => false,
+            TaskItem::Memo(item) => !item.get_large_object().is_editing(),
+            _ => {
+                self.get_object_type() != DeskObjectType::CustomerObject
+                    && self.get_object().get_type() != OnDeskType::BorrowingRecordBook
+            }
         }
     }
+
+    ///
+    /// 今の向きから、radだけ回す。向きは物そのものに持たせる
+    ///
+    pub fn rotate_by(&mut self, rad: f32) {
+        let object = self.get_object_mut();
+        let rotation = normalize_rotation(object.get_rotation() + rad);
+        object.set_rotation(rotation);
+    }
+
+    fn rotation_pivot(area: numeric::Rect) -> numeric::Point2f {
+        numeric::Point2f::new(area.x, area.y)
+    }
+
+    ///
+    /// 回した分も含めて、pointが物の上にあるか
+    ///
+    pub fn contains_rotated(&self, ctx: &mut ggez::Context, point: numeric::Point2f) -> bool {
+        let object = self.get_object();
+        let area = object.get_drawing_area(ctx);
+        rotated_contains(
+            area,
+            Self::rotation_pivot(area),
+            object.get_rotation(),
+            point,
+        )
+    }
+
+    ///
+    /// 回した物の上の点を、回す前の物の上の点に戻す。物の中の欄を探すときに使う
+    ///
+    pub fn unrotated_point(
+        &self,
+        ctx: &mut ggez::Context,
+        point: numeric::Point2f,
+    ) -> numeric::Point2f {
+        let object = self.get_object();
+        let area = object.get_drawing_area(ctx);
+        unrotate_point(Self::rotation_pivot(area), object.get_rotation(), point)
+    }
+
+    ///
+    /// 回した分も含めて、物全体を囲む矩形
+    ///
+    pub fn rotated_drawing_area(&self, ctx: &mut ggez::Context) -> numeric::Rect {
+        let object = self.get_object();
+        let area = object.get_drawing_area(ctx);
+        rotated_bounds(area, Self::rotation_pivot(area), object.get_rotation())
+    }
 }
 
 impl DrawableComponent for TaskItem {
@@ -3033,6 +5294,11 @@ impl DrawableComponent for TaskItem {
             TaskItem::Book(item) => item.get_object_mut().draw(ctx),
             TaskItem::Texture(item) => item.get_object_mut().draw(ctx),
             TaskItem::Coin(item) => item.get_object_mut().draw(ctx),
+            TaskItem::Board(item) => item.get_object_mut().draw(ctx),
+            TaskItem::Memo(item) => item.get_object_mut().draw(ctx),
+            TaskItem::Bundle(item) => item.get_object_mut().draw(ctx),
+            TaskItem::Letter(item) => item.get_object_mut().draw(ctx),
+            TaskItem::Card(item) => item.get_object_mut().draw(ctx),
         }
     }
 
@@ -3041,6 +5307,11 @@ impl DrawableComponent for TaskItem {
             TaskItem::Book(item) => item.get_object_mut().hide(),
             TaskItem::Texture(item) => item.get_object_mut().hide(),
             TaskItem::Coin(item) => item.get_object_mut().hide(),
+            TaskItem::Board(item) => item.get_object_mut().hide(),
+            TaskItem::Memo(item) => item.get_object_mut().hide(),
+            TaskItem::Bundle(item) => item.get_object_mut().hide(),
+            TaskItem::Letter(item) => item.get_object_mut().hide(),
+            TaskItem::Card(item) => item.get_object_mut().hide(),
         }
     }
 
@@ -3049,6 +5320,11 @@ impl DrawableComponent for TaskItem {
             TaskItem::Book(item) => item.get_object_mut().appear(),
             TaskItem::Texture(item) => item.get_object_mut().appear(),
             TaskItem::Coin(item) => item.get_object_mut().appear(),
+            TaskItem::Board(item) => item.get_object_mut().appear(),
+            TaskItem::Memo(item) => item.get_object_mut().appear(),
+            TaskItem::Bundle(item) => item.get_object_mut().appear(),
+            TaskItem::Letter(item) => item.get_object_mut().appear(),
+            TaskItem::Card(item) => item.get_object_mut().appear(),
         }
     }
 
@@ -3057,6 +5333,11 @@ impl DrawableComponent for TaskItem {
             TaskItem::Book(item) => item.get_object().is_visible(),
             TaskItem::Texture(item) => item.get_object().is_visible(),
             TaskItem::Coin(item) => item.get_object().is_visible(),
+            TaskItem::Board(item) => item.get_object().is_visible(),
+            TaskItem::Memo(item) => item.get_object().is_visible(),
+            TaskItem::Bundle(item) => item.get_object().is_visible(),
+            TaskItem::Letter(item) => item.get_object().is_visible(),
+            TaskItem::Card(item) => item.get_object().is_visible(),
         }
     }
 
@@ -3065,6 +5346,11 @@ impl DrawableComponent for TaskItem {
             TaskItem::Book(item) => item.get_object_mut().set_drawing_depth(depth),
             TaskItem::Texture(item) => item.get_object_mut().set_drawing_depth(depth),
             TaskItem::Coin(item) => item.get_object_mut().set_drawing_depth(depth),
+            TaskItem::Board(item) => item.get_object_mut().set_drawing_depth(depth),
+            TaskItem::Memo(item) => item.get_object_mut().set_drawing_depth(depth),
+            TaskItem::Bundle(item) => item.get_object_mut().set_drawing_depth(depth),
+            TaskItem::Letter(item) => item.get_object_mut().set_drawing_depth(depth),
+            TaskItem::Card(item) => item.get_object_mut().set_drawing_depth(depth),
         }
     }
 
@@ -3073,6 +5359,11 @@ impl DrawableComponent for TaskItem {
             TaskItem::Book(item) => item.get_object().get_drawing_depth(),
             TaskItem::Texture(item) => item.get_object().get_drawing_depth(),
             TaskItem::Coin(item) => item.get_object().get_drawing_depth(),
+            TaskItem::Board(item) => item.get_object().get_drawing_depth(),
+            TaskItem::Memo(item) => item.get_object().get_drawing_depth(),
+            TaskItem::Bundle(item) => item.get_object().get_drawing_depth(),
+            TaskItem::Letter(item) => item.get_object().get_drawing_depth(),
+            TaskItem::Card(item) => item.get_object().get_drawing_depth(),
         }
     }
 }
@@ -11,15 +11,18 @@ use torifune::impl_texture_object_for_wrapped;
 use torifune::numeric;
 use torifune::roundup2f;
 
-use crate::core::{FontID, GensoDate, ResultReport, SavableData, SuzuContext, TileBatchTextureID};
+use crate::core::diary::{DiaryEntry, DiaryPrompt, DIARY_PROMPT_MAX, DIARY_TEXT_MAX_CHARS};
+use crate::core::{
+    BookCondition, FontID, GensoDate, MistakeRecord, MistakeSortKey, ResultReport, SavableData,
+    SuzuContext, TextureID, TileBatchTextureID,
+};
 use crate::object::effect;
+use crate::object::task_object::tt_sub_component::BorrowingRecordBookPage;
 use crate::object::util_object::*;
 use crate::set_table_frame_cell_center;
 
 use crate::object::simulation_ui::*;
 
-use number_to_jk::number_to_jk;
-
 struct DrawableEvaluationFlow {
     eval_frame: TableFrame,
     desc_text: Vec<VerticalText>,
@@ -42,7 +45,7 @@ impl DrawableEvaluationFlow {
             ctx.resource,
             pos,
             TileBatchTextureID::OldStyleFrame,
-            FrameData::new(vec![250.0, 250.0], vec![50.0; 4]),
+            FrameData::new(vec![250.0, 250.0], vec![50.0; 8]),
             numeric::Vector2f::new(0.5, 0.5),
 	    ggraphics::FilterMode::Nearest,
             0,
@@ -57,6 +60,10 @@ impl DrawableEvaluationFlow {
         effect_time_list.push_back(t + effect_clock_offset + 50);
         effect_time_list.push_back(t + effect_clock_offset + 100);
         effect_time_list.push_back(t + effect_clock_offset + 150);
+        effect_time_list.push_back(t + effect_clock_offset + 200);
+        effect_time_list.push_back(t + effect_clock_offset + 250);
+        effect_time_list.push_back(t + effect_clock_offset + 300);
+        effect_time_list.push_back(t + effect_clock_offset + 350);
 
         let font_info = FontInformation::new(
             ctx.resource.get_font(FontID::JpFude1),
@@ -64,7 +71,16 @@ impl DrawableEvaluationFlow {
             ggraphics::Color::from_rgba_u32(0xff),
         );
 
-        for (index, s) in vec!["総合評価", "誤評価数", "配架完了", "客を待たせた時間"]
+        for (index, s) in vec![
+            "総合評価",
+            "誤評価数",
+            "配架完了",
+            "客を待たせた時間",
+            "受付の正確さ",
+            "平均対応秒数",
+            "夜間営業",
+            "帳簿の正確さ",
+        ]
             .iter()
             .enumerate()
         {
@@ -87,7 +103,7 @@ impl DrawableEvaluationFlow {
             desc_text.push(vtext);
         }
 
-        let result_report_string_table = result_report.create_table();
+        let result_report_string_table = result_report.create_table(ctx.number_format());
 
         let mut eval_mistakes_vtext = EffectableWrap::new(
             MovableWrap::new(
@@ -188,6 +204,91 @@ impl DrawableEvaluationFlow {
             numeric::Vector2u::new(3, 1)
         );
 
+        let mut desk_stats_vtexts = Vec::new();
+        for (index, s) in vec![
+            result_report_string_table.average_handling_time,
+            result_report_string_table.desk_accuracy,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut vtext = EffectableWrap::new(
+                MovableWrap::new(
+                    Box::new(VerticalText::new(
+                        s,
+                        numeric::Point2f::new(0.0, 0.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        font_info.clone(),
+                    )),
+                    None,
+                    t,
+                ),
+                Vec::new(),
+            );
+            vtext.set_crop(init_crop);
+
+            set_table_frame_cell_center!(
+                ctx.context,
+                eval_frame,
+                vtext,
+                numeric::Vector2u::new(5 - index as u32, 1)
+            );
+            desk_stats_vtexts.push(vtext);
+        }
+
+        let mut late_night_vtext = EffectableWrap::new(
+            MovableWrap::new(
+                Box::new(VerticalText::new(
+                    result_report_string_table.late_night_time,
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )),
+                None,
+                t,
+            ),
+            Vec::new(),
+        );
+        late_night_vtext.set_crop(init_crop);
+
+        set_table_frame_cell_center!(
+            ctx.context,
+            eval_frame,
+            late_night_vtext,
+            numeric::Vector2u::new(6, 1)
+        );
+
+        let mut record_accuracy_vtext = EffectableWrap::new(
+            MovableWrap::new(
+                Box::new(VerticalText::new(
+                    result_report_string_table.record_accuracy,
+                    numeric::Point2f::new(0.0, 0.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )),
+                None,
+                t,
+            ),
+            Vec::new(),
+        );
+        record_accuracy_vtext.set_crop(init_crop);
+
+        set_table_frame_cell_center!(
+            ctx.context,
+            eval_frame,
+            record_accuracy_vtext,
+            numeric::Vector2u::new(7, 1)
+        );
+
+        yet_effect_text.push_back(record_accuracy_vtext);
+        yet_effect_text.push_back(late_night_vtext);
+        yet_effect_text.extend(desk_stats_vtexts);
         yet_effect_text.push_back(waiting_vtext);
         yet_effect_text.push_back(shelving_vtext);
         yet_effect_text.push_back(eval_mistakes_vtext);
@@ -348,7 +449,7 @@ impl DrawableTaskResult {
             ctx.resource,
             numeric::Point2f::new(750.0, 80.0),
             TileBatchTextureID::OldStyleFrame,
-            FrameData::new(vec![280.0, 270.0], vec![50.0; 3]),
+            FrameData::new(vec![280.0, 270.0], vec![50.0; 5]),
             numeric::Vector2f::new(0.5, 0.5),
 	    ggraphics::FilterMode::Nearest,
             0,
@@ -358,7 +459,7 @@ impl DrawableTaskResult {
         let mut effect_text = VecDeque::new();
 
         let title_text = VerticalText::new(
-            format!("{}", date.to_string()),
+            ctx.number_format().date(&date),
             numeric::Point2f::new(1100.0, 80.0),
             numeric::Vector2f::new(1.0, 1.0),
             0.0,
@@ -389,7 +490,7 @@ impl DrawableTaskResult {
             ctx.context,
             result_frame,
             done_work_text,
-            numeric::Vector2u::new(2, 0)
+            numeric::Vector2u::new(4, 0)
         );
         fixed_text.push(done_work_text);
 
@@ -397,6 +498,8 @@ impl DrawableTaskResult {
         effect_time_list.push_back(t + 50);
         effect_time_list.push_back(t + 100);
         effect_time_list.push_back(t + 150);
+        effect_time_list.push_back(t + 200);
+        effect_time_list.push_back(t + 250);
 
         let task_result = ctx.take_save_data().task_result.clone();
 
@@ -404,7 +507,7 @@ impl DrawableTaskResult {
         let mut done_work_num_text = EffectableWrap::new(
             MovableWrap::new(
                 Box::new(VerticalText::new(
-                    format!("{}人", number_to_jk(done_work_num as u64)),
+                    ctx.number_format().count(done_work_num as u64, "人"),
                     numeric::Point2f::new(600.0, 100.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0.0,
@@ -421,7 +524,7 @@ impl DrawableTaskResult {
             ctx.context,
             result_frame,
             done_work_num_text,
-            numeric::Vector2u::new(2, 1)
+            numeric::Vector2u::new(4, 1)
         );
         effect_text.push_back(done_work_num_text);
         ctx.take_save_data_mut()
@@ -440,19 +543,16 @@ impl DrawableTaskResult {
             ctx.context,
             result_frame,
             money_desc_text,
-            numeric::Vector2u::new(1, 0)
+            numeric::Vector2u::new(3, 0)
         );
         fixed_text.push(money_desc_text);
 
         let mut money_text = EffectableWrap::new(
             MovableWrap::new(
                 Box::new(VerticalText::new(
-                    format!(
-                        "{}円",
-                        number_to_jk(
-                            (task_result.total_money - initial_save_data.task_result.total_money)
-                                as u64
-                        )
+                    ctx.number_format().money(
+                        (task_result.total_money - initial_save_data.task_result.total_money)
+                            as u64,
                     ),
                     numeric::Point2f::new(600.0, 100.0),
                     numeric::Vector2f::new(1.0, 1.0),
@@ -470,10 +570,63 @@ impl DrawableTaskResult {
             ctx.context,
             result_frame,
             money_text,
-            numeric::Vector2u::new(1, 1)
+            numeric::Vector2u::new(3, 1)
         );
         effect_text.push_back(money_text);
 
+        // 収入のうち、貸出料と延滞料の内訳
+        let earnings = ctx
+            .take_save_data()
+            .economy
+            .diff_from(&initial_save_data.economy);
+        for (index, (desc, money)) in vec![
+            ("貸出料", earnings.rental_fees),
+            ("延滞料", earnings.late_fees),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut desc_text = VerticalText::new(
+                desc.to_string(),
+                numeric::Point2f::new(0.0, 0.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info,
+            );
+            set_table_frame_cell_center!(
+                ctx.context,
+                result_frame,
+                desc_text,
+                numeric::Vector2u::new(2 - index as u32, 0)
+            );
+            fixed_text.push(desc_text);
+
+            let mut money_text = EffectableWrap::new(
+                MovableWrap::new(
+                    Box::new(VerticalText::new(
+                        ctx.number_format().money(money.max(0) as u64),
+                        numeric::Point2f::new(0.0, 0.0),
+                        numeric::Vector2f::new(1.0, 1.0),
+                        0.0,
+                        0,
+                        font_info_small,
+                    )),
+                    None,
+                    t,
+                ),
+                Vec::new(),
+            );
+            money_text.set_crop(init_crop);
+            set_table_frame_cell_center!(
+                ctx.context,
+                result_frame,
+                money_text,
+                numeric::Vector2u::new(2 - index as u32, 1)
+            );
+            effect_text.push_back(money_text);
+        }
+
         let mut total_money_desc_text = VerticalText::new(
             format!("所持金"),
             numeric::Point2f::new(0.0, 0.0),
@@ -493,10 +646,8 @@ impl DrawableTaskResult {
         let mut total_money_text = EffectableWrap::new(
             MovableWrap::new(
                 Box::new(VerticalText::new(
-                    format!(
-                        "{}円",
-                        number_to_jk(ctx.take_save_data().task_result.total_money as u64)
-                    ),
+                    ctx.number_format()
+                        .money(ctx.take_save_data().task_result.total_money as u64),
                     numeric::Point2f::new(0.0, 0.0),
                     numeric::Vector2f::new(1.0, 1.0),
                     0.0,
@@ -685,3 +836,646 @@ impl DrawableObject for DrawableTaskResult {
 impl TextureObject for DrawableTaskResult {
     impl_texture_object_for_wrapped! {canvas}
 }
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MistakeReviewAction {
+    Keep,
+    Close,
+}
+
+///
+/// # 見直し中の受付一件分の表示
+/// 左に署名したときの頁、右に正しい状態を並べる
+///
+struct MistakeDetailView {
+    record: MistakeRecord,
+    page_canvas: SubScreen,
+    page: BorrowingRecordBookPage,
+    expected_text: Vec<UniText>,
+    practice_text: UniText,
+    back_text: UniText,
+}
+
+impl MistakeDetailView {
+    fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        record: MistakeRecord,
+        font_info: FontInformation,
+        t: Clock,
+    ) -> Self {
+        let page_rect = numeric::Rect::new(0.0, 0.0, 1000.0, 550.0);
+        let page = BorrowingRecordBookPage::new(
+            ctx,
+            page_rect,
+            TextureID::Paper1,
+            record.submitted_page.clone(),
+            t,
+        );
+
+        let mut expected_text = Vec::new();
+        let mut text_pos = numeric::Point2f::new(1020.0, 20.0);
+
+        expected_text.push(UniText::new(
+            format!("{}様 正シイ記入", record.request.borrower),
+            text_pos,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        ));
+        text_pos.y += 45.0;
+
+        let mistake_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xbb2222ff),
+        );
+
+        for (book_info, eval) in record.evaluations.iter() {
+            let line_font = if book_info.get_condition() != *eval {
+                mistake_font_info
+            } else {
+                font_info
+            };
+
+            expected_text.push(UniText::new(
+                format!("『{}』", book_info.name),
+                text_pos,
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                line_font,
+            ));
+            text_pos.y += 30.0;
+
+            expected_text.push(UniText::new(
+                format!(
+                    "  記入 {} / 正 {}",
+                    eval.to_string(),
+                    book_info.get_condition().to_string()
+                ),
+                text_pos,
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                line_font,
+            ));
+            text_pos.y += 40.0;
+        }
+
+        let practice_text = UniText::new(
+            "翌朝に練習する".to_string(),
+            numeric::Point2f::new(1020.0, 600.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        );
+
+        let back_text = UniText::new(
+            "一覧へ戻る".to_string(),
+            numeric::Point2f::new(1020.0, 650.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        );
+
+        MistakeDetailView {
+            record: record,
+            page_canvas: SubScreen::new(
+                ctx.context,
+                page_rect,
+                0,
+                ggraphics::Color::from_rgba_u32(0),
+            ),
+            page: page,
+            expected_text: expected_text,
+            practice_text: practice_text,
+            back_text: back_text,
+        }
+    }
+}
+
+impl DrawableComponent for MistakeDetailView {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        sub_screen::stack_screen(ctx, &self.page_canvas);
+        self.page.draw(ctx)?;
+        sub_screen::pop_screen(ctx);
+        self.page_canvas.draw(ctx)?;
+
+        for text in self.expected_text.iter_mut() {
+            text.draw(ctx)?;
+        }
+
+        self.practice_text.draw(ctx)?;
+        self.back_text.draw(ctx)?;
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.page_canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.page_canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.page_canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.page_canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.page_canvas.get_drawing_depth()
+    }
+}
+
+///
+/// # 状態の評価を誤った受付の見直し
+/// 一覧から受付を選ぶと、署名したときの頁と正しい状態を見比べられる
+///
+/// ## filter
+/// 本来の状態で絞り込む。Noneなら全て
+///
+/// ## header_text
+/// 並び順, 絞り込み, 閉じるの順
+///
+pub struct MistakeReviewWindow {
+    result_report: ResultReport,
+    sort_key: MistakeSortKey,
+    filter: Option<BookCondition>,
+    records: Vec<MistakeRecord>,
+    header_text: Vec<UniText>,
+    row_text: Vec<UniText>,
+    detail: Option<MistakeDetailView>,
+    font_info: FontInformation,
+    canvas: SubScreen,
+}
+
+impl MistakeReviewWindow {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        rect: numeric::Rect,
+        result_report: ResultReport,
+        depth: i8,
+    ) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0x000000ff),
+        );
+
+        let mut window = MistakeReviewWindow {
+            result_report: result_report,
+            sort_key: MistakeSortKey::Order,
+            filter: None,
+            records: Vec::new(),
+            header_text: Vec::new(),
+            row_text: Vec::new(),
+            detail: None,
+            font_info: font_info,
+            canvas: SubScreen::new(
+                ctx.context,
+                rect,
+                depth,
+                ggraphics::Color::from_rgba_u32(0xf5eedcff),
+            ),
+        };
+        window.update_list();
+        window
+    }
+
+    fn filter_to_string(&self) -> String {
+        match self.filter.as_ref() {
+            Some(condition) => condition.to_string(),
+            None => "全て".to_string(),
+        }
+    }
+
+    fn next_filter(&self) -> Option<BookCondition> {
+        match self.filter {
+            None => Some(BookCondition::Good),
+            Some(BookCondition::Good) => Some(BookCondition::Fair),
+            Some(BookCondition::Fair) => Some(BookCondition::Bad),
+            Some(BookCondition::Bad) => None,
+        }
+    }
+
+    fn update_list(&mut self) {
+        self.records = self
+            .result_report
+            .review_mistakes(self.sort_key, self.filter.as_ref());
+
+        let sort_str = match self.sort_key {
+            MistakeSortKey::Order => "受付順",
+            MistakeSortKey::MistakeCount => "誤り数順",
+        };
+
+        let header_str = vec![
+            (format!("並び順: {}", sort_str), 20.0),
+            (format!("絞り込み: {}", self.filter_to_string()), 320.0),
+            ("閉じる".to_string(), 1200.0),
+        ];
+
+        self.header_text = header_str
+            .into_iter()
+            .map(|(s, x)| {
+                UniText::new(
+                    s,
+                    numeric::Point2f::new(x, 20.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    self.font_info,
+                )
+            })
+            .collect();
+
+        self.row_text.clear();
+        if self.records.is_empty() {
+            self.row_text.push(UniText::new(
+                "該当スル受付ハアリマセン".to_string(),
+                numeric::Point2f::new(40.0, 80.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                self.font_info,
+            ));
+            return;
+        }
+
+        for (index, record) in self.records.iter().enumerate() {
+            self.row_text.push(UniText::new(
+                format!(
+                    "{}件目  {}様  誤り{}冊",
+                    record.order,
+                    record.request.borrower,
+                    record.count_mistake()
+                ),
+                numeric::Point2f::new(40.0, 80.0 + (index as f32 * 40.0)),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                self.font_info,
+            ));
+        }
+    }
+
+    fn click_detail<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        rpoint: numeric::Point2f,
+    ) -> MistakeReviewAction {
+        let detail = self.detail.as_mut().unwrap();
+
+        if detail.practice_text.contains(ctx.context, rpoint) {
+            ctx.take_save_data_mut().practice_request = Some(detail.record.clone());
+            detail
+                .practice_text
+                .replace_text("翌朝ニ練習シマス".to_string());
+        } else if detail.back_text.contains(ctx.context, rpoint) {
+            self.detail = None;
+        }
+
+        MistakeReviewAction::Keep
+    }
+
+    ///
+    /// 閉じるが押された場合はMistakeReviewAction::Closeを返す
+    ///
+    pub fn click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) -> MistakeReviewAction {
+        let rpoint = self.canvas.relative_point(point);
+
+        if self.detail.is_some() {
+            return self.click_detail(ctx, rpoint);
+        }
+
+        if self.header_text[0].contains(ctx.context, rpoint) {
+            self.sort_key = match self.sort_key {
+                MistakeSortKey::Order => MistakeSortKey::MistakeCount,
+                MistakeSortKey::MistakeCount => MistakeSortKey::Order,
+            };
+            self.update_list();
+        } else if self.header_text[1].contains(ctx.context, rpoint) {
+            self.filter = self.next_filter();
+            self.update_list();
+        } else if self.header_text[2].contains(ctx.context, rpoint) {
+            return MistakeReviewAction::Close;
+        } else if !self.records.is_empty() {
+            let mut selected = None;
+            for (index, text) in self.row_text.iter().enumerate() {
+                if text.contains(ctx.context, rpoint) {
+                    selected = Some(index);
+                    break;
+                }
+            }
+
+            if let Some(index) = selected {
+                let record = self.records[index].clone();
+                self.detail = Some(MistakeDetailView::new(ctx, record, self.font_info, t));
+            }
+        }
+
+        MistakeReviewAction::Keep
+    }
+}
+
+impl DrawableComponent for MistakeReviewWindow {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            if let Some(detail) = self.detail.as_mut() {
+                detail.draw(ctx)?;
+            } else {
+                for text in self.header_text.iter_mut() {
+                    text.draw(ctx)?;
+                }
+
+                for text in self.row_text.iter_mut() {
+                    text.draw(ctx)?;
+                }
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiaryAction {
+    Keep,
+    Write,
+    Skip,
+}
+
+///
+/// # 一日の終わりに書く日記
+/// 書き出しを選び、キーボードで一言書き足せる
+///
+/// ## selected
+/// 選んだ書き出しの番号。選んだ順に並ぶ
+///
+pub struct DiaryWindow {
+    date: GensoDate,
+    prompts: Vec<DiaryPrompt>,
+    selected: Vec<usize>,
+    typed: String,
+    header_text: UniText,
+    prompt_text: Vec<UniText>,
+    typed_text: UniText,
+    write_text: UniText,
+    skip_text: UniText,
+    font_info: FontInformation,
+    selected_font_info: FontInformation,
+    canvas: SubScreen,
+}
+
+impl DiaryWindow {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        rect: numeric::Rect,
+        date: GensoDate,
+        prompts: Vec<DiaryPrompt>,
+        depth: i8,
+    ) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0x000000ff),
+        );
+        let selected_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::JpFude1),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xbb2222ff),
+        );
+
+        let header_text = UniText::new(
+            format!(
+                "{} 小鈴ノ日記  書キ出シヲ{}ツマデ選ブ",
+                ctx.number_format().short_date(&date),
+                ctx.number_format().number(DIARY_PROMPT_MAX as u64)
+            ),
+            numeric::Point2f::new(20.0, 20.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        );
+
+        let write_text = UniText::new(
+            "書イテ眠ル".to_string(),
+            numeric::Point2f::new(20.0, rect.h - 50.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        );
+
+        let skip_text = UniText::new(
+            "書カズニ眠ル".to_string(),
+            numeric::Point2f::new(240.0, rect.h - 50.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        );
+
+        let typed_text = UniText::new(
+            String::new(),
+            numeric::Point2f::new(20.0, rect.h - 110.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info,
+        );
+
+        let mut window = DiaryWindow {
+            date: date,
+            prompts: prompts,
+            selected: Vec::new(),
+            typed: String::new(),
+            header_text: header_text,
+            prompt_text: Vec::new(),
+            typed_text: typed_text,
+            write_text: write_text,
+            skip_text: skip_text,
+            font_info: font_info,
+            selected_font_info: selected_font_info,
+            canvas: SubScreen::new(
+                ctx.context,
+                rect,
+                depth,
+                ggraphics::Color::from_rgba_u32(0xf5eedcff),
+            ),
+        };
+        window.update_text();
+        window
+    }
+
+    fn update_text(&mut self) {
+        let mut prompt_text = Vec::new();
+
+        for (index, prompt) in self.prompts.iter().enumerate() {
+            let (mark, font_info) = if self.selected.contains(&index) {
+                ("●", self.selected_font_info)
+            } else {
+                ("○", self.font_info)
+            };
+
+            prompt_text.push(UniText::new(
+                format!("{} {}", mark, prompt.text),
+                numeric::Point2f::new(40.0, 80.0 + (index as f32 * 40.0)),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info,
+            ));
+        }
+
+        self.prompt_text = prompt_text;
+        self.typed_text
+            .replace_text(format!("書キ足シ: {}_", self.typed));
+    }
+
+    ///
+    /// 選んでいない書き出しは上限まで選び、選んでいれば外す
+    ///
+    fn toggle_prompt(&mut self, index: usize) {
+        if let Some(pos) = self.selected.iter().position(|i| *i == index) {
+            self.selected.remove(pos);
+        } else if self.selected.len() < DIARY_PROMPT_MAX {
+            self.selected.push(index);
+        }
+        self.update_text();
+    }
+
+    pub fn input_char(&mut self, c: char) {
+        if self.typed.chars().count() < DIARY_TEXT_MAX_CHARS {
+            self.typed.push(c);
+            self.update_text();
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.typed.pop();
+        self.update_text();
+    }
+
+    ///
+    /// 選んだ書き出しと書き足した文から、今日の日記を作る
+    ///
+    pub fn compose_entry(&self) -> DiaryEntry {
+        let prompts: Vec<DiaryPrompt> = self
+            .selected
+            .iter()
+            .map(|index| self.prompts[*index].clone())
+            .collect();
+
+        DiaryEntry::compose(self.date, &prompts, &self.typed)
+    }
+
+    ///
+    /// 何も選ばず、何も書いていなければ書いたことにしない
+    ///
+    pub fn is_blank(&self) -> bool {
+        self.selected.is_empty() && self.typed.is_empty()
+    }
+
+    pub fn click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+    ) -> DiaryAction {
+        let rpoint = self.canvas.relative_point(point);
+
+        if self.write_text.contains(ctx.context, rpoint) {
+            return DiaryAction::Write;
+        } else if self.skip_text.contains(ctx.context, rpoint) {
+            return DiaryAction::Skip;
+        }
+
+        let clicked = self
+            .prompt_text
+            .iter()
+            .position(|text| text.contains(ctx.context, rpoint));
+
+        if let Some(index) = clicked {
+            self.toggle_prompt(index);
+        }
+
+        DiaryAction::Keep
+    }
+}
+
+impl DrawableComponent for DiaryWindow {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.header_text.draw(ctx)?;
+            for text in self.prompt_text.iter_mut() {
+                text.draw(ctx)?;
+            }
+            self.typed_text.draw(ctx)?;
+            self.write_text.draw(ctx)?;
+            self.skip_text.draw(ctx)?;
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide()
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth)
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
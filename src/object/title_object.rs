@@ -24,7 +24,10 @@ use crate::{
     set_table_frame_cell_center,
 };
 use crate::{
-    core::{GameMode, WINDOW_SIZE_X, WINDOW_SIZE_Y},
+    core::first_run::{FirstRunStep, FirstRunWizard},
+    core::preferences::{ControlScheme, Locale},
+    core::shortcut_palette::PaletteKey,
+    core::{preferences, quicksave::AutosaveFrequency, GameMode, WINDOW_SIZE_X, WINDOW_SIZE_Y},
     flush_delay_event, flush_delay_event_and_redraw_check,
     object::util_object::{CheckBox, SeekBar, SelectButton, TextButtonTexture},
     scene::DelayEventList,
@@ -36,11 +39,13 @@ use super::{
     util_object::{FrameData, FramedButton, TableFrame},
     DarkEffectPanel,
 };
+use crate::log_warn;
 
 extern crate reqwest;
 
 #[derive(Clone, Copy)]
 pub enum TitleBuiltinCommand {
+    Continue,
     Exit,
 }
 
@@ -48,6 +53,7 @@ impl FromStr for TitleBuiltinCommand {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, ()> {
         match s {
+            "continue" => Ok(TitleBuiltinCommand::Continue),
             "exit" => Ok(TitleBuiltinCommand::Exit),
             _ => Err(()),
         }
@@ -390,6 +396,10 @@ type DynamicTitleSoundPlayer = MovableWrap<TitleSoundPlayer>;
 pub enum GameConfigElement {
     BGMVolume,
     SEVolume,
+    IdleTimeout,
+    UIScale,
+    VoiceBlipVolume,
+    AutosaveFrequency,
 }
 
 struct TemporaryConfigData {
@@ -397,6 +407,14 @@ struct TemporaryConfigData {
     se_volume: f32,
     pause_when_inactive: bool,
     fullscreen_mode: bool,
+    idle_timeout_minute: u32,
+    ui_scale: f32,
+    reduce_motion: bool,
+    voice_blip_volume: f32,
+    mute_voice_blips: bool,
+    content_warnings: bool,
+    kosuzu_comments: bool,
+    autosave_frequency: AutosaveFrequency,
 }
 
 impl TemporaryConfigData {
@@ -406,6 +424,14 @@ impl TemporaryConfigData {
             se_volume: ctx.config.get_se_volume(),
             pause_when_inactive: ctx.config.is_pause_when_inactive(),
 	    fullscreen_mode: ctx.config.is_fullscreen_mode_configed(),
+            idle_timeout_minute: ctx.config.get_idle_timeout_minute(),
+            ui_scale: ctx.preferences.get_ui_scale(),
+            reduce_motion: ctx.preferences.is_reduce_motion(),
+            voice_blip_volume: ctx.preferences.get_voice_blip_volume(),
+            mute_voice_blips: ctx.preferences.is_voice_blip_muted(),
+            content_warnings: ctx.preferences.is_content_warning_enabled(),
+            kosuzu_comments: ctx.preferences.is_kosuzu_comment_enabled(),
+            autosave_frequency: ctx.preferences.get_autosave_frequency(),
         }
     }
 }
@@ -418,10 +444,19 @@ pub struct ConfigPanel {
     header_text: UniText,
     bgm_volume_bar: SeekBar,
     se_volume_bar: SeekBar,
+    idle_timeout_bar: SeekBar,
+    ui_scale_bar: SeekBar,
+    voice_blip_volume_bar: SeekBar,
+    autosave_frequency_bar: SeekBar,
     checkbox: CheckBox,
     fullscreen_checkbox: CheckBox,
+    reduce_motion_checkbox: CheckBox,
+    mute_voice_blips_checkbox: CheckBox,
+    content_warnings_checkbox: CheckBox,
+    kosuzu_comments_checkbox: CheckBox,
     apply_button: SelectButton,
     cancel_button: SelectButton,
+    first_run_button: SelectButton,
     original_config_data: TemporaryConfigData,
 }
 
@@ -462,10 +497,17 @@ impl ConfigPanel {
             ggraphics::Color::from_rgba_u32(0xbbbbbbff),
         );
 
-        for (s, p) in vec![("BGM音量", 180.0), ("SE音量", 280.0)] {
+        for (s, p) in vec![
+            ("BGM音量", numeric::Point2f::new(200.0, 180.0)),
+            ("SE音量", numeric::Point2f::new(200.0, 280.0)),
+            ("無操作で休憩確認", numeric::Point2f::new(750.0, 180.0)),
+            ("画面の大きさ(再起動後)", numeric::Point2f::new(750.0, 280.0)),
+            ("声の音量", numeric::Point2f::new(200.0, 500.0)),
+            ("自動保存", numeric::Point2f::new(200.0, 600.0)),
+        ] {
             let text = UniText::new(
                 s.to_string(),
-                numeric::Point2f::new(200.0, p),
+                p,
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
                 0,
@@ -501,6 +543,57 @@ impl ConfigPanel {
             ),
         );
 
+        sb_dynamic_text.insert(
+            GameConfigElement::IdleTimeout,
+            UniText::new(
+                Self::idle_timeout_text(ctx.config.get_idle_timeout_minute()),
+                numeric::Point2f::new(1050.0, 180.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                hrzn_text_font_info.clone(),
+            ),
+        );
+
+        sb_dynamic_text.insert(
+            GameConfigElement::UIScale,
+            UniText::new(
+                format!("{}%", (ctx.preferences.get_ui_scale() * 100.0).round()),
+                numeric::Point2f::new(1100.0, 280.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                hrzn_text_font_info.clone(),
+            ),
+        );
+
+        sb_dynamic_text.insert(
+            GameConfigElement::VoiceBlipVolume,
+            UniText::new(
+                format!(
+                    "{}%",
+                    (ctx.preferences.get_voice_blip_volume() * 100.0).round()
+                ),
+                numeric::Point2f::new(400.0, 500.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                hrzn_text_font_info.clone(),
+            ),
+        );
+
+        sb_dynamic_text.insert(
+            GameConfigElement::AutosaveFrequency,
+            UniText::new(
+                ctx.preferences.get_autosave_frequency().label().to_string(),
+                numeric::Point2f::new(400.0, 600.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                hrzn_text_font_info.clone(),
+            ),
+        );
+
         let text_texture = Box::new(TextButtonTexture::new(
             ctx,
             numeric::Point2f::new(0.0, 0.0),
@@ -533,6 +626,22 @@ impl ConfigPanel {
             text_texture,
         );
 
+        let text_texture = Box::new(TextButtonTexture::new(
+            ctx,
+            numeric::Point2f::new(0.0, 0.0),
+            "初期設定".to_string(),
+            hrzn_text_font_info.clone(),
+            8.0,
+            ggraphics::Color::from_rgba_u32(0x362d33ff),
+            0,
+        ));
+
+        let first_run_button = SelectButton::new(
+            ctx,
+            numeric::Rect::new(1050.0, 600.0, 150.0, 50.0),
+            text_texture,
+        );
+
         let pause_text = UniText::new(
             "店番中の非アクティブ時にポーズ".to_string(),
             numeric::Point2f::new(200.0, 400.0),
@@ -581,6 +690,102 @@ impl ConfigPanel {
             0,
         );
 
+        let reduce_motion_text = UniText::new(
+            "動きを減らす".to_string(),
+            numeric::Point2f::new(1050.0, 400.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            hrzn_text_font_info.clone(),
+        );
+        hrzn_text_list.push(reduce_motion_text);
+        let choice_box_texture = Box::new(UniTexture::new(
+            ctx.ref_texture(TextureID::CheckCircle),
+            numeric::Point2f::new(1050.0, 440.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        ));
+        let reduce_motion_check = CheckBox::new(
+            ctx,
+            numeric::Rect::new(1050.0, 440.0, 50.0, 50.0),
+            choice_box_texture,
+            ctx.preferences.is_reduce_motion(),
+            0,
+        );
+
+        let mute_voice_blips_text = UniText::new(
+            "声を消す".to_string(),
+            numeric::Point2f::new(750.0, 500.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            hrzn_text_font_info.clone(),
+        );
+        hrzn_text_list.push(mute_voice_blips_text);
+        let choice_box_texture = Box::new(UniTexture::new(
+            ctx.ref_texture(TextureID::CheckCircle),
+            numeric::Point2f::new(750.0, 540.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        ));
+        let mute_voice_blips_check = CheckBox::new(
+            ctx,
+            numeric::Rect::new(750.0, 540.0, 50.0, 50.0),
+            choice_box_texture,
+            ctx.preferences.is_voice_blip_muted(),
+            0,
+        );
+
+        let content_warnings_text = UniText::new(
+            "注意書きを出す".to_string(),
+            numeric::Point2f::new(1050.0, 500.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            hrzn_text_font_info.clone(),
+        );
+        hrzn_text_list.push(content_warnings_text);
+        let choice_box_texture = Box::new(UniTexture::new(
+            ctx.ref_texture(TextureID::CheckCircle),
+            numeric::Point2f::new(1050.0, 540.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        ));
+        let content_warnings_check = CheckBox::new(
+            ctx,
+            numeric::Rect::new(1050.0, 540.0, 50.0, 50.0),
+            choice_box_texture,
+            ctx.preferences.is_content_warning_enabled(),
+            0,
+        );
+
+        let kosuzu_comments_text = UniText::new(
+            "独り言".to_string(),
+            numeric::Point2f::new(1260.0, 500.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            hrzn_text_font_info.clone(),
+        );
+        hrzn_text_list.push(kosuzu_comments_text);
+        let choice_box_texture = Box::new(UniTexture::new(
+            ctx.ref_texture(TextureID::CheckCircle),
+            numeric::Point2f::new(1260.0, 540.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        ));
+        let kosuzu_comments_check = CheckBox::new(
+            ctx,
+            numeric::Rect::new(1260.0, 540.0, 50.0, 50.0),
+            choice_box_texture,
+            ctx.preferences.is_kosuzu_comment_enabled(),
+            0,
+        );
+
         ConfigPanel {
             header_text: header_text,
             sb_dynamic_text: sb_dynamic_text,
@@ -610,17 +815,80 @@ impl ConfigPanel {
                 ctx.config.get_se_volume() * 100.0,
                 0,
             ),
+            idle_timeout_bar: SeekBar::new(
+                ctx,
+                numeric::Rect::new(750.0, 210.0, 450.0, 40.0),
+                10.0,
+                30.0,
+                0.0,
+                ctx.config.get_idle_timeout_minute() as f32,
+                0,
+            ),
+            ui_scale_bar: SeekBar::new(
+                ctx,
+                numeric::Rect::new(750.0, 310.0, 450.0, 40.0),
+                10.0,
+                preferences::UI_SCALE_MAX * 100.0,
+                preferences::UI_SCALE_MIN * 100.0,
+                ctx.preferences.get_ui_scale() * 100.0,
+                0,
+            ),
+            voice_blip_volume_bar: SeekBar::new(
+                ctx,
+                numeric::Rect::new(200.0, 530.0, 450.0, 40.0),
+                10.0,
+                100.0,
+                0.0,
+                ctx.preferences.get_voice_blip_volume() * 100.0,
+                0,
+            ),
+            autosave_frequency_bar: SeekBar::new(
+                ctx,
+                numeric::Rect::new(200.0, 630.0, 400.0, 40.0),
+                10.0,
+                (AutosaveFrequency::ALL.len() - 1) as f32,
+                0.0,
+                ctx.preferences.get_autosave_frequency().to_index() as f32,
+                0,
+            ),
             apply_button: apply_button,
             cancel_button: cancel_button,
+            first_run_button: first_run_button,
             original_config_data: TemporaryConfigData::new(ctx),
             checkbox: check_box,
 	    fullscreen_checkbox: fullscreen_check,
+            reduce_motion_checkbox: reduce_motion_check,
+            mute_voice_blips_checkbox: mute_voice_blips_check,
+            content_warnings_checkbox: content_warnings_check,
+            kosuzu_comments_checkbox: kosuzu_comments_check,
+        }
+    }
+
+    fn idle_timeout_text(minute: u32) -> String {
+        if minute == 0 {
+            "無効".to_string()
+        } else {
+            format!("{}分", minute)
         }
     }
 
     fn update_seek_bar_value(&mut self) {
         let bgm_volume = self.bgm_volume_bar.get_current_value() as i32;
         let se_volume = self.se_volume_bar.get_current_value() as i32;
+        let idle_timeout = self.idle_timeout_bar.get_current_value() as u32;
+        let ui_scale = self.ui_scale_bar.get_current_value().round();
+        let voice_blip_volume = self.voice_blip_volume_bar.get_current_value() as i32;
+        let autosave_frequency = self.selected_autosave_frequency();
+
+        self.sb_dynamic_text
+            .get_mut(&GameConfigElement::UIScale)
+            .unwrap()
+            .replace_text(format!("{}%", ui_scale));
+
+        self.sb_dynamic_text
+            .get_mut(&GameConfigElement::IdleTimeout)
+            .unwrap()
+            .replace_text(Self::idle_timeout_text(idle_timeout));
 
         self.sb_dynamic_text
             .get_mut(&GameConfigElement::BGMVolume)
@@ -630,21 +898,72 @@ impl ConfigPanel {
             .get_mut(&GameConfigElement::SEVolume)
             .unwrap()
             .replace_text(format!("{}%", se_volume));
+        self.sb_dynamic_text
+            .get_mut(&GameConfigElement::VoiceBlipVolume)
+            .unwrap()
+            .replace_text(format!("{}%", voice_blip_volume));
+        self.sb_dynamic_text
+            .get_mut(&GameConfigElement::AutosaveFrequency)
+            .unwrap()
+            .replace_text(autosave_frequency.label().to_string());
+    }
+
+    fn selected_autosave_frequency(&self) -> AutosaveFrequency {
+        AutosaveFrequency::from_index(
+            self.autosave_frequency_bar.get_current_value().round() as usize
+        )
     }
 
-    fn recover_original_config<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+    ///
+    /// 適用していない変更を捨て、開いたときの設定に戻す
+    ///
+    pub fn recover_original_config<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         let original_bgm = self.original_config_data.bgm_volume * 100.0;
         let original_se = self.original_config_data.se_volume * 100.0;
         let original_pause = self.original_config_data.pause_when_inactive;
 	let original_fullscreen_config = self.original_config_data.fullscreen_mode;
+        let original_idle_timeout = self.original_config_data.idle_timeout_minute;
+        let original_ui_scale = self.original_config_data.ui_scale;
+        let original_reduce_motion = self.original_config_data.reduce_motion;
+        let original_voice_blip = self.original_config_data.voice_blip_volume;
+        let original_mute_voice_blips = self.original_config_data.mute_voice_blips;
+        let original_content_warnings = self.original_config_data.content_warnings;
+        let original_kosuzu_comments = self.original_config_data.kosuzu_comments;
+        let original_autosave_frequency = self.original_config_data.autosave_frequency;
 
         ctx.change_bgm_volume(original_bgm);
         ctx.change_se_volume(original_se);
         ctx.config.set_pause_when_inactive(original_pause);
 	ctx.config.set_fullscreen_mode_config(original_fullscreen_config);
+        ctx.config.set_idle_timeout_minute(original_idle_timeout);
+        ctx.preferences.set_ui_scale(original_ui_scale);
+        ctx.preferences.set_reduce_motion(original_reduce_motion);
+        ctx.preferences.set_voice_blip_volume(original_voice_blip);
+        ctx.preferences
+            .set_voice_blip_muted(original_mute_voice_blips);
+        ctx.preferences
+            .set_content_warning_enabled(original_content_warnings);
+        ctx.preferences
+            .set_kosuzu_comment_enabled(original_kosuzu_comments);
+        ctx.preferences
+            .set_autosave_frequency(original_autosave_frequency);
 
         self.bgm_volume_bar.set_value(ctx, original_bgm);
         self.se_volume_bar.set_value(ctx, original_se);
+        self.idle_timeout_bar
+            .set_value(ctx, original_idle_timeout as f32);
+        self.ui_scale_bar.set_value(ctx, original_ui_scale * 100.0);
+        self.reduce_motion_checkbox.try_check(original_reduce_motion);
+        self.voice_blip_volume_bar
+            .set_value(ctx, original_voice_blip * 100.0);
+        self.mute_voice_blips_checkbox
+            .try_check(original_mute_voice_blips);
+        self.content_warnings_checkbox
+            .try_check(original_content_warnings);
+        self.kosuzu_comments_checkbox
+            .try_check(original_kosuzu_comments);
+        self.autosave_frequency_bar
+            .set_value(ctx, original_autosave_frequency.to_index() as f32);
         self.checkbox.try_check(original_pause);
 	self.fullscreen_checkbox.try_check(original_fullscreen_config);
 
@@ -657,7 +976,7 @@ impl ConfigPanel {
 	    }
 	) {
 	    Ok(_) => (),
-	    Err(e) => eprintln!("{}", e),
+	    Err(e) => log_warn!("{}", e),
 	}
     }
 
@@ -678,6 +997,10 @@ impl ConfigPanel {
 
                 self.bgm_volume_bar.start_dragging_check(ctx, rpoint);
                 self.se_volume_bar.start_dragging_check(ctx, rpoint);
+                self.idle_timeout_bar.start_dragging_check(ctx, rpoint);
+                self.ui_scale_bar.start_dragging_check(ctx, rpoint);
+                self.voice_blip_volume_bar.start_dragging_check(ctx, rpoint);
+                self.autosave_frequency_bar.start_dragging_check(ctx, rpoint);
             }
             _ => (),
         }
@@ -698,7 +1021,7 @@ impl ConfigPanel {
 		}
 	    ) {
 		Ok(_) => (),
-		Err(e) => eprintln!("{}", e),
+		Err(e) => log_warn!("{}", e),
 	    }
 	}
     }
@@ -711,9 +1034,17 @@ impl ConfigPanel {
     ) -> Option<TitleContentsEvent> {
         self.bgm_volume_bar.release_handler();
         self.se_volume_bar.release_handler();
+        self.idle_timeout_bar.release_handler();
+        self.ui_scale_bar.release_handler();
+        self.voice_blip_volume_bar.release_handler();
+        self.autosave_frequency_bar.release_handler();
 
         let rpoint = self.canvas.relative_point(point);
         self.checkbox.click_handler(rpoint);
+        self.reduce_motion_checkbox.click_handler(rpoint);
+        self.mute_voice_blips_checkbox.click_handler(rpoint);
+        self.content_warnings_checkbox.click_handler(rpoint);
+        self.kosuzu_comments_checkbox.click_handler(rpoint);
 	self.fullscreen_checkbox_handler(ctx, rpoint);
 
         if self.apply_button.contains(ctx.context, rpoint) {
@@ -722,7 +1053,25 @@ impl ConfigPanel {
             ctx.config
                 .set_pause_when_inactive(self.checkbox.checked_now());
 	    ctx.config.set_fullscreen_mode_config(self.fullscreen_checkbox.checked_now());
+            ctx.config
+                .set_idle_timeout_minute(self.idle_timeout_bar.get_current_value() as u32);
             ctx.config.save_config();
+
+            ctx.preferences
+                .set_ui_scale(self.ui_scale_bar.get_current_value().round() / 100.0);
+            ctx.preferences
+                .set_reduce_motion(self.reduce_motion_checkbox.checked_now());
+            ctx.preferences
+                .set_voice_blip_volume(self.voice_blip_volume_bar.get_current_value() / 100.0);
+            ctx.preferences
+                .set_voice_blip_muted(self.mute_voice_blips_checkbox.checked_now());
+            ctx.preferences
+                .set_content_warning_enabled(self.content_warnings_checkbox.checked_now());
+            ctx.preferences
+                .set_kosuzu_comment_enabled(self.kosuzu_comments_checkbox.checked_now());
+            ctx.preferences
+                .set_autosave_frequency(self.selected_autosave_frequency());
+            ctx.preferences.save();
 	    
             return Some(TitleContentsEvent::NextContents("init-menu".to_string()));
         }
@@ -732,6 +1081,12 @@ impl ConfigPanel {
             return Some(TitleContentsEvent::NextContents("init-menu".to_string()));
         }
 
+        // 適用していない変更は捨てて、初めての設定をやり直す
+        if self.first_run_button.contains(ctx.context, rpoint) {
+            self.recover_original_config(ctx);
+            return Some(TitleContentsEvent::NextContents("first-run".to_string()));
+        }
+
         None
     }
 
@@ -746,6 +1101,10 @@ impl ConfigPanel {
 
         self.bgm_volume_bar.dragging_handler(ctx, rpoint);
         self.se_volume_bar.dragging_handler(ctx, rpoint);
+        self.idle_timeout_bar.dragging_handler(ctx, rpoint);
+        self.ui_scale_bar.dragging_handler(ctx, rpoint);
+        self.voice_blip_volume_bar.dragging_handler(ctx, rpoint);
+        self.autosave_frequency_bar.dragging_handler(ctx, rpoint);
 
         self.update_seek_bar_value();
 
@@ -764,6 +1123,10 @@ impl DrawableComponent for ConfigPanel {
             self.header_text.draw(ctx)?;
             self.bgm_volume_bar.draw(ctx)?;
             self.se_volume_bar.draw(ctx)?;
+            self.idle_timeout_bar.draw(ctx)?;
+            self.ui_scale_bar.draw(ctx)?;
+            self.voice_blip_volume_bar.draw(ctx)?;
+            self.autosave_frequency_bar.draw(ctx)?;
 
             for text in self.hrzn_text_list.iter_mut() {
                 text.draw(ctx)?;
@@ -775,9 +1138,14 @@ impl DrawableComponent for ConfigPanel {
 
             self.apply_button.draw(ctx)?;
             self.cancel_button.draw(ctx)?;
+            self.first_run_button.draw(ctx)?;
 
             self.checkbox.draw(ctx)?;
 	    self.fullscreen_checkbox.draw(ctx)?;
+            self.reduce_motion_checkbox.draw(ctx)?;
+            self.mute_voice_blips_checkbox.draw(ctx)?;
+            self.content_warnings_checkbox.draw(ctx)?;
+            self.kosuzu_comments_checkbox.draw(ctx)?;
 
             sub_screen::pop_screen(ctx);
             self.canvas.draw(ctx).unwrap();
@@ -1094,6 +1462,310 @@ impl DrawableComponent for UpdatePanel {
     }
 }
 
+///
+/// # 初めて起動したときの設定
+/// 言葉、呼び名、操作の型を順に選び、終えると開始画面へ進む。設定画面からやり直せる
+///
+pub struct FirstRunPanel {
+    canvas: sub_screen::SubScreen,
+    background: DarkEffectPanel,
+    header_text: UniText,
+    step_text: UniText,
+    value_text: UniText,
+    language_buttons: Vec<(Locale, SelectButton)>,
+    control_buttons: Vec<(ControlScheme, SelectButton)>,
+    next_button: SelectButton,
+    back_button: SelectButton,
+    wizard: FirstRunWizard,
+}
+
+impl FirstRunPanel {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        pos_rect: numeric::Rect,
+        depth: i8,
+        t: Clock,
+    ) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(32.0, 32.0),
+            ggraphics::Color::from_rgba_u32(0xbbbbbbff),
+        );
+
+        let mut background = DarkEffectPanel::new(
+            ctx.context,
+            numeric::Rect::new(0.0, 0.0, WINDOW_SIZE_X as f32, WINDOW_SIZE_X as f32),
+            t,
+        );
+        background.set_alpha(0.5);
+
+        let hrzn_text_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(29.0, 29.0),
+            ggraphics::Color::from_rgba_u32(0xbbbbbbff),
+        );
+
+        let header_text = UniText::new(
+            "初メテノ設定".to_string(),
+            numeric::Point2f::new(600.0, 80.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info.clone(),
+        );
+
+        let step_text = UniText::new(
+            "".to_string(),
+            numeric::Point2f::new(200.0, 200.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            font_info.clone(),
+        );
+
+        let value_text = UniText::new(
+            "".to_string(),
+            numeric::Point2f::new(200.0, 400.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            hrzn_text_font_info.clone(),
+        );
+
+        let new_button = |ctx: &mut SuzuContext<'a>, text: &str, rect: numeric::Rect| {
+            let text_texture = Box::new(TextButtonTexture::new(
+                ctx,
+                numeric::Point2f::new(0.0, 0.0),
+                text.to_string(),
+                hrzn_text_font_info.clone(),
+                8.0,
+                ggraphics::Color::from_rgba_u32(0x362d33ff),
+                0,
+            ));
+            SelectButton::new(ctx, rect, text_texture)
+        };
+
+        let language_buttons = vec![
+            (
+                Locale::Japanese,
+                new_button(ctx, "日本語", numeric::Rect::new(200.0, 300.0, 150.0, 50.0)),
+            ),
+            (
+                Locale::English,
+                new_button(
+                    ctx,
+                    "English",
+                    numeric::Rect::new(400.0, 300.0, 150.0, 50.0),
+                ),
+            ),
+        ];
+
+        let control_buttons = vec![
+            (
+                ControlScheme::Mouse,
+                new_button(ctx, "マウス", numeric::Rect::new(200.0, 300.0, 150.0, 50.0)),
+            ),
+            (
+                ControlScheme::Keyboard,
+                new_button(
+                    ctx,
+                    "キーボード",
+                    numeric::Rect::new(400.0, 300.0, 200.0, 50.0),
+                ),
+            ),
+        ];
+
+        let next_button = new_button(ctx, "次ヘ", numeric::Rect::new(850.0, 600.0, 100.0, 50.0));
+        let back_button = new_button(ctx, "戻ル", numeric::Rect::new(650.0, 600.0, 100.0, 50.0));
+
+        let mut panel = FirstRunPanel {
+            canvas: sub_screen::SubScreen::new(
+                ctx.context,
+                pos_rect,
+                depth,
+                ggraphics::Color::from_rgba_u32(0),
+            ),
+            background: background,
+            header_text: header_text,
+            step_text: step_text,
+            value_text: value_text,
+            language_buttons: language_buttons,
+            control_buttons: control_buttons,
+            next_button: next_button,
+            back_button: back_button,
+            wizard: FirstRunWizard::new(ctx.preferences),
+        };
+        panel.update_texts();
+
+        panel
+    }
+
+    pub fn get_name(&self) -> String {
+        "first-run".to_string()
+    }
+
+    ///
+    /// 設定画面から開き直したときは、今の設定から選び直す
+    ///
+    pub fn reset<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.wizard = FirstRunWizard::new(ctx.preferences);
+        self.update_texts();
+    }
+
+    pub fn is_editing_nickname(&self) -> bool {
+        self.wizard.is_editing_nickname()
+    }
+
+    fn update_texts(&mut self) {
+        self.step_text
+            .replace_text(self.wizard.get_step().title().to_string());
+
+        let value = match self.wizard.get_step() {
+            FirstRunStep::Language => match self.wizard.get_locale() {
+                Locale::Japanese => "日本語".to_string(),
+                Locale::English => "English".to_string(),
+            },
+            FirstRunStep::Nickname => format!(
+                "{}_  (空ナラ「{}」)",
+                self.wizard.get_nickname(),
+                preferences::DEFAULT_NICKNAME
+            ),
+            FirstRunStep::Controls => match self.wizard.get_control_scheme() {
+                ControlScheme::Mouse => "マウスヲ主ニ使ウ".to_string(),
+                ControlScheme::Keyboard => "Z、Enterデ決定 Xデ取リ消シ".to_string(),
+            },
+        };
+        self.value_text.replace_text(value);
+    }
+
+    fn finish<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> TitleContentsEvent {
+        self.wizard.apply(ctx.preferences);
+        ctx.preferences.save();
+
+        TitleContentsEvent::NextContents("init-menu".to_string())
+    }
+
+    ///
+    /// 呼び名を打っている間のキー入力。打っている最中でなければfalse
+    ///
+    pub fn key_event(&mut self, key: PaletteKey) -> bool {
+        if !self.wizard.is_editing_nickname() {
+            return false;
+        }
+
+        match key {
+            PaletteKey::Char(c) => self.wizard.push_char(c),
+            PaletteKey::Backspace => self.wizard.pop_char(),
+            PaletteKey::Enter => {
+                self.wizard.next();
+            }
+            PaletteKey::Escape => self.wizard.back(),
+            PaletteKey::Toggle | PaletteKey::Up | PaletteKey::Down | PaletteKey::Tab => (),
+        }
+
+        self.update_texts();
+        true
+    }
+
+    pub fn mouse_button_up<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        _t: Clock,
+    ) -> Option<TitleContentsEvent> {
+        let rpoint = self.canvas.relative_point(point);
+
+        match self.wizard.get_step() {
+            FirstRunStep::Language => {
+                for (locale, button) in self.language_buttons.iter() {
+                    if button.contains(ctx.context, rpoint) {
+                        self.wizard.set_locale(*locale);
+                    }
+                }
+            }
+            FirstRunStep::Controls => {
+                for (scheme, button) in self.control_buttons.iter() {
+                    if button.contains(ctx.context, rpoint) {
+                        self.wizard.set_control_scheme(*scheme);
+                    }
+                }
+            }
+            FirstRunStep::Nickname => (),
+        }
+
+        if self.next_button.contains(ctx.context, rpoint) && self.wizard.next() {
+            return Some(self.finish(ctx));
+        }
+
+        if self.back_button.contains(ctx.context, rpoint) {
+            // 設定画面から開き直したときは、最初の手順から戻れば何も変えずに閉じる
+            if self.wizard.get_step() == FirstRunStep::Language && !ctx.preferences.is_first_run() {
+                return Some(TitleContentsEvent::NextContents("config-panel".to_string()));
+            }
+            self.wizard.back();
+        }
+
+        self.update_texts();
+        None
+    }
+}
+
+impl DrawableComponent for FirstRunPanel {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.background.draw(ctx)?;
+
+            self.header_text.draw(ctx)?;
+            self.step_text.draw(ctx)?;
+            self.value_text.draw(ctx)?;
+
+            match self.wizard.get_step() {
+                FirstRunStep::Language => {
+                    for (_, button) in self.language_buttons.iter_mut() {
+                        button.draw(ctx)?;
+                    }
+                }
+                FirstRunStep::Controls => {
+                    for (_, button) in self.control_buttons.iter_mut() {
+                        button.draw(ctx)?;
+                    }
+                }
+                FirstRunStep::Nickname => (),
+            }
+
+            self.next_button.draw(ctx)?;
+            self.back_button.draw(ctx)?;
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
 pub struct Gallery {
     canvas: sub_screen::SubScreen,
     background: DarkEffectPanel,
@@ -1579,7 +2251,7 @@ impl RecordRoom {
             }
 
             let mut number = UniText::new(
-                number_to_jk::number_to_jk(index as u64 + 1),
+                ctx.number_format().ordinal(index),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1597,10 +2269,7 @@ impl RecordRoom {
             );
 
             let mut money = UniText::new(
-                format!(
-                    "{}円",
-                    number_to_jk::number_to_jk(data.get_total_money() as u64)
-                ),
+                ctx.number_format().money(data.get_total_money() as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1644,7 +2313,7 @@ impl RecordRoom {
             }
 
             let mut number = UniText::new(
-                number_to_jk::number_to_jk(index as u64 + 1),
+                ctx.number_format().ordinal(index),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1662,10 +2331,7 @@ impl RecordRoom {
             );
 
             let mut money = UniText::new(
-                format!(
-                    "{}円",
-                    number_to_jk::number_to_jk(data.get_total_money() as u64)
-                ),
+                ctx.number_format().money(data.get_total_money() as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1731,7 +2397,7 @@ impl RecordRoom {
             }
 
             let mut number = UniText::new(
-                number_to_jk::number_to_jk(index as u64 + 1),
+                ctx.number_format().ordinal(index),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1749,10 +2415,7 @@ impl RecordRoom {
             );
 
             let mut money = UniText::new(
-                format!(
-                    "{}円",
-                    number_to_jk::number_to_jk(data.get_total_money() as u64)
-                ),
+                ctx.number_format().money(data.get_total_money() as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1796,7 +2459,7 @@ impl RecordRoom {
             }
 
             let mut number = UniText::new(
-                number_to_jk::number_to_jk(index as u64 + 1),
+                ctx.number_format().ordinal(index),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1814,10 +2477,7 @@ impl RecordRoom {
             );
 
             let mut money = UniText::new(
-                format!(
-                    "{}円",
-                    number_to_jk::number_to_jk(data.get_total_money() as u64)
-                ),
+                ctx.number_format().money(data.get_total_money() as u64),
                 numeric::Point2f::new(0.0, 0.0),
                 numeric::Vector2f::new(1.0, 1.0),
                 0.0,
@@ -1929,6 +2589,7 @@ pub enum TitleContents {
     UpdatePanel(UpdatePanel),
     Gallery(Gallery),
     RecordRoom(RecordRoom),
+    FirstRun(FirstRunPanel),
 }
 
 impl TitleContents {
@@ -1994,6 +2655,12 @@ impl TitleContents {
                 0,
                 t,
             ))),
+            "FirstRunPanel" => Some(TitleContents::FirstRun(FirstRunPanel::new(
+                ctx,
+                numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
+                0,
+                t,
+            ))),
             _ => None,
         }
     }
@@ -2006,6 +2673,7 @@ impl TitleContents {
             TitleContents::UpdatePanel(panel) => panel.get_name(),
             TitleContents::Gallery(gallery) => gallery.get_name(),
             TitleContents::RecordRoom(rr) => rr.get_name(),
+            TitleContents::FirstRun(panel) => panel.get_name(),
         }
     }
 
@@ -2017,6 +2685,7 @@ impl TitleContents {
             TitleContents::UpdatePanel(panel) => panel.flush_delayed_event(ctx, t),
             TitleContents::Gallery(gallery) => gallery.flush_delayed_event(ctx, t),
             TitleContents::RecordRoom(rr) => rr.flush_delayed_event(ctx, t),
+            TitleContents::FirstRun(_) => (),
         }
     }
 
@@ -2028,6 +2697,7 @@ impl TitleContents {
             TitleContents::UpdatePanel(panel) => panel.notify_switched(ctx, t),
             TitleContents::Gallery(_) => (),
             TitleContents::RecordRoom(rr) => rr.reset(ctx),
+            TitleContents::FirstRun(panel) => panel.reset(ctx),
         }
     }
 }
@@ -2041,6 +2711,7 @@ impl DrawableComponent for TitleContents {
             TitleContents::UpdatePanel(panel) => panel.draw(ctx),
             TitleContents::Gallery(gallery) => gallery.draw(ctx),
             TitleContents::RecordRoom(rr) => rr.draw(ctx),
+            TitleContents::FirstRun(panel) => panel.draw(ctx),
         }
     }
 
@@ -2052,6 +2723,7 @@ impl DrawableComponent for TitleContents {
             TitleContents::UpdatePanel(panel) => panel.hide(),
             TitleContents::Gallery(gallery) => gallery.hide(),
             TitleContents::RecordRoom(rr) => rr.hide(),
+            TitleContents::FirstRun(panel) => panel.hide(),
         }
     }
 
@@ -2063,6 +2735,7 @@ impl DrawableComponent for TitleContents {
             TitleContents::UpdatePanel(panel) => panel.appear(),
             TitleContents::Gallery(gallery) => gallery.appear(),
             TitleContents::RecordRoom(rr) => rr.appear(),
+            TitleContents::FirstRun(panel) => panel.appear(),
         }
     }
 
@@ -2074,6 +2747,7 @@ impl DrawableComponent for TitleContents {
             TitleContents::UpdatePanel(panel) => panel.is_visible(),
             TitleContents::Gallery(gallery) => gallery.is_visible(),
             TitleContents::RecordRoom(rr) => rr.is_visible(),
+            TitleContents::FirstRun(panel) => panel.is_visible(),
         }
     }
 
@@ -2085,6 +2759,7 @@ impl DrawableComponent for TitleContents {
             TitleContents::UpdatePanel(panel) => panel.set_drawing_depth(depth),
             TitleContents::Gallery(gallery) => gallery.set_drawing_depth(depth),
             TitleContents::RecordRoom(rr) => rr.set_drawing_depth(depth),
+            TitleContents::FirstRun(panel) => panel.set_drawing_depth(depth),
         }
     }
 
@@ -2096,6 +2771,7 @@ impl DrawableComponent for TitleContents {
             TitleContents::UpdatePanel(panel) => panel.get_drawing_depth(),
             TitleContents::Gallery(gallery) => gallery.get_drawing_depth(),
             TitleContents::RecordRoom(rr) => rr.get_drawing_depth(),
+            TitleContents::FirstRun(panel) => panel.get_drawing_depth(),
         }
     }
 }
@@ -0,0 +1,217 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::shape::MeshShape;
+use torifune::graphics::object::*;
+use torifune::numeric;
+
+///
+/// カーソルを乗せてから説明を出すまでのフレーム数
+///
+pub const TOOLTIP_DELAY: Clock = 30;
+
+///
+/// 説明の文字と、枠との間の余白
+///
+pub const TOOLTIP_PADDING: f32 = 6.0;
+
+///
+/// 説明をカーソルから離して出す距離
+///
+pub const TOOLTIP_CURSOR_OFFSET: f32 = 16.0;
+
+///
+/// # カーソルを乗せている時間を数えるもの
+/// 乗せている物の説明が変わるたびに数え直す
+///
+/// ## since
+/// 今の説明の物に乗せ始めた時刻。次のupdateで決める
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoverTimer {
+    text: Option<String>,
+    since: Option<Clock>,
+    shown: bool,
+}
+
+impl HoverTimer {
+    pub fn new() -> Self {
+        HoverTimer {
+            text: None,
+            since: None,
+            shown: false,
+        }
+    }
+
+    ///
+    /// カーソルの下にある物の説明を渡す。前と違えば数え直す
+    ///
+    /// 出していた説明を引っ込めたときはtrueを返す
+    ///
+    pub fn hover(&mut self, text: Option<String>) -> bool {
+        if self.text == text {
+            return false;
+        }
+
+        let was_shown = self.shown;
+        self.text = text;
+        self.since = None;
+        self.shown = false;
+
+        was_shown
+    }
+
+    ///
+    /// 時刻を進める。説明を出し始めたときはtrueを返す
+    ///
+    pub fn update(&mut self, t: Clock) -> bool {
+        if self.text.is_none() || self.shown {
+            return false;
+        }
+
+        let since = *self.since.get_or_insert(t);
+        if t.saturating_sub(since) >= TOOLTIP_DELAY {
+            self.shown = true;
+            return true;
+        }
+
+        false
+    }
+
+    ///
+    /// 出している説明
+    ///
+    pub fn shown_text(&self) -> Option<&str> {
+        if self.shown {
+            self.text.as_ref().map(|text| text.as_str())
+        } else {
+            None
+        }
+    }
+}
+
+///
+/// # カーソルを暫く乗せた物の説明
+/// 乗せている間は、カーソルの右下に小さな札を出す
+///
+/// ## point
+/// 最後にカーソルがあった点
+///
+pub struct Tooltip {
+    timer: HoverTimer,
+    point: numeric::Point2f,
+    font_info: FontInformation,
+    text: Option<UniText>,
+    drwob_essential: DrawableObjectEssential,
+}
+
+impl Tooltip {
+    pub fn new(font_info: FontInformation, depth: i8) -> Self {
+        Tooltip {
+            timer: HoverTimer::new(),
+            point: numeric::Point2f::new(0.0, 0.0),
+            font_info: font_info,
+            text: None,
+            drwob_essential: DrawableObjectEssential::new(true, depth),
+        }
+    }
+
+    ///
+    /// pointにカーソルがあり、その下の物の説明がtextであることを伝える
+    ///
+    /// 説明を引っ込めたときは、描き直しが要るのでtrueを返す
+    ///
+    pub fn hover(&mut self, text: Option<String>, point: numeric::Point2f) -> bool {
+        self.point = point;
+
+        if self.timer.hover(text) {
+            self.text = None;
+            return true;
+        }
+
+        false
+    }
+
+    ///
+    /// 時刻を進める。説明を出し始めたときは、描き直しが要るのでtrueを返す
+    ///
+    pub fn update(&mut self, t: Clock) -> bool {
+        if !self.timer.update(t) {
+            return false;
+        }
+
+        self.text = self.timer.shown_text().map(|text| {
+            UniText::new(
+                text.to_string(),
+                numeric::Point2f::new(
+                    self.point.x + TOOLTIP_CURSOR_OFFSET + TOOLTIP_PADDING,
+                    self.point.y + TOOLTIP_CURSOR_OFFSET + TOOLTIP_PADDING,
+                ),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                self.font_info.clone(),
+            )
+        });
+
+        true
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.text.is_some()
+    }
+}
+
+impl DrawableComponent for Tooltip {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if !self.is_visible() {
+            return Ok(());
+        }
+
+        if let Some(text) = self.text.as_mut() {
+            let area = text.get_drawing_area(ctx);
+            let frame = numeric::Rect::new(
+                area.x - TOOLTIP_PADDING,
+                area.y - TOOLTIP_PADDING,
+                area.w + (TOOLTIP_PADDING * 2.0),
+                area.h + (TOOLTIP_PADDING * 2.0),
+            );
+
+            let mut builder = ggraphics::MeshBuilder::new();
+            shape::Rectangle::new(
+                frame,
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0x000000c0),
+            )
+            .add_to_builder(&mut builder);
+
+            let mesh = builder.build(ctx)?;
+            ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())?;
+
+            text.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.drwob_essential.visible = false;
+    }
+
+    fn appear(&mut self) {
+        self.drwob_essential.visible = true;
+    }
+
+    fn is_visible(&self) -> bool {
+        self.drwob_essential.visible
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.drwob_essential.drawing_depth = depth;
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.drwob_essential.drawing_depth
+    }
+}
@@ -0,0 +1,165 @@
+use torifune::core::Clock;
+use torifune::numeric;
+
+///
+/// # 補間曲線
+/// 0.0 ~ 1.0の進捗を、0.0 ~ 1.0の補間率へ変換する
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    EaseOutCubic,
+}
+
+impl Easing {
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.max(0.0).min(1.0);
+
+        match self {
+            Easing::Linear => x,
+            Easing::EaseInQuad => x * x,
+            Easing::EaseOutQuad => 1.0 - ((1.0 - x) * (1.0 - x)),
+            Easing::EaseInOutQuad => {
+                if x < 0.5 {
+                    2.0 * x * x
+                } else {
+                    1.0 - ((-2.0 * x + 2.0).powi(2) / 2.0)
+                }
+            }
+            Easing::EaseOutCubic => 1.0 - (1.0 - x).powi(3),
+        }
+    }
+}
+
+///
+/// 補間可能な値
+///
+pub trait Lerp: Copy {
+    fn lerp(from: Self, to: Self, ratio: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(from: Self, to: Self, ratio: f32) -> Self {
+        from + ((to - from) * ratio)
+    }
+}
+
+impl Lerp for numeric::Point2f {
+    fn lerp(from: Self, to: Self, ratio: f32) -> Self {
+        numeric::Point2f::new(
+            f32::lerp(from.x, to.x, ratio),
+            f32::lerp(from.y, to.y, ratio),
+        )
+    }
+}
+
+impl Lerp for numeric::Vector2f {
+    fn lerp(from: Self, to: Self, ratio: f32) -> Self {
+        numeric::Vector2f::new(
+            f32::lerp(from.x, to.x, ratio),
+            f32::lerp(from.y, to.y, ratio),
+        )
+    }
+}
+
+///
+/// # 時間経過で値を補間する
+///
+/// ## start
+/// 補間を開始する時間, 未来を指定することもできる
+///
+/// ## duration
+/// 補間にかける時間。0の場合は開始時点で終了値になる
+///
+#[derive(Debug, Clone)]
+pub struct Tween<T: Lerp> {
+    from: T,
+    to: T,
+    start: Clock,
+    duration: Clock,
+    easing: Easing,
+    finished: bool,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: Clock, easing: Easing, start: Clock) -> Self {
+        Tween {
+            from: from,
+            to: to,
+            start: start,
+            duration: duration,
+            easing: easing,
+            finished: false,
+        }
+    }
+
+    ///
+    /// 時刻tにおける進捗（0.0 ~ 1.0）
+    ///
+    pub fn progress(&self, t: Clock) -> f32 {
+        if t < self.start {
+            return 0.0;
+        }
+
+        if self.duration == 0 {
+            return 1.0;
+        }
+
+        let elapsed = t - self.start;
+        if elapsed >= self.duration {
+            1.0
+        } else {
+            elapsed as f32 / self.duration as f32
+        }
+    }
+
+    pub fn value_at(&self, t: Clock) -> T {
+        let ratio = self.progress(t);
+
+        // 浮動小数の誤差で終了値からずれないようにする
+        if ratio >= 1.0 {
+            self.to
+        } else {
+            T::lerp(self.from, self.to, self.easing.apply(ratio))
+        }
+    }
+
+    pub fn has_started(&self, t: Clock) -> bool {
+        self.start <= t
+    }
+
+    pub fn is_finished_at(&self, t: Clock) -> bool {
+        t >= self.start && t - self.start >= self.duration
+    }
+
+    ///
+    /// 時刻tの値を返し、終了していれば終了状態を記録する
+    ///
+    pub fn update(&mut self, t: Clock) -> T {
+        if self.is_finished_at(t) {
+            self.finished = true;
+        }
+        self.value_at(t)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn get_end_value(&self) -> T {
+        self.to
+    }
+}
+
+pub fn tween<T: Lerp>(
+    value_from: T,
+    value_to: T,
+    duration: Clock,
+    easing: Easing,
+    start: Clock,
+) -> Tween<T> {
+    Tween::new(value_from, value_to, duration, easing, start)
+}
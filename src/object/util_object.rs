@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 
 use ggez::graphics as ggraphics;
 
@@ -14,9 +15,11 @@ use torifune::impl_texture_object_for_wrapped;
 use torifune::numeric;
 use torifune::{mintp, mintp_new};
 
+use crate::core::preferences::TextOrientation;
+use crate::core::shortcut_palette::{PaletteKey, SHORTCUT_PALETTE_VISIBLE_ROWS};
 use crate::core::*;
 
-use super::DarkEffectPanel;
+use super::cell_alignment::CellAlignment;
 
 extern crate mint;
 
@@ -131,6 +134,23 @@ impl TableFrame {
         )
     }
 
+    ///
+    /// 罫線の内側の、文字を書ける範囲を返す
+    ///
+    pub fn get_cell_rect(
+        &self,
+        grid_pos: numeric::Vector2u,
+        offset: numeric::Point2f,
+    ) -> numeric::Rect {
+        let left_top = self.get_grid_topleft(grid_pos, numeric::Vector2f::new(offset.x, offset.y));
+        numeric::Rect::new(
+            left_top.x,
+            left_top.y,
+            self.frame_data.get_row_size_at(grid_pos.x as usize),
+            self.frame_data.get_col_size_at(grid_pos.y as usize),
+        )
+    }
+
     ///
     /// 線の幅を含めてTableFrameの高さを返す
     ///
@@ -533,6 +553,311 @@ macro_rules! set_table_frame_cell_center {
     };
 }
 
+///
+/// # 表の枠に文字を置く
+/// alignmentの揃え方で置き、罫線に掛かる文字は縮める
+///
+pub fn align_in_table_cell<T: TextureObject>(
+    ctx: &mut ggez::Context,
+    table_frame: &TableFrame,
+    alignment: &CellAlignment,
+    grid_pos: numeric::Vector2u,
+    obj: &mut T,
+) {
+    // 前に縮めていても、元の大きさで測り直す
+    obj.set_scale(numeric::Vector2f::new(1.0, 1.0));
+    let placement = alignment.place(
+        table_frame.get_cell_rect(grid_pos, table_frame.get_position()),
+        obj.get_drawing_size(ctx),
+    );
+
+    obj.set_scale(numeric::Vector2f::new(
+        placement.fit_ratio,
+        placement.fit_ratio,
+    ));
+    obj.set_position(placement.position);
+}
+
+///
+/// 題目の文字に対する、振り仮名の文字の大きさ
+///
+pub const FURIGANA_FONT_RATIO: f32 = 0.3;
+
+///
+/// # 題目の振り仮名
+/// 縦書きの題目の右に、小さな文字で読みを添える。題目の丈より長い読みは縮める
+///
+/// 題目と振り仮名を合わせた幅が元の題目と同じ所に収まるよう、題目を左に寄せる
+///
+pub fn new_furigana_vtext(
+    ctx: &mut ggez::Context,
+    title_vtext: &mut VerticalText,
+    reading: &str,
+    font_info: FontInformation,
+) -> VerticalText {
+    let title_area = title_vtext.get_drawing_area(ctx);
+    let mut furigana = VerticalText::new(
+        reading.to_string(),
+        numeric::Point2f::new(title_area.x + title_area.w, title_area.y),
+        numeric::Vector2f::new(1.0, 1.0),
+        0.0,
+        title_vtext.get_drawing_depth(),
+        font_info,
+    );
+
+    let furigana_size = furigana.get_drawing_size(ctx);
+    if furigana_size.y > title_area.h {
+        let ratio = title_area.h / furigana_size.y;
+        furigana.set_scale(numeric::Vector2f::new(ratio, ratio));
+    }
+
+    let furigana_size = furigana.get_drawing_size(ctx);
+    let shift = (furigana_size.x + 1.0) / 2.0;
+    title_vtext.move_diff(numeric::Vector2f::new(-shift, 0.0));
+
+    furigana.set_position(numeric::Point2f::new(
+        title_area.x + title_area.w + 1.0 - shift,
+        title_area.y + ((title_area.h - furigana_size.y) / 2.0),
+    ));
+
+    furigana
+}
+
+///
+/// # 向きを切り替えられる文字列
+/// 言語設定に合わせて、縦書き(VerticalText)か横書き(UniText)で描画する
+///
+pub enum OrientedText {
+    Vertical(VerticalText),
+    Horizontal(UniText),
+}
+
+impl OrientedText {
+    pub fn new(
+        orientation: TextOrientation,
+        text: String,
+        position: numeric::Point2f,
+        scale: numeric::Vector2f,
+        rotation: f32,
+        drawing_depth: i8,
+        font_info: FontInformation,
+    ) -> Self {
+        match orientation {
+            TextOrientation::Vertical => OrientedText::Vertical(VerticalText::new(
+                text,
+                position,
+                scale,
+                rotation,
+                drawing_depth,
+                font_info,
+            )),
+            TextOrientation::Horizontal => OrientedText::Horizontal(UniText::new(
+                text,
+                position,
+                scale,
+                rotation,
+                drawing_depth,
+                font_info,
+            )),
+        }
+    }
+
+    pub fn get_orientation(&self) -> TextOrientation {
+        match self {
+            OrientedText::Vertical(_) => TextOrientation::Vertical,
+            OrientedText::Horizontal(_) => TextOrientation::Horizontal,
+        }
+    }
+
+    pub fn replace_text(&mut self, text: String) {
+        match self {
+            OrientedText::Vertical(vtext) => vtext.replace_text(text),
+            OrientedText::Horizontal(text_obj) => text_obj.replace_text(text),
+        }
+    }
+}
+
+impl DrawableComponent for OrientedText {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        match self {
+            OrientedText::Vertical(text) => text.draw(ctx),
+            OrientedText::Horizontal(text) => text.draw(ctx),
+        }
+    }
+
+    fn hide(&mut self) {
+        match self {
+            OrientedText::Vertical(text) => text.hide(),
+            OrientedText::Horizontal(text) => text.hide(),
+        };
+    }
+
+    fn appear(&mut self) {
+        match self {
+            OrientedText::Vertical(text) => text.appear(),
+            OrientedText::Horizontal(text) => text.appear(),
+        };
+    }
+
+    fn is_visible(&self) -> bool {
+        match self {
+            OrientedText::Vertical(text) => text.is_visible(),
+            OrientedText::Horizontal(text) => text.is_visible(),
+        }
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        match self {
+            OrientedText::Vertical(text) => text.set_drawing_depth(depth),
+            OrientedText::Horizontal(text) => text.set_drawing_depth(depth),
+        };
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        match self {
+            OrientedText::Vertical(text) => text.get_drawing_depth(),
+            OrientedText::Horizontal(text) => text.get_drawing_depth(),
+        }
+    }
+}
+
+impl DrawableObject for OrientedText {
+    fn set_position(&mut self, pos: numeric::Point2f) {
+        match self {
+            OrientedText::Vertical(text) => text.set_position(pos),
+            OrientedText::Horizontal(text) => text.set_position(pos),
+        };
+    }
+
+    fn get_position(&self) -> numeric::Point2f {
+        match self {
+            OrientedText::Vertical(text) => text.get_position(),
+            OrientedText::Horizontal(text) => text.get_position(),
+        }
+    }
+
+    fn move_diff(&mut self, offset: numeric::Vector2f) {
+        match self {
+            OrientedText::Vertical(text) => text.move_diff(offset),
+            OrientedText::Horizontal(text) => text.move_diff(offset),
+        };
+    }
+}
+
+impl TextureObject for OrientedText {
+    fn set_scale(&mut self, scale: numeric::Vector2f) {
+        match self {
+            OrientedText::Vertical(text) => text.set_scale(scale),
+            OrientedText::Horizontal(text) => text.set_scale(scale),
+        };
+    }
+
+    fn get_scale(&self) -> numeric::Vector2f {
+        match self {
+            OrientedText::Vertical(text) => text.get_scale(),
+            OrientedText::Horizontal(text) => text.get_scale(),
+        }
+    }
+
+    fn set_rotation(&mut self, rad: f32) {
+        match self {
+            OrientedText::Vertical(text) => text.set_rotation(rad),
+            OrientedText::Horizontal(text) => text.set_rotation(rad),
+        };
+    }
+
+    fn get_rotation(&self) -> f32 {
+        match self {
+            OrientedText::Vertical(text) => text.get_rotation(),
+            OrientedText::Horizontal(text) => text.get_rotation(),
+        }
+    }
+
+    fn set_crop(&mut self, crop: ggraphics::Rect) {
+        match self {
+            OrientedText::Vertical(text) => text.set_crop(crop),
+            OrientedText::Horizontal(text) => text.set_crop(crop),
+        };
+    }
+
+    fn get_crop(&self) -> ggraphics::Rect {
+        match self {
+            OrientedText::Vertical(text) => text.get_crop(),
+            OrientedText::Horizontal(text) => text.get_crop(),
+        }
+    }
+
+    fn set_drawing_color(&mut self, color: ggraphics::Color) {
+        match self {
+            OrientedText::Vertical(text) => text.set_drawing_color(color),
+            OrientedText::Horizontal(text) => text.set_drawing_color(color),
+        };
+    }
+
+    fn get_drawing_color(&self) -> ggraphics::Color {
+        match self {
+            OrientedText::Vertical(text) => text.get_drawing_color(),
+            OrientedText::Horizontal(text) => text.get_drawing_color(),
+        }
+    }
+
+    fn set_alpha(&mut self, alpha: f32) {
+        match self {
+            OrientedText::Vertical(text) => text.set_alpha(alpha),
+            OrientedText::Horizontal(text) => text.set_alpha(alpha),
+        };
+    }
+
+    fn get_alpha(&self) -> f32 {
+        match self {
+            OrientedText::Vertical(text) => text.get_alpha(),
+            OrientedText::Horizontal(text) => text.get_alpha(),
+        }
+    }
+
+    fn set_transform_offset(&mut self, offset: numeric::Point2f) {
+        match self {
+            OrientedText::Vertical(text) => text.set_transform_offset(offset),
+            OrientedText::Horizontal(text) => text.set_transform_offset(offset),
+        };
+    }
+
+    fn get_transform_offset(&self) -> numeric::Point2f {
+        match self {
+            OrientedText::Vertical(text) => text.get_transform_offset(),
+            OrientedText::Horizontal(text) => text.get_transform_offset(),
+        }
+    }
+
+    fn get_texture_size(&self, ctx: &mut ggez::Context) -> numeric::Vector2f {
+        match self {
+            OrientedText::Vertical(text) => text.get_texture_size(ctx),
+            OrientedText::Horizontal(text) => text.get_texture_size(ctx),
+        }
+    }
+
+    fn replace_texture(&mut self, texture: ggraphics::Image) {
+        match self {
+            OrientedText::Vertical(text) => text.replace_texture(texture),
+            OrientedText::Horizontal(text) => text.replace_texture(texture),
+        };
+    }
+
+    fn set_color(&mut self, color: ggraphics::Color) {
+        match self {
+            OrientedText::Vertical(text) => text.set_color(color),
+            OrientedText::Horizontal(text) => text.set_color(color),
+        };
+    }
+
+    fn get_color(&mut self) -> ggraphics::Color {
+        match self {
+            OrientedText::Vertical(text) => text.get_color(),
+            OrientedText::Horizontal(text) => text.get_color(),
+        }
+    }
+}
+
 ///
 /// # ボタンみたいなものを表示する構造体
 ///
@@ -1265,6 +1590,10 @@ impl SeqTexture {
         self.index = 0;
     }
 
+    pub fn frame_index(&self) -> usize {
+        self.index % self.textures.len()
+    }
+
     pub fn current_frame(&self) -> ggraphics::Image {
         self.textures[self.index % self.textures.len()].clone()
     }
@@ -1285,21 +1614,25 @@ impl SeqTexture {
     }
 }
 
-pub struct TextureAnimation {
-    textures: HashMap<ObjectDirection, SeqTexture>,
-    current_mode: ObjectDirection,
+///
+/// # モードごとの連番テクスチャを切り替えて動かすもの
+/// モードは既定では向き。客の顔付きなど、Hashできるものなら何でも使える
+///
+pub struct TextureAnimation<M = ObjectDirection> {
+    textures: HashMap<M, SeqTexture>,
+    current_mode: M,
     object: SimpleObject,
     animation_type: AnimationType,
-    next_mode: ObjectDirection,
+    next_mode: M,
     frame_speed: Clock,
 }
 
-impl TextureAnimation {
+impl<M: Copy + Eq + Hash> TextureAnimation<M> {
     pub fn new(
         obj: SimpleObject,
-        mode_order: Vec<ObjectDirection>,
+        mode_order: Vec<M>,
         textures: Vec<Vec<ggraphics::Image>>,
-        mode: ObjectDirection,
+        mode: M,
         frame_speed: Clock,
     ) -> Self {
         let mut texture_table = HashMap::new();
@@ -1329,12 +1662,7 @@ impl TextureAnimation {
         &mut self.object
     }
 
-    pub fn change_mode(
-        &mut self,
-        mode: ObjectDirection,
-        animation_type: AnimationType,
-        next_mode: ObjectDirection,
-    ) {
+    pub fn change_mode(&mut self, mode: M, animation_type: AnimationType, next_mode: M) {
         if self.current_mode != mode {
             self.current_mode = mode;
             self.next_mode = next_mode;
@@ -1402,8 +1730,22 @@ impl TextureAnimation {
         }
     }
 
-    pub fn get_current_mode(&self) -> ObjectDirection {
-        self.current_mode.clone()
+    pub fn get_current_mode(&self) -> M {
+        self.current_mode
+    }
+
+    ///
+    /// OneShotやTimesを再生し終えた後に移るモードを差し替える
+    ///
+    pub fn set_next_mode(&mut self, next_mode: M) {
+        self.next_mode = next_mode;
+    }
+
+    ///
+    /// 今のモードの何コマ目を出しているか
+    ///
+    pub fn get_current_frame_index(&self) -> usize {
+        self.textures.get(&self.current_mode).unwrap().frame_index()
     }
 }
 
@@ -1518,302 +1860,70 @@ impl TextureObject for GraphDrawer {
     impl_texture_object_for_wrapped! {canvas}
 }
 
-pub enum PauseResult {
-    ReleasePause,
-    GoToTitle,
-}
-
-pub struct PauseScreenSet {
-    entries: Vec<VerticalText>,
-    cursored_index: Option<usize>,
+pub struct SeekBar {
+    rect: numeric::Rect,
+    seek_offset: numeric::Vector2f,
+    handle: UniTexture,
+    seek_edge: ggraphics::Mesh,
+    dragging: bool,
     drwob_essential: DrawableObjectEssential,
-    config_panel: Option<crate::object::title_object::ConfigPanel>,
-    dark_effect: DarkEffectPanel,
-    is_paused_now: bool,
+    min_value: f32,
+    max_value: f32,
+    current_value: f32,
 }
 
-impl PauseScreenSet {
-    pub fn new<'a>(ctx: &mut SuzuContext<'a>, depth: i8, t: Clock) -> Self {
-        let font_info = FontInformation::new(
-            ctx.resource.get_font(FontID::Cinema),
-            numeric::Vector2f::new(28.0, 28.0),
-            ggraphics::Color::BLACK,
+impl SeekBar {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        pos_rect: numeric::Rect,
+        bar_height: f32,
+        max_value: f32,
+        min_value: f32,
+        init_value: f32,
+        depth: i8,
+    ) -> Self {
+        let mut handle = UniTexture::new(
+            ctx.ref_texture(TextureID::Knob),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(0.3, 0.3),
+            0.0,
+            0,
         );
 
-        let mut entries_vtext = Vec::new();
-        let mut text_pos = numeric::Point2f::new(750.0, 200.0);
+        handle.set_position(numeric::Point2f::new(
+            pos_rect.x
+                + ((pos_rect.w - handle.get_drawing_size(ctx.context).x)
+                    * ((init_value - min_value) / (max_value - min_value))),
+            pos_rect.y,
+        ));
 
-        for text in vec!["設定", "開始画面へ", "再開"] {
-            let mut vtext = VerticalText::new(
-                text.to_string(),
-                text_pos,
-                numeric::Vector2f::new(1.0, 1.0),
-                0.0,
-                0,
-                font_info.clone(),
-            );
-            vtext.hide();
-            entries_vtext.push(vtext);
+        let handle_area = handle.get_drawing_area(ctx.context);
 
-            text_pos.x -= 50.0;
-        }
+        let seek = shape::Rectangle::new(
+            numeric::Rect::new(
+                pos_rect.x,
+                pos_rect.y + (handle_area.h / 2.0) - (bar_height / 2.0),
+                pos_rect.w,
+                bar_height,
+            ),
+            ggraphics::DrawMode::fill(),
+            ggraphics::Color::from_rgba_u32(0xdbd4b8ff),
+        );
+
+        let mut builder = ggraphics::MeshBuilder::new();
+
+        seek.add_to_builder(&mut builder);
 
-        PauseScreenSet {
-            entries: entries_vtext,
+        SeekBar {
+            rect: pos_rect,
+            seek_offset: numeric::Vector2f::new(0.0, 0.0),
+            handle: handle,
+            seek_edge: builder.build(ctx.context).unwrap(),
             drwob_essential: DrawableObjectEssential::new(true, depth),
-            cursored_index: None,
-            config_panel: None,
-            dark_effect: DarkEffectPanel::new(
-                ctx.context,
-                numeric::Rect::new(0.0, 0.0, WINDOW_SIZE_X as f32, WINDOW_SIZE_Y as f32),
-                t,
-            ),
-            is_paused_now: false,
-        }
-    }
-
-    ///
-    /// 再描画要求有り
-    ///
-    fn select_entries_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, index: usize) {
-        if let Some(cursored_index) = self.cursored_index {
-            if cursored_index == index {
-                return;
-            }
-        }
-
-        self.entries
-            .get_mut(index)
-            .unwrap()
-            .set_color(ggraphics::Color::from_rgba_u32(0x222222ff));
-
-        self.cursored_index = Some(index);
-        ctx.process_utility.redraw();
-    }
-
-    fn unselect_entries_handler(&mut self) {
-        for vtext in self.entries.iter_mut() {
-            vtext.set_color(ggraphics::Color::BLACK);
-        }
-    }
-
-    pub fn dragging_handler<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        button: ggez::input::mouse::MouseButton,
-        point: numeric::Point2f,
-        t: Clock,
-    ) {
-        if let Some(panel) = self.config_panel.as_mut() {
-            panel.mouse_dragging_handler(ctx, button, point, t);
-        }
-    }
-
-    pub fn mouse_motion_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
-        if self.config_panel.is_none() {
-            for (index, vtext) in self.entries.iter().enumerate() {
-                if vtext.contains(ctx.context, point) {
-                    self.select_entries_handler(ctx, index);
-                    return;
-                }
-            }
-        }
-
-        // ここまで到達するのは、すべてのテキストにカーソルが重なっていなかった場合
-        if self.cursored_index.is_some() {
-            self.unselect_entries_handler();
-            self.cursored_index = None;
-        }
-    }
-
-    pub fn mouse_button_down<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        button: ggez::input::mouse::MouseButton,
-        point: numeric::Point2f,
-        t: Clock,
-    ) {
-        if let Some(panel) = self.config_panel.as_mut() {
-            panel.mouse_button_down(ctx, button, point, t);
-        }
-    }
-
-    pub fn mouse_click_handler<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        point: numeric::Point2f,
-        t: Clock,
-    ) -> Option<PauseResult> {
-        if let Some(panel) = self.config_panel.as_mut() {
-            if let Some(_) = panel.mouse_button_up(ctx, point, t) {
-                // ここに到達する場合は、"init-menu"に移動するとき
-                // ここでは、ConfigPanelのExitととしてみなす
-                ctx.process_utility.redraw();
-                self.config_panel = None;
-            }
-            return None;
-        }
-
-        for (index, vtext) in self.entries.iter().enumerate() {
-            if vtext.contains(ctx.context, point) {
-                return match index {
-                    0 => {
-                        self.config_panel = Some(crate::object::title_object::ConfigPanel::new(
-                            ctx,
-                            numeric::Rect::new(50.0, 50.0, 1266.0, 668.0),
-                            0,
-                            t,
-                        ));
-                        None
-                    }
-                    1 => Some(PauseResult::GoToTitle),
-                    2 => Some(PauseResult::ReleasePause),
-                    _ => panic!("index is out of bounds"),
-                };
-            }
-        }
-
-        None
-    }
-
-    pub fn effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
-        self.dark_effect.run_effect(ctx, t);
-    }
-
-    pub fn exit_pause(&mut self, t: Clock) {
-        self.is_paused_now = false;
-        self.dark_effect.new_effect(8, t, 235, 0);
-        for vtext in self.entries.iter_mut() {
-            vtext.hide();
-        }
-        if let Some(config_panel) = self.config_panel.as_mut() {
-            config_panel.hide();
-        }
-    }
-
-    pub fn enter_pause(&mut self, t: Clock) {
-        self.is_paused_now = true;
-        self.dark_effect.new_effect(8, t, 0, 235);
-        for vtext in self.entries.iter_mut() {
-            vtext.appear();
-        }
-        if let Some(config_panel) = self.config_panel.as_mut() {
-            config_panel.appear();
-        }
-    }
-
-    pub fn is_paused_now(&self) -> bool {
-        self.is_paused_now
-    }
-}
-
-impl DrawableComponent for PauseScreenSet {
-    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
-        if self.is_visible() {
-            self.dark_effect.draw(ctx)?;
-
-            for vtext in self.entries.iter_mut() {
-                vtext.draw(ctx)?;
-            }
-
-            if let Some(panel) = self.config_panel.as_mut() {
-                panel.draw(ctx)?;
-            }
-        }
-
-        Ok(())
-    }
-
-    #[inline(always)]
-    fn hide(&mut self) {
-        self.drwob_essential.visible = false;
-    }
-
-    #[inline(always)]
-    fn appear(&mut self) {
-        self.drwob_essential.visible = true;
-    }
-
-    #[inline(always)]
-    fn is_visible(&self) -> bool {
-        self.drwob_essential.visible
-    }
-
-    #[inline(always)]
-    fn set_drawing_depth(&mut self, depth: i8) {
-        self.drwob_essential.drawing_depth = depth;
-    }
-
-    #[inline(always)]
-    fn get_drawing_depth(&self) -> i8 {
-        self.drwob_essential.drawing_depth
-    }
-}
-
-pub struct SeekBar {
-    rect: numeric::Rect,
-    seek_offset: numeric::Vector2f,
-    handle: UniTexture,
-    seek_edge: ggraphics::Mesh,
-    dragging: bool,
-    drwob_essential: DrawableObjectEssential,
-    min_value: f32,
-    max_value: f32,
-    current_value: f32,
-}
-
-impl SeekBar {
-    pub fn new<'a>(
-        ctx: &mut SuzuContext<'a>,
-        pos_rect: numeric::Rect,
-        bar_height: f32,
-        max_value: f32,
-        min_value: f32,
-        init_value: f32,
-        depth: i8,
-    ) -> Self {
-        let mut handle = UniTexture::new(
-            ctx.ref_texture(TextureID::Knob),
-            numeric::Point2f::new(0.0, 0.0),
-            numeric::Vector2f::new(0.3, 0.3),
-            0.0,
-            0,
-        );
-
-        handle.set_position(numeric::Point2f::new(
-            pos_rect.x
-                + ((pos_rect.w - handle.get_drawing_size(ctx.context).x)
-                    * (init_value / max_value)),
-            pos_rect.y,
-        ));
-
-        let handle_area = handle.get_drawing_area(ctx.context);
-
-        let seek = shape::Rectangle::new(
-            numeric::Rect::new(
-                pos_rect.x,
-                pos_rect.y + (handle_area.h / 2.0) - (bar_height / 2.0),
-                pos_rect.w,
-                bar_height,
-            ),
-            ggraphics::DrawMode::fill(),
-            ggraphics::Color::from_rgba_u32(0xdbd4b8ff),
-        );
-
-        let mut builder = ggraphics::MeshBuilder::new();
-
-        seek.add_to_builder(&mut builder);
-
-        SeekBar {
-            rect: pos_rect,
-            seek_offset: numeric::Vector2f::new(0.0, 0.0),
-            handle: handle,
-            seek_edge: builder.build(ctx.context).unwrap(),
-            drwob_essential: DrawableObjectEssential::new(true, depth),
-            dragging: false,
-            current_value: init_value,
-            min_value: min_value,
-            max_value: max_value,
+            dragging: false,
+            current_value: init_value,
+            min_value: min_value,
+            max_value: max_value,
         }
     }
 
@@ -1869,7 +1979,7 @@ impl SeekBar {
         self.handle.set_position(numeric::Point2f::new(
             self.rect.x
                 + ((self.rect.w - self.handle.get_drawing_size(ctx.context).x)
-                    * (value / self.max_value)),
+                    * ((value - self.min_value) / (self.max_value - self.min_value))),
             self.rect.y,
         ));
 
@@ -2403,3 +2513,504 @@ impl DrawableComponent for FramedButton {
         self.drwob_essential.drawing_depth
     }
 }
+
+///
+/// # 統計情報を横一列に並べて表示するバー
+///
+/// ## labels
+/// 各項目の見出し
+///
+/// ## texts
+/// 見出しと値を連結した描画用テキスト
+///
+pub struct StatsBar {
+    canvas: SubScreen,
+    labels: Vec<String>,
+    texts: Vec<UniText>,
+    redraw: bool,
+}
+
+impl StatsBar {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        pos_rect: numeric::Rect,
+        labels: Vec<&str>,
+        font_info: FontInformation,
+        depth: i8,
+    ) -> Self {
+        let item_width = pos_rect.w / labels.len() as f32;
+
+        let texts = labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| {
+                UniText::new(
+                    format!("{} -", label),
+                    numeric::Point2f::new(8.0 + (index as f32 * item_width), 4.0),
+                    numeric::Vector2f::new(1.0, 1.0),
+                    0.0,
+                    0,
+                    font_info.clone(),
+                )
+            })
+            .collect();
+
+        StatsBar {
+            canvas: SubScreen::new(
+                ctx.context,
+                pos_rect,
+                depth,
+                ggraphics::Color::from_rgba_u32(0xffffffcc),
+            ),
+            labels: labels.iter().map(|s| s.to_string()).collect(),
+            texts: texts,
+            redraw: true,
+        }
+    }
+
+    pub fn update_value(&mut self, index: usize, value: String) {
+        if let Some(text) = self.texts.get_mut(index) {
+            text.replace_text(format!("{} {}", self.labels[index], value));
+            self.redraw = true;
+        }
+    }
+
+    ///
+    /// # 再描画要求有り
+    ///
+    pub fn update<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if self.redraw {
+            ctx.process_utility.redraw();
+        }
+    }
+}
+
+impl DrawableComponent for StatsBar {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            if self.redraw {
+                self.redraw = false;
+                sub_screen::stack_screen(ctx, &self.canvas);
+
+                for text in self.texts.iter_mut() {
+                    text.draw(ctx)?;
+                }
+
+                sub_screen::pop_screen(ctx);
+            }
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+///
+/// # ショートカットの一覧の窓
+/// 入力中の文字と、絞り込んだ操作の名前を上から並べる
+///
+/// ## entry_texts
+/// 一度に出せるのはSHORTCUT_PALETTE_VISIBLE_ROWS個まで。選ばれている操作が必ず入るようにずらす
+///
+pub struct ShortcutPaletteWindow {
+    canvas: SubScreen,
+    query_text: UniText,
+    entry_texts: Vec<UniText>,
+    font_info: FontInformation,
+    selected_font_info: FontInformation,
+}
+
+impl ShortcutPaletteWindow {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, pos_rect: numeric::Rect, depth: i8) -> Self {
+        let font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0x222222ff),
+        );
+        let selected_font_info = FontInformation::new(
+            ctx.resource.get_font(FontID::Cinema),
+            numeric::Vector2f::new(24.0, 24.0),
+            ggraphics::Color::from_rgba_u32(0xbb2222ff),
+        );
+
+        ShortcutPaletteWindow {
+            canvas: SubScreen::new(
+                ctx.context,
+                pos_rect,
+                depth,
+                ggraphics::Color::from_rgba_u32(0xf5eedcee),
+            ),
+            query_text: UniText::new(
+                "＞".to_string(),
+                numeric::Point2f::new(16.0, 12.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info.clone(),
+            ),
+            entry_texts: Vec::new(),
+            font_info: font_info,
+            selected_font_info: selected_font_info,
+        }
+    }
+
+    pub fn update_contents(&mut self, query: &str, labels: Vec<String>, selected: usize) {
+        self.query_text.replace_text(format!("＞{}", query));
+
+        let first = (selected + 1).saturating_sub(SHORTCUT_PALETTE_VISIBLE_ROWS);
+        let mut text_pos = numeric::Point2f::new(32.0, 52.0);
+        self.entry_texts.clear();
+
+        for (index, label) in labels
+            .into_iter()
+            .enumerate()
+            .skip(first)
+            .take(SHORTCUT_PALETTE_VISIBLE_ROWS)
+        {
+            let (text, font_info) = if index == selected {
+                (format!("▶ {}", label), self.selected_font_info.clone())
+            } else {
+                (format!("　 {}", label), self.font_info.clone())
+            };
+
+            self.entry_texts.push(UniText::new(
+                text,
+                text_pos,
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info,
+            ));
+            text_pos.y += 32.0;
+        }
+
+        if self.entry_texts.is_empty() {
+            self.entry_texts.push(UniText::new(
+                "該当なし".to_string(),
+                text_pos,
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                self.font_info.clone(),
+            ));
+        }
+    }
+}
+
+impl DrawableComponent for ShortcutPaletteWindow {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.query_text.draw(ctx)?;
+            for text in self.entry_texts.iter_mut() {
+                text.draw(ctx)?;
+            }
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
+
+///
+/// # 文字入力の中身
+/// 描画とは切り離して、文字の出し入れだけを受け持つ
+///
+/// ## max_chars
+/// 書ける文字数の上限
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextInputBuffer {
+    text: String,
+    max_chars: usize,
+}
+
+impl TextInputBuffer {
+    pub fn new(max_chars: usize) -> Self {
+        TextInputBuffer {
+            text: String::new(),
+            max_chars: max_chars,
+        }
+    }
+
+    ///
+    /// 一文字書き足す。書き足せればtrue
+    ///
+    pub fn push_char(&mut self, c: char) -> bool {
+        if c.is_control() || self.text.chars().count() >= self.max_chars {
+            return false;
+        }
+
+        self.text.push(c);
+        true
+    }
+
+    ///
+    /// 最後の一文字を消す。消せればtrue
+    ///
+    pub fn pop_char(&mut self) -> bool {
+        self.text.pop().is_some()
+    }
+
+    ///
+    /// 中身を置き換える。上限を超えた分は切り捨てる
+    ///
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(self.max_chars)
+            .collect();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    ///
+    /// 入力されたキーで中身を書き換え、何が起きたかを返す
+    ///
+    pub fn key_event(&mut self, key: PaletteKey) -> TextInputEvent {
+        match key {
+            PaletteKey::Char(c) => {
+                if self.push_char(c) {
+                    TextInputEvent::Changed
+                } else {
+                    TextInputEvent::Nothing
+                }
+            }
+            PaletteKey::Backspace => {
+                if self.pop_char() {
+                    TextInputEvent::Changed
+                } else {
+                    TextInputEvent::Nothing
+                }
+            }
+            PaletteKey::Enter => TextInputEvent::Committed(self.text.trim().to_string()),
+            PaletteKey::Escape => TextInputEvent::Canceled,
+            PaletteKey::Toggle | PaletteKey::Up | PaletteKey::Down | PaletteKey::Tab => {
+                TextInputEvent::Nothing
+            }
+        }
+    }
+}
+
+///
+/// # 文字入力で起きたこと
+///
+/// ## Committed
+/// 入力を確定した。前後の空白は除いてある
+///
+/// ## Canceled
+/// 入力を取りやめた
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextInputEvent {
+    Nothing,
+    Changed,
+    Committed(String),
+    Canceled,
+}
+
+///
+/// # 手で文字を書き込む欄
+/// 日本語はIMEで確定した文字がggezの文字入力として届くので、それをPaletteKey::Charで受け取る
+///
+/// 確定か取りやめで閉じる。閉じている間は描かない
+///
+pub struct TextInput {
+    canvas: SubScreen,
+    buffer: TextInputBuffer,
+    text: UniText,
+    placeholder: String,
+    editing: bool,
+}
+
+impl TextInput {
+    pub fn new<'a>(
+        ctx: &mut SuzuContext<'a>,
+        pos_rect: numeric::Rect,
+        max_chars: usize,
+        font_info: FontInformation,
+        depth: i8,
+    ) -> Self {
+        let mut canvas = SubScreen::new(
+            ctx.context,
+            pos_rect,
+            depth,
+            ggraphics::Color::from_rgba_u32(0xf5eedcf0),
+        );
+        canvas.hide();
+
+        TextInput {
+            canvas: canvas,
+            buffer: TextInputBuffer::new(max_chars),
+            text: UniText::new(
+                String::new(),
+                numeric::Point2f::new(8.0, 6.0),
+                numeric::Vector2f::new(1.0, 1.0),
+                0.0,
+                0,
+                font_info,
+            ),
+            placeholder: String::new(),
+            editing: false,
+        }
+    }
+
+    ///
+    /// 何も書いていないときに薄く出す案内
+    ///
+    pub fn set_placeholder(&mut self, placeholder: &str) {
+        self.placeholder = placeholder.to_string();
+        self.update_text();
+    }
+
+    fn update_text(&mut self) {
+        let text = if self.buffer.is_empty() && !self.placeholder.is_empty() {
+            format!("{}＿", self.placeholder)
+        } else {
+            format!("{}＿", self.buffer.get_text())
+        };
+
+        self.text.replace_text(text);
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    ///
+    /// positionに欄を出し、initialを書いた状態から入力を始める
+    ///
+    pub fn start_editing(&mut self, position: numeric::Point2f, initial: &str) {
+        self.buffer.set_text(initial);
+        self.editing = true;
+        self.canvas.set_position(position);
+        self.update_text();
+        self.canvas.appear();
+    }
+
+    ///
+    /// 入力を終えて欄を閉じる。中身は捨てる
+    ///
+    pub fn finish_editing(&mut self) {
+        self.editing = false;
+        self.buffer.clear();
+        self.canvas.hide();
+    }
+
+    pub fn get_text(&self) -> &str {
+        self.buffer.get_text()
+    }
+
+    ///
+    /// 入力中の欄にキーを渡す。確定か取りやめなら欄を閉じる
+    ///
+    /// 入力中でなければ、TextInputEvent::Nothing
+    ///
+    pub fn key_event(&mut self, key: PaletteKey) -> TextInputEvent {
+        if !self.editing {
+            return TextInputEvent::Nothing;
+        }
+
+        let event = self.buffer.key_event(key);
+        match event {
+            TextInputEvent::Changed => self.update_text(),
+            TextInputEvent::Committed(_) | TextInputEvent::Canceled => self.finish_editing(),
+            TextInputEvent::Nothing => (),
+        }
+
+        event
+    }
+
+    pub fn contains(&self, point: numeric::Point2f) -> bool {
+        self.editing && self.canvas.contains(point)
+    }
+}
+
+impl DrawableComponent for TextInput {
+    fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if self.is_visible() {
+            sub_screen::stack_screen(ctx, &self.canvas);
+
+            self.text.draw(ctx)?;
+
+            sub_screen::pop_screen(ctx);
+            self.canvas.draw(ctx).unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn hide(&mut self) {
+        self.canvas.hide();
+    }
+
+    fn appear(&mut self) {
+        self.canvas.appear();
+    }
+
+    fn is_visible(&self) -> bool {
+        self.canvas.is_visible()
+    }
+
+    fn set_drawing_depth(&mut self, depth: i8) {
+        self.canvas.set_drawing_depth(depth);
+    }
+
+    fn get_drawing_depth(&self) -> i8 {
+        self.canvas.get_drawing_depth()
+    }
+}
@@ -1,6 +1,11 @@
+pub mod album_scene;
+pub mod book_fair_scene;
+pub mod credits_scene;
 pub mod end_scene;
+pub mod ledger_audit_scene;
 pub mod save_scene;
 pub mod scenario_scene;
+pub mod score_attack_scene;
 pub mod shop_scene;
 pub mod suzuna_scene;
 pub mod title_scene;
@@ -12,6 +17,7 @@ use torifune::core::Clock;
 use torifune::device as tdev;
 use torifune::numeric;
 
+use crate::core::shortcut_palette::PaletteKey;
 use crate::core::SuzuContext;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
@@ -38,7 +44,7 @@ impl FromStr for SceneTransition {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum SceneID {
     Null,
     MainDesk,
@@ -49,6 +55,11 @@ pub enum SceneID {
     Copying,
     End,
     Title,
+    Album,
+    BookFair,
+    LedgerAudit,
+    ScoreAttack,
+    Credits,
 }
 
 impl FromStr for SceneID {
@@ -63,11 +74,40 @@ impl FromStr for SceneID {
             "Save" => Ok(Self::Save),
             "Title" => Ok(Self::Title),
             "End" => Ok(Self::End),
+            "Album" => Ok(Self::Album),
+            "BookFair" => Ok(Self::BookFair),
+            "LedgerAudit" => Ok(Self::LedgerAudit),
+            "ScoreAttack" => Ok(Self::ScoreAttack),
+            "Credits" => Ok(Self::Credits),
             _ => Err(()),
         }
     }
 }
 
+impl SceneID {
+    ///
+    /// from_strで読み込める文字列に変換する
+    ///
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::MainDesk => "MainDesk",
+            Self::Scenario => "Scenario",
+            Self::SuzunaShop => "SuzunaShop",
+            Self::DayResult => "WorkResult",
+            Self::Save => "Save",
+            Self::Title => "Title",
+            Self::End => "End",
+            Self::Null => "Null",
+            Self::Copying => "Copying",
+            Self::Album => "Album",
+            Self::BookFair => "BookFair",
+            Self::LedgerAudit => "LedgerAudit",
+            Self::ScoreAttack => "ScoreAttack",
+            Self::Credits => "Credits",
+        }
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum DrawRequest {
     InitDraw,
@@ -137,6 +177,16 @@ pub trait SceneManager {
     ) {
     }
 
+    ///
+    /// パッドの左スティックの向き。遊びの内側なら(0, 0)が届く
+    ///
+    fn gamepad_stick_event<'a>(
+        &mut self,
+        _ctx: &mut SuzuContext<'a>,
+        _direction: numeric::Vector2f,
+    ) {
+    }
+
     fn scene_popping_return_handler<'a>(&mut self, _: &mut SuzuContext<'a>) {}
 
     fn pre_process<'a>(&mut self, _ctx: &mut SuzuContext<'a>);
@@ -151,6 +201,37 @@ pub trait SceneManager {
 
     fn focus_event<'a>(&mut self, _ctx: &mut SuzuContext<'a>) {}
     fn unfocus_event<'a>(&mut self, _ctx: &mut SuzuContext<'a>) {}
+
+    ///
+    /// 一定時間入力が無かった場合に呼ばれる
+    ///
+    fn idle_timeout_event<'a>(&mut self, _ctx: &mut SuzuContext<'a>) {}
+
+    ///
+    /// ショートカットの一覧を開いている間は、キー入力を仮想キーとして届けない
+    ///
+    fn shortcut_palette_is_open(&self) -> bool {
+        false
+    }
+
+    ///
+    /// ショートカットの一覧への入力。一覧が入力を受け取った場合はtrueを返す
+    ///
+    fn shortcut_palette_event<'a>(&mut self, _ctx: &mut SuzuContext<'a>, _key: PaletteKey) -> bool {
+        false
+    }
+
+    ///
+    /// クイックセーブ、クイックロードをしてよい場面ならtrue
+    ///
+    fn quicksave_available(&self) -> bool {
+        false
+    }
+
+    ///
+    /// クイックセーブの結果を知らせる
+    ///
+    fn quicksave_notification<'a>(&mut self, _ctx: &mut SuzuContext<'a>, _message: String) {}
 }
 
 pub struct NullScene {}
@@ -0,0 +1,302 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+
+use crate::core::book_fair::*;
+use crate::core::{FontID, SoundID, SuzuContext, TextureID, TileBatchTextureID};
+use crate::object::book_fair_object::*;
+use crate::object::effect_object;
+use crate::object::util_object::*;
+use crate::scene::*;
+
+use crate::flush_delay_event;
+use crate::log_info;
+
+///
+/// # 季節の本市
+/// 季節の最初の日の朝、開店前に開く。窓口に並ぶ客へ、制限時間内に題名札を次々と渡す
+///
+/// ## counter
+/// 本市の受付台。締めた後はNone
+///
+/// ## start_clock
+/// 受付を始めた時刻
+///
+pub struct BookFairScene {
+    background: UniTexture,
+    title_text: UniText,
+    counter: Option<BookFairCounter>,
+    result_panel: Option<BookFairResultPanel>,
+    next_button: FramedButton,
+    event_list: DelayEventList<Self>,
+    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    start_clock: Clock,
+    scene_transition: SceneID,
+    scene_transition_type: SceneTransition,
+    clock: Clock,
+}
+
+impl BookFairScene {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>) -> Self {
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(
+            ctx.context,
+            numeric::Vector2f::new(
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+        );
+
+        let (_, season) = fair_season_of(&ctx.today());
+        let mut title_text = UniText::new(
+            format!("{}ノ本市", season.to_str()),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(40.0, 40.0),
+                ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+            ),
+        );
+        title_text.make_center(
+            ctx.context,
+            numeric::Point2f::new(crate::core::WINDOW_SIZE_X as f32 / 2.0, 60.0),
+        );
+
+        let requests = (0..BOOK_FAIR_REQUESTS)
+            .map(|_| {
+                let customer_name = ctx.resource.customer_random_select().to_string();
+                FairRequest::new(customer_name, ctx.resource.book_random_select().clone())
+            })
+            .collect();
+
+        let mut next_button = FramedButton::create_design1(
+            ctx,
+            numeric::Point2f::new(1150.0, 650.0),
+            "店ヘ",
+            numeric::Vector2f::new(28.0, 28.0),
+        );
+        next_button.hide();
+
+        let scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Open,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            0,
+        ));
+
+        let mut event_list = DelayEventList::new();
+        event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition_effect = None;
+            }),
+            31,
+        );
+
+        BookFairScene {
+            background: background,
+            title_text: title_text,
+            counter: Some(BookFairCounter::new(ctx, requests)),
+            result_panel: None,
+            next_button: next_button,
+            event_list: event_list,
+            scene_transition_effect: scene_transition_effect,
+            start_clock: 31,
+            scene_transition: SceneID::BookFair,
+            scene_transition_type: SceneTransition::Keep,
+            clock: 0,
+        }
+    }
+
+    fn remaining_time(&self) -> Clock {
+        let elapsed = self.get_current_clock().saturating_sub(self.start_clock);
+        BOOK_FAIR_TIME_LIMIT.saturating_sub(elapsed)
+    }
+
+    ///
+    /// 本市を締め、売上と評判を反映して番付を出す
+    ///
+    fn close_fair<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let remaining_time = self.remaining_time();
+        let counter = match self.counter.take() {
+            Some(counter) => counter,
+            None => return,
+        };
+
+        let save_data = ctx.take_save_data_mut();
+        let result = counter.finish(save_data.date, remaining_time);
+        save_data.task_result.add_total_money(result.earned_money());
+        save_data
+            .suzunaan_status
+            .add_reputation(result.reputation_gain());
+        save_data.book_fair.record(result.clone());
+        log_info!("book fair closed: score {}", result.score);
+
+        let leaderboard: Vec<BookFairResult> = save_data
+            .book_fair
+            .leaderboard()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        self.result_panel = Some(BookFairResultPanel::new(
+            ctx,
+            numeric::Rect::new(283.0, 110.0, 800.0, 560.0),
+            &result,
+            &leaderboard,
+        ));
+        self.next_button.appear();
+    }
+
+    fn exit_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Close,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            t,
+        ));
+
+        self.event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition = SceneID::Scenario;
+                slf.scene_transition_type = SceneTransition::SwapTransition;
+            }),
+            t + 31,
+        );
+    }
+}
+
+impl SceneManager for BookFairScene {
+    fn mouse_motion_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        _: numeric::Vector2f,
+    ) {
+        if self.next_button.is_visible() {
+            self.next_button.mouse_motion_handler(ctx, point);
+        }
+    }
+
+    fn mouse_button_up_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        _button: ginput::mouse::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        let t = self.get_current_clock();
+
+        if let Some(counter) = self.counter.as_mut() {
+            match counter.click_handler(point) {
+                BookFairAction::Served(true) => {
+                    ctx.play_sound_as_se(SoundID::SeCustomerBell, None);
+                }
+                BookFairAction::None => return,
+                _ => (),
+            }
+
+            if counter.is_finished() {
+                self.close_fair(ctx);
+            }
+            ctx.process_utility.redraw();
+            return;
+        }
+
+        if self.next_button.is_visible() && self.next_button.contains(point) {
+            self.next_button.hide();
+            self.exit_scene(ctx, t);
+        }
+    }
+
+    fn pre_process<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let t = self.get_current_clock();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.effect(ctx.context, t);
+            ctx.process_utility.redraw();
+        }
+
+        let remaining_time = self.remaining_time();
+        if let Some(counter) = self.counter.as_mut() {
+            // 秒が変わったときだけ描き直す
+            if remaining_time % 60 == 0 {
+                counter.update_timer(remaining_time);
+                ctx.process_utility.redraw();
+            }
+        }
+
+        if remaining_time == 0 {
+            self.close_fair(ctx);
+            ctx.process_utility.redraw();
+        }
+
+        if flush_delay_event!(self, self.event_list, ctx, self.get_current_clock()) > 0 {
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn drawing_process(&mut self, ctx: &mut ggez::Context) {
+        self.background.draw(ctx).unwrap();
+        self.title_text.draw(ctx).unwrap();
+
+        if let Some(counter) = self.counter.as_mut() {
+            counter.draw(ctx).unwrap();
+        }
+
+        if let Some(panel) = self.result_panel.as_mut() {
+            panel.draw(ctx).unwrap();
+        }
+
+        self.next_button.draw(ctx).unwrap();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.draw(ctx).unwrap();
+        }
+    }
+
+    fn post_process<'a>(&mut self, _ctx: &mut SuzuContext<'a>) -> SceneTransition {
+        self.update_current_clock();
+
+        self.scene_transition_type
+    }
+
+    fn transition(&self) -> SceneID {
+        self.scene_transition
+    }
+
+    fn get_current_clock(&self) -> Clock {
+        self.clock
+    }
+
+    fn update_current_clock(&mut self) {
+        self.clock += 1;
+    }
+}
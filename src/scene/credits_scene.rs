@@ -0,0 +1,309 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::device as tdev;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+
+use crate::core::credits::{CreditsScroll, CreditsTable};
+use crate::core::{util, FontID, SuzuContext, TextureID, TileBatchTextureID};
+use crate::object::credits_object::*;
+use crate::object::effect_object;
+use crate::object::util_object::*;
+use crate::scene::*;
+
+use crate::flush_delay_event;
+
+const CREDITS_PATH: &str = "/credits.toml";
+
+///
+/// # 制作の画面
+/// 開始画面から重ねて開くか、終わりの画面の後に入れ替えて開く
+/// 筆の書体で縦書きにした制作の一覧を、鈴奈庵の背景の上で下から上へ流す
+///
+/// ## exit_transition
+/// 開始画面から開いたならPoppingTransition、終わりの画面の後ならSwapTransitionで開始画面へ戻る
+///
+pub struct CreditsScene {
+    far_layer: ParallaxLayer,
+    near_layer: ParallaxLayer,
+    credits_roll: CreditsRoll,
+    credits_scroll: CreditsScroll,
+    sparks: CreditSparks,
+    skip_button: SelectButton,
+    event_list: DelayEventList<Self>,
+    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    exit_transition: SceneTransition,
+    exiting: bool,
+    scene_transition: SceneID,
+    scene_transition_type: SceneTransition,
+    clock: Clock,
+}
+
+impl CreditsScene {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>, exit_transition: SceneTransition) -> Self {
+        let table = match CreditsTable::from_toml_str(&util::read_from_resources_as_string(
+            ctx.context,
+            CREDITS_PATH,
+        )) {
+            Ok(table) => table,
+            Err(e) => panic!("failed to parse credits: {}", e),
+        };
+
+        let credits_roll = CreditsRoll::new(
+            ctx,
+            &table,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+        );
+        let credits_scroll = CreditsScroll::new(credits_roll.get_length());
+
+        let far_layer = ParallaxLayer::new(
+            ctx,
+            TextureID::JpHouseTexture,
+            numeric::Vector2f::new(1.0, 1.0),
+            1.0,
+            0.05,
+        );
+        let near_layer = ParallaxLayer::new(
+            ctx,
+            TextureID::Library,
+            numeric::Vector2f::new(1.0, 1.0),
+            0.35,
+            0.2,
+        );
+
+        let scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Open,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            0,
+        ));
+
+        let texture = Box::new(TextButtonTexture::new(
+            ctx,
+            numeric::Point2f::new(0.0, 0.0),
+            "飛バス".to_string(),
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(24.0, 24.0),
+                ggraphics::Color::from_rgba_u32(0xf6e1d5ff),
+            ),
+            10.0,
+            ggraphics::Color::from_rgba_u32(0x5a4f3fff),
+            0,
+        ));
+
+        let skip_button = SelectButton::new(
+            ctx,
+            numeric::Rect::new(
+                1150.0,
+                (crate::core::WINDOW_SIZE_Y as f32) - 100.0,
+                100.0,
+                50.0,
+            ),
+            texture,
+        );
+
+        let mut event_list = DelayEventList::new();
+        event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition_effect = None;
+            }),
+            31,
+        );
+
+        CreditsScene {
+            far_layer: far_layer,
+            near_layer: near_layer,
+            credits_roll: credits_roll,
+            credits_scroll: credits_scroll,
+            sparks: CreditSparks::new(),
+            skip_button: skip_button,
+            event_list: event_list,
+            scene_transition_effect: scene_transition_effect,
+            exit_transition: exit_transition,
+            exiting: false,
+            scene_transition: SceneID::Credits,
+            scene_transition_type: SceneTransition::Keep,
+            clock: 0,
+        }
+    }
+
+    fn exit_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.exiting {
+            return;
+        }
+        self.exiting = true;
+
+        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Close,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            t,
+        ));
+
+        let exit_transition = self.exit_transition;
+        self.event_list.add_event(
+            Box::new(move |slf: &mut Self, ctx, _| {
+                // 終わりの画面から続けて流していた曲は、ここで止める
+                if exit_transition == SceneTransition::SwapTransition {
+                    ctx.resource.stop_scene_bgm(ctx.context, SceneID::Credits);
+                }
+                slf.scene_transition = SceneID::Title;
+                slf.scene_transition_type = exit_transition;
+            }),
+            t + 31,
+        );
+    }
+
+    fn scroll_to(&mut self, offset: f32) {
+        self.credits_roll.scroll_to(offset);
+        self.far_layer.scroll_to(offset);
+        self.near_layer.scroll_to(offset);
+    }
+}
+
+impl SceneManager for CreditsScene {
+    fn key_down_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, vkey: tdev::VirtualKey) {
+        let t = self.get_current_clock();
+
+        match vkey {
+            tdev::VirtualKey::Action1 => {
+                self.exit_scene(ctx, t);
+            }
+            tdev::VirtualKey::Action3 => {
+                self.credits_scroll.speed_up();
+            }
+            tdev::VirtualKey::Action4 => {
+                self.credits_scroll.speed_down();
+            }
+            _ => (),
+        }
+    }
+
+    fn mouse_button_up_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        button: ginput::mouse::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        let t = self.get_current_clock();
+
+        match button {
+            ginput::mouse::MouseButton::Left => {
+                if self.skip_button.contains(ctx.context, point) {
+                    self.exit_scene(ctx, t);
+                    return;
+                }
+
+                if let Some(center) = self.credits_roll.click_name(ctx, point) {
+                    self.sparks.burst(ctx.resource.rng(), center, t);
+                    ctx.process_utility.redraw();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn mouse_wheel_event<'a>(
+        &mut self,
+        _ctx: &mut SuzuContext<'a>,
+        _point: numeric::Point2f,
+        _x: f32,
+        y: f32,
+    ) {
+        // 下へ回すと速く、上へ回すと遅くなる
+        if y < 0.0 {
+            self.credits_scroll.speed_up();
+        } else if y > 0.0 {
+            self.credits_scroll.speed_down();
+        }
+    }
+
+    fn pre_process<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let t = self.get_current_clock();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.effect(ctx.context, t);
+            ctx.process_utility.redraw();
+        }
+
+        if self.credits_scroll.advance() > 0.0 {
+            self.scroll_to(self.credits_scroll.get_offset());
+            ctx.process_utility.redraw();
+        }
+
+        if !self.sparks.is_empty() {
+            self.sparks.update(t);
+            ctx.process_utility.redraw();
+        }
+
+        if self.credits_scroll.is_finished() {
+            self.exit_scene(ctx, t);
+        }
+
+        if flush_delay_event!(self, self.event_list, ctx, self.get_current_clock()) > 0 {
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn drawing_process(&mut self, ctx: &mut ggez::Context) {
+        self.far_layer.draw(ctx).unwrap();
+        self.near_layer.draw(ctx).unwrap();
+        self.credits_roll.draw(ctx).unwrap();
+        self.sparks.draw(ctx).unwrap();
+
+        self.skip_button.draw(ctx).unwrap();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.draw(ctx).unwrap();
+        }
+    }
+
+    fn post_process<'a>(&mut self, _ctx: &mut SuzuContext<'a>) -> SceneTransition {
+        self.update_current_clock();
+
+        self.scene_transition_type
+    }
+
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        // 止めたまま放置された場合も、開始画面へ戻る
+        let t = self.get_current_clock();
+        self.exit_scene(ctx, t);
+    }
+
+    fn transition(&self) -> SceneID {
+        self.scene_transition
+    }
+
+    fn get_current_clock(&self) -> Clock {
+        self.clock
+    }
+
+    fn update_current_clock(&mut self) {
+        self.clock += 1;
+    }
+}
@@ -131,7 +131,7 @@ impl EndScene {
             Box::new(move |slf: &mut Self, ctx, _| {
                 slf.scene_transition = scene_id;
                 slf.scene_transition_type = SceneTransition::SwapTransition;
-                ctx.resource.stop_bgm(ctx.context, SoundID::EndBGM);
+                ctx.resource.stop_scene_bgm(ctx.context, SceneID::End);
             }),
             t + 31,
         );
@@ -193,12 +193,12 @@ impl SceneManager for EndScene {
                 },
                 t + 80
             );
+            // 曲は止めずに、制作の画面で流し終えてから止める
             add_delay_event!(
                 self.event_list,
-                |slf, ctx, _| {
-                    slf.scene_transition = SceneID::Title;
+                |slf, _, _| {
+                    slf.scene_transition = SceneID::Credits;
                     slf.scene_transition_type = SceneTransition::SwapTransition;
-                    ctx.resource.stop_bgm(ctx.context, SoundID::EndBGM);
                 },
                 t + 140
             );
@@ -262,6 +262,12 @@ impl SceneManager for EndScene {
     ) {
     }
 
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        // 結果画面で放置された場合は、開始画面へ戻る
+        let t = self.get_current_clock();
+        self.transition_selected_scene(ctx, SceneID::Title, t);
+    }
+
     fn transition(&self) -> SceneID {
         self.scene_transition
     }
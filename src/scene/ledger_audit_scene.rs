@@ -0,0 +1,303 @@
+use ggez::graphics as ggraphics;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+
+use crate::core::ledger_audit::*;
+use crate::core::{FontID, SoundID, SuzuContext, TextureID, TileBatchTextureID};
+use crate::object::effect_object;
+use crate::object::ledger_audit_object::*;
+use crate::object::util_object::*;
+use crate::scene::*;
+
+use crate::flush_delay_event;
+use crate::log_info;
+
+///
+/// # 週末の帳簿監査
+/// 週の終わりの晩、帳簿を写した表から不整合を探して印を付ける。見つけた分だけ売上を取り戻す
+///
+/// ## sheet
+/// 監査表。締めた後はNone
+///
+/// ## start_clock
+/// 監査を始めた時刻
+///
+pub struct LedgerAuditScene {
+    background: UniTexture,
+    title_text: UniText,
+    sheet: Option<LedgerAuditSheet>,
+    result_panel: Option<LedgerAuditResultPanel>,
+    next_button: FramedButton,
+    event_list: DelayEventList<Self>,
+    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    start_clock: Clock,
+    scene_transition: SceneID,
+    scene_transition_type: SceneTransition,
+    clock: Clock,
+}
+
+impl LedgerAuditScene {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>) -> Self {
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(
+            ctx.context,
+            numeric::Vector2f::new(
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+        );
+
+        let mut title_text = UniText::new(
+            "帳簿監査".to_string(),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(40.0, 40.0),
+                ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+            ),
+        );
+        title_text.make_center(
+            ctx.context,
+            numeric::Point2f::new(crate::core::WINDOW_SIZE_X as f32 / 2.0, 60.0),
+        );
+
+        // 写すのは控えなので、保存されている帳簿には手を付けない
+        let entries = collect_entries(&ctx.take_save_data().record_book_data.pages_data);
+        let flaws = pick_flaws(ctx.resource.rng(), entries.len());
+        let sheet = LedgerAuditSheet::new(
+            ctx,
+            numeric::Point2f::new(300.0, 120.0),
+            LedgerAudit::new(entries, &flaws),
+        );
+
+        let mut next_button = FramedButton::create_design1(
+            ctx,
+            numeric::Point2f::new(1150.0, 650.0),
+            "次ヘ",
+            numeric::Vector2f::new(28.0, 28.0),
+        );
+        next_button.hide();
+
+        let scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Open,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            0,
+        ));
+
+        let mut event_list = DelayEventList::new();
+        event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition_effect = None;
+            }),
+            31,
+        );
+
+        LedgerAuditScene {
+            background: background,
+            title_text: title_text,
+            sheet: Some(sheet),
+            result_panel: None,
+            next_button: next_button,
+            event_list: event_list,
+            scene_transition_effect: scene_transition_effect,
+            start_clock: 31,
+            scene_transition: SceneID::LedgerAudit,
+            scene_transition_type: SceneTransition::Keep,
+            clock: 0,
+        }
+    }
+
+    fn remaining_time(&self) -> Clock {
+        let elapsed = self.get_current_clock().saturating_sub(self.start_clock);
+        AUDIT_TIME_LIMIT.saturating_sub(elapsed)
+    }
+
+    ///
+    /// 監査を締め、取り戻した売上を反映する
+    ///
+    fn close_audit<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let sheet = match self.sheet.take() {
+            Some(sheet) => sheet,
+            None => return,
+        };
+
+        let result = sheet.finish();
+        ctx.take_save_data_mut()
+            .task_result
+            .add_total_money(result.recovered_money());
+        log_info!(
+            "ledger audit closed: found {}, missed {}",
+            result.found,
+            result.missed
+        );
+
+        self.result_panel = Some(LedgerAuditResultPanel::new(
+            ctx,
+            numeric::Rect::new(383.0, 180.0, 600.0, 300.0),
+            &result,
+        ));
+        self.next_button.appear();
+    }
+
+    fn exit_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Close,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            t,
+        ));
+
+        // 週の初めが季節の初めと重なれば、続けて本市を開く
+        let date = ctx.today();
+        let next_scene = if ctx.take_save_data().book_fair.is_due(&date) {
+            SceneID::BookFair
+        } else {
+            SceneID::Scenario
+        };
+
+        self.event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition = next_scene;
+                slf.scene_transition_type = SceneTransition::SwapTransition;
+            }),
+            t + 31,
+        );
+    }
+}
+
+impl SceneManager for LedgerAuditScene {
+    fn mouse_motion_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        _: numeric::Vector2f,
+    ) {
+        if self.next_button.is_visible() {
+            self.next_button.mouse_motion_handler(ctx, point);
+        }
+    }
+
+    fn mouse_button_up_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        _button: ginput::mouse::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        let t = self.get_current_clock();
+
+        if let Some(sheet) = self.sheet.as_mut() {
+            match sheet.click_handler(ctx.context, point) {
+                Some(true) => {
+                    ctx.play_sound_as_se(SoundID::SeCustomerBell, None);
+                }
+                Some(false) => (),
+                None => return,
+            }
+
+            if sheet.is_complete() {
+                self.close_audit(ctx);
+            }
+            ctx.process_utility.redraw();
+            return;
+        }
+
+        if self.next_button.is_visible() && self.next_button.contains(point) {
+            self.next_button.hide();
+            self.exit_scene(ctx, t);
+        }
+    }
+
+    fn pre_process<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let t = self.get_current_clock();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.effect(ctx.context, t);
+            ctx.process_utility.redraw();
+        }
+
+        let remaining_time = self.remaining_time();
+        if let Some(sheet) = self.sheet.as_mut() {
+            // 秒が変わったときだけ描き直す
+            if remaining_time % 60 == 0 {
+                sheet.update_timer(remaining_time);
+                ctx.process_utility.redraw();
+            }
+        }
+
+        if remaining_time == 0 {
+            self.close_audit(ctx);
+            ctx.process_utility.redraw();
+        }
+
+        if flush_delay_event!(self, self.event_list, ctx, self.get_current_clock()) > 0 {
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn drawing_process(&mut self, ctx: &mut ggez::Context) {
+        self.background.draw(ctx).unwrap();
+        self.title_text.draw(ctx).unwrap();
+
+        if let Some(sheet) = self.sheet.as_mut() {
+            sheet.draw(ctx).unwrap();
+        }
+
+        if let Some(panel) = self.result_panel.as_mut() {
+            panel.draw(ctx).unwrap();
+        }
+
+        self.next_button.draw(ctx).unwrap();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.draw(ctx).unwrap();
+        }
+    }
+
+    fn post_process<'a>(&mut self, _ctx: &mut SuzuContext<'a>) -> SceneTransition {
+        self.update_current_clock();
+
+        self.scene_transition_type
+    }
+
+    fn transition(&self) -> SceneID {
+        self.scene_transition
+    }
+
+    fn get_current_clock(&self) -> Clock {
+        self.clock
+    }
+
+    fn update_current_clock(&mut self) {
+        self.clock += 1;
+    }
+}
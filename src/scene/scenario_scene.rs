@@ -1,30 +1,42 @@
 use ggez::input::mouse::MouseButton;
 
+use torifune::core::Clock;
 use torifune::device as tdev;
 use torifune::graphics::object::Effectable;
 use torifune::numeric;
-use torifune::{core::Clock, sound::SoundPlayFlags};
 
-use crate::core::{GameMode, GeneralScenarioID, MouseInformation, ScenarioSceneSaveData, SoundID, SuzuContext, TileBatchTextureID};
+use crate::core::{
+    GameMode, GeneralScenarioID, MouseInformation, ScenarioSceneSaveData, SuzuContext, TextureID,
+};
 
 use crate::add_delay_event;
+use crate::core::decision_log::EndingTable;
 use crate::core::game_system;
 use crate::core::game_system::*;
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::util;
 use crate::flush_delay_event;
 use crate::flush_delay_event_and_redraw_check;
 use crate::object::effect_object;
+use crate::object::pause::{PauseResult, PauseScreenSet};
 use crate::object::scenario::*;
 use crate::object::scenario_object::*;
 use crate::object::util_object::*;
 
 #[allow(unused_imports)]
 use crate::perf_measure;
+use crate::{log_debug, log_info, log_warn};
 
-use effect_object::{SceneTransitionEffectType, TilingEffectType};
+use effect_object::SceneTransitionEffectType;
 use torifune::graphics::drawable::*;
 
 use super::*;
 
+///
+/// 決断の記録から結末を選ぶ表
+///
+const ENDING_TABLE_PATH: &str = "/ending_table.toml";
+
 #[derive(Clone)]
 pub enum ScenarioSelect {
     DayBegin = 0,
@@ -48,9 +60,10 @@ pub struct ScenarioScene {
     event_list: DelayEventList<Self>,
     status_screen: SuzunaStatusScreen,
     scene_transition_type: SceneTransition,
-    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    scene_transition_effect: Option<Box<dyn effect_object::SceneTransitionEffect>>,
     scene_transition: SceneID,
     scenario_ctx: ScenarioContext,
+    last_still: Option<TextureID>,
     clock: Clock,
 }
 
@@ -58,20 +71,29 @@ impl ScenarioScene {
     pub fn new<'a>(ctx: &mut SuzuContext<'a>, scenario_select: ScenarioSelect) -> Self {
         let file_path = match scenario_select {
             ScenarioSelect::DayBegin => match &ctx.take_save_data().game_mode {
-                GameMode::Story => ctx
-                    .resource
-                    .get_day_scenario_path(&ctx.take_save_data().date)
-                    .expect("BUG"),
+                GameMode::Story => match ctx
+                    .take_save_data()
+                    .decision_log
+                    .get_ending()
+                    .and_then(|ending| ending.scenario.clone())
+                {
+                    // 結末が決まっていれば、その場面を見せる
+                    Some(ending_path) => ending_path,
+                    None => ctx
+                        .resource
+                        .get_day_scenario_path(&ctx.today())
+                        .expect("BUG"),
+                },
                 GameMode::TimeAttack(data) => {
-                    if ctx.take_save_data().date.first_day() {
+                    if ctx.today().first_day() {
                         "/scenario/time_attack_first.toml".to_string()
-                    } else if ctx.take_save_data().date.is_past(data.get_limit()) {
+                    } else if ctx.today().is_past(data.get_limit()) {
                         "/scenario/time_attack_over.toml".to_string()
-                    } else if ctx.take_save_data().date.is_week_first() {
-			println!("time attack first");
+                    } else if ctx.today().is_week_first() {
+			log_debug!("time attack first");
                         "/scenario/time_attack_week_first.toml".to_string()
                     } else {
-			println!("time attack default");
+			log_debug!("time attack default");
                         "/scenario/time_attack_default.toml".to_string()
                     }
                 }
@@ -132,51 +154,105 @@ impl ScenarioScene {
 	    status_screen.show(ctx, save_data.status_page.clone());
 	}
 
-        ctx.play_sound_as_bgm(
-            SoundID::ScenarioBGM,
-            Some(SoundPlayFlags::new(10000, 1.0, true, ctx.config.get_bgm_volume())),
+        ctx.play_scene_bgm(SceneID::Scenario, 10000);
+
+        // 会話の場面は、暗転を挟んでゆっくり切り替える
+        let animation_time = 30;
+        let scene_transition_effect = effect_object::new_scene_transition_effect(
+            ctx,
+            effect_object::SceneTransitionEffectKind::CrossFade,
+            animation_time,
+            SceneTransitionEffectType::Open,
+            -128,
+            0,
         );
 
+        let mut event_list = DelayEventList::new();
+        event_list.add_event(
+            Box::new(|slf: &mut ScenarioScene, _, _| {
+                slf.scene_transition_effect = None;
+            }),
+            animation_time + 1,
+        );
 
         ScenarioScene {
             mouse_info: MouseInformation::new(),
             scenario_event: scenario,
             pause_screen_set: PauseScreenSet::new(ctx, 0, 0),
-            scene_transition_effect: None,
-            event_list: DelayEventList::new(),
+            scene_transition_effect: Some(scene_transition_effect),
+            event_list: event_list,
             //graph_sample: graph_drawer,
             scene_transition: SceneID::Scenario,
             status_screen: status_screen,
             scene_transition_type: SceneTransition::Keep,
             scenario_ctx: scenario_ctx,
+            last_still: None,
             clock: 0,
         }
     }
 
     fn scene_transition_close_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
-        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+        self.scene_transition_effect = Some(effect_object::new_scene_transition_effect(
             ctx,
-            TileBatchTextureID::Shoji,
-            numeric::Rect::new(
-                0.0,
-                0.0,
-                crate::core::WINDOW_SIZE_X as f32,
-                crate::core::WINDOW_SIZE_Y as f32,
-            ),
+            effect_object::SceneTransitionEffectKind::CrossFade,
             60,
             SceneTransitionEffectType::Close,
-            TilingEffectType::WholeTile,
             -128,
             t,
         ));
     }
 
+    ///
+    /// 物語の終わりに、決断の記録から結末を選ぶ
+    ///
+    /// 結末の場面があれば先にそれを見せ、見終わったら終わりの画面に移る
+    ///
+    fn select_ending<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> SceneID {
+        match ctx.take_save_data().game_mode {
+            GameMode::Story => (),
+            GameMode::TimeAttack(_) => return SceneID::End,
+        }
+
+        if ctx.take_save_data().decision_log.get_ending().is_some() {
+            return SceneID::End;
+        }
+
+        let table = match EndingTable::from_toml_str(&util::read_from_resources_as_string(
+            ctx.context,
+            ENDING_TABLE_PATH,
+        )) {
+            Ok(table) => table,
+            Err(e) => {
+                log_warn!("failed to parse ending table: {}", e);
+                return SceneID::End;
+            }
+        };
+
+        let save_data = ctx.take_save_data_mut();
+        let ending = match table.select(&save_data.decision_log) {
+            Some(ending) => ending.clone(),
+            None => return SceneID::End,
+        };
+        log_info!("ending: {}", ending.id);
+
+        let has_scenario = ending.scenario.is_some();
+        save_data.decision_log.set_ending(ending);
+
+        if has_scenario {
+            // 最後の日の途中から始まらないよう、シナリオの保存位置は捨てる
+            save_data.scenario_save_data = None;
+            SceneID::Scenario
+        } else {
+            SceneID::End
+        }
+    }
+
     fn transition_to_title_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         self.event_list.add_event(
             Box::new(|slf: &mut Self, ctx, _| {
                 slf.scene_transition_type = SceneTransition::SwapTransition;
                 slf.scene_transition = SceneID::Title;
-                ctx.resource.stop_bgm(ctx.context, SoundID::ScenarioBGM);
+                ctx.resource.stop_scene_bgm(ctx.context, SceneID::Scenario);
             }),
             t + 60,
         );
@@ -184,6 +260,22 @@ impl ScenarioScene {
         self.scene_transition_close_effect(ctx, t);
     }
 
+    ///
+    /// 場面の背景が変わったら、その一枚を写真帖に貼る
+    ///
+    fn paste_cutscene_still<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let background = self.scenario_event.current_background_texture();
+        if background.is_none() || background == self.last_still {
+            return;
+        }
+
+        self.last_still = background;
+
+        let save_data = ctx.take_save_data_mut();
+        let date = save_data.date;
+        save_data.album.record_still(date, background.unwrap() as u32);
+    }
+
     fn exit_pause_screen(&mut self, t: Clock) {
         self.pause_screen_set.exit_pause(t);
     }
@@ -330,6 +422,8 @@ impl ScenarioScene {
                         );
                     }
                     "GoToEndRoll" => {
+                        let next_scene = self.select_ending(ctx);
+
                         add_delay_event!(
                             self.event_list,
                             |slf, ctx, t| {
@@ -340,10 +434,10 @@ impl ScenarioScene {
 
                         add_delay_event!(
                             self.event_list,
-                            |slf, ctx, _| {
-                                slf.scene_transition = SceneID::End;
+                            move |slf, ctx, _| {
+                                slf.scene_transition = next_scene;
                                 slf.scene_transition_type = SceneTransition::SwapTransition;
-                                ctx.resource.stop_bgm(ctx.context, SoundID::ScenarioBGM);
+                                ctx.resource.stop_scene_bgm(ctx.context, SceneID::Scenario);
                                 //ctx.go_next_day();
                             },
                             self.get_current_clock() + 1
@@ -397,7 +491,10 @@ impl ScenarioScene {
             self.get_current_clock() + 100
         );
 
-        let money_diff = ctx.current_total_ad_agency_money_gain() - ctx.current_total_ad_cost();
+        // 包装用品が少なくなっていれば、店を開ける前に仕入れておく
+        let restock_cost = ctx.take_save_data_mut().wrapping_supplies.restock_if_low();
+        let money_diff =
+            ctx.current_total_ad_agency_money_gain() - ctx.current_total_ad_cost() - restock_cost;
         self.status_screen
             .change_main_page_money(ctx, money_diff, self.get_current_clock());
         ctx.take_save_data_mut().task_result.total_money += money_diff;
@@ -408,7 +505,7 @@ impl ScenarioScene {
                 slf.scene_transition = SceneID::SuzunaShop;
                 slf.scene_transition_type = SceneTransition::SwapTransition;
                 ctx.take_save_data_mut().award_data.shop_work_count += 1;
-                ctx.resource.stop_bgm(ctx.context, SoundID::ScenarioBGM);
+                ctx.resource.stop_scene_bgm(ctx.context, SceneID::Scenario);
             },
             self.get_current_clock() + 300
         );
@@ -706,6 +803,7 @@ impl SceneManager for ScenarioScene {
             // 再描画要求はupdate_textメソッドの中で行われている
             self.scenario_event
                 .update_text(ctx, Some(&mut self.scenario_ctx));
+            self.paste_cutscene_still(ctx);
 
             if self.scenario_event.get_status() == ScenarioEventStatus::StartSchedule
                 && !self.scenario_ctx.builtin_command_inexec
@@ -771,6 +869,23 @@ impl SceneManager for ScenarioScene {
         self.scene_transition_type
     }
 
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if !self.now_paused() {
+            self.pause_screen_set
+                .enter_idle_pause(self.get_current_clock());
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        if self.now_paused() {
+            return false;
+        }
+
+        let t = self.get_current_clock();
+        self.scenario_event.choice_key_event(ctx, key, t)
+    }
+
     fn transition(&self) -> SceneID {
         self.scene_transition
     }
@@ -0,0 +1,339 @@
+use ggez::graphics as ggraphics;
+use rand::RngCore;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::graphics::object::*;
+
+use crate::core::book_fair::FairRequest;
+use crate::core::score_attack::*;
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::{
+    util, FontID, GameResource, SoundID, SuzuContext, TextureID, TileBatchTextureID,
+};
+use crate::object::book_fair_object::BookFairAction;
+use crate::object::effect_object;
+use crate::object::score_attack_object::*;
+use crate::object::util_object::*;
+use crate::scene::*;
+
+use crate::flush_delay_event;
+use crate::log_info;
+
+///
+/// 腕試しの調整値のファイル
+///
+const SCORE_ATTACK_TUNING_PATH: &str = "/score_attack.toml";
+
+///
+/// 作った客の名前と本の題名から、窓口に来る客を一人決める
+///
+fn random_request(resource: &mut GameResource) -> FairRequest {
+    let customer_name = resource.customer_random_select().to_string();
+    FairRequest::new(customer_name, resource.book_random_select().clone())
+}
+
+///
+/// # 腕試し
+/// 題名から始める、物語とは別の遊び方。時間が切れるまで途切れずに来る客へ題名札を渡し、点数を競う
+///
+/// ## counter
+/// 受付台。時間が切れた後はNone
+///
+/// ## leaderboard
+/// セーブデータとは別のファイルに置く番付
+///
+pub struct ScoreAttackScene {
+    background: UniTexture,
+    title_text: UniText,
+    counter: Option<ScoreAttackCounter>,
+    result_panel: Option<ScoreAttackResultPanel>,
+    leaderboard: ScoreAttackLeaderboard,
+    back_button: FramedButton,
+    event_list: DelayEventList<Self>,
+    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    scene_transition: SceneID,
+    scene_transition_type: SceneTransition,
+    clock: Clock,
+}
+
+impl ScoreAttackScene {
+    pub fn new<'a>(ctx: &mut SuzuContext<'a>) -> Self {
+        let mut background = UniTexture::new(
+            ctx.ref_texture(TextureID::Paper1),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+        );
+        background.fit_scale(
+            ctx.context,
+            numeric::Vector2f::new(
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+        );
+
+        let mut title_text = UniText::new(
+            "腕試シ".to_string(),
+            numeric::Point2f::new(0.0, 0.0),
+            numeric::Vector2f::new(1.0, 1.0),
+            0.0,
+            0,
+            FontInformation::new(
+                ctx.resource.get_font(FontID::Cinema),
+                numeric::Vector2f::new(40.0, 40.0),
+                ggraphics::Color::from_rgba_u32(0x3a2e22ff),
+            ),
+        );
+        title_text.make_center(
+            ctx.context,
+            numeric::Point2f::new(crate::core::WINDOW_SIZE_X as f32 / 2.0, 60.0),
+        );
+
+        // 物語の調整値とは混ぜない
+        let tuning = ScoreAttackTuning::from_toml_str(&util::read_from_resources_as_string(
+            ctx.context,
+            SCORE_ATTACK_TUNING_PATH,
+        ));
+
+        // 毎回違う客と本が来るよう、名前と題名を作り直す
+        let procedural_seed = ctx.resource.rng().next_u64();
+        ctx.resource.setup_procedural_catalog(Some(procedural_seed));
+        let session = ScoreAttackSession::new(tuning, || random_request(ctx.resource));
+
+        let mut back_button = FramedButton::create_design1(
+            ctx,
+            numeric::Point2f::new(1150.0, 650.0),
+            "戻ル",
+            numeric::Vector2f::new(28.0, 28.0),
+        );
+        back_button.hide();
+
+        let scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Open,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            0,
+        ));
+
+        let mut event_list = DelayEventList::new();
+        event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition_effect = None;
+            }),
+            31,
+        );
+
+        ScoreAttackScene {
+            background: background,
+            title_text: title_text,
+            counter: Some(ScoreAttackCounter::new(ctx, session)),
+            result_panel: None,
+            leaderboard: ScoreAttackLeaderboard::load(),
+            back_button: back_button,
+            event_list: event_list,
+            scene_transition_effect: scene_transition_effect,
+            scene_transition: SceneID::ScoreAttack,
+            scene_transition_type: SceneTransition::Keep,
+            clock: 0,
+        }
+    }
+
+    ///
+    /// 時間切れ。結果を出し、番付に載る点数なら頭文字を打たせる
+    ///
+    fn close_counter<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let counter = match self.counter.take() {
+            Some(counter) => counter,
+            None => return,
+        };
+
+        let result = counter.finish();
+        log_info!("score attack finished: score {}", result.score);
+
+        let panel = ScoreAttackResultPanel::new(
+            ctx,
+            numeric::Rect::new(283.0, 110.0, 800.0, 560.0),
+            result,
+            &self.leaderboard,
+        );
+        if !panel.is_entering_initials() {
+            self.back_button.appear();
+        }
+        self.result_panel = Some(panel);
+    }
+
+    fn exit_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+            ctx,
+            TileBatchTextureID::Shoji,
+            numeric::Rect::new(
+                0.0,
+                0.0,
+                crate::core::WINDOW_SIZE_X as f32,
+                crate::core::WINDOW_SIZE_Y as f32,
+            ),
+            30,
+            effect_object::SceneTransitionEffectType::Close,
+            effect_object::TilingEffectType::WholeTile,
+            -128,
+            t,
+        ));
+
+        self.event_list.add_event(
+            Box::new(move |slf: &mut Self, _, _| {
+                slf.scene_transition = SceneID::Title;
+                slf.scene_transition_type = SceneTransition::SwapTransition;
+            }),
+            t + 31,
+        );
+    }
+}
+
+impl SceneManager for ScoreAttackScene {
+    fn mouse_motion_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        _: numeric::Vector2f,
+    ) {
+        if self.back_button.is_visible() {
+            self.back_button.mouse_motion_handler(ctx, point);
+        }
+    }
+
+    fn mouse_button_up_event<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        _button: ginput::mouse::MouseButton,
+        point: numeric::Point2f,
+    ) {
+        let t = self.get_current_clock();
+
+        if let Some(counter) = self.counter.as_mut() {
+            let resource = &mut *ctx.resource;
+            match counter.click_handler(point, || random_request(resource)) {
+                BookFairAction::Served(true) => {
+                    ctx.play_sound_as_se(SoundID::SeCustomerBell, None);
+                }
+                BookFairAction::None => return,
+                _ => (),
+            }
+
+            ctx.process_utility.redraw();
+            return;
+        }
+
+        if self.back_button.is_visible() && self.back_button.contains(point) {
+            self.back_button.hide();
+            self.exit_scene(ctx, t);
+        }
+    }
+
+    ///
+    /// 頭文字の入力は、文字入力を受け取るショートカットの一覧の入り口から受け取る
+    ///
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        let panel = match self.result_panel.as_mut() {
+            Some(panel) if panel.is_entering_initials() => panel,
+            _ => return false,
+        };
+
+        match key {
+            PaletteKey::Char(c) => {
+                panel.initials_input(c, &self.leaderboard);
+            }
+            PaletteKey::Backspace => panel.initials_backspace(&self.leaderboard),
+            PaletteKey::Enter => {
+                if panel.commit_initials(&mut self.leaderboard) {
+                    self.back_button.appear();
+                }
+            }
+            _ => return false,
+        }
+
+        ctx.process_utility.redraw();
+        true
+    }
+
+    fn shortcut_palette_is_open(&self) -> bool {
+        self.result_panel
+            .as_ref()
+            .map_or(false, |panel| panel.is_entering_initials())
+    }
+
+    fn pre_process<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let t = self.get_current_clock();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.effect(ctx.context, t);
+            ctx.process_utility.redraw();
+        }
+
+        // 幕が開いてから時間を数える
+        if self.scene_transition_effect.is_none() {
+            if let Some(counter) = self.counter.as_mut() {
+                // 秒が変わったときだけ描き直す
+                if counter.tick() {
+                    ctx.process_utility.redraw();
+                }
+
+                if counter.is_over() {
+                    self.close_counter(ctx);
+                    ctx.process_utility.redraw();
+                }
+            }
+        }
+
+        if flush_delay_event!(self, self.event_list, ctx, self.get_current_clock()) > 0 {
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn drawing_process(&mut self, ctx: &mut ggez::Context) {
+        self.background.draw(ctx).unwrap();
+        self.title_text.draw(ctx).unwrap();
+
+        if let Some(counter) = self.counter.as_mut() {
+            counter.draw(ctx).unwrap();
+        }
+
+        if let Some(panel) = self.result_panel.as_mut() {
+            panel.draw(ctx).unwrap();
+        }
+
+        self.back_button.draw(ctx).unwrap();
+
+        if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
+            transition_effect.draw(ctx).unwrap();
+        }
+    }
+
+    fn post_process<'a>(&mut self, _ctx: &mut SuzuContext<'a>) -> SceneTransition {
+        self.update_current_clock();
+
+        self.scene_transition_type
+    }
+
+    fn transition(&self) -> SceneID {
+        self.scene_transition
+    }
+
+    fn get_current_clock(&self) -> Clock {
+        self.clock
+    }
+
+    fn update_current_clock(&mut self) {
+        self.clock += 1;
+    }
+}
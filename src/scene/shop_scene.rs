@@ -3,6 +3,7 @@ use std::cmp::PartialOrd;
 use std::collections::VecDeque;
 use std::rc::Rc;
 
+use torifune::graphics::object::shape::MeshShape;
 use torifune::graphics::object::*;
 use torifune::manhattan_distance;
 use torifune::{device as tdev, sound::SoundPlayFlags};
@@ -13,20 +14,50 @@ use torifune::core::Updatable;
 use torifune::graphics::drawable::*;
 
 use ggez::input::mouse::MouseButton;
+use rand::Rng;
 use torifune::numeric;
 
 use super::suzuna_scene::suzuna_sub_scene::TaskTutorialContext;
 use super::*;
+use crate::core::ambient_zone::{self, AmbientMixer};
+use crate::core::assistant::{self, AssistantMistake, AssistantRole};
+use crate::core::auto_shelving::{self, AutoShelvingTuning};
+use crate::core::background_sim::{self, BackgroundEvent, BackgroundShopSim};
+use crate::core::crowd_density::ArrivalCurve;
+use crate::core::customer_watchdog::WatchdogAction;
+use crate::core::day_modifier::*;
+use crate::core::decision_log::DecisionKind;
+use crate::core::desk_autosave::DeskAutosave;
+use crate::core::difficulty::{Difficulty, DifficultyTuning};
+use crate::core::economy;
+use crate::core::errand::{self, DeliveryDestination, DeliveryRequest};
+use crate::core::game_system::{
+    count_skipped_customers, priority_insert_index, CustomerPriority, ASSIST_PATIENCE_RATE,
+    QUEUE_FAIRNESS_PENALTY,
+};
+use crate::core::instrument::ProbeCategory;
+use crate::core::kosuzu_comment::{self, CommentTrigger, KosuzuCommentary};
 use crate::core::map_parser as mp;
+use crate::core::positional_audio;
+use crate::core::quicksave;
+use crate::core::reputation::{self, SatisfactionEvent};
+use crate::core::reservation::BookReservation;
+use crate::core::shop_expansion::{self, ShopExpansionType};
+use crate::core::shortcut_palette::{PaletteKey, ShortcutEntry, ShortcutPalette};
+use crate::core::util;
+use crate::core::weather::{self, PuddleSet, UmbrellaStand, Weather};
 use crate::core::{
-    BookInformation, FontID, MouseInformation, ResultReport, SavableData, SuzuContext,
-    TileBatchTextureID,
+    BookInformation, DeskSessionStats, FontID, MistakeRecord, MouseInformation, ResultReport,
+    SavableData, SuzuContext, LATE_NIGHT_FATIGUE_PER_MINUTE, REGULAR_CLOSING_HOUR,
 };
 use crate::flush_delay_event;
 use crate::flush_delay_event_and_redraw_check;
+use crate::instrument;
 use crate::object::effect_object;
+use crate::object::highlight::{Highlight, CALLING_HIGHLIGHT_COLOR};
 use crate::object::map_object::*;
 use crate::object::notify;
+use crate::object::pause::{PauseResult, PauseScreenSet};
 use crate::object::scenario::*;
 use crate::object::shop_object::*;
 use crate::object::task_object::tt_main_component::CustomerRequest;
@@ -35,16 +66,29 @@ use crate::object::util_object::*;
 use crate::object::*;
 use crate::{
     add_delay_event,
-    core::{SoundID, WINDOW_SIZE_X},
+    core::{SoundID, WINDOW_SIZE_X, WINDOW_SIZE_Y},
     object::task_object::tt_main_component::CustomerRequestOrder,
 };
 
 #[allow(unused_imports)]
 use crate::perf_measure;
+use crate::{log_debug, log_info, log_warn};
 
-use effect_object::{SceneTransitionEffectType, TilingEffectType};
+use effect_object::SceneTransitionEffectType;
 use notify::*;
 
+const AUTO_SHELVING_TUNING_PATH: &str = "/auto_shelving.toml";
+
+///
+/// 日が進むにつれて難しくなる調整値のファイル
+///
+const DIFFICULTY_TUNING_PATH: &str = "/difficulty.toml";
+
+///
+/// スティックを倒し切ったときの歩く速さ。マウスで遠くを指したときと同じ
+///
+const STICK_MAX_SPEED_RATE: f32 = 1.5;
+
 struct CharacterGroup {
     group: Vec<CustomerCharacter>,
     drwob_essential: DrawableObjectEssential,
@@ -88,6 +132,69 @@ impl CharacterGroup {
         self.group.retain(|c| !f(c));
     }
 
+    ///
+    /// 通れるかどうかの変わったタイルを通る客に、ルートを計算し直させる
+    ///
+    pub fn replan_routes(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        changed_tiles: &[numeric::Vector2u],
+    ) {
+        for customer in self.group.iter_mut() {
+            customer.replan_route(ctx, map_data, changed_tiles);
+        }
+    }
+
+    ///
+    /// 進まなくなった客を見張る。まずルートを計算し直させ、それでも駄目なら
+    /// 近くの通れるタイルへ移し、最後は店から帰ってもらう
+    ///
+    /// 帰ってもらった客を返す
+    ///
+    pub fn run_watchdog(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        exit: numeric::Vector2u,
+        t: Clock,
+    ) -> Vec<CustomerCharacter> {
+        let mut despawned = Vec::new();
+
+        for index in (0..self.group.len()).rev() {
+            let customer = self.group.get_mut(index).unwrap();
+            match customer.observe_progress(ctx, t) {
+                WatchdogAction::Keep => (),
+                WatchdogAction::Repath => {
+                    if !customer.repath(ctx, map_data, exit, t) {
+                        log_warn!(
+                            "watchdog: repath failed, {}",
+                            customer.stuck_diagnostic(ctx, map_data)
+                        );
+                    }
+                }
+                WatchdogAction::Teleport => {
+                    log_warn!(
+                        "watchdog: teleport, {}",
+                        customer.stuck_diagnostic(ctx, map_data)
+                    );
+                    if customer.teleport_to_open_tile(ctx, map_data) {
+                        customer.repath(ctx, map_data, exit, t);
+                    }
+                }
+                WatchdogAction::Despawn => {
+                    log_warn!(
+                        "watchdog: despawn, {}",
+                        customer.stuck_diagnostic(ctx, map_data)
+                    );
+                    despawned.push(self.group.swap_remove(index));
+                }
+            }
+        }
+
+        despawned
+    }
+
     pub fn sort_by_y_position(&mut self) {
         self.group.sort_by(|a, b| {
             a.get_map_position()
@@ -262,6 +369,36 @@ impl MapData {
             ),
         )
     }
+
+    ///
+    /// predを満たすイベントのうち、fromに最も近いものの発火方法と、そのマスの中心の座標を返す
+    ///
+    pub fn find_nearest_event<F>(
+        &self,
+        from: numeric::Point2f,
+        pred: F,
+    ) -> Option<(EventTrigger, numeric::Point2f)>
+    where
+        F: Fn(&MapEventElement) -> bool,
+    {
+        let tile_size = self.get_tile_size();
+
+        self.event_map
+            .iter_events()
+            .filter(|(_, element)| pred(element))
+            .map(|(position, element)| {
+                let center = numeric::Point2f::new(
+                    (position.x as f32 + 0.5) * tile_size.x,
+                    (position.y as f32 + 0.5) * tile_size.y,
+                );
+                (element.get_trigger_method(), center)
+            })
+            .min_by(|(_, a), (_, b)| {
+                let a_distance = (a.x - from.x).powi(2) + (a.y - from.y).powi(2);
+                let b_distance = (b.x - from.x).powi(2) + (b.y - from.y).powi(2);
+                a_distance.partial_cmp(&b_distance).unwrap()
+            })
+    }
 }
 
 struct MapObjectDrawer<'a> {
@@ -310,12 +447,44 @@ impl CustomerQueue {
         }
     }
 
-    pub fn push_back(&mut self, customer: CustomerCharacter, t: Clock) {
-        self.customer_queue.push_back((customer, t));
+    ///
+    /// 客を列に並ばせ、入った位置を返す。先に通してもらえる客は普通の客より前に入る
+    ///
+    pub fn push_back(&mut self, customer: CustomerCharacter, t: Clock) -> usize {
+        let index = priority_insert_index(&self.priorities(), customer.get_priority());
+        self.customer_queue.insert(index, (customer, t));
+        index
+    }
+
+    ///
+    /// index番目の客を列の先頭に移す
+    ///
+    pub fn move_to_head(&mut self, index: usize) {
+        if let Some(entry) = self.customer_queue.remove(index) {
+            self.customer_queue.push_front(entry);
+        }
+    }
+
+    pub fn priorities(&self) -> Vec<CustomerPriority> {
+        self.customer_queue
+            .iter()
+            .map(|(customer, _)| customer.get_priority())
+            .collect()
+    }
+
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<(CustomerCharacter, Clock)> {
+        self.customer_queue.iter()
     }
 
+    ///
+    /// 先頭の客を列から出す。呼んでいる印はここで外す
+    ///
     pub fn pop_head_customer(&mut self) -> Option<(CustomerCharacter, Clock)> {
-        self.customer_queue.pop_front()
+        let mut head = self.customer_queue.pop_front();
+        if let Some((customer, _)) = head.as_mut() {
+            customer.get_mut_character_object().set_highlight(None);
+        }
+        head
     }
 
     pub fn iter_mut(&mut self) -> std::collections::vec_deque::IterMut<(CustomerCharacter, Clock)> {
@@ -330,23 +499,32 @@ impl CustomerQueue {
         self.customer_queue.len()
     }
 
-    pub fn drain_giveup_customers(&mut self, now: Clock) -> Vec<CustomerCharacter> {
+    ///
+    /// patienceより長く待たされた客を列から外す
+    /// 外した順に、外したときの列の位置と一緒に返す
+    ///
+    pub fn drain_giveup_customers(
+        &mut self,
+        now: Clock,
+        patience: Clock,
+    ) -> Vec<(usize, CustomerCharacter)> {
         let mut giveup_customers = Vec::new();
 
         for index in (0..self.customer_queue.len()).rev() {
             let (_, t) = self.customer_queue.get(index).unwrap();
 
-            if (now - t) > 1200 {
-                let (giveup, _) = self.customer_queue.remove(index).unwrap();
-                giveup_customers.push(giveup);
+            if (now - t) > patience {
+                let (mut giveup, _) = self.customer_queue.remove(index).unwrap();
+                giveup.get_mut_character_object().set_highlight(None);
+                giveup_customers.push((index, giveup));
             }
         }
 
         giveup_customers
     }
 
-    pub fn tail_map_position(&self) -> numeric::Vector2u {
-        numeric::Vector2u::new(5 + self.len() as u32, 14)
+    pub fn tail_map_position(&self, layout: &mp::MapLayout) -> numeric::Vector2u {
+        layout.queue_slot(self.len())
     }
 }
 
@@ -469,6 +647,17 @@ impl GoToCheckCustomers {
         self.customers.len()
     }
 
+    pub fn replan_routes(
+        &mut self,
+        ctx: &mut ggez::Context,
+        map_data: &mp::StageObjectMap,
+        changed_tiles: &[numeric::Vector2u],
+    ) {
+        for customer in self.customers.iter_mut() {
+            customer.replan_route(ctx, map_data, changed_tiles);
+        }
+    }
+
     pub fn go_moving<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
@@ -482,8 +671,8 @@ impl GoToCheckCustomers {
             customer.try_update_move_effect(
                 ctx,
                 map_data,
-                numeric::Vector2u::new(5, 14),
-                numeric::Vector2u::new(15, 14),
+                map_data.get_layout().counter_tile,
+                map_data.get_layout().exit_tile,
                 t,
             );
             customer.get_mut_character_object().update_texture(t);
@@ -510,7 +699,7 @@ impl GoToCheckCustomers {
         let tile_size = map_data.get_tile_drawing_size();
         for customer in self.customers.iter() {
             let map_pos = customer.get_map_position();
-            println!(
+            log_debug!(
                 "goto customer => {}, {}",
                 map_pos.x / tile_size.x,
                 map_pos.y / tile_size.y
@@ -564,6 +753,7 @@ impl DrawableComponent for GoToCheckCustomers {
 pub enum ShopTimeStatus {
     Preparing,
     Opening,
+    LateNight,
     Closing,
     Closed,
 }
@@ -573,6 +763,7 @@ impl ShopTimeStatus {
         match self {
             ShopTimeStatus::Preparing => "仕度中",
             ShopTimeStatus::Opening => "営業中",
+            ShopTimeStatus::LateNight => "夜間営業",
             ShopTimeStatus::Closing => "終業中",
             ShopTimeStatus::Closed => "閉店",
         }
@@ -580,6 +771,25 @@ impl ShopTimeStatus {
     }
 }
 
+///
+/// # ショートカットの一覧から店で選べる操作
+///
+/// ## CallNextCustomer
+/// 受付のマスで調べたときと同じく、並んでいる先頭の客を机に呼ぶ
+///
+/// ## JumpToShelf
+/// プレイヤーに一番近い本棚を調べる
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShopShortcut {
+    Action,
+    CallNextCustomer,
+    ToggleMenu,
+    ShowMap,
+    JumpToShelf,
+    Pause,
+}
+
 struct ShopTutorialList {
     hello: bool,
     go_ret_box: bool,
@@ -664,6 +874,9 @@ impl ShopTutorialList {
 /// ### camera
 /// マップを覗くカメラ
 ///
+/// ### difficulty
+/// 今日の難しさ。客足と、客の頼む冊数や返却期限に効く
+///
 pub struct ShopScene {
     mouse_info: MouseInformation,
     player: PlayableCharacter,
@@ -686,18 +899,46 @@ pub struct ShopScene {
     pause_screen_set: PauseScreenSet,
     transition_status: SceneTransition,
     transition_scene: SceneID,
-    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    scene_transition_effect: Option<Box<dyn effect_object::SceneTransitionEffect>>,
     notification_area: NotificationArea,
     begining_save_data: SavableData,
     drawable_shop_clock: DrawableShopClock,
+    stamina_gauge: StaminaGauge,
+    queue_panel: CustomerQueuePanel,
+    kosuzu_commentary: KosuzuCommentary,
+    kosuzu_comment_balloon: KosuzuCommentBalloon,
+    stick_walking: bool,
     shop_command_palette: ShopCommandPalette,
+    shortcut_palette: Option<ShortcutPalette<ShopShortcut>>,
+    shortcut_palette_window: ShortcutPaletteWindow,
     shop_time_status: ShopTimeStatus,
     shop_time_status_header: EffectableWrap<MovableWrap<UniText>>,
     random_customer_add_timing: Clock,
+    arrival_curve: ArrivalCurve,
+    difficulty: Difficulty,
+    weather: Weather,
+    umbrella_stand: UmbrellaStand,
+    puddles: PuddleSet,
     new_books: Vec<BookInformation>,
     tutorial_list: ShopTutorialList,
     task_tutorial_context: TaskTutorialContext,
     shop_closing_lock: bool,
+    late_night_asked: bool,
+    auto_shelving_asked: bool,
+    expansion_offer: Option<ShopExpansionType>,
+    delivery_offer: Option<DeliveryRequest>,
+    delivery: Option<DeliveryRequest>,
+    parked_shop_map: Option<MapData>,
+    ambient_mixer: AmbientMixer,
+    background_sim: Option<BackgroundShopSim>,
+    shop_return_position: numeric::Point2f,
+    last_auto_handle_minute: u32,
+    practice_requested: bool,
+    desk_restore_requested: bool,
+    served_customer: Option<CustomerCharacter>,
+    reservation_visitors: VecDeque<BookReservation>,
+    #[cfg(feature = "map_editor")]
+    map_event_editor: map_editor::MapEventEditor,
 }
 
 impl ShopScene {
@@ -711,19 +952,20 @@ impl ShopScene {
 
         let camera = Rc::new(RefCell::new(numeric::Rect::new(0.0, 0.0, 1366.0, 768.0)));
 
-        let map_position = numeric::Point2f::new(172.0, 1330.0);
+        let character_group = CharacterGroup::new();
+
+        // 増築していれば、広くなった店のマップを使う
+        let map_id = begining_save_data.shop_expansion.current_map_id(map_id);
+        let mut map = MapData::new(ctx, map_id, camera.clone());
+        map.tile_map.build_collision_map();
 
         let player = PlayableCharacter::new(character_factory::create_character(
             character_factory::CharacterFactoryOrder::PlayableDoremy1,
             ctx,
             &camera.borrow(),
-            map_position,
+            map.tile_map.get_layout().player_spawn,
         ));
-
-        let character_group = CharacterGroup::new();
-
-        let mut map = MapData::new(ctx, map_id, camera.clone());
-        map.tile_map.build_collision_map();
+        let check_point = map.tile_map.get_layout().check_point;
 
         let shop_time = ShopClock::new(8, 0);
         let drawble_shop_clock =
@@ -740,7 +982,7 @@ impl ShopScene {
 
         let mut delay_event_list = DelayEventList::new();
 
-        if ctx.take_save_data().date.first_day()
+        if ctx.today().first_day()
             && ctx.take_save_data().game_mode.is_story_mode()
             && ctx.take_save_data().run_tutorial
         {
@@ -762,6 +1004,168 @@ impl ShopScene {
             );
         }
 
+        // 昨日の一括配架で差し違えた本が見つかり、差し直すことになる
+        let today = ctx.today();
+        let save_data = ctx.take_save_data_mut();
+        let misfiled_books = save_data.misfiled_books.take_corrections(&today);
+        if !misfiled_books.is_empty() {
+            let count = misfiled_books.len();
+            save_data
+                .task_result
+                .not_shelved_books
+                .extend(misfiled_books);
+            delay_event_list.add_event(
+                Box::new(move |slf: &mut ShopScene, ctx, t| {
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            "誤配架".to_string(),
+                            format!("昨日差シ違エタ本ガ{}冊見ツカッタ", count),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                }),
+                75,
+            );
+        }
+
+        // 取り置いた本を受け取りに来る客は、開店後に優先して店へ来る
+        let reservation_visitors: VecDeque<BookReservation> = ctx
+            .take_save_data_mut()
+            .reservation_ledger
+            .take_revisits(&today)
+            .into_iter()
+            .collect();
+        if !reservation_visitors.is_empty() {
+            let count = reservation_visitors.len();
+            delay_event_list.add_event(
+                Box::new(move |slf: &mut ShopScene, ctx, t| {
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            "予約".to_string(),
+                            format!("取リ置キノ本ヲ受ケ取リニ{}人来ル", count),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                }),
+                90,
+            );
+        }
+
+        // 朝の仕度の間に、今日の客足の見込みを知らせる
+        let arrival_curve = ctx.resource.get_todays_arrival_curve(&ctx.today());
+        let busyness = arrival_curve.expected_busyness();
+
+        let difficulty = DifficultyTuning::from_toml_str(&util::read_from_resources_as_string(
+            ctx.context,
+            DIFFICULTY_TUNING_PATH,
+        ))
+        .for_date(&ctx.take_save_data().game_mode, &ctx.today());
+        log_debug!("today's difficulty: {:?}", difficulty);
+
+        let busyness_title = match arrival_curve.get_festival() {
+            Some(festival) => format!("今日ノ客足 {}", festival.name),
+            None => format!("今日ノ客足 {}", busyness.to_str()),
+        };
+        delay_event_list.add_event(
+            Box::new(move |slf: &mut ShopScene, ctx, t| {
+                slf.notification_area.insert_new_contents_generic(
+                    ctx,
+                    NotificationContentsData::new(
+                        busyness_title,
+                        busyness.advice().to_string(),
+                        NotificationType::Time,
+                    ),
+                    t,
+                );
+            }),
+            45,
+        );
+
+        // 雨の日は、客の傘から垂れた雫で床が濡れる
+        let weather = Weather::of_day(&ctx.today());
+        if weather.is_rainy() {
+            delay_event_list.add_event(
+                Box::new(move |slf: &mut ShopScene, ctx, t| {
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            format!("天気 {}", weather.to_str()),
+                            "水溜マリハ近クデ押シテ拭ク".to_string(),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                    slf.kosuzu_comment(ctx, CommentTrigger::RainyDay, t);
+                }),
+                50,
+            );
+        }
+
+        // 評判が上がると、特別な客が来るようになる
+        let reputation = ctx
+            .take_save_data()
+            .suzunaan_status
+            .get_current_reputation();
+        let special_customers = ctx
+            .take_save_data_mut()
+            .special_customers
+            .unlock(reputation);
+        for name in special_customers {
+            log_info!("special customer unlocked: {}", name);
+            delay_event_list.add_event(
+                Box::new(move |slf: &mut ShopScene, ctx, t| {
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            "評判".to_string(),
+                            format!("{}ガ来ルヨウニナッタ", name),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                }),
+                55,
+            );
+        }
+
+        if DeskAutosave::load_for(&ctx.today()).is_some() {
+            delay_event_list.add_event(
+                Box::new(move |slf: &mut ShopScene, ctx, t| {
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            "書キカケ".to_string(),
+                            "受付ヘ行クト書キカケノ頁ヲ戻セル".to_string(),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                }),
+                60,
+            );
+        }
+
+        if ctx.take_save_data().practice_request.is_some() {
+            delay_event_list.add_event(
+                Box::new(move |slf: &mut ShopScene, ctx, t| {
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            "練習".to_string(),
+                            "開店前ニ受付ヘ行クト昨日ノ返却ヲ練習デキル".to_string(),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                }),
+                90,
+            );
+        }
+
         let mut shop_time_status_header = EffectableWrap::new(
             MovableWrap::new(
                 Box::new(UniText::new(
@@ -785,12 +1189,14 @@ impl ShopScene {
             ctx.context,
             numeric::Point2f::new(WINDOW_SIZE_X as f32 / 2.0, 45.0),
         );
+        let stamina = ctx.take_save_data().suzunaan_status.get_stamina();
         //ctx.pay_ad_cost();
 
-        ctx.play_sound_as_bgm(
-            SoundID::ShopBGM,
-            Some(SoundPlayFlags::new(10000, 1.0, true, ctx.config.get_bgm_volume())),
-        );
+        ctx.play_scene_bgm(SceneID::SuzunaShop, 10000);
+
+        let mut shortcut_palette_window =
+            ShortcutPaletteWindow::new(ctx, numeric::Rect::new(383.0, 120.0, 600.0, 330.0), 0);
+        shortcut_palette_window.hide();
 
         ShopScene {
             mouse_info: MouseInformation::new(),
@@ -802,7 +1208,11 @@ impl ShopScene {
             map: map,
             event_list: delay_event_list,
             result_report: result_report,
-            shop_menu: ShopMenuMaster::new(ctx, numeric::Vector2f::new(450.0, 768.0), 0),
+            shop_menu: ShopMenuMaster::new(
+                ctx,
+                numeric::Vector2f::new(ctx.config.get_shop_menu_width(), 768.0),
+                0,
+            ),
             shop_map: MovableWrap::new(
                 Box::new(ShopMapViewer::new(
                     ctx,
@@ -815,7 +1225,7 @@ impl ShopScene {
             shop_map_is_staged: false,
             customer_request_queue: VecDeque::new(),
             customer_queue: CustomerQueue::new(0),
-            goto_check_customers: GoToCheckCustomers::new(numeric::Vector2u::new(15, 4), 0),
+            goto_check_customers: GoToCheckCustomers::new(check_point, 0),
             dark_effect_panel: DarkEffectPanel::new(
                 ctx.context,
                 numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
@@ -838,19 +1248,37 @@ impl ShopScene {
             ),
             begining_save_data: begining_save_data,
             drawable_shop_clock: drawble_shop_clock,
+            stamina_gauge: StaminaGauge::new(
+                ctx,
+                numeric::Point2f::new(30.0, 185.0),
+                stamina,
+                0,
+            ),
+            queue_panel: CustomerQueuePanel::new(ctx, numeric::Point2f::new(30.0, 220.0), 0),
+            kosuzu_commentary: KosuzuCommentary::standard(),
+            kosuzu_comment_balloon: KosuzuCommentBalloon::new(
+                ctx,
+                numeric::Rect::new(250.0, 140.0, 400.0, 200.0),
+            ),
+            stick_walking: false,
             shop_command_palette: ShopCommandPalette::new(
                 ctx,
                 numeric::Rect::new(160.0, 720.0, 1066.0, 100.0),
                 0,
                 0,
             ),
+            shortcut_palette: None,
+            shortcut_palette_window: shortcut_palette_window,
             shop_time_status: ShopTimeStatus::Preparing,
             shop_time_status_header: shop_time_status_header,
-            random_customer_add_timing: ctx
-                .resource
-                .get_todays_customer_dist(&ctx.take_save_data().date),
+            random_customer_add_timing: arrival_curve.get_base(),
+            arrival_curve: arrival_curve,
+            difficulty: difficulty,
+            weather: weather,
+            umbrella_stand: UmbrellaStand::new(),
+            puddles: PuddleSet::new(),
             new_books: new_books,
-            tutorial_list: if ctx.take_save_data().date.first_day()
+            tutorial_list: if ctx.today().first_day()
                 && ctx.take_save_data().game_mode.is_story_mode()
                 && ctx.take_save_data().run_tutorial
             {
@@ -860,6 +1288,22 @@ impl ShopScene {
             },
             task_tutorial_context: task_tutorial,
 	    shop_closing_lock: false,
+            late_night_asked: false,
+            auto_shelving_asked: false,
+            expansion_offer: None,
+            delivery_offer: None,
+            delivery: None,
+            parked_shop_map: None,
+            ambient_mixer: AmbientMixer::new(ambient_zone::AMBIENT_CROSSFADE_FRAMES),
+            background_sim: None,
+            shop_return_position: map_position,
+            last_auto_handle_minute: 0,
+            practice_requested: false,
+            desk_restore_requested: false,
+            served_customer: None,
+            reservation_visitors: reservation_visitors,
+            #[cfg(feature = "map_editor")]
+            map_event_editor: map_editor::MapEventEditor::new(ctx),
         }
     }
 
@@ -933,30 +1377,33 @@ impl ShopScene {
             .update_display_position(camera);
     }
 
+    ///
+    /// カメラが動ける範囲の右下。マップの右端と下端の一列は壁なので映さない
+    ///
+    fn camera_limit(&self) -> numeric::Vector2f {
+        let map_size = self.map.tile_map.get_map_size();
+        let tile_size = self.map.get_tile_size();
+
+        numeric::Vector2f::new(
+            (map_size.x - tile_size.x - WINDOW_SIZE_X as f32).max(0.0),
+            (map_size.y - tile_size.y - WINDOW_SIZE_Y as f32).max(0.0),
+        )
+    }
+
     fn camera_focus_character_x(&mut self) {
         let chara_pos = self.player.get_map_position();
+        let limit = self.camera_limit();
 
-        let x = if chara_pos.x <= 683.0 {
-            0.0
-        } else if chara_pos.x >= 853.0 {
-            170.0
-        } else {
-            chara_pos.x - 683.0
-        };
+        let x = (chara_pos.x - 683.0).max(0.0).min(limit.x);
 
         self.set_camera_x(x);
     }
 
     fn camera_focus_character_y(&mut self) {
         let chara_pos = self.player.get_map_position();
+        let limit = self.camera_limit();
 
-        let y = if chara_pos.y <= 384.0 {
-            0.0
-        } else if chara_pos.y >= 1344.0 {
-            960.0
-        } else {
-            chara_pos.y - 384.0
-        };
+        let y = (chara_pos.y - 384.0).max(0.0).min(limit.y);
 
         self.set_camera_y(y);
     }
@@ -970,7 +1417,10 @@ impl ShopScene {
         tile_map: &mp::StageObjectMap,
         t: Clock,
     ) {
-        let collision_info = tile_map.check_character_collision(ctx, character);
+        let collision_info = instrument!(
+            ProbeCategory::Collision,
+            tile_map.check_character_collision(ctx, character)
+        );
 
         // 衝突していたか？
         if collision_info.collision {
@@ -989,7 +1439,10 @@ impl ShopScene {
         tile_map: &mp::StageObjectMap,
         t: Clock,
     ) {
-        let collision_info = tile_map.check_character_collision(ctx, character);
+        let collision_info = instrument!(
+            ProbeCategory::Collision,
+            tile_map.check_character_collision(ctx, character)
+        );
 
         // 衝突していたか？
         if collision_info.collision {
@@ -1205,11 +1658,21 @@ impl ShopScene {
         self.camera_focus_character_y();
     }
 
+    fn player_terrain_speed_rate(&self, ctx: &mut ggez::Context) -> f32 {
+        let position = self
+            .player
+            .get_character_object()
+            .get_map_position_with_collision_top_offset(ctx);
+        self.map.tile_map.terrain_speed_rate(position)
+    }
+
     fn move_playable_character_x(&mut self, ctx: &mut ggez::Context, t: Clock) {
         // プレイヤーのX方向の移動
+        let speed_rate = self.player_terrain_speed_rate(ctx);
         self.player.move_map_current_speed_x(
             ctx,
             numeric::Vector2f::new(0.0, self.map.tile_map.get_map_size().x),
+            speed_rate,
         );
 
         // マップ座標を更新, これで、衝突判定を行えるようになる
@@ -1220,15 +1683,19 @@ impl ShopScene {
         // マップチップとの衝突判定（横）
         self.playable_check_collision_horizon(ctx);
 
-        // 他キャラクターとの当たり判定
-        self.check_character_collision_x(ctx, t);
+        // 他キャラクターとの当たり判定。店を空けている間は、店の客とは出会わない
+        if !self.is_out_on_errand() {
+            self.check_character_collision_x(ctx, t);
+        }
     }
 
     fn move_playable_character_y(&mut self, ctx: &mut ggez::Context, t: Clock) {
         // プレイヤーのY方向の移動
+        let speed_rate = self.player_terrain_speed_rate(ctx);
         self.player.move_map_current_speed_y(
             ctx,
             numeric::Vector2f::new(0.0, self.map.tile_map.get_map_size().y),
+            speed_rate,
         );
         // マップ座標を更新, これで、衝突判定を行えるようになる
         self.player
@@ -1238,8 +1705,10 @@ impl ShopScene {
         // マップチップとの衝突判定（縦）
         self.playable_check_collision_vertical(ctx);
 
-        // 他キャラクターとの当たり判定
-        self.check_character_collision_y(ctx, t);
+        // 他キャラクターとの当たり判定。店を空けている間は、店の客とは出会わない
+        if !self.is_out_on_errand() {
+            self.check_character_collision_y(ctx, t);
+        }
     }
 
     fn move_playable_character(&mut self, ctx: &mut ggez::Context, t: Clock) {
@@ -1250,6 +1719,20 @@ impl ShopScene {
         self.move_playable_character_y(ctx, t);
     }
 
+    ///
+    /// 走ったり本を抱えて歩いたりした分だけ体力を減らし、ゲージに反映する
+    ///
+    fn drain_stamina_by_moving<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let speed = self.player.get_speed();
+        let status = &mut ctx.take_save_data_mut().suzunaan_status;
+
+        status.drain_stamina_by_moving(
+            (speed.x.powf(2.0) + speed.y.powf(2.0)).sqrt(),
+            self.player.get_shelving_book().len(),
+        );
+        self.stamina_gauge.update_stamina(status.get_stamina());
+    }
+
     pub fn command_palette_go_register_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         self.check_event_panel_onmap(ctx, EventTrigger::Action);
     }
@@ -1315,27 +1798,50 @@ impl ShopScene {
                     );
                 }
             }
+            BuiltinEventSymbol::GoErrand => {
+                if self.delivery.is_some() && !self.is_out_on_errand() {
+                    self.go_out_on_errand(ctx, self.get_current_clock());
+                }
+            }
+            BuiltinEventSymbol::ReturnShop => {
+                if self.is_out_on_errand() {
+                    self.return_from_errand(ctx, self.get_current_clock());
+                }
+            }
+            BuiltinEventSymbol::DeliverBook => {
+                self.try_deliver_book(ctx, self.get_current_clock());
+            }
         }
     }
 
-    fn scene_transition_close_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
-        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+    fn scene_transition_close_effect<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        kind: effect_object::SceneTransitionEffectKind,
+        t: Clock,
+    ) {
+        self.scene_transition_effect = Some(effect_object::new_scene_transition_effect(
             ctx,
-            TileBatchTextureID::Shoji,
-            numeric::Rect::new(
-                0.0,
-                0.0,
-                crate::core::WINDOW_SIZE_X as f32,
-                crate::core::WINDOW_SIZE_Y as f32,
-            ),
+            kind,
             60,
             SceneTransitionEffectType::Close,
-            TilingEffectType::WholeTile,
             -128,
             t,
         ));
     }
 
+    ///
+    /// 店から出ていくときは、プレイヤーを中心に円形に閉じる
+    ///
+    fn player_iris_kind(
+        &self,
+        ctx: &mut ggez::Context,
+    ) -> effect_object::SceneTransitionEffectKind {
+        effect_object::SceneTransitionEffectKind::CircleIris(
+            self.player.get_character_object().obj().get_center(ctx),
+        )
+    }
+
     fn run_event_panel_onmap_at<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
@@ -1350,37 +1856,72 @@ impl ShopScene {
         if let Some(event_element) = target_event {
             match event_element {
                 MapEventElement::TextEvent(_text) => {
-                    println!("Unsuported");
+                    log_warn!("text event is not supported");
                 }
                 MapEventElement::SwitchScene(switch_scene) => {
-                    if !self.customer_request_queue.is_empty() && !self.customer_queue.is_empty() {
+                    if switch_scene.get_switch_scene_id() == SceneID::MainDesk
+                        && self.customer_queue.is_empty()
+                        && (self.desk_restore_is_available(ctx) || self.practice_is_available(ctx))
+                    {
+                        // 書きかけの頁が残っていれば、練習より先にそちらを片付ける
+                        if self.desk_restore_is_available(ctx) {
+                            self.desk_restore_requested = true;
+                        } else {
+                            self.practice_requested = true;
+                        }
+                        self.event_list.add_event(
+                            Box::new(move |slf: &mut ShopScene, _, _| {
+                                slf.transition_status = SceneTransition::StackingTransition;
+                                slf.transition_scene = SceneID::MainDesk;
+                            }),
+                            t + 31,
+                        );
+                        self.scene_transition_close_effect(
+                            ctx,
+                            effect_object::SceneTransitionEffectKind::PageSlide,
+                            t,
+                        );
+                    } else if !self.customer_request_queue.is_empty()
+                        && !self.customer_queue.is_empty()
+                    {
                         let switch_scene_id = switch_scene.get_switch_scene_id();
 
                         self.event_list.add_event(
                             Box::new(move |slf: &mut ShopScene, ctx, _| {
-                                let mut customer = if let Some((customer, _)) =
+                                let mut customer = if let Some((customer, queued)) =
                                     slf.customer_queue.pop_head_customer()
                                 {
+                                    ctx.take_save_data_mut().suzunaan_status.apply_satisfaction(
+                                        SatisfactionEvent::Served {
+                                            waiting: slf.get_current_clock() - queued,
+                                        },
+                                    );
                                     customer
                                 } else {
                                     return;
                                 };
-
-                                customer.get_out_shop(
-                                    ctx.context,
-                                    &slf.map.tile_map,
-                                    numeric::Vector2u::new(15, 14),
-                                    t,
-                                );
+                                slf.update_queue_panel(ctx);
 
                                 slf.goto_check_customers.reset_each_customers_goal(
                                     ctx,
                                     &slf.map.tile_map,
-                                    slf.customer_queue.tail_map_position(),
+                                    slf.customer_queue
+                                        .tail_map_position(slf.map.tile_map.get_layout()),
                                     t,
                                 );
 
-                                slf.character_group.add(customer);
+                                if switch_scene_id == SceneID::MainDesk {
+                                    // 接客が終わって戻ってくるまで、店を出ていかない
+                                    slf.served_customer = Some(customer);
+                                } else {
+                                    customer.get_out_shop(
+                                        ctx.context,
+                                        &slf.map.tile_map,
+                                        slf.map.tile_map.get_layout().exit_tile,
+                                        t,
+                                    );
+                                    slf.character_group.add(customer);
+                                }
 
                                 slf.transition_status = SceneTransition::StackingTransition;
                                 slf.transition_scene = switch_scene_id;
@@ -1396,7 +1937,12 @@ impl ShopScene {
                         );
 
 			self.shop_closing_lock = true;
-                        self.scene_transition_close_effect(ctx, t);
+                        let kind = if switch_scene_id == SceneID::MainDesk {
+                            effect_object::SceneTransitionEffectKind::PageSlide
+                        } else {
+                            self.player_iris_kind(ctx.context)
+                        };
+                        self.scene_transition_close_effect(ctx, kind, t);
                     }
                 }
                 MapEventElement::BookStoreEvent(book_store_event) => {
@@ -1496,8 +2042,12 @@ impl ShopScene {
         }
     }
 
-    pub fn start_mouse_move(&mut self, ctx: &mut ggez::Context, point: numeric::Point2f) {
-        let current = self.player.get_character_object().obj().get_center(ctx);
+    pub fn start_mouse_move<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) {
+        let current = self
+            .player
+            .get_character_object()
+            .obj()
+            .get_center(ctx.context);
 
         let offset = numeric::Point2f::new(point.x - current.x, point.y - current.y);
 
@@ -1508,6 +2058,27 @@ impl ShopScene {
         let d = (offset.x.powf(2.0) + offset.y.powf(2.0)).sqrt();
         let speed_k = if d > 300.0 { 300.0 } else { d } / 200.0;
 
+        self.start_move_toward(ctx, offset, speed_k);
+    }
+
+    ///
+    /// パッドのスティックを倒した向きへ歩く。倒した分だけ速くなる
+    ///
+    fn start_stick_move<'a>(&mut self, ctx: &mut SuzuContext<'a>, direction: numeric::Vector2f) {
+        let d = (direction.x.powf(2.0) + direction.y.powf(2.0)).sqrt();
+        self.start_move_toward(
+            ctx,
+            numeric::Point2f::new(direction.x, direction.y),
+            d * STICK_MAX_SPEED_RATE,
+        );
+    }
+
+    fn start_move_toward<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        offset: numeric::Point2f,
+        speed_k: f32,
+    ) {
         let rad = if offset.x >= 0.0 {
             if offset.y >= 0.0 {
                 (offset.y / offset.x).atan()
@@ -1517,41 +2088,141 @@ impl ShopScene {
         } else {
             (offset.y / offset.x).atan() + 180.0_f32.to_radians()
         };
+        // 疲れていると歩みが遅くなる
+        let speed_k = speed_k * ctx.take_save_data().suzunaan_status.fatigue_speed_rate();
         let speed = numeric::Vector2f::new(rad.cos() * 4.0 * speed_k, rad.sin() * 4.0 * speed_k);
 
         self.player.set_speed(speed);
         self.update_playable_character_texture(rad);
     }
 
-    pub fn switched_and_restart<'a>(
-        &mut self,
-        ctx: &mut SuzuContext<'a>,
-        elapsed_clock: Clock,
-        condition_eval_report: Option<BookConditionEvalReport>,
-        task_tutorial: TaskTutorialContext,
-    ) {
+    ///
+    /// 机に向かっている間も、店の時を進めて客の出入りを追う
+    /// 急ぎの出来事は通知で知らせる
+    ///
+    pub fn background_tick<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.update_current_clock();
         let t = self.get_current_clock();
-        let animation_time = 30;
 
-        self.transition_scene = SceneID::SuzunaShop;
+        if self.background_sim.is_none() {
+            let patience = self.customer_patience(ctx);
+            self.background_sim = Some(BackgroundShopSim::new(
+                self.shop_clock.total_minutes(),
+                patience,
+            ));
+        }
 
-        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
-            ctx,
-            TileBatchTextureID::Shoji,
-            numeric::Rect::new(
-                0.0,
-                0.0,
-                crate::core::WINDOW_SIZE_X as f32,
-                crate::core::WINDOW_SIZE_Y as f32,
-            ),
+        let arrival = self.tutorial_is_done()
+            && self.character_group.len()
+                + self.background_sim.as_ref().unwrap().pending_arrivals()
+                < 6
+            && match self.shop_time_status {
+                ShopTimeStatus::Opening => true,
+                // 夜は客足が遠のく
+                ShopTimeStatus::LateNight => ctx.resource.rng().below(3) == 0,
+                _ => false,
+            }
+            && background_sim::roll_arrival(
+                ctx.resource.rng(),
+                self.difficulty.scale_add_timing(
+                    self.arrival_curve
+                        .add_timing(self.background_sim.as_ref().unwrap().current_hour()),
+                ),
+            );
+
+        let sim = self.background_sim.as_mut().unwrap();
+        let mut notifications = Vec::new();
+
+        for event in sim.tick(arrival) {
+            match event {
+                BackgroundEvent::CustomerArrived => {
+                    // 机に向かっている間は客の姿が見えないので、鈴の音で知らせる
+                    let volume = ctx.config.get_se_volume();
+                    ctx.play_sound_as_se(
+                        SoundID::SeCustomerBell,
+                        Some(SoundPlayFlags::new(10, 1.0, false, volume)),
+                    );
+                    notifications.push((
+                        "おしらせ",
+                        "御客ガ来マシタ".to_string(),
+                        NotificationType::CustomerCalling,
+                    ));
+                }
+                BackgroundEvent::ArrivalGaveUp => notifications.push((
+                    "おしらせ",
+                    "待チキレズ帰ッタ御客ガイマス".to_string(),
+                    NotificationType::CustomerCalling,
+                )),
+                BackgroundEvent::HourChimed(hour) => notifications.push((
+                    "時ノ鐘",
+                    format!("{}時ニナリマシタ", hour),
+                    NotificationType::Time,
+                )),
+            }
+        }
+
+        for (customer, queued_at) in self.customer_queue.iter() {
+            if sim.check_restless(*queued_at, t) {
+                notifications.push((
+                    "おしらせ",
+                    format!("{}サンガ待チクタビレテイマス", customer.get_customer_name()),
+                    NotificationType::CustomerCalling,
+                ));
+            }
+        }
+
+        let queue_len = self.customer_queue.len();
+        self.check_waiting_customer_giveup(ctx, t);
+        if self.customer_queue.len() < queue_len {
+            notifications.push((
+                "おしらせ",
+                "列ニ並ンデイタ御客ガ帰リマシタ".to_string(),
+                NotificationType::CustomerCalling,
+            ));
+        }
+
+        for (header, message, notification_type) in notifications {
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(header.to_string(), message, notification_type),
+                t,
+            );
+        }
+
+        self.notification_area.update(ctx, t);
+    }
+
+    ///
+    /// 机の画面の上に、店からの通知を重ねて描く
+    ///
+    pub fn draw_background_notification(&mut self, ctx: &mut ggez::Context) {
+        self.notification_area.draw(ctx).unwrap();
+    }
+
+    pub fn switched_and_restart<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        elapsed_clock: Clock,
+        condition_eval_report: Option<BookConditionEvalReport>,
+        task_tutorial: TaskTutorialContext,
+    ) {
+        let t = self.get_current_clock();
+        let animation_time = 30;
+
+        self.transition_scene = SceneID::SuzunaShop;
+
+        // 机から戻るときは、入るときと逆向きに頁を滑らせる
+        self.scene_transition_effect = Some(effect_object::new_scene_transition_effect(
+            ctx,
+            effect_object::SceneTransitionEffectKind::PageSlide,
             animation_time,
             SceneTransitionEffectType::Open,
-            TilingEffectType::WholeTile,
             -128,
             t,
         ));
 
-        self.shop_clock.add_minute((elapsed_clock / 360) as u8);
+        self.shop_clock
+            .add_minute((elapsed_clock / background_sim::DESK_FRAMES_PER_SHOP_MINUTE) as u8);
         if let Some(report) = condition_eval_report {
             self.result_report
                 .add_condition_eval_mistakes(report.count_mistake());
@@ -1559,6 +2230,41 @@ impl ShopScene {
 
         self.task_tutorial_context = task_tutorial;
 
+        // 予約されていた本が返ってきていれば、取り置くように知らせる
+        let reminders = ctx.take_save_data_mut().reservation_ledger.take_reminders();
+        for reservation in reminders {
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "取リ置キ".to_string(),
+                    format!(
+                        "{}ヲ{}サンノ為ニ取リ置ク",
+                        reservation.book_title, reservation.customer_name
+                    ),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+        }
+
+        // 机に向かっている間に戸口まで来ていた客を、店に入れる
+        if let Some(mut sim) = self.background_sim.take() {
+            for _ in 0..sim.take_arrivals() {
+                self.insert_goto_check_customer(ctx, t);
+            }
+        }
+
+        // 接客を終えた客を出口まで歩かせる。出口に着いた時点で消える
+        if let Some(mut customer) = self.served_customer.take() {
+            customer.get_out_shop(
+                ctx.context,
+                &self.map.tile_map,
+                self.map.tile_map.get_layout().exit_tile,
+                t,
+            );
+            self.character_group.add(customer);
+        }
+
         // self.event_list.add_event(
         //     Box::new(move |slf: &mut ShopScene, _, _| { slf.scene_transition_effect = None; }),
         //     animation_time + 1
@@ -1576,6 +2282,13 @@ impl ShopScene {
             .hide_shelving_select_ui(self.get_current_clock());
         if let Some((boxed, shelving)) = select_result {
             ctx.take_save_data_mut().task_result.not_shelved_books = boxed;
+            if shelving
+                .iter()
+                .any(|book| book.name.chars().count() >= kosuzu_comment::LONG_TITLE_CHARS)
+            {
+                let t = self.get_current_clock();
+                self.kosuzu_comment(ctx, CommentTrigger::LongBookTitle, t);
+            }
             self.player.update_shelving_book(shelving);
             self.shop_menu
                 .update_contents(ctx, self.player.get_shelving_book());
@@ -1633,6 +2346,49 @@ impl ShopScene {
             self.shop_clock.add_minute(1);
             self.drawable_shop_clock.update_time(&self.shop_clock);
 
+            let save_data = ctx.take_save_data_mut();
+            save_data
+                .day_modifiers
+                .update_minute(self.shop_clock.total_minutes());
+
+            // 昼食の効果で体力が少しずつ戻る
+            if save_data
+                .day_modifiers
+                .is_active(DayModifierType::StaminaRegen)
+            {
+                save_data
+                    .suzunaan_status
+                    .restore_stamina(STAMINA_REGEN_PER_MINUTE);
+                self.stamina_gauge
+                    .update_stamina(save_data.suzunaan_status.get_stamina());
+            }
+
+            // 夜間営業の間は疲労が溜まっていく
+            let status = &mut save_data.suzunaan_status;
+            if status.is_late_night() && self.shop_clock.is_past(REGULAR_CLOSING_HOUR, 0) {
+                status.add_fatigue(LATE_NIGHT_FATIGUE_PER_MINUTE);
+                self.stamina_gauge.update_stamina(status.get_stamina());
+                self.result_report.add_late_night_minute();
+            }
+
+            if self.shop_clock.equals(10, 0) {
+                self.try_offer_shop_expansion(ctx, t);
+                self.try_unlock_assistant(ctx, t);
+            }
+
+            if self.shop_clock.total_minutes() % assistant::ASSISTANT_ACTION_INTERVAL == 0 {
+                self.run_assistant(ctx, t);
+            }
+
+            if self.shop_clock.total_minutes() % 60 == 0 {
+                self.try_offer_delivery(ctx, t);
+                self.kosuzu_comment(ctx, CommentTrigger::HourChimed, t);
+
+                if ctx.preferences.get_autosave_frequency().saves_on_hour() {
+                    quicksave::autosave(ctx.take_save_data());
+                }
+            }
+
             if self.shop_clock.equals(12, 0) {
                 self.notification_area.insert_new_contents_generic(
                     ctx,
@@ -1643,6 +2399,12 @@ impl ShopScene {
                     ),
                     t,
                 );
+
+                // 昼食の献立を選んでもらう
+                if self.tutorial_is_done() && self.map.scenario_event.is_none() {
+                    self.set_fixed_text_into_scenario_box(ctx, "/scenario/lunch.toml", t);
+                    self.dark_effect_panel_shallow.new_effect(8, t, 0, 200);
+                }
             }
 
             ctx.process_utility.redraw();
@@ -1657,58 +2419,268 @@ impl ShopScene {
         self.result_report.clone()
     }
 
+    pub fn add_mistake_record(&mut self, record: MistakeRecord) {
+        self.result_report.add_mistake_record(record);
+    }
+
+    ///
+    /// 受付で練習を始めることになっていればtrueを返し、要求を取り下げる
+    ///
+    pub fn take_practice_request(&mut self) -> bool {
+        let requested = self.practice_requested;
+        self.practice_requested = false;
+        requested
+    }
+
+    fn shortcut_entries(&self) -> Vec<ShortcutEntry<ShopShortcut>> {
+        let mut entries = vec![ShortcutEntry::new(
+            ShopShortcut::Action,
+            "目の前を調べる",
+            "めのまえをしらべる",
+        )];
+
+        if !self.customer_queue.is_empty() {
+            entries.push(ShortcutEntry::new(
+                ShopShortcut::CallNextCustomer,
+                "次の客を呼ぶ",
+                "つぎのきゃくをよぶ",
+            ));
+        }
+
+        entries.push(ShortcutEntry::new(
+            ShopShortcut::ToggleMenu,
+            "メニュー",
+            "めにゅー",
+        ));
+        entries.push(ShortcutEntry::new(ShopShortcut::ShowMap, "地図", "ちず"));
+        entries.push(ShortcutEntry::new(
+            ShopShortcut::JumpToShelf,
+            "近くの本棚",
+            "ちかくのほんだな",
+        ));
+        entries.push(ShortcutEntry::new(
+            ShopShortcut::Pause,
+            "休憩",
+            "きゅうけい",
+        ));
+
+        entries
+    }
+
+    fn update_shortcut_palette_window<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if let Some(palette) = self.shortcut_palette.as_ref() {
+            let labels = palette
+                .matched_entries()
+                .iter()
+                .map(|entry| entry.label.clone())
+                .collect();
+            self.shortcut_palette_window.update_contents(
+                palette.get_query(),
+                labels,
+                palette.get_selected_index(),
+            );
+        }
+
+        ctx.process_utility.redraw();
+    }
+
+    fn open_shortcut_palette<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.player.reset_speed();
+        self.shortcut_palette = Some(ShortcutPalette::new(self.shortcut_entries()));
+        self.shortcut_palette_window.appear();
+        self.update_shortcut_palette_window(ctx);
+    }
+
+    fn close_shortcut_palette<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.shortcut_palette = None;
+        self.shortcut_palette_window.hide();
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 一覧で選ばれた操作を、ボタンやマップ上のイベントと同じ処理で実行する
+    ///
+    fn run_shop_shortcut<'a>(&mut self, ctx: &mut SuzuContext<'a>, shortcut: ShopShortcut) {
+        match shortcut {
+            ShopShortcut::Action => self.command_palette_handler(ctx, CommandPaletteFunc::Action),
+            ShopShortcut::ToggleMenu => {
+                self.command_palette_handler(ctx, CommandPaletteFunc::ShowShopMenu)
+            }
+            ShopShortcut::ShowMap => self.command_palette_handler(ctx, CommandPaletteFunc::ShowMap),
+            ShopShortcut::Pause => self.command_palette_handler(ctx, CommandPaletteFunc::Pause),
+            ShopShortcut::CallNextCustomer => {
+                self.run_nearest_event_onmap(ctx, |element| match element {
+                    MapEventElement::SwitchScene(switch_scene) => {
+                        switch_scene.get_switch_scene_id() == SceneID::MainDesk
+                    }
+                    _ => false,
+                });
+            }
+            ShopShortcut::JumpToShelf => {
+                self.run_nearest_event_onmap(ctx, |element| match element {
+                    MapEventElement::BookStoreEvent(_) => true,
+                    _ => false,
+                });
+            }
+        }
+    }
+
+    fn run_nearest_event_onmap<'a, F>(&mut self, ctx: &mut SuzuContext<'a>, pred: F)
+    where
+        F: Fn(&MapEventElement) -> bool,
+    {
+        let player_position = self.player.get_center_map_position(ctx.context);
+
+        if let Some((trigger, map_position)) = self.map.find_nearest_event(player_position, pred) {
+            let _ = self.run_event_panel_onmap_at(ctx, trigger, map_position);
+        }
+    }
+
+    fn update_queue_panel<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let entries: Vec<(String, CustomerPriority)> = self
+            .customer_queue
+            .iter()
+            .map(|(customer, _)| {
+                (
+                    customer.get_customer_name().to_string(),
+                    customer.get_priority(),
+                )
+            })
+            .collect();
+
+        self.queue_panel.update_entries(&entries);
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 待ち列のindex番目の客を、次に受付へ呼ぶ客にする
+    /// 先に並んでいた客を追い越させた分だけ、評判が下がる
+    ///
+    fn call_customer_out_of_order<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        index: usize,
+        t: Clock,
+    ) {
+        if index == 0 || index >= self.customer_queue.len() {
+            return;
+        }
+
+        let skipped = count_skipped_customers(&self.customer_queue.priorities(), index);
+
+        self.customer_queue.move_to_head(index);
+        if let Some(request) = self.customer_request_queue.remove(index) {
+            self.customer_request_queue.push_front(request);
+        }
+        self.update_queue_panel(ctx);
+
+        if skipped > 0 {
+            ctx.take_save_data_mut()
+                .suzunaan_status
+                .add_reputation(-QUEUE_FAIRNESS_PENALTY * skipped as f32);
+
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "順番".to_string(),
+                    "追イ越サレタ客ガ不満サウダ".to_string(),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+        }
+    }
+
+    ///
+    /// 書きかけの頁を戻すために机を開くことになっていればtrueを返し、要求を取り下げる
+    ///
+    pub fn take_desk_restore_request(&mut self) -> bool {
+        let requested = self.desk_restore_requested;
+        self.desk_restore_requested = false;
+        requested
+    }
+
+    ///
+    /// 今日の受付の途中で落ちて、書きかけの頁が残っているか
+    ///
+    fn desk_restore_is_available<'a>(&self, ctx: &mut SuzuContext<'a>) -> bool {
+        self.tutorial_is_done() && DeskAutosave::load_for(&ctx.today()).is_some()
+    }
+
+    ///
+    /// 開店前に、前日に見誤った返却受付の練習ができるか
+    ///
+    fn practice_is_available<'a>(&self, ctx: &mut SuzuContext<'a>) -> bool {
+        self.shop_time_status == ShopTimeStatus::Preparing
+            && self.tutorial_is_done()
+            && ctx.take_save_data().practice_request.is_some()
+    }
+
+    pub fn update_desk_stats(&mut self, stats: DeskSessionStats) {
+        self.result_report.update_desk_stats(stats);
+    }
+
     ///
     /// # 再描画要求有り
     ///
     pub fn check_shop_clock_regular<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         if self.shop_time_status == ShopTimeStatus::Preparing && self.shop_clock.is_past(9, 0) {
-            self.shop_time_status = ShopTimeStatus::Opening;
-            self.shop_time_status_header.make_center(
-                ctx.context,
-                numeric::Point2f::new(WINDOW_SIZE_X as f32 / 2.0, 45.0),
-            );
-            self.shop_time_status_header.clear_effect();
-            self.shop_time_status_header.add_effect(vec![
-                effect::alpha_effect(20, t, 255, 0),
-                effect::alpha_effect(20, t + 20, 0, 255),
-            ]);
-            add_delay_event!(
-                self.event_list,
-                |slf, _ctx, _t| {
-                    slf.shop_time_status_header
-                        .replace_text(slf.shop_time_status.to_string());
-                },
-                t + 20
-            );
+            self.change_shop_time_status(ctx, ShopTimeStatus::Opening, t);
         }
 
         if self.shop_time_status == ShopTimeStatus::Opening && self.shop_clock.is_past(17, 0) {
 	    if self.shop_closing_lock {
 		return;
 	    }
-	    
-            self.shop_time_status = ShopTimeStatus::Closing;
-            self.shop_time_status_header.make_center(
-                ctx.context,
-                numeric::Point2f::new(WINDOW_SIZE_X as f32 / 2.0, 45.0),
-            );
-            self.shop_time_status_header.clear_effect();
-            self.shop_time_status_header.add_effect(vec![
-                effect::alpha_effect(20, t, 255, 0),
-                effect::alpha_effect(20, t + 20, 0, 255),
-            ]);
-            add_delay_event!(
-                self.event_list,
-                |slf, _ctx, _t| {
-                    slf.shop_time_status_header
-                        .replace_text(slf.shop_time_status.to_string());
-                },
-                t + 20
-            );
-            self.get_out_all_customers(ctx, t);
+
+            // 夜間営業をするか決めてもらう
+            if self.tutorial_is_done() && !self.late_night_asked {
+                self.late_night_asked = true;
+                self.set_fixed_text_into_scenario_box(ctx, "/scenario/late_night.toml", t);
+                self.dark_effect_panel_shallow.new_effect(8, t, 0, 200);
+                return;
+            }
+
+            if self.map.scenario_event.is_none() {
+                self.start_closing(ctx, t);
+            }
+        }
+
+        let closing_hour = ctx.take_save_data().suzunaan_status.closing_hour();
+
+        if self.shop_time_status == ShopTimeStatus::LateNight
+            && self.shop_clock.is_past(closing_hour - 1, 0)
+        {
+            if self.shop_closing_lock {
+                return;
+            }
+
+            self.start_closing(ctx, t);
         }
 
-        if self.shop_time_status == ShopTimeStatus::Closing && self.shop_clock.is_past(18, 0) {
+        if self.shop_time_status == ShopTimeStatus::Closing
+            && self.shop_clock.is_past(closing_hour, 0)
+        {
+            // 配架が終わらなかったときは、まとめて棚に戻すか決めてもらう
+            if self.auto_shelving_asked && self.map.scenario_event.is_some() {
+                return;
+            }
+
+            if self.tutorial_is_done()
+                && !self.auto_shelving_asked
+                && self.map.scenario_event.is_none()
+                && !ctx
+                    .take_save_data()
+                    .task_result
+                    .not_shelved_books
+                    .is_empty()
+            {
+                self.auto_shelving_asked = true;
+                self.set_fixed_text_into_scenario_box(ctx, "/scenario/auto_shelving.toml", t);
+                self.dark_effect_panel_shallow.new_effect(8, t, 0, 200);
+                return;
+            }
+
             self.event_list.add_event(
                 Box::new(move |slf: &mut Self, ctx, _| {
                     // reportに未配架の本のIDをメモする
@@ -1717,34 +2689,911 @@ impl ShopScene {
                             .add_yet_shelved_book_id(book_info.get_unique_id());
                     }
 
-                    slf.transition_status = SceneTransition::SwapTransition;
-                    slf.transition_scene = SceneID::DayResult;
+                    slf.transition_status = SceneTransition::SwapTransition;
+                    slf.transition_scene = SceneID::DayResult;
+
+                    ctx.resource.stop_scene_bgm(ctx.context, SceneID::SuzunaShop);
+                    ctx.resource.stop_ambient_sound(ctx.context);
+                }),
+                t + 120,
+            );
+
+            let kind = self.player_iris_kind(ctx.context);
+            self.scene_transition_close_effect(ctx, kind, t);
+            self.shop_time_status = ShopTimeStatus::Closed;
+        }
+    }
+
+    fn change_shop_time_status<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        status: ShopTimeStatus,
+        t: Clock,
+    ) {
+        self.shop_time_status = status;
+        self.shop_time_status_header.make_center(
+            ctx.context,
+            numeric::Point2f::new(WINDOW_SIZE_X as f32 / 2.0, 45.0),
+        );
+        self.shop_time_status_header.clear_effect();
+        self.shop_time_status_header.add_effect(vec![
+            effect::alpha_effect(20, t, 255, 0),
+            effect::alpha_effect(20, t + 20, 0, 255),
+        ]);
+        add_delay_event!(
+            self.event_list,
+            |slf, _ctx, _t| {
+                slf.shop_time_status_header
+                    .replace_text(slf.shop_time_status.to_string());
+            },
+            t + 20
+        );
+    }
+
+    fn start_closing<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.change_shop_time_status(ctx, ShopTimeStatus::Closing, t);
+        self.get_out_all_customers(ctx, t);
+
+        // 届けられなかった配達は、先方の信用を失う
+        if let Some(delivery) = self.delivery.take() {
+            let outcome = delivery.abandoned_outcome();
+            ctx.take_save_data_mut()
+                .suzunaan_status
+                .add_reputation(outcome.reputation);
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "配達".to_string(),
+                    format!("{}ヘ届ケラレナカッタ", delivery.destination.to_str()),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+        }
+
+        // 直されなかった手伝いの失敗は、店を閉めるときに評判に響く
+        let save_data = ctx.take_save_data_mut();
+        let penalty = save_data.assistant.close_day();
+        if penalty < 0.0 {
+            save_data.suzunaan_status.add_reputation(penalty);
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "手伝イ".to_string(),
+                    "直サレナカッタ失敗ガアリマシタ".to_string(),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+        }
+    }
+
+    fn start_late_night<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        ctx.take_save_data_mut().suzunaan_status.start_late_night();
+        self.change_shop_time_status(ctx, ShopTimeStatus::LateNight, t);
+    }
+
+    ///
+    /// 残った未配架の本をまとめて棚に戻す。すぐに終わるが、何冊かは差し違えて翌日に差し直すことになる
+    ///
+    fn auto_shelve_remaining_books<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let tuning = AutoShelvingTuning::from_toml_str(&util::read_from_resources_as_string(
+            ctx.context,
+            AUTO_SHELVING_TUNING_PATH,
+        ));
+
+        let save_data = ctx.take_save_data_mut();
+        let today = save_data.date;
+        let misfile_rate = tuning.misfile_rate(
+            &save_data.game_mode,
+            save_data.difficulty_assist.is_enabled(),
+        );
+
+        let books: Vec<BookInformation> =
+            save_data.task_result.not_shelved_books.drain(..).collect();
+        let (shelved, misfiled) =
+            auto_shelving::split_misfiled(books, misfile_rate, &mut auto_shelving::day_rng(&today));
+        let shelved_count = shelved.len();
+        let misfiled_count = misfiled.len();
+        log_debug!(
+            "auto shelving: {} shelved, {} misfiled (rate {})",
+            shelved_count,
+            misfiled_count,
+            misfile_rate
+        );
+
+        save_data.misfiled_books.record(misfiled, &today);
+        self.update_task_result(ctx);
+
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new(
+                "一括配架".to_string(),
+                format!("{}冊ヲ棚ニ戻シタ", shelved_count + misfiled_count),
+                NotificationType::Time,
+            ),
+            t,
+        );
+    }
+
+    ///
+    /// 昼食を取って体力を戻し、献立に応じた効果を夕方まで付ける
+    ///
+    fn eat_lunch<'a>(&mut self, ctx: &mut SuzuContext<'a>, menu: LunchMenu, t: Clock) {
+        let save_data = ctx.take_save_data_mut();
+        save_data
+            .suzunaan_status
+            .restore_stamina(LUNCH_STAMINA_RESTORE);
+        save_data.day_modifiers.add(
+            menu.modifier_type(),
+            DayModifiers::to_minute(LUNCH_BUFF_UNTIL_HOUR, 0),
+        );
+        self.stamina_gauge
+            .update_stamina(save_data.suzunaan_status.get_stamina());
+
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new(
+                format!("{}ヲ食ベタ", menu.to_string()),
+                menu.effect_description(),
+                NotificationType::Time,
+            ),
+            t,
+        );
+    }
+
+    ///
+    /// 所持金と評判が節目に達していれば、増築を勧める
+    ///
+    fn try_offer_shop_expansion<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if !self.tutorial_is_done() || self.map.scenario_event.is_some() {
+            return;
+        }
+
+        let save_data = ctx.take_save_data();
+        let offer = save_data.shop_expansion.next_offer(
+            save_data.task_result.total_money,
+            save_data.suzunaan_status.get_current_reputation(),
+            &save_data.date,
+        );
+
+        if let Some(expansion) = offer {
+            self.expansion_offer = Some(expansion);
+            self.set_fixed_text_into_scenario_box(ctx, expansion.offer_scenario_path(), t);
+            self.dark_effect_panel_shallow.new_effect(8, t, 0, 200);
+        }
+    }
+
+    ///
+    /// 増築の代金を払う。広くなった店は明日から使える
+    ///
+    fn purchase_shop_expansion<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        expansion: ShopExpansionType,
+        t: Clock,
+    ) {
+        let save_data = ctx.take_save_data_mut();
+        if save_data.task_result.total_money < expansion.price()
+            || !save_data.shop_expansion.purchase(expansion)
+        {
+            return;
+        }
+        save_data.task_result.total_money -= expansion.price();
+
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new(
+                format!("{}ヲ増築", expansion.to_str()),
+                format!("明日カラ{}ガ使エマス", expansion.to_str()),
+                NotificationType::Time,
+            ),
+            t,
+        );
+    }
+
+    fn is_out_on_errand(&self) -> bool {
+        self.parked_shop_map.is_some()
+    }
+
+    ///
+    /// 営業中、毎時ちょうどに写本の配達を頼まれることがある
+    ///
+    fn try_offer_delivery<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if !self.tutorial_is_done()
+            || self.shop_time_status != ShopTimeStatus::Opening
+            || self.shop_clock.is_past(15, 0)
+            || self.map.scenario_event.is_some()
+            || self.delivery.is_some()
+            || self.is_out_on_errand()
+            || !ctx.resource.rng().chance(errand::DELIVERY_ASK_RATE)
+        {
+            return;
+        }
+
+        let book_title = ctx.resource.book_random_select().name.clone();
+        self.delivery_offer = Some(DeliveryRequest::random(
+            ctx.resource.rng(),
+            &book_title,
+            self.shop_clock.total_minutes(),
+        ));
+        self.set_fixed_text_into_scenario_box(ctx, "/scenario/delivery.toml", t);
+        self.dark_effect_panel_shallow.new_effect(8, t, 0, 200);
+    }
+
+    fn accept_delivery<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let mut delivery = match self.delivery_offer.take() {
+            Some(delivery) => delivery,
+            None => return,
+        };
+        delivery.accepted_minute = self.shop_clock.total_minutes();
+
+        let deadline = delivery.deadline_minute();
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new(
+                format!("{}ヘ配達", delivery.destination.to_str()),
+                format!(
+                    "{}ノ写本ヲ{}時{:02}分マデニ",
+                    delivery.book_title,
+                    deadline / 60,
+                    deadline % 60
+                ),
+                NotificationType::Time,
+            ),
+            t,
+        );
+        self.delivery = Some(delivery);
+    }
+
+    ///
+    /// プレイヤーをマップ上のmap_positionへ移し、カメラを合わせる
+    ///
+    fn warp_player(&mut self, map_position: numeric::Point2f) {
+        self.player.reset_speed();
+        self.player.set_map_position(map_position);
+        self.camera_focus_character_x();
+        self.camera_focus_character_y();
+        self.player
+            .get_mut_character_object()
+            .update_display_position(&self.camera.borrow());
+
+        self.map.tile_map.request_redraw();
+        self.map.tile_map.request_updating_tile_batch();
+    }
+
+    fn scene_transition_open_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let kind = self.player_iris_kind(ctx.context);
+        self.scene_transition_effect = Some(effect_object::new_scene_transition_effect(
+            ctx,
+            kind,
+            30,
+            SceneTransitionEffectType::Open,
+            -128,
+            t,
+        ));
+    }
+
+    ///
+    /// 通りのマップをその場で読み込んで店のマップと入れ替える
+    /// 店のマップは客ごと取っておき、戻ってきたときに元に戻す
+    ///
+    fn go_out_on_errand<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.player.reset_speed();
+        self.event_list.add_event(
+            Box::new(move |slf: &mut ShopScene, ctx, t| {
+                let mut street = MapData::new(ctx, errand::STREET_MAP_ID, slf.camera.clone());
+                street.tile_map.build_collision_map();
+
+                let shop_map = std::mem::replace(&mut slf.map, street);
+                slf.parked_shop_map = Some(shop_map);
+                slf.shop_return_position = slf.player.get_map_position();
+                slf.last_auto_handle_minute = slf.shop_clock.total_minutes();
+
+                let entrance = slf.map.tile_map.get_layout().player_spawn;
+                slf.warp_player(entrance);
+                slf.scene_transition_open_effect(ctx, t);
+
+                if let Some(delivery) = slf.delivery.as_ref() {
+                    let destination = delivery.destination;
+                    slf.notification_area.insert_new_contents_generic(
+                        ctx,
+                        NotificationContentsData::new(
+                            "配達".to_string(),
+                            format!("{}ハ{}", destination.to_str(), destination.direction_hint()),
+                            NotificationType::Time,
+                        ),
+                        t,
+                    );
+                }
+            }),
+            t + 60,
+        );
+
+        let kind = self.player_iris_kind(ctx.context);
+        self.scene_transition_close_effect(ctx, kind, t);
+    }
+
+    ///
+    /// 通りのマップを捨てて、取っておいた店のマップに戻る
+    ///
+    fn return_from_errand<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.player.reset_speed();
+        self.event_list.add_event(
+            Box::new(move |slf: &mut ShopScene, ctx, t| {
+                if let Some(mut shop_map) = slf.parked_shop_map.take() {
+                    shop_map.scenario_event = slf.map.scenario_event.take();
+                    slf.map = shop_map;
+                }
+
+                let position = slf.shop_return_position;
+                slf.warp_player(position);
+                slf.scene_transition_open_effect(ctx, t);
+            }),
+            t + 60,
+        );
+
+        let kind = self.player_iris_kind(ctx.context);
+        self.scene_transition_close_effect(ctx, kind, t);
+    }
+
+    fn try_deliver_book<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let tile = match self
+            .map
+            .tile_map
+            .map_position_to_tile_position(self.player.get_center_map_position(ctx.context))
+        {
+            Some(tile) => tile,
+            None => return,
+        };
+
+        let destination = match DeliveryDestination::at(tile.x, tile.y) {
+            Some(destination) => destination,
+            None => return,
+        };
+
+        let on_the_way = self
+            .delivery
+            .as_ref()
+            .map_or(false, |delivery| delivery.destination == destination);
+        if !on_the_way {
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "配達".to_string(),
+                    format!("{}ヘノ届ケ物ハナイ", destination.to_str()),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+            return;
+        }
+
+        let delivery = self.delivery.take().unwrap();
+        let outcome = delivery.outcome(self.shop_clock.total_minutes());
+
+        let save_data = ctx.take_save_data_mut();
+        save_data.task_result.total_money += outcome.money;
+        save_data.suzunaan_status.add_reputation(outcome.reputation);
+
+        let message = if outcome.on_time {
+            "間ニ合ッタ"
+        } else {
+            "遅レテシマッタ"
+        };
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new(
+                format!("{}ヘ配達済ミ", destination.to_str()),
+                format!("{} 駄賃{}円", message, outcome.money),
+                NotificationType::Time,
+            ),
+            t,
+        );
+    }
+
+    ///
+    /// 店を空けている間は、列の先頭の客を一定の間隔で代わりにさばく
+    /// 手が行き届かない分、代金は減り、評判も落ちる
+    ///
+    fn auto_handle_waiting_customer<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let now = self.shop_clock.total_minutes();
+        if now < self.last_auto_handle_minute + errand::AUTO_HANDLE_INTERVAL {
+            return;
+        }
+        self.last_auto_handle_minute = now;
+
+        let shop_map = match self.parked_shop_map.as_ref() {
+            Some(shop_map) => shop_map,
+            None => return,
+        };
+        let mut customer = match self.customer_queue.pop_head_customer() {
+            Some((customer, _)) => customer,
+            None => return,
+        };
+
+        let save_data = ctx.take_save_data_mut();
+        match self.customer_request_queue.pop_front() {
+            Some(CustomerRequest::Borrowing(request_information)) => {
+                let fee = errand::auto_handled_fee(request_information.calc_fee());
+                let fee = save_data.suzunaan_status.apply_late_night_pay(fee);
+                save_data.task_result.done_works += 1;
+                save_data
+                    .economy
+                    .receive_rental_fee(&mut save_data.task_result, fee);
+                save_data
+                    .task_result
+                    .borrowing_books
+                    .extend(request_information.borrowing);
+            }
+            Some(CustomerRequest::Returning(request_information)) => {
+                let today = save_data.date;
+                for book in request_information.returning.iter() {
+                    save_data
+                        .reservation_ledger
+                        .book_returned(&book.name, &today);
+                }
+                let late_fee = economy::late_return_fee(&request_information, &today);
+                save_data
+                    .economy
+                    .receive_late_fee(&mut save_data.task_result, late_fee);
+                save_data.task_result.done_works += 1;
+                save_data
+                    .task_result
+                    .not_shelved_books
+                    .extend(request_information.returning);
+            }
+            None => (),
+        }
+        save_data
+            .suzunaan_status
+            .add_reputation(errand::AUTO_HANDLE_REPUTATION_PENALTY);
+
+        customer.get_out_shop(
+            ctx.context,
+            &shop_map.tile_map,
+            shop_map.tile_map.get_layout().exit_tile,
+            t,
+        );
+        self.character_group.add(customer);
+
+        self.goto_check_customers.reset_each_customers_goal(
+            ctx,
+            &shop_map.tile_map,
+            self.customer_queue
+                .tail_map_position(shop_map.tile_map.get_layout()),
+            t,
+        );
+        self.update_queue_panel(ctx);
+    }
+
+    ///
+    /// 二間目を増築し、評判も十分になっていれば手伝いがやってくる
+    ///
+    fn try_unlock_assistant<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let save_data = ctx.take_save_data_mut();
+        let reputation = save_data.suzunaan_status.get_current_reputation();
+        if !save_data
+            .assistant
+            .try_unlock(&save_data.shop_expansion, reputation)
+        {
+            return;
+        }
+
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new(
+                "手伝イガ来マシタ".to_string(),
+                "メニューカラ仕事ヲ任セラレマス".to_string(),
+                NotificationType::Time,
+            ),
+            t,
+        );
+        self.update_task_result(ctx);
+    }
+
+    ///
+    /// 手伝いに任せる仕事を変える。任せ直せるのは一時間に一度まで
+    ///
+    fn assign_assistant_role<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        role: Option<AssistantRole>,
+        t: Clock,
+    ) {
+        let hour = (self.shop_clock.total_minutes() / 60) as u8;
+        let assistant = &mut ctx.take_save_data_mut().assistant;
+        if assistant.get_role() == role {
+            return;
+        }
+
+        if !assistant.assign(role, hour) {
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "手伝イ".to_string(),
+                    "次ノ刻マデ任セ直セマセン".to_string(),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+            return;
+        }
+
+        self.update_task_result(ctx);
+    }
+
+    ///
+    /// 手伝いの失敗を直す。誤配架の本は未配架に戻り、記録漏れの返却は記録し直す
+    ///
+    fn correct_assistant_mistake<'a>(&mut self, ctx: &mut SuzuContext<'a>, index: usize) {
+        let save_data = ctx.take_save_data_mut();
+        match save_data.assistant.correct(index) {
+            Some(AssistantMistake::MisShelved(book)) => {
+                save_data.task_result.not_shelved_books.push(book);
+            }
+            Some(AssistantMistake::UnrecordedReturn(book_title)) => {
+                let today = save_data.date;
+                save_data
+                    .reservation_ledger
+                    .book_returned(&book_title, &today);
+            }
+            Some(AssistantMistake::RudeGreeting) | None => (),
+        }
+
+        self.update_task_result(ctx);
+    }
+
+    ///
+    /// 任せた仕事を手伝いが一度こなす。失敗したときは直すべきものとして残る
+    ///
+    fn run_assistant<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.shop_time_status != ShopTimeStatus::Opening
+            && self.shop_time_status != ShopTimeStatus::LateNight
+        {
+            return;
+        }
+
+        let role = match ctx.take_save_data().assistant.get_role() {
+            Some(role) => role,
+            None => return,
+        };
+
+        let mistakes = match role {
+            AssistantRole::Shelving => self.assistant_shelving(ctx),
+            AssistantRole::Greeting => self.assistant_greeting(ctx),
+            AssistantRole::SimpleReturns => self.assistant_simple_returns(ctx, t),
+        };
+
+        for mistake in mistakes.iter() {
+            self.notification_area.insert_new_contents_generic(
+                ctx,
+                NotificationContentsData::new(
+                    "手伝イノ失敗".to_string(),
+                    mistake.description(),
+                    NotificationType::Time,
+                ),
+                t,
+            );
+        }
+
+        self.update_task_result(ctx);
+    }
+
+    fn assistant_shelving<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> Vec<AssistantMistake> {
+        let rng = ctx.resource.rng();
+        let save_data = ctx.savable_data.as_mut().expect("save data not found");
+        let mut mistakes = Vec::new();
+
+        for _ in 0..assistant::ASSISTANT_SHELVING_PER_ACTION {
+            let book = match save_data.task_result.not_shelved_books.pop() {
+                Some(book) => book,
+                None => break,
+            };
+
+            let mistake = if save_data.assistant.is_mistake(rng.gen::<f32>()) {
+                Some(AssistantMistake::MisShelved(book))
+            } else {
+                None
+            };
+
+            mistakes.extend(mistake.clone());
+            save_data.assistant.record_action(mistake);
+        }
+
+        mistakes
+    }
+
+    fn assistant_greeting<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> Vec<AssistantMistake> {
+        if self.character_group.len() == 0 && self.customer_queue.is_empty() {
+            return Vec::new();
+        }
+
+        let roll = ctx.resource.rng().gen::<f32>();
+        let save_data = ctx.take_save_data_mut();
+        if save_data.assistant.is_mistake(roll) {
+            save_data
+                .assistant
+                .record_action(Some(AssistantMistake::RudeGreeting));
+            return vec![AssistantMistake::RudeGreeting];
+        }
+
+        save_data
+            .suzunaan_status
+            .add_reputation(assistant::ASSISTANT_GREETING_REPUTATION);
+        save_data.assistant.record_action(None);
+        Vec::new()
+    }
+
+    ///
+    /// 列の先頭が返却の客なら、手伝いが代わりに受け付ける
+    ///
+    fn assistant_simple_returns<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        t: Clock,
+    ) -> Vec<AssistantMistake> {
+        if self.is_out_on_errand() {
+            return Vec::new();
+        }
+
+        match self.customer_request_queue.front() {
+            Some(CustomerRequest::Returning(_)) => (),
+            _ => return Vec::new(),
+        }
+
+        let mut customer = match self.customer_queue.pop_head_customer() {
+            Some((customer, _)) => customer,
+            None => return Vec::new(),
+        };
+
+        let request_information = match self.customer_request_queue.pop_front() {
+            Some(CustomerRequest::Returning(request_information)) => request_information,
+            _ => return Vec::new(),
+        };
+
+        let rng = ctx.resource.rng();
+        let save_data = ctx.savable_data.as_mut().expect("save data not found");
+        let today = save_data.date;
+        let mut mistakes = Vec::new();
+
+        for book in request_information.returning.iter() {
+            let mistake = if save_data.assistant.is_mistake(rng.gen::<f32>()) {
+                Some(AssistantMistake::UnrecordedReturn(book.name.clone()))
+            } else {
+                save_data
+                    .reservation_ledger
+                    .book_returned(&book.name, &today);
+                None
+            };
+
+            mistakes.extend(mistake.clone());
+            save_data.assistant.record_action(mistake);
+        }
+
+        save_data.task_result.done_works += 1;
+        save_data
+            .task_result
+            .not_shelved_books
+            .extend(request_information.returning);
+
+        customer.get_out_shop(
+            ctx.context,
+            &self.map.tile_map,
+            self.map.tile_map.get_layout().exit_tile,
+            t,
+        );
+        self.character_group.add(customer);
+
+        self.goto_check_customers.reset_each_customers_goal(
+            ctx,
+            &self.map.tile_map,
+            self.customer_queue
+                .tail_map_position(self.map.tile_map.get_layout()),
+            t,
+        );
+        self.update_queue_panel(ctx);
+
+        mistakes
+    }
+
+    ///
+    /// 雫の垂れている客が、歩いた床に時々水溜まりを作る。水溜まりの上は歩みが遅くなる
+    ///
+    fn update_rain_puddles<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if !self.weather.is_rainy() || t % 15 != 0 {
+            return;
+        }
+
+        let mut dripped = Vec::new();
+        for customer in self.character_group.iter_mut() {
+            if !customer.is_dripping(t) || !ctx.resource.rng().chance(weather::DRIP_RATE) {
+                continue;
+            }
+
+            let position = customer
+                .get_character_object()
+                .get_map_position_with_collision_top_offset(ctx.context);
+            if let Some(tile) = self.map.tile_map.map_position_to_tile_position(position) {
+                dripped.push(tile);
+            }
+        }
+
+        for tile in dripped {
+            if self.puddles.add(tile) {
+                self.map
+                    .tile_map
+                    .set_tile_speed_overlay(tile, Some(weather::PUDDLE_SPEED_RATE));
+                ctx.process_utility.redraw();
+            }
+        }
+    }
+
+    ///
+    /// 押された場所の水溜まりを雑巾で拭く。拭けたときはtrueを返す
+    ///
+    fn try_mop_puddle<'a>(&mut self, ctx: &mut SuzuContext<'a>, point: numeric::Point2f) -> bool {
+        if self.puddles.is_empty()
+            || self.is_out_on_errand()
+            || self.map.scenario_event.is_some()
+            || self.shop_menu.first_menu_is_open()
+            || self.shop_special_object.is_enable_now()
+        {
+            return false;
+        }
+
+        let camera = self.camera.borrow().clone();
+        let map_point = numeric::Point2f::new(point.x + camera.x, point.y + camera.y);
+        let player_position = self
+            .player
+            .get_character_object()
+            .get_map_position_with_collision_top_offset(ctx.context);
+
+        let tiles = (
+            self.map.tile_map.map_position_to_tile_position(map_point),
+            self.map
+                .tile_map
+                .map_position_to_tile_position(player_position),
+        );
+        let (tile, player_tile) = match tiles {
+            (Some(tile), Some(player_tile)) => (tile, player_tile),
+            _ => return false,
+        };
+
+        if !self.puddles.mop(tile, player_tile) {
+            return false;
+        }
+
+        self.map.tile_map.set_tile_speed_overlay(tile, None);
+
+        let status = &mut ctx.take_save_data_mut().suzunaan_status;
+        status.add_fatigue(weather::MOP_FATIGUE);
+        self.stamina_gauge.update_stamina(status.get_stamina());
+
+        ctx.process_utility.redraw();
+        true
+    }
+
+    ///
+    /// 床の水溜まりと、戸口の傘立ての傘を描く
+    ///
+    fn draw_rain_decorations(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
+        if !self.weather.is_rainy() {
+            return Ok(());
+        }
+
+        let camera = self.camera.borrow().clone();
+        let tile_size = self.map.get_tile_size();
+        let mut builder = ggraphics::MeshBuilder::new();
+
+        for puddle in self.puddles.iter() {
+            let center = mp::map_to_display(
+                &numeric::Point2f::new(
+                    (puddle.x as f32 + 0.5) * tile_size.x,
+                    (puddle.y as f32 + 0.5) * tile_size.y,
+                ),
+                &camera,
+            );
+
+            // 円を少しずらして重ね、水溜まりらしい形にする
+            for (offset, radius) in [(-0.12, 0.3), (0.15, 0.24)].iter() {
+                shape::Circle::new(
+                    numeric::Point2f::new(center.x + (offset * tile_size.x), center.y),
+                    radius * tile_size.x,
+                    0.1,
+                    ggraphics::DrawMode::fill(),
+                    ggraphics::Color::from_rgba_u32(0x5a7fb080),
+                )
+                .add_to_builder(&mut builder);
+            }
+        }
+
+        let stand_tile = match self
+            .map
+            .tile_map
+            .get_layout()
+            .get_decoration_anchor("umbrella_stand")
+        {
+            Some(tile) => tile,
+            None => return Ok(()),
+        };
+        let stand = mp::map_to_display(
+            &numeric::Point2f::new(
+                stand_tile.x as f32 * tile_size.x,
+                stand_tile.y as f32 * tile_size.y,
+            ),
+            &camera,
+        );
+        for index in 0..self.umbrella_stand.get_count() {
+            shape::Rectangle::new(
+                numeric::Rect::new(
+                    stand.x + (index as f32 * tile_size.x * 0.2),
+                    stand.y,
+                    tile_size.x * 0.12,
+                    tile_size.y * 0.9,
+                ),
+                ggraphics::DrawMode::fill(),
+                ggraphics::Color::from_rgba_u32(0x8c3a3aff),
+            )
+            .add_to_builder(&mut builder);
+        }
+
+        if self.puddles.is_empty() && self.umbrella_stand.get_count() == 0 {
+            return Ok(());
+        }
+
+        let mesh = builder.build(ctx)?;
+        ggraphics::draw(ctx, &mesh, ggraphics::DrawParam::default())
+    }
+
+    ///
+    /// 配達で店を空けている間の更新。店の中の客は止まったままで、時計と代わりの接客だけが進む
+    ///
+    fn update_out_on_errand<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.move_playable_character(ctx.context, t);
+        self.drain_stamina_by_moving(ctx);
+        self.check_event_panel_onmap(ctx, EventTrigger::Touch);
 
-                    ctx.resource.stop_bgm(ctx.context, SoundID::ShopBGM);
-                }),
-                t + 120,
-            );
+        self.map.tile_map.update(ctx.context, t);
+        ctx.resource.update_positional_sound(&self.camera.borrow());
+        self.update_ambient_sound(ctx);
 
-            self.scene_transition_close_effect(ctx, t);
-            self.shop_time_status = ShopTimeStatus::Closed;
-        }
+        self.update_shop_clock_regular(ctx, t);
+        self.auto_handle_waiting_customer(ctx, t);
+
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// カメラの中心がいる区域に合わせて、環境音を混ぜ直す
+    ///
+    fn update_ambient_sound<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let center = positional_audio::camera_center(&self.camera.borrow());
+        let tile_size = self.map.tile_map.get_tile_drawing_size();
+        let listener = numeric::Point2f::new(center.x / tile_size.x, center.y / tile_size.y);
+
+        let targets = ambient_zone::target_levels(self.map.event_map.get_ambient_zones(), listener);
+        self.ambient_mixer.update(&targets);
+        ctx.update_ambient_sound(&self.ambient_mixer);
     }
 
     fn insert_goto_check_customer<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let entrance = self
+            .map
+            .tile_map
+            .get_layout()
+            .customer_entrance(ctx.resource.rng().gen::<usize>());
         let customer = character_factory::create_character(
             character_factory::CharacterFactoryOrder::CustomerSample,
             ctx,
             &self.camera.borrow(),
-            numeric::Point2f::new(1430.0, 1246.0),
+            entrance,
         );
 
         let customer = CustomerCharacter::new(
             ctx.resource,
             customer,
-            CustomerDestPoint::new(vec![
-                numeric::Vector2u::new(10, 4),
-                numeric::Vector2u::new(6, 4),
-            ]),
+            CustomerDestPoint::new(self.map.tile_map.get_layout().browse_spots.clone()),
         );
 
         self.character_group.add(customer);
@@ -1754,7 +3603,8 @@ impl ShopScene {
                 ctx,
                 customer,
                 &self.map.tile_map,
-                self.customer_queue.tail_map_position(),
+                self.customer_queue
+                    .tail_map_position(self.map.tile_map.get_layout()),
                 t,
             );
         }
@@ -1767,6 +3617,12 @@ impl ShopScene {
 
         match self.shop_time_status {
             ShopTimeStatus::Opening => (),
+            ShopTimeStatus::LateNight => {
+                // 夜は客足が遠のく
+                if ctx.resource.rng().below(3) != 0 {
+                    return;
+                }
+            }
             _ => return,
         }
 
@@ -1774,27 +3630,88 @@ impl ShopScene {
 	    return;
 	}
 
-        if rand::random::<usize>() % self.random_customer_add_timing as usize == 0 {
+        // 曜日と時刻、祭り、その日の難しさ、評判で客足が変わる
+        let add_timing = self.difficulty.scale_add_timing(
+            self.arrival_curve
+                .add_timing((self.shop_clock.total_minutes() / 60) as u8),
+        );
+        let add_timing = reputation::scale_add_timing(
+            add_timing,
+            ctx.take_save_data()
+                .suzunaan_status
+                .get_current_reputation(),
+        );
+        if ctx.resource.rng().below(add_timing as usize) == 0 {
+            let entrance = self
+                .map
+                .tile_map
+                .get_layout()
+                .customer_entrance(ctx.resource.rng().gen::<usize>());
             let character = character_factory::create_character(
                 character_factory::CharacterFactoryOrder::CustomerSample,
                 ctx,
                 &self.camera.borrow(),
-                numeric::Point2f::new(1430.0, 1246.0),
+                entrance,
             );
 
-            self.character_group.add(CustomerCharacter::new(
+            let expansion = &ctx.take_save_data().shop_expansion;
+            let mut destinations = self.map.tile_map.get_layout().browse_spots.clone();
+            destinations.extend(expansion.customer_destinations());
+
+            // 読書処があれば、本を借りる前に座って読んでいく客もいる
+            let reading_spots = expansion.reading_spots();
+            let reading_spot = if !reading_spots.is_empty()
+                && ctx
+                    .resource
+                    .rng()
+                    .chance(shop_expansion::READING_CORNER_VISIT_RATE)
+            {
+                Some(reading_spots[ctx.resource.rng().below(reading_spots.len())])
+            } else {
+                None
+            };
+
+            let mut customer = CustomerCharacter::new(
                 ctx.resource,
                 character,
-                CustomerDestPoint::new(vec![
-                    numeric::Vector2u::new(10, 4),
-                    numeric::Vector2u::new(6, 4),
-                    //numeric::Vector2u::new(5, 14),
-                ]),
-            ));
+                CustomerDestPoint::new(destinations),
+            );
+            let special_visitor = ctx
+                .savable_data
+                .as_ref()
+                .expect("save data not found")
+                .special_customers
+                .pick_visitor(ctx.resource.rng());
+            if let Some(reservation) = self.reservation_visitors.pop_front() {
+                customer.set_reservation(reservation);
+            } else if let Some(spot) = reading_spot {
+                customer.plan_reading(spot);
+            } else if let Some(name) = special_visitor {
+                customer.set_special_customer(name);
+            }
+
+            // 雨の日は傘を戸口の傘立てに置いてから入ってくる
+            if self.weather.is_rainy() {
+                let in_stand = self.umbrella_stand.put();
+                customer.come_in_from_rain(
+                    in_stand,
+                    self.get_current_clock() + weather::DRIP_DURATION,
+                );
+            }
+
+            self.character_group.add(customer);
         }
     }
 
-    fn notify_customer_calling<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+    ///
+    /// 呼び鈴は呼んだ客の位置で鳴らす
+    ///
+    fn notify_customer_calling<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        customer_position: numeric::Point2f,
+        t: Clock,
+    ) {
         let notification = Box::new(notify::GeneralNotificationContents::new(
             ctx,
             NotificationContentsData::new(
@@ -1806,10 +3723,23 @@ impl ShopScene {
         ));
         self.notification_area
             .insert_new_contents(ctx, notification, t);
-        ctx.play_sound_as_se(
-	    SoundID::SeCustomerBell,
-	    Some(SoundPlayFlags::new(10, 1.0, false, ctx.config.get_se_volume()))
-	);
+        ctx.play_sound_at(SoundID::SeCustomerBell, customer_position);
+        self.kosuzu_comment(ctx, CommentTrigger::CustomerCalling, t);
+    }
+
+    ///
+    /// 小鈴に独り言を言わせる。設定で切っていれば何も言わない
+    ///
+    fn kosuzu_comment<'a>(&mut self, ctx: &mut SuzuContext<'a>, trigger: CommentTrigger, t: Clock) {
+        if !ctx.preferences.is_kosuzu_comment_enabled() {
+            return;
+        }
+
+        if let Some(text) = self.kosuzu_commentary.react(trigger, t, ctx.resource.rng()) {
+            self.kosuzu_comment_balloon
+                .show_comment(ctx.context, text, t);
+            ctx.process_utility.redraw();
+        }
     }
 
     fn transition_to_title_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
@@ -1817,11 +3747,13 @@ impl ShopScene {
             Box::new(|slf: &mut Self, ctx, _| {
                 slf.transition_status = SceneTransition::SwapTransition;
                 slf.transition_scene = SceneID::Title;
-		ctx.resource.stop_bgm(ctx.context, SoundID::ShopBGM);
+                ctx.resource.stop_scene_bgm(ctx.context, SceneID::SuzunaShop);
+                ctx.resource.stop_ambient_sound(ctx.context);
             }),
             t + 60,
         );
-        self.scene_transition_close_effect(ctx, t);
+        let kind = self.player_iris_kind(ctx.context);
+        self.scene_transition_close_effect(ctx, kind, t);
     }
 
     fn exit_pause_screen(&mut self, t: Clock) {
@@ -1848,48 +3780,113 @@ impl ShopScene {
         }
 
         if let Some(pause_result) = self.pause_screen_set.mouse_click_handler(ctx, point, t) {
-            match pause_result {
-                PauseResult::GoToTitle => self.transition_to_title_scene(ctx, t),
-                PauseResult::ReleasePause => self.exit_pause_screen(t),
+            self.run_pause_result(ctx, pause_result, t);
+        }
+    }
+
+    fn run_pause_result<'a>(&mut self, ctx: &mut SuzuContext<'a>, result: PauseResult, t: Clock) {
+        match result {
+            PauseResult::GoToTitle => self.transition_to_title_scene(ctx, t),
+            PauseResult::ReleasePause => self.exit_pause_screen(t),
+        }
+    }
+
+    ///
+    /// 一時停止の画面をキーボードで操作する。Escapeで開き、開いている間のキーはすべて受け取る
+    ///
+    fn pause_key_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        let t = self.get_current_clock();
+
+        if self.now_paused() {
+            if let Some(pause_result) = self.pause_screen_set.key_event(ctx, key, t) {
+                self.run_pause_result(ctx, pause_result, t);
             }
+            return true;
+        }
+
+        if key == PaletteKey::Escape
+            && self.map.scenario_event.is_none()
+            && self.scene_transition_effect.is_none()
+        {
+            self.enter_pause_screen(t);
+            return true;
         }
+
+        false
     }
 
     fn tutorial_is_done(&self) -> bool {
         self.tutorial_list.all_done() && self.task_tutorial_context.all_done()
     }
 
+    ///
+    /// 列に並んだ客が、帰ってしまうまで待ってくれるフレーム数
+    ///
+    fn customer_patience<'a>(&self, ctx: &mut SuzuContext<'a>) -> Clock {
+        let save_data = ctx.take_save_data();
+        let mut patience_rate = 1.0;
+        if save_data
+            .day_modifiers
+            .is_active(DayModifierType::PatienceAura)
+        {
+            patience_rate *= PATIENCE_AURA_RATE;
+        }
+        if save_data.difficulty_assist.is_enabled() {
+            patience_rate *= ASSIST_PATIENCE_RATE;
+        }
+
+        (1200.0 * patience_rate) as Clock
+    }
+
     fn check_waiting_customer_giveup<'a>(&mut self, ctx: &mut SuzuContext<'a>, now: Clock) {
         if !self.tutorial_is_done() {
             return;
         }
 
-        let giveup_customers = self.customer_queue.drain_giveup_customers(now);
+        let patience = self.customer_patience(ctx);
+
+        let giveup_customers = self
+            .customer_queue
+            .drain_giveup_customers(now, patience);
 
         if giveup_customers.len() > 0 {
+            self.kosuzu_comment(ctx, CommentTrigger::CustomerGaveUp, now);
             self.goto_check_customers.reset_each_customers_goal(
                 ctx,
                 &self.map.tile_map,
-                self.customer_queue.tail_map_position(),
+                self.customer_queue
+                    .tail_map_position(self.map.tile_map.get_layout()),
                 now,
             );
         }
 
-        for mut customer in giveup_customers {
+        for (index, mut customer) in giveup_customers {
+            ctx.take_save_data_mut()
+                .suzunaan_status
+                .apply_satisfaction(SatisfactionEvent::GaveUp);
             customer.get_out_shop(
                 ctx.context,
                 &self.map.tile_map,
-                numeric::Vector2u::new(15, 14),
+                self.map.tile_map.get_layout().exit_tile,
                 now,
             );
             self.character_group.add(customer);
-            self.customer_request_queue.pop_front();
+            self.customer_request_queue.remove(index);
         }
+
+        self.update_queue_panel(ctx);
     }
 
     fn non_paused_key_down_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, vkey: tdev::VirtualKey) {
         match vkey {
             tdev::VirtualKey::Action1 => {
+                // 会話の途中なら、クリックと同じく先へ進める
+                if let Some(scenario_event) = self.map.scenario_event.as_mut() {
+                    let t = self.get_current_clock();
+                    scenario_event.key_down_action1(ctx, None, t);
+                    return;
+                }
+
                 self.check_event_panel_onmap(ctx, EventTrigger::Action);
             }
             tdev::VirtualKey::Action2 => {
@@ -1910,6 +3907,13 @@ impl ShopScene {
                 self.enter_pause_screen(t);
             }
             tdev::VirtualKey::Action5 => {
+                #[cfg(feature = "map_editor")]
+                {
+                    self.map_event_editor.toggle();
+                    self.player.reset_speed();
+                    ctx.process_utility.redraw();
+                }
+
                 // self.transition_status = SceneTransition::StackingTransition;
                 // self.transition_scene = SceneID::MainDesk;
                 // if self.goto_check_customers.is_visible() {
@@ -1937,7 +3941,8 @@ impl ShopScene {
                 ctx,
                 customer,
                 &self.map.tile_map,
-                self.customer_queue.tail_map_position(),
+                self.customer_queue
+                    .tail_map_position(self.map.tile_map.get_layout()),
                 t,
             );
         }
@@ -1994,12 +3999,59 @@ impl ShopScene {
         self.shop_map_is_staged = !self.shop_map_is_staged;
     }
 
+    ///
+    /// 家具を動かしたり戸を開けたりして通れるタイルが変わったら、そこを通る客の道を引き直す
+    ///
+    fn replan_customer_routes(&mut self, ctx: &mut ggez::Context) {
+        let changed_tiles = self.map.tile_map.take_collision_changes();
+        if changed_tiles.is_empty() {
+            return;
+        }
+
+        self.character_group
+            .replan_routes(ctx, &self.map.tile_map, &changed_tiles);
+        self.goto_check_customers
+            .replan_routes(ctx, &self.map.tile_map, &changed_tiles);
+    }
+
+    ///
+    /// 客と小鈴の立っているタイルを地図に教え、塞がれたタイルへ踏み込もうとしている客に道を譲らせる
+    ///
+    fn avoid_occupied_tiles(&mut self, ctx: &mut ggez::Context, t: Clock) {
+        let tile_map = &self.map.tile_map;
+        let mut occupied = Vec::new();
+
+        let player_position = self
+            .player
+            .get_character_object()
+            .get_map_position_with_collision_top_offset(ctx);
+        occupied.extend(tile_map.map_position_to_tile_position(player_position));
+        for customer in self.character_group.iter_mut() {
+            occupied.extend(customer.current_tile(ctx, tile_map));
+        }
+        for customer in self.goto_check_customers.iter_mut() {
+            occupied.extend(customer.current_tile(ctx, tile_map));
+        }
+        for (customer, _) in self.customer_queue.iter_mut() {
+            occupied.extend(customer.current_tile(ctx, tile_map));
+        }
+
+        self.map.tile_map.set_occupied_tiles(occupied);
+
+        for customer in self.character_group.iter_mut() {
+            customer.avoid_occupied_tile(ctx, &self.map.tile_map, t);
+        }
+        for customer in self.goto_check_customers.iter_mut() {
+            customer.avoid_occupied_tile(ctx, &self.map.tile_map, t);
+        }
+    }
+
     fn get_out_all_customers<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         for customer in self.character_group.iter_mut() {
             customer.get_out_shop(
                 ctx.context,
                 &self.map.tile_map,
-                numeric::Vector2u::new(15, 14),
+                self.map.tile_map.get_layout().exit_tile,
                 t,
             );
         }
@@ -2008,7 +4060,7 @@ impl ShopScene {
             customer.get_out_shop(
                 ctx.context,
                 &self.map.tile_map,
-                numeric::Vector2u::new(15, 14),
+                self.map.tile_map.get_layout().exit_tile,
                 t,
             );
             self.character_group.add(customer);
@@ -2018,11 +4070,12 @@ impl ShopScene {
             customer.get_out_shop(
                 ctx.context,
                 &self.map.tile_map,
-                numeric::Vector2u::new(15, 14),
+                self.map.tile_map.get_layout().exit_tile,
                 t,
             );
             self.character_group.add(customer);
         }
+        self.update_queue_panel(ctx);
     }
 
     fn set_fixed_text_into_scenario_box<'a>(
@@ -2096,6 +4149,69 @@ impl ShopScene {
                         self.dark_effect_panel_shallow.new_effect(8, t, 220, 0);
                         self.tutorial_list.customer_is_comming = true;
                     }
+                    "LateNightOpen" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        self.start_late_night(ctx, t);
+                    }
+                    "LateNightDecline" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        self.start_closing(ctx, t);
+                    }
+                    "AutoShelveAccept" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        self.auto_shelve_remaining_books(ctx, t);
+                    }
+                    "AutoShelveDecline" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                    }
+                    "LunchOnigiri" | "LunchSoba" | "LunchDango" => {
+                        let menu = LunchMenu::from_opecode(opecode).unwrap();
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        self.eat_lunch(ctx, menu, t);
+                    }
+                    "ExpansionPurchase" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        if let Some(expansion) = self.expansion_offer.take() {
+                            self.purchase_shop_expansion(ctx, expansion, t);
+                        }
+                    }
+                    "ExpansionDecline" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        let save_data = ctx.take_save_data_mut();
+                        let today = save_data.date;
+                        save_data.shop_expansion.decline(&today);
+                        if let Some(expansion) = self.expansion_offer.take() {
+                            save_data.decision_log.record(
+                                DecisionKind::Refusal,
+                                expansion.to_str(),
+                                today,
+                            );
+                        }
+                    }
+                    "DeliveryAccept" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        self.accept_delivery(ctx, t);
+                    }
+                    "DeliveryDecline" => {
+                        self.map.scenario_event = None;
+                        self.dark_effect_panel_shallow.new_effect(8, t, 200, 0);
+                        if let Some(offer) = self.delivery_offer.take() {
+                            let today = ctx.today();
+                            ctx.take_save_data_mut().decision_log.record(
+                                DecisionKind::Refusal,
+                                &offer.book_title,
+                                today,
+                            );
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -2118,6 +4234,30 @@ impl SceneManager for ShopScene {
         }
     }
 
+    fn gamepad_stick_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, direction: numeric::Vector2f) {
+        if direction.x == 0.0 && direction.y == 0.0 {
+            // マウスで歩いている途中は止めない
+            if self.stick_walking {
+                self.stick_walking = false;
+                self.player.reset_speed();
+                self.player.update_animation_for_stop();
+            }
+            return;
+        }
+
+        if self.now_paused()
+            || self.shop_menu.first_menu_is_open()
+            || self.shop_menu.detail_menu_is_open()
+            || self.shop_special_object.is_enable_now()
+            || self.map.scenario_event.is_some()
+        {
+            return;
+        }
+
+        self.start_stick_move(ctx, direction);
+        self.stick_walking = true;
+    }
+
     fn mouse_motion_event<'a>(
         &mut self,
         ctx: &mut SuzuContext<'a>,
@@ -2142,6 +4282,20 @@ impl SceneManager for ShopScene {
         } else {
             let left_pressed = ggez::input::mouse::button_pressed(ctx.context, MouseButton::Left);
 
+            #[cfg(feature = "map_editor")]
+            {
+                if self.map_event_editor.is_enabled() {
+                    return;
+                }
+            }
+
+            if self.shop_menu.is_resizing() {
+                if left_pressed {
+                    self.shop_menu.resize_dragging(ctx, point, t);
+                }
+                return;
+            }
+
             if !self.shop_menu.first_menu_is_open()
                 && !self.shop_menu.detail_menu_is_open()
                 && self.map.scenario_event.is_none()
@@ -2149,7 +4303,7 @@ impl SceneManager for ShopScene {
             {
                 if left_pressed {
                     if !self.shop_command_palette.contains_buttons(point) {
-                        self.start_mouse_move(ctx.context, point);
+                        self.start_mouse_move(ctx, point);
                     }
                 }
             }
@@ -2180,15 +4334,50 @@ impl SceneManager for ShopScene {
                     .mouse_button_down(ctx, button, point, t);
             }
         } else {
+            #[cfg(feature = "map_editor")]
+            {
+                if self.map_event_editor.is_enabled() {
+                    if button == MouseButton::Left {
+                        let camera = self.camera.borrow().clone();
+                        let tile_size = self.map.get_tile_size();
+                        self.map_event_editor.click_handler(
+                            ctx,
+                            point,
+                            &camera,
+                            tile_size,
+                            &mut self.map.event_map,
+                        );
+                    }
+                    return;
+                }
+            }
+
+            if button == MouseButton::Left && self.shop_menu.contains_resize_handle(point) {
+                self.shop_menu.resize_handle_down(ctx, t);
+                return;
+            }
+
             match button {
                 MouseButton::Left => {
+                    if self.try_mop_puddle(ctx, point) {
+                        return;
+                    }
+
                     if !self.shop_command_palette.contains_buttons(point)
                         && !self.shop_menu.first_menu_is_open()
                         && !self.shop_menu.detail_menu_is_open()
                         && !self.shop_special_object.is_enable_now()
                         && self.map.scenario_event.is_none()
                     {
-                        self.start_mouse_move(ctx.context, point);
+                        self.start_mouse_move(ctx, point);
+                    }
+
+                    if let Some(role) = self.shop_menu.click_assistant_role(ctx, point) {
+                        self.assign_assistant_role(ctx, role, t);
+                    }
+
+                    if let Some(index) = self.shop_menu.click_assistant_mistake(ctx, point) {
+                        self.correct_assistant_mistake(ctx, index);
                     }
 
                     if !self.shop_menu.contains_some_menu(ctx, point) {
@@ -2274,6 +4463,11 @@ impl SceneManager for ShopScene {
 
             return;
         } else {
+            if self.shop_menu.is_resizing() {
+                self.shop_menu.finish_resize(ctx);
+                return;
+            }
+
             match button {
                 MouseButton::Left => {
                     if let Some(scenario_event) = self.map.scenario_event.as_mut() {
@@ -2305,6 +4499,11 @@ impl SceneManager for ShopScene {
                         && self.map.scenario_event.is_none()
                         && !self.shop_special_object.is_enable_now()
                     {
+                        if let Some(index) = self.queue_panel.click_entry(ctx.context, point) {
+                            self.call_customer_out_of_order(ctx, index, t);
+                            return;
+                        }
+
                         self.player.reset_speed();
                         self.player.update_animation_for_stop();
                         self.shop_command_palette
@@ -2344,9 +4543,12 @@ impl SceneManager for ShopScene {
             scenario_event.update_text(ctx, None);
             self.try_finish_scenario_event(ctx, t);
         }
-        if !self.now_paused() && self.map.scenario_event.is_none() {
+        if !self.now_paused() && self.map.scenario_event.is_none() && self.is_out_on_errand() {
+            self.update_out_on_errand(ctx, t);
+        } else if !self.now_paused() && self.map.scenario_event.is_none() {
             self.random_add_customer(ctx);
             self.move_playable_character(ctx.context, t);
+            self.drain_stamina_by_moving(ctx);
             self.check_event_panel_onmap(ctx, EventTrigger::Touch);
 
             self.character_group.move_and_collision_check(
@@ -2355,6 +4557,7 @@ impl SceneManager for ShopScene {
                 &self.map.tile_map,
                 t,
             );
+            self.update_rain_puddles(ctx, t);
 
             let mut rising_customers = self
                 .goto_check_customers
@@ -2362,43 +4565,62 @@ impl SceneManager for ShopScene {
             let reset_goto_check_customers_goal_flag = !rising_customers.is_empty();
 
             // 新しく客が列に並んだら、通知をする
-            if !rising_customers.is_empty() {
-                self.notify_customer_calling(ctx, t);
-            }
-
-            for customer in &mut rising_customers {
-                if let Some(request) =
-                    customer.check_rise_hand(ctx, self.tutorial_list.gen_tutorial_customer_order())
-                {
-                    self.tutorial_list.inc_checking_customer_count();
-                    self.customer_request_queue.push_back(request);
-                }
+            if let Some(customer) = rising_customers.first() {
+                let customer_position = customer.get_map_position();
+                self.notify_customer_calling(ctx, customer_position, t);
             }
 
             for mut customer in rising_customers {
+                let request = customer.check_rise_hand(
+                    ctx,
+                    self.tutorial_list.gen_tutorial_customer_order(),
+                    &self.difficulty,
+                );
+
                 customer
                     .get_mut_character_object()
                     .change_animation_mode(ObjectDirection::StopLeft);
-                self.customer_queue.push_back(customer, t);
+
+                // 呼ばれるまで、列で手を挙げている客を明滅させる
+                customer
+                    .get_mut_character_object()
+                    .set_highlight(Some(Highlight::pulse(
+                        ggraphics::Color::from_rgba_u32(CALLING_HIGHLIGHT_COLOR),
+                        t,
+                    )));
+
+                // 客と依頼は、列の同じ位置に並べる
+                let index = self.customer_queue.push_back(customer, t);
+                if let Some(request) = request {
+                    self.tutorial_list.inc_checking_customer_count();
+                    self.customer_request_queue.insert(index, request);
+                }
+            }
+
+            if reset_goto_check_customers_goal_flag {
+                self.update_queue_panel(ctx);
             }
 
             if reset_goto_check_customers_goal_flag {
                 self.goto_check_customers.reset_each_customers_goal(
                     ctx,
                     &self.map.tile_map,
-                    self.customer_queue.tail_map_position(),
+                    self.customer_queue
+                        .tail_map_position(self.map.tile_map.get_layout()),
                     t,
                 );
             }
 
             self.check_waiting_customer_giveup(ctx, t);
+            self.replan_customer_routes(ctx.context);
+            self.avoid_occupied_tiles(ctx.context, t);
 
             for customer in self.character_group.iter_mut() {
                 customer.try_update_move_effect(
                     ctx,
                     &self.map.tile_map,
-                    numeric::Vector2u::new(5, 14),
-                    numeric::Vector2u::new(15, 14),
+                    self.map.tile_map.get_layout().counter_tile,
+                    self.map.tile_map.get_layout().exit_tile,
                     t,
                 );
                 customer.get_mut_character_object().update_texture(t);
@@ -2420,16 +4642,37 @@ impl SceneManager for ShopScene {
                 customer.try_update_move_effect(
                     ctx,
                     &self.map.tile_map,
-                    numeric::Vector2u::new(5, 14),
-                    numeric::Vector2u::new(15, 14),
+                    self.map.tile_map.get_layout().counter_tile,
+                    self.map.tile_map.get_layout().exit_tile,
                     t,
                 );
                 customer.get_mut_character_object().update_texture(t);
+                if customer.get_mut_character_object().update_highlight(t) {
+                    ctx.process_utility.redraw();
+                }
             }
 
             self.shop_time_status_header.effect(ctx.context, t);
 
-            self.character_group.remove_if(|c| c.is_got_out());
+            // 動けなくなった客は、見張りが手当てする
+            let despawned = self.character_group.run_watchdog(
+                ctx.context,
+                &self.map.tile_map,
+                self.map.tile_map.get_layout().exit_tile,
+                t,
+            );
+
+            // 帰る客は傘立てから傘を持っていく
+            for customer in self
+                .character_group
+                .drain_remove_if(|c| c.is_got_out())
+                .into_iter()
+                .chain(despawned)
+            {
+                if customer.has_umbrella_in_stand() {
+                    self.umbrella_stand.take();
+                }
+            }
 
             self.character_group.sort_by_y_position();
 
@@ -2437,6 +4680,8 @@ impl SceneManager for ShopScene {
 
             // マップ描画の準備
             self.map.tile_map.update(ctx.context, t);
+            ctx.resource.update_positional_sound(&self.camera.borrow());
+            self.update_ambient_sound(ctx);
 
             self.shop_map.move_with_func(t);
             self.shop_command_palette.effect(ctx, t);
@@ -2450,6 +4695,9 @@ impl SceneManager for ShopScene {
 
         // 通知の更新
         self.notification_area.update(ctx, t);
+        if self.kosuzu_comment_balloon.update(t) {
+            ctx.process_utility.redraw();
+        }
 
         // 暗転の描画
         self.dark_effect_panel.run_effect(ctx, t);
@@ -2474,49 +4722,67 @@ impl SceneManager for ShopScene {
 
         self.map.tile_map.draw(ctx).unwrap();
 
+        if self.parked_shop_map.is_none() {
+            self.draw_rain_decorations(ctx).unwrap();
+        }
+
         let mut map_obj_drawer = MapObjectDrawer::new();
 
         map_obj_drawer.add(&mut self.player);
 
-        for customer in self.character_group.iter_mut() {
-            if customer
-                .get_character_object()
-                .obj()
-                .get_drawing_area(ctx)
-                .overlaps(&numeric::Rect::new(0.0, 0.0, 1366.0, 768.0))
-            {
-                map_obj_drawer.add(customer);
+        // 店を空けている間は、店の中の客は映らない
+        if self.parked_shop_map.is_none() {
+            for customer in self.character_group.iter_mut() {
+                if customer
+                    .get_character_object()
+                    .obj()
+                    .get_drawing_area(ctx)
+                    .overlaps(&numeric::Rect::new(0.0, 0.0, 1366.0, 768.0))
+                {
+                    map_obj_drawer.add(customer);
+                }
             }
-        }
 
-        for (customer, _) in self.customer_queue.iter_mut() {
-            if customer
-                .get_character_object()
-                .obj()
-                .get_drawing_area(ctx)
-                .overlaps(&numeric::Rect::new(0.0, 0.0, 1366.0, 768.0))
-            {
-                map_obj_drawer.add(customer);
+            for (customer, _) in self.customer_queue.iter_mut() {
+                if customer
+                    .get_character_object()
+                    .obj()
+                    .get_drawing_area(ctx)
+                    .overlaps(&numeric::Rect::new(0.0, 0.0, 1366.0, 768.0))
+                {
+                    map_obj_drawer.add(customer);
+                }
             }
-        }
 
-        for customer in self.goto_check_customers.iter_mut() {
-            if customer
-                .get_character_object()
-                .obj()
-                .get_drawing_area(ctx)
-                .overlaps(&numeric::Rect::new(0.0, 0.0, 1366.0, 768.0))
-            {
-                map_obj_drawer.add(customer);
+            for customer in self.goto_check_customers.iter_mut() {
+                if customer
+                    .get_character_object()
+                    .obj()
+                    .get_drawing_area(ctx)
+                    .overlaps(&numeric::Rect::new(0.0, 0.0, 1366.0, 768.0))
+                {
+                    map_obj_drawer.add(customer);
+                }
             }
         }
 
         map_obj_drawer.sort(ctx);
         map_obj_drawer.draw(ctx);
 
+        #[cfg(feature = "map_editor")]
+        {
+            let camera = self.camera.borrow().clone();
+            self.map_event_editor
+                .draw_preview(ctx, &self.map.event_map, &camera, self.map.get_tile_size())
+                .unwrap();
+        }
+
         self.shop_map.draw(ctx).unwrap();
 
         self.drawable_shop_clock.draw(ctx).unwrap();
+        self.stamina_gauge.draw(ctx).unwrap();
+        self.queue_panel.draw(ctx).unwrap();
+        self.kosuzu_comment_balloon.draw(ctx).unwrap();
         self.shop_time_status_header.draw(ctx).unwrap();
 
         self.dark_effect_panel.draw(ctx).unwrap();
@@ -2534,6 +4800,8 @@ impl SceneManager for ShopScene {
 
         self.notification_area.draw(ctx).unwrap();
 
+        self.shortcut_palette_window.draw(ctx).unwrap();
+
         self.pause_screen_set.draw(ctx).unwrap();
 
         if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
@@ -2554,6 +4822,85 @@ impl SceneManager for ShopScene {
         }
     }
 
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if !self.now_paused() {
+            let t = self.get_current_clock();
+            self.player.reset_speed();
+            self.pause_screen_set.enter_idle_pause(t);
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn shortcut_palette_is_open(&self) -> bool {
+        self.shortcut_palette.is_some()
+    }
+
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        if self.shortcut_palette.is_none() {
+            if key == PaletteKey::Toggle
+                && !self.now_paused()
+                && self.map.scenario_event.is_none()
+                && self.scene_transition_effect.is_none()
+            {
+                self.open_shortcut_palette(ctx);
+                return true;
+            }
+
+            if !self.now_paused() {
+                let t = self.get_current_clock();
+                if let Some(scenario_event) = self.map.scenario_event.as_mut() {
+                    if scenario_event.choice_key_event(ctx, key, t) {
+                        return true;
+                    }
+                }
+            }
+
+            return self.pause_key_event(ctx, key);
+        }
+
+        let palette = self.shortcut_palette.as_mut().unwrap();
+
+        match key {
+            PaletteKey::Toggle | PaletteKey::Escape => {
+                self.close_shortcut_palette(ctx);
+                return true;
+            }
+            PaletteKey::Enter => {
+                let command = palette.selected_command();
+                self.close_shortcut_palette(ctx);
+                if let Some(command) = command {
+                    self.run_shop_shortcut(ctx, command);
+                }
+                return true;
+            }
+            PaletteKey::Up => palette.select_prev(),
+            PaletteKey::Down | PaletteKey::Tab => palette.select_next(),
+            PaletteKey::Backspace => palette.pop_char(),
+            PaletteKey::Char(c) => palette.push_char(c),
+        }
+
+        self.update_shortcut_palette_window(ctx);
+        true
+    }
+
+    fn quicksave_available(&self) -> bool {
+        self.tutorial_is_done()
+            && !self.now_paused()
+            && self.map.scenario_event.is_none()
+            && self.scene_transition_effect.is_none()
+            && self.shortcut_palette.is_none()
+            && self.served_customer.is_none()
+    }
+
+    fn quicksave_notification<'a>(&mut self, ctx: &mut SuzuContext<'a>, message: String) {
+        let t = self.get_current_clock();
+        self.notification_area.insert_new_contents_generic(
+            ctx,
+            NotificationContentsData::new("保存".to_string(), message, NotificationType::Time),
+            t,
+        );
+    }
+
     fn transition(&self) -> SceneID {
         self.transition_scene
     }
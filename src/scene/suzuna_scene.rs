@@ -6,6 +6,7 @@ use torifune::core::*;
 use torifune::device::VirtualKey;
 use torifune::numeric;
 
+use crate::core::shortcut_palette::PaletteKey;
 use crate::core::{GensoDate, SuzuContext};
 use crate::scene::*;
 
@@ -99,6 +100,10 @@ impl SceneManager for SuzunaScene {
         self.sub_scene.mouse_wheel_event(ctx, point, x, y);
     }
 
+    fn gamepad_stick_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, direction: numeric::Vector2f) {
+        self.sub_scene.gamepad_stick_event(ctx, direction);
+    }
+
     fn pre_process<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         self.sub_scene.pre_process(ctx)
     }
@@ -155,4 +160,24 @@ impl SceneManager for SuzunaScene {
     fn unfocus_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         self.sub_scene.unfocus_event(ctx);
     }
+
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.sub_scene.idle_timeout_event(ctx);
+    }
+
+    fn shortcut_palette_is_open(&self) -> bool {
+        self.sub_scene.shortcut_palette_is_open()
+    }
+
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        self.sub_scene.shortcut_palette_event(ctx, key)
+    }
+
+    fn quicksave_available(&self) -> bool {
+        self.sub_scene.quicksave_available()
+    }
+
+    fn quicksave_notification<'a>(&mut self, ctx: &mut SuzuContext<'a>, message: String) {
+        self.sub_scene.quicksave_notification(ctx, message);
+    }
 }
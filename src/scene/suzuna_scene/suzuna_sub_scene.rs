@@ -7,7 +7,11 @@ use torifune::device::VirtualKey;
 use torifune::numeric;
 
 use crate::core::book_management::*;
+use crate::core::desk_autosave::DeskAutosave;
+use crate::core::shortcut_palette::PaletteKey;
 use crate::core::*;
+use crate::log_debug;
+use crate::log_info;
 use crate::scene::*;
 
 use crate::scene::shop_scene::ShopScene;
@@ -16,6 +20,9 @@ use task_result_scene::*;
 use task_scene::*;
 
 use crate::object::task_object::tt_main_component::*;
+use crate::object::task_object::tt_sub_component::{
+    BorrowingRecordBookData, BorrowingRecordBookPageData,
+};
 
 #[derive(PartialEq, Clone, Copy)]
 pub enum SuzunaSceneStatus {
@@ -64,14 +71,26 @@ pub struct SuzunaSubScene {
 
 impl SuzunaSubScene {
     pub fn new<'a>(ctx: &mut SuzuContext<'a>, map_id: u32) -> Self {
-        let date = ctx.take_save_data().date.clone();
+        let date = ctx.today();
+
+        // 同じ日をやり直せば、同じ客が同じ本を頼みに来る
+        let day_seed = game_rng::day_seed(ctx.take_save_data().rng_seed, &date);
+        ctx.resource.reseed_rng(day_seed);
+        log_info!("rng seed for {}: {:#x}", date.to_string(), day_seed);
+
+        // 熟練の店番では、作り足した客の名前と本の題名も使う
+        let procedural_seed = match &ctx.take_save_data().game_mode {
+            GameMode::Story => None,
+            GameMode::TimeAttack(data) => Some(data.get_procedural_seed()),
+        };
+        ctx.resource.setup_procedural_catalog(procedural_seed);
 
 	let todays_new_books = match ctx.take_save_data().game_mode {
 	    GameMode::Story => DayNewBooks::random(ctx.resource, 7, 2),
 	    GameMode::TimeAttack(_) => DayNewBooks::random(ctx.resource, 8, 2),
 	};
 
-        let task_tutorial = if ctx.take_save_data().date.first_day()
+        let task_tutorial = if ctx.today().first_day()
             && ctx.take_save_data().game_mode.is_story_mode()
             && ctx.take_save_data().run_tutorial
         {
@@ -118,6 +137,50 @@ impl SuzunaSubScene {
     ) {
         if transition == SceneTransition::StackingTransition {
             if let Some(shop_scene) = self.shop_scene.as_mut() {
+                // 落ちる前に書いていた頁が残っていれば、客を呼ばずに机を開き、戻すか尋ねる
+                if shop_scene.take_desk_restore_request() {
+                    let today = ctx.today();
+                    if let Some(autosave) = DeskAutosave::load_for(&today) {
+                        let record_book_data = ctx.take_save_data().record_book_data.clone();
+                        let desk_stats = shop_scene.clone_result_report().get_desk_stats().clone();
+
+                        let mut task_scene = TaskScene::new(
+                            ctx,
+                            None,
+                            record_book_data,
+                            &self.tutorial_context,
+                            desk_stats,
+                            false,
+                        );
+                        task_scene.offer_desk_restore(autosave);
+
+                        self.scene_status = SuzunaSceneStatus::DeskWork;
+                        self.desk_work_scene = Some(Box::new(task_scene));
+                    }
+                    return ();
+                }
+
+                // 朝の練習では、前日に見誤った返却受付を、その頁だけの帳簿で受け直す
+                if shop_scene.take_practice_request() {
+                    if let Some(record) = ctx.take_save_data_mut().practice_request.take() {
+                        let record_book_data = BorrowingRecordBookData {
+                            pages_data: vec![BorrowingRecordBookPageData::from(&record.request)],
+                        };
+                        let desk_stats = shop_scene.clone_result_report().get_desk_stats().clone();
+
+                        self.scene_status = SuzunaSceneStatus::DeskWork;
+                        self.desk_work_scene = Some(Box::new(TaskScene::new(
+                            ctx,
+                            Some(CustomerRequest::Returning(record.request)),
+                            record_book_data,
+                            &self.tutorial_context,
+                            desk_stats,
+                            true,
+                        )));
+                    }
+                    return ();
+                }
+
                 // CustomerRequestを構築する上で必須な要素を取得
                 let customer_request_hint = shop_scene.pop_customer_request();
                 if customer_request_hint.is_none() {
@@ -127,29 +190,42 @@ impl SuzunaSubScene {
                 // 今回のTaskSceneで扱われるCustomerRequestを構築
                 let customer_request = match customer_request_hint.as_ref().unwrap() {
                     CustomerRequest::Borrowing(raw_info) => {
+                        // 客が頼んだ冊数までを、店の蔵書から取り出す
                         let borrowing_info = ctx
-                            .take_save_data_mut()
+                            .savable_data
+                            .as_mut()
+                            .expect("save data not found")
                             .suzuna_book_pool
                             .generate_borrowing_request(
+                                ctx.resource.rng(),
                                 &raw_info.borrower,
                                 raw_info.borrow_date,
                                 raw_info.rental_limit.clone(),
+                                raw_info.borrowing.len(),
                             );
 
+                        let today = ctx.today();
+                        ctx.take_save_data_mut()
+                            .book_stats
+                            .record_requested_books(&borrowing_info.borrowing, &today);
+
                         CustomerRequest::Borrowing(borrowing_info)
                     }
                     CustomerRequest::Returning(_) => {
                         let request = ctx
-                            .take_save_data()
+                            .savable_data
+                            .as_ref()
+                            .expect("save data not found")
                             .record_book_data
-                            .pick_returning_request_up()
+                            .pick_returning_request_up(ctx.resource.rng())
                             .unwrap();
-                        println!("returning count: {}", request.returning.len());
+                        log_debug!("returning count: {}", request.returning.len());
                         CustomerRequest::Returning(request)
                     }
                 };
 
                 let record_book_data = ctx.take_save_data().record_book_data.clone();
+                let desk_stats = shop_scene.clone_result_report().get_desk_stats().clone();
 
                 self.scene_status = SuzunaSceneStatus::DeskWork;
                 self.desk_work_scene = Some(Box::new(TaskScene::new(
@@ -157,6 +233,8 @@ impl SuzunaSubScene {
                     Some(customer_request),
                     record_book_data,
                     &self.tutorial_context,
+                    desk_stats,
+                    false,
                 )));
             }
         }
@@ -189,12 +267,32 @@ impl SuzunaSubScene {
         transition: SceneTransition,
     ) {
         if transition == SceneTransition::PoppingTransition {
-            println!("switch!!!!!!!!!, deskwork -> shop");
-            ctx.take_save_data_mut().record_book_data = self
-                .desk_work_scene
-                .as_ref()
-                .unwrap()
-                .export_borrowing_record_book_data();
+            log_debug!("switch!!!!!!!!!, deskwork -> shop");
+            let practice = self.desk_work_scene.as_ref().unwrap().is_practice();
+
+            // 練習で書いた帳簿や成績は残さない
+            if !practice {
+                // 机を離れられたのなら、書きかけの頁の控えはもう要らない
+                DeskAutosave::delete();
+
+                ctx.take_save_data_mut().record_book_data = self
+                    .desk_work_scene
+                    .as_ref()
+                    .unwrap()
+                    .export_borrowing_record_book_data();
+                self.shop_scene.as_mut().unwrap().update_desk_stats(
+                    self.desk_work_scene
+                        .as_ref()
+                        .unwrap()
+                        .get_session_stats()
+                        .clone(),
+                );
+
+                if let Some(record) = self.desk_work_scene.as_mut().unwrap().take_mistake_record() {
+                    self.shop_scene.as_mut().unwrap().add_mistake_record(record);
+                }
+            }
+
             self.tutorial_context = self
                 .desk_work_scene
                 .as_ref()
@@ -202,13 +300,19 @@ impl SuzunaSubScene {
                 .get_tutorial_context()
                 .clone();
             self.scene_status = SuzunaSceneStatus::Shop;
-            self.shop_scene.as_mut().unwrap().switched_and_restart(
-                ctx,
-                self.desk_work_scene.as_ref().unwrap().get_elapsed_clock(),
+
+            let condition_eval_report = if practice {
+                None
+            } else {
                 self.desk_work_scene
                     .as_ref()
                     .unwrap()
-                    .get_target_page_book_condition_eval_report(),
+                    .get_target_page_book_condition_eval_report()
+            };
+            self.shop_scene.as_mut().unwrap().switched_and_restart(
+                ctx,
+                self.desk_work_scene.as_ref().unwrap().get_elapsed_clock(),
+                condition_eval_report,
                 self.tutorial_context.clone(),
             );
             self.desk_work_scene = None;
@@ -372,6 +476,19 @@ impl SceneManager for SuzunaSubScene {
         }
     }
 
+    fn gamepad_stick_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, direction: numeric::Vector2f) {
+        // スティックで歩けるのは店の中だけ
+        match self.scene_status {
+            SuzunaSceneStatus::Shop => {
+                self.shop_scene
+                    .as_mut()
+                    .unwrap()
+                    .gamepad_stick_event(ctx, direction);
+            }
+            _ => (),
+        }
+    }
+
     fn pre_process<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
         match self.scene_status {
             SuzunaSceneStatus::Shop => {
@@ -379,6 +496,11 @@ impl SceneManager for SuzunaSubScene {
             }
             SuzunaSceneStatus::DeskWork => {
                 self.desk_work_scene.as_mut().unwrap().pre_process(ctx);
+
+                // 練習でなければ、机に向かっている間も店の時は進む
+                if !self.desk_work_scene.as_ref().unwrap().is_practice() {
+                    self.shop_scene.as_mut().unwrap().background_tick(ctx);
+                }
             }
             SuzunaSceneStatus::DayResult => {
                 self.day_result_scene.as_mut().unwrap().pre_process(ctx);
@@ -393,6 +515,10 @@ impl SceneManager for SuzunaSubScene {
             }
             SuzunaSceneStatus::DeskWork => {
                 self.desk_work_scene.as_mut().unwrap().drawing_process(ctx);
+                self.shop_scene
+                    .as_mut()
+                    .unwrap()
+                    .draw_background_notification(ctx);
             }
             SuzunaSceneStatus::DayResult => {
                 self.day_result_scene.as_mut().unwrap().drawing_process(ctx);
@@ -457,4 +583,75 @@ impl SceneManager for SuzunaSubScene {
             }
         }
     }
+
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        match self.scene_status {
+            SuzunaSceneStatus::Shop => self.shop_scene.as_mut().unwrap().idle_timeout_event(ctx),
+            SuzunaSceneStatus::DeskWork => self
+                .desk_work_scene
+                .as_mut()
+                .unwrap()
+                .idle_timeout_event(ctx),
+            SuzunaSceneStatus::DayResult => self
+                .day_result_scene
+                .as_mut()
+                .unwrap()
+                .idle_timeout_event(ctx),
+        }
+    }
+
+    fn shortcut_palette_is_open(&self) -> bool {
+        match self.scene_status {
+            SuzunaSceneStatus::Shop => self.shop_scene.as_ref().unwrap().shortcut_palette_is_open(),
+            SuzunaSceneStatus::DeskWork => self
+                .desk_work_scene
+                .as_ref()
+                .unwrap()
+                .shortcut_palette_is_open(),
+            SuzunaSceneStatus::DayResult => self
+                .day_result_scene
+                .as_ref()
+                .unwrap()
+                .shortcut_palette_is_open(),
+        }
+    }
+
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        match self.scene_status {
+            SuzunaSceneStatus::Shop => self
+                .shop_scene
+                .as_mut()
+                .unwrap()
+                .shortcut_palette_event(ctx, key),
+            SuzunaSceneStatus::DeskWork => self
+                .desk_work_scene
+                .as_mut()
+                .unwrap()
+                .shortcut_palette_event(ctx, key),
+            SuzunaSceneStatus::DayResult => self
+                .day_result_scene
+                .as_mut()
+                .unwrap()
+                .shortcut_palette_event(ctx, key),
+        }
+    }
+
+    ///
+    /// 店の中を歩いている間だけ。机で受付をしている途中では使えない
+    ///
+    fn quicksave_available(&self) -> bool {
+        match self.scene_status {
+            SuzunaSceneStatus::Shop => self.shop_scene.as_ref().unwrap().quicksave_available(),
+            _ => false,
+        }
+    }
+
+    fn quicksave_notification<'a>(&mut self, ctx: &mut SuzuContext<'a>, message: String) {
+        if self.scene_status == SuzunaSceneStatus::Shop {
+            self.shop_scene
+                .as_mut()
+                .unwrap()
+                .quicksave_notification(ctx, message);
+        }
+    }
 }
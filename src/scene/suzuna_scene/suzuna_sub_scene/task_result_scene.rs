@@ -8,6 +8,11 @@ use torifune::graphics::object::*;
 use super::super::*;
 
 use crate::flush_delay_event_and_redraw_check;
+use crate::core::diary::{DaySummary, DiaryPromptTable};
+use crate::core::ledger_audit;
+use crate::core::quicksave;
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::{util, GameMode};
 use crate::object::effect_object;
 use crate::object::task_result_object::*;
 use crate::object::util_object;
@@ -17,16 +22,22 @@ use crate::{core::SoundID, flush_delay_event};
 #[allow(unused_imports)]
 use crate::{
     core::{MouseInformation, ResultReport, SavableData, TextureID, TileBatchTextureID},
-    perf_measure,
+    log_warn, perf_measure,
 };
 use effect_object::TilingEffectType;
 
+const DIARY_PROMPTS_PATH: &str = "/diary_prompts.toml";
+
 pub struct TaskResultScene {
     clock: Clock,
     mouse_info: MouseInformation,
     event_list: DelayEventList<Self>,
     drawable_task_result: DrawableTaskResult,
     ok_button: util_object::FramedButton,
+    review_button: util_object::FramedButton,
+    result_report: ResultReport,
+    mistake_review: Option<MistakeReviewWindow>,
+    diary: Option<DiaryWindow>,
     scene_transition_status: SceneTransition,
     transition_scene: SceneID,
     scene_transition_effect: Option<effect_object::ScreenTileEffect>,
@@ -68,6 +79,14 @@ impl TaskResultScene {
 
 	ok_button.hide();
 
+        let mut review_button = util_object::FramedButton::create_design1(
+            ctx,
+            numeric::Point2f::new(60.0, 450.0),
+            "見直し",
+            numeric::Vector2f::new(28.0, 28.0),
+        );
+        review_button.hide();
+
         let scene_transition = Some(effect_object::ScreenTileEffect::new(
             ctx,
             TileBatchTextureID::Shoji,
@@ -106,22 +125,103 @@ impl TaskResultScene {
                 numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
                 SimpleObject::new(background_object, Vec::new()),
                 initial_save_data,
-                result_report,
+                result_report.clone(),
                 date,
                 0,
             ),
             ok_button: ok_button,
+            review_button: review_button,
+            result_report: result_report,
+            mistake_review: None,
+            diary: None,
             scene_transition_status: SceneTransition::Keep,
             transition_scene: SceneID::DayResult,
             scene_transition_effect: scene_transition,
         }
     }
 
+    ///
+    /// 戸締まりの後、眠る前に日記を開く。物語以外では書かない
+    ///
+    fn open_diary<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> bool {
+        match ctx.take_save_data().game_mode {
+            GameMode::Story => (),
+            GameMode::TimeAttack(_) => return false,
+        }
+
+        let table = match DiaryPromptTable::from_toml_str(&util::read_from_resources_as_string(
+            ctx.context,
+            DIARY_PROMPTS_PATH,
+        )) {
+            Ok(table) => table.with_nickname(ctx.preferences.nickname_or_default()),
+            Err(e) => {
+                log_warn!("failed to parse diary prompts: {}", e);
+                return false;
+            }
+        };
+
+        let desk_stats = self.result_report.get_desk_stats();
+        let save_data = ctx.take_save_data();
+        let day = DaySummary::new(
+            desk_stats.get_completed_requests(),
+            desk_stats.get_earned_money(),
+            &save_data.decision_log,
+            &save_data.date,
+        );
+        let date = save_data.date;
+
+        self.diary = Some(DiaryWindow::new(
+            ctx,
+            numeric::Rect::new(283.0, 84.0, 800.0, 600.0),
+            date,
+            table.available(&day),
+            0,
+        ));
+        true
+    }
+
+    fn close_diary<'a>(&mut self, ctx: &mut SuzuContext<'a>, action: DiaryAction, t: Clock) {
+        let diary = self.diary.take().unwrap();
+
+        if action == DiaryAction::Write && !diary.is_blank() {
+            ctx.take_save_data_mut().diary.write(diary.compose_entry());
+        }
+
+        self.ready_to_finish_scene(ctx, t);
+    }
+
     fn ready_to_finish_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         self.transition_scene = SceneID::Scenario;
         self.scene_transition_status = SceneTransition::SwapTransition;
+
+        // 日付を進める前に、今日の受取を写真帖に貼っておく
+        let desk_stats = self.result_report.get_desk_stats();
+        let eval = self.result_report.generate_eval_result();
+        let save_data = ctx.take_save_data_mut();
+        let date = save_data.date;
+        save_data.album.record_day(
+            date,
+            desk_stats.get_completed_requests(),
+            desk_stats.get_earned_money(),
+            eval,
+        );
+
         ctx.go_next_day();
 
+        if ctx.preferences.get_autosave_frequency().saves_on_new_day() {
+            quicksave::autosave(ctx.take_save_data());
+        }
+
+        // 週の終わりは帳簿を監査し、季節が替わって最初の朝は開店前に本市を開く
+        let next_date = ctx.today();
+        let audit_rows =
+            ledger_audit::collect_entries(&ctx.take_save_data().record_book_data.pages_data).len();
+        if next_date.is_week_first() && audit_rows >= ledger_audit::AUDIT_MIN_ROWS {
+            self.transition_scene = SceneID::LedgerAudit;
+        } else if ctx.take_save_data().book_fair.is_due(&next_date) {
+            self.transition_scene = SceneID::BookFair;
+        }
+
         self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
             ctx,
             TileBatchTextureID::Shoji,
@@ -148,6 +248,7 @@ impl SceneManager for TaskResultScene {
         _: numeric::Vector2f,
     ) {
         self.ok_button.mouse_motion_handler(ctx, point);
+        self.review_button.mouse_motion_handler(ctx, point);
         if self.mouse_info.is_dragging(MouseButton::Left) {
             self.mouse_info
                 .set_last_dragged(MouseButton::Left, point, self.get_current_clock());
@@ -164,6 +265,10 @@ impl SceneManager for TaskResultScene {
             self.ok_button.make_this_pressed_status(ctx);
         }
 
+        if self.review_button.contains(point) {
+            self.review_button.make_this_pressed_status(ctx);
+        }
+
         self.mouse_info
             .set_last_clicked(button, point, self.get_current_clock());
         self.mouse_info
@@ -181,13 +286,36 @@ impl SceneManager for TaskResultScene {
     ) {
         let t = self.get_current_clock();
 
-        if self.ok_button.is_visible() && self.ok_button.contains(point) {
-            self.ok_button.make_this_hovered_status(ctx);
-            self.ready_to_finish_scene(ctx, t);
+        if let Some(diary) = self.diary.as_mut() {
+            let action = diary.click_handler(ctx, point);
+            if action != DiaryAction::Keep {
+                self.close_diary(ctx, action, t);
+            }
+            ctx.process_utility.redraw();
+        } else if let Some(review) = self.mistake_review.as_mut() {
+            if review.click_handler(ctx, point, t) == MistakeReviewAction::Close {
+                self.mistake_review = None;
+            }
+            ctx.process_utility.redraw();
+        } else {
+            if self.ok_button.is_visible() && self.ok_button.contains(point) {
+                self.ok_button.make_this_hovered_status(ctx);
+                if !self.open_diary(ctx) {
+                    self.ready_to_finish_scene(ctx, t);
+                }
+            } else if self.review_button.is_visible() && self.review_button.contains(point) {
+                self.review_button.make_this_hovered_status(ctx);
+                self.mistake_review = Some(MistakeReviewWindow::new(
+                    ctx,
+                    numeric::Rect::new(20.0, 20.0, 1326.0, 728.0),
+                    self.result_report.clone(),
+                    0,
+                ));
+            }
+
+            self.drawable_task_result.click_handler(ctx, t);
         }
 
-        self.drawable_task_result.click_handler(ctx, t);
-
         self.mouse_info.update_dragging(button, false);
         self.mouse_info
             .set_last_up(button, point, self.get_current_clock());
@@ -204,6 +332,10 @@ impl SceneManager for TaskResultScene {
 
 	if self.drawable_task_result.evaluation_flow_is_done() {
 	    self.ok_button.appear();
+
+            if self.result_report.has_mistake_record() {
+                self.review_button.appear();
+            }
 	}
 
         if let Some(effect) = self.scene_transition_effect.as_mut() {
@@ -217,6 +349,15 @@ impl SceneManager for TaskResultScene {
         //println!("ResultScene::drawing_process {}", perf_measure!({
         self.drawable_task_result.draw(ctx).unwrap();
         self.ok_button.draw(ctx).unwrap();
+        self.review_button.draw(ctx).unwrap();
+
+        if let Some(review) = self.mistake_review.as_mut() {
+            review.draw(ctx).unwrap();
+        }
+
+        if let Some(diary) = self.diary.as_mut() {
+            diary.draw(ctx).unwrap();
+        }
 
         if let Some(effect) = self.scene_transition_effect.as_mut() {
             effect.draw(ctx).unwrap();
@@ -224,6 +365,26 @@ impl SceneManager for TaskResultScene {
         //}));
     }
 
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        let diary = match self.diary.as_mut() {
+            Some(diary) => diary,
+            None => return false,
+        };
+
+        match key {
+            PaletteKey::Char(c) => diary.input_char(c),
+            PaletteKey::Backspace => diary.backspace(),
+            _ => return false,
+        }
+
+        ctx.process_utility.redraw();
+        true
+    }
+
+    fn shortcut_palette_is_open(&self) -> bool {
+        self.diary.is_some()
+    }
+
     fn post_process<'a>(&mut self, _ctx: &mut SuzuContext<'a>) -> SceneTransition {
         self.update_current_clock();
         self.scene_transition_status
@@ -2,14 +2,35 @@ use ginput::mouse::MouseButton;
 use torifune::core::*;
 use torifune::device as tdev;
 use torifune::graphics::drawable::*;
-use torifune::graphics::object::Effectable;
+use torifune::graphics::object::{Effectable, FontInformation};
 use torifune::numeric;
 
 use super::super::*;
-use crate::{core::SoundID, object::{scenario::ScenarioEvent, Clickable, DarkEffectPanel}};
-
-use crate::core::{MouseActionRecord, MouseInformation, TileBatchTextureID};
+use crate::object::{scenario::ScenarioEvent, Clickable, DarkEffectPanel};
+
+use crate::core::day_modifier::DayModifierType;
+use crate::core::decision_log::DecisionKind;
+use crate::core::desk_autosave::DeskAutosave;
+use crate::core::economy;
+use crate::core::evaluation::{Evaluator, RequestOutcome};
+use crate::core::game_rng::GameRng;
+use crate::core::game_system::AssistChange;
+use crate::core::gift_wrapping::{self, GIFT_WRAPPING_ASK_RATE};
+use crate::core::membership::{
+    CardCheck, MembershipCard, MEMBERSHIP_MISTAKE_PENALTY, MEMBERSHIP_RENEWAL_FEE,
+};
+use crate::core::record_validation;
+use crate::core::reputation::SatisfactionEvent;
+use crate::core::reservation::RESERVATION_ASK_RATE;
+use crate::core::shortcut_palette::{PaletteKey, ShortcutEntry, ShortcutPalette};
+use crate::core::{
+    BorrowingInformation, DeskSessionStats, FontID, GensoDate, MistakeRecord, MouseActionRecord,
+    MouseInformation,
+};
+use crate::object::desk_shortcut::DeskShortcut;
 use crate::object::effect_object;
+use crate::object::highlight::{Highlight, TUTORIAL_HIGHLIGHT_COLOR};
+use crate::object::pause::{PauseResult, PauseScreenSet};
 use crate::object::task_object::*;
 use crate::object::util_object::*;
 use crate::scene::{SceneID, SceneTransition};
@@ -19,8 +40,16 @@ use crate::perf_measure;
 
 use crate::flush_delay_event;
 use crate::object::task_object::tt_main_component::*;
+use crate::object::task_object::tt_menu_component::{
+    GiftWrappingBoard, MembershipForm, ReservationForm,
+};
 use crate::{flush_delay_event_and_redraw_check, object::task_object::tt_sub_component::*};
 
+///
+/// 押した位置からこれ以上動いたらドラッグを始める（px）
+///
+const DESK_DRAG_THRESHOLD: f32 = 4.0;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TaskSceneStatus {
     CustomerFree,
@@ -29,6 +58,18 @@ pub enum TaskSceneStatus {
     FinishDay,
 }
 
+///
+/// # ショートカットの一覧から机で選べる操作
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskShortcut {
+    OpenRecordBook,
+    CloseRecordBook,
+    BundleDeskSelection,
+    UnbundleDeskSelection,
+    Pause,
+}
+
 pub struct TaskScene {
     task_table: TaskTable,
     clock: Clock,
@@ -39,10 +80,25 @@ pub struct TaskScene {
     customer_request: Option<CustomerRequest>,
     transition_status: SceneTransition,
     transition_scene: SceneID,
-    scene_transition_effect: Option<effect_object::ScreenTileEffect>,
+    scene_transition_effect: Option<Box<dyn effect_object::SceneTransitionEffect>>,
     tutorial_context: TaskTutorialContext,
     scenario_event: Option<ScenarioEvent>,
     dark_effect_panel: DarkEffectPanel,
+    session_stats: DeskSessionStats,
+    evaluator: Evaluator,
+    stats_bar: StatsBar,
+    customer_event_start: Clock,
+    money_at_event_start: i32,
+    practice: bool,
+    mistake_record: Option<MistakeRecord>,
+    restore_offer: Option<DeskAutosave>,
+    reservation_form: Option<ReservationForm>,
+    membership_form: Option<MembershipForm>,
+    membership_request: Option<(String, usize)>,
+    gift_wrapping_requested: bool,
+    gift_wrapping_board: Option<GiftWrappingBoard>,
+    shortcut_palette: Option<ShortcutPalette<TaskShortcut>>,
+    shortcut_palette_window: ShortcutPaletteWindow,
 }
 
 impl TaskScene {
@@ -51,21 +107,30 @@ impl TaskScene {
         customer_request: Option<CustomerRequest>,
         record_book_data: BorrowingRecordBookData,
         tutorial_context: &TaskTutorialContext,
+        session_stats: DeskSessionStats,
+        practice: bool,
     ) -> TaskScene {
         let animation_time = 30;
 
-        let scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+        let mut stats_bar = StatsBar::new(
             ctx,
-            TileBatchTextureID::Shoji,
-            numeric::Rect::new(
-                0.0,
-                0.0,
-                crate::core::WINDOW_SIZE_X as f32,
-                crate::core::WINDOW_SIZE_Y as f32,
+            numeric::Rect::new(300.0, 0.0, 766.0, 30.0),
+            vec!["受付", "正確さ", "平均対応", "売上", "評点"],
+            FontInformation::new(
+                ctx.resource.get_font(FontID::JpFude1),
+                numeric::Vector2f::new(18.0, 18.0),
+                ggez::graphics::Color::from_rgba_u32(0xff),
             ),
+            0,
+        );
+        Self::update_stats_bar_values(&mut stats_bar, &session_stats);
+
+        // 机に向かうときは、頁をめくるように画面を切り替える
+        let scene_transition_effect = Some(effect_object::new_scene_transition_effect(
+            ctx,
+            effect_object::SceneTransitionEffectKind::PageSlide,
             animation_time,
             effect_object::SceneTransitionEffectType::Open,
-            effect_object::TilingEffectType::WholeTile,
             -128,
             0,
         ));
@@ -78,9 +143,13 @@ impl TaskScene {
             animation_time + 1,
         );
 
-        if let Some(customer_request) = customer_request.as_ref() {
+        let mut gift_wrapping_requested = false;
+        let mut membership_request = None;
+
+        // 練習の受付は記録にも残さない
+        if let Some(customer_request) = customer_request.as_ref().filter(|_| !practice) {
             match customer_request {
-                CustomerRequest::Borrowing(_) => {
+                CustomerRequest::Borrowing(request_information) => {
                     ctx.take_save_data_mut().award_data.borrowing_count += 1;
                     if !tutorial_context.borrowing_request {
                         event_list.add_event(
@@ -91,9 +160,70 @@ impl TaskScene {
                                     t,
                                 );
                                 slf.dark_effect_panel.new_effect(8, t, 0, 200);
+                                // 客の持ってきた本を指し示す
+                                slf.task_table.highlight_desk_objects(
+                                    ctx,
+                                    DeskObjectType::CustomerObject,
+                                    Some(Highlight::pulse(
+                                        ggez::graphics::Color::from_rgba_u32(
+                                            TUTORIAL_HIGHLIGHT_COLOR,
+                                        ),
+                                        t,
+                                    )),
+                                );
                             }),
                             31,
                         );
+                    } else if let Some(title) = Self::pick_reserving_title(ctx, request_information)
+                    {
+                        // 客が机の前に来てから予約を申し出る
+                        let customer_name = request_information.borrower.clone();
+                        event_list.add_event(
+                            Box::new(move |slf: &mut TaskScene, ctx, t| {
+                                slf.open_reservation_form(ctx, &customer_name, &title, t);
+                            }),
+                            160,
+                        );
+                    }
+
+                    // 客は本と一緒に会員証を出す
+                    if tutorial_context.borrowing_request {
+                        let today = ctx.today();
+                        let borrower = request_information.borrower.clone();
+                        let rng = ctx.resource.rng();
+                        let card = ctx
+                            .savable_data
+                            .as_mut()
+                            .expect("save data not found")
+                            .membership
+                            .get_or_issue(&borrower, || {
+                                Self::issue_membership_card(rng, &borrower, &today)
+                            })
+                            .clone();
+                        membership_request = Some((borrower, request_information.borrowing.len()));
+                        event_list.add_event(
+                            Box::new(move |slf: &mut TaskScene, ctx, t| {
+                                slf.task_table.present_membership_card(ctx, card, t);
+                            }),
+                            120,
+                        );
+                    }
+
+                    // 贈り物にする客は、受付が済んだ後に包んでもらう
+                    if tutorial_context.borrowing_request
+                        && ctx.resource.rng().chance(GIFT_WRAPPING_ASK_RATE)
+                    {
+                        gift_wrapping_requested = true;
+                        event_list.add_event(
+                            Box::new(move |slf: &mut TaskScene, ctx, t| {
+                                slf.task_table.insert_kosuzu_phrase(
+                                    ctx,
+                                    "贈り物なのね。後で包むわ",
+                                    t,
+                                );
+                            }),
+                            100,
+                        );
                     }
                 }
                 CustomerRequest::Returning(_) => {
@@ -107,6 +237,17 @@ impl TaskScene {
                                     t,
                                 );
                                 slf.dark_effect_panel.new_effect(8, t, 0, 200);
+                                // 客の持ってきた本を指し示す
+                                slf.task_table.highlight_desk_objects(
+                                    ctx,
+                                    DeskObjectType::CustomerObject,
+                                    Some(Highlight::pulse(
+                                        ggez::graphics::Color::from_rgba_u32(
+                                            TUTORIAL_HIGHLIGHT_COLOR,
+                                        ),
+                                        t,
+                                    )),
+                                );
                             }),
                             31,
                         );
@@ -115,18 +256,27 @@ impl TaskScene {
             }
         }
 
+        let mut task_table = TaskTable::new(
+            ctx,
+            numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
+            numeric::Rect::new(0.0, 0.0, 300.0, 768.0),
+            numeric::Rect::new(300.0, 0.0, 1066.0, 300.0),
+            numeric::Rect::new(300.0, 300.0, 766.0, 468.0),
+            numeric::Rect::new(1066.0, 300.0, 300.0, 468.0),
+            record_book_data,
+            customer_request.clone(),
+            0,
+        );
+        if practice {
+            task_table.disable_autosave();
+        }
+
+        let mut shortcut_palette_window =
+            ShortcutPaletteWindow::new(ctx, numeric::Rect::new(383.0, 120.0, 600.0, 330.0), 0);
+        shortcut_palette_window.hide();
+
         TaskScene {
-            task_table: TaskTable::new(
-                ctx,
-                numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
-                numeric::Rect::new(0.0, 0.0, 300.0, 768.0),
-                numeric::Rect::new(300.0, 0.0, 1066.0, 300.0),
-                numeric::Rect::new(300.0, 300.0, 766.0, 468.0),
-                numeric::Rect::new(1066.0, 300.0, 300.0, 468.0),
-                record_book_data,
-                customer_request.clone(),
-                0,
-            ),
+            task_table: task_table,
             clock: 0,
             pause_screen_set: PauseScreenSet::new(ctx, 0, 0),
             mouse_info: MouseInformation::new(),
@@ -143,7 +293,476 @@ impl TaskScene {
                 numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
                 0,
             ),
+            session_stats: session_stats,
+            evaluator: Evaluator::new(),
+            stats_bar: stats_bar,
+            customer_event_start: 0,
+            money_at_event_start: 0,
+            practice: practice,
+            mistake_record: None,
+            restore_offer: None,
+            reservation_form: None,
+            membership_form: None,
+            membership_request: membership_request,
+            gift_wrapping_requested: gift_wrapping_requested,
+            gift_wrapping_board: None,
+            shortcut_palette: None,
+            shortcut_palette_window: shortcut_palette_window,
+        }
+    }
+
+    pub fn is_practice(&self) -> bool {
+        self.practice
+    }
+
+    ///
+    /// 落ちる前に書いていた頁を戻すかどうか、机に着いたところで尋ねる
+    ///
+    pub fn offer_desk_restore(&mut self, autosave: DeskAutosave) {
+        self.restore_offer = Some(autosave);
+        self.event_list.add_event(
+            Box::new(move |slf: &mut TaskScene, ctx, t| {
+                slf.set_fixed_text_into_scenario_box(ctx, "/scenario/desk_restore.toml", t);
+                slf.dark_effect_panel.new_effect(8, t, 0, 200);
+            }),
+            31,
+        );
+    }
+
+    ///
+    /// 頼まれた本のほかに、貸出中で予約したい本があればその題名を返す
+    ///
+    fn pick_reserving_title<'a>(
+        ctx: &mut SuzuContext<'a>,
+        request_information: &BorrowingInformation,
+    ) -> Option<String> {
+        if !ctx.resource.rng().chance(RESERVATION_ASK_RATE) {
+            return None;
+        }
+
+        let save_data = ctx.take_save_data();
+        let candidates: Vec<String> = save_data
+            .record_book_data
+            .lent_out_titles()
+            .into_iter()
+            .filter(|title| {
+                !request_information
+                    .borrowing
+                    .iter()
+                    .any(|book| &book.name == title)
+            })
+            .filter(|title| {
+                !save_data
+                    .reservation_ledger
+                    .is_reserved_by(&request_information.borrower, title)
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let index = ctx.resource.rng().below(candidates.len());
+        Some(candidates[index].clone())
+    }
+
+    fn open_reservation_form<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        customer_name: &str,
+        book_title: &str,
+        t: Clock,
+    ) {
+        self.reservation_form = Some(ReservationForm::new(
+            ctx,
+            numeric::Point2f::new(473.0, 164.0),
+            customer_name,
+            book_title,
+            0,
+        ));
+        self.task_table
+            .insert_kosuzu_phrase(ctx, "その本は今貸し出し中なのよ", t);
+        ctx.process_utility.redraw();
+    }
+
+    fn reservation_form_click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) {
+        let accepted = match self.reservation_form.as_mut() {
+            Some(form) => {
+                form.click_handler(ctx.context, point);
+                match form.get_last_clicked_index() {
+                    Some(index) => index == 0,
+                    None => return,
+                }
+            }
+            None => return,
+        };
+
+        let form = self.reservation_form.take().unwrap();
+        if accepted {
+            let today = ctx.today();
+            ctx.take_save_data_mut().reservation_ledger.reserve(
+                form.get_customer_name(),
+                form.get_book_title(),
+                &today,
+            );
+            self.task_table
+                .insert_kosuzu_phrase(ctx, "返ってきたら取り置いておくわね", t);
+        } else {
+            let today = ctx.today();
+            ctx.take_save_data_mut().decision_log.record(
+                DecisionKind::Refusal,
+                form.get_book_title(),
+                today,
+            );
+            ctx.take_save_data_mut()
+                .suzunaan_status
+                .apply_satisfaction(SatisfactionEvent::Refused);
+            self.task_table.react_customer(false);
+            self.task_table
+                .insert_kosuzu_phrase(ctx, "ごめんなさい、また今度ね", t);
+        }
+
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 名簿に無い客の会員証を作る。前から持っていたものとして、上限や期限はまちまちにする
+    ///
+    fn issue_membership_card(rng: &mut GameRng, holder: &str, today: &GensoDate) -> MembershipCard {
+        let limit = 3 + rng.below(3);
+        let lending = rng.below(limit + 1);
+        let offset = rng.below(60) as i32 - 15;
+        let expiry = if offset < 0 {
+            today.sub_days_chain(-offset)
+        } else {
+            today.add_days_chain(offset)
+        };
+
+        MembershipCard::new(holder, limit, lending, expiry)
+    }
+
+    fn open_membership_form<'a>(&mut self, ctx: &mut SuzuContext<'a>, card: MembershipCard) {
+        let requested = match self.membership_request.as_ref() {
+            Some((_, requested)) => *requested,
+            None => return,
+        };
+
+        self.membership_form = Some(MembershipForm::new(
+            ctx,
+            numeric::Point2f::new(473.0, 164.0),
+            card,
+            requested,
+            0,
+        ));
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 名簿の会員証で、今回の貸出を見る
+    ///
+    fn check_membership<'a>(&self, ctx: &mut SuzuContext<'a>) -> Option<CardCheck> {
+        let (holder, requested) = self.membership_request.as_ref()?;
+        let save_data = ctx.take_save_data();
+        let card = save_data.membership.get(holder)?;
+
+        Some(card.check(*requested, &save_data.date))
+    }
+
+    fn penalize_membership_mistake<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.session_stats.add_score(-MEMBERSHIP_MISTAKE_PENALTY);
+        Self::update_stats_bar_values(&mut self.stats_bar, &self.session_stats);
+        self.task_table
+            .insert_kosuzu_phrase(ctx, "あっ、会員証を確かめ損ねたわ", t);
+    }
+
+    fn lend_on_membership<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let check = self.check_membership(ctx);
+        let (holder, requested) = match self.membership_request.take() {
+            Some(request) => request,
+            None => return,
+        };
+
+        if check == Some(CardCheck::Valid) {
+            self.task_table
+                .insert_kosuzu_phrase(ctx, "会員証、確かに拝見しました", t);
+        } else {
+            self.penalize_membership_mistake(ctx, t);
+        }
+
+        ctx.take_save_data_mut().membership.lend(&holder, requested);
+    }
+
+    fn renew_membership<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.check_membership(ctx) != Some(CardCheck::Expired) {
+            self.task_table
+                .insert_kosuzu_phrase(ctx, "まだ期限は切れていないわね", t);
+            return;
+        }
+
+        let holder = match self.membership_request.as_ref() {
+            Some((holder, _)) => holder.clone(),
+            None => return,
+        };
+        let save_data = ctx.take_save_data_mut();
+        let today = save_data.date;
+        let card = match save_data.membership.renew(&holder, &today) {
+            Some(card) => card,
+            None => return,
+        };
+        save_data.task_result.total_money += MEMBERSHIP_RENEWAL_FEE;
+
+        self.task_table.replace_membership_card(ctx, card);
+        self.task_table
+            .insert_kosuzu_phrase(ctx, "会員証を更新しておきますね", t);
+    }
+
+    ///
+    /// 貸出を断る。受付の始めに付けた売上と貸出の記録を取り消す
+    ///
+    fn refuse_on_membership<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let check = self.check_membership(ctx);
+        let (holder, _) = match self.membership_request.take() {
+            Some(request) => request,
+            None => return,
+        };
+        ctx.take_save_data_mut()
+            .suzunaan_status
+            .apply_satisfaction(SatisfactionEvent::Refused);
+        self.task_table.react_customer(false);
+
+        if check == Some(CardCheck::Valid) {
+            self.penalize_membership_mistake(ctx, t);
+        } else {
+            self.task_table
+                .insert_kosuzu_phrase(ctx, "ごめんなさい、今日はお貸しできないの", t);
+        }
+
+        if let Some(CustomerRequest::Borrowing(info)) = self.customer_request.as_ref() {
+            let save_data = ctx.take_save_data_mut();
+            let fee = save_data
+                .suzunaan_status
+                .apply_late_night_pay(info.calc_fee());
+            save_data
+                .economy
+                .refund_rental_fee(&mut save_data.task_result, fee);
+
+            for book in info.borrowing.iter() {
+                let borrowing_books = &mut save_data.task_result.borrowing_books;
+                if let Some(index) = borrowing_books.iter().position(|b| b == book) {
+                    borrowing_books.remove(index);
+                }
+            }
+        }
+
+        let today = ctx.today();
+        ctx.take_save_data_mut()
+            .decision_log
+            .record(DecisionKind::Refusal, &holder, today);
+    }
+
+    fn membership_form_click_handler<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) {
+        let index = match self.membership_form.as_mut() {
+            Some(form) => {
+                form.click_handler(ctx.context, point);
+                match form.get_last_clicked_index() {
+                    Some(index) => index,
+                    None => return,
+                }
+            }
+            None => return,
+        };
+
+        self.membership_form = None;
+        match index {
+            0 => self.lend_on_membership(ctx, t),
+            1 => self.renew_membership(ctx, t),
+            _ => self.refuse_on_membership(ctx, t),
+        }
+
+        ctx.process_utility.redraw();
+    }
+
+    fn open_gift_wrapping_board<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.gift_wrapping_board = Some(GiftWrappingBoard::new(
+            ctx,
+            numeric::Point2f::new(463.0, 164.0),
+            0,
+        ));
+        self.task_table
+            .insert_kosuzu_phrase(ctx, "角を揃えて、丁寧に包まないと", t);
+        ctx.process_utility.redraw();
+    }
+
+    fn gift_wrapping_board_button_up<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        point: numeric::Point2f,
+        t: Clock,
+    ) {
+        let quality = match self.gift_wrapping_board.as_mut() {
+            Some(board) => {
+                board.button_up(ctx.context, point);
+                if !board.is_done() {
+                    ctx.process_utility.redraw();
+                    return;
+                }
+                board.get_quality()
+            }
+            None => return,
+        };
+
+        self.gift_wrapping_board = None;
+
+        let tip = gift_wrapping::wrapping_tip(quality);
+        let save_data = ctx.take_save_data_mut();
+        save_data.wrapping_supplies.consume();
+        save_data.task_result.total_money += tip;
+
+        let phrase = if tip > 0 {
+            format!("綺麗に包めた。{}円も頂いちゃった", tip)
+        } else {
+            "ちょっと不格好になっちゃった".to_string()
+        };
+        self.task_table.insert_kosuzu_phrase(ctx, &phrase, t);
+
+        self.after_task_done_process(ctx, t);
+    }
+
+    ///
+    /// 状態評価を誤った返却受付だった場合、その記録を取り出す
+    ///
+    pub fn take_mistake_record(&mut self) -> Option<MistakeRecord> {
+        self.mistake_record.take()
+    }
+
+    fn update_stats_bar_values(stats_bar: &mut StatsBar, stats: &DeskSessionStats) {
+        stats_bar.update_value(0, format!("{}件", stats.get_completed_requests()));
+        stats_bar.update_value(1, format!("{}%", stats.accuracy_percent()));
+        stats_bar.update_value(2, format!("{}秒", stats.average_handling_clock() / 60));
+        stats_bar.update_value(3, format!("{}円", stats.get_earned_money()));
+        stats_bar.update_value(4, format!("{}点", stats.get_total_score()));
+    }
+
+    pub fn get_session_stats(&self) -> &DeskSessionStats {
+        &self.session_stats
+    }
+
+    ///
+    /// 完了した受付をStatsBarの集計に加える
+    ///
+    fn record_session_stats<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let (evaluated, mistakes) =
+            match self.task_table.get_target_page_book_condition_eval_report() {
+                Some(report) => (report.count_evaluated(), report.count_mistake()),
+                None => (0, 0),
+            };
+
+        if self.practice {
+            let phrase = if mistakes == 0 {
+                "今度は見誤らなかったわ"
+            } else {
+                "まだ見誤っているわね"
+            };
+            self.task_table.insert_kosuzu_phrase(ctx, phrase, t);
+            return;
+        }
+
+        if mistakes > 0 {
+            self.record_mistake();
+        }
+        self.record_page_score(ctx);
+        let earned = ctx.take_save_data().task_result.total_money - self.money_at_event_start;
+        let handling_clock = t - self.customer_event_start;
+
+        self.session_stats.record_task(evaluated, mistakes, handling_clock, earned);
+
+        // 受付の種類に合った決まりで点を付ける
+        if let Some(request) = self.customer_request.as_ref() {
+            let outcome = RequestOutcome::new(
+                request.get_request_kind(),
+                evaluated,
+                mistakes,
+                handling_clock,
+                earned,
+            );
+            if let Some(score) = self.evaluator.evaluate(&outcome) {
+                self.session_stats.add_score(score);
+            }
         }
+        Self::update_stats_bar_values(&mut self.stats_bar, &self.session_stats);
+
+        // 状態評価に苦戦していれば補助を勧め、持ち直していれば補助を切る
+        let change = ctx
+            .take_save_data_mut()
+            .difficulty_assist
+            .record_request(evaluated, mistakes);
+        match change {
+            AssistChange::Offer => {
+                self.set_fixed_text_into_scenario_box(ctx, "/scenario/assist_offer.toml", t);
+                self.dark_effect_panel.new_effect(8, t, 0, 200);
+            }
+            AssistChange::Recovered => {
+                self.task_table.update_assist_highlight(ctx);
+                self.task_table
+                    .insert_kosuzu_phrase(ctx, "もう手助けは要らないわね", t);
+            }
+            AssistChange::Keep => (),
+        }
+    }
+
+    ///
+    /// 署名した頁を依頼と突き合わせて採点し、StatsBarの集計とTaskResultに残す
+    ///
+    fn record_page_score<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        let score = match (
+            self.task_table.get_signed_page_data(),
+            self.customer_request.as_ref(),
+        ) {
+            (Some(page_data), Some(request)) => {
+                record_validation::validate_page(page_data, request)
+            }
+            _ => return,
+        };
+
+        self.session_stats.record_page_score(&score);
+
+        let save_data = ctx.take_save_data_mut();
+        save_data
+            .suzunaan_status
+            .apply_satisfaction(SatisfactionEvent::RecordMistakes(score.mistakes.len()));
+        save_data.task_result.record_scores.push(score);
+    }
+
+    fn record_mistake(&mut self) {
+        let request = match self.customer_request.as_ref() {
+            Some(CustomerRequest::Returning(request)) => request.clone(),
+            _ => return,
+        };
+        let submitted_page = match self.task_table.get_signed_page_data() {
+            Some(page_data) => page_data.clone(),
+            None => return,
+        };
+        let evaluations = match self.task_table.get_target_page_book_condition_eval_report() {
+            Some(report) => report.to_evaluation_pairs(),
+            None => return,
+        };
+
+        self.mistake_record = Some(MistakeRecord {
+            order: self.session_stats.get_completed_requests() + 1,
+            request: request,
+            submitted_page: submitted_page,
+            evaluations: evaluations,
+        });
     }
 
     fn dragging_handler<'a>(
@@ -153,6 +772,18 @@ impl TaskScene {
         _offset: numeric::Vector2f,
     ) {
         let last = self.mouse_info.get_last_dragged(MouseButton::Left);
+
+        // 疲れていると手元が揺れる。団子を食べて集中している間は揺れない
+        let save_data = ctx.take_save_data();
+        let wobble = if save_data.day_modifiers.is_active(DayModifierType::Focus) {
+            numeric::Vector2f::new(0.0, 0.0)
+        } else {
+            save_data
+                .suzunaan_status
+                .drag_wobble(self.get_current_clock())
+        };
+        let point = numeric::Point2f::new(point.x + wobble.x, point.y + wobble.y);
+
         self.task_table.dragging_handler(ctx, point, last);
         self.task_table.hand_over_check(ctx, point);
     }
@@ -164,27 +795,52 @@ impl TaskScene {
     fn insert_customer_event(&mut self, request: CustomerRequest, delay_clock: Clock) {
         self.event_list.add_event(
             Box::new(|s: &mut TaskScene, ctx, _| {
+                s.customer_event_start = s.get_current_clock();
+                s.money_at_event_start = ctx.take_save_data().task_result.total_money;
                 s.task_table
                     .start_customer_event(ctx, request.clone(), s.get_current_clock());
                 s.status = TaskSceneStatus::CustomerEvent;
-                s.check_done_today_work(ctx, request);
+                if !s.practice {
+                    s.check_done_today_work(ctx, request);
+                }
             }),
             self.get_current_clock() + delay_clock,
         );
     }
 
     fn check_done_today_work<'a>(&mut self, ctx: &mut SuzuContext<'a>, request: CustomerRequest) {
-        let task_result = &mut ctx.take_save_data_mut().task_result;
+        let save_data = ctx.take_save_data_mut();
+        let task_result = &mut save_data.task_result;
         match request {
             CustomerRequest::Borrowing(request_information) => {
                 // 貸出本を記録
                 task_result.done_works += 1;
-                task_result.total_money += request_information.calc_fee();
+                let fee = save_data
+                    .suzunaan_status
+                    .apply_late_night_pay(request_information.calc_fee());
+                save_data.economy.receive_rental_fee(task_result, fee);
                 task_result
                     .borrowing_books
                     .extend(request_information.borrowing);
             }
             CustomerRequest::Returning(request_information) => {
+                // 予約されていた本が返ってきたら、取り置いて翌日の来店を待つ
+                let today = save_data.date;
+                for book in request_information.returning.iter() {
+                    save_data
+                        .reservation_ledger
+                        .book_returned(&book.name, &today);
+                }
+
+                save_data.membership.give_back(
+                    &request_information.borrower,
+                    request_information.returning.len(),
+                );
+
+                // 返却期限を過ぎていれば延滞料を頂く
+                let late_fee = economy::late_return_fee(&request_information, &today);
+                save_data.economy.receive_late_fee(task_result, late_fee);
+
                 // 貸出本を記録
                 task_result.done_works += 1;
                 task_result
@@ -200,6 +856,7 @@ impl TaskScene {
 
     pub fn ready_to_finish_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         self.status = TaskSceneStatus::FinishDay;
+        self.task_table.close_all_menus(t);
         self.event_list.add_event(
             Box::new(move |slf: &mut TaskScene, _, _| {
                 slf.transition_scene = SceneID::SuzunaShop;
@@ -224,29 +881,23 @@ impl TaskScene {
     }
 
     fn scene_transition_close_effect<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
-        self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
+        self.scene_transition_effect = Some(effect_object::new_scene_transition_effect(
             ctx,
-            TileBatchTextureID::Shoji,
-            numeric::Rect::new(
-                0.0,
-                0.0,
-                crate::core::WINDOW_SIZE_X as f32,
-                crate::core::WINDOW_SIZE_Y as f32,
-            ),
+            effect_object::SceneTransitionEffectKind::PageSlide,
             30,
             effect_object::SceneTransitionEffectType::Close,
-            effect_object::TilingEffectType::WholeTile,
             -128,
             t,
         ));
     }
 
     fn transition_to_title_scene<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.task_table.close_all_menus(t);
         self.event_list.add_event(
             Box::new(|slf: &mut Self, ctx, _| {
                 slf.transition_status = SceneTransition::SwapTransition;
                 slf.transition_scene = SceneID::Title;
-		ctx.resource.stop_bgm(ctx.context, SoundID::ShopBGM);
+		ctx.resource.stop_scene_bgm(ctx.context, SceneID::MainDesk);
             }),
             t + 60,
         );
@@ -258,6 +909,7 @@ impl TaskScene {
     }
 
     fn enter_pause_screen<'a>(&mut self, t: Clock) {
+        self.task_table.close_all_menus(t);
         self.pause_screen_set.enter_pause(t);
     }
 
@@ -265,6 +917,91 @@ impl TaskScene {
         self.pause_screen_set.is_paused_now()
     }
 
+    fn shortcut_entries(&self) -> Vec<ShortcutEntry<TaskShortcut>> {
+        let record_book = if self.task_table.record_book_is_staged() {
+            ShortcutEntry::new(
+                TaskShortcut::CloseRecordBook,
+                "帳簿を閉じる",
+                "ちょうぼをとじる",
+            )
+        } else {
+            ShortcutEntry::new(
+                TaskShortcut::OpenRecordBook,
+                "帳簿を開く",
+                "ちょうぼをひらく",
+            )
+        };
+
+        let mut entries = vec![record_book];
+
+        if self.task_table.desk_selected_count() >= 2 {
+            entries.push(ShortcutEntry::new(
+                TaskShortcut::BundleDeskSelection,
+                "書類を束ねる",
+                "しょるいをたばねる",
+            ));
+        }
+
+        if self.task_table.desk_has_selected_bundle() {
+            entries.push(ShortcutEntry::new(
+                TaskShortcut::UnbundleDeskSelection,
+                "束を解く",
+                "たばをとく",
+            ));
+        }
+
+        entries.push(ShortcutEntry::new(TaskShortcut::Pause, "休憩", "きゅうけい"));
+        entries
+    }
+
+    fn update_shortcut_palette_window<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if let Some(palette) = self.shortcut_palette.as_ref() {
+            let labels = palette
+                .matched_entries()
+                .iter()
+                .map(|entry| entry.label.clone())
+                .collect();
+            self.shortcut_palette_window.update_contents(
+                palette.get_query(),
+                labels,
+                palette.get_selected_index(),
+            );
+        }
+
+        ctx.process_utility.redraw();
+    }
+
+    fn close_shortcut_palette<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        self.shortcut_palette = None;
+        self.shortcut_palette_window.hide();
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// 一覧で選ばれた操作を、キーやクリックと同じ処理で実行する
+    ///
+    fn run_task_shortcut<'a>(&mut self, ctx: &mut SuzuContext<'a>, shortcut: TaskShortcut) {
+        let t = self.get_current_clock();
+
+        match shortcut {
+            TaskShortcut::OpenRecordBook => {
+                self.task_table.open_record_book(t);
+            }
+            TaskShortcut::CloseRecordBook => {
+                self.task_table.close_record_book(t);
+            }
+            TaskShortcut::BundleDeskSelection => {
+                self.task_table.bundle_desk_selection(ctx, t);
+            }
+            TaskShortcut::UnbundleDeskSelection => {
+                self.task_table.unbundle_desk_selection(ctx);
+            }
+            TaskShortcut::Pause => self.enter_pause_screen(t),
+        }
+
+        ctx.process_utility.redraw();
+    }
+
     pub fn get_elapsed_clock(&self) -> Clock {
         self.get_current_clock()
     }
@@ -297,6 +1034,29 @@ impl TaskScene {
         //self.paper.button_up(ctx, button, point);
         self.unselect_dragging_object(ctx, self.get_current_clock());
 
+        // 包んでいる間は、包装台のほかに触らせない
+        if self.gift_wrapping_board.is_some() {
+            self.mouse_info
+                .set_last_up(button, point, self.get_current_clock());
+            self.gift_wrapping_board_button_up(ctx, point, t);
+            return;
+        }
+
+        // 予約票を出している間は、机の上の物に触らせない
+        if self.reservation_form.is_some() {
+            self.mouse_info
+                .set_last_up(button, point, self.get_current_clock());
+            self.reservation_form_click_handler(ctx, point, t);
+            return;
+        }
+
+        if self.membership_form.is_some() {
+            self.mouse_info
+                .set_last_up(button, point, self.get_current_clock());
+            self.membership_form_click_handler(ctx, point, t);
+            return;
+        }
+
         self.task_table
             .button_up(ctx, self.get_current_clock(), button, point);
 
@@ -304,6 +1064,10 @@ impl TaskScene {
         if (info.point.x - point.x).powf(2.0) + (info.point.y - point.y).powf(2.0) < 2.5 {
             self.task_table
                 .on_click(ctx, self.get_current_clock(), button, point);
+
+            if let Some(card) = self.task_table.take_membership_check_request() {
+                self.open_membership_form(ctx, card);
+            }
         }
 
         self.mouse_info
@@ -329,6 +1093,16 @@ impl TaskScene {
         self.mouse_info
             .set_last_dragged(button, point, self.get_current_clock());
 
+        if let Some(board) = self.gift_wrapping_board.as_mut() {
+            board.button_down(ctx.context, point);
+            ctx.process_utility.redraw();
+            return;
+        }
+
+        if self.reservation_form.is_some() || self.membership_form.is_some() {
+            return;
+        }
+
         self.task_table
             .button_down(ctx, self.get_current_clock(), button, point);
     }
@@ -341,12 +1115,80 @@ impl TaskScene {
         }
     }
 
+    ///
+    /// 接客の出来から客の別れ際の調子を決め、心付けや評判を反映する
+    ///
+    fn customer_farewell<'a>(&mut self, ctx: &mut SuzuContext<'a>) -> CustomerFarewellTone {
+        let mistakes = match self.task_table.get_target_page_book_condition_eval_report() {
+            Some(report) => report.count_mistake(),
+            None => 0,
+        };
+        let tone = CustomerFarewellTone::from_mistakes(mistakes);
+
+        if self.practice {
+            return tone;
+        }
+
+        let tip = match tone.tip_chance() {
+            Some(chance) if ctx.resource.rng().below(chance as usize) == 0 => {
+                Some(50 * (1 + ctx.resource.rng().below(4) as i32))
+            }
+            _ => None,
+        };
+
+        let save_data = ctx.take_save_data_mut();
+        // 疲れていると、失敗したときの印象が悪くなる
+        let reputation_diff = if tone.reputation_diff() < 0.0 {
+            tone.reputation_diff() * save_data.suzunaan_status.fatigue_penalty_rate()
+        } else {
+            tone.reputation_diff()
+        };
+        save_data.suzunaan_status.add_reputation(reputation_diff);
+
+        if let Some(tip) = tip {
+            // 心付け
+            save_data.task_result.total_money += tip;
+            save_data.suzunaan_status.add_reputation(0.5);
+        }
+
+        tone
+    }
+
     fn after_task_done_process<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        // 会員証を確かめないまま受付を終えたら、そのまま貸したことにする
+        self.membership_form = None;
+        if self.membership_request.is_some() {
+            self.lend_on_membership(ctx, t);
+        }
+
+        if self.gift_wrapping_requested {
+            self.gift_wrapping_requested = false;
+
+            if ctx.take_save_data().wrapping_supplies.can_wrap() {
+                self.status = TaskSceneStatus::CustomerWait;
+                self.open_gift_wrapping_board(ctx, t);
+                return;
+            }
+
+            self.task_table
+                .insert_kosuzu_phrase(ctx, "いけない、包み紙を切らしてた", t);
+            ctx.take_save_data_mut()
+                .suzunaan_status
+                .add_reputation(-0.5);
+        }
+
         self.event_list.add_event(
-            Box::new(|scene: &mut TaskScene, _, _| {
+            Box::new(|scene: &mut TaskScene, ctx, _| {
+                let tone = scene.customer_farewell(ctx);
+                match tone {
+                    CustomerFarewellTone::Satisfied => scene.task_table.react_customer(true),
+                    CustomerFarewellTone::Displeased => scene.task_table.react_customer(false),
+                    CustomerFarewellTone::Neutral => (),
+                }
                 scene
                     .task_table
-                    .finish_customer_event(scene.get_current_clock());
+                    .finish_customer_event(ctx, tone, scene.get_current_clock());
+                scene.record_session_stats(ctx, scene.get_current_clock());
                 scene.status = TaskSceneStatus::CustomerFree;
             }),
             self.get_current_clock() + 30,
@@ -366,7 +1208,7 @@ impl TaskScene {
         ctx.process_utility.redraw();
     }
 
-    fn scenario_event_handler<'a>(&mut self, _ctx: &mut SuzuContext<'a>, t: Clock) {
+    fn scenario_event_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
         if let Some(scenario_event) = self.scenario_event.as_ref() {
             if let Some(opecode) = scenario_event.get_scenario_waiting_opecode() {
                 match opecode {
@@ -374,11 +1216,54 @@ impl TaskScene {
                         self.scenario_event = None;
                         self.tutorial_context.borrowing_request = true;
                         self.dark_effect_panel.new_effect(8, t, 200, 0);
+                        self.task_table.highlight_desk_objects(
+                            ctx,
+                            DeskObjectType::CustomerObject,
+                            None,
+                        );
                     }
                     "TutorialFinishReturning" => {
                         self.scenario_event = None;
                         self.tutorial_context.returning_request = true;
                         self.dark_effect_panel.new_effect(8, t, 200, 0);
+                        self.task_table.highlight_desk_objects(
+                            ctx,
+                            DeskObjectType::CustomerObject,
+                            None,
+                        );
+                    }
+                    "AssistAccept" => {
+                        self.scenario_event = None;
+                        self.dark_effect_panel.new_effect(8, t, 200, 0);
+                        ctx.take_save_data_mut().difficulty_assist.accept();
+                        self.task_table.update_assist_highlight(ctx);
+                    }
+                    "AssistDecline" => {
+                        self.scenario_event = None;
+                        self.dark_effect_panel.new_effect(8, t, 200, 0);
+                        ctx.take_save_data_mut().difficulty_assist.decline();
+                    }
+                    "DeskRestoreAccept" => {
+                        self.scenario_event = None;
+                        self.dark_effect_panel.new_effect(8, t, 200, 0);
+
+                        // 客が来て頁が開かれてから、書きかけの内容を戻す
+                        if let Some(autosave) = self.restore_offer.take() {
+                            self.insert_customer_event(autosave.request.clone(), 30);
+                            self.event_list.add_event(
+                                Box::new(move |slf: &mut TaskScene, ctx, t| {
+                                    slf.task_table.restore_autosave(ctx, autosave, t);
+                                }),
+                                t + 31,
+                            );
+                        }
+                    }
+                    "DeskRestoreDecline" => {
+                        self.scenario_event = None;
+                        self.restore_offer = None;
+                        self.dark_effect_panel.new_effect(8, t, 200, 0);
+                        DeskAutosave::delete();
+                        self.ready_to_finish_scene(ctx, t);
                     }
                     _ => (),
                 }
@@ -416,6 +1301,13 @@ impl SceneManager for TaskScene {
             }
         } else {
             match vkey {
+                tdev::VirtualKey::Action1 => {
+                    // 会話はクリックと同じく決定のキーでも進める
+                    let t = self.get_current_clock();
+                    if let Some(scenario_event) = self.scenario_event.as_mut() {
+                        scenario_event.key_down_action1(ctx, None, t);
+                    }
+                }
                 tdev::VirtualKey::Action4 => {
                     let t = self.get_current_clock();
                     self.enter_pause_screen(t);
@@ -445,8 +1337,18 @@ impl SceneManager for TaskScene {
                 }
             }
 	    ctx.process_utility.redraw();
+        } else if let Some(board) = self.gift_wrapping_board.as_mut() {
+            board.mouse_motion(ctx.context, point);
+            ctx.process_utility.redraw();
         } else {
-            if self.mouse_info.is_dragging(MouseButton::Left) {
+            // 押した直後の小さな手ぶれはクリックとして扱う
+            if self.mouse_info.is_dragging(MouseButton::Left)
+                && ctx.input_history.exceeds_drag_threshold(
+                    MouseButton::Left,
+                    point,
+                    DESK_DRAG_THRESHOLD,
+                )
+            {
                 let d = numeric::Vector2f::new(offset.x / 2.0, offset.y / 2.0);
                 self.dragging_handler(ctx, point, d);
                 self.mouse_info.set_last_dragged(
@@ -456,6 +1358,10 @@ impl SceneManager for TaskScene {
                 );
             }
 
+            if self.mouse_info.is_dragging(MouseButton::Middle) {
+                self.task_table.pan_desk(ctx, point);
+            }
+
             self.task_table.mouse_motion_handler(ctx, point, offset);
 
             let mouse_cursor_status = self.task_table.clickable_status(ctx.context, point);
@@ -524,6 +1430,12 @@ impl SceneManager for TaskScene {
             self.scenario_event_handler(ctx, t);
 
             self.task_table.update(ctx, self.get_current_clock());
+            self.stats_bar.update(ctx);
+
+            if self.status == TaskSceneStatus::CustomerEvent {
+                self.task_table
+                    .update_customer_waiting(t - self.customer_event_start);
+            }
 
             if self.status == TaskSceneStatus::CustomerEvent && self.task_table.task_is_done() {
                 self.after_task_done_process(ctx, t);
@@ -563,6 +1475,21 @@ impl SceneManager for TaskScene {
         //println!("{}", perf_measure!(
         {
             self.task_table.draw(ctx).unwrap();
+            self.stats_bar.draw(ctx).unwrap();
+
+            if let Some(reservation_form) = self.reservation_form.as_mut() {
+                reservation_form.draw(ctx).unwrap();
+            }
+
+            if let Some(membership_form) = self.membership_form.as_mut() {
+                membership_form.draw(ctx).unwrap();
+            }
+
+            if let Some(gift_wrapping_board) = self.gift_wrapping_board.as_mut() {
+                gift_wrapping_board.draw(ctx).unwrap();
+            }
+
+            self.shortcut_palette_window.draw(ctx).unwrap();
             self.pause_screen_set.draw(ctx).unwrap();
 
             self.dark_effect_panel.draw(ctx).unwrap();
@@ -582,6 +1509,100 @@ impl SceneManager for TaskScene {
         self.transition_status
     }
 
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        if !self.now_paused() {
+            self.pause_screen_set
+                .enter_idle_pause(self.get_current_clock());
+            ctx.process_utility.redraw();
+        }
+    }
+
+    fn shortcut_palette_is_open(&self) -> bool {
+        // 覚書や帳簿の欄に書き込んでいる間も、文字入力を優先する
+        self.shortcut_palette.is_some()
+            || self.task_table.memo_is_editing()
+            || self.task_table.manual_entry_is_editing()
+            || self.task_table.record_book_search_is_editing()
+    }
+
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        if self.shortcut_palette.is_none() {
+            let t = self.get_current_clock();
+            if self.task_table.manual_entry_key_event(ctx, key, t) {
+                return true;
+            }
+
+            if self.task_table.record_book_search_key_event(ctx, key, t) {
+                return true;
+            }
+
+            if self.task_table.memo_key_event(ctx, key) {
+                return true;
+            }
+
+            // 開いているメニューがあれば、Escapeはまずそれを閉じる
+            if key == PaletteKey::Escape
+                && !self.now_paused()
+                && self.task_table.close_menus_by_escape(t)
+            {
+                return true;
+            }
+
+            if !self.now_paused() {
+                if let Some(scenario_event) = self.scenario_event.as_mut() {
+                    if scenario_event.choice_key_event(ctx, key, t) {
+                        return true;
+                    }
+                }
+            }
+
+            // 数字、Tab、空白、Escapeで机の上を操作する
+            if !self.now_paused() && self.gift_wrapping_board.is_none() {
+                if let Some(shortcut) = DeskShortcut::from_palette_key(key) {
+                    if self.task_table.desk_shortcut_event(ctx, shortcut, t) {
+                        return true;
+                    }
+                }
+            }
+
+            if key == PaletteKey::Toggle
+                && !self.now_paused()
+                && self.scenario_event.is_none()
+                && self.scene_transition_effect.is_none()
+            {
+                self.shortcut_palette = Some(ShortcutPalette::new(self.shortcut_entries()));
+                self.shortcut_palette_window.appear();
+                self.update_shortcut_palette_window(ctx);
+                return true;
+            }
+            return false;
+        }
+
+        let palette = self.shortcut_palette.as_mut().unwrap();
+
+        match key {
+            PaletteKey::Toggle | PaletteKey::Escape => {
+                self.close_shortcut_palette(ctx);
+                return true;
+            }
+            PaletteKey::Enter => {
+                let command = palette.selected_command();
+                self.close_shortcut_palette(ctx);
+                if let Some(command) = command {
+                    self.run_task_shortcut(ctx, command);
+                }
+                return true;
+            }
+            PaletteKey::Up => palette.select_prev(),
+            PaletteKey::Down | PaletteKey::Tab => palette.select_next(),
+            PaletteKey::Backspace => palette.pop_char(),
+            PaletteKey::Char(c) => palette.push_char(c),
+        }
+
+        self.update_shortcut_palette_window(ctx);
+        true
+    }
+
     fn transition(&self) -> SceneID {
         self.transition_scene
     }
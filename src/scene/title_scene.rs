@@ -6,14 +6,16 @@ use torifune::graphics::drawable::*;
 use torifune::graphics::object::*;
 use torifune::sound::*;
 
-use crate::core::{
-    GameMode, MouseInformation, SoundID, SuzuContext, TextureID, TileBatchTextureID,
-};
+use crate::core::quicksave;
+use crate::core::shortcut_palette::PaletteKey;
+use crate::core::{GameMode, MouseInformation, SuzuContext, TextureID, TileBatchTextureID};
 use crate::object::effect_object;
+use crate::object::scenario::ScenarioEvent;
 use crate::object::title_object::*;
 use crate::scene::*;
 
 use crate::flush_delay_event;
+use crate::{log_debug, log_info};
 
 pub struct TitleScene {
     mouse_info: MouseInformation,
@@ -26,6 +28,7 @@ pub struct TitleScene {
     current_title_contents: Option<TitleContents>,
     title_contents_set: TitleContentsSet,
     scene_transition_lock: bool,
+    attract_event: Option<ScenarioEvent>,
     clock: Clock,
 }
 
@@ -78,10 +81,14 @@ impl TitleScene {
             0,
         );
 
-        ctx.play_sound_as_bgm(
-            SoundID::Title,
-            Some(SoundPlayFlags::new(10000, 1.0, true, ctx.config.get_bgm_volume())),
-        );
+        // 設定ファイルが無ければ、開始画面の前に初めての設定を済ませてもらう
+        let first_contents = if ctx.preferences.is_first_run() {
+            "first-run"
+        } else {
+            "init-menu"
+        };
+
+        ctx.play_scene_bgm(SceneID::Title, 10000);
 
         TitleScene {
             mouse_info: MouseInformation::new(),
@@ -90,14 +97,64 @@ impl TitleScene {
             scene_transition_effect: scene_transition_effect,
             scene_transition: SceneID::Title,
             scene_transition_type: SceneTransition::Keep,
-            current_title_contents: title_contents_set.remove_pickup("init-menu"),
+            current_title_contents: title_contents_set.remove_pickup(first_contents),
             title_contents_set: title_contents_set,
             logo: logo,
             scene_transition_lock: false,
+            attract_event: None,
             clock: 0,
         }
     }
 
+    ///
+    /// 放置されていた場合に流す、デモ用の短い寸劇を開始する
+    ///
+    fn start_attract_mode<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.attract_event.is_some() || self.is_scene_transition_locked() {
+            return;
+        }
+
+        self.attract_event = Some(ScenarioEvent::new(
+            ctx,
+            numeric::Rect::new(0.0, 0.0, 1366.0, 768.0),
+            "/scenario/attract.toml",
+            None,
+            false,
+            t,
+        ));
+        ctx.process_utility.redraw();
+    }
+
+    ///
+    /// デモを終了し、開始画面の最初のメニューに戻す
+    ///
+    fn finish_attract_mode<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        self.attract_event = None;
+
+        let on_init_menu = match self.current_title_contents.as_ref() {
+            Some(content) => content.get_content_name() == "init-menu",
+            None => true,
+        };
+        if !on_init_menu {
+            self.switch_current_content(ctx, "init-menu".to_string(), t);
+        }
+
+        ctx.process_utility.redraw();
+    }
+
+    fn attract_mode_handler<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        let finished = if let Some(attract_event) = self.attract_event.as_mut() {
+            attract_event.update_text(ctx, None);
+            attract_event.get_scenario_waiting_opecode() == Some("FinishAttract")
+        } else {
+            false
+        };
+
+        if finished {
+            self.finish_attract_mode(ctx, t);
+        }
+    }
+
     fn lock_scene_transition(&mut self) {
         self.scene_transition_lock = true;
     }
@@ -122,6 +179,49 @@ impl TitleScene {
             return;
         }
 
+        // 新規開始ならセーブデータを初期化
+        match scene_id {
+            SceneID::Scenario => {
+		ctx.reset_save_data(if let Some(game_mode) = game_mode {
+                    game_mode
+		} else {
+                    GameMode::story()
+		});
+	    },
+	    _ => (),
+        }
+
+        self.close_and_transition(ctx, scene_id, trans, t);
+    }
+
+    ///
+    /// 一番新しいセーブを読み、その日の朝から続ける。セーブが一つも無ければ何もしない
+    ///
+    fn continue_from_newest_save<'a>(&mut self, ctx: &mut SuzuContext<'a>, t: Clock) {
+        if self.is_scene_transition_locked() {
+            return;
+        }
+
+        match quicksave::load_newest_save() {
+            Some(data) => {
+                ctx.savable_data.replace(data);
+            }
+            None => {
+                log_info!("no save data to continue from");
+                return;
+            }
+        }
+
+        self.close_and_transition(ctx, SceneID::Scenario, SceneTransition::SwapTransition, t);
+    }
+
+    fn close_and_transition<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        scene_id: SceneID,
+        trans: SceneTransition,
+        t: Clock,
+    ) {
         self.lock_scene_transition();
 
         self.scene_transition_effect = Some(effect_object::ScreenTileEffect::new(
@@ -140,26 +240,17 @@ impl TitleScene {
             t,
         ));
 
-        // 新規開始ならセーブデータを初期化
-        match scene_id {
-            SceneID::Scenario => {
-		ctx.reset_save_data(if let Some(game_mode) = game_mode {
-                    game_mode
-		} else {
-                    GameMode::story()
-		});
-	    },
-	    _ => (),
-        }
-
         self.event_list.add_event(
             Box::new(move |slf: &mut Self, ctx, _| {
                 slf.scene_transition = scene_id;
                 slf.scene_transition_type = trans;
                 slf.unlock_scene_transition();
-		if slf.scene_transition != SceneID::Save {
-                    ctx.resource.stop_bgm(ctx.context, SoundID::Title);
-		}
+                if slf.scene_transition != SceneID::Save
+                    && slf.scene_transition != SceneID::Album
+                    && slf.scene_transition != SceneID::Credits
+                {
+                    ctx.resource.stop_scene_bgm(ctx.context, SceneID::Title);
+                }
             }),
             t + 31,
         );
@@ -180,13 +271,14 @@ impl TitleScene {
                 contents.update_highlight(ctx, point);
             }
             TitleContents::TitleSoundPlayer(contents) => {
-                println!("sound-player");
+                log_debug!("sound-player");
                 contents.dragging_handler(ctx, point, offset);
             }
             TitleContents::ConfigPanel(_) => (),
             TitleContents::UpdatePanel(_) => (),
             TitleContents::Gallery(_) => (),
             TitleContents::RecordRoom(_) => (),
+            TitleContents::FirstRun(_) => (),
         }
     }
 
@@ -245,9 +337,18 @@ impl TitleScene {
             .add(old.as_ref().unwrap().get_content_name(), old.unwrap());
     }
 
-    fn run_builtin_command(&mut self, command: TitleBuiltinCommand) {
+    fn run_builtin_command<'a>(
+        &mut self,
+        ctx: &mut SuzuContext<'a>,
+        command: TitleBuiltinCommand,
+        t: Clock,
+    ) {
         match command {
-            TitleBuiltinCommand::Exit => std::process::exit(0),
+            TitleBuiltinCommand::Continue => self.continue_from_newest_save(ctx, t),
+            TitleBuiltinCommand::Exit => {
+                ctx.save_window_placement();
+                std::process::exit(0)
+            }
         }
     }
 
@@ -275,7 +376,7 @@ impl TitleScene {
                             self.transition_selected_scene(ctx, scene_id, trans, game_mode, t);
                         }
                         TitleContentsEvent::BuiltinEvent(command) => {
-                            self.run_builtin_command(command);
+                            self.run_builtin_command(ctx, command, t);
                         }
                     }
                 }
@@ -327,11 +428,37 @@ impl TitleScene {
                     }
                 }
             }
+            TitleContents::FirstRun(panel) => {
+                let maybe_event = panel.mouse_button_up(ctx, point, t);
+                if let Some(event) = maybe_event {
+                    match event {
+                        TitleContentsEvent::NextContents(content_name) => {
+                            self.switch_current_content(ctx, content_name, t);
+                        }
+                        _ => (),
+                    }
+                }
+            }
         }
     }
 }
 
 impl SceneManager for TitleScene {
+    fn key_down_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, vkey: tdev::VirtualKey) {
+        if self.attract_event.is_some() {
+            let t = self.get_current_clock();
+            match vkey {
+                VirtualKey::Action1 => {
+                    self.attract_event
+                        .as_mut()
+                        .unwrap()
+                        .key_down_action1(ctx, None, t);
+                }
+                _ => self.finish_attract_mode(ctx, t),
+            }
+        }
+    }
+
     fn key_up_event<'a>(&mut self, _ctx: &mut SuzuContext<'a>, vkey: tdev::VirtualKey) {
         match vkey {
             VirtualKey::Action2 => {
@@ -371,6 +498,8 @@ impl SceneManager for TitleScene {
         if let Some(contents) = self.current_title_contents.as_mut() {
             contents.update(ctx, t);
         }
+
+        self.attract_mode_handler(ctx, t);
     }
 
     fn drawing_process(&mut self, ctx: &mut ggez::Context) {
@@ -381,6 +510,10 @@ impl SceneManager for TitleScene {
             contetns.draw(ctx).unwrap();
         }
 
+        if let Some(attract_event) = self.attract_event.as_mut() {
+            attract_event.draw(ctx).unwrap();
+        }
+
         if let Some(transition_effect) = self.scene_transition_effect.as_mut() {
             transition_effect.draw(ctx).unwrap();
         }
@@ -400,6 +533,10 @@ impl SceneManager for TitleScene {
     ) {
         let t = self.get_current_clock();
 
+        if self.attract_event.is_some() {
+            return;
+        }
+
         self.mouse_info.set_last_clicked(button, point, t);
         self.mouse_info.set_last_down(button, point, t);
         self.mouse_info.set_last_dragged(button, point, t);
@@ -422,6 +559,12 @@ impl SceneManager for TitleScene {
     ) {
         let t = self.get_current_clock();
 
+        if self.attract_event.is_some() {
+            // デモ中のクリックはデモのスキップとして扱う
+            self.finish_attract_mode(ctx, t);
+            return;
+        }
+
         self.mouse_info.update_dragging(button, false);
 
         self.contents_mouse_click_handler(ctx, point, t);
@@ -461,6 +604,39 @@ impl SceneManager for TitleScene {
         ));
     }
 
+    fn idle_timeout_event<'a>(&mut self, ctx: &mut SuzuContext<'a>) {
+        // 初めての設定の途中では、デモを流さない
+        if let Some(TitleContents::FirstRun(_)) = self.current_title_contents.as_ref() {
+            return;
+        }
+
+        let t = self.get_current_clock();
+        self.start_attract_mode(ctx, t);
+    }
+
+    ///
+    /// 呼び名を打っている間は、キー入力を文字として受け取る
+    ///
+    fn shortcut_palette_is_open(&self) -> bool {
+        match self.current_title_contents.as_ref() {
+            Some(TitleContents::FirstRun(panel)) => panel.is_editing_nickname(),
+            _ => false,
+        }
+    }
+
+    fn shortcut_palette_event<'a>(&mut self, ctx: &mut SuzuContext<'a>, key: PaletteKey) -> bool {
+        let consumed = match self.current_title_contents.as_mut() {
+            Some(TitleContents::FirstRun(panel)) => panel.key_event(key),
+            _ => false,
+        };
+
+        if consumed {
+            ctx.process_utility.redraw();
+        }
+
+        consumed
+    }
+
     fn transition(&self) -> SceneID {
         self.scene_transition
     }
@@ -0,0 +1,55 @@
+extern crate suzu;
+
+use suzu::core::album::*;
+use suzu::core::GensoDate;
+
+#[test]
+fn weekly_chart_follows_seventh_receipt() {
+    let mut album = PhotoAlbum::new();
+    let mut date = GensoDate::new(112, 4, 1);
+
+    for day in 0..ALBUM_DAYS_PER_WEEK {
+        album.record_day(date, 3, 100 * day as i32, 3.0);
+        date.add_days(1);
+    }
+
+    assert_eq!(album.len(), ALBUM_DAYS_PER_WEEK + 1);
+    match album.get_photos().last().unwrap() {
+        AlbumPhoto::WeeklyChart { daily_money, .. } => {
+            assert_eq!(daily_money.len(), ALBUM_DAYS_PER_WEEK);
+            assert_eq!(daily_money[6], 600);
+        }
+        photo => panic!("unexpected photo: {:?}", photo),
+    }
+
+    album.record_day(date, 1, 50, 2.0);
+    assert_eq!(album.len(), ALBUM_DAYS_PER_WEEK + 2);
+}
+
+#[test]
+fn same_still_is_pasted_once() {
+    let mut album = PhotoAlbum::new();
+    let date = GensoDate::new(112, 4, 1);
+
+    assert!(album.record_still(date, 5));
+    assert!(!album.record_still(date.add_days_chain(3), 5));
+    assert!(album.record_still(date, 6));
+    assert_eq!(album.len(), 2);
+}
+
+#[test]
+fn pages_hold_fixed_number_of_photos() {
+    let mut album = PhotoAlbum::new();
+    assert_eq!(album.page_count(), 1);
+    assert!(album.page(0).is_empty());
+
+    let date = GensoDate::new(112, 4, 1);
+    for texture in 0..(ALBUM_PHOTOS_PER_PAGE as u32 + 1) {
+        album.record_still(date, texture);
+    }
+
+    assert_eq!(album.page_count(), 2);
+    assert_eq!(album.page(0).len(), ALBUM_PHOTOS_PER_PAGE);
+    assert_eq!(album.page(1).len(), 1);
+    assert!(album.page(2).is_empty());
+}
@@ -0,0 +1,84 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::core::ambient_zone::*;
+
+fn level_of(levels: &[(AmbientKind, f32)], kind: AmbientKind) -> f32 {
+    levels
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, level)| *level)
+        .unwrap()
+}
+
+#[test]
+fn zone_weight_fades_out_from_the_edge() {
+    let zone = AmbientZone::new(
+        AmbientKind::Street,
+        numeric::Rect::new(10.0, 10.0, 4.0, 4.0),
+        1.0,
+        4.0,
+    );
+
+    assert_eq!(zone.weight_at(numeric::Point2f::new(12.0, 12.0)), 1.0);
+    assert_eq!(zone.weight_at(numeric::Point2f::new(16.0, 12.0)), 0.5);
+    assert_eq!(zone.weight_at(numeric::Point2f::new(20.0, 12.0)), 0.0);
+
+    let sharp = AmbientZone::new(
+        AmbientKind::Birds,
+        numeric::Rect::new(0.0, 0.0, 2.0, 2.0),
+        1.0,
+        0.0,
+    );
+    assert_eq!(sharp.weight_at(numeric::Point2f::new(2.0, 2.0)), 1.0);
+    assert_eq!(sharp.weight_at(numeric::Point2f::new(2.5, 2.0)), 0.0);
+}
+
+#[test]
+fn quiet_zone_pushes_other_sounds_away() {
+    let zones = vec![
+        AmbientZone::new(
+            AmbientKind::Street,
+            numeric::Rect::new(0.0, 0.0, 20.0, 20.0),
+            0.8,
+            0.0,
+        ),
+        AmbientZone::new(
+            AmbientKind::Quiet,
+            numeric::Rect::new(0.0, 10.0, 20.0, 10.0),
+            1.0,
+            0.0,
+        ),
+    ];
+
+    let outside = target_levels(&zones, numeric::Point2f::new(5.0, 5.0));
+    assert_eq!(level_of(&outside, AmbientKind::Street), 0.8);
+    assert_eq!(level_of(&outside, AmbientKind::Birds), 0.0);
+
+    let storeroom = target_levels(&zones, numeric::Point2f::new(5.0, 15.0));
+    assert_eq!(level_of(&storeroom, AmbientKind::Street), 0.0);
+}
+
+#[test]
+fn mixer_crossfades_towards_targets() {
+    let mut mixer = AmbientMixer::new(4);
+    let targets = vec![(AmbientKind::Street, 1.0), (AmbientKind::Birds, 0.0)];
+
+    mixer.update(&targets);
+    assert_eq!(mixer.get_level(AmbientKind::Street), 0.25);
+
+    for _ in 0..10 {
+        mixer.update(&targets);
+    }
+    assert_eq!(mixer.get_level(AmbientKind::Street), 1.0);
+
+    // 鳥の区域へ移ると、通りの音が下がりながら鳥の声が上がる
+    let targets = vec![(AmbientKind::Street, 0.0), (AmbientKind::Birds, 0.5)];
+    mixer.update(&targets);
+    mixer.update(&targets);
+    assert_eq!(mixer.get_level(AmbientKind::Street), 0.5);
+    assert_eq!(mixer.get_level(AmbientKind::Birds), 0.5);
+    mixer.update(&targets);
+    assert_eq!(mixer.get_level(AmbientKind::Birds), 0.5);
+}
@@ -0,0 +1,78 @@
+extern crate suzu;
+
+use suzu::core::assistant::*;
+use suzu::core::shop_expansion::{ShopExpansionState, ShopExpansionType};
+
+fn unlocked_assistant() -> AssistantState {
+    let mut expansion = ShopExpansionState::new();
+    expansion.purchase(ShopExpansionType::ReadingCorner);
+    expansion.purchase(ShopExpansionType::SecondRoom);
+
+    let mut assistant = AssistantState::new();
+    assert!(assistant.try_unlock(&expansion, ASSISTANT_UNLOCK_REPUTATION));
+    assistant
+}
+
+#[test]
+fn assistant_needs_second_room_and_reputation() {
+    let mut expansion = ShopExpansionState::new();
+    let mut assistant = AssistantState::new();
+
+    assert!(!assistant.try_unlock(&expansion, 100.0));
+
+    expansion.purchase(ShopExpansionType::ReadingCorner);
+    expansion.purchase(ShopExpansionType::SecondRoom);
+    assert!(!assistant.try_unlock(&expansion, ASSISTANT_UNLOCK_REPUTATION - 1.0));
+    assert!(assistant.try_unlock(&expansion, ASSISTANT_UNLOCK_REPUTATION));
+    assert!(assistant.is_unlocked());
+    assert!(!assistant.try_unlock(&expansion, 100.0));
+}
+
+#[test]
+fn role_changes_once_per_hour() {
+    let mut assistant = AssistantState::new();
+    assert!(!assistant.assign(Some(AssistantRole::Shelving), 10));
+
+    let mut assistant = unlocked_assistant();
+    assert!(assistant.assign(Some(AssistantRole::Shelving), 10));
+    assert!(!assistant.assign(Some(AssistantRole::Greeting), 10));
+    assert_eq!(assistant.get_role(), Some(AssistantRole::Shelving));
+
+    assert!(assistant.assign(None, 11));
+    assert_eq!(assistant.get_role(), None);
+}
+
+#[test]
+fn training_lowers_error_rate() {
+    let mut assistant = unlocked_assistant();
+    let first_rate = assistant.error_rate();
+
+    assistant.record_action(Some(AssistantMistake::RudeGreeting));
+    assert!(assistant.error_rate() < first_rate);
+
+    let trained_rate = assistant.error_rate();
+    assert_eq!(assistant.correct(0), Some(AssistantMistake::RudeGreeting));
+    assert!(assistant.error_rate() < trained_rate);
+    assert_eq!(assistant.correct(0), None);
+
+    for _ in 0..1000 {
+        assistant.record_action(None);
+    }
+    assert!(assistant.error_rate() > 0.0);
+    assert!(!assistant.is_mistake(assistant.error_rate()));
+}
+
+#[test]
+fn uncorrected_mistakes_cost_reputation() {
+    let mut assistant = unlocked_assistant();
+    assistant.assign(Some(AssistantRole::SimpleReturns), 10);
+    assistant.record_action(Some(AssistantMistake::UnrecordedReturn(
+        "竹取物語".to_string(),
+    )));
+    assistant.record_action(Some(AssistantMistake::RudeGreeting));
+
+    assert!(assistant.close_day() < 0.0);
+    assert!(assistant.get_mistakes().is_empty());
+    assert_eq!(assistant.get_role(), None);
+    assert_eq!(assistant.close_day(), 0.0);
+}
@@ -0,0 +1,60 @@
+extern crate suzu;
+
+use suzu::core::auto_shelving::*;
+use suzu::core::{BookInformation, GameMode, GensoDate};
+
+#[test]
+fn misfile_rate_follows_game_mode_and_assist() {
+    let tuning = AutoShelvingTuning::from_toml_str(
+        "story_misfile_rate = 0.2\ntime_attack_misfile_rate = 0.4\nassist_misfile_rate = 0.05",
+    );
+
+    assert_eq!(tuning.misfile_rate(&GameMode::story(), false), 0.2);
+    assert_eq!(tuning.misfile_rate(&GameMode::time_attack(), false), 0.4);
+    assert_eq!(tuning.misfile_rate(&GameMode::time_attack(), true), 0.05);
+
+    // 補助の方が高く書かれていても、補助で難しくはならない
+    let odd =
+        AutoShelvingTuning::from_toml_str("story_misfile_rate = 0.1\nassist_misfile_rate = 0.3");
+    assert_eq!(odd.misfile_rate(&GameMode::story(), true), 0.1);
+
+    let broken = AutoShelvingTuning::from_toml_str("story_misfile_rate = 3.0");
+    assert_eq!(broken.misfile_rate(&GameMode::story(), false), 1.0);
+}
+
+#[test]
+fn split_misfiled_is_reproducible_for_the_same_day() {
+    let today = GensoDate::new(112, 8, 3);
+    let books: Vec<u32> = (0..40).collect();
+
+    let first = split_misfiled(books.clone(), 0.3, &mut day_rng(&today));
+    let second = split_misfiled(books.clone(), 0.3, &mut day_rng(&today));
+    assert_eq!(first, second);
+    assert_eq!(first.0.len() + first.1.len(), books.len());
+
+    let (shelved, misfiled) = split_misfiled(books.clone(), 0.0, &mut day_rng(&today));
+    assert_eq!(shelved, books);
+    assert!(misfiled.is_empty());
+
+    let (shelved, misfiled) = split_misfiled(books.clone(), 1.0, &mut day_rng(&today));
+    assert!(shelved.is_empty());
+    assert_eq!(misfiled, books);
+}
+
+#[test]
+fn misfiled_books_are_found_the_next_day() {
+    let today = GensoDate::new(112, 8, 3);
+    let book = BookInformation::new("竹取物語".to_string(), 120, "中判".to_string(), 1, 300);
+
+    let mut misfiled = MisfiledBooks::new();
+    misfiled.record(vec![book.clone()], &today);
+    assert_eq!(misfiled.len(), 1);
+    assert!(misfiled.take_corrections(&today).is_empty());
+
+    let corrections = misfiled.take_corrections(&today.add_days_chain(1));
+    assert_eq!(corrections, vec![book]);
+    assert!(misfiled.is_empty());
+    assert!(misfiled
+        .take_corrections(&today.add_days_chain(2))
+        .is_empty());
+}
@@ -0,0 +1,56 @@
+extern crate suzu;
+
+use suzu::core::background_sim::*;
+
+#[test]
+fn arrivals_wait_at_the_door_until_patience_runs_out() {
+    let mut sim = BackgroundShopSim::new(10 * 60, 100);
+
+    assert_eq!(sim.tick(true), vec![BackgroundEvent::CustomerArrived]);
+    for _ in 0..MAX_BACKGROUND_ARRIVALS + 2 {
+        sim.tick(true);
+    }
+    assert_eq!(sim.pending_arrivals(), MAX_BACKGROUND_ARRIVALS);
+
+    // 最初の客は1フレーム目に来た。我慢の100フレームを超えた所で帰る
+    let mut gave_up = 0;
+    for _ in sim.get_elapsed()..102 {
+        gave_up += sim
+            .tick(false)
+            .iter()
+            .filter(|event| **event == BackgroundEvent::ArrivalGaveUp)
+            .count();
+    }
+    assert_eq!(gave_up, 1);
+    assert_eq!(sim.take_arrivals(), MAX_BACKGROUND_ARRIVALS - 1);
+    assert_eq!(sim.pending_arrivals(), 0);
+}
+
+#[test]
+fn hour_chimes_once_while_at_the_desk() {
+    // 10時59分から机に向かう
+    let mut sim = BackgroundShopSim::new(10 * 60 + 59, 1200);
+
+    let mut chimes = Vec::new();
+    for _ in 0..DESK_FRAMES_PER_SHOP_MINUTE * 3 {
+        for event in sim.tick(false) {
+            if let BackgroundEvent::HourChimed(hour) = event {
+                chimes.push(hour);
+            }
+        }
+    }
+
+    assert_eq!(chimes, vec![11]);
+    assert_eq!(sim.current_hour(), 11);
+}
+
+#[test]
+fn restless_customer_is_reported_once() {
+    let mut sim = BackgroundShopSim::new(10 * 60, 1000);
+
+    assert!(!sim.check_restless(100, 700));
+    assert!(sim.check_restless(100, 850));
+    assert!(!sim.check_restless(100, 900));
+    assert!(sim.check_restless(200, 950));
+    assert!(!sim.check_restless(900, 100));
+}
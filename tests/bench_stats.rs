@@ -0,0 +1,39 @@
+extern crate suzu;
+
+use std::time::Duration;
+
+use suzu::core::bench_stats::*;
+
+#[test]
+fn empty_samples_have_no_summary() {
+    let samples = FrameTimeSamples::new();
+    assert!(samples.is_empty());
+    assert_eq!(samples.summary(), None);
+}
+
+#[test]
+fn summary_picks_mean_median_p95_and_max() {
+    let mut samples = FrameTimeSamples::new();
+    for micros in (1..=100).rev() {
+        samples.push_micros(micros * 10);
+    }
+
+    let summary = samples.summary().unwrap();
+    assert_eq!(summary.frames, 100);
+    assert_eq!(summary.mean, 505);
+    assert_eq!(summary.median, 510);
+    assert_eq!(summary.p95, 960);
+    assert_eq!(summary.max, 1000);
+}
+
+#[test]
+fn durations_are_recorded_in_microseconds() {
+    let mut samples = FrameTimeSamples::new();
+    samples.push(Duration::from_millis(2));
+    samples.push(Duration::from_micros(1500));
+
+    let summary = samples.summary().unwrap();
+    assert_eq!(summary.max, 2000);
+    assert_eq!(summary.mean, 1750);
+    assert!(summary.to_report("draw").starts_with("draw"));
+}
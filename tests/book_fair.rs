@@ -0,0 +1,85 @@
+extern crate suzu;
+
+use suzu::core::book_fair::*;
+use suzu::core::{BookInformation, GensoDate};
+
+fn book(name: &str) -> BookInformation {
+    BookInformation::new(name.to_string(), 128, "中判本".to_string(), 1, 100)
+}
+
+fn sample_session() -> BookFairSession {
+    let requests = ["竹取物語", "百鬼夜行絵巻", "伊勢物語", "今昔物語", "方丈記"]
+        .iter()
+        .enumerate()
+        .map(|(index, name)| FairRequest::new(format!("客{}", index), book(name)))
+        .collect();
+
+    BookFairSession::new(requests)
+}
+
+fn shelf_index_of(session: &BookFairSession, name: &str) -> usize {
+    session
+        .get_shelf()
+        .iter()
+        .position(|title| title.as_ref().map_or(false, |book| book.name == name))
+        .unwrap()
+}
+
+#[test]
+fn titles_are_not_laid_under_their_customers() {
+    let session = sample_session();
+
+    for (slot, title) in session.get_counter().iter().zip(session.get_shelf().iter()) {
+        assert_ne!(slot.as_ref().map(|request| &request.book), title.as_ref());
+    }
+    assert_eq!(session.remaining(), 5);
+}
+
+#[test]
+fn serving_refills_counter_and_counts_combo() {
+    let mut session = sample_session();
+
+    assert_eq!(session.serve(0), None);
+
+    session.select_title(shelf_index_of(&session, "竹取物語"));
+    assert_eq!(session.serve(0), Some(true));
+    assert_eq!(session.get_counter()[0].as_ref().unwrap().book.name, "方丈記");
+
+    session.select_title(shelf_index_of(&session, "方丈記"));
+    assert_eq!(session.serve(0), Some(true));
+    assert_eq!(session.get_combo(), 2);
+    assert_eq!(session.get_score(), 100 + 120);
+
+    session.select_title(shelf_index_of(&session, "伊勢物語"));
+    assert_eq!(session.serve(1), Some(false));
+    assert_eq!(session.get_combo(), 0);
+    assert_eq!(session.remaining(), 3);
+
+    let result = session.finish(GensoDate::new(112, 9, 1), 10 * 60);
+    assert_eq!(result.served, 2);
+    assert_eq!(result.missed, 1);
+    assert_eq!(result.best_combo, 2);
+    assert_eq!(result.score, 220 - 50 + 50);
+}
+
+#[test]
+fn fair_is_held_once_per_season() {
+    let mut record = BookFairRecord::new(&GensoDate::new(112, 7, 23));
+    assert!(!record.is_due(&GensoDate::new(112, 8, 31)));
+    assert!(record.is_due(&GensoDate::new(112, 9, 1)));
+
+    let mut session = sample_session();
+    session.select_title(shelf_index_of(&session, "竹取物語"));
+    session.serve(0);
+    record.record(session.finish(GensoDate::new(112, 9, 1), 0));
+    assert!(!record.is_due(&GensoDate::new(112, 11, 30)));
+
+    // 一月と二月は前の年の冬に数える
+    record.record(session.finish(GensoDate::new(112, 12, 1), 60));
+    assert!(!record.is_due(&GensoDate::new(113, 2, 28)));
+    assert!(record.is_due(&GensoDate::new(113, 3, 1)));
+
+    let leaderboard = record.leaderboard();
+    assert_eq!(leaderboard.len(), 2);
+    assert!(leaderboard[0].score >= leaderboard[1].score);
+}
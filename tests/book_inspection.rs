@@ -0,0 +1,111 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::book_inspection::*;
+use suzu::core::request_generator::*;
+use suzu::core::BookInformation;
+
+fn sample_books() -> Vec<BookInformation> {
+    vec!["雨月物語", "奥の細道", "遠野物語", "養生訓", "改暦弁"]
+        .iter()
+        .map(|name| BookInformation::new(name.to_string(), 128, "中判本".to_string(), 1, 100))
+        .collect()
+}
+
+#[test]
+fn anomaly_rate_zero_keeps_books_clean() {
+    let books = sample_books();
+    let generator = RequestGenerator::new(REQUEST_MAX_BOOKS);
+    assert_eq!(generator.get_anomaly_rate(), 0.0);
+
+    for seed in 0..100 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for book_info in generator.select_books(&mut rng, &books) {
+            assert_eq!(book_info.get_anomaly(), None);
+        }
+    }
+}
+
+#[test]
+fn anomaly_rate_one_taints_every_book() {
+    let books = sample_books();
+    let generator = RequestGenerator::new(REQUEST_MAX_BOOKS).with_anomaly_rate(1.0);
+
+    for seed in 0..100 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for book_info in generator.select_books(&mut rng, &books) {
+            assert!(book_info.get_anomaly().is_some());
+        }
+    }
+}
+
+#[test]
+fn anomaly_does_not_survive_clone_with_new_id() {
+    let mut book_info = sample_books().remove(0);
+    book_info.set_anomaly(Some(BookAnomaly::Forgery));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(
+        book_info
+            .clone_with_new_id_condition(&mut rng)
+            .get_anomaly(),
+        None
+    );
+}
+
+#[test]
+fn each_anomaly_shows_two_tells() {
+    for anomaly in [BookAnomaly::Forgery, BookAnomaly::ForbiddenTome].iter() {
+        let tells = InspectionPoint::ALL
+            .iter()
+            .filter(|point| anomaly.has_tell(**point))
+            .count();
+        assert_eq!(tells, 2);
+
+        for point in InspectionPoint::ALL.iter() {
+            assert_eq!(
+                point.finding(Some(*anomaly)) != point.finding(None),
+                anomaly.has_tell(*point)
+            );
+        }
+    }
+}
+
+#[test]
+fn only_expected_decision_is_correct() {
+    assert!(judge(None, InspectionDecision::Accept).correct);
+
+    for anomaly in [BookAnomaly::Forgery, BookAnomaly::ForbiddenTome].iter() {
+        for decision in InspectionDecision::ALL.iter() {
+            let outcome = judge(Some(*anomaly), *decision);
+            assert_eq!(outcome.correct, *decision == anomaly.expected_decision());
+        }
+    }
+
+    let missed = judge(Some(BookAnomaly::ForbiddenTome), InspectionDecision::Accept);
+    assert!(missed.money < 0);
+    assert!(missed.reputation < judge(None, InspectionDecision::Report).reputation);
+}
+
+#[test]
+fn story_hooks_fire_on_milestones() {
+    let mut record = InspectionRecord::new();
+
+    assert!(record
+        .record(Some(BookAnomaly::ForbiddenTome), InspectionDecision::Report)
+        .is_some());
+    assert!(record
+        .record(Some(BookAnomaly::ForbiddenTome), InspectionDecision::Report)
+        .is_none());
+    assert!(record
+        .record(Some(BookAnomaly::ForbiddenTome), InspectionDecision::Report)
+        .is_some());
+
+    assert!(record
+        .record(None, InspectionDecision::Confiscate)
+        .is_none());
+    assert_eq!(record.reported_tomes, 3);
+    assert_eq!(record.mistakes, 1);
+}
@@ -0,0 +1,56 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::BookInformation;
+
+#[test]
+fn book_is_found_by_title_or_reading() {
+    let mut book = BookInformation::new("養生訓".to_string(), 120, "中判本".to_string(), 4, 300);
+    assert!(book.is_called("養生訓"));
+    assert!(!book.is_called("ようじょうくん"));
+
+    book.set_reading(Some("ようじょうくん".to_string()));
+    assert_eq!(book.get_reading(), Some("ようじょうくん"));
+    assert!(book.is_called("養生訓"));
+    assert!(book.is_called("ようじょうくん"));
+    assert!(!book.is_called("ようじょう"));
+}
+
+#[test]
+fn reading_is_optional_in_book_data() {
+    let without: BookInformation = toml::from_str(
+        r#"
+name = "女大学"
+pages = 128
+size = "大判本"
+billing_number = 3
+base_price = 100
+condition = "Bad"
+unique_id = 0
+"#,
+    )
+    .unwrap();
+    assert_eq!(without.get_reading(), None);
+
+    let with: BookInformation = toml::from_str(
+        r#"
+name = "改暦弁"
+reading = "かいれきべん"
+pages = 128
+size = "中判本"
+billing_number = 12
+base_price = 100
+condition = "Good"
+unique_id = 0
+"#,
+    )
+    .unwrap();
+    assert_eq!(with.get_reading(), Some("かいれきべん"));
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(
+        with.clone_with_new_id_condition(&mut rng).get_reading(),
+        Some("かいれきべん")
+    );
+}
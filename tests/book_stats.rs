@@ -0,0 +1,48 @@
+extern crate suzu;
+
+use suzu::core::book_management::*;
+use suzu::core::GensoDate;
+
+#[test]
+fn ranking_counts_requests_and_lends() {
+    let mut stats = BookPopularityStats::new();
+    let today = GensoDate::new(112, 8, 2);
+
+    stats.record_request("雨月物語", &today);
+    stats.record_lend("雨月物語", &today);
+    stats.record_request("遠野物語", &today);
+    stats.record_request("奥の細道", &today);
+    stats.record_request("奥の細道", &today);
+    stats.record_lend("奥の細道", &today);
+
+    let ranking = stats.top_titles_in_week(&today, 5);
+    assert_eq!(ranking[0], ("奥の細道".to_string(), 3));
+    assert_eq!(ranking[1], ("雨月物語".to_string(), 2));
+    assert_eq!(ranking[2], ("遠野物語".to_string(), 1));
+
+    assert_eq!(stats.top_titles_in_week(&today, 1).len(), 1);
+    assert_eq!(stats.get_total_requested("奥の細道"), 2);
+    assert_eq!(stats.get_total_lent("奥の細道"), 1);
+    assert_eq!(stats.get_total_lent("善の研究"), 0);
+}
+
+#[test]
+fn old_records_leave_the_weekly_ranking() {
+    let mut stats = BookPopularityStats::new();
+
+    stats.record_lend("雨月物語", &GensoDate::new(112, 7, 28));
+    stats.record_lend("遠野物語", &GensoDate::new(112, 8, 1));
+
+    // 七日前までは今週に含まれる
+    let today = GensoDate::new(112, 8, 3);
+    assert_eq!(stats.top_titles_in_week(&today, 5).len(), 2);
+
+    let today = GensoDate::new(112, 8, 4);
+    let ranking = stats.top_titles_in_week(&today, 5);
+    assert_eq!(ranking.len(), 1);
+    assert!(stats.is_bestseller("遠野物語", &today));
+    assert!(!stats.is_bestseller("雨月物語", &today));
+
+    // 累計は消えない
+    assert_eq!(stats.get_total_lent("雨月物語"), 1);
+}
@@ -0,0 +1,46 @@
+extern crate suzu;
+
+use suzu::object::cell_alignment::*;
+use torifune::numeric;
+
+fn cell() -> numeric::Rect {
+    numeric::Rect::new(10.0, 20.0, 60.0, 200.0)
+}
+
+#[test]
+fn center_anchor_snaps_inside_padding() {
+    let alignment = CellAlignment::new(CellAnchor::Center, PixelSnap::Round, 4.0);
+    let placement = alignment.place(cell(), numeric::Vector2f::new(23.0, 101.0));
+
+    assert_eq!(placement.fit_ratio, 1.0);
+    assert_eq!(placement.position, numeric::Point2f::new(29.0, 70.0));
+
+    let floor = CellAlignment::new(CellAnchor::Center, PixelSnap::Floor, 4.0);
+    let placement = floor.place(cell(), numeric::Vector2f::new(23.0, 101.0));
+    assert_eq!(placement.position, numeric::Point2f::new(28.0, 69.0));
+}
+
+#[test]
+fn top_right_anchor_follows_the_rules() {
+    let alignment = CellAlignment::new(CellAnchor::TopRight, PixelSnap::Off, 6.0);
+    let placement = alignment.place(cell(), numeric::Vector2f::new(24.5, 80.0));
+
+    assert_eq!(placement.position, numeric::Point2f::new(39.5, 26.0));
+}
+
+#[test]
+fn oversized_text_shrinks_to_stay_off_the_lines() {
+    let alignment = CellAlignment::new(CellAnchor::Center, PixelSnap::Round, 5.0);
+    let placement = alignment.place(cell(), numeric::Vector2f::new(24.0, 380.0));
+
+    // 余白の内側は190ピクセルなので半分に縮む
+    assert_eq!(placement.fit_ratio, 0.5);
+
+    let inner = alignment.inner_rect(cell());
+    let height = 380.0 * placement.fit_ratio;
+    let width = 24.0 * placement.fit_ratio;
+    assert!(placement.position.x >= inner.x);
+    assert!(placement.position.y >= inner.y);
+    assert!(placement.position.x + width <= inner.x + inner.w);
+    assert!(placement.position.y + height <= inner.y + inner.h);
+}
@@ -0,0 +1,81 @@
+extern crate suzu;
+
+use suzu::core::map_parser::*;
+use torifune::numeric;
+
+fn open_room(width: u32, height: u32) -> CollisionMap {
+    CollisionMap::new(
+        numeric::Vector2u::new(width, height),
+        Vec::new(),
+        vec![vec![TERRAIN_BASE_COST; height as usize]; width as usize],
+    )
+}
+
+fn route(map: &CollisionMap, start: (i32, i32), goal: (i32, i32)) -> Option<Vec<numeric::Point2i>> {
+    map.find_path(
+        numeric::Point2i::new(start.0, start.1),
+        numeric::Point2i::new(goal.0, goal.1),
+    )
+}
+
+#[test]
+fn blocked_tiles_force_a_detour() {
+    let mut map = open_room(5, 3);
+    assert_eq!(route(&map, (0, 1), (4, 1)).unwrap().len(), 5);
+
+    map.set_blocked(numeric::Vector2u::new(2, 1), true);
+    assert!(map.is_blocked(numeric::Vector2u::new(2, 1)));
+
+    let detour = route(&map, (0, 1), (4, 1)).unwrap();
+    assert_eq!(detour.len(), 7);
+    assert!(!detour.contains(&numeric::Point2i::new(2, 1)));
+}
+
+#[test]
+fn unblocking_restores_the_short_route() {
+    let mut map = open_room(5, 3);
+    map.set_blocked(numeric::Vector2u::new(2, 1), true);
+    map.set_blocked(numeric::Vector2u::new(2, 1), true);
+
+    map.set_blocked(numeric::Vector2u::new(2, 1), false);
+    assert!(!map.is_blocked(numeric::Vector2u::new(2, 1)));
+    assert_eq!(route(&map, (0, 1), (4, 1)).unwrap().len(), 5);
+}
+
+#[test]
+fn walled_off_goal_has_no_route() {
+    let mut map = open_room(5, 3);
+    for y in 0..3 {
+        map.set_blocked(numeric::Vector2u::new(2, y), true);
+    }
+
+    // マップの外を回り込むことはできない
+    assert_eq!(route(&map, (0, 1), (4, 1)), None);
+}
+
+#[test]
+fn occupied_tiles_are_avoided_but_still_passable() {
+    let map = open_room(5, 3);
+    let occupied = vec![numeric::Vector2u::new(2, 1)];
+
+    let detour = map
+        .find_path_avoiding(
+            numeric::Point2i::new(0, 1),
+            numeric::Point2i::new(4, 1),
+            &occupied,
+        )
+        .unwrap();
+    assert_eq!(detour.len(), 7);
+    assert!(!detour.contains(&numeric::Point2i::new(2, 1)));
+
+    // 一本道なら、立っている人の所を通り抜ける
+    let corridor = open_room(5, 1);
+    let route = corridor
+        .find_path_avoiding(
+            numeric::Point2i::new(0, 0),
+            numeric::Point2i::new(4, 0),
+            &[numeric::Vector2u::new(2, 0)],
+        )
+        .unwrap();
+    assert_eq!(route.len(), 5);
+}
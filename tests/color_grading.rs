@@ -0,0 +1,63 @@
+extern crate suzu;
+
+use suzu::core::color_grading::*;
+
+#[test]
+fn sepia_warms_and_cold_blue_cools_a_grey() {
+    let grey = [0.5, 0.5, 0.5];
+
+    let sepia = ColorGradePreset::Sepia.to_grade().apply(grey);
+    assert!(sepia[0] > sepia[1] && sepia[1] > sepia[2]);
+
+    let cold = ColorGradePreset::ColdBlue.to_grade().apply(grey);
+    assert!(cold[2] > cold[1] && cold[1] > cold[0]);
+
+    assert_eq!(ColorGrade::neutral().apply(grey), grey);
+    assert_eq!(
+        ColorGradePreset::from_str("cold-blue"),
+        Some(ColorGradePreset::ColdBlue)
+    );
+    assert_eq!(ColorGradePreset::from_str("noir"), None);
+}
+
+#[test]
+fn grade_blends_over_the_duration() {
+    let mut grading = ColorGrading::new(Default::default());
+    grading.set_preset(ColorGradePreset::Sepia, 100, 10);
+
+    assert_eq!(grading.current(10), ColorGrade::neutral());
+    assert!(grading.is_blending(60));
+    let halfway = grading.current(60);
+    let sepia = ColorGradePreset::Sepia.to_grade();
+    assert!((halfway.gain[2] - (1.0 + sepia.gain[2]) / 2.0).abs() < 0.0001);
+    assert_eq!(grading.current(110), sepia);
+    assert!(!grading.is_blending(110));
+
+    // 移り変わりの途中で戻すと、その時点の色味から戻り始める
+    grading.set_preset(ColorGradePreset::Sepia, 100, 0);
+    grading.set_preset(ColorGradePreset::Neutral, 10, 50);
+    assert_eq!(grading.current(50), ColorGrade::neutral().lerp(&sepia, 0.5));
+    assert!(grading.current(60).is_neutral());
+}
+
+#[test]
+fn scene_preset_applies_on_entering_and_keeps_scripted_grade() {
+    let presets = ColorGrading::scene_presets_from_toml_str(
+        "[scenes]\nAlbum = \"sepia\"\nLedgerAudit = \"cold-blue\"\n",
+    );
+    assert_eq!(presets.get("Album"), Some(&ColorGradePreset::Sepia));
+    assert!(ColorGrading::scene_presets_from_toml_str("[scenes]\nAlbum = \"noir\"").is_empty());
+
+    let mut grading = ColorGrading::new(presets);
+    grading.enter_scene("Album", 0);
+    assert_eq!(grading.get_preset(), ColorGradePreset::Sepia);
+
+    // 同じ場面にいる間は、台本が変えた色味を戻さない
+    grading.enter_scene("Scenario", 100);
+    grading.set_preset(ColorGradePreset::ColdBlue, 0, 120);
+    grading.enter_scene("Scenario", 121);
+    assert_eq!(grading.get_preset(), ColorGradePreset::ColdBlue);
+
+    grading.enter_scene("SuzunaShop", 200);
+    assert_eq!(grading.get_preset(), ColorGradePreset::Neutral);
+}
@@ -0,0 +1,49 @@
+extern crate suzu;
+
+use suzu::core::config::*;
+
+#[test]
+fn settings_are_read_from_toml() {
+    let settings = Settings::from_toml_str(
+        r#"
+        resolution = [1920.0, 1080.0]
+        fullscreen = true
+        vsync = false
+        master_volume = 0.5
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(settings.resolution, (1920.0, 1080.0));
+    assert!(settings.fullscreen);
+    assert!(!settings.vsync);
+    assert_eq!(settings.master_volume, 0.5);
+}
+
+#[test]
+fn missing_entries_and_files_fall_back_to_defaults() {
+    let settings = Settings::from_toml_str("fullscreen = true").unwrap();
+    assert_eq!(settings.resolution, Settings::default().resolution);
+    assert!(settings.vsync);
+    assert_eq!(settings.master_volume, 1.0);
+
+    assert_eq!(
+        Settings::load_from_path("./tests/fixtures/no_such_settings.toml"),
+        None
+    );
+    assert_eq!(Settings::from_toml_str("resolution = \"large\""), None);
+}
+
+#[test]
+fn odd_values_are_clamped() {
+    let settings = Settings::from_toml_str(
+        r#"
+        resolution = [10.0, 10.0]
+        master_volume = 3.0
+        "#,
+    )
+    .unwrap();
+
+    assert!(settings.resolution.0 >= 683.0 && settings.resolution.1 >= 384.0);
+    assert_eq!(settings.master_volume, 1.0);
+}
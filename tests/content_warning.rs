@@ -0,0 +1,33 @@
+extern crate suzu;
+
+use suzu::core::content_warning::*;
+
+#[test]
+fn unknown_and_repeated_tags_are_dropped() {
+    let tags = parse_tags(["scary", "flash", "loud", "scary"].iter().cloned());
+    assert_eq!(tags, vec![ContentTag::Scary, ContentTag::Loud]);
+    assert!(parse_tags(Vec::new()).is_empty());
+}
+
+#[test]
+fn tagged_scenes_are_left_alone_when_disabled() {
+    let tags = [ContentTag::Scary, ContentTag::Loud];
+    assert_eq!(scene_treatment(false, &tags, true), SceneTreatment::as_is());
+    assert_eq!(scene_treatment(true, &[], true), SceneTreatment::as_is());
+}
+
+#[test]
+fn skip_is_offered_only_with_a_destination() {
+    let scary = scene_treatment(true, &[ContentTag::Scary], true);
+    assert!(scary.offer_skip);
+    assert!(!scary.mute_sudden_se);
+
+    let loud = scene_treatment(true, &[ContentTag::Loud], false);
+    assert!(!loud.offer_skip);
+    assert!(loud.mute_sudden_se);
+
+    assert_eq!(
+        warning_header(&[ContentTag::Scary, ContentTag::Loud]),
+        "コノ先ニハ怖イ描写ヤ大キナ音ガアリマス。飛バシマスカ？"
+    );
+}
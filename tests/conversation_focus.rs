@@ -0,0 +1,51 @@
+extern crate suzu;
+
+use suzu::object::conversation_focus::*;
+use torifune::numeric;
+
+#[test]
+fn speaking_zooms_in_and_dims_the_desk() {
+    let mut focus = ConversationFocus::new();
+    assert!(!focus.is_moving_at(0));
+    assert_eq!(focus.zoom_at(0), 1.0);
+
+    focus.speak(100);
+    assert!(focus.is_focused());
+    assert!(focus.is_moving_at(100 + FOCUS_IN_TIME / 2));
+    assert!(focus.amount_at(100 + FOCUS_IN_TIME / 2) > 0.0);
+
+    let settled = 100 + FOCUS_IN_TIME;
+    assert!(!focus.is_moving_at(settled));
+    assert_eq!(focus.zoom_at(settled), 1.0 + FOCUS_ZOOM);
+    assert_eq!(focus.desk_dim_at(settled), FOCUS_DESK_DIM);
+}
+
+#[test]
+fn release_restores_from_the_current_amount() {
+    let mut focus = ConversationFocus::new();
+    focus.speak(0);
+
+    // 寄っている途中で書類に手を付けても、その位置から戻る
+    let half = FOCUS_IN_TIME / 2;
+    let amount = focus.amount_at(half);
+    focus.release(half);
+    assert!(!focus.is_focused());
+    assert_eq!(focus.amount_at(half), amount);
+    assert_eq!(focus.amount_at(half + FOCUS_OUT_TIME), 0.0);
+
+    // 同じ台詞の間に何度呼ばれても寄り直さない
+    focus.speak(200);
+    let before = focus.amount_at(220);
+    focus.speak(220);
+    assert_eq!(focus.amount_at(220), before);
+}
+
+#[test]
+fn zoom_position_keeps_the_center_fixed() {
+    let center = numeric::Point2f::new(175.0, 150.0);
+    assert_eq!(zoom_position(center, center, 1.5), center);
+
+    let moved = zoom_position(numeric::Point2f::new(0.0, 0.0), center, 1.1);
+    assert!((moved.x - -17.5).abs() < 0.001);
+    assert!((moved.y - -15.0).abs() < 0.001);
+}
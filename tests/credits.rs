@@ -0,0 +1,60 @@
+extern crate suzu;
+
+use suzu::core::credits::*;
+
+#[test]
+fn credits_table_reads_sections_in_order() {
+    let table = CreditsTable::from_toml_str(
+        r#"
+[[section]]
+role = "企画"
+names = ["一人目"]
+
+[[section]]
+role = "書体"
+names = ["二人目", "三人目"]
+"#,
+    )
+    .unwrap();
+
+    let sections = table.get_sections();
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].role, "企画");
+    assert_eq!(sections[1].names, vec!["二人目", "三人目"]);
+    assert_eq!(table.names_count(), 3);
+}
+
+#[test]
+fn scroll_stops_at_the_end_of_the_roll() {
+    let mut scroll = CreditsScroll::new(10.0);
+    let speed = CREDITS_SPEED_LEVELS[DEFAULT_SPEED_LEVEL];
+
+    assert_eq!(scroll.advance(), speed);
+    assert!(!scroll.is_finished());
+
+    while !scroll.is_finished() {
+        scroll.advance();
+    }
+    assert_eq!(scroll.get_offset(), 10.0);
+    assert_eq!(scroll.advance(), 0.0);
+
+    // 奥の絵は流れた長さの一部しか動かない
+    assert_eq!(scroll.parallax(0.5), 5.0);
+}
+
+#[test]
+fn speed_control_is_bounded_and_can_pause() {
+    let mut scroll = CreditsScroll::new(100.0);
+
+    while scroll.speed_up() {}
+    assert_eq!(scroll.get_speed_level(), CREDITS_SPEED_LEVELS.len() - 1);
+    assert!(!scroll.speed_up());
+
+    while scroll.speed_down() {}
+    assert!(scroll.is_paused());
+    assert_eq!(scroll.advance(), 0.0);
+    assert_eq!(scroll.get_offset(), 0.0);
+
+    scroll.skip();
+    assert!(scroll.is_finished());
+}
@@ -0,0 +1,48 @@
+extern crate suzu;
+
+use suzu::core::crowd_density::*;
+
+#[test]
+fn curve_scales_arrival_timing_by_hour() {
+    let curve = ArrivalCurve::new(300, 300, vec![0.5, 1.0, 1.5], None);
+
+    assert_eq!(curve.add_timing(ARRIVAL_CURVE_START_HOUR), 600);
+    assert_eq!(curve.add_timing(ARRIVAL_CURVE_START_HOUR + 1), 300);
+    assert_eq!(curve.add_timing(ARRIVAL_CURVE_START_HOUR + 2), 200);
+
+    // 曲線の外は端の値を使う
+    assert_eq!(curve.add_timing(ARRIVAL_CURVE_START_HOUR - 1), 600);
+    assert_eq!(curve.add_timing(ARRIVAL_CURVE_START_HOUR + 10), 200);
+}
+
+#[test]
+fn festival_brings_more_customers() {
+    let festival = FestivalDay {
+        name: "秋祭リ".to_string(),
+        month: 10,
+        day: 10,
+        scale: 2.0,
+    };
+    let normal = ArrivalCurve::new(300, 300, vec![1.0], None);
+    let festive = ArrivalCurve::new(300, 300, vec![1.0], Some(festival));
+
+    assert!(festive.add_timing(12) < normal.add_timing(12));
+    assert_eq!(festive.expected_busyness(), Busyness::Festival);
+    assert_eq!(normal.expected_busyness(), Busyness::Normal);
+}
+
+#[test]
+fn busyness_compares_with_the_week() {
+    assert_eq!(
+        ArrivalCurve::new(400, 300, vec![0.8, 1.0], None).expected_busyness(),
+        Busyness::Quiet
+    );
+    assert_eq!(
+        ArrivalCurve::new(200, 300, vec![1.0, 1.2], None).expected_busyness(),
+        Busyness::Busy
+    );
+    assert_eq!(
+        ArrivalCurve::new(300, 300, Vec::new(), None).expected_busyness(),
+        Busyness::Normal
+    );
+}
@@ -0,0 +1,49 @@
+extern crate suzu;
+
+use suzu::object::customer_emotion::*;
+
+#[test]
+fn waiting_too_long_makes_the_customer_impatient() {
+    let mut mood = CustomerMood::new();
+    assert_eq!(mood.get_resting(), CustomerEmotion::Idle);
+
+    assert_eq!(mood.update_waiting(0), None);
+    assert_eq!(mood.update_waiting(CUSTOMER_IMPATIENT_CLOCK - 1), None);
+    assert_eq!(
+        mood.update_waiting(CUSTOMER_IMPATIENT_CLOCK),
+        Some(CustomerEmotion::Impatient)
+    );
+    assert_eq!(mood.update_waiting(CUSTOMER_IMPATIENT_CLOCK + 100), None);
+    assert_eq!(mood.get_resting(), CustomerEmotion::Impatient);
+
+    // 次の客を待ち始めると落ち着く
+    assert_eq!(mood.update_waiting(0), Some(CustomerEmotion::Idle));
+}
+
+#[test]
+fn handovers_pick_a_reaction() {
+    assert_eq!(CustomerEmotion::reaction(true), CustomerEmotion::Happy);
+    assert_eq!(CustomerEmotion::reaction(false), CustomerEmotion::Angry);
+    assert!(CustomerEmotion::Happy.is_reaction());
+    assert!(CustomerEmotion::Angry.is_reaction());
+    assert!(!CustomerEmotion::Idle.is_reaction());
+    assert!(!CustomerEmotion::Impatient.is_reaction());
+}
+
+#[test]
+fn every_emotion_has_frames_and_a_pose() {
+    let modes = CustomerEmotion::mode_order();
+    assert_eq!(modes.len(), 4);
+
+    for emotion in modes {
+        assert!(emotion.frame_count() > 0);
+        // コマの番号が数を越えても、揺れは繰り返す
+        assert_eq!(
+            emotion.pose_offset(0),
+            emotion.pose_offset(emotion.frame_count() * 3)
+        );
+    }
+
+    assert!(CustomerEmotion::Happy.pose_offset(2).y < 0.0);
+    assert!(CustomerEmotion::Angry.pose_offset(0).x != 0.0);
+}
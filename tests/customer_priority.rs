@@ -0,0 +1,35 @@
+extern crate suzu;
+
+use suzu::core::game_system::*;
+
+#[test]
+fn prioritized_customers_queue_behind_each_other() {
+    use CustomerPriority::*;
+
+    assert_eq!(priority_insert_index(&[], Elderly), 0);
+    assert_eq!(priority_insert_index(&[Normal, Normal], Normal), 2);
+    assert_eq!(priority_insert_index(&[Normal, Normal], Expecting), 0);
+
+    // 先に並んでいる優先の客は追い越さない
+    assert_eq!(
+        priority_insert_index(&[Elderly, Normal, Normal], Expecting),
+        1
+    );
+    assert_eq!(priority_insert_index(&[Elderly, Expecting], Elderly), 2);
+}
+
+#[test]
+fn only_unfair_overtaking_is_counted() {
+    use CustomerPriority::*;
+
+    let queued = [Elderly, Normal, Normal, Normal];
+    assert_eq!(count_skipped_customers(&queued, 0), 0);
+    assert_eq!(count_skipped_customers(&queued, 3), 3);
+    assert_eq!(count_skipped_customers(&queued, 4), 0);
+
+    // 優先の客を先に呼ぶのは、普通の客を追い越しても不公平ではない
+    let queued = [Normal, Normal, Elderly];
+    assert_eq!(count_skipped_customers(&queued, 2), 0);
+    let queued = [Expecting, Normal, Elderly];
+    assert_eq!(count_skipped_customers(&queued, 2), 1);
+}
@@ -0,0 +1,61 @@
+extern crate suzu;
+
+use suzu::core::customer_watchdog::*;
+use torifune::numeric;
+
+#[test]
+fn moving_customer_is_never_flagged() {
+    let mut watch = ProgressWatch::new(numeric::Point2f::new(0.0, 0.0), 0);
+
+    for step in 1..10 {
+        let position = numeric::Point2f::new(step as f32 * 10.0, 0.0);
+        let t = step * STUCK_TIMEOUT;
+        assert_eq!(watch.observe(position, t), WatchdogAction::Keep);
+    }
+}
+
+#[test]
+fn stuck_customer_is_repathed_then_teleported_then_despawned() {
+    let position = numeric::Point2f::new(32.0, 32.0);
+    let mut watch = ProgressWatch::new(position, 0);
+
+    assert_eq!(
+        watch.observe(position, STUCK_TIMEOUT - 1),
+        WatchdogAction::Keep
+    );
+
+    let mut t = 0;
+    for _ in 0..REPATH_ATTEMPTS {
+        t += STUCK_TIMEOUT;
+        assert_eq!(watch.observe(position, t), WatchdogAction::Repath);
+    }
+
+    t += STUCK_TIMEOUT;
+    assert_eq!(watch.observe(position, t), WatchdogAction::Teleport);
+    t += STUCK_TIMEOUT;
+    assert_eq!(watch.observe(position, t), WatchdogAction::Despawn);
+
+    // 動き出せば、また一から見張る
+    t += STUCK_TIMEOUT;
+    let moved = numeric::Point2f::new(64.0, 32.0);
+    assert_eq!(watch.observe(moved, t), WatchdogAction::Keep);
+    assert_eq!(watch.get_attempts(), 0);
+}
+
+#[test]
+fn nearest_open_tile_skips_blocked_tiles() {
+    let size = numeric::Vector2u::new(5, 5);
+    let from = numeric::Vector2u::new(2, 2);
+
+    let open = nearest_open_tile(from, size, |_| false);
+    assert_eq!(open, Some(from));
+
+    // (2, 2)の周りは、右隣の(3, 2)だけが通れる
+    let blocked = |tile: numeric::Vector2u| !(tile.x == 3 && tile.y == 2) && tile.x <= 3;
+    assert_eq!(
+        nearest_open_tile(from, size, blocked),
+        Some(numeric::Vector2u::new(3, 2))
+    );
+
+    assert_eq!(nearest_open_tile(from, size, |_| true), None);
+}
@@ -0,0 +1,25 @@
+extern crate suzu;
+
+use suzu::core::day_modifier::*;
+
+#[test]
+fn lunch_modifier_expires_at_deadline() {
+    let mut modifiers = DayModifiers::new();
+    let menu = LunchMenu::from_opecode("LunchSoba").unwrap();
+    assert_eq!(menu.modifier_type(), DayModifierType::PatienceAura);
+    assert!(LunchMenu::from_opecode("LateNightOpen").is_none());
+
+    modifiers.update_minute(DayModifiers::to_minute(12, 0));
+    modifiers.add(
+        menu.modifier_type(),
+        DayModifiers::to_minute(LUNCH_BUFF_UNTIL_HOUR, 0),
+    );
+    assert!(modifiers.is_active(DayModifierType::PatienceAura));
+    assert!(!modifiers.is_active(DayModifierType::Focus));
+
+    modifiers.update_minute(DayModifiers::to_minute(16, 59));
+    assert!(modifiers.is_active(DayModifierType::PatienceAura));
+
+    modifiers.update_minute(DayModifiers::to_minute(LUNCH_BUFF_UNTIL_HOUR, 0));
+    assert!(!modifiers.is_active(DayModifierType::PatienceAura));
+}
@@ -0,0 +1,93 @@
+extern crate suzu;
+
+use std::collections::HashMap;
+
+use suzu::core::decision_log::*;
+use suzu::core::GensoDate;
+
+const SAMPLE_TABLE: &str = r#"
+[[ending]]
+id = "ordinary"
+title = "イツモノ鈴奈庵"
+base = 2.0
+
+[[ending]]
+id = "guardian"
+title = "妖魔本ノ番人"
+scenario = "/scenario/ending_guardian.toml"
+   [ending.weights]
+   confiscation = 0.5
+   report = 1.0
+
+[[ending]]
+id = "merchant"
+title = "商売上手"
+   [ending.weights]
+   "policy:late_night" = 2.0
+"#;
+
+fn today() -> GensoDate {
+    GensoDate::new(112, 7, 23)
+}
+
+#[test]
+fn ending_score_adds_kind_and_specific_weights() {
+    let mut weights = HashMap::new();
+    weights.insert("policy".to_string(), 0.5);
+    weights.insert("policy:late_night".to_string(), 1.0);
+    let ending = Ending {
+        id: "merchant".to_string(),
+        title: "商売上手".to_string(),
+        scenario: None,
+        base: 1.0,
+        weights: weights,
+    };
+
+    let mut log = DecisionLog::new();
+    assert_eq!(ending.score(&log), 1.0);
+
+    log.record(DecisionKind::Policy, "late_night", today());
+    log.record(DecisionKind::Policy, "reading_corner", today());
+    log.record(DecisionKind::Refusal, "竹取物語", today());
+    assert_eq!(ending.score(&log), 1.0 + 1.5 + 0.5);
+}
+
+#[test]
+fn table_selects_highest_scoring_ending() {
+    let table = EndingTable::from_toml_str(SAMPLE_TABLE).unwrap();
+    let mut log = DecisionLog::new();
+
+    assert_eq!(table.select(&log).unwrap().id, "ordinary");
+
+    // 同じ点数なら先に書いた結末
+    log.record(DecisionKind::Report, "百鬼夜行絵巻", today());
+    log.record(DecisionKind::Report, "妖魔本", today());
+    assert_eq!(table.select(&log).unwrap().id, "ordinary");
+
+    log.record(DecisionKind::Confiscation, "贋作", today());
+    let ending = table.select(&log).unwrap();
+    assert_eq!(ending.id, "guardian");
+    assert_eq!(
+        ending.scenario.as_ref().map(|s| s.as_str()),
+        Some("/scenario/ending_guardian.toml")
+    );
+}
+
+#[test]
+fn summary_counts_recorded_kinds() {
+    let mut log = DecisionLog::new();
+    log.record(DecisionKind::Refusal, "読書処", today());
+    log.record(DecisionKind::Confiscation, "贋作", today());
+    log.record(DecisionKind::Refusal, "竹取物語", today());
+
+    assert_eq!(
+        log.summary(),
+        vec![(DecisionKind::Refusal, 2), (DecisionKind::Confiscation, 1)]
+    );
+    assert_eq!(
+        DecisionKind::from_str("affinity"),
+        Some(DecisionKind::Affinity)
+    );
+    assert_eq!(DecisionKind::from_str("unknown"), None);
+    assert!(log.get_ending().is_none());
+}
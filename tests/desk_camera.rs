@@ -0,0 +1,58 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::object::desk_camera::*;
+
+fn close(a: numeric::Point2f, b: numeric::Point2f) -> bool {
+    (a.x - b.x).abs() < 1e-3 && (a.y - b.y).abs() < 1e-3
+}
+
+#[test]
+fn zoom_keeps_point_under_cursor() {
+    let mut camera = DeskCamera::new(numeric::Vector2f::new(800.0, 400.0));
+    let cursor = numeric::Point2f::new(300.0, 200.0);
+    let before = camera.to_world(cursor);
+
+    assert!(camera.zoom_at(cursor, 2.0));
+    assert!(camera.is_zoomed());
+    assert!(close(camera.to_world(cursor), before));
+    assert!(close(camera.to_local(before), cursor));
+}
+
+#[test]
+fn zoom_is_clamped_and_view_stays_on_desk() {
+    let mut camera = DeskCamera::new(numeric::Vector2f::new(800.0, 400.0));
+
+    // 縮める方向には、机全体より先へ行かない
+    assert!(!camera.zoom_at(numeric::Point2f::new(0.0, 0.0), -3.0));
+    assert_eq!(camera.get_zoom(), DESK_ZOOM_MIN);
+
+    camera.zoom_at(numeric::Point2f::new(800.0, 400.0), 100.0);
+    assert_eq!(camera.get_zoom(), DESK_ZOOM_MAX);
+
+    // 右下の隅で拡大しても、机の外は映らない
+    let bottom_right = camera.to_world(numeric::Point2f::new(800.0, 400.0));
+    assert!(close(bottom_right, numeric::Point2f::new(800.0, 400.0)));
+}
+
+#[test]
+fn pan_moves_view_within_desk() {
+    let mut camera = DeskCamera::new(numeric::Vector2f::new(800.0, 400.0));
+
+    // 拡大していなければ、動かす余地はない
+    assert!(!camera.pan_by(numeric::Vector2f::new(-50.0, 0.0)));
+
+    camera.zoom_at(numeric::Point2f::new(0.0, 0.0), 5.0);
+    let zoom = camera.get_zoom();
+
+    assert!(camera.pan_by(numeric::Vector2f::new(-100.0, 0.0)));
+    assert!((camera.get_offset().x - 100.0 / zoom).abs() < 1e-3);
+
+    // 左上より先へは動かない
+    camera.pan_by(numeric::Vector2f::new(10000.0, 10000.0));
+    assert!(close(camera.get_offset(), numeric::Point2f::new(0.0, 0.0)));
+
+    camera.reset();
+    assert!(!camera.is_zoomed());
+}
@@ -0,0 +1,68 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use torifune::numeric;
+
+use suzu::core::desk_nuisance::*;
+
+#[test]
+fn scheduler_never_fires_on_easy_or_when_disabled() {
+    let mut rng = StdRng::seed_from_u64(3740);
+
+    let mut easy = DeskNuisanceScheduler::new(DeskNuisanceTuning::default(), true, 0);
+    assert!(!easy.is_active());
+
+    let disabled_tuning = DeskNuisanceTuning::from_toml_str("enabled = false");
+    let mut disabled = DeskNuisanceScheduler::new(disabled_tuning, false, 0);
+    assert!(!disabled.is_active());
+
+    for t in (0..60 * 60 * 10).step_by(60) {
+        assert_eq!(easy.poll(t, &mut rng), None);
+        assert_eq!(disabled.poll(t, &mut rng), None);
+    }
+}
+
+#[test]
+fn scheduler_fires_after_delay_and_reschedules_within_interval() {
+    let mut rng = StdRng::seed_from_u64(3740);
+    let tuning = DeskNuisanceTuning::from_toml_str(
+        "first_delay_seconds = 10\nmin_interval_seconds = 20\nmax_interval_seconds = 30\ncat_weight = 0",
+    );
+    let mut scheduler = DeskNuisanceScheduler::new(tuning, false, 100);
+
+    assert_eq!(scheduler.get_next_at(), Some(100 + 600));
+    assert_eq!(scheduler.poll(699, &mut rng), None);
+    assert_eq!(scheduler.poll(700, &mut rng), Some(NuisanceKind::Gust));
+
+    let next_at = scheduler.get_next_at().unwrap();
+    assert!(next_at >= 700 + 20 * 60 && next_at <= 700 + 30 * 60);
+
+    scheduler.retry_later(800);
+    assert_eq!(scheduler.get_next_at(), Some(800 + NUISANCE_RETRY_DELAY));
+
+    scheduler.disable();
+    assert_eq!(scheduler.poll(100000, &mut rng), None);
+}
+
+#[test]
+fn scattered_papers_stay_on_the_desk() {
+    let desk = numeric::Rect::new(0.0, 0.0, 800.0, 400.0);
+    let size = numeric::Vector2f::new(100.0, 60.0);
+
+    let blown = scatter_destination(
+        numeric::Point2f::new(50.0, 300.0),
+        size,
+        desk,
+        numeric::Vector2f::new(-200.0, 100.0),
+    );
+    assert_eq!(blown, numeric::Point2f::new(0.0, 340.0));
+
+    let inside = scatter_destination(
+        numeric::Point2f::new(400.0, 100.0),
+        size,
+        desk,
+        numeric::Vector2f::new(-150.0, 20.0),
+    );
+    assert_eq!(inside, numeric::Point2f::new(250.0, 120.0));
+}
@@ -0,0 +1,54 @@
+extern crate suzu;
+
+use std::f32::consts::PI;
+
+use torifune::numeric;
+
+use suzu::object::desk_rotation::*;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn rotation_is_kept_within_half_turn() {
+    assert!(close(normalize_rotation(0.5), 0.5));
+    assert!(close(normalize_rotation(PI + 0.5), -PI + 0.5));
+    assert!(close(normalize_rotation(-PI - 0.5), PI - 0.5));
+    assert!(close(normalize_rotation(4.0 * PI + 0.25), 0.25));
+}
+
+#[test]
+fn rotated_area_is_hit_where_it_is_drawn() {
+    let area = numeric::Rect::new(0.0, 0.0, 100.0, 20.0);
+    let pivot = numeric::Point2f::new(0.0, 0.0);
+
+    // 左上を中心に四半回転させると、横長の物が下へ伸びる
+    assert!(rotated_contains(
+        area,
+        pivot,
+        PI / 2.0,
+        numeric::Point2f::new(-10.0, 80.0)
+    ));
+    assert!(!rotated_contains(
+        area,
+        pivot,
+        PI / 2.0,
+        numeric::Point2f::new(80.0, 10.0)
+    ));
+
+    let p = unrotate_point(pivot, PI / 2.0, numeric::Point2f::new(-10.0, 80.0));
+    assert!(close(p.x, 80.0) && close(p.y, 10.0));
+}
+
+#[test]
+fn rotated_bounds_cover_every_corner() {
+    let area = numeric::Rect::new(10.0, 10.0, 100.0, 20.0);
+    let pivot = numeric::Point2f::new(10.0, 10.0);
+
+    let bounds = rotated_bounds(area, pivot, PI / 2.0);
+    assert!(close(bounds.x, -10.0) && close(bounds.y, 10.0));
+    assert!(close(bounds.w, 20.0) && close(bounds.h, 100.0));
+
+    assert_eq!(rotated_bounds(area, pivot, 0.0), area);
+}
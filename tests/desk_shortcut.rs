@@ -0,0 +1,61 @@
+extern crate suzu;
+
+use suzu::core::shortcut_palette::PaletteKey;
+use suzu::object::desk_shortcut::*;
+
+#[test]
+fn keys_are_mapped_to_desk_shortcuts() {
+    assert_eq!(
+        DeskShortcut::from_palette_key(PaletteKey::Char('1')),
+        Some(DeskShortcut::ChooseEntry(0))
+    );
+    assert_eq!(
+        DeskShortcut::from_palette_key(PaletteKey::Char('4')),
+        Some(DeskShortcut::ChooseEntry(3))
+    );
+    assert_eq!(
+        DeskShortcut::from_palette_key(PaletteKey::Tab),
+        Some(DeskShortcut::NextCell)
+    );
+    assert_eq!(
+        DeskShortcut::from_palette_key(PaletteKey::Char(' ')),
+        Some(DeskShortcut::HandOver)
+    );
+    assert_eq!(
+        DeskShortcut::from_palette_key(PaletteKey::Escape),
+        Some(DeskShortcut::CancelDrag)
+    );
+    assert_eq!(DeskShortcut::from_palette_key(PaletteKey::Char('0')), None);
+    assert_eq!(DeskShortcut::from_palette_key(PaletteKey::Char('a')), None);
+    assert_eq!(DeskShortcut::from_palette_key(PaletteKey::Enter), None);
+}
+
+#[test]
+fn tab_cells_follow_the_request_kind() {
+    let borrowing = record_book_tab_cells(true, 4);
+    assert_eq!(
+        borrowing,
+        vec![
+            RecordBookCell::CustomerName,
+            RecordBookCell::BorrowDate,
+            RecordBookCell::ReturnDate,
+            RecordBookCell::BookTitle(0),
+            RecordBookCell::BookTitle(1),
+            RecordBookCell::BookTitle(2),
+        ]
+    );
+
+    let returning = record_book_tab_cells(false, 4);
+    assert_eq!(returning[0], RecordBookCell::ReturnDate);
+    assert!(returning[0].is_customer_info());
+    assert_eq!(returning.len(), 4);
+    assert!(!returning[1].is_customer_info());
+}
+
+#[test]
+fn tab_wraps_around_the_cells() {
+    assert_eq!(next_cell_index(None, 3), Some(0));
+    assert_eq!(next_cell_index(Some(0), 3), Some(1));
+    assert_eq!(next_cell_index(Some(2), 3), Some(0));
+    assert_eq!(next_cell_index(None, 0), None);
+}
@@ -0,0 +1,91 @@
+extern crate suzu;
+
+use suzu::core::decision_log::{DecisionKind, DecisionLog};
+use suzu::core::diary::*;
+use suzu::core::GensoDate;
+
+const SAMPLE_PROMPTS: &str = r#"
+[[prompt]]
+text = "今日モ平和ダッタ。"
+
+[[prompt]]
+text = "忙シカッタ。"
+when = "busy"
+flag = "overworked"
+
+[[prompt]]
+text = "断ッタ御客ガ気ニナル。"
+when = "refused"
+flag = "regrets_refusal"
+
+[[prompt]]
+text = "読メナイ条件。"
+when = "unknown"
+"#;
+
+fn today() -> GensoDate {
+    GensoDate::new(112, 7, 23)
+}
+
+#[test]
+fn day_summary_counts_only_todays_decisions() {
+    let mut log = DecisionLog::new();
+    log.record(DecisionKind::Refusal, "竹取物語", today().sub_days_chain(1));
+    log.record(DecisionKind::Refusal, "読書処", today());
+    log.record(DecisionKind::Report, "妖魔本", today());
+    log.record(DecisionKind::Policy, "late_night", today());
+
+    let day = DaySummary::new(3, 120, &log, &today());
+    assert_eq!(day.refusals, 1);
+    assert_eq!(day.confiscations, 1);
+    assert!(DiaryCondition::Earned.is_met(&day));
+    assert!(!DiaryCondition::Busy.is_met(&day));
+    assert!(!DiaryCondition::Quiet.is_met(&day));
+}
+
+#[test]
+fn table_offers_prompts_matching_the_day() {
+    let table = DiaryPromptTable::from_toml_str(SAMPLE_PROMPTS).unwrap();
+
+    let quiet_day = DaySummary::new(1, 0, &DecisionLog::new(), &today());
+    let texts: Vec<String> = table
+        .available(&quiet_day)
+        .into_iter()
+        .map(|prompt| prompt.text)
+        .collect();
+    assert_eq!(texts, vec!["今日モ平和ダッタ。".to_string()]);
+
+    let mut log = DecisionLog::new();
+    log.record(DecisionKind::Refusal, "読書処", today());
+    let busy_day = DaySummary::new(8, 300, &log, &today());
+    assert_eq!(table.available(&busy_day).len(), 3);
+}
+
+#[test]
+fn written_entries_set_flags_and_replace_same_day() {
+    let table = DiaryPromptTable::from_toml_str(SAMPLE_PROMPTS).unwrap();
+    let busy_day = DaySummary::new(8, 300, &DecisionLog::new(), &today());
+    let prompts = table.available(&busy_day);
+
+    let mut diary = Diary::new();
+    assert!(diary.is_empty());
+
+    let entry = DiaryEntry::compose(
+        today(),
+        &prompts,
+        "本ガ沢山借リラレタ。明日モ頑張ロウ。栞ヲ沢山作ル。",
+    );
+    assert_eq!(entry.flags, vec!["overworked".to_string()]);
+    assert_eq!(entry.text.chars().count(), DIARY_TEXT_MAX_CHARS);
+    diary.write(entry);
+    assert!(diary.has_flag("overworked"));
+
+    // 同じ日に書き直すと、前の日記の印は消える
+    diary.write(DiaryEntry::compose(today(), &prompts[..1], "平和"));
+    assert!(!diary.has_flag("overworked"));
+    assert_eq!(diary.iter_recent().count(), 1);
+    assert_eq!(
+        diary.iter_recent().next().unwrap().body_text(),
+        "今日モ平和ダッタ。平和"
+    );
+}
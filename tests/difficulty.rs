@@ -0,0 +1,71 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use torifune::core::Clock;
+
+use suzu::core::difficulty::*;
+use suzu::core::request_generator::*;
+use suzu::core::{GameMode, GensoDate, RentalLimit};
+
+#[test]
+fn difficulty_ramps_with_the_date() {
+    let tuning = DifficultyTuning::default();
+    let first_day = GensoDate::new(112, 7, 23);
+
+    let easy = tuning.for_date(&GameMode::story(), &first_day);
+    assert_eq!(easy.max_books, tuning.start_max_books);
+    assert_eq!(easy.return_date_offset, tuning.start_return_date_offset);
+
+    let later = tuning.for_date(&GameMode::story(), &first_day.add_days_chain(14));
+    assert!(later.spawn_scale > easy.spawn_scale);
+    assert!(later.short_term_rate > easy.short_term_rate);
+
+    let hard = tuning.for_date(&GameMode::story(), &first_day.add_days_chain(60));
+    assert_eq!(hard, tuning.at_progress(1.0));
+    assert_eq!(hard.max_books, REQUEST_MAX_BOOKS);
+
+    // 熟練の遊び方は最初から最も難しい
+    assert_eq!(tuning.for_date(&GameMode::time_attack(), &first_day), hard);
+}
+
+#[test]
+fn spawn_scale_shortens_add_timing() {
+    let mut difficulty = Difficulty::default();
+    assert_eq!(difficulty.scale_add_timing(150), 150);
+
+    difficulty.spawn_scale = 1.5;
+    assert_eq!(difficulty.scale_add_timing(150), 100);
+    assert_eq!(difficulty.scale_add_timing(0), 1);
+
+    difficulty.spawn_scale = 0.0;
+    assert_eq!(difficulty.scale_add_timing(150), Clock::max_value());
+
+    let tuning = DifficultyTuning::from_toml_str("ramp_days = 0\nend_max_books = 3\n");
+    assert_eq!(tuning.progress(0), 1.0);
+    assert_eq!(tuning.at_progress(1.0).max_books, 3);
+}
+
+#[test]
+fn request_generator_follows_difficulty() {
+    let today = GensoDate::new(112, 8, 2);
+    let strict = Difficulty {
+        spawn_scale: 1.0,
+        max_books: 1,
+        short_term_rate: 1.0,
+        return_date_offset: 0,
+    };
+    let generator = strict.request_generator();
+    assert_eq!(generator.get_max_books(), 1);
+
+    for seed in 0..50 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (borrow_date, return_date) = generator.returning_dates(&mut rng, &today);
+
+        assert_eq!(today.diff_days(&return_date), 0);
+        match borrow_date.rental_limit_type(&return_date) {
+            Some(RentalLimit::ShortTerm) => (),
+            _ => panic!("short term expected"),
+        }
+    }
+}
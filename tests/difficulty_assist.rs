@@ -0,0 +1,35 @@
+extern crate suzu;
+
+use suzu::core::game_system::*;
+
+#[test]
+fn assist_is_offered_once_while_struggling() {
+    let mut assist = DifficultyAssist::new();
+
+    // 状態評価の無い受付は数えない
+    assert_eq!(assist.record_request(0, 0), AssistChange::Keep);
+
+    for _ in 0..(ASSIST_WINDOW - 1) {
+        assert_eq!(assist.record_request(4, 3), AssistChange::Keep);
+    }
+    assert_eq!(assist.record_request(4, 3), AssistChange::Offer);
+    assert_eq!(assist.record_request(4, 3), AssistChange::Keep);
+
+    assist.decline();
+    assert!(!assist.is_enabled());
+    assert_eq!(assist.get_declined_count(), 1);
+}
+
+#[test]
+fn assist_turns_off_after_recovery() {
+    let mut assist = DifficultyAssist::new();
+    assist.accept();
+    assert!(assist.is_enabled());
+
+    for _ in 0..(ASSIST_WINDOW - 1) {
+        assert_eq!(assist.record_request(4, 0), AssistChange::Keep);
+    }
+    assert_eq!(assist.record_request(4, 0), AssistChange::Recovered);
+    assert!(!assist.is_enabled());
+    assert_eq!(assist.get_accepted_count(), 1);
+}
@@ -0,0 +1,64 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::object::drop_target::*;
+use suzu::object::highlight::HIGHLIGHT_PULSE_PERIOD;
+
+fn sample_targets() -> Vec<DropTarget> {
+    vec![
+        DropTarget::new(
+            DropTargetKind::HandOver,
+            numeric::Rect::new(0.0, 0.0, 500.0, 200.0),
+        ),
+        DropTarget::new(
+            DropTargetKind::ShelvingBox,
+            numeric::Rect::new(500.0, 200.0, 200.0, 500.0),
+        ),
+    ]
+}
+
+#[test]
+fn layer_is_active_only_while_targets_are_set() {
+    let mut layer = DropTargetLayer::new(0);
+    assert!(!layer.is_active());
+    assert!(!layer.update(10));
+
+    layer.set_targets(sample_targets(), numeric::Point2f::new(100.0, 400.0));
+    assert!(layer.is_active());
+    assert!(layer.update(11));
+
+    layer.clear();
+    assert!(!layer.is_active());
+    assert_eq!(layer.get_hovered(), None);
+}
+
+#[test]
+fn hovered_target_follows_the_drag_point() {
+    let mut layer = DropTargetLayer::new(0);
+
+    layer.set_targets(sample_targets(), numeric::Point2f::new(100.0, 100.0));
+    assert_eq!(layer.get_hovered(), Some(DropTargetKind::HandOver));
+
+    layer.set_targets(sample_targets(), numeric::Point2f::new(600.0, 300.0));
+    assert_eq!(layer.get_hovered(), Some(DropTargetKind::ShelvingBox));
+
+    layer.set_targets(sample_targets(), numeric::Point2f::new(100.0, 400.0));
+    assert_eq!(layer.get_hovered(), None);
+    assert_eq!(
+        layer.target_at(numeric::Point2f::new(10.0, 10.0)),
+        Some(DropTargetKind::HandOver)
+    );
+}
+
+#[test]
+fn glow_pulses_and_hover_is_strongest() {
+    assert!((drop_target_alpha(0, false) - DROP_TARGET_ALPHA_MIN).abs() < 1e-6);
+    assert!(
+        (drop_target_alpha(HIGHLIGHT_PULSE_PERIOD / 2, false) - DROP_TARGET_ALPHA_MAX).abs() < 1e-6
+    );
+
+    for elapsed in 0..HIGHLIGHT_PULSE_PERIOD {
+        assert!(drop_target_alpha(elapsed, false) < drop_target_alpha(elapsed, true));
+    }
+}
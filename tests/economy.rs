@@ -0,0 +1,65 @@
+extern crate suzu;
+
+use suzu::core::economy::*;
+use suzu::core::{BookInformation, GensoDate, RentalLimit, ReturnBookInformation, TaskResult};
+
+fn book(name: &str, base_price: u32) -> BookInformation {
+    BookInformation::new(name.to_string(), 120, "中".to_string(), 1, base_price)
+}
+
+#[test]
+fn rental_fee_follows_rental_limit() {
+    let books = vec![book("竹取物語", 100), book("百鬼夜行絵巻", 200)];
+
+    assert_eq!(rental_fee(&books, &RentalLimit::ShortTerm), 300);
+    assert_eq!(rental_fee(&books, &RentalLimit::LongTerm), 450);
+    assert_eq!(rental_fee(&books, &RentalLimit::Today), 0);
+    assert_eq!(rental_fee(&[], &RentalLimit::LongTerm), 0);
+}
+
+#[test]
+fn late_fee_counts_overdue_days_per_book() {
+    let today = GensoDate::new(112, 8, 10);
+
+    assert_eq!(late_days(&GensoDate::new(112, 8, 10), &today), 0);
+    assert_eq!(late_days(&GensoDate::new(112, 8, 12), &today), 0);
+    assert_eq!(late_days(&GensoDate::new(112, 8, 7), &today), 3);
+    // 月をまたいでも数えられ、上限で止まる
+    assert_eq!(late_days(&GensoDate::new(112, 7, 30), &today), 11);
+    assert_eq!(
+        late_days(&GensoDate::new(112, 6, 1), &today),
+        LATE_RETURN_MAX_DAYS
+    );
+
+    let info = ReturnBookInformation::new(
+        vec![book("竹取物語", 100), book("百鬼夜行絵巻", 200)],
+        "霧雨魔理沙",
+        GensoDate::new(112, 8, 1),
+        GensoDate::new(112, 8, 8),
+    );
+    assert_eq!(
+        late_return_fee(&info, &today),
+        2 * 2 * LATE_RETURN_FEE_PER_BOOK_DAY
+    );
+}
+
+#[test]
+fn economy_tracks_income_alongside_money() {
+    let mut task_result = TaskResult::new();
+    let start_money = task_result.total_money;
+    let mut economy = Economy::new();
+    let begin = economy.clone();
+
+    economy.receive_rental_fee(&mut task_result, 300);
+    economy.receive_late_fee(&mut task_result, 40);
+    economy.refund_rental_fee(&mut task_result, 100);
+
+    assert_eq!(task_result.total_money, start_money + 240);
+    assert_eq!(
+        economy.diff_from(&begin),
+        Economy {
+            rental_fees: 200,
+            late_fees: 40,
+        }
+    );
+}
@@ -0,0 +1,39 @@
+extern crate suzu;
+
+use suzu::core::errand::*;
+
+#[test]
+fn destination_is_found_next_to_the_door() {
+    for destination in DeliveryDestination::ALL.iter() {
+        let (x, y) = destination.tile_position();
+        assert_eq!(DeliveryDestination::at(x, y), Some(*destination));
+        assert_eq!(DeliveryDestination::at(x, y + 1), Some(*destination));
+        assert_eq!(DeliveryDestination::at(x + 1, y + 1), None);
+    }
+
+    assert_eq!(DeliveryDestination::at(1, 7), None);
+}
+
+#[test]
+fn late_delivery_pays_less() {
+    let delivery = DeliveryRequest::new("竹取物語", DeliveryDestination::Terakoya, 600);
+    assert_eq!(delivery.deadline_minute(), 600 + DELIVERY_TIME_BUDGET);
+
+    let on_time = delivery.outcome(delivery.deadline_minute());
+    assert!(on_time.on_time);
+    assert!(on_time.reputation > 0.0);
+
+    let late = delivery.outcome(delivery.deadline_minute() + 1);
+    assert!(!late.on_time);
+    assert!(late.money < on_time.money);
+    assert!(late.reputation < 0.0);
+
+    assert!(delivery.abandoned_outcome().reputation < late.reputation);
+}
+
+#[test]
+fn auto_handled_fee_is_reduced() {
+    assert_eq!(auto_handled_fee(0), 0);
+    assert!(auto_handled_fee(500) < 500);
+    assert_eq!(auto_handled_fee(500), (500.0 * AUTO_HANDLE_FEE_RATE) as i32);
+}
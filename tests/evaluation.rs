@@ -0,0 +1,50 @@
+extern crate suzu;
+
+use suzu::core::evaluation::*;
+
+struct FlatRule(i32);
+
+impl EvaluationRule for FlatRule {
+    fn evaluate(&self, _: &RequestOutcome) -> i32 {
+        self.0
+    }
+}
+
+#[test]
+fn each_request_kind_uses_its_own_rule() {
+    let evaluator = Evaluator::new();
+
+    let borrowing = RequestOutcome::new(RequestKind::Borrowing, 0, 0, 60 * 60, 500);
+    let returning = RequestOutcome::new(RequestKind::Returning, 4, 1, 90 * 60, 0);
+    let copying = RequestOutcome::new(RequestKind::Copying, 0, 0, 180 * 60, 500);
+
+    assert_eq!(evaluator.evaluate(&borrowing), Some(10));
+    assert_eq!(evaluator.evaluate(&returning), Some(10 + 9 - 8));
+    assert_eq!(evaluator.evaluate(&copying), Some(10 + 5));
+}
+
+#[test]
+fn quick_handling_earns_bonus() {
+    let evaluator = Evaluator::new();
+
+    let slow = RequestOutcome::new(RequestKind::Borrowing, 0, 0, 120 * 60, 0);
+    let quick = RequestOutcome::new(RequestKind::Borrowing, 0, 0, 30 * 60, 0);
+
+    assert_eq!(evaluator.evaluate(&slow), Some(10));
+    assert_eq!(evaluator.evaluate(&quick), Some(40));
+}
+
+#[test]
+fn rules_can_be_registered_without_touching_evaluator() {
+    let mut evaluator = Evaluator::empty();
+    let outcome = RequestOutcome::new(RequestKind::Copying, 0, 0, 0, 0);
+
+    assert!(!evaluator.has_rule(RequestKind::Copying));
+    assert_eq!(evaluator.evaluate(&outcome), None);
+
+    evaluator.register(RequestKind::Copying, Box::new(FlatRule(7)));
+    assert_eq!(evaluator.evaluate(&outcome), Some(7));
+
+    evaluator.register(RequestKind::Copying, Box::new(FlatRule(3)));
+    assert_eq!(evaluator.evaluate(&outcome), Some(3));
+}
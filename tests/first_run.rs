@@ -0,0 +1,75 @@
+extern crate suzu;
+
+use ggez::input::keyboard::KeyCode;
+
+use suzu::core::first_run::*;
+use suzu::core::preferences::*;
+
+#[test]
+fn wizard_steps_forward_and_back() {
+    let mut wizard = FirstRunWizard::new(&Preferences::new());
+    assert_eq!(wizard.get_step(), FirstRunStep::Language);
+
+    assert!(!wizard.next());
+    assert_eq!(wizard.get_step(), FirstRunStep::Nickname);
+    assert!(!wizard.next());
+    assert_eq!(wizard.get_step(), FirstRunStep::Controls);
+
+    wizard.back();
+    assert_eq!(wizard.get_step(), FirstRunStep::Nickname);
+    wizard.back();
+    wizard.back();
+    assert_eq!(wizard.get_step(), FirstRunStep::Language);
+
+    wizard.next();
+    wizard.next();
+    assert!(wizard.next());
+}
+
+#[test]
+fn nickname_is_edited_only_on_its_step() {
+    let mut wizard = FirstRunWizard::new(&Preferences::new());
+    wizard.push_char('a');
+    assert_eq!(wizard.get_nickname(), "");
+
+    wizard.next();
+    for c in "阿求阿求阿求阿求阿求".chars() {
+        wizard.push_char(c);
+    }
+    wizard.push_char('\n');
+    assert_eq!(wizard.get_nickname().chars().count(), NICKNAME_MAX_CHARS);
+
+    wizard.pop_char();
+    assert_eq!(
+        wizard.get_nickname().chars().count(),
+        NICKNAME_MAX_CHARS - 1
+    );
+}
+
+#[test]
+fn apply_writes_choices_to_preferences() {
+    let mut preferences = Preferences::new();
+    assert_eq!(
+        preferences.interpolate_nickname("{nickname}ハ眠イ"),
+        format!("{}ハ眠イ", DEFAULT_NICKNAME)
+    );
+
+    let mut wizard = FirstRunWizard::new(&preferences);
+    wizard.next();
+    for c in " 阿求 ".chars() {
+        wizard.push_char(c);
+    }
+    wizard.set_control_scheme(ControlScheme::Keyboard);
+    wizard.apply(&mut preferences);
+
+    assert!(!preferences.is_first_run());
+    assert_eq!(preferences.get_nickname(), "阿求");
+    assert_eq!(
+        preferences.interpolate_nickname("{nickname}ハ眠イ"),
+        "阿求ハ眠イ"
+    );
+    assert!(preferences.remap_key(KeyCode::Z).is_some());
+
+    preferences.set_control_scheme(ControlScheme::Mouse);
+    assert!(preferences.remap_key(KeyCode::Z).is_none());
+}
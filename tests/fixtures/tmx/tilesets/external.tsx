@@ -0,0 +1,9 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.4" tiledversion="1.4.2" name="external" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+ <image source="../tiles.png" width="32" height="32"/>
+ <tile id="1">
+  <properties>
+   <property name="terrain" value="doorway"/>
+  </properties>
+ </tile>
+</tileset>
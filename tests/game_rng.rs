@@ -0,0 +1,45 @@
+extern crate suzu;
+
+use rand::Rng;
+
+use suzu::core::game_rng::*;
+use suzu::core::GensoDate;
+
+#[test]
+fn same_seed_gives_same_sequence() {
+    let mut a = GameRng::new(3759);
+    let mut b = GameRng::new(3759);
+
+    let xs: Vec<usize> = (0..32).map(|_| a.below(100)).collect();
+    let ys: Vec<usize> = (0..32).map(|_| b.below(100)).collect();
+    assert_eq!(xs, ys);
+    assert!(xs.iter().all(|x| *x < 100));
+
+    assert_eq!(a.below(0), 0);
+    assert_eq!(a.get_seed(), 3759);
+}
+
+#[test]
+fn reseed_restarts_the_sequence() {
+    let mut rng = GameRng::new(1);
+    let first: Vec<u32> = (0..8).map(|_| rng.gen::<u32>()).collect();
+
+    rng.reseed(2);
+    assert_eq!(rng.get_seed(), 2);
+    rng.reseed(1);
+    let again: Vec<u32> = (0..8).map(|_| rng.gen::<u32>()).collect();
+    assert_eq!(first, again);
+
+    assert!(!rng.chance(0.0));
+    assert!(rng.chance(1.0));
+}
+
+#[test]
+fn day_seed_depends_on_date() {
+    let today = GensoDate::new(112, 7, 23);
+    let tomorrow = GensoDate::new(112, 7, 24);
+
+    assert_eq!(day_seed(42, &today), day_seed(42, &today));
+    assert_ne!(day_seed(42, &today), day_seed(42, &tomorrow));
+    assert_ne!(day_seed(42, &today), day_seed(43, &today));
+}
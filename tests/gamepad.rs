@@ -0,0 +1,74 @@
+extern crate suzu;
+
+use ggez::input::gamepad::gilrs::Button;
+use torifune::device as tdev;
+use torifune::numeric;
+
+use suzu::core::gamepad::*;
+use suzu::core::preferences::Preferences;
+use suzu::core::shortcut_palette::PaletteKey;
+
+#[test]
+fn default_bindings_cover_confirm_and_menu_keys() {
+    let preferences = Preferences::new();
+
+    match preferences.remap_gamepad_button(Button::South) {
+        Some(GamepadAction::Key(tdev::VirtualKey::Action1)) => (),
+        _ => panic!("South should confirm"),
+    }
+    assert_eq!(
+        preferences
+            .remap_gamepad_button(Button::South)
+            .unwrap()
+            .palette_key(),
+        Some(PaletteKey::Enter)
+    );
+    assert_eq!(
+        preferences
+            .remap_gamepad_button(Button::DPadDown)
+            .unwrap()
+            .palette_key(),
+        Some(PaletteKey::Down)
+    );
+    assert!(preferences.remap_gamepad_button(Button::Mode).is_none());
+}
+
+#[test]
+fn gamepad_bindings_override_defaults() {
+    let mut preferences = Preferences::new();
+
+    assert!(preferences.bind_gamepad_button("South", "Action2"));
+    assert!(!preferences.bind_gamepad_button("NoSuchButton", "Action1"));
+    assert!(!preferences.bind_gamepad_button("East", "Jump"));
+
+    match preferences.remap_gamepad_button(Button::South) {
+        Some(GamepadAction::Key(tdev::VirtualKey::Action2)) => (),
+        _ => panic!("South should be rebound"),
+    }
+
+    let s = "[gamepad_bindings]\nNorth = \"Escape\"\n";
+    let loaded = Preferences::from_toml_str(s).unwrap();
+    assert_eq!(
+        loaded
+            .remap_gamepad_button(Button::North)
+            .unwrap()
+            .palette_key(),
+        Some(PaletteKey::Escape)
+    );
+    assert!(loaded.remap_gamepad_button(Button::East).is_some());
+}
+
+#[test]
+fn stick_direction_ignores_dead_zone() {
+    let still = stick_direction(numeric::Vector2f::new(0.1, -0.1));
+    assert_eq!((still.x, still.y), (0.0, 0.0));
+
+    // 上に倒すと画面の上(yが負)へ向く
+    let up = stick_direction(numeric::Vector2f::new(0.0, 1.0));
+    assert!((up.x - 0.0).abs() < 1e-6);
+    assert!((up.y + 1.0).abs() < 1e-6);
+
+    let over = stick_direction(numeric::Vector2f::new(1.0, 1.0));
+    let length = (over.x.powf(2.0) + over.y.powf(2.0)).sqrt();
+    assert!((length - 1.0).abs() < 1e-6);
+}
@@ -0,0 +1,66 @@
+extern crate suzu;
+
+use suzu::core::GensoDate;
+
+#[test]
+fn add_days_follows_month_lengths() {
+    assert_eq!(
+        GensoDate::new(112, 5, 30).add_days_chain(1),
+        GensoDate::new(112, 5, 31)
+    );
+    assert_eq!(
+        GensoDate::new(112, 5, 31).add_days_chain(1),
+        GensoDate::new(112, 6, 1)
+    );
+    assert_eq!(
+        GensoDate::new(112, 2, 28).add_days_chain(1),
+        GensoDate::new(112, 3, 1)
+    );
+    assert_eq!(
+        GensoDate::new(112, 12, 31).add_days_chain(1),
+        GensoDate::new(113, 1, 1)
+    );
+    assert_eq!(
+        GensoDate::new(112, 7, 23).add_days_chain(365),
+        GensoDate::new(113, 7, 23)
+    );
+
+    assert_eq!(GensoDate::days_in_month(5), 31);
+    assert_eq!(GensoDate::days_in_month(13), 0);
+    assert!(!GensoDate::new(112, 6, 31).is_valid());
+}
+
+#[test]
+fn negative_days_go_back() {
+    assert_eq!(
+        GensoDate::new(112, 8, 1).add_days_chain(-1),
+        GensoDate::new(112, 7, 31)
+    );
+    assert_eq!(
+        GensoDate::new(113, 1, 1).sub_days_chain(1),
+        GensoDate::new(112, 12, 31)
+    );
+    assert_eq!(
+        GensoDate::new(112, 3, 1).sub_days_chain(1),
+        GensoDate::new(112, 2, 28)
+    );
+
+    let date = GensoDate::new(112, 7, 23);
+    assert_eq!(date.add_days_chain(100).sub_days_chain(100), date);
+}
+
+#[test]
+fn diff_and_order_cross_seasons() {
+    let summer = GensoDate::new(112, 7, 23);
+    let next_spring = GensoDate::new(113, 3, 1);
+
+    assert_eq!(summer.diff_days(&next_spring), 221);
+    assert_eq!(next_spring.diff_days(&summer), -221);
+    assert_eq!(summer.add_days_chain(221), next_spring);
+
+    assert!(summer < next_spring);
+    assert!(GensoDate::new(112, 12, 31) < GensoDate::new(113, 1, 1));
+    assert!(next_spring.is_past(&summer));
+    assert!(!summer.is_past(&summer));
+    assert_eq!(vec![next_spring, summer].iter().max(), Some(&next_spring));
+}
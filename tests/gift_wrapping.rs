@@ -0,0 +1,92 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::core::gesture::*;
+use suzu::core::gift_wrapping::*;
+
+fn circle_points(radius: f32, steps: usize) -> Vec<numeric::Point2f> {
+    (0..=steps)
+        .map(|i| {
+            let angle = (i as f32 / steps as f32) * 2.0 * std::f32::consts::PI;
+            numeric::Point2f::new(100.0 + radius * angle.cos(), 100.0 + radius * angle.sin())
+        })
+        .collect()
+}
+
+#[test]
+fn classify_strokes_and_loops() {
+    let right = vec![
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Point2f::new(40.0, 5.0),
+        numeric::Point2f::new(80.0, 8.0),
+    ];
+    assert_eq!(classify(&right), Gesture::Stroke(StrokeDirection::Right));
+
+    let up = vec![
+        numeric::Point2f::new(0.0, 100.0),
+        numeric::Point2f::new(3.0, 0.0),
+    ];
+    assert_eq!(classify(&up), Gesture::Stroke(StrokeDirection::Up));
+
+    match classify(&circle_points(40.0, 32)) {
+        Gesture::Loop(circularity) => assert!(circularity > 0.9),
+        other => panic!("expected a loop, got {:?}", other),
+    }
+
+    let jitter = vec![
+        numeric::Point2f::new(0.0, 0.0),
+        numeric::Point2f::new(2.0, 1.0),
+    ];
+    assert_eq!(classify(&jitter), Gesture::Unknown);
+}
+
+#[test]
+fn recognizer_only_records_between_begin_and_finish() {
+    let mut recognizer = GestureRecognizer::new();
+    recognizer.push(numeric::Point2f::new(500.0, 500.0));
+    assert!(!recognizer.is_recording());
+
+    recognizer.begin(numeric::Point2f::new(0.0, 0.0));
+    recognizer.push(numeric::Point2f::new(0.0, 60.0));
+    assert_eq!(recognizer.finish(), Gesture::Stroke(StrokeDirection::Down));
+    assert!(!recognizer.is_recording());
+}
+
+#[test]
+fn corners_must_be_folded_in_order_before_tying() {
+    let mut session = WrappingSession::new();
+
+    assert!(!session.tie(Gesture::Loop(1.0)));
+    assert!(!session.fold(FOLD_ORDER[1], 1.0));
+
+    for corner in FOLD_ORDER.iter() {
+        assert!(session.fold(*corner, 1.0));
+    }
+    assert!(session.all_folded());
+
+    assert!(!session.tie(Gesture::Stroke(StrokeDirection::Left)));
+    assert!(session.tie(Gesture::Loop(1.0)));
+    assert!(session.is_done());
+
+    // 順番を一度間違えたので10点引かれる
+    assert_eq!(session.quality(), 90);
+    assert_eq!(wrapping_tip(session.quality()), 200);
+}
+
+#[test]
+fn supplies_are_consumed_and_restocked() {
+    let mut supplies = WrappingSupplies::new();
+    assert_eq!(supplies.restock_if_low(), 0);
+
+    while supplies.consume() {}
+    assert!(!supplies.can_wrap());
+    assert_eq!(supplies.paper, 0);
+
+    let cost = supplies.restock_if_low();
+    assert_eq!(
+        cost,
+        WRAPPING_SUPPLIES_CAPACITY as i32 * (WRAPPING_PAPER_PRICE + WRAPPING_STRING_PRICE)
+    );
+    assert!(supplies.can_wrap());
+}
@@ -0,0 +1,91 @@
+extern crate suzu;
+
+use ggez::graphics as ggraphics;
+
+use suzu::object::highlight::*;
+
+#[test]
+fn pulse_starts_dark_and_peaks_mid_period() {
+    assert!(pulse_ratio(0) < 0.001);
+    assert!((pulse_ratio(HIGHLIGHT_PULSE_PERIOD / 2) - 1.0).abs() < 0.001);
+    assert!((pulse_ratio(HIGHLIGHT_PULSE_PERIOD) - pulse_ratio(0)).abs() < 0.001);
+
+    for t in 0..(HIGHLIGHT_PULSE_PERIOD * 2) {
+        let ratio = pulse_ratio(t);
+        assert!(ratio >= 0.0 && ratio <= 1.0);
+    }
+}
+
+#[test]
+fn tint_keeps_alpha_and_follows_the_pulse() {
+    let base = ggraphics::Color::new(1.0, 1.0, 1.0, 0.5);
+    let red = ggraphics::Color::new(1.0, 0.0, 0.0, 1.0);
+
+    let mut highlight = Highlight::pulse(red, 100);
+    assert_eq!(highlight.tint(base), base);
+
+    assert!(highlight.update(100 + HIGHLIGHT_PULSE_PERIOD / 2));
+    let tinted = highlight.tint(base);
+    assert_eq!(tinted.a, 0.5);
+    assert_eq!(tinted.r, 1.0);
+    assert!((tinted.g - (1.0 - HIGHLIGHT_PULSE_STRENGTH)).abs() < 0.001);
+
+    assert_eq!(
+        mix_color(base, red, 2.0),
+        ggraphics::Color::new(1.0, 0.0, 0.0, 0.5)
+    );
+}
+
+#[test]
+fn outline_surrounds_at_the_same_distance() {
+    let mut outline = Highlight::outline(ggraphics::Color::new(0.0, 0.0, 1.0, 1.0), 0);
+    assert!(!outline.update(30));
+    assert_eq!(outline.get_style(), HighlightStyle::Outline);
+
+    let offsets = outline_offsets(HIGHLIGHT_OUTLINE_WIDTH);
+    for offset in offsets.iter() {
+        let length = (offset.x * offset.x + offset.y * offset.y).sqrt();
+        assert!((length - HIGHLIGHT_OUTLINE_WIDTH).abs() < 0.001);
+    }
+
+    let sum = offsets
+        .iter()
+        .fold((0.0, 0.0), |(x, y), offset| (x + offset.x, y + offset.y));
+    assert!(sum.0.abs() < 0.001 && sum.1.abs() < 0.001);
+}
+
+#[test]
+fn pulse_outline_fades_but_keeps_its_hue() {
+    let blue = ggraphics::Color::new(0.0, 0.0, 1.0, 1.0);
+    let mut highlight = Highlight::pulse_outline(blue, 0);
+    assert_eq!(highlight.get_style(), HighlightStyle::PulseOutline);
+
+    let faint = highlight.outline_color();
+    assert!((faint.a - HIGHLIGHT_PULSE_OUTLINE_MIN_ALPHA).abs() < 0.001);
+    assert_eq!(faint.b, 1.0);
+
+    assert!(highlight.update(HIGHLIGHT_PULSE_PERIOD / 2));
+    assert!((highlight.outline_color().a - 1.0).abs() < 0.001);
+
+    let outline = Highlight::outline(blue, 0);
+    assert_eq!(outline.outline_color(), blue);
+}
+
+#[test]
+fn inspect_mode_only_marks_objects_with_data() {
+    let mut inspect = InspectMode::new();
+    assert!(!inspect.is_active());
+    assert_eq!(inspect.highlight_for(true), None);
+
+    assert!(inspect.set_active(true, 10));
+    assert!(!inspect.set_active(true, 11));
+    assert!(inspect.update(12));
+
+    let highlight = inspect.highlight_for(true).unwrap();
+    assert_eq!(highlight.get_style(), HighlightStyle::PulseOutline);
+    assert_eq!(inspect.highlight_for(false), None);
+
+    assert!(inspect.set_active(false, 20));
+    assert!(!inspect.update(21));
+    assert_eq!(inspect.highlight_for(true), None);
+}
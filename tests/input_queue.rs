@@ -0,0 +1,92 @@
+extern crate suzu;
+
+use ggez::input::keyboard::{KeyCode, KeyMods};
+use ggez::input::mouse::MouseButton;
+use torifune::numeric;
+
+use suzu::core::input_queue::*;
+
+fn press(history: &mut InputHistory, x: f32, y: f32, clock: u64) {
+    history.record(&TimedInputEvent {
+        event: InputEvent::MouseButtonDown(MouseButton::Left, numeric::Point2f::new(x, y)),
+        clock: clock,
+    });
+}
+
+#[test]
+fn queue_keeps_arrival_order() {
+    let mut queue = InputQueue::new();
+
+    queue.push(
+        InputEvent::MouseButtonDown(MouseButton::Left, numeric::Point2f::new(0.0, 0.0)),
+        10,
+    );
+    queue.push(
+        InputEvent::MouseButtonUp(MouseButton::Left, numeric::Point2f::new(0.0, 0.0)),
+        10,
+    );
+    queue.push(InputEvent::KeyDown(KeyCode::Z, KeyMods::empty(), false), 11);
+    assert_eq!(queue.len(), 3);
+
+    match queue.pop().unwrap().event {
+        InputEvent::MouseButtonDown(_, _) => (),
+        _ => panic!("unexpected order"),
+    }
+    match queue.pop().unwrap().event {
+        InputEvent::MouseButtonUp(_, _) => (),
+        _ => panic!("unexpected order"),
+    }
+    assert_eq!(queue.pop().unwrap().clock, 11);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn pressed_within_ticks() {
+    let mut history = InputHistory::new();
+    press(&mut history, 0.0, 0.0, 100);
+    history.update_now(110);
+
+    assert!(history.pressed_within(MouseButton::Left, 10));
+    assert!(!history.pressed_within(MouseButton::Left, 9));
+    assert!(!history.pressed_within(MouseButton::Right, 100));
+
+    history.record(&TimedInputEvent {
+        event: InputEvent::KeyDown(KeyCode::Z, KeyMods::empty(), true),
+        clock: 110,
+    });
+    assert!(!history.key_pressed_within(KeyCode::Z, 10));
+}
+
+#[test]
+fn double_click_detection() {
+    let mut history = InputHistory::new();
+    press(&mut history, 100.0, 100.0, 0);
+    press(&mut history, 102.0, 101.0, 12);
+    history.update_now(12);
+    assert!(history.is_double_click(MouseButton::Left, 20, 5.0));
+    assert!(!history.is_double_click(MouseButton::Left, 10, 5.0));
+
+    // 離れた位置でのクリックはダブルクリックではない
+    press(&mut history, 300.0, 100.0, 20);
+    history.update_now(20);
+    assert!(!history.is_double_click(MouseButton::Left, 20, 5.0));
+}
+
+#[test]
+fn drag_threshold_survives_history_pruning() {
+    let mut history = InputHistory::new();
+    press(&mut history, 50.0, 50.0, 0);
+    history.update_now(10000);
+
+    assert!(!history.pressed_within(MouseButton::Left, 100));
+    assert!(!history.exceeds_drag_threshold(
+        MouseButton::Left,
+        numeric::Point2f::new(52.0, 50.0),
+        4.0
+    ));
+    assert!(history.exceeds_drag_threshold(
+        MouseButton::Left,
+        numeric::Point2f::new(60.0, 50.0),
+        4.0
+    ));
+}
@@ -0,0 +1,59 @@
+extern crate suzu;
+
+use suzu::core::instrument::*;
+use suzu::instrument;
+
+#[test]
+fn top_offender_is_the_slowest_category() {
+    let mut counters = FrameCounters::new();
+    assert_eq!(counters.top_offender(), None);
+
+    counters.add(ProbeCategory::Collision, 300);
+    counters.add(ProbeCategory::Pathfinding, 1200);
+    counters.add(ProbeCategory::Collision, 400);
+
+    assert_eq!(counters.get(ProbeCategory::Collision), 700);
+    assert_eq!(counters.total(), 1900);
+    assert_eq!(
+        counters.top_offender(),
+        Some((ProbeCategory::Pathfinding, 1200))
+    );
+}
+
+#[test]
+fn macro_accumulates_into_current_frame() {
+    take_frame_counters();
+
+    let value = instrument!(ProbeCategory::TextLayout, {
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        42
+    });
+    assert_eq!(value, 42);
+
+    let counters = take_frame_counters();
+    assert!(counters.get(ProbeCategory::TextLayout) >= 2000);
+    assert_eq!(counters.get(ProbeCategory::DrawSubmit), 0);
+
+    assert_eq!(take_frame_counters().total(), 0);
+}
+
+#[test]
+fn budget_warnings_are_throttled() {
+    let mut counters = FrameCounters::new();
+    counters.add(ProbeCategory::DrawSubmit, 15000);
+
+    let mut monitor = FrameBudgetMonitor::new(16666);
+    assert_eq!(monitor.end_frame(&counters, 16000, 0), None);
+
+    let warning = monitor.end_frame(&counters, 20000, 10).unwrap();
+    assert_eq!(warning.offender, Some((ProbeCategory::DrawSubmit, 15000)));
+    assert!(warning.to_message().contains("描画"));
+
+    assert_eq!(monitor.end_frame(&counters, 20000, 11), None);
+    assert!(monitor
+        .end_frame(&counters, 20000, 10 + BUDGET_WARNING_COOLDOWN)
+        .is_some());
+
+    monitor.set_budget_micros(0);
+    assert_eq!(monitor.end_frame(&counters, 99999, 1000), None);
+}
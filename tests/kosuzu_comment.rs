@@ -0,0 +1,69 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::kosuzu_comment::*;
+
+#[test]
+fn comment_is_picked_from_the_trigger_pool() {
+    let mut rng = StdRng::seed_from_u64(7);
+
+    for _ in 0..50 {
+        let text = pick_comment(
+            &STANDARD_COMMENT_LINES,
+            CommentTrigger::LongBookTitle,
+            None,
+            &mut rng,
+        )
+        .unwrap();
+
+        assert!(STANDARD_COMMENT_LINES
+            .iter()
+            .any(|line| line.trigger == CommentTrigger::LongBookTitle && line.text == text));
+    }
+}
+
+#[test]
+fn same_comment_is_not_repeated_when_others_exist() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let lines = vec![
+        CommentLine {
+            trigger: CommentTrigger::RainyDay,
+            text: "a",
+            weight: 100,
+        },
+        CommentLine {
+            trigger: CommentTrigger::RainyDay,
+            text: "b",
+            weight: 1,
+        },
+    ];
+
+    for _ in 0..20 {
+        assert_eq!(
+            pick_comment(&lines, CommentTrigger::RainyDay, Some("a"), &mut rng),
+            Some("b")
+        );
+    }
+    assert_eq!(
+        pick_comment(&lines, CommentTrigger::HourChimed, None, &mut rng),
+        None
+    );
+}
+
+#[test]
+fn commentary_is_rate_limited() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut commentary = KosuzuCommentary::new(STANDARD_COMMENT_LINES.to_vec(), 100);
+
+    assert!(commentary
+        .react(CommentTrigger::CustomerCalling, 10, &mut rng)
+        .is_some());
+    assert!(commentary
+        .react(CommentTrigger::CustomerCalling, 109, &mut rng)
+        .is_none());
+    assert!(commentary
+        .react(CommentTrigger::CustomerCalling, 110, &mut rng)
+        .is_some());
+}
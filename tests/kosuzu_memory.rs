@@ -0,0 +1,40 @@
+extern crate suzu;
+
+use suzu::object::task_object::tt_menu_component::KosuzuMemory;
+
+#[test]
+fn customer_names_are_remembered_once() {
+    let mut memory = KosuzuMemory::new();
+
+    memory.add_customer_name("霧雨魔理沙".to_string());
+    memory.add_customer_name("稗田阿求".to_string());
+    memory.add_customer_name("霧雨魔理沙".to_string());
+
+    assert_eq!(
+        memory.get_customers_name(),
+        &vec!["霧雨魔理沙".to_string(), "稗田阿求".to_string()]
+    );
+}
+
+#[test]
+fn used_customer_name_is_removed() {
+    let mut memory = KosuzuMemory::new();
+    memory.add_customer_name("霧雨魔理沙".to_string());
+    memory.add_customer_name("稗田阿求".to_string());
+
+    assert_eq!(
+        memory.get_customer_name_remove(0),
+        Some("霧雨魔理沙".to_string())
+    );
+    assert_eq!(memory.get_customers_name(), &vec!["稗田阿求".to_string()]);
+}
+
+#[test]
+fn removing_unknown_index_keeps_names() {
+    let mut memory = KosuzuMemory::new();
+    assert_eq!(memory.get_customer_name_remove(0), None);
+
+    memory.add_customer_name("博麗霊夢".to_string());
+    assert_eq!(memory.get_customer_name_remove(3), None);
+    assert_eq!(memory.get_customers_name().len(), 1);
+}
@@ -0,0 +1,54 @@
+extern crate suzu;
+
+use suzu::core::*;
+
+#[test]
+fn late_night_extends_hours_and_pay() {
+    let mut status = SuzunaAnStatus::new();
+    assert_eq!(status.closing_hour(), REGULAR_CLOSING_HOUR);
+    assert_eq!(status.apply_late_night_pay(200), 200);
+
+    status.start_late_night();
+    assert_eq!(status.closing_hour(), LATE_NIGHT_CLOSING_HOUR);
+    assert_eq!(status.apply_late_night_pay(200), 300);
+
+    status.pass_night();
+    assert!(!status.is_late_night());
+}
+
+#[test]
+fn fatigue_slows_and_recovers_overnight() {
+    let mut status = SuzunaAnStatus::new();
+    assert_eq!(status.fatigue_speed_rate(), 1.0);
+    assert_eq!(status.fatigue_penalty_rate(), 1.0);
+
+    status.add_fatigue(FATIGUE_MAX * 2.0);
+    assert_eq!(status.get_fatigue(), FATIGUE_MAX);
+    assert!(status.fatigue_speed_rate() < 1.0);
+    assert!(status.fatigue_penalty_rate() > 1.0);
+
+    status.pass_night();
+    assert!(status.get_fatigue() < FATIGUE_MAX);
+    assert!(status.get_fatigue() > 0.0);
+}
+
+#[test]
+fn running_and_carrying_drain_stamina() {
+    let mut status = SuzunaAnStatus::new();
+
+    status.drain_stamina_by_moving(2.0, CARRY_FREE_BOOKS);
+    assert_eq!(status.get_stamina(), FATIGUE_MAX);
+
+    status.drain_stamina_by_moving(6.0, CARRY_FREE_BOOKS + 10);
+    assert!(status.get_stamina() < FATIGUE_MAX);
+
+    let wobble = status.drag_wobble(10);
+    assert_eq!((wobble.x, wobble.y), (0.0, 0.0));
+
+    status.add_fatigue(FATIGUE_MAX);
+    assert!(status.is_stamina_low());
+    assert!(status.fatigue_speed_rate() < 1.0);
+
+    status.restore_stamina(FATIGUE_MAX);
+    assert_eq!(status.get_stamina(), FATIGUE_MAX);
+}
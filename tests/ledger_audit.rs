@@ -0,0 +1,82 @@
+extern crate suzu;
+
+use suzu::core::ledger_audit::*;
+use suzu::core::GensoDate;
+
+fn entry(customer: &str, rental_day: u8, return_day: u8, returned: bool) -> AuditEntry {
+    AuditEntry::new(
+        customer.to_string(),
+        GensoDate::new(112, 8, rental_day),
+        GensoDate::new(112, 8, return_day),
+        3,
+        if returned { 3 } else { 0 },
+        returned,
+    )
+}
+
+fn sample_entries() -> Vec<AuditEntry> {
+    vec![
+        entry("霧雨魔理沙", 1, 8, true),
+        entry("博麗霊夢", 2, 2, true),
+        entry("十六夜咲夜", 3, 10, false),
+        entry("魂魄妖夢", 4, 11, false),
+    ]
+}
+
+#[test]
+fn injected_flaws_are_detectable() {
+    let mut swapped = entry("博麗霊夢", 2, 9, false);
+    swapped.inject_flaw(AuditFlaw::DateOutOfOrder);
+    assert_eq!(swapped.find_flaw(), Some(AuditFlaw::DateOutOfOrder));
+    assert_eq!(swapped.return_date, GensoDate::new(112, 8, 2));
+
+    // 当日返却の頁でも、貸出日が後ろにずれる
+    let mut same_day = entry("博麗霊夢", 2, 2, true);
+    same_day.inject_flaw(AuditFlaw::DateOutOfOrder);
+    assert_eq!(same_day.find_flaw(), Some(AuditFlaw::DateOutOfOrder));
+
+    let mut lending = entry("十六夜咲夜", 3, 10, false);
+    assert_eq!(lending.find_flaw(), None);
+    lending.inject_flaw(AuditFlaw::MissingStatus);
+    assert_eq!(lending.find_flaw(), Some(AuditFlaw::MissingStatus));
+    assert_eq!(lending.statuses, 2);
+}
+
+#[test]
+fn marking_counts_hits_and_wrong_marks() {
+    let mut audit = LedgerAudit::new(
+        sample_entries(),
+        &[(0, AuditFlaw::DateOutOfOrder), (3, AuditFlaw::MissingStatus)],
+    );
+    assert_eq!(audit.flaw_count(), 2);
+
+    assert_eq!(audit.mark(1), Some(false));
+    assert_eq!(audit.mark(1), None);
+    assert_eq!(audit.mark(9), None);
+    assert_eq!(audit.mark(3), Some(true));
+    assert!(!audit.is_complete());
+
+    let result = audit.finish();
+    assert_eq!(result.found, 1);
+    assert_eq!(result.missed, 1);
+    assert_eq!(result.wrong_marks, 1);
+    assert_eq!(
+        result.recovered_money(),
+        AUDIT_RECOVERY_PER_FLAW - AUDIT_WRONG_MARK_PENALTY
+    );
+
+    assert_eq!(audit.mark(0), Some(true));
+    assert!(audit.is_complete());
+}
+
+#[test]
+fn recovered_money_never_goes_negative() {
+    let mut audit = LedgerAudit::new(sample_entries(), &[]);
+    assert_eq!(audit.flaw_count(), 0);
+
+    for row in 0..4 {
+        assert_eq!(audit.mark(row), Some(false));
+    }
+
+    assert_eq!(audit.finish().recovered_money(), 0);
+}
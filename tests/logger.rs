@@ -0,0 +1,76 @@
+extern crate suzu;
+
+use suzu::core::logger::*;
+
+fn record(message: &str) -> LogRecord {
+    LogRecord {
+        level: LogLevel::Info,
+        target: "suzu::core".to_string(),
+        timestamp: "12:00:00.000".to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn log_filter_uses_longest_matching_module() {
+    let filter = LogFilter::parse("warn,suzu::scene=debug,scene::shop_scene=off").unwrap();
+
+    assert!(filter.enabled(LogLevel::Warn, "suzu::core"));
+    assert!(!filter.enabled(LogLevel::Info, "suzu::core"));
+    assert!(filter.enabled(LogLevel::Debug, "suzu::scene::title_scene"));
+    assert!(!filter.enabled(LogLevel::Trace, "suzu::scene::title_scene"));
+    assert!(!filter.enabled(LogLevel::Error, "suzu::scene::shop_scene"));
+
+    // 道筋の途中で切れているものには当てはめない
+    assert!(!filter.enabled(LogLevel::Debug, "suzu::scenery"));
+
+    assert_eq!(
+        filter.to_spec(),
+        "warn,suzu::scene=debug,scene::shop_scene=off"
+    );
+    assert_eq!(LogFilter::parse(&filter.to_spec()).unwrap(), filter);
+}
+
+#[test]
+fn log_command_parses_console_lines() {
+    assert_eq!(LogCommand::parse("log"), Some(Ok(LogCommand::ShowFilter)));
+    assert_eq!(
+        LogCommand::parse("log view"),
+        Some(Ok(LogCommand::ToggleViewer))
+    );
+    assert_eq!(
+        LogCommand::parse("log debug,suzu::core=info"),
+        Some(Ok(LogCommand::SetFilter(
+            LogFilter::parse("debug,suzu::core=info").unwrap()
+        )))
+    );
+
+    assert!(LogCommand::parse("log loud").unwrap().is_err());
+    assert!(LogCommand::parse("log =debug").unwrap().is_err());
+    assert_eq!(LogCommand::parse("logs"), None);
+    assert_eq!(LogCommand::parse("speed 2x"), None);
+}
+
+#[test]
+fn recent_log_keeps_newest_records() {
+    let mut recent = RecentLog::new(3);
+    for index in 0..5 {
+        recent.push(record(&index.to_string()));
+    }
+
+    assert_eq!(recent.len(), 3);
+    assert_eq!(recent.get_total(), 5);
+
+    let messages: Vec<String> = recent
+        .tail(2)
+        .into_iter()
+        .map(|record| record.message)
+        .collect();
+    assert_eq!(messages, vec!["3".to_string(), "4".to_string()]);
+    assert_eq!(recent.tail(10).len(), 3);
+
+    assert_eq!(
+        record("loaded").to_line(),
+        "12:00:00.000 INFO  suzu::core: loaded"
+    );
+}
@@ -0,0 +1,105 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::mail::*;
+use suzu::core::{BookInformation, GensoDate};
+
+fn senders() -> Vec<String> {
+    vec!["霧雨魔理沙".to_string(), "十六夜咲夜".to_string()]
+}
+
+fn books() -> Vec<BookInformation> {
+    vec![BookInformation::new(
+        "雨月物語".to_string(),
+        120,
+        "中判本".to_string(),
+        1,
+        100,
+    )]
+}
+
+#[test]
+fn unread_letters_stay_until_opened() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut mail_box = MailBox::new();
+    let mut date = GensoDate::new(112, 8, 1);
+
+    let mut letters = Vec::new();
+    while letters.is_empty() {
+        date.add_days(1);
+        letters = mail_box.deliver_morning(&mut rng, &date, &senders(), &books());
+    }
+
+    // 同じ朝に二度受け取っても増えない
+    assert_eq!(
+        mail_box.deliver_morning(&mut rng, &date, &senders(), &books()),
+        letters
+    );
+
+    let first = letters[0].clone();
+    assert_eq!(mail_box.open(first.id, &date), Some(first.clone()));
+    assert_eq!(mail_box.open(first.id, &date), None);
+    assert_eq!(mail_box.unread_count(), letters.len() - 1);
+
+    date.add_days(1);
+    let next = mail_box.deliver_morning(&mut rng, &date, &senders(), &books());
+    assert!(letters[1..].iter().all(|letter| next.contains(letter)));
+}
+
+#[test]
+fn posted_copy_requests_are_finished_after_a_few_days() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let mut mail_box = MailBox::new();
+    let mut date = GensoDate::new(112, 8, 1);
+
+    let request = loop {
+        date.add_days(1);
+        let letters = mail_box.deliver_morning(&mut rng, &date, &senders(), &books());
+        if let Some(letter) = letters.iter().find(|letter| match letter.content {
+            LetterContent::CopyRequest { .. } => true,
+            _ => false,
+        }) {
+            break letter.clone();
+        }
+    };
+
+    mail_box.open(request.id, &date);
+    assert_eq!(mail_box.get_copy_requests().len(), 1);
+    assert_eq!(
+        mail_box.get_copy_requests()[0].fee(),
+        POSTAL_COPY_BASE_FEE + 120 * POSTAL_COPY_FEE_PER_PAGE
+    );
+
+    assert!(mail_box
+        .take_finished_copies(&date.add_days_chain(POSTAL_COPY_DAYS - 1))
+        .is_empty());
+    let finished = mail_box.take_finished_copies(&date.add_days_chain(POSTAL_COPY_DAYS));
+    assert_eq!(finished.len(), 1);
+    assert_eq!(finished[0].title, "雨月物語");
+    assert!(mail_box.get_copy_requests().is_empty());
+}
+
+#[test]
+fn story_letters_set_flags_once() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut mail_box = MailBox::new();
+    let mut date = GensoDate::new(112, 8, 1);
+    let mut story_letters = 0;
+
+    for _ in 0..300 {
+        date.add_days(1);
+        for letter in mail_box.deliver_morning(&mut rng, &date, &senders(), &books()) {
+            if let LetterContent::StoryHook { .. } = letter.content {
+                story_letters += 1;
+            }
+            mail_box.open(letter.id, &date);
+        }
+    }
+
+    assert_eq!(story_letters, STORY_LETTERS.len());
+    assert!(STORY_LETTERS
+        .iter()
+        .all(|(flag, _, _)| mail_box.has_flag(flag)));
+}
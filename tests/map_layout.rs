@@ -0,0 +1,92 @@
+extern crate suzu;
+
+use suzu::core::map_parser::*;
+use torifune::numeric;
+
+fn object(kind: &str, name: &str, x: f32, y: f32, order: i32) -> LayoutObject {
+    LayoutObject {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        x: x,
+        y: y,
+        order: order,
+    }
+}
+
+fn parse(objects: &[LayoutObject]) -> MapLayout {
+    MapLayout::from_objects(
+        objects,
+        numeric::Vector2u::new(32, 32),
+        numeric::Vector2f::new(3.0, 3.0),
+    )
+}
+
+#[test]
+fn objects_override_the_shop_layout() {
+    let layout = parse(&[
+        object("player_spawn", "", 100.0, 200.0, 0),
+        object("counter", "", 40.0, 70.0, 0),
+        object("exit", "", 330.0, 10.0, 0),
+        object("decoration", "lantern", 65.0, 96.0, 0),
+    ]);
+
+    assert_eq!(layout.player_spawn, numeric::Point2f::new(300.0, 600.0));
+    assert_eq!(layout.counter_tile, numeric::Vector2u::new(1, 2));
+    assert_eq!(layout.exit_tile, numeric::Vector2u::new(10, 0));
+    assert_eq!(
+        layout.get_decoration_anchor("lantern"),
+        Some(numeric::Vector2u::new(2, 3))
+    );
+
+    // 目印の無いものは鈴奈庵の配置のまま
+    let shop = MapLayout::shop_default();
+    assert_eq!(layout.customer_entrances, shop.customer_entrances);
+    assert_eq!(layout.browse_spots, shop.browse_spots);
+    assert_eq!(
+        layout.get_decoration_anchor("umbrella_stand"),
+        shop.get_decoration_anchor("umbrella_stand")
+    );
+    assert_eq!(parse(&[]), shop);
+}
+
+#[test]
+fn listed_objects_follow_their_order() {
+    let layout = parse(&[
+        object("browse_spot", "", 64.0, 0.0, 1),
+        object("browse_spot", "", 32.0, 0.0, 0),
+        object("customer_entrance", "", 10.0, 10.0, 0),
+        object("customer_entrance", "", 20.0, 20.0, 0),
+    ]);
+
+    assert_eq!(
+        layout.browse_spots,
+        vec![numeric::Vector2u::new(1, 0), numeric::Vector2u::new(2, 0)]
+    );
+    assert_eq!(
+        layout.customer_entrance(0),
+        numeric::Point2f::new(30.0, 30.0)
+    );
+    assert_eq!(
+        layout.customer_entrance(1),
+        numeric::Point2f::new(60.0, 60.0)
+    );
+    assert_eq!(
+        layout.customer_entrance(2),
+        numeric::Point2f::new(30.0, 30.0)
+    );
+}
+
+#[test]
+fn queue_extends_past_the_last_slot() {
+    let layout = parse(&[
+        object("queue_slot", "", 160.0, 448.0, 0),
+        object("queue_slot", "", 160.0, 416.0, 1),
+    ]);
+
+    assert_eq!(layout.queue_slot(0), numeric::Vector2u::new(5, 14));
+    assert_eq!(layout.queue_slot(1), numeric::Vector2u::new(5, 13));
+    assert_eq!(layout.queue_slot(3), numeric::Vector2u::new(5, 11));
+
+    let single = parse(&[object("queue_slot", "", 160.0, 448.0, 0)]);
+    assert_eq!(single.queue_slot(2), numeric::Vector2u::new(7, 14));
+}
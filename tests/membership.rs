@@ -0,0 +1,68 @@
+extern crate suzu;
+
+use suzu::core::membership::*;
+use suzu::core::GensoDate;
+
+fn today() -> GensoDate {
+    GensoDate::new(112, 7, 23)
+}
+
+#[test]
+fn expiry_is_checked_before_lending_limit() {
+    let card = MembershipCard::new("霧雨 魔理沙", 3, 2, GensoDate::new(112, 7, 30));
+
+    assert_eq!(card.check(1, &today()), CardCheck::Valid);
+    assert_eq!(card.check(2, &today()), CardCheck::OverLimit);
+
+    // 期限の当日まではまだ使える
+    assert_eq!(card.check(1, &GensoDate::new(112, 7, 30)), CardCheck::Valid);
+    assert_eq!(
+        card.check(1, &GensoDate::new(112, 7, 31)),
+        CardCheck::Expired
+    );
+    assert_eq!(
+        card.check(2, &GensoDate::new(112, 8, 1)),
+        CardCheck::Expired
+    );
+}
+
+#[test]
+fn renewal_extends_expiry_from_today() {
+    let mut registry = MembershipRegistry::new();
+    registry.get_or_issue("稗田 阿求", || {
+        MembershipCard::new("稗田 阿求", 5, 0, GensoDate::new(112, 7, 10))
+    });
+    assert!(registry.get("稗田 阿求").unwrap().is_expired(&today()));
+
+    let renewed = registry.renew("稗田 阿求", &today()).unwrap();
+    assert_eq!(
+        renewed.expiry,
+        today().add_days_chain(MEMBERSHIP_VALID_DAYS)
+    );
+    assert_eq!(registry.get("稗田 阿求").unwrap(), &renewed);
+    assert_eq!(renewed.check(5, &today()), CardCheck::Valid);
+
+    assert!(registry.renew("博麗 霊夢", &today()).is_none());
+}
+
+#[test]
+fn registry_tracks_lending_counts() {
+    let mut registry = MembershipRegistry::new();
+    let expiry = today().add_days_chain(MEMBERSHIP_VALID_DAYS);
+    registry.get_or_issue("本居 小鈴", || {
+        MembershipCard::new("本居 小鈴", 3, 0, expiry)
+    });
+
+    // 名簿に載っていれば、作り直さない
+    let card = registry.get_or_issue("本居 小鈴", || unreachable!());
+    assert_eq!(card.limit, 3);
+
+    registry.lend("本居 小鈴", 2);
+    assert_eq!(
+        registry.get("本居 小鈴").unwrap().check(2, &today()),
+        CardCheck::OverLimit
+    );
+
+    registry.give_back("本居 小鈴", 5);
+    assert_eq!(registry.get("本居 小鈴").unwrap().lending, 0);
+}
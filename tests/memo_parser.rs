@@ -0,0 +1,69 @@
+extern crate suzu;
+
+use suzu::core::memo_parser::*;
+use suzu::core::{BookInformation, GensoDate};
+
+fn sample_parser() -> MemoParser {
+    let books = ["竹取物語", "竹取物語絵巻", "百鬼夜行絵巻"]
+        .iter()
+        .map(|name| BookInformation::new(name.to_string(), 128, "中判本".to_string(), 1, 100))
+        .collect();
+    let customers = vec!["魔理沙".to_string(), "阿求".to_string()];
+
+    MemoParser::new(books, customers)
+}
+
+#[test]
+fn dates_are_read_in_several_forms() {
+    let parser = sample_parser();
+    let today = GensoDate::new(112, 4, 10);
+
+    assert_eq!(
+        parser.parse("四月十二日に返却", &today),
+        vec![MemoChip::Date(GensoDate::new(112, 4, 12))]
+    );
+    assert_eq!(
+        parser.parse("5/3 と ５月２０日", &today),
+        vec![
+            MemoChip::Date(GensoDate::new(112, 5, 3)),
+            MemoChip::Date(GensoDate::new(112, 5, 20)),
+        ]
+    );
+    assert_eq!(
+        parser.parse("明日か三日後", &today),
+        vec![
+            MemoChip::Date(GensoDate::new(112, 4, 11)),
+            MemoChip::Date(GensoDate::new(112, 4, 13)),
+        ]
+    );
+    assert!(parser.parse("十三月一日 2/30", &today).is_empty());
+}
+
+#[test]
+fn longest_known_word_wins() {
+    let parser = sample_parser();
+    let today = GensoDate::new(112, 4, 10);
+
+    let chips = parser.parse("阿求さんが竹取物語絵巻を予約", &today);
+    assert_eq!(chips.len(), 2);
+    assert_eq!(chips[0], MemoChip::Customer("阿求".to_string()));
+    match &chips[1] {
+        MemoChip::Book(book) => assert_eq!(book.name, "竹取物語絵巻"),
+        chip => panic!("unexpected chip: {:?}", chip),
+    }
+}
+
+#[test]
+fn same_word_is_offered_once() {
+    let parser = sample_parser();
+    let today = GensoDate::new(112, 4, 10);
+
+    let chips = parser.parse("魔理沙 魔理沙 今日 4/10", &today);
+    assert_eq!(
+        chips,
+        vec![
+            MemoChip::Customer("魔理沙".to_string()),
+            MemoChip::Date(today),
+        ]
+    );
+}
@@ -0,0 +1,131 @@
+extern crate suzu;
+
+use torifune::core::Clock;
+use torifune::graphics::drawable::*;
+use torifune::numeric;
+
+use suzu::core::SuzuContext;
+use suzu::object::task_object::tt_menu_component::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DummyKind {
+    Status,
+    Title,
+}
+
+struct DummyMenu {
+    kind: DummyKind,
+    label: u32,
+    closing_at: Option<Clock>,
+}
+
+impl DummyMenu {
+    fn new(kind: DummyKind, label: u32) -> Self {
+        DummyMenu {
+            kind: kind,
+            label: label,
+            closing_at: None,
+        }
+    }
+}
+
+impl DrawableComponent for DummyMenu {
+    fn draw(&mut self, _: &mut ggez::Context) -> ggez::GameResult<()> {
+        Ok(())
+    }
+
+    fn hide(&mut self) {}
+
+    fn appear(&mut self) {}
+
+    fn is_visible(&self) -> bool {
+        true
+    }
+
+    fn set_drawing_depth(&mut self, _: i8) {}
+
+    fn get_drawing_depth(&self) -> i8 {
+        0
+    }
+}
+
+impl StackMenu for DummyMenu {
+    type Kind = DummyKind;
+
+    fn menu_kind(&self) -> DummyKind {
+        self.kind
+    }
+
+    fn menu_contains(&self, _: &mut ggez::Context, _: numeric::Point2f) -> bool {
+        true
+    }
+
+    fn menu_click<'a>(
+        &mut self,
+        _: &mut SuzuContext<'a>,
+        _: ggez::input::mouse::MouseButton,
+        _: numeric::Point2f,
+        _: Clock,
+    ) {
+    }
+
+    fn start_closing(&mut self, t: Clock) {
+        self.closing_at = Some(t);
+    }
+
+    fn update_menu(&mut self, _: &mut ggez::Context, _: Clock) {}
+}
+
+#[test]
+fn opening_same_kind_replaces_and_moves_to_top() {
+    let mut stack = MenuStack::new();
+    assert!(stack.is_empty());
+    assert_eq!(stack.top_kind(), None);
+
+    stack.open(DummyMenu::new(DummyKind::Status, 1));
+    stack.open(DummyMenu::new(DummyKind::Title, 2));
+    assert_eq!(stack.top_kind(), Some(DummyKind::Title));
+
+    stack.open(DummyMenu::new(DummyKind::Status, 3));
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.top_kind(), Some(DummyKind::Status));
+    assert_eq!(stack.get(DummyKind::Status).unwrap().label, 3);
+}
+
+#[test]
+fn closed_menu_stays_until_fade_out_ends() {
+    let mut stack = MenuStack::new();
+    stack.open(DummyMenu::new(DummyKind::Status, 1));
+    stack.open(DummyMenu::new(DummyKind::Title, 2));
+
+    stack.close(DummyKind::Status, 100);
+    assert!(stack.is_closing(DummyKind::Status));
+    assert_eq!(stack.get(DummyKind::Status).unwrap().closing_at, Some(100));
+
+    // 閉じている途中で、もう一度閉じても数え直さない
+    stack.close(DummyKind::Status, 105);
+    stack.flush_closed(100 + MENU_CLOSE_DELAY - 1);
+    assert!(stack.is_opened(DummyKind::Status));
+
+    stack.flush_closed(100 + MENU_CLOSE_DELAY);
+    assert!(!stack.is_opened(DummyKind::Status));
+    assert!(stack.is_opened(DummyKind::Title));
+
+    stack.dismiss_all();
+    assert!(stack.is_empty());
+}
+
+#[test]
+fn reopening_cancels_pending_close() {
+    let mut stack = MenuStack::new();
+    stack.open(DummyMenu::new(DummyKind::Title, 1));
+    stack.close_all(10);
+
+    stack.open(DummyMenu::new(DummyKind::Title, 2));
+    assert!(!stack.is_closing(DummyKind::Title));
+
+    stack.flush_closed(10 + MENU_CLOSE_DELAY);
+    let reopened = stack.get(DummyKind::Title).unwrap();
+    assert_eq!(reopened.label, 2);
+    assert_eq!(reopened.closing_at, None);
+}
@@ -0,0 +1,99 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::name_generator::*;
+
+fn bank() -> NameBank {
+    NameBank::from_toml_str(
+        r#"
+name_heads = ["ハ", "ユ", "春", "菊"]
+name_tails = ["ナ", "ル", "子", "代"]
+title_heads = ["東海", "百鬼", "月下"]
+title_subjects = ["怪談", "奇譚", "草紙"]
+title_suffixes = ["拾遺", "抄"]
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn vertical_text_rejects_long_vowels_and_ascii() {
+    assert!(is_vertical_safe("百鬼怪談抄", MAX_TITLE_CHARS));
+    assert!(is_vertical_safe("ハナ", MAX_NAME_CHARS));
+    assert!(!is_vertical_safe("ハーナ", MAX_NAME_CHARS));
+    assert!(!is_vertical_safe("怪談2", MAX_TITLE_CHARS));
+    assert!(!is_vertical_safe("", MAX_TITLE_CHARS));
+    assert_eq!(differing_chars("東海怪談", "東海奇譚"), 2);
+    assert_eq!(differing_chars("東海怪談", "東海怪談抄"), 1);
+}
+
+#[test]
+fn generated_titles_are_distinct_from_each_other_and_known_titles() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut generator = NameGenerator::new(bank(), vec!["東海怪談".to_string()], Vec::new());
+
+    let mut titles = vec!["東海怪談".to_string()];
+    while let Some(title) = generator.book_title(&mut rng) {
+        titles.push(title);
+    }
+
+    assert!(titles.len() > 1);
+    for (i, a) in titles.iter().enumerate() {
+        for b in titles[i + 1..].iter() {
+            assert!(!a.starts_with(b.as_str()) && !b.starts_with(a.as_str()));
+            assert!(differing_chars(a, b) >= MIN_DIFFERING_CHARS);
+        }
+    }
+}
+
+#[test]
+fn same_seed_gives_same_names() {
+    let generate = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut generator = NameGenerator::new(bank(), Vec::new(), vec!["ハナ".to_string()]);
+        (0..5)
+            .filter_map(|_| generator.customer_name(&mut rng))
+            .collect::<Vec<String>>()
+    };
+
+    let names = generate(9);
+    assert_eq!(names, generate(9));
+    assert!(names
+        .iter()
+        .all(|name| !name.starts_with("ハナ") && is_vertical_safe(name, MAX_NAME_CHARS)));
+}
+
+#[test]
+fn generated_title_reading_is_built_from_word_readings() {
+    let bank = NameBank::from_toml_str(
+        r#"
+name_heads = ["ハ"]
+name_tails = ["ナ"]
+title_heads = ["百鬼", "月下"]
+title_subjects = ["奇譚", "見聞録"]
+title_suffixes = ["抄"]
+
+[readings]
+"百鬼" = "ひゃっき"
+"奇譚" = "きたん"
+"見聞" = "けんぶん"
+"見聞録" = "けんぶんろく"
+"抄" = "しょう"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        bank.reading_of("百鬼奇譚抄"),
+        Some("ひゃっききたんしょう".to_string())
+    );
+    assert_eq!(
+        bank.reading_of("百鬼見聞録"),
+        Some("ひゃっきけんぶんろく".to_string())
+    );
+    // 読みの分からない語が混じれば、振り仮名は付けない
+    assert_eq!(bank.reading_of("月下奇譚"), None);
+    assert_eq!(bank.reading_of(""), None);
+}
@@ -0,0 +1,40 @@
+extern crate suzu;
+
+use suzu::core::number_format::NumberFormat;
+use suzu::core::preferences::Locale;
+use suzu::core::GensoDate;
+
+#[test]
+fn japanese_locale_writes_kanji_numerals() {
+    let format = NumberFormat::new(Locale::Japanese);
+
+    assert_eq!(format.number(7), "七");
+    assert_eq!(format.count(3, "冊"), "三冊");
+    assert_eq!(format.money(5), "五円");
+    assert_eq!(format.ordinal(0), "一");
+}
+
+#[test]
+fn other_locales_write_arabic_numerals() {
+    let format = NumberFormat::new(Locale::English);
+
+    assert_eq!(format.number(12), "12");
+    assert_eq!(format.count(3, "冊"), "3冊");
+    assert_eq!(format.ordinal(9), "10");
+}
+
+#[test]
+fn calendar_formats_follow_the_locale() {
+    let date = GensoDate::new(3, 4, 5);
+
+    let japanese = NumberFormat::new(Locale::Japanese);
+    assert_eq!(japanese.season(3), "第三季");
+    assert_eq!(japanese.date(&date), "第三季 四月 五日");
+    assert_eq!(japanese.short_date(&date), "四月五日");
+    assert_eq!(date.to_string(), japanese.date(&date));
+
+    let english = NumberFormat::new(Locale::English);
+    assert_eq!(english.season(3), "Season 3");
+    assert_eq!(english.date(&date), "Season 3, Apr. 5");
+    assert_eq!(english.short_date(&date), "Apr. 5");
+}
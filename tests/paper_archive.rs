@@ -0,0 +1,82 @@
+extern crate suzu;
+
+use suzu::core::mail::{Letter, LetterContent};
+use suzu::core::paper_archive::*;
+use suzu::core::GensoDate;
+
+fn letter(id: u32, content: LetterContent) -> Letter {
+    Letter {
+        id: id,
+        sender: "稗田阿求".to_string(),
+        received: GensoDate::new(112, 7, 23),
+        content: content,
+    }
+}
+
+#[test]
+fn letters_are_kept_by_the_day_they_were_archived() {
+    let mut archive = PaperArchive::new();
+    let first_day = GensoDate::new(112, 7, 23);
+    let next_day = GensoDate::new(112, 7, 24);
+
+    archive.archive(
+        letter(1, LetterContent::ThankYou { reputation: 2.0 }),
+        &first_day,
+    );
+    archive.archive(
+        letter(2, LetterContent::ThankYou { reputation: 2.0 }),
+        &next_day,
+    );
+    archive.archive(
+        letter(3, LetterContent::ThankYou { reputation: 2.0 }),
+        &next_day,
+    );
+
+    assert_eq!(archive.count_on(&first_day), 1);
+    assert_eq!(archive.count_on(&next_day), 2);
+    assert_eq!(archive.iter_recent().next().unwrap().letter.id, 3);
+}
+
+#[test]
+fn same_letter_is_archived_once() {
+    let mut archive = PaperArchive::new();
+    let today = GensoDate::new(112, 7, 23);
+
+    archive.archive(
+        letter(1, LetterContent::ThankYou { reputation: 2.0 }),
+        &today,
+    );
+    archive.archive(
+        letter(1, LetterContent::ThankYou { reputation: 2.0 }),
+        &today,
+    );
+
+    assert_eq!(archive.len(), 1);
+    assert!(archive.contains(1));
+}
+
+#[test]
+fn old_entries_are_dropped_over_the_limit() {
+    let mut archive = PaperArchive::new();
+    let today = GensoDate::new(112, 7, 23);
+
+    for id in 0..(PAPER_ARCHIVE_MAX_ENTRIES as u32 + 1) {
+        archive.archive(
+            letter(
+                id,
+                LetterContent::CopyRequest {
+                    title: "幻想郷縁起".to_string(),
+                    pages: 10,
+                },
+            ),
+            &today,
+        );
+    }
+
+    assert_eq!(archive.len(), PAPER_ARCHIVE_MAX_ENTRIES);
+    assert!(!archive.contains(0));
+    assert_eq!(
+        archive.iter_recent().next().unwrap().summary_text(),
+        "稗田阿求ヨリ「幻想郷縁起」10頁ノ写本依頼"
+    );
+}
@@ -0,0 +1,34 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::core::positional_audio::*;
+
+#[test]
+fn volume_falls_off_with_distance() {
+    let listener = numeric::Point2f::new(0.0, 0.0);
+
+    assert_eq!(attenuation(listener, listener), 1.0);
+
+    let near = attenuation(numeric::Point2f::new(100.0, 0.0), listener);
+    let far = attenuation(numeric::Point2f::new(600.0, 0.0), listener);
+    assert!(near > far);
+    assert!(far > 0.0);
+
+    let beyond = numeric::Point2f::new(AUDIBLE_DISTANCE, 0.0);
+    assert_eq!(attenuation(beyond, listener), 0.0);
+}
+
+#[test]
+fn pan_follows_camera_center() {
+    let camera = numeric::Rect::new(0.0, 0.0, 1366.0, 768.0);
+    let listener = camera_center(&camera);
+
+    assert_eq!(pan(listener, listener), 0.0);
+    assert!(pan(numeric::Point2f::new(100.0, 384.0), listener) < 0.0);
+    assert_eq!(pan(numeric::Point2f::new(5000.0, 384.0), listener), 1.0);
+
+    let mut audio = PositionalAudio::new();
+    audio.set_listener(&numeric::Rect::new(1000.0, 0.0, 1366.0, 768.0));
+    assert!(audio.volume_at(listener, 1.0) < 1.0);
+}
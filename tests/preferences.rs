@@ -0,0 +1,117 @@
+extern crate suzu;
+
+use ggez::input::keyboard::KeyCode;
+use torifune::numeric;
+
+use suzu::core::preferences::*;
+
+#[test]
+fn missing_fields_use_defaults() {
+    let preferences = Preferences::from_toml_str("reduce_motion = true\n").unwrap();
+
+    assert!(preferences.is_reduce_motion());
+    assert_eq!(preferences.get_ui_scale(), 1.0);
+    assert_eq!(preferences.get_bgm_volume(), 0.5);
+    assert!(preferences.remap_key(KeyCode::Z).is_none());
+}
+
+#[test]
+fn ui_scale_is_clamped() {
+    let preferences = Preferences::from_toml_str("ui_scale = 3.0\n").unwrap();
+    assert_eq!(preferences.get_ui_scale(), UI_SCALE_MAX);
+
+    let mut preferences = Preferences::new();
+    preferences.set_ui_scale(0.1);
+    assert_eq!(preferences.get_ui_scale(), UI_SCALE_MIN);
+}
+
+#[test]
+fn key_bindings_round_trip() {
+    let mut preferences = Preferences::new();
+
+    assert!(preferences.bind_key("J", "Action1"));
+    assert!(!preferences.bind_key("NoSuchKey", "Action1"));
+    assert!(!preferences.bind_key("K", "Jump"));
+
+    assert!(preferences.remap_key(KeyCode::J).is_some());
+    assert!(preferences.remap_key(KeyCode::K).is_none());
+
+    let s = "[key_bindings]\nJ = \"Action1\"\n";
+    let loaded = Preferences::from_toml_str(s).unwrap();
+    assert!(loaded.remap_key(KeyCode::J).is_some());
+
+    assert_eq!(keycode_from_str("Space"), Some(KeyCode::Space));
+    assert!(Preferences::from_toml_str("ui_scale = \"big\"\n").is_none());
+}
+
+#[test]
+fn locale_decides_text_orientation() {
+    let preferences = Preferences::new();
+    assert_eq!(preferences.get_locale(), Locale::Japanese);
+    assert_eq!(preferences.text_orientation(), TextOrientation::Vertical);
+
+    let preferences = Preferences::from_toml_str("locale = \"English\"\n").unwrap();
+    assert_eq!(preferences.text_orientation(), TextOrientation::Horizontal);
+}
+
+#[test]
+fn window_placement_round_trip() {
+    let mut preferences = Preferences::new();
+    assert!(preferences.get_window_placement().is_none());
+
+    let mut placement = WindowPlacement::new(1366.0, 768.0);
+    placement.position = Some((120, -8));
+    preferences.set_window_placement(placement);
+
+    let s = "[window]\nposition = [120, -8]\nwidth = 1366.0\nheight = 768.0\nfullscreen = false\n";
+    let loaded = Preferences::from_toml_str(s).unwrap();
+    assert_eq!(
+        loaded.get_window_placement(),
+        preferences.get_window_placement()
+    );
+
+    // 倍率を変えると、次に開く窓もその大きさになる
+    preferences.set_ui_scale(1.25);
+    let resized = preferences.get_window_placement().unwrap();
+    assert_eq!(
+        (resized.width, resized.height),
+        (1366.0 * 1.25, 768.0 * 1.25)
+    );
+    assert_eq!(resized.position, Some((120, -8)));
+}
+
+#[test]
+fn window_on_missing_monitor_moves_to_primary() {
+    let primary = numeric::Rect::new(0.0, 0.0, 1920.0, 1080.0);
+    let right = numeric::Rect::new(1920.0, 0.0, 2560.0, 1440.0);
+
+    let mut placement = WindowPlacement::new(1366.0, 768.0);
+    placement.position = Some((2400, 300));
+
+    // 右のモニタが残っていれば、そのままの位置に戻す
+    let restored = placement.restore_on(&[primary, right], &primary);
+    assert_eq!(restored.position, Some((2400, 300)));
+
+    // 右のモニタが外されていれば、主画面の右端に寄せる
+    let restored = placement.restore_on(&[primary], &primary);
+    assert_eq!(restored.position, Some((1920 - 1366, 300)));
+}
+
+#[test]
+fn window_is_shrunk_to_fit_a_small_display() {
+    let small = numeric::Rect::new(0.0, 0.0, 1280.0, 720.0);
+
+    let mut placement = WindowPlacement::new(2049.0, 1152.0);
+    placement.position = Some((-500, -40));
+
+    let clamped = placement.clamp_to_display(&small);
+    assert_eq!((clamped.width, clamped.height), (1280.0, 720.0));
+    assert_eq!(clamped.position, Some((0, 0)));
+
+    let tiny = WindowPlacement::new(100.0, 50.0).clamp_to_display(&small);
+    assert_eq!(
+        (tiny.width, tiny.height),
+        (WINDOW_MIN_WIDTH, WINDOW_MIN_HEIGHT)
+    );
+    assert_eq!(tiny.position, None);
+}
@@ -0,0 +1,58 @@
+extern crate suzu;
+
+use std::time::{Duration, SystemTime};
+
+use suzu::core::preferences::Preferences;
+use suzu::core::quicksave::*;
+
+fn at(seconds: u64) -> Option<SystemTime> {
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+#[test]
+fn quicksave_fills_empty_slots_then_rolls_over_the_oldest() {
+    assert_eq!(next_quicksave_slot(&[None, None, None]), 0);
+    assert_eq!(next_quicksave_slot(&[at(10), None, at(5)]), 1);
+    assert_eq!(next_quicksave_slot(&[at(10), at(30), at(20)]), 0);
+    assert_eq!(next_quicksave_slot(&[at(40), at(30), at(20)]), 2);
+}
+
+#[test]
+fn quickload_picks_the_newest_slot() {
+    assert_eq!(newest_slot(&[None, None, None]), None);
+    assert_eq!(newest_slot(&[at(10), None, at(5)]), Some(0));
+    assert_eq!(newest_slot(&[at(40), at(30), at(50), None]), Some(2));
+}
+
+#[test]
+fn autosave_frequency_defaults_to_daily() {
+    let preferences = Preferences::from_toml_str("").unwrap();
+    assert_eq!(
+        preferences.get_autosave_frequency(),
+        AutosaveFrequency::Daily
+    );
+
+    let hourly = Preferences::from_toml_str("autosave_frequency = \"Hourly\"").unwrap();
+    assert!(hourly.get_autosave_frequency().saves_on_hour());
+    assert!(hourly.get_autosave_frequency().saves_on_new_day());
+    assert!(!AutosaveFrequency::Off.saves_on_new_day());
+    assert!(!AutosaveFrequency::Daily.saves_on_hour());
+
+    for frequency in AutosaveFrequency::ALL.iter() {
+        assert_eq!(
+            AutosaveFrequency::from_index(frequency.to_index()),
+            *frequency
+        );
+    }
+    assert_eq!(AutosaveFrequency::from_index(7), AutosaveFrequency::Hourly);
+}
+
+#[test]
+fn continue_looks_at_every_save_file() {
+    let paths = continue_paths();
+
+    assert_eq!(paths.len(), SAVE_SLOTS as usize + QUICKSAVE_SLOTS + 1);
+    assert_eq!(paths[0], "./resources/save1");
+    assert_eq!(paths[SAVE_SLOTS as usize - 1], "./resources/save4");
+    assert_eq!(paths.last().unwrap(), "./resources/autosave");
+}
@@ -0,0 +1,28 @@
+extern crate suzu;
+
+use suzu::object::task_object::tt_sub_component::{
+    record_book_page_indicator, record_book_scroll_target,
+};
+
+#[test]
+fn page_indicator_counts_from_one() {
+    assert_eq!(record_book_page_indicator(0, 1), "1/1");
+    assert_eq!(record_book_page_indicator(2, 7), "3/7");
+    assert_eq!(record_book_page_indicator(6, 7), "7/7");
+}
+
+#[test]
+fn page_indicator_handles_empty_and_stale_index() {
+    assert_eq!(record_book_page_indicator(0, 0), "0/0");
+    // 頁が減った直後でも、最後の頁を指す
+    assert_eq!(record_book_page_indicator(9, 4), "4/4");
+}
+
+#[test]
+fn scrolling_stops_at_first_and_last_page() {
+    assert_eq!(record_book_scroll_target(0, 25, 10), 10);
+    assert_eq!(record_book_scroll_target(20, 25, 10), 24);
+    assert_eq!(record_book_scroll_target(12, 25, -10), 2);
+    assert_eq!(record_book_scroll_target(3, 25, -10), 0);
+    assert_eq!(record_book_scroll_target(0, 0, 10), 0);
+}
@@ -0,0 +1,75 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::core::BookInformation;
+use suzu::object::task_object::tt_sub_component::{BorrowingRecordBookPageData, RecordBookSearch};
+
+fn page(customer_name: Option<&str>, titles: &[&str]) -> BorrowingRecordBookPageData {
+    BorrowingRecordBookPageData {
+        borrowing_book_title: titles
+            .iter()
+            .enumerate()
+            .map(|(row, title)| {
+                (
+                    numeric::Vector2u::new(row as u32, 0),
+                    BookInformation::new(title.to_string(), 120, "中".to_string(), 1, 100),
+                )
+            })
+            .collect(),
+        borrowing_book_status: Vec::new(),
+        customer_name: customer_name.map(|name| name.to_string()),
+        return_date: None,
+        rental_date: None,
+        rental_limit: None,
+        borrowing_is_signed: false,
+        returning_is_signed: false,
+    }
+}
+
+fn sample_pages() -> Vec<BorrowingRecordBookPageData> {
+    vec![
+        page(Some("霧雨魔理沙"), &["竹取物語"]),
+        page(Some("稗田阿求"), &["百鬼夜行絵巻"]),
+        page(None, &[]),
+        page(Some("博麗霊夢"), &["竹取物語", "妖魔本"]),
+    ]
+}
+
+#[test]
+fn page_matches_customer_name_or_book_title() {
+    let page = page(Some("霧雨魔理沙"), &["竹取物語"]);
+
+    assert!(page.matches_search("魔理沙"));
+    assert!(page.matches_search(" 竹取 "));
+    assert!(!page.matches_search("霊夢"));
+    assert!(!page.matches_search("  "));
+}
+
+#[test]
+fn search_jumps_to_first_match_and_cycles() {
+    let mut search = RecordBookSearch::new();
+
+    assert_eq!(search.search(&sample_pages(), "竹取物語"), Some(0));
+    assert_eq!(search.match_count(), 2);
+    assert_eq!(search.status_text(), "「竹取物語」 1/2件");
+
+    assert_eq!(search.next_match(), Some(3));
+    assert_eq!(search.next_match(), Some(0));
+    assert_eq!(search.prev_match(), Some(3));
+    assert_eq!(search.status_text(), "「竹取物語」 2/2件");
+}
+
+#[test]
+fn search_without_matches_reports_it() {
+    let mut search = RecordBookSearch::new();
+
+    assert_eq!(search.search(&sample_pages(), "小鈴"), None);
+    assert!(search.is_active());
+    assert_eq!(search.next_match(), None);
+    assert_eq!(search.status_text(), "「小鈴」は見当たらない");
+
+    search.clear();
+    assert!(!search.is_active());
+    assert_eq!(search.status_text(), "");
+}
@@ -0,0 +1,104 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::core::record_validation::*;
+use suzu::core::{
+    BookCondition, BookInformation, BorrowingInformation, GensoDate, RentalLimit,
+    ReturnBookInformation,
+};
+use suzu::object::task_object::tt_main_component::CustomerRequest;
+use suzu::object::task_object::tt_sub_component::BorrowingRecordBookPageData;
+
+fn book(name: &str) -> BookInformation {
+    BookInformation::new(name.to_string(), 120, "中".to_string(), 1, 300)
+}
+
+fn borrowing_request() -> BorrowingInformation {
+    BorrowingInformation::new(
+        vec![book("竹取物語"), book("竹取物語"), book("百鬼夜行絵巻")],
+        "霧雨魔理沙",
+        GensoDate::new(112, 8, 1),
+        RentalLimit::ShortTerm,
+    )
+}
+
+fn written_page(info: &BorrowingInformation) -> BorrowingRecordBookPageData {
+    BorrowingRecordBookPageData {
+        borrowing_book_title: info
+            .borrowing
+            .iter()
+            .enumerate()
+            .map(|(index, book)| (numeric::Vector2u::new(index as u32, 0), book.clone()))
+            .collect(),
+        borrowing_book_status: Vec::new(),
+        customer_name: Some(info.borrower.clone()),
+        return_date: Some(info.return_date),
+        rental_date: Some(info.borrow_date),
+        rental_limit: Some(info.rental_limit.clone()),
+        borrowing_is_signed: true,
+        returning_is_signed: false,
+    }
+}
+
+#[test]
+fn correctly_written_page_scores_full() {
+    let info = borrowing_request();
+    let page = written_page(&info);
+
+    let score = validate_page(&page, &CustomerRequest::Borrowing(info));
+    assert!(score.is_perfect());
+    assert_eq!(score.checked, 6);
+    assert_eq!(score.score(), 100);
+}
+
+#[test]
+fn wrong_fields_and_titles_are_reported() {
+    let info = borrowing_request();
+    let mut page = written_page(&info);
+    page.customer_name = Some("博麗霊夢".to_string());
+    page.return_date = Some(GensoDate::new(112, 8, 15));
+    // 同じ題目の二冊目を、頼まれていない本と取り違えた
+    page.borrowing_book_title[1].1 = book("東方求聞史紀");
+
+    let score = validate_page(&page, &CustomerRequest::Borrowing(info));
+    assert_eq!(
+        score.mistakes,
+        vec![
+            RecordMistake::WrongBorrower,
+            RecordMistake::WrongReturnDate,
+            RecordMistake::MissingTitle("竹取物語".to_string()),
+            RecordMistake::ExtraTitle("東方求聞史紀".to_string()),
+        ]
+    );
+    assert_eq!(score.checked, 7);
+    assert_eq!(score.score(), 42);
+}
+
+#[test]
+fn returning_page_checks_each_status() {
+    let info = ReturnBookInformation::new(
+        vec![book("竹取物語"), book("百鬼夜行絵巻")],
+        "十六夜咲夜",
+        GensoDate::new(112, 8, 1),
+        GensoDate::new(112, 8, 8),
+    );
+    let mut page = BorrowingRecordBookPageData::from(&info);
+
+    let unwritten = validate_page(&page, &CustomerRequest::Returning(info.clone()));
+    assert_eq!(unwritten.mistakes.len(), 2);
+
+    let first_row = page.borrowing_book_title[0].0.x;
+    let second_row = page.borrowing_book_title[1].0.x;
+    page.borrowing_book_status
+        .push((numeric::Vector2u::new(first_row, 1), BookCondition::Good));
+    page.borrowing_book_status
+        .push((numeric::Vector2u::new(second_row, 1), BookCondition::Bad));
+
+    let score = validate_page(&page, &CustomerRequest::Returning(info));
+    assert_eq!(
+        score.mistakes,
+        vec![RecordMistake::WrongStatus("百鬼夜行絵巻".to_string())]
+    );
+    assert_eq!(score.checked, 7);
+}
@@ -0,0 +1,70 @@
+extern crate suzu;
+
+use std::fs;
+
+use suzu::core::recoverable_error::*;
+
+fn temp_log_path(name: &str) -> std::path::PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "suzu_recoverable_error_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    dir.push("error.log");
+    dir
+}
+
+#[test]
+fn log_line_keeps_details_on_one_line() {
+    let error = RecoverableError::new(
+        ErrorSource::Texture,
+        "画像ガ読メマセン",
+        "TextureID::Ghost1\n/ghost1.png",
+    );
+
+    assert_eq!(
+        error.to_log_line(),
+        "[texture] 画像ガ読メマセン: TextureID::Ghost1 / /ghost1.png"
+    );
+    assert_eq!(ErrorSource::Script.to_str(), "台本");
+}
+
+#[test]
+fn rotating_log_keeps_only_the_newest_generations() {
+    let path = temp_log_path("rotate");
+    let log = RotatingLog::new(path.clone(), 16, 2);
+
+    for line in ["first line", "second line", "third line", "fourth line"].iter() {
+        log.append(line).unwrap();
+    }
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "fourth line\n");
+    assert_eq!(
+        fs::read_to_string(log.rotated_path(1)).unwrap(),
+        "third line\n"
+    );
+    assert_eq!(
+        fs::read_to_string(log.rotated_path(2)).unwrap(),
+        "second line\n"
+    );
+    assert!(!log.rotated_path(3).exists());
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn small_lines_share_one_file_until_the_limit() {
+    let path = temp_log_path("share");
+    let log = RotatingLog::new(path.clone(), 1024, 3);
+
+    log.append("a").unwrap();
+    log.append("b").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a\nb\n");
+    assert!(!log.rotated_path(1).exists());
+    assert_eq!(log.rotated_path(0), path);
+
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+}
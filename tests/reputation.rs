@@ -0,0 +1,75 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::reputation::*;
+
+#[test]
+fn satisfaction_events_move_reputation() {
+    assert_eq!(
+        SatisfactionEvent::Served { waiting: 60 }.reputation_diff(),
+        SERVED_BONUS
+    );
+
+    // 猶予を十分過ぎると、下げ幅は上限で止まる
+    let waited = SatisfactionEvent::Served {
+        waiting: WAITING_GRACE + 60 * 4,
+    };
+    assert!((waited.reputation_diff() + 4.0 * WAITING_PENALTY_PER_MINUTE).abs() < 1e-6);
+    let waited_long = SatisfactionEvent::Served {
+        waiting: WAITING_GRACE + 60 * 600,
+    };
+    assert_eq!(waited_long.reputation_diff(), -WAITING_PENALTY_MAX);
+
+    assert_eq!(SatisfactionEvent::RecordMistakes(0).reputation_diff(), 0.0);
+    assert!(
+        (SatisfactionEvent::RecordMistakes(2).reputation_diff() + 2.0 * RECORD_MISTAKE_PENALTY)
+            .abs()
+            < 1e-6
+    );
+    assert_eq!(
+        SatisfactionEvent::Refused.reputation_diff(),
+        -REFUSED_PENALTY
+    );
+    assert_eq!(
+        SatisfactionEvent::GaveUp.reputation_diff(),
+        -GAVE_UP_PENALTY
+    );
+}
+
+#[test]
+fn reputation_scales_customer_frequency() {
+    assert!((spawn_scale(STANDARD_REPUTATION) - 1.0).abs() < 1e-6);
+    assert!(spawn_scale(100.0) > 1.0);
+    assert!(spawn_scale(0.0) < 1.0);
+    assert_eq!(spawn_scale(-20.0), spawn_scale(0.0));
+
+    assert_eq!(scale_add_timing(100, STANDARD_REPUTATION), 100);
+    assert!(scale_add_timing(100, 100.0) < 100);
+    assert!(scale_add_timing(100, 0.0) > 100);
+    assert_eq!(scale_add_timing(0, 100.0), 1);
+}
+
+#[test]
+fn special_customers_unlock_once_and_stay() {
+    let mut special = SpecialCustomers::new();
+    let mut rng = StdRng::seed_from_u64(7);
+
+    assert!(special.unlock(50.0).is_empty());
+    assert!((0..100).all(|_| special.pick_visitor(&mut rng).is_none()));
+
+    let (first_name, first_required) = SPECIAL_CUSTOMERS[0];
+    assert_eq!(special.unlock(first_required), vec![first_name.to_string()]);
+    assert!(special.unlock(first_required).is_empty());
+
+    // 評判が下がっても、来るようになった客はそのまま
+    assert!(special.unlock(0.0).is_empty());
+    assert!(special.is_unlocked(first_name));
+
+    let visitors: Vec<String> = (0..1000)
+        .filter_map(|_| special.pick_visitor(&mut rng))
+        .collect();
+    assert!(!visitors.is_empty());
+    assert!(visitors.iter().all(|name| name == first_name));
+}
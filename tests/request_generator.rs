@@ -0,0 +1,116 @@
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use suzu::core::request_generator::*;
+use suzu::core::{BookInformation, GensoDate, RentalLimit};
+
+fn book(name: &str, popularity: u32) -> BookInformation {
+    let mut info = BookInformation::new(name.to_string(), 128, "中判本".to_string(), 1, 100);
+    info.popularity = popularity;
+    info
+}
+
+fn sample_books() -> Vec<BookInformation> {
+    vec![
+        book("雨月物語", 10),
+        book("奥の細道", 10),
+        book("遠野物語", 10),
+        book("養生訓", 10),
+        book("改暦弁", 10),
+        book("善の研究", 10),
+        book("北越奇談", 10),
+    ]
+}
+
+#[test]
+fn selected_books_are_never_empty_and_distinct() {
+    let books = sample_books();
+    let generator = RequestGenerator::new(REQUEST_MAX_BOOKS);
+
+    for seed in 0..200 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let selected = generator.select_books(&mut rng, &books);
+
+        assert!(selected.len() >= 1);
+        assert!(selected.len() <= REQUEST_MAX_BOOKS);
+
+        for (i, a) in selected.iter().enumerate() {
+            for b in selected.iter().skip(i + 1) {
+                assert_ne!(a.name, b.name);
+            }
+        }
+    }
+}
+
+#[test]
+fn duplicated_candidates_are_selected_once() {
+    let books = vec![
+        book("雨月物語", 10),
+        book("雨月物語", 10),
+        book("雨月物語", 10),
+    ];
+    let generator = RequestGenerator::new(REQUEST_MAX_BOOKS);
+
+    for seed in 0..50 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        assert_eq!(generator.select_book_indices(&mut rng, &books).len(), 1);
+    }
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert!(generator.select_book_indices(&mut rng, &[]).is_empty());
+    assert_eq!(RequestGenerator::new(0).get_max_books(), 1);
+}
+
+#[test]
+fn popular_books_are_selected_more_often() {
+    let books = vec![book("雨月物語", 1), book("学問のすゝめ", 99)];
+    let generator = RequestGenerator::new(1);
+    let mut rng = StdRng::seed_from_u64(3683);
+
+    let popular_count = (0..1000)
+        .filter(|_| generator.select_books(&mut rng, &books)[0].name == "学問のすゝめ")
+        .count();
+
+    assert!(popular_count > 900);
+}
+
+#[test]
+fn returning_dates_are_plausible() {
+    let generator = RequestGenerator::new(REQUEST_MAX_BOOKS);
+    let today = GensoDate::new(112, 8, 2);
+
+    for seed in 0..200 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (borrow_date, return_date) = generator.returning_dates(&mut rng, &today);
+
+        match borrow_date.rental_limit_type(&return_date) {
+            Some(RentalLimit::ShortTerm) | Some(RentalLimit::LongTerm) => (),
+            _ => panic!("invalid rental period"),
+        }
+
+        assert!(today.diff_days(&return_date).abs() <= RETURN_DATE_MAX_OFFSET);
+        assert!(!borrow_date.is_past(&today));
+    }
+}
+
+#[test]
+fn sub_day_crosses_month_and_season() {
+    assert_eq!(
+        GensoDate::new(112, 8, 5).sub_days_chain(7),
+        GensoDate::new(112, 7, 29)
+    );
+    assert_eq!(
+        GensoDate::new(112, 8, 5).sub_days_chain(5),
+        GensoDate::new(112, 7, 31)
+    );
+    assert_eq!(
+        GensoDate::new(113, 1, 3).sub_days_chain(3),
+        GensoDate::new(112, 12, 31)
+    );
+    assert_eq!(
+        GensoDate::new(112, 8, 5).sub_days_chain(0),
+        GensoDate::new(112, 8, 5)
+    );
+}
@@ -0,0 +1,53 @@
+extern crate suzu;
+
+use suzu::core::reservation::*;
+use suzu::core::GensoDate;
+
+#[test]
+fn reserve_rejects_duplicates() {
+    let today = GensoDate::new(112, 7, 23);
+    let mut ledger = ReservationLedger::new();
+
+    assert!(ledger.reserve("稗田阿求", "竹取物語", &today));
+    assert!(!ledger.reserve("稗田阿求", "竹取物語", &today));
+    assert!(ledger.reserve("霧雨魔理沙", "竹取物語", &today));
+    assert_eq!(ledger.len(), 2);
+    assert!(ledger.is_reserved_by("稗田阿求", "竹取物語"));
+    assert!(!ledger.is_reserved_by("稗田阿求", "方丈記"));
+}
+
+#[test]
+fn returned_book_is_set_aside_for_oldest_reservation() {
+    let today = GensoDate::new(112, 7, 23);
+    let mut ledger = ReservationLedger::new();
+    ledger.reserve("稗田阿求", "竹取物語", &today);
+    ledger.reserve("霧雨魔理沙", "竹取物語", &today);
+
+    assert!(ledger.book_returned("方丈記", &today).is_none());
+
+    let set_aside = ledger.book_returned("竹取物語", &today).unwrap();
+    assert_eq!(set_aside.customer_name, "稗田阿求");
+    assert_eq!(set_aside.revisit_date, Some(GensoDate::new(112, 7, 24)));
+
+    let reminders = ledger.take_reminders();
+    assert_eq!(reminders.len(), 1);
+    assert!(ledger.take_reminders().is_empty());
+
+    // 二冊目が返ってくるまで、次の予約は取り置かれない
+    assert!(ledger.take_revisits(&today).is_empty());
+    let revisits = ledger.take_revisits(&GensoDate::new(112, 7, 24));
+    assert_eq!(revisits, vec![set_aside]);
+    assert_eq!(ledger.len(), 1);
+}
+
+#[test]
+fn overdue_revisit_is_still_taken() {
+    let today = GensoDate::new(112, 7, 31);
+    let mut ledger = ReservationLedger::new();
+    ledger.reserve("稗田阿求", "竹取物語", &today);
+    ledger.book_returned("竹取物語", &today);
+
+    let revisits = ledger.take_revisits(&GensoDate::new(112, 8, 3));
+    assert_eq!(revisits.len(), 1);
+    assert_eq!(revisits[0].revisit_date, Some(GensoDate::new(112, 8, 1)));
+}
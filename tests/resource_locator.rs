@@ -0,0 +1,67 @@
+extern crate suzu;
+
+use std::fs;
+use std::path::PathBuf;
+
+use suzu::core::resource_locator::*;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("suzu_resource_locator_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn first_directory_with_game_data_wins() {
+    let root = scratch_dir("first");
+    let empty = root.join("empty");
+    let installed = root.join("installed");
+    let fallback = root.join("fallback");
+    for dir in [&empty, &installed, &fallback].iter() {
+        fs::create_dir_all(dir).unwrap();
+    }
+    fs::write(installed.join(RESOURCE_MARKER_FILE), "").unwrap();
+    fs::write(fallback.join(RESOURCE_MARKER_FILE), "").unwrap();
+
+    let locator = ResourceLocator::new(vec![
+        root.join("missing"),
+        empty,
+        installed.clone(),
+        fallback,
+    ]);
+    assert_eq!(locator.locate(), Ok(installed));
+}
+
+#[test]
+fn missing_resources_list_every_searched_path() {
+    let root = scratch_dir("missing");
+    let candidates = vec![root.join("a"), root.join("b")];
+
+    let error = ResourceLocator::new(candidates.clone())
+        .locate()
+        .unwrap_err();
+    assert_eq!(error.searched, candidates);
+
+    let message = error.to_string();
+    assert!(candidates
+        .iter()
+        .all(|path| message.contains(&path.display().to_string())));
+    assert!(message.contains(RESOURCES_ENV_VAR));
+}
+
+#[test]
+fn env_var_is_searched_first() {
+    let root = scratch_dir("env");
+    std::env::set_var(RESOURCES_ENV_VAR, &root);
+
+    let locator = ResourceLocator::from_env();
+    assert_eq!(locator.get_candidates()[0], root);
+    assert_eq!(
+        locator.get_candidates().last(),
+        Some(&PathBuf::from("resources"))
+    );
+
+    std::env::remove_var(RESOURCES_ENV_VAR);
+}
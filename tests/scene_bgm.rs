@@ -0,0 +1,42 @@
+extern crate suzu;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use suzu::core::{default_scene_bgm, parse_scene_bgm_table, SoundID};
+use suzu::scene::SceneID;
+
+#[test]
+fn parses_known_scene_bgm_entries() {
+    let mut raw = HashMap::new();
+    raw.insert("Title".to_string(), "EndBGM".to_string());
+    raw.insert("Scenario".to_string(), "ShopBGM".to_string());
+
+    let table = parse_scene_bgm_table(&raw);
+
+    assert_eq!(table.len(), 2);
+    assert!(table.get(&SceneID::Title) == Some(&SoundID::EndBGM));
+    assert!(table.get(&SceneID::Scenario) == Some(&SoundID::ShopBGM));
+}
+
+#[test]
+fn skips_unknown_scene_bgm_entries() {
+    let mut raw = HashMap::new();
+    raw.insert("Title".to_string(), "NoSuchSound".to_string());
+    raw.insert("NoSuchScene".to_string(), "ShopBGM".to_string());
+    raw.insert("End".to_string(), "EndBGM".to_string());
+
+    let table = parse_scene_bgm_table(&raw);
+
+    assert_eq!(table.len(), 1);
+    assert!(table.get(&SceneID::End) == Some(&SoundID::EndBGM));
+}
+
+#[test]
+fn default_scene_bgm_covers_music_scenes() {
+    assert!(default_scene_bgm(SceneID::Save) == Some(SoundID::Title));
+    assert!(default_scene_bgm(SceneID::MainDesk) == Some(SoundID::ShopBGM));
+    assert!(default_scene_bgm(SceneID::Credits) == Some(SoundID::EndBGM));
+    assert!(default_scene_bgm(SceneID::DayResult).is_none());
+    assert!(SoundID::from_str("SeCustomerBell") == Ok(SoundID::SeCustomerBell));
+}
@@ -0,0 +1,114 @@
+extern crate suzu;
+
+use suzu::core::book_fair::FairRequest;
+use suzu::core::score_attack::*;
+use suzu::core::BookInformation;
+
+fn numbered_request(n: usize) -> FairRequest {
+    FairRequest::new(
+        format!("客{}", n),
+        BookInformation::new(format!("本{}", n), 128, "中判本".to_string(), 1, 100),
+    )
+}
+
+fn sample_session() -> ScoreAttackSession {
+    let mut next = 0;
+    ScoreAttackSession::new(ScoreAttackTuning::default(), || {
+        next += 1;
+        numbered_request(next - 1)
+    })
+}
+
+fn shelf_index_of(session: &ScoreAttackSession, name: &str) -> usize {
+    session
+        .get_shelf()
+        .iter()
+        .position(|title| title.as_ref().map_or(false, |book| book.name == name))
+        .unwrap()
+}
+
+fn entry(initials: &str, score: i32) -> LeaderboardEntry {
+    LeaderboardEntry {
+        initials: initials.to_string(),
+        score: score,
+        served: 1,
+        best_combo: 1,
+    }
+}
+
+#[test]
+fn perfect_serves_raise_multiplier_and_extend_time() {
+    let tuning = ScoreAttackTuning::default();
+    let mut session = sample_session();
+    let start = session.get_remaining_time();
+
+    session.select_title(shelf_index_of(&session, "本0"));
+    assert_eq!(session.serve(0, || numbered_request(4)), Some(true));
+    session.select_title(shelf_index_of(&session, "本1"));
+    assert_eq!(session.serve(1, || numbered_request(5)), Some(true));
+
+    assert_eq!(session.get_combo(), 2);
+    assert_eq!(session.get_score(), 100 + 125);
+    assert_eq!(
+        session.get_remaining_time(),
+        start + 2 * tuning.perfect_bonus_seconds * 60
+    );
+    assert_eq!(session.get_counter()[0].as_ref().unwrap().book.name, "本4");
+    assert_eq!(tuning.combo_multiplier(1000), tuning.max_multiplier);
+}
+
+#[test]
+fn a_miss_breaks_the_combo_and_the_retry_scores_base() {
+    let mut session = sample_session();
+
+    session.select_title(shelf_index_of(&session, "本0"));
+    session.serve(0, || numbered_request(4));
+
+    session.select_title(shelf_index_of(&session, "本3"));
+    assert_eq!(session.serve(2, || numbered_request(5)), Some(false));
+    assert_eq!(session.get_combo(), 0);
+    assert_eq!(session.get_score(), 100 - 80);
+
+    session.select_title(shelf_index_of(&session, "本2"));
+    assert_eq!(session.serve(2, || numbered_request(5)), Some(true));
+    assert_eq!(session.get_combo(), 0);
+    assert_eq!(session.get_score(), 100 - 80 + 100);
+
+    let result = session.finish();
+    assert_eq!(result.served, 2);
+    assert_eq!(result.perfect, 1);
+    assert_eq!(result.missed, 1);
+    assert_eq!(result.best_combo, 1);
+}
+
+#[test]
+fn leaderboard_keeps_top_scores_with_initials() {
+    let mut leaderboard = ScoreAttackLeaderboard::new();
+    assert!(!leaderboard.qualifies(0));
+
+    for n in 0..SCORE_ATTACK_LEADERBOARD_SIZE {
+        leaderboard.insert(entry("AAA", 100 * (n as i32 + 1)));
+    }
+    assert!(!leaderboard.qualifies(100));
+    assert_eq!(leaderboard.insert(entry("BBB", 150)), Some(9));
+    assert_eq!(leaderboard.insert(entry("CCC", 150)), None);
+    assert_eq!(leaderboard.insert(entry("DDD", 250)), Some(8));
+    assert_eq!(
+        leaderboard.get_entries().len(),
+        SCORE_ATTACK_LEADERBOARD_SIZE
+    );
+    assert_eq!(leaderboard.get_entries()[9].score, 200);
+    assert!(leaderboard
+        .get_entries()
+        .iter()
+        .all(|e| e.initials != "BBB"));
+
+    let mut initials = InitialsInput::new();
+    assert!(!initials.push('1'));
+    assert!(initials.push('k'));
+    assert_eq!(initials.display(), "K__");
+    assert!(initials.push('o'));
+    assert!(initials.push('s'));
+    assert!(!initials.push('z'));
+    assert_eq!(initials.get(), "KOS");
+}
@@ -0,0 +1,70 @@
+extern crate suzu;
+
+use suzu::core::shop_expansion::*;
+use suzu::core::GensoDate;
+
+#[test]
+fn offer_waits_for_money_and_reputation() {
+    let today = GensoDate::new(112, 7, 23);
+    let state = ShopExpansionState::new();
+
+    assert_eq!(state.next_offer(1000, 80.0, &today), None);
+    assert_eq!(state.next_offer(5000, 50.0, &today), None);
+    assert_eq!(
+        state.next_offer(5000, 65.0, &today),
+        Some(ShopExpansionType::ReadingCorner)
+    );
+}
+
+#[test]
+fn second_room_needs_reading_corner() {
+    let today = GensoDate::new(112, 7, 23);
+    let mut state = ShopExpansionState::new();
+
+    assert!(!state.purchase(ShopExpansionType::SecondRoom));
+    assert!(state.purchase(ShopExpansionType::ReadingCorner));
+    assert!(!state.purchase(ShopExpansionType::ReadingCorner));
+
+    assert_eq!(state.next_offer(5000, 80.0, &today), None);
+    assert_eq!(
+        state.next_offer(10000, 80.0, &today),
+        Some(ShopExpansionType::SecondRoom)
+    );
+    assert!(state.purchase(ShopExpansionType::SecondRoom));
+    assert_eq!(state.next_offer(10000, 80.0, &today), None);
+}
+
+#[test]
+fn declined_offer_comes_back_later() {
+    let today = GensoDate::new(112, 7, 23);
+    let mut state = ShopExpansionState::new();
+    state.decline(&today);
+
+    assert_eq!(state.next_offer(5000, 65.0, &today.add_days_chain(1)), None);
+    assert_eq!(
+        state.next_offer(
+            5000,
+            65.0,
+            &today.add_days_chain(EXPANSION_REOFFER_INTERVAL)
+        ),
+        Some(ShopExpansionType::ReadingCorner)
+    );
+}
+
+#[test]
+fn map_and_spots_follow_purchases() {
+    let mut state = ShopExpansionState::new();
+    assert_eq!(state.current_map_id(1), 1);
+    assert!(state.reading_spots().is_empty());
+    assert!(state.customer_destinations().is_empty());
+
+    state.purchase(ShopExpansionType::ReadingCorner);
+    assert_eq!(state.current_map_id(1), 2);
+    assert!(!state.reading_spots().is_empty());
+    assert!(state.customer_destinations().is_empty());
+
+    state.purchase(ShopExpansionType::SecondRoom);
+    assert_eq!(state.current_map_id(1), 3);
+    assert!(!state.reading_spots().is_empty());
+    assert!(!state.customer_destinations().is_empty());
+}
@@ -0,0 +1,75 @@
+extern crate suzu;
+
+use suzu::core::shortcut_palette::*;
+
+#[test]
+fn kana_to_romaji_handles_youon_and_sokuon() {
+    assert_eq!(kana_to_romaji("ちょうぼ"), "choubo");
+    assert_eq!(kana_to_romaji("きっぷ"), "kippu");
+    assert_eq!(kana_to_romaji("まっちゃ"), "matcha");
+    assert_eq!(kana_to_romaji("しゃしん"), "shashin");
+    assert_eq!(kana_to_romaji("メニュー"), "menyu");
+    assert_eq!(kana_to_romaji("ほんだな"), "hondana");
+}
+
+#[test]
+fn fuzzy_score_requires_ordered_match() {
+    assert!(fuzzy_score("hdn", "hondana").is_some());
+    assert!(fuzzy_score("ndh", "hondana").is_none());
+    assert!(fuzzy_score("hon", "hondana") > fuzzy_score("hdn", "hondana"));
+    assert_eq!(fuzzy_score("", "hondana"), Some(0));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    RecordBook,
+    Shelf,
+    Pause,
+}
+
+fn palette() -> ShortcutPalette<Command> {
+    ShortcutPalette::new(vec![
+        ShortcutEntry::new(Command::RecordBook, "帳簿を開く", "ちょうぼをひらく"),
+        ShortcutEntry::new(Command::Shelf, "近くの本棚", "ちかくのほんだな"),
+        ShortcutEntry::new(Command::Pause, "休憩", "きゅうけい"),
+    ])
+}
+
+#[test]
+fn palette_filters_by_romaji_kana_and_label() {
+    let mut palette = palette();
+    assert_eq!(palette.matched_entries().len(), 3);
+
+    for c in "hon".chars() {
+        palette.push_char(c);
+    }
+    assert_eq!(palette.selected_command(), Some(Command::Shelf));
+
+    palette.pop_char();
+    palette.pop_char();
+    palette.pop_char();
+    for c in "きゅう".chars() {
+        palette.push_char(c);
+    }
+    assert_eq!(palette.selected_command(), Some(Command::Pause));
+
+    palette.pop_char();
+    palette.pop_char();
+    palette.pop_char();
+    palette.push_char('帳');
+    assert_eq!(palette.selected_command(), Some(Command::RecordBook));
+
+    palette.push_char('x');
+    assert!(palette.matched_entries().is_empty());
+    assert_eq!(palette.selected_command(), None);
+}
+
+#[test]
+fn palette_selection_wraps() {
+    let mut palette = palette();
+
+    palette.select_prev();
+    assert_eq!(palette.get_selected_index(), 2);
+    palette.select_next();
+    assert_eq!(palette.get_selected_index(), 0);
+}
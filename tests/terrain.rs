@@ -0,0 +1,28 @@
+extern crate suzu;
+
+use suzu::core::map_parser::*;
+
+#[test]
+fn terrain_property_names() {
+    assert_eq!(
+        TerrainType::from_property("tatami"),
+        Some(TerrainType::Tatami)
+    );
+    assert_eq!(
+        TerrainType::from_property("doorway"),
+        Some(TerrainType::Doorway)
+    );
+    assert_eq!(
+        TerrainType::from_property("path"),
+        Some(TerrainType::OutsidePath)
+    );
+    assert_eq!(TerrainType::from_property("lava"), None);
+}
+
+#[test]
+fn faster_terrain_is_cheaper_to_walk() {
+    assert_eq!(TerrainType::Tatami.path_cost(), TERRAIN_BASE_COST);
+    assert!(TerrainType::Doorway.path_cost() > TerrainType::Tatami.path_cost());
+    assert!(TerrainType::OutsidePath.path_cost() < TerrainType::Tatami.path_cost());
+    assert!(TerrainType::Doorway.speed_rate() < 1.0);
+}
@@ -0,0 +1,78 @@
+extern crate suzu;
+
+use suzu::core::memo_parser::parse_date;
+use suzu::core::shortcut_palette::PaletteKey;
+use suzu::core::GensoDate;
+use suzu::object::util_object::{TextInputBuffer, TextInputEvent};
+
+#[test]
+fn buffer_keeps_within_max_chars() {
+    let mut buffer = TextInputBuffer::new(4);
+
+    for c in "霧雨魔理沙".chars() {
+        buffer.push_char(c);
+    }
+    assert_eq!(buffer.get_text(), "霧雨魔理");
+    assert!(!buffer.push_char('沙'));
+
+    assert!(!buffer.push_char('\n'));
+    assert!(buffer.pop_char());
+    assert_eq!(buffer.get_text(), "霧雨魔");
+
+    buffer.set_text("博麗\t霊夢さん");
+    assert_eq!(buffer.get_text(), "博麗霊夢");
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert!(!buffer.pop_char());
+}
+
+#[test]
+fn key_events_edit_and_commit() {
+    let mut buffer = TextInputBuffer::new(8);
+
+    assert_eq!(
+        buffer.key_event(PaletteKey::Char('咲')),
+        TextInputEvent::Changed
+    );
+    assert_eq!(
+        buffer.key_event(PaletteKey::Char('夜')),
+        TextInputEvent::Changed
+    );
+    assert_eq!(
+        buffer.key_event(PaletteKey::Char(' ')),
+        TextInputEvent::Changed
+    );
+    assert_eq!(buffer.key_event(PaletteKey::Up), TextInputEvent::Nothing);
+    assert_eq!(
+        buffer.key_event(PaletteKey::Enter),
+        TextInputEvent::Committed("咲夜".to_string())
+    );
+    assert_eq!(
+        buffer.key_event(PaletteKey::Escape),
+        TextInputEvent::Canceled
+    );
+
+    buffer.clear();
+    assert_eq!(
+        buffer.key_event(PaletteKey::Backspace),
+        TextInputEvent::Nothing
+    );
+}
+
+#[test]
+fn manual_date_is_read_as_a_whole() {
+    let today = GensoDate::new(112, 7, 23);
+
+    assert_eq!(parse_date("7/30", &today), Some(GensoDate::new(112, 7, 30)));
+    assert_eq!(
+        parse_date(" 八月六日 ", &today),
+        Some(GensoDate::new(112, 8, 6))
+    );
+    assert_eq!(parse_date("明日", &today), Some(GensoDate::new(112, 7, 24)));
+    assert_eq!(parse_date("5/31", &today), Some(GensoDate::new(112, 5, 31)));
+
+    assert_eq!(parse_date("", &today), None);
+    assert_eq!(parse_date("6/31", &today), None);
+    assert_eq!(parse_date("7/30まで", &today), None);
+}
@@ -0,0 +1,59 @@
+extern crate suzu;
+
+use std::time::{Duration, Instant};
+
+use suzu::core::text_layout::*;
+
+#[test]
+fn segments_are_sliced_and_split_into_lines() {
+    // 今までのsliceと同じく、length + 1文字まで出る
+    assert_eq!(slice_chars("本居小鈴", 0), "本");
+    assert_eq!(slice_chars("本居小鈴", 2), "本居小");
+    assert_eq!(slice_chars("本居小鈴", 10), "本居小鈴");
+
+    let lines = layout_lines(&[
+        LayoutSegment::new("一行目\n二行目", 5),
+        LayoutSegment::new("三行目", 0),
+    ]);
+    let texts: Vec<(usize, &str)> = lines
+        .iter()
+        .map(|line| (line.segment, line.text.as_str()))
+        .collect();
+    assert_eq!(texts, vec![(0, "一行目"), (0, "二行"), (1, "三")]);
+}
+
+#[test]
+fn only_changed_lines_need_rebuilding() {
+    let before = layout_lines(&[LayoutSegment::new("一行目\n二行目", 5)]);
+    let after = layout_lines(&[LayoutSegment::new("一行目\n二行目\n三行目", 9)]);
+
+    assert_eq!(reusable_lines(&before, &after), vec![true, false, false]);
+    assert_eq!(reusable_lines(&after, &before), vec![true, false]);
+}
+
+#[test]
+fn worker_lays_out_in_the_background() {
+    let mut worker = TextLayoutWorker::spawn();
+
+    let first = worker.submit(vec![LayoutSegment::new("一行目\n二行目", 10)]);
+    assert!(first.is_some());
+    // 同じ中身は二度頼まない
+    assert_eq!(
+        worker.submit(vec![LayoutSegment::new("一行目\n二行目", 10)]),
+        None
+    );
+    let second = worker.submit(vec![LayoutSegment::new("三行目", 10)]);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut results = Vec::new();
+    while results.len() < 2 && Instant::now() < deadline {
+        results.extend(worker.poll());
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(Some(results[0].serial), first);
+    assert_eq!(results[0].lines.len(), 2);
+    assert_eq!(Some(results[1].serial), second);
+    assert_eq!(results[1].lines[0].text, "三行目");
+}
@@ -0,0 +1,35 @@
+extern crate suzu;
+
+use suzu::core::map_parser::*;
+
+#[test]
+fn frames_cycle_by_duration() {
+    // 500ms, 250msのコマは、60fpsで30, 15フレーム
+    let animation = TileAnimation::from_millis_frames(&[(4, 500), (5, 250)]).unwrap();
+    assert_eq!(animation.get_total_frames(), 45);
+
+    assert_eq!(animation.tile_id_at(0), 4);
+    assert_eq!(animation.tile_id_at(29), 4);
+    assert_eq!(animation.tile_id_at(30), 5);
+    assert_eq!(animation.tile_id_at(44), 5);
+    assert_eq!(animation.tile_id_at(45), 4);
+    assert_eq!(animation.tile_id_at(45 * 100 + 31), 5);
+}
+
+#[test]
+fn very_short_frames_last_at_least_one_frame() {
+    let animation = TileAnimation::from_millis_frames(&[(1, 0), (2, 3), (3, 1000)]).unwrap();
+    assert_eq!(animation.get_total_frames(), 62);
+
+    assert_eq!(animation.tile_id_at(0), 1);
+    assert_eq!(animation.tile_id_at(1), 2);
+    assert_eq!(animation.tile_id_at(2), 3);
+}
+
+#[test]
+fn tiles_without_frames_do_not_animate() {
+    assert_eq!(TileAnimation::from_millis_frames(&[]), None);
+
+    let still = TileAnimation::from_millis_frames(&[(7, 100)]).unwrap();
+    assert!((0..100).all(|t| still.tile_id_at(t) == 7));
+}
@@ -0,0 +1,66 @@
+extern crate suzu;
+
+use suzu::core::time_scale::*;
+
+fn run_frames(clock: &mut SimulationClock, frames: usize) -> Vec<u32> {
+    (0..frames).map(|_| clock.steps_this_frame()).collect()
+}
+
+#[test]
+fn scale_sets_steps_per_frame() {
+    let mut clock = SimulationClock::new(TimeScale::Normal);
+    assert_eq!(run_frames(&mut clock, 3), vec![1, 1, 1]);
+
+    clock.apply(TimeScaleCommand::Slower);
+    assert_eq!(clock.get_scale(), TimeScale::Half);
+    assert_eq!(run_frames(&mut clock, 4), vec![0, 1, 0, 1]);
+
+    clock.apply(TimeScaleCommand::Set(TimeScale::Double));
+    assert_eq!(run_frames(&mut clock, 2), vec![2, 2]);
+
+    clock.apply(TimeScaleCommand::Faster);
+    assert_eq!(clock.get_scale(), TimeScale::Double);
+}
+
+#[test]
+fn step_pauses_and_advances_one_frame() {
+    let mut clock = SimulationClock::new(TimeScale::Double);
+
+    clock.apply(TimeScaleCommand::Step);
+    assert!(clock.is_paused());
+    assert_eq!(run_frames(&mut clock, 3), vec![1, 0, 0]);
+
+    clock.apply(TimeScaleCommand::Step);
+    clock.apply(TimeScaleCommand::Step);
+    assert_eq!(clock.steps_this_frame(), 2);
+
+    clock.apply(TimeScaleCommand::TogglePause);
+    assert!(!clock.is_paused());
+    assert_eq!(clock.steps_this_frame(), 2);
+}
+
+#[test]
+fn console_lines_are_parsed() {
+    assert_eq!(
+        TimeScaleCommand::parse("speed 0.5"),
+        Some(TimeScaleCommand::Set(TimeScale::Half))
+    );
+    assert_eq!(
+        TimeScaleCommand::parse("  speed   2x "),
+        Some(TimeScaleCommand::Set(TimeScale::Double))
+    );
+    assert_eq!(
+        TimeScaleCommand::parse("speed slower"),
+        Some(TimeScaleCommand::Slower)
+    );
+    assert_eq!(
+        TimeScaleCommand::parse("pause"),
+        Some(TimeScaleCommand::TogglePause)
+    );
+    assert_eq!(
+        TimeScaleCommand::parse("step"),
+        Some(TimeScaleCommand::Step)
+    );
+    assert_eq!(TimeScaleCommand::parse("speed 3"), None);
+    assert_eq!(TimeScaleCommand::parse("step 2"), None);
+}
@@ -0,0 +1,64 @@
+extern crate suzu;
+
+use suzu::core::map_parser::*;
+
+fn gids(map: &tiled::Map) -> Vec<Vec<u32>> {
+    match &map.layers[0].tiles {
+        tiled::LayerData::Finite(tiles) => tiles
+            .iter()
+            .map(|row| row.iter().map(|tile| tile.gid).collect())
+            .collect(),
+        tiled::LayerData::Infinite(_) => panic!("infinite layer was not flattened"),
+    }
+}
+
+#[test]
+fn external_tileset_is_read_from_tsx() {
+    let map = load_tmx("./tests/fixtures/tmx/external.tmx").unwrap();
+    assert_eq!(map.tilesets.len(), 1);
+    assert_eq!(map.tilesets[0].first_gid, 1);
+    assert_eq!(map.tilesets[0].name, "external");
+    assert_eq!(gids(&map), vec![vec![1, 2], vec![2, 1]]);
+
+    let tmx = std::fs::read_to_string("./tests/fixtures/tmx/external.tmx").unwrap();
+    assert_eq!(
+        external_tileset_sources(&tmx),
+        vec![(1, "tilesets/external.tsx".to_string())]
+    );
+
+    let tmx = std::fs::read_to_string("./tests/fixtures/tmx/csv.tmx").unwrap();
+    assert!(external_tileset_sources(&tmx).is_empty());
+}
+
+#[test]
+fn compressed_layers_match_csv() {
+    let csv = gids(&load_tmx("./tests/fixtures/tmx/csv.tmx").unwrap());
+    assert_eq!(csv, vec![vec![1, 2, 3], vec![4, 0, 1]]);
+
+    for path in &[
+        "./tests/fixtures/tmx/zlib.tmx",
+        "./tests/fixtures/tmx/gzip.tmx",
+    ] {
+        assert_eq!(gids(&load_tmx(path).unwrap()), csv);
+    }
+}
+
+#[test]
+fn infinite_chunks_are_flattened() {
+    let map = load_tmx("./tests/fixtures/tmx/infinite.tmx").unwrap();
+    assert_eq!((map.width, map.height), (4, 2));
+    assert_eq!(gids(&map), vec![vec![1, 2, 4, 3], vec![3, 4, 0, 1]]);
+
+    // 左端のチャンクが原点に来るので、オブジェクトも二タイル分ずれる
+    let entrance = &map.object_groups[0].objects[0];
+    assert_eq!((entrance.x, entrance.y), (0.0, 16.0));
+}
+
+#[test]
+fn errors_name_the_map() {
+    let e = load_tmx("./tests/fixtures/tmx/missing_tileset.tmx").unwrap_err();
+    assert!(e.to_string().contains("missing_tileset.tmx"));
+
+    let e = load_tmx("./tests/fixtures/tmx/no_such_map.tmx").unwrap_err();
+    assert!(e.to_string().contains("no_such_map.tmx"));
+}
@@ -0,0 +1,49 @@
+extern crate suzu;
+
+use suzu::object::tooltip::*;
+
+#[test]
+fn tooltip_is_shown_after_delay() {
+    let mut timer = HoverTimer::new();
+
+    assert!(!timer.hover(Some("幻想郷縁起　120頁".to_string())));
+    assert!(!timer.update(100));
+    assert_eq!(timer.shown_text(), None);
+
+    assert!(!timer.update(100 + TOOLTIP_DELAY - 1));
+    assert!(timer.update(100 + TOOLTIP_DELAY));
+    assert_eq!(timer.shown_text(), Some("幻想郷縁起　120頁"));
+
+    // 出した後は、出し直さない
+    assert!(!timer.update(100 + TOOLTIP_DELAY + 1));
+}
+
+#[test]
+fn moving_to_another_object_restarts_the_delay() {
+    let mut timer = HoverTimer::new();
+
+    timer.hover(Some("稗田阿求".to_string()));
+    timer.update(0);
+    assert!(timer.update(TOOLTIP_DELAY));
+
+    // 出していた説明は引っ込める
+    assert!(timer.hover(Some("霧雨魔理沙".to_string())));
+    assert_eq!(timer.shown_text(), None);
+    assert!(!timer.update(TOOLTIP_DELAY + 1));
+    assert!(timer.update((TOOLTIP_DELAY * 2) + 1));
+    assert_eq!(timer.shown_text(), Some("霧雨魔理沙"));
+}
+
+#[test]
+fn staying_on_the_same_object_keeps_counting() {
+    let mut timer = HoverTimer::new();
+
+    timer.hover(Some("稗田阿求".to_string()));
+    timer.update(0);
+    assert!(!timer.hover(Some("稗田阿求".to_string())));
+    assert!(timer.update(TOOLTIP_DELAY));
+
+    assert!(timer.hover(None));
+    assert!(!timer.update(TOOLTIP_DELAY * 3));
+    assert_eq!(timer.shown_text(), None);
+}
@@ -0,0 +1,68 @@
+extern crate suzu;
+
+use suzu::object::tween::*;
+
+const EASINGS: [Easing; 5] = [
+    Easing::Linear,
+    Easing::EaseInQuad,
+    Easing::EaseOutQuad,
+    Easing::EaseInOutQuad,
+    Easing::EaseOutCubic,
+];
+
+#[test]
+fn easing_endpoints_are_fixed() {
+    for easing in EASINGS.iter() {
+        assert!(easing.apply(0.0).abs() < 0.0001);
+        assert!((easing.apply(1.0) - 1.0).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn easing_is_monotonic_and_clamped() {
+    for easing in EASINGS.iter() {
+        let mut prev = easing.apply(0.0);
+        for i in 1..=100 {
+            let v = easing.apply(i as f32 / 100.0);
+            assert!(v >= prev);
+            prev = v;
+        }
+
+        assert_eq!(easing.apply(-1.0), easing.apply(0.0));
+        assert_eq!(easing.apply(2.0), easing.apply(1.0));
+    }
+
+    assert!(Easing::EaseInQuad.apply(0.5) < 0.5);
+    assert!(Easing::EaseOutQuad.apply(0.5) > 0.5);
+    assert!((Easing::EaseInOutQuad.apply(0.5) - 0.5).abs() < 0.0001);
+}
+
+#[test]
+fn tween_clock_edges() {
+    let mut t = tween(10.0, 20.0, 10, Easing::Linear, 100);
+
+    // 開始前は開始値のまま
+    assert!(!t.has_started(99));
+    assert_eq!(t.value_at(0), 10.0);
+    assert_eq!(t.update(99), 10.0);
+    assert!(!t.finished());
+
+    assert_eq!(t.value_at(100), 10.0);
+    assert_eq!(t.value_at(105), 15.0);
+    assert!(!t.is_finished_at(109));
+
+    // 終了時刻ちょうどで終了値になる
+    assert_eq!(t.update(110), 20.0);
+    assert!(t.finished());
+    assert_eq!(t.value_at(1000), 20.0);
+}
+
+#[test]
+fn tween_zero_duration_finishes_immediately() {
+    let mut t = tween(1.0, 0.0, 0, Easing::EaseOutCubic, 50);
+
+    assert_eq!(t.value_at(49), 1.0);
+    assert!(!t.is_finished_at(49));
+    assert_eq!(t.update(50), 0.0);
+    assert!(t.finished());
+}
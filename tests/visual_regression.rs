@@ -0,0 +1,112 @@
+#![cfg(feature = "visual_regression")]
+
+extern crate suzu;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use torifune::graphics::drawable::*;
+use torifune::numeric;
+
+use suzu::core::request_generator::RequestGenerator;
+use suzu::core::visual_regression::*;
+use suzu::core::{GensoDate, ResultReport, TextureID};
+use suzu::object::task_object::tt_sub_component::*;
+use suzu::object::task_result_object::MistakeReviewWindow;
+use suzu::object::util_object::FramedButton;
+
+const CHANNEL_TOLERANCE: u8 = 8;
+const PIXEL_RATIO_TOLERANCE: f32 = 0.001;
+
+#[test]
+fn compare_rgba_counts_differing_pixels() {
+    let expected = RgbaImage::new(2, 1, vec![0, 0, 0, 255, 100, 100, 100, 255]);
+    let actual = RgbaImage::new(2, 1, vec![4, 0, 0, 255, 200, 100, 100, 255]);
+
+    let diff = compare_rgba(&expected, &actual, 8).unwrap();
+    assert_eq!(diff.differing_pixels, 1);
+    assert_eq!(diff.max_channel_diff, 100);
+    assert!(!diff.is_within(0.1));
+    assert!(diff.is_within(0.5));
+
+    let other_size = RgbaImage::new(1, 1, vec![0, 0, 0, 255]);
+    assert!(compare_rgba(&expected, &other_size, 8).is_none());
+}
+
+#[test]
+fn rgba_image_round_trip() {
+    let image = RgbaImage::new(1, 2, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(RgbaImage::from_bytes(&image.to_bytes()), Some(image));
+    assert!(RgbaImage::from_bytes(b"SZRG").is_none());
+}
+
+#[test]
+fn scenes_match_reference_images() {
+    let mut harness = VisualRegressionHarness::new();
+    let mut failures = Vec::new();
+
+    let mut page = harness.with_context(|ctx| {
+        let mut rng = StdRng::seed_from_u64(3698);
+        let books: Vec<_> = ctx.resource.iter_available_books().cloned().collect();
+        let request = RequestGenerator::new(3).generate_returning(
+            &mut rng,
+            &books,
+            "稗田阿求",
+            &GensoDate::new(112, 7, 23),
+        );
+
+        BorrowingRecordBookPage::new(
+            ctx,
+            numeric::Rect::new(0.0, 0.0, 1000.0, 550.0),
+            TextureID::Paper1,
+            BorrowingRecordBookPageData::from(&request),
+            0,
+        )
+    });
+    let image = harness.render(|ctx| page.draw(ctx)).unwrap();
+    if let Err(e) = harness.check_reference(
+        "record_book_page",
+        &image,
+        CHANNEL_TOLERANCE,
+        PIXEL_RATIO_TOLERANCE,
+    ) {
+        failures.push(e);
+    }
+
+    let mut button = harness.with_context(|ctx| {
+        FramedButton::create_design1(
+            ctx,
+            numeric::Point2f::new(60.0, 550.0),
+            "戸締まり",
+            numeric::Vector2f::new(28.0, 28.0),
+        )
+    });
+    let image = harness.render(|ctx| button.draw(ctx)).unwrap();
+    if let Err(e) = harness.check_reference(
+        "framed_button",
+        &image,
+        CHANNEL_TOLERANCE,
+        PIXEL_RATIO_TOLERANCE,
+    ) {
+        failures.push(e);
+    }
+
+    let mut review = harness.with_context(|ctx| {
+        MistakeReviewWindow::new(
+            ctx,
+            numeric::Rect::new(20.0, 20.0, 1326.0, 728.0),
+            ResultReport::new(),
+            0,
+        )
+    });
+    let image = harness.render(|ctx| review.draw(ctx)).unwrap();
+    if let Err(e) = harness.check_reference(
+        "mistake_review_window",
+        &image,
+        CHANNEL_TOLERANCE,
+        PIXEL_RATIO_TOLERANCE,
+    ) {
+        failures.push(e);
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,49 @@
+extern crate suzu;
+
+use suzu::core::voice_blip::*;
+
+#[test]
+fn voice_follows_the_speaker_name() {
+    assert_eq!(VoiceArchetype::from_name("小鈴"), VoiceArchetype::Kosuzu);
+    assert_eq!(
+        VoiceArchetype::from_name("本居小鈴"),
+        VoiceArchetype::Kosuzu
+    );
+
+    for name in ["霧雨魔理沙", "十六夜咲夜", "稗田阿求"].iter() {
+        assert_eq!(
+            VoiceArchetype::from_name(name),
+            VoiceArchetype::from_name(name)
+        );
+        assert_ne!(VoiceArchetype::from_name(name), VoiceArchetype::Kosuzu);
+    }
+}
+
+#[test]
+fn counter_blips_every_few_voiced_chars() {
+    let mut counter = BlipCounter::new(VoiceArchetype::Adult);
+
+    let blips: Vec<bool> = "あいうえお".chars().map(|c| counter.feed_char(c)).collect();
+    assert_eq!(blips, vec![true, false, false, true, false]);
+
+    // 句読点や空白では鳴らず、数えもしない
+    assert!(!counter.feed("、。 "));
+    assert!(counter.feed("かき"));
+
+    counter.reset();
+    assert!(counter.feed_char('く'));
+}
+
+#[test]
+fn balloon_schedule_follows_reading_speed() {
+    let schedule = balloon_blip_schedule("こんにちは", VoiceArchetype::Kosuzu);
+    assert_eq!(schedule, vec![0, 4, 8]);
+
+    assert!(balloon_blip_schedule("……！", VoiceArchetype::Elder).is_empty());
+
+    let long_line: String = std::iter::repeat("あ").take(200).collect();
+    assert_eq!(
+        balloon_blip_schedule(&long_line, VoiceArchetype::Child).len(),
+        MAX_BLIPS_PER_LINE
+    );
+}
@@ -0,0 +1,50 @@
+extern crate suzu;
+
+use torifune::numeric;
+
+use suzu::core::weather::*;
+use suzu::core::GensoDate;
+
+#[test]
+fn weather_is_fixed_for_the_day() {
+    let date = GensoDate::new(112, 8, 3);
+    assert_eq!(Weather::of_day(&date), Weather::of_day(&date));
+
+    let rainy_days = (0..120)
+        .filter(|day| Weather::of_day(&date.add_days_chain(*day)).is_rainy())
+        .count();
+    assert!(rainy_days > 0);
+    assert!(rainy_days < 120);
+}
+
+#[test]
+fn umbrella_stand_fills_up() {
+    let mut stand = UmbrellaStand::new();
+    for _ in 0..UMBRELLA_STAND_CAPACITY {
+        assert!(stand.put());
+    }
+    assert!(!stand.put());
+
+    stand.take();
+    assert_eq!(stand.get_count(), UMBRELLA_STAND_CAPACITY - 1);
+    assert!(stand.put());
+}
+
+#[test]
+fn puddles_are_mopped_within_reach() {
+    let mut puddles = PuddleSet::new();
+    let tile = numeric::Vector2u::new(8, 10);
+
+    assert!(puddles.add(tile));
+    assert!(!puddles.add(tile));
+
+    assert!(!puddles.mop(tile, numeric::Vector2u::new(8, 10 + MOP_REACH + 1)));
+    assert!(puddles.mop(tile, numeric::Vector2u::new(8, 10 + MOP_REACH)));
+    assert!(puddles.is_empty());
+    assert!(!puddles.mop(tile, tile));
+
+    for x in 0..(PUDDLE_MAX as u32 + 1) {
+        puddles.add(numeric::Vector2u::new(x, 0));
+    }
+    assert_eq!(puddles.len(), PUDDLE_MAX);
+}